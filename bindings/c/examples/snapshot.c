@@ -0,0 +1,33 @@
+/*
+ * Open a page and print its interactive snapshot:
+ *
+ *   cargo build --release
+ *   cc examples/snapshot.c -Iinclude -Ltarget/release -lagent_browser -o snapshot
+ *   LD_LIBRARY_PATH=target/release ./snapshot https://example.com
+ */
+
+#include <stdio.h>
+
+#include "agent_browser.h"
+
+static int fail(const char *what) {
+  const char *code = ab_last_error_code();
+  fprintf(stderr, "%s failed: %s%s%s\n", what, ab_last_error(), code ? " " : "", code ? code : "");
+  return 1;
+}
+
+int main(int argc, char **argv) {
+  const char *url = argc > 1 ? argv[1] : "https://example.com";
+
+  ab_session *session = ab_session_create("default", 0);
+  if (!session) return fail("ab_session_create");
+
+  char *json = NULL;
+  if (ab_navigate(session, url, NULL) != AB_OK) return fail("ab_navigate");
+  if (ab_snapshot(session, "{\"interactive\": true}", &json) != AB_OK) return fail("ab_snapshot");
+  printf("%s\n", json);
+  ab_string_free(json);
+
+  ab_session_destroy(session);
+  return 0;
+}
@@ -0,0 +1,90 @@
+/*
+ * agent-browser C API
+ *
+ * Drives agent-browser sessions from any runtime with a C FFI (Go's cgo,
+ * C#'s P/Invoke, Java's FFM or JNA, ...). Link against libagent_browser
+ * (shared or static) built from bindings/c.
+ *
+ * Conventions:
+ *  - Functions returning int return AB_OK or one of the AB_ERR_* codes.
+ *    After a failure, ab_last_error() and ab_last_error_code() describe it,
+ *    until the next call on the same thread.
+ *  - Results are JSON, the command's response data, in a NUL-terminated
+ *    UTF-8 string the caller frees with ab_string_free(). Pass NULL as
+ *    `out_json` to discard it.
+ *  - A session handle may be shared between threads, but must not be used
+ *    after ab_session_destroy().
+ *
+ * The API is versioned by AB_ABI_VERSION: functions are only ever added,
+ * and an existing function's signature or meaning never changes within a
+ * version.
+ */
+
+#ifndef AGENT_BROWSER_H
+#define AGENT_BROWSER_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define AB_ABI_VERSION 1
+
+#define AB_OK 0
+/* A NULL pointer, invalid UTF-8 or malformed JSON argument */
+#define AB_ERR_INVALID_ARGUMENT 1
+/* The session's daemon couldn't be reached or didn't answer in time */
+#define AB_ERR_DAEMON 2
+/* The daemon ran the command and it failed; see ab_last_error_code() */
+#define AB_ERR_COMMAND 3
+
+typedef struct ab_session ab_session;
+
+/* AB_ABI_VERSION of the loaded library */
+uint32_t ab_abi_version(void);
+
+/*
+ * Connect to the session `name` (NULL for "default"), starting its daemon
+ * with the agent-browser CLI unless it's running. `timeout_ms` bounds each
+ * command; 0 means 30 seconds. Returns NULL on failure.
+ */
+ab_session *ab_session_create(const char *name, uint32_t timeout_ms);
+
+/* Open `url` in the session's browser, launching it if needed */
+int ab_navigate(ab_session *session, const char *url, char **out_json);
+
+/*
+ * The page's accessibility snapshot: {"snapshot": "...", "refs": {...}}.
+ * `options_json` takes the snapshot command's fields, e.g.
+ * {"interactive": true, "maxDepth": 5}, or NULL for none.
+ */
+int ab_snapshot(ab_session *session, const char *options_json, char **out_json);
+
+/*
+ * Run any daemon command, e.g. ab_act(s, "click", "{\"selector\":\"@e2\"}", NULL).
+ * `fields_json` is a JSON object of the command's fields, or NULL for none.
+ */
+int ab_act(ab_session *session, const char *action, const char *fields_json, char **out_json);
+
+/* Close the session's browser and stop its daemon. The handle stays valid. */
+int ab_session_close(ab_session *session);
+
+/* Free a session handle; the daemon keeps running. NULL is ignored. */
+void ab_session_destroy(ab_session *session);
+
+/* Free a string returned by this library. NULL is ignored. */
+void ab_string_free(char *s);
+
+/* The calling thread's last error message, or NULL. Owned by the library. */
+const char *ab_last_error(void);
+
+/* The daemon's error code for the last AB_ERR_COMMAND, e.g.
+ * "E_SELECTOR_NOT_FOUND", or NULL. Owned by the library. */
+const char *ab_last_error_code(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* AGENT_BROWSER_H */
@@ -0,0 +1,389 @@
+//! The C API declared in `include/agent_browser.h`: opaque session handles
+//! over `agent-browser-client`, JSON in and out, and errors reported through
+//! return codes and a per-thread last error.
+//!
+//! Every function catches panics, so none unwinds into the caller.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use agent_browser_client::{Client, Error};
+use serde_json::{Map, Value};
+use tokio::runtime::Runtime;
+
+pub const AB_ABI_VERSION: u32 = 1;
+
+pub const AB_OK: c_int = 0;
+pub const AB_ERR_INVALID_ARGUMENT: c_int = 1;
+pub const AB_ERR_DAEMON: c_int = 2;
+pub const AB_ERR_COMMAND: c_int = 3;
+
+/// A session handle; C only sees it as `ab_session *`
+pub struct Session {
+    client: Client,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+    static LAST_ERROR_CODE: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// One runtime for every session, with a single worker: callers block on
+/// each command anyway, so it only has to drive the sockets
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("agent-browser-ffi")
+            .enable_all()
+            .build()
+            .expect("failed to start the tokio runtime")
+    })
+}
+
+/// A failed call: its return code, message and the daemon's error code
+struct Failure {
+    status: c_int,
+    message: String,
+    code: Option<String>,
+}
+
+impl Failure {
+    fn invalid(message: impl Into<String>) -> Self {
+        Failure {
+            status: AB_ERR_INVALID_ARGUMENT,
+            message: message.into(),
+            code: None,
+        }
+    }
+}
+
+impl From<Error> for Failure {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Command { message, code } => Failure {
+                status: AB_ERR_COMMAND,
+                message,
+                code,
+            },
+            other => Failure {
+                status: AB_ERR_DAEMON,
+                message: other.to_string(),
+                code: None,
+            },
+        }
+    }
+}
+
+/// C strings can't hold NULs, so they're cut at the first one
+fn c_string(s: String) -> CString {
+    CString::new(s).unwrap_or_else(|e| {
+        let end = e.nul_position();
+        CString::new(&e.into_vec()[..end]).unwrap_or_default()
+    })
+}
+
+fn set_last_error(message: Option<String>, code: Option<String>) {
+    LAST_ERROR.with(|e| *e.borrow_mut() = message.map(c_string));
+    LAST_ERROR_CODE.with(|e| *e.borrow_mut() = code.map(c_string));
+}
+
+/// Run `f`, recording its failure (or panic) as the thread's last error
+fn guard<T>(fallback: T, f: impl FnOnce() -> Result<T, Failure>) -> (T, c_int) {
+    set_last_error(None, None);
+    let failure = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => return (value, AB_OK),
+        Ok(Err(failure)) => failure,
+        Err(_) => Failure {
+            status: AB_ERR_DAEMON,
+            message: "agent-browser panicked".to_string(),
+            code: None,
+        },
+    };
+    set_last_error(Some(failure.message), failure.code);
+    (fallback, failure.status)
+}
+
+/// # Safety
+/// `s` is NULL or a NUL-terminated string
+unsafe fn str_arg<'a>(s: *const c_char, name: &str) -> Result<Option<&'a str>, Failure> {
+    if s.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map(Some)
+        .map_err(|_| Failure::invalid(format!("{} is not valid UTF-8", name)))
+}
+
+/// # Safety
+/// `s` is NULL or a NUL-terminated string
+unsafe fn fields_arg(s: *const c_char, name: &str) -> Result<Map<String, Value>, Failure> {
+    match str_arg(s, name)? {
+        None => Ok(Map::new()),
+        Some(json) => match serde_json::from_str(json) {
+            Ok(Value::Object(map)) => Ok(map),
+            Ok(_) => Err(Failure::invalid(format!("{} must be a JSON object", name))),
+            Err(e) => Err(Failure::invalid(format!(
+                "{} is not valid JSON: {}",
+                name, e
+            ))),
+        },
+    }
+}
+
+/// # Safety
+/// `session` is NULL or a handle from `ab_session_create`
+unsafe fn session_arg<'a>(session: *mut Session) -> Result<&'a Session, Failure> {
+    session
+        .as_ref()
+        .ok_or_else(|| Failure::invalid("session is NULL"))
+}
+
+/// Send a command and hand its data to the caller as JSON
+///
+/// # Safety
+/// `out_json` is NULL or valid for a write
+unsafe fn run(
+    session: &Session,
+    action: &str,
+    fields: Map<String, Value>,
+    out_json: *mut *mut c_char,
+) -> Result<(), Failure> {
+    let data = runtime().block_on(session.client.send_raw(action, fields))?;
+    if !out_json.is_null() {
+        *out_json = c_string(data.to_string()).into_raw();
+    }
+    Ok(())
+}
+
+#[no_mangle]
+pub extern "C" fn ab_abi_version() -> u32 {
+    AB_ABI_VERSION
+}
+
+/// # Safety
+/// `name` is NULL or a NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn ab_session_create(name: *const c_char, timeout_ms: u32) -> *mut Session {
+    guard(ptr::null_mut(), || {
+        let name = str_arg(name, "name")?.unwrap_or("default");
+        if name.is_empty() {
+            return Err(Failure::invalid("name is empty"));
+        }
+        let mut client = Client::new(name);
+        if timeout_ms > 0 {
+            client = client.timeout(Duration::from_millis(timeout_ms.into()));
+        }
+        runtime().block_on(client.start())?;
+        Ok(Box::into_raw(Box::new(Session { client })))
+    })
+    .0
+}
+
+/// # Safety
+/// `session` is a live handle, `url` a NUL-terminated string, and `out_json`
+/// NULL or valid for a write
+#[no_mangle]
+pub unsafe extern "C" fn ab_navigate(
+    session: *mut Session,
+    url: *const c_char,
+    out_json: *mut *mut c_char,
+) -> c_int {
+    guard((), || {
+        let session = session_arg(session)?;
+        let url = str_arg(url, "url")?.ok_or_else(|| Failure::invalid("url is NULL"))?;
+        let mut fields = Map::new();
+        fields.insert("url".to_string(), url.into());
+        run(session, "navigate", fields, out_json)
+    })
+    .1
+}
+
+/// # Safety
+/// `session` is a live handle, `options_json` NULL or a NUL-terminated
+/// string, and `out_json` NULL or valid for a write
+#[no_mangle]
+pub unsafe extern "C" fn ab_snapshot(
+    session: *mut Session,
+    options_json: *const c_char,
+    out_json: *mut *mut c_char,
+) -> c_int {
+    guard((), || {
+        let session = session_arg(session)?;
+        let fields = fields_arg(options_json, "options_json")?;
+        run(session, "snapshot", fields, out_json)
+    })
+    .1
+}
+
+/// # Safety
+/// `session` is a live handle, `action` a NUL-terminated string,
+/// `fields_json` NULL or a NUL-terminated string, and `out_json` NULL or
+/// valid for a write
+#[no_mangle]
+pub unsafe extern "C" fn ab_act(
+    session: *mut Session,
+    action: *const c_char,
+    fields_json: *const c_char,
+    out_json: *mut *mut c_char,
+) -> c_int {
+    guard((), || {
+        let session = session_arg(session)?;
+        let action = match str_arg(action, "action")? {
+            Some(action) if !action.is_empty() => action,
+            _ => return Err(Failure::invalid("action is required")),
+        };
+        let fields = fields_arg(fields_json, "fields_json")?;
+        run(session, action, fields, out_json)
+    })
+    .1
+}
+
+/// # Safety
+/// `session` is a live handle
+#[no_mangle]
+pub unsafe extern "C" fn ab_session_close(session: *mut Session) -> c_int {
+    guard((), || {
+        let session = session_arg(session)?;
+        run(session, "close", Map::new(), ptr::null_mut())
+    })
+    .1
+}
+
+/// # Safety
+/// `session` is NULL or a handle from `ab_session_create` that isn't used
+/// afterwards
+#[no_mangle]
+pub unsafe extern "C" fn ab_session_destroy(session: *mut Session) {
+    if !session.is_null() {
+        drop(Box::from_raw(session));
+    }
+}
+
+/// # Safety
+/// `s` is NULL or a string returned by this library, not freed before
+#[no_mangle]
+pub unsafe extern "C" fn ab_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn ab_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
+
+#[no_mangle]
+pub extern "C" fn ab_last_error_code() -> *const c_char {
+    LAST_ERROR_CODE.with(|e| e.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixListener;
+
+    /// A daemon for session "test" that echoes commands and fails `click`
+    fn fake_daemon() -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("ab-ffi-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.sock");
+        let _ = std::fs::remove_file(&path);
+        // Set before any client is made; this is the only test that makes one
+        std::env::set_var("AGENT_BROWSER_SOCKET_DIR", &dir);
+        let listener = runtime()
+            .block_on(async { UnixListener::bind(&path) })
+            .unwrap();
+        runtime().spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (read, mut write) = stream.into_split();
+                let mut lines = BufReader::new(read).lines();
+                let Ok(Some(line)) = lines.next_line().await else {
+                    continue;
+                };
+                let request: Value = serde_json::from_str(&line).unwrap();
+                let reply = if request["action"] == "click" {
+                    json!({
+                        "id": request["id"], "success": false,
+                        "error": "Element not found", "code": "E_SELECTOR_NOT_FOUND",
+                    })
+                } else {
+                    json!({ "id": request["id"], "success": true, "data": { "echo": request } })
+                };
+                let _ = write.write_all(format!("{}\n", reply).as_bytes()).await;
+            }
+        });
+        dir
+    }
+
+    fn last_error() -> Option<String> {
+        let s = ab_last_error();
+        (!s.is_null()).then(|| unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned())
+    }
+
+    unsafe fn take(s: *mut c_char) -> Value {
+        let json = serde_json::from_str(CStr::from_ptr(s).to_str().unwrap()).unwrap();
+        ab_string_free(s);
+        json
+    }
+
+    #[test]
+    fn test_session_calls() {
+        let dir = fake_daemon();
+        unsafe {
+            let session = ab_session_create(c"test".as_ptr(), 5000);
+            assert!(!session.is_null(), "{:?}", last_error());
+
+            let mut out = ptr::null_mut();
+            assert_eq!(
+                ab_navigate(session, c"https://example.com".as_ptr(), &mut out),
+                AB_OK
+            );
+            let data = take(out);
+            assert_eq!(data["echo"]["action"], "navigate");
+            assert_eq!(data["echo"]["url"], "https://example.com");
+
+            let mut out = ptr::null_mut();
+            let options = cr#"{"interactive": true, "maxDepth": 3}"#;
+            assert_eq!(ab_snapshot(session, options.as_ptr(), &mut out), AB_OK);
+            assert_eq!(take(out)["echo"]["maxDepth"], 3);
+
+            let fields = cr#"{"selector": "@e9"}"#;
+            assert_eq!(
+                ab_act(session, c"click".as_ptr(), fields.as_ptr(), ptr::null_mut()),
+                AB_ERR_COMMAND
+            );
+            assert_eq!(last_error().as_deref(), Some("Element not found"));
+            let code = CStr::from_ptr(ab_last_error_code());
+            assert_eq!(code.to_str().unwrap(), "E_SELECTOR_NOT_FOUND");
+
+            let bad = c"[1]";
+            assert_eq!(
+                ab_act(session, c"fill".as_ptr(), bad.as_ptr(), ptr::null_mut()),
+                AB_ERR_INVALID_ARGUMENT
+            );
+            assert!(last_error().unwrap().contains("JSON object"));
+            assert_eq!(
+                ab_act(session, c"title".as_ptr(), ptr::null(), ptr::null_mut()),
+                AB_OK
+            );
+            assert!(last_error().is_none());
+
+            assert_eq!(
+                ab_navigate(ptr::null_mut(), c"x".as_ptr(), ptr::null_mut()),
+                AB_ERR_INVALID_ARGUMENT
+            );
+            ab_session_destroy(session);
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
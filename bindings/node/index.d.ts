@@ -0,0 +1,100 @@
+import { EventEmitter } from 'node:events';
+
+export interface SessionOptions {
+  /** Milliseconds to wait for each response (default 30000) */
+  timeout?: number;
+}
+
+export interface NavigateOptions {
+  waitUntil?: 'load' | 'domcontentloaded' | 'networkidle';
+  headers?: Record<string, string>;
+}
+
+export interface SnapshotOptions {
+  interactive?: boolean;
+  compact?: boolean;
+  selector?: string;
+  maxDepth?: number;
+  fresh?: boolean;
+}
+
+export interface Snapshot {
+  snapshot: string;
+  refs: Record<string, { role: string; name?: string; nth?: number }>;
+  [key: string]: unknown;
+}
+
+export interface WatchOptions {
+  events?: Array<'childList' | 'attributes' | 'characterData'>;
+  count?: number;
+  timeout?: number;
+}
+
+export interface PollOptions {
+  interval: number;
+  attribute?: string;
+  reload?: boolean;
+  untilChanged?: boolean;
+  untilMatch?: string;
+  max?: number;
+}
+
+/** The daemon ran the command and it failed */
+export class CommandError extends Error {
+  /** Stable error code, e.g. `E_SELECTOR_NOT_FOUND` */
+  readonly code?: string;
+}
+
+/** A running streaming command */
+export class Subscription<E = unknown> extends EventEmitter {
+  readonly action: string;
+  /** Resolves with the final response data once the stream ends (undefined if closed) */
+  done(): Promise<unknown>;
+  /** Stop the stream; nothing is emitted after this */
+  close(): void;
+
+  on(event: 'event', listener: (event: E) => void): this;
+  on(event: 'end', listener: (data: unknown) => void): this;
+  on(event: 'error', listener: (error: Error) => void): this;
+  once(event: 'event', listener: (event: E) => void): this;
+  once(event: 'end', listener: (data: unknown) => void): this;
+  once(event: 'error', listener: (error: Error) => void): this;
+}
+
+export class Session extends EventEmitter {
+  constructor(name?: string, options?: SessionOptions);
+  /** Create a session and start its daemon unless it's running */
+  static start(name?: string, options?: SessionOptions): Promise<Session>;
+
+  readonly name: string;
+  /** Start the session's daemon unless it's running */
+  start(): Promise<void>;
+  /** Run any daemon command and return its response data */
+  send<T = unknown>(action: string, fields?: Record<string, unknown>): Promise<T>;
+
+  launch(options?: Record<string, unknown>): Promise<unknown>;
+  navigate(url: string, options?: NavigateOptions): Promise<{ url: string; title: string }>;
+  snapshot(options?: SnapshotOptions): Promise<Snapshot>;
+  /** Run an action like `act('click', { selector: '@e2' })` */
+  act<T = unknown>(action: string, fields?: Record<string, unknown>): Promise<T>;
+  /** An element's text, or the value of one of its attributes */
+  extract(selector: string, options?: { attribute?: string }): Promise<string | null>;
+  evaluate<T = unknown>(script: string, options?: { args?: unknown[] }): Promise<T>;
+
+  /** Run a streaming command (`watch`, `poll`, `cdp_listen`) */
+  stream<E = unknown>(action: string, fields?: Record<string, unknown>): Subscription<E>;
+  watch(selector: string, options?: WatchOptions): Subscription;
+  poll(selector: string, options: PollOptions): Subscription;
+  cdpListen(events: string[], options?: { count?: number; timeout?: number }): Subscription;
+
+  /** Close the browser and stop the session's daemon */
+  close(): Promise<void>;
+
+  on(event: 'event', listener: (event: unknown, action: string) => void): this;
+  on(event: 'close', listener: () => void): this;
+  once(event: 'event', listener: (event: unknown, action: string) => void): this;
+  once(event: 'close', listener: () => void): this;
+}
+
+/** Names of the sessions whose daemon is running */
+export function sessions(): Promise<string[]>;
@@ -0,0 +1,195 @@
+import { EventEmitter } from 'node:events';
+import { existsSync } from 'node:fs';
+import { createRequire } from 'node:module';
+import { dirname, join } from 'node:path';
+import { fileURLToPath } from 'node:url';
+
+const require = createRequire(import.meta.url);
+const here = dirname(fileURLToPath(import.meta.url));
+
+/** The platform suffix `napi build --platform` names the addon with */
+function platformSuffix() {
+  const { platform, arch } = process;
+  if (platform === 'win32') return `win32-${arch}-msvc`;
+  if (platform === 'linux') {
+    const glibc = process.report?.getReport().header.glibcVersionRuntime;
+    return `linux-${arch}-${glibc ? 'gnu' : 'musl'}`;
+  }
+  return `${platform}-${arch}`;
+}
+
+function loadNative() {
+  const candidates = [`agent-browser.${platformSuffix()}.node`, 'agent-browser.node'];
+  for (const file of candidates) {
+    const path = join(here, file);
+    if (existsSync(path)) return require(path);
+  }
+  throw new Error(
+    `agent-browser-node has no native addon for ${process.platform}-${process.arch}; ` +
+      'build it with `npm run build` in bindings/node'
+  );
+}
+
+const native = loadNative();
+
+/** The daemon ran the command and it failed; `code` is its stable error code */
+export class CommandError extends Error {
+  constructor(message, code) {
+    super(message);
+    this.name = 'CommandError';
+    this.code = code ?? undefined;
+  }
+}
+
+function unwrap(reply) {
+  if (!reply.success) throw new CommandError(reply.error ?? 'Command failed', reply.code);
+  return reply.data;
+}
+
+/**
+ * A running streaming command. Emits 'event' for each event, then 'end' with
+ * the final response data, or 'error'.
+ */
+export class Subscription extends EventEmitter {
+  #stream;
+  #done;
+  #closed = false;
+  #resolve;
+
+  constructor(session, action, fields) {
+    super();
+    this.action = action;
+    this.#done = new Promise((resolve, reject) => {
+      this.#resolve = resolve;
+      this.#stream = session.stream(action, fields, (message) => {
+        if (this.#closed) return;
+        if ('event' in message) {
+          this.emit('event', message.event);
+          return;
+        }
+        try {
+          if (message.error !== undefined) throw new Error(message.error);
+          const data = unwrap(message.reply);
+          this.emit('end', data);
+          resolve(data);
+        } catch (error) {
+          // Without listeners an 'error' event would throw; done() reports it
+          if (this.listenerCount('error') > 0) this.emit('error', error);
+          reject(error);
+        }
+      });
+    });
+    // Callers who only listen for events shouldn't see an unhandled rejection
+    this.#done.catch(() => {});
+  }
+
+  /** Resolves with the final response data once the stream ends */
+  done() {
+    return this.#done;
+  }
+
+  /**
+   * Stop the stream; the daemon ends the command when the connection closes.
+   * Nothing is emitted after this, and done() resolves with undefined.
+   */
+  close() {
+    if (this.#closed) return;
+    this.#closed = true;
+    this.#stream.close();
+    this.#resolve(undefined);
+  }
+}
+
+/**
+ * A session of the agent-browser daemon. Emits 'event' (event, action) for
+ * the events of its streams, and 'close' once closed.
+ */
+export class Session extends EventEmitter {
+  #native;
+
+  constructor(name = 'default', options = {}) {
+    super();
+    this.#native = new native.NativeSession(name, options.timeout);
+  }
+
+  /** Create a session and start its daemon unless it's running */
+  static async start(name = 'default', options = {}) {
+    const session = new Session(name, options);
+    await session.start();
+    return session;
+  }
+
+  get name() {
+    return this.#native.name;
+  }
+
+  /** Start the session's daemon unless it's running */
+  async start() {
+    await this.#native.start();
+  }
+
+  /** Run any daemon command and return its response data */
+  async send(action, fields = {}) {
+    return unwrap(await this.#native.send(action, fields));
+  }
+
+  async launch(options = {}) {
+    return this.send('launch', options);
+  }
+
+  async navigate(url, options = {}) {
+    return this.send('navigate', { ...options, url });
+  }
+
+  async snapshot(options = {}) {
+    return this.send('snapshot', options);
+  }
+
+  /** Run an action like `act('click', { selector: '@e2' })` */
+  async act(action, fields = {}) {
+    return this.send(action, fields);
+  }
+
+  /** An element's text, or the value of one of its attributes */
+  async extract(selector, options = {}) {
+    if (options.attribute) {
+      const data = await this.send('getattribute', { selector, attribute: options.attribute });
+      return data.value;
+    }
+    return (await this.send('gettext', { selector })).text;
+  }
+
+  async evaluate(script, options = {}) {
+    return (await this.send('evaluate', { ...options, script })).result;
+  }
+
+  /** Run a streaming command (`watch`, `poll`, `cdp_listen`) */
+  stream(action, fields = {}) {
+    const subscription = new Subscription(this.#native, action, fields);
+    subscription.on('event', (event) => this.emit('event', event, action));
+    return subscription;
+  }
+
+  watch(selector, options = {}) {
+    return this.stream('watch', { ...options, selector });
+  }
+
+  poll(selector, options) {
+    return this.stream('poll', { ...options, selector });
+  }
+
+  cdpListen(events, options = {}) {
+    return this.stream('cdp_listen', { ...options, events });
+  }
+
+  /** Close the browser and stop the session's daemon */
+  async close() {
+    await this.send('close');
+    this.emit('close');
+  }
+}
+
+/** Names of the sessions whose daemon is running */
+export function sessions() {
+  return native.sessions();
+}
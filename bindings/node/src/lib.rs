@@ -0,0 +1,181 @@
+//! The native half of the `agent-browser-node` package: sessions driven over
+//! the daemon socket by `agent-browser-client`. `index.js` wraps these in the
+//! public `Session` class, which turns failed replies into `CommandError`s
+//! and stream callbacks into events.
+
+#[macro_use]
+extern crate napi_derive;
+
+use std::time::Duration;
+
+use agent_browser_client::{Client, Error};
+use napi::bindgen_prelude::spawn;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use serde_json::{json, Map, Value};
+use tokio::task::JoinHandle;
+
+/// A command's outcome. Failed commands resolve to one of these rather than
+/// rejecting, so the daemon's error code reaches JavaScript intact; only
+/// connection failures and timeouts reject.
+#[napi(object)]
+pub struct Reply {
+    pub success: bool,
+    pub data: Option<Value>,
+    pub error: Option<String>,
+    pub code: Option<String>,
+}
+
+fn reply(result: Result<Value, Error>) -> napi::Result<Reply> {
+    match result {
+        Ok(data) => Ok(Reply {
+            success: true,
+            data: Some(data),
+            error: None,
+            code: None,
+        }),
+        Err(Error::Command { message, code }) => Ok(Reply {
+            success: false,
+            data: None,
+            error: Some(message),
+            code,
+        }),
+        Err(other) => Err(napi::Error::from_reason(other.to_string())),
+    }
+}
+
+fn fields(fields: Option<Value>) -> napi::Result<Map<String, Value>> {
+    match fields {
+        None | Some(Value::Null) => Ok(Map::new()),
+        Some(Value::Object(map)) => Ok(map),
+        Some(_) => Err(napi::Error::from_reason("command fields must be an object")),
+    }
+}
+
+#[napi]
+pub struct NativeSession {
+    client: Client,
+}
+
+#[napi]
+impl NativeSession {
+    /// `timeout` is in milliseconds, like everywhere else in Node
+    #[napi(constructor)]
+    pub fn new(name: String, timeout: Option<f64>) -> napi::Result<Self> {
+        let mut client = Client::new(name);
+        if let Some(ms) = timeout {
+            if !ms.is_finite() || ms <= 0.0 {
+                return Err(napi::Error::from_reason(
+                    "timeout must be a positive number",
+                ));
+            }
+            client = client.timeout(Duration::from_secs_f64(ms / 1000.0));
+        }
+        Ok(NativeSession { client })
+    }
+
+    #[napi(getter)]
+    pub fn name(&self) -> String {
+        self.client.session().to_string()
+    }
+
+    /// Start the session's daemon unless it's running
+    #[napi]
+    pub async fn start(&self) -> napi::Result<()> {
+        self.client
+            .start()
+            .await
+            .map_err(|e| napi::Error::from_reason(e.to_string()))
+    }
+
+    #[napi]
+    pub async fn send(&self, action: String, fields: Option<Value>) -> napi::Result<Reply> {
+        let fields = self::fields(fields)?;
+        reply(self.client.send_raw(&action, fields).await)
+    }
+
+    /// Run a streaming command. `on_message` is called with `{ event }` for
+    /// each event, then once with `{ reply }` or, if the connection failed,
+    /// `{ error }`. One callback keeps them in order.
+    #[napi(
+        ts_args_type = "action: string, fields: Record<string, unknown> | undefined | null, onMessage: (message: { event?: unknown; reply?: Reply; error?: string }) => void"
+    )]
+    pub fn stream(
+        &self,
+        action: String,
+        fields: Option<Value>,
+        on_message: ThreadsafeFunction<Value, ErrorStrategy::Fatal>,
+    ) -> napi::Result<NativeStream> {
+        let fields = self::fields(fields)?;
+        let client = self.client.clone();
+        let task = spawn(async move {
+            let result = async {
+                let mut events = client.stream_raw(&action, fields).await?;
+                while let Some(event) = events.next().await? {
+                    on_message.call(
+                        json!({ "event": event }),
+                        ThreadsafeFunctionCallMode::NonBlocking,
+                    );
+                }
+                events.finish().await
+            }
+            .await;
+            let end = match reply(result) {
+                Ok(reply) => json!({ "reply": {
+                    "success": reply.success,
+                    "data": reply.data,
+                    "error": reply.error,
+                    "code": reply.code,
+                } }),
+                Err(err) => json!({ "error": err.reason }),
+            };
+            on_message.call(end, ThreadsafeFunctionCallMode::NonBlocking);
+        });
+        Ok(NativeStream { task })
+    }
+}
+
+/// A running stream; closing it drops the connection, which ends the
+/// command on the daemon's side
+#[napi]
+pub struct NativeStream {
+    task: JoinHandle<()>,
+}
+
+#[napi]
+impl NativeStream {
+    #[napi]
+    pub fn close(&self) {
+        self.task.abort();
+    }
+}
+
+/// Names of the sessions whose daemon is running
+#[napi]
+pub async fn sessions() -> Vec<String> {
+    agent_browser_client::sessions().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_errors_resolve_with_their_code() {
+        let failed = reply(Err(Error::Command {
+            message: "Element not found".to_string(),
+            code: Some("E_SELECTOR_NOT_FOUND".to_string()),
+        }))
+        .unwrap();
+        assert!(!failed.success);
+        assert_eq!(failed.code.as_deref(), Some("E_SELECTOR_NOT_FOUND"));
+        assert!(reply(Err(Error::Timeout)).is_err());
+        assert_eq!(reply(Ok(json!(1))).unwrap().data, Some(json!(1)));
+    }
+
+    #[test]
+    fn test_fields_must_be_an_object() {
+        assert!(fields(None).unwrap().is_empty());
+        assert_eq!(fields(Some(json!({"url": "x"}))).unwrap()["url"], "x");
+        assert!(fields(Some(json!([1]))).is_err());
+    }
+}
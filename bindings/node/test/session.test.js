@@ -0,0 +1,92 @@
+import { after, before, describe, it } from 'node:test';
+import assert from 'node:assert/strict';
+import { mkdtempSync, rmSync } from 'node:fs';
+import { createServer } from 'node:net';
+import { tmpdir } from 'node:os';
+import { join } from 'node:path';
+import { createInterface } from 'node:readline';
+
+// A daemon that answers from a table, streaming two events for `watch`
+const dir = mkdtempSync(join(tmpdir(), 'agent-browser-node-'));
+process.env.AGENT_BROWSER_SOCKET_DIR = dir;
+const requests = [];
+const server = createServer((socket) => {
+  const lines = createInterface({ input: socket });
+  // Closed subscriptions hang up mid-stream
+  lines.on('error', () => {});
+  lines.on('line', (line) => {
+    const command = JSON.parse(line);
+    requests.push(command);
+    const send = (message) => socket.write(JSON.stringify({ id: command.id, ...message }) + '\n');
+    switch (command.action) {
+      case 'gettext':
+        return send({ success: true, data: { text: 'Example Domain' } });
+      case 'click':
+        return send({ success: false, error: 'No element', code: 'E_SELECTOR_NOT_FOUND' });
+      case 'watch':
+        send({ event: { type: 'childList', added: 1 } });
+        send({ event: { type: 'childList', added: 2 } });
+        return send({ success: true, data: { events: 2 } });
+      default:
+        return send({ success: true, data: { echo: command } });
+    }
+  });
+});
+
+const { CommandError, Session } = await import('../index.js');
+
+describe('Session', () => {
+  before(() => new Promise((resolve) => server.listen(join(dir, 'test.sock'), resolve)));
+  after(() => {
+    server.close();
+    rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('sends commands and returns their data', async () => {
+    const session = new Session('test');
+    const data = await session.navigate('https://example.com', { waitUntil: 'load' });
+    assert.equal(data.echo.action, 'navigate');
+    assert.equal(data.echo.waitUntil, 'load');
+    assert.equal(await session.extract('h1'), 'Example Domain');
+    assert.deepEqual(requests.at(-1).selector, 'h1');
+  });
+
+  it('throws command failures with their code', async () => {
+    const session = new Session('test');
+    await assert.rejects(session.act('click', { selector: '@e9' }), (error) => {
+      assert.ok(error instanceof CommandError);
+      assert.equal(error.code, 'E_SELECTOR_NOT_FOUND');
+      return true;
+    });
+  });
+
+  it('rejects when the daemon is unreachable', async () => {
+    await assert.rejects(new Session('missing').snapshot(), /daemon connection failed/);
+  });
+
+  it('emits stream events on the subscription and the session', async () => {
+    const session = new Session('test');
+    const seen = [];
+    session.on('event', (event, action) => seen.push([action, event.added]));
+    const watch = session.watch('#feed', { count: 2 });
+    const events = [];
+    watch.on('event', (event) => events.push(event));
+    assert.deepEqual(await watch.done(), { events: 2 });
+    assert.equal(events.length, 2);
+    assert.deepEqual(seen, [
+      ['watch', 1],
+      ['watch', 2],
+    ]);
+  });
+
+  it('stops emitting once a subscription is closed', async () => {
+    const session = new Session('test');
+    const watch = session.watch('#feed');
+    let events = 0;
+    watch.on('event', () => events++);
+    watch.close();
+    assert.equal(await watch.done(), undefined);
+    await new Promise((resolve) => setTimeout(resolve, 50));
+    assert.equal(events, 0);
+  });
+});
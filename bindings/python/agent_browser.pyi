@@ -0,0 +1,65 @@
+from types import TracebackType
+from typing import Any, Awaitable, Optional
+
+class AgentBrowserError(Exception): ...
+class DaemonError(AgentBrowserError): ...
+
+class CommandError(AgentBrowserError):
+    code: Optional[str]
+
+class Session:
+    def __init__(self, name: str = "default", *, timeout: float = 30.0, start: bool = True) -> None: ...
+    @property
+    def name(self) -> str: ...
+    def launch(self, **options: Any) -> Any: ...
+    def navigate(self, url: str, *, wait_until: Optional[str] = None) -> Any: ...
+    def snapshot(
+        self,
+        *,
+        interactive: bool = False,
+        compact: bool = False,
+        selector: Optional[str] = None,
+        max_depth: Optional[int] = None,
+        fresh: bool = False,
+    ) -> dict[str, Any]: ...
+    def act(self, action: str, **fields: Any) -> Any: ...
+    def extract(self, selector: str, *, attribute: Optional[str] = None) -> Any: ...
+    def evaluate(self, script: str) -> Any: ...
+    def close(self) -> None: ...
+    def __enter__(self) -> "Session": ...
+    def __exit__(
+        self,
+        exc_type: Optional[type[BaseException]],
+        exc: Optional[BaseException],
+        tb: Optional[TracebackType],
+    ) -> None: ...
+
+class AsyncSession:
+    def __init__(self, name: str = "default", *, timeout: float = 30.0) -> None: ...
+    @property
+    def name(self) -> str: ...
+    def start(self) -> Awaitable[None]: ...
+    def launch(self, **options: Any) -> Awaitable[Any]: ...
+    def navigate(self, url: str, *, wait_until: Optional[str] = None) -> Awaitable[Any]: ...
+    def snapshot(
+        self,
+        *,
+        interactive: bool = False,
+        compact: bool = False,
+        selector: Optional[str] = None,
+        max_depth: Optional[int] = None,
+        fresh: bool = False,
+    ) -> Awaitable[dict[str, Any]]: ...
+    def act(self, action: str, **fields: Any) -> Awaitable[Any]: ...
+    def extract(self, selector: str, *, attribute: Optional[str] = None) -> Awaitable[Any]: ...
+    def evaluate(self, script: str) -> Awaitable[Any]: ...
+    def close(self) -> Awaitable[None]: ...
+    async def __aenter__(self) -> "AsyncSession": ...
+    async def __aexit__(
+        self,
+        exc_type: Optional[type[BaseException]],
+        exc: Optional[BaseException],
+        tb: Optional[TracebackType],
+    ) -> None: ...
+
+def sessions() -> list[str]: ...
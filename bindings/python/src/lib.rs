@@ -0,0 +1,525 @@
+//! The `agent_browser` Python module: sessions driven over the daemon
+//! socket by `agent-browser-client`, in a blocking flavor ([`Session`]) and
+//! an asyncio one ([`AsyncSession`]).
+
+use std::future::Future;
+use std::time::Duration;
+
+use agent_browser_client::{Client, Error};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+use serde_json::{Map, Number, Value};
+
+create_exception!(
+    agent_browser,
+    AgentBrowserError,
+    PyException,
+    "Base class of the errors raised by agent_browser."
+);
+create_exception!(
+    agent_browser,
+    DaemonError,
+    AgentBrowserError,
+    "The session's daemon couldn't be reached, or didn't answer properly."
+);
+create_exception!(
+    agent_browser,
+    CommandError,
+    AgentBrowserError,
+    "The daemon ran the command and it failed; `code` is its stable error code."
+);
+
+fn to_py_err(err: Error) -> PyErr {
+    match err {
+        Error::Command { message, code } => Python::with_gil(|py| {
+            let err = CommandError::new_err(message);
+            let _ = err.value(py).setattr("code", code);
+            err
+        }),
+        other => DaemonError::new_err(other.to_string()),
+    }
+}
+
+/// `wait_until` as the daemon spells it, `waitUntil`
+fn camel_case(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' && !out.is_empty() {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn to_json(obj: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if obj.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(b) = obj.downcast::<PyBool>() {
+        Ok(Value::Bool(b.is_true()))
+    } else if obj.is_instance_of::<PyInt>() {
+        match obj.extract::<i64>() {
+            Ok(i) => Ok(Value::from(i)),
+            Err(_) => Ok(Value::from(obj.extract::<u64>()?)),
+        }
+    } else if let Ok(f) = obj.downcast::<PyFloat>() {
+        Number::from_f64(f.value())
+            .map(Value::Number)
+            .ok_or_else(|| PyValueError::new_err("NaN and infinity can't be sent"))
+    } else if let Ok(s) = obj.downcast::<PyString>() {
+        Ok(Value::String(s.to_cow()?.into_owned()))
+    } else if let Ok(dict) = obj.downcast::<PyDict>() {
+        let mut map = Map::new();
+        for (key, value) in dict.iter() {
+            map.insert(key.extract::<String>()?, to_json(&value)?);
+        }
+        Ok(Value::Object(map))
+    } else if obj.is_instance_of::<PyList>() || obj.is_instance_of::<PyTuple>() {
+        obj.try_iter()?
+            .map(|item| to_json(&item?))
+            .collect::<PyResult<Vec<_>>>()
+            .map(Value::Array)
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "{} can't be sent to the daemon",
+            obj.get_type().name()?
+        )))
+    }
+}
+
+fn to_py(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    Ok(match value {
+        Value::Null => py.None(),
+        Value::Bool(b) => PyBool::new(py, *b).to_owned().into_any().unbind(),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => i.into_pyobject(py)?.into_any().unbind(),
+            (_, Some(u)) => u.into_pyobject(py)?.into_any().unbind(),
+            _ => n
+                .as_f64()
+                .unwrap_or(0.0)
+                .into_pyobject(py)?
+                .into_any()
+                .unbind(),
+        },
+        Value::String(s) => s.into_pyobject(py)?.into_any().unbind(),
+        Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(to_py(py, item)?)?;
+            }
+            list.into_any().unbind()
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, item) in map {
+                dict.set_item(key, to_py(py, item)?)?;
+            }
+            dict.into_any().unbind()
+        }
+    })
+}
+
+/// Keyword arguments as command fields, with `None` ones left out
+fn fields(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Map<String, Value>> {
+    let mut map = Map::new();
+    if let Some(kwargs) = kwargs {
+        for (key, value) in kwargs.iter() {
+            if !value.is_none() {
+                map.insert(camel_case(&key.extract::<String>()?), to_json(&value)?);
+            }
+        }
+    }
+    Ok(map)
+}
+
+/// The commands both session flavors offer, as an action, its fields, and
+/// the response field to return (the whole response when None)
+struct Request {
+    action: String,
+    fields: Map<String, Value>,
+    pick: Option<&'static str>,
+}
+
+impl Request {
+    fn new(action: impl Into<String>, fields: Map<String, Value>) -> Self {
+        Request {
+            action: action.into(),
+            fields,
+            pick: None,
+        }
+    }
+
+    fn pick(mut self, field: &'static str) -> Self {
+        self.pick = Some(field);
+        self
+    }
+
+    async fn send(self, client: Client) -> Result<Value, Error> {
+        let mut data = client.send_raw(&self.action, self.fields).await?;
+        Ok(match self.pick {
+            Some(field) => data.get_mut(field).map(Value::take).unwrap_or(Value::Null),
+            None => data,
+        })
+    }
+}
+
+fn navigate(url: &str, wait_until: Option<&str>) -> Request {
+    let mut fields = Map::new();
+    fields.insert("url".into(), url.into());
+    if let Some(wait_until) = wait_until {
+        fields.insert("waitUntil".into(), wait_until.into());
+    }
+    Request::new("navigate", fields)
+}
+
+fn snapshot(
+    interactive: bool,
+    compact: bool,
+    selector: Option<&str>,
+    max_depth: Option<u32>,
+    fresh: bool,
+) -> Request {
+    let mut fields = Map::new();
+    for (key, on) in [
+        ("interactive", interactive),
+        ("compact", compact),
+        ("fresh", fresh),
+    ] {
+        if on {
+            fields.insert(key.into(), true.into());
+        }
+    }
+    if let Some(selector) = selector {
+        fields.insert("selector".into(), selector.into());
+    }
+    if let Some(depth) = max_depth {
+        fields.insert("maxDepth".into(), depth.into());
+    }
+    Request::new("snapshot", fields)
+}
+
+fn extract(selector: &str, attribute: Option<&str>) -> Request {
+    let mut fields = Map::new();
+    fields.insert("selector".into(), selector.into());
+    match attribute {
+        Some(attribute) => {
+            fields.insert("attribute".into(), attribute.into());
+            Request::new("getattribute", fields).pick("value")
+        }
+        None => Request::new("gettext", fields).pick("text"),
+    }
+}
+
+fn evaluate(script: &str) -> Request {
+    let mut fields = Map::new();
+    fields.insert("script".into(), script.into());
+    Request::new("evaluate", fields).pick("result")
+}
+
+fn client(name: &str, timeout: f64) -> PyResult<Client> {
+    if !timeout.is_finite() || timeout <= 0.0 {
+        return Err(PyValueError::new_err("timeout must be positive"));
+    }
+    Ok(Client::new(name).timeout(Duration::from_secs_f64(timeout)))
+}
+
+/// A session, with methods that block until the daemon answers.
+///
+/// Creating one starts the session's daemon unless it's running; the browser
+/// launches with the first command that needs it, or with `launch()`.
+#[pyclass(module = "agent_browser", frozen)]
+struct Session {
+    client: Client,
+}
+
+impl Session {
+    fn run(&self, py: Python<'_>, request: Request) -> PyResult<PyObject> {
+        let client = self.client.clone();
+        let value = block_on(py, request.send(client))?;
+        to_py(py, &value)
+    }
+}
+
+fn block_on<T: Send>(
+    py: Python<'_>,
+    future: impl Future<Output = Result<T, Error>> + Send,
+) -> PyResult<T> {
+    let runtime = pyo3_async_runtimes::tokio::get_runtime();
+    py.allow_threads(|| runtime.block_on(future))
+        .map_err(to_py_err)
+}
+
+#[pymethods]
+impl Session {
+    #[new]
+    #[pyo3(signature = (name = "default", *, timeout = 30.0, start = true))]
+    fn new(py: Python<'_>, name: &str, timeout: f64, start: bool) -> PyResult<Self> {
+        let client = client(name, timeout)?;
+        if start {
+            let starting = client.clone();
+            block_on(py, async move { starting.start().await })?;
+        }
+        Ok(Session { client })
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        self.client.session()
+    }
+
+    /// Launch the browser with options like `headless=False` or
+    /// `executable_path=...`, as the daemon's `launch` command takes them
+    #[pyo3(signature = (**options))]
+    fn launch(&self, py: Python<'_>, options: Option<&Bound<'_, PyDict>>) -> PyResult<PyObject> {
+        self.run(py, Request::new("launch", fields(options)?))
+    }
+
+    #[pyo3(signature = (url, *, wait_until = None))]
+    fn navigate(&self, py: Python<'_>, url: &str, wait_until: Option<&str>) -> PyResult<PyObject> {
+        self.run(py, navigate(url, wait_until))
+    }
+
+    /// The page's accessibility tree: a dict with the `snapshot` text and the
+    /// `refs` (like `@e2`) that `act()` takes as selectors
+    #[pyo3(signature = (*, interactive = false, compact = false, selector = None, max_depth = None, fresh = false))]
+    fn snapshot(
+        &self,
+        py: Python<'_>,
+        interactive: bool,
+        compact: bool,
+        selector: Option<&str>,
+        max_depth: Option<u32>,
+        fresh: bool,
+    ) -> PyResult<PyObject> {
+        let request = snapshot(interactive, compact, selector, max_depth, fresh);
+        self.run(py, request)
+    }
+
+    /// Run any daemon command, e.g. `act("click", selector="@e2")` or
+    /// `act("fill", selector="@e3", value="hi")`. Returns its response data.
+    #[pyo3(signature = (action, **fields))]
+    fn act(
+        &self,
+        py: Python<'_>,
+        action: String,
+        fields: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<PyObject> {
+        self.run(py, Request::new(action, self::fields(fields)?))
+    }
+
+    /// An element's text, or the value of one of its attributes
+    #[pyo3(signature = (selector, *, attribute = None))]
+    fn extract(
+        &self,
+        py: Python<'_>,
+        selector: &str,
+        attribute: Option<&str>,
+    ) -> PyResult<PyObject> {
+        self.run(py, extract(selector, attribute))
+    }
+
+    /// Run JavaScript in the page and return its result
+    fn evaluate(&self, py: Python<'_>, script: &str) -> PyResult<PyObject> {
+        self.run(py, evaluate(script))
+    }
+
+    /// Close the browser and stop the session's daemon
+    fn close(&self, py: Python<'_>) -> PyResult<()> {
+        self.run(py, Request::new("close", Map::new()))?;
+        Ok(())
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __exit__(&self, py: Python<'_>, _args: &Bound<'_, PyTuple>) -> PyResult<()> {
+        self.close(py)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Session({:?})", self.client.session())
+    }
+}
+
+/// A session for asyncio: every method returns an awaitable.
+///
+/// `await session.start()` starts the daemon unless it's running; `async
+/// with AsyncSession(...)` does it on entry and closes the session on exit.
+#[pyclass(module = "agent_browser", frozen)]
+struct AsyncSession {
+    client: Client,
+}
+
+impl AsyncSession {
+    fn run<'py>(&self, py: Python<'py>, request: Request) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let value = request.send(client).await.map_err(to_py_err)?;
+            Python::with_gil(|py| to_py(py, &value))
+        })
+    }
+}
+
+#[pymethods]
+impl AsyncSession {
+    #[new]
+    #[pyo3(signature = (name = "default", *, timeout = 30.0))]
+    fn new(name: &str, timeout: f64) -> PyResult<Self> {
+        Ok(AsyncSession {
+            client: client(name, timeout)?,
+        })
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        self.client.session()
+    }
+
+    fn start<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            client.start().await.map_err(to_py_err)
+        })
+    }
+
+    #[pyo3(signature = (**options))]
+    fn launch<'py>(
+        &self,
+        py: Python<'py>,
+        options: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.run(py, Request::new("launch", fields(options)?))
+    }
+
+    #[pyo3(signature = (url, *, wait_until = None))]
+    fn navigate<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        wait_until: Option<&str>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.run(py, navigate(url, wait_until))
+    }
+
+    #[pyo3(signature = (*, interactive = false, compact = false, selector = None, max_depth = None, fresh = false))]
+    fn snapshot<'py>(
+        &self,
+        py: Python<'py>,
+        interactive: bool,
+        compact: bool,
+        selector: Option<&str>,
+        max_depth: Option<u32>,
+        fresh: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let request = snapshot(interactive, compact, selector, max_depth, fresh);
+        self.run(py, request)
+    }
+
+    #[pyo3(signature = (action, **fields))]
+    fn act<'py>(
+        &self,
+        py: Python<'py>,
+        action: String,
+        fields: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.run(py, Request::new(action, self::fields(fields)?))
+    }
+
+    #[pyo3(signature = (selector, *, attribute = None))]
+    fn extract<'py>(
+        &self,
+        py: Python<'py>,
+        selector: &str,
+        attribute: Option<&str>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.run(py, extract(selector, attribute))
+    }
+
+    fn evaluate<'py>(&self, py: Python<'py>, script: &str) -> PyResult<Bound<'py, PyAny>> {
+        self.run(py, evaluate(script))
+    }
+
+    fn close<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            client
+                .send_raw("close", Map::new())
+                .await
+                .map(|_| ())
+                .map_err(to_py_err)
+        })
+    }
+
+    fn __aenter__<'py>(slf: Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        let client = slf.get().client.clone();
+        let session: Py<Self> = slf.clone().unbind();
+        pyo3_async_runtimes::tokio::future_into_py(slf.py(), async move {
+            client.start().await.map_err(to_py_err)?;
+            Ok(session)
+        })
+    }
+
+    #[pyo3(signature = (*_args))]
+    fn __aexit__<'py>(
+        &self,
+        py: Python<'py>,
+        _args: &Bound<'_, PyTuple>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        self.close(py)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("AsyncSession({:?})", self.client.session())
+    }
+}
+
+/// Names of the sessions whose daemon is running
+#[pyfunction]
+fn sessions(py: Python<'_>) -> PyResult<Vec<String>> {
+    block_on(py, async { Ok(agent_browser_client::sessions().await) })
+}
+
+#[pymodule]
+fn agent_browser(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Session>()?;
+    m.add_class::<AsyncSession>()?;
+    m.add_function(wrap_pyfunction!(sessions, m)?)?;
+    m.add("AgentBrowserError", m.py().get_type::<AgentBrowserError>())?;
+    m.add("DaemonError", m.py().get_type::<DaemonError>())?;
+    m.add("CommandError", m.py().get_type::<CommandError>())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_camel_case() {
+        assert_eq!(camel_case("wait_until"), "waitUntil");
+        assert_eq!(camel_case("ignore_https_errors"), "ignoreHttpsErrors");
+        assert_eq!(camel_case("selector"), "selector");
+        assert_eq!(camel_case("_private"), "_private");
+    }
+
+    #[test]
+    fn test_extract_picks_the_value() {
+        let text = extract("h1", None);
+        assert_eq!((text.action.as_str(), text.pick), ("gettext", Some("text")));
+        let href = extract("a", Some("href"));
+        assert_eq!(
+            (href.action.as_str(), href.pick),
+            ("getattribute", Some("value"))
+        );
+        assert_eq!(href.fields["attribute"], "href");
+    }
+}
@@ -0,0 +1,423 @@
+//! Artifacts directory management.
+//!
+//! Files the daemon writes without an explicit path (screenshots, PDFs,
+//! traces, downloads) go to `~/.browseros/artifacts/<session>/`.
+//! `agent-browser artifacts list|open|prune` works on that tree, and the
+//! `artifacts-max-age` / `artifacts-max-size` config keys are applied as a
+//! retention policy whenever a session's daemon starts.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+use serde_json::json;
+
+use crate::color;
+use crate::config::Config;
+use crate::errors::ErrorCode;
+use crate::install::get_browseros_home;
+use crate::output::format_bytes;
+use crate::ui;
+
+/// Root of all sessions' artifacts; `AGENT_BROWSER_ARTIFACTS_DIR` overrides it
+/// (the daemon reads the same variable).
+pub fn artifacts_root() -> PathBuf {
+    env::var_os("AGENT_BROWSER_ARTIFACTS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| get_browseros_home().join("artifacts"))
+}
+
+pub fn session_dir(session: &str) -> PathBuf {
+    artifacts_root().join(session)
+}
+
+/// Parse an age like `90m`, `12h`, `7d` or `2w`.
+pub fn parse_age(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit())?;
+    let (number, unit) = value.split_at(split);
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => return None,
+    };
+    Some(Duration::from_secs(number.parse::<u64>().ok()? * scale))
+}
+
+/// Parse a size like `500M`, `5G` or `5GB` (decimal units, like `format_bytes`).
+pub fn parse_size(value: &str) -> Option<u64> {
+    let upper = value.trim().to_ascii_uppercase();
+    let unit_start = upper
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(upper.len());
+    let (number, unit) = upper.split_at(unit_start);
+    let scale = match unit.trim().trim_end_matches('B') {
+        "" => 1.0,
+        "K" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        "T" => 1e12,
+        _ => return None,
+    };
+    let number: f64 = number.parse().ok()?;
+    Some((number * scale) as u64)
+}
+
+struct Artifact {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+/// Every file under `dir`, newest first.
+fn collect(dir: &Path) -> Vec<Artifact> {
+    fn walk(dir: &Path, found: &mut Vec<Artifact>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                walk(&path, found);
+            } else {
+                found.push(Artifact {
+                    path,
+                    size: meta.len(),
+                    modified: meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                });
+            }
+        }
+    }
+    let mut found = Vec::new();
+    walk(dir, &mut found);
+    found.sort_by_key(|a| std::cmp::Reverse(a.modified));
+    found
+}
+
+/// Files a prune removes: those older than `max_age`, then the oldest of the
+/// rest until the total fits in `max_size`. `artifacts` must be newest first.
+fn select_for_prune(
+    artifacts: &[Artifact],
+    now: SystemTime,
+    max_age: Option<Duration>,
+    max_size: Option<u64>,
+) -> Vec<usize> {
+    let mut kept_size = 0u64;
+    let mut removed = Vec::new();
+    for (i, artifact) in artifacts.iter().enumerate() {
+        let age = now.duration_since(artifact.modified).unwrap_or_default();
+        let too_old = max_age.is_some_and(|max| age > max);
+        let too_big = max_size.is_some_and(|max| kept_size + artifact.size > max);
+        if too_old || too_big {
+            removed.push(i);
+        } else {
+            kept_size += artifact.size;
+        }
+    }
+    removed
+}
+
+/// Remove directories left empty under `dir`, keeping `dir` itself.
+fn remove_empty_dirs(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            remove_empty_dirs(&path);
+            // Fails, harmlessly, unless the directory is now empty
+            let _ = fs::remove_dir(&path);
+        }
+    }
+}
+
+struct PruneResult {
+    files: usize,
+    bytes: u64,
+}
+
+fn prune(
+    dir: &Path,
+    max_age: Option<Duration>,
+    max_size: Option<u64>,
+    dry_run: bool,
+) -> PruneResult {
+    let artifacts = collect(dir);
+    let selected = select_for_prune(&artifacts, SystemTime::now(), max_age, max_size);
+    let mut result = PruneResult { files: 0, bytes: 0 };
+    for artifact in selected.iter().map(|&i| &artifacts[i]) {
+        if dry_run || fs::remove_file(&artifact.path).is_ok() {
+            result.files += 1;
+            result.bytes += artifact.size;
+        }
+    }
+    if !dry_run {
+        remove_empty_dirs(dir);
+    }
+    result
+}
+
+/// Apply the configured retention policy to every session. Called when a
+/// daemon starts; does nothing unless a limit is configured.
+pub fn apply_retention() {
+    let config = Config::load();
+    let max_age = config
+        .get_str("artifacts-max-age")
+        .and_then(|v| parse_age(&v));
+    let max_size = config
+        .get_str("artifacts-max-size")
+        .and_then(|v| parse_size(&v));
+    if max_age.is_none() && max_size.is_none() {
+        return;
+    }
+    let result = prune(&artifacts_root(), max_age, max_size, false);
+    if result.files > 0 {
+        tracing::info!(
+            files = result.files,
+            bytes = result.bytes,
+            "pruned artifacts"
+        );
+    }
+}
+
+fn age(modified: SystemTime) -> String {
+    let secs = SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or_default()
+        .as_secs();
+    match secs {
+        s if s < 3600 => format!("{}m ago", s / 60),
+        s if s < 86400 => format!("{}h ago", s / 3600),
+        s => format!("{}d ago", s / 86400),
+    }
+}
+
+/// Open a file or directory with the desktop's default application.
+fn open_path(path: &Path) -> Result<(), String> {
+    let mut cmd = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "start", ""]);
+        cmd
+    } else {
+        Command::new("xdg-open")
+    };
+    cmd.arg(path)
+        .status()
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+        .and_then(|status| {
+            if status.success() {
+                Ok(())
+            } else {
+                Err(format!("Failed to open {}", path.display()))
+            }
+        })
+}
+
+const USAGE: &str =
+    "artifacts <list|open|prune> [--all] [--older-than <age>] [--max-size <size>] [--dry-run]";
+
+/// Handle `agent-browser artifacts <list|open|prune>` (runs locally, no daemon).
+pub fn run_artifacts(args: &[String], session: &str) {
+    let rest: Vec<&str> = args.iter().skip(2).map(|s| s.as_str()).collect();
+    let value = |flag: &str| {
+        rest.iter()
+            .position(|&a| a == flag)
+            .map(|i| rest.get(i + 1).copied())
+    };
+    let all = rest.contains(&"--all");
+    let dir = if all {
+        artifacts_root()
+    } else {
+        session_dir(session)
+    };
+
+    match args.get(1).map(|s| s.as_str()) {
+        Some("list") | None => {
+            let artifacts = collect(&dir);
+            let total: u64 = artifacts.iter().map(|a| a.size).sum();
+            if ui::json_mode() {
+                let items: Vec<_> = artifacts
+                    .iter()
+                    .map(|a| {
+                        let modified = a
+                            .modified
+                            .duration_since(SystemTime::UNIX_EPOCH)
+                            .unwrap_or_default();
+                        json!({
+                            "path": a.path,
+                            "size": a.size,
+                            "modified": modified.as_millis() as u64,
+                        })
+                    })
+                    .collect();
+                ui::print_json_data(json!({
+                    "dir": dir,
+                    "artifacts": items,
+                    "totalBytes": total,
+                }));
+            } else if artifacts.is_empty() {
+                println!("No artifacts in {}", dir.display());
+            } else {
+                for artifact in &artifacts {
+                    let shown = artifact.path.strip_prefix(&dir).unwrap_or(&artifact.path);
+                    println!(
+                        "{:>10}  {:>8}  {}",
+                        format_bytes(artifact.size),
+                        color::dim(&age(artifact.modified)),
+                        shown.display()
+                    );
+                }
+                println!(
+                    "{}",
+                    color::dim(&format!(
+                        "{} files, {} in {}",
+                        artifacts.len(),
+                        format_bytes(total),
+                        dir.display()
+                    ))
+                );
+            }
+        }
+        Some("open") => {
+            let target = match rest.iter().find(|a| !a.starts_with("--")) {
+                Some(name) if Path::new(name).is_absolute() => PathBuf::from(name),
+                Some(name) => dir.join(name),
+                None => dir,
+            };
+            if !target.exists() {
+                ui::fail(
+                    ErrorCode::InvalidArgs,
+                    format!("Not found: {}", target.display()),
+                );
+            }
+            if let Err(e) = open_path(&target) {
+                ui::fail(ErrorCode::Unknown, e);
+            }
+            if ui::json_mode() {
+                ui::print_json_data(json!({ "opened": target }));
+            }
+        }
+        Some("prune") => {
+            let config = Config::load();
+            let max_age = match value("--older-than") {
+                Some(Some(v)) => Some(parse_age(v).unwrap_or_else(|| {
+                    ui::fail(
+                        ErrorCode::InvalidArgs,
+                        format!("Invalid --older-than value: {} (expected e.g. 12h, 7d)", v),
+                    )
+                })),
+                Some(None) => ui::fail(ErrorCode::InvalidArgs, "--older-than requires a value"),
+                None => config
+                    .get_str("artifacts-max-age")
+                    .and_then(|v| parse_age(&v)),
+            };
+            let max_size = match value("--max-size") {
+                Some(Some(v)) => Some(parse_size(v).unwrap_or_else(|| {
+                    ui::fail(
+                        ErrorCode::InvalidArgs,
+                        format!("Invalid --max-size value: {} (expected e.g. 500M, 5G)", v),
+                    )
+                })),
+                Some(None) => ui::fail(ErrorCode::InvalidArgs, "--max-size requires a value"),
+                None => config
+                    .get_str("artifacts-max-size")
+                    .and_then(|v| parse_size(&v)),
+            };
+            if max_age.is_none() && max_size.is_none() {
+                ui::fail(
+                    ErrorCode::InvalidArgs,
+                    format!(
+                        "Nothing to prune by: pass --older-than or --max-size, or set artifacts-max-age / artifacts-max-size\nUsage: agent-browser {}",
+                        USAGE
+                    ),
+                );
+            }
+            let dry_run = rest.contains(&"--dry-run");
+            let result = prune(&dir, max_age, max_size, dry_run);
+            if ui::json_mode() {
+                ui::print_json_data(json!({
+                    "dir": dir,
+                    "removed": result.files,
+                    "freedBytes": result.bytes,
+                    "dryRun": dry_run,
+                }));
+            } else {
+                let verb = if dry_run { "Would remove" } else { "Removed" };
+                ui::status(format!(
+                    "{} {} {} files ({})",
+                    color::success_indicator(),
+                    verb,
+                    result.files,
+                    format_bytes(result.bytes)
+                ));
+            }
+        }
+        Some(other) => ui::fail(
+            ErrorCode::InvalidArgs,
+            format!(
+                "Unknown subcommand: {}\nValid options: list, open, prune",
+                other
+            ),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_age_and_size() {
+        assert_eq!(parse_age("7d"), Some(Duration::from_secs(7 * 86_400)));
+        assert_eq!(parse_age("90m"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_age("7"), None);
+        assert_eq!(parse_age("7y"), None);
+        assert_eq!(parse_size("5G"), Some(5_000_000_000));
+        assert_eq!(parse_size("500mb"), Some(500_000_000));
+        assert_eq!(parse_size("1.5K"), Some(1500));
+        assert_eq!(parse_size("1024"), Some(1024));
+        assert_eq!(parse_size("5X"), None);
+    }
+
+    #[test]
+    fn test_select_for_prune() {
+        let now = SystemTime::now();
+        let artifact = |hours: u64, size: u64| Artifact {
+            path: PathBuf::new(),
+            size,
+            modified: now - Duration::from_secs(hours * 3600),
+        };
+        // Newest first
+        let artifacts = vec![
+            artifact(1, 400),
+            artifact(5, 400),
+            artifact(30, 100),
+            artifact(200, 100),
+        ];
+        let day = Duration::from_secs(86_400);
+        assert_eq!(
+            select_for_prune(&artifacts, now, Some(day), None),
+            vec![2, 3]
+        );
+        assert_eq!(
+            select_for_prune(&artifacts, now, None, Some(500)),
+            vec![1, 3]
+        );
+        assert_eq!(
+            select_for_prune(&artifacts, now, Some(day), Some(500)),
+            vec![1, 2, 3]
+        );
+        assert!(select_for_prune(&artifacts, now, None, None).is_empty());
+    }
+}
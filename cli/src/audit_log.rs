@@ -0,0 +1,244 @@
+//! Tamper-evident audit logs (`agent-browser audit-log`).
+//!
+//! With `--audit-log`, a session's daemon appends every command, main-frame
+//! navigation and download to `~/.browseros/audit/<session>.jsonl` (see
+//! `src/audit-log.ts`). Each line's `hash` is the SHA-256 of the line as
+//! written, minus that trailing `"hash"` field, and its `prev` is the hash of
+//! the line before, so `audit-log verify` can tell if any entry was edited,
+//! removed or reordered. It prints the hash of the last entry: a log cut
+//! short still verifies, so compare against a head hash kept elsewhere.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::color;
+use crate::errors::ErrorCode;
+use crate::install::get_browseros_home;
+use crate::ui;
+use crate::upload::hex;
+
+const USAGE: &str =
+    "audit-log <verify [<file.jsonl>] | export [--format jsonl|json] [--out <file>]>";
+
+/// `prev` of the first entry
+const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+pub fn audit_log_path(session: &str) -> PathBuf {
+    get_browseros_home()
+        .join("audit")
+        .join(format!("{}.jsonl", session))
+}
+
+/// An intact chain: how many entries it has and the hash of the last
+#[derive(Debug, PartialEq)]
+struct Chain {
+    entries: usize,
+    head: String,
+}
+
+/// Check every entry's hash, its link to the one before and its sequence
+/// number, stopping at the first line that doesn't hold up
+fn verify_chain(text: &str) -> Result<Chain, String> {
+    let mut head = GENESIS.to_string();
+    let mut entries = 0;
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let broken = |reason: &str| format!("line {}: {}", i + 1, reason);
+        let entry: Value = serde_json::from_str(line).map_err(|_| broken("not JSON"))?;
+        let hash = entry["hash"].as_str().ok_or_else(|| broken("no hash"))?;
+        let unhashed = line
+            .strip_suffix(&format!(",\"hash\":\"{}\"}}", hash))
+            .ok_or_else(|| broken("hash is not the last field"))?;
+        if hex(&Sha256::digest(format!("{}}}", unhashed).as_bytes())) != hash {
+            return Err(broken("contents do not match its hash"));
+        }
+        if entry["prev"].as_str() != Some(head.as_str()) {
+            return Err(broken("does not follow the entry before it"));
+        }
+        entries += 1;
+        if entry["seq"].as_u64() != Some(entries as u64) {
+            return Err(broken(&format!("expected seq {}", entries)));
+        }
+        head = hash.to_string();
+    }
+    Ok(Chain { entries, head })
+}
+
+fn read_log(path: &Path, session: &str) -> String {
+    if !path.exists() {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            format!(
+                "No audit log for session {}; record one with --audit-log",
+                session
+            ),
+        );
+    }
+    fs::read_to_string(path).unwrap_or_else(|e| {
+        ui::fail(
+            ErrorCode::Unknown,
+            format!("Could not read {}: {}", path.display(), e),
+        )
+    })
+}
+
+pub fn run_audit_log(args: &[String], session: &str, out: Option<&str>) {
+    let invalid = |message: String| -> ! {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            format!("{}\nUsage: agent-browser {}", message, USAGE),
+        )
+    };
+    match args.get(1).map(String::as_str) {
+        Some("verify") => {
+            if args.len() > 3 {
+                invalid(format!("Unknown argument: {}", args[3]));
+            }
+            let path = args
+                .get(2)
+                .map(PathBuf::from)
+                .unwrap_or_else(|| audit_log_path(session));
+            let text = read_log(&path, session);
+            let chain = verify_chain(&text).unwrap_or_else(|e| {
+                ui::fail(
+                    ErrorCode::AuditBroken,
+                    format!("Audit log {} is broken at {}", path.display(), e),
+                )
+            });
+            if ui::json_mode() {
+                ui::print_json_data(json!({
+                    "path": path,
+                    "entries": chain.entries,
+                    "head": chain.head,
+                }));
+            } else {
+                ui::status(format!(
+                    "{} {} entries intact in {}\n  head {}",
+                    color::success_indicator(),
+                    chain.entries,
+                    path.display(),
+                    chain.head
+                ));
+            }
+        }
+        Some("export") => {
+            let mut json_format = false;
+            let mut i = 2;
+            while i < args.len() {
+                match args[i].as_str() {
+                    "--format" => {
+                        json_format = match args.get(i + 1).map(String::as_str) {
+                            Some("jsonl") => false,
+                            Some("json") => true,
+                            Some(other) => invalid(format!(
+                                "Unknown audit log format: {} (expected jsonl or json)",
+                                other
+                            )),
+                            None => invalid("--format needs a value".into()),
+                        };
+                        i += 1;
+                    }
+                    other => invalid(format!("Unknown argument: {}", other)),
+                }
+                i += 1;
+            }
+            let path = audit_log_path(session);
+            let text = read_log(&path, session);
+            if let Err(e) = verify_chain(&text) {
+                ui::warn(format!("Exporting a broken audit log (at {})", e));
+            }
+            let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+            // jsonl keeps lines byte for byte, so the export verifies on its own
+            let rendered = if json_format {
+                let entries: Vec<Value> = lines
+                    .iter()
+                    .filter_map(|l| serde_json::from_str(l).ok())
+                    .collect();
+                format!(
+                    "{}\n",
+                    serde_json::to_string_pretty(&entries).unwrap_or_default()
+                )
+            } else {
+                lines.iter().map(|l| format!("{}\n", l)).collect()
+            };
+            let Some(out) = out else {
+                let _ = std::io::stdout().write_all(rendered.as_bytes());
+                return;
+            };
+            if let Err(e) = fs::write(out, rendered) {
+                ui::fail(
+                    ErrorCode::Unknown,
+                    format!("Could not write {}: {}", out, e),
+                );
+            }
+            if ui::json_mode() {
+                ui::print_json_data(json!({ "path": out, "entries": lines.len() }));
+            } else {
+                ui::status(format!(
+                    "{} Exported {} entries to {}",
+                    color::success_indicator(),
+                    lines.len(),
+                    out
+                ));
+            }
+        }
+        Some(other) => invalid(format!("Unknown audit-log subcommand: {}", other)),
+        None => invalid("audit-log needs a subcommand".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A line as the daemon writes it
+    fn entry(seq: u64, prev: &str, url: &str) -> (String, String) {
+        let unhashed = format!(
+            r#"{{"seq":{},"time":"2026-01-31T10:00:00.000Z","session":"work","type":"navigation","url":"{}","prev":"{}"}}"#,
+            seq, url, prev
+        );
+        let hash = hex(&Sha256::digest(unhashed.as_bytes()));
+        let line = format!(
+            "{},\"hash\":\"{}\"}}",
+            &unhashed[..unhashed.len() - 1],
+            hash
+        );
+        (line, hash)
+    }
+
+    #[test]
+    fn test_verify_chain() {
+        let (first, first_hash) = entry(1, GENESIS, "https://example.com/");
+        let (second, second_hash) = entry(2, &first_hash, "https://example.com/pay");
+        let log = format!("{}\n{}\n", first, second);
+        assert_eq!(
+            verify_chain(&log),
+            Ok(Chain {
+                entries: 2,
+                head: second_hash.clone()
+            })
+        );
+        assert_eq!(verify_chain("").unwrap().head, GENESIS);
+
+        let edited = log.replace("/pay", "/home");
+        assert_eq!(
+            verify_chain(&edited),
+            Err("line 2: contents do not match its hash".to_string())
+        );
+        assert_eq!(
+            verify_chain(&format!("{}\n", second)),
+            Err("line 1: does not follow the entry before it".to_string())
+        );
+        let (skipped, _) = entry(3, &first_hash, "https://example.com/");
+        assert_eq!(
+            verify_chain(&format!("{}\n{}\n", first, skipped)),
+            Err("line 2: expected seq 2".to_string())
+        );
+    }
+}
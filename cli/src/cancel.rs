@@ -0,0 +1,87 @@
+//! Ctrl-C handling.
+//!
+//! Once `install` has run, the first Ctrl-C (SIGINT) doesn't kill the CLI.
+//! Instead a `cancel` command is sent to the sessions' daemons, which answer
+//! the commands in flight with `E_CANCELLED`, stop page loads and release
+//! held keys and mouse buttons. The CLI then finishes like any failed
+//! command, and `run` still writes its report, trace and uploads. A second
+//! Ctrl-C exits at once with status 130.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, Once};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::commands::gen_id;
+use crate::connection::{daemon_ready, send_command};
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+static SESSIONS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+static INSTALL: Once = Once::new();
+
+/// Cancel the commands in flight in `sessions` on Ctrl-C. Later calls
+/// replace the sessions.
+pub fn install(sessions: Vec<String>) {
+    *SESSIONS.lock().unwrap_or_else(|e| e.into_inner()) = sessions;
+    INSTALL.call_once(|| {
+        set_handler();
+        thread::spawn(watch);
+    });
+}
+
+/// Whether Ctrl-C has been pressed since `install`.
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+/// The signal handler only sets a flag; this thread does the talking.
+fn watch() {
+    while !requested() {
+        thread::sleep(Duration::from_millis(50));
+    }
+    let sessions = SESSIONS.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    for session in sessions.iter().filter(|s| daemon_ready(s)) {
+        let cmd = json!({ "id": gen_id(), "action": "cancel" });
+        if let Err(e) = send_command(cmd, session) {
+            tracing::debug!(session = %session, error = %e, "cancel failed");
+        }
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn on_interrupt(_: libc::c_int) {
+    if REQUESTED.swap(true, Ordering::SeqCst) {
+        // SAFETY: _exit is async-signal-safe. 130 is what shells report for
+        // a process killed by SIGINT, and E_CANCELLED's exit status
+        unsafe { libc::_exit(130) };
+    }
+}
+
+#[cfg(unix)]
+fn set_handler() {
+    // SAFETY: the handler only touches an atomic and calls _exit
+    unsafe {
+        libc::signal(
+            libc::SIGINT,
+            on_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t,
+        );
+    }
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn on_interrupt(ctrl_type: u32) -> windows_sys::Win32::Foundation::BOOL {
+    use windows_sys::Win32::System::Console::CTRL_C_EVENT;
+    // Anything but the first Ctrl-C gets the default handling, which exits
+    (ctrl_type == CTRL_C_EVENT && !REQUESTED.swap(true, Ordering::SeqCst)) as i32
+}
+
+#[cfg(windows)]
+fn set_handler() {
+    use windows_sys::Win32::System::Console::SetConsoleCtrlHandler;
+    // SAFETY: registers a handler for this process's console events
+    unsafe {
+        SetConsoleCtrlHandler(Some(on_interrupt), 1);
+    }
+}
@@ -1,15 +1,124 @@
-//! Color output utilities respecting NO_COLOR environment variable.
+//! Color output utilities.
 //!
-//! When the NO_COLOR environment variable is present (regardless of value),
-//! all color formatting is disabled per https://no-color.org/
+//! Whether to color is decided once per process by `init`, from the
+//! `--color` flag and the environment:
+//!
+//! - `--color always` / `--color never` win over everything else
+//! - `NO_COLOR` (any non-empty value) disables color, per https://no-color.org/
+//! - `CLICOLOR_FORCE` (any value but `0`) enables it even when piped
+//! - `CLICOLOR=0`, `TERM=dumb` or a non-terminal stdout disable it
+//!
+//! On Windows, ANSI output also needs virtual terminal processing, which is
+//! switched on here; consoles that refuse it get plain text.
 
 use std::env;
+use std::io::IsTerminal;
 use std::sync::OnceLock;
 
-/// Returns true if color output is enabled (NO_COLOR is NOT set)
+static COLORS_ENABLED: OnceLock<bool> = OnceLock::new();
+
+pub const CHOICES: &[&str] = &["auto", "always", "never"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse(value: &str) -> Option<ColorChoice> {
+        match value {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+}
+
+/// What `resolve` needs to know about the environment.
+struct Env {
+    no_color: bool,
+    clicolor_force: bool,
+    clicolor_off: bool,
+    dumb_term: bool,
+    is_terminal: bool,
+}
+
+impl Env {
+    fn detect() -> Env {
+        let set = |name: &str| env::var_os(name).is_some_and(|v| !v.is_empty());
+        Env {
+            no_color: set("NO_COLOR"),
+            clicolor_force: set("CLICOLOR_FORCE")
+                && env::var("CLICOLOR_FORCE").as_deref() != Ok("0"),
+            clicolor_off: env::var("CLICOLOR").as_deref() == Ok("0"),
+            dumb_term: env::var("TERM").as_deref() == Ok("dumb"),
+            is_terminal: std::io::stdout().is_terminal(),
+        }
+    }
+}
+
+fn resolve(choice: ColorChoice, env: &Env) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            if env.no_color {
+                false
+            } else if env.clicolor_force {
+                true
+            } else {
+                !env.clicolor_off && !env.dumb_term && env.is_terminal
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+fn enable_ansi_support() -> bool {
+    use windows_sys::Win32::System::Console::{
+        GetConsoleMode, GetStdHandle, SetConsoleMode, ENABLE_VIRTUAL_TERMINAL_PROCESSING,
+        STD_ERROR_HANDLE, STD_OUTPUT_HANDLE,
+    };
+
+    let mut enabled = true;
+    for std_handle in [STD_OUTPUT_HANDLE, STD_ERROR_HANDLE] {
+        // SAFETY: plain Win32 calls on the process's own standard handles
+        unsafe {
+            let handle = GetStdHandle(std_handle);
+            let mut mode = 0;
+            if GetConsoleMode(handle, &mut mode) == 0 {
+                // Not a console (redirected); nothing to enable
+                continue;
+            }
+            if mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING == 0
+                && SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) == 0
+            {
+                enabled = false;
+            }
+        }
+    }
+    enabled
+}
+
+#[cfg(not(windows))]
+fn enable_ansi_support() -> bool {
+    true
+}
+
+/// Decide whether to color output. Call once at startup, before anything is
+/// printed; later calls have no effect.
+pub fn init(choice: ColorChoice) {
+    let enabled = resolve(choice, &Env::detect()) && enable_ansi_support();
+    let _ = COLORS_ENABLED.set(enabled);
+}
+
+/// Returns true if color output is enabled
 pub fn is_enabled() -> bool {
-    static COLORS_ENABLED: OnceLock<bool> = OnceLock::new();
-    *COLORS_ENABLED.get_or_init(|| env::var("NO_COLOR").is_err())
+    *COLORS_ENABLED
+        .get_or_init(|| resolve(ColorChoice::Auto, &Env::detect()) && enable_ansi_support())
 }
 
 /// Format text in red (errors)
@@ -148,6 +257,46 @@ mod tests {
         assert!(console_level_prefix("log").contains("log"));
     }
 
+    #[test]
+    fn test_resolve_color_choice() {
+        let tty = Env {
+            no_color: false,
+            clicolor_force: false,
+            clicolor_off: false,
+            dumb_term: false,
+            is_terminal: true,
+        };
+        assert!(resolve(ColorChoice::Auto, &tty));
+        assert!(!resolve(ColorChoice::Never, &tty));
+
+        let piped = Env {
+            is_terminal: false,
+            ..tty
+        };
+        assert!(!resolve(ColorChoice::Auto, &piped));
+        assert!(resolve(ColorChoice::Always, &piped));
+
+        let forced = Env {
+            clicolor_force: true,
+            ..piped
+        };
+        assert!(resolve(ColorChoice::Auto, &forced));
+
+        // NO_COLOR beats CLICOLOR_FORCE, but not an explicit --color always
+        let both = Env {
+            no_color: true,
+            ..forced
+        };
+        assert!(!resolve(ColorChoice::Auto, &both));
+        assert!(resolve(ColorChoice::Always, &both));
+
+        let dumb = Env {
+            dumb_term: true,
+            ..tty
+        };
+        assert!(!resolve(ColorChoice::Auto, &dumb));
+    }
+
     #[test]
     fn test_indicators_contain_symbols() {
         // Regardless of color state, symbols should be present
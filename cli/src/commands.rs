@@ -2,7 +2,7 @@ use base64::{engine::general_purpose::STANDARD, Engine};
 use serde_json::{json, Value};
 use std::io::{self, BufRead};
 
-use crate::flags::Flags;
+use crate::flags::{parse_duration_ms, Flags};
 
 /// Error type for command parsing with contextual information
 #[derive(Debug)]
@@ -81,21 +81,11 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
     match cmd {
         // === Navigation ===
         "open" | "goto" | "navigate" => {
-            let url = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let url = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: cmd.to_string(),
                 usage: "open <url>",
             })?;
-            let url_lower = url.to_lowercase();
-            let url = if url_lower.starts_with("http://")
-                || url_lower.starts_with("https://")
-                || url_lower.starts_with("about:")
-                || url_lower.starts_with("data:")
-                || url_lower.starts_with("file:")
-            {
-                url.to_string()
-            } else {
-                format!("https://{}", url)
-            };
+            let url = normalize_url(url);
             let mut nav_cmd = json!({ "id": id, "action": "navigate", "url": url });
             // If --headers flag is set, include headers (scoped to this origin)
             if let Some(ref headers_json) = flags.headers {
@@ -117,63 +107,63 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
 
         // === Core Actions ===
         "click" => {
-            let sel = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "click".to_string(),
                 usage: "click <selector>",
             })?;
             Ok(json!({ "id": id, "action": "click", "selector": sel }))
         }
         "dblclick" => {
-            let sel = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "dblclick".to_string(),
                 usage: "dblclick <selector>",
             })?;
             Ok(json!({ "id": id, "action": "dblclick", "selector": sel }))
         }
         "fill" => {
-            let sel = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "fill".to_string(),
                 usage: "fill <selector> <text>",
             })?;
             Ok(json!({ "id": id, "action": "fill", "selector": sel, "value": rest[1..].join(" ") }))
         }
         "type" => {
-            let sel = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "type".to_string(),
                 usage: "type <selector> <text>",
             })?;
             Ok(json!({ "id": id, "action": "type", "selector": sel, "text": rest[1..].join(" ") }))
         }
         "hover" => {
-            let sel = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "hover".to_string(),
                 usage: "hover <selector>",
             })?;
             Ok(json!({ "id": id, "action": "hover", "selector": sel }))
         }
         "focus" => {
-            let sel = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "focus".to_string(),
                 usage: "focus <selector>",
             })?;
             Ok(json!({ "id": id, "action": "focus", "selector": sel }))
         }
         "check" => {
-            let sel = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "check".to_string(),
                 usage: "check <selector>",
             })?;
             Ok(json!({ "id": id, "action": "check", "selector": sel }))
         }
         "uncheck" => {
-            let sel = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "uncheck".to_string(),
                 usage: "uncheck <selector>",
             })?;
             Ok(json!({ "id": id, "action": "uncheck", "selector": sel }))
         }
         "select" => {
-            let sel = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "select".to_string(),
                 usage: "select <selector> <value...>",
             })?;
@@ -189,7 +179,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
             }
         }
         "drag" => {
-            let src = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let src = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "drag".to_string(),
                 usage: "drag <source> <target>",
             })?;
@@ -200,14 +190,14 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
             Ok(json!({ "id": id, "action": "drag", "source": src, "target": tgt }))
         }
         "upload" => {
-            let sel = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "upload".to_string(),
                 usage: "upload <selector> <files...>",
             })?;
             Ok(json!({ "id": id, "action": "upload", "selector": sel, "files": &rest[1..] }))
         }
         "download" => {
-            let sel = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "download".to_string(),
                 usage: "download <selector> <path>",
             })?;
@@ -220,21 +210,21 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
 
         // === Keyboard ===
         "press" | "key" => {
-            let key = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let key = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "press".to_string(),
                 usage: "press <key>",
             })?;
             Ok(json!({ "id": id, "action": "press", "key": key }))
         }
         "keydown" => {
-            let key = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let key = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "keydown".to_string(),
                 usage: "keydown <key>",
             })?;
             Ok(json!({ "id": id, "action": "keydown", "key": key }))
         }
         "keyup" => {
-            let key = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let key = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "keyup".to_string(),
                 usage: "keyup <key>",
             })?;
@@ -243,7 +233,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
 
         // === Scroll ===
         "scroll" => {
-            let dir = rest.get(0).unwrap_or(&"down");
+            let dir = rest.first().unwrap_or(&"down");
             let amount = rest
                 .get(1)
                 .and_then(|s| s.parse::<i32>().ok())
@@ -251,7 +241,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
             Ok(json!({ "id": id, "action": "scroll", "direction": dir, "amount": amount }))
         }
         "scrollintoview" | "scrollinto" => {
-            let sel = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "scrollintoview".to_string(),
                 usage: "scrollintoview <selector>",
             })?;
@@ -320,19 +310,33 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                         cmd["path"] = json!(path);
                     }
                 }
-                // Check for optional timeout
-                if let Some(idx) = rest.iter().position(|&s| s == "--timeout") {
-                    if let Some(timeout_str) = rest.get(idx + 1) {
-                        if let Ok(timeout) = timeout_str.parse::<u64>() {
-                            cmd["timeout"] = json!(timeout);
-                        }
-                    }
-                }
+                apply_wait_options(&mut cmd, &rest);
+                return Ok(cmd);
+            }
+
+            // Check for --popup flag: wait --popup [--arm] [--timeout ms]
+            if rest.contains(&"--popup") {
+                let mut cmd = json!({ "id": id, "action": "waitforpopup" });
+                apply_wait_options(&mut cmd, &rest);
+                return Ok(cmd);
+            }
+
+            // Check for --request flag: wait --request <pattern> [--arm] [--timeout ms]
+            if let Some(idx) = rest.iter().position(|&s| s == "--request") {
+                let url = rest
+                    .get(idx + 1)
+                    .filter(|s| !s.starts_with("--"))
+                    .ok_or_else(|| ParseError::MissingArguments {
+                        context: "wait --request".to_string(),
+                        usage: "wait --request <pattern> [--arm]",
+                    })?;
+                let mut cmd = json!({ "id": id, "action": "waitforrequest", "url": url });
+                apply_wait_options(&mut cmd, &rest);
                 return Ok(cmd);
             }
 
             // Default: selector or timeout
-            if let Some(arg) = rest.get(0) {
+            if let Some(arg) = rest.first() {
                 if arg.parse::<u64>().is_ok() {
                     Ok(
                         json!({ "id": id, "action": "wait", "timeout": arg.parse::<u64>().unwrap() }),
@@ -343,7 +347,8 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
             } else {
                 Err(ParseError::MissingArguments {
                     context: "wait".to_string(),
-                    usage: "wait <selector|ms|--url|--load|--fn|--text>",
+                    usage:
+                        "wait <selector|ms|--url|--load|--fn|--text|--download|--popup|--request>",
                 })
             }
         }
@@ -353,7 +358,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
             // screenshot [selector] [path]
             // selector: @ref or CSS selector
             // path: file path (contains / or . or ends with known extension)
-            let (selector, path) = match (rest.get(0), rest.get(1)) {
+            let (selector, path) = match (rest.first(), rest.get(1)) {
                 (Some(first), Some(second)) => {
                     // Two args: first is selector, second is path
                     (Some(*first), Some(*second))
@@ -382,12 +387,127 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                 json!({ "id": id, "action": "screenshot", "path": path, "selector": selector, "fullPage": flags.full }),
             )
         }
-        "pdf" => {
-            let path = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
-                context: "pdf".to_string(),
-                usage: "pdf <path>",
+        "compare-screenshot" => {
+            const USAGE: &str = "compare-screenshot --baseline <file> [selector] [--threshold <0-1>] [--max-diff <ratio>] [--mask <selector>]... [--diff <file>] [--update-baseline]";
+            let value = |flag: &str| {
+                rest.iter()
+                    .position(|&s| s == flag)
+                    .and_then(|i| rest.get(i + 1).copied())
+            };
+            let baseline = value("--baseline").ok_or_else(|| ParseError::MissingArguments {
+                context: "compare-screenshot".to_string(),
+                usage: USAGE,
             })?;
-            Ok(json!({ "id": id, "action": "pdf", "path": path }))
+            let ratio = |flag: &str| -> Result<Option<f64>, ParseError> {
+                value(flag)
+                    .map(|v| {
+                        v.parse::<f64>()
+                            .ok()
+                            .filter(|n| (0.0..=1.0).contains(n))
+                            .ok_or_else(|| ParseError::InvalidValue {
+                                message: format!("{} must be between 0 and 1: {}", flag, v),
+                                usage: USAGE,
+                            })
+                    })
+                    .transpose()
+            };
+            let threshold = ratio("--threshold")?.unwrap_or(0.1);
+            let max_diff = ratio("--max-diff")?.unwrap_or(0.0);
+            let masks: Vec<&str> = rest
+                .windows(2)
+                .filter(|w| w[0] == "--mask")
+                .map(|w| w[1])
+                .collect();
+            let absolute = |p: &str| std::path::absolute(p).unwrap_or_else(|_| p.into());
+            let mut compare = json!({
+                "baseline": absolute(baseline),
+                "threshold": threshold,
+                "maxDiffRatio": max_diff,
+                "updateBaseline": rest.contains(&"--update-baseline"),
+            });
+            if let Some(diff) = value("--diff") {
+                compare["diff"] = json!(absolute(diff));
+            }
+            // The selector is the only positional argument left once flag values are removed
+            let value_flags = [
+                "--baseline",
+                "--threshold",
+                "--max-diff",
+                "--mask",
+                "--diff",
+            ];
+            let selector = rest.iter().enumerate().find_map(|(i, a)| {
+                let is_value = i > 0 && value_flags.contains(&rest[i - 1]);
+                (!a.starts_with("--") && !is_value).then_some(*a)
+            });
+            // The CLI compares once the daemon has saved the screenshot here
+            let path = std::env::temp_dir()
+                .join(format!("agent-browser-compare-{}.png", std::process::id()));
+            Ok(json!({
+                "id": id,
+                "action": "screenshot",
+                "path": path,
+                "selector": selector,
+                "fullPage": flags.full,
+                "mask": masks,
+                "compare": compare,
+            }))
+        }
+        "pdf" => {
+            // Without a path the PDF goes to the session's artifacts directory
+            let mut cmd = json!({ "id": id, "action": "pdf" });
+            if let Some(path) = rest.first() {
+                cmd["path"] = json!(path);
+            }
+            Ok(cmd)
+        }
+        "archive" => {
+            const USAGE: &str = "archive [--format mhtml|warc] [path]";
+            let mut format = None;
+            let mut path = flags.out.as_deref();
+            let mut i = 0;
+            while i < rest.len() {
+                match rest[i] {
+                    "--format" => {
+                        i += 1;
+                        format =
+                            Some(*rest.get(i).ok_or_else(|| ParseError::MissingArguments {
+                                context: "archive --format".to_string(),
+                                usage: USAGE,
+                            })?);
+                    }
+                    arg => path = Some(arg),
+                }
+                i += 1;
+            }
+            // The format follows the file's extension unless given
+            let format = match format {
+                Some(f @ ("mhtml" | "warc")) => f,
+                Some(other) => {
+                    return Err(ParseError::InvalidValue {
+                        message: format!(
+                            "Unknown archive format: {} (expected mhtml or warc)",
+                            other
+                        ),
+                        usage: USAGE,
+                    })
+                }
+                None if path.is_some_and(|p| p.to_ascii_lowercase().ends_with(".warc")) => "warc",
+                None => "mhtml",
+            };
+            let mut cmd = json!({ "id": id, "action": "archive", "format": format });
+            if let Some(path) = path {
+                cmd["path"] = json!(std::path::absolute(path).unwrap_or_else(|_| path.into()));
+            }
+            Ok(cmd)
+        }
+        "freeze" => {
+            // Without a path the page goes to the session's artifacts directory
+            let mut cmd = json!({ "id": id, "action": "freeze" });
+            if let Some(path) = rest.first().copied().or(flags.out.as_deref()) {
+                cmd["path"] = json!(std::path::absolute(path).unwrap_or_else(|_| path.into()));
+            }
+            Ok(cmd)
         }
 
         // === Snapshot ===
@@ -406,6 +526,9 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                     "-C" | "--cursor" => {
                         obj.insert("cursor".to_string(), json!(true));
                     }
+                    "--fresh" => {
+                        obj.insert("fresh".to_string(), json!(true));
+                    }
                     "-d" | "--depth" => {
                         if let Some(d) = rest.get(i + 1) {
                             if let Ok(n) = d.parse::<i32>() {
@@ -429,6 +552,17 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
 
         // === Eval ===
         "eval" => {
+            // A leading --target <id> runs the script in a tab or worker from `targets`
+            let (target, rest) = if rest.first() == Some(&"--target") {
+                let target = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
+                    context: "eval --target".to_string(),
+                    usage: "eval --target <id> <js>",
+                })?;
+                (Some(*target), &rest[2..])
+            } else {
+                (None, rest.as_slice())
+            };
+
             // Check for flags: -b/--base64 or --stdin
             let (is_base64, is_stdin, script_parts): (bool, bool, &[&str]) =
                 if rest.first() == Some(&"-b") || rest.first() == Some(&"--base64") {
@@ -436,23 +570,28 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                 } else if rest.first() == Some(&"--stdin") {
                     (false, true, &rest[1..])
                 } else {
-                    (false, false, rest.as_slice())
+                    (false, false, rest)
                 };
 
             let script = if is_stdin {
                 // Read script from stdin
                 let stdin = io::stdin();
-                let lines: Vec<String> = stdin.lock().lines()
+                let lines: Vec<String> = stdin
+                    .lock()
+                    .lines()
                     .map(|l| l.unwrap_or_default())
                     .collect();
                 lines.join("\n")
             } else {
                 let raw_script = script_parts.join(" ");
                 if is_base64 {
-                    let decoded = STANDARD.decode(&raw_script).map_err(|_| ParseError::InvalidValue {
-                        message: "Invalid base64 encoding".to_string(),
-                        usage: "eval -b <base64-encoded-script>",
-                    })?;
+                    let decoded =
+                        STANDARD
+                            .decode(&raw_script)
+                            .map_err(|_| ParseError::InvalidValue {
+                                message: "Invalid base64 encoding".to_string(),
+                                usage: "eval -b <base64-encoded-script>",
+                            })?;
                     String::from_utf8(decoded).map_err(|_| ParseError::InvalidValue {
                         message: "Base64 decoded to invalid UTF-8".to_string(),
                         usage: "eval -b <base64-encoded-script>",
@@ -461,7 +600,11 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                     raw_script
                 }
             };
-            Ok(json!({ "id": id, "action": "evaluate", "script": script }))
+            let mut cmd = json!({ "id": id, "action": "evaluate", "script": script });
+            if let Some(target) = target {
+                cmd["target"] = json!(target);
+            }
+            Ok(cmd)
         }
 
         // === Close ===
@@ -483,7 +626,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
             } else {
                 // It's a port number - validate and use cdpPort field
                 let port: u16 = match endpoint.parse::<u32>() {
-                    Ok(p) if p == 0 => {
+                    Ok(0) => {
                         return Err(ParseError::InvalidValue {
                             message: "Invalid port: port must be greater than 0".to_string(),
                             usage: "connect <port|url>",
@@ -529,14 +672,14 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
         "set" => parse_set(&rest, &id),
 
         // === Network ===
-        "network" => parse_network(&rest, &id),
+        "network" | "net" => parse_network(&rest, &id, flags.out.as_deref()),
 
         // === Storage ===
         "storage" => parse_storage(&rest, &id),
 
         // === Cookies ===
         "cookies" => {
-            let op = rest.get(0).unwrap_or(&"get");
+            let op = rest.first().unwrap_or(&"get");
             match *op {
                 "set" => {
                     let name = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
@@ -650,7 +793,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
         }
 
         // === Tabs ===
-        "tab" => match rest.get(0).map(|s| *s) {
+        "tab" => match rest.first().copied() {
             Some("new") => {
                 let mut cmd = json!({ "id": id, "action": "tab_new" });
                 if let Some(url) = rest.get(1) {
@@ -672,10 +815,213 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
             _ => Ok(json!({ "id": id, "action": "tab_list" })),
         },
 
+        "audit" => match rest.first().copied() {
+            Some(kind @ ("perf" | "security")) => {
+                let mut cmd = json!({ "id": id, "action": format!("audit_{}", kind) });
+                if let Some(url) = rest.get(1) {
+                    cmd["url"] = json!(normalize_url(url));
+                }
+                Ok(cmd)
+            }
+            Some("seo") => {
+                const USAGE: &str = "audit seo [url] [--links <n>]";
+                let mut cmd = json!({ "id": id, "action": "audit_seo" });
+                let mut i = 1;
+                while i < rest.len() {
+                    if rest[i] == "--links" {
+                        let n = rest
+                            .get(i + 1)
+                            .ok_or_else(|| ParseError::MissingArguments {
+                                context: "audit seo --links".to_string(),
+                                usage: USAGE,
+                            })?;
+                        let n: u32 = n.parse().map_err(|_| ParseError::InvalidValue {
+                            message: format!("--links must be a number, got '{}'", n),
+                            usage: USAGE,
+                        })?;
+                        cmd["links"] = json!(n);
+                        i += 2;
+                    } else {
+                        cmd["url"] = json!(normalize_url(rest[i]));
+                        i += 1;
+                    }
+                }
+                Ok(cmd)
+            }
+            Some(sub) => Err(ParseError::UnknownSubcommand {
+                subcommand: sub.to_string(),
+                valid_options: &["perf", "seo", "security"],
+            }),
+            None => Err(ParseError::MissingArguments {
+                context: "audit".to_string(),
+                usage: "audit <perf|seo|security> [url]",
+            }),
+        },
+
+        "clock" => {
+            const USAGE: &str =
+                "clock set <time> [--tick] [--timezone <tz>] | clock advance <duration> | clock resume";
+            match rest.first().copied() {
+                Some("set") => {
+                    let time = rest
+                        .get(1)
+                        .filter(|t| !t.starts_with("--"))
+                        .ok_or_else(|| ParseError::MissingArguments {
+                            context: "clock set".to_string(),
+                            usage: USAGE,
+                        })?;
+                    let mut cmd = json!({ "id": id, "action": "clock_set", "time": time });
+                    if rest.contains(&"--tick") {
+                        cmd["tick"] = json!(true);
+                    }
+                    if let Some(i) = rest.iter().position(|&s| s == "--timezone") {
+                        let tz = rest
+                            .get(i + 1)
+                            .ok_or_else(|| ParseError::MissingArguments {
+                                context: "clock set --timezone".to_string(),
+                                usage: USAGE,
+                            })?;
+                        cmd["timezone"] = json!(tz);
+                    }
+                    Ok(cmd)
+                }
+                Some("advance") => {
+                    let raw = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
+                        context: "clock advance".to_string(),
+                        usage: USAGE,
+                    })?;
+                    let ms = parse_duration_ms(raw).filter(|&ms| ms > 0).ok_or_else(|| {
+                        ParseError::InvalidValue {
+                            message: format!(
+                                "Invalid duration: {} (expected e.g. 30s, 10m, 2h, 14d)",
+                                raw
+                            ),
+                            usage: USAGE,
+                        }
+                    })?;
+                    Ok(json!({ "id": id, "action": "clock_advance", "ms": ms }))
+                }
+                Some("resume") => Ok(json!({ "id": id, "action": "clock_resume" })),
+                Some(sub) => Err(ParseError::UnknownSubcommand {
+                    subcommand: sub.to_string(),
+                    valid_options: &["set", "advance", "resume"],
+                }),
+                None => Err(ParseError::MissingArguments {
+                    context: "clock".to_string(),
+                    usage: USAGE,
+                }),
+            }
+        }
+
+        "schedule" => {
+            const USAGE: &str =
+                "schedule add \"<cron>\" <command...> | schedule list | schedule remove <id> | schedule logs <id>";
+            let job_id = |sub: &str| {
+                rest.get(1).ok_or_else(|| ParseError::MissingArguments {
+                    context: format!("schedule {}", sub),
+                    usage: USAGE,
+                })
+            };
+            match rest.first().copied() {
+                Some("add") => {
+                    let (Some(cron), Some(_)) = (rest.get(1), rest.get(2)) else {
+                        return Err(ParseError::MissingArguments {
+                            context: "schedule add".to_string(),
+                            usage: USAGE,
+                        });
+                    };
+                    // Jobs run in their own directory, so files named here need full paths
+                    let command: Vec<String> = rest[2..]
+                        .iter()
+                        .map(|arg| match std::path::absolute(arg) {
+                            Ok(path) if !arg.starts_with('-') && path.exists() => {
+                                path.display().to_string()
+                            }
+                            _ => arg.to_string(),
+                        })
+                        .collect();
+                    Ok(json!({
+                        "id": id,
+                        "action": "schedule_add",
+                        "cron": cron,
+                        "command": command,
+                    }))
+                }
+                Some("list") => Ok(json!({ "id": id, "action": "schedule_list" })),
+                Some("remove") => {
+                    Ok(json!({ "id": id, "action": "schedule_remove", "jobId": job_id("remove")? }))
+                }
+                Some("logs") => {
+                    Ok(json!({ "id": id, "action": "schedule_logs", "jobId": job_id("logs")? }))
+                }
+                Some(sub) => Err(ParseError::UnknownSubcommand {
+                    subcommand: sub.to_string(),
+                    valid_options: &["add", "list", "remove", "logs"],
+                }),
+                None => Err(ParseError::MissingArguments {
+                    context: "schedule".to_string(),
+                    usage: USAGE,
+                }),
+            }
+        }
+
+        "jobs" => Ok(json!({ "id": id, "action": "jobs" })),
+
+        "cancel" => Ok(json!({ "id": id, "action": "cancel" })),
+        "lanes" => Ok(json!({ "id": id, "action": "lanes" })),
+        "memory" => Ok(json!({ "id": id, "action": "memory" })),
+
+        "which" => {
+            let mut cmd = json!({ "id": id, "action": "which" });
+            if flags.cli_executable_path {
+                if let Some(ref path) = flags.executable_path {
+                    cmd["executablePath"] = json!(path);
+                }
+            }
+            if let Some(ref browser) = flags.browser {
+                cmd["channel"] = json!(browser);
+            }
+            Ok(cmd)
+        }
+
+        "tls" => match rest.first().copied() {
+            Some("info") => {
+                let url = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
+                    context: "tls info".to_string(),
+                    usage: "tls info <url>",
+                })?;
+                Ok(json!({ "id": id, "action": "tls_info", "url": normalize_url(url) }))
+            }
+            Some(sub) => Err(ParseError::UnknownSubcommand {
+                subcommand: sub.to_string(),
+                valid_options: &["info"],
+            }),
+            None => Err(ParseError::MissingArguments {
+                context: "tls".to_string(),
+                usage: "tls info <url>",
+            }),
+        },
+
+        "robots" => {
+            let url = rest.first().ok_or_else(|| ParseError::MissingArguments {
+                context: "robots".to_string(),
+                usage: "robots <url>",
+            })?;
+            Ok(json!({ "id": id, "action": "robots", "url": normalize_url(url) }))
+        }
+
+        "targets" => match rest.first().copied() {
+            None | Some("list") => Ok(json!({ "id": id, "action": "targets" })),
+            Some(sub) => Err(ParseError::UnknownSubcommand {
+                subcommand: sub.to_string(),
+                valid_options: &["list"],
+            }),
+        },
+
         // === Window ===
         "window" => {
             const VALID: &[&str] = &["new"];
-            match rest.get(0).map(|s| *s) {
+            match rest.first().copied() {
                 Some("new") => Ok(json!({ "id": id, "action": "window_new" })),
                 Some(sub) => Err(ParseError::UnknownSubcommand {
                     subcommand: sub.to_string(),
@@ -690,10 +1036,10 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
 
         // === Frame ===
         "frame" => {
-            if rest.get(0).map(|s| *s) == Some("main") {
+            if rest.first().copied() == Some("main") {
                 Ok(json!({ "id": id, "action": "mainframe" }))
             } else {
-                let sel = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+                let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                     context: "frame".to_string(),
                     usage: "frame <selector|main>",
                 })?;
@@ -704,7 +1050,7 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
         // === Dialog ===
         "dialog" => {
             const VALID: &[&str] = &["accept", "dismiss"];
-            match rest.get(0).map(|s| *s) {
+            match rest.first().copied() {
                 Some("accept") => {
                     let mut cmd = json!({ "id": id, "action": "dialog", "response": "accept" });
                     if let Some(prompt_text) = rest.get(1) {
@@ -723,17 +1069,45 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
             }
         }
 
+        "selectors" => {
+            const VALID: &[&str] = &["suggest", "heal"];
+            match rest.first().copied() {
+                Some("suggest") => {
+                    let target = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
+                        context: "selectors suggest".to_string(),
+                        usage: "selectors suggest <ref|selector>",
+                    })?;
+                    Ok(json!({ "id": id, "action": "selectors_suggest", "selector": target }))
+                }
+                Some("heal") => {
+                    let target = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
+                        context: "selectors heal".to_string(),
+                        usage: "selectors heal <ref|selector>",
+                    })?;
+                    Ok(json!({ "id": id, "action": "selectors_heal", "selector": target }))
+                }
+                Some(sub) => Err(ParseError::UnknownSubcommand {
+                    subcommand: sub.to_string(),
+                    valid_options: VALID,
+                }),
+                None => Err(ParseError::MissingArguments {
+                    context: "selectors".to_string(),
+                    usage: "selectors <suggest|heal> <ref|selector>",
+                }),
+            }
+        }
+
         // === Debug ===
         "trace" => {
             const VALID: &[&str] = &["start", "stop"];
-            match rest.get(0).map(|s| *s) {
+            match rest.first().copied() {
                 Some("start") => Ok(json!({ "id": id, "action": "trace_start" })),
                 Some("stop") => {
-                    let path = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
-                        context: "trace stop".to_string(),
-                        usage: "trace stop <path>",
-                    })?;
-                    Ok(json!({ "id": id, "action": "trace_stop", "path": path }))
+                    let mut cmd = json!({ "id": id, "action": "trace_stop" });
+                    if let Some(path) = rest.get(1) {
+                        cmd["path"] = json!(path);
+                    }
+                    Ok(cmd)
                 }
                 Some(sub) => Err(ParseError::UnknownSubcommand {
                     subcommand: sub.to_string(),
@@ -746,10 +1120,139 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
             }
         }
 
+        // === HAR replay ===
+        "replay" => {
+            const USAGE: &str =
+                "replay --har <file> [url] [--not-found <abort|fallback>] [--match <pattern>]";
+            let value = |flag: &str| {
+                rest.iter()
+                    .position(|&s| s == flag)
+                    .and_then(|i| rest.get(i + 1).copied())
+            };
+            let har = value("--har").ok_or_else(|| ParseError::MissingArguments {
+                context: "replay".to_string(),
+                usage: USAGE,
+            })?;
+            let har = std::path::absolute(har).unwrap_or_else(|_| har.into());
+            let mut cmd = json!({ "id": id, "action": "har_replay", "path": har });
+            if let Some(not_found) = value("--not-found") {
+                if !matches!(not_found, "abort" | "fallback") {
+                    return Err(ParseError::InvalidValue {
+                        message: format!(
+                            "Invalid --not-found value: {} (use abort or fallback)",
+                            not_found
+                        ),
+                        usage: USAGE,
+                    });
+                }
+                cmd["notFound"] = json!(not_found);
+            }
+            if let Some(pattern) = value("--match") {
+                cmd["match"] = json!(pattern);
+            }
+            // The URL is the only positional argument left once flag values are removed
+            let flag_values = ["--har", "--not-found", "--match"].map(value);
+            if let Some(url) = rest
+                .iter()
+                .find(|a| !a.starts_with("--") && !flag_values.contains(&Some(**a)))
+            {
+                cmd["url"] = json!(normalize_url(url));
+            }
+            Ok(cmd)
+        }
+
+        // === WebSocket inspection ===
+        "ws" => {
+            const VALID: &[&str] = &["list", "tail", "export", "clear"];
+            match rest.first().copied() {
+                Some("list") | None => Ok(json!({ "id": id, "action": "ws", "op": "list" })),
+                Some("tail") => {
+                    let socket = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
+                        context: "ws tail".to_string(),
+                        usage: "ws tail <id> [-n <count>]",
+                    })?;
+                    let mut cmd =
+                        json!({ "id": id, "action": "ws", "op": "tail", "socket": socket });
+                    if let Some(i) = rest.iter().position(|&s| s == "-n" || s == "--count") {
+                        let count = rest
+                            .get(i + 1)
+                            .and_then(|s| s.parse::<u32>().ok())
+                            .filter(|&n| n > 0)
+                            .ok_or_else(|| ParseError::InvalidValue {
+                                message: "Count must be a positive number".to_string(),
+                                usage: "ws tail <id> [-n <count>]",
+                            })?;
+                        cmd["count"] = json!(count);
+                    }
+                    Ok(cmd)
+                }
+                Some("export") => {
+                    let path = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
+                        context: "ws export".to_string(),
+                        usage: "ws export <path>",
+                    })?;
+                    Ok(json!({ "id": id, "action": "ws", "op": "export", "path": path }))
+                }
+                Some("clear") => Ok(json!({ "id": id, "action": "ws", "op": "clear" })),
+                Some(sub) => Err(ParseError::UnknownSubcommand {
+                    subcommand: sub.to_string(),
+                    valid_options: VALID,
+                }),
+            }
+        }
+
+        // === Input timelines ===
+        "input" => {
+            const VALID: &[&str] = &["record", "stop", "replay"];
+            const USAGE: &str = "input <record <file>|stop|replay <file> [--speed <n>]>";
+            let path = |op: &str| {
+                rest.get(1)
+                    .map(|p| std::path::absolute(p).unwrap_or_else(|_| p.into()))
+                    .ok_or_else(|| ParseError::MissingArguments {
+                        context: format!("input {}", op),
+                        usage: USAGE,
+                    })
+            };
+            match rest.first().copied() {
+                Some("record") => Ok(
+                    json!({ "id": id, "action": "input", "op": "record", "path": path("record")? }),
+                ),
+                Some("stop") => Ok(json!({ "id": id, "action": "input", "op": "stop" })),
+                Some("replay") => {
+                    let mut cmd = json!({
+                        "id": id,
+                        "action": "input",
+                        "op": "replay",
+                        "path": path("replay")?,
+                    });
+                    if let Some(i) = rest.iter().position(|&s| s == "--speed") {
+                        let speed = rest
+                            .get(i + 1)
+                            .and_then(|s| s.parse::<f64>().ok())
+                            .filter(|&n| n > 0.0)
+                            .ok_or_else(|| ParseError::InvalidValue {
+                                message: "Speed must be a positive number".to_string(),
+                                usage: USAGE,
+                            })?;
+                        cmd["speed"] = json!(speed);
+                    }
+                    Ok(cmd)
+                }
+                Some(sub) => Err(ParseError::UnknownSubcommand {
+                    subcommand: sub.to_string(),
+                    valid_options: VALID,
+                }),
+                None => Err(ParseError::MissingArguments {
+                    context: "input".to_string(),
+                    usage: USAGE,
+                }),
+            }
+        }
+
         // === Recording (Playwright native video recording) ===
         "record" => {
             const VALID: &[&str] = &["start", "stop", "restart"];
-            match rest.get(0).map(|s| *s) {
+            match rest.first().copied() {
                 Some("start") => {
                     let path = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
                         context: "record start".to_string(),
@@ -769,7 +1272,24 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                     }
                     Ok(cmd)
                 }
-                Some("stop") => Ok(json!({ "id": id, "action": "recording_stop" })),
+                Some("stop") => {
+                    let mut cmd = json!({ "id": id, "action": "recording_stop" });
+                    // Handled by the CLI once the daemon has saved the video
+                    if let Some(i) = rest.iter().position(|&a| a == "--upload") {
+                        let usage =
+                            "record stop [--upload <s3://bucket/prefix|gs://bucket/prefix>]";
+                        let destination =
+                            rest.get(i + 1)
+                                .ok_or_else(|| ParseError::MissingArguments {
+                                    context: "record stop --upload".to_string(),
+                                    usage,
+                                })?;
+                        crate::upload::Destination::parse(destination)
+                            .map_err(|message| ParseError::InvalidValue { message, usage })?;
+                        cmd["upload"] = json!(destination);
+                    }
+                    Ok(cmd)
+                }
                 Some("restart") => {
                     let path = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
                         context: "record restart".to_string(),
@@ -799,26 +1319,333 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
                 }),
             }
         }
+        "screencast" => {
+            const VALID: &[&str] = &["start", "stop"];
+            const USAGE: &str = "screencast start [<file|pipe>] [--quality <0-100>] [--scale <0.05-1>] [--fps <n>] [--format jpeg|png]";
+            match rest.first().copied() {
+                Some("start") => {
+                    let options = &rest[1..];
+                    let value = |flag: &str| {
+                        options
+                            .iter()
+                            .position(|&s| s == flag)
+                            .and_then(|i| options.get(i + 1).copied())
+                    };
+                    let number = |flag: &str, min: f64, max: f64| {
+                        value(flag)
+                            .map(|raw| {
+                                raw.parse::<f64>()
+                                    .ok()
+                                    .filter(|n| *n >= min && *n <= max)
+                                    .ok_or_else(|| ParseError::InvalidValue {
+                                        message: format!(
+                                            "{} must be between {} and {}: {}",
+                                            flag, min, max, raw
+                                        ),
+                                        usage: USAGE,
+                                    })
+                            })
+                            .transpose()
+                    };
+                    let mut cmd = json!({ "id": id, "action": "screencast_start" });
+                    if let Some(quality) = number("--quality", 0.0, 100.0)? {
+                        cmd["quality"] = json!(quality.round() as u64);
+                    }
+                    if let Some(scale) = number("--scale", 0.05, 1.0)? {
+                        cmd["scale"] = json!(scale);
+                    }
+                    if let Some(fps) = number("--fps", 0.1, 60.0)? {
+                        cmd["fps"] = json!(fps);
+                    }
+                    match value("--format") {
+                        Some(format @ ("jpeg" | "png")) => cmd["format"] = json!(format),
+                        Some(other) => {
+                            return Err(ParseError::InvalidValue {
+                                message: format!("--format must be jpeg or png: {}", other),
+                                usage: USAGE,
+                            })
+                        }
+                        None => {}
+                    }
+                    // The first argument that isn't an option or its value
+                    let mut i = 0;
+                    while i < options.len() {
+                        if options[i].starts_with("--") {
+                            i += 2;
+                            continue;
+                        }
+                        let path = options[i];
+                        cmd["path"] =
+                            json!(std::path::absolute(path).unwrap_or_else(|_| path.into()));
+                        break;
+                    }
+                    Ok(cmd)
+                }
+                Some("stop") => Ok(json!({ "id": id, "action": "screencast_stop" })),
+                Some(sub) => Err(ParseError::UnknownSubcommand {
+                    subcommand: sub.to_string(),
+                    valid_options: VALID,
+                }),
+                None => Err(ParseError::MissingArguments {
+                    context: "screencast".to_string(),
+                    usage: USAGE,
+                }),
+            }
+        }
         "console" => {
-            let clear = rest.iter().any(|&s| s == "--clear");
+            let clear = rest.contains(&"--clear");
             Ok(json!({ "id": id, "action": "console", "clear": clear }))
         }
         "errors" => {
-            let clear = rest.iter().any(|&s| s == "--clear");
+            let clear = rest.contains(&"--clear");
             Ok(json!({ "id": id, "action": "errors", "clear": clear }))
         }
         "highlight" => {
-            let sel = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "highlight".to_string(),
                 usage: "highlight <selector>",
             })?;
             Ok(json!({ "id": id, "action": "highlight", "selector": sel }))
         }
+        "inspect" => {
+            let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
+                context: "inspect".to_string(),
+                usage: "inspect <selector>",
+            })?;
+            Ok(json!({ "id": id, "action": "inspect", "selector": sel }))
+        }
+        "dismiss-overlays" => Ok(json!({ "id": id, "action": "dismiss_overlays" })),
+        "stats" => {
+            let reset = rest.contains(&"--reset");
+            Ok(json!({ "id": id, "action": "stats", "reset": reset }))
+        }
+        "cdp-listen" => {
+            const USAGE: &str =
+                "cdp-listen <Domain.event>... [--follow] [--count <n>] [--timeout <duration>]";
+            let value = |flag: &str| {
+                rest.iter()
+                    .position(|&s| s == flag)
+                    .and_then(|i| rest.get(i + 1).copied())
+            };
+            let mut events = Vec::new();
+            let mut i = 0;
+            while i < rest.len() {
+                match rest[i] {
+                    "--count" | "--timeout" => i += 1,
+                    s if s.starts_with("--") => {}
+                    event if event.contains('.') => events.push(event),
+                    other => {
+                        return Err(ParseError::InvalidValue {
+                            message: format!(
+                                "Expected a protocol event like Network.responseReceived: {}",
+                                other
+                            ),
+                            usage: USAGE,
+                        })
+                    }
+                }
+                i += 1;
+            }
+            if events.is_empty() {
+                return Err(ParseError::MissingArguments {
+                    context: "cdp-listen".to_string(),
+                    usage: USAGE,
+                });
+            }
+            let follow = rest.contains(&"--follow");
+            let count = value("--count")
+                .map(|raw| {
+                    raw.parse::<u64>().ok().filter(|n| *n > 0).ok_or_else(|| {
+                        ParseError::InvalidValue {
+                            message: format!("--count must be a positive integer: {}", raw),
+                            usage: USAGE,
+                        }
+                    })
+                })
+                .transpose()?;
+            let timeout = value("--timeout")
+                .map(|raw| {
+                    parse_duration_ms(raw).ok_or_else(|| ParseError::InvalidValue {
+                        message: format!("Invalid --timeout duration: {}", raw),
+                        usage: USAGE,
+                    })
+                })
+                .transpose()?;
+            let mut cmd = json!({ "id": id, "action": "cdp_listen", "events": events });
+            // Without --follow, wait for the first matching event like other waits
+            if let Some(count) = count.or((!follow).then_some(1)) {
+                cmd["count"] = json!(count);
+            }
+            if let Some(timeout) = timeout.or((!follow).then_some(30_000)) {
+                cmd["timeout"] = json!(timeout);
+            }
+            Ok(cmd)
+        }
+        "watch" => {
+            const USAGE: &str = "watch --selector <css> [--events childList,attributes,characterData] [--follow] [--count <n>] [--timeout <duration>]";
+            let value = |flag: &str| {
+                rest.iter()
+                    .position(|&s| s == flag)
+                    .and_then(|i| rest.get(i + 1).copied())
+            };
+            let selector = value("--selector").ok_or_else(|| ParseError::MissingArguments {
+                context: "watch".to_string(),
+                usage: USAGE,
+            })?;
+            let events = value("--events")
+                .map(|raw| {
+                    raw.split(',')
+                        .map(|event| match event.trim().to_lowercase().as_str() {
+                            "childlist" => Ok("childList"),
+                            "attributes" => Ok("attributes"),
+                            "characterdata" => Ok("characterData"),
+                            _ => Err(ParseError::InvalidValue {
+                                message: format!(
+                                    "Unknown mutation type: {} (expected childList, attributes or characterData)",
+                                    event
+                                ),
+                                usage: USAGE,
+                            }),
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .transpose()?;
+            let follow = rest.contains(&"--follow");
+            let count = value("--count")
+                .map(|raw| {
+                    raw.parse::<u64>().ok().filter(|n| *n > 0).ok_or_else(|| {
+                        ParseError::InvalidValue {
+                            message: format!("--count must be a positive integer: {}", raw),
+                            usage: USAGE,
+                        }
+                    })
+                })
+                .transpose()?;
+            let timeout = value("--timeout")
+                .map(|raw| {
+                    parse_duration_ms(raw).ok_or_else(|| ParseError::InvalidValue {
+                        message: format!("Invalid --timeout duration: {}", raw),
+                        usage: USAGE,
+                    })
+                })
+                .transpose()?;
+            let mut cmd = json!({ "id": id, "action": "watch", "selector": selector });
+            if let Some(events) = events {
+                cmd["events"] = json!(events);
+            }
+            // Without --follow, wait for the first change like cdp-listen
+            if let Some(count) = count.or((!follow).then_some(1)) {
+                cmd["count"] = json!(count);
+            }
+            if let Some(timeout) = timeout.or((!follow).then_some(30_000)) {
+                cmd["timeout"] = json!(timeout);
+            }
+            Ok(cmd)
+        }
+        "poll" => {
+            const USAGE: &str = "poll --selector <css> [--attr <name>] [--every <duration>] [--until-changed] [--until-match <regex>] [--max <n>] [--reload]";
+            let value = |flag: &str| {
+                rest.iter()
+                    .position(|&s| s == flag)
+                    .and_then(|i| rest.get(i + 1).copied())
+            };
+            let selector = value("--selector").ok_or_else(|| ParseError::MissingArguments {
+                context: "poll".to_string(),
+                usage: USAGE,
+            })?;
+            let interval = match value("--every") {
+                Some(raw) => parse_duration_ms(raw).filter(|ms| *ms > 0).ok_or_else(|| {
+                    ParseError::InvalidValue {
+                        message: format!("Invalid --every duration: {}", raw),
+                        usage: USAGE,
+                    }
+                })?,
+                None => 30_000,
+            };
+            let max = value("--max")
+                .map(|raw| {
+                    raw.parse::<u64>().ok().filter(|n| *n > 0).ok_or_else(|| {
+                        ParseError::InvalidValue {
+                            message: format!("--max must be a positive integer: {}", raw),
+                            usage: USAGE,
+                        }
+                    })
+                })
+                .transpose()?;
+            let mut cmd = json!({
+                "id": id,
+                "action": "poll",
+                "selector": selector,
+                "interval": interval,
+            });
+            if let Some(attr) = value("--attr") {
+                cmd["attribute"] = json!(attr);
+            }
+            if rest.contains(&"--reload") {
+                cmd["reload"] = json!(true);
+            }
+            if rest.contains(&"--until-changed") {
+                cmd["untilChanged"] = json!(true);
+            }
+            if let Some(pattern) = value("--until-match") {
+                cmd["untilMatch"] = json!(pattern);
+            }
+            if let Some(max) = max {
+                cmd["max"] = json!(max);
+            }
+            Ok(cmd)
+        }
+        "cdp" => {
+            const USAGE: &str =
+                "cdp <Domain.method> [--params <json>] [--events <Domain.event,...>] [--wait <duration>]";
+            let value = |flag: &str| {
+                rest.iter()
+                    .position(|&s| s == flag)
+                    .and_then(|i| rest.get(i + 1).copied())
+            };
+            let method = rest.first().ok_or_else(|| ParseError::MissingArguments {
+                context: "cdp".to_string(),
+                usage: USAGE,
+            })?;
+            if !method.contains('.') || method.starts_with('-') {
+                return Err(ParseError::InvalidValue {
+                    message: format!("Expected a protocol method like Page.navigate: {}", method),
+                    usage: USAGE,
+                });
+            }
+            let mut cmd = json!({ "id": id, "action": "cdp", "method": method });
+            if let Some(raw) = value("--params") {
+                let params: Value = serde_json::from_str(raw)
+                    .ok()
+                    .filter(Value::is_object)
+                    .ok_or_else(|| ParseError::InvalidValue {
+                        message: format!("--params must be a JSON object: {}", raw),
+                        usage: USAGE,
+                    })?;
+                cmd["params"] = params;
+            }
+            if let Some(events) = value("--events") {
+                let events: Vec<&str> = events
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|e| !e.is_empty())
+                    .collect();
+                cmd["events"] = json!(events);
+            }
+            if let Some(raw) = value("--wait") {
+                let wait = parse_duration_ms(raw).ok_or_else(|| ParseError::InvalidValue {
+                    message: format!("Invalid --wait duration: {}", raw),
+                    usage: USAGE,
+                })?;
+                cmd["wait"] = json!(wait);
+            }
+            Ok(cmd)
+        }
 
         // === State ===
         "state" => {
             const VALID: &[&str] = &["save", "load"];
-            match rest.get(0).map(|s| *s) {
+            match rest.first().copied() {
                 Some("save") => {
                     let path = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
                         context: "state save".to_string(),
@@ -844,17 +1671,46 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
             }
         }
 
+        // `session` and `session list` are handled locally in main.rs
+        "session" => {
+            const VALID: &[&str] = &["save", "restore"];
+            const USAGE: &str = "session <save|restore> <file>";
+            let op = match rest.first().copied() {
+                Some(op @ ("save" | "restore")) => op,
+                Some(sub) => {
+                    return Err(ParseError::UnknownSubcommand {
+                        subcommand: sub.to_string(),
+                        valid_options: VALID,
+                    })
+                }
+                None => {
+                    return Err(ParseError::MissingArguments {
+                        context: "session".to_string(),
+                        usage: USAGE,
+                    })
+                }
+            };
+            let path = rest
+                .get(1)
+                .map(|p| std::path::absolute(p).unwrap_or_else(|_| p.into()))
+                .ok_or_else(|| ParseError::MissingArguments {
+                    context: format!("session {}", op),
+                    usage: USAGE,
+                })?;
+            Ok(json!({ "id": id, "action": format!("session_{}", op), "path": path }))
+        }
+
         // === iOS-specific commands ===
         "tap" => {
             // Alias for click (semantic clarity for touch interfaces)
-            let sel = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let sel = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "tap".to_string(),
                 usage: "tap <selector>",
             })?;
             Ok(json!({ "id": id, "action": "tap", "selector": sel }))
         }
         "swipe" => {
-            let direction = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+            let direction = rest.first().ok_or_else(|| ParseError::MissingArguments {
                 context: "swipe".to_string(),
                 usage: "swipe <up|down|left|right> [distance]",
             })?;
@@ -868,13 +1724,15 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
             let mut cmd = json!({ "id": id, "action": "swipe", "direction": direction });
             if let Some(distance) = rest.get(1) {
                 if let Ok(d) = distance.parse::<u32>() {
-                    cmd.as_object_mut().unwrap().insert("distance".to_string(), json!(d));
+                    cmd.as_object_mut()
+                        .unwrap()
+                        .insert("distance".to_string(), json!(d));
                 }
             }
             Ok(cmd)
         }
         "device" => {
-            match rest.get(0).map(|s| *s) {
+            match rest.first().copied() {
                 Some("list") | None => {
                     // List available iOS simulators
                     Ok(json!({ "id": id, "action": "device_list" }))
@@ -892,12 +1750,26 @@ pub fn parse_command(args: &[String], flags: &Flags) -> Result<Value, ParseError
     }
 }
 
-fn parse_get(rest: &[&str], id: &str) -> Result<Value, ParseError> {
+/// Shared options for event waits: `--timeout <ms>` and `--arm`.
+/// An armed wait starts listening and returns immediately; running the same
+/// wait again afterwards collects the event, even if it fired in between.
+fn apply_wait_options(cmd: &mut Value, rest: &[&str]) {
+    if let Some(idx) = rest.iter().position(|&s| s == "--timeout") {
+        if let Some(timeout) = rest.get(idx + 1).and_then(|s| s.parse::<u64>().ok()) {
+            cmd["timeout"] = json!(timeout);
+        }
+    }
+    if rest.contains(&"--arm") {
+        cmd["arm"] = json!(true);
+    }
+}
+
+fn parse_get(rest: &[&str], id: &str) -> Result<Value, ParseError> {
     const VALID: &[&str] = &[
         "text", "html", "value", "attr", "url", "title", "count", "box", "styles",
     ];
 
-    match rest.get(0).map(|s| *s) {
+    match rest.first().copied() {
         Some("text") => {
             let sel = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
                 context: "get text".to_string(),
@@ -967,7 +1839,7 @@ fn parse_get(rest: &[&str], id: &str) -> Result<Value, ParseError> {
 fn parse_is(rest: &[&str], id: &str) -> Result<Value, ParseError> {
     const VALID: &[&str] = &["visible", "enabled", "checked"];
 
-    match rest.get(0).map(|s| *s) {
+    match rest.first().copied() {
         Some("visible") => {
             let sel = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
                 context: "is visible".to_string(),
@@ -1014,14 +1886,14 @@ fn parse_find(rest: &[&str], id: &str) -> Result<Value, ParseError> {
         "nth",
     ];
 
-    let locator = rest.get(0).ok_or_else(|| ParseError::MissingArguments {
+    let locator = rest.first().ok_or_else(|| ParseError::MissingArguments {
         context: "find".to_string(),
         usage: "find <locator> <value> [action] [text]",
     })?;
 
     let name_idx = rest.iter().position(|&s| s == "--name");
-    let name = name_idx.and_then(|i| rest.get(i + 1).map(|s| *s));
-    let exact = rest.iter().any(|&s| s == "--exact");
+    let name = name_idx.and_then(|i| rest.get(i + 1).copied());
+    let exact = rest.contains(&"--exact");
 
     match *locator {
         "role" | "text" | "label" | "placeholder" | "alt" | "title" | "testid" | "first"
@@ -1140,7 +2012,7 @@ fn parse_find(rest: &[&str], id: &str) -> Result<Value, ParseError> {
 fn parse_mouse(rest: &[&str], id: &str) -> Result<Value, ParseError> {
     const VALID: &[&str] = &["move", "down", "up", "wheel"];
 
-    match rest.get(0).map(|s| *s) {
+    match rest.first().copied() {
         Some("move") => {
             let x_str = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
                 context: "mouse move".to_string(),
@@ -1202,7 +2074,7 @@ fn parse_set(rest: &[&str], id: &str) -> Result<Value, ParseError> {
         "media",
     ];
 
-    match rest.get(0).map(|s| *s) {
+    match rest.first().copied() {
         Some("viewport") => {
             let w_str = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
                 context: "set viewport".to_string(),
@@ -1288,14 +2160,14 @@ fn parse_set(rest: &[&str], id: &str) -> Result<Value, ParseError> {
             Ok(json!({ "id": id, "action": "credentials", "username": user, "password": pass }))
         }
         Some("media") => {
-            let color = if rest.iter().any(|&s| s == "dark") {
+            let color = if rest.contains(&"dark") {
                 "dark"
-            } else if rest.iter().any(|&s| s == "light") {
+            } else if rest.contains(&"light") {
                 "light"
             } else {
                 "no-preference"
             };
-            let reduced = if rest.iter().any(|&s| s == "reduced-motion") {
+            let reduced = if rest.contains(&"reduced-motion") {
                 "reduce"
             } else {
                 "no-preference"
@@ -1315,18 +2187,49 @@ fn parse_set(rest: &[&str], id: &str) -> Result<Value, ParseError> {
     }
 }
 
-fn parse_network(rest: &[&str], id: &str) -> Result<Value, ParseError> {
-    const VALID: &[&str] = &["route", "unroute", "requests"];
+/// Add `https://` to URLs given without a scheme.
+fn normalize_url(url: &str) -> String {
+    let url_lower = url.to_lowercase();
+    if url_lower.starts_with("http://")
+        || url_lower.starts_with("https://")
+        || url_lower.starts_with("about:")
+        || url_lower.starts_with("data:")
+        || url_lower.starts_with("file:")
+    {
+        url.to_string()
+    } else {
+        format!("https://{}", url)
+    }
+}
+
+/// Parse a byte size such as `1048576`, `500kb` or `2mb` (1kb = 1024 bytes).
+fn parse_size(s: &str) -> Option<u64> {
+    let lower = s.trim().to_ascii_lowercase();
+    let (num, multiplier) = if let Some(n) = lower.strip_suffix("gb") {
+        (n, 1024 * 1024 * 1024)
+    } else if let Some(n) = lower.strip_suffix("mb") {
+        (n, 1024 * 1024)
+    } else if let Some(n) = lower.strip_suffix("kb") {
+        (n, 1024)
+    } else {
+        (lower.strip_suffix('b').unwrap_or(&lower), 1)
+    };
+    let value: f64 = num.trim().parse().ok()?;
+    (value > 0.0).then_some((value * multiplier as f64) as u64)
+}
+
+fn parse_network(rest: &[&str], id: &str, out: Option<&str>) -> Result<Value, ParseError> {
+    const VALID: &[&str] = &["route", "unroute", "requests", "capture", "apis"];
 
-    match rest.get(0).map(|s| *s) {
+    match rest.first().copied() {
         Some("route") => {
             let url = rest.get(1).ok_or_else(|| ParseError::MissingArguments {
                 context: "network route".to_string(),
                 usage: "network route <url> [--abort|--body <json>]",
             })?;
-            let abort = rest.iter().any(|&s| s == "--abort");
+            let abort = rest.contains(&"--abort");
             let body_idx = rest.iter().position(|&s| s == "--body");
-            let body = body_idx.and_then(|i| rest.get(i + 1).map(|s| *s));
+            let body = body_idx.and_then(|i| rest.get(i + 1).copied());
             Ok(json!({ "id": id, "action": "route", "url": url, "abort": abort, "body": body }))
         }
         Some("unroute") => {
@@ -1337,22 +2240,68 @@ fn parse_network(rest: &[&str], id: &str) -> Result<Value, ParseError> {
             Ok(cmd)
         }
         Some("requests") => {
-            let clear = rest.iter().any(|&s| s == "--clear");
+            let clear = rest.contains(&"--clear");
             let filter_idx = rest.iter().position(|&s| s == "--filter");
-            let filter = filter_idx.and_then(|i| rest.get(i + 1).map(|s| *s));
+            let filter = filter_idx.and_then(|i| rest.get(i + 1).copied());
             let mut cmd = json!({ "id": id, "action": "requests", "clear": clear });
             if let Some(f) = filter {
                 cmd["filter"] = json!(f);
             }
             Ok(cmd)
         }
+        Some("apis") => {
+            let clear = rest.contains(&"--clear");
+            let filter_idx = rest.iter().position(|&s| s == "--filter");
+            let filter = filter_idx.and_then(|i| rest.get(i + 1).copied());
+            let mut cmd = json!({ "id": id, "action": "apis", "clear": clear });
+            if let Some(f) = filter {
+                cmd["filter"] = json!(f);
+            }
+            Ok(cmd)
+        }
+        Some("capture") => {
+            const USAGE: &str =
+                "network capture --url <pattern> --out <dir> [--max-size <size>] [--mime <types>]";
+            if rest.get(1) == Some(&"stop") {
+                return Ok(json!({ "id": id, "action": "capture_stop" }));
+            }
+            let value = |flag: &str| {
+                rest.iter()
+                    .position(|&s| s == flag)
+                    .and_then(|i| rest.get(i + 1).copied())
+            };
+            // --out is a global flag, so it only reaches here unparsed in tests
+            let (Some(url), Some(out)) = (value("--url"), value("--out").or(out)) else {
+                return Err(ParseError::MissingArguments {
+                    context: "network capture".to_string(),
+                    usage: USAGE,
+                });
+            };
+            let mut cmd = json!({ "id": id, "action": "capture_start", "url": url, "out": out });
+            if let Some(size) = value("--max-size") {
+                let bytes = parse_size(size).ok_or_else(|| ParseError::InvalidValue {
+                    message: format!("Invalid size: {} (use e.g. 500kb, 2mb)", size),
+                    usage: USAGE,
+                })?;
+                cmd["maxSize"] = json!(bytes);
+            }
+            if let Some(mime) = value("--mime") {
+                let types: Vec<&str> = mime
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                cmd["mime"] = json!(types);
+            }
+            Ok(cmd)
+        }
         Some(sub) => Err(ParseError::UnknownSubcommand {
             subcommand: sub.to_string(),
             valid_options: VALID,
         }),
         None => Err(ParseError::MissingArguments {
             context: "network".to_string(),
-            usage: "network <route|unroute|requests> [args...]",
+            usage: "network <route|unroute|requests|capture|apis> [args...]",
         }),
     }
 }
@@ -1360,9 +2309,9 @@ fn parse_network(rest: &[&str], id: &str) -> Result<Value, ParseError> {
 fn parse_storage(rest: &[&str], id: &str) -> Result<Value, ParseError> {
     const VALID: &[&str] = &["local", "session"];
 
-    match rest.get(0).map(|s| *s) {
+    match rest.first().copied() {
         Some("local") | Some("session") => {
-            let storage_type = rest.get(0).unwrap();
+            let storage_type = rest.first().unwrap();
             let op = rest.get(1).unwrap_or(&"get");
             let key = rest.get(2);
             let value = rest.get(3);
@@ -1412,12 +2361,27 @@ mod tests {
         Flags {
             session: "test".to_string(),
             json: false,
+            quiet: false,
+            verbosity: 0,
+            log_level: None,
+            log_file: None,
+            download_concurrency: None,
+            into: None,
+            json_path: None,
+            output_format: None,
+            out: None,
+            sink: None,
+            log_format: None,
+            color: None,
+            otel_endpoint: None,
             full: false,
             headed: false,
             debug: false,
             headers: None,
             executable_path: None,
+            browser: None,
             extensions: Vec::new(),
+            init_scripts: Vec::new(),
             cdp: None,
             profile: None,
             state: None,
@@ -1426,11 +2390,49 @@ mod tests {
             args: None,
             user_agent: None,
             provider: None,
+            remote: None,
+            remote_token: None,
+            remote_keepalive: None,
             ignore_https_errors: false,
+            extra_ca: None,
+            ignore_cert_errors_for: None,
+            client_cert: None,
+            client_key: None,
+            client_cert_origin: None,
+            auth_server_allowlist: None,
+            auth_delegate_allowlist: None,
             allow_file_access: false,
             device: None,
             browseros_profile_name: None,
+            default_timeout: None,
+            action_timeout: None,
+            nav_timeout: None,
+            idle_timeout: None,
+            on_popup: None,
+            auto_dismiss_overlays: false,
+            adblock: false,
+            stealth: false,
+            deterministic: false,
+            retries: None,
+            retry_backoff: None,
+            humanize: false,
+            humanize_seed: None,
+            rate: None,
+            nav_rate: None,
+            max_concurrent: None,
+            action_rate: None,
+            max_tab_memory: None,
+            recycle_mode: None,
+            respect_robots: false,
+            transcript: false,
+            audit_log: false,
+            read_only: false,
+            guardrails: false,
+            guardrail_rules: None,
+            allow_purchases: false,
+            robots_override: false,
             cli_executable_path: false,
+            cli_browser: false,
             cli_extensions: false,
             cli_profile: false,
             cli_state: false,
@@ -1438,6 +2440,13 @@ mod tests {
             cli_user_agent: false,
             cli_proxy: false,
             cli_proxy_bypass: false,
+            cli_extra_ca: false,
+            cli_ignore_cert_errors_for: false,
+            cli_client_cert: false,
+            cli_auth_allowlist: false,
+            cli_stealth: false,
+            cli_deterministic: false,
+            cli_init_scripts: false,
             cli_allow_file_access: false,
             cli_browseros_mode: false,
         }
@@ -1900,6 +2909,29 @@ mod tests {
         assert_eq!(cmd["fullPage"], true);
     }
 
+    #[test]
+    fn test_compare_screenshot() {
+        let cmd = parse_command(
+            &args("compare-screenshot --baseline /tmp/home.png #main --mask .clock --mask .ad --threshold 0.2"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["action"], "screenshot");
+        assert_eq!(cmd["selector"], "#main");
+        assert_eq!(cmd["mask"], json!([".clock", ".ad"]));
+        assert_eq!(cmd["compare"]["baseline"], "/tmp/home.png");
+        assert_eq!(cmd["compare"]["threshold"], 0.2);
+        assert_eq!(cmd["compare"]["updateBaseline"], false);
+        assert!(cmd["path"].as_str().unwrap().ends_with(".png"));
+
+        assert!(parse_command(&args("compare-screenshot"), &default_flags()).is_err());
+        assert!(parse_command(
+            &args("compare-screenshot --baseline a.png --threshold 2"),
+            &default_flags()
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_screenshot_with_ref() {
         let cmd = parse_command(&args("screenshot @e1"), &default_flags()).unwrap();
@@ -1984,6 +3016,13 @@ mod tests {
         assert_eq!(cmd["maxDepth"], 3);
     }
 
+    #[test]
+    fn test_snapshot_fresh() {
+        let cmd = parse_command(&args("snapshot -i --fresh"), &default_flags()).unwrap();
+        assert_eq!(cmd["fresh"], true);
+        assert_eq!(cmd["interactive"], true);
+    }
+
     // === Wait ===
 
     #[test]
@@ -2042,6 +3081,21 @@ mod tests {
 
     // === Record Tests ===
 
+    #[test]
+    fn test_record_stop_upload() {
+        let cmd = parse_command(&args("record stop"), &default_flags()).unwrap();
+        assert!(cmd.get("upload").is_none());
+        let cmd = parse_command(
+            &args("record stop --upload s3://evidence/videos"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["action"], "recording_stop");
+        assert_eq!(cmd["upload"], "s3://evidence/videos");
+        let result = parse_command(&args("record stop --upload ftp://x"), &default_flags());
+        assert!(matches!(result, Err(ParseError::InvalidValue { .. })));
+    }
+
     #[test]
     fn test_record_start() {
         let cmd = parse_command(&args("record start output.webm"), &default_flags()).unwrap();
@@ -2130,6 +3184,27 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_screencast_start() {
+        let cmd = parse_command(
+            &args("screencast start /tmp/frames.mjpeg --quality 60 --scale 0.5 --fps 5"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["action"], "screencast_start");
+        assert_eq!(cmd["path"], "/tmp/frames.mjpeg");
+        assert_eq!(cmd["quality"], 60);
+        assert_eq!(cmd["scale"], 0.5);
+        assert_eq!(cmd["fps"], 5.0);
+
+        let cmd = parse_command(&args("screencast start --fps 2"), &default_flags()).unwrap();
+        assert!(cmd.get("path").is_none());
+        assert!(parse_command(&args("screencast start --scale 2"), &default_flags()).is_err());
+        assert!(parse_command(&args("screencast start --format gif"), &default_flags()).is_err());
+        let cmd = parse_command(&args("screencast stop"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "screencast_stop");
+    }
+
     #[test]
     fn test_record_missing_subcommand() {
         let result = parse_command(&args("record"), &default_flags());
@@ -2160,8 +3235,11 @@ mod tests {
     #[test]
     fn test_eval_base64_long_flag() {
         // "document.title" in base64
-        let cmd =
-            parse_command(&args("eval --base64 ZG9jdW1lbnQudGl0bGU="), &default_flags()).unwrap();
+        let cmd = parse_command(
+            &args("eval --base64 ZG9jdW1lbnQudGl0bGU="),
+            &default_flags(),
+        )
+        .unwrap();
         assert_eq!(cmd["action"], "evaluate");
         assert_eq!(cmd["script"], "document.title");
     }
@@ -2187,6 +3265,150 @@ mod tests {
         assert!(err.format().contains("Invalid base64"));
     }
 
+    #[test]
+    fn test_eval_target() {
+        let cmd = parse_command(&args("eval --target w1 self.name"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "evaluate");
+        assert_eq!(cmd["target"], "w1");
+        assert_eq!(cmd["script"], "self.name");
+
+        let cmd = parse_command(
+            &args("eval --target sw1 -b ZG9jdW1lbnQudGl0bGU="),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["target"], "sw1");
+        assert_eq!(cmd["script"], "document.title");
+
+        let cmd = parse_command(&args("robots example.com/private"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "robots");
+        assert_eq!(cmd["url"], "https://example.com/private");
+
+        let cmd = parse_command(&args("targets list"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "targets");
+        assert!(parse_command(&args("eval --target"), &default_flags()).is_err());
+    }
+
+    #[test]
+    fn test_audit_perf() {
+        let cmd = parse_command(&args("audit perf"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "audit_perf");
+        assert!(cmd.get("url").is_none());
+        let cmd = parse_command(&args("audit perf example.com"), &default_flags()).unwrap();
+        assert_eq!(cmd["url"], "https://example.com");
+        assert!(parse_command(&args("audit"), &default_flags()).is_err());
+        assert!(parse_command(&args("audit a11y"), &default_flags()).is_err());
+
+        let cmd = parse_command(&args("audit security example.com"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "audit_security");
+        assert_eq!(cmd["url"], "https://example.com");
+    }
+
+    #[test]
+    fn test_audit_seo() {
+        let cmd = parse_command(&args("audit seo"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "audit_seo");
+        assert!(cmd.get("url").is_none());
+        let cmd =
+            parse_command(&args("audit seo example.com --links 5"), &default_flags()).unwrap();
+        assert_eq!(cmd["url"], "https://example.com");
+        assert_eq!(cmd["links"], 5);
+        assert!(parse_command(&args("audit seo --links"), &default_flags()).is_err());
+        assert!(parse_command(&args("audit seo --links many"), &default_flags()).is_err());
+    }
+
+    #[test]
+    fn test_tls_info() {
+        let cmd = parse_command(&args("tls info intranet.local:8443"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "tls_info");
+        assert_eq!(cmd["url"], "https://intranet.local:8443");
+        assert!(parse_command(&args("tls info"), &default_flags()).is_err());
+        assert!(parse_command(&args("tls"), &default_flags()).is_err());
+    }
+
+    #[test]
+    fn test_clock() {
+        let cmd = parse_command(
+            &args("clock set 2024-06-01T10:00:00Z --tick --timezone Europe/Berlin"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["action"], "clock_set");
+        assert_eq!(cmd["time"], "2024-06-01T10:00:00Z");
+        assert_eq!(cmd["tick"], true);
+        assert_eq!(cmd["timezone"], "Europe/Berlin");
+        let cmd = parse_command(&args("clock set 2024-06-01"), &default_flags()).unwrap();
+        assert!(cmd.get("tick").is_none());
+        let cmd = parse_command(&args("clock advance 14d"), &default_flags()).unwrap();
+        assert_eq!(cmd["ms"], 14 * 86_400_000u64);
+        assert!(parse_command(&args("clock advance soon"), &default_flags()).is_err());
+        assert!(parse_command(&args("clock set --tick"), &default_flags()).is_err());
+        assert!(parse_command(&args("clock stop"), &default_flags()).is_err());
+    }
+
+    #[test]
+    fn test_schedule() {
+        let input = vec![
+            "schedule".to_string(),
+            "add".to_string(),
+            "0 * * * *".to_string(),
+            "run".to_string(),
+            "Cargo.toml".to_string(),
+            "--reporter".to_string(),
+            "json".to_string(),
+        ];
+        let cmd = parse_command(&input, &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "schedule_add");
+        assert_eq!(cmd["cron"], "0 * * * *");
+        let command = cmd["command"].as_array().unwrap();
+        assert_eq!(command[0], "run");
+        // Existing files are made absolute, everything else is kept as typed
+        assert!(std::path::Path::new(command[1].as_str().unwrap()).is_absolute());
+        assert_eq!(command[2..], [json!("--reporter"), json!("json")]);
+
+        let cmd = parse_command(&args("schedule list"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "schedule_list");
+        let cmd = parse_command(&args("schedule remove 2"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "schedule_remove");
+        assert_eq!(cmd["jobId"], "2");
+        let cmd = parse_command(&args("schedule logs 2"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "schedule_logs");
+
+        assert!(parse_command(&args("schedule add @hourly"), &default_flags()).is_err());
+        assert!(parse_command(&args("schedule logs"), &default_flags()).is_err());
+        assert!(parse_command(&args("schedule pause 1"), &default_flags()).is_err());
+    }
+
+    #[test]
+    fn test_session_save_restore() {
+        let cmd = parse_command(&args("session save task.json"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "session_save");
+        assert!(std::path::Path::new(cmd["path"].as_str().unwrap()).is_absolute());
+        let cmd = parse_command(&args("session restore task.json"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "session_restore");
+
+        assert!(parse_command(&args("session save"), &default_flags()).is_err());
+        assert!(parse_command(&args("session load task.json"), &default_flags()).is_err());
+    }
+
+    #[test]
+    fn test_input_timeline() {
+        let cmd = parse_command(&args("input record timeline.json"), &default_flags()).unwrap();
+        assert_eq!(cmd["op"], "record");
+        assert!(std::path::Path::new(cmd["path"].as_str().unwrap()).is_absolute());
+
+        let cmd = parse_command(
+            &args("input replay timeline.json --speed 2"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["op"], "replay");
+        assert_eq!(cmd["speed"], 2.0);
+
+        assert!(parse_command(&args("input replay"), &default_flags()).is_err());
+        assert!(parse_command(&args("input replay t.json --speed 0"), &default_flags()).is_err());
+    }
+
     #[test]
     fn test_unknown_command() {
         let result = parse_command(&args("unknowncommand"), &default_flags());
@@ -2237,6 +3459,269 @@ mod tests {
         assert!(err.format().contains("get text"));
     }
 
+    // === Inspect tests ===
+
+    #[test]
+    fn test_inspect() {
+        let cmd = parse_command(&args("inspect #submit"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "inspect");
+        assert_eq!(cmd["selector"], "#submit");
+    }
+
+    #[test]
+    fn test_inspect_ref() {
+        let cmd = parse_command(&args("inspect @e3"), &default_flags()).unwrap();
+        assert_eq!(cmd["selector"], "@e3");
+    }
+
+    #[test]
+    fn test_inspect_missing_selector() {
+        let result = parse_command(&args("inspect"), &default_flags());
+        assert!(matches!(
+            result.unwrap_err(),
+            ParseError::MissingArguments { .. }
+        ));
+    }
+
+    #[test]
+    fn test_dismiss_overlays() {
+        let cmd = parse_command(&args("dismiss-overlays"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "dismiss_overlays");
+    }
+
+    #[test]
+    fn test_network_capture() {
+        let cmd = parse_command(
+            &args("net capture --url /api/ --out captures --max-size 2mb --mime application/json,image/*"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["action"], "capture_start");
+        assert_eq!(cmd["url"], "/api/");
+        assert_eq!(cmd["out"], "captures");
+        assert_eq!(cmd["maxSize"], 2 * 1024 * 1024);
+        assert_eq!(cmd["mime"], json!(["application/json", "image/*"]));
+
+        let cmd = parse_command(&args("network capture stop"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "capture_stop");
+
+        assert!(parse_command(&args("network capture --url /api/"), &default_flags()).is_err());
+        assert!(parse_command(
+            &args("network capture --url a --out b --max-size lots"),
+            &default_flags()
+        )
+        .is_err());
+        assert_eq!(parse_size("500kb"), Some(512000));
+        assert_eq!(parse_size("100"), Some(100));
+    }
+
+    #[test]
+    fn test_network_apis() {
+        let cmd = parse_command(&args("net apis --filter graphql"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "apis");
+        assert_eq!(cmd["filter"], "graphql");
+        assert_eq!(cmd["clear"], false);
+        let cmd = parse_command(&args("network apis --clear"), &default_flags()).unwrap();
+        assert_eq!(cmd["clear"], true);
+    }
+
+    #[test]
+    fn test_ws() {
+        let cmd = parse_command(&args("ws"), &default_flags()).unwrap();
+        assert_eq!(cmd["op"], "list");
+        let cmd = parse_command(&args("ws tail ws2 -n 5"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "ws");
+        assert_eq!(cmd["op"], "tail");
+        assert_eq!(cmd["socket"], "ws2");
+        assert_eq!(cmd["count"], 5);
+        let cmd = parse_command(&args("ws export sockets.json"), &default_flags()).unwrap();
+        assert_eq!(cmd["path"], "sockets.json");
+        assert!(parse_command(&args("ws tail"), &default_flags()).is_err());
+        assert!(parse_command(&args("ws tail ws1 -n 0"), &default_flags()).is_err());
+    }
+
+    #[test]
+    fn test_replay() {
+        let cmd = parse_command(
+            &args("replay --har /tmp/run.har example.com --not-found fallback"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["action"], "har_replay");
+        assert_eq!(cmd["path"], "/tmp/run.har");
+        assert_eq!(cmd["url"], "https://example.com");
+        assert_eq!(cmd["notFound"], "fallback");
+
+        let cmd = parse_command(&args("replay --har /tmp/run.har"), &default_flags()).unwrap();
+        assert!(cmd.get("url").is_none());
+
+        assert!(parse_command(&args("replay example.com"), &default_flags()).is_err());
+        assert!(parse_command(
+            &args("replay --har a.har --not-found skip"),
+            &default_flags()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_stats() {
+        let cmd = parse_command(&args("stats"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "stats");
+        assert_eq!(cmd["reset"], false);
+        let cmd = parse_command(&args("stats --reset"), &default_flags()).unwrap();
+        assert_eq!(cmd["reset"], true);
+    }
+
+    #[test]
+    fn test_cdp() {
+        let cmd = parse_command(
+            &args(r#"cdp Page.reload --params {"ignoreCache":true} --events Page.loadEventFired,Page.frameNavigated --wait 5s"#),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["action"], "cdp");
+        assert_eq!(cmd["method"], "Page.reload");
+        assert_eq!(cmd["params"], json!({ "ignoreCache": true }));
+        assert_eq!(
+            cmd["events"],
+            json!(["Page.loadEventFired", "Page.frameNavigated"])
+        );
+        assert_eq!(cmd["wait"], 5000);
+        let cmd = parse_command(&args("cdp Browser.getVersion"), &default_flags()).unwrap();
+        assert!(cmd.get("params").is_none());
+
+        let err = |input: &str| parse_command(&args(input), &default_flags()).unwrap_err();
+        assert!(matches!(err("cdp"), ParseError::MissingArguments { .. }));
+        assert!(matches!(
+            err("cdp getVersion"),
+            ParseError::InvalidValue { .. }
+        ));
+        assert!(matches!(
+            err("cdp Page.navigate --params [1]"),
+            ParseError::InvalidValue { .. }
+        ));
+    }
+
+    #[test]
+    fn test_cdp_listen() {
+        let cmd = parse_command(
+            &args("cdp-listen Network.responseReceived Page.frameNavigated --follow"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["action"], "cdp_listen");
+        assert_eq!(
+            cmd["events"],
+            json!(["Network.responseReceived", "Page.frameNavigated"])
+        );
+        assert!(cmd.get("count").is_none());
+        assert!(cmd.get("timeout").is_none());
+
+        let cmd = parse_command(
+            &args("cdp-listen Page.loadEventFired --count 2 --timeout 10s"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["events"], json!(["Page.loadEventFired"]));
+        assert_eq!(cmd["count"], 2);
+        assert_eq!(cmd["timeout"], 10_000);
+
+        let cmd = parse_command(&args("cdp-listen Page.loadEventFired"), &default_flags()).unwrap();
+        assert_eq!(cmd["count"], 1);
+        assert_eq!(cmd["timeout"], 30_000);
+
+        let err = |input: &str| parse_command(&args(input), &default_flags()).unwrap_err();
+        assert!(matches!(
+            err("cdp-listen --follow"),
+            ParseError::MissingArguments { .. }
+        ));
+        assert!(matches!(
+            err("cdp-listen load"),
+            ParseError::InvalidValue { .. }
+        ));
+        assert!(matches!(
+            err("cdp-listen Page.loadEventFired --count 0"),
+            ParseError::InvalidValue { .. }
+        ));
+    }
+
+    #[test]
+    fn test_watch() {
+        let cmd = parse_command(
+            &args("watch --selector #feed --events childList,attributes --follow"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["action"], "watch");
+        assert_eq!(cmd["selector"], "#feed");
+        assert_eq!(cmd["events"], json!(["childList", "attributes"]));
+        assert!(cmd.get("count").is_none());
+        assert!(cmd.get("timeout").is_none());
+
+        let cmd = parse_command(&args("watch --selector .status"), &default_flags()).unwrap();
+        assert!(cmd.get("events").is_none());
+        assert_eq!(cmd["count"], 1);
+        assert_eq!(cmd["timeout"], 30_000);
+
+        let cmd = parse_command(
+            &args("watch --selector .status --events characterdata --count 5 --timeout 1m"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["events"], json!(["characterData"]));
+        assert_eq!(cmd["count"], 5);
+        assert_eq!(cmd["timeout"], 60_000);
+
+        let err = |input: &str| parse_command(&args(input), &default_flags()).unwrap_err();
+        assert!(matches!(
+            err("watch --follow"),
+            ParseError::MissingArguments { .. }
+        ));
+        assert!(matches!(
+            err("watch --selector #feed --events styles"),
+            ParseError::InvalidValue { .. }
+        ));
+    }
+
+    #[test]
+    fn test_poll() {
+        let cmd = parse_command(
+            &args("poll --selector .price --every 30s --until-changed --max 20"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["action"], "poll");
+        assert_eq!(cmd["selector"], ".price");
+        assert_eq!(cmd["interval"], 30_000);
+        assert_eq!(cmd["untilChanged"], true);
+        assert_eq!(cmd["max"], 20);
+        assert!(cmd.get("reload").is_none());
+
+        let cmd = parse_command(
+            &args("poll --selector #status --attr data-state --until-match ^done$ --reload"),
+            &default_flags(),
+        )
+        .unwrap();
+        assert_eq!(cmd["interval"], 30_000);
+        assert_eq!(cmd["attribute"], "data-state");
+        assert_eq!(cmd["untilMatch"], "^done$");
+        assert_eq!(cmd["reload"], true);
+
+        let err = |input: &str| parse_command(&args(input), &default_flags()).unwrap_err();
+        assert!(matches!(
+            err("poll --every 5s"),
+            ParseError::MissingArguments { .. }
+        ));
+        assert!(matches!(
+            err("poll --selector .price --every 0s"),
+            ParseError::InvalidValue { .. }
+        ));
+        assert!(matches!(
+            err("poll --selector .price --max 0"),
+            ParseError::InvalidValue { .. }
+        ));
+    }
+
     // === Protocol alignment tests ===
 
     #[test]
@@ -2315,6 +3800,75 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_which_passes_executable_path_flag() {
+        let cmd = parse_command(&args("which"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "which");
+        assert!(cmd.get("executablePath").is_none());
+
+        let mut flags = default_flags();
+        flags.executable_path = Some("/opt/BrowserOS".to_string());
+        flags.cli_executable_path = true;
+        let cmd = parse_command(&args("which"), &flags).unwrap();
+        assert_eq!(cmd["executablePath"], "/opt/BrowserOS");
+    }
+
+    #[test]
+    fn test_archive() {
+        let cmd = parse_command(&args("archive"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "archive");
+        assert_eq!(cmd["format"], "mhtml");
+        assert!(cmd.get("path").is_none());
+        let mut flags = default_flags();
+        flags.out = Some("evidence/page.WARC".to_string());
+        let cmd = parse_command(&args("archive"), &flags).unwrap();
+        assert_eq!(cmd["format"], "warc");
+        let path = cmd["path"].as_str().unwrap();
+        assert!(std::path::Path::new(path).is_absolute() && path.ends_with("page.WARC"));
+        let cmd = parse_command(&args("archive --format warc page.bin"), &flags).unwrap();
+        assert_eq!(cmd["format"], "warc");
+        assert!(cmd["path"].as_str().unwrap().ends_with("page.bin"));
+        assert!(parse_command(&args("archive --format zip"), &default_flags()).is_err());
+        assert!(parse_command(&args("archive --format"), &default_flags()).is_err());
+    }
+
+    #[test]
+    fn test_freeze() {
+        let cmd = parse_command(&args("freeze"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "freeze");
+        assert!(cmd.get("path").is_none());
+        let mut flags = default_flags();
+        flags.out = Some("page.html".to_string());
+        let cmd = parse_command(&args("freeze"), &flags).unwrap();
+        assert!(std::path::Path::new(cmd["path"].as_str().unwrap()).is_absolute());
+        let cmd = parse_command(&args("freeze copy.html"), &flags).unwrap();
+        assert!(cmd["path"].as_str().unwrap().ends_with("copy.html"));
+    }
+
+    #[test]
+    fn test_selectors_suggest() {
+        let cmd = parse_command(&args("selectors suggest @e3"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "selectors_suggest");
+        assert_eq!(cmd["selector"], "@e3");
+        assert!(parse_command(&args("selectors suggest"), &default_flags()).is_err());
+        assert!(parse_command(&args("selectors best @e3"), &default_flags()).is_err());
+        let cmd = parse_command(&args("selectors heal #buy"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "selectors_heal");
+        assert_eq!(cmd["selector"], "#buy");
+    }
+
+    #[test]
+    fn test_pdf_and_trace_stop_path_is_optional() {
+        let cmd = parse_command(&args("pdf"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "pdf");
+        assert!(cmd.get("path").is_none());
+        let cmd = parse_command(&args("pdf page.pdf"), &default_flags()).unwrap();
+        assert_eq!(cmd["path"], "page.pdf");
+        let cmd = parse_command(&args("trace stop"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "trace_stop");
+        assert!(cmd.get("path").is_none());
+    }
+
     #[test]
     fn test_download_missing_selector() {
         let result = parse_command(&args("download"), &default_flags());
@@ -2368,6 +3922,40 @@ mod tests {
         assert_eq!(cmd["path"], "./file.pdf");
     }
 
+    #[test]
+    fn test_wait_download_arm() {
+        let cmd = parse_command(&args("wait --download --arm"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "waitfordownload");
+        assert_eq!(cmd["arm"], true);
+        assert!(cmd.get("path").is_none());
+    }
+
+    #[test]
+    fn test_wait_popup() {
+        let cmd = parse_command(&args("wait --popup --timeout 5000"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "waitforpopup");
+        assert_eq!(cmd["timeout"], 5000);
+        assert!(cmd.get("arm").is_none());
+    }
+
+    #[test]
+    fn test_wait_request_arm() {
+        let cmd =
+            parse_command(&args("wait --request /api/login --arm"), &default_flags()).unwrap();
+        assert_eq!(cmd["action"], "waitforrequest");
+        assert_eq!(cmd["url"], "/api/login");
+        assert_eq!(cmd["arm"], true);
+    }
+
+    #[test]
+    fn test_wait_request_missing_pattern() {
+        let result = parse_command(&args("wait --request --arm"), &default_flags());
+        assert!(matches!(
+            result.unwrap_err(),
+            ParseError::MissingArguments { .. }
+        ));
+    }
+
     // === Connect (CDP) tests ===
 
     #[test]
@@ -2465,4 +4053,34 @@ mod tests {
         assert_eq!(cmd["action"], "launch");
         assert_eq!(cmd["cdpPort"], 1);
     }
+
+    #[test]
+    fn test_every_parsed_command_is_in_commands() {
+        // Every quoted name in a match arm is a candidate; the ones
+        // parse_command doesn't reject as unknown are top-level commands.
+        let source = include_str!("commands.rs");
+        for line in source.lines() {
+            let Some(arm) = line.trim().strip_suffix("=> {").or_else(|| {
+                line.trim()
+                    .split_once("=>")
+                    .map(|(arm, _)| arm)
+                    .filter(|arm| arm.starts_with('"'))
+            }) else {
+                continue;
+            };
+            for name in arm.split('|').map(|n| n.trim().trim_matches('"')) {
+                if name.is_empty() || !name.chars().all(|c| c.is_ascii_lowercase() || c == '-') {
+                    continue;
+                }
+                let parsed = parse_command(&[name.to_string()], &default_flags());
+                if !matches!(parsed, Err(ParseError::UnknownCommand { .. })) {
+                    assert!(
+                        crate::flags::COMMANDS.contains(&name),
+                        "{} is parsed but missing from COMMANDS",
+                        name
+                    );
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,565 @@
+//! Shell completion scripts.
+//!
+//! `agent-browser completions <shell>` prints a completion script for bash,
+//! zsh, fish or PowerShell. Commands, subcommands and global flags are baked
+//! into the script; values that live on disk (session and profile names) are
+//! looked up when completing by calling back into `agent-browser __complete`.
+
+use std::fs;
+
+use crate::color;
+use crate::connection::list_sessions;
+use crate::errors::ErrorCode;
+use crate::flags::{
+    COMMANDS, GLOBAL_FLAGS, GLOBAL_FLAGS_WITH_OPTIONAL_VALUE, GLOBAL_FLAGS_WITH_VALUE,
+};
+use crate::install::get_browseros_home;
+use crate::logging::LEVELS;
+use crate::ui;
+
+pub const SHELLS: &[&str] = &["bash", "zsh", "fish", "powershell"];
+
+/// Subcommands offered after a top-level command.
+const SUBCOMMANDS: &[(&str, &[&str])] = &[
+    (
+        "get",
+        &[
+            "text", "html", "value", "attr", "url", "title", "count", "box", "styles",
+        ],
+    ),
+    ("is", &["visible", "enabled", "checked"]),
+    (
+        "find",
+        &[
+            "role",
+            "text",
+            "label",
+            "placeholder",
+            "alt",
+            "title",
+            "testid",
+            "first",
+            "last",
+            "nth",
+        ],
+    ),
+    (
+        "set",
+        &[
+            "viewport",
+            "device",
+            "geo",
+            "geolocation",
+            "offline",
+            "headers",
+            "credentials",
+            "auth",
+            "media",
+        ],
+    ),
+    ("mouse", &["move", "down", "up", "wheel"]),
+    ("cookies", &["set", "clear"]),
+    ("storage", &["local", "session"]),
+    ("tab", &["new", "list", "close"]),
+    ("window", &["new"]),
+    ("targets", &["list"]),
+    ("device", &["list"]),
+    ("state", &["save", "load"]),
+    ("clock", &["set", "advance", "resume"]),
+    ("input", &["record", "stop", "replay"]),
+    ("dialog", &["accept", "dismiss"]),
+    ("selectors", &["suggest", "heal"]),
+    ("trace", &["start", "stop"]),
+    ("record", &["start", "stop", "restart"]),
+    ("screencast", &["start", "stop"]),
+    ("session", &["list", "save", "restore"]),
+    ("schedule", &["add", "list", "remove", "logs"]),
+    ("audit", &["perf", "seo", "security"]),
+    ("tls", &["info"]),
+    ("var", &["set", "get", "list", "unset", "clear"]),
+    ("config", &["get", "set", "list", "edit"]),
+    ("completions", SHELLS),
+    ("ext", &["add", "list", "remove"]),
+    ("filters", &["update", "list"]),
+    ("artifacts", &["list", "open", "prune"]),
+    ("transcript", &["export", "replay"]),
+    ("audit-log", &["verify", "export"]),
+    ("ws", &["list", "tail", "export", "clear"]),
+    ("network", NETWORK_SUBCOMMANDS),
+    ("net", NETWORK_SUBCOMMANDS),
+];
+
+const NETWORK_SUBCOMMANDS: &[&str] = &["route", "unroute", "requests", "capture", "apis"];
+
+/// How the value of a global flag is completed.
+enum Values {
+    /// Free-form value, nothing to suggest.
+    None,
+    Files,
+    Fixed(&'static [&'static str]),
+    /// Looked up at completion time via `agent-browser __complete <kind>`.
+    Dynamic(&'static str),
+}
+
+fn flag_values(flag: &str) -> Values {
+    match flag {
+        "--session" => Values::Dynamic("sessions"),
+        "--existing" | "--new" => Values::Dynamic("profiles"),
+        "--executable-path" | "--extension" | "--load-extension" | "--profile" | "--state"
+        | "--log-file" => Values::Files,
+        "--log-level" => Values::Fixed(LEVELS),
+        "--log-format" => Values::Fixed(&["text", "json"]),
+        "--color" => Values::Fixed(color::CHOICES),
+        "--on-popup" => Values::Fixed(&["follow", "block", "list"]),
+        "-p" | "--provider" => Values::Fixed(&[
+            "ios",
+            "browserbase",
+            "kernel",
+            "browseruse",
+            "browseros-existing",
+            "browseros-new",
+        ]),
+        _ => Values::None,
+    }
+}
+
+fn all_flags() -> Vec<&'static str> {
+    GLOBAL_FLAGS
+        .iter()
+        .chain(GLOBAL_FLAGS_WITH_VALUE)
+        .chain(GLOBAL_FLAGS_WITH_OPTIONAL_VALUE)
+        .copied()
+        .chain(["--help", "--version"])
+        .collect()
+}
+
+fn bash_script() -> String {
+    let mut value_cases = String::new();
+    for flag in GLOBAL_FLAGS_WITH_VALUE
+        .iter()
+        .chain(GLOBAL_FLAGS_WITH_OPTIONAL_VALUE)
+    {
+        let action = match flag_values(flag) {
+            Values::None => "return".to_string(),
+            Values::Files => r#"COMPREPLY=( $(compgen -f -- "$cur") ); return"#.to_string(),
+            Values::Fixed(values) => format!(
+                r#"COMPREPLY=( $(compgen -W "{}" -- "$cur") ); return"#,
+                values.join(" ")
+            ),
+            Values::Dynamic(kind) => format!(
+                r#"COMPREPLY=( $(compgen -W "$(agent-browser __complete {} 2>/dev/null)" -- "$cur") ); return"#,
+                kind
+            ),
+        };
+        value_cases.push_str(&format!("        {})\n            {} ;;\n", flag, action));
+    }
+
+    let mut sub_cases = String::new();
+    for (command, subs) in SUBCOMMANDS {
+        sub_cases.push_str(&format!(
+            "            {}) COMPREPLY=( $(compgen -W \"{}\" -- \"$cur\") ) ;;\n",
+            command,
+            subs.join(" ")
+        ));
+    }
+
+    r#"# agent-browser bash completion
+# Install: agent-browser completions bash > /etc/bash_completion.d/agent-browser
+#      or: echo 'source <(agent-browser completions bash)' >> ~/.bashrc
+
+_agent_browser() {
+    local cur prev cmd i
+    COMPREPLY=()
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+
+    case "$prev" in
+@VALUE_CASES@    esac
+
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=( $(compgen -W "@FLAGS@" -- "$cur") )
+        return
+    fi
+
+    cmd=""
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${COMP_WORDS[i]}" in
+            @VALUE_FLAGS@) ((i++)) ;;
+            -*) ;;
+            *) cmd="${COMP_WORDS[i]}"; break ;;
+        esac
+    done
+
+    if [[ -z "$cmd" ]]; then
+        COMPREPLY=( $(compgen -W "@COMMANDS@" -- "$cur") )
+    elif [[ $i -eq $((COMP_CWORD - 1)) ]]; then
+        case "$cmd" in
+@SUB_CASES@        esac
+    fi
+}
+
+complete -o default -F _agent_browser agent-browser
+"#
+    .replace("@VALUE_CASES@", &value_cases)
+    .replace("@SUB_CASES@", &sub_cases)
+    .replace("@FLAGS@", &all_flags().join(" "))
+    .replace("@VALUE_FLAGS@", &GLOBAL_FLAGS_WITH_VALUE.join("|"))
+    .replace("@COMMANDS@", &COMMANDS.join(" "))
+}
+
+fn zsh_script() -> String {
+    let mut value_cases = String::new();
+    for flag in GLOBAL_FLAGS_WITH_VALUE
+        .iter()
+        .chain(GLOBAL_FLAGS_WITH_OPTIONAL_VALUE)
+    {
+        let action = match flag_values(flag) {
+            Values::None => "return".to_string(),
+            Values::Files => "_files; return".to_string(),
+            Values::Fixed(values) => format!("compadd -- {}; return", values.join(" ")),
+            Values::Dynamic(kind) => format!(
+                r#"compadd -- ${{(f)"$(agent-browser __complete {} 2>/dev/null)"}}; return"#,
+                kind
+            ),
+        };
+        value_cases.push_str(&format!("        {})\n            {} ;;\n", flag, action));
+    }
+
+    let mut sub_cases = String::new();
+    for (command, subs) in SUBCOMMANDS {
+        sub_cases.push_str(&format!(
+            "            {}) compadd -- {} ;;\n",
+            command,
+            subs.join(" ")
+        ));
+    }
+
+    r#"#compdef agent-browser
+# agent-browser zsh completion
+# Install: agent-browser completions zsh > "${fpath[1]}/_agent-browser"
+#      or: echo 'source <(agent-browser completions zsh)' >> ~/.zshrc
+
+_agent_browser() {
+    local prev=${words[CURRENT-1]}
+    local cmd i
+
+    case $prev in
+@VALUE_CASES@    esac
+
+    if [[ $PREFIX == -* ]]; then
+        compadd -- @FLAGS@
+        return
+    fi
+
+    for ((i = 2; i < CURRENT; i++)); do
+        case ${words[i]} in
+            @VALUE_FLAGS@) ((i++)) ;;
+            -*) ;;
+            *) cmd=${words[i]}; break ;;
+        esac
+    done
+
+    if [[ -z $cmd ]]; then
+        compadd -- @COMMANDS@
+    elif (( i == CURRENT - 1 )); then
+        case $cmd in
+@SUB_CASES@        esac
+    fi
+}
+
+if [[ $funcstack[1] == _agent_browser ]]; then
+    _agent_browser "$@"
+else
+    compdef _agent_browser agent-browser
+fi
+"#
+    .replace("@VALUE_CASES@", &value_cases)
+    .replace("@SUB_CASES@", &sub_cases)
+    .replace("@FLAGS@", &all_flags().join(" "))
+    .replace("@VALUE_FLAGS@", &GLOBAL_FLAGS_WITH_VALUE.join("|"))
+    .replace("@COMMANDS@", &COMMANDS.join(" "))
+}
+
+fn fish_script() -> String {
+    let mut lines = String::new();
+    for flag in all_flags() {
+        let name = match flag.strip_prefix("--") {
+            Some(long) => format!("-l {}", long),
+            None => format!("-s {}", flag.trim_start_matches('-')),
+        };
+        let takes_value = GLOBAL_FLAGS_WITH_VALUE.contains(&flag)
+            || GLOBAL_FLAGS_WITH_OPTIONAL_VALUE.contains(&flag);
+        let values = if takes_value {
+            match flag_values(flag) {
+                Values::None => " -x".to_string(),
+                Values::Files => " -r -F".to_string(),
+                Values::Fixed(values) => format!(" -x -a \"{}\"", values.join(" ")),
+                Values::Dynamic(kind) => {
+                    format!(" -x -a \"(agent-browser __complete {} 2>/dev/null)\"", kind)
+                }
+            }
+        } else {
+            String::new()
+        };
+        lines.push_str(&format!("complete -c agent-browser {}{}\n", name, values));
+    }
+    for (command, subs) in SUBCOMMANDS {
+        lines.push_str(&format!(
+            "complete -c agent-browser -n \"__agent_browser_at_command {}\" -a \"{}\"\n",
+            command,
+            subs.join(" ")
+        ));
+    }
+
+    let value_flags: Vec<String> = GLOBAL_FLAGS_WITH_VALUE
+        .iter()
+        .map(|f| format!("'{}'", f))
+        .collect();
+
+    r#"# agent-browser fish completion
+# Install: agent-browser completions fish > ~/.config/fish/completions/agent-browser.fish
+
+# With no argument: true when the next word is the command itself.
+# With a command name: true when the next word directly follows that command.
+function __agent_browser_at_command
+    set -l tokens (commandline -opc)
+    set -e tokens[1]
+    set -l cmd
+    set -l after 0
+    set -l skip 0
+    for t in $tokens
+        if test $skip -eq 1
+            set skip 0
+            continue
+        end
+        if test -n "$cmd"
+            set after (math $after + 1)
+            continue
+        end
+        switch $t
+            case @VALUE_FLAGS@
+                set skip 1
+            case '-*'
+            case '*'
+                set cmd $t
+        end
+    end
+    if test (count $argv) -eq 0
+        test -z "$cmd"
+    else
+        test "$cmd" = "$argv[1]"; and test $after -eq 0
+    end
+end
+
+complete -c agent-browser -f
+complete -c agent-browser -n __agent_browser_at_command -a "@COMMANDS@"
+@LINES@"#
+        .replace("@VALUE_FLAGS@", &value_flags.join(" "))
+        .replace("@COMMANDS@", &COMMANDS.join(" "))
+        .replace("@LINES@", &lines)
+}
+
+fn powershell_script() -> String {
+    fn quoted(values: &[&str]) -> String {
+        values
+            .iter()
+            .map(|v| format!("'{}'", v))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    let mut value_cases = String::new();
+    for flag in GLOBAL_FLAGS_WITH_VALUE
+        .iter()
+        .chain(GLOBAL_FLAGS_WITH_OPTIONAL_VALUE)
+    {
+        let action = match flag_values(flag) {
+            // Returning nothing falls back to PowerShell's path completion.
+            Values::None | Values::Files => "return".to_string(),
+            Values::Fixed(values) => quoted(values),
+            Values::Dynamic(kind) => format!("agent-browser __complete {} 2>$null", kind),
+        };
+        value_cases.push_str(&format!("        '{}' {{ {} }}\n", flag, action));
+    }
+
+    let subcommands: Vec<String> = SUBCOMMANDS
+        .iter()
+        .map(|(command, subs)| format!("        '{}' = @({})", command, quoted(subs)))
+        .collect();
+
+    r#"# agent-browser PowerShell completion
+# Install: agent-browser completions powershell >> $PROFILE
+
+Register-ArgumentCompleter -Native -CommandName agent-browser -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $commands = @(@COMMANDS@)
+    $flags = @(@FLAGS@)
+    $valueFlags = @(@VALUE_FLAGS@)
+    $subcommands = @{
+@SUBCOMMANDS@
+    }
+
+    $words = @($commandAst.CommandElements | Select-Object -Skip 1 | ForEach-Object { $_.ToString() })
+    if ($wordToComplete -and $words.Count) {
+        $words = @($words | Select-Object -SkipLast 1)
+    }
+    $prev = if ($words.Count) { $words[-1] } else { '' }
+
+    $candidates = switch ($prev) {
+@VALUE_CASES@        default {
+            if ($wordToComplete -like '-*') {
+                $flags
+            } else {
+                $cmd = $null
+                $after = 0
+                $skip = $false
+                foreach ($w in $words) {
+                    if ($skip) { $skip = $false; continue }
+                    if ($cmd) { $after++; continue }
+                    if ($valueFlags -contains $w) { $skip = $true }
+                    elseif ($w -notlike '-*') { $cmd = $w }
+                }
+                if (-not $cmd) { $commands }
+                elseif ($after -eq 0 -and $subcommands.ContainsKey($cmd)) { $subcommands[$cmd] }
+            }
+        }
+    }
+
+    $candidates | Where-Object { $_ -like "$wordToComplete*" } | ForEach-Object {
+        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+    }
+}
+"#
+    .replace("@COMMANDS@", &quoted(COMMANDS))
+    .replace("@FLAGS@", &quoted(&all_flags()))
+    .replace("@VALUE_FLAGS@", &quoted(GLOBAL_FLAGS_WITH_VALUE))
+    .replace("@SUBCOMMANDS@", &subcommands.join("\n"))
+    .replace("@VALUE_CASES@", &value_cases)
+}
+
+pub fn completion_script(shell: &str) -> Option<String> {
+    match shell {
+        "bash" => Some(bash_script()),
+        "zsh" => Some(zsh_script()),
+        "fish" => Some(fish_script()),
+        "powershell" | "pwsh" => Some(powershell_script()),
+        _ => None,
+    }
+}
+
+/// BrowserOS profile names created with `--new <name>`.
+fn list_profiles() -> Vec<String> {
+    let mut profiles: Vec<String> = fs::read_dir(get_browseros_home().join("profiles"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    if !profiles.iter().any(|p| p == "default") {
+        profiles.push("default".to_string());
+    }
+    profiles.sort();
+    profiles
+}
+
+/// Handle `agent-browser completions <shell>` (runs locally, no daemon).
+pub fn run_completions(args: &[String]) {
+    let Some(shell) = args.get(1) else {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            format!(
+                "Missing arguments for: completions\nUsage: agent-browser completions <{}>",
+                SHELLS.join("|")
+            ),
+        );
+    };
+    match completion_script(shell) {
+        Some(script) => print!("{}", script),
+        None => ui::fail(
+            ErrorCode::InvalidArgs,
+            format!(
+                "Unknown shell: {}\nValid options: {}",
+                shell,
+                SHELLS.join(", ")
+            ),
+        ),
+    }
+}
+
+/// Handle the hidden `agent-browser __complete <kind>` used by the scripts.
+/// Prints one value per line and never fails, so a broken state directory
+/// only means fewer suggestions.
+pub fn run_complete(kind: Option<&str>) {
+    let values = match kind {
+        Some("sessions") => list_sessions(),
+        Some("profiles") => list_profiles(),
+        Some("commands") => COMMANDS.iter().map(|c| c.to_string()).collect(),
+        _ => Vec::new(),
+    };
+    for value in values {
+        println!("{}", value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{parse_command, ParseError};
+    use crate::flags::parse_flags;
+
+    #[test]
+    fn test_every_shell_has_a_script() {
+        for shell in SHELLS {
+            let script = completion_script(shell).unwrap();
+            assert!(script.contains("agent-browser __complete sessions"));
+            assert!(script.contains("snapshot"));
+            for placeholder in ["@COMMANDS@", "@FLAGS@", "@VALUE_FLAGS@", "@VALUE_CASES@"] {
+                assert!(
+                    !script.contains(placeholder),
+                    "{} left in {}",
+                    placeholder,
+                    shell
+                );
+            }
+        }
+        assert!(completion_script("tcsh").is_none());
+    }
+
+    #[test]
+    fn test_subcommands_are_known_commands() {
+        for (command, _) in SUBCOMMANDS {
+            assert!(COMMANDS.contains(command), "{} is not a command", command);
+        }
+    }
+
+    #[test]
+    fn test_every_parsed_subcommand_is_completed() {
+        // The parser lists a command's subcommands when given one it doesn't know
+        let flags = parse_flags(&[]);
+        for command in COMMANDS {
+            let args = [command.to_string(), "not-a-subcommand".to_string()];
+            let Err(ParseError::UnknownSubcommand { valid_options, .. }) =
+                parse_command(&args, &flags)
+            else {
+                continue;
+            };
+            let offered = SUBCOMMANDS
+                .iter()
+                .find(|(name, _)| name == command)
+                .map(|(_, subs)| *subs)
+                .unwrap_or_else(|| panic!("{} has no subcommand completions", command));
+            for sub in valid_options {
+                assert!(
+                    offered.contains(sub),
+                    "{} {} is not completed",
+                    command,
+                    sub
+                );
+            }
+        }
+    }
+}
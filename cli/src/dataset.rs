@@ -0,0 +1,173 @@
+//! Data files for data-driven runs (`run --data <file>`).
+//!
+//! CSV files have a header row naming the fields, with fields quoted as in
+//! RFC 4180 (`"a, b"`, `"say ""hi"""`, line breaks inside quotes). NDJSON
+//! (or JSON Lines) files have one JSON object per line; values that aren't
+//! strings are kept as JSON text. Either way each row becomes a list of
+//! `(field, value)` pairs: in column order for CSV, sorted for NDJSON.
+
+use std::fs;
+use std::path::Path;
+
+pub type Row = Vec<(String, String)>;
+
+/// Split CSV text into records of fields.
+fn parse_records(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                line += 1;
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!("unterminated quote (line {})", line));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    // Blank lines separate nothing
+    records.retain(|r| !(r.len() == 1 && r[0].is_empty()));
+    Ok(records)
+}
+
+pub fn parse_csv(text: &str) -> Result<Vec<Row>, String> {
+    let mut records = parse_records(text)?.into_iter();
+    let header: Vec<String> = records
+        .next()
+        .ok_or("no header row")?
+        .into_iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+    if let Some(empty) = header.iter().position(String::is_empty) {
+        return Err(format!("column {} has no name in the header", empty + 1));
+    }
+    records
+        .enumerate()
+        .map(|(i, record)| {
+            if record.len() != header.len() {
+                return Err(format!(
+                    "row {} has {} fields, but the header has {}",
+                    i + 1,
+                    record.len(),
+                    header.len()
+                ));
+            }
+            Ok(header.iter().cloned().zip(record).collect())
+        })
+        .collect()
+}
+
+pub fn parse_ndjson(text: &str) -> Result<Vec<Row>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let value: serde_json::Value =
+                serde_json::from_str(line).map_err(|e| format!("line {}: {}", i + 1, e))?;
+            let object = value
+                .as_object()
+                .ok_or_else(|| format!("line {}: expected a JSON object", i + 1))?;
+            Ok(object
+                .iter()
+                .map(|(k, v)| {
+                    let value = match v {
+                        serde_json::Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    (k.clone(), value)
+                })
+                .collect())
+        })
+        .collect()
+}
+
+/// Read the rows of a `.csv`, `.ndjson` or `.jsonl` file.
+pub fn load_rows(path: &Path) -> Result<Vec<Row>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let rows = match extension.as_str() {
+        "csv" => parse_csv(&text),
+        "ndjson" | "jsonl" => parse_ndjson(&text),
+        _ => {
+            return Err(format!(
+                "Unsupported data file: {} (expected .csv, .ndjson or .jsonl)",
+                path.display()
+            ))
+        }
+    };
+    let rows = rows.map_err(|e| format!("{}: {}", path.display(), e))?;
+    if rows.is_empty() {
+        return Err(format!("{} has no rows", path.display()));
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(row: &Row) -> Vec<(&str, &str)> {
+        row.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect()
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let rows = parse_csv(
+            "name,address,note\r\nAda,\"1 Main St, Springfield\",\"said \"\"hi\"\"\"\n\nBob,,\"two\nlines\"\n",
+        )
+        .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            pairs(&rows[0]),
+            vec![
+                ("name", "Ada"),
+                ("address", "1 Main St, Springfield"),
+                ("note", "said \"hi\"")
+            ]
+        );
+        assert_eq!(rows[1][2].1, "two\nlines");
+
+        assert!(parse_csv("a,b\n1\n").is_err());
+        assert!(parse_csv("a,\n1,2\n").is_err());
+        assert!(parse_csv("a\n\"open\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_ndjson() {
+        let rows = parse_ndjson("{\"sku\":\"A-1\",\"qty\":2}\n\n{\"sku\":\"B-2\",\"gift\":true}\n")
+            .unwrap();
+        assert_eq!(pairs(&rows[0]), vec![("qty", "2"), ("sku", "A-1")]);
+        assert_eq!(pairs(&rows[1]), vec![("gift", "true"), ("sku", "B-2")]);
+        assert!(parse_ndjson("[1,2]\n").is_err());
+    }
+}
@@ -0,0 +1,689 @@
+//! Page state diffs (`agent-browser diff`).
+//!
+//! Compares two saved states of a page, locally: `snapshot` output or any
+//! other text (`text`), HTML from `freeze` or `get html` (`dom`), or
+//! screenshots (`visual`). The mode follows the files' extension unless
+//! given, and `--json` output of `snapshot` or `get html` can be compared as
+//! saved.
+//!
+//! Text and DOM changes are line diffs. Snapshot refs (`[ref=e12]`) are
+//! ignored, since every snapshot renumbers them. The DOM is compared as an
+//! outline, one element or text node per line with attributes sorted, so
+//! reformatting isn't a change; scripts are left out, and long attribute
+//! values (data: URLs in a frozen page) and stylesheets are compared by hash.
+//! Visual diffs use the pixel comparison of `compare-screenshot` and write a
+//! diff image.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex_lite::Regex;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::color;
+use crate::errors::ErrorCode;
+use crate::ui;
+use crate::visual;
+
+const USAGE: &str = "diff --before <file> --after <file> [--mode text|dom|visual] [--threshold <0-1>] [--diff <path>] [--exit-code]";
+
+/// Unchanged lines shown around each change
+const CONTEXT: usize = 3;
+
+/// Past this many edits the files are treated as replaced outright
+const MAX_EDITS: usize = 4000;
+
+/// Attribute values longer than this are compared by hash
+const LONG_VALUE: usize = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    Text,
+    Dom,
+    Visual,
+}
+
+impl Mode {
+    fn parse(name: &str) -> Option<Mode> {
+        match name {
+            "text" => Some(Mode::Text),
+            "dom" => Some(Mode::Dom),
+            "visual" => Some(Mode::Visual),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Mode::Text => "text",
+            Mode::Dom => "dom",
+            Mode::Visual => "visual",
+        }
+    }
+
+    /// From the extension: screenshots are visual, HTML is DOM, the rest text
+    fn detect(path: &Path) -> Mode {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("png") => Mode::Visual,
+            Some("html" | "htm" | "xhtml") => Mode::Dom,
+            _ => Mode::Text,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Same,
+    Removed,
+    Added,
+}
+
+/// Line edits turning `a` into `b` (Myers), common prefix and suffix first
+pub fn diff_lines<'a>(a: &[&'a str], b: &[&'a str]) -> Vec<(Op, &'a str)> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+    let mut ops: Vec<(Op, &str)> = a[..prefix].iter().map(|l| (Op::Same, *l)).collect();
+    ops.extend(myers(a_mid, b_mid).unwrap_or_else(|| {
+        a_mid
+            .iter()
+            .map(|l| (Op::Removed, *l))
+            .chain(b_mid.iter().map(|l| (Op::Added, *l)))
+            .collect()
+    }));
+    ops.extend(a[a.len() - suffix..].iter().map(|l| (Op::Same, *l)));
+    ops
+}
+
+/// The shortest edit script, or None past `MAX_EDITS`
+fn myers<'a>(a: &[&'a str], b: &[&'a str]) -> Option<Vec<(Op, &'a str)>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = (n + m).min(MAX_EDITS as isize);
+    let offset = max + 1;
+    let mut v = vec![0isize; 2 * max as usize + 3];
+    // v[k] for -d..=d, kept for every d to walk back through
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    let at = |k: isize| (k + offset) as usize;
+    'search: {
+        for d in 0..=max {
+            for k in (-d..=d).step_by(2) {
+                let mut x = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                    v[at(k + 1)]
+                } else {
+                    v[at(k - 1)] + 1
+                };
+                let mut y = x - k;
+                while x < n && y < m && a[x as usize] == b[y as usize] {
+                    x += 1;
+                    y += 1;
+                }
+                v[at(k)] = x;
+                if x >= n && y >= m {
+                    trace.push(v[at(-d)..=at(d)].to_vec());
+                    break 'search;
+                }
+            }
+            trace.push(v[at(-d)..=at(d)].to_vec());
+        }
+        return None;
+    }
+    let mut ops = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (0..trace.len() as isize).rev() {
+        let k = x - y;
+        let get = |d: isize, k: isize| trace[d as usize][(k + d) as usize];
+        let prev_k = if d == 0 {
+            0
+        } else if k == -d || (k != d && get(d - 1, k - 1) < get(d - 1, k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = if d == 0 { 0 } else { get(d - 1, prev_k) };
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            ops.push((Op::Same, a[x as usize - 1]));
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            if x == prev_x {
+                ops.push((Op::Added, b[y as usize - 1]));
+            } else {
+                ops.push((Op::Removed, a[x as usize - 1]));
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    ops.reverse();
+    Some(ops)
+}
+
+fn short_hash(text: &str) -> String {
+    let digest = Sha256::digest(text.as_bytes());
+    digest[..6].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// `(name, attributes, self-closing)` of the tag inside `<...>`
+fn parse_tag(inner: &str) -> (String, Vec<(String, String)>, bool) {
+    let self_closing = inner.ends_with('/');
+    let inner = inner.trim_end_matches('/');
+    let chars: Vec<char> = inner.chars().collect();
+    let mut i = 0;
+    let word = |i: &mut usize| {
+        let start = *i;
+        while *i < chars.len() && !chars[*i].is_whitespace() && !matches!(chars[*i], '=' | '/') {
+            *i += 1;
+        }
+        chars[start..*i].iter().collect::<String>()
+    };
+    let name = word(&mut i).to_ascii_lowercase();
+    let mut attrs = Vec::new();
+    while i < chars.len() {
+        if chars[i].is_whitespace() || chars[i] == '/' {
+            i += 1;
+            continue;
+        }
+        let attr = word(&mut i).to_ascii_lowercase();
+        let mut value = String::new();
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        if chars.get(i) == Some(&'=') {
+            i += 1;
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+            match chars.get(i) {
+                Some(&q @ ('"' | '\'')) => {
+                    let start = i + 1;
+                    i = start;
+                    while i < chars.len() && chars[i] != q {
+                        i += 1;
+                    }
+                    value = chars[start..i.min(chars.len())].iter().collect();
+                    i += 1;
+                }
+                _ => {
+                    let start = i;
+                    while i < chars.len() && !chars[i].is_whitespace() {
+                        i += 1;
+                    }
+                    value = chars[start..i].iter().collect();
+                }
+            }
+        }
+        if !attr.is_empty() {
+            attrs.push((attr, value));
+        }
+    }
+    attrs.sort();
+    (name, attrs, self_closing)
+}
+
+/// End of the tag starting at `start` (`html[start] == '<'`), skipping quotes
+fn tag_end(html: &str, start: usize) -> usize {
+    let mut quote = None;
+    for (i, c) in html[start..].char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '>') => return start + i,
+            _ => {}
+        }
+    }
+    html.len()
+}
+
+/// The document as an indented outline: one line per element and text node
+pub fn dom_outline(html: &str) -> Vec<String> {
+    const VOID: &[&str] = &[
+        "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param",
+        "source", "track", "wbr",
+    ];
+    let lower = html.to_ascii_lowercase();
+    let mut lines = Vec::new();
+    let mut stack: Vec<String> = Vec::new();
+    let mut i = 0;
+    let push_text = |lines: &mut Vec<String>, depth: usize, text: &str| {
+        let text = collapse(text);
+        if !text.is_empty() {
+            lines.push(format!("{}\"{}\"", "  ".repeat(depth), text));
+        }
+    };
+    while i < html.len() {
+        let Some(offset) = html[i..].find('<') else {
+            push_text(&mut lines, stack.len(), &html[i..]);
+            break;
+        };
+        push_text(&mut lines, stack.len(), &html[i..i + offset]);
+        let start = i + offset;
+        if html[start..].starts_with("<!--") {
+            i = html[start..]
+                .find("-->")
+                .map_or(html.len(), |e| start + e + 3);
+            continue;
+        }
+        let end = tag_end(html, start);
+        let inner = &html[start + 1..end];
+        i = end + 1;
+        if inner.starts_with('!') || inner.starts_with('?') {
+            continue;
+        }
+        if let Some(name) = inner.strip_prefix('/') {
+            let name = name.trim().to_ascii_lowercase();
+            if let Some(pos) = stack.iter().rposition(|open| *open == name) {
+                stack.truncate(pos);
+            }
+            continue;
+        }
+        let (name, attrs, self_closing) = parse_tag(inner);
+        if name.is_empty() {
+            continue;
+        }
+        // Raw text elements run to their closing tag
+        if matches!(name.as_str(), "script" | "style" | "textarea" | "title") {
+            let close = format!("</{}", name);
+            let body_end = lower[i.min(html.len())..]
+                .find(&close)
+                .map_or(html.len(), |e| i + e);
+            let body = &html[i.min(html.len())..body_end];
+            i = tag_end(html, body_end).saturating_add(1).min(html.len());
+            if name == "script" {
+                continue;
+            }
+            let depth = "  ".repeat(stack.len());
+            lines.push(format!("{}{}", depth, render_tag(&name, &attrs)));
+            if name == "style" {
+                let css = collapse(body);
+                if !css.is_empty() {
+                    lines.push(format!(
+                        "{}  css #{} ({} bytes)",
+                        depth,
+                        short_hash(&css),
+                        css.len()
+                    ));
+                }
+            } else {
+                push_text(&mut lines, stack.len() + 1, body);
+            }
+            continue;
+        }
+        lines.push(format!(
+            "{}{}",
+            "  ".repeat(stack.len()),
+            render_tag(&name, &attrs)
+        ));
+        if !self_closing && !VOID.contains(&name.as_str()) {
+            stack.push(name);
+        }
+    }
+    lines
+}
+
+fn render_tag(name: &str, attrs: &[(String, String)]) -> String {
+    let mut out = format!("<{}", name);
+    for (attr, value) in attrs {
+        if value.is_empty() {
+            out += &format!(" {}", attr);
+        } else if value.len() > LONG_VALUE {
+            let head: String = value.chars().take(24).collect();
+            out += &format!(" {}=\"{}…#{}\"", attr, head, short_hash(value));
+        } else {
+            out += &format!(" {}=\"{}\"", attr, value);
+        }
+    }
+    out + ">"
+}
+
+/// Snapshot refs, which differ between any two snapshots
+fn strip_refs(text: &str) -> String {
+    let refs = Regex::new(r" ?\[ref=e\d+\]").unwrap();
+    refs.replace_all(text, "").to_string()
+}
+
+/// A file's text; saved `--json` output gives the field the mode compares
+fn read_text(path: &Path, mode: Mode) -> Result<String, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let field = if mode == Mode::Dom {
+        "html"
+    } else {
+        "snapshot"
+    };
+    let saved = serde_json::from_str::<Value>(&text).ok().and_then(|v| {
+        v.get("data")
+            .unwrap_or(&v)
+            .get(field)
+            .and_then(|f| f.as_str())
+            .map(String::from)
+    });
+    Ok(saved.unwrap_or(text))
+}
+
+/// Changed lines, numbered in the file they belong to
+fn changes(ops: &[(Op, &str)]) -> Vec<Value> {
+    let (mut before, mut after) = (0, 0);
+    let mut out = Vec::new();
+    for (op, line) in ops {
+        match op {
+            Op::Same => {
+                before += 1;
+                after += 1;
+            }
+            Op::Removed => {
+                before += 1;
+                out.push(json!({ "op": "removed", "line": before, "text": line }));
+            }
+            Op::Added => {
+                after += 1;
+                out.push(json!({ "op": "added", "line": after, "text": line }));
+            }
+        }
+    }
+    out
+}
+
+/// Unified diff hunks with `CONTEXT` lines around each change
+pub fn hunks(ops: &[(Op, &str)]) -> Vec<String> {
+    let changed: Vec<usize> = (0..ops.len()).filter(|&i| ops[i].0 != Op::Same).collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < changed.len() {
+        let start = changed[i].saturating_sub(CONTEXT);
+        let mut end = changed[i];
+        while i < changed.len() && changed[i] <= end + 2 * CONTEXT + 1 {
+            end = changed[i];
+            i += 1;
+        }
+        let end = (end + CONTEXT + 1).min(ops.len());
+        let count = |skip: Op, range: std::ops::Range<usize>| {
+            ops[range].iter().filter(|(op, _)| *op != skip).count()
+        };
+        let before_start = count(Op::Added, 0..start) + 1;
+        let after_start = count(Op::Removed, 0..start) + 1;
+        out.push(color::cyan(&format!(
+            "@@ -{},{} +{},{} @@",
+            before_start,
+            count(Op::Added, start..end),
+            after_start,
+            count(Op::Removed, start..end)
+        )));
+        for (op, line) in &ops[start..end] {
+            out.push(match op {
+                Op::Same => format!("  {}", line),
+                Op::Removed => color::red(&format!("- {}", line)),
+                Op::Added => color::green(&format!("+ {}", line)),
+            });
+        }
+    }
+    out
+}
+
+/// Lines of snapshot output or other text as they are compared
+fn text_lines(text: &str) -> Vec<String> {
+    strip_refs(text)
+        .lines()
+        .map(|l| l.trim_end().to_string())
+        .collect()
+}
+
+/// Compare two snapshots (or other texts) held in memory, like `diff --mode text`
+pub fn compare_text(before: &str, after: &str) -> (Value, Vec<String>) {
+    compare(&text_lines(before), &text_lines(after))
+}
+
+fn compare_lines(before: &Path, after: &Path, mode: Mode) -> Result<(Value, Vec<String>), String> {
+    let load = |path: &Path| -> Result<Vec<String>, String> {
+        let text = read_text(path, mode)?;
+        Ok(match mode {
+            Mode::Dom => dom_outline(&text),
+            _ => text_lines(&text),
+        })
+    };
+    Ok(compare(&load(before)?, &load(after)?))
+}
+
+/// The change summary and hunks between two lists of lines
+fn compare(a: &[String], b: &[String]) -> (Value, Vec<String>) {
+    let a: Vec<&str> = a.iter().map(String::as_str).collect();
+    let b: Vec<&str> = b.iter().map(String::as_str).collect();
+    let ops = diff_lines(&a, &b);
+    let changes = changes(&ops);
+    let added = changes.iter().filter(|c| c["op"] == "added").count();
+    let data = json!({
+        "changed": !changes.is_empty(),
+        "added": added,
+        "removed": changes.len() - added,
+        "changes": changes,
+    });
+    (data, hunks(&ops))
+}
+
+fn compare_images(
+    before: &Path,
+    after: &Path,
+    threshold: f64,
+    diff_path: &Path,
+) -> Result<Value, String> {
+    let (a, b) = (visual::read_png(before)?, visual::read_png(after)?);
+    if (a.width, a.height) != (b.width, b.height) {
+        return Ok(json!({
+            "changed": true,
+            "note": format!("Sizes differ: {}x{} before, {}x{} after", a.width, a.height, b.width, b.height),
+        }));
+    }
+    let comparison = visual::compare(&a, &b, threshold)?;
+    let mut data = json!({
+        "changed": comparison.diff_pixels > 0,
+        "diffPixels": comparison.diff_pixels,
+        "totalPixels": comparison.total_pixels,
+        "diffRatio": comparison.diff_pixels as f64 / comparison.total_pixels.max(1) as f64,
+    });
+    if comparison.diff_pixels > 0 {
+        visual::write_png(diff_path, &comparison.diff_image)?;
+        data["diff"] = json!(diff_path);
+        data["artifacts"] = json!([diff_path]);
+    }
+    Ok(data)
+}
+
+/// Handle `agent-browser diff` (runs locally, no daemon)
+pub fn run_diff(args: &[String]) {
+    let invalid = |message: String| -> ! {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            format!("{}\nUsage: agent-browser {}", message, USAGE),
+        )
+    };
+    let (mut before, mut after, mut mode, mut diff_path) = (None, None, None, None);
+    let mut threshold = 0.1;
+    let mut exit_code = false;
+    let mut i = 1;
+    while i < args.len() {
+        let value = |i: usize| {
+            args.get(i + 1)
+                .cloned()
+                .unwrap_or_else(|| invalid(format!("{} needs a value", args[i])))
+        };
+        match args[i].as_str() {
+            "--before" => before = Some(PathBuf::from(value(i))),
+            "--after" => after = Some(PathBuf::from(value(i))),
+            "--mode" => {
+                let name = value(i);
+                mode = Some(Mode::parse(&name).unwrap_or_else(|| {
+                    invalid(format!(
+                        "Unknown diff mode: {} (expected text, dom or visual)",
+                        name
+                    ))
+                }));
+            }
+            "--threshold" => {
+                threshold = value(i)
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|t| (0.0..=1.0).contains(t))
+                    .unwrap_or_else(|| invalid("--threshold must be between 0 and 1".into()));
+            }
+            "--diff" => diff_path = Some(PathBuf::from(value(i))),
+            "--exit-code" => {
+                exit_code = true;
+                i += 1;
+                continue;
+            }
+            // Positionals fill --before, then --after
+            arg if !arg.starts_with("--") && before.is_none() => before = Some(PathBuf::from(arg)),
+            arg if !arg.starts_with("--") && after.is_none() => after = Some(PathBuf::from(arg)),
+            other => invalid(format!("Unknown diff option: {}", other)),
+        }
+        i += if args[i].starts_with("--") { 2 } else { 1 };
+    }
+    let (Some(before), Some(after)) = (before, after) else {
+        invalid("diff needs --before and --after".into());
+    };
+    let mode = mode.unwrap_or_else(|| Mode::detect(&before));
+
+    let (mut data, hunks) = match mode {
+        Mode::Visual => {
+            let diff_path = diff_path.unwrap_or_else(|| visual::default_diff_path(&after));
+            compare_images(&before, &after, threshold, &diff_path).map(|d| (d, Vec::new()))
+        }
+        _ => compare_lines(&before, &after, mode),
+    }
+    .unwrap_or_else(|e| ui::fail(ErrorCode::Unknown, e));
+    data["mode"] = json!(mode.name());
+    data["before"] = json!(before);
+    data["after"] = json!(after);
+    let changed = data["changed"].as_bool().unwrap_or(false);
+
+    if ui::json_mode() {
+        ui::print_json_data(data);
+    } else if !changed {
+        ui::status(format!(
+            "{} No changes {}",
+            color::success_indicator(),
+            color::dim(&format!("({})", mode.name()))
+        ));
+    } else if mode == Mode::Visual {
+        match data["note"].as_str() {
+            Some(note) => println!("{}", note),
+            None => println!(
+                "{} of {} pixels changed ({:.2}%)",
+                data["diffPixels"],
+                data["totalPixels"],
+                data["diffRatio"].as_f64().unwrap_or(0.0) * 100.0
+            ),
+        }
+        if let Some(diff) = data["diff"].as_str() {
+            println!("  diff: {}", color::green(diff));
+        }
+    } else {
+        println!("{}", color::red(&format!("--- {}", before.display())));
+        println!("{}", color::green(&format!("+++ {}", after.display())));
+        for line in hunks {
+            println!("{}", line);
+        }
+        ui::status(color::dim(&format!(
+            "{} line(s) added, {} removed",
+            data["added"], data["removed"]
+        )));
+    }
+    if exit_code && changed {
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ops(a: &str, b: &str) -> String {
+        let (a, b): (Vec<&str>, Vec<&str>) = (a.split(' ').collect(), b.split(' ').collect());
+        diff_lines(&a, &b)
+            .iter()
+            .map(|(op, line)| match op {
+                Op::Same => line.to_string(),
+                Op::Removed => format!("-{}", line),
+                Op::Added => format!("+{}", line),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        assert_eq!(ops("a b c", "a b c"), "a b c");
+        assert_eq!(ops("a b c d", "a x c d e"), "a -b +x c d +e");
+        assert_eq!(
+            ops("a b c a b b a", "c b a b a c"),
+            "-a -b c +b a b -b a +c"
+        );
+    }
+
+    #[test]
+    fn test_hunks() {
+        let a: Vec<String> = (1..=20).map(|i| i.to_string()).collect();
+        let mut b = a.clone();
+        b[9] = "ten".to_string();
+        let (a, b): (Vec<&str>, Vec<&str>) = (
+            a.iter().map(String::as_str).collect(),
+            b.iter().map(String::as_str).collect(),
+        );
+        let hunks = hunks(&diff_lines(&a, &b));
+        assert_eq!(hunks.len(), 9);
+        assert!(hunks[0].contains("@@ -7,7 +7,7 @@"));
+        assert!(hunks[4].contains("- 10") && hunks[5].contains("+ ten"));
+    }
+
+    #[test]
+    fn test_dom_outline() {
+        let html = r#"<!DOCTYPE html><html><head><title>Shop</title>
+            <script>if (a < b) {}</script><style>p { color: red }</style></head>
+            <body class="x"   id='main'><!-- note --><p>Hello
+            <b>world</b><br>!</p><img alt="a > b" src=img/pic.png /></body></html>"#;
+        let outline = dom_outline(html);
+        assert_eq!(
+            outline,
+            vec![
+                "<html>",
+                "  <head>",
+                "    <title>",
+                "      \"Shop\"",
+                "    <style>",
+                &format!("      css #{} (16 bytes)", short_hash("p { color: red }")),
+                "  <body class=\"x\" id=\"main\">",
+                "    <p>",
+                "      \"Hello\"",
+                "      <b>",
+                "        \"world\"",
+                "      <br>",
+                "      \"!\"",
+                "    <img alt=\"a > b\" src=\"img/pic.png\">",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strip_refs() {
+        assert_eq!(
+            strip_refs("- button \"Buy\" [ref=e12]\n- link \"Home\" [ref=e3] [nth=1]"),
+            "- button \"Buy\"\n- link \"Home\" [nth=1]"
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! Disk space of the BrowserOS home.
+//!
+//! `agent-browser install` checks there is room for the package and what
+//! installing it takes before it downloads anything. The optional `quota`
+//! config key (or `AGENT_BROWSER_QUOTA`) caps how much `~/.browseros` may
+//! hold; installs that would exceed it are refused, and `doctor` (see
+//! `doctor.rs`) and `status` warn when it is nearly or entirely used up.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use serde_json::json;
+
+use crate::artifacts::parse_size;
+use crate::color;
+use crate::config::Config;
+use crate::connection::{daemon_ready, list_sessions};
+use crate::errors::ErrorCode;
+use crate::i18n::tf;
+use crate::install::get_browseros_home;
+use crate::output::format_bytes;
+use crate::ui;
+
+/// Below this much free space the browser itself starts failing to write its
+/// profile and cache
+const LOW_FREE_BYTES: u64 = 1_000_000_000;
+/// Share of the quota at which `doctor` and `status` start warning
+const QUOTA_WARN_PERCENT: u64 = 90;
+
+/// Bytes available to this user on the filesystem holding `path`, which need
+/// not exist yet. None where it can't be determined.
+#[cfg(unix)]
+pub fn free_space(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let c_path = CString::new(existing.as_os_str().as_bytes()).ok()?;
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    let rc = unsafe { libc::statvfs(c_path.as_ptr(), &mut stats) };
+    (rc == 0).then(|| stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+pub fn free_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Total size of the files under `dir`, not following symlinks
+pub fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) if meta.is_file() => meta.len(),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// The configured quota for `~/.browseros`, if any
+pub fn quota() -> Option<u64> {
+    env::var("AGENT_BROWSER_QUOTA")
+        .ok()
+        .or_else(|| Config::load().get_str("quota"))
+        .and_then(|v| parse_size(&v))
+}
+
+pub struct Usage {
+    pub used: u64,
+    pub quota: Option<u64>,
+    pub free: Option<u64>,
+}
+
+pub fn usage() -> Usage {
+    let home = get_browseros_home();
+    Usage {
+        used: dir_size(&home),
+        quota: quota(),
+        free: free_space(&home),
+    }
+}
+
+/// Warnings about the BrowserOS home running out of room
+pub fn warnings(usage: &Usage) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if let Some(quota) = usage.quota {
+        let args: &[(&str, &dyn std::fmt::Display)] = &[
+            ("used", &format_bytes(usage.used)),
+            ("quota", &format_bytes(quota)),
+        ];
+        if usage.used > quota {
+            warnings.push(tf("disk.over_quota", args));
+        } else if usage.used * 100 >= quota * QUOTA_WARN_PERCENT {
+            warnings.push(tf("disk.near_quota", args));
+        }
+    }
+    if let Some(free) = usage.free.filter(|&free| free < LOW_FREE_BYTES) {
+        warnings.push(tf("disk.low_free", &[("free", &format_bytes(free))]));
+    }
+    warnings
+}
+
+/// Why `needed` more bytes can't be written to the BrowserOS home, if so
+pub fn check_room(usage: &Usage, needed: u64) -> Result<(), String> {
+    if let Some(free) = usage.free.filter(|&free| free < needed) {
+        return Err(tf(
+            "disk.no_room",
+            &[
+                ("needed", &format_bytes(needed)),
+                ("free", &format_bytes(free)),
+                ("path", &get_browseros_home().display()),
+            ],
+        ));
+    }
+    if let Some(quota) = usage.quota.filter(|&quota| usage.used + needed > quota) {
+        return Err(tf(
+            "disk.quota_exceeded",
+            &[
+                ("needed", &format_bytes(needed)),
+                ("used", &format_bytes(usage.used)),
+                ("quota", &format_bytes(quota)),
+            ],
+        ));
+    }
+    Ok(())
+}
+
+/// Fail before a download that won't fit
+pub fn preflight(needed: u64) {
+    if let Err(e) = check_room(&usage(), needed) {
+        ui::fail(ErrorCode::DiskSpace, e);
+    }
+}
+
+pub fn usage_json(usage: &Usage, warnings: &[String]) -> serde_json::Value {
+    json!({
+        "home": get_browseros_home(),
+        "used": usage.used,
+        "quota": usage.quota,
+        "free": usage.free,
+        "warnings": warnings,
+    })
+}
+
+pub fn print_usage(usage: &Usage) {
+    let quota = usage
+        .quota
+        .map(|q| format!(" of {} quota", format_bytes(q)))
+        .unwrap_or_default();
+    println!(
+        "  {:<10} {}{} {}",
+        "Disk",
+        format_bytes(usage.used),
+        quota,
+        color::dim(&format!("({})", get_browseros_home().display()))
+    );
+    if let Some(free) = usage.free {
+        println!("  {:<10} {}", "Free", format_bytes(free));
+    }
+}
+
+/// Handle `agent-browser status` (runs locally, no daemon)
+pub fn run_status(session: &str) {
+    let usage = usage();
+    let warnings = warnings(&usage);
+    let running = daemon_ready(session);
+    if ui::json_mode() {
+        ui::print_json_data(json!({
+            "session": session,
+            "running": running,
+            "sessions": list_sessions(),
+            "disk": usage_json(&usage, &warnings),
+        }));
+        return;
+    }
+    let state = if running {
+        color::green("running")
+    } else {
+        color::dim("not running")
+    };
+    println!("  {:<10} {} ({})", "Session", session, state);
+    print_usage(&usage);
+    for warning in &warnings {
+        println!("{} {}", color::warning_indicator(), warning);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GB: u64 = 1_000_000_000;
+
+    #[test]
+    fn test_check_room() {
+        let roomy = Usage {
+            used: GB,
+            quota: None,
+            free: Some(10 * GB),
+        };
+        assert!(check_room(&roomy, 2 * GB).is_ok());
+        assert!(check_room(&roomy, 20 * GB).is_err());
+
+        let capped = Usage {
+            quota: Some(2 * GB),
+            ..roomy
+        };
+        assert!(check_room(&capped, GB / 2).is_ok());
+        assert!(check_room(&capped, 2 * GB).is_err());
+
+        let unknown = Usage {
+            used: 0,
+            quota: None,
+            free: None,
+        };
+        assert!(check_room(&unknown, 100 * GB).is_ok());
+    }
+
+    #[test]
+    fn test_warnings() {
+        let usage = |used, quota, free| Usage { used, quota, free };
+        assert!(warnings(&usage(GB, Some(5 * GB), Some(10 * GB))).is_empty());
+        assert_eq!(warnings(&usage(19 * GB / 4, Some(5 * GB), None)).len(), 1);
+        assert_eq!(
+            warnings(&usage(6 * GB, Some(5 * GB), Some(GB / 2))).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_dir_size() {
+        let dir = env::temp_dir().join(format!("agent-browser-disk-{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("a"), [0u8; 10]).unwrap();
+        fs::write(dir.join("nested").join("b"), [0u8; 5]).unwrap();
+        assert_eq!(dir_size(&dir), 15);
+        if cfg!(unix) {
+            assert!(free_space(&dir.join("missing").join("deeper")).is_some());
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,235 @@
+//! `agent-browser doctor`: what a session needs before it can start.
+//!
+//! Runs locally, without a daemon. It checks that the daemon's socket
+//! directory is writable and the session's daemon (if running) answers, that
+//! the browser a launch would use is there and can be run, that
+//! `~/.browseros` is writable and not open to other users, and the disk
+//! space and quota warnings of `disk`.
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::json;
+
+use crate::color;
+use crate::connection::{daemon_ready, get_socket_dir, is_daemon_running};
+use crate::disk;
+use crate::flags::Flags;
+use crate::i18n::{t, tf};
+use crate::install::{get_browseros_home, installed_executable};
+use crate::ui;
+
+/// One line of the report: what was looked at, and what's wrong with it
+struct Check {
+    name: &'static str,
+    detail: String,
+    problems: Vec<String>,
+}
+
+/// Why files can't be created in `dir`, or its nearest existing ancestor
+/// when it doesn't exist yet (the CLI creates it on first use)
+fn not_writable(dir: &Path) -> Option<String> {
+    let existing = dir.ancestors().find(|p| p.exists())?;
+    let probe = existing.join(format!(".agent-browser-doctor-{}", std::process::id()));
+    match fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+            None
+        }
+        Err(e) => Some(tf(
+            "doctor.not_writable",
+            &[("path", &existing.display()), ("error", &e)],
+        )),
+    }
+}
+
+/// Whether any user may write to `path` (world-writable, without the sticky
+/// bit that keeps them from replacing others' files)
+#[cfg(unix)]
+fn open_to_others(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o1002 == 0o002)
+}
+
+#[cfg(not(unix))]
+fn open_to_others(_path: &Path) -> bool {
+    false
+}
+
+/// Problems with a directory the CLI writes to
+fn directory_problems(dir: &Path) -> Vec<String> {
+    let mut problems: Vec<String> = not_writable(dir).into_iter().collect();
+    if open_to_others(dir) {
+        problems.push(tf("doctor.open_to_others", &[("path", &dir.display())]));
+    }
+    problems
+}
+
+fn check_home() -> Check {
+    let home = get_browseros_home();
+    Check {
+        name: "Home",
+        detail: home.display().to_string(),
+        problems: directory_problems(&home),
+    }
+}
+
+fn check_socket(session: &str) -> Check {
+    let dir = get_socket_dir();
+    let mut problems = directory_problems(&dir);
+    let state = match (is_daemon_running(session), daemon_ready(session)) {
+        (_, true) => "running",
+        (true, false) => {
+            problems.push(tf("doctor.daemon_unresponsive", &[("session", &session)]));
+            "not responding"
+        }
+        (false, false) => "not running",
+    };
+    Check {
+        name: "Daemon",
+        detail: format!("{} (session {} {})", dir.display(), session, state),
+        problems,
+    }
+}
+
+/// Why the executable at `path` can't be launched, if so
+fn executable_problem(path: &Path) -> Option<String> {
+    if !path.is_file() {
+        return Some(tf("doctor.browser_missing", &[("path", &path.display())]));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(path).map_or(0, |m| m.permissions().mode());
+        if mode & 0o111 == 0 {
+            return Some(tf(
+                "doctor.browser_not_executable",
+                &[("path", &path.display())],
+            ));
+        }
+    }
+    None
+}
+
+fn check_browser(flags: &Flags) -> Check {
+    let configured = flags.executable_path.clone().or_else(|| {
+        flags
+            .browser
+            .as_deref()
+            .and_then(|b| b.strip_prefix("path:"))
+            .map(String::from)
+    });
+    let (detail, problems) = match (configured, flags.browser.as_deref()) {
+        (Some(path), _) => (path.clone(), executable_problem(Path::new(&path))),
+        // The daemon looks these up when it launches them
+        (None, Some(browser @ ("chrome" | "edge" | "chromium"))) => {
+            (format!("{} (found at launch)", browser), None)
+        }
+        (None, _) => match installed_executable() {
+            Some(path) => (path.display().to_string(), executable_problem(&path)),
+            None => (
+                "BrowserOS not installed".to_string(),
+                Some(t("doctor.browseros_missing")),
+            ),
+        },
+    };
+    Check {
+        name: "Browser",
+        detail,
+        problems: problems.into_iter().collect(),
+    }
+}
+
+/// Handle `agent-browser doctor` (runs locally, no daemon)
+pub fn run_doctor(flags: &Flags) {
+    let checks = [
+        check_socket(&flags.session),
+        check_browser(flags),
+        check_home(),
+    ];
+    let usage = disk::usage();
+    let disk_warnings = disk::warnings(&usage);
+    let problems: Vec<&String> = checks
+        .iter()
+        .flat_map(|c| &c.problems)
+        .chain(&disk_warnings)
+        .collect();
+
+    if ui::json_mode() {
+        let mut data = json!({
+            "ok": problems.is_empty(),
+            "problems": problems,
+            "disk": disk::usage_json(&usage, &disk_warnings),
+        });
+        for check in &checks {
+            data[check.name.to_lowercase()] = json!({
+                "detail": check.detail,
+                "problems": check.problems,
+            });
+        }
+        ui::print_json_data(data);
+        return;
+    }
+    for check in &checks {
+        println!("  {:<10} {}", check.name, check.detail);
+    }
+    disk::print_usage(&usage);
+    if problems.is_empty() {
+        println!("{} No problems found", color::success_indicator());
+    }
+    for problem in problems {
+        println!("{} {}", color::warning_indicator(), problem);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!(
+            "agent-browser-doctor-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_directory_problems() {
+        let dir = temp_dir("dirs");
+        assert!(directory_problems(&dir).is_empty());
+        // Not created yet: its parent is what must be writable
+        assert!(directory_problems(&dir.join("later").join("deeper")).is_empty());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&dir, fs::Permissions::from_mode(0o777)).unwrap();
+            assert_eq!(directory_problems(&dir).len(), 1);
+            fs::set_permissions(&dir, fs::Permissions::from_mode(0o1777)).unwrap();
+            assert!(directory_problems(&dir).is_empty());
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_executable_problem() {
+        let dir = temp_dir("exe");
+        let exe = dir.join("browser");
+        assert!(executable_problem(&exe).is_some());
+        fs::write(&exe, "#!/bin/sh\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&exe, fs::Permissions::from_mode(0o644)).unwrap();
+            assert!(executable_problem(&exe).is_some());
+            fs::set_permissions(&exe, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        assert_eq!(executable_problem(&exe), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
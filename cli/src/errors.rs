@@ -0,0 +1,131 @@
+//! Stable error codes and their process exit codes.
+//!
+//! The daemon attaches a `code` to every error response (see `src/errors.ts`);
+//! the CLI adds its own codes for failures that never reach the daemon. Each
+//! code maps to a distinct exit status so callers can branch on `$?` without
+//! parsing messages. Never renumber or rename existing codes.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ErrorCode {
+    Unknown,
+    /// Bad CLI arguments or a command the daemon rejected.
+    InvalidArgs,
+    SelectorNotFound,
+    SelectorAmbiguous,
+    ElementNotInteractable,
+    Timeout,
+    NavTimeout,
+    NavFailed,
+    BrowserCrashed,
+    DownloadFailed,
+    Unsupported,
+    /// Daemon could not be started or reached.
+    Daemon,
+    /// `compare-screenshot` found more differences than allowed.
+    VisualMismatch,
+    /// Artifacts could not be uploaded with `--upload`.
+    UploadFailed,
+    /// Not enough free space or quota left for an install.
+    DiskSpace,
+    /// `audit-log verify` found an entry edited, removed or out of order.
+    AuditBroken,
+    /// Input or a server write refused in a `--read-only` session.
+    ReadOnly,
+    /// A request matched a `--guardrails` rule.
+    Guardrail,
+    /// `self-update --check` found a newer release. Not a failure; it only
+    /// sets the exit status.
+    UpdateAvailable,
+    /// Stopped by Ctrl-C or a cancelled job; exits like an interrupted process.
+    Cancelled,
+}
+
+const CODES: &[(ErrorCode, &str, i32)] = &[
+    (ErrorCode::Unknown, "E_UNKNOWN", 1),
+    (ErrorCode::InvalidArgs, "E_INVALID_ARGS", 2),
+    (ErrorCode::SelectorNotFound, "E_SELECTOR_NOT_FOUND", 3),
+    (ErrorCode::SelectorAmbiguous, "E_SELECTOR_AMBIGUOUS", 4),
+    (
+        ErrorCode::ElementNotInteractable,
+        "E_ELEMENT_NOT_INTERACTABLE",
+        5,
+    ),
+    (ErrorCode::Timeout, "E_TIMEOUT", 6),
+    (ErrorCode::NavTimeout, "E_NAV_TIMEOUT", 7),
+    (ErrorCode::NavFailed, "E_NAV_FAILED", 8),
+    (ErrorCode::BrowserCrashed, "E_BROWSER_CRASHED", 9),
+    (ErrorCode::DownloadFailed, "E_DOWNLOAD_FAILED", 10),
+    (ErrorCode::Unsupported, "E_UNSUPPORTED", 11),
+    (ErrorCode::Daemon, "E_DAEMON", 12),
+    (ErrorCode::VisualMismatch, "E_VISUAL_MISMATCH", 13),
+    (ErrorCode::UploadFailed, "E_UPLOAD_FAILED", 14),
+    (ErrorCode::DiskSpace, "E_DISK_SPACE", 15),
+    (ErrorCode::AuditBroken, "E_AUDIT_CHAIN_BROKEN", 16),
+    (ErrorCode::ReadOnly, "E_READ_ONLY", 17),
+    (ErrorCode::Guardrail, "E_GUARDRAIL", 18),
+    (ErrorCode::UpdateAvailable, "E_UPDATE_AVAILABLE", 19),
+    (ErrorCode::Cancelled, "E_CANCELLED", 130),
+];
+
+impl ErrorCode {
+    /// Parse a code from a daemon response. The daemon reports rejected
+    /// commands as `E_INVALID_COMMAND`, which shares the CLI's argument code.
+    pub fn from_code(code: Option<&str>) -> ErrorCode {
+        match code {
+            Some("E_INVALID_COMMAND") => ErrorCode::InvalidArgs,
+            Some(code) => CODES
+                .iter()
+                .find(|(_, name, _)| *name == code)
+                .map(|(c, _, _)| *c)
+                .unwrap_or(ErrorCode::Unknown),
+            None => ErrorCode::Unknown,
+        }
+    }
+
+    fn entry(self) -> &'static (ErrorCode, &'static str, i32) {
+        CODES
+            .iter()
+            .find(|(c, _, _)| *c == self)
+            .expect("every ErrorCode has an entry")
+    }
+
+    pub fn as_str(self) -> &'static str {
+        self.entry().1
+    }
+
+    pub fn exit_code(self) -> i32 {
+        self.entry().2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        for (i, (_, name, exit)) in CODES.iter().enumerate() {
+            for (_, other_name, other_exit) in &CODES[i + 1..] {
+                assert_ne!(exit, other_exit, "{} and {}", name, other_name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_code() {
+        assert_eq!(
+            ErrorCode::from_code(Some("E_NAV_TIMEOUT")),
+            ErrorCode::NavTimeout
+        );
+        assert_eq!(
+            ErrorCode::from_code(Some("E_INVALID_COMMAND")).exit_code(),
+            2
+        );
+        assert_eq!(
+            ErrorCode::from_code(Some("E_SOMETHING_NEW")),
+            ErrorCode::Unknown
+        );
+        assert_eq!(ErrorCode::from_code(None).exit_code(), 1);
+        assert_eq!(ErrorCode::from_code(Some("E_CANCELLED")).exit_code(), 130);
+    }
+}
@@ -0,0 +1,577 @@
+//! Output formats for bulk results (`--output-format ndjson|csv|parquet`).
+//!
+//! A command's data is turned into rows: a list is one row per element, and
+//! an object holding a list of records (`eval`'s `result`, `network apis`,
+//! `network requests`, ...) is one row per record. Anything else is a single
+//! row. With `--json-path`, the selection is used instead. `poll` writes one
+//! row per reading.
+//!
+//! The columns are the keys of the rows in the order they first appear, and
+//! each column's type is inferred from its values: booleans, integers and
+//! floats stay as such, nested lists and objects become JSON text, and a
+//! column with mixed types is text. Missing keys and nulls are empty (null in
+//! Parquet). The Parquet writer is minimal: one row group, plain encoding, no
+//! compression and no statistics, which any reader can load.
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::color;
+use crate::errors::ErrorCode;
+use crate::ui;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Ndjson,
+    Csv,
+    Parquet,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Format> {
+        match name.to_ascii_lowercase().as_str() {
+            "ndjson" | "jsonl" => Some(Format::Ndjson),
+            "csv" => Some(Format::Csv),
+            "parquet" => Some(Format::Parquet),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Ndjson => "ndjson",
+            Format::Csv => "csv",
+            Format::Parquet => "parquet",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Boolean,
+    Integer,
+    Float,
+    String,
+    Json,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Column {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: Kind,
+}
+
+/// The rows of a command's data
+pub fn rows(data: &Value) -> Vec<Map<String, Value>> {
+    let records = match data {
+        Value::Array(items) => items.clone(),
+        Value::Object(map) => {
+            let is_records = |v: &Value| {
+                v.as_array()
+                    .is_some_and(|items| items.iter().all(Value::is_object))
+            };
+            match map.get("result").filter(|v| v.is_array()) {
+                Some(result) => result.as_array().cloned().unwrap_or_default(),
+                None => map
+                    .values()
+                    .find(|v| is_records(v))
+                    .and_then(|v| v.as_array().cloned())
+                    .unwrap_or_else(|| vec![data.clone()]),
+            }
+        }
+        Value::Null => Vec::new(),
+        other => vec![other.clone()],
+    };
+    records.into_iter().map(row).collect()
+}
+
+/// A record as a row; values that aren't objects go in a `value` column
+pub fn row(record: Value) -> Map<String, Value> {
+    match record {
+        Value::Object(map) => map,
+        other => [("value".to_string(), other)].into_iter().collect(),
+    }
+}
+
+fn kind_of(value: &Value) -> Option<Kind> {
+    match value {
+        Value::Null => None,
+        Value::Bool(_) => Some(Kind::Boolean),
+        Value::Number(n) if n.is_i64() => Some(Kind::Integer),
+        Value::Number(_) => Some(Kind::Float),
+        Value::String(_) => Some(Kind::String),
+        Value::Array(_) | Value::Object(_) => Some(Kind::Json),
+    }
+}
+
+/// Columns in order of first appearance, with their inferred types
+pub fn infer_schema(rows: &[Map<String, Value>]) -> Vec<Column> {
+    let mut columns: Vec<(String, Option<Kind>)> = Vec::new();
+    for row in rows {
+        for (name, value) in row {
+            let index = match columns.iter().position(|(n, _)| n == name) {
+                Some(index) => index,
+                None => {
+                    columns.push((name.clone(), None));
+                    columns.len() - 1
+                }
+            };
+            let kind = &mut columns[index].1;
+            *kind = match (*kind, kind_of(value)) {
+                (known, None) => known,
+                (None, seen) => seen,
+                (Some(a), Some(b)) if a == b => Some(a),
+                (Some(Kind::Integer | Kind::Float), Some(Kind::Integer | Kind::Float)) => {
+                    Some(Kind::Float)
+                }
+                _ => Some(Kind::String),
+            };
+        }
+    }
+    columns
+        .into_iter()
+        .map(|(name, kind)| Column {
+            name,
+            kind: kind.unwrap_or(Kind::String),
+        })
+        .collect()
+}
+
+/// A value as text: strings as they are, anything else as JSON
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+pub fn to_ndjson(rows: &[Map<String, Value>]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for row in rows {
+        let _ = writeln!(out, "{}", Value::Object(row.clone()));
+    }
+    out
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn to_csv(rows: &[Map<String, Value>], columns: &[Column]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let header: Vec<String> = columns.iter().map(|c| csv_field(&c.name)).collect();
+    let _ = write!(out, "{}\r\n", header.join(","));
+    for row in rows {
+        let fields: Vec<String> = columns
+            .iter()
+            .map(|c| match row.get(&c.name) {
+                None | Some(Value::Null) => String::new(),
+                Some(value) => csv_field(&text(value)),
+            })
+            .collect();
+        let _ = write!(out, "{}\r\n", fields.join(","));
+    }
+    out
+}
+
+/// Thrift compact protocol, as much of it as Parquet metadata needs
+mod thrift {
+    pub const I32: u8 = 5;
+    pub const I64: u8 = 6;
+    pub const BINARY: u8 = 8;
+    pub const LIST: u8 = 9;
+    pub const STRUCT: u8 = 12;
+
+    #[derive(Default)]
+    pub struct Writer {
+        pub buf: Vec<u8>,
+        /// Last field id of each open struct
+        last: Vec<i16>,
+    }
+
+    impl Writer {
+        pub fn varint(&mut self, mut n: u64) {
+            while n >= 0x80 {
+                self.buf.push((n as u8) | 0x80);
+                n >>= 7;
+            }
+            self.buf.push(n as u8);
+        }
+
+        fn zigzag(&mut self, n: i64) {
+            self.varint(((n << 1) ^ (n >> 63)) as u64);
+        }
+
+        pub fn field(&mut self, id: i16, kind: u8) {
+            let last = self.last.last_mut().expect("field outside a struct");
+            let delta = id - std::mem::replace(last, id);
+            if (1..=15).contains(&delta) {
+                self.buf.push(((delta as u8) << 4) | kind);
+            } else {
+                self.buf.push(kind);
+                self.zigzag(i64::from(id));
+            }
+        }
+
+        pub fn i32(&mut self, id: i16, n: i32) {
+            self.field(id, I32);
+            self.zigzag(i64::from(n));
+        }
+
+        pub fn i64(&mut self, id: i16, n: i64) {
+            self.field(id, I64);
+            self.zigzag(n);
+        }
+
+        pub fn bytes(&mut self, bytes: &[u8]) {
+            self.varint(bytes.len() as u64);
+            self.buf.extend_from_slice(bytes);
+        }
+
+        pub fn string(&mut self, id: i16, s: &str) {
+            self.field(id, BINARY);
+            self.bytes(s.as_bytes());
+        }
+
+        pub fn list(&mut self, id: i16, kind: u8, len: usize) {
+            self.field(id, LIST);
+            if len < 15 {
+                self.buf.push(((len as u8) << 4) | kind);
+            } else {
+                self.buf.push(0xf0 | kind);
+                self.varint(len as u64);
+            }
+        }
+
+        pub fn i32_element(&mut self, n: i32) {
+            self.zigzag(i64::from(n));
+        }
+
+        /// Start a struct: a field of one (`Some(id)`) or a list element
+        pub fn begin(&mut self, id: Option<i16>) {
+            if let Some(id) = id {
+                self.field(id, STRUCT);
+            }
+            self.last.push(0);
+        }
+
+        pub fn end(&mut self) {
+            self.buf.push(0);
+            self.last.pop();
+        }
+    }
+}
+
+// Parquet physical types, converted types and encodings
+const BOOLEAN: i32 = 0;
+const INT64: i32 = 2;
+const DOUBLE: i32 = 5;
+const BYTE_ARRAY: i32 = 6;
+const UTF8: i32 = 0;
+const JSON: i32 = 19;
+const PLAIN: i32 = 0;
+const RLE: i32 = 3;
+
+/// Definition levels (1 for a value, 0 for null) as bit-packed runs
+fn definition_levels(present: &[bool]) -> Vec<u8> {
+    let mut out = thrift::Writer::default();
+    let groups = present.len().div_ceil(8);
+    out.varint(((groups as u64) << 1) | 1);
+    for chunk in present.chunks(8) {
+        out.buf.push(
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (i, set)| byte | (u8::from(*set) << i)),
+        );
+    }
+    let mut levels = (out.buf.len() as u32).to_le_bytes().to_vec();
+    levels.extend(out.buf);
+    levels
+}
+
+/// A column's page data: definition levels, then the plain-encoded values
+fn column_page(rows: &[Map<String, Value>], column: &Column) -> Vec<u8> {
+    let values: Vec<Option<&Value>> = rows
+        .iter()
+        .map(|row| row.get(&column.name).filter(|v| !v.is_null()))
+        .collect();
+    let present: Vec<bool> = values.iter().map(Option::is_some).collect();
+    let mut page = definition_levels(&present);
+    let values = values.into_iter().flatten();
+    match column.kind {
+        Kind::Boolean => {
+            let bits: Vec<bool> = values.map(|v| v.as_bool().unwrap_or(false)).collect();
+            for chunk in bits.chunks(8) {
+                page.push(
+                    chunk
+                        .iter()
+                        .enumerate()
+                        .fold(0u8, |byte, (i, set)| byte | (u8::from(*set) << i)),
+                );
+            }
+        }
+        Kind::Integer => {
+            for v in values {
+                page.extend(v.as_i64().unwrap_or_default().to_le_bytes());
+            }
+        }
+        Kind::Float => {
+            for v in values {
+                page.extend(v.as_f64().unwrap_or_default().to_le_bytes());
+            }
+        }
+        Kind::String | Kind::Json => {
+            for v in values {
+                let s = text(v);
+                page.extend((s.len() as u32).to_le_bytes());
+                page.extend(s.as_bytes());
+            }
+        }
+    }
+    page
+}
+
+fn physical_type(kind: Kind) -> i32 {
+    match kind {
+        Kind::Boolean => BOOLEAN,
+        Kind::Integer => INT64,
+        Kind::Float => DOUBLE,
+        Kind::String | Kind::Json => BYTE_ARRAY,
+    }
+}
+
+pub fn to_parquet(rows: &[Map<String, Value>], columns: &[Column]) -> Vec<u8> {
+    let mut out = b"PAR1".to_vec();
+    // (offset, page size) of each column's data page
+    let mut pages = Vec::new();
+    for column in columns {
+        let page = column_page(rows, column);
+        let mut header = thrift::Writer::default();
+        header.begin(None);
+        header.i32(1, 0); // DATA_PAGE
+        header.i32(2, page.len() as i32);
+        header.i32(3, page.len() as i32);
+        header.begin(Some(5));
+        header.i32(1, rows.len() as i32);
+        header.i32(2, PLAIN);
+        header.i32(3, RLE);
+        header.i32(4, RLE);
+        header.end();
+        header.end();
+        let offset = out.len() as i64;
+        let size = (header.buf.len() + page.len()) as i64;
+        out.extend(header.buf);
+        out.extend(page);
+        pages.push((offset, size));
+    }
+
+    let mut meta = thrift::Writer::default();
+    meta.begin(None);
+    meta.i32(1, 1);
+    meta.list(2, thrift::STRUCT, columns.len() + 1);
+    meta.begin(None);
+    meta.string(4, "schema");
+    meta.i32(5, columns.len() as i32);
+    meta.end();
+    for column in columns {
+        meta.begin(None);
+        meta.i32(1, physical_type(column.kind));
+        meta.i32(3, 1); // OPTIONAL
+        meta.string(4, &column.name);
+        match column.kind {
+            Kind::String => meta.i32(6, UTF8),
+            Kind::Json => meta.i32(6, JSON),
+            _ => {}
+        }
+        meta.end();
+    }
+    meta.i64(3, rows.len() as i64);
+    meta.list(4, thrift::STRUCT, 1);
+    meta.begin(None);
+    meta.list(1, thrift::STRUCT, columns.len());
+    for (column, (offset, size)) in columns.iter().zip(&pages) {
+        meta.begin(None);
+        meta.i64(2, *offset);
+        meta.begin(Some(3));
+        meta.i32(1, physical_type(column.kind));
+        meta.list(2, thrift::I32, 2);
+        meta.i32_element(PLAIN);
+        meta.i32_element(RLE);
+        meta.list(3, thrift::BINARY, 1);
+        meta.bytes(column.name.as_bytes());
+        meta.i32(4, 0); // UNCOMPRESSED
+        meta.i64(5, rows.len() as i64);
+        meta.i64(6, *size);
+        meta.i64(7, *size);
+        meta.i64(9, *offset);
+        meta.end();
+        meta.end();
+    }
+    meta.i64(2, pages.iter().map(|(_, size)| size).sum());
+    meta.i64(3, rows.len() as i64);
+    meta.end();
+    meta.string(6, concat!("agent-browser ", env!("CARGO_PKG_VERSION")));
+    meta.end();
+
+    let length = meta.buf.len() as u32;
+    out.extend(meta.buf);
+    out.extend(length.to_le_bytes());
+    out.extend(b"PAR1");
+    out
+}
+
+/// Encode rows in a format, returning the bytes and the inferred columns
+pub fn encode(format: Format, rows: &[Map<String, Value>]) -> (Vec<u8>, Vec<Column>) {
+    let columns = infer_schema(rows);
+    let bytes = match format {
+        Format::Ndjson => to_ndjson(rows),
+        Format::Csv => to_csv(rows, &columns),
+        Format::Parquet => to_parquet(rows, &columns),
+    };
+    (bytes, columns)
+}
+
+/// Write rows to `--out`, or to stdout for the text formats
+pub fn export(format: Format, out: Option<&Path>, rows: &[Map<String, Value>]) {
+    let (bytes, columns) = encode(format, rows);
+    let Some(path) = out else {
+        let _ = std::io::stdout().write_all(&bytes);
+        return;
+    };
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        if let Err(e) = fs::create_dir_all(dir) {
+            ui::fail(
+                ErrorCode::Unknown,
+                format!("Failed to create {}: {}", dir.display(), e),
+            );
+        }
+    }
+    if let Err(e) = fs::write(path, &bytes) {
+        ui::fail(
+            ErrorCode::Unknown,
+            format!("Failed to write {}: {}", path.display(), e),
+        );
+    }
+    if ui::json_mode() {
+        ui::print_json_data(json!({
+            "path": path,
+            "format": format.name(),
+            "rows": rows.len(),
+            "columns": columns,
+        }));
+    } else {
+        ui::status(format!(
+            "{} Wrote {} row(s) with {} column(s) to {}",
+            color::success_indicator(),
+            rows.len(),
+            columns.len(),
+            path.display()
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rows_and_schema() {
+        let data = json!({ "result": [
+            { "name": "Pen", "price": 2, "tags": ["office"] },
+            { "name": "Ink, blue", "price": 2.5, "stock": null, "ok": true },
+            { "name": "Pad \"A5\"", "price": "n/a" }
+        ] });
+        let rows = rows(&data);
+        assert_eq!(rows.len(), 3);
+        let columns = infer_schema(&rows);
+        let kinds: Vec<(&str, Kind)> = columns.iter().map(|c| (c.name.as_str(), c.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("name", Kind::String),
+                ("price", Kind::String),
+                ("tags", Kind::Json),
+                ("ok", Kind::Boolean),
+                ("stock", Kind::String)
+            ]
+        );
+        assert_eq!(
+            String::from_utf8(to_csv(&rows, &columns)).unwrap(),
+            "name,price,tags,ok,stock\r\nPen,2,\"[\"\"office\"\"]\",,\r\n\"Ink, blue\",2.5,,true,\r\n\"Pad \"\"A5\"\"\",n/a,,,\r\n"
+        );
+
+        let apis = json!({ "count": 1, "apis": [{ "url": "/a" }] });
+        assert_eq!(rows_of(&apis), vec![json!({ "url": "/a" })]);
+        assert_eq!(
+            rows_of(&json!([1, 2])),
+            vec![json!({ "value": 1 }), json!({ "value": 2 })]
+        );
+        assert_eq!(
+            rows_of(&json!({ "title": "Home" })),
+            vec![json!({ "title": "Home" })]
+        );
+    }
+
+    fn rows_of(data: &Value) -> Vec<Value> {
+        rows(data).into_iter().map(Value::Object).collect()
+    }
+
+    #[test]
+    fn test_parquet_layout() {
+        let rows = rows(&json!([{ "n": 1, "ok": true }, { "n": 3 }]));
+        let (bytes, columns) = encode(Format::Parquet, &rows);
+        assert_eq!(columns[0].kind, Kind::Integer);
+        assert!(bytes.starts_with(b"PAR1") && bytes.ends_with(b"PAR1"));
+        let footer = bytes.len() - 8;
+        let length = u32::from_le_bytes(bytes[footer..footer + 4].try_into().unwrap()) as usize;
+        assert!(length > 0 && length < footer);
+        // Two definition levels, bit-packed: 1 then 0 for `ok`
+        assert_eq!(definition_levels(&[true, false]), vec![2, 0, 0, 0, 3, 1]);
+    }
+
+    #[test]
+    fn test_parquet_round_trip() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let data = json!([
+            { "name": "Pen", "n": 1, "price": 2.5, "ok": true, "tags": ["office"] },
+            { "name": null, "n": 3, "price": 1, "ok": false },
+            { "n": -7, "note": "caf\u{e9}" }
+        ]);
+        let rows = rows(&data);
+        let (bytes, _) = encode(Format::Parquet, &rows);
+        let path = std::env::temp_dir().join(format!(
+            "agent-browser-export-test-{}.parquet",
+            std::process::id()
+        ));
+        fs::write(&path, bytes).unwrap();
+        let reader = SerializedFileReader::new(fs::File::open(&path).unwrap()).unwrap();
+        let read: Vec<Value> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| row.unwrap().to_json_value())
+            .collect();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(
+            read,
+            vec![
+                json!({ "name": "Pen", "n": 1, "price": 2.5, "ok": true, "tags": "[\"office\"]", "note": null }),
+                json!({ "name": null, "n": 3, "price": 1.0, "ok": false, "tags": null, "note": null }),
+                json!({ "name": null, "n": -7, "price": null, "ok": null, "tags": null, "note": "caf\u{e9}" }),
+            ]
+        );
+    }
+}
@@ -0,0 +1,623 @@
+//! Expressions for `assert`, `if` and `until` lines in `run` scripts.
+//!
+//! A small language over the JSON values of the session's variables, so
+//! simple checks don't need an `eval` round trip to the page:
+//!
+//! ```text
+//! title contains "Welcome" && count >= 3
+//! order.items[0].price < 100
+//! lower(status) matches "^(paid|shipped)$"
+//! "sale" in order.tags || len(results[*].name) == 0
+//! ```
+//!
+//! Variables are referred to by name, and `vars["order-id"]` reaches names
+//! that aren't identifiers. `.key`, `[index]` and `["key"]` walk into objects
+//! and arrays, and `[*]` maps the rest of the path over an array. Strings that
+//! look like numbers compare as numbers, since `get text` stores text.
+//! In `--json-path` filters, `@` is the element being tested.
+
+use serde_json::Value;
+
+use crate::vars::Vars;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+}
+
+const OPERATORS: &[&str] = &[
+    "==", "!=", "<=", ">=", "&&", "||", "<", ">", "!", "(", ")", "[", "]", ".", ",", "+", "-", "*",
+];
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '"' || c == '\'' {
+            let mut s = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err("unterminated string".to_string()),
+                    Some(&q) if q == c => break,
+                    Some('\\') => {
+                        i += 1;
+                        match chars.get(i) {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(&other) => s.push(other),
+                            None => return Err("unterminated string".to_string()),
+                        }
+                    }
+                    Some(&other) => s.push(other),
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(Token::Str(s));
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().collect();
+            tokens.push(Token::Number(
+                number
+                    .parse()
+                    .map_err(|_| format!("invalid number: {}", number))?,
+            ));
+        } else if c.is_ascii_alphabetic() || c == '_' || c == '@' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(**op))
+                .ok_or_else(|| format!("unexpected '{}'", c))?;
+            i += op.len();
+            tokens.push(Token::Op(op));
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone)]
+pub enum Segment {
+    Key(String),
+    Index(Expr),
+    /// `[*]`: the rest of the path applies to every element
+    Each,
+}
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Literal(Value),
+    Var(String),
+    List(Vec<Expr>),
+    Path(Box<Expr>, Vec<Segment>),
+    Call(String, Vec<Expr>),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(String, Box<Expr>, Box<Expr>),
+}
+
+const FUNCTIONS: &[&str] = &["len", "lower", "upper", "trim", "number", "string", "json"];
+const COMPARISONS: &[&str] = &[
+    "==",
+    "!=",
+    "<",
+    "<=",
+    ">",
+    ">=",
+    "contains",
+    "startswith",
+    "endswith",
+    "matches",
+    "in",
+];
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Consume an operator or keyword if it is next
+    fn eat(&mut self, word: &str) -> bool {
+        let matches = match self.peek() {
+            Some(Token::Op(op)) => *op == word,
+            Some(Token::Ident(ident)) => ident == word,
+            _ => false,
+        };
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), String> {
+        if self.eat(op) {
+            Ok(())
+        } else {
+            Err(format!("expected '{}'", op))
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.eat("||") || self.eat("or") {
+            left = Expr::Binary("||".to_string(), Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.not()?;
+        while self.eat("&&") || self.eat("and") {
+            left = Expr::Binary("&&".to_string(), Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if self.eat("!") || self.eat("not") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let left = self.sum()?;
+        for op in COMPARISONS {
+            if self.eat(op) {
+                return Ok(Expr::Binary(
+                    op.to_string(),
+                    Box::new(left),
+                    Box::new(self.sum()?),
+                ));
+            }
+        }
+        Ok(left)
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        loop {
+            let op = if self.eat("+") {
+                "+"
+            } else if self.eat("-") {
+                "-"
+            } else {
+                return Ok(left);
+            };
+            left = Expr::Binary(op.to_string(), Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> Result<Expr, String> {
+        let base = self.primary()?;
+        let mut segments = Vec::new();
+        loop {
+            if self.eat(".") {
+                match self.next() {
+                    Some(Token::Ident(key)) => segments.push(Segment::Key(key)),
+                    Some(Token::Op("*")) => segments.push(Segment::Each),
+                    _ => return Err("expected a field name after '.'".to_string()),
+                }
+            } else if self.eat("[") {
+                if self.eat("*") {
+                    segments.push(Segment::Each);
+                } else {
+                    segments.push(Segment::Index(self.or()?));
+                }
+                self.expect("]")?;
+            } else {
+                break;
+            }
+        }
+        Ok(if segments.is_empty() {
+            base
+        } else {
+            Expr::Path(Box::new(base), segments)
+        })
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Literal(number(n))),
+            Some(Token::Str(s)) => Ok(Expr::Literal(Value::String(s))),
+            Some(Token::Op("(")) => {
+                let inner = self.or()?;
+                self.expect(")")?;
+                Ok(inner)
+            }
+            Some(Token::Op("[")) => {
+                let mut items = Vec::new();
+                if !self.eat("]") {
+                    loop {
+                        items.push(self.or()?);
+                        if self.eat("]") {
+                            break;
+                        }
+                        self.expect(",")?;
+                    }
+                }
+                Ok(Expr::List(items))
+            }
+            Some(Token::Ident(ident)) => match ident.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                _ if self.peek() == Some(&Token::Op("(")) => {
+                    if !FUNCTIONS.contains(&ident.as_str()) {
+                        return Err(format!(
+                            "unknown function: {} (available: {})",
+                            ident,
+                            FUNCTIONS.join(", ")
+                        ));
+                    }
+                    self.pos += 1;
+                    let mut args = Vec::new();
+                    if !self.eat(")") {
+                        loop {
+                            args.push(self.or()?);
+                            if self.eat(")") {
+                                break;
+                            }
+                            self.expect(",")?;
+                        }
+                    }
+                    Ok(Expr::Call(ident, args))
+                }
+                _ => Ok(Expr::Var(ident)),
+            },
+            Some(Token::Op(op)) => Err(format!("unexpected '{}'", op)),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+}
+
+/// Parse an expression, checking its syntax before the script runs
+pub fn parse(text: &str) -> Result<Expr, String> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        pos: 0,
+    };
+    if parser.tokens.is_empty() {
+        return Err("empty expression".to_string());
+    }
+    let expr = parser.or()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(Token::Op(op)) => Err(format!("unexpected '{}'", op)),
+        Some(Token::Ident(word)) => Err(format!("unexpected '{}'", word)),
+        Some(_) => Err("unexpected value; is an operator missing?".to_string()),
+    }
+}
+
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        Value::from(n as i64)
+    } else {
+        Value::from(n)
+    }
+}
+
+/// A value as a number, including strings of digits
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// A value as text: strings as they are, anything else as JSON
+fn as_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+pub fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64() != Some(0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+fn equal(a: &Value, b: &Value) -> bool {
+    match (as_number(a), as_number(b)) {
+        (Some(x), Some(y)) if a.is_number() || b.is_number() => x == y,
+        _ => a == b,
+    }
+}
+
+fn contains(haystack: &Value, needle: &Value) -> bool {
+    match haystack {
+        Value::Array(items) => items.iter().any(|item| equal(item, needle)),
+        Value::Object(map) => map.contains_key(&as_text(needle)),
+        other => as_text(other).contains(&as_text(needle)),
+    }
+}
+
+fn walk(value: Value, segments: &[Segment], vars: &Vars) -> Result<Value, String> {
+    let Some((segment, rest)) = segments.split_first() else {
+        return Ok(value);
+    };
+    match segment {
+        Segment::Each => match value {
+            Value::Array(items) => items
+                .into_iter()
+                .map(|item| walk(item, rest, vars))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array),
+            other => Err(format!("[*] needs a list, not {}", other)),
+        },
+        Segment::Key(key) => walk(value.get(key).cloned().unwrap_or(Value::Null), rest, vars),
+        Segment::Index(index) => {
+            let index = eval(index, vars)?;
+            let next = match (&value, &index) {
+                (Value::Array(items), _) => {
+                    let i = as_number(&index)
+                        .ok_or_else(|| format!("list index must be a number, not {}", index))?;
+                    let i = if i < 0.0 { items.len() as f64 + i } else { i };
+                    items.get(i as usize).cloned()
+                }
+                (Value::Object(map), _) => map.get(&as_text(&index)).cloned(),
+                _ => None,
+            };
+            walk(next.unwrap_or(Value::Null), rest, vars)
+        }
+    }
+}
+
+fn call(name: &str, args: Vec<Value>) -> Result<Value, String> {
+    let [arg] = <[Value; 1]>::try_from(args)
+        .map_err(|args| format!("{}() takes 1 argument, not {}", name, args.len()))?;
+    Ok(match name {
+        "len" => Value::from(match &arg {
+            Value::Array(items) => items.len(),
+            Value::Object(map) => map.len(),
+            Value::Null => 0,
+            other => as_text(other).chars().count(),
+        }),
+        "lower" => Value::String(as_text(&arg).to_lowercase()),
+        "upper" => Value::String(as_text(&arg).to_uppercase()),
+        "trim" => Value::String(as_text(&arg).trim().to_string()),
+        "string" => Value::String(as_text(&arg)),
+        "number" => as_number(&arg)
+            .map(number)
+            .ok_or_else(|| format!("number(): not a number: {}", arg))?,
+        "json" => serde_json::from_str(&as_text(&arg)).map_err(|e| format!("json(): {}", e))?,
+        _ => return Err(format!("unknown function: {}", name)),
+    })
+}
+
+/// Evaluate an expression against the variables
+pub fn eval(expr: &Expr, vars: &Vars) -> Result<Value, String> {
+    Ok(match expr {
+        Expr::Literal(value) => value.clone(),
+        Expr::Var(name) if name == "vars" && !vars.contains_key("vars") => {
+            Value::Object(vars.clone().into_iter().collect())
+        }
+        Expr::Var(name) => vars
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("variable not set: {}", name))?,
+        Expr::List(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| eval(item, vars))
+                .collect::<Result<_, _>>()?,
+        ),
+        Expr::Path(base, segments) => walk(eval(base, vars)?, segments, vars)?,
+        Expr::Call(name, args) => call(
+            name,
+            args.iter()
+                .map(|arg| eval(arg, vars))
+                .collect::<Result<_, _>>()?,
+        )?,
+        Expr::Not(inner) => Value::Bool(!truthy(&eval(inner, vars)?)),
+        Expr::Neg(inner) => {
+            let value = eval(inner, vars)?;
+            number(-as_number(&value).ok_or_else(|| format!("cannot negate {}", value))?)
+        }
+        Expr::Binary(op, left, right) => {
+            let left = eval(left, vars)?;
+            // && and || only evaluate the right side when it decides the result
+            match op.as_str() {
+                "&&" if !truthy(&left) => return Ok(Value::Bool(false)),
+                "||" if truthy(&left) => return Ok(Value::Bool(true)),
+                "&&" | "||" => return Ok(Value::Bool(truthy(&eval(right, vars)?))),
+                _ => {}
+            }
+            let right = eval(right, vars)?;
+            binary(op, &left, &right)?
+        }
+    })
+}
+
+fn binary(op: &str, left: &Value, right: &Value) -> Result<Value, String> {
+    let order = || match (as_number(left), as_number(right)) {
+        (Some(x), Some(y)) => x.partial_cmp(&y),
+        _ => Some(as_text(left).cmp(&as_text(right))),
+    };
+    Ok(match op {
+        "==" => Value::Bool(equal(left, right)),
+        "!=" => Value::Bool(!equal(left, right)),
+        "<" => Value::Bool(order().is_some_and(|o| o.is_lt())),
+        "<=" => Value::Bool(order().is_some_and(|o| o.is_le())),
+        ">" => Value::Bool(order().is_some_and(|o| o.is_gt())),
+        ">=" => Value::Bool(order().is_some_and(|o| o.is_ge())),
+        "contains" => Value::Bool(contains(left, right)),
+        "in" => Value::Bool(contains(right, left)),
+        "startswith" => Value::Bool(as_text(left).starts_with(&as_text(right))),
+        "endswith" => Value::Bool(as_text(left).ends_with(&as_text(right))),
+        "matches" => {
+            let pattern = as_text(right);
+            let regex = regex_lite::Regex::new(&pattern)
+                .map_err(|e| format!("invalid regex '{}': {}", pattern, e))?;
+            Value::Bool(regex.is_match(&as_text(left)))
+        }
+        "+" => match (left, right) {
+            (Value::Number(_), Value::Number(_)) => {
+                number(as_number(left).unwrap_or_default() + as_number(right).unwrap_or_default())
+            }
+            _ => Value::String(as_text(left) + &as_text(right)),
+        },
+        "-" => match (as_number(left), as_number(right)) {
+            (Some(x), Some(y)) => number(x - y),
+            _ => return Err(format!("cannot subtract {} from {}", right, left)),
+        },
+        _ => return Err(format!("unknown operator: {}", op)),
+    })
+}
+
+/// Describe the variables an expression read, for failure messages:
+/// `title = "Home", count = 2`
+pub fn describe(expr: &Expr, vars: &Vars) -> String {
+    fn collect(expr: &Expr, names: &mut Vec<String>) {
+        match expr {
+            Expr::Var(name) if !names.contains(name) => names.push(name.clone()),
+            Expr::List(items) | Expr::Call(_, items) => {
+                items.iter().for_each(|item| collect(item, names))
+            }
+            Expr::Path(base, segments) => {
+                collect(base, names);
+                for segment in segments {
+                    if let Segment::Index(index) = segment {
+                        collect(index, names);
+                    }
+                }
+            }
+            Expr::Not(inner) | Expr::Neg(inner) => collect(inner, names),
+            Expr::Binary(_, left, right) => {
+                collect(left, names);
+                collect(right, names);
+            }
+            _ => {}
+        }
+    }
+    let mut names = Vec::new();
+    collect(expr, &mut names);
+    names
+        .iter()
+        .filter_map(|name| vars.get(name).map(|value| format!("{} = {}", name, value)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn to_vars(value: &Value) -> Vars {
+        value.as_object().unwrap().clone().into_iter().collect()
+    }
+
+    fn check(text: &str, vars: &Value) -> Result<Value, String> {
+        eval(&parse(text)?, &to_vars(vars))
+    }
+
+    #[test]
+    fn test_comparisons_and_logic() {
+        let vars = json!({ "title": "Welcome back", "count": "3", "paid": true });
+        assert_eq!(check("count >= 3 && paid", &vars), Ok(json!(true)));
+        assert_eq!(check("count == 3", &vars), Ok(json!(true)));
+        assert_eq!(
+            check("title contains 'back' and not (count > 5)", &vars),
+            Ok(json!(true))
+        );
+        assert_eq!(
+            check("title startswith \"Wel\" || missing", &vars),
+            Ok(json!(true))
+        );
+        assert_eq!(
+            check("lower(title) matches '^welcome\\\\b'", &vars),
+            Ok(json!(true))
+        );
+        assert_eq!(check("count - 1 + 10", &vars), Ok(json!(12)));
+        assert_eq!(check("'a' + title", &vars), Ok(json!("aWelcome back")));
+        assert!(check("missing == 1", &vars)
+            .unwrap_err()
+            .contains("not set"));
+    }
+
+    #[test]
+    fn test_paths() {
+        let vars = json!({
+            "order": { "items": [{ "name": "Pen", "price": 2 }, { "name": "Ink", "price": 12 }], "tags": ["sale"] },
+            "order-id": "A-1",
+        });
+        assert_eq!(check("order.items[1].price > 10", &vars), Ok(json!(true)));
+        assert_eq!(check("order.items[-1]['name']", &vars), Ok(json!("Ink")));
+        assert_eq!(
+            check("order.items[*].name", &vars),
+            Ok(json!(["Pen", "Ink"]))
+        );
+        assert_eq!(
+            check("'Pen' in order.items[*].name", &vars),
+            Ok(json!(true))
+        );
+        assert_eq!(
+            check("len(order.tags) == 1 && 'sale' in order.tags", &vars),
+            Ok(json!(true))
+        );
+        assert_eq!(check("vars['order-id']", &vars), Ok(json!("A-1")));
+        assert_eq!(
+            check("order.missing.deeper == null", &vars),
+            Ok(json!(true))
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        assert!(parse("").is_err());
+        assert!(parse("a ==").is_err());
+        assert!(parse("a b").is_err());
+        assert!(parse("'open").is_err());
+        assert!(parse("eval(1)").unwrap_err().contains("unknown function"));
+        let vars = json!({ "title": "Home", "count": 2 });
+        let expr = parse("title == 'Shop' && count > 1").unwrap();
+        assert_eq!(
+            describe(&expr, &to_vars(&vars)),
+            "title = \"Home\", count = 2"
+        );
+    }
+}
@@ -0,0 +1,397 @@
+//! Browser extension management.
+//!
+//! `agent-browser ext add <dir|crx>` copies (or unpacks) an extension into
+//! `~/.browseros/extensions/<name>/`. Managed extensions are loaded into
+//! every browser the CLI launches itself (including BrowserOS with `--new`),
+//! next to any passed with `--extension` / `--load-extension`. They are left
+//! out when the launch can't take extensions (`--cdp`, `--existing`, cloud
+//! providers, `--state`).
+//!
+//! Packed `.crx` files are unpacked by stripping the CRX header and
+//! extracting the zip payload that follows it.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::UNIX_EPOCH;
+
+use serde_json::{json, Value};
+
+use crate::color;
+use crate::errors::ErrorCode;
+use crate::flags::Flags;
+use crate::install::get_browseros_home;
+use crate::ui;
+
+pub fn extensions_dir() -> PathBuf {
+    get_browseros_home().join("extensions")
+}
+
+/// Unpacked copies of `.crx` files passed directly to `--load-extension`.
+fn crx_cache_dir() -> PathBuf {
+    extensions_dir().join(".crx")
+}
+
+/// Managed extensions, sorted by name.
+pub fn list_extensions() -> Vec<(String, PathBuf)> {
+    let mut list: Vec<(String, PathBuf)> = fs::read_dir(extensions_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| {
+                    let name = e.file_name().to_str()?.to_string();
+                    let path = e.path();
+                    (!name.starts_with('.') && path.join("manifest.json").is_file())
+                        .then_some((name, path))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    list.sort();
+    list
+}
+
+fn read_manifest(dir: &Path) -> Option<Value> {
+    let content = fs::read_to_string(dir.join("manifest.json")).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Offset of the zip payload in a CRX file (format version 2 or 3).
+fn crx_zip_offset(bytes: &[u8]) -> Result<usize, String> {
+    let u32_at = |at: usize| -> Result<usize, String> {
+        bytes
+            .get(at..at + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+            .ok_or_else(|| "Truncated CRX header".to_string())
+    };
+    if bytes.get(..4) != Some(b"Cr24") {
+        return Err("Not a CRX file (missing Cr24 header)".to_string());
+    }
+    let offset = match u32_at(4)? {
+        2 => 16 + u32_at(8)? + u32_at(12)?,
+        3 => 12 + u32_at(8)?,
+        v => return Err(format!("Unsupported CRX version: {}", v)),
+    };
+    if offset >= bytes.len() {
+        return Err("Truncated CRX file".to_string());
+    }
+    Ok(offset)
+}
+
+fn unpack_crx(crx: &Path, dest: &Path) -> Result<(), String> {
+    let bytes = fs::read(crx).map_err(|e| format!("Failed to read {}: {}", crx.display(), e))?;
+    let offset = crx_zip_offset(&bytes).map_err(|e| format!("{}: {}", crx.display(), e))?;
+
+    let zip = env::temp_dir().join(format!("agent-browser-crx-{}.zip", std::process::id()));
+    fs::write(&zip, &bytes[offset..])
+        .map_err(|e| format!("Failed to write {}: {}", zip.display(), e))?;
+    if dest.exists() {
+        let _ = fs::remove_dir_all(dest);
+    }
+    fs::create_dir_all(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+
+    let zip_str = zip.to_string_lossy().to_string();
+    let dest_str = dest.to_string_lossy().to_string();
+    let status = if cfg!(windows) {
+        let script = format!(
+            "Expand-Archive -Force -LiteralPath '{}' -DestinationPath '{}'",
+            zip_str, dest_str
+        );
+        Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            .stdout(Stdio::null())
+            .status()
+    } else {
+        Command::new("unzip")
+            .args(["-q", "-o", &zip_str, "-d", &dest_str])
+            .status()
+    };
+    let _ = fs::remove_file(&zip);
+
+    match status {
+        Ok(s) if s.success() => Ok(()),
+        Ok(s) => Err(format!("Failed to unpack {} ({})", crx.display(), s)),
+        Err(e) => Err(format!("Failed to unpack {}: {}", crx.display(), e)),
+    }
+}
+
+fn is_crx(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("crx"))
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+    let entries =
+        fs::read_dir(from).map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+    for entry in entries.flatten() {
+        let target = to.join(entry.file_name());
+        if entry.path().is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)
+                .map_err(|e| format!("Failed to copy {}: {}", entry.path().display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// What a cached unpack was made from: the `.crx` path, size and mtime.
+fn crx_stamp(crx: &Path) -> Result<String, String> {
+    let meta = fs::metadata(crx).map_err(|e| format!("Failed to read {}: {}", crx.display(), e))?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos());
+    let path = fs::canonicalize(crx).unwrap_or_else(|_| crx.to_path_buf());
+    Ok(format!("{}\n{}\n{}", path.display(), meta.len(), modified))
+}
+
+/// Turn a `--load-extension` argument into a directory Chrome can load,
+/// unpacking `.crx` files into a cache first.
+fn resolve(path: &str) -> Result<String, String> {
+    resolve_in(path, &crx_cache_dir())
+}
+
+/// This runs on every invocation, not just at launch, so a `.crx` is only
+/// unpacked again when it changed; otherwise it would delete the directory
+/// of an extension the running browser has loaded.
+fn resolve_in(path: &str, cache: &Path) -> Result<String, String> {
+    let p = Path::new(path);
+    if !is_crx(p) {
+        return Ok(path.to_string());
+    }
+    let stem = p
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("extension");
+    let dest = cache.join(stem);
+    let stamp_file = cache.join(format!("{}.stamp", stem));
+    let stamp = crx_stamp(p)?;
+    let fresh = dest.join("manifest.json").is_file()
+        && fs::read_to_string(&stamp_file).is_ok_and(|s| s == stamp);
+    if !fresh {
+        let _ = fs::remove_file(&stamp_file);
+        unpack_crx(p, &dest)?;
+        let _ = fs::write(&stamp_file, stamp);
+    }
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Extensions to load for this invocation: explicit ones (with `.crx` files
+/// unpacked), then managed ones when the launch mode supports extensions.
+pub fn launch_extensions(flags: &Flags) -> Result<Vec<String>, String> {
+    let mut extensions = flags
+        .extensions
+        .iter()
+        .map(|p| resolve(p))
+        .collect::<Result<Vec<_>, _>>()?;
+    let local = matches!(flags.provider.as_deref(), None | Some("browseros-new"));
+    if local && flags.cdp.is_none() && flags.state.is_none() {
+        for (_, path) in list_extensions() {
+            let path = path.to_string_lossy().to_string();
+            if !extensions.contains(&path) {
+                extensions.push(path);
+            }
+        }
+    }
+    Ok(extensions)
+}
+
+fn add(source: &str, name: Option<&str>) -> Result<(String, PathBuf), String> {
+    let source_path = Path::new(source);
+    if !source_path.exists() {
+        return Err(format!("Extension not found: {}", source));
+    }
+    let name = match name {
+        Some(n) => n.to_string(),
+        None => source_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| format!("Cannot derive a name from {}; pass --name", source))?
+            .to_string(),
+    };
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("Invalid extension name: {}", name));
+    }
+
+    let crx = is_crx(source_path);
+    if !crx && !source_path.join("manifest.json").is_file() {
+        return Err(format!(
+            "{} is neither a .crx file nor a directory with a manifest.json",
+            source
+        ));
+    }
+
+    let dest = extensions_dir().join(&name);
+    if dest.exists() {
+        fs::remove_dir_all(&dest)
+            .map_err(|e| format!("Failed to replace {}: {}", dest.display(), e))?;
+    }
+    if crx {
+        unpack_crx(source_path, &dest)?;
+    } else {
+        copy_dir(source_path, &dest)?;
+    }
+    if !dest.join("manifest.json").is_file() {
+        let _ = fs::remove_dir_all(&dest);
+        return Err(format!("{} has no manifest.json", source));
+    }
+    Ok((name, dest))
+}
+
+/// Handle `agent-browser ext <add|list|remove>` (runs locally, no daemon).
+pub fn run_ext(args: &[String]) {
+    let name_flag = args
+        .iter()
+        .position(|a| a == "--name")
+        .and_then(|i| args.get(i + 1));
+    let positional: Vec<&str> = args
+        .iter()
+        .skip(1)
+        .filter(|a| Some(*a) != name_flag && !a.starts_with("--"))
+        .map(|s| s.as_str())
+        .collect();
+
+    match positional.first().copied() {
+        Some("add") => {
+            let Some(source) = positional.get(1) else {
+                ui::fail(
+                    ErrorCode::InvalidArgs,
+                    "Missing arguments for: ext add\nUsage: agent-browser ext add <dir|crx> [--name <name>]",
+                );
+            };
+            let (name, path) = add(source, name_flag.map(|s| s.as_str()))
+                .unwrap_or_else(|e| ui::fail(ErrorCode::InvalidArgs, e));
+            if ui::json_mode() {
+                ui::print_json_data(json!({ "name": name, "path": path }));
+            } else {
+                ui::status(format!(
+                    "{} Added extension {} {}",
+                    color::success_indicator(),
+                    name,
+                    color::dim(&path.display().to_string())
+                ));
+            }
+        }
+        Some("list") | None => {
+            let list = list_extensions();
+            if ui::json_mode() {
+                let items: Vec<Value> = list
+                    .iter()
+                    .map(|(name, path)| {
+                        let manifest = read_manifest(path);
+                        json!({
+                            "name": name,
+                            "path": path,
+                            "extensionName": manifest.as_ref().and_then(|m| m.get("name")),
+                            "version": manifest.as_ref().and_then(|m| m.get("version")),
+                        })
+                    })
+                    .collect();
+                ui::print_json_data(json!({ "extensions": items }));
+            } else if list.is_empty() {
+                println!("No extensions installed");
+            } else {
+                for (name, path) in &list {
+                    let version = read_manifest(path)
+                        .and_then(|m| m.get("version")?.as_str().map(String::from))
+                        .unwrap_or_default();
+                    println!(
+                        "{:<20} {:<10} {}",
+                        name,
+                        version,
+                        color::dim(&path.display().to_string())
+                    );
+                }
+            }
+        }
+        Some("remove") => {
+            let Some(name) = positional.get(1) else {
+                ui::fail(
+                    ErrorCode::InvalidArgs,
+                    "Missing arguments for: ext remove\nUsage: agent-browser ext remove <name>",
+                );
+            };
+            let Some((_, path)) = list_extensions().into_iter().find(|(n, _)| n == name) else {
+                ui::fail(
+                    ErrorCode::InvalidArgs,
+                    format!("Extension not installed: {}", name),
+                );
+            };
+            if let Err(e) = fs::remove_dir_all(&path) {
+                ui::fail(
+                    ErrorCode::Unknown,
+                    format!("Failed to remove {}: {}", path.display(), e),
+                );
+            }
+            if ui::json_mode() {
+                ui::print_json_data(json!({ "name": name, "removed": true }));
+            } else {
+                ui::status(format!(
+                    "{} Removed extension {}",
+                    color::success_indicator(),
+                    name
+                ));
+            }
+        }
+        Some(other) => ui::fail(
+            ErrorCode::InvalidArgs,
+            format!(
+                "Unknown subcommand: {}\nValid options: add, list, remove",
+                other
+            ),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crx_zip_offset() {
+        let mut crx3 = b"Cr24".to_vec();
+        crx3.extend(3u32.to_le_bytes());
+        crx3.extend(4u32.to_le_bytes());
+        crx3.extend(b"hdr!PK\x03\x04");
+        assert_eq!(crx_zip_offset(&crx3), Ok(16));
+
+        let mut crx2 = b"Cr24".to_vec();
+        crx2.extend(2u32.to_le_bytes());
+        crx2.extend(1u32.to_le_bytes());
+        crx2.extend(2u32.to_le_bytes());
+        crx2.extend(b"kssPK\x03\x04");
+        assert_eq!(crx_zip_offset(&crx2), Ok(19));
+
+        assert!(crx_zip_offset(b"PK\x03\x04").is_err());
+        assert!(crx_zip_offset(b"Cr24\x03\x00\x00\x00\xff\x00\x00\x00").is_err());
+    }
+
+    #[test]
+    fn test_resolve_reuses_unchanged_crx() {
+        let dir = env::temp_dir().join(format!("agent-browser-ext-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cache = dir.join("cache");
+        let crx = dir.join("demo.crx");
+        fs::create_dir_all(cache.join("demo")).unwrap();
+        fs::write(&crx, b"not a real crx").unwrap();
+        fs::write(cache.join("demo/manifest.json"), "{}").unwrap();
+        fs::write(cache.join("demo.stamp"), crx_stamp(&crx).unwrap()).unwrap();
+
+        // Unchanged: the loaded directory is left alone
+        let crx_str = crx.to_string_lossy().to_string();
+        let resolved = resolve_in(&crx_str, &cache).unwrap();
+        assert_eq!(Path::new(&resolved), cache.join("demo"));
+        assert!(cache.join("demo/manifest.json").is_file());
+
+        // Changed: it is unpacked again (and this one is not a valid CRX)
+        fs::write(&crx, b"still not a real crx").unwrap();
+        assert!(resolve_in(&crx_str, &cache).is_err());
+        assert!(!cache.join("demo.stamp").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,186 @@
+//! Ad and tracker filter lists.
+//!
+//! `agent-browser filters update` downloads EasyList and EasyPrivacy into
+//! `~/.browseros/filters/`. With `--adblock`, the daemon loads every `.txt`
+//! list in that directory and aborts matching requests, so custom lists in
+//! the same EasyList syntax can be dropped in next to the built-in ones.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde_json::{json, Value};
+
+use crate::color;
+use crate::errors::ErrorCode;
+use crate::install::{download_files, get_browseros_home};
+use crate::ui;
+
+/// Lists fetched by `filters update`, saved as `<name>.txt`.
+const LISTS: &[(&str, &str)] = &[
+    ("easylist", "https://easylist.to/easylist/easylist.txt"),
+    (
+        "easyprivacy",
+        "https://easylist.to/easylist/easyprivacy.txt",
+    ),
+];
+
+pub fn filters_dir() -> PathBuf {
+    get_browseros_home().join("filters")
+}
+
+/// Number of rules in a list, skipping blank lines, comments and the header.
+fn count_rules(text: &str) -> usize {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('!') && !l.starts_with('['))
+        .count()
+}
+
+/// Lists start with an `[Adblock Plus ...]` header or `!` metadata comments;
+/// anything else (e.g. an HTML error page) is rejected.
+fn looks_like_list(text: &str) -> bool {
+    text.lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .is_some_and(|l| l.starts_with('[') || l.starts_with('!'))
+}
+
+/// Installed lists, sorted by name.
+fn installed_lists() -> Vec<(String, PathBuf)> {
+    let mut lists: Vec<(String, PathBuf)> = fs::read_dir(filters_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| {
+                    let path = e.path();
+                    let name = path.file_stem()?.to_str()?.to_string();
+                    (path.extension()? == "txt").then_some((name, path))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    lists.sort();
+    lists
+}
+
+fn age(path: &Path) -> Option<String> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let secs = SystemTime::now().duration_since(modified).ok()?.as_secs();
+    Some(match secs {
+        s if s < 3600 => format!("{}m ago", s / 60),
+        s if s < 86400 => format!("{}h ago", s / 3600),
+        s => format!("{}d ago", s / 86400),
+    })
+}
+
+fn partial_path(name: &str) -> PathBuf {
+    filters_dir().join(format!("{}.txt.part", name))
+}
+
+/// Download every list at once, next to the installed copies.
+fn download_lists() -> Result<(), String> {
+    let dir = filters_dir();
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let partials: Vec<PathBuf> = LISTS.iter().map(|(name, _)| partial_path(name)).collect();
+    let files: Vec<(&str, &Path)> = LISTS
+        .iter()
+        .zip(&partials)
+        .map(|((_, url), partial)| (*url, partial.as_path()))
+        .collect();
+    download_files(&files)
+}
+
+/// Replace the installed copy of a downloaded list only if the new one parses.
+fn install_list(name: &str, url: &str) -> Result<usize, String> {
+    let dir = filters_dir();
+    let partial = partial_path(name);
+    let text = fs::read_to_string(&partial).unwrap_or_default();
+    let rules = count_rules(&text);
+    if !looks_like_list(&text) || rules == 0 {
+        let _ = fs::remove_file(&partial);
+        return Err(format!("{} did not return a filter list", url));
+    }
+    let dest = dir.join(format!("{}.txt", name));
+    fs::rename(&partial, &dest)
+        .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+    Ok(rules)
+}
+
+/// Handle `agent-browser filters <update|list>` (runs locally, no daemon).
+pub fn run_filters(args: &[String]) {
+    match args.get(1).map(|s| s.as_str()) {
+        Some("update") => {
+            let names: Vec<&str> = LISTS.iter().map(|(name, _)| *name).collect();
+            ui::status(format!("Downloading {}...", names.join(", ")));
+            if let Err(e) = download_lists() {
+                ui::fail(ErrorCode::DownloadFailed, e);
+            }
+            let mut updated = Vec::new();
+            for (name, url) in LISTS {
+                match install_list(name, url) {
+                    Ok(rules) => {
+                        ui::status(format!(
+                            "{} {} {}",
+                            color::success_indicator(),
+                            name,
+                            color::dim(&format!("({} rules)", rules))
+                        ));
+                        updated.push(json!({ "name": name, "rules": rules }));
+                    }
+                    Err(e) => ui::fail(ErrorCode::DownloadFailed, e),
+                }
+            }
+            if ui::json_mode() {
+                ui::print_json_data(json!({ "lists": updated, "path": filters_dir() }));
+            }
+        }
+        Some("list") | None => {
+            let lists = installed_lists();
+            if ui::json_mode() {
+                let items: Vec<Value> = lists
+                    .iter()
+                    .map(|(name, path)| {
+                        let rules = fs::read_to_string(path)
+                            .map(|t| count_rules(&t))
+                            .unwrap_or(0);
+                        json!({ "name": name, "path": path, "rules": rules })
+                    })
+                    .collect();
+                ui::print_json_data(json!({ "lists": items }));
+            } else if lists.is_empty() {
+                println!("No filter lists installed (run: agent-browser filters update)");
+            } else {
+                for (name, path) in &lists {
+                    let rules = fs::read_to_string(path)
+                        .map(|t| count_rules(&t))
+                        .unwrap_or(0);
+                    println!(
+                        "{:<20} {:>8} rules  {}",
+                        name,
+                        rules,
+                        color::dim(&age(path).unwrap_or_default())
+                    );
+                }
+            }
+        }
+        Some(other) => ui::fail(
+            ErrorCode::InvalidArgs,
+            format!("Unknown subcommand: {}\nValid options: update, list", other),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_rules_and_validate() {
+        let list = "[Adblock Plus 2.0]\n! Title: EasyList\n\n||ads.example.com^\n@@||example.com/ok/\nexample.com##.ad\n";
+        assert_eq!(count_rules(list), 3);
+        assert!(looks_like_list(list));
+        assert!(!looks_like_list("<html><body>Not found</body></html>"));
+        assert_eq!(count_rules(""), 0);
+    }
+}
@@ -1,74 +1,151 @@
 use std::env;
 
+use crate::artifacts::parse_size;
+use crate::config::Config;
+
+/// Top-level command names, used to tell commands apart from flag values.
+pub(crate) const COMMANDS: &[&str] = &[
+    "open",
+    "goto",
+    "navigate",
+    "back",
+    "forward",
+    "reload",
+    "click",
+    "dblclick",
+    "type",
+    "fill",
+    "press",
+    "key",
+    "keydown",
+    "keyup",
+    "tap",
+    "swipe",
+    "hover",
+    "focus",
+    "select",
+    "check",
+    "uncheck",
+    "scroll",
+    "scrollintoview",
+    "scrollinto",
+    "drag",
+    "upload",
+    "download",
+    "screenshot",
+    "compare-screenshot",
+    "pdf",
+    "archive",
+    "freeze",
+    "diff",
+    "selectors",
+    "snapshot",
+    "eval",
+    "get",
+    "is",
+    "find",
+    "wait",
+    "cookies",
+    "storage",
+    "dialog",
+    "trace",
+    "route",
+    "unroute",
+    "requests",
+    "network",
+    "net",
+    "ws",
+    "replay",
+    "console",
+    "errors",
+    "highlight",
+    "inspect",
+    "dismiss-overlays",
+    "stats",
+    "cdp",
+    "cdp-listen",
+    "watch",
+    "poll",
+    "config",
+    "tab",
+    "frame",
+    "targets",
+    "robots",
+    "audit",
+    "tls",
+    "clock",
+    "schedule",
+    "jobs",
+    "cancel",
+    "lanes",
+    "memory",
+    "which",
+    "input",
+    "window",
+    "record",
+    "screencast",
+    "set",
+    "mouse",
+    "close",
+    "quit",
+    "exit",
+    "state",
+    "install",
+    "init",
+    "verify",
+    "var",
+    "upgrade",
+    "print-dockerfile",
+    "doctor",
+    "status",
+    "self-update",
+    "connect",
+    "session",
+    "device",
+    "completions",
+    "plugins",
+    "ext",
+    "filters",
+    "run",
+    "artifacts",
+    "transcript",
+    "audit-log",
+];
+
 fn looks_like_command_token(token: &str) -> bool {
-    matches!(
-        token,
-        "open"
-            | "goto"
-            | "navigate"
-            | "back"
-            | "forward"
-            | "reload"
-            | "click"
-            | "dblclick"
-            | "type"
-            | "fill"
-            | "press"
-            | "key"
-            | "keydown"
-            | "keyup"
-            | "hover"
-            | "focus"
-            | "select"
-            | "check"
-            | "uncheck"
-            | "scroll"
-            | "scrollintoview"
-            | "scrollinto"
-            | "drag"
-            | "upload"
-            | "download"
-            | "screenshot"
-            | "pdf"
-            | "snapshot"
-            | "eval"
-            | "get"
-            | "is"
-            | "find"
-            | "wait"
-            | "cookies"
-            | "storage"
-            | "dialog"
-            | "trace"
-            | "route"
-            | "unroute"
-            | "requests"
-            | "console"
-            | "errors"
-            | "highlight"
-            | "tab"
-            | "window"
-            | "record"
-            | "set"
-            | "mouse"
-            | "close"
-            | "install"
-            | "connect"
-            | "session"
-            | "device"
-    )
+    COMMANDS.contains(&token)
 }
 
 pub struct Flags {
     pub json: bool,
+    pub quiet: bool,
+    pub verbosity: u8,
+    pub log_level: Option<String>,
+    pub log_file: Option<String>,
+    pub log_format: Option<String>,
+    pub color: Option<String>,
+    pub download_concurrency: Option<String>,
+    /// Variable to store the command's result in (`--into`)
+    pub into: Option<String>,
+    /// JSONPath applied to the command's data (`--json-path`)
+    pub json_path: Option<String>,
+    /// Write the command's rows in this format (`--output-format`)
+    pub output_format: Option<String>,
+    /// File the command writes its output to (`--out`)
+    pub out: Option<String>,
+    /// Where to append collected rows (`--sink sqlite://path.db#table`)
+    pub sink: Option<String>,
+    pub otel_endpoint: Option<String>,
     pub full: bool,
     pub headed: bool,
     pub debug: bool,
     pub session: String,
     pub headers: Option<String>,
     pub executable_path: Option<String>,
+    pub browser: Option<String>,
     pub cdp: Option<String>,
     pub extensions: Vec<String>,
+    pub init_scripts: Vec<String>,
     pub profile: Option<String>,
     pub state: Option<String>,
     pub proxy: Option<String>,
@@ -76,14 +153,54 @@ pub struct Flags {
     pub args: Option<String>,
     pub user_agent: Option<String>,
     pub provider: Option<String>,
+    pub remote: Option<String>,
+    pub remote_token: Option<String>,
+    pub remote_keepalive: Option<String>,
     pub ignore_https_errors: bool,
+    pub extra_ca: Option<String>,
+    pub ignore_cert_errors_for: Option<String>,
+    pub client_cert: Option<String>,
+    pub client_key: Option<String>,
+    pub client_cert_origin: Option<String>,
+    pub auth_server_allowlist: Option<String>,
+    pub auth_delegate_allowlist: Option<String>,
     pub allow_file_access: bool,
     pub device: Option<String>,
     pub browseros_profile_name: Option<String>,
+    pub default_timeout: Option<u64>,
+    /// `--action-timeout`, `--nav-timeout` and `--idle-timeout` as given;
+    /// env vars and config are read by `timeouts::command_timeouts`
+    pub action_timeout: Option<String>,
+    pub nav_timeout: Option<String>,
+    pub idle_timeout: Option<String>,
+    pub on_popup: Option<String>,
+    pub auto_dismiss_overlays: bool,
+    pub adblock: bool,
+    pub stealth: bool,
+    pub deterministic: bool,
+    pub retries: Option<String>,
+    pub retry_backoff: Option<String>,
+    pub humanize: bool,
+    pub humanize_seed: Option<String>,
+    pub rate: Option<String>,
+    pub nav_rate: Option<String>,
+    pub max_concurrent: Option<String>,
+    pub action_rate: Option<String>,
+    pub max_tab_memory: Option<String>,
+    pub recycle_mode: Option<String>,
+    pub respect_robots: bool,
+    pub transcript: bool,
+    pub audit_log: bool,
+    pub read_only: bool,
+    pub guardrails: bool,
+    pub guardrail_rules: Option<String>,
+    pub allow_purchases: bool,
+    pub robots_override: bool,
 
     // Track which launch-time options were explicitly passed via CLI
     // (as opposed to being set only via environment variables)
     pub cli_executable_path: bool,
+    pub cli_browser: bool,
     pub cli_extensions: bool,
     pub cli_profile: bool,
     pub cli_state: bool,
@@ -91,6 +208,13 @@ pub struct Flags {
     pub cli_user_agent: bool,
     pub cli_proxy: bool,
     pub cli_proxy_bypass: bool,
+    pub cli_extra_ca: bool,
+    pub cli_ignore_cert_errors_for: bool,
+    pub cli_client_cert: bool,
+    pub cli_auth_allowlist: bool,
+    pub cli_stealth: bool,
+    pub cli_deterministic: bool,
+    pub cli_init_scripts: bool,
     pub cli_allow_file_access: bool,
     pub cli_browseros_mode: bool,
 }
@@ -106,29 +230,158 @@ pub fn parse_flags(args: &[String]) -> Flags {
         })
         .unwrap_or_default();
 
+    // Config files sit below env vars in precedence, so they only fill gaps
+    let config = Config::load();
+    // --init-script adds to these rather than replacing them
+    let init_scripts_base = env::var("AGENT_BROWSER_INIT_SCRIPT")
+        .ok()
+        .or_else(|| config.get_str("init-script"))
+        .map(|s| {
+            s.split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
     let mut flags = Flags {
-        json: false,
+        json: config.get_str("output").as_deref() == Some("json"),
+        quiet: env::var("AGENT_BROWSER_QUIET").is_ok(),
+        verbosity: env::var("AGENT_BROWSER_VERBOSE")
+            .ok()
+            .map(|v| v.parse().unwrap_or(1))
+            .unwrap_or(0),
+        log_level: env::var("AGENT_BROWSER_LOG_LEVEL").ok(),
+        log_file: env::var("AGENT_BROWSER_LOG_FILE").ok(),
+        log_format: env::var("AGENT_BROWSER_LOG_FORMAT").ok(),
+        color: env::var("AGENT_BROWSER_COLOR").ok(),
+        download_concurrency: env::var("AGENT_BROWSER_DOWNLOAD_CONCURRENCY").ok(),
+        into: None,
+        json_path: None,
+        output_format: None,
+        out: None,
+        sink: None,
+        otel_endpoint: env::var("AGENT_BROWSER_OTEL_ENDPOINT")
+            .or_else(|_| env::var("OTEL_EXPORTER_OTLP_ENDPOINT"))
+            .ok()
+            .or_else(|| config.get_str("otel-endpoint")),
         full: false,
-        headed: false,
+        headed: env::var("AGENT_BROWSER_HEADED").is_ok() || config.get_bool("headed") == Some(true),
         debug: false,
         session: env::var("AGENT_BROWSER_SESSION").unwrap_or_else(|_| "default".to_string()),
         headers: None,
-        executable_path: env::var("AGENT_BROWSER_EXECUTABLE_PATH").ok(),
+        executable_path: env::var("AGENT_BROWSER_EXECUTABLE_PATH")
+            .ok()
+            .or_else(|| config.get_str("executable-path")),
+        browser: env::var("AGENT_BROWSER_BROWSER")
+            .ok()
+            .or_else(|| config.get_str("browser")),
         cdp: None,
         extensions: extensions_env,
-        profile: env::var("AGENT_BROWSER_PROFILE").ok(),
+        init_scripts: init_scripts_base,
+        profile: env::var("AGENT_BROWSER_PROFILE")
+            .ok()
+            .or_else(|| config.get_str("profile")),
         state: env::var("AGENT_BROWSER_STATE").ok(),
-        proxy: env::var("AGENT_BROWSER_PROXY").ok(),
-        proxy_bypass: env::var("AGENT_BROWSER_PROXY_BYPASS").ok(),
+        proxy: env::var("AGENT_BROWSER_PROXY")
+            .ok()
+            .or_else(|| config.get_str("proxy")),
+        proxy_bypass: env::var("AGENT_BROWSER_PROXY_BYPASS")
+            .ok()
+            .or_else(|| config.get_str("proxy-bypass")),
         args: env::var("AGENT_BROWSER_ARGS").ok(),
         user_agent: env::var("AGENT_BROWSER_USER_AGENT").ok(),
         provider: env::var("AGENT_BROWSER_PROVIDER").ok(),
+        remote: env::var("AGENT_BROWSER_REMOTE")
+            .ok()
+            .or_else(|| config.get_str("remote")),
+        remote_token: env::var("AGENT_BROWSER_REMOTE_TOKEN").ok(),
+        remote_keepalive: env::var("AGENT_BROWSER_REMOTE_KEEPALIVE")
+            .ok()
+            .or_else(|| config.get_str("remote-keepalive")),
         ignore_https_errors: false,
+        extra_ca: env::var("AGENT_BROWSER_EXTRA_CA")
+            .ok()
+            .or_else(|| config.get_str("extra-ca")),
+        ignore_cert_errors_for: env::var("AGENT_BROWSER_IGNORE_CERT_ERRORS_FOR")
+            .ok()
+            .or_else(|| config.get_str("ignore-cert-errors-for")),
+        client_cert: env::var("AGENT_BROWSER_CLIENT_CERT")
+            .ok()
+            .or_else(|| config.get_str("client-cert")),
+        client_key: env::var("AGENT_BROWSER_CLIENT_KEY")
+            .ok()
+            .or_else(|| config.get_str("client-key")),
+        client_cert_origin: env::var("AGENT_BROWSER_CLIENT_CERT_ORIGIN")
+            .ok()
+            .or_else(|| config.get_str("client-cert-origin")),
+        auth_server_allowlist: env::var("AGENT_BROWSER_AUTH_SERVER_ALLOWLIST")
+            .ok()
+            .or_else(|| config.get_str("auth-server-allowlist")),
+        auth_delegate_allowlist: env::var("AGENT_BROWSER_AUTH_DELEGATE_ALLOWLIST")
+            .ok()
+            .or_else(|| config.get_str("auth-delegate-allowlist")),
         allow_file_access: env::var("AGENT_BROWSER_ALLOW_FILE_ACCESS").is_ok(),
         device: env::var("AGENT_BROWSER_IOS_DEVICE").ok(),
         browseros_profile_name: env::var("BROWSEROS_PROFILE_NAME").ok(),
+        default_timeout: env::var("AGENT_BROWSER_DEFAULT_TIMEOUT")
+            .ok()
+            .and_then(|t| parse_duration_ms(&t))
+            .or_else(|| config.get_duration_ms("timeout")),
+        action_timeout: None,
+        nav_timeout: None,
+        idle_timeout: None,
+        on_popup: env::var("AGENT_BROWSER_ON_POPUP").ok(),
+        auto_dismiss_overlays: env::var("AGENT_BROWSER_AUTO_DISMISS_OVERLAYS").is_ok(),
+        adblock: env::var("AGENT_BROWSER_ADBLOCK").is_ok()
+            || config.get_bool("adblock") == Some(true),
+        stealth: env::var("AGENT_BROWSER_STEALTH").is_ok()
+            || config.get_bool("stealth") == Some(true),
+        deterministic: env::var("AGENT_BROWSER_DETERMINISTIC").is_ok()
+            || config.get_bool("deterministic") == Some(true),
+        retries: env::var("AGENT_BROWSER_RETRIES").ok(),
+        retry_backoff: env::var("AGENT_BROWSER_RETRY_BACKOFF").ok(),
+        humanize: env::var("AGENT_BROWSER_HUMANIZE").is_ok()
+            || config.get_bool("humanize") == Some(true),
+        humanize_seed: env::var("AGENT_BROWSER_HUMANIZE_SEED")
+            .ok()
+            .or_else(|| config.get_str("humanize-seed")),
+        rate: env::var("AGENT_BROWSER_RATE")
+            .ok()
+            .or_else(|| config.get_str("rate")),
+        nav_rate: env::var("AGENT_BROWSER_NAV_RATE")
+            .ok()
+            .or_else(|| config.get_str("nav-rate")),
+        max_concurrent: env::var("AGENT_BROWSER_MAX_CONCURRENT")
+            .ok()
+            .or_else(|| config.get_str("max-concurrent")),
+        action_rate: env::var("AGENT_BROWSER_ACTION_RATE")
+            .ok()
+            .or_else(|| config.get_str("action-rate")),
+        max_tab_memory: env::var("AGENT_BROWSER_MAX_TAB_MEMORY")
+            .ok()
+            .or_else(|| config.get_str("max-tab-memory")),
+        recycle_mode: env::var("AGENT_BROWSER_RECYCLE_MODE")
+            .ok()
+            .or_else(|| config.get_str("recycle-mode")),
+        respect_robots: env::var("AGENT_BROWSER_RESPECT_ROBOTS").is_ok()
+            || config.get_bool("respect-robots") == Some(true),
+        transcript: env::var("AGENT_BROWSER_TRANSCRIPT").is_ok()
+            || config.get_bool("transcript") == Some(true),
+        audit_log: env::var("AGENT_BROWSER_AUDIT_LOG").is_ok()
+            || config.get_bool("audit-log") == Some(true),
+        read_only: env::var("AGENT_BROWSER_READ_ONLY").is_ok()
+            || config.get_bool("read-only") == Some(true),
+        guardrails: env::var("AGENT_BROWSER_GUARDRAILS").is_ok()
+            || config.get_bool("guardrails") == Some(true),
+        guardrail_rules: env::var("AGENT_BROWSER_GUARDRAIL_RULES")
+            .ok()
+            .or_else(|| config.get_str("guardrail-rules")),
+        allow_purchases: false,
+        robots_override: false,
         // Track CLI-passed flags (default false, set to true when flag is passed)
         cli_executable_path: false,
+        cli_browser: false,
         cli_extensions: false,
         cli_profile: false,
         cli_state: false,
@@ -136,6 +389,13 @@ pub fn parse_flags(args: &[String]) -> Flags {
         cli_user_agent: false,
         cli_proxy: false,
         cli_proxy_bypass: false,
+        cli_extra_ca: false,
+        cli_ignore_cert_errors_for: false,
+        cli_client_cert: false,
+        cli_auth_allowlist: false,
+        cli_stealth: false,
+        cli_deterministic: false,
+        cli_init_scripts: false,
         cli_allow_file_access: false,
         cli_browseros_mode: false,
     };
@@ -144,6 +404,9 @@ pub fn parse_flags(args: &[String]) -> Flags {
     while i < args.len() {
         match args[i].as_str() {
             "--json" => flags.json = true,
+            "--quiet" | "-q" => flags.quiet = true,
+            "--verbose" | "-v" => flags.verbosity = flags.verbosity.saturating_add(1),
+            "-vv" => flags.verbosity = flags.verbosity.saturating_add(2),
             "--full" | "-f" => flags.full = true,
             "--headed" => flags.headed = true,
             "--debug" => flags.debug = true,
@@ -166,7 +429,21 @@ pub fn parse_flags(args: &[String]) -> Flags {
                     i += 1;
                 }
             }
-            "--extension" => {
+            "--browser" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.browser = Some(s.clone());
+                    flags.cli_browser = true;
+                    i += 1;
+                }
+            }
+            "--init-script" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.init_scripts.push(s.clone());
+                    flags.cli_init_scripts = true;
+                    i += 1;
+                }
+            }
+            "--extension" | "--load-extension" => {
                 if let Some(s) = args.get(i + 1) {
                     flags.extensions.push(s.clone());
                     flags.cli_extensions = true;
@@ -227,7 +504,92 @@ pub fn parse_flags(args: &[String]) -> Flags {
                     i += 1;
                 }
             }
+            "--remote" => {
+                if let Some(url) = args.get(i + 1) {
+                    flags.remote = Some(url.clone());
+                    i += 1;
+                }
+            }
+            "--remote-token" => {
+                if let Some(token) = args.get(i + 1) {
+                    flags.remote_token = Some(token.clone());
+                    i += 1;
+                }
+            }
+            "--remote-keepalive" => {
+                if let Some(d) = args.get(i + 1) {
+                    flags.remote_keepalive = Some(d.clone());
+                    i += 1;
+                }
+            }
             "--ignore-https-errors" => flags.ignore_https_errors = true,
+            "--extra-ca" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.extra_ca = Some(s.clone());
+                    flags.cli_extra_ca = true;
+                    i += 1;
+                }
+            }
+            "--ignore-cert-errors-for" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.ignore_cert_errors_for = Some(s.clone());
+                    flags.cli_ignore_cert_errors_for = true;
+                    i += 1;
+                }
+            }
+            "--client-cert" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.client_cert = Some(s.clone());
+                    flags.cli_client_cert = true;
+                    i += 1;
+                }
+            }
+            "--client-key" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.client_key = Some(s.clone());
+                    flags.cli_client_cert = true;
+                    i += 1;
+                }
+            }
+            "--client-cert-origin" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.client_cert_origin = Some(s.clone());
+                    flags.cli_client_cert = true;
+                    i += 1;
+                }
+            }
+            "--auth-server-allowlist" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.auth_server_allowlist = Some(s.clone());
+                    flags.cli_auth_allowlist = true;
+                    i += 1;
+                }
+            }
+            "--auth-delegate-allowlist" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.auth_delegate_allowlist = Some(s.clone());
+                    flags.cli_auth_allowlist = true;
+                    i += 1;
+                }
+            }
+            "--auto-dismiss-overlays" => flags.auto_dismiss_overlays = true,
+            "--adblock" => flags.adblock = true,
+            "--stealth" => {
+                flags.stealth = true;
+                flags.cli_stealth = true;
+            }
+            "--deterministic" => {
+                flags.deterministic = true;
+                flags.cli_deterministic = true;
+            }
+            "--humanize" => flags.humanize = true,
+            "--respect-robots" => flags.respect_robots = true,
+            "--transcript" => flags.transcript = true,
+            "--audit-log" => flags.audit_log = true,
+            "--read-only" => flags.read_only = true,
+            "--guardrails" => flags.guardrails = true,
+            "--allow-purchases" => flags.allow_purchases = true,
+            "--robots-override" => flags.robots_override = true,
             "--allow-file-access" => {
                 flags.allow_file_access = true;
                 flags.cli_allow_file_access = true;
@@ -238,6 +600,149 @@ pub fn parse_flags(args: &[String]) -> Flags {
                     i += 1;
                 }
             }
+            "--on-popup" => {
+                if let Some(p) = args.get(i + 1) {
+                    flags.on_popup = Some(p.clone());
+                    i += 1;
+                }
+            }
+            "--action-timeout" | "--nav-timeout" | "--idle-timeout" => {
+                if let Some(t) = args.get(i + 1) {
+                    let slot = match args[i].as_str() {
+                        "--action-timeout" => &mut flags.action_timeout,
+                        "--nav-timeout" => &mut flags.nav_timeout,
+                        _ => &mut flags.idle_timeout,
+                    };
+                    *slot = Some(t.clone());
+                    i += 1;
+                }
+            }
+            "--retries" => {
+                if let Some(n) = args.get(i + 1) {
+                    flags.retries = Some(n.clone());
+                    i += 1;
+                }
+            }
+            "--log-level" => {
+                if let Some(l) = args.get(i + 1) {
+                    flags.log_level = Some(l.clone());
+                    i += 1;
+                }
+            }
+            "--log-file" => {
+                if let Some(f) = args.get(i + 1) {
+                    flags.log_file = Some(f.clone());
+                    i += 1;
+                }
+            }
+            "--log-format" => {
+                if let Some(f) = args.get(i + 1) {
+                    flags.log_format = Some(f.clone());
+                    i += 1;
+                }
+            }
+            "--download-concurrency" => {
+                if let Some(n) = args.get(i + 1) {
+                    flags.download_concurrency = Some(n.clone());
+                    i += 1;
+                }
+            }
+            "--into" => {
+                if let Some(name) = args.get(i + 1) {
+                    flags.into = Some(name.clone());
+                    i += 1;
+                }
+            }
+            "--json-path" => {
+                if let Some(path) = args.get(i + 1) {
+                    flags.json_path = Some(path.clone());
+                    i += 1;
+                }
+            }
+            "--output-format" => {
+                if let Some(format) = args.get(i + 1) {
+                    flags.output_format = Some(format.clone());
+                    i += 1;
+                }
+            }
+            "--out" => {
+                if let Some(path) = args.get(i + 1) {
+                    flags.out = Some(path.clone());
+                    i += 1;
+                }
+            }
+            "--sink" => {
+                if let Some(uri) = args.get(i + 1) {
+                    flags.sink = Some(uri.clone());
+                    i += 1;
+                }
+            }
+            "--color" => {
+                if let Some(c) = args.get(i + 1) {
+                    flags.color = Some(c.clone());
+                    i += 1;
+                }
+            }
+            "--otel-endpoint" => {
+                if let Some(e) = args.get(i + 1) {
+                    flags.otel_endpoint = Some(e.clone());
+                    i += 1;
+                }
+            }
+            "--retry-backoff" => {
+                if let Some(d) = args.get(i + 1) {
+                    flags.retry_backoff = Some(d.clone());
+                    i += 1;
+                }
+            }
+            "--humanize-seed" => {
+                if let Some(s) = args.get(i + 1) {
+                    flags.humanize_seed = Some(s.clone());
+                    i += 1;
+                }
+            }
+            "--rate" => {
+                if let Some(r) = args.get(i + 1) {
+                    flags.rate = Some(r.clone());
+                    i += 1;
+                }
+            }
+            "--nav-rate" => {
+                if let Some(r) = args.get(i + 1) {
+                    flags.nav_rate = Some(r.clone());
+                    i += 1;
+                }
+            }
+            "--max-concurrent" => {
+                if let Some(n) = args.get(i + 1) {
+                    flags.max_concurrent = Some(n.clone());
+                    i += 1;
+                }
+            }
+            "--action-rate" => {
+                if let Some(r) = args.get(i + 1) {
+                    flags.action_rate = Some(r.clone());
+                    i += 1;
+                }
+            }
+            "--max-tab-memory" => {
+                if let Some(m) = args.get(i + 1) {
+                    flags.max_tab_memory = Some(m.clone());
+                    i += 1;
+                }
+            }
+            "--recycle-mode" => {
+                if let Some(m) = args.get(i + 1) {
+                    flags.recycle_mode = Some(m.clone());
+                    i += 1;
+                }
+            }
+            "--guardrail-rules" => {
+                if let Some(path) = args.get(i + 1) {
+                    flags.guardrail_rules = Some(path.clone());
+                    i += 1;
+                }
+            }
             "--existing" => {
                 flags.provider = Some("browseros-existing".to_string());
                 flags.cli_browseros_mode = true;
@@ -265,54 +770,243 @@ pub fn parse_flags(args: &[String]) -> Flags {
     flags
 }
 
+/// Parse a duration like `500ms`, `2s`, `1m`, `2h`, `1d`, or a bare number of milliseconds.
+pub fn parse_duration_ms(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let (number, scale) = if let Some(n) = value.strip_suffix("ms") {
+        (n, 1)
+    } else if let Some(n) = value.strip_suffix('s') {
+        (n, 1000)
+    } else if let Some(n) = value.strip_suffix('m') {
+        (n, 60_000)
+    } else if let Some(n) = value.strip_suffix('h') {
+        (n, 3_600_000)
+    } else if let Some(n) = value.strip_suffix('d') {
+        (n, 86_400_000)
+    } else {
+        (value, 1)
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(scale))
+}
+
+/// A per-domain rate limit: at most `count` requests every `per_ms`, to
+/// `host` and its subdomains, or to each host when `host` is unset.
+#[derive(Debug, PartialEq)]
+pub struct RateRule {
+    pub host: Option<String>,
+    pub count: u32,
+    pub per_ms: u64,
+}
+
+/// Parse rate limits like `5/s` or `2/s,api.example.com=1/10s`. The part
+/// after the slash is a unit (`s`, `m`, `h`) or a duration. `off` clears the
+/// limits.
+pub fn parse_rate(value: &str) -> Result<Vec<RateRule>, String> {
+    if matches!(value.trim(), "off" | "none") {
+        return Ok(Vec::new());
+    }
+    value
+        .split(',')
+        .map(|part| {
+            let part = part.trim();
+            let (host, limit) = match part.split_once('=') {
+                Some((host, limit)) => (Some(host.trim().to_lowercase()), limit),
+                None => (None, part),
+            };
+            let invalid = || {
+                format!(
+                    "Invalid rate '{}' (expected e.g. 5/s, 30/m or example.com=1/10s)",
+                    part
+                )
+            };
+            let (count, per) = limit.trim().split_once('/').ok_or_else(invalid)?;
+            let count = count
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(invalid)?;
+            let per_ms = match per.trim() {
+                "s" => Some(1000),
+                "m" => Some(60_000),
+                "h" => Some(3_600_000),
+                per => parse_duration_ms(per),
+            }
+            .filter(|&ms| ms > 0)
+            .ok_or_else(invalid)?;
+            if host.as_deref() == Some("") {
+                return Err(invalid());
+            }
+            Ok(RateRule {
+                host,
+                count,
+                per_ms,
+            })
+        })
+        .collect()
+}
+
+/// Parse `--action-rate`: one limit like `10/s`, without a host, or `off`.
+pub fn parse_action_rate(value: &str) -> Result<Option<RateRule>, String> {
+    let mut rules = parse_rate(value)?;
+    match rules.len() {
+        0 => Ok(None),
+        1 if rules[0].host.is_none() => Ok(rules.pop()),
+        _ => Err(format!(
+            "Invalid action rate '{}' (expected one limit like 10/s, for every host)",
+            value
+        )),
+    }
+}
+
+/// `--max-concurrent`: a number of commands; 0 removes the cap.
+pub fn parse_max_concurrent(value: &str) -> Result<u32, String> {
+    value.trim().parse::<u32>().map_err(|_| {
+        format!(
+            "Invalid max concurrent '{}' (expected a number of commands, or 0 for no cap)",
+            value
+        )
+    })
+}
+
+/// `--max-tab-memory`: a size like `500M` or `1G`; `off` or 0 stops recycling.
+pub fn parse_tab_memory(value: &str) -> Result<u64, String> {
+    if matches!(value.trim(), "off" | "none") {
+        return Ok(0);
+    }
+    parse_size(value).ok_or_else(|| {
+        format!(
+            "Invalid tab memory '{}' (expected e.g. 500M, 1G or off)",
+            value
+        )
+    })
+}
+
+/// `--recycle-mode`: how a tab over `--max-tab-memory` is recycled.
+pub fn parse_recycle_mode(value: &str) -> Result<&'static str, String> {
+    match value.trim() {
+        "reload" => Ok("reload"),
+        "recreate" => Ok("recreate"),
+        _ => Err(format!(
+            "Invalid recycle mode '{}' (expected reload or recreate)",
+            value
+        )),
+    }
+}
+
+/// Global flags that should be stripped from command args
+pub(crate) const GLOBAL_FLAGS: &[&str] = &[
+    "--json",
+    "--quiet",
+    "-q",
+    "--verbose",
+    "-v",
+    "-vv",
+    "--full",
+    "--headed",
+    "--debug",
+    "--ignore-https-errors",
+    "--allow-file-access",
+    "--auto-dismiss-overlays",
+    "--adblock",
+    "--stealth",
+    "--deterministic",
+    "--humanize",
+    "--respect-robots",
+    "--robots-override",
+    "--transcript",
+    "--audit-log",
+    "--read-only",
+    "--guardrails",
+    "--allow-purchases",
+];
+/// Global flags that take a value (need to skip the next arg too)
+pub(crate) const GLOBAL_FLAGS_WITH_VALUE: &[&str] = &[
+    "--session",
+    "--download-concurrency",
+    "--into",
+    "--json-path",
+    "--output-format",
+    "--out",
+    "--sink",
+    "--headers",
+    "--executable-path",
+    "--browser",
+    "--cdp",
+    "--extension",
+    "--load-extension",
+    "--init-script",
+    "--profile",
+    "--state",
+    "--proxy",
+    "--proxy-bypass",
+    "--extra-ca",
+    "--ignore-cert-errors-for",
+    "--client-cert",
+    "--client-key",
+    "--client-cert-origin",
+    "--auth-server-allowlist",
+    "--auth-delegate-allowlist",
+    "--args",
+    "--user-agent",
+    "-p",
+    "--provider",
+    "--remote",
+    "--remote-token",
+    "--remote-keepalive",
+    "--device",
+    "--on-popup",
+    "--retries",
+    "--retry-backoff",
+    "--log-level",
+    "--log-file",
+    "--log-format",
+    "--color",
+    "--otel-endpoint",
+    "--humanize-seed",
+    "--rate",
+    "--nav-rate",
+    "--max-concurrent",
+    "--action-rate",
+    "--max-tab-memory",
+    "--recycle-mode",
+    "--guardrail-rules",
+    "--action-timeout",
+    "--nav-timeout",
+    "--idle-timeout",
+];
+/// Global flags that may take an optional value
+pub(crate) const GLOBAL_FLAGS_WITH_OPTIONAL_VALUE: &[&str] = &["--existing", "--new"];
+
 pub fn clean_args(args: &[String]) -> Vec<String> {
-    let mut result = Vec::new();
+    split_args(args).0
+}
 
-    // Global flags that should be stripped from command args
-    const GLOBAL_FLAGS: &[&str] = &[
-        "--json",
-        "--full",
-        "--headed",
-        "--debug",
-        "--ignore-https-errors",
-        "--allow-file-access",
-    ];
-    // Global flags that take a value (need to skip the next arg too)
-    const GLOBAL_FLAGS_WITH_VALUE: &[&str] = &[
-        "--session",
-        "--headers",
-        "--executable-path",
-        "--cdp",
-        "--extension",
-        "--profile",
-        "--state",
-        "--proxy",
-        "--proxy-bypass",
-        "--args",
-        "--user-agent",
-        "-p",
-        "--provider",
-        "--device",
-    ];
-    // Global flags that may take an optional value
-    const GLOBAL_FLAGS_WITH_OPTIONAL_VALUE: &[&str] = &["--existing", "--new"];
+/// Split arguments into the command and its arguments, and the global flags
+/// with their values (which `run` forwards to every step).
+pub fn split_args(args: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut result = Vec::new();
+    let mut global = Vec::new();
 
     let mut i = 0;
     while i < args.len() {
         let arg = &args[i];
 
         if GLOBAL_FLAGS_WITH_VALUE.contains(&arg.as_str()) {
-            i += 1;
-            if i < args.len() {
-                i += 1;
-            }
+            global.extend(args[i..].iter().take(2).cloned());
+            i += 2;
             continue;
         }
 
         if GLOBAL_FLAGS_WITH_OPTIONAL_VALUE.contains(&arg.as_str()) {
+            global.push(arg.clone());
             i += 1;
-            if i < args.len() && !args[i].starts_with('-') && !looks_like_command_token(&args[i])
-            {
+            if i < args.len() && !args[i].starts_with('-') && !looks_like_command_token(&args[i]) {
+                global.push(args[i].clone());
                 i += 1;
             }
             continue;
@@ -320,6 +1014,7 @@ pub fn clean_args(args: &[String]) -> Vec<String> {
 
         // Only strip known global flags, not command-specific flags
         if GLOBAL_FLAGS.contains(&arg.as_str()) || arg == "-f" {
+            global.push(arg.clone());
             i += 1;
             continue;
         }
@@ -328,7 +1023,7 @@ pub fn clean_args(args: &[String]) -> Vec<String> {
         i += 1;
     }
 
-    result
+    (result, global)
 }
 
 #[cfg(test)]
@@ -361,6 +1056,336 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_on_popup_flag() {
+        let flags = parse_flags(&args("click @e1 --on-popup follow"));
+        assert_eq!(flags.on_popup.as_deref(), Some("follow"));
+        assert_eq!(
+            clean_args(&args("click @e1 --on-popup follow")),
+            vec!["click", "@e1"]
+        );
+    }
+
+    #[test]
+    fn test_parse_retry_flags() {
+        let flags = parse_flags(&args("click @e1 --retries 3 --retry-backoff 500ms"));
+        assert_eq!(flags.retries.as_deref(), Some("3"));
+        assert_eq!(flags.retry_backoff.as_deref(), Some("500ms"));
+        assert_eq!(
+            clean_args(&args("click @e1 --retries 3 --retry-backoff 500ms")),
+            vec!["click", "@e1"]
+        );
+    }
+
+    #[test]
+    fn test_parse_humanize_flags() {
+        let input = "click @e1 --humanize --humanize-seed 42";
+        let flags = parse_flags(&args(input));
+        assert!(flags.humanize);
+        assert_eq!(flags.humanize_seed.as_deref(), Some("42"));
+        assert_eq!(clean_args(&args(input)), vec!["click", "@e1"]);
+    }
+
+    #[test]
+    fn test_parse_rate() {
+        assert_eq!(
+            parse_rate("5/s, API.example.com=1/10s").unwrap(),
+            vec![
+                RateRule {
+                    host: None,
+                    count: 5,
+                    per_ms: 1000
+                },
+                RateRule {
+                    host: Some("api.example.com".to_string()),
+                    count: 1,
+                    per_ms: 10_000
+                },
+            ]
+        );
+        assert_eq!(parse_rate("30/m").unwrap()[0].per_ms, 60_000);
+        assert!(parse_rate("off").unwrap().is_empty());
+        assert!(parse_rate("0/s").is_err());
+        assert!(parse_rate("5").is_err());
+        assert!(parse_rate("=1/s").is_err());
+
+        let flags = parse_flags(&args("--rate 2/s --nav-rate 10/m open example.com"));
+        assert_eq!(flags.rate.as_deref(), Some("2/s"));
+        assert_eq!(flags.nav_rate.as_deref(), Some("10/m"));
+    }
+
+    #[test]
+    fn test_parse_lane_limits() {
+        let rule = parse_action_rate("20/m").unwrap().unwrap();
+        assert_eq!((rule.count, rule.per_ms), (20, 60_000));
+        assert!(parse_action_rate("off").unwrap().is_none());
+        assert!(parse_action_rate("example.com=1/s").is_err());
+        assert!(parse_action_rate("1/s,2/m").is_err());
+        assert_eq!(parse_max_concurrent("4"), Ok(4));
+        assert_eq!(parse_max_concurrent("0"), Ok(0));
+        assert!(parse_max_concurrent("many").is_err());
+
+        let flags = parse_flags(&args("--max-concurrent 2 --action-rate 5/s snapshot"));
+        assert_eq!(flags.max_concurrent.as_deref(), Some("2"));
+        assert_eq!(flags.action_rate.as_deref(), Some("5/s"));
+    }
+
+    #[test]
+    fn test_parse_tab_memory() {
+        assert_eq!(parse_tab_memory("500M"), Ok(500_000_000));
+        assert_eq!(parse_tab_memory("off"), Ok(0));
+        assert!(parse_tab_memory("lots").is_err());
+        assert_eq!(parse_recycle_mode("recreate"), Ok("recreate"));
+        assert!(parse_recycle_mode("restart").is_err());
+
+        let flags = parse_flags(&args(
+            "--max-tab-memory 1G --recycle-mode recreate open a.com",
+        ));
+        assert_eq!(flags.max_tab_memory.as_deref(), Some("1G"));
+        assert_eq!(flags.recycle_mode.as_deref(), Some("recreate"));
+    }
+
+    #[test]
+    fn test_parse_cert_flags() {
+        let input =
+            "--extra-ca ca.pem --ignore-cert-errors-for *.corp.example open git.corp.example";
+        let flags = parse_flags(&args(input));
+        assert_eq!(flags.extra_ca.as_deref(), Some("ca.pem"));
+        assert_eq!(
+            flags.ignore_cert_errors_for.as_deref(),
+            Some("*.corp.example")
+        );
+        assert!(flags.cli_extra_ca && flags.cli_ignore_cert_errors_for);
+        assert_eq!(clean_args(&args(input)), vec!["open", "git.corp.example"]);
+    }
+
+    #[test]
+    fn test_parse_client_cert_flags() {
+        let input = "--client-cert me.pem --client-key me.key --client-cert-origin https://vpn.corp.example open vpn.corp.example";
+        let flags = parse_flags(&args(input));
+        assert_eq!(flags.client_cert.as_deref(), Some("me.pem"));
+        assert_eq!(flags.client_key.as_deref(), Some("me.key"));
+        assert_eq!(
+            flags.client_cert_origin.as_deref(),
+            Some("https://vpn.corp.example")
+        );
+        assert!(flags.cli_client_cert);
+        assert_eq!(clean_args(&args(input)), vec!["open", "vpn.corp.example"]);
+    }
+
+    #[test]
+    fn test_parse_auth_allowlist_flags() {
+        let input = "--auth-server-allowlist *.corp.example --auth-delegate-allowlist sso.corp.example open intranet";
+        let flags = parse_flags(&args(input));
+        assert_eq!(
+            flags.auth_server_allowlist.as_deref(),
+            Some("*.corp.example")
+        );
+        assert_eq!(
+            flags.auth_delegate_allowlist.as_deref(),
+            Some("sso.corp.example")
+        );
+        assert!(flags.cli_auth_allowlist);
+        assert_eq!(clean_args(&args(input)), vec!["open", "intranet"]);
+    }
+
+    #[test]
+    fn test_parse_robots_flags() {
+        let input = "--respect-robots open example.com/private --robots-override";
+        let flags = parse_flags(&args(input));
+        assert!(flags.respect_robots);
+        assert!(flags.robots_override);
+        assert_eq!(
+            clean_args(&args(input)),
+            vec!["open", "example.com/private"]
+        );
+    }
+
+    #[test]
+    fn test_parse_otel_endpoint_flag() {
+        let input = "--otel-endpoint http://localhost:4318 open example.com";
+        let flags = parse_flags(&args(input));
+        assert_eq!(
+            flags.otel_endpoint.as_deref(),
+            Some("http://localhost:4318")
+        );
+        assert_eq!(clean_args(&args(input)), vec!["open", "example.com"]);
+    }
+
+    #[test]
+    fn test_parse_quiet_and_verbose_flags() {
+        let flags = parse_flags(&args("-q snapshot -v -vv"));
+        assert!(flags.quiet);
+        assert_eq!(flags.verbosity, 3);
+        assert_eq!(clean_args(&args("-q snapshot -v -vv")), vec!["snapshot"]);
+    }
+
+    #[test]
+    fn test_parse_log_flags() {
+        let input = "--log-file /tmp/ab.log --log-level debug --log-format json open example.com";
+        let flags = parse_flags(&args(input));
+        assert_eq!(flags.log_file.as_deref(), Some("/tmp/ab.log"));
+        assert_eq!(flags.log_level.as_deref(), Some("debug"));
+        assert_eq!(flags.log_format.as_deref(), Some("json"));
+        assert_eq!(clean_args(&args(input)), vec!["open", "example.com"]);
+    }
+
+    #[test]
+    fn test_parse_download_concurrency_flag() {
+        let input = "--download-concurrency 8 filters update";
+        let flags = parse_flags(&args(input));
+        assert_eq!(flags.download_concurrency.as_deref(), Some("8"));
+        assert_eq!(clean_args(&args(input)), vec!["filters", "update"]);
+    }
+
+    #[test]
+    fn test_parse_into_flag() {
+        let input = "get text h1 --into title";
+        let flags = parse_flags(&args(input));
+        assert_eq!(flags.into.as_deref(), Some("title"));
+        assert_eq!(clean_args(&args(input)), vec!["get", "text", "h1"]);
+    }
+
+    #[test]
+    fn test_parse_json_path_flag() {
+        let input = "eval 1 --json-path $.result";
+        let flags = parse_flags(&args(input));
+        assert_eq!(flags.json_path.as_deref(), Some("$.result"));
+        assert_eq!(clean_args(&args(input)), vec!["eval", "1"]);
+    }
+
+    #[test]
+    fn test_parse_output_format_flags() {
+        let input = "net apis --output-format csv --out apis.csv";
+        let flags = parse_flags(&args(input));
+        assert_eq!(flags.output_format.as_deref(), Some("csv"));
+        assert_eq!(flags.out.as_deref(), Some("apis.csv"));
+        assert_eq!(clean_args(&args(input)), vec!["net", "apis"]);
+
+        let flags = parse_flags(&args("poll --selector .price --sink sqlite://a.db#prices"));
+        assert_eq!(flags.sink.as_deref(), Some("sqlite://a.db#prices"));
+    }
+
+    #[test]
+    fn test_parse_color_flag() {
+        let flags = parse_flags(&args("--color never snapshot"));
+        assert_eq!(flags.color.as_deref(), Some("never"));
+        assert_eq!(
+            clean_args(&args("--color never snapshot")),
+            vec!["snapshot"]
+        );
+    }
+
+    #[test]
+    fn test_parse_adblock_flag() {
+        let flags = parse_flags(&args("--adblock open example.com"));
+        assert!(flags.adblock);
+        assert_eq!(
+            clean_args(&args("--adblock open example.com")),
+            vec!["open", "example.com"]
+        );
+    }
+
+    #[test]
+    fn test_parse_stealth_flag() {
+        let flags = parse_flags(&args("open example.com --stealth"));
+        assert!(flags.stealth && flags.cli_stealth);
+        assert_eq!(
+            clean_args(&args("open example.com --stealth")),
+            vec!["open", "example.com"]
+        );
+    }
+
+    #[test]
+    fn test_parse_transcript_flag() {
+        let flags = parse_flags(&args("--transcript click @e1"));
+        assert!(flags.transcript);
+        assert_eq!(
+            clean_args(&args("--transcript click @e1")),
+            vec!["click", "@e1"]
+        );
+    }
+
+    #[test]
+    fn test_parse_audit_log_flag() {
+        let flags = parse_flags(&args("--audit-log open example.com"));
+        assert!(flags.audit_log);
+        assert_eq!(
+            clean_args(&args("--audit-log open example.com")),
+            vec!["open", "example.com"]
+        );
+    }
+
+    #[test]
+    fn test_parse_read_only_flag() {
+        let flags = parse_flags(&args("--read-only snapshot -i"));
+        assert!(flags.read_only);
+        assert_eq!(
+            clean_args(&args("--read-only snapshot -i")),
+            vec!["snapshot", "-i"]
+        );
+    }
+
+    #[test]
+    fn test_parse_guardrail_flags() {
+        let input = args("--guardrails --guardrail-rules rules.json --allow-purchases click @e1");
+        let flags = parse_flags(&input);
+        assert!(flags.guardrails);
+        assert!(flags.allow_purchases);
+        assert_eq!(flags.guardrail_rules.as_deref(), Some("rules.json"));
+        assert_eq!(clean_args(&input), vec!["click", "@e1"]);
+    }
+
+    #[test]
+    fn test_parse_remote_flags() {
+        let input =
+            "--remote wss://pool.example.com --remote-token abc --remote-keepalive 10s snapshot";
+        let flags = parse_flags(&args(input));
+        assert_eq!(flags.remote.as_deref(), Some("wss://pool.example.com"));
+        assert_eq!(flags.remote_token.as_deref(), Some("abc"));
+        assert_eq!(flags.remote_keepalive.as_deref(), Some("10s"));
+        assert_eq!(clean_args(&args(input)), vec!["snapshot"]);
+    }
+
+    #[test]
+    fn test_parse_deterministic_flag() {
+        let flags = parse_flags(&args("--deterministic screenshot page.png"));
+        assert!(flags.deterministic && flags.cli_deterministic);
+        assert_eq!(
+            clean_args(&args("--deterministic screenshot page.png")),
+            vec!["screenshot", "page.png"]
+        );
+    }
+
+    #[test]
+    fn test_parse_init_script_repeatable() {
+        let input = "--init-script shim.js open example.com --init-script seed.js";
+        let flags = parse_flags(&args(input));
+        assert_eq!(flags.init_scripts, vec!["shim.js", "seed.js"]);
+        assert!(flags.cli_init_scripts);
+        assert_eq!(clean_args(&args(input)), vec!["open", "example.com"]);
+    }
+
+    #[test]
+    fn test_split_args() {
+        let (clean, global) = split_args(&args("--session ci run flow.ab --headed -r junit"));
+        assert_eq!(clean, vec!["run", "flow.ab", "-r", "junit"]);
+        assert_eq!(global, vec!["--session", "ci", "--headed"]);
+    }
+
+    #[test]
+    fn test_parse_duration_ms() {
+        assert_eq!(parse_duration_ms("500ms"), Some(500));
+        assert_eq!(parse_duration_ms("2s"), Some(2000));
+        assert_eq!(parse_duration_ms("1m"), Some(60_000));
+        assert_eq!(parse_duration_ms("2h"), Some(7_200_000));
+        assert_eq!(parse_duration_ms("1d"), Some(86_400_000));
+        assert_eq!(parse_duration_ms("250"), Some(250));
+        assert_eq!(parse_duration_ms("fast"), None);
+        assert_eq!(parse_duration_ms("-1s"), None);
+        assert_eq!(parse_duration_ms("99999999999999h"), None);
+    }
+
     #[test]
     fn test_parse_no_headers_flag() {
         let flags = parse_flags(&args("open example.com"));
@@ -418,6 +1443,17 @@ mod tests {
         assert_eq!(flags.executable_path, Some("/path/to/chromium".to_string()));
     }
 
+    #[test]
+    fn test_parse_browser_flag() {
+        let flags = parse_flags(&args("--browser edge open example.com"));
+        assert_eq!(flags.browser.as_deref(), Some("edge"));
+        assert!(flags.cli_browser);
+        assert_eq!(
+            clean_args(&args("--browser edge open example.com")),
+            vec!["open", "example.com"]
+        );
+    }
+
     #[test]
     fn test_parse_executable_path_flag_no_value() {
         let flags = parse_flags(&args("--executable-path"));
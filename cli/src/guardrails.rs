@@ -0,0 +1,65 @@
+//! Extra guardrail rules for `--guardrail-rules`.
+//!
+//! The daemon always applies its built-in rules once guardrails are on (see
+//! `src/guardrails.ts`); the file adds rules of its own, as a JSON array:
+//!
+//! ```json
+//! [{ "name": "no-deletes", "url": "/admin/", "methods": ["DELETE"] }]
+//! ```
+//!
+//! `url` is a regular expression tested against the full URL, ignoring
+//! case. `navigation: true` limits a rule to opening pages in the top frame,
+//! and `purchase: true` lets `--allow-purchases` lift it.
+
+use std::fs;
+
+use serde_json::Value;
+
+/// The rules in a file, checked for the fields every rule needs
+pub fn load_rules(path: &str) -> Result<Vec<Value>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    parse_rules(&text).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn parse_rules(text: &str) -> Result<Vec<Value>, String> {
+    let rules: Vec<Value> = serde_json::from_str(text)
+        .map_err(|e| format!("expected a JSON array of rules ({})", e))?;
+    for (i, rule) in rules.iter().enumerate() {
+        for key in ["name", "url"] {
+            if !rule.get(key).is_some_and(Value::is_string) {
+                return Err(format!("rule {} needs a \"{}\" string", i + 1, key));
+            }
+        }
+        if let Some(methods) = rule.get("methods") {
+            if !methods
+                .as_array()
+                .is_some_and(|m| m.iter().all(Value::is_string))
+            {
+                return Err(format!(
+                    "rule {}: \"methods\" must be a list of strings",
+                    i + 1
+                ));
+            }
+        }
+    }
+    Ok(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules() {
+        let rules =
+            parse_rules(r#"[{ "name": "no-deletes", "url": "/admin/", "methods": ["DELETE"] }]"#)
+                .unwrap();
+        assert_eq!(rules[0]["name"], "no-deletes");
+        assert_eq!(
+            parse_rules(r#"[{ "name": "x" }]"#),
+            Err("rule 1 needs a \"url\" string".to_string())
+        );
+        assert!(parse_rules(r#"[{ "name": "x", "url": "/", "methods": "POST" }]"#).is_err());
+        assert!(parse_rules(r#"{ "name": "x" }"#).is_err());
+    }
+}
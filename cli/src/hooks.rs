@@ -0,0 +1,392 @@
+//! Config-defined hooks and webhooks.
+//!
+//! A hook is a shell command set in a config file under one of the `on-*`
+//! keys. When its event fires, the command runs with a JSON payload on stdin
+//! describing the event. Hook output goes to stderr so it never mixes with
+//! `--json` output, and a failing hook only produces a warning: hooks can't
+//! change the outcome of the command that triggered them.
+//!
+//! The same events are POSTed to the `webhook` URL, if one is set. With a
+//! `webhook-secret`, the body is signed with HMAC-SHA256 in the
+//! `X-Agent-Browser-Signature` header (`sha256=<hex>`), so receivers can check
+//! that it came from agent-browser. `webhook-format = "slack"` sends a Slack
+//! message instead of the raw payload.
+//!
+//! A failed command reaches hooks with its secrets replaced by `[redacted]`:
+//! fields named like passwords, tokens or cookies, as in the audit log, and
+//! the text that `fill`, `type` and the like would have typed.
+
+use std::env;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use crate::config::Config;
+use crate::ui;
+use crate::upload::{hex, hmac_sha256};
+
+/// Environment override for the `webhook-secret` config key, to keep the
+/// secret out of config files.
+const WEBHOOK_SECRET_ENV: &str = "AGENT_BROWSER_WEBHOOK_SECRET";
+
+/// Field names whose values never reach a hook, matching `SECRET_FIELDS` in
+/// src/audit-log.ts.
+const SECRET_FIELDS: &[&str] = &[
+    "password",
+    "passphrase",
+    "secret",
+    "token",
+    "authorization",
+    "cookie",
+];
+
+/// Actions (and locator sub-actions) whose `value` or `text` is typed into
+/// the page, which is often a password.
+const TYPING_ACTIONS: &[&str] = &[
+    "fill",
+    "type",
+    "inserttext",
+    "setvalue",
+    "keyboard",
+    "input_keyboard",
+];
+
+/// Hook events and the config key that holds each command.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hook {
+    /// A new daemon was started for the session.
+    SessionStart,
+    /// The session's browser was closed with `close`.
+    SessionEnd,
+    /// A command sent to the daemon failed.
+    StepFailure,
+    /// A download finished and was saved.
+    Download,
+    /// The browser crashed, or the daemon stopped responding mid-command.
+    Crash,
+}
+
+impl Hook {
+    pub const ALL: &'static [Hook] = &[
+        Hook::SessionStart,
+        Hook::SessionEnd,
+        Hook::StepFailure,
+        Hook::Download,
+        Hook::Crash,
+    ];
+
+    pub fn config_key(self) -> &'static str {
+        match self {
+            Hook::SessionStart => "on-session-start",
+            Hook::SessionEnd => "on-session-end",
+            Hook::StepFailure => "on-step-failure",
+            Hook::Download => "on-download",
+            Hook::Crash => "on-crash",
+        }
+    }
+
+    pub fn event(self) -> &'static str {
+        match self {
+            Hook::SessionStart => "session_start",
+            Hook::SessionEnd => "session_end",
+            Hook::StepFailure => "step_failure",
+            Hook::Download => "download",
+            Hook::Crash => "crash",
+        }
+    }
+}
+
+pub struct Hooks {
+    config: Config,
+    session: String,
+}
+
+impl Hooks {
+    pub fn load(session: &str) -> Hooks {
+        Hooks {
+            config: Config::load(),
+            session: session.to_string(),
+        }
+    }
+
+    /// Run the hook for `hook` if one is configured. `data` is merged into the
+    /// payload next to the event name and session.
+    pub fn fire(&self, hook: Hook, data: Value) {
+        let payload = payload(hook, &self.session, data);
+        if let Some(command) = self.config.get_str(hook.config_key()) {
+            tracing::debug!(hook = hook.config_key(), command = %command, "running hook");
+            if let Err(e) = run(&command, &payload) {
+                ui::warn(format!("{} hook failed: {}", hook.config_key(), e));
+            }
+        }
+        if let Some(url) = self.config.get_str("webhook") {
+            let wanted = self
+                .config
+                .get_str("webhook-events")
+                .is_none_or(|events| events.split(',').any(|e| e.trim() == hook.event()));
+            if wanted {
+                tracing::debug!(event = hook.event(), url = %url, "posting webhook");
+                if let Err(e) = self.post(&url, hook, payload) {
+                    ui::warn(format!("webhook failed: {}", e));
+                }
+            }
+        }
+    }
+
+    fn post(&self, url: &str, hook: Hook, mut payload: Value) -> Result<(), String> {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        payload["timestamp"] = json!(millis);
+        let body = match self.config.get_str("webhook-format").as_deref() {
+            Some("slack") => json!({ "text": slack_text(&payload) }),
+            _ => payload,
+        }
+        .to_string();
+        let mut headers = vec![
+            "Content-Type: application/json".to_string(),
+            format!("X-Agent-Browser-Event: {}", hook.event()),
+        ];
+        let secret = env::var(WEBHOOK_SECRET_ENV)
+            .ok()
+            .or_else(|| self.config.get_str("webhook-secret"));
+        if let Some(secret) = secret {
+            headers.push(format!(
+                "X-Agent-Browser-Signature: {}",
+                signature(&secret, &body)
+            ));
+        }
+        post(url, &headers, &body)
+    }
+}
+
+/// `sha256=<hex HMAC of the body>`, like GitHub's webhook signatures.
+fn signature(secret: &str, body: &str) -> String {
+    format!(
+        "sha256={}",
+        hex(&hmac_sha256(secret.as_bytes(), body.as_bytes()))
+    )
+}
+
+/// One-line summary for chat webhooks.
+fn slack_text(payload: &Value) -> String {
+    let field = |key: &str| {
+        payload
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+    };
+    let session = field("session");
+    match field("event") {
+        "session_start" => format!("agent-browser session `{}` started", session),
+        "session_end" => format!("agent-browser session `{}` closed", session),
+        "download" => format!(
+            "agent-browser session `{}` downloaded `{}`",
+            session,
+            field("path")
+        ),
+        "crash" => format!(
+            ":rotating_light: agent-browser session `{}` crashed: {}",
+            session,
+            field("error")
+        ),
+        event => {
+            let action = payload
+                .pointer("/command/action")
+                .and_then(|v| v.as_str())
+                .unwrap_or(event);
+            format!(
+                ":x: agent-browser session `{}`: `{}` failed ({}): {}",
+                session,
+                action,
+                field("code"),
+                field("error")
+            )
+        }
+    }
+}
+
+/// POST `body` with curl, giving up after a few seconds so a slow receiver
+/// can't hold up the command.
+fn post(url: &str, headers: &[String], body: &str) -> Result<(), String> {
+    let mut curl = Command::new("curl");
+    curl.args([
+        "-sS",
+        "-f",
+        "--max-time",
+        "5",
+        "-X",
+        "POST",
+        "--data-binary",
+        "@-",
+    ]);
+    for header in headers {
+        curl.args(["-H", header]);
+    }
+    let mut child = curl
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(body.as_bytes());
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+fn payload(hook: Hook, session: &str, mut data: Value) -> Value {
+    if let Some(command) = data.get_mut("command") {
+        redact(command);
+    }
+    let mut payload = json!({ "event": hook.event(), "session": session });
+    if let (Some(obj), Value::Object(data)) = (payload.as_object_mut(), data) {
+        obj.extend(data);
+    }
+    payload
+}
+
+/// Replace the secrets in a command, nested ones included.
+fn redact(command: &mut Value) {
+    let typing = ["action", "subaction"].iter().any(|key| {
+        command
+            .get(key)
+            .and_then(|v| v.as_str())
+            .is_some_and(|action| TYPING_ACTIONS.contains(&action))
+    });
+    match command {
+        Value::Object(fields) => {
+            for (key, value) in fields.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if SECRET_FIELDS.iter().any(|s| key.contains(s))
+                    || (typing && (key == "value" || key == "text"))
+                {
+                    *value = json!("[redacted]");
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn run(command: &str, payload: &Value) -> Result<(), String> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    };
+    let mut child = shell
+        .stdin(Stdio::piped())
+        .stdout(Stdio::from(std::io::stderr()))
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that doesn't read stdin closes the pipe early; that's fine
+        let _ = stdin.write_all(payload.to_string().as_bytes());
+    }
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("exited with {}", status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_merges_event_data() {
+        let payload = payload(Hook::Download, "work", json!({ "path": "/tmp/report.pdf" }));
+        assert_eq!(
+            payload,
+            json!({ "event": "download", "session": "work", "path": "/tmp/report.pdf" })
+        );
+    }
+
+    #[test]
+    fn test_payload_redacts_secrets() {
+        let failure = |command: Value| {
+            payload(Hook::StepFailure, "work", json!({ "command": command }))["command"].clone()
+        };
+        assert_eq!(
+            failure(json!({ "action": "fill", "selector": "#pw", "value": "hunter2" })),
+            json!({ "action": "fill", "selector": "#pw", "value": "[redacted]" })
+        );
+        assert_eq!(
+            failure(json!({ "action": "credentials", "username": "me", "password": "hunter2" })),
+            json!({ "action": "credentials", "username": "me", "password": "[redacted]" })
+        );
+        assert_eq!(
+            failure(json!({ "action": "headers", "headers": { "Authorization": "Bearer t" } })),
+            json!({ "action": "headers", "headers": { "Authorization": "[redacted]" } })
+        );
+        assert_eq!(
+            failure(
+                json!({ "action": "getbylabel", "text": "Password", "subaction": "fill", "value": "x" })
+            )["value"],
+            "[redacted]"
+        );
+        // Text that only finds an element is kept
+        assert_eq!(
+            failure(json!({ "action": "getbytext", "text": "Sign in", "subaction": "click" }))
+                ["text"],
+            "Sign in"
+        );
+    }
+
+    #[test]
+    fn test_webhook_signature_and_slack_text() {
+        // Same vector as GitHub's webhook validation docs
+        assert_eq!(
+            signature("It's a Secret to Everybody", "Hello, World!"),
+            "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17"
+        );
+        let failure = payload(
+            Hook::StepFailure,
+            "work",
+            json!({
+                "command": { "action": "click" },
+                "error": "Element not found",
+                "code": "E_SELECTOR_NOT_FOUND",
+            }),
+        );
+        assert_eq!(
+            slack_text(&failure),
+            ":x: agent-browser session `work`: `click` failed (E_SELECTOR_NOT_FOUND): Element not found"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_hook_receives_payload_on_stdin() {
+        let dir = std::env::temp_dir().join(format!("ab-hook-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let out = dir.join("payload.json");
+        let command = format!("cat > '{}'", out.display());
+        run(&command, &json!({ "event": "step_failure" })).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            r#"{"event":"step_failure"}"#
+        );
+        assert!(run("exit 3", &json!({})).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,300 @@
+//! User-facing message catalog.
+//!
+//! Messages for the install and launch flows are looked up by key instead of
+//! being written inline, so operators can translate them. English is built
+//! in; setting `AGENT_BROWSER_LANG` (e.g. `de` or `pt_BR.UTF-8`) loads
+//! `~/.browseros/locales/<lang>.toml` on top of it, trying the full tag
+//! (`pt-BR.toml`) before the bare language (`pt.toml`). Keys missing from a
+//! catalog fall back to English.
+//!
+//! Messages are templates: `{name}` is replaced by the argument of that name.
+//! Catalog files may use dotted keys or nested tables:
+//!
+//! ```toml
+//! [install]
+//! downloading = "Lade BrowserOS {version} herunter..."
+//! ```
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::install::get_browseros_home;
+
+const EN: &[(&str, &str)] = &[
+    ("install.deps.installing", "Installing system dependencies..."),
+    (
+        "install.deps.no_package_manager",
+        "No supported package manager found (apt-get, dnf, or yum)",
+    ),
+    ("install.deps.running", "Running: {command}"),
+    ("install.deps.installed", "System dependencies installed"),
+    (
+        "install.deps.failed",
+        "Failed to install some dependencies. You may need to run manually with sudo.",
+    ),
+    (
+        "install.deps.run_failed",
+        "Could not run install command: {error}",
+    ),
+    (
+        "install.linux_hint",
+        "Linux detected. If browser fails to launch, run:\n  agent-browser install --with-deps\n",
+    ),
+    (
+        "install.unsupported_platform",
+        "Unsupported platform for BrowserOS install: {os} / {arch}",
+    ),
+    (
+        "install.create_dir_failed",
+        "Failed to create download directory {path}: {error}",
+    ),
+    ("install.label", "Installing"),
+    ("install.downloading", "Downloading BrowserOS {version}..."),
+    ("install.downloaded", "BrowserOS package downloaded\n  {path}"),
+    ("install.ready", "BrowserOS executable ready:\n  {path}"),
+    ("install.set_env", "Set this in your shell:"),
+    (
+        "install.windows_next",
+        "\nRun the downloaded installer. agent-browser finds BrowserOS in the standard install locations; check with:\n  agent-browser which",
+    ),
+    (
+        "install.quarantine_kept",
+        "Kept the quarantine flag on {path}; macOS asks before its first launch. Open it once from Finder, or run:\n  xattr -dr com.apple.quarantine \"{path}\"",
+    ),
+    (
+        "install.quarantine_failed",
+        "Could not remove the quarantine flag from {path}. If the first launch fails, run:\n  xattr -dr com.apple.quarantine \"{path}\"",
+    ),
+    (
+        "install.gatekeeper_rejected",
+        "Gatekeeper will not run BrowserOS ({reason}), so launches fail until it's allowed. Open {path} once from Finder with Control-click > Open, or allow it under System Settings > Privacy & Security.",
+    ),
+    (
+        "install.no_fuse",
+        "FUSE is not available, so the AppImage can't mount itself; extracting it instead",
+    ),
+    ("install.extracting", "Extracting BrowserOS AppImage into {path}..."),
+    (
+        "install.delta",
+        "Updating from {previous}, downloading only what changed...",
+    ),
+    (
+        "install.delta_unavailable",
+        "No delta update available; downloading the full package",
+    ),
+    (
+        "install.up_to_date",
+        "BrowserOS {version} is already installed",
+    ),
+    ("install.note", "Note:"),
+    (
+        "install.missing_libs",
+        "If BrowserOS fails to start due to missing shared libraries, run:\n  agent-browser install --with-deps",
+    ),
+    (
+        "disk.no_room",
+        "Not enough disk space: installing needs about {needed}, but only {free} is free for {path}",
+    ),
+    (
+        "disk.quota_exceeded",
+        "Installing needs about {needed}, which would take ~/.browseros past its {quota} quota ({used} used). Free space with 'agent-browser artifacts prune' or raise the quota.",
+    ),
+    (
+        "disk.over_quota",
+        "~/.browseros uses {used}, over its {quota} quota. Run 'agent-browser artifacts prune' or raise the quota.",
+    ),
+    (
+        "disk.near_quota",
+        "~/.browseros uses {used} of its {quota} quota",
+    ),
+    ("disk.low_free", "Only {free} of disk space is free"),
+    ("doctor.not_writable", "{path} is not writable: {error}"),
+    (
+        "doctor.open_to_others",
+        "{path} is writable by every user on this machine",
+    ),
+    (
+        "doctor.daemon_unresponsive",
+        "The daemon for session '{session}' is running but doesn't accept connections",
+    ),
+    ("doctor.browser_missing", "Browser executable not found: {path}"),
+    (
+        "doctor.browser_not_executable",
+        "Browser executable can't be run: {path}",
+    ),
+    (
+        "doctor.browseros_missing",
+        "BrowserOS is not installed, so launches use any Chrome, Edge or Chromium found. Run 'agent-browser install'.",
+    ),
+    (
+        "launch.flags_ignored",
+        "{flags} ignored: daemon already running. Use 'agent-browser close' first to restart with new options.",
+    ),
+    ("launch.cdp_failed", "CDP connection failed"),
+    ("launch.provider_failed", "Provider connection failed"),
+    ("launch.remote_failed", "Remote browser connection failed"),
+    ("launch.failed", "Browser launch failed"),
+    (
+        "launch.small_shm",
+        "/dev/shm is only {size} MB in this {runtime} container, so the browser keeps shared memory in /tmp, which is slower. Start the container with --shm-size=1g.",
+    ),
+    ("launch.configure_failed", "Could not configure browser: {error}"),
+    (
+        "launch.session_options_failed",
+        "Failed to apply session options",
+    ),
+    ("daemon.start_failed", "Failed to start daemon: {error}"),
+    (
+        "daemon.socket_dir_failed",
+        "Failed to create socket directory: {error}",
+    ),
+    (
+        "daemon.socket_dir_not_writable",
+        "Socket directory '{path}' is not writable: {error}",
+    ),
+    (
+        "daemon.session_name_too_long",
+        "Session name '{session}' is too long. Socket path would be {length} bytes (max 103).\nUse a shorter session name or set AGENT_BROWSER_SOCKET_DIR to a shorter path.",
+    ),
+    ("daemon.not_ready", "Daemon failed to start (socket: {path})"),
+];
+
+fn catalog() -> &'static HashMap<String, String> {
+    static CATALOG: OnceLock<HashMap<String, String>> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        let mut catalog: HashMap<String, String> = EN
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        if let Ok(lang) = env::var("AGENT_BROWSER_LANG") {
+            let dir = get_browseros_home().join("locales");
+            if let Some(overrides) = locale_candidates(&lang)
+                .iter()
+                .find_map(|tag| load_catalog(&dir.join(format!("{}.toml", tag))))
+            {
+                catalog.extend(overrides);
+            }
+        }
+        catalog
+    })
+}
+
+/// Catalog names to try for a locale, most specific first. `C`, `POSIX` and
+/// English need no catalog.
+fn locale_candidates(lang: &str) -> Vec<String> {
+    let tag = lang
+        .split(['.', '@'])
+        .next()
+        .unwrap_or("")
+        .trim()
+        .replace('_', "-");
+    let primary = tag.split('-').next().unwrap_or("").to_ascii_lowercase();
+    if primary.is_empty() || matches!(primary.as_str(), "c" | "posix" | "en") {
+        return Vec::new();
+    }
+    let mut candidates = vec![tag.clone()];
+    if tag != primary {
+        candidates.push(primary);
+    }
+    candidates
+}
+
+fn load_catalog(path: &Path) -> Option<HashMap<String, String>> {
+    let content = fs::read_to_string(path).ok()?;
+    let table = content.parse::<toml::Table>().ok()?;
+    let mut messages = HashMap::new();
+    flatten("", &table, &mut messages);
+    Some(messages)
+}
+
+fn flatten(prefix: &str, table: &toml::Table, out: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            toml::Value::Table(inner) => flatten(&key, inner, out),
+            toml::Value::String(s) => {
+                out.insert(key, s.clone());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn render(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), &value.to_string())
+        })
+}
+
+/// Look up a message with no arguments.
+pub fn t(key: &str) -> String {
+    tf(key, &[])
+}
+
+/// Look up a message and fill in its `{name}` placeholders.
+pub fn tf(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    match catalog().get(key) {
+        Some(template) => render(template, args),
+        None => key.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_and_lookup() {
+        assert_eq!(
+            render(
+                "Downloading {what} {version}...",
+                &[("what", &"BrowserOS"), ("version", &1)]
+            ),
+            "Downloading BrowserOS 1..."
+        );
+        assert_eq!(
+            tf("install.downloading", &[("version", &"0.39.0.3")]),
+            "Downloading BrowserOS 0.39.0.3..."
+        );
+        assert_eq!(t("no.such.key"), "no.such.key");
+    }
+
+    #[test]
+    fn test_locale_candidates() {
+        assert_eq!(locale_candidates("pt_BR.UTF-8"), vec!["pt-BR", "pt"]);
+        assert_eq!(locale_candidates("de"), vec!["de"]);
+        assert!(locale_candidates("en_US.UTF-8").is_empty());
+        assert!(locale_candidates("C").is_empty());
+    }
+
+    #[test]
+    fn test_catalog_files_flatten_tables() {
+        let dir = env::temp_dir().join(format!("ab-i18n-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("de.toml");
+        fs::write(
+            &path,
+            "\"launch.failed\" = \"Browserstart fehlgeschlagen\"\n[install]\ndownloading = \"Lade BrowserOS {version}...\"\n",
+        )
+        .unwrap();
+        let messages = load_catalog(&path).unwrap();
+        assert_eq!(
+            messages.get("install.downloading").map(String::as_str),
+            Some("Lade BrowserOS {version}...")
+        );
+        assert_eq!(
+            messages.get("launch.failed").map(String::as_str),
+            Some("Browserstart fehlgeschlagen")
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
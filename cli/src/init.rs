@@ -0,0 +1,213 @@
+//! First-run setup (`agent-browser init`).
+//!
+//! Walks through what otherwise takes several commands: installs BrowserOS if
+//! it isn't there, creates a persistent profile and points the user config at
+//! it, and runs a smoke test (open example.com, snapshot, screenshot) in a
+//! separate `init` session. Each question has a default, taken without asking
+//! with `--yes`, `--json`, or when stdin is not a terminal.
+
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+use crate::color;
+use crate::config::{self, Config};
+use crate::errors::ErrorCode;
+use crate::i18n::t;
+use crate::install::{get_browseros_home, install_browseros, installed_executable, InstallOptions};
+use crate::run::Status;
+use crate::ui;
+use crate::verify::{run_stages, Stage};
+
+const SMOKE_URL: &str = "https://example.com";
+
+const SMOKE_HINT: &str =
+    "'agent-browser verify', 'agent-browser doctor' and 'agent-browser which' can help find out why.";
+
+/// Stages of the smoke test, run in the `init` session
+fn smoke_stages(screenshot: &Path) -> Vec<Stage> {
+    let screenshot = screenshot.to_string_lossy();
+    vec![
+        Stage::new("open", &["open", SMOKE_URL], SMOKE_HINT),
+        Stage::new("snapshot", &["snapshot", "-i"], SMOKE_HINT),
+        Stage::new("screenshot", &["screenshot", &screenshot], SMOKE_HINT),
+    ]
+}
+
+struct Prompter {
+    interactive: bool,
+}
+
+impl Prompter {
+    /// Ask a question, returning `default` for an empty answer or when not
+    /// interactive
+    fn ask(&self, question: &str, default: &str) -> String {
+        if !self.interactive {
+            return default.to_string();
+        }
+        print!("{} {} ", question, color::dim(&format!("[{}]", default)));
+        let _ = io::stdout().flush();
+        let mut answer = String::new();
+        if io::stdin().lock().read_line(&mut answer).is_err() {
+            return default.to_string();
+        }
+        let answer = answer.trim();
+        if answer.is_empty() {
+            default.to_string()
+        } else {
+            answer.to_string()
+        }
+    }
+
+    fn confirm(&self, question: &str) -> bool {
+        let answer = self.ask(question, "Y/n");
+        !matches!(answer.to_ascii_lowercase().as_str(), "n" | "no")
+    }
+}
+
+fn print_quick_start(profile: &Path) {
+    println!();
+    println!("{}", color::bold("Quick start:"));
+    println!("  agent-browser open example.com");
+    println!("  agent-browser snapshot -i          # Interactive elements with refs");
+    println!("  agent-browser click @e1            # Act on a ref from the snapshot");
+    println!("  agent-browser screenshot page.png");
+    println!("  agent-browser close");
+    println!();
+    println!(
+        "Sessions use the profile in {} (config key: profile).",
+        color::dim(&profile.display().to_string())
+    );
+    println!("Run 'agent-browser --help' for every command.");
+}
+
+/// Handle `agent-browser init` (runs locally; the smoke test starts its own session)
+pub fn run_init(args: &[String]) {
+    let yes = args.iter().any(|a| a == "--yes" || a == "-y");
+    let skip_smoke_test = args.iter().any(|a| a == "--no-smoke-test");
+    let prompter = Prompter {
+        interactive: !yes && !ui::json_mode() && io::stdin().is_terminal(),
+    };
+
+    if !ui::json_mode() {
+        println!("{}", color::bold("Setting up agent-browser"));
+        println!();
+    }
+
+    // 1. BrowserOS
+    let mut executable = installed_executable();
+    if let Some(ref path) = executable {
+        ui::status(format!(
+            "{} BrowserOS is installed: {}",
+            color::success_indicator(),
+            color::dim(&path.display().to_string())
+        ));
+    } else if prompter.confirm("BrowserOS is not installed. Install it now?") {
+        executable = install_browseros(&InstallOptions::from_args(args)).executable;
+        if executable.is_none() && cfg!(target_os = "windows") {
+            ui::status(t("install.windows_next"));
+        }
+    } else {
+        ui::status(format!(
+            "{} Skipped installing BrowserOS; launches use any Chrome, Edge or Chromium found",
+            color::warning_indicator()
+        ));
+    }
+
+    // 2. Profile and config
+    let config = Config::load();
+    let profile = match config.get_str("profile") {
+        Some(existing) => {
+            ui::status(format!(
+                "{} Using the configured profile: {}",
+                color::success_indicator(),
+                color::dim(&existing)
+            ));
+            PathBuf::from(existing)
+        }
+        None => {
+            let name = prompter.ask("Profile name?", "default");
+            let profile = get_browseros_home().join("profiles").join(&name);
+            if let Err(e) = fs::create_dir_all(&profile) {
+                ui::fail(
+                    ErrorCode::Unknown,
+                    format!("Failed to create {}: {}", profile.display(), e),
+                );
+            }
+            let path = config::user_config_path();
+            let value = toml::Value::String(profile.to_string_lossy().to_string());
+            if let Err(e) = config::write_value(&path, "profile", value) {
+                ui::fail(ErrorCode::Unknown, e);
+            }
+            ui::status(format!(
+                "{} Created profile {} and set it in {}",
+                color::success_indicator(),
+                color::dim(&profile.display().to_string()),
+                color::dim(&path.display().to_string())
+            ));
+            profile
+        }
+    };
+
+    // 3. Smoke test
+    let screenshot = get_browseros_home().join("init-smoke-test.png");
+    let results = if skip_smoke_test {
+        Vec::new()
+    } else {
+        ui::status(format!("\nSmoke test ({}):", SMOKE_URL));
+        let global_args = vec![
+            "--session".to_string(),
+            "init".to_string(),
+            "--profile".to_string(),
+            profile.to_string_lossy().to_string(),
+        ];
+        run_stages(&global_args, &smoke_stages(&screenshot))
+    };
+    if let Some(failed) = results.iter().find(|r| r.status == Status::Failed) {
+        ui::fail(
+            ErrorCode::Unknown,
+            format!(
+                "Smoke test failed at '{}': {}\n{}",
+                failed.command,
+                failed.error.as_deref().unwrap_or("unknown error"),
+                SMOKE_HINT
+            ),
+        );
+    }
+
+    if ui::json_mode() {
+        ui::print_json_data(json!({
+            "executablePath": executable,
+            "profile": profile,
+            "config": config::user_config_path(),
+            "smokeTest": (!skip_smoke_test).then_some(&results),
+            "screenshot": (!skip_smoke_test).then_some(&screenshot),
+        }));
+    } else {
+        print_quick_start(&profile);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_without_a_terminal() {
+        let prompter = Prompter { interactive: false };
+        assert_eq!(prompter.ask("Profile name?", "default"), "default");
+        assert!(prompter.confirm("Install it now?"));
+    }
+
+    #[test]
+    fn test_smoke_stages_screenshot_to_path() {
+        let stages = smoke_stages(Path::new("/tmp/smoke.png"));
+        assert_eq!(stages[0].args, vec!["open", SMOKE_URL]);
+        assert_eq!(
+            stages.last().unwrap().args,
+            vec!["screenshot", "/tmp/smoke.png"]
+        );
+    }
+}
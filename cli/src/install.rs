@@ -1,22 +1,128 @@
 use crate::color;
+use crate::disk;
+use crate::errors::ErrorCode;
+use crate::i18n::{t, tf};
+use crate::ui;
+use serde_json::json;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::{exit, Command, Stdio};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 const BROWSEROS_VERSION: &str = "0.39.0.3";
 
+/// Shared libraries BrowserOS needs on Debian and Ubuntu, except libasound,
+/// whose package was renamed to libasound2t64 in newer releases
+pub(crate) const APT_DEPS: &[&str] = &[
+    "libxcb-shm0",
+    "libx11-xcb1",
+    "libx11-6",
+    "libxcb1",
+    "libxext6",
+    "libxrandr2",
+    "libxcomposite1",
+    "libxcursor1",
+    "libxdamage1",
+    "libxfixes3",
+    "libxi6",
+    "libgtk-3-0",
+    "libpangocairo-1.0-0",
+    "libpango-1.0-0",
+    "libatk1.0-0",
+    "libcairo-gobject2",
+    "libcairo2",
+    "libgdk-pixbuf-2.0-0",
+    "libxrender1",
+    "libfreetype6",
+    "libfontconfig1",
+    "libdbus-1-3",
+    "libnss3",
+    "libnspr4",
+    "libatk-bridge2.0-0",
+    "libdrm2",
+    "libxkbcommon0",
+    "libatspi2.0-0",
+    "libcups2",
+    "libxshmfence1",
+    "libgbm1",
+];
+
 struct BrowserOSPackage {
     url: &'static str,
     file_name: &'static str,
+    /// Approximate bytes of the download, and of what installing it adds to
+    /// ~/.browseros, for the free space check
+    size: u64,
+    unpacked: u64,
+}
+
+const MB: u64 = 1_000_000;
+
+pub struct InstallOptions {
+    pub with_deps: bool,
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub keep_quarantine: bool,
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub extract_appimage: bool,
+    /// Build the package from the previous one when a delta is published
+    pub delta: bool,
+}
+
+impl InstallOptions {
+    pub fn from_args(args: &[String]) -> InstallOptions {
+        let has = |flag: &str| args.iter().any(|a| a == flag);
+        InstallOptions {
+            with_deps: has("--with-deps") || has("-d"),
+            keep_quarantine: has("--keep-quarantine"),
+            extract_appimage: has("--extract-appimage"),
+            delta: !has("--no-delta"),
+        }
+    }
 }
 
-pub fn run_install(with_deps: bool) {
+/// Handle `agent-browser upgrade`: install the BrowserOS this release pins,
+/// unless its package was already installed
+pub fn run_upgrade(options: &InstallOptions) {
+    if let Some(package) = get_browseros_package() {
+        // Install keeps the package it last installed from, and only that one
+        let current = get_browseros_home()
+            .join("downloads")
+            .join(package.file_name);
+        if current.exists() {
+            if ui::json_mode() {
+                ui::print_json_data(json!({
+                    "browserVersion": BROWSEROS_VERSION,
+                    "upToDate": true,
+                }));
+            } else {
+                ui::status(format!(
+                    "{} {}",
+                    color::success_indicator(),
+                    tf("install.up_to_date", &[("version", &BROWSEROS_VERSION)])
+                ));
+            }
+            return;
+        }
+    }
+    run_install(options);
+}
+
+pub(crate) struct Installed {
+    download_path: PathBuf,
+    /// None on Windows, where the downloaded installer still has to be run
+    pub executable: Option<PathBuf>,
+    delta: bool,
+}
+
+/// Download and install BrowserOS, exiting on failure
+pub(crate) fn install_browseros(options: &InstallOptions) -> Installed {
+    let with_deps = options.with_deps;
     let is_linux = cfg!(target_os = "linux");
 
     if is_linux {
         if with_deps {
-            println!("{}", color::cyan("Installing system dependencies..."));
+            ui::status(color::cyan(&t("install.deps.installing")));
 
             let (pkg_mgr, deps) = if which_exists("apt-get") {
                 let libasound = if package_exists_apt("libasound2t64") {
@@ -25,43 +131,9 @@ pub fn run_install(with_deps: bool) {
                     "libasound2"
                 };
 
-                (
-                    "apt-get",
-                    vec![
-                        "libxcb-shm0",
-                        "libx11-xcb1",
-                        "libx11-6",
-                        "libxcb1",
-                        "libxext6",
-                        "libxrandr2",
-                        "libxcomposite1",
-                        "libxcursor1",
-                        "libxdamage1",
-                        "libxfixes3",
-                        "libxi6",
-                        "libgtk-3-0",
-                        "libpangocairo-1.0-0",
-                        "libpango-1.0-0",
-                        "libatk1.0-0",
-                        "libcairo-gobject2",
-                        "libcairo2",
-                        "libgdk-pixbuf-2.0-0",
-                        "libxrender1",
-                        libasound,
-                        "libfreetype6",
-                        "libfontconfig1",
-                        "libdbus-1-3",
-                        "libnss3",
-                        "libnspr4",
-                        "libatk-bridge2.0-0",
-                        "libdrm2",
-                        "libxkbcommon0",
-                        "libatspi2.0-0",
-                        "libcups2",
-                        "libxshmfence1",
-                        "libgbm1",
-                    ],
-                )
+                let mut deps = APT_DEPS.to_vec();
+                deps.push(libasound);
+                ("apt-get", deps)
             } else if which_exists("dnf") {
                 (
                     "dnf",
@@ -110,11 +182,7 @@ pub fn run_install(with_deps: bool) {
                     ],
                 )
             } else {
-                eprintln!(
-                    "{} No supported package manager found (apt-get, dnf, or yum)",
-                    color::error_indicator()
-                );
-                exit(1);
+                ui::fail(ErrorCode::Unsupported, t("install.deps.no_package_manager"));
             };
 
             let install_cmd = match pkg_mgr {
@@ -127,82 +195,112 @@ pub fn run_install(with_deps: bool) {
                 _ => format!("sudo {} install -y {}", pkg_mgr, deps.join(" ")),
             };
 
-            println!("Running: {}", install_cmd);
-            let status = Command::new("sh").arg("-c").arg(&install_cmd).status();
+            ui::status(tf("install.deps.running", &[("command", &install_cmd)]));
+            let mut command = Command::new("sh");
+            command.arg("-c").arg(&install_cmd);
+            if ui::json_mode() {
+                // Keep stdout clean for the JSON result
+                command.stdout(Stdio::from(std::io::stderr()));
+            }
 
-            match status {
-                Ok(s) if s.success() => {
-                    println!("{} System dependencies installed", color::success_indicator())
-                }
-                Ok(_) => eprintln!(
-                    "{} Failed to install some dependencies. You may need to run manually with sudo.",
-                    color::warning_indicator()
-                ),
-                Err(e) => eprintln!("{} Could not run install command: {}", color::warning_indicator(), e),
+            match command.status() {
+                Ok(s) if s.success() => ui::status(format!(
+                    "{} {}",
+                    color::success_indicator(),
+                    t("install.deps.installed")
+                )),
+                Ok(_) => ui::warn(t("install.deps.failed")),
+                Err(e) => ui::warn(tf("install.deps.run_failed", &[("error", &e)])),
             }
         } else {
-            println!(
-                "{} Linux detected. If browser fails to launch, run:",
-                color::warning_indicator()
-            );
-            println!("  agent-browser install --with-deps");
-            println!();
+            ui::status(format!(
+                "{} {}",
+                color::warning_indicator(),
+                t("install.linux_hint")
+            ));
         }
     }
 
     let Some(package) = get_browseros_package() else {
-        eprintln!(
-            "{} Unsupported platform for BrowserOS install: {} / {}",
-            color::error_indicator(),
-            env::consts::OS,
-            env::consts::ARCH
+        ui::fail(
+            ErrorCode::Unsupported,
+            tf(
+                "install.unsupported_platform",
+                &[("os", &env::consts::OS), ("arch", &env::consts::ARCH)],
+            ),
         );
-        exit(1);
     };
 
+    #[allow(unused_mut)]
+    let mut needed = package.size + package.unpacked;
+    #[cfg(target_os = "linux")]
+    if options.extract_appimage || !fuse_available() {
+        // Unpacked, the AppImage takes about three times its size
+        needed += 3 * package.size;
+    }
+    disk::preflight(needed);
+
     let browseros_home = get_browseros_home();
     let downloads_dir = browseros_home.join("downloads");
     if let Err(e) = fs::create_dir_all(&downloads_dir) {
-        eprintln!(
-            "{} Failed to create download directory {}: {}",
-            color::error_indicator(),
-            downloads_dir.display(),
-            e
+        ui::fail(
+            ErrorCode::Unknown,
+            tf(
+                "install.create_dir_failed",
+                &[("path", &downloads_dir.display()), ("error", &e)],
+            ),
         );
-        exit(1);
     }
 
     let download_path = downloads_dir.join(package.file_name);
-    println!(
-        "{} Downloading BrowserOS {}...",
-        color::cyan("Installing"),
-        BROWSEROS_VERSION
-    );
+    ui::status(format!(
+        "{} {}",
+        color::cyan(&t("install.label")),
+        tf("install.downloading", &[("version", &BROWSEROS_VERSION)])
+    ));
+    tracing::info!(url = package.url, path = %download_path.display(), "downloading BrowserOS");
 
-    if let Err(e) = download_file(package.url, &download_path) {
-        eprintln!("{} {}", color::error_indicator(), e);
-        exit(1);
+    let seed = options
+        .delta
+        .then(|| previous_package(&downloads_dir, package.file_name))
+        .flatten();
+    let mut delta = false;
+    if let Some(seed) = seed {
+        ui::status(tf(
+            "install.delta",
+            &[(
+                "previous",
+                &seed.file_name().unwrap_or_default().to_string_lossy(),
+            )],
+        ));
+        match download_delta(package.url, &seed, &download_path) {
+            Ok(()) => delta = true,
+            Err(e) => {
+                tracing::info!(error = %e, "no delta update, downloading the full package");
+                ui::status(t("install.delta_unavailable"));
+            }
+        }
+    }
+    if !delta {
+        if let Err(e) = download_file(package.url, &download_path) {
+            ui::fail(ErrorCode::DownloadFailed, e);
+        }
     }
 
     let installed_executable: Option<PathBuf> = {
         #[cfg(target_os = "macos")]
         {
-            match install_macos_dmg(&download_path, &browseros_home) {
+            match install_macos_dmg(&download_path, &browseros_home, options.keep_quarantine) {
                 Ok(path) => Some(path),
-                Err(e) => {
-                    eprintln!("{} {}", color::error_indicator(), e);
-                    exit(1);
-                }
+                Err(e) => ui::fail(ErrorCode::Unknown, e),
             }
         }
         #[cfg(target_os = "linux")]
         {
-            match install_linux_appimage(&download_path, &browseros_home) {
+            match install_linux_appimage(&download_path, &browseros_home, options.extract_appimage)
+            {
                 Ok(path) => Some(path),
-                Err(e) => {
-                    eprintln!("{} {}", color::error_indicator(), e);
-                    exit(1);
-                }
+                Err(e) => ui::fail(ErrorCode::Unknown, e),
             }
         }
         #[cfg(not(any(target_os = "macos", target_os = "linux")))]
@@ -211,40 +309,91 @@ pub fn run_install(with_deps: bool) {
         }
     };
 
-    println!("{} BrowserOS package downloaded", color::success_indicator());
-    println!("  {}", download_path.display());
+    // The installed package is the seed for the next delta; older ones aren't needed
+    remove_older_packages(&downloads_dir, package.file_name);
+
+    Installed {
+        download_path,
+        executable: installed_executable,
+        delta,
+    }
+}
+
+pub fn run_install(options: &InstallOptions) {
+    let Installed {
+        download_path,
+        executable: installed_executable,
+        delta,
+    } = install_browseros(options);
+
+    if ui::json_mode() {
+        ui::print_json_data(json!({
+            "browserVersion": BROWSEROS_VERSION,
+            "downloadPath": download_path,
+            "executablePath": installed_executable,
+            "delta": delta,
+        }));
+        return;
+    }
+
+    ui::status(format!(
+        "{} {}",
+        color::success_indicator(),
+        tf("install.downloaded", &[("path", &download_path.display())])
+    ));
 
     if let Some(executable_path) = installed_executable {
-        println!(
-            "{} BrowserOS executable ready:",
-            color::success_indicator()
-        );
-        println!("  {}", executable_path.display());
-        println!();
-        println!("Set this in your shell:");
+        ui::status(format!(
+            "{} {}",
+            color::success_indicator(),
+            tf("install.ready", &[("path", &executable_path.display())])
+        ));
+        // The export line is the actionable result, so it survives --quiet
+        if !ui::quiet() {
+            println!();
+            println!("{}", t("install.set_env"));
+        }
         println!(
             "  export AGENT_BROWSER_EXECUTABLE_PATH=\"{}\"",
             executable_path.display()
         );
     } else if cfg!(target_os = "windows") {
-        println!();
-        println!("Run the downloaded installer, then set:");
-        println!(
-            "  set AGENT_BROWSER_EXECUTABLE_PATH=C:\\Program Files\\BrowserOS\\BrowserOS.exe"
-        );
+        ui::status(t("install.windows_next"));
     }
 
-    if is_linux && !with_deps {
-        println!();
-        println!(
-            "{} If BrowserOS fails to start due to missing shared libraries, run:",
-            color::yellow("Note:")
-        );
-        println!("  agent-browser install --with-deps");
+    if cfg!(target_os = "linux") && !options.with_deps {
+        ui::status(format!(
+            "\n{} {}",
+            color::yellow(&t("install.note")),
+            t("install.missing_libs")
+        ));
     }
 }
 
-fn get_browseros_home() -> PathBuf {
+/// BrowserOS as `agent-browser install` left it, if it's there
+pub(crate) fn installed_executable() -> Option<PathBuf> {
+    let home = get_browseros_home();
+    let mut candidates = Vec::new();
+    if cfg!(target_os = "macos") {
+        candidates.push(home.join("BrowserOS.app/Contents/MacOS/BrowserOS"));
+    } else if cfg!(target_os = "linux") {
+        if let Ok(entries) = fs::read_dir(home.join("versions")) {
+            candidates.extend(
+                entries
+                    .flatten()
+                    .map(|e| e.path().join("squashfs-root").join("AppRun")),
+            );
+        }
+        candidates.push(home.join("bin").join("BrowserOS"));
+    } else if cfg!(target_os = "windows") {
+        if let Some(local) = env::var_os("LOCALAPPDATA") {
+            candidates.push(PathBuf::from(local).join("BrowserOS\\Application\\BrowserOS.exe"));
+        }
+    }
+    candidates.into_iter().find(|p| p.is_file())
+}
+
+pub(crate) fn get_browseros_home() -> PathBuf {
     dirs::home_dir()
         .unwrap_or_else(env::temp_dir)
         .join(".browseros")
@@ -256,14 +405,20 @@ fn get_browseros_package() -> Option<BrowserOSPackage> {
             "aarch64" => Some(BrowserOSPackage {
                 url: "http://cdn.browseros.com/releases/0.39.0.3/macos/BrowserOS_v0.39.0.3_arm64.dmg",
                 file_name: "BrowserOS_v0.39.0.3_arm64.dmg",
+                size: 250 * MB,
+                unpacked: 700 * MB,
             }),
             "x86_64" => Some(BrowserOSPackage {
                 url: "http://cdn.browseros.com/releases/0.39.0.3/macos/BrowserOS_v0.39.0.3_x64.dmg",
                 file_name: "BrowserOS_v0.39.0.3_x64.dmg",
+                size: 250 * MB,
+                unpacked: 700 * MB,
             }),
             _ => Some(BrowserOSPackage {
                 url: "http://cdn.browseros.com/releases/0.39.0.3/macos/BrowserOS_v0.39.0.3_universal.dmg",
                 file_name: "BrowserOS_v0.39.0.3_universal.dmg",
+                size: 250 * MB,
+                unpacked: 700 * MB,
             }),
         };
     }
@@ -272,30 +427,142 @@ fn get_browseros_package() -> Option<BrowserOSPackage> {
         return Some(BrowserOSPackage {
             url: "http://cdn.browseros.com/releases/0.39.0.3/win/BrowserOS_v0.39.0.3_x64_installer.exe",
             file_name: "BrowserOS_v0.39.0.3_x64_installer.exe",
+            size: 200 * MB,
+            unpacked: 0,
         });
     }
 
     if cfg!(target_os = "linux") {
         return Some(BrowserOSPackage {
-            url: "http://cdn.browseros.com/releases/0.39.0.3/linux/BrowserOS_v0.39.0.3_x64.AppImage",
+            url:
+                "http://cdn.browseros.com/releases/0.39.0.3/linux/BrowserOS_v0.39.0.3_x64.AppImage",
             file_name: "BrowserOS_v0.39.0.3_x64.AppImage",
+            size: 250 * MB,
+            unpacked: 250 * MB,
         });
     }
 
     None
 }
 
-fn download_file(url: &str, output_path: &Path) -> Result<(), String> {
-    let output = output_path
-        .to_str()
-        .ok_or_else(|| format!("Invalid output path: {}", output_path.display()))?;
+/// Version and platform suffix of a package file name, e.g.
+/// `BrowserOS_v0.39.0.3_x64.AppImage` is `[0, 39, 0, 3]` and `x64.AppImage`
+fn package_version(file_name: &str) -> Option<(Vec<u32>, &str)> {
+    let (version, suffix) = file_name.strip_prefix("BrowserOS_v")?.split_once('_')?;
+    let version = version
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    Some((version, suffix))
+}
+
+/// Downloaded packages for the same platform as `current`, but another version
+fn other_packages(downloads_dir: &Path, current: &str) -> Vec<(Vec<u32>, PathBuf)> {
+    let Some((current_version, suffix)) = package_version(current) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(downloads_dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let (version, other_suffix) = package_version(&name)?;
+            (other_suffix == suffix && version != current_version).then(|| (version, entry.path()))
+        })
+        .collect()
+}
+
+/// The newest earlier package to build a delta update from
+fn previous_package(downloads_dir: &Path, current: &str) -> Option<PathBuf> {
+    let (current_version, _) = package_version(current)?;
+    other_packages(downloads_dir, current)
+        .into_iter()
+        .filter(|(version, _)| *version < current_version)
+        .max()
+        .map(|(_, path)| path)
+}
+
+fn remove_older_packages(downloads_dir: &Path, current: &str) {
+    for (_, path) in other_packages(downloads_dir, current) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Build the package from `seed` and only the blocks of it that changed,
+/// using the `.zsync` file published next to it. Fails, so the caller
+/// downloads the whole package, if zsync isn't installed or there is no
+/// `.zsync` file for this release.
+fn download_delta(url: &str, seed: &Path, output: &Path) -> Result<(), String> {
+    if !which_exists("zsync") {
+        return Err("zsync is not installed".to_string());
+    }
+    // zsync keeps an existing output as a second seed, under .zs-old
+    let _ = fs::remove_file(output);
+    let control = format!("{}.zsync", url);
+    let status = Command::new("zsync")
+        .args(["-q", "-i"])
+        .arg(seed)
+        .arg("-o")
+        .arg(output)
+        .arg(&control)
+        .current_dir(output.parent().unwrap_or(Path::new(".")))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("Failed to run zsync: {}", e))?;
+    let _ = fs::remove_file(output.with_extension("zs-old"));
+    if status.success() && output.exists() {
+        Ok(())
+    } else {
+        let _ = fs::remove_file(output);
+        Err(format!("zsync could not update from {}", control))
+    }
+}
+
+/// Files `download_files` fetches at once unless `--download-concurrency`
+/// says otherwise
+const DEFAULT_DOWNLOAD_CONCURRENCY: usize = 4;
+
+static DOWNLOAD_CONCURRENCY: AtomicUsize = AtomicUsize::new(DEFAULT_DOWNLOAD_CONCURRENCY);
+
+/// Set the global `--download-concurrency` limit
+pub fn set_download_concurrency(limit: usize) {
+    DOWNLOAD_CONCURRENCY.store(limit.max(1), Ordering::Relaxed);
+}
+
+pub(crate) fn download_file(url: &str, output_path: &Path) -> Result<(), String> {
+    download_files(&[(url, output_path)])
+}
+
+/// Download several files with one HTTP client, so requests to the same host
+/// share a connection: a single curl process, or a single .NET `HttpClient`
+/// on Windows. Both fetch up to `--download-concurrency` files at once (curl
+/// older than 7.66 fetches them in turn). The wget fallback has no way to
+/// name several output files, so it runs once per file.
+pub(crate) fn download_files(files: &[(&str, &Path)]) -> Result<(), String> {
+    let mut downloads = Vec::with_capacity(files.len());
+    for (url, output_path) in files {
+        let output = output_path
+            .to_str()
+            .ok_or_else(|| format!("Invalid output path: {}", output_path.display()))?;
+        downloads.push((*url, output));
+        // A file left from an earlier attempt would pass the check below
+        let _ = fs::remove_file(output_path);
+    }
+    let urls = || {
+        downloads
+            .iter()
+            .map(|(url, _)| *url)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
 
     #[cfg(windows)]
     let status = {
-        let script = format!(
-            "$ProgressPreference='SilentlyContinue'; Invoke-WebRequest -Uri '{}' -OutFile '{}'",
-            url, output
-        );
+        let limit = DOWNLOAD_CONCURRENCY.load(Ordering::Relaxed);
+        let script = powershell_download_script(&downloads, limit);
         Command::new("powershell")
             .args(["-NoProfile", "-NonInteractive", "-Command", &script])
             .status()
@@ -305,32 +572,109 @@ fn download_file(url: &str, output_path: &Path) -> Result<(), String> {
     #[cfg(not(windows))]
     let status = {
         if which_exists("curl") {
-            Command::new("curl")
-                .args(["-fL", "--retry", "3", "-o", output, url])
-                .status()
+            let mut curl = Command::new("curl");
+            // Without --fail-early, curl's status is only that of the last transfer
+            curl.args(["-fL", "--fail-early", "--retry", "3"]);
+            if ui::quiet() {
+                curl.arg("-sS");
+            }
+            if downloads.len() > 1 && curl_supports_parallel() {
+                let limit = DOWNLOAD_CONCURRENCY.load(Ordering::Relaxed).to_string();
+                curl.args(["--parallel", "--parallel-max", &limit]);
+            }
+            for (url, output) in &downloads {
+                curl.args(["-o", output, url]);
+            }
+            curl.status()
                 .map_err(|e| format!("Failed to run curl: {}", e))?
         } else if which_exists("wget") {
-            Command::new("wget")
-                .args(["-O", output, url])
-                .status()
-                .map_err(|e| format!("Failed to run wget: {}", e))?
+            // wget has one -O for all its URLs, so it runs once per file
+            for (url, output) in &downloads {
+                let status = Command::new("wget")
+                    .args(["-O", output, url])
+                    .status()
+                    .map_err(|e| format!("Failed to run wget: {}", e))?;
+                if !status.success() {
+                    return Err(format!(
+                        "Download failed for {} (exit status: {})",
+                        url, status
+                    ));
+                }
+            }
+            return Ok(());
         } else {
             return Err("Neither curl nor wget is available in PATH".to_string());
         }
     };
 
-    if status.success() {
-        Ok(())
-    } else {
-        Err(format!(
+    if !status.success() {
+        return Err(format!(
             "Download failed for {} (exit status: {})",
-            url, status
-        ))
+            urls(),
+            status
+        ));
+    }
+    match downloads
+        .iter()
+        .find(|(_, output)| !Path::new(output).is_file())
+    {
+        Some((url, _)) => Err(format!("Download failed for {}", url)),
+        None => Ok(()),
     }
 }
 
+/// PowerShell that downloads `(url, output)` pairs over one `HttpClient`,
+/// `limit` at a time. Any failed request ends the script with a failing
+/// status.
+#[cfg(any(windows, test))]
+fn powershell_download_script(downloads: &[(&str, &str)], limit: usize) -> String {
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+    let list = |items: Vec<String>| format!("@({})", items.join(","));
+    let urls = list(downloads.iter().map(|(url, _)| quote(url)).collect());
+    let outputs = list(downloads.iter().map(|(_, output)| quote(output)).collect());
+    format!(
+        "$ErrorActionPreference='Stop'; \
+         [Net.ServicePointManager]::SecurityProtocol = \
+         [Net.ServicePointManager]::SecurityProtocol -bor [Net.SecurityProtocolType]::Tls12; \
+         Add-Type -AssemblyName System.Net.Http; \
+         $client = New-Object System.Net.Http.HttpClient; \
+         $client.DefaultRequestHeaders.UserAgent.ParseAdd('agent-browser'); \
+         $urls = {urls}; $outputs = {outputs}; \
+         for ($i = 0; $i -lt $urls.Count; $i += {limit}) {{ \
+         $end = [Math]::Min($i + {limit}, $urls.Count) - 1; \
+         $tasks = @(foreach ($k in $i..$end) {{ $client.GetByteArrayAsync($urls[$k]) }}); \
+         [Threading.Tasks.Task]::WaitAll([Threading.Tasks.Task[]]$tasks); \
+         foreach ($k in $i..$end) {{ [IO.File]::WriteAllBytes($outputs[$k], $tasks[$k - $i].Result) }} }}",
+        limit = limit.max(1),
+    )
+}
+
+#[cfg(not(windows))]
+fn curl_supports_parallel() -> bool {
+    Command::new("curl")
+        .arg("--version")
+        .output()
+        .map(|o| parallel_in_version(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or(false)
+}
+
+/// Whether `curl --version` output is from 7.66 or later, which added
+/// `--parallel`
+#[cfg(any(not(windows), test))]
+fn parallel_in_version(output: &str) -> bool {
+    let version = output.split_whitespace().nth(1).unwrap_or("");
+    let mut parts = version.split('.').map(|p| p.parse::<u32>().unwrap_or(0));
+    let major = parts.next().unwrap_or(0);
+    let minor = parts.next().unwrap_or(0);
+    (major, minor) >= (7, 66)
+}
+
 #[cfg(target_os = "macos")]
-fn install_macos_dmg(dmg_path: &Path, browseros_home: &Path) -> Result<PathBuf, String> {
+fn install_macos_dmg(
+    dmg_path: &Path,
+    browseros_home: &Path,
+    keep_quarantine: bool,
+) -> Result<PathBuf, String> {
     let mount_dir = browseros_home.join("mount");
     let app_target = browseros_home.join("BrowserOS.app");
 
@@ -349,13 +693,25 @@ fn install_macos_dmg(dmg_path: &Path, browseros_home: &Path) -> Result<PathBuf,
             .status();
         let _ = fs::remove_dir_all(&mount_dir);
     }
-    fs::create_dir_all(&mount_dir)
-        .map_err(|e| format!("Failed to create mount directory {}: {}", mount_dir.display(), e))?;
+    fs::create_dir_all(&mount_dir).map_err(|e| {
+        format!(
+            "Failed to create mount directory {}: {}",
+            mount_dir.display(),
+            e
+        )
+    })?;
 
     let mount_str = mount_dir.to_string_lossy().to_string();
     let dmg_str = dmg_path.to_string_lossy().to_string();
     let attach = Command::new("hdiutil")
-        .args(["attach", "-nobrowse", "-quiet", "-mountpoint", &mount_str, &dmg_str])
+        .args([
+            "attach",
+            "-nobrowse",
+            "-quiet",
+            "-mountpoint",
+            &mount_str,
+            &dmg_str,
+        ])
         .status()
         .map_err(|e| format!("Failed to mount BrowserOS DMG: {}", e))?;
 
@@ -410,11 +766,87 @@ fn install_macos_dmg(dmg_path: &Path, browseros_home: &Path) -> Result<PathBuf,
         .status();
     let _ = fs::remove_dir_all(&mount_dir);
 
-    result
+    let executable = result?;
+    prepare_macos_app(&app_target, keep_quarantine)?;
+    Ok(executable)
+}
+
+/// Make sure macOS will launch the copied app. Gatekeeper kills a quarantined
+/// app it can't assess on first launch without telling the process that
+/// started it, so the quarantine flag is dropped, the signature is checked to
+/// have survived the copy, and Gatekeeper is asked up front.
+#[cfg(target_os = "macos")]
+fn prepare_macos_app(app: &Path, keep_quarantine: bool) -> Result<(), String> {
+    let app_str = app.to_string_lossy().to_string();
+
+    if keep_quarantine {
+        ui::status(format!(
+            "{} {}",
+            color::warning_indicator(),
+            tf("install.quarantine_kept", &[("path", &app.display())])
+        ));
+    } else {
+        let removed = Command::new("xattr")
+            .args(["-dr", "com.apple.quarantine", &app_str])
+            .stderr(Stdio::null())
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false);
+        if !removed {
+            ui::warn(tf("install.quarantine_failed", &[("path", &app.display())]));
+        }
+    }
+
+    let codesign = Command::new("codesign")
+        .args(["--verify", "--deep", "--strict", &app_str])
+        .output()
+        .map_err(|e| format!("Failed to run codesign: {}", e))?;
+    if !codesign.status.success() {
+        return Err(format!(
+            "BrowserOS.app failed signature verification, so macOS will refuse to launch it:\n  {}\nThe download may be damaged. Delete {} and run: agent-browser install",
+            String::from_utf8_lossy(&codesign.stderr).trim(),
+            app.display()
+        ));
+    }
+
+    // spctl prints its verdict on stderr and exits non-zero on rejection
+    if let Ok(assess) = Command::new("spctl")
+        .args(["--assess", "--type", "execute", "-vv", &app_str])
+        .output()
+    {
+        if !assess.status.success() {
+            let reason = gatekeeper_rejection(&String::from_utf8_lossy(&assess.stderr))
+                .unwrap_or_else(|| "rejected".to_string());
+            ui::warn(tf(
+                "install.gatekeeper_rejected",
+                &[("reason", &reason), ("path", &app.display())],
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Why `spctl --assess -vv` rejected an app: its `source=` line, or None if
+/// the output doesn't say it was rejected
+#[cfg(any(target_os = "macos", test))]
+fn gatekeeper_rejection(output: &str) -> Option<String> {
+    if !output.lines().any(|l| l.trim_end().ends_with(": rejected")) {
+        return None;
+    }
+    let source = output
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("source="))
+        .unwrap_or("rejected");
+    Some(source.to_string())
 }
 
 #[cfg(target_os = "linux")]
-fn install_linux_appimage(appimage_path: &Path, browseros_home: &Path) -> Result<PathBuf, String> {
+fn install_linux_appimage(
+    appimage_path: &Path,
+    browseros_home: &Path,
+    extract: bool,
+) -> Result<PathBuf, String> {
     let bin_dir = browseros_home.join("bin");
     fs::create_dir_all(&bin_dir).map_err(|e| {
         format!(
@@ -446,6 +878,75 @@ fn install_linux_appimage(appimage_path: &Path, browseros_home: &Path) -> Result
         ));
     }
 
+    let versions_dir = browseros_home.join("versions");
+    if !extract && fuse_available() {
+        // The launcher prefers an extracted copy, so drop any older one
+        let _ = fs::remove_dir_all(&versions_dir);
+        return Ok(executable);
+    }
+    if !extract {
+        ui::status(format!(
+            "{} {}",
+            color::warning_indicator(),
+            t("install.no_fuse")
+        ));
+    }
+    extract_appimage(&executable, &versions_dir)
+}
+
+/// Whether AppImages can mount themselves: the FUSE device and its mount
+/// helper are both needed, and containers usually have neither
+#[cfg(target_os = "linux")]
+fn fuse_available() -> bool {
+    Path::new("/dev/fuse").exists() && (which_exists("fusermount") || which_exists("fusermount3"))
+}
+
+/// Unpack the AppImage with its own `--appimage-extract`, which needs no FUSE,
+/// into `versions/<version>/squashfs-root` and return its entry point
+#[cfg(target_os = "linux")]
+fn extract_appimage(appimage: &Path, versions_dir: &Path) -> Result<PathBuf, String> {
+    // One extracted version at a time; each is several hundred MB
+    let _ = fs::remove_dir_all(versions_dir);
+    let version_dir = versions_dir.join(BROWSEROS_VERSION);
+    fs::create_dir_all(&version_dir).map_err(|e| {
+        format!(
+            "Failed to create BrowserOS version directory {}: {}",
+            version_dir.display(),
+            e
+        )
+    })?;
+
+    ui::status(format!(
+        "{} {}",
+        color::cyan(&t("install.label")),
+        tf("install.extracting", &[("path", &version_dir.display())])
+    ));
+    let extract = Command::new(appimage)
+        .arg("--appimage-extract")
+        .current_dir(&version_dir)
+        .stdout(Stdio::null())
+        .status()
+        .map_err(|e| {
+            format!(
+                "Failed to run {} --appimage-extract: {}",
+                appimage.display(),
+                e
+            )
+        })?;
+    if !extract.success() {
+        return Err(format!(
+            "Failed to extract the BrowserOS AppImage into {}",
+            version_dir.display()
+        ));
+    }
+
+    let executable = version_dir.join("squashfs-root").join("AppRun");
+    if !executable.exists() {
+        return Err(format!(
+            "Extracted BrowserOS entry point not found: {}",
+            executable.display()
+        ));
+    }
     Ok(executable)
 }
 
@@ -482,3 +983,85 @@ fn package_exists_apt(pkg: &str) -> bool {
         .map(|s| s.success())
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_previous_package() {
+        assert_eq!(
+            package_version("BrowserOS_v0.39.0.3_x64.AppImage"),
+            Some((vec![0, 39, 0, 3], "x64.AppImage"))
+        );
+        assert_eq!(package_version("BrowserOS_vnext_x64.AppImage"), None);
+
+        let dir = env::temp_dir().join(format!("agent-browser-packages-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "BrowserOS_v0.38.0.1_x64.AppImage",
+            "BrowserOS_v0.38.1.0_x64.AppImage",
+            "BrowserOS_v0.38.2.0_arm64.dmg",
+            "BrowserOS_v0.40.0.0_x64.AppImage",
+        ] {
+            fs::write(dir.join(name), b"").unwrap();
+        }
+        let current = "BrowserOS_v0.39.0.3_x64.AppImage";
+        assert_eq!(
+            previous_package(&dir, current),
+            Some(dir.join("BrowserOS_v0.38.1.0_x64.AppImage"))
+        );
+        remove_older_packages(&dir, current);
+        assert_eq!(previous_package(&dir, current), None);
+        assert!(dir.join("BrowserOS_v0.38.2.0_arm64.dmg").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parallel_in_version() {
+        let modern = "curl 8.5.0 (x86_64-pc-linux-gnu) libcurl/8.5.0 OpenSSL/3.0.13\nRelease-Date: 2023-12-06\n";
+        assert!(parallel_in_version(modern));
+        assert!(parallel_in_version("curl 7.66.0 (x86_64-apple-darwin19.0)"));
+        assert!(!parallel_in_version(
+            "curl 7.58.0 (x86_64-pc-linux-gnu) libcurl/7.58.0"
+        ));
+        assert!(!parallel_in_version(""));
+    }
+
+    #[test]
+    fn test_powershell_download_script() {
+        let script = powershell_download_script(
+            &[
+                ("https://example.com/a", "C:\\tmp\\a"),
+                ("https://example.com/b", "C:\\Users\\o'brien\\b"),
+            ],
+            4,
+        );
+        assert!(script.starts_with("$ErrorActionPreference='Stop';"));
+        assert_eq!(
+            script
+                .matches("New-Object System.Net.Http.HttpClient")
+                .count(),
+            1
+        );
+        assert!(script.contains("$urls = @('https://example.com/a','https://example.com/b');"));
+        assert!(script.contains("'C:\\Users\\o''brien\\b'"));
+        assert!(script.contains("$i += 4)"));
+    }
+
+    #[test]
+    fn test_gatekeeper_rejection() {
+        let rejected =
+            "/Users/ada/.browseros/BrowserOS.app: rejected\nsource=Unnotarized Developer ID\n";
+        assert_eq!(
+            gatekeeper_rejection(rejected).as_deref(),
+            Some("Unnotarized Developer ID")
+        );
+        let accepted = "/Users/ada/.browseros/BrowserOS.app: accepted\nsource=Notarized Developer ID\norigin=Developer ID Application: BrowserOS\n";
+        assert_eq!(gatekeeper_rejection(accepted), None);
+        assert_eq!(
+            gatekeeper_rejection("BrowserOS.app: rejected\n").as_deref(),
+            Some("rejected")
+        );
+    }
+}
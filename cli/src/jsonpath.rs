@@ -0,0 +1,358 @@
+//! JSONPath post-filters (`--json-path <expr>`).
+//!
+//! Selects from a command's JSON data (what `--json` prints under `data`) so
+//! pipelines can reshape results without jq. The supported subset:
+//!
+//! ```text
+//! $.result.items[0].name       names and indexes ($ is optional)
+//! $.apis[*].url  $.apis.*      every element or value
+//! $.result[-1]  $.result[1:3]  negative indexes and slices
+//! $['a-b', 'c']  $.result[0,2] several names or indexes
+//! $..price                     recursive descent
+//! $.apis[?(@.method == "POST" && @.count > 1)].url
+//! ```
+//!
+//! Filters use the script expression language (see `expr`), with `@` as the
+//! element being tested. A path without wildcards, slices, unions, filters or
+//! `..` selects one value, or null if it isn't there; any other path selects a
+//! list of the matches.
+
+use serde_json::Value;
+
+use crate::expr::{self, Expr};
+use crate::vars::Vars;
+
+#[derive(Debug, Clone)]
+enum Selector {
+    Name(String),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>),
+    Wildcard,
+    Filter(Expr),
+}
+
+#[derive(Debug, Clone)]
+struct Segment {
+    /// `..`: the selectors apply at every depth
+    recursive: bool,
+    selectors: Vec<Selector>,
+}
+
+#[derive(Debug, Clone)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    /// Whether the path selects at most one value
+    fn is_single(&self) -> bool {
+        self.segments.iter().all(|segment| {
+            !segment.recursive
+                && matches!(
+                    segment.selectors.as_slice(),
+                    [Selector::Name(_) | Selector::Index(_)]
+                )
+        })
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+/// A quoted name inside brackets; `chars[*i]` is the opening quote
+fn parse_quoted(chars: &[char], i: &mut usize) -> Result<String, String> {
+    let quote = chars[*i];
+    let mut name = String::new();
+    *i += 1;
+    loop {
+        match chars.get(*i) {
+            None => return Err("unterminated string".to_string()),
+            Some(&c) if c == quote => break,
+            Some('\\') => {
+                *i += 1;
+                name.push(*chars.get(*i).ok_or("unterminated string")?);
+            }
+            Some(&c) => name.push(c),
+        }
+        *i += 1;
+    }
+    *i += 1;
+    Ok(name)
+}
+
+/// The text of a filter up to its closing `]`, skipping quoted strings
+fn filter_text(chars: &[char], i: &mut usize) -> Result<String, String> {
+    let start = *i;
+    let mut depth = 0;
+    while let Some(&c) = chars.get(*i) {
+        match c {
+            '"' | '\'' => {
+                parse_quoted(chars, i)?;
+                continue;
+            }
+            '[' | '(' => depth += 1,
+            ')' => depth -= 1,
+            ']' if depth == 0 => return Ok(chars[start..*i].iter().collect()),
+            ']' => depth -= 1,
+            _ => {}
+        }
+        *i += 1;
+    }
+    Err("unterminated filter".to_string())
+}
+
+fn parse_int(text: &str) -> Result<Option<i64>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    text.parse()
+        .map(Some)
+        .map_err(|_| format!("invalid index: {}", text))
+}
+
+/// The selectors between `[` and `]`; `chars[*i]` follows the `[`
+fn parse_brackets(chars: &[char], i: &mut usize) -> Result<Vec<Selector>, String> {
+    let skip_spaces = |i: &mut usize| {
+        while chars.get(*i).is_some_and(|c| c.is_whitespace()) {
+            *i += 1;
+        }
+    };
+    skip_spaces(i);
+    if chars.get(*i) == Some(&'?') {
+        *i += 1;
+        let text = filter_text(chars, i)?;
+        *i += 1;
+        let text = text.trim();
+        let text = text
+            .strip_prefix('(')
+            .and_then(|t| t.strip_suffix(')'))
+            .unwrap_or(text);
+        let filter = expr::parse(text).map_err(|e| format!("in filter '{}': {}", text, e))?;
+        return Ok(vec![Selector::Filter(filter)]);
+    }
+    let mut selectors = Vec::new();
+    loop {
+        skip_spaces(i);
+        match chars.get(*i) {
+            Some('"' | '\'') => selectors.push(Selector::Name(parse_quoted(chars, i)?)),
+            Some('*') => {
+                *i += 1;
+                selectors.push(Selector::Wildcard);
+            }
+            Some(_) => {
+                let start = *i;
+                while chars.get(*i).is_some_and(|c| !matches!(c, ',' | ']')) {
+                    *i += 1;
+                }
+                let text: String = chars[start..*i].iter().collect();
+                selectors.push(match text.split_once(':') {
+                    Some((from, to)) => Selector::Slice(parse_int(from)?, parse_int(to)?),
+                    None => Selector::Index(
+                        parse_int(&text)?.ok_or_else(|| "empty brackets".to_string())?,
+                    ),
+                });
+            }
+            None => return Err("missing ']'".to_string()),
+        }
+        skip_spaces(i);
+        match chars.get(*i) {
+            Some(',') => *i += 1,
+            Some(']') => {
+                *i += 1;
+                return Ok(selectors);
+            }
+            Some(c) => return Err(format!("unexpected '{}' in brackets", c)),
+            None => return Err("missing ']'".to_string()),
+        }
+    }
+}
+
+pub fn parse(text: &str) -> Result<JsonPath, String> {
+    let chars: Vec<char> = text.trim().chars().collect();
+    let mut i = usize::from(chars.first() == Some(&'$'));
+    let mut segments = Vec::new();
+    // A leading name needs no dot: `result.items` is `$.result.items`
+    if i == 0 && chars.first().copied().is_some_and(is_name_char) {
+        let start = i;
+        while chars.get(i).copied().is_some_and(is_name_char) {
+            i += 1;
+        }
+        segments.push(Segment {
+            recursive: false,
+            selectors: vec![Selector::Name(chars[start..i].iter().collect())],
+        });
+    }
+    while i < chars.len() {
+        let recursive = chars[i..].starts_with(&['.', '.']);
+        let selectors = if chars[i] == '[' {
+            i += 1;
+            parse_brackets(&chars, &mut i)?
+        } else if chars[i] == '.' {
+            i += if recursive { 2 } else { 1 };
+            match chars.get(i) {
+                Some('*') => {
+                    i += 1;
+                    vec![Selector::Wildcard]
+                }
+                Some('[') if recursive => {
+                    i += 1;
+                    parse_brackets(&chars, &mut i)?
+                }
+                Some(&c) if is_name_char(c) => {
+                    let start = i;
+                    while chars.get(i).copied().is_some_and(is_name_char) {
+                        i += 1;
+                    }
+                    vec![Selector::Name(chars[start..i].iter().collect())]
+                }
+                _ => return Err(format!("expected a name after '.' at {}", i + 1)),
+            }
+        } else {
+            return Err(format!("unexpected '{}' at {}", chars[i], i + 1));
+        };
+        segments.push(Segment {
+            recursive,
+            selectors,
+        });
+    }
+    Ok(JsonPath { segments })
+}
+
+/// The node and everything below it, in document order
+fn descendants<'a>(value: &'a Value, out: &mut Vec<&'a Value>) {
+    out.push(value);
+    match value {
+        Value::Array(items) => items.iter().for_each(|item| descendants(item, out)),
+        Value::Object(map) => map.values().for_each(|item| descendants(item, out)),
+        _ => {}
+    }
+}
+
+fn children(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => map.values().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn select_one<'a>(
+    value: &'a Value,
+    selector: &Selector,
+    out: &mut Vec<&'a Value>,
+) -> Result<(), String> {
+    let index = |len: usize, i: i64| -> i64 {
+        if i < 0 {
+            len as i64 + i
+        } else {
+            i
+        }
+    };
+    match selector {
+        Selector::Name(name) => out.extend(value.get(name)),
+        Selector::Index(i) => {
+            if let Value::Array(items) = value {
+                let i = index(items.len(), *i);
+                out.extend(usize::try_from(i).ok().and_then(|i| items.get(i)));
+            }
+        }
+        Selector::Slice(from, to) => {
+            if let Value::Array(items) = value {
+                let len = items.len() as i64;
+                let from = from.map_or(0, |f| index(items.len(), f).clamp(0, len));
+                let to = to.map_or(len, |t| index(items.len(), t).clamp(0, len));
+                if from < to {
+                    out.extend(&items[from as usize..to as usize]);
+                }
+            }
+        }
+        Selector::Wildcard => out.extend(children(value)),
+        Selector::Filter(filter) => {
+            for child in children(value) {
+                let vars: Vars = [("@".to_string(), child.clone())].into_iter().collect();
+                if expr::truthy(&expr::eval(filter, &vars)?) {
+                    out.push(child);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply a path to a value: the single value for a definite path (null if it
+/// isn't there), else the list of matches.
+pub fn select(path: &JsonPath, root: &Value) -> Result<Value, String> {
+    let mut nodes = vec![root];
+    for segment in &path.segments {
+        let mut next = Vec::new();
+        for node in nodes {
+            let mut targets = Vec::new();
+            if segment.recursive {
+                descendants(node, &mut targets);
+            } else {
+                targets.push(node);
+            }
+            for target in targets {
+                for selector in &segment.selectors {
+                    select_one(target, selector, &mut next)?;
+                }
+            }
+        }
+        nodes = next;
+    }
+    Ok(if path.is_single() {
+        nodes.first().map_or(Value::Null, |v| (*v).clone())
+    } else {
+        Value::Array(nodes.into_iter().cloned().collect())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn query(path: &str, data: &Value) -> Value {
+        select(&parse(path).unwrap(), data).unwrap()
+    }
+
+    #[test]
+    fn test_select() {
+        let data = json!({
+            "result": [
+                { "name": "Pen", "price": 2, "tags": ["office"] },
+                { "name": "Ink", "price": 12, "tags": [] },
+                { "name": "Pad", "price": 5, "tags": ["office", "paper"] }
+            ],
+            "content-type": "json"
+        });
+        assert_eq!(query("$.result[0].name", &data), json!("Pen"));
+        assert_eq!(query("result[-1].name", &data), json!("Pad"));
+        assert_eq!(query("$.result[*].price", &data), json!([2, 12, 5]));
+        assert_eq!(query("$.result[1:].name", &data), json!(["Ink", "Pad"]));
+        assert_eq!(query("$.result[0,2].name", &data), json!(["Pen", "Pad"]));
+        assert_eq!(query("$['content-type']", &data), json!("json"));
+        assert_eq!(query("$..price", &data), json!([2, 12, 5]));
+        assert_eq!(
+            query("$.result[?(@.price > 4 && len(@.tags) > 0)].name", &data),
+            json!(["Pad"])
+        );
+        assert_eq!(
+            query("$.result[?'office' in @.tags].name", &data),
+            json!(["Pen", "Pad"])
+        );
+        assert_eq!(query("$.missing.name", &data), Value::Null);
+        assert_eq!(query("$", &data), data);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("$.").is_err());
+        assert!(parse("$.result[").is_err());
+        assert!(parse("$.result[a]").is_err());
+        assert!(parse("$.result[?(@.price >)]").is_err());
+        assert!(parse("$ result").is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! Structured logging.
+//!
+//! Events are emitted with `tracing` and routed to two optional sinks:
+//! stderr (enabled by `-v` / `-vv` or `--log-level`) and an append-only log
+//! file (`--log-file`). Both can use `--log-format json`, one object per line.
+//! Every event inside a command carries the session name and command id, so
+//! logs from many sessions or machines can be merged and filtered centrally.
+
+use std::fs::OpenOptions;
+use std::sync::Mutex;
+
+use tracing::level_filters::LevelFilter;
+use tracing::Subscriber;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, Layer, Registry};
+
+use crate::color;
+
+pub const LEVELS: &[&str] = &["off", "error", "warn", "info", "debug", "trace"];
+
+pub struct LogOptions<'a> {
+    /// Count of `-v` flags; raises the stderr level to debug (1) or trace (2+).
+    pub verbosity: u8,
+    pub level: Option<&'a str>,
+    pub file: Option<&'a str>,
+    pub format: Option<&'a str>,
+}
+
+pub fn parse_level(level: &str) -> Option<LevelFilter> {
+    match level.to_ascii_lowercase().as_str() {
+        "off" => Some(LevelFilter::OFF),
+        "error" => Some(LevelFilter::ERROR),
+        "warn" | "warning" => Some(LevelFilter::WARN),
+        "info" => Some(LevelFilter::INFO),
+        "debug" => Some(LevelFilter::DEBUG),
+        "trace" => Some(LevelFilter::TRACE),
+        _ => None,
+    }
+}
+
+/// Level for stderr: `-v` flags win, then `--log-level`, else silent.
+fn stderr_level(verbosity: u8, configured: Option<LevelFilter>) -> LevelFilter {
+    match verbosity {
+        0 => configured.unwrap_or(LevelFilter::OFF),
+        1 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+fn layer<S, W>(writer: W, json: bool, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    W: for<'w> fmt::MakeWriter<'w> + Send + Sync + 'static,
+{
+    if json {
+        fmt::layer()
+            .json()
+            .with_current_span(false)
+            .with_span_list(true)
+            .with_writer(writer)
+            .boxed()
+    } else {
+        fmt::layer().with_ansi(ansi).with_writer(writer).boxed()
+    }
+}
+
+/// Install the global subscriber. Returns an error for invalid options or
+/// an unwritable log file.
+pub fn init(opts: &LogOptions) -> Result<(), String> {
+    let configured = match opts.level {
+        Some(level) => Some(parse_level(level).ok_or_else(|| {
+            format!(
+                "Invalid --log-level value: '{}' (expected one of {})",
+                level,
+                LEVELS.join(", ")
+            )
+        })?),
+        None => None,
+    };
+    let json = match opts.format {
+        None | Some("text") => false,
+        Some("json") => true,
+        Some(other) => {
+            return Err(format!(
+                "Invalid --log-format value: '{}' (expected text or json)",
+                other
+            ))
+        }
+    };
+
+    let mut layers: Vec<Box<dyn Layer<Registry> + Send + Sync>> = Vec::new();
+
+    // Span fields are formatted once and shared between layers, so colored
+    // stderr output would leak escape codes into the log file
+    let ansi = color::is_enabled() && opts.file.is_none();
+
+    let stderr = stderr_level(opts.verbosity, configured);
+    if stderr != LevelFilter::OFF {
+        layers.push(
+            layer(std::io::stderr, json, ansi)
+                .with_filter(stderr)
+                .boxed(),
+        );
+    }
+
+    if let Some(path) = opts.file {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open log file {}: {}", path, e))?;
+        layers.push(
+            layer(Mutex::new(file), json, false)
+                .with_filter(configured.unwrap_or(LevelFilter::INFO))
+                .boxed(),
+        );
+    }
+
+    if !layers.is_empty() {
+        let _ = tracing_subscriber::registry().with(layers).try_init();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("DEBUG"), Some(LevelFilter::DEBUG));
+        assert_eq!(parse_level("warning"), Some(LevelFilter::WARN));
+        assert_eq!(parse_level("loud"), None);
+    }
+
+    #[test]
+    fn test_verbosity_overrides_log_level_on_stderr() {
+        assert_eq!(stderr_level(0, None), LevelFilter::OFF);
+        assert_eq!(stderr_level(0, Some(LevelFilter::WARN)), LevelFilter::WARN);
+        assert_eq!(stderr_level(1, Some(LevelFilter::WARN)), LevelFilter::DEBUG);
+        assert_eq!(stderr_level(2, None), LevelFilter::TRACE);
+    }
+}
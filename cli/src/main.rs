@@ -1,28 +1,63 @@
+mod artifacts;
+mod audit_log;
+mod cancel;
 mod color;
 mod commands;
+mod completions;
+mod config;
 mod connection;
+mod container;
+mod dataset;
+mod diff;
+mod disk;
+mod doctor;
+mod errors;
+mod export;
+mod expr;
+mod extensions;
+mod filters;
 mod flags;
+mod guardrails;
+mod hooks;
+mod i18n;
+mod init;
 mod install;
+mod jsonpath;
+mod logging;
+mod msgpack;
+mod otel;
 mod output;
+mod plugins;
+mod report;
+mod run;
+mod self_update;
+mod sink;
+mod timeouts;
+mod trace;
+mod transcript;
+mod ui;
+mod upload;
+mod vars;
+mod verify;
+mod visual;
+mod wasm_plugin;
 
 use serde_json::json;
 use std::env;
-use std::fs;
+use std::io::{self, Write};
 use std::process::exit;
-
-#[cfg(unix)]
-use libc;
-
-#[cfg(windows)]
-use windows_sys::Win32::Foundation::CloseHandle;
-#[cfg(windows)]
-use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+use std::time::Instant;
 
 use commands::{gen_id, parse_command, ParseError};
-use connection::{ensure_daemon, get_socket_dir, send_command};
-use flags::{clean_args, parse_flags};
-use install::run_install;
-use output::{print_command_help, print_help, print_response, print_version};
+use connection::{ensure_daemon, list_sessions, send_command, stream_command};
+use errors::ErrorCode;
+use flags::{
+    clean_args, parse_action_rate, parse_duration_ms, parse_flags, parse_max_concurrent,
+    parse_rate, parse_recycle_mode, parse_tab_memory, split_args,
+};
+use hooks::{Hook, Hooks};
+use install::{run_install, InstallOptions};
+use output::{print_command_help, print_help, print_response, print_selected, print_version};
 
 fn parse_proxy(proxy_str: &str) -> serde_json::Value {
     let Some(protocol_end) = proxy_str.find("://") else {
@@ -54,53 +89,28 @@ fn parse_proxy(proxy_str: &str) -> serde_json::Value {
     })
 }
 
+/// Absolute path of a file given on the command line, since the daemon may run
+/// from another directory. Fails if it doesn't exist.
+fn existing_file(path: &str, what: &str) -> String {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.into());
+    if !absolute.is_file() {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            format!("{} not found: {}", what, path),
+        );
+    }
+    absolute.display().to_string()
+}
+
 fn run_session(args: &[String], session: &str, json_mode: bool) {
     let subcommand = args.get(1).map(|s| s.as_str());
 
     match subcommand {
         Some("list") => {
-            let socket_dir = get_socket_dir();
-            let mut sessions: Vec<String> = Vec::new();
-
-            if let Ok(entries) = fs::read_dir(&socket_dir) {
-                for entry in entries.flatten() {
-                    let name = entry.file_name().to_string_lossy().to_string();
-                    // Look for pid files in socket directory
-                    if name.ends_with(".pid") {
-                        let session_name = name.strip_suffix(".pid").unwrap_or("");
-                        if !session_name.is_empty() {
-                            // Check if session is actually running
-                            let pid_path = socket_dir.join(&name);
-                            if let Ok(pid_str) = fs::read_to_string(&pid_path) {
-                                if let Ok(pid) = pid_str.trim().parse::<u32>() {
-                                    #[cfg(unix)]
-                                    let running = unsafe { libc::kill(pid as i32, 0) == 0 };
-                                    #[cfg(windows)]
-                                    let running = unsafe {
-                                        let handle =
-                                            OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
-                                        if handle != 0 {
-                                            CloseHandle(handle);
-                                            true
-                                        } else {
-                                            false
-                                        }
-                                    };
-                                    if running {
-                                        sessions.push(session_name.to_string());
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+            let sessions = list_sessions();
 
             if json_mode {
-                println!(
-                    r#"{{"success":true,"data":{{"sessions":{}}}}}"#,
-                    serde_json::to_string(&sessions).unwrap_or_default()
-                );
+                ui::print_json_data(json!({ "sessions": sessions }));
             } else if sessions.is_empty() {
                 println!("No active sessions");
             } else {
@@ -118,7 +128,7 @@ fn run_session(args: &[String], session: &str, json_mode: bool) {
         None | Some(_) => {
             // Just show current session
             if json_mode {
-                println!(r#"{{"success":true,"data":{{"session":"{}"}}}}"#, session);
+                ui::print_json_data(json!({ "session": session }));
             } else {
                 println!("{}", session);
             }
@@ -135,13 +145,49 @@ fn main() {
 
     let args: Vec<String> = env::args().skip(1).collect();
     let flags = parse_flags(&args);
-    let clean = clean_args(&args);
+    ui::init(flags.json, flags.quiet);
+    match flags.color.as_deref().map(color::ColorChoice::parse) {
+        None => color::init(color::ColorChoice::Auto),
+        Some(Some(choice)) => color::init(choice),
+        Some(None) => {
+            color::init(color::ColorChoice::Auto);
+            ui::fail(
+                ErrorCode::InvalidArgs,
+                format!(
+                    "Invalid --color value: '{}' (expected {})",
+                    flags.color.as_deref().unwrap_or_default(),
+                    color::CHOICES.join(", ")
+                ),
+            );
+        }
+    }
+    if let Err(e) = logging::init(&logging::LogOptions {
+        verbosity: flags.verbosity,
+        level: flags.log_level.as_deref(),
+        file: flags.log_file.as_deref(),
+        format: flags.log_format.as_deref(),
+    }) {
+        ui::fail(ErrorCode::InvalidArgs, e);
+    }
+    if let Some(ref n) = flags.download_concurrency {
+        match n.parse::<usize>() {
+            Ok(limit) if limit > 0 => install::set_download_concurrency(limit),
+            _ => ui::fail(
+                ErrorCode::InvalidArgs,
+                format!(
+                    "Invalid --download-concurrency value: '{}' (expected a positive number)",
+                    n
+                ),
+            ),
+        }
+    }
+    let mut clean = clean_args(&args);
 
     let has_help = args.iter().any(|a| a == "--help" || a == "-h");
     let has_version = args.iter().any(|a| a == "--version" || a == "-V");
 
     if has_help {
-        if let Some(cmd) = clean.get(0) {
+        if let Some(cmd) = clean.first() {
             if print_command_help(cmd) {
                 return;
             }
@@ -161,19 +207,180 @@ fn main() {
     }
 
     // Handle install separately
-    if clean.get(0).map(|s| s.as_str()) == Some("install") {
-        let with_deps = args.iter().any(|a| a == "--with-deps" || a == "-d");
-        run_install(with_deps);
+    if clean.first().map(|s| s.as_str()) == Some("install") {
+        run_install(&InstallOptions::from_args(&args));
+        return;
+    }
+    if clean.first().map(|s| s.as_str()) == Some("init") {
+        init::run_init(&args);
+        return;
+    }
+    if clean.first().map(|s| s.as_str()) == Some("verify") {
+        verify::run_verify(&split_args(&args).1);
+        return;
+    }
+    if clean.first().map(|s| s.as_str()) == Some("upgrade") {
+        install::run_upgrade(&InstallOptions::from_args(&args));
+        return;
+    }
+
+    // Handle doctor and status separately (don't need daemon)
+    if clean.first().map(|s| s.as_str()) == Some("doctor") {
+        doctor::run_doctor(&flags);
+        return;
+    }
+    if clean.first().map(|s| s.as_str()) == Some("status") {
+        disk::run_status(&flags.session);
+        return;
+    }
+
+    // Handle print-dockerfile separately (doesn't need daemon)
+    if clean.first().map(|s| s.as_str()) == Some("print-dockerfile") {
+        container::run_print_dockerfile();
         return;
     }
 
-    // Handle session separately (doesn't need daemon)
-    if clean.get(0).map(|s| s.as_str()) == Some("session") {
+    // Handle self-update separately (doesn't need daemon)
+    if clean.first().map(|s| s.as_str()) == Some("self-update") {
+        self_update::run_self_update(&clean);
+        return;
+    }
+
+    // Handle completions separately (doesn't need daemon)
+    if clean.first().map(|s| s.as_str()) == Some("completions") {
+        completions::run_completions(&clean);
+        return;
+    }
+    if clean.first().map(|s| s.as_str()) == Some("__complete") {
+        completions::run_complete(clean.get(1).map(|s| s.as_str()));
+        return;
+    }
+
+    // Handle config separately (doesn't need daemon)
+    if clean.first().map(|s| s.as_str()) == Some("config") {
+        config::run_config(&clean);
+        return;
+    }
+
+    // Handle session separately (doesn't need daemon), except saving and
+    // restoring, which the daemon does
+    if clean.first().map(|s| s.as_str()) == Some("session")
+        && !matches!(clean.get(1).map(|s| s.as_str()), Some("save" | "restore"))
+    {
         run_session(&clean, &flags.session, flags.json);
         return;
     }
 
-    let cmd = match parse_command(&clean, &flags) {
+    // Handle ext separately (doesn't need daemon)
+    if clean.first().map(|s| s.as_str()) == Some("ext") {
+        extensions::run_ext(&clean);
+        return;
+    }
+
+    // Handle filters separately (doesn't need daemon)
+    if clean.first().map(|s| s.as_str()) == Some("filters") {
+        filters::run_filters(&clean);
+        return;
+    }
+
+    // Handle artifacts separately (doesn't need daemon)
+    if clean.first().map(|s| s.as_str()) == Some("artifacts") {
+        artifacts::run_artifacts(&clean, &flags.session);
+        return;
+    }
+
+    // Handle audit-log separately (reads the log the daemon wrote)
+    if clean.first().map(|s| s.as_str()) == Some("audit-log") {
+        audit_log::run_audit_log(&clean, &flags.session, flags.out.as_deref());
+        return;
+    }
+
+    // Handle transcript separately (export reads the recorded file; replay sends
+    // each step to the daemon itself)
+    if clean.first().map(|s| s.as_str()) == Some("transcript") {
+        transcript::run_transcript(
+            &clean,
+            &flags.session,
+            flags.out.as_deref(),
+            &split_args(&args).1,
+        );
+        return;
+    }
+
+    // Handle diff separately (compares saved files, doesn't need daemon)
+    if clean.first().map(|s| s.as_str()) == Some("diff") {
+        diff::run_diff(&clean);
+        return;
+    }
+
+    // Handle run separately (each step is its own invocation)
+    if clean.first().map(|s| s.as_str()) == Some("run") {
+        run::run_command(&clean, &split_args(&args).1, &flags.session);
+        return;
+    }
+
+    // {{name}} in arguments is replaced with the session's variables
+    clean = vars::interpolate_args(&clean, &flags.session);
+
+    // Handle var separately (doesn't need daemon)
+    if clean.first().map(|s| s.as_str()) == Some("var") {
+        vars::run_var(&clean, &flags.session);
+        return;
+    }
+    if let Some(name) = flags.into.as_deref().filter(|n| !vars::valid_name(n)) {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            format!(
+                "Invalid --into variable name: '{}' (use letters, digits, _, - and .)",
+                name
+            ),
+        );
+    }
+    let json_path = flags.json_path.as_deref().map(|path| {
+        jsonpath::parse(path).unwrap_or_else(|e| {
+            ui::fail(
+                ErrorCode::InvalidArgs,
+                format!("Invalid --json-path '{}': {}", path, e),
+            )
+        })
+    });
+    let output_format = flags.output_format.as_deref().map(|name| {
+        export::Format::parse(name).unwrap_or_else(|| {
+            ui::fail(
+                ErrorCode::InvalidArgs,
+                format!(
+                    "Unknown --output-format: {} (expected ndjson, csv or parquet)",
+                    name
+                ),
+            )
+        })
+    });
+    if output_format == Some(export::Format::Parquet) && flags.out.is_none() {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            "--output-format parquet needs --out <file>",
+        );
+    }
+    let export_to = flags.out.as_deref().map(std::path::Path::new);
+    let sink = flags
+        .sink
+        .as_deref()
+        .map(|uri| sink::parse(uri).unwrap_or_else(|e| ui::fail(ErrorCode::InvalidArgs, e)));
+
+    // Handle plugins separately (doesn't need daemon)
+    if clean.first().map(|s| s.as_str()) == Some("plugins") {
+        plugins::run_plugins_command();
+        return;
+    }
+
+    // Unknown commands fall through to agent-browser-<name> on PATH
+    if let Some(name) = clean.first() {
+        if let Some(path) = plugins::find_plugin(name) {
+            plugins::run_plugin(&path, name, &args, &flags);
+        }
+    }
+
+    let mut cmd = match parse_command(&clean, &flags) {
         Ok(c) => c,
         Err(e) => {
             if flags.json {
@@ -183,45 +390,185 @@ fn main() {
                     ParseError::MissingArguments { .. } => "missing_arguments",
                     ParseError::InvalidValue { .. } => "invalid_value",
                 };
-                println!(
-                    r#"{{"success":false,"error":"{}","type":"{}"}}"#,
+                ui::fail_with_type(
+                    ErrorCode::InvalidArgs,
                     e.format().replace('\n', " "),
-                    error_type
+                    Some(error_type),
                 );
             } else {
                 eprintln!("{}", color::red(&e.format()));
             }
-            exit(1);
+            exit(ErrorCode::InvalidArgs.exit_code());
+        }
+    };
+
+    // Every event below carries the session and command id for log correlation
+    let _span = tracing::info_span!(
+        "command",
+        session = %flags.session,
+        id = cmd.get("id").and_then(|v| v.as_str()).unwrap_or(""),
+        action = cmd.get("action").and_then(|v| v.as_str()).unwrap_or(""),
+    )
+    .entered();
+
+    let action_name = cmd["action"].as_str().unwrap_or_default().to_string();
+    let mut trace = otel::Trace::start(
+        flags.otel_endpoint.as_deref(),
+        format!("agent-browser {}", action_name),
+    );
+    let trace_attributes = json!({
+        "agent_browser.session": flags.session,
+        "agent_browser.action": action_name,
+    });
+
+    let extensions = extensions::launch_extensions(&flags)
+        .unwrap_or_else(|e| ui::fail(ErrorCode::InvalidArgs, e));
+
+    let init_scripts: Vec<String> = flags
+        .init_scripts
+        .iter()
+        .map(|p| existing_file(p, "Init script"))
+        .collect();
+    let extra_ca: Vec<String> = flags
+        .extra_ca
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|p| existing_file(p, "CA certificate"))
+        .collect();
+    let ignore_cert_hosts: Vec<String> = flags
+        .ignore_cert_errors_for
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(|h| h.trim().to_lowercase())
+        .filter(|h| !h.is_empty())
+        .collect();
+
+    // Mutual TLS: a PEM certificate and key, or a .p12/.pfx bundle on its own
+    let client_cert = flags
+        .client_cert
+        .as_deref()
+        .map(|p| existing_file(p, "Client certificate"));
+    let client_key = flags
+        .client_key
+        .as_deref()
+        .map(|p| existing_file(p, "Client key"));
+    let client_cert_origins: Vec<String> = flags
+        .client_cert_origin
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(|o| o.trim().trim_end_matches('/'))
+        .filter(|o| !o.is_empty())
+        .map(|o| {
+            if o.contains("://") {
+                o.to_string()
+            } else {
+                format!("https://{}", o)
+            }
+        })
+        .collect();
+    let client_certificates: Vec<serde_json::Value> = match (&client_cert, &client_key) {
+        (None, None) => Vec::new(),
+        (None, Some(_)) => ui::fail(ErrorCode::InvalidArgs, "--client-key needs --client-cert"),
+        (Some(cert), key) => {
+            let lower = cert.to_lowercase();
+            let bundle = lower.ends_with(".p12") || lower.ends_with(".pfx");
+            if key.is_none() && !bundle {
+                ui::fail(
+                    ErrorCode::InvalidArgs,
+                    "--client-cert needs --client-key, unless it is a .p12/.pfx bundle",
+                );
+            }
+            if client_cert_origins.is_empty() {
+                ui::fail(
+                    ErrorCode::InvalidArgs,
+                    "--client-cert needs --client-cert-origin with the sites to present it to",
+                );
+            }
+            let passphrase = env::var("AGENT_BROWSER_CLIENT_CERT_PASSPHRASE").ok();
+            // Certificates are matched to origins exactly, so each origin gets an entry
+            client_cert_origins
+                .iter()
+                .map(|origin| {
+                    let mut entry = json!({ "origin": origin });
+                    match key {
+                        Some(key) => {
+                            entry["certPath"] = json!(cert);
+                            entry["keyPath"] = json!(key);
+                        }
+                        None => entry["pfxPath"] = json!(cert),
+                    }
+                    if let Some(ref passphrase) = passphrase {
+                        entry["passphrase"] = json!(passphrase);
+                    }
+                    entry
+                })
+                .collect()
         }
     };
 
+    let connect = trace.child("daemon.connect");
     let daemon_result = match ensure_daemon(
         &flags.session,
         flags.headed,
         flags.executable_path.as_deref(),
-        &flags.extensions,
+        flags.browser.as_deref(),
+        &extensions,
         flags.args.as_deref(),
         flags.user_agent.as_deref(),
         flags.proxy.as_deref(),
         flags.proxy_bypass.as_deref(),
         flags.ignore_https_errors,
+        (!extra_ca.is_empty())
+            .then(|| extra_ca.join(","))
+            .as_deref(),
+        (!ignore_cert_hosts.is_empty())
+            .then(|| ignore_cert_hosts.join(","))
+            .as_deref(),
+        client_cert.as_deref(),
+        client_key.as_deref(),
+        (!client_cert_origins.is_empty())
+            .then(|| client_cert_origins.join(","))
+            .as_deref(),
+        flags.auth_server_allowlist.as_deref(),
+        flags.auth_delegate_allowlist.as_deref(),
+        flags.stealth,
+        flags.deterministic,
+        &init_scripts,
         flags.allow_file_access,
         flags.profile.as_deref(),
         flags.state.as_deref(),
         flags.provider.as_deref(),
         flags.browseros_profile_name.as_deref(),
         flags.device.as_deref(),
+        flags.default_timeout,
     ) {
         Ok(result) => result,
         Err(e) => {
-            if flags.json {
-                println!(r#"{{"success":false,"error":"{}"}}"#, e);
-            } else {
-                eprintln!("{} {}", color::error_indicator(), e);
-            }
-            exit(1);
+            trace.end(connect, json!({}), Some(&e));
+            trace.finish(trace_attributes, Some(&e));
+            ui::fail(ErrorCode::Daemon, e);
         }
     };
+    trace.end(
+        connect,
+        json!({ "daemon.already_running": daemon_result.already_running }),
+        None,
+    );
+    tracing::debug!(
+        already_running = daemon_result.already_running,
+        "daemon ready"
+    );
+
+    let hooks = Hooks::load(&flags.session);
+    if !daemon_result.already_running {
+        hooks.fire(Hook::SessionStart, json!({}));
+        artifacts::apply_retention();
+    }
 
     // Warn if launch-time options were explicitly passed via CLI but daemon was already running
     // Only warn about flags that were passed on the command line, not those set via environment
@@ -233,6 +580,7 @@ fn main() {
             } else {
                 None
             },
+            flags.cli_browser.then_some("--browser"),
             if flags.cli_extensions {
                 Some("--extension")
             } else {
@@ -248,11 +596,7 @@ fn main() {
             } else {
                 None
             },
-            if flags.cli_args {
-                Some("--args")
-            } else {
-                None
-            },
+            if flags.cli_args { Some("--args") } else { None },
             if flags.cli_user_agent {
                 Some("--user-agent")
             } else {
@@ -273,41 +617,137 @@ fn main() {
             } else {
                 None
             },
-            flags.ignore_https_errors.then(|| "--ignore-https-errors"),
-            flags.cli_allow_file_access.then(|| "--allow-file-access"),
+            flags.ignore_https_errors.then_some("--ignore-https-errors"),
+            flags.cli_extra_ca.then_some("--extra-ca"),
+            flags
+                .cli_ignore_cert_errors_for
+                .then_some("--ignore-cert-errors-for"),
+            flags.cli_client_cert.then_some("--client-cert"),
+            flags
+                .cli_auth_allowlist
+                .then_some("--auth-server-allowlist/--auth-delegate-allowlist"),
+            flags.cli_stealth.then_some("--stealth"),
+            flags.cli_deterministic.then_some("--deterministic"),
+            flags.cli_init_scripts.then_some("--init-script"),
+            flags.cli_allow_file_access.then_some("--allow-file-access"),
         ]
         .into_iter()
         .flatten()
         .collect();
 
         if !ignored_flags.is_empty() && !flags.json {
-            eprintln!(
-                "{} {} ignored: daemon already running. Use 'agent-browser close' first to restart with new options.",
-                color::warning_indicator(),
-                ignored_flags.join(", ")
-            );
+            ui::warn(i18n::tf(
+                "launch.flags_ignored",
+                &[("flags", &ignored_flags.join(", "))],
+            ));
         }
     }
 
     // Validate mutually exclusive options
     if flags.cdp.is_some() && flags.provider.is_some() {
         let msg = "Cannot use --cdp and -p/--provider together";
-        if flags.json {
-            println!(r#"{{"success":false,"error":"{}"}}"#, msg);
-        } else {
-            eprintln!("\x1b[31m✗\x1b[0m {}", msg);
+        ui::fail(ErrorCode::InvalidArgs, msg);
+    }
+    if flags.remote.is_some() && (flags.cdp.is_some() || flags.provider.is_some()) {
+        let msg = "Cannot use --remote with --cdp or -p/--provider";
+        ui::fail(ErrorCode::InvalidArgs, msg);
+    }
+    if flags.browser.is_some()
+        && (flags.cdp.is_some() || flags.provider.is_some() || flags.remote.is_some())
+    {
+        let msg = "Cannot use --browser with --cdp, -p/--provider or --remote";
+        ui::fail(ErrorCode::InvalidArgs, msg);
+    }
+    if let Some(ref browser) = flags.browser {
+        let known = ["browseros", "chrome", "edge", "chromium"];
+        if !known.contains(&browser.as_str())
+            && browser.strip_prefix("path:").is_none_or(str::is_empty)
+        {
+            ui::fail(
+                ErrorCode::InvalidArgs,
+                format!(
+                    "Unknown --browser '{}' (expected browseros, chrome, edge, chromium or path:<exe>)",
+                    browser
+                ),
+            );
         }
-        exit(1);
     }
+    let remote_keepalive = flags.remote_keepalive.as_deref().map(|d| {
+        parse_duration_ms(d).unwrap_or_else(|| {
+            ui::fail(
+                ErrorCode::InvalidArgs,
+                format!(
+                    "Invalid --remote-keepalive value: '{}' (expected e.g. 30s, or 0 to disable)",
+                    d
+                ),
+            )
+        })
+    });
 
-    if flags.provider.is_some() && !flags.extensions.is_empty() {
-        let msg = "Cannot use --extension with -p/--provider (extensions require local browser)";
-        if flags.json {
-            println!(r#"{{"success":false,"error":"{}"}}"#, msg);
-        } else {
-            eprintln!("\x1b[31m✗\x1b[0m {}", msg);
+    if let Some(ref policy) = flags.on_popup {
+        if !matches!(policy.as_str(), "follow" | "block" | "list") {
+            let msg = format!(
+                "Invalid --on-popup value: '{}' (expected follow, block, or list)",
+                policy
+            );
+            ui::fail(ErrorCode::InvalidArgs, msg);
+        }
+    }
+
+    let retry_error = match (&flags.retries, &flags.retry_backoff) {
+        (Some(n), _) if n.parse::<u32>().is_err() => Some(format!(
+            "Invalid --retries value: '{}' (expected a non-negative integer)",
+            n
+        )),
+        (_, Some(d)) if parse_duration_ms(d).is_none() => Some(format!(
+            "Invalid --retry-backoff value: '{}' (expected e.g. 500ms, 2s)",
+            d
+        )),
+        _ => None,
+    };
+    if let Some(msg) = retry_error {
+        ui::fail(ErrorCode::InvalidArgs, msg);
+    }
+    if flags.robots_override {
+        cmd["robotsOverride"] = json!(true);
+    }
+    if flags.allow_purchases {
+        cmd["allowPurchases"] = json!(true);
+    }
+    if flags.humanize {
+        cmd["humanize"] = json!(true);
+        if let Some(seed) = &flags.humanize_seed {
+            match seed.parse::<u32>() {
+                Ok(seed) => cmd["humanizeSeed"] = json!(seed),
+                Err(_) => ui::fail(
+                    ErrorCode::InvalidArgs,
+                    format!(
+                        "Invalid --humanize-seed value: '{}' (expected a non-negative integer)",
+                        seed
+                    ),
+                ),
+            }
+        }
+    }
+    if let Some(n) = flags.retries.as_deref().and_then(|n| n.parse::<u32>().ok()) {
+        cmd["retries"] = json!(n);
+        if let Some(ms) = flags.retry_backoff.as_deref().and_then(parse_duration_ms) {
+            cmd["retryBackoff"] = json!(ms);
         }
-        exit(1);
+    }
+    match timeouts::command_timeouts(&flags) {
+        Ok(timeouts) => cmd["timeouts"] = timeouts,
+        Err(e) => ui::fail(ErrorCode::InvalidArgs, e),
+    }
+
+    let launches_locally =
+        matches!(flags.provider.as_deref(), None | Some("browseros-new")) && flags.remote.is_none();
+    if !launches_locally && !flags.extensions.is_empty() {
+        let msg = "Cannot use --extension with -p/--provider or --remote (extensions require local browser)";
+        ui::fail(ErrorCode::InvalidArgs, msg);
+    }
+    if launches_locally && flags.cdp.is_none() && !daemon_result.already_running {
+        container::warn_small_shm();
     }
 
     // Connect via CDP if --cdp flag is set
@@ -327,26 +767,16 @@ fn main() {
         } else {
             // It's a port number - validate and use cdpPort field
             let cdp_port: u16 = match cdp_value.parse::<u32>() {
-                Ok(p) if p == 0 => {
+                Ok(0) => {
                     let msg = "Invalid CDP port: port must be greater than 0".to_string();
-                    if flags.json {
-                        println!(r#"{{"success":false,"error":"{}"}}"#, msg);
-                    } else {
-                        eprintln!("{} {}", color::error_indicator(), msg);
-                    }
-                    exit(1);
+                    ui::fail(ErrorCode::InvalidArgs, msg);
                 }
                 Ok(p) if p > 65535 => {
                     let msg = format!(
                         "Invalid CDP port: {} is out of range (valid range: 1-65535)",
                         p
                     );
-                    if flags.json {
-                        println!(r#"{{"success":false,"error":"{}"}}"#, msg);
-                    } else {
-                        eprintln!("{} {}", color::error_indicator(), msg);
-                    }
-                    exit(1);
+                    ui::fail(ErrorCode::InvalidArgs, msg);
                 }
                 Ok(p) => p as u16,
                 Err(_) => {
@@ -354,12 +784,7 @@ fn main() {
                         "Invalid CDP value: '{}' is not a valid port number or URL",
                         cdp_value
                     );
-                    if flags.json {
-                        println!(r#"{{"success":false,"error":"{}"}}"#, msg);
-                    } else {
-                        eprintln!("{} {}", color::error_indicator(), msg);
-                    }
-                    exit(1);
+                    ui::fail(ErrorCode::InvalidArgs, msg);
                 }
             };
             json!({
@@ -372,50 +797,109 @@ fn main() {
         if flags.ignore_https_errors {
             launch_cmd["ignoreHTTPSErrors"] = json!(true);
         }
+        if !extra_ca.is_empty() {
+            launch_cmd["extraCa"] = json!(extra_ca);
+        }
+        if !ignore_cert_hosts.is_empty() {
+            launch_cmd["ignoreCertErrorsFor"] = json!(ignore_cert_hosts);
+        }
+        if !client_certificates.is_empty() {
+            launch_cmd["clientCertificates"] = json!(client_certificates);
+        }
+        if flags.stealth {
+            launch_cmd["stealth"] = json!(true);
+        }
+        if flags.deterministic {
+            launch_cmd["deterministic"] = json!(true);
+        }
+        if !init_scripts.is_empty() {
+            launch_cmd["initScripts"] = json!(init_scripts);
+        }
 
         let err = match send_command(launch_cmd, &flags.session) {
             Ok(resp) if resp.success => None,
-            Ok(resp) => Some(
-                resp.error
-                    .unwrap_or_else(|| "CDP connection failed".to_string()),
-            ),
-            Err(e) => Some(e.to_string()),
+            Ok(resp) => Some((
+                ErrorCode::from_code(resp.code.as_deref()),
+                resp.error.unwrap_or_else(|| i18n::t("launch.cdp_failed")),
+            )),
+            Err(e) => Some((ErrorCode::Daemon, e.to_string())),
         };
 
-        if let Some(msg) = err {
-            if flags.json {
-                println!(r#"{{"success":false,"error":"{}"}}"#, msg);
-            } else {
-                eprintln!("{} {}", color::error_indicator(), msg);
-            }
-            exit(1);
+        if let Some((code, msg)) = err {
+            ui::fail(code, msg);
         }
     }
 
     // Launch with cloud provider if -p flag is set
     if let Some(ref provider) = flags.provider {
-        let launch_cmd = json!({
+        let mut launch_cmd = json!({
             "id": gen_id(),
             "action": "launch",
             "provider": provider
         });
+        if provider == "browseros-new" && !extensions.is_empty() {
+            launch_cmd["extensions"] = json!(extensions);
+        }
 
         let err = match send_command(launch_cmd, &flags.session) {
             Ok(resp) if resp.success => None,
-            Ok(resp) => Some(
+            Ok(resp) => Some((
+                ErrorCode::from_code(resp.code.as_deref()),
                 resp.error
-                    .unwrap_or_else(|| "Provider connection failed".to_string()),
-            ),
-            Err(e) => Some(e.to_string()),
+                    .unwrap_or_else(|| i18n::t("launch.provider_failed")),
+            )),
+            Err(e) => Some((ErrorCode::Daemon, e.to_string())),
         };
 
-        if let Some(msg) = err {
-            if flags.json {
-                println!(r#"{{"success":false,"error":"{}"}}"#, msg);
-            } else {
-                eprintln!("\x1b[31m✗\x1b[0m {}", msg);
-            }
-            exit(1);
+        if let Some((code, msg)) = err {
+            ui::fail(code, msg);
+        }
+    }
+
+    // Connect to a remote browser pool if --remote is set
+    if let Some(ref remote) = flags.remote {
+        let mut launch_cmd = json!({
+            "id": gen_id(),
+            "action": "launch",
+            "remote": remote
+        });
+        if let Some(ref token) = flags.remote_token {
+            launch_cmd["remoteToken"] = json!(token);
+        }
+        if let Some(ms) = remote_keepalive {
+            launch_cmd["remoteKeepAlive"] = json!(ms);
+        }
+        if flags.ignore_https_errors {
+            launch_cmd["ignoreHTTPSErrors"] = json!(true);
+        }
+        if !extra_ca.is_empty() {
+            launch_cmd["extraCa"] = json!(extra_ca);
+        }
+        if !ignore_cert_hosts.is_empty() {
+            launch_cmd["ignoreCertErrorsFor"] = json!(ignore_cert_hosts);
+        }
+        if flags.stealth {
+            launch_cmd["stealth"] = json!(true);
+        }
+        if flags.deterministic {
+            launch_cmd["deterministic"] = json!(true);
+        }
+        if !init_scripts.is_empty() {
+            launch_cmd["initScripts"] = json!(init_scripts);
+        }
+
+        let err = match send_command(launch_cmd, &flags.session) {
+            Ok(resp) if resp.success => None,
+            Ok(resp) => Some((
+                ErrorCode::from_code(resp.code.as_deref()),
+                resp.error
+                    .unwrap_or_else(|| i18n::t("launch.remote_failed")),
+            )),
+            Err(e) => Some((ErrorCode::Daemon, e.to_string())),
+        };
+
+        if let Some((code, msg)) = err {
+            ui::fail(code, msg);
         }
     }
 
@@ -429,6 +913,7 @@ fn main() {
         || flags.allow_file_access)
         && flags.cdp.is_none()
         && flags.provider.is_none()
+        && flags.remote.is_none()
     {
         let mut launch_cmd = json!({
             "id": gen_id(),
@@ -465,6 +950,10 @@ fn main() {
             cmd_obj.insert("userAgent".to_string(), json!(ua));
         }
 
+        if let Some(ref browser) = flags.browser {
+            cmd_obj.insert("channel".to_string(), json!(browser));
+        }
+
         if let Some(ref a) = flags.args {
             // Parse args (comma or newline separated)
             let args_vec: Vec<String> = a
@@ -478,35 +967,62 @@ fn main() {
         if flags.ignore_https_errors {
             launch_cmd["ignoreHTTPSErrors"] = json!(true);
         }
+        if !extra_ca.is_empty() {
+            launch_cmd["extraCa"] = json!(extra_ca);
+        }
+        if !ignore_cert_hosts.is_empty() {
+            launch_cmd["ignoreCertErrorsFor"] = json!(ignore_cert_hosts);
+        }
+        if !client_certificates.is_empty() {
+            launch_cmd["clientCertificates"] = json!(client_certificates);
+        }
+        if flags.stealth {
+            launch_cmd["stealth"] = json!(true);
+        }
+        if flags.deterministic {
+            launch_cmd["deterministic"] = json!(true);
+        }
+        if !init_scripts.is_empty() {
+            launch_cmd["initScripts"] = json!(init_scripts);
+        }
 
         if flags.allow_file_access {
             launch_cmd["allowFileAccess"] = json!(true);
         }
 
+        // Chromium matches these itself, wildcards included, so they pass through as lists
+        let host_list = |hosts: &Option<String>| -> Vec<String> {
+            hosts
+                .as_deref()
+                .unwrap_or("")
+                .split(',')
+                .map(|h| h.trim().to_string())
+                .filter(|h| !h.is_empty())
+                .collect()
+        };
+        let auth_servers = host_list(&flags.auth_server_allowlist);
+        if !auth_servers.is_empty() {
+            launch_cmd["authServerAllowlist"] = json!(auth_servers);
+        }
+        let auth_delegates = host_list(&flags.auth_delegate_allowlist);
+        if !auth_delegates.is_empty() {
+            launch_cmd["authDelegateAllowlist"] = json!(auth_delegates);
+        }
+
         match send_command(launch_cmd, &flags.session) {
             Ok(resp) if !resp.success => {
                 // Launch command failed (e.g., invalid state file, profile error)
-                let error_msg = resp
-                    .error
-                    .unwrap_or_else(|| "Browser launch failed".to_string());
-                if flags.json {
-                    println!(r#"{{"success":false,"error":"{}"}}"#, error_msg);
-                } else {
-                    eprintln!("{} {}", color::error_indicator(), error_msg);
-                }
-                exit(1);
+                let error_msg = resp.error.unwrap_or_else(|| i18n::t("launch.failed"));
+                ui::fail(ErrorCode::from_code(resp.code.as_deref()), error_msg);
             }
             Err(e) => {
-                if flags.json {
-                    println!(r#"{{"success":false,"error":"{}"}}"#, e);
-                } else {
-                    eprintln!(
-                        "{} Could not configure browser: {}",
-                        color::error_indicator(),
-                        e
-                    );
+                if ui::json_mode() {
+                    ui::fail(ErrorCode::Daemon, e);
                 }
-                exit(1);
+                ui::fail(
+                    ErrorCode::Daemon,
+                    i18n::tf("launch.configure_failed", &[("error", &e)]),
+                );
             }
             Ok(_) => {
                 // Launch succeeded
@@ -514,23 +1030,304 @@ fn main() {
         }
     }
 
-    match send_command(cmd.clone(), &flags.session) {
-        Ok(resp) => {
+    // Apply session options after any launch so they don't trigger an auto-launch first
+    let mut session_options = serde_json::Map::new();
+    if let Some(ref policy) = flags.on_popup {
+        session_options.insert("popupPolicy".to_string(), json!(policy));
+    }
+    if flags.auto_dismiss_overlays {
+        session_options.insert("autoDismissOverlays".to_string(), json!(true));
+    }
+    if flags.adblock {
+        session_options.insert("adblock".to_string(), json!(true));
+    }
+    if flags.respect_robots {
+        session_options.insert("respectRobots".to_string(), json!(true));
+    }
+    if flags.transcript {
+        session_options.insert("transcript".to_string(), json!(true));
+    }
+    if flags.audit_log {
+        session_options.insert("auditLog".to_string(), json!(true));
+    }
+    if flags.read_only {
+        session_options.insert("readOnly".to_string(), json!(true));
+    }
+    if flags.guardrails || flags.guardrail_rules.is_some() {
+        let rules = match flags.guardrail_rules {
+            Some(ref path) => guardrails::load_rules(path).unwrap_or_else(|e| {
+                ui::fail(ErrorCode::InvalidArgs, format!("--guardrail-rules: {}", e))
+            }),
+            None => Vec::new(),
+        };
+        session_options.insert("guardrails".to_string(), json!(rules));
+    }
+    let mut rate_limits = serde_json::Map::new();
+    for (key, flag, value) in [
+        ("requests", "--rate", &flags.rate),
+        ("navigations", "--nav-rate", &flags.nav_rate),
+    ] {
+        let Some(value) = value else { continue };
+        let rules = parse_rate(value)
+            .unwrap_or_else(|e| ui::fail(ErrorCode::InvalidArgs, format!("{}: {}", flag, e)));
+        let rules: Vec<_> = rules
+            .into_iter()
+            .map(|r| {
+                let mut rule = json!({ "count": r.count, "per": r.per_ms });
+                if let Some(host) = r.host {
+                    rule["host"] = json!(host);
+                }
+                rule
+            })
+            .collect();
+        rate_limits.insert(key.to_string(), json!(rules));
+    }
+    if !rate_limits.is_empty() {
+        session_options.insert("rateLimits".to_string(), json!(rate_limits));
+    }
+    let mut lanes = serde_json::Map::new();
+    if let Some(ref value) = flags.max_concurrent {
+        let max = parse_max_concurrent(value).unwrap_or_else(|e| {
+            ui::fail(ErrorCode::InvalidArgs, format!("--max-concurrent: {}", e))
+        });
+        lanes.insert("maxConcurrent".to_string(), json!(max));
+    }
+    if let Some(ref value) = flags.action_rate {
+        let rule = parse_action_rate(value)
+            .unwrap_or_else(|e| ui::fail(ErrorCode::InvalidArgs, format!("--action-rate: {}", e)));
+        let rate = rule.map(|r| json!({ "count": r.count, "per": r.per_ms }));
+        lanes.insert("actionRate".to_string(), json!(rate));
+    }
+    if !lanes.is_empty() {
+        session_options.insert("lanes".to_string(), json!(lanes));
+    }
+    if let Some(ref value) = flags.max_tab_memory {
+        let limit = parse_tab_memory(value).unwrap_or_else(|e| {
+            ui::fail(ErrorCode::InvalidArgs, format!("--max-tab-memory: {}", e))
+        });
+        let mut tab_memory = json!({ "limit": limit });
+        if let Some(ref mode) = flags.recycle_mode {
+            let mode = parse_recycle_mode(mode).unwrap_or_else(|e| {
+                ui::fail(ErrorCode::InvalidArgs, format!("--recycle-mode: {}", e))
+            });
+            tab_memory["mode"] = json!(mode);
+        }
+        session_options.insert("tabMemory".to_string(), tab_memory);
+    }
+    if !session_options.is_empty() && cmd.get("action").and_then(|v| v.as_str()) != Some("close") {
+        session_options.insert("id".to_string(), json!(gen_id()));
+        session_options.insert("action".to_string(), json!("session_options"));
+        match send_command(serde_json::Value::Object(session_options), &flags.session) {
+            Ok(resp) if resp.success => {}
+            Ok(resp) => {
+                let msg = resp
+                    .error
+                    .unwrap_or_else(|| i18n::t("launch.session_options_failed"));
+                ui::fail(ErrorCode::from_code(resp.code.as_deref()), msg);
+            }
+            Err(e) => {
+                ui::fail(ErrorCode::Daemon, e);
+            }
+        }
+    }
+
+    // compare-screenshot: the daemon only takes the screenshot
+    let compare = cmd.as_object_mut().and_then(|o| o.remove("compare"));
+    // record stop: the CLI uploads the saved video
+    let upload_to = match cmd.as_object_mut().and_then(|o| o.remove("upload")) {
+        Some(destination) => destination.as_str().map(String::from),
+        None if cmd["action"] == "recording_stop" => upload::configured_destination(),
+        None => None,
+    };
+
+    // cdp-listen, watch and poll stream events as JSON lines until the daemon sends the response
+    if matches!(
+        cmd["action"].as_str(),
+        Some("cdp_listen" | "watch" | "poll")
+    ) {
+        let mut stdout = io::stdout().lock();
+        // With --output-format, events are collected and written as rows at the end
+        let mut events = Vec::new();
+        let action = cmd["action"].as_str().unwrap_or_default();
+        let result = stream_command(&cmd, &flags.session, |event| {
+            // Readings are appended as they arrive, so a long poll keeps what it saw
+            if let Some(sink) = &sink {
+                let row = (sink::now_ms(), export::row(event.clone()));
+                if let Err(e) = sink.append(&flags.session, action, &[row]) {
+                    ui::fail(ErrorCode::Unknown, e);
+                }
+            }
+            if output_format.is_some() {
+                events.push(export::row(event.clone()));
+                return;
+            }
+            // Stop quietly when the reader goes away (e.g. `| head`)
+            if writeln!(stdout, "{}", event)
+                .and_then(|_| stdout.flush())
+                .is_err()
+            {
+                exit(0);
+            }
+        });
+        drop(stdout);
+        if let (Some(format), Ok(_)) = (output_format, &result) {
+            export::export(format, export_to, &events);
+        }
+        match result {
+            Ok(resp) if resp.success => {}
+            Ok(resp) => {
+                print_response(&resp, flags.json, cmd["action"].as_str());
+                exit(ErrorCode::from_code(resp.code.as_deref()).exit_code());
+            }
+            Err(e) => ui::fail(ErrorCode::Daemon, e),
+        }
+        return;
+    }
+
+    cancel::install(vec![flags.session.clone()]);
+    tracing::trace!(request = %cmd, "sending command");
+    let started = Instant::now();
+    let request = trace.request(&action_name);
+    let mut request_cmd = cmd.clone();
+    if let Some(endpoint) = trace.endpoint() {
+        request_cmd["traceparent"] = json!(request.traceparent(&trace));
+        request_cmd["otelEndpoint"] = json!(endpoint);
+    }
+    // A replay takes as long as the recording, which can outlast send_command's read timeout
+    let result = if cmd["action"] == "input" && cmd["op"] == "replay" {
+        stream_command(&request_cmd, &flags.session, |_| {})
+    } else {
+        send_command(request_cmd, &flags.session)
+    };
+    let error = match &result {
+        Ok(resp) => resp.error.clone(),
+        Err(e) => Some(e.clone()),
+    };
+    trace.end(request, json!({}), error.as_deref());
+    trace.finish(trace_attributes, error.as_deref());
+    match result {
+        Ok(mut resp) => {
+            tracing::info!(
+                success = resp.success,
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "command finished"
+            );
+            tracing::trace!(
+                response = %serde_json::to_string(&resp).unwrap_or_default(),
+                "received response"
+            );
             let success = resp.success;
             // Extract action for context-specific output handling
             let action = cmd.get("action").and_then(|v| v.as_str());
-            print_response(&resp, flags.json, action);
+            if let (true, Some(compare), Some(path)) = (success, &compare, cmd["path"].as_str()) {
+                visual::finish_compare(std::path::Path::new(path), compare);
+            }
+            let mut uploaded = None;
+            if let (true, Some(destination)) = (success, &upload_to) {
+                let files: Vec<std::path::PathBuf> = resp
+                    .data
+                    .as_ref()
+                    .and_then(|d| d.get("artifacts"))
+                    .and_then(|v| v.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|v| v.as_str().map(std::path::PathBuf::from))
+                    .collect();
+                let urls = upload::upload(destination, &upload::run_folder(&flags.session), &files)
+                    .unwrap_or_else(|e| ui::fail(ErrorCode::UploadFailed, e));
+                if let Some(data) = resp.data.as_mut() {
+                    data["uploads"] = json!(urls);
+                }
+                uploaded = Some(urls);
+            }
+            // --json-path replaces the data with what it selects
+            let selected = json_path.as_ref().filter(|_| success).map(|path| {
+                let data = resp.data.clone().unwrap_or_default();
+                jsonpath::select(path, &data).unwrap_or_else(|e| {
+                    ui::fail(ErrorCode::InvalidArgs, format!("--json-path: {}", e))
+                })
+            });
+            match (success, flags.into.as_deref(), &selected) {
+                (true, Some(name), Some(value)) => {
+                    if let Err(e) = vars::set(&flags.session, name, value.clone()) {
+                        ui::fail(ErrorCode::Unknown, e);
+                    }
+                }
+                (true, Some(name), None) => vars::store_result(
+                    &flags.session,
+                    name,
+                    action.unwrap_or_default(),
+                    resp.data.as_ref(),
+                ),
+                _ => {}
+            }
+            let sink_rows = sink.as_ref().filter(|_| success).map(|_| {
+                let data = selected.as_ref().or(resp.data.as_ref());
+                export::rows(data.unwrap_or(&serde_json::Value::Null))
+            });
+            match (selected, output_format.filter(|_| success)) {
+                (selected, Some(format)) => {
+                    let data = selected.or_else(|| resp.data.clone()).unwrap_or_default();
+                    export::export(format, export_to, &export::rows(&data));
+                }
+                (Some(value), None) if !flags.json => print_selected(&value),
+                (Some(value), None) => ui::print_json_data(value),
+                (None, None) => print_response(&resp, flags.json, action),
+            }
+            if let (Some(sink), Some(rows)) = (&sink, sink_rows) {
+                let collected_at = sink::now_ms();
+                let rows: Vec<_> = rows.into_iter().map(|row| (collected_at, row)).collect();
+                match sink.append(&flags.session, action.unwrap_or_default(), &rows) {
+                    Ok(n) => ui::status(color::dim(&format!(
+                        "Appended {} row(s) to {}",
+                        n,
+                        sink.display()
+                    ))),
+                    Err(e) => ui::fail(ErrorCode::Unknown, e),
+                }
+            }
+            if let Some(urls) = uploaded.filter(|u| !u.is_empty()) {
+                ui::status(format!(
+                    "{} Uploaded to {}",
+                    color::success_indicator(),
+                    urls.join(", ")
+                ));
+            }
+            // An armed wait succeeds without a file yet, so require a path
+            let saved = resp.data.as_ref().filter(|d| d.get("path").is_some());
+            if let (Some("download" | "waitfordownload"), Some(data)) = (action, saved) {
+                hooks.fire(Hook::Download, data.clone());
+            }
+            if success && action == Some("close") {
+                hooks.fire(Hook::SessionEnd, json!({}));
+            }
             if !success {
-                exit(1);
+                let code = ErrorCode::from_code(resp.code.as_deref());
+                hooks.fire(
+                    if code == ErrorCode::BrowserCrashed {
+                        Hook::Crash
+                    } else {
+                        Hook::StepFailure
+                    },
+                    json!({
+                        "command": cmd,
+                        "error": resp.error,
+                        "code": code.as_str(),
+                    }),
+                );
+                exit(code.exit_code());
             }
         }
         Err(e) => {
-            if flags.json {
-                println!(r#"{{"success":false,"error":"{}"}}"#, e);
-            } else {
-                eprintln!("{} {}", color::error_indicator(), e);
-            }
-            exit(1);
+            hooks.fire(
+                Hook::Crash,
+                json!({
+                    "command": cmd,
+                    "error": e,
+                    "code": ErrorCode::Daemon.as_str(),
+                }),
+            );
+            ui::fail(ErrorCode::Daemon, e);
         }
     }
 }
@@ -0,0 +1,269 @@
+//! The MessagePack subset the CLI and daemon exchange (see `src/msgpack.ts`):
+//! the JSON data model, so any `serde_json::Value` round-trips unchanged.
+
+use serde_json::{Map, Number, Value};
+
+pub fn encode(value: &Value) -> Vec<u8> {
+    let mut out = Vec::with_capacity(128);
+    encode_into(&mut out, value);
+    out
+}
+
+fn encode_into(out: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Bool(b) => out.push(if *b { 0xc3 } else { 0xc2 }),
+        Value::Number(n) => encode_number(out, n),
+        Value::String(s) => encode_str(out, s),
+        Value::Array(items) => {
+            header(out, items.len(), 0x90, 0xdc, 0xdd);
+            for item in items {
+                encode_into(out, item);
+            }
+        }
+        Value::Object(map) => {
+            header(out, map.len(), 0x80, 0xde, 0xdf);
+            for (key, item) in map {
+                encode_str(out, key);
+                encode_into(out, item);
+            }
+        }
+    }
+}
+
+fn encode_number(out: &mut Vec<u8>, n: &Number) {
+    if let Some(u) = n.as_u64() {
+        if u < 0x80 {
+            out.push(u as u8);
+        } else if u <= u8::MAX as u64 {
+            out.extend_from_slice(&[0xcc, u as u8]);
+        } else if u <= u16::MAX as u64 {
+            out.push(0xcd);
+            out.extend_from_slice(&(u as u16).to_be_bytes());
+        } else if u <= u32::MAX as u64 {
+            out.push(0xce);
+            out.extend_from_slice(&(u as u32).to_be_bytes());
+        } else {
+            out.push(0xcf);
+            out.extend_from_slice(&u.to_be_bytes());
+        }
+    } else if let Some(i) = n.as_i64() {
+        // Only negative numbers get here
+        if i >= -32 {
+            out.push(i as u8);
+        } else if i >= i8::MIN as i64 {
+            out.extend_from_slice(&[0xd0, i as u8]);
+        } else if i >= i16::MIN as i64 {
+            out.push(0xd1);
+            out.extend_from_slice(&(i as i16).to_be_bytes());
+        } else if i >= i32::MIN as i64 {
+            out.push(0xd2);
+            out.extend_from_slice(&(i as i32).to_be_bytes());
+        } else {
+            out.push(0xd3);
+            out.extend_from_slice(&i.to_be_bytes());
+        }
+    } else {
+        out.push(0xcb);
+        out.extend_from_slice(&n.as_f64().unwrap_or(0.0).to_be_bytes());
+    }
+}
+
+fn encode_str(out: &mut Vec<u8>, s: &str) {
+    let len = s.len();
+    if len < 32 {
+        out.push(0xa0 | len as u8);
+    } else if len <= u8::MAX as usize {
+        out.extend_from_slice(&[0xd9, len as u8]);
+    } else if len <= u16::MAX as usize {
+        out.push(0xda);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        out.push(0xdb);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn header(out: &mut Vec<u8>, len: usize, fix: u8, m16: u8, m32: u8) {
+    if len < 16 {
+        out.push(fix | len as u8);
+    } else if len <= u16::MAX as usize {
+        out.push(m16);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        out.push(m32);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Value, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let value = reader.value()?;
+    if reader.pos != bytes.len() {
+        return Err("Trailing bytes after MessagePack value".to_string());
+    }
+    Ok(value)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or("Truncated MessagePack data")?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.take(N)?);
+        Ok(out)
+    }
+
+    fn len(&mut self, size: usize) -> Result<usize, String> {
+        Ok(match size {
+            1 => self.array::<1>()?[0] as usize,
+            2 => u16::from_be_bytes(self.array()?) as usize,
+            _ => u32::from_be_bytes(self.array()?) as usize,
+        })
+    }
+
+    fn str(&mut self, len: usize) -> Result<Value, String> {
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec())
+            .map(Value::String)
+            .map_err(|_| "Invalid UTF-8 in MessagePack string".to_string())
+    }
+
+    fn list(&mut self, len: usize) -> Result<Value, String> {
+        // Each item is at least a byte, so a bogus length fails before allocating
+        let mut items = Vec::with_capacity(len.min(self.bytes.len() - self.pos));
+        for _ in 0..len {
+            items.push(self.value()?);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn map(&mut self, len: usize) -> Result<Value, String> {
+        let mut map = Map::new();
+        for _ in 0..len {
+            let key = match self.value()? {
+                Value::String(key) => key,
+                _ => return Err("MessagePack map keys must be strings".to_string()),
+            };
+            map.insert(key, self.value()?);
+        }
+        Ok(Value::Object(map))
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        let b = self.array::<1>()?[0];
+        Ok(match b {
+            0x00..=0x7f => Value::from(b),
+            0x80..=0x8f => return self.map((b & 0x0f) as usize),
+            0x90..=0x9f => return self.list((b & 0x0f) as usize),
+            0xa0..=0xbf => return self.str((b & 0x1f) as usize),
+            0xe0..=0xff => Value::from(b as i8),
+            0xc0 => Value::Null,
+            0xc2 => Value::Bool(false),
+            0xc3 => Value::Bool(true),
+            0xca => float(f32::from_be_bytes(self.array()?) as f64),
+            0xcb => float(f64::from_be_bytes(self.array()?)),
+            0xcc => Value::from(self.array::<1>()?[0]),
+            0xcd => Value::from(u16::from_be_bytes(self.array()?)),
+            0xce => Value::from(u32::from_be_bytes(self.array()?)),
+            0xcf => Value::from(u64::from_be_bytes(self.array()?)),
+            0xd0 => Value::from(self.array::<1>()?[0] as i8),
+            0xd1 => Value::from(i16::from_be_bytes(self.array()?)),
+            0xd2 => Value::from(i32::from_be_bytes(self.array()?)),
+            0xd3 => Value::from(i64::from_be_bytes(self.array()?)),
+            0xd9 => {
+                let len = self.len(1)?;
+                return self.str(len);
+            }
+            0xda => {
+                let len = self.len(2)?;
+                return self.str(len);
+            }
+            0xdb => {
+                let len = self.len(4)?;
+                return self.str(len);
+            }
+            0xdc => {
+                let len = self.len(2)?;
+                return self.list(len);
+            }
+            0xdd => {
+                let len = self.len(4)?;
+                return self.list(len);
+            }
+            0xde => {
+                let len = self.len(2)?;
+                return self.map(len);
+            }
+            0xdf => {
+                let len = self.len(4)?;
+                return self.map(len);
+            }
+            _ => return Err(format!("Unsupported MessagePack type 0x{:x}", b)),
+        })
+    }
+}
+
+/// Like JSON, non-finite numbers become null
+fn float(f: f64) -> Value {
+    Number::from_f64(f)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_round_trip() {
+        let value = json!({
+            "id": "r1",
+            "success": true,
+            "data": {
+                "snapshot": "- heading \"Example\" [ref=e1]\n".repeat(20),
+                "counts": [0, 127, 128, 255, 256, 65536, 4294967296u64, -1, -32, -33, -129, -40000, -2147483649i64],
+                "ratio": 0.5,
+                "empty": {},
+                "list": (0..20).collect::<Vec<_>>(),
+                "text": "héllo ✓",
+            },
+            "error": null,
+        });
+        assert_eq!(decode(&encode(&value)).unwrap(), value);
+    }
+
+    #[test]
+    fn test_decodes_what_node_sends() {
+        // {"a": [1, -1, 1.5]} as encoded by src/msgpack.ts
+        let bytes = [
+            0x81, 0xa1, b'a', 0x93, 0x01, 0xff, 0xcb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0,
+        ];
+        assert_eq!(decode(&bytes).unwrap(), json!({"a": [1, -1, 1.5]}));
+    }
+
+    #[test]
+    fn test_rejects_bad_data() {
+        let bytes = encode(&json!({"url": "https://example.com"}));
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(decode(&[0xc1]).is_err());
+        // An array claiming 2^32-1 items
+        assert!(decode(&[0xdd, 0xff, 0xff, 0xff, 0xff]).is_err());
+    }
+}
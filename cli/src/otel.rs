@@ -0,0 +1,289 @@
+//! OpenTelemetry tracing over OTLP/HTTP.
+//!
+//! With `--otel-endpoint` (or `AGENT_BROWSER_OTEL_ENDPOINT`, or the standard
+//! `OTEL_EXPORTER_OTLP_ENDPOINT`), each command becomes a trace: a root span
+//! for the CLI invocation, with children for reaching the daemon and for the
+//! request itself. The request carries a W3C `traceparent` so the daemon's
+//! spans (command handling, browser launch, CDP calls) join the same trace.
+//!
+//! Spans are sent as OTLP JSON to `<endpoint>/v1/traces` with curl, once, when
+//! the command finishes. A `TRACEPARENT` environment variable makes the
+//! command a child of an outer trace, such as a CI job.
+
+use std::env;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::upload::hex;
+
+/// Span kinds, as numbered by OTLP.
+const KIND_INTERNAL: u8 = 1;
+const KIND_CLIENT: u8 = 3;
+
+/// A started span. Pass it back to [`Trace::end`] to record it.
+pub struct Span {
+    id: String,
+    parent: Option<String>,
+    name: String,
+    kind: u8,
+    start_ns: u128,
+}
+
+impl Span {
+    /// W3C trace context for requests made inside this span.
+    pub fn traceparent(&self, trace: &Trace) -> String {
+        format!("00-{}-{}-01", trace.trace_id, self.id)
+    }
+}
+
+/// A command's trace. Without an endpoint every method is a no-op, so call
+/// sites don't need to check whether tracing is on.
+pub struct Trace {
+    endpoint: Option<String>,
+    trace_id: String,
+    root: Span,
+    spans: Vec<Value>,
+}
+
+impl Trace {
+    /// Start a trace whose root span covers the whole CLI invocation.
+    pub fn start(endpoint: Option<&str>, name: String) -> Self {
+        let outer = env::var("TRACEPARENT")
+            .ok()
+            .and_then(|t| parse_traceparent(&t));
+        let (trace_id, parent) = match outer {
+            Some((trace_id, span_id)) => (trace_id, Some(span_id)),
+            None => (random_id(16), None),
+        };
+        Trace {
+            endpoint: endpoint.map(|e| e.trim_end_matches('/').to_string()),
+            trace_id,
+            root: Span {
+                id: random_id(8),
+                parent,
+                name,
+                kind: KIND_CLIENT,
+                start_ns: now_ns(),
+            },
+            spans: Vec::new(),
+        }
+    }
+
+    pub fn endpoint(&self) -> Option<&str> {
+        self.endpoint.as_deref()
+    }
+
+    /// Start a child of the root span.
+    pub fn child(&self, name: &str) -> Span {
+        Span {
+            id: random_id(8),
+            parent: Some(self.root.id.clone()),
+            name: name.to_string(),
+            kind: KIND_INTERNAL,
+            start_ns: now_ns(),
+        }
+    }
+
+    /// Start the span for a daemon request, whose `traceparent` the daemon
+    /// continues from.
+    pub fn request(&self, action: &str) -> Span {
+        Span {
+            kind: KIND_CLIENT,
+            ..self.child(&format!("request {}", action))
+        }
+    }
+
+    pub fn end(&mut self, span: Span, attributes: Value, error: Option<&str>) {
+        if self.endpoint.is_none() {
+            return;
+        }
+        let record = record(&self.trace_id, span, now_ns(), attributes, error);
+        self.spans.push(record);
+    }
+
+    /// End the root span and send every span to the collector.
+    pub fn finish(self, attributes: Value, error: Option<&str>) {
+        let Trace {
+            endpoint,
+            trace_id,
+            root,
+            mut spans,
+        } = self;
+        let Some(endpoint) = endpoint else {
+            return;
+        };
+        spans.push(record(&trace_id, root, now_ns(), attributes, error));
+        if let Err(e) = export(&endpoint, spans) {
+            tracing::warn!(endpoint = %endpoint, error = %e, "failed to export trace");
+        }
+    }
+}
+
+/// `(trace id, span id)` from a W3C `traceparent` header.
+pub fn parse_traceparent(value: &str) -> Option<(String, String)> {
+    let parts: Vec<&str> = value.trim().split('-').collect();
+    let [_, trace_id, span_id, _] = parts[..] else {
+        return None;
+    };
+    let valid = |s: &str, len: usize| {
+        s.len() == len && s.bytes().all(|b| b.is_ascii_hexdigit()) && s.bytes().any(|b| b != b'0')
+    };
+    (valid(trace_id, 32) && valid(span_id, 16))
+        .then(|| (trace_id.to_lowercase(), span_id.to_lowercase()))
+}
+
+fn record(
+    trace_id: &str,
+    span: Span,
+    end_ns: u128,
+    attributes: Value,
+    error: Option<&str>,
+) -> Value {
+    let attributes: Vec<Value> = attributes
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(key, value)| json!({ "key": key, "value": any_value(value) }))
+        .collect();
+    let status = match error {
+        Some(message) => json!({ "code": 2, "message": message }),
+        None => json!({ "code": 1 }),
+    };
+    let mut span_json = json!({
+        "traceId": trace_id,
+        "spanId": span.id,
+        "name": span.name,
+        "kind": span.kind,
+        "startTimeUnixNano": span.start_ns.to_string(),
+        "endTimeUnixNano": end_ns.to_string(),
+        "attributes": attributes,
+        "status": status,
+    });
+    if let Some(parent) = span.parent {
+        span_json["parentSpanId"] = json!(parent);
+    }
+    span_json
+}
+
+fn any_value(value: &Value) -> Value {
+    match value {
+        Value::Bool(b) => json!({ "boolValue": b }),
+        Value::Number(n) if n.is_i64() || n.is_u64() => json!({ "intValue": n.to_string() }),
+        Value::Number(n) => json!({ "doubleValue": n }),
+        Value::String(s) => json!({ "stringValue": s }),
+        other => json!({ "stringValue": other.to_string() }),
+    }
+}
+
+fn body(spans: Vec<Value>) -> Value {
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    { "key": "service.name", "value": { "stringValue": "agent-browser" } },
+                    { "key": "service.version", "value": { "stringValue": env!("CARGO_PKG_VERSION") } },
+                ],
+            },
+            "scopeSpans": [{
+                "scope": { "name": "agent-browser" },
+                "spans": spans,
+            }],
+        }],
+    })
+}
+
+fn export(endpoint: &str, spans: Vec<Value>) -> Result<(), String> {
+    let url = if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{}/v1/traces", endpoint)
+    };
+    let output = Command::new("curl")
+        .args(["-sS", "-f", "--max-time", "2", "-X", "POST"])
+        .args(["-H", "Content-Type: application/json"])
+        .args(["--data-binary", &body(spans).to_string()])
+        .arg(&url)
+        .stdout(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+fn now_ns() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+}
+
+/// Hex id of `bytes` length. Uniqueness is all that matters here, so the
+/// process id, clock and a counter are hashed rather than pulling in an RNG.
+fn random_id(bytes: usize) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut hasher = Sha256::new();
+    hasher.update(std::process::id().to_le_bytes());
+    hasher.update(now_ns().to_le_bytes());
+    hasher.update(COUNTER.fetch_add(1, Ordering::Relaxed).to_le_bytes());
+    hex(&hasher.finalize()[..bytes])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traceparent_round_trip() {
+        let trace = Trace::start(
+            Some("http://localhost:4318/"),
+            "agent-browser click".to_string(),
+        );
+        assert_eq!(trace.endpoint(), Some("http://localhost:4318"));
+        let span = trace.request("click");
+        let (trace_id, span_id) = parse_traceparent(&span.traceparent(&trace)).unwrap();
+        assert_eq!(trace_id, trace.trace_id);
+        assert_eq!(span_id, span.id);
+        assert_ne!(span.id, trace.root.id);
+
+        assert!(
+            parse_traceparent("00-00000000000000000000000000000000-b7ad6b7169203331-01").is_none()
+        );
+        assert!(parse_traceparent("00-0af7651916cd43dd8448eb211c80319c-b7ad6b71-01").is_none());
+    }
+
+    #[test]
+    fn test_span_record_is_otlp_json() {
+        let span = Span {
+            id: "b7ad6b7169203331".to_string(),
+            parent: Some("00f067aa0ba902b7".to_string()),
+            name: "daemon.connect".to_string(),
+            kind: KIND_INTERNAL,
+            start_ns: 1_000,
+        };
+        let record = record(
+            "0af7651916cd43dd8448eb211c80319c",
+            span,
+            2_000,
+            json!({ "daemon.already_running": true, "attempts": 2 }),
+            Some("boom"),
+        );
+        assert_eq!(record["parentSpanId"], "00f067aa0ba902b7");
+        assert_eq!(record["startTimeUnixNano"], "1000");
+        assert_eq!(record["status"]["code"], 2);
+        assert_eq!(
+            record["attributes"][0],
+            json!({ "key": "attempts", "value": { "intValue": "2" } })
+        );
+        assert_eq!(
+            record["attributes"][1]["value"],
+            json!({ "boolValue": true })
+        );
+    }
+}
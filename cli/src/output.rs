@@ -1,9 +1,54 @@
 use crate::color;
 use crate::connection::Response;
+use crate::timeouts;
+use crate::ui;
+
+/// Confirmation for a file written by the daemon. With --quiet only the path
+/// is printed so scripts can capture it.
+/// "3 tabs, 12 cookies, localStorage for 2 origins" for session_save/restore
+fn session_counts(data: &serde_json::Value) -> String {
+    let count = |key: &str| data.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    format!(
+        "{} tabs, {} cookies, localStorage for {} origins",
+        count("tabs"),
+        count("cookies"),
+        count("origins")
+    )
+}
+
+fn print_saved(what: &str, path: &str) {
+    if ui::quiet() {
+        println!("{}", path);
+    } else {
+        println!(
+            "{} {} {}",
+            color::success_indicator(),
+            what,
+            color::green(path)
+        );
+    }
+}
+
+/// What `--json-path` selected: one line per element of a list, strings as
+/// they are and anything else as compact JSON, ready for `while read`.
+pub fn print_selected(value: &serde_json::Value) {
+    let line = |v: &serde_json::Value| match v {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    match value {
+        serde_json::Value::Array(items) => items.iter().for_each(|v| println!("{}", line(v))),
+        other => println!("{}", line(other)),
+    }
+}
 
 pub fn print_response(resp: &Response, json_mode: bool, action: Option<&str>) {
     if json_mode {
-        println!("{}", serde_json::to_string(resp).unwrap_or_default());
+        let mut out = ui::envelope(resp.success, resp.data.clone(), resp.error.as_deref());
+        if !resp.success {
+            out["code"] = serde_json::json!(resp.code.as_deref().unwrap_or("E_UNKNOWN"));
+        }
+        println!("{}", out);
         return;
     }
 
@@ -17,11 +62,293 @@ pub fn print_response(resp: &Response, json_mode: bool, action: Option<&str>) {
     }
 
     if let Some(data) = &resp.data {
+        // Inspect output mixes keys that the heuristics below would pick up
+        if action == Some("inspect") {
+            print_inspect(data);
+            return;
+        }
+        if action == Some("stats") {
+            print_stats(data);
+            return;
+        }
+        if action == Some("audit_perf") {
+            print_audit_perf(data);
+            return;
+        }
+        if action == Some("audit_seo") {
+            print_audit_seo(data);
+            return;
+        }
+        if action == Some("audit_security") {
+            print_audit_security(data);
+            return;
+        }
+        if action == Some("tls_info") {
+            print_tls_info(data);
+            return;
+        }
+        if matches!(
+            action,
+            Some("clock_set") | Some("clock_advance") | Some("clock_resume")
+        ) {
+            let time = data.get("time").and_then(|v| v.as_str()).unwrap_or("");
+            let state = if data.get("paused").and_then(|v| v.as_bool()) == Some(true) {
+                "paused"
+            } else {
+                "ticking"
+            };
+            println!(
+                "{} Clock at {} ({})",
+                color::success_indicator(),
+                time,
+                state
+            );
+            if let (Some(local), Some(tz)) = (
+                data.get("local").and_then(|v| v.as_str()),
+                data.get("timezone").and_then(|v| v.as_str()),
+            ) {
+                println!("  {} {}", local, color::dim(tz));
+            }
+            return;
+        }
+        if let Some(
+            schedule @ ("schedule_add" | "schedule_list" | "schedule_remove" | "schedule_logs"),
+        ) = action
+        {
+            print_schedule(schedule, data);
+            return;
+        }
+        if action == Some("jobs") {
+            print_jobs(data);
+            return;
+        }
+        if action == Some("which") {
+            print_which(data);
+            return;
+        }
+        if action == Some("lanes") {
+            print_lanes(data);
+            return;
+        }
+        if action == Some("memory") {
+            print_memory(data);
+            return;
+        }
+        if action == Some("screencast_start") || action == Some("screencast_stop") {
+            print_screencast(data);
+            return;
+        }
+        if action == Some("cancel") {
+            let cancelled: Vec<&str> = data["cancelled"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str())
+                .collect();
+            if cancelled.is_empty() {
+                println!("Nothing to cancel");
+            } else {
+                ui::status(format!(
+                    "{} Cancelled {}",
+                    color::success_indicator(),
+                    cancelled.join(", ")
+                ));
+            }
+            return;
+        }
+        if action == Some("cdp") {
+            print_cdp(data);
+            return;
+        }
+        if action == Some("har_replay") {
+            let har = data.get("replaying").and_then(|v| v.as_str()).unwrap_or("");
+            ui::status(format!("{} Replaying {}", color::success_indicator(), har));
+            if let Some(url) = data.get("url").and_then(|v| v.as_str()) {
+                let title = data.get("title").and_then(|v| v.as_str()).unwrap_or("");
+                println!("{}", color::bold(title));
+                println!("  {}", color::dim(url));
+            }
+            return;
+        }
+        if action == Some("robots") {
+            let url = data.get("url").and_then(|v| v.as_str()).unwrap_or("");
+            let rule = data.get("rule").and_then(|v| v.as_str());
+            let rule = rule
+                .map(|r| color::dim(&format!(" ({})", r)))
+                .unwrap_or_default();
+            if data
+                .get("allowed")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true)
+            {
+                println!("{} Allowed {}{}", color::success_indicator(), url, rule);
+            } else {
+                println!("{} Disallowed {}{}", color::error_indicator(), url, rule);
+            }
+            return;
+        }
+        if action == Some("input") {
+            let path = data.get("path").and_then(|v| v.as_str()).unwrap_or("");
+            let n = data.get("events").and_then(|v| v.as_u64()).unwrap_or(0);
+            let secs = data.get("duration").and_then(|v| v.as_f64()).unwrap_or(0.0) / 1000.0;
+            let ok = color::success_indicator();
+            if let Some(recording) = data.get("recording").and_then(|v| v.as_str()) {
+                ui::status(format!("{} Recording input to {}", ok, recording));
+            } else if data.get("artifacts").is_some() {
+                println!(
+                    "{} Saved {} input event(s) over {:.1}s to {}",
+                    ok, n, secs, path
+                );
+            } else {
+                println!("{} Replayed {} input event(s) in {:.1}s", ok, n, secs);
+            }
+            return;
+        }
+        if action == Some("ws") {
+            print_ws(data);
+            return;
+        }
+        if action == Some("apis") {
+            print_apis(data);
+            return;
+        }
+        if action == Some("archive") {
+            let path = data.get("path").and_then(|v| v.as_str()).unwrap_or("");
+            let n = |k: &str| data.get(k).and_then(|v| v.as_u64()).unwrap_or(0);
+            print_saved("Archive saved to", path);
+            let format = data.get("format").and_then(|v| v.as_str()).unwrap_or("");
+            let mut detail = format!(
+                "{}, {} resource(s), {}",
+                format.to_uppercase(),
+                n("resources"),
+                format_bytes(n("bytes"))
+            );
+            if n("skipped") > 0 {
+                detail += &format!(", {} no longer held by the browser", n("skipped"));
+            }
+            ui::status(format!("  {}", color::dim(&detail)));
+            return;
+        }
+        if action == Some("freeze") {
+            let path = data.get("path").and_then(|v| v.as_str()).unwrap_or("");
+            let n = |k: &str| data.get(k).and_then(|v| v.as_u64()).unwrap_or(0);
+            print_saved("Page saved to", path);
+            let mut detail = format!("{} inlined, {}", n("inlined"), format_bytes(n("bytes")));
+            if n("external") > 0 {
+                detail += &format!(", {} still linked to the web", n("external"));
+            }
+            ui::status(format!("  {}", color::dim(&detail)));
+            return;
+        }
+        if action == Some("selectors_suggest") {
+            let candidates = data
+                .get("candidates")
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default();
+            if candidates.is_empty() {
+                println!("No selector matches only this element");
+            }
+            for candidate in &candidates {
+                let s = |k: &str| candidate.get(k).and_then(|v| v.as_str()).unwrap_or("");
+                println!(
+                    "{:>3}  {:<10}  {}",
+                    candidate.get("score").and_then(|v| v.as_u64()).unwrap_or(0),
+                    s("strategy"),
+                    color::cyan(s("selector"))
+                );
+            }
+            let fallbacks = data
+                .get("fallbacks")
+                .and_then(|v| v.as_array())
+                .map_or(0, |f| f.len());
+            if fallbacks > 0 {
+                ui::status(color::dim(&format!(
+                    "Stored {} fallback(s) for {}",
+                    fallbacks,
+                    data.get("target").and_then(|v| v.as_str()).unwrap_or("")
+                )));
+            }
+            return;
+        }
+        if action == Some("selectors_heal") {
+            let target = data.get("target").and_then(|v| v.as_str()).unwrap_or("");
+            match data.get("selector").and_then(|v| v.as_str()) {
+                Some(selector) => println!(
+                    "{} {} {} {}",
+                    target,
+                    color::dim("→"),
+                    color::cyan(selector),
+                    color::dim(&format!(
+                        "({})",
+                        data.get("strategy").and_then(|v| v.as_str()).unwrap_or("")
+                    ))
+                ),
+                None => println!("No element found for {}", target),
+            }
+            return;
+        }
+        if action == Some("capture_start") {
+            let dir = data.get("dir").and_then(|v| v.as_str()).unwrap_or("");
+            ui::status(format!(
+                "{} Capturing responses to {}",
+                color::success_indicator(),
+                dir
+            ));
+            return;
+        }
+        if action == Some("capture_stop") {
+            let n = |k: &str| data.get(k).and_then(|v| v.as_u64()).unwrap_or(0);
+            let dir = data.get("dir").and_then(|v| v.as_str()).unwrap_or("");
+            println!(
+                "{} Saved {} response(s) to {}",
+                color::success_indicator(),
+                n("saved"),
+                dir
+            );
+            if n("skipped") > 0 {
+                println!("  {} skipped over --max-size", n("skipped"));
+            }
+            return;
+        }
+        // Dismissed overlays
+        if let Some(dismissed) = data.get("dismissed").and_then(|v| v.as_array()) {
+            if dismissed.is_empty() {
+                println!("No overlays found");
+            }
+            for overlay in dismissed {
+                let category = overlay
+                    .get("category")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("");
+                let target = overlay.get("target").and_then(|v| v.as_str()).unwrap_or("");
+                ui::status(format!(
+                    "{} Dismissed {} {}",
+                    color::success_indicator(),
+                    category,
+                    color::dim(target)
+                ));
+            }
+            return;
+        }
+        // Armed wait
+        if let Some(armed) = data.get("armed").and_then(|v| v.as_str()) {
+            ui::status(format!(
+                "{} Listening for {}",
+                color::success_indicator(),
+                armed
+            ));
+            return;
+        }
         // Navigation response
         if let Some(url) = data.get("url").and_then(|v| v.as_str()) {
             if let Some(title) = data.get("title").and_then(|v| v.as_str()) {
-                println!("{} {}", color::success_indicator(), color::bold(title));
-                println!("  {}", color::dim(url));
+                ui::status(format!(
+                    "{} {}\n  {}",
+                    color::success_indicator(),
+                    color::bold(title),
+                    color::dim(url)
+                ));
                 return;
             }
             println!("{}", url);
@@ -88,17 +415,28 @@ pub fn print_response(resp: &Response, json_mode: bool, action: Option<&str>) {
             // Separate real devices from simulators
             let real_devices: Vec<_> = devices
                 .iter()
-                .filter(|d| d.get("isRealDevice").and_then(|v| v.as_bool()).unwrap_or(false))
+                .filter(|d| {
+                    d.get("isRealDevice")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false)
+                })
                 .collect();
             let simulators: Vec<_> = devices
                 .iter()
-                .filter(|d| !d.get("isRealDevice").and_then(|v| v.as_bool()).unwrap_or(false))
+                .filter(|d| {
+                    !d.get("isRealDevice")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false)
+                })
                 .collect();
 
             if !real_devices.is_empty() {
                 println!("Connected Devices:\n");
                 for device in real_devices.iter() {
-                    let name = device.get("name").and_then(|v| v.as_str()).unwrap_or("Unknown");
+                    let name = device
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("Unknown");
                     let runtime = device.get("runtime").and_then(|v| v.as_str()).unwrap_or("");
                     let udid = device.get("udid").and_then(|v| v.as_str()).unwrap_or("");
                     println!("  {} {} ({})", color::green("●"), name, runtime);
@@ -110,9 +448,15 @@ pub fn print_response(resp: &Response, json_mode: bool, action: Option<&str>) {
             if !simulators.is_empty() {
                 println!("Simulators:\n");
                 for device in simulators.iter() {
-                    let name = device.get("name").and_then(|v| v.as_str()).unwrap_or("Unknown");
+                    let name = device
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("Unknown");
                     let runtime = device.get("runtime").and_then(|v| v.as_str()).unwrap_or("");
-                    let state = device.get("state").and_then(|v| v.as_str()).unwrap_or("Unknown");
+                    let state = device
+                        .get("state")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("Unknown");
                     let udid = device.get("udid").and_then(|v| v.as_str()).unwrap_or("");
                     let state_indicator = if state == "Booted" {
                         color::green("●")
@@ -143,6 +487,20 @@ pub fn print_response(resp: &Response, json_mode: bool, action: Option<&str>) {
             }
             return;
         }
+        // Targets
+        if let Some(targets) = data.get("targets").and_then(|v| v.as_array()) {
+            for target in targets {
+                let id = target.get("id").and_then(|v| v.as_str()).unwrap_or("");
+                let kind = target.get("type").and_then(|v| v.as_str()).unwrap_or("");
+                let url = target.get("url").and_then(|v| v.as_str()).unwrap_or("");
+                let owner = match target.get("page").and_then(|v| v.as_u64()) {
+                    Some(page) => color::dim(&format!(" (tab {})", page)),
+                    None => String::new(),
+                };
+                println!("  {:<6} {:<15} {}{}", id, kind, url, owner);
+            }
+            return;
+        }
         // Console logs
         if let Some(logs) = data.get("messages").and_then(|v| v.as_array()) {
             for log in logs {
@@ -189,7 +547,10 @@ pub fn print_response(resp: &Response, json_mode: bool, action: Option<&str>) {
         // Cleared requests
         if let Some(cleared) = data.get("cleared").and_then(|v| v.as_bool()) {
             if cleared {
-                println!("{} Request log cleared", color::success_indicator());
+                ui::status(format!(
+                    "{} Request log cleared",
+                    color::success_indicator()
+                ));
                 return;
             }
         }
@@ -252,16 +613,20 @@ pub fn print_response(resp: &Response, json_mode: bool, action: Option<&str>) {
         }
         // Closed
         if data.get("closed").is_some() {
-            println!("{} Browser closed", color::success_indicator());
+            ui::status(format!("{} Browser closed", color::success_indicator()));
             return;
         }
         // Recording start (has "started" field)
         if let Some(started) = data.get("started").and_then(|v| v.as_bool()) {
             if started {
                 if let Some(path) = data.get("path").and_then(|v| v.as_str()) {
-                    println!("{} Recording started: {}", color::success_indicator(), path);
+                    ui::status(format!(
+                        "{} Recording started: {}",
+                        color::success_indicator(),
+                        path
+                    ));
                 } else {
-                    println!("{} Recording started", color::success_indicator());
+                    ui::status(format!("{} Recording started", color::success_indicator()));
                 }
                 return;
             }
@@ -273,14 +638,18 @@ pub fn print_response(resp: &Response, json_mode: bool, action: Option<&str>) {
                 .and_then(|v| v.as_str())
                 .unwrap_or("unknown");
             if let Some(prev_path) = data.get("previousPath").and_then(|v| v.as_str()) {
-                println!(
+                ui::status(format!(
                     "{} Recording restarted: {} (previous saved to {})",
                     color::success_indicator(),
                     path,
                     prev_path
-                );
+                ));
             } else {
-                println!("{} Recording started: {}", color::success_indicator(), path);
+                ui::status(format!(
+                    "{} Recording started: {}",
+                    color::success_indicator(),
+                    path
+                ));
             }
             return;
         }
@@ -295,10 +664,14 @@ pub fn print_response(resp: &Response, json_mode: bool, action: Option<&str>) {
                         error
                     );
                 } else {
-                    println!("{} Recording saved to {}", color::success_indicator(), path);
+                    ui::status(format!(
+                        "{} Recording saved to {}",
+                        color::success_indicator(),
+                        path
+                    ));
                 }
             } else {
-                println!("{} Recording stopped", color::success_indicator());
+                ui::status(format!("{} Recording stopped", color::success_indicator()));
             }
             return;
         }
@@ -311,18 +684,14 @@ pub fn print_response(resp: &Response, json_mode: bool, action: Option<&str>) {
                     .and_then(|v| v.as_str())
                     .unwrap_or("");
                 if filename.is_empty() {
-                    println!(
-                        "{} Downloaded to {}",
-                        color::success_indicator(),
-                        color::green(path)
-                    );
+                    print_saved("Downloaded to", path);
                 } else {
-                    println!(
+                    ui::status(format!(
                         "{} Downloaded to {} ({})",
                         color::success_indicator(),
                         color::green(path),
                         filename
-                    );
+                    ));
                 }
                 return;
             }
@@ -330,50 +699,30 @@ pub fn print_response(resp: &Response, json_mode: bool, action: Option<&str>) {
         // Path-based operations (screenshot/pdf/trace/har/download/state/video)
         if let Some(path) = data.get("path").and_then(|v| v.as_str()) {
             match action.unwrap_or("") {
-                "screenshot" => println!(
-                    "{} Screenshot saved to {}",
-                    color::success_indicator(),
-                    color::green(path)
-                ),
-                "pdf" => println!(
-                    "{} PDF saved to {}",
-                    color::success_indicator(),
-                    color::green(path)
-                ),
-                "trace_stop" => println!(
-                    "{} Trace saved to {}",
-                    color::success_indicator(),
-                    color::green(path)
-                ),
-                "har_stop" => println!(
-                    "{} HAR saved to {}",
-                    color::success_indicator(),
-                    color::green(path)
-                ),
-                "download" | "waitfordownload" => println!(
-                    "{} Download saved to {}",
-                    color::success_indicator(),
-                    color::green(path)
-                ),
-                "video_stop" => println!(
-                    "{} Video saved to {}",
-                    color::success_indicator(),
-                    color::green(path)
-                ),
-                "state_save" => println!(
-                    "{} State saved to {}",
-                    color::success_indicator(),
-                    color::green(path)
-                ),
-                "state_load" => {
-                    if let Some(note) = data.get("note").and_then(|v| v.as_str()) {
-                        println!("{}", note);
-                    }
+                "screenshot" => print_saved("Screenshot saved to", path),
+                "pdf" => print_saved("PDF saved to", path),
+                "trace_stop" => print_saved("Trace saved to", path),
+                "har_stop" => print_saved("HAR saved to", path),
+                "download" | "waitfordownload" => print_saved("Download saved to", path),
+                "video_stop" => print_saved("Video saved to", path),
+                "state_save" => print_saved("State saved to", path),
+                "session_save" => {
+                    print_saved("Session saved to", path);
+                    ui::status(format!("  {}", color::dim(&session_counts(data))));
+                }
+                "session_restore" => {
                     println!(
-                        "{} State path set to {}",
+                        "{} Restored session from {}",
                         color::success_indicator(),
                         color::green(path)
                     );
+                    ui::status(format!("  {}", color::dim(&session_counts(data))));
+                }
+                "state_load" => {
+                    if let Some(note) = data.get("note").and_then(|v| v.as_str()) {
+                        println!("{}", note);
+                    }
+                    print_saved("State path set to", path);
                 }
                 // video_start and other commands that provide a path with a note
                 "video_start" => {
@@ -382,11 +731,7 @@ pub fn print_response(resp: &Response, json_mode: bool, action: Option<&str>) {
                     }
                     println!("Path: {}", path);
                 }
-                _ => println!(
-                    "{} Saved to {}",
-                    color::success_indicator(),
-                    color::green(path)
-                ),
+                _ => print_saved("Saved to", path),
             }
             return;
         }
@@ -397,81 +742,955 @@ pub fn print_response(resp: &Response, json_mode: bool, action: Option<&str>) {
             return;
         }
         // Default success
-        println!("{} Done", color::success_indicator());
+        ui::status(format!("{} Done", color::success_indicator()));
     }
 }
 
-/// Print command-specific help. Returns true if help was printed, false if command unknown.
-pub fn print_command_help(command: &str) -> bool {
-    let help = match command {
-        // === Navigation ===
-        "open" | "goto" | "navigate" => {
-            r##"
-agent-browser open - Navigate to a URL
-
-Usage: agent-browser open <url>
+/// Human-readable byte count, e.g. 1.4 MB.
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
 
-Navigates the browser to the specified URL. If no protocol is provided,
-https:// is automatically prepended.
+fn print_ws(data: &serde_json::Value) {
+    let s = |v: &serde_json::Value, k: &str| {
+        v.get(k).and_then(|v| v.as_str()).unwrap_or("").to_string()
+    };
+    let n = |v: &serde_json::Value, k: &str| v.get(k).and_then(|v| v.as_u64()).unwrap_or(0);
+
+    if data.get("cleared").and_then(|v| v.as_bool()) == Some(true) {
+        ui::status(format!(
+            "{} WebSocket log cleared",
+            color::success_indicator()
+        ));
+    } else if let Some(sockets) = data.get("sockets").and_then(|v| v.as_array()) {
+        if sockets.is_empty() {
+            println!("No WebSockets observed (Chromium only)");
+        }
+        for socket in sockets {
+            let state = if socket.get("closed").is_some() {
+                color::dim("closed")
+            } else {
+                color::green("open")
+            };
+            println!(
+                "{:<5} {} {} {}",
+                s(socket, "id"),
+                state,
+                s(socket, "url"),
+                color::dim(&format!(
+                    "{} sent, {} received",
+                    n(socket, "framesSent"),
+                    n(socket, "framesReceived")
+                ))
+            );
+        }
+    } else if let Some(frames) = data.get("frames").and_then(|v| v.as_array()) {
+        if let Some(socket) = data.get("socket") {
+            println!("{} {}", color::bold(&s(socket, "id")), s(socket, "url"));
+        }
+        if frames.is_empty() {
+            println!("No frames yet");
+        }
+        for frame in frames {
+            let arrow = if s(frame, "direction") == "sent" {
+                color::cyan("->")
+            } else {
+                color::green("<-")
+            };
+            let data = match n(frame, "opcode") {
+                1 => s(frame, "data"),
+                2 => format!("<binary, {} bytes>", n(frame, "size")),
+                8 => "<close>".to_string(),
+                op => format!("<opcode {}>", op),
+            };
+            println!("{} {}", arrow, data);
+        }
+    } else if let Some(path) = data.get("path").and_then(|v| v.as_str()) {
+        println!(
+            "{} Exported {} WebSocket(s) to {}",
+            color::success_indicator(),
+            n(data, "sockets"),
+            path
+        );
+    }
+}
 
-Aliases: goto, navigate
+fn print_apis(data: &serde_json::Value) {
+    if data.get("cleared").and_then(|v| v.as_bool()) == Some(true) {
+        ui::status(format!(
+            "{} API call log cleared",
+            color::success_indicator()
+        ));
+        return;
+    }
+    let apis = data
+        .get("apis")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    if apis.is_empty() {
+        println!("No XHR/fetch calls observed");
+        return;
+    }
+    for api in &apis {
+        let s = |k: &str| api.get(k).and_then(|v| v.as_str()).unwrap_or("");
+        let statuses: Vec<String> = api
+            .get("statuses")
+            .and_then(|v| v.as_array())
+            .map(|a| a.iter().map(|v| v.to_string()).collect())
+            .unwrap_or_default();
+        let operation = match s("operation") {
+            "" => String::new(),
+            op => format!(" {}", color::cyan(op)),
+        };
+        println!(
+            "{:<6} {}{} {} {}",
+            s("method"),
+            s("url"),
+            operation,
+            statuses.join(","),
+            color::dim(&format!(
+                "x{}",
+                api.get("count").and_then(|v| v.as_u64()).unwrap_or(0)
+            ))
+        );
+        if let Some(schema) = api.get("schema") {
+            println!("       {}", color::dim(&schema.to_string()));
+        }
+    }
+}
 
-Global Options:
-  --json               Output as JSON
-  --session <name>     Use specific session
-  --headers <json>     Set HTTP headers (scoped to this origin)
-  --headed             Show browser window
+fn print_cdp(data: &serde_json::Value) {
+    let result = data.get("result").cloned().unwrap_or_default();
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&result).unwrap_or_default()
+    );
+    for event in data
+        .get("events")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let method = event.get("method").and_then(|v| v.as_str()).unwrap_or("");
+        let params = event.get("params").cloned().unwrap_or_default();
+        println!(
+            "{} {} {}",
+            color::dim("←"),
+            color::cyan(method),
+            color::dim(&params.to_string())
+        );
+    }
+}
 
-Examples:
-  agent-browser open example.com
-  agent-browser open https://github.com
-  agent-browser open localhost:3000
-  agent-browser open api.example.com --headers '{"Authorization": "Bearer token"}'
-    # ^ Headers only sent to api.example.com, not other domains
-"##
-        }
-        "back" => {
-            r##"
-agent-browser back - Navigate back in history
+/// Color a vital by the web-vitals thresholds for good and poor.
+fn rate_vital(text: String, value: f64, good: f64, poor: f64) -> String {
+    if value <= good {
+        color::green(&text)
+    } else if value <= poor {
+        color::yellow(&text)
+    } else {
+        color::red(&text)
+    }
+}
 
-Usage: agent-browser back
+fn print_audit_perf(data: &serde_json::Value) {
+    let url = data.get("url").and_then(|v| v.as_str()).unwrap_or("");
+    println!("{} {}", color::bold("Performance"), url);
+
+    if let Some(nav) = data.get("navigation").filter(|v| v.is_object()) {
+        let n = |k: &str| nav.get(k).and_then(|v| v.as_u64()).unwrap_or(0);
+        println!(
+            "  TTFB {}ms  DOMContentLoaded {}ms  Load {}ms",
+            n("ttfb"),
+            n("domContentLoaded"),
+            n("load")
+        );
+        println!(
+            "  {}",
+            color::dim(&format!(
+                "dns {}ms, connect {}ms, tls {}ms, download {}ms",
+                n("dns"),
+                n("connect"),
+                n("tls"),
+                n("download")
+            ))
+        );
+    }
 
-Goes back one page in the browser history, equivalent to clicking
-the browser's back button.
+    let vitals = &data["vitals"];
+    let ms = |k: &str, good: f64, poor: f64| match vitals.get(k).and_then(|v| v.as_f64()) {
+        Some(v) => rate_vital(format!("{:.0}ms", v), v, good, poor),
+        None => color::dim("n/a"),
+    };
+    let cls = vitals.get("cls").and_then(|v| v.as_f64()).unwrap_or(0.0);
+    println!(
+        "  FCP {}  LCP {}  CLS {}  TBT {}",
+        ms("fcp", 1800.0, 3000.0),
+        ms("lcp", 2500.0, 4000.0),
+        rate_vital(format!("{:.3}", cls), cls, 0.1, 0.25),
+        ms("tbt", 200.0, 600.0)
+    );
 
-Global Options:
-  --json               Output as JSON
-  --session <name>     Use specific session
+    let totals = &data["totals"];
+    let total = |k: &str| totals.get(k).and_then(|v| v.as_u64()).unwrap_or(0);
+    println!(
+        "{} {}",
+        color::bold("Resources"),
+        color::dim(&format!(
+            "({} requests, {})",
+            total("requests"),
+            format_bytes(total("size"))
+        ))
+    );
+    for group in data["resources"].as_array().into_iter().flatten() {
+        let n = |k: &str| group.get(k).and_then(|v| v.as_u64()).unwrap_or(0);
+        println!(
+            "  {:<12} {:>4} {:>10}",
+            group.get("type").and_then(|v| v.as_str()).unwrap_or(""),
+            n("count"),
+            format_bytes(n("size"))
+        );
+    }
 
-Examples:
-  agent-browser back
-"##
+    let largest = data["largest"].as_array().cloned().unwrap_or_default();
+    if !largest.is_empty() {
+        println!("{}", color::bold("Largest requests"));
+        for entry in &largest {
+            let n = |k: &str| entry.get(k).and_then(|v| v.as_u64()).unwrap_or(0);
+            println!(
+                "  {:>10} {:<12} {} {}",
+                format_bytes(n("size")),
+                entry.get("type").and_then(|v| v.as_str()).unwrap_or(""),
+                entry.get("url").and_then(|v| v.as_str()).unwrap_or(""),
+                color::dim(&format!("{}ms", n("duration")))
+            );
         }
-        "forward" => {
-            r##"
-agent-browser forward - Navigate forward in history
-
-Usage: agent-browser forward
+    }
+}
 
-Goes forward one page in the browser history, equivalent to clicking
-the browser's forward button.
+fn print_audit_seo(data: &serde_json::Value) {
+    let s = |v: &serde_json::Value, k: &str| v.get(k).and_then(|v| v.as_str()).map(String::from);
+    println!(
+        "{} {}",
+        color::bold("SEO"),
+        s(data, "url").unwrap_or_default()
+    );
 
-Global Options:
-  --json               Output as JSON
-  --session <name>     Use specific session
+    let meta = &data["meta"];
+    for (label, key) in [
+        ("title", "title"),
+        ("description", "description"),
+        ("canonical", "canonical"),
+        ("robots", "robots"),
+    ] {
+        let value = s(meta, key).unwrap_or_else(|| color::dim("(none)"));
+        println!("  {:<12} {}", label, value);
+    }
 
-Examples:
-  agent-browser forward
-"##
+    let headings = data["headings"].as_array().cloned().unwrap_or_default();
+    let counts: Vec<String> = (1..=6)
+        .filter_map(|level| {
+            let n = headings
+                .iter()
+                .filter(|h| h.get("level").and_then(|v| v.as_u64()) == Some(level))
+                .count();
+            (n > 0).then(|| format!("h{} {}", level, n))
+        })
+        .collect();
+    println!(
+        "  {:<12} {}",
+        "headings",
+        if counts.is_empty() {
+            color::dim("(none)")
+        } else {
+            counts.join(", ")
         }
-        "reload" => {
-            r##"
-agent-browser reload - Reload the current page
-
-Usage: agent-browser reload
+    );
 
-Reloads the current page, equivalent to pressing F5 or clicking
-the browser's reload button.
+    let links = &data["links"];
+    let n = |k: &str| links.get(k).and_then(|v| v.as_u64()).unwrap_or(0);
+    let broken = links["broken"].as_array().map(|b| b.len()).unwrap_or(0);
+    println!(
+        "  {:<12} {} internal, {} checked, {} broken",
+        "links",
+        n("internal"),
+        n("checked"),
+        broken
+    );
+
+    let blocks = data["structuredData"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let types: Vec<String> = blocks
+        .iter()
+        .flat_map(|b| b["types"].as_array().cloned().unwrap_or_default())
+        .filter_map(|t| t.as_str().map(String::from))
+        .collect();
+    println!(
+        "  {:<12} {}",
+        "json-ld",
+        if blocks.is_empty() {
+            color::dim("(none)")
+        } else if types.is_empty() {
+            format!("{} blocks", blocks.len())
+        } else {
+            types.join(", ")
+        }
+    );
+
+    print_audit_issues(data);
+}
+
+fn print_audit_security(data: &serde_json::Value) {
+    let s = |v: &serde_json::Value, k: &str| v.get(k).and_then(|v| v.as_str()).map(String::from);
+    println!(
+        "{} {}",
+        color::bold("Security"),
+        s(data, "url").unwrap_or_default()
+    );
+
+    let headers = &data["headers"];
+    for (label, key) in [
+        ("CSP", "csp"),
+        ("HSTS", "hsts"),
+        ("X-Frame-Options", "xFrameOptions"),
+    ] {
+        let value = s(headers, key).unwrap_or_else(|| color::dim("(none)"));
+        println!("  {:<16} {}", label, value);
+    }
+
+    let cookies = data["cookies"].as_array().cloned().unwrap_or_default();
+    let missing = |flag: &str| {
+        cookies
+            .iter()
+            .filter(|c| c.get(flag).and_then(|v| v.as_bool()) != Some(true))
+            .count()
+    };
+    println!(
+        "  {:<16} {} {}",
+        "cookies",
+        cookies.len(),
+        color::dim(&format!(
+            "({} without Secure, {} without HttpOnly)",
+            missing("secure"),
+            missing("httpOnly")
+        ))
+    );
+    let mixed = data["mixedContent"]
+        .as_array()
+        .map(|m| m.len())
+        .unwrap_or(0);
+    println!("  {:<16} {}", "mixed content", mixed);
+
+    let third_party = data["thirdParty"].as_array().cloned().unwrap_or_default();
+    if !third_party.is_empty() {
+        println!(
+            "{} {}",
+            color::bold("Third-party origins"),
+            color::dim(&format!("({})", third_party.len()))
+        );
+        for entry in &third_party {
+            let types: Vec<&str> = entry["types"]
+                .as_array()
+                .map(|t| t.iter().filter_map(|v| v.as_str()).collect())
+                .unwrap_or_default();
+            println!(
+                "  {:<40} {:>4} req {}",
+                s(entry, "origin").unwrap_or_default(),
+                entry.get("requests").and_then(|v| v.as_u64()).unwrap_or(0),
+                color::dim(&types.join(", "))
+            );
+        }
+    }
+    print_audit_issues(data);
+}
+
+fn print_tls_info(data: &serde_json::Value) {
+    fn s<'a>(v: &'a serde_json::Value, k: &str) -> &'a str {
+        v.get(k).and_then(|v| v.as_str()).unwrap_or("")
+    }
+    let port = data.get("port").and_then(|v| v.as_u64()).unwrap_or(443);
+    let protocol = format!("{} {}", s(data, "protocol"), s(data, "cipher"));
+    println!(
+        "{}:{} {}",
+        s(data, "host"),
+        port,
+        color::dim(protocol.trim())
+    );
+    if data.get("authorized").and_then(|v| v.as_bool()) == Some(true) {
+        println!("{} Trusted", color::success_indicator());
+    } else {
+        println!(
+            "{} Not trusted: {}",
+            color::error_indicator(),
+            s(data, "error")
+        );
+    }
+
+    let chain = data["chain"].as_array().cloned().unwrap_or_default();
+    for (i, cert) in chain.iter().enumerate() {
+        let days = cert.get("daysLeft").and_then(|v| v.as_i64()).unwrap_or(0);
+        // validTo is ISO 8601; the date is enough here
+        let expiry = s(cert, "validTo").get(..10).unwrap_or_default();
+        let left = if days < 0 {
+            color::red(&format!("(expired {} days ago)", -days))
+        } else if days < 30 {
+            color::yellow(&format!("({} days left)", days))
+        } else {
+            color::dim(&format!("({} days left)", days))
+        };
+        println!(
+            "{}",
+            color::bold(&format!("[{}] {}", i, s(cert, "subject")))
+        );
+        println!("  issuer   {}", s(cert, "issuer"));
+        println!("  expires  {} {}", expiry, left);
+        if let Some(san) = cert.get("san").and_then(|v| v.as_array()) {
+            let names: Vec<&str> = san.iter().filter_map(|v| v.as_str()).collect();
+            println!("  san      {}", names.join(", "));
+        }
+        println!("  sha256   {}", color::dim(s(cert, "fingerprint256")));
+    }
+}
+
+fn print_schedule(action: &str, data: &serde_json::Value) {
+    fn s<'a>(v: &'a serde_json::Value, k: &str) -> &'a str {
+        v.get(k).and_then(|v| v.as_str()).unwrap_or("")
+    }
+    fn command(job: &serde_json::Value) -> String {
+        let args: Vec<&str> = job["command"]
+            .as_array()
+            .map(|a| a.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+        format!("agent-browser {}", args.join(" "))
+    }
+    fn status(run: &serde_json::Value) -> String {
+        match s(run, "status") {
+            "passed" => format!("{} passed", color::success_indicator()),
+            "failed" => format!(
+                "{} failed (exit {})",
+                color::error_indicator(),
+                run.get("exitCode").and_then(|v| v.as_i64()).unwrap_or(1)
+            ),
+            other => format!("{} {}", color::warning_indicator(), other),
+        }
+    }
+    match action {
+        "schedule_add" => {
+            let job = &data["job"];
+            println!(
+                "{} Scheduled job {}: {}",
+                color::success_indicator(),
+                s(job, "id"),
+                command(job)
+            );
+            println!("  next run {}", s(job, "nextRun"));
+        }
+        "schedule_remove" => println!(
+            "{} Removed job {}",
+            color::success_indicator(),
+            s(data, "removed")
+        ),
+        "schedule_logs" => match data.get("run").filter(|r| !r.is_null()) {
+            Some(run) => {
+                println!(
+                    "{} {}",
+                    color::dim(&format!("{} {}", s(run, "started"), s(run, "dir"))),
+                    status(run)
+                );
+                print!("{}", s(data, "output"));
+            }
+            None => println!("Job {} hasn't run yet", s(data, "id")),
+        },
+        _ => {
+            let jobs = data["jobs"].as_array().cloned().unwrap_or_default();
+            if jobs.is_empty() {
+                println!("No scheduled jobs");
+                return;
+            }
+            for job in &jobs {
+                let running = if job["running"].as_bool() == Some(true) {
+                    format!(" {}", color::yellow("(running)"))
+                } else {
+                    String::new()
+                };
+                println!(
+                    "{}{}",
+                    color::bold(&format!(
+                        "[{}] {}  {}",
+                        s(job, "id"),
+                        s(job, "cron"),
+                        command(job)
+                    )),
+                    running
+                );
+                println!("  next run {}", s(job, "nextRun"));
+                if let Some(run) = job.get("lastRun").filter(|r| !r.is_null()) {
+                    println!("  last run {} {}", s(run, "started"), status(run));
+                }
+            }
+        }
+    }
+}
+
+/// The executable launches use, then every place that was looked in.
+fn print_which(data: &serde_json::Value) {
+    match data["path"].as_str() {
+        Some(path) => println!(
+            "{} {}",
+            color::bold(path),
+            color::dim(&format!("({})", data["source"].as_str().unwrap_or("")))
+        ),
+        None => println!(
+            "{} No browser found; launches use Playwright's bundled Chromium",
+            color::warning_indicator()
+        ),
+    }
+    for candidate in data["candidates"].as_array().into_iter().flatten() {
+        let s = |k: &str| candidate.get(k).and_then(|v| v.as_str()).unwrap_or("");
+        let indicator = if candidate["exists"].as_bool() == Some(true) {
+            color::success_indicator()
+        } else {
+            color::error_indicator()
+        };
+        println!("  {} {:<28} {}", indicator, s("source"), s("path"));
+    }
+}
+
+fn print_lanes(data: &serde_json::Value) {
+    let mut limits = Vec::new();
+    if let Some(max) = data["maxConcurrent"].as_u64() {
+        limits.push(format!("{} at a time", max));
+    }
+    if let (Some(count), Some(per)) = (
+        data["actionRate"]["count"].as_u64(),
+        data["actionRate"]["per"].as_u64(),
+    ) {
+        limits.push(format!("{} per {}", count, timeouts::format_ms(per)));
+    }
+    if limits.is_empty() {
+        println!("No limits; commands run as they come (see --max-concurrent, --action-rate)");
+        return;
+    }
+    let n = |k: &str| data[k].as_u64().unwrap_or(0);
+    println!("Limits: {}", limits.join(", "));
+    println!(
+        "  running {}  sessions queued {}  waiting here {}",
+        n("active"),
+        n("queued"),
+        n("waiting")
+    );
+    println!(
+        "  {} commands, {} waited ({} in all)",
+        n("scheduled"),
+        n("delayed"),
+        timeouts::format_ms(n("waitedMs"))
+    );
+}
+
+fn print_memory(data: &serde_json::Value) {
+    let limit = data["limit"].as_u64();
+    match limit {
+        Some(limit) => println!(
+            "Tabs are recycled ({}) past {} of JS heap",
+            data["mode"].as_str().unwrap_or("reload"),
+            format_bytes(limit)
+        ),
+        None => println!("No tab memory limit (see --max-tab-memory)"),
+    }
+    for tab in data["tabs"].as_array().into_iter().flatten() {
+        let used = tab["heapUsed"].as_u64().unwrap_or(0);
+        let size = format!("{:>10}", format_bytes(used));
+        let size = if limit.is_some_and(|limit| used > limit) {
+            color::red(&size)
+        } else {
+            size
+        };
+        println!(
+            "  [{}] {}  {}",
+            tab["index"].as_u64().unwrap_or(0),
+            size,
+            tab["url"].as_str().unwrap_or("")
+        );
+    }
+    let recycles = data["recycles"].as_array().cloned().unwrap_or_default();
+    if recycles.is_empty() {
+        return;
+    }
+    println!(
+        "{}",
+        color::bold(&format!(
+            "Recycled {} time(s)",
+            data["total"].as_u64().unwrap_or(recycles.len() as u64)
+        ))
+    );
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let ago = |at: u64| {
+        let secs = now.saturating_sub(at) / 1000;
+        match secs {
+            0..=59 => format!("{}s ago", secs),
+            60..=3599 => format!("{}m ago", secs / 60),
+            _ => format!("{}h ago", secs / 3600),
+        }
+    };
+    for recycle in &recycles {
+        let outcome = match recycle["error"].as_str() {
+            Some(error) => format!("{} {}", color::error_indicator(), error),
+            None => color::success_indicator().to_string(),
+        };
+        println!(
+            "  {} [{}] {} by {}  {}  {}",
+            outcome,
+            recycle["index"].as_u64().unwrap_or(0),
+            format_bytes(recycle["heapUsed"].as_u64().unwrap_or(0)),
+            recycle["mode"].as_str().unwrap_or(""),
+            recycle["url"].as_str().unwrap_or(""),
+            color::dim(&ago(recycle["at"].as_u64().unwrap_or(0)))
+        );
+    }
+}
+
+fn print_screencast(data: &serde_json::Value) {
+    if data["started"].as_bool() == Some(true) {
+        let mut settings = vec![format!(
+            "{} q{}",
+            data["format"].as_str().unwrap_or("jpeg"),
+            data["quality"].as_u64().unwrap_or(80)
+        )];
+        if let Some(scale) = data["scale"].as_f64() {
+            settings.push(format!("scale {}", scale));
+        }
+        if let Some(fps) = data["fps"].as_f64() {
+            settings.push(format!("{} fps", fps));
+        }
+        let target = data["path"].as_str().unwrap_or("stream clients");
+        ui::status(format!(
+            "{} Screencast to {} ({})",
+            color::success_indicator(),
+            color::green(target),
+            settings.join(", ")
+        ));
+        return;
+    }
+    let Some(path) = data["path"].as_str() else {
+        ui::status(format!("{} Screencast stopped", color::success_indicator()));
+        return;
+    };
+    let indicator = if data["error"].is_string() {
+        color::warning_indicator()
+    } else {
+        color::success_indicator()
+    };
+    println!(
+        "{} Wrote {} frame(s), {}, to {}",
+        indicator,
+        data["frames"].as_u64().unwrap_or(0),
+        format_bytes(data["bytes"].as_u64().unwrap_or(0)),
+        color::green(path)
+    );
+    let dropped = data["dropped"].as_u64().unwrap_or(0);
+    if dropped > 0 {
+        println!(
+            "  {}",
+            color::dim(&format!("{} dropped while the reader was behind", dropped))
+        );
+    }
+    if let Some(error) = data["error"].as_str() {
+        println!("  {}", error);
+    }
+}
+
+fn print_jobs(data: &serde_json::Value) {
+    match data["url"].as_str() {
+        Some(url) => println!(
+            "Job API at {} ({} at a time)",
+            color::cyan(url),
+            data["concurrency"].as_u64().unwrap_or(1)
+        ),
+        None => println!(
+            "{} Job API off; start the daemon with AGENT_BROWSER_JOBS_PORT set",
+            color::warning_indicator()
+        ),
+    }
+    let pool = &data["pool"];
+    if pool.is_object() {
+        let n = |k: &str| pool[k].as_u64().unwrap_or(0);
+        println!(
+            "Browser pool: {} idle, {} in use, {} launching (size {}, recycle {})",
+            n("idle"),
+            n("leased"),
+            n("launching"),
+            n("size"),
+            pool["recycle"].as_str().unwrap_or("close")
+        );
+        println!(
+            "  {} warm, {} launched on demand, {} retired",
+            n("hits"),
+            n("misses"),
+            n("retired")
+        );
+        if let Some(error) = pool["lastError"].as_str() {
+            println!("  {} {}", color::warning_indicator(), error);
+        }
+    }
+    let jobs = data["jobs"].as_array().cloned().unwrap_or_default();
+    if jobs.is_empty() {
+        println!("No jobs");
+        return;
+    }
+    for job in &jobs {
+        let s = |k: &str| job.get(k).and_then(|v| v.as_str()).unwrap_or("");
+        let status = match s("status") {
+            "passed" => format!("{} passed", color::success_indicator()),
+            "failed" => format!(
+                "{} failed (exit {})",
+                color::error_indicator(),
+                job["exitCode"].as_i64().unwrap_or(1)
+            ),
+            "running" => color::yellow("running"),
+            other => color::dim(other),
+        };
+        let name = if s("name").is_empty() {
+            String::new()
+        } else {
+            format!(" {}", s("name"))
+        };
+        println!(
+            "{}{}  {}  priority {}",
+            color::bold(&format!("[{}]", s("id"))),
+            name,
+            status,
+            job["priority"].as_i64().unwrap_or(0)
+        );
+        println!("  {}", color::dim(s("dir")));
+    }
+}
+
+/// The `issues` list shared by the audit commands, errors marked ✗ and warnings ⚠.
+fn print_audit_issues(data: &serde_json::Value) {
+    let issues = data["issues"].as_array().cloned().unwrap_or_default();
+    if issues.is_empty() {
+        println!("{} No issues", color::success_indicator());
+        return;
+    }
+    println!("{}", color::bold("Issues"));
+    for issue in &issues {
+        let indicator = if issue.get("severity").and_then(|v| v.as_str()) == Some("error") {
+            color::error_indicator()
+        } else {
+            color::warning_indicator()
+        };
+        println!(
+            "  {} {}",
+            indicator,
+            issue.get("message").and_then(|v| v.as_str()).unwrap_or("")
+        );
+    }
+}
+
+fn print_stats(data: &serde_json::Value) {
+    let n = |k: &str| data.get(k).and_then(|v| v.as_u64()).unwrap_or(0);
+    let requests = n("requests");
+    let cache_rate = if requests > 0 {
+        n("cacheHits") as f64 * 100.0 / requests as f64
+    } else {
+        0.0
+    };
+    println!("  requests   {}", requests);
+    println!("  failed     {}", n("failed"));
+    println!("  blocked    {}", n("blocked"));
+    println!(
+        "  throttled  {} {}",
+        n("throttled"),
+        color::dim(&format!(
+            "({:.1}s waited)",
+            n("throttledMs") as f64 / 1000.0
+        ))
+    );
+    println!(
+        "  cache hits {} {}",
+        n("cacheHits"),
+        color::dim(&format!("({:.0}%)", cache_rate))
+    );
+    println!("  received   {}", format_bytes(n("bytesIn")));
+    println!("  sent       {}", format_bytes(n("bytesOut")));
+
+    let hosts = data
+        .get("topHosts")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    if !hosts.is_empty() {
+        println!("{}", color::bold("Top hosts"));
+        for host in &hosts {
+            let h = |k: &str| host.get(k).and_then(|v| v.as_u64()).unwrap_or(0);
+            println!(
+                "  {:<40} {:>10} {}",
+                host.get("host").and_then(|v| v.as_str()).unwrap_or(""),
+                format_bytes(h("bytes")),
+                color::dim(&format!("{} req", h("requests")))
+            );
+        }
+    }
+}
+
+fn print_inspect(data: &serde_json::Value) {
+    let tag = data
+        .get("tag")
+        .and_then(|v| v.as_str())
+        .unwrap_or("element");
+    println!("{}", color::bold(&format!("<{}>", tag)));
+
+    if let Some(b) = data.get("box") {
+        let n = |k: &str| b.get(k).and_then(|v| v.as_f64()).unwrap_or(0.0);
+        println!(
+            "  box        x={} y={} w={} h={}",
+            n("x"),
+            n("y"),
+            n("width"),
+            n("height")
+        );
+    }
+
+    let visible = data
+        .get("visible")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    println!("  visible    {}", visible);
+    match data.get("occludedBy").and_then(|v| v.as_str()) {
+        Some(by) => println!("  occluded   {} {}", color::yellow("yes"), color::dim(by)),
+        None => println!("  occluded   no"),
+    }
+
+    if let Some(scroll) = data.get("scroll") {
+        let n = |k: &str| scroll.get(k).and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let in_viewport = scroll
+            .get("inViewport")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        println!(
+            "  scroll     element={},{} window={},{} in-viewport={}",
+            n("left"),
+            n("top"),
+            n("windowX"),
+            n("windowY"),
+            in_viewport
+        );
+    }
+
+    if let Some(listeners) = data.get("listeners").and_then(|v| v.as_array()) {
+        let names: Vec<&str> = listeners.iter().filter_map(|v| v.as_str()).collect();
+        if names.is_empty() {
+            println!("  listeners  {}", color::dim("none"));
+        } else {
+            println!("  listeners  {}", names.join(", "));
+        }
+    }
+
+    for (label, key) in [("Styles", "styles"), ("Attributes", "attributes")] {
+        if let Some(map) = data.get(key).and_then(|v| v.as_object()) {
+            if map.is_empty() {
+                continue;
+            }
+            println!("{}", color::bold(label));
+            for (k, v) in map {
+                let value = v
+                    .as_str()
+                    .map(String::from)
+                    .unwrap_or_else(|| v.to_string());
+                println!("  {} {}", color::cyan(k), value);
+            }
+        }
+    }
+}
+
+/// Print command-specific help. Returns true if help was printed, false if command unknown.
+pub fn print_command_help(command: &str) -> bool {
+    let help = match command {
+        // === Navigation ===
+        "open" | "goto" | "navigate" => {
+            r##"
+agent-browser open - Navigate to a URL
+
+Usage: agent-browser open <url>
+
+Navigates the browser to the specified URL. If no protocol is provided,
+https:// is automatically prepended.
+
+Aliases: goto, navigate
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+  --headers <json>     Set HTTP headers (scoped to this origin)
+  --headed             Show browser window
+
+Examples:
+  agent-browser open example.com
+  agent-browser open https://github.com
+  agent-browser open localhost:3000
+  agent-browser open api.example.com --headers '{"Authorization": "Bearer token"}'
+    # ^ Headers only sent to api.example.com, not other domains
+"##
+        }
+        "back" => {
+            r##"
+agent-browser back - Navigate back in history
+
+Usage: agent-browser back
+
+Goes back one page in the browser history, equivalent to clicking
+the browser's back button.
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser back
+"##
+        }
+        "forward" => {
+            r##"
+agent-browser forward - Navigate forward in history
+
+Usage: agent-browser forward
+
+Goes forward one page in the browser history, equivalent to clicking
+the browser's forward button.
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser forward
+"##
+        }
+        "reload" => {
+            r##"
+agent-browser reload - Reload the current page
+
+Usage: agent-browser reload
+
+Reloads the current page, equivalent to pressing F5 or clicking
+the browser's reload button.
 
 Global Options:
   --json               Output as JSON
@@ -830,10 +2049,16 @@ Modes:
   --load <state>       Wait for load state (load, domcontentloaded, networkidle)
   --fn <expression>    Wait for JavaScript expression to be truthy
   --text <text>        Wait for text to appear on page
-  --download [path]    Wait for a download to complete (optionally save to path)
+  --download [path]    Wait for a download to complete and save it to path
+                       (default: the session's artifacts directory)
+  --popup              Wait for a popup window opened by the page
+  --request <pattern>  Wait for a request whose URL contains pattern
 
-Download Options (with --download):
-  --timeout <ms>       Timeout in milliseconds for download to start
+Event Options (with --download, --popup, --request):
+  --timeout <ms>       Timeout in milliseconds for the event
+  --arm                Start listening and return immediately. Run the same
+                       wait again after the triggering action to collect the
+                       event, even if it fired in between.
 
 Global Options:
   --json               Output as JSON
@@ -848,6 +2073,8 @@ Examples:
   agent-browser wait --text "Welcome back"
   agent-browser wait --download ./file.pdf
   agent-browser wait --download ./report.xlsx --timeout 30000
+  agent-browser wait --popup --arm && agent-browser click @e4 && agent-browser wait --popup
+  agent-browser wait --request /api/login --arm
 "##
         }
 
@@ -859,7 +2086,8 @@ agent-browser screenshot - Take a screenshot
 Usage: agent-browser screenshot [path]
 
 Captures a screenshot of the current page. If no path is provided,
-saves to a temporary directory with a generated filename.
+saves to ~/.browseros/artifacts/<session>/screenshots/ with a generated
+filename.
 
 Options:
   --full, -f           Capture full page (not just viewport)
@@ -874,67 +2102,245 @@ Examples:
   agent-browser screenshot --full ./full-page.png
 "##
         }
-        "pdf" => {
+        "compare-screenshot" => {
             r##"
-agent-browser pdf - Save page as PDF
+agent-browser compare-screenshot - Compare a screenshot against a baseline
+
+Usage: agent-browser compare-screenshot --baseline <file> [selector] [options]
 
-Usage: agent-browser pdf <path>
+Takes a screenshot and compares it pixel by pixel with a baseline PNG.
+If the baseline does not exist yet, the screenshot becomes the baseline.
+When the comparison fails, a diff image is written with differing pixels
+in red and the command exits with code 13 (E_VISUAL_MISMATCH).
 
-Saves the current page as a PDF file.
+Options:
+  --baseline <file>    Baseline PNG (created if missing)
+  --threshold <0-1>    Per-pixel color tolerance (default: 0.1, 0 = exact)
+  --max-diff <ratio>   Fraction of pixels allowed to differ (default: 0)
+  --mask <selector>    Paint over an element before comparing (repeatable)
+  --diff <file>        Diff image path (default: <baseline>.diff.png)
+  --update-baseline    Replace the baseline with the current screenshot
+  --full, -f           Capture full page (not just viewport)
 
 Global Options:
   --json               Output as JSON
   --session <name>     Use specific session
 
 Examples:
-  agent-browser pdf ./page.pdf
-  agent-browser pdf ~/Documents/report.pdf
+  agent-browser compare-screenshot --baseline home.png
+  agent-browser compare-screenshot --baseline header.png "#header"
+  agent-browser compare-screenshot --baseline home.png --mask .clock --mask .ad
+  agent-browser compare-screenshot --baseline home.png --update-baseline
 "##
         }
-
-        // === Snapshot ===
-        "snapshot" => {
+        "selectors" => {
             r##"
-agent-browser snapshot - Get accessibility tree snapshot
+agent-browser selectors - Durable selectors for an element
 
-Usage: agent-browser snapshot [options]
+Usage: agent-browser selectors suggest <ref|selector>
+       agent-browser selectors heal <ref|selector>
 
-Returns an accessibility tree representation of the page with element
-references (like @e1, @e2) that can be used in subsequent commands.
-Designed for AI agents to understand page structure.
+suggest lists selectors that match only this element, best first, each with a
+score (0-100) for how likely it is to keep working as the page changes:
 
-Options:
-  -i, --interactive    Only include interactive elements
-  -C, --cursor         Include cursor-interactive elements (cursor:pointer, onclick, tabindex)
-  -c, --compact        Remove empty structural elements
-  -d, --depth <n>      Limit tree depth
-  -s, --selector <sel> Scope snapshot to CSS selector
+  test-id     data-testid, data-test-id, data-test, data-cy, data-qa
+  id          #id (ids that look generated score low)
+  role        role=button[name="Buy" s], from the snapshot for refs
+  aria-label  [aria-label="..."]
+  name        input[name="email"]
+  text        text="Buy now", for short texts
+  css-path    body > main > div:nth-of-type(2) > button, the last resort
+
+Candidates scoring 50 or more are also stored as fallbacks. For a ref,
+later actions on it find the element through any of them, so they survive
+its role or name changing until the next snapshot. For a selector, they are
+kept for heal.
+
+heal finds the element a selector that no longer matches meant, trying in
+turn its stored fallbacks, the accessible name the selector mentions (role
+and name, label or exact text), and the visible control whose text is
+closest to it. It prints the best selector for that element. `run` heals
+failing steps this way.
+
+Use the best selector in scripts in place of refs, which only last until
+the next snapshot. With --into, the best selector is stored as a variable.
 
 Global Options:
-  --json               Output as JSON
-  --session <name>     Use specific session
+  --json               Output as JSON (selector, candidates, fallbacks)
+  --into <name>        Store the best selector in a variable
 
 Examples:
-  agent-browser snapshot
-  agent-browser snapshot -i
-  agent-browser snapshot -i -C         # Interactive + cursor-interactive elements
-  agent-browser snapshot --compact --depth 5
-  agent-browser snapshot -s "#main-content"
+  agent-browser selectors suggest @e3
+  agent-browser selectors suggest "#checkout button" --json
+  agent-browser selectors suggest @e3 --into buy
+  agent-browser click "{{buy}}"
+  agent-browser selectors heal "#old-checkout-button"
 "##
         }
-
-        // === Eval ===
-        "eval" => {
+        "diff" => {
             r##"
-agent-browser eval - Execute JavaScript
+agent-browser diff - Compare two saved page states
 
-Usage: agent-browser eval [options] <script>
+Usage: agent-browser diff --before <file> --after <file> [options]
 
-Executes JavaScript code in the browser context and returns the result.
+Reports what changed between two saved states of a page, such as
+yesterday's and today's. Runs locally; the browser isn't needed.
+
+Modes (default: from the --before file's extension):
+  text     Line diff of snapshot output or any text file. Snapshot refs
+           ([ref=e12]) are ignored, since every snapshot renumbers them
+  dom      HTML (.html, .htm) from freeze or get html, compared as an
+           outline of elements and text, so formatting doesn't count.
+           Scripts are left out; long attributes and stylesheets are
+           compared by hash
+  visual   Screenshots (.png), pixel by pixel like compare-screenshot
+
+Saved --json output of snapshot or get html is compared by its snapshot
+or html field.
 
 Options:
-  -b, --base64         Decode script from base64 (avoids shell escaping issues)
-  --stdin              Read script from stdin (useful for heredocs/multiline)
+  --before <file>      Earlier state (or the first argument)
+  --after <file>       Later state (or the second argument)
+  --mode <mode>        text, dom or visual
+  --threshold <0-1>    Per-pixel color tolerance for visual (default: 0.1)
+  --diff <path>        Where visual writes the diff image
+                       (default: <after>.diff.png)
+  --exit-code          Exit with 1 when anything changed
+
+Global Options:
+  --json               Output as JSON (changed, added, removed, changes)
+
+Examples:
+  agent-browser snapshot > today.txt
+  agent-browser diff --before yesterday.txt --after today.txt
+  agent-browser diff --before old.html --after new.html --exit-code
+  agent-browser diff --before a.png --after b.png --mode visual
+"##
+        }
+        "pdf" => {
+            r##"
+agent-browser pdf - Save page as PDF
+
+Usage: agent-browser pdf [path]
+
+Saves the current page as a PDF file. If no path is provided, saves to
+~/.browseros/artifacts/<session>/pdfs/.
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser pdf ./page.pdf
+  agent-browser pdf ~/Documents/report.pdf
+"##
+        }
+        "archive" => {
+            r##"
+agent-browser archive - Save the rendered page with its subresources
+
+Usage: agent-browser archive [--format mhtml|warc] [path]
+
+Preserves what the page looked like at this point in the session, as
+evidence or for offline review.
+
+  mhtml  Chrome's single-file snapshot of the rendered page, with its
+         stylesheets, images and frames. Opens in Chrome and Edge.
+  warc   WARC 1.1: every document and subresource the page loaded, plus
+         the rendered DOM and a full-page screenshot as conversion records.
+         Replays in pywb or replayweb.page.
+
+The path can also be given with --out. The format follows a .warc
+extension unless --format says otherwise, and is mhtml by default. If no
+path is provided, saves to ~/.browseros/artifacts/<session>/archives/.
+
+Options:
+  --format <format>    mhtml (default) or warc
+
+Global Options:
+  --out <path>         Where to write the archive
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser archive --out page.mhtml
+  agent-browser archive --format warc --out evidence/checkout.warc
+  agent-browser archive
+"##
+        }
+        "freeze" => {
+            r##"
+agent-browser freeze - Save the page as one self-contained HTML file
+
+Usage: agent-browser freeze [path]
+
+Writes the current DOM, including what has been typed into forms, with
+stylesheets, images and fonts inlined as data: URLs, so the copy opens in
+any browser and can be attached to a report. Scripts and event handlers are
+removed and scripts are blocked, so the copy stays as it was. Links are made
+absolute; frames, and files the browser no longer holds, still point at the
+web. Password fields are left empty.
+
+The path can also be given with --out. If no path is provided, saves to
+~/.browseros/artifacts/<session>/pages/.
+
+Global Options:
+  --out <path>         Where to write the page
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser freeze --out page.html
+  agent-browser freeze reports/checkout.html
+"##
+        }
+
+        // === Snapshot ===
+        "snapshot" => {
+            r##"
+agent-browser snapshot - Get accessibility tree snapshot
+
+Usage: agent-browser snapshot [options]
+
+Returns an accessibility tree representation of the page with element
+references (like @e1, @e2) that can be used in subsequent commands.
+Designed for AI agents to understand page structure.
+
+Options:
+  -i, --interactive    Only include interactive elements
+  -C, --cursor         Include cursor-interactive elements (cursor:pointer, onclick, tabindex)
+  -c, --compact        Remove empty structural elements
+  -d, --depth <n>      Limit tree depth
+  -s, --selector <sel> Scope snapshot to CSS selector
+  --fresh              Walk every frame again; by default frames whose DOM
+                       hasn't changed since the last snapshot reuse its tree
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser snapshot
+  agent-browser snapshot -i
+  agent-browser snapshot -i -C         # Interactive + cursor-interactive elements
+  agent-browser snapshot --compact --depth 5
+  agent-browser snapshot -s "#main-content"
+"##
+        }
+
+        // === Eval ===
+        "eval" => {
+            r##"
+agent-browser eval - Execute JavaScript
+
+Usage: agent-browser eval [options] <script>
+
+Executes JavaScript code in the browser context and returns the result.
+
+Options:
+  --target <id>        Run in a tab or worker from `targets` (must come first)
+  -b, --base64         Decode script from base64 (avoids shell escaping issues)
+  --stdin              Read script from stdin (useful for heredocs/multiline)
 
 Global Options:
   --json               Output as JSON
@@ -945,6 +2351,7 @@ Examples:
   agent-browser eval "window.location.href"
   agent-browser eval "document.querySelectorAll('a').length"
   agent-browser eval -b "ZG9jdW1lbnQudGl0bGU="
+  agent-browser eval --target sw1 "self.registration.scope"
 
   # Read from stdin with heredoc
   cat <<'EOF' | agent-browser eval --stdin
@@ -1109,6 +2516,42 @@ Examples:
   agent-browser mouse wheel -50 0
 "##
         }
+        "input" => {
+            r##"
+agent-browser input - Record and replay raw input
+
+Usage: agent-browser input <record <file>|stop|replay <file> [--speed <n>]>
+
+Records trusted mouse, wheel and keyboard events in the session's pages with
+their exact timing, and replays them with the same pacing. Useful for apps
+that react to how input arrives (hover intent, drag gestures, bot checks),
+not just to the final clicks.
+
+Only the top frame of each page is recorded. Coordinates are viewport
+positions, so replay with the viewport and page the timeline was recorded
+on (both are saved in the file).
+
+Subcommands:
+  record <file>        Start recording; the timeline is written on stop
+  stop                 Stop recording and save the timeline
+  replay <file>        Send the recorded events to the current page
+
+Options:
+  --speed <n>          Replay pacing multiplier (2 = twice as fast)
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser --headed open https://example.com
+  agent-browser input record drag.json
+  # ... interact with the page ...
+  agent-browser input stop
+  agent-browser input replay drag.json
+  agent-browser input replay drag.json --speed 0.5
+"##
+        }
 
         // === Set ===
         "set" => {
@@ -1146,7 +2589,7 @@ Examples:
         }
 
         // === Network ===
-        "network" => {
+        "network" | "net" => {
             r##"
 agent-browser network - Network interception and monitoring
 
@@ -1162,6 +2605,17 @@ Subcommands:
   requests [options]         List captured requests
     --clear                  Clear request log
     --filter <pattern>       Filter by URL pattern
+  capture [options]          Save matching response bodies to a directory
+    --url <pattern>          URL substring, or pattern with * wildcards
+    --out <dir>              Directory for bodies and index.jsonl
+    --max-size <size>        Skip bodies larger than this (e.g. 500kb, 2mb)
+    --mime <types>           Comma-separated MIME types (e.g. application/json,image/*)
+  capture stop               Stop capturing and show how many bodies were saved
+  apis [options]             Summarize observed XHR/fetch endpoints
+    --filter <pattern>       Only endpoints whose URL or GraphQL operation matches
+    --clear                  Forget observed endpoints
+
+The alias `net` works for all subcommands.
 
 Global Options:
   --json               Output as JSON
@@ -1174,6 +2628,9 @@ Examples:
   agent-browser network requests
   agent-browser network requests --filter "api"
   agent-browser network requests --clear
+  agent-browser net capture --url /api/ --out ./captures --mime application/json
+  agent-browser net capture stop
+  agent-browser net apis --filter graphql
 "##
         }
 
@@ -1277,6 +2734,13 @@ Operations:
   close [index]        Close tab (current if no index)
   <index>              Switch to tab by index
 
+Popups:
+  Pages opened with window.open or target="_blank" appear as tabs. Set
+  --on-popup (or AGENT_BROWSER_ON_POPUP) to control what happens:
+    list               Add to the tab list, stay on the current tab (default)
+    follow             Switch to the popup; switch back when it closes
+    block              Close popups as soon as they open
+
 Global Options:
   --json               Output as JSON
   --session <name>     Use specific session
@@ -1289,6 +2753,323 @@ Examples:
   agent-browser tab 2
   agent-browser tab close
   agent-browser tab close 1
+  agent-browser --on-popup follow click @e3   # OAuth popup becomes active
+"##
+        }
+        "targets" => {
+            r##"
+agent-browser targets - List tabs and workers
+
+Usage: agent-browser targets [list]
+
+Lists every tab and the dedicated, shared and service workers running in the
+browser. Pass an id to `eval --target` to run code in that context.
+
+Ids:
+  p<n>                 Tab n
+  w<n>                 Dedicated worker (the tab that started it is shown)
+  sw<n>                Service worker
+  shw<n>               Shared worker (Chromium only)
+
+Worker ids stay the same for as long as the worker runs.
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser targets
+  agent-browser eval --target w1 "self.location.href"
+  agent-browser eval --target sw1 "caches.keys()"
+"##
+        }
+        "audit" => {
+            r##"
+agent-browser audit - Audit the page
+
+Usage: agent-browser audit perf [url]
+       agent-browser audit seo [url] [--links <n>]
+       agent-browser audit security [url]
+
+perf loads the page fresh, the current one or the URL given, and reports
+where the time and bytes went:
+
+  Navigation timing    TTFB, DNS, connect, TLS, DOMContentLoaded and load
+  Vitals               FCP, LCP, CLS and TBT, colored good / needs work / poor
+  Resources            Request count and size by type
+  Largest requests     The ten biggest responses
+
+Vitals are lab approximations from a single load without user input. TBT sums
+the part over 50ms of each long task after first contentful paint. Cached and
+cross-origin responses without Timing-Allow-Origin count as 0 bytes.
+
+seo checks the current page, or opens the URL given, and lists errors and
+warnings for:
+
+  Metadata             Title, meta description, canonical link, robots meta
+  Headings             A single h1 and no skipped levels
+  Links                A sample of internal links, requested with the page's
+                       cookies; 4xx, 5xx and failed requests count as broken
+  Structured data      JSON-LD blocks that don't parse or have no @type
+
+security loads the page fresh and reports:
+
+  Headers              CSP, HSTS and X-Frame-Options, with weak settings
+                       such as 'unsafe-inline' scripts or a short max-age
+  Cookies              Secure, HttpOnly and SameSite for each cookie
+  Mixed content        http requests from an https page; scripts, styles
+                       and fetches are errors, images and media warnings
+  Third parties        Every other site contacted during the load
+
+Options:
+  --links <n>          Internal links to check, spread over the page
+                       (default: 20, 0 to skip)
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser audit perf
+  agent-browser audit perf example.com
+  agent-browser audit perf --json | jq .vitals
+  agent-browser audit seo example.com/blog --links 50
+  agent-browser audit security example.com
+"##
+        }
+        "clock" => {
+            r##"
+agent-browser clock - Control the page's clock
+
+Usage:
+  agent-browser clock set <time> [--tick] [--timezone <tz>]
+  agent-browser clock advance <duration>
+  agent-browser clock resume
+
+Replaces Date, performance.now() and timers in the session's pages with a
+fake clock, so trials, countdowns and schedules can be tested at any time.
+
+  set <time>           Move the clock to <time>: ISO 8601 (2024-06-01T10:00:00Z)
+                       or epoch milliseconds. Paused there unless --tick
+    --tick             Let time run on from <time>
+    --timezone <tz>    Emulate an IANA timezone (Europe/Berlin) too; a time
+                       without an offset is read in it
+  advance <duration>   Run the clock forward (30s, 10m, 2h, 14d), firing the
+                       timers that fall due
+  resume               Let a paused clock tick again
+
+The clock stays installed for the session. Timers a page started before
+`clock set` keep real time, so reload afterwards to run the whole page on it.
+--timezone uses CDP and needs Chromium.
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser clock set 2024-06-01T10:00:00Z
+  agent-browser clock set 2024-06-01T09:00 --timezone Asia/Tokyo --tick
+  agent-browser clock advance 14d
+  agent-browser clock resume
+"##
+        }
+        "schedule" => {
+            r##"
+agent-browser schedule - Run commands on a recurring schedule
+
+Usage:
+  agent-browser schedule add "<cron>" <command...>
+  agent-browser schedule list
+  agent-browser schedule remove <id>
+  agent-browser schedule logs <id>
+
+The session's daemon runs the command at the times the cron expression
+matches, exactly as if it were typed after `agent-browser`. Each run uses a
+session of its own (<session>-schedule-<id>) with a fresh browser, so jobs
+never disturb interactive use.
+
+  add "<cron>" <command...>  Save a job. <cron> is minute hour day-of-month
+                             month day-of-week in local time (e.g. "0 * * * *",
+                             "*/15 9-17 * * 1-5") or @hourly, @daily, @weekly
+  list                       Show jobs, their next run and how the last one went
+  remove <id>                Delete a job
+  logs <id>                  Print the output of the job's latest run
+
+A job still running when its next time comes is skipped, not started twice.
+Each run gets a directory under the session's artifacts
+(schedules/<id>/<time>/) with its output.log; the command runs there, so files
+it writes to relative paths land next to the log. Files named in the command
+are saved with their full path when the job is added.
+
+Jobs are saved to ~/.browseros/schedules/<session>.json and only run while the
+session's daemon is up. They resume when it restarts, and `close` keeps the
+daemon running while it has jobs.
+
+Examples:
+  agent-browser schedule add "0 * * * *" run check-inventory.ab
+  agent-browser schedule add "*/10 * * * *" run checks/ --reporter junit --output report.xml
+  agent-browser schedule list
+  agent-browser schedule logs 1
+  agent-browser schedule remove 1
+"##
+        }
+        "lanes" => {
+            r##"
+agent-browser lanes - Show the queue for a browser shared by sessions
+
+Usage:
+  agent-browser lanes
+
+Shows the limits set with --max-concurrent and --action-rate, how many
+commands are running on the browser from every session attached to it, how
+many sessions are waiting for a turn, and how many of this session's
+commands are. Also counts the commands that had to wait, and for how long.
+
+Sessions attached to one browser (--cdp with the same endpoint, or
+--existing with the same BrowserOS profile) share its limits: each session
+holds one place in the queue at a time and the oldest place goes first, so a
+session with many commands waiting can't starve the others. With the metrics
+port set (AGENT_BROWSER_METRICS_PORT), the same numbers are served as
+agent_browser_lane_* series.
+
+Examples:
+  agent-browser --cdp 9222 --max-concurrent 2 --action-rate 10/s snapshot
+  agent-browser --cdp 9222 lanes
+"##
+        }
+        "cancel" => {
+            r##"
+agent-browser cancel - Stop the commands running in this session
+
+Usage:
+  agent-browser cancel
+
+Answers every command the session's daemon is working on with E_CANCELLED
+(exit status 130), then stops a page load in progress and lets go of any mouse
+button or modifier key an interrupted action held, so the next command starts
+from a settled page. A Playwright call already waiting for an element can't be
+interrupted; it gives up at its action timeout and its result is dropped.
+
+Pressing Ctrl-C while a command runs does the same for that command. During
+`run`, Ctrl-C stops the step in flight, skips the rest and still writes the
+report, trace and uploads for the steps that ran. Press Ctrl-C again to exit
+at once.
+
+Examples:
+  agent-browser cancel
+  agent-browser --session scraper cancel
+"##
+        }
+        "jobs" => {
+            r##"
+agent-browser jobs - Show the daemon's job queue
+
+Usage:
+  agent-browser jobs
+
+With AGENT_BROWSER_JOBS_PORT set when the daemon starts, it accepts `run`
+scripts over HTTP and runs them by priority, a few at a time. Each job runs in
+a session of its own (<session>-job-<id>) and a directory under the session's
+artifacts (jobs/<id>/) holding script.ab, output.log and any files the script
+wrote to relative paths. `jobs` prints where the API listens and every job.
+
+  POST   /jobs                       Submit {"script", "priority"?, "name"?, "args"?}
+  GET    /jobs                       List jobs
+  GET    /jobs/<id>                  Status of one job
+  DELETE /jobs/<id>                  Cancel a queued job, or stop a running one
+                                     mid-step (its reports are still written)
+  GET    /jobs/<id>/logs[?follow=1]  Output so far, or streamed until the job ends
+  GET    /jobs/<id>/artifacts        Files in the job's directory
+  GET    /jobs/<id>/artifacts/<path> Download one of them
+
+Higher priorities run first; equal ones run in the order submitted. `args` are
+passed to `run`, e.g. ["--reporter", "junit", "-o", "report.xml"]. POST needs
+Content-Type: application/json, and requests carrying an Origin header are
+refused so web pages can't submit jobs. Jobs live in memory and the last 100
+finished ones are kept; `close` leaves a daemon serving the API running.
+
+With AGENT_BROWSER_POOL_SIZE set too, the daemon keeps that many headless
+browsers launched, and each job attaches to one over CDP instead of launching
+its own, which takes its first command from seconds to milliseconds. After the
+job the browser is closed and a fresh one launched in its place, or with
+AGENT_BROWSER_POOL_RECYCLE=reuse put back with a single blank tab (cookies and
+cache carry over). Idle browsers older than AGENT_BROWSER_POOL_MAX_AGE are
+replaced.
+
+Environment:
+  AGENT_BROWSER_JOBS_PORT         Serve the job API on this port
+  AGENT_BROWSER_JOBS_HOST         Address to bind (default: 127.0.0.1)
+  AGENT_BROWSER_JOBS_TOKEN        Require Authorization: Bearer <token>
+  AGENT_BROWSER_JOBS_CONCURRENCY  Jobs run at once (default: 2)
+  AGENT_BROWSER_POOL_SIZE         Warm browsers kept for jobs (default: none)
+  AGENT_BROWSER_POOL_MAX_AGE      Replace idle browsers older than this, in ms
+                                  (default: 1800000, 30 minutes)
+  AGENT_BROWSER_POOL_RECYCLE      close (default) or reuse browsers after a job
+
+Examples:
+  AGENT_BROWSER_JOBS_PORT=8400 agent-browser --session api jobs
+  curl -s localhost:8400/jobs -H 'Content-Type: application/json' \
+    -d '{"script": "open example.com\nscreenshot home.png", "priority": 1}'
+  curl -s 'localhost:8400/jobs/1/logs?follow=1'
+  curl -s localhost:8400/jobs/1/artifacts/home.png -o home.png
+"##
+        }
+        "tls" => {
+            r##"
+agent-browser tls info - Inspect a site's certificate
+
+Usage: agent-browser tls info <url>
+
+Connects to the host and shows the certificate chain it serves, leaf first:
+subject, issuer, expiry, subject alternative names and SHA-256 fingerprint.
+The chain is checked against the system roots plus any --extra-ca, and the
+reason is shown when it isn't trusted.
+
+Launch options for internal sites:
+  --extra-ca <pem>                  Trust the CA certificates in these PEM
+                                    files (comma-separated) in addition to the
+                                    system roots
+  --ignore-cert-errors-for <hosts>  Skip certificate checks for these hosts
+                                    only; *.example.com covers subdomains
+
+Unlike --ignore-https-errors, both leave certificate checks on for every
+other site. Requests that need them are served by the daemon instead of the
+browser, which turns off the HTTP cache for those hosts.
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser tls info example.com
+  agent-browser tls info intranet.local:8443
+  agent-browser --extra-ca ~/corp-root.pem open https://wiki.corp.example
+  agent-browser --ignore-cert-errors-for dev.local open https://dev.local
+"##
+        }
+        "robots" => {
+            r##"
+agent-browser robots - Check a URL against robots.txt
+
+Usage: agent-browser robots <url>
+
+Fetches the site's robots.txt and reports whether agent-browser may open the
+URL, along with the rule that decided it. Groups for `agent-browser` take
+precedence over `*`. A missing robots.txt allows everything; one that can't
+be fetched disallows everything.
+
+With --respect-robots, `open` and navigations started by the page are blocked
+when robots.txt disallows them. Add --robots-override to a single command to
+open the page anyway.
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser robots example.com/private
+  agent-browser --respect-robots open example.com/search
+  agent-browser --respect-robots --robots-override open example.com/search
 "##
         }
 
@@ -1372,7 +3153,8 @@ Record a trace for debugging with Playwright Trace Viewer.
 
 Operations:
   start [path]         Start recording trace
-  stop [path]          Stop recording and save trace
+  stop [path]          Stop recording and save trace (default: the session's
+                       artifacts directory)
 
 Global Options:
   --json               Output as JSON
@@ -1392,7 +3174,7 @@ Examples:
 agent-browser record - Record browser session to video
 
 Usage: agent-browser record start <path.webm> [url]
-       agent-browser record stop
+       agent-browser record stop [--upload <s3://...|gs://...>]
        agent-browser record restart <path.webm> [url]
 
 Record the browser to a WebM video file using Playwright's native recording.
@@ -1402,6 +3184,9 @@ If no URL is provided, automatically navigates to your current page.
 Operations:
   start <path> [url]     Start recording (defaults to current URL if omitted)
   stop                   Stop recording and save video
+    --upload <dest>        Then upload it to s3://bucket/prefix or
+                           gs://bucket/prefix (default: upload key in the
+                           user config)
   restart <path> [url]   Stop current recording (if any) and start a new one
 
 Global Options:
@@ -1423,6 +3208,39 @@ Examples:
   agent-browser record restart ./take2.webm
 "##
         }
+        "screencast" => {
+            r##"
+agent-browser screencast - Capture viewport frames continuously
+
+Usage: agent-browser screencast start [<file|pipe>] [options]
+       agent-browser screencast stop
+
+Streams the viewport as the browser paints it, for vision models and live
+previews. With a file or named pipe, the frames are appended back to back as
+raw JPEG (or PNG) images, which ffmpeg reads with -f image2pipe. Without one,
+frames go to the clients of the stream server (AGENT_BROWSER_STREAM_PORT).
+Frames are decoded from CDP once and written as bytes; when the reader falls
+behind, frames are dropped rather than buffered.
+
+Options:
+  --quality <0-100>    JPEG quality (default: 80)
+  --scale <0.05-1>     Frame size relative to the viewport (default: up to 1280x720)
+  --fps <n>            Most frames per second (default: every paint)
+  --format <fmt>       jpeg (default) or png
+
+`screencast stop` reports the frames and bytes written and how many were
+dropped. Stream clients that connect with ?binary=1 get each frame as a
+binary message: a 4-byte big-endian length, that many bytes of JSON
+metadata, then the image. AGENT_BROWSER_STREAM_QUALITY, _SCALE and _FPS set
+the same options for the stream server.
+
+Examples:
+  mkfifo /tmp/frames
+  ffmpeg -f image2pipe -framerate 5 -i /tmp/frames out.mp4 &
+  agent-browser screencast start /tmp/frames --scale 0.5 --fps 5
+  agent-browser screencast stop
+"##
+        }
 
         // === Console/Errors ===
         "console" => {
@@ -1485,74 +3303,483 @@ Examples:
 "##
         }
 
-        // === State ===
-        "state" => {
+        // === HAR replay ===
+        "replay" => {
             r##"
-agent-browser state - Save/load browser state
-
-Usage: agent-browser state <operation> <path>
-
-Save or restore browser state (cookies, localStorage, sessionStorage).
+agent-browser replay - Serve responses from a recorded HAR file
 
-Operations:
-  save <path>          Save current state to file
-  load <path>          Note: State must be loaded at browser launch via --state flag
+Usage: agent-browser replay --har <file> [url] [options]
 
-Applying State:
-  Use --state flag when launching browser to load saved state:
-  agent-browser --state ./auth-state.json open https://example.com
+Answers the session's requests from a HAR file (e.g. exported from DevTools)
+instead of the live site, so a script can be re-run deterministically. Replay
+applies to every tab and lasts until the session is closed.
 
-  Or set AGENT_BROWSER_STATE environment variable.
+Options:
+  --har <file>                 HAR file to serve responses from
+  --not-found <abort|fallback> Requests missing from the HAR: abort them
+                               (default) or send them to the network
+  --match <pattern>            Only replay requests whose URL matches this glob
 
 Global Options:
   --json               Output as JSON
   --session <name>     Use specific session
 
 Examples:
-  agent-browser state save ./auth-state.json
-  agent-browser --state ./auth-state.json open https://example.com
+  agent-browser replay --har checkout.har https://shop.example.com
+  agent-browser replay --har api.har --match "**/api/**" --not-found fallback
+  agent-browser --session debug replay --har failing-run.har example.com
 "##
         }
 
-        // === Session ===
-        "session" => {
+        // === WebSockets ===
+        "ws" => {
             r##"
-agent-browser session - Manage sessions
-
-Usage: agent-browser session [operation]
+agent-browser ws - Inspect WebSocket traffic
 
-Manage isolated browser sessions. Each session has its own browser
-instance with separate cookies, storage, and state.
+Usage: agent-browser ws <list|tail|export|clear> [args]
 
-Operations:
-  (none)               Show current session name
-  list                 List all active sessions
+Records WebSocket handshakes and frames from every page in the session
+(Chromium only). The most recent 1000 frames per socket are kept, with
+payloads longer than 4 KB truncated.
 
-Environment:
-  AGENT_BROWSER_SESSION    Default session name
+Subcommands:
+  list                 List sockets with their state and frame counts (default)
+  tail <id> [-n <n>]   Show the last n frames (default 20) of a socket, by id
+                       (e.g. ws1) or URL substring
+  export <path>        Write all sockets, handshake headers and frames as JSON
+  clear                Forget recorded sockets
 
 Global Options:
   --json               Output as JSON
   --session <name>     Use specific session
 
 Examples:
-  agent-browser session
-  agent-browser session list
-  agent-browser --session test open example.com
+  agent-browser ws list
+  agent-browser ws tail ws1 -n 50
+  agent-browser ws tail chat.example.com
+  agent-browser ws export sockets.json
 "##
         }
 
-        // === Install ===
-        "install" => {
+        // === Stats ===
+        "memory" => {
             r##"
-agent-browser install - Install browser binaries
+agent-browser memory - Show each tab's JS heap and the tabs recycled
 
-Usage: agent-browser install [--with-deps]
+Usage: agent-browser memory
 
-Downloads and installs browser binaries required for automation.
+Measures every tab's JS heap over CDP and lists the tabs recycled for going
+past --max-tab-memory, with how much they were using.
 
-Options:
-  -d, --with-deps      Also install system dependencies (Linux only)
+With --max-tab-memory set, the daemon measures the tabs between commands (at
+most every 5 seconds) and recycles the ones over the limit, so a long crawl
+doesn't grow until the browser runs out of memory. --recycle-mode picks how:
+  reload               Reload the page (default)
+  recreate             Open the same URL in a fresh tab that takes the old
+                       tab's number, then close the old one. Also frees what
+                       a reload keeps, like workers and leaked detached DOM
+Either way the page's state (form input, scroll position, in-page data) is
+lost, so set the limit well above what a page normally uses.
+
+Global Options:
+  --max-tab-memory <size>  Recycle tabs past this much JS heap, e.g. 1G; off stops it
+                           (or AGENT_BROWSER_MAX_TAB_MEMORY, config max-tab-memory)
+  --recycle-mode <mode>    reload or recreate (or AGENT_BROWSER_RECYCLE_MODE)
+  --json                   Output as JSON
+
+Examples:
+  agent-browser --max-tab-memory 1G --recycle-mode recreate open example.com
+  agent-browser memory
+"##
+        }
+        "stats" => {
+            r##"
+agent-browser stats - Show bandwidth and request counts for the session
+
+Usage: agent-browser stats [--reset]
+
+Counts every request made by the session's pages since the browser launched
+(or since the last --reset):
+  requests             Requests started
+  failed               Requests that failed or were aborted
+  blocked              Requests blocked by --adblock
+  throttled            Requests held back by --rate / --nav-rate, and for how long
+  cache hits           Responses served from the browser cache (Chromium only)
+  received / sent      Bytes transferred, headers included
+  top hosts            Hosts that received the most bytes
+
+Options:
+  --reset              Print the current counts, then start over from zero
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser stats
+  agent-browser stats --reset
+  agent-browser --json stats
+"##
+        }
+
+        // === CDP ===
+        "cdp" => {
+            r##"
+agent-browser cdp - Send a raw Chrome DevTools Protocol message
+
+Usage: agent-browser cdp <Domain.method> [--params <json>] [--events <names>] [--wait <duration>]
+
+Sends one protocol message on the session's CDP connection to the current page
+and prints the result. Use it for protocol features the CLI doesn't wrap yet.
+Chromium only.
+
+Options:
+  --params <json>      Method parameters, as a JSON object
+  --events <names>     Comma-separated events to collect, e.g. Network.responseReceived
+  --wait <duration>    How long to collect events after the call (default: 1s with --events)
+
+Events are printed after the result, one per line. Domains the CLI doesn't use
+itself may need enabling first (e.g. cdp Performance.enable).
+
+Global Options:
+  --json               Output as JSON ({ result, events })
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser cdp Browser.getVersion
+  agent-browser cdp Performance.enable
+  agent-browser cdp Performance.getMetrics
+  agent-browser cdp Emulation.setCPUThrottlingRate --params '{"rate":4}'
+  agent-browser cdp Page.reload --events Page.loadEventFired --wait 5s
+"##
+        }
+
+        "cdp-listen" => {
+            r##"
+agent-browser cdp-listen - Stream DevTools protocol events as JSON lines
+
+Usage: agent-browser cdp-listen <Domain.event>... [--follow] [--count <n>] [--timeout <duration>]
+
+Prints each matching event from the current page as one JSON object per line:
+  {"method":"Page.frameNavigated","params":{...},"timestamp":1767225600000}
+
+The events' domains are enabled first. Without --follow, exits after the first
+event (or --count events) and fails with E_TIMEOUT after 30s.
+
+Options:
+  --follow             Stream until interrupted
+  --count <n>          Exit after n events
+  --timeout <duration> Give up after this long (default: 30s without --follow)
+
+Examples:
+  agent-browser cdp-listen Network.responseReceived Page.frameNavigated --follow
+  agent-browser cdp-listen Page.loadEventFired --timeout 10s
+  agent-browser cdp-listen Network.requestWillBeSent --follow | jq -r .params.request.url
+"##
+        }
+
+        "watch" => {
+            r##"
+agent-browser watch - Stream DOM mutations as JSON lines
+
+Usage: agent-browser watch --selector <css> [--events <types>] [--follow] [--count <n>] [--timeout <duration>]
+
+Watches the current page for changes inside elements matching the CSS
+selector, and for matching elements being added or removed. Prints each
+change as one JSON object per line:
+  {"type":"childList","target":"ul#feed","added":[{"node":"li.msg","text":"Hi"}],"removed":[],...}
+  {"type":"attributes","target":"div.status","attribute":"class","oldValue":"busy","value":"idle",...}
+  {"type":"characterData","target":"span.count","oldValue":"3","value":"4",...}
+
+The watch survives navigations. Without --follow, exits after the first
+change (or --count changes) and fails with E_TIMEOUT after 30s.
+
+Options:
+  --selector <css>     Elements to watch (required)
+  --events <types>     Comma-separated: childList, attributes, characterData (default: all)
+  --follow             Stream until interrupted
+  --count <n>          Exit after n changes
+  --timeout <duration> Give up after this long (default: 30s without --follow)
+
+Examples:
+  agent-browser watch --selector "#chat .messages" --events childList --follow
+  agent-browser watch --selector .build-status --events attributes,characterData --timeout 5m
+  agent-browser watch --selector "#feed" --follow | jq -r '.added[]?.text'
+"##
+        }
+
+        "poll" => {
+            r##"
+agent-browser poll - Read an element on an interval and stream each reading
+
+Usage: agent-browser poll --selector <css> [--attr <name>] [--every <duration>]
+                          [--until-changed] [--until-match <regex>] [--max <n>] [--reload]
+
+Reads the text of the first element matching the selector (or one of its
+attributes) right away and then every --every, printing each reading as one
+JSON object per line:
+  {"check":3,"value":"$41.99","changed":true,"url":"https://...","timestamp":1767225600000}
+
+value is null while the element is missing. Runs until a stop condition holds,
+--max readings have been taken, or it is interrupted. If --max runs out before
+a stop condition holds, exits with E_TIMEOUT.
+
+Options:
+  --selector <css>       Element to read (a ref like @e3 works too) (required)
+  --attr <name>          Read this attribute instead of the text
+  --every <duration>     Time between readings (default: 30s)
+  --until-changed        Stop once the value differs from the first reading
+  --until-match <regex>  Stop once the value matches
+  --max <n>              Stop after n readings
+  --reload               Reload the page before each reading after the first
+
+Examples:
+  agent-browser poll --selector .price --every 30s --until-changed --max 20
+  agent-browser poll --selector "#build" --attr data-state --until-match "^(passed|failed)$" --every 10s
+  agent-browser poll --selector .stock --every 5m --reload | jq -r .value
+  agent-browser poll --selector .price --every 1h --max 24 --output-format parquet --out prices.parquet
+  agent-browser poll --selector .price --every 1h --sink sqlite://history.db#prices
+"##
+        }
+
+        // === Dismiss overlays ===
+        "dismiss-overlays" => {
+            r##"
+agent-browser dismiss-overlays - Close cookie banners and similar overlays
+
+Usage: agent-browser dismiss-overlays
+
+Clicks through interstitials that block the page:
+  consent              Cookie and privacy banners (accepts)
+  newsletter           Newsletter and discount sign-up modals (declines)
+  app-banner           "Open in app" banners (declines)
+
+Known consent platforms are matched by selector. Other overlays are found by
+text, but only inside dialogs and fixed/sticky containers.
+
+Use --auto-dismiss-overlays (or AGENT_BROWSER_AUTO_DISMISS_OVERLAYS) to run
+this automatically after navigation and before actions and snapshots.
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser dismiss-overlays
+  agent-browser --auto-dismiss-overlays open example.com
+"##
+        }
+
+        // === Inspect ===
+        "inspect" => {
+            r##"
+agent-browser inspect - Show element metadata
+
+Usage: agent-browser inspect <selector>
+
+Prints debugging metadata for a single element: bounding box, a subset of
+computed styles, attributes, visibility and occlusion, scroll position, and
+which event listeners are attached.
+
+Occlusion is checked at the element's center point. If another element sits
+on top, it is reported so you can tell why a click may not land.
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser inspect "#submit"
+  agent-browser inspect @e5
+  agent-browser inspect @e5 --json
+"##
+        }
+
+        // === State ===
+        "state" => {
+            r##"
+agent-browser state - Save/load browser state
+
+Usage: agent-browser state <operation> <path>
+
+Save or restore browser state (cookies, localStorage, sessionStorage).
+
+Operations:
+  save <path>          Save current state to file
+  load <path>          Note: State must be loaded at browser launch via --state flag
+
+Applying State:
+  Use --state flag when launching browser to load saved state:
+  agent-browser --state ./auth-state.json open https://example.com
+
+  Or set AGENT_BROWSER_STATE environment variable.
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser state save ./auth-state.json
+  agent-browser --state ./auth-state.json open https://example.com
+"##
+        }
+
+        // === Session ===
+        "var" => {
+            r##"
+agent-browser var - Session variables
+
+Usage:
+  agent-browser var set <name> <value> [--parse-json]
+  agent-browser var get <name>
+  agent-browser var list
+  agent-browser var unset <name>
+  agent-browser var clear
+
+Each session has its own variables, kept in ~/.browseros/vars/<session>.json
+until cleared. `{{name}}` in the arguments of any command, and so in every
+step of a `run` script, is replaced with the variable's value. Names that
+are not set are left as written.
+
+`--into <name>` on any command stores what it returned: the result of
+`eval`, the text of `get text`, the value of `get value` or `get attr`, the
+count of `get count`, the URL of `get url`, or the best selector of
+`selectors suggest`.
+
+Names use letters, digits, _, - and `.`. Values are strings unless set with
+--parse-json, which stores numbers, booleans, lists and objects as such
+(substituted as JSON).
+
+Examples:
+  agent-browser var set user ada@example.com
+  agent-browser fill "#email" "{{user}}"
+  agent-browser get text ".order-id" --into order
+  agent-browser open "https://example.com/orders/{{order}}"
+  agent-browser eval "document.querySelectorAll('li').length" --into items
+"##
+        }
+        "session" => {
+            r##"
+agent-browser session - Manage sessions
+
+Usage: agent-browser session [operation]
+
+Manage isolated browser sessions. Each session has its own browser
+instance with separate cookies, storage, and state.
+
+Operations:
+  (none)               Show current session name
+  list                 List all active sessions
+  save <file>          Save the open tabs (URL, scroll position,
+                       sessionStorage), cookies and localStorage
+  restore <file>       Reopen the saved tabs and reapply their storage
+
+`save` and `restore` let a long task resume roughly where it stopped after
+the browser or machine restarts. Tabs open after those already open (a lone
+blank tab is reused), and the saved active tab becomes active again. State
+that only lives in the page's memory, such as unsaved form input, is lost.
+The file holds cookies, so it is written readable only by you.
+
+Environment:
+  AGENT_BROWSER_SESSION    Default session name
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser session
+  agent-browser session list
+  agent-browser --session test open example.com
+  agent-browser session save ./task.json
+  agent-browser session restore ./task.json
+"##
+        }
+
+        // === Config ===
+        "config" => {
+            r##"
+agent-browser config - Manage configuration files
+
+Usage: agent-browser config <operation> [args]
+
+Defaults can be stored in TOML files instead of passing flags every time:
+  ~/.browseros/config.toml   User config
+  .agent-browser.toml        Project config (current directory or a parent)
+
+Precedence: flags > environment variables > project config > user config
+
+Keys that run commands, send data elsewhere or weaken TLS (executable-path,
+browser path:<exe>, on-*, webhook*, upload, extra-ca, ignore-cert-errors-for)
+are only read from the user config, so a checked-out project can't set them.
+
+Operations:
+  list                       Show effective values and where they come from
+  get <key>                  Print the effective value of a key
+  set <key> <value>          Write a key to the user config
+  set <key> <value> --project
+                             Write a key to the project config
+  edit [--project]           Open the config file in $VISUAL / $EDITOR
+
+Keys:
+  executable-path            Browser executable path (user config only)
+  profile                    Persistent browser profile directory
+  headed                     Show browser window (true/false)
+  timeout                    Default action timeout (e.g. 30s, 15000)
+  proxy                      Proxy server URL
+  proxy-bypass               Hosts to bypass the proxy for
+  output                     Output format: text or json
+
+Examples:
+  agent-browser config set headed true
+  agent-browser config set timeout 30s --project
+  agent-browser config get proxy
+  agent-browser config list
+"##
+        }
+
+        // === Completions ===
+        "completions" => {
+            r##"
+agent-browser completions - Print a shell completion script
+
+Usage: agent-browser completions <bash|zsh|fish|powershell>
+
+Completes commands, subcommands and global flags. Session names (--session)
+and BrowserOS profile names (--existing, --new) are read from disk each time
+you press Tab, so new sessions show up without regenerating the script.
+
+Install:
+  bash        agent-browser completions bash > /etc/bash_completion.d/agent-browser
+  zsh         agent-browser completions zsh > "${fpath[1]}/_agent-browser"
+  fish        agent-browser completions fish > ~/.config/fish/completions/agent-browser.fish
+  powershell  agent-browser completions powershell >> $PROFILE
+
+Examples:
+  source <(agent-browser completions bash)
+  agent-browser completions zsh > ~/.zfunc/_agent-browser
+"##
+        }
+
+        // === Install ===
+        "install" => {
+            r##"
+agent-browser install - Install browser binaries
+
+Usage: agent-browser install [--with-deps] [--keep-quarantine] [--extract-appimage] [--no-delta]
+
+Downloads and installs browser binaries required for automation.
+
+On macOS the quarantine flag is removed from the copied BrowserOS.app, its
+code signature is verified, and Gatekeeper is asked whether it will run it,
+so a blocked first launch is reported here instead of failing silently.
+
+Options:
+  -d, --with-deps      Also install system dependencies (Linux only)
+  --keep-quarantine    Leave the quarantine flag on BrowserOS.app (macOS only)
+  --extract-appimage   Unpack the AppImage into ~/.browseros/versions so it runs
+                       without FUSE (Linux only; automatic when FUSE is missing)
+  --no-delta           Always download the full package (see: agent-browser upgrade --help)
 
 Examples:
   agent-browser install
@@ -1560,6 +3787,533 @@ Examples:
 "##
         }
 
+        "which" => {
+            r##"
+agent-browser which - Show which browser executable launches use
+
+Usage: agent-browser which
+
+Prints the executable the session's browser is launched from and every place
+that was looked in, in order, marking those that exist:
+
+  1. --executable-path, then AGENT_BROWSER_EXECUTABLE_PATH (used even if
+     the file is missing), then --browser path:<exe>
+  2. BrowserOS, Chrome, Edge and Chromium, in that order, or only the one
+     --browser names. Each is looked for in:
+     Windows: its App Paths registry entry (HKCU, then HKLM),
+     %LOCALAPPDATA%, then Program Files
+     macOS: /Applications and ~/Applications
+     Linux: its package install directory, then PATH
+     BrowserOS also where `agent-browser install` puts it (~/.browseros)
+
+If none exist, Chromium launches use Playwright's bundled Chromium.
+
+Global Options:
+  --browser <name>     Only look for this browser
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser which
+  agent-browser --browser edge which
+  agent-browser --executable-path /opt/BrowserOS which
+"##
+        }
+        "init" => {
+            r##"
+agent-browser init - Set up agent-browser on this machine
+
+Usage: agent-browser init [--yes] [--no-smoke-test] [install options]
+
+1. Installs BrowserOS if it isn't installed
+2. Creates a profile in ~/.browseros/profiles/<name> and sets it as `profile`
+   in the user config (kept if one is already configured)
+3. Runs a smoke test in the `init` session: open https://example.com,
+   snapshot -i, and a screenshot to ~/.browseros/init-smoke-test.png
+4. Prints a quick start
+
+A failed smoke test exits non-zero with the step that failed.
+
+Options:
+  -y, --yes            Take every default without asking (also with --json,
+                       or when stdin is not a terminal)
+  --no-smoke-test      Skip the smoke test
+
+Examples:
+  agent-browser init
+  agent-browser init --yes --json
+"##
+        }
+        "verify" => {
+            r##"
+agent-browser verify - Check that agent-browser works on this machine
+
+Usage: agent-browser verify [global options]
+
+Runs these stages headless in a separate `verify` session, stopping at the
+first that fails, then closes the session:
+
+  launch        open a data: URL (starts the browser)
+  javascript    eval a script on that page
+  https         open https://example.com
+  screenshot    take a screenshot and check the file was written
+
+Each stage prints its duration. A failed stage exits non-zero with its error
+code and a hint about the usual causes, so provisioning scripts and CI jobs
+can gate on it. Global options such as --browser, --executable-path, --proxy
+and --args apply to every stage; --headed and --session are ignored.
+
+Global Options:
+  --json               Output the stages as JSON
+
+Examples:
+  agent-browser verify
+  agent-browser --browser chrome verify
+  agent-browser verify --json
+"##
+        }
+        "upgrade" => {
+            r##"
+agent-browser upgrade - Update BrowserOS to the version this release pins
+
+Usage: agent-browser upgrade [install options]
+
+Installs the BrowserOS version this agent-browser release pins, unless it is
+already installed.
+
+When the package of an earlier version is still in ~/.browseros/downloads and
+zsync is installed, only the blocks that changed are downloaded, using the
+.zsync file published next to the package. Without either, or if the release
+has no .zsync file, the full package is downloaded. `install` does the same.
+Older packages are deleted once the new one is installed.
+
+Options:
+  --no-delta           Always download the full package
+  Any other option of `agent-browser install`
+
+Examples:
+  agent-browser upgrade
+  agent-browser upgrade --no-delta
+"##
+        }
+        "doctor" => {
+            r##"
+agent-browser doctor - Check the environment for problems
+
+Usage: agent-browser doctor
+
+Checks what a session needs before it starts, without starting one:
+  Daemon     The socket directory is writable, and the session's daemon,
+             if running, accepts connections
+  Browser    The executable a launch would use (--executable-path,
+             --browser path:<exe>, or installed BrowserOS) exists and can run
+  Home       ~/.browseros is writable and not writable by every user
+  Disk       How much ~/.browseros holds and how much space is free; warns
+             when the quota is 90% used or exceeded, or less than 1 GB is free
+
+The quota is set with the quota config key or AGENT_BROWSER_QUOTA (e.g. 10G).
+`agent-browser install` checks free space and the quota before downloading,
+and exits with E_DISK_SPACE (15) if the package won't fit.
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Check this session's daemon
+
+Examples:
+  agent-browser doctor
+  agent-browser --session work doctor --json
+  agent-browser config set quota 10G
+"##
+        }
+        "status" => {
+            r##"
+agent-browser status - Show session and disk status
+
+Usage: agent-browser status
+
+Shows whether the session's daemon is running, and the same disk usage and
+quota warnings as `agent-browser doctor`.
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser status
+  agent-browser --session work status --json
+"##
+        }
+        "print-dockerfile" => {
+            r##"
+agent-browser print-dockerfile - Print a Dockerfile for running agent-browser
+
+Usage: agent-browser print-dockerfile > Dockerfile
+
+Prints a Dockerfile for a Debian image with this version of agent-browser,
+BrowserOS and every shared library it needs already installed.
+
+In a container (Docker, Podman, Kubernetes, containerd or LXC) the browser is
+launched with --no-sandbox, and with --disable-dev-shm-usage when /dev/shm is
+smaller than 512 MB, as it is by default in Docker. Set
+AGENT_BROWSER_CONTAINER=1 to force these switches or 0 to turn them off.
+
+Global Options:
+  --json               Output as JSON ({"dockerfile": "..."})
+
+Examples:
+  agent-browser print-dockerfile > Dockerfile
+  docker build -t agent-browser .
+  docker run --rm --shm-size=1g agent-browser open example.com
+"##
+        }
+
+        // === Extensions ===
+        "ext" => {
+            r##"
+agent-browser ext - Manage browser extensions
+
+Usage: agent-browser ext <add|list|remove> [args]
+
+Installed extensions are kept in ~/.browseros/extensions/ and loaded into
+every browser agent-browser launches itself (including BrowserOS --new).
+
+Subcommands:
+  add <dir|crx>        Install an unpacked extension or a .crx file
+    --name <name>      Name to install under (default: file or directory name)
+  list                 List installed extensions
+  remove <name>        Uninstall an extension
+
+Global Options:
+  --json               Output as JSON
+
+Examples:
+  agent-browser ext add ./my-extension
+  agent-browser ext add ublock.crx --name ublock
+  agent-browser ext list
+  agent-browser ext remove ublock
+"##
+        }
+
+        // === Filter lists ===
+        "filters" => {
+            r##"
+agent-browser filters - Manage ad and tracker filter lists
+
+Usage: agent-browser filters <update|list>
+
+Filter lists are kept in ~/.browseros/filters/ and used by --adblock to block
+ad and tracker requests. Any .txt list in EasyList syntax placed in that
+directory is loaded too.
+
+Subcommands:
+  update               Download the latest EasyList and EasyPrivacy
+  list                 List installed filter lists
+
+Global Options:
+  --json               Output as JSON
+
+Examples:
+  agent-browser filters update
+  agent-browser --adblock open example.com
+"##
+        }
+
+        // === Artifacts ===
+        "artifacts" => {
+            r##"
+agent-browser artifacts - Manage screenshots, PDFs, traces and downloads
+
+Usage: agent-browser artifacts <list|open|prune> [options]
+
+Commands that write a file without being given a path (screenshot, pdf,
+trace stop, wait --download) save it under ~/.browseros/artifacts/<session>/.
+Every command that writes a file lists it in the "artifacts" field of its
+--json output.
+
+Subcommands:
+  list                 List files, newest first, with size and age
+  open [file]          Open the directory, or a file in it, in the default app
+  prune                Delete files older than --older-than, then the oldest
+                       until the rest fit in --max-size
+
+Options:
+  --all                Work on every session's artifacts, not just this one
+  --older-than <age>   e.g. 12h, 7d, 2w (default: artifacts-max-age config)
+  --max-size <size>    e.g. 500M, 5G (default: artifacts-max-size config)
+  --dry-run            Report what prune would delete without deleting
+
+With artifacts-max-age or artifacts-max-size set in the config, the policy is
+applied to all sessions whenever a session's browser starts.
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser screenshot && agent-browser artifacts list
+  agent-browser artifacts open
+  agent-browser artifacts prune --all --older-than 7d --max-size 5G
+  agent-browser config set artifacts-max-age 14d
+"##
+        }
+
+        // === Transcript ===
+        "transcript" => {
+            r##"
+agent-browser transcript - Export or replay what an agent did in a session
+
+Usage: agent-browser transcript export [--format jsonl|json] [--out <file>]
+       agent-browser transcript replay <file.jsonl> [--pause-on-step] [--compare-snapshots]
+
+Sessions started with --transcript (or AGENT_BROWSER_TRANSCRIPT, or the
+transcript config key) record every step an agent takes in
+~/.browseros/artifacts/<session>/transcripts/: what it was looking at, what
+it did and what came back. snapshot and screenshot aren't steps; they update
+what the next step saw. Before each step the page is screenshotted to
+step-NNNN.png next to the transcript.
+
+Each exported line is one step:
+  observation          url, the last snapshot and the screenshot's path
+  action               The command as sent to the daemon
+  result               success with data, or error and code
+
+replay runs the recorded actions again in this session and flags each step
+where the live page diverges: the URL before the action differs, or the
+action fails where it succeeded (or the other way round). Where the agent
+took a fresh snapshot, it is taken again with the same options, so refs in
+later actions resolve. It exits 1 if any step diverged.
+
+Export options:
+  --format <format>    jsonl (default, one step per line) or json (an array)
+  --out <file>         Write to a file instead of stdout
+
+Replay options:
+  --pause-on-step      Ask before each step: Enter runs it, s skips, q quits
+  --compare-snapshots  Also flag snapshots that differ from the recorded ones
+                       (refs are ignored), with the changed lines
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser --transcript --session shop open example.com
+  agent-browser --session shop transcript export --format jsonl --out shop.jsonl
+  agent-browser --session debug transcript replay shop.jsonl --compare-snapshots
+"##
+        }
+
+        // === Audit log ===
+        "audit-log" => {
+            r##"
+agent-browser audit-log - Verify or export a session's tamper-evident audit log
+
+Usage: agent-browser audit-log verify [<file.jsonl>]
+       agent-browser audit-log export [--format jsonl|json] [--out <file>]
+
+Sessions started with --audit-log (or AGENT_BROWSER_AUDIT_LOG, or the
+audit-log config key) append every command, main-frame navigation and
+download to ~/.browseros/audit/<session>.jsonl. Once on, it stays on until
+the daemon exits. Secret fields such as passwords and tokens are redacted.
+
+Each entry carries its seq, time, session and type (command, navigation or
+download), plus:
+  prev                 The hash of the entry before it (64 zeros for the first)
+  hash                 SHA-256 of the line as written, without this field
+
+verify checks every hash, link and sequence number, and prints the hash of
+the last entry. It exits with E_AUDIT_CHAIN_BROKEN (16) at the first entry
+that was edited, removed or moved. Removing entries from the end leaves a
+valid chain, so keep the head hash somewhere else to compare against.
+
+Export options:
+  --format <format>    jsonl (default, lines exactly as logged) or json (an array)
+  --out <file>         Write to a file instead of stdout
+
+Global Options:
+  --json               Output as JSON
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser --audit-log --session bank open example.com
+  agent-browser --session bank audit-log verify
+  agent-browser --session bank audit-log export --out bank-audit.jsonl
+  agent-browser audit-log verify bank-audit.jsonl
+"##
+        }
+
+        // === Plugins ===
+        "plugins" => {
+            r##"
+agent-browser plugins - List external subcommands
+
+Usage: agent-browser plugins
+
+Any executable named agent-browser-<name> on PATH can be run as
+`agent-browser <name> [args...]`. Built-in commands always take precedence.
+
+The plugin receives all arguments after its name, plus these variables:
+  AGENT_BROWSER_SESSION    Session of the calling command
+  AGENT_BROWSER_BIN        Path of the agent-browser binary, for calling back
+  AGENT_BROWSER_JSON       Set to 1 when --json was given
+  AGENT_BROWSER_QUIET      Set to 1 when --quiet was given
+
+Its exit status becomes the exit status of agent-browser.
+
+<name>.wasm modules in ~/.browseros/plugins are plugins too. They run
+inside the CLI and can only run commands that navigate, interact with or
+read the page (no eval, cdp, config, upload, screenshot or file paths), so
+they can be used as steps in `run` scripts. They can still read and navigate
+the page, so only install plugins you trust. See the README for the module
+interface.
+
+Examples:
+  agent-browser plugins
+  agent-browser plugins --json
+"##
+        }
+
+        // === Script runner ===
+        "run" => {
+            r##"
+agent-browser run - Run a script of commands as a test
+
+Usage: agent-browser run <script|dir>... [options]
+
+A script is a text file with one agent-browser command per line, written as
+it would be typed after `agent-browser` and quoted like in a shell. Blank
+lines and lines starting with # are ignored. Every step runs with the global
+flags given to `run` (e.g. --session, --headed). The first failing step fails
+the script; the remaining steps are skipped and the exit code is that of the
+first failed step. Directories are searched recursively for *.ab scripts.
+
+Options:
+  -r, --reporter <name>     pretty (default), json, junit or github
+  -o, --output <file>       Write the report to a file instead of stdout
+  --matrix <name>=<a>,<b>   Run each script once per value, replacing ${name}
+                            in the script (repeatable; combinations multiply)
+  --shard <i>/<n>           Run only the i-th of n equal slices of the suite
+  --step-retries <n>        Retry a failing step up to n times
+  --script-retries <n>      Rerun a failing script up to n times
+  --quarantine <file>       Scripts (names or paths, one per line) whose
+                            failures are reported but don't fail the run
+  --trace <file.html|dir>   Write an offline trace: a screenshot, console
+                            and network activity per step, and the script.
+                            One self-contained file for .html, else a directory
+  --upload <dest>           Upload the report, trace and step artifacts to
+                            s3://bucket/prefix or gs://bucket/prefix
+                            (default: upload key in the user config)
+  --resume-from <name>      Skip the steps before `checkpoint <name>` and
+                            restore the state it saved instead
+  --data <file>             Run each script once per row of a .csv (with a
+                            header row) or .ndjson file, with the row's fields
+                            as variables: {{field}} or ${field}
+  --parallel <n>            Spread the runs over n sessions (<session>-1 to
+                            <session>-n), each with its own browser
+  --sink <uri>              Append one row per step to sqlite://path.db#table
+  --capture <when>          Attach a full-page screenshot, the URL, the last
+                            console messages and a trimmed snapshot to
+                            failed steps (on-failure) or every step (always)
+  --no-heal                 Don't retry steps with healed selectors
+  --update-selectors        Write healed selectors back to the scripts
+  --step-timeout <dur>      Kill a step still running after <dur>, retries
+                            and healing included (or AGENT_BROWSER_STEP_TIMEOUT,
+                            config step-timeout)
+  --deadline <dur>          Fail the run's remaining steps once <dur> has
+                            passed (or AGENT_BROWSER_RUN_DEADLINE, config
+                            run-deadline)
+
+Steps or scripts that pass only on a retry are reported as flaky.
+
+Selector healing: when a step fails because its selector matches nothing
+(or more than one element), the runner asks `selectors heal` for the
+element it meant and reruns the step with that selector. The substitution
+is shown under the step. Steps that fail again keep the original error.
+
+Checkpoints: a `checkpoint <name>` line saves the session's tabs, URLs,
+scroll positions, cookies and storage, and the script's variables, to
+~/.browseros/checkpoints/<script>/. A later run with --resume-from <name>
+picks up from there, so a late failure doesn't mean starting over. Every
+script run must contain that checkpoint and have reached it before.
+
+Timeouts: a `timeout <step|action|nav|idle> <dur>` line sets that timeout
+for the rest of the script, unless run was given its flag (--step-timeout,
+--action-timeout, --nav-timeout or --idle-timeout). Timeout errors name the
+setting that ran out, e.g. (nav timeout 30s, from flows/a.ab line 2).
+
+Ctrl-C stops the step in flight with E_CANCELLED and skips the rest; the
+report, trace and uploads still cover the steps that ran. Press it again to
+exit at once.
+
+Checks: lines the runner evaluates against the session's variables, such as
+those stored with --into, without a round trip to the page:
+  assert <expr>             Fail the script unless <expr> holds
+  if <expr> ... else ... end
+                            Run the steps of one branch
+  until <expr> [--max <n>] ... end
+                            Run the steps until <expr> holds, up to n
+                            times (default: 30), else fail the script
+Expressions compare with == != < <= > >=, combine with && || ! (or and or
+not), test strings with contains, startswith, endswith and matches (a
+regex), and test lists with in. Paths reach into JSON values: order.items[0],
+rows[*].name, vars["order-id"]. Functions: len, lower, upper, trim, number,
+string and json. Numeric strings compare as numbers.
+
+Reporters:
+  pretty   Steps with timing as they run, then a summary
+  json     Steps with timing, errors, error codes, artifacts, healed
+           selectors and captures
+  junit    JUnit XML; one testsuite per script, one testcase per step.
+           Screenshots and diff images are attached as [[ATTACHMENT|path]],
+           and captures are written to <system-out>
+  github   GitHub Actions annotations on the failing script lines, plus a
+           table in the job summary
+
+Global Options:
+  --json               Output as JSON (same data as --reporter json)
+  --session <name>     Use specific session
+
+Examples:
+  agent-browser run flows/checkout.ab
+  agent-browser run flows/checkout.ab --reporter junit -o results.xml
+  agent-browser --session ci run flows/login.ab --reporter github
+  agent-browser run flows/ --matrix device="iPhone 14","Pixel 7"
+  agent-browser run flows/ --shard 2/4 --reporter junit -o shard-2.xml
+  agent-browser run flows/ --step-retries 2 --quarantine quarantine.txt
+  agent-browser run flows/checkout.ab --trace trace.html
+  agent-browser run flows/onboarding.ab --resume-from account-created
+  agent-browser run flows/ --update-selectors
+  agent-browser run flows/ --capture on-failure --reporter junit -o results.xml
+  agent-browser run flows/ --step-timeout 2m --deadline 20m --nav-timeout 45s
+  agent-browser run flows/search.ab    # with: assert len(results) >= 10
+  agent-browser run flows/signup.ab --data users.csv --parallel 4 -r junit -o signups.xml
+  agent-browser run flows/ -r junit -o results.xml --upload s3://ci-evidence/nightly
+"##
+        }
+
+        // === Self-update ===
+        "self-update" => {
+            r##"
+agent-browser self-update - Update agent-browser to the latest release
+
+Usage: agent-browser self-update [--check]
+
+Downloads the latest release binary for this platform from GitHub, verifies
+it against the release's SHA256SUMS file and atomically replaces the running
+executable.
+
+Options:
+  --check              Only report whether an update is available; exits 19
+                       (E_UPDATE_AVAILABLE) when the installed version is
+                       out of date
+
+Examples:
+  agent-browser self-update
+  agent-browser self-update --check
+  agent-browser self-update --check --json
+"##
+        }
+
         // === Connect ===
         "connect" => {
             r##"
@@ -1694,7 +4448,13 @@ Core Commands:
   scrollintoview <sel>       Scroll element into view
   wait <sel|ms>              Wait for element or time
   screenshot [path]          Take screenshot
-  pdf <path>                 Save as PDF
+  compare-screenshot <opts>  Compare screenshot with a baseline PNG
+  diff <before> <after>      Compare saved snapshots, HTML or screenshots
+  selectors suggest <ref>    Durable selectors for an element, ranked
+  selectors heal <sel>       Find the element a stale selector meant
+  pdf [path]                 Save as PDF
+  archive [path]             Save page as MHTML or WARC (--format)
+  freeze [path]              Save page as self-contained HTML
   snapshot                   Accessibility tree with refs (for AI)
   eval <js>                  Run JavaScript
   connect <port|url>         Connect to browser via CDP
@@ -1717,15 +4477,24 @@ Find Elements:  agent-browser find <locator> <value> <action> [text]
 Mouse:  agent-browser mouse <action> [args]
   move <x> <y>, down [btn], up [btn], wheel <dy> [dx]
 
+Input Timelines:  agent-browser input <action> [args]
+  record <file>, stop, replay <file> [--speed <n>]
+
 Browser Settings:  agent-browser set <setting> [value]
   viewport <w> <h>, device <name>, geo <lat> <lng>
   offline [on|off], headers <json>, credentials <user> <pass>
   media [dark|light] [reduced-motion]
 
+Clock:  agent-browser clock <action>
+  set <time> [--tick] [--timezone <tz>], advance <duration>, resume
+
 Network:  agent-browser network <action>
   route <url> [--abort|--body <json>]
   unroute [url]
   requests [--clear] [--filter <pattern>]
+  capture --url <p> --out <dir> [--max-size <size>] [--mime <types>]
+  capture stop
+  apis [--filter <pattern>] [--clear]
 
 Storage:
   cookies [get|set|clear]    Manage cookies (set supports --url, --domain, --path, --httpOnly, --secure, --sameSite, --expires)
@@ -1733,22 +4502,100 @@ Storage:
 
 Tabs:
   tab [new|list|close|<n>]   Manage tabs
+  targets                    List tabs and workers (eval --target <id>)
+  robots <url>               Check a URL against the site's robots.txt
+  tls info <url>             Show a site's certificate chain, expiry and SANs
+  audit perf [url]           Reload and report load timing, vitals and resources
+  audit seo [url]            Check metadata, headings, links and structured data
+  audit security [url]       Reload and check security headers, cookies, mixed content
 
 Debug:
   trace start|stop [path]    Record trace
   record start <path> [url]  Start video recording (WebM)
   record stop                Stop and save video
+  screencast start [file]    Stream JPEG frames to a file or pipe (--scale, --fps)
+  screencast stop            Stop and report frames written
   console [--clear]          View console logs
   errors [--clear]           View page errors
   highlight <sel>            Highlight element
+  inspect <sel>              Show element box, styles, occlusion, listeners
+  dismiss-overlays           Close cookie banners, newsletter modals, app banners
+  stats [--reset]            Show bandwidth, request and cache counts
+  memory                     Each tab's JS heap, and tabs recycled by --max-tab-memory
+  cdp <method> [--params <json>]
+                             Send a raw DevTools protocol message
+  cdp-listen <event>... [--follow]
+                             Stream DevTools protocol events as JSON lines
+  watch --selector <css> [--follow]
+                             Stream DOM mutations as JSON lines
+  poll --selector <css> [--every <dur>] [--until-changed]
+                             Read an element on an interval, one JSON line each
+  replay --har <file> [url]  Serve responses from a recorded HAR
+  ws list                    List WebSockets and frame counts
+  ws tail <id> [-n <n>]      Show recent WebSocket frames
+  ws export <path>           Save WebSocket handshakes and frames as JSON
 
 Sessions:
   session                    Show current session name
   session list               List active sessions
+  session save|restore <f>   Save or reopen tabs, scroll positions and storage
+
+Variables:
+  var set <name> <value>     Set a session variable, used as {{name}} in arguments
+  var get <name>             Print a variable
+  var list                   List the session's variables
+  var unset <name>           Remove a variable (var clear removes all)
+
+Config:
+  config list                Show effective config values
+  config get|set <key>       Read or write a config value
+  config edit                Open the config file in $EDITOR
+
+Shell:
+  completions <shell>        Print completion script (bash, zsh, fish, powershell)
+
+Extensions:
+  ext add <dir|crx>          Install an extension for every session
+  ext list                   List installed extensions
+  ext remove <name>          Uninstall an extension
+  filters update             Download ad and tracker filter lists
+  filters list               List installed filter lists
+
+Scripts:
+  run <script|dir>           Run command scripts (--reporter, --shard, --matrix)
+  schedule add "<cron>" <command...>
+                             Run a command on a schedule while the daemon is up
+  schedule list|remove <id>|logs <id>
+                             Show, remove or read the output of scheduled jobs
+  jobs                       Job queue served over HTTP (AGENT_BROWSER_JOBS_PORT)
+  cancel                     Stop the commands running in this session
+  lanes                      Queue for a browser shared by sessions
+
+Artifacts:
+  artifacts list             List this session's screenshots, PDFs, downloads...
+  artifacts open [file]      Open the artifacts directory or a file in it
+  artifacts prune            Delete old artifacts (--older-than 7d, --max-size 5G)
+  transcript export          Write a --transcript session's steps as a dataset (jsonl)
+  transcript replay <file>   Re-run a transcript and flag where the page diverges
+  audit-log verify [file]    Check an --audit-log session's hash chain
+  audit-log export           Write an --audit-log session's entries (jsonl)
+
+Plugins:
+  plugins                    List agent-browser-<name> executables on PATH
+  <name> [args]              Run the agent-browser-<name> plugin
 
 Setup:
+  init                       Install BrowserOS, create a profile, run a smoke test
+  verify                     Check that the browser launches and loads pages (CI)
   install                    Install browser binaries
   install --with-deps        Also install system dependencies (Linux)
+  upgrade                    Update BrowserOS, downloading only what changed
+  print-dockerfile           Print a Dockerfile with BrowserOS and its dependencies
+  doctor                     Check the daemon, browser, ~/.browseros and disk space
+  status                     Show whether the session is running, and disk usage
+  which                      Show which browser executable launches use, and why
+  self-update                Update agent-browser to the latest release
+  self-update --check        Exit 19 if a newer release is available
 
 Snapshot Options:
   -i, --interactive          Only interactive elements
@@ -1762,7 +4609,8 @@ Options:
   --state <path>             Load storage state from JSON file (or AGENT_BROWSER_STATE env)
   --headers <json>           HTTP headers scoped to URL's origin (for auth)
   --executable-path <path>   Custom browser executable (or AGENT_BROWSER_EXECUTABLE_PATH)
-  --extension <path>         Load browser extensions (repeatable)
+  --browser <name>           browseros, chrome, edge, chromium or path:<exe> (or AGENT_BROWSER_BROWSER)
+  --extension <dir|crx>      Load browser extensions (repeatable, alias --load-extension)
   --args <args>              Browser launch args, comma or newline separated (or AGENT_BROWSER_ARGS)
                              e.g., --args "--no-sandbox,--disable-blink-features=AutomationControlled"
   --user-agent <ua>          Custom User-Agent (or AGENT_BROWSER_USER_AGENT)
@@ -1771,12 +4619,75 @@ Options:
   --proxy-bypass <hosts>     Bypass proxy for these hosts (or AGENT_BROWSER_PROXY_BYPASS)
                              e.g., --proxy-bypass "localhost,*.internal.com"
   --ignore-https-errors      Ignore HTTPS certificate errors
+  --extra-ca <pem>           Trust CA certificates from PEM files (or AGENT_BROWSER_EXTRA_CA)
+  --ignore-cert-errors-for <hosts>  Skip certificate checks for these hosts only
+  --client-cert <pem>        Client certificate for mutual TLS (or AGENT_BROWSER_CLIENT_CERT)
+  --client-key <pem>         Its private key; not needed for a .p12/.pfx bundle
+  --client-cert-origin <origins>  Origins to present the certificate to
+  --auth-server-allowlist <hosts>    Hosts allowed integrated Windows auth (NTLM/Kerberos)
+  --auth-delegate-allowlist <hosts>  Hosts Kerberos credentials may be delegated to
   --allow-file-access        Allow file:// URLs to access local files (Chromium only)
   -p, --provider <name>      Browser provider: ios, browserbase, kernel, browseruse, browseros-existing, browseros-new
+  --remote <url>             Browser from a remote pool: wss:// DevTools or https:// Selenium Grid
+                             (or AGENT_BROWSER_REMOTE)
+  --remote-token <token>     Token for the remote pool (or AGENT_BROWSER_REMOTE_TOKEN)
+  --remote-keepalive <dur>   Ping the remote connection this often (default: 30s, 0 disables)
   --existing [profile]       Connect to existing BrowserOS profile (defaults to "default")
   --new [profile]            Launch BrowserOS profile and connect via CDP
   --device <name>            iOS device name (e.g., "iPhone 15 Pro")
-  --json                     JSON output
+  --on-popup <policy>        Popup handling: list, follow, block (or AGENT_BROWSER_ON_POPUP)
+  --auto-dismiss-overlays    Dismiss cookie banners automatically (or AGENT_BROWSER_AUTO_DISMISS_OVERLAYS)
+  --adblock                  Block ads and trackers via filter lists (or AGENT_BROWSER_ADBLOCK)
+  --action-timeout <dur>     How long actions wait for their element (default: 60s,
+                             or AGENT_BROWSER_ACTION_TIMEOUT)
+  --nav-timeout <dur>        Page loads and URL waits (default: the action timeout,
+                             or AGENT_BROWSER_NAV_TIMEOUT)
+  --idle-timeout <dur>       Network-idle waits (default: the nav timeout,
+                             or AGENT_BROWSER_IDLE_TIMEOUT)
+  --retries <n>              Retry click, hover and focus on transient failures (or AGENT_BROWSER_RETRIES)
+  --retry-backoff <dur>      Delay before first retry, doubled each time (default: 500ms)
+  --humanize                 Human-like mouse paths and typing for click, type, scroll
+                             (or AGENT_BROWSER_HUMANIZE)
+  --humanize-seed <n>        Make humanized input repeatable (or AGENT_BROWSER_HUMANIZE_SEED)
+  --stealth                  Hide common headless fingerprints from page scripts
+                             (or AGENT_BROWSER_STEALTH)
+  --deterministic            Stable rendering: seeded Math.random, no animations, carets or
+                             smooth scrolling (or AGENT_BROWSER_DETERMINISTIC)
+  --init-script <file>       Run a script in every document before the page's own
+                             (repeatable, or AGENT_BROWSER_INIT_SCRIPT)
+  --rate <limits>            Per-domain request limit, e.g. 5/s or 5/s,example.com=1/s
+                             (or AGENT_BROWSER_RATE)
+  --nav-rate <limits>        Per-domain navigation limit, e.g. 10/m (or AGENT_BROWSER_NAV_RATE)
+  --max-concurrent <n>       Commands running at once on a browser shared by sessions
+                             (or AGENT_BROWSER_MAX_CONCURRENT)
+  --action-rate <rate>       Commands started on a shared browser, e.g. 10/s
+                             (or AGENT_BROWSER_ACTION_RATE)
+  --max-tab-memory <size>    Recycle tabs whose JS heap grows past this, e.g. 1G
+                             (or AGENT_BROWSER_MAX_TAB_MEMORY)
+  --recycle-mode <mode>      reload (default) or recreate (or AGENT_BROWSER_RECYCLE_MODE)
+  --respect-robots           Block navigations robots.txt disallows (or AGENT_BROWSER_RESPECT_ROBOTS)
+  --robots-override          Ignore robots.txt for this command
+  --transcript               Record each step for `transcript export` (or AGENT_BROWSER_TRANSCRIPT)
+  --audit-log                Keep a hash-chained log of actions (or AGENT_BROWSER_AUDIT_LOG)
+  --read-only                Refuse input, eval and POSTs; reading still works (or AGENT_BROWSER_READ_ONLY)
+  --guardrails               Block payment requests and checkout pages (or AGENT_BROWSER_GUARDRAILS)
+  --guardrail-rules <file>   Extra guardrail rules, JSON (or AGENT_BROWSER_GUARDRAIL_RULES)
+  --allow-purchases          Lift purchase guardrails for this command
+  --json                     JSON output (versioned envelope)
+  -q, --quiet                Only print requested data and errors
+  -v, -vv                    Log CLI activity to stderr (debug / trace)
+  --log-file <path>          Append logs to a file (or AGENT_BROWSER_LOG_FILE)
+  --log-level <level>        error, warn, info, debug, trace (or AGENT_BROWSER_LOG_LEVEL)
+  --log-format <fmt>         text or json (or AGENT_BROWSER_LOG_FORMAT)
+  --otel-endpoint <url>      Send OpenTelemetry traces to an OTLP/HTTP collector
+  --color <when>             auto, always or never (or AGENT_BROWSER_COLOR)
+  --download-concurrency <n> Files install, self-update and filters update fetch at once (default 4)
+  --into <name>              Store the command's result (text, value, eval result...) in a variable
+  --json-path <expr>         Print only what a JSONPath selects from the command's data
+                             (e.g. '$.result[*].name', '$.apis[?(@.count > 5)].url')
+  --output-format <fmt>      Write the command's rows as ndjson, csv or parquet (with --out)
+  --out <file>               File for --output-format (ndjson and csv default to stdout)
+  --sink <uri>               Append the command's rows to sqlite://path.db#table (needs sqlite3)
   --full, -f                 Full page screenshot
   --headed                   Show browser window (not headless)
   --cdp <port>               Connect via CDP (Chrome DevTools Protocol)
@@ -1786,8 +4697,25 @@ Options:
 Environment:
   AGENT_BROWSER_SESSION          Session name (default: "default")
   AGENT_BROWSER_EXECUTABLE_PATH  Custom browser executable path
+  AGENT_BROWSER_BROWSER          Browser to launch (browseros, chrome, edge, chromium, path:<exe>)
   AGENT_BROWSER_PROVIDER         Browser provider (ios, browserbase, kernel, browseruse, browseros-existing, browseros-new)
+  AGENT_BROWSER_REMOTE           Remote browser pool URL (wss:// DevTools or https:// Selenium Grid)
+  AGENT_BROWSER_REMOTE_TOKEN     Token for the remote pool
+  AGENT_BROWSER_CONTAINER        Force (1) or skip (0) the container launch switches
+  AGENT_BROWSER_QUOTA            Most ~/.browseros may hold, e.g. 10G (or quota config key)
   AGENT_BROWSER_STREAM_PORT      Enable WebSocket streaming on port (e.g., 9223)
+  AGENT_BROWSER_METRICS_PORT     Serve Prometheus metrics at /metrics on port (e.g., 9464)
+  AGENT_BROWSER_JOBS_PORT        Accept run scripts over HTTP at /jobs on port (e.g., 8400)
+  AGENT_BROWSER_HEALTH_PORT      Serve /healthz and /readyz on port; SIGTERM drains first
+  AGENT_BROWSER_LISTEN_PORT      Also take commands over TCP on port, for remote clients
+  AGENT_BROWSER_LISTEN_TOKEN     Token commands over TCP must carry
+  AGENT_BROWSER_TRANSCRIPT       Record session transcripts (see: transcript export)
+  AGENT_BROWSER_AUDIT_LOG        Keep tamper-evident audit logs (see: audit-log verify)
+  AGENT_BROWSER_READ_ONLY        Start sessions read-only (see: --read-only)
+  AGENT_BROWSER_GUARDRAILS       Turn on purchase guardrails (see: --guardrails)
+  AGENT_BROWSER_GUARDRAIL_RULES  JSON file of extra guardrail rules
+  AGENT_BROWSER_DRAIN_TIMEOUT    Max ms to wait for commands in flight on SIGTERM (default: 25000)
+  AGENT_BROWSER_IPC              Set to json to talk to the daemon in JSON lines, not MessagePack
   AGENT_BROWSER_IOS_DEVICE       Default iOS device name
   AGENT_BROWSER_IOS_UDID         Default iOS device UDID
   BROWSEROS_PROFILE_NAME         BrowserOS profile name for --existing/--new (default: "default")
@@ -0,0 +1,190 @@
+//! External subcommands.
+//!
+//! Like git, any executable named `agent-browser-<name>` on `PATH` becomes
+//! `agent-browser <name>`. Built-in commands always win, so a plugin can't
+//! shadow them. The plugin receives every argument after its name, and the
+//! session, output mode and path of the calling binary through environment
+//! variables so it can drive the same browser by calling back into the CLI.
+//!
+//! `<name>.wasm` files in `~/.browseros/plugins` are plugins too, run in
+//! process (see `wasm_plugin.rs`). An executable on `PATH` wins over a WASM
+//! plugin of the same name.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::json;
+
+use crate::errors::ErrorCode;
+use crate::flags::{Flags, COMMANDS};
+use crate::ui;
+use crate::wasm_plugin;
+
+const PREFIX: &str = "agent-browser-";
+
+/// Platform binaries shipped next to the CLI (e.g. agent-browser-linux-x64)
+/// share the prefix but are not plugins.
+const RESERVED: &[&str] = &["linux", "darwin", "win32"];
+
+fn is_executable(path: &Path) -> bool {
+    let Ok(meta) = fs::metadata(path) else {
+        return false;
+    };
+    if !meta.is_file() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        meta.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        true
+    }
+}
+
+/// Plugin name for a file name, or None if the file isn't a plugin.
+fn plugin_name(file_name: &str) -> Option<&str> {
+    let name = file_name.strip_prefix(PREFIX)?;
+    let name = if cfg!(windows) {
+        let lower = name.to_ascii_lowercase();
+        [".exe", ".cmd", ".bat"]
+            .iter()
+            .find(|ext| lower.ends_with(*ext))
+            .map(|ext| &name[..name.len() - ext.len()])?
+    } else {
+        name
+    };
+    let reserved = RESERVED.iter().any(|p| name.split('-').next() == Some(*p));
+    if name.is_empty() || name.starts_with('-') || reserved {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+/// All plugins on PATH, then WASM plugins. Earlier PATH entries win, as they
+/// would for the shell.
+pub fn list_plugins() -> BTreeMap<String, PathBuf> {
+    let mut plugins = BTreeMap::new();
+    let path = env::var_os("PATH").unwrap_or_default();
+    for dir in env::split_paths(&path) {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name();
+            let Some(name) = file_name.to_str().and_then(plugin_name) else {
+                continue;
+            };
+            let path = entry.path();
+            if !plugins.contains_key(name) && is_executable(&path) {
+                plugins.insert(name.to_string(), path);
+            }
+        }
+    }
+    if let Ok(entries) = fs::read_dir(wasm_plugin::plugins_dir()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(wasm_plugin::EXTENSION) {
+                continue;
+            }
+            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                if !name.starts_with('-') && !plugins.contains_key(name) {
+                    plugins.insert(name.to_string(), path);
+                }
+            }
+        }
+    }
+    plugins
+}
+
+/// Find the plugin for an unknown command name.
+pub fn find_plugin(name: &str) -> Option<PathBuf> {
+    if COMMANDS.contains(&name) || name.starts_with('-') {
+        return None;
+    }
+    list_plugins().remove(name)
+}
+
+/// Run a plugin and exit with its status.
+pub fn run_plugin(path: &Path, name: &str, args: &[String], flags: &Flags) -> ! {
+    if path.extension().and_then(|e| e.to_str()) == Some(wasm_plugin::EXTENSION) {
+        wasm_plugin::run_wasm_plugin(path, name, args, flags);
+    }
+    // Everything after the plugin name, untouched by global flag parsing
+    let plugin_args = args
+        .iter()
+        .position(|a| a == name)
+        .map(|i| &args[i + 1..])
+        .unwrap_or(&[]);
+
+    let mut command = Command::new(path);
+    command
+        .args(plugin_args)
+        .env("AGENT_BROWSER_SESSION", &flags.session);
+    if let Ok(exe) = env::current_exe() {
+        command.env("AGENT_BROWSER_BIN", exe);
+    }
+    if flags.json {
+        command.env("AGENT_BROWSER_JSON", "1");
+    }
+    if flags.quiet {
+        command.env("AGENT_BROWSER_QUIET", "1");
+    }
+
+    tracing::debug!(plugin = name, path = %path.display(), "running plugin");
+    match command.status() {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(e) => ui::fail(
+            ErrorCode::Unknown,
+            format!("Failed to run plugin {}: {}", path.display(), e),
+        ),
+    }
+}
+
+pub fn run_plugins_command() {
+    let plugins = list_plugins();
+    if ui::json_mode() {
+        let list: Vec<_> = plugins
+            .iter()
+            .map(|(name, path)| json!({ "name": name, "path": path }))
+            .collect();
+        ui::print_json_data(json!({ "plugins": list }));
+    } else if plugins.is_empty() {
+        println!(
+            "No plugins found (add agent-browser-<name> executables to PATH, or <name>.wasm to {})",
+            wasm_plugin::plugins_dir().display()
+        );
+    } else {
+        for (name, path) in &plugins {
+            println!("{:<20} {}", name, path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn test_plugin_name() {
+        assert_eq!(plugin_name("agent-browser-audit"), Some("audit"));
+        assert_eq!(plugin_name("agent-browser-my-tool"), Some("my-tool"));
+        assert_eq!(plugin_name("agent-browser-linux-x64"), None);
+        assert_eq!(plugin_name("agent-browser-"), None);
+        assert_eq!(plugin_name("agent-browser"), None);
+        assert_eq!(plugin_name("other-audit"), None);
+    }
+
+    #[test]
+    fn test_builtins_are_never_plugins() {
+        assert_eq!(find_plugin("open"), None);
+        assert_eq!(find_plugin("--json"), None);
+    }
+}
@@ -0,0 +1,532 @@
+//! Report formats for `agent-browser run`.
+//!
+//! `junit` is the XML most CI dashboards import, with one `<testsuite>` per
+//! script and one `<testcase>` per step. Artifacts are attached with the
+//! `[[ATTACHMENT|path]]` convention understood by Jenkins and GitLab. `github`
+//! prints workflow commands, so failed steps show up as annotations on the
+//! script line, and appends a summary table to `$GITHUB_STEP_SUMMARY`.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io::Write as _;
+
+use serde_json::{json, Value};
+
+use crate::color;
+use crate::run::{ScriptResult, Status};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Reporter {
+    Pretty,
+    Json,
+    Junit,
+    Github,
+}
+
+impl Reporter {
+    pub const NAMES: &'static [&'static str] = &["pretty", "json", "junit", "github"];
+
+    pub fn parse(name: &str) -> Option<Reporter> {
+        match name {
+            "pretty" => Some(Reporter::Pretty),
+            "json" => Some(Reporter::Json),
+            "junit" | "xml" => Some(Reporter::Junit),
+            "github" => Some(Reporter::Github),
+            _ => None,
+        }
+    }
+
+    /// Whether steps are printed as they finish.
+    pub fn is_live(self) -> bool {
+        matches!(self, Reporter::Pretty | Reporter::Github)
+    }
+}
+
+struct Totals {
+    passed: usize,
+    failed: usize,
+    /// Failed steps of quarantined scripts
+    quarantined: usize,
+    skipped: usize,
+    /// Steps that passed on a retry
+    flaky: usize,
+    duration_ms: u64,
+}
+
+fn step_totals<'a>(scripts: impl Iterator<Item = &'a ScriptResult> + Clone) -> Totals {
+    let count = |status, quarantined| {
+        scripts
+            .clone()
+            .filter(|s| s.quarantined == quarantined)
+            .flat_map(|s| &s.steps)
+            .filter(|r| r.status == status)
+            .count()
+    };
+    Totals {
+        passed: count(Status::Passed, false) + count(Status::Passed, true),
+        failed: count(Status::Failed, false),
+        quarantined: count(Status::Failed, true),
+        skipped: count(Status::Skipped, false) + count(Status::Skipped, true),
+        flaky: scripts
+            .clone()
+            .flat_map(|s| &s.steps)
+            .filter(|r| r.flaky)
+            .count(),
+        duration_ms: scripts.map(|s| s.duration_ms).sum(),
+    }
+}
+
+fn totals(results: &[ScriptResult]) -> Totals {
+    step_totals(results.iter())
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+/// `3 passed, 1 failed, 1 quarantined, 2 skipped, 1 flaky`, leaving out zeros.
+fn counts(t: &Totals) -> String {
+    let mut parts = vec![color::green(&format!("{} passed", t.passed))];
+    if t.failed > 0 {
+        parts.push(color::red(&format!("{} failed", t.failed)));
+    }
+    if t.quarantined > 0 {
+        parts.push(color::yellow(&format!("{} quarantined", t.quarantined)));
+    }
+    if t.skipped > 0 {
+        parts.push(format!("{} skipped", t.skipped));
+    }
+    if t.flaky > 0 {
+        parts.push(color::yellow(&format!("{} flaky", t.flaky)));
+    }
+    parts.join(", ")
+}
+
+/// Script counts, in the same shape as step counts.
+fn script_totals(results: &[ScriptResult]) -> Totals {
+    let count = |f: &dyn Fn(&ScriptResult) -> bool| results.iter().filter(|s| f(s)).count();
+    Totals {
+        passed: count(&|s| s.status == Status::Passed),
+        failed: count(&|s| s.is_hard_failure()),
+        quarantined: count(&|s| s.quarantined),
+        skipped: 0,
+        flaky: count(&|s| s.flaky),
+        duration_ms: results.iter().map(|s| s.duration_ms).sum(),
+    }
+}
+
+/// `5 passed, 1 failed, 2 skipped (3.2s)`, after a line of script counts for
+/// suites or when scripts were retried or quarantined.
+pub fn summary_line(results: &[ScriptResult]) -> String {
+    let t = totals(results);
+    let steps = format!(
+        "{} {}",
+        counts(&t),
+        color::dim(&format!("({:.1}s)", t.duration_ms as f64 / 1000.0))
+    );
+    let scripts = script_totals(results);
+    if results.len() < 2 && scripts.flaky == 0 && scripts.quarantined == 0 {
+        return steps;
+    }
+    format!("Scripts: {}\nSteps:   {}", counts(&scripts), steps)
+}
+
+pub fn to_json(results: &[ScriptResult]) -> Value {
+    let t = totals(results);
+    let scripts = script_totals(results);
+    json!({
+        "scripts": results,
+        "passed": t.passed,
+        "failed": t.failed,
+        "quarantined": t.quarantined,
+        "skipped": t.skipped,
+        "flaky": t.flaky,
+        "scriptsPassed": scripts.passed,
+        "scriptsFailed": scripts.failed,
+        "scriptsQuarantined": scripts.quarantined,
+        "scriptsFlaky": scripts.flaky,
+        "durationMs": t.duration_ms,
+    })
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            // Control characters other than tab and newlines are invalid in XML 1.0
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+pub fn junit(results: &[ScriptResult]) -> String {
+    let t = totals(results);
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"agent-browser\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">",
+        t.passed + t.failed + t.quarantined + t.skipped,
+        t.failed,
+        t.quarantined + t.skipped,
+        seconds(t.duration_ms)
+    );
+    for script in results {
+        let t = step_totals(std::iter::once(script));
+        let _ = writeln!(
+            out,
+            "  <testsuite name=\"{}\" file=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">",
+            escape_xml(&script.name),
+            escape_xml(&script.file.display().to_string()),
+            script.steps.len(),
+            t.failed,
+            t.quarantined + t.skipped,
+            seconds(script.duration_ms)
+        );
+        for step in &script.steps {
+            let _ = write!(
+                out,
+                "    <testcase name=\"{}: {}\" classname=\"{}\" file=\"{}\" line=\"{}\" time=\"{}\"",
+                step.line,
+                escape_xml(&step.command),
+                escape_xml(&script.name),
+                escape_xml(&script.file.display().to_string()),
+                step.line,
+                seconds(step.duration_ms)
+            );
+            if step.status == Status::Passed
+                && step.artifacts.is_empty()
+                && !step.flaky
+                && step.capture.is_none()
+            {
+                out.push_str("/>\n");
+                continue;
+            }
+            out.push_str(">\n");
+            match step.status {
+                // Quarantined failures are reported as skipped so they don't fail the build
+                Status::Failed if script.quarantined => {
+                    let message = step.error.as_deref().unwrap_or("Step failed");
+                    let _ = writeln!(
+                        out,
+                        "      <skipped message=\"Quarantined: {}\"/>",
+                        escape_xml(message.lines().next().unwrap_or_default())
+                    );
+                }
+                Status::Failed => {
+                    let message = step.error.as_deref().unwrap_or("Step failed");
+                    let _ = writeln!(
+                        out,
+                        "      <failure message=\"{}\" type=\"{}\">{}</failure>",
+                        escape_xml(message.lines().next().unwrap_or_default()),
+                        escape_xml(step.code.as_deref().unwrap_or("E_UNKNOWN")),
+                        escape_xml(message)
+                    );
+                }
+                Status::Skipped => out.push_str("      <skipped/>\n"),
+                Status::Passed => {}
+            }
+            // Surefire's rerun format, understood by Jenkins and GitLab
+            for error in &step.retry_errors {
+                let _ = writeln!(
+                    out,
+                    "      <flakyFailure message=\"{}\">{}</flakyFailure>",
+                    escape_xml(error.lines().next().unwrap_or_default()),
+                    escape_xml(error)
+                );
+            }
+            if !step.artifacts.is_empty() || step.capture.is_some() {
+                let mut lines: Vec<String> = step
+                    .artifacts
+                    .iter()
+                    .map(|a| format!("[[ATTACHMENT|{}]]", a))
+                    .collect();
+                lines.extend(step.capture.as_ref().map(capture_text));
+                let _ = writeln!(
+                    out,
+                    "      <system-out>{}</system-out>",
+                    escape_xml(&lines.join("\n"))
+                );
+            }
+            out.push_str("    </testcase>\n");
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+/// A `--capture` as plain text: the page, console tail and snapshot.
+fn capture_text(capture: &Value) -> String {
+    let text = |key: &str| {
+        capture
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+    };
+    let mut out = format!("Page: {} ({})\n", text("url"), text("title"));
+    let console = capture.get("console").and_then(|v| v.as_array());
+    if let Some(messages) = console.filter(|m| !m.is_empty()) {
+        out.push_str("\nConsole:\n");
+        for message in messages {
+            let field = |key: &str| {
+                message
+                    .get(key)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+            };
+            let _ = writeln!(out, "[{}] {}", field("type"), field("text"));
+        }
+    }
+    if !text("snapshot").is_empty() {
+        let _ = writeln!(out, "\nSnapshot:\n{}", text("snapshot"));
+        if capture.get("truncated").and_then(|v| v.as_bool()) == Some(true) {
+            out.push_str("...\n");
+        }
+    }
+    out
+}
+
+/// Escape a workflow command message.
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a workflow command property value.
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+pub fn github(results: &[ScriptResult]) -> String {
+    let mut out = String::new();
+    for script in results {
+        let file = escape_property(&script.file.display().to_string());
+        for step in &script.steps {
+            let title = |label: &str| {
+                escape_property(&format!("{}{}: {}", label, script.name, step.command))
+            };
+            let error = step.error.as_deref().unwrap_or("Step failed");
+            if step.status == Status::Failed && script.quarantined {
+                let _ = writeln!(
+                    out,
+                    "::warning file={},line={},title={}::{}",
+                    file,
+                    step.line,
+                    title("Quarantined "),
+                    escape_data(error)
+                );
+            } else if step.status == Status::Failed {
+                let _ = writeln!(
+                    out,
+                    "::error file={},line={},title={}::{}",
+                    file,
+                    step.line,
+                    title(""),
+                    escape_data(error)
+                );
+            } else if step.flaky {
+                let _ = writeln!(
+                    out,
+                    "::warning file={},line={},title={}::{}",
+                    file,
+                    step.line,
+                    title("Flaky "),
+                    escape_data(&format!(
+                        "Passed on attempt {} after: {}",
+                        step.attempts,
+                        step.retry_errors.join("; ")
+                    ))
+                );
+            }
+        }
+    }
+    let t = totals(results);
+    let _ = writeln!(
+        out,
+        "::notice title=agent-browser run::{} passed, {} failed, {} quarantined, {} skipped, {} flaky in {}s",
+        t.passed,
+        t.failed,
+        t.quarantined,
+        t.skipped,
+        t.flaky,
+        seconds(t.duration_ms)
+    );
+    out
+}
+
+/// Markdown table for the GitHub job summary.
+fn github_summary(results: &[ScriptResult]) -> String {
+    let mut out = String::from("| Script | Result | Steps | Time |\n| --- | --- | --- | --- |\n");
+    for script in results {
+        let result = match script.status {
+            Status::Failed if script.quarantined => "⚠ quarantined",
+            Status::Failed => "✗ failed",
+            _ if script.flaky => "⚠ flaky",
+            _ => "✓ passed",
+        };
+        let passed = script
+            .steps
+            .iter()
+            .filter(|r| r.status == Status::Passed)
+            .count();
+        let _ = writeln!(
+            out,
+            "| {} | {} | {}/{} | {}s |",
+            script.name.replace('|', "\\|"),
+            result,
+            passed,
+            script.steps.len(),
+            seconds(script.duration_ms)
+        );
+    }
+    out
+}
+
+/// Render the report. The pretty reporter only adds the summary line, since
+/// steps were already printed as they ran.
+pub fn render(reporter: Reporter, results: &[ScriptResult]) -> String {
+    match reporter {
+        Reporter::Pretty => format!("\n{}\n", summary_line(results)),
+        Reporter::Json => {
+            serde_json::to_string_pretty(&to_json(results)).unwrap_or_default() + "\n"
+        }
+        Reporter::Junit => junit(results),
+        Reporter::Github => {
+            if let Ok(path) = env::var("GITHUB_STEP_SUMMARY") {
+                if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+                    let _ = file.write_all(github_summary(results).as_bytes());
+                }
+            }
+            github(results)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run::StepResult;
+    use std::path::PathBuf;
+
+    fn sample() -> Vec<ScriptResult> {
+        let step = |line, command: &str, status| StepResult {
+            line,
+            command: command.to_string(),
+            status,
+            duration_ms: 1500,
+            error: None,
+            code: None,
+            artifacts: Vec::new(),
+            attempts: 1,
+            flaky: false,
+            retry_errors: Vec::new(),
+            healed: None,
+            capture: None,
+            trace: None,
+        };
+        let mut failed = step(2, "click '#buy & save'", Status::Failed);
+        failed.error = Some("Element not found: #buy\nTry a snapshot".to_string());
+        failed.code = Some("E_SELECTOR_NOT_FOUND".to_string());
+        let mut shot = step(1, "screenshot home.png", Status::Passed);
+        shot.artifacts = vec!["/tmp/home.png".to_string()];
+        vec![ScriptResult {
+            name: "checkout".to_string(),
+            file: PathBuf::from("flows/checkout.ab"),
+            params: Default::default(),
+            row: None,
+            status: Status::Failed,
+            duration_ms: 3000,
+            attempts: 1,
+            flaky: false,
+            quarantined: false,
+            source: String::new(),
+            steps: vec![shot, failed, step(3, "close", Status::Skipped)],
+        }]
+    }
+
+    /// The sample script quarantined, with its first step passing on a retry.
+    fn flaky_sample() -> Vec<ScriptResult> {
+        let mut results = sample();
+        results[0].quarantined = true;
+        let shot = &mut results[0].steps[0];
+        shot.attempts = 2;
+        shot.flaky = true;
+        shot.retry_errors = vec!["Timeout 5000ms exceeded".to_string()];
+        results
+    }
+
+    #[test]
+    fn test_junit() {
+        let xml = junit(&sample());
+        assert!(xml.contains(
+            r#"<testsuites name="agent-browser" tests="3" failures="1" skipped="1" time="3.000">"#
+        ));
+        assert!(xml.contains(r#"<testcase name="2: click '#buy &amp; save'" classname="checkout""#));
+        assert!(xml.contains(
+            r#"<failure message="Element not found: #buy" type="E_SELECTOR_NOT_FOUND">"#
+        ));
+        assert!(xml.contains("<system-out>[[ATTACHMENT|/tmp/home.png]]</system-out>"));
+        assert!(xml.contains("<skipped/>"));
+    }
+
+    #[test]
+    fn test_junit_capture() {
+        let mut results = sample();
+        results[0].steps[1].artifacts = vec!["/tmp/step.png".to_string()];
+        results[0].steps[1].capture = Some(json!({
+            "url": "https://shop.example/cart",
+            "title": "Cart",
+            "screenshot": "/tmp/step.png",
+            "console": [{ "type": "error", "text": "Failed to load <cart>" }],
+            "snapshot": "- button \"Checkout\" [ref=e1]",
+            "truncated": true,
+        }));
+        let xml = junit(&results);
+        assert!(xml.contains(
+            "<system-out>[[ATTACHMENT|/tmp/step.png]]\nPage: https://shop.example/cart (Cart)\n\nConsole:\n[error] Failed to load &lt;cart&gt;\n\nSnapshot:\n- button &quot;Checkout&quot; [ref=e1]\n...\n</system-out>"
+        ));
+    }
+
+    #[test]
+    fn test_github() {
+        let out = github(&sample());
+        assert!(out.starts_with(
+            "::error file=flows/checkout.ab,line=2,title=checkout%3A click '#buy & save'::Element not found: #buy%0ATry a snapshot\n"
+        ));
+        assert!(out.contains(
+            "::notice title=agent-browser run::1 passed, 1 failed, 0 quarantined, 1 skipped, 0 flaky"
+        ));
+    }
+
+    #[test]
+    fn test_flaky_and_quarantined() {
+        let results = flaky_sample();
+        assert!(!results[0].is_hard_failure());
+
+        let xml = junit(&results);
+        assert!(xml.contains(r#"failures="0" skipped="2""#));
+        assert!(xml.contains(r#"<skipped message="Quarantined: Element not found: #buy"/>"#));
+        assert!(xml.contains(
+            r#"<flakyFailure message="Timeout 5000ms exceeded">Timeout 5000ms exceeded</flakyFailure>"#
+        ));
+
+        let out = github(&results);
+        assert!(out.starts_with("::warning file=flows/checkout.ab,line=1,title=Flaky checkout%3A screenshot home.png::Passed on attempt 2 after: Timeout 5000ms exceeded\n"));
+        assert!(out.contains("::warning file=flows/checkout.ab,line=2,title=Quarantined checkout"));
+        assert!(!out.contains("::error"));
+    }
+
+    #[test]
+    fn test_reporter_parse() {
+        assert_eq!(Reporter::parse("xml"), Some(Reporter::Junit));
+        assert_eq!(Reporter::parse("tap"), None);
+    }
+}
@@ -0,0 +1,2291 @@
+//! Script runner (`agent-browser run <script>`).
+//!
+//! A script is a text file with one agent-browser command per line, as it
+//! would be typed after `agent-browser`. Blank lines and lines starting with
+//! `#` are ignored, and arguments are quoted like in a shell (a `#` inside a
+//! line is kept, since `#id` is a selector). Each step runs as a
+//! separate `agent-browser --json` invocation with the same global flags as
+//! `run`, so a script behaves exactly like the commands typed by hand. The
+//! first failing step fails the script and the remaining steps are skipped.
+//!
+//! Suites: directories are searched for `*.ab` scripts. `--matrix name=a,b`
+//! runs each script once per value, with `${name}` replaced in its text, and
+//! `--shard i/n` keeps every n-th of the sorted (script, values) jobs so CI
+//! machines can split a suite without coordinating.
+//!
+//! Flakiness: `--step-retries` and `--script-retries` rerun failures, and
+//! anything that passes only on a retry is reported as flaky. Scripts listed
+//! in the `--quarantine` file still run, but their failures are reported as
+//! warnings and don't affect the exit code.
+//!
+//! Data-driven runs: `--data rows.csv` (or `.ndjson`) runs each script once
+//! per row, with the row's fields bound as variables (`{{field}}`, and
+//! `${field}` like matrix values). `--parallel <n>` spreads the runs over n
+//! sessions, `<session>-1` to `<session>-n`, each with its own browser.
+//!
+//! Checkpoints: a `checkpoint <name>` line saves the session's tabs, storage
+//! and URLs (as `session save` does) and its variables (`var`) to
+//! `~/.browseros/checkpoints/`. `run --resume-from <name>` skips every step up
+//! to that line and restores what it saved instead, so a long flow that fails
+//! late doesn't have to start over.
+//!
+//! Checks: `assert <expr>`, `if <expr>` ... `else` ... `end` and
+//! `until <expr>` ... `end` lines are evaluated by the runner against the
+//! session's variables (see `expr`), so scripts don't need `eval` for simple
+//! logic. An `until` block runs until its expression holds, up to
+//! `--max <n>` times (30 by default), and fails the script if it never does.
+//!
+//! Captures: `--capture on-failure|always` attaches a full-page screenshot,
+//! the URL and title, the last console messages and a trimmed interactive
+//! snapshot to failed (or all) steps, so a CI failure can be read without
+//! reproducing it.
+//!
+//! Healing: a step that fails because its selector no longer matches is
+//! rerun with the selector `selectors heal` finds for the element it meant
+//! (unless `--no-heal`), and the substitution is reported with the step.
+//! `--update-selectors` writes healed selectors back to the scripts.
+//!
+//! Timeouts: `--step-timeout` kills a step (retries and healing included)
+//! that runs too long, and `--deadline` bounds the whole run; steps left
+//! when it passes fail without starting. A `timeout <step|action|nav|idle>
+//! <duration>` line sets a level for the rest of its script unless `run` got
+//! that level's flag (see `timeouts`).
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cancel;
+use crate::color;
+use crate::commands::gen_id;
+use crate::config::Config;
+use crate::connection::{daemon_ready, send_command};
+use crate::dataset::{self, Row};
+use crate::errors::ErrorCode;
+use crate::expr::{self, Expr};
+use crate::flags::parse_flags;
+use crate::install::get_browseros_home;
+use crate::report::{self, Reporter};
+use crate::sink;
+use crate::timeouts::{self, Level, Timeout};
+use crate::trace;
+use crate::ui;
+use crate::upload;
+use crate::vars;
+
+pub struct Step {
+    /// 1-based line number in the script
+    pub line: usize,
+    pub args: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepResult {
+    pub line: usize,
+    pub command: String,
+    pub status: Status,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Files the step wrote, such as screenshots and diff images
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<String>,
+    /// More than one when the step was retried
+    pub attempts: u32,
+    /// Failed at first but passed on a retry
+    #[serde(skip_serializing_if = "is_false")]
+    pub flaky: bool,
+    /// Errors of the attempts before the last one
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub retry_errors: Vec<String>,
+    /// The selector the step passed with after its own stopped matching
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub healed: Option<Healed>,
+    /// Page state after the step, with `--capture`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capture: Option<Value>,
+    /// Page state after the step, with `--trace`
+    #[serde(skip)]
+    pub trace: Option<Value>,
+}
+
+impl StepResult {
+    fn new(step: &Step, status: Status) -> StepResult {
+        StepResult {
+            line: step.line,
+            command: display_command(&step.args),
+            status,
+            duration_ms: 0,
+            error: None,
+            code: None,
+            artifacts: Vec::new(),
+            attempts: 1,
+            flaky: false,
+            retry_errors: Vec::new(),
+            healed: None,
+            capture: None,
+            trace: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Healed {
+    pub from: String,
+    pub to: String,
+    /// fallback, accessible-name or nearest-text
+    pub strategy: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptResult {
+    pub name: String,
+    pub file: PathBuf,
+    /// Matrix values and data row fields this run used
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+    /// 1-based row of the `--data` file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row: Option<usize>,
+    pub status: Status,
+    pub duration_ms: u64,
+    /// More than one when the script was rerun with `--script-retries`
+    pub attempts: u32,
+    /// Passed only after a step or script retry
+    #[serde(skip_serializing_if = "is_false")]
+    pub flaky: bool,
+    /// Failed, but listed in the `--quarantine` file, so it doesn't fail the run
+    #[serde(skip_serializing_if = "is_false")]
+    pub quarantined: bool,
+    /// The script as run, after matrix substitution
+    #[serde(skip)]
+    pub source: String,
+    pub steps: Vec<StepResult>,
+}
+
+impl ScriptResult {
+    /// Whether this script should fail the run.
+    pub fn is_hard_failure(&self) -> bool {
+        self.status == Status::Failed && !self.quarantined
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// Split a line into arguments the way a POSIX shell would, without expansion.
+pub fn split_line(line: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("Unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err("Unterminated double quote".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("Unterminated double quote".to_string()),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                if let Some(c) = chars.next() {
+                    current.push(c);
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_word {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+/// Parse a script into steps. A leading `agent-browser` on a line is
+/// dropped, so commands can be pasted from a shell session.
+pub fn parse_script(text: &str) -> Result<Vec<Step>, String> {
+    let mut steps = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        let mut args = split_line(line).map_err(|e| format!("line {}: {}", i + 1, e))?;
+        if args.first().map(|s| s.as_str()) == Some("agent-browser") {
+            args.remove(0);
+        }
+        if !args.is_empty() {
+            steps.push(Step { line: i + 1, args });
+        }
+    }
+    Ok(steps)
+}
+
+/// Quote arguments for display, so the command can be copied back to a shell.
+fn display_command(args: &[String]) -> String {
+    args.iter()
+        .map(|a| {
+            if a.is_empty() || a.contains(|c: char| c.is_whitespace() || "'\"\\#$`".contains(c)) {
+                format!("'{}'", a.replace('\'', r"'\''"))
+            } else {
+                a.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Files a step wrote that still exist on disk.
+fn artifacts(data: &Value) -> Vec<String> {
+    let listed = data.get("artifacts").and_then(|v| v.as_array());
+    listed
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+        .filter(|p| Path::new(p).is_file())
+        .map(String::from)
+        .collect()
+}
+
+/// When a step has to be done by, and the timeout that says so
+#[derive(Clone, Debug)]
+pub struct Limit {
+    pub at: Instant,
+    /// e.g. `step timeout 20s, from --step-timeout`
+    pub reason: String,
+}
+
+impl Limit {
+    fn new(level: &str, timeout: &Timeout, from: Instant) -> Self {
+        Limit {
+            at: from + Duration::from_millis(timeout.ms),
+            reason: format!(
+                "{} {}, from {}",
+                level,
+                timeouts::format_ms(timeout.ms),
+                timeout.source
+            ),
+        }
+    }
+
+    fn passed(&self) -> bool {
+        Instant::now() >= self.at
+    }
+
+    /// A step that was not started because the limit had passed
+    fn missed(&self, step: &Step) -> StepResult {
+        let mut result = StepResult::new(step, Status::Failed);
+        result.error = Some(format!("Not started: out of time ({})", self.reason));
+        result.code = Some(ErrorCode::Timeout.as_str().to_string());
+        result
+    }
+}
+
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+/// Run a command to completion, or kill it at `until`. None if it was killed.
+fn output_until(command: &mut Command, until: Option<Instant>) -> std::io::Result<Option<Output>> {
+    let Some(until) = until else {
+        return command.output().map(Some);
+    };
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // Drain both pipes so a chatty step can't block on a full one
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(Output {
+                status,
+                stdout: stdout.join().unwrap_or_default(),
+                stderr: stderr.join().unwrap_or_default(),
+            }));
+        }
+        if Instant::now() >= until {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+pub(crate) fn run_step(exe: &Path, global_args: &[String], step: &Step) -> StepResult {
+    run_limited(exe, global_args, &[], None, step)
+}
+
+/// `run_step` with extra env vars, killed if it's still running at `limit`.
+fn run_limited(
+    exe: &Path,
+    global_args: &[String],
+    envs: &[(String, String)],
+    limit: Option<&Limit>,
+    step: &Step,
+) -> StepResult {
+    if let Some(limit) = limit.filter(|l| l.passed()) {
+        return limit.missed(step);
+    }
+    let started = Instant::now();
+    let output = output_until(
+        Command::new(exe)
+            // The run uploads every step's artifacts together at the end
+            .env(upload::UPLOAD_ENV, "off")
+            .envs(envs.iter().map(|(k, v)| (k, v)))
+            .args(global_args)
+            .arg("--json")
+            .args(&step.args),
+        limit.map(|l| l.at),
+    );
+    let mut result = StepResult::new(step, Status::Passed);
+    result.duration_ms = started.elapsed().as_millis() as u64;
+
+    let output = match output {
+        Ok(Some(output)) => output,
+        Ok(None) => {
+            let limit = limit.expect("only limited steps are killed");
+            result.status = Status::Failed;
+            result.error = Some(format!(
+                "Timed out after {} ({})",
+                timeouts::format_ms(result.duration_ms),
+                limit.reason
+            ));
+            result.code = Some(ErrorCode::Timeout.as_str().to_string());
+            return result;
+        }
+        Err(e) => {
+            result.status = Status::Failed;
+            result.error = Some(format!("Failed to run {}: {}", exe.display(), e));
+            return result;
+        }
+    };
+    // The envelope is the last JSON line; anything before it is diagnostics
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let envelope: Option<Value> = stdout
+        .lines()
+        .rev()
+        .find_map(|l| serde_json::from_str(l).ok());
+    if let Some(data) = envelope.as_ref().and_then(|e| e.get("data")) {
+        result.artifacts = artifacts(data);
+    }
+    let succeeded = output.status.success()
+        && envelope
+            .as_ref()
+            .and_then(|e| e.get("success"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+    if !succeeded {
+        result.status = Status::Failed;
+        let field = |key: &str| {
+            envelope
+                .as_ref()
+                .and_then(|e| e.get(key))
+                .and_then(|v| v.as_str())
+                .map(String::from)
+        };
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        result.error = field("error")
+            .or_else(|| (!stderr.is_empty()).then_some(stderr))
+            .or_else(|| Some(format!("Exited with status {}", output.status)));
+        result.code = field("code");
+    }
+    result
+}
+
+fn print_step(result: &StepResult) {
+    let (indicator, time) = match result.status {
+        Status::Passed if result.flaky => (
+            color::warning_indicator(),
+            format!(
+                "({}ms, flaky: passed on attempt {})",
+                result.duration_ms, result.attempts
+            ),
+        ),
+        Status::Passed => (
+            color::success_indicator(),
+            format!("({}ms)", result.duration_ms),
+        ),
+        Status::Failed if result.attempts > 1 => (
+            color::error_indicator(),
+            format!("({}ms, {} attempts)", result.duration_ms, result.attempts),
+        ),
+        Status::Failed => (
+            color::error_indicator(),
+            format!("({}ms)", result.duration_ms),
+        ),
+        Status::Skipped => ("-", "(skipped)".to_string()),
+    };
+    println!("{} {} {}", indicator, result.command, color::dim(&time));
+    if let Some(error) = &result.error {
+        println!("    {}", color::red(error));
+    }
+    if let (Status::Failed, Some(capture)) = (result.status, &result.capture) {
+        let text = |key: &str| {
+            capture
+                .get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+        };
+        println!("    {}", color::dim(&format!("page: {}", text("url"))));
+        if !text("screenshot").is_empty() {
+            println!(
+                "    {}",
+                color::dim(&format!("screenshot: {}", text("screenshot")))
+            );
+        }
+    }
+    if let Some(healed) = &result.healed {
+        println!(
+            "    {}",
+            color::yellow(&format!(
+                "healed: {} → {} ({})",
+                healed.from, healed.to, healed.strategy
+            ))
+        );
+    }
+}
+
+/// Replace `${name}` with matrix values. Unknown names are left as written.
+pub fn substitute(text: &str, params: &[(String, String)]) -> String {
+    params.iter().fold(text.to_string(), |text, (name, value)| {
+        text.replace(&format!("${{{}}}", name), value)
+    })
+}
+
+/// `login [viewport=mobile]` for a matrix run of `login.ab`, and
+/// `login [viewport=mobile, row 3]` for one of its `--data` rows.
+fn job_name(file: &Path, params: &[(String, String)], row: Option<usize>) -> String {
+    let stem = file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("script");
+    let mut values: Vec<String> = params.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    values.extend(row.map(|row| format!("row {}", row)));
+    if values.is_empty() {
+        return stem.to_string();
+    }
+    format!("{} [{}]", stem, values.join(", "))
+}
+
+/// One run of a script: a matrix combination and, with `--data`, a row
+pub struct Job {
+    pub script: PathBuf,
+    pub params: Vec<(String, String)>,
+    /// 1-based row number and its fields
+    pub row: Option<(usize, Row)>,
+}
+
+/// Which steps `--capture` attaches page state to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CaptureMode {
+    OnFailure,
+    Always,
+}
+
+impl CaptureMode {
+    fn parse(value: &str) -> Option<CaptureMode> {
+        match value {
+            "on-failure" => Some(CaptureMode::OnFailure),
+            "always" => Some(CaptureMode::Always),
+            _ => None,
+        }
+    }
+
+    fn applies(self, status: Status) -> bool {
+        match self {
+            CaptureMode::OnFailure => status == Status::Failed,
+            CaptureMode::Always => status != Status::Skipped,
+        }
+    }
+}
+
+/// Screenshot, URL, console tail and snapshot for a step's result. The
+/// screenshot is also listed as an artifact, so reporters attach it and
+/// `--upload` uploads it. Nothing is captured when no browser is running.
+fn capture_step(session: &str, result: &mut StepResult) {
+    if !daemon_ready(session) {
+        return;
+    }
+    let resp = send_command(
+        serde_json::json!({ "id": gen_id(), "action": "capture_step" }),
+        session,
+    );
+    let Some(data) = resp.ok().filter(|r| r.success).and_then(|r| r.data) else {
+        return;
+    };
+    let screenshot = data.get("path").and_then(|v| v.as_str());
+    result.artifacts.extend(screenshot.map(String::from));
+    result.capture = Some(serde_json::json!({
+        "url": data.get("url"),
+        "title": data.get("title"),
+        "screenshot": screenshot,
+        "console": data.get("console"),
+        "snapshot": data.get("snapshot"),
+        "truncated": data.get("truncated"),
+    }));
+}
+
+/// How each script is run.
+#[derive(Clone)]
+pub struct RunSettings {
+    /// Global flags forwarded to every step
+    pub global_args: Vec<String>,
+    /// Print steps as they finish
+    pub live: bool,
+    /// Extra attempts for a failing step
+    pub step_retries: u32,
+    /// Session the steps run in
+    pub session: String,
+    /// Capture page state after each step for `--trace`
+    pub trace: bool,
+    /// Checkpoint to restore instead of running the steps before it
+    pub resume_from: Option<String>,
+    /// Rerun steps whose selector stopped matching with a healed one
+    pub heal: bool,
+    /// Attach page state to failed or all steps
+    pub capture: Option<CaptureMode>,
+    /// Longest a step may take, retries and healing included
+    pub step_timeout: Option<Timeout>,
+    /// When the whole run has to be done by
+    pub deadline: Option<Limit>,
+    /// Env vars for every step, from the script's `timeout` lines
+    pub envs: Vec<(String, String)>,
+}
+
+impl RunSettings {
+    /// When the step starting now has to be done by: its step timeout or the
+    /// run deadline, whichever comes first.
+    fn step_limit(&self) -> Option<Limit> {
+        let step = self
+            .step_timeout
+            .as_ref()
+            .map(|t| Limit::new("step timeout", t, Instant::now()));
+        match (step, self.deadline.clone()) {
+            (Some(step), Some(deadline)) if deadline.at < step.at => Some(deadline),
+            (step, deadline) => step.or(deadline),
+        }
+    }
+
+    /// Apply a script's `timeout <level> <duration>` line for the rest of
+    /// the script. A level whose flag was given to `run` keeps the flag's value.
+    fn set_timeout(&mut self, level: &Level, ms: u64, source: String) {
+        if level.flag == timeouts::STEP.flag {
+            if self
+                .step_timeout
+                .as_ref()
+                .is_none_or(|t| t.source != level.flag)
+            {
+                self.step_timeout = Some(Timeout { ms, source });
+            }
+            return;
+        }
+        if self.global_args.iter().any(|a| a == level.flag) {
+            return;
+        }
+        let source_env = level.source_env();
+        self.envs
+            .retain(|(k, _)| k != level.env && *k != source_env);
+        self.envs.push((level.env.to_string(), format!("{}ms", ms)));
+        self.envs.push((source_env, source));
+    }
+}
+
+/// Run a step, retrying it up to `retries` more times while it fails.
+fn run_step_with_retries(
+    exe: &Path,
+    settings: &RunSettings,
+    step: &Step,
+    limit: Option<&Limit>,
+) -> StepResult {
+    let run = || run_limited(exe, &settings.global_args, &settings.envs, limit, step);
+    let mut result = run();
+    let mut retry_errors = Vec::new();
+    let mut duration_ms = result.duration_ms;
+    while result.status == Status::Failed
+        && retry_errors.len() < settings.step_retries as usize
+        && !limit.is_some_and(Limit::passed)
+        && !cancel::requested()
+    {
+        retry_errors.push(result.error.take().unwrap_or_default());
+        result = run();
+        duration_ms += result.duration_ms;
+    }
+    result.duration_ms = duration_ms;
+    result.attempts = retry_errors.len() as u32 + 1;
+    result.flaky = result.status == Status::Passed && !retry_errors.is_empty();
+    result.retry_errors = retry_errors;
+    result
+}
+
+/// Where a step's selector is in its arguments, for commands that take one.
+fn selector_index(args: &[String]) -> Option<usize> {
+    let index = match args.first()?.as_str() {
+        "click" | "dblclick" | "fill" | "type" | "hover" | "focus" | "check" | "uncheck"
+        | "select" | "drag" | "upload" | "download" | "scrollintoview" | "scrollinto" => 1,
+        "wait" if args.get(1).is_some_and(|a| a.parse::<u64>().is_err()) => 1,
+        "get"
+            if matches!(
+                args.get(1).map(String::as_str),
+                Some("text" | "html" | "value" | "attr" | "count" | "box" | "styles")
+            ) =>
+        {
+            2
+        }
+        "is" => 2,
+        _ => return None,
+    };
+    args.get(index)
+        .filter(|a| !a.is_empty() && !a.starts_with('-'))
+        .map(|_| index)
+}
+
+/// The data of a command's --json envelope, if it succeeded.
+fn query(exe: &Path, global_args: &[String], args: &[&str]) -> Option<Value> {
+    let output = Command::new(exe)
+        .env(upload::UPLOAD_ENV, "off")
+        .args(global_args)
+        .arg("--json")
+        .args(args)
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let envelope: Value = stdout
+        .lines()
+        .rev()
+        .find_map(|l| serde_json::from_str(l).ok())?;
+    let succeeded = envelope.get("success").and_then(|v| v.as_bool()) == Some(true);
+    (output.status.success() && succeeded).then(|| envelope.get("data").cloned())?
+}
+
+/// Rerun a step that failed because its selector matched nothing (or too
+/// much) with the selector `selectors heal` finds. The failure is kept if
+/// nothing is found, the step fails again or it ran out of time.
+fn heal_step(
+    exe: &Path,
+    settings: &RunSettings,
+    step: &Step,
+    limit: Option<&Limit>,
+    failed: StepResult,
+) -> StepResult {
+    let selector_failure = matches!(
+        ErrorCode::from_code(failed.code.as_deref()),
+        ErrorCode::SelectorNotFound | ErrorCode::SelectorAmbiguous | ErrorCode::Timeout
+    );
+    let index = match selector_index(&step.args) {
+        Some(index)
+            if settings.heal
+                && selector_failure
+                && !limit.is_some_and(Limit::passed)
+                && !cancel::requested() =>
+        {
+            index
+        }
+        _ => return failed,
+    };
+    let from = &step.args[index];
+    let Some(data) = query(exe, &settings.global_args, &["selectors", "heal", from]) else {
+        return failed;
+    };
+    let field = |key: &str| data.get(key).and_then(|v| v.as_str()).map(String::from);
+    let Some(to) = field("selector").filter(|to| to != from) else {
+        return failed;
+    };
+    let mut args = step.args.clone();
+    args[index] = to.clone();
+    let mut result = run_limited(
+        exe,
+        &settings.global_args,
+        &settings.envs,
+        limit,
+        &Step {
+            line: step.line,
+            args,
+        },
+    );
+    if result.status == Status::Failed {
+        return failed;
+    }
+    result.command = failed.command;
+    result.duration_ms += failed.duration_ms;
+    result.attempts = failed.attempts + 1;
+    result.retry_errors = failed.retry_errors;
+    result.retry_errors.extend(failed.error);
+    result.healed = Some(Healed {
+        from: from.clone(),
+        to,
+        strategy: field("strategy").unwrap_or_default(),
+    });
+    result
+}
+
+/// The name of a `checkpoint <name>` step, which the runner handles itself.
+fn checkpoint_name(step: &Step) -> Option<Result<&str, String>> {
+    if step.args.first().map(String::as_str) != Some("checkpoint") {
+        return None;
+    }
+    Some(match &step.args[1..] {
+        [name]
+            if name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') =>
+        {
+            Ok(name.as_str())
+        }
+        _ => Err(format!(
+            "line {}: expected 'checkpoint <name>' with a name of letters, digits, _ and -",
+            step.line
+        )),
+    })
+}
+
+/// Where the checkpoints of a script run are kept; matrix runs of the same
+/// script get their own.
+fn checkpoint_dir(job_name: &str) -> PathBuf {
+    let key: String = job_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    get_browseros_home().join("checkpoints").join(key)
+}
+
+/// Save the session and the script's variables at a checkpoint
+fn save_checkpoint(
+    exe: &Path,
+    settings: &RunSettings,
+    step: &Step,
+    dir: &Path,
+    name: &str,
+    params: &[(String, String)],
+) -> StepResult {
+    let session_file = dir.join(format!("{}.session.json", name));
+    if let Err(e) = fs::create_dir_all(dir) {
+        let mut result = StepResult::new(step, Status::Failed);
+        result.error = Some(format!("Failed to create {}: {}", dir.display(), e));
+        return result;
+    }
+    let save = Step {
+        line: step.line,
+        args: vec![
+            "session".to_string(),
+            "save".to_string(),
+            session_file.to_string_lossy().to_string(),
+        ],
+    };
+    let mut result = run_step(exe, &settings.global_args, &save);
+    result.command = display_command(&step.args);
+    result.artifacts.clear();
+    if result.status == Status::Passed {
+        let saved_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let state = serde_json::json!({
+            "checkpoint": name,
+            "line": step.line,
+            "savedAt": saved_at,
+            "params": params.iter().cloned().collect::<BTreeMap<_, _>>(),
+            "variables": vars::load(&settings.session),
+            "session": session_file,
+        });
+        let path = dir.join(format!("{}.json", name));
+        if let Err(e) = fs::write(&path, format!("{:#}\n", state)) {
+            result.status = Status::Failed;
+            result.error = Some(format!("Failed to write {}: {}", path.display(), e));
+        }
+    }
+    result
+}
+
+/// Restore what a checkpoint saved, in place of the steps before it
+fn restore_checkpoint(
+    exe: &Path,
+    settings: &RunSettings,
+    step: &Step,
+    dir: &Path,
+    name: &str,
+) -> StepResult {
+    let session_file = dir.join(format!("{}.session.json", name));
+    let restore = Step {
+        line: step.line,
+        args: vec![
+            "session".to_string(),
+            "restore".to_string(),
+            session_file.to_string_lossy().to_string(),
+        ],
+    };
+    let mut result = run_step(exe, &settings.global_args, &restore);
+    result.command = format!("{} (restored)", display_command(&step.args));
+    if result.status == Status::Passed {
+        let state = fs::read_to_string(dir.join(format!("{}.json", name)))
+            .ok()
+            .and_then(|text| serde_json::from_str::<Value>(&text).ok());
+        let variables = state
+            .and_then(|s| s.get("variables").cloned())
+            .and_then(|v| serde_json::from_value::<vars::Vars>(v).ok())
+            .unwrap_or_default();
+        if let Err(e) = vars::save(&settings.session, &variables) {
+            result.status = Status::Failed;
+            result.error = Some(e);
+        }
+    }
+    result
+}
+
+/// How many times an `until` block runs before the script fails
+const UNTIL_MAX: u32 = 30;
+
+/// What a script line is to the runner: a command, or one of the lines it
+/// evaluates itself. Block lines know where their block continues.
+enum Line {
+    Command,
+    Assert(String, Expr),
+    If {
+        text: String,
+        cond: Expr,
+        else_at: Option<usize>,
+        end: usize,
+    },
+    Else {
+        end: usize,
+    },
+    Until {
+        text: String,
+        cond: Expr,
+        max: u32,
+        end: usize,
+    },
+    End {
+        start: usize,
+    },
+}
+
+/// The text after the keyword of an `assert`, `if` or `until` line, as
+/// written, since splitting it into arguments would drop its quotes.
+fn expression_text(source: &str, step: &Step) -> String {
+    let line = source.lines().nth(step.line - 1).unwrap_or_default().trim();
+    let line = match line.strip_prefix("agent-browser") {
+        Some(rest) if rest.starts_with(char::is_whitespace) => rest.trim_start(),
+        _ => line,
+    };
+    line.get(step.args[0].len()..)
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+/// Parse expressions and match `if`/`else`/`until` with their `end` lines.
+fn parse_lines(steps: &[Step], source: &str) -> Result<Vec<Line>, String> {
+    let mut lines: Vec<Line> = Vec::new();
+    let mut open: Vec<usize> = Vec::new();
+    for (i, step) in steps.iter().enumerate() {
+        let at = |e: String| format!("line {}: {}", step.line, e);
+        let keyword = step.args[0].as_str();
+        if matches!(keyword, "else" | "end") && step.args.len() > 1 {
+            return Err(at(format!("'{}' goes on a line of its own", keyword)));
+        }
+        let line = match keyword {
+            "assert" => {
+                let text = expression_text(source, step);
+                Line::Assert(text.clone(), expr::parse(&text).map_err(at)?)
+            }
+            "if" => {
+                let text = expression_text(source, step);
+                open.push(i);
+                Line::If {
+                    cond: expr::parse(&text).map_err(at)?,
+                    text,
+                    else_at: None,
+                    end: 0,
+                }
+            }
+            "until" => {
+                let mut text = expression_text(source, step);
+                let mut max = UNTIL_MAX;
+                if let Some((cond, n)) = text.rsplit_once(" --max ") {
+                    max = n
+                        .trim()
+                        .parse()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| at(format!("invalid --max: {}", n.trim())))?;
+                    text = cond.trim().to_string();
+                }
+                open.push(i);
+                Line::Until {
+                    cond: expr::parse(&text).map_err(at)?,
+                    text,
+                    max,
+                    end: 0,
+                }
+            }
+            "else" => match open.last().map(|&start| &mut lines[start]) {
+                Some(Line::If { else_at, .. }) if else_at.is_none() => {
+                    *else_at = Some(i);
+                    Line::Else { end: 0 }
+                }
+                _ => return Err(at("'else' without 'if'".to_string())),
+            },
+            "end" => {
+                let start = open
+                    .pop()
+                    .ok_or_else(|| at("'end' without 'if' or 'until'".to_string()))?;
+                match &mut lines[start] {
+                    Line::If { else_at, end, .. } => {
+                        *end = i;
+                        if let Some(else_at) = *else_at {
+                            lines[else_at] = Line::Else { end: i };
+                        }
+                    }
+                    Line::Until { end, .. } => *end = i,
+                    _ => {}
+                }
+                Line::End { start }
+            }
+            _ => Line::Command,
+        };
+        lines.push(line);
+    }
+    if let Some(&start) = open.last() {
+        return Err(format!(
+            "line {}: '{}' has no 'end'",
+            steps[start].line, steps[start].args[0]
+        ));
+    }
+    Ok(lines)
+}
+
+/// Evaluate an expression with the session's variables and the run's
+/// parameters, returning whether it holds and the values it read.
+fn evaluate(
+    text: &str,
+    cond: &Expr,
+    settings: &RunSettings,
+    params: &[(String, String)],
+) -> Result<(bool, String), String> {
+    let mut variables = vars::load(&settings.session);
+    for (name, value) in params {
+        variables
+            .entry(name.clone())
+            .or_insert_with(|| Value::String(value.clone()));
+    }
+    let value =
+        expr::eval(cond, &variables).map_err(|e| format!("Cannot evaluate '{}': {}", text, e))?;
+    Ok((expr::truthy(&value), expr::describe(cond, &variables)))
+}
+
+/// A failed check, with the values the expression read
+fn check_failed(step: &Step, text: &str, message: String, values: &str) -> StepResult {
+    let mut result = StepResult::new(step, Status::Failed);
+    result.command = format!("{} {}", step.args[0], text);
+    result.error = Some(if values.is_empty() {
+        message
+    } else {
+        format!("{} ({})", message, values)
+    });
+    result
+}
+
+/// Run every step of a script, stopping at the first failure.
+pub fn run_script(job: &Job, settings: &RunSettings) -> ScriptResult {
+    let file = job.script.as_path();
+    let name = job_name(file, &job.params, job.row.as_ref().map(|(row, _)| *row));
+    let mut params = job.params.clone();
+    if let Some((_, fields)) = &job.row {
+        params.extend(fields.iter().cloned());
+        // Steps are separate invocations, which read {{field}} from the store
+        let mut store = vars::load(&settings.session);
+        for (field, value) in fields {
+            store.insert(field.clone(), Value::String(value.clone()));
+        }
+        if let Err(e) = vars::save(&settings.session, &store) {
+            ui::fail(ErrorCode::Unknown, e);
+        }
+    }
+    let params = params.as_slice();
+    let text = fs::read_to_string(file).unwrap_or_else(|e| {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            format!("Failed to read {}: {}", file.display(), e),
+        )
+    });
+    let source = substitute(&text, params);
+    let steps = parse_script(&source)
+        .unwrap_or_else(|e| ui::fail(ErrorCode::InvalidArgs, format!("{}: {}", file.display(), e)));
+    let exe = env::current_exe().unwrap_or_else(|_| PathBuf::from("agent-browser"));
+
+    if settings.trace {
+        trace::reset(&settings.session);
+    }
+    let checkpoints = checkpoint_dir(&name);
+    for step in &steps {
+        if let Some(Err(e)) = checkpoint_name(step) {
+            ui::fail(ErrorCode::InvalidArgs, format!("{}: {}", file.display(), e));
+        }
+        if let Some(Err(e)) = timeouts::parse_script_line(&step.args) {
+            ui::fail(
+                ErrorCode::InvalidArgs,
+                format!("{}: line {}: {}", file.display(), step.line, e),
+            );
+        }
+    }
+    // `timeout` lines change the settings for the rest of this script
+    let mut scoped = settings.clone();
+    let settings = &mut scoped;
+    let lines = parse_lines(&steps, &source)
+        .unwrap_or_else(|e| ui::fail(ErrorCode::InvalidArgs, format!("{}: {}", file.display(), e)));
+    // Index of the checkpoint step to resume from
+    let resume_at = settings.resume_from.as_deref().map(|resume| {
+        let index = steps
+            .iter()
+            .position(|s| matches!(checkpoint_name(s), Some(Ok(n)) if n == resume))
+            .unwrap_or_else(|| {
+                ui::fail(
+                    ErrorCode::InvalidArgs,
+                    format!("{} has no 'checkpoint {}' line", file.display(), resume),
+                )
+            });
+        if !checkpoints.join(format!("{}.json", resume)).is_file() {
+            ui::fail(
+                ErrorCode::InvalidArgs,
+                format!(
+                    "Checkpoint '{}' of {} has not been saved yet; run the script without --resume-from first",
+                    resume, name
+                ),
+            );
+        }
+        index
+    });
+
+    let started = Instant::now();
+    let mut results: Vec<StepResult> = Vec::new();
+    // Times each `until` block has run, by the index of its line
+    let mut iterations: BTreeMap<usize, u32> = BTreeMap::new();
+    let mut i = 0;
+    while i < steps.len() {
+        let step = &steps[i];
+        let mut next = i + 1;
+        let checkpoint = checkpoint_name(step).and_then(Result::ok);
+        if let Some(Ok((level, ms))) = timeouts::parse_script_line(&step.args) {
+            let source = format!("{} line {}", file.display(), step.line);
+            settings.set_timeout(level, ms, source);
+            i = next;
+            continue;
+        }
+        let result = if results.iter().any(|r| r.status == Status::Failed)
+            || resume_at.is_some_and(|at| i < at)
+        {
+            matches!(lines[i], Line::Command | Line::Assert(..))
+                .then(|| StepResult::new(step, Status::Skipped))
+        } else if let Some(deadline) = settings.deadline.as_ref().filter(|d| d.passed()) {
+            matches!(lines[i], Line::Command | Line::Assert(..)).then(|| deadline.missed(step))
+        } else if cancel::requested() {
+            matches!(lines[i], Line::Command | Line::Assert(..)).then(|| {
+                let mut result = StepResult::new(step, Status::Failed);
+                result.error = Some("Cancelled".to_string());
+                result.code = Some(ErrorCode::Cancelled.as_str().to_string());
+                result
+            })
+        } else {
+            match &lines[i] {
+                Line::Command => Some(if let Some(checkpoint) = checkpoint {
+                    if resume_at == Some(i) {
+                        restore_checkpoint(&exe, settings, step, &checkpoints, checkpoint)
+                    } else {
+                        save_checkpoint(&exe, settings, step, &checkpoints, checkpoint, params)
+                    }
+                } else {
+                    let limit = settings.step_limit();
+                    let mut result = run_step_with_retries(&exe, settings, step, limit.as_ref());
+                    if result.status == Status::Failed {
+                        result = heal_step(&exe, settings, step, limit.as_ref(), result);
+                    }
+                    let closes = matches!(
+                        step.args.first().map(String::as_str),
+                        Some("close" | "quit" | "exit")
+                    );
+                    if settings.trace && !closes {
+                        result.trace = trace::capture(&settings.session);
+                    }
+                    result
+                }),
+                Line::Assert(text, cond) => {
+                    let checked = Instant::now();
+                    let mut result = match evaluate(text, cond, settings, params) {
+                        Ok((true, _)) => {
+                            let mut result = StepResult::new(step, Status::Passed);
+                            result.command = format!("assert {}", text);
+                            result
+                        }
+                        Ok((false, values)) => {
+                            check_failed(step, text, format!("Assertion failed: {}", text), &values)
+                        }
+                        Err(e) => check_failed(step, text, e, ""),
+                    };
+                    result.duration_ms = checked.elapsed().as_millis() as u64;
+                    Some(result)
+                }
+                Line::If {
+                    text,
+                    cond,
+                    else_at,
+                    end,
+                } => match evaluate(text, cond, settings, params) {
+                    Ok((true, _)) => None,
+                    Ok((false, _)) => {
+                        next = else_at.unwrap_or(*end) + 1;
+                        None
+                    }
+                    Err(e) => Some(check_failed(step, text, e, "")),
+                },
+                Line::Else { end } => {
+                    next = end + 1;
+                    None
+                }
+                Line::Until {
+                    text, cond, end, ..
+                } => {
+                    iterations.insert(i, 0);
+                    match evaluate(text, cond, settings, params) {
+                        Ok((true, _)) => {
+                            next = end + 1;
+                            None
+                        }
+                        Ok((false, _)) => None,
+                        Err(e) => Some(check_failed(step, text, e, "")),
+                    }
+                }
+                Line::End { start } => match &lines[*start] {
+                    Line::Until {
+                        text, cond, max, ..
+                    } => {
+                        let opening = &steps[*start];
+                        match evaluate(text, cond, settings, params) {
+                            Ok((true, _)) => None,
+                            Ok((false, values)) => {
+                                let count = iterations.entry(*start).or_default();
+                                *count += 1;
+                                if *count >= *max {
+                                    Some(check_failed(
+                                        opening,
+                                        text,
+                                        format!("Still false after {} iterations: {}", max, text),
+                                        &values,
+                                    ))
+                                } else {
+                                    next = start + 1;
+                                    None
+                                }
+                            }
+                            Err(e) => Some(check_failed(opening, text, e, "")),
+                        }
+                    }
+                    _ => None,
+                },
+            }
+        };
+        if let Some(mut result) = result {
+            if settings
+                .capture
+                .is_some_and(|mode| mode.applies(result.status))
+            {
+                capture_step(&settings.session, &mut result);
+            }
+            if settings.live {
+                print_step(&result);
+            }
+            results.push(result);
+        }
+        i = next;
+    }
+    let status = if results.iter().any(|r| r.status == Status::Failed) {
+        Status::Failed
+    } else {
+        Status::Passed
+    };
+    ScriptResult {
+        name,
+        file: file.to_path_buf(),
+        params: params.iter().cloned().collect(),
+        row: job.row.as_ref().map(|(row, _)| *row),
+        status,
+        duration_ms: started.elapsed().as_millis() as u64,
+        attempts: 1,
+        flaky: results.iter().any(|r| r.flaky),
+        quarantined: false,
+        source,
+        steps: results,
+    }
+}
+
+/// Replace the selector `from` in a script line with `to`, leaving the rest
+/// of the line as written. None if `from` isn't written out as the line's
+/// selector, as when it comes from a `${name}` or `{{name}}`.
+fn replace_argument(line: &str, from: &str, to: &str) -> Option<String> {
+    let before = split_line(line).ok()?;
+    let offset = usize::from(before.first().map(String::as_str) == Some("agent-browser"));
+    let index = offset + selector_index(&before[offset..])?;
+    if before[index] != from {
+        return None;
+    }
+    let mut expected = before.clone();
+    expected[index] = to.to_string();
+    let spellings = [
+        display_command(&[from.to_string()]),
+        format!("\"{}\"", from.replace('\\', "\\\\").replace('"', "\\\"")),
+        format!("'{}'", from),
+        from.to_string(),
+    ];
+    for spelling in &spellings {
+        for (at, _) in line.match_indices(spelling.as_str()) {
+            let end = at + spelling.len();
+            let bounded = line[..at].chars().last().is_none_or(char::is_whitespace)
+                && line[end..].chars().next().is_none_or(char::is_whitespace);
+            let replaced = format!(
+                "{}{}{}",
+                &line[..at],
+                display_command(&[to.to_string()]),
+                &line[end..]
+            );
+            // Only the selector may change
+            if bounded && split_line(&replaced).ok().as_ref() == Some(&expected) {
+                return Some(replaced);
+            }
+        }
+    }
+    None
+}
+
+/// Write the selectors healed in a run back to its scripts.
+fn update_selectors(results: &[ScriptResult]) {
+    let mut by_file: BTreeMap<&Path, BTreeMap<usize, &Healed>> = BTreeMap::new();
+    for script in results {
+        for step in &script.steps {
+            if let Some(healed) = &step.healed {
+                by_file
+                    .entry(script.file.as_path())
+                    .or_default()
+                    .insert(step.line, healed);
+            }
+        }
+    }
+    for (file, healed) in by_file {
+        let text = match fs::read_to_string(file) {
+            Ok(text) => text,
+            Err(e) => {
+                ui::warn(format!("Failed to read {}: {}", file.display(), e));
+                continue;
+            }
+        };
+        let mut lines: Vec<String> = text.lines().map(String::from).collect();
+        let mut updated = 0;
+        for (line, healed) in healed {
+            let Some(written) = lines.get_mut(line - 1) else {
+                continue;
+            };
+            match replace_argument(written, &healed.from, &healed.to) {
+                Some(replaced) => {
+                    *written = replaced;
+                    updated += 1;
+                }
+                None => ui::warn(format!(
+                    "{}:{}: {} is not written out in the script; left unchanged",
+                    file.display(),
+                    line,
+                    healed.from
+                )),
+            }
+        }
+        if updated == 0 {
+            continue;
+        }
+        let mut text_out = lines.join("\n");
+        if text.ends_with('\n') {
+            text_out.push('\n');
+        }
+        match fs::write(file, text_out) {
+            Ok(()) => ui::status(format!(
+                "{} Updated {} selector(s) in {}",
+                color::success_indicator(),
+                updated,
+                file.display()
+            )),
+            Err(e) => ui::warn(format!("Failed to write {}: {}", file.display(), e)),
+        }
+    }
+}
+
+/// One `--sink` row per step, with the script it belongs to
+fn sink_rows(results: &[ScriptResult]) -> Vec<serde_json::Map<String, Value>> {
+    let mut rows = Vec::new();
+    for script in results {
+        for step in &script.steps {
+            let row = serde_json::json!({
+                "script": script.name,
+                "file": script.file,
+                "data_row": script.row,
+                "params": (!script.params.is_empty()).then_some(&script.params),
+                "script_status": script.status,
+                "line": step.line,
+                "step": step.command,
+                "status": step.status,
+                "duration_ms": step.duration_ms,
+                "attempts": step.attempts,
+                "flaky": step.flaky,
+                "error": step.error,
+                "code": step.code,
+            });
+            rows.extend(row.as_object().cloned());
+        }
+    }
+    rows
+}
+
+/// Scripts listed in a quarantine file: one script name or path per line,
+/// with `#` comments.
+pub fn load_quarantine(path: &Path) -> Result<Vec<String>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(text
+        .lines()
+        .map(|l| l.split('#').next().unwrap_or_default().trim())
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// An entry matches the script's name (`login`), its matrix name
+/// (`login [device=Pixel 7]`), or the end of its path (`flows/login.ab`).
+pub fn is_quarantined(quarantine: &[String], file: &Path, name: &str) -> bool {
+    let stem = file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    quarantine
+        .iter()
+        .any(|entry| entry == stem || entry == name || file.ends_with(entry))
+}
+
+const USAGE: &str = "run <script|dir>... [--reporter pretty|json|junit|github] [--output <file>] [--shard <i>/<n>] [--matrix <name>=<a>,<b>]... [--step-retries <n>] [--script-retries <n>] [--quarantine <file>] [--trace <file.html|dir>] [--upload <s3://|gs://...>] [--resume-from <checkpoint>] [--data <rows.csv|rows.ndjson>] [--parallel <n>] [--no-heal] [--update-selectors] [--capture on-failure|always] [--step-timeout <duration>] [--deadline <duration>]";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct RunOptions {
+    pub paths: Vec<String>,
+    pub reporter: Option<Reporter>,
+    pub output: Option<PathBuf>,
+    /// 1-based shard index and shard count
+    pub shard: Option<(usize, usize)>,
+    pub matrix: Vec<(String, Vec<String>)>,
+    pub step_retries: u32,
+    pub script_retries: u32,
+    pub quarantine: Option<PathBuf>,
+    pub trace: Option<PathBuf>,
+    pub upload: Option<String>,
+    pub resume_from: Option<String>,
+    pub data: Option<PathBuf>,
+    /// Sessions to spread the runs over; 0 when not given (one)
+    pub parallel: usize,
+    pub no_heal: bool,
+    pub update_selectors: bool,
+    pub capture: Option<CaptureMode>,
+    pub step_timeout: Option<String>,
+    pub deadline: Option<String>,
+}
+
+/// Parse `2/5` into `(2, 5)`.
+fn parse_shard(value: &str) -> Option<(usize, usize)> {
+    let (index, count) = value.split_once('/')?;
+    let (index, count) = (index.parse().ok()?, count.parse().ok()?);
+    (index >= 1 && index <= count).then_some((index, count))
+}
+
+/// Parse `viewport=desktop,mobile`.
+fn parse_dimension(value: &str) -> Option<(String, Vec<String>)> {
+    let (name, values) = value.split_once('=')?;
+    let values: Vec<String> = values
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(String::from)
+        .collect();
+    let valid_name = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    (valid_name && !values.is_empty()).then(|| (name.to_string(), values))
+}
+
+pub fn parse_run_args(args: &[String]) -> Result<RunOptions, String> {
+    let mut options = RunOptions::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .map(|v| v.as_str())
+                .ok_or_else(|| format!("{} requires a value", arg))
+        };
+        match arg.as_str() {
+            "--reporter" | "-r" => {
+                let name = value()?;
+                options.reporter = Some(Reporter::parse(name).ok_or_else(|| {
+                    format!(
+                        "Invalid --reporter value: '{}' (expected {})",
+                        name,
+                        Reporter::NAMES.join(", ")
+                    )
+                })?);
+            }
+            "--output" | "-o" => options.output = Some(PathBuf::from(value()?)),
+            "--shard" => {
+                let shard = value()?;
+                options.shard = Some(parse_shard(shard).ok_or_else(|| {
+                    format!("Invalid --shard value: '{}' (expected e.g. 2/5)", shard)
+                })?);
+            }
+            "--matrix" => {
+                let dimension = value()?;
+                let (name, values) = parse_dimension(dimension).ok_or_else(|| {
+                    format!(
+                        "Invalid --matrix value: '{}' (expected name=a,b,...)",
+                        dimension
+                    )
+                })?;
+                options.matrix.retain(|(n, _)| *n != name);
+                options.matrix.push((name, values));
+            }
+            "--step-retries" | "--script-retries" => {
+                let count = value()?;
+                let count: u32 = count.parse().map_err(|_| {
+                    format!(
+                        "Invalid {} value: '{}' (expected a non-negative integer)",
+                        arg, count
+                    )
+                })?;
+                if arg == "--step-retries" {
+                    options.step_retries = count;
+                } else {
+                    options.script_retries = count;
+                }
+            }
+            "--quarantine" => options.quarantine = Some(PathBuf::from(value()?)),
+            "--resume-from" => options.resume_from = Some(value()?.to_string()),
+            "--data" => options.data = Some(PathBuf::from(value()?)),
+            "--parallel" => {
+                let count = value()?;
+                options.parallel = count.parse().ok().filter(|&n| n > 0).ok_or_else(|| {
+                    format!(
+                        "Invalid --parallel value: '{}' (expected a positive integer)",
+                        count
+                    )
+                })?;
+            }
+            "--trace" => options.trace = Some(PathBuf::from(value()?)),
+            "--step-timeout" | "--deadline" => {
+                let duration = value()?;
+                timeouts::parse(arg, duration)?;
+                let slot = match arg.as_str() {
+                    "--step-timeout" => &mut options.step_timeout,
+                    _ => &mut options.deadline,
+                };
+                *slot = Some(duration.to_string());
+            }
+            "--capture" => {
+                let mode = value()?;
+                options.capture = Some(CaptureMode::parse(mode).ok_or_else(|| {
+                    format!(
+                        "Invalid --capture value: '{}' (expected on-failure or always)",
+                        mode
+                    )
+                })?);
+            }
+            "--no-heal" => options.no_heal = true,
+            "--update-selectors" => options.update_selectors = true,
+            "--upload" => {
+                let destination = value()?;
+                upload::Destination::parse(destination)?;
+                options.upload = Some(destination.to_string());
+            }
+            flag if flag.starts_with('-') => return Err(format!("Unknown option: {}", flag)),
+            path => options.paths.push(path.to_string()),
+        }
+    }
+    if options.paths.is_empty() {
+        return Err("Missing script".to_string());
+    }
+    if options.no_heal && options.update_selectors {
+        return Err("--update-selectors needs healing; drop --no-heal".to_string());
+    }
+    Ok(options)
+}
+
+/// Scripts named on the command line, with directories searched recursively
+/// for `*.ab` files. Sorted, so every shard sees the same order.
+fn discover(paths: &[String]) -> Result<Vec<PathBuf>, String> {
+    fn walk(dir: &Path, found: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                walk(&path, found)?;
+            } else if path.extension().is_some_and(|e| e == SCRIPT_EXTENSION) {
+                found.push(path);
+            }
+        }
+        Ok(())
+    }
+    let mut scripts = Vec::new();
+    for path in paths.iter().map(PathBuf::from) {
+        if path.is_dir() {
+            let mut found = Vec::new();
+            walk(&path, &mut found)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            if found.is_empty() {
+                return Err(format!(
+                    "No .{} scripts found in {}",
+                    SCRIPT_EXTENSION,
+                    path.display()
+                ));
+            }
+            scripts.extend(found);
+        } else if path.is_file() {
+            scripts.push(path);
+        } else {
+            return Err(format!("Script not found: {}", path.display()));
+        }
+    }
+    scripts.sort();
+    scripts.dedup();
+    Ok(scripts)
+}
+
+const SCRIPT_EXTENSION: &str = "ab";
+
+/// Every combination of matrix values, in the order the dimensions were given.
+pub fn expand_matrix(matrix: &[(String, Vec<String>)]) -> Vec<Vec<(String, String)>> {
+    matrix
+        .iter()
+        .fold(vec![Vec::new()], |combos, (name, values)| {
+            combos
+                .iter()
+                .flat_map(|combo| {
+                    values.iter().map(move |value| {
+                        let mut combo = combo.clone();
+                        combo.push((name.clone(), value.clone()));
+                        combo
+                    })
+                })
+                .collect()
+        })
+}
+
+/// Keep every `count`-th job starting at `index` (1-based), so shards are
+/// disjoint, cover every job, and differ in size by at most one.
+pub fn shard<T>(jobs: Vec<T>, index: usize, count: usize) -> Vec<T> {
+    jobs.into_iter()
+        .enumerate()
+        .filter(|(i, _)| i % count == index - 1)
+        .map(|(_, job)| job)
+        .collect()
+}
+
+/// Global flags with the session replaced by `session`
+fn with_session(global_args: &[String], session: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut iter = global_args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--session" {
+            iter.next();
+        } else {
+            args.push(arg.clone());
+        }
+    }
+    args.extend(["--session".to_string(), session.to_string()]);
+    args
+}
+
+/// Run jobs on `workers` threads, each in a session of its own, and close
+/// those sessions at the end. Steps aren't printed as they run, since runs
+/// interleave; each run prints one line when it ends. Results keep job order.
+fn run_parallel<F>(
+    jobs: &[Job],
+    settings: &RunSettings,
+    workers: usize,
+    run_job: F,
+) -> Vec<ScriptResult>
+where
+    F: Fn(&Job, &RunSettings) -> ScriptResult + Sync,
+{
+    let exe = env::current_exe().unwrap_or_else(|_| PathBuf::from("agent-browser"));
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<ScriptResult>>> = Mutex::new(jobs.iter().map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for worker in 1..=workers {
+            let (next, results, run_job, exe) = (&next, &results, &run_job, &exe);
+            scope.spawn(move || {
+                let session = format!("{}-{}", settings.session, worker);
+                let worker_settings = RunSettings {
+                    global_args: with_session(&settings.global_args, &session),
+                    live: false,
+                    session,
+                    ..settings.clone()
+                };
+                loop {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(job) = jobs.get(i).filter(|_| !cancel::requested()) else {
+                        break;
+                    };
+                    let result = run_job(job, &worker_settings);
+                    if settings.live {
+                        let indicator = match result.status {
+                            Status::Passed if result.flaky => color::warning_indicator(),
+                            Status::Passed => color::success_indicator(),
+                            _ => color::error_indicator(),
+                        };
+                        println!(
+                            "{} {} {}",
+                            indicator,
+                            result.name,
+                            color::dim(&format!(
+                                "({}ms, {})",
+                                result.duration_ms, worker_settings.session
+                            ))
+                        );
+                    }
+                    results.lock().unwrap()[i] = Some(result);
+                }
+                let close = Step {
+                    line: 0,
+                    args: vec!["close".to_string()],
+                };
+                run_step(exe, &worker_settings.global_args, &close);
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Handle `agent-browser run <script|dir>...` (runs locally; each step starts
+/// the daemon as needed).
+pub fn run_command(clean: &[String], global_args: &[String], session: &str) {
+    let options = parse_run_args(&clean[1..]).unwrap_or_else(|e| {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            format!("{}\nUsage: agent-browser {}", e, USAGE),
+        )
+    });
+    let reporter = options.reporter.unwrap_or(if ui::json_mode() {
+        Reporter::Json
+    } else {
+        Reporter::Pretty
+    });
+    let output = options.output;
+    let quarantine = match &options.quarantine {
+        Some(path) => load_quarantine(path).unwrap_or_else(|e| ui::fail(ErrorCode::InvalidArgs, e)),
+        None => Vec::new(),
+    };
+    let scripts = discover(&options.paths).unwrap_or_else(|e| ui::fail(ErrorCode::InvalidArgs, e));
+    let sink = parse_flags(global_args)
+        .sink
+        .map(|uri| sink::parse(&uri).unwrap_or_else(|e| ui::fail(ErrorCode::InvalidArgs, e)));
+    let rows: Vec<Option<(usize, Row)>> = match &options.data {
+        Some(path) => dataset::load_rows(path)
+            .unwrap_or_else(|e| ui::fail(ErrorCode::InvalidArgs, e))
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| Some((i + 1, row)))
+            .collect(),
+        None => vec![None],
+    };
+    let mut jobs: Vec<Job> = Vec::new();
+    for script in &scripts {
+        for params in expand_matrix(&options.matrix) {
+            for row in &rows {
+                jobs.push(Job {
+                    script: script.clone(),
+                    params: params.clone(),
+                    row: row.clone(),
+                });
+            }
+        }
+    }
+    let total_jobs = jobs.len();
+    if let Some((index, count)) = options.shard {
+        jobs = shard(jobs, index, count);
+        tracing::info!(index, count, jobs = jobs.len(), total_jobs, "running shard");
+    }
+    let config = Config::load();
+    let resolve = |level, flag: &Option<String>| {
+        timeouts::resolve(level, flag.as_deref(), &config)
+            .unwrap_or_else(|e| ui::fail(ErrorCode::InvalidArgs, e))
+    };
+    let step_timeout = resolve(&timeouts::STEP, &options.step_timeout);
+    let deadline = resolve(&timeouts::DEADLINE, &options.deadline);
+    let settings = RunSettings {
+        // Steps report through their own --json envelope, store, filter and
+        // write results with their own --into, --json-path and --out, and the
+        // run appends the steps to --sink itself
+        global_args: global_args
+            .iter()
+            .scan(false, |after_value, a| {
+                let takes_value = matches!(
+                    a.as_str(),
+                    "--into" | "--json-path" | "--output-format" | "--out" | "--sink"
+                );
+                let skip = *after_value || a == "--json" || takes_value;
+                *after_value = takes_value;
+                Some((!skip).then(|| a.clone()))
+            })
+            .flatten()
+            .collect(),
+        // Progress goes to stdout unless a machine-readable report does
+        live: !ui::json_mode() && (reporter.is_live() || output.is_some()),
+        step_retries: options.step_retries,
+        session: session.to_string(),
+        trace: options.trace.is_some(),
+        resume_from: options.resume_from.clone(),
+        heal: !options.no_heal,
+        capture: options.capture,
+        step_timeout,
+        deadline: deadline.map(|d| Limit::new("run deadline", &d, Instant::now())),
+        envs: Vec::new(),
+    };
+    let live = settings.live;
+    let run_job = |job: &Job, settings: &RunSettings| {
+        let mut result = run_script(job, settings);
+        let mut attempts = 1;
+        while result.status == Status::Failed
+            && attempts <= options.script_retries
+            && !cancel::requested()
+        {
+            attempts += 1;
+            if live {
+                println!(
+                    "{} Retrying {} (attempt {} of {})",
+                    color::warning_indicator(),
+                    result.name,
+                    attempts,
+                    options.script_retries + 1
+                );
+            }
+            result = run_script(job, settings);
+        }
+        result.attempts = attempts;
+        result.flaky |= result.status == Status::Passed && attempts > 1;
+        result.quarantined = result.status == Status::Failed
+            && is_quarantined(&quarantine, &job.script, &result.name);
+        if live && result.quarantined {
+            println!(
+                "{} {} is quarantined; its failure does not fail the run",
+                color::warning_indicator(),
+                result.name
+            );
+        }
+        result
+    };
+    let workers = options.parallel.min(jobs.len());
+    // Ctrl-C (or a cancelled job) stops the step in flight in every session
+    // and skips the rest; what ran is still reported
+    cancel::install(if workers > 1 {
+        (1..=workers)
+            .map(|w| format!("{}-{}", session, w))
+            .collect()
+    } else {
+        vec![session.to_string()]
+    });
+    let results: Vec<ScriptResult> = if workers > 1 {
+        run_parallel(&jobs, &settings, workers, run_job)
+    } else {
+        let mut results = Vec::new();
+        for (i, job) in jobs.iter().enumerate() {
+            if cancel::requested() {
+                break;
+            }
+            if live && jobs.len() > 1 {
+                if i > 0 {
+                    println!();
+                }
+                let row = job.row.as_ref().map(|(row, _)| *row);
+                println!("{}", color::bold(&job_name(&job.script, &job.params, row)));
+            }
+            results.push(run_job(job, &settings));
+        }
+        results
+    };
+    if cancel::requested() {
+        ui::warn("Cancelled; reporting the steps that ran");
+    }
+    if options.update_selectors {
+        update_selectors(&results);
+    }
+    let rendered = report::render(reporter, &results);
+    if let Some(path) = &options.trace {
+        trace::write_bundle(path, &results).unwrap_or_else(|e| ui::fail(ErrorCode::Unknown, e));
+    }
+
+    if let Some(path) = &output {
+        if let Err(e) = fs::write(path, &rendered) {
+            ui::fail(
+                ErrorCode::Unknown,
+                format!("Failed to write {}: {}", path.display(), e),
+            );
+        }
+    }
+    let destination = options
+        .upload
+        .clone()
+        .or_else(upload::configured_destination);
+    let mut uploaded = Vec::new();
+    let mut upload_error = None;
+    if let Some(destination) = &destination {
+        let mut paths: Vec<PathBuf> = output.iter().chain(&options.trace).cloned().collect();
+        paths.extend(
+            results
+                .iter()
+                .flat_map(|s| &s.steps)
+                .flat_map(|r| &r.artifacts)
+                .map(PathBuf::from),
+        );
+        match upload::upload(destination, &upload::run_folder(session), &paths) {
+            Ok(urls) => uploaded = urls,
+            Err(e) => upload_error = Some(e),
+        }
+    }
+
+    let mut sunk = 0;
+    let mut sink_error = None;
+    if let Some(sink) = &sink {
+        let collected_at = sink::now_ms();
+        let rows: Vec<_> = sink_rows(&results)
+            .into_iter()
+            .map(|row| (collected_at, row))
+            .collect();
+        match sink.append(session, "run", &rows) {
+            Ok(n) => sunk = n,
+            Err(e) => sink_error = Some(e),
+        }
+    }
+
+    let failed = results
+        .iter()
+        .filter(|s| s.is_hard_failure())
+        .flat_map(|s| &s.steps)
+        .find(|r| r.status == Status::Failed);
+    let code = match (failed, &upload_error) {
+        (Some(step), _) => ErrorCode::from_code(step.code.as_deref()),
+        (None, Some(_)) => ErrorCode::UploadFailed,
+        (None, None) => ErrorCode::Unknown,
+    };
+    let error = match (failed, &upload_error) {
+        (Some(step), _) => Some(format!(
+            "Step failed at line {}: {}",
+            step.line, step.command
+        )),
+        (None, upload_error) => upload_error.clone().or_else(|| sink_error.clone()),
+    };
+
+    if ui::json_mode() {
+        let mut data = report::to_json(&results);
+        if destination.is_some() {
+            data["uploads"] = serde_json::json!(uploaded);
+        }
+        let mut out = ui::envelope(error.is_none(), Some(data), error.as_deref());
+        if error.is_some() {
+            out["code"] = serde_json::json!(code.as_str());
+        }
+        println!("{}", out);
+    } else {
+        if let Some(path) = &output {
+            println!("\n{}", report::summary_line(&results));
+            ui::status(color::dim(&format!("Report written to {}", path.display())));
+        } else {
+            print!("{}", rendered);
+        }
+        if let (Some(destination), Some(first)) = (&destination, uploaded.first()) {
+            let folder = first
+                .rsplit_once('/')
+                .map_or(first.as_str(), |(dir, _)| dir);
+            ui::status(format!(
+                "{} Uploaded {} files to {}",
+                color::success_indicator(),
+                uploaded.len(),
+                if folder.starts_with(destination.as_str()) {
+                    folder
+                } else {
+                    destination
+                }
+            ));
+        }
+        if let Some(e) = &upload_error {
+            eprintln!("{} {}", color::error_indicator(), e);
+        }
+        match (&sink, &sink_error) {
+            (Some(_), Some(e)) => eprintln!("{} {}", color::error_indicator(), e),
+            (Some(sink), None) => ui::status(color::dim(&format!(
+                "Appended {} step(s) to {}",
+                sunk,
+                sink.display()
+            ))),
+            _ => {}
+        }
+    }
+
+    if error.is_some() {
+        std::process::exit(code.exit_code());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_line() {
+        assert_eq!(
+            split_line(r#"fill #email  "a b@example.com""#).unwrap(),
+            vec!["fill", "#email", "a b@example.com"]
+        );
+        assert_eq!(
+            split_line(r#"eval 'document.title' x\ y "say \"hi\"""#).unwrap(),
+            vec!["eval", "document.title", "x y", r#"say "hi""#]
+        );
+        assert_eq!(split_line("fill @e1 ''").unwrap(), vec!["fill", "@e1", ""]);
+        assert!(split_line("click 'unterminated").is_err());
+    }
+
+    #[test]
+    fn test_parse_script() {
+        let script = "# login flow\n\nagent-browser open example.com\nclick \"text=Sign in\"\n";
+        let steps = parse_script(script).unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].line, 3);
+        assert_eq!(steps[0].args, vec!["open", "example.com"]);
+        assert_eq!(steps[1].args, vec!["click", "text=Sign in"]);
+        assert_eq!(display_command(&steps[1].args), "click 'text=Sign in'");
+    }
+
+    #[test]
+    fn test_parse_run_args() {
+        let args: Vec<String> =
+            "tests/ --shard 2/5 --matrix viewport=desktop,mobile -r junit --matrix locale=en"
+                .split(' ')
+                .map(String::from)
+                .collect();
+        let options = parse_run_args(&args).unwrap();
+        assert_eq!(options.paths, vec!["tests/"]);
+        assert_eq!(options.shard, Some((2, 5)));
+        assert_eq!(options.reporter, Some(Reporter::Junit));
+        assert_eq!(options.matrix.len(), 2);
+
+        let parse = |s: &str| parse_run_args(&[s.to_string(), "--shard".into(), "6/5".into()]);
+        assert!(parse("a.ab").is_err());
+        assert!(parse_run_args(&["--matrix".to_string(), "x".to_string()]).is_err());
+        assert!(parse_run_args(&[]).is_err());
+
+        let args: Vec<String> = "a.ab --step-retries 2 --script-retries 1 --quarantine q.txt"
+            .split(' ')
+            .map(String::from)
+            .collect();
+        let options = parse_run_args(&args).unwrap();
+        assert_eq!((options.step_retries, options.script_retries), (2, 1));
+        assert_eq!(options.quarantine, Some(PathBuf::from("q.txt")));
+
+        let args = ["a.ab", "--trace", "t.html", "--upload", "gs://b/ci"].map(String::from);
+        let options = parse_run_args(&args).unwrap();
+        assert_eq!(options.trace, Some(PathBuf::from("t.html")));
+        assert_eq!(options.upload.as_deref(), Some("gs://b/ci"));
+        assert!(parse_run_args(&["a.ab", "--upload", "/tmp"].map(String::from)).is_err());
+
+        let options = parse_run_args(&["a.ab", "--update-selectors"].map(String::from)).unwrap();
+        assert!(options.update_selectors && !options.no_heal);
+        let args = ["a.ab", "--no-heal", "--update-selectors"].map(String::from);
+        assert!(parse_run_args(&args).is_err());
+
+        let options = parse_run_args(&["a.ab", "--capture", "always"].map(String::from)).unwrap();
+        assert_eq!(options.capture, Some(CaptureMode::Always));
+        assert!(CaptureMode::OnFailure.applies(Status::Failed));
+        assert!(!CaptureMode::OnFailure.applies(Status::Passed));
+        assert!(!CaptureMode::Always.applies(Status::Skipped));
+        assert!(parse_run_args(&["a.ab", "--capture", "never"].map(String::from)).is_err());
+    }
+
+    #[test]
+    fn test_run_timeouts() {
+        let options = parse_run_args(
+            &["a.ab", "--step-timeout", "20s", "--deadline", "5m"].map(String::from),
+        )
+        .unwrap();
+        assert_eq!(options.step_timeout.as_deref(), Some("20s"));
+        assert_eq!(options.deadline.as_deref(), Some("5m"));
+        assert!(parse_run_args(&["a.ab", "--deadline", "soon"].map(String::from)).is_err());
+
+        let mut settings = RunSettings {
+            global_args: vec!["--nav-timeout".to_string(), "30s".to_string()],
+            live: false,
+            step_retries: 0,
+            session: "default".to_string(),
+            trace: false,
+            resume_from: None,
+            heal: true,
+            capture: None,
+            step_timeout: None,
+            deadline: None,
+            envs: Vec::new(),
+        };
+        // The `run` flag beats the script line
+        settings.set_timeout(&timeouts::NAVIGATION, 5_000, "a.ab line 1".to_string());
+        assert!(settings.envs.is_empty());
+        settings.set_timeout(&timeouts::ACTION, 5_000, "a.ab line 2".to_string());
+        settings.set_timeout(&timeouts::ACTION, 2_000, "a.ab line 3".to_string());
+        assert_eq!(
+            settings.envs,
+            [
+                ("AGENT_BROWSER_ACTION_TIMEOUT", "2000ms"),
+                ("AGENT_BROWSER_ACTION_TIMEOUT_SOURCE", "a.ab line 3")
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+        );
+
+        settings.set_timeout(&timeouts::STEP, 60_000, "a.ab line 4".to_string());
+        let deadline = Timeout {
+            ms: 1_000,
+            source: "--deadline".to_string(),
+        };
+        settings.deadline = Some(Limit::new("run deadline", &deadline, Instant::now()));
+        let limit = settings.step_limit().unwrap();
+        assert_eq!(limit.reason, "run deadline 1s, from --deadline");
+        settings.deadline = None;
+        let limit = settings.step_limit().unwrap();
+        assert_eq!(limit.reason, "step timeout 60s, from a.ab line 4");
+
+        settings.step_timeout = Some(Timeout {
+            ms: 20_000,
+            source: "--step-timeout".to_string(),
+        });
+        settings.set_timeout(&timeouts::STEP, 60_000, "a.ab line 5".to_string());
+        assert_eq!(settings.step_timeout.unwrap().ms, 20_000);
+    }
+
+    #[test]
+    fn test_selector_index() {
+        let index = |line: &str| selector_index(&split_line(line).unwrap());
+        assert_eq!(index("click '#buy'"), Some(1));
+        assert_eq!(index("fill @e2 hello"), Some(1));
+        assert_eq!(index("get text .price"), Some(2));
+        assert_eq!(index("is visible #modal"), Some(2));
+        assert_eq!(index("wait #spinner"), Some(1));
+        assert_eq!(index("wait 500"), None);
+        assert_eq!(index("wait --text Done"), None);
+        assert_eq!(index("get title"), None);
+        assert_eq!(index("open https://example.com"), None);
+    }
+
+    #[test]
+    fn test_replace_argument() {
+        assert_eq!(
+            replace_argument("click '#buy'", "#buy", "role=button[name=\"Buy\" s]").as_deref(),
+            Some("click 'role=button[name=\"Buy\" s]'")
+        );
+        assert_eq!(
+            replace_argument("fill #email \"#email\"", "#email", "[name=\"email\"]").as_deref(),
+            Some("fill '[name=\"email\"]' \"#email\"")
+        );
+        assert_eq!(
+            replace_argument("  get text .total --into total", ".total", "#total").as_deref(),
+            Some("  get text '#total' --into total")
+        );
+        assert_eq!(
+            replace_argument("click .buy-now", ".buy", "#buy").as_deref(),
+            None
+        );
+        assert_eq!(
+            replace_argument("click '{{buy}}'", "#buy", "#b").as_deref(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let source = "agent-browser assert title == \"Shop\"\nif count > 0\nclick '#next'\nelse\nuntil done --max 5\nget text #status --into done\nend\nend\n";
+        let steps = parse_script(source).unwrap();
+        let lines = parse_lines(&steps, source).unwrap();
+        assert!(matches!(&lines[0], Line::Assert(text, _) if text == "title == \"Shop\""));
+        assert!(matches!(
+            lines[1],
+            Line::If {
+                else_at: Some(3),
+                end: 7,
+                ..
+            }
+        ));
+        assert!(matches!(lines[2], Line::Command));
+        assert!(matches!(lines[3], Line::Else { end: 7 }));
+        assert!(matches!(&lines[4], Line::Until { text, max: 5, end: 6, .. } if text == "done"));
+        assert!(matches!(lines[6], Line::End { start: 4 }));
+
+        let error = |source: &str| {
+            parse_lines(&parse_script(source).unwrap(), source)
+                .map(|_| ())
+                .unwrap_err()
+        };
+        assert!(error("if a\nopen x\n").contains("line 1: 'if' has no 'end'"));
+        assert!(error("open x\nend\n").contains("line 2: 'end' without"));
+        assert!(error("until a\nelse\nend\n").contains("'else' without 'if'"));
+        assert!(error("assert a ==\n").starts_with("line 1:"));
+        assert!(error("if a\nend now\n").contains("on a line of its own"));
+    }
+
+    #[test]
+    fn test_checkpoints() {
+        let steps = parse_script("open example.com\ncheckpoint logged-in\ncheckpoint\n").unwrap();
+        assert!(checkpoint_name(&steps[0]).is_none());
+        assert_eq!(checkpoint_name(&steps[1]), Some(Ok("logged-in")));
+        assert!(checkpoint_name(&steps[2]).unwrap().is_err());
+
+        let dir = checkpoint_dir("login [device=Pixel 7]");
+        assert!(dir.ends_with("checkpoints/login__device_Pixel_7_"));
+
+        let args = ["a.ab", "--resume-from", "logged-in"].map(String::from);
+        let options = parse_run_args(&args).unwrap();
+        assert_eq!(options.resume_from.as_deref(), Some("logged-in"));
+    }
+
+    #[test]
+    fn test_data_rows() {
+        let args = ["a.ab", "--data", "rows.csv", "--parallel", "4"].map(String::from);
+        let options = parse_run_args(&args).unwrap();
+        assert_eq!(options.data, Some(PathBuf::from("rows.csv")));
+        assert_eq!(options.parallel, 4);
+        assert!(parse_run_args(&["a.ab", "--parallel", "0"].map(String::from)).is_err());
+
+        let params = vec![("device".to_string(), "Pixel 7".to_string())];
+        assert_eq!(
+            job_name(Path::new("flows/signup.ab"), &params, Some(3)),
+            "signup [device=Pixel 7, row 3]"
+        );
+        assert_eq!(
+            job_name(Path::new("signup.ab"), &[], Some(1)),
+            "signup [row 1]"
+        );
+
+        let global = ["--session", "ci", "--headed"].map(String::from);
+        assert_eq!(
+            with_session(&global, "ci-2"),
+            ["--headed", "--session", "ci-2"].map(String::from)
+        );
+    }
+
+    #[test]
+    fn test_is_quarantined() {
+        let list = vec![
+            "login".to_string(),
+            "flows/checkout.ab".to_string(),
+            "search [device=Pixel 7]".to_string(),
+        ];
+        let q = |file: &str, name: &str| is_quarantined(&list, Path::new(file), name);
+        assert!(q("suite/login.ab", "login [device=Desktop]"));
+        assert!(q("suite/flows/checkout.ab", "checkout"));
+        assert!(q("suite/search.ab", "search [device=Pixel 7]"));
+        assert!(!q("suite/search.ab", "search [device=Desktop]"));
+        assert!(!q("suite/other/checkout.ab", "checkout"));
+    }
+
+    #[test]
+    fn test_matrix_and_shards() {
+        let matrix = vec![
+            (
+                "viewport".to_string(),
+                vec!["desktop".to_string(), "mobile".to_string()],
+            ),
+            (
+                "locale".to_string(),
+                vec!["en".to_string(), "de".to_string()],
+            ),
+        ];
+        let combos = expand_matrix(&matrix);
+        assert_eq!(combos.len(), 4);
+        assert_eq!(combos[1][1], ("locale".to_string(), "de".to_string()));
+        assert_eq!(expand_matrix(&[]), vec![Vec::new()]);
+        assert_eq!(
+            substitute("set device \"${viewport}\" ${other}", &combos[2]),
+            "set device \"mobile\" ${other}"
+        );
+
+        let jobs: Vec<u32> = (0..7).collect();
+        assert_eq!(shard(jobs.clone(), 1, 3), vec![0, 3, 6]);
+        assert_eq!(shard(jobs.clone(), 3, 3), vec![2, 5]);
+        let all: usize = (1..=3).map(|i| shard(jobs.clone(), i, 3).len()).sum();
+        assert_eq!(all, jobs.len());
+    }
+}
@@ -0,0 +1,411 @@
+//! `agent-browser self-update`.
+//!
+//! Looks up the latest GitHub release, downloads the binary for this
+//! platform, checks it against the release's `SHA256SUMS` file and swaps it
+//! in place of the running executable. The new binary is staged next to the
+//! current one and moved over it with a rename, so an interrupted update
+//! never leaves a half-written executable behind.
+//!
+//! The checksum catches a corrupted or truncated download. It comes from the
+//! same release as the binary, so it can't catch a tampered release; there is
+//! no signature check.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::color;
+use crate::errors::ErrorCode;
+use crate::install::{download_file, download_files};
+use crate::ui;
+
+/// Same repository the npm postinstall script downloads binaries from.
+const GITHUB_REPO: &str = "vercel-labs/agent-browser";
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+struct Release {
+    version: String,
+    assets: Vec<(String, String)>,
+}
+
+impl Release {
+    fn asset_url(&self, name: &str) -> Option<&str> {
+        self.assets
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, url)| url.as_str())
+    }
+}
+
+pub fn run_self_update(args: &[String]) {
+    let check_only = args.iter().skip(1).any(|a| a == "--check");
+    if let Some(other) = args.iter().skip(1).find(|a| a.as_str() != "--check") {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            format!(
+                "Unknown argument for self-update: {}\nUsage: agent-browser self-update [--check]",
+                other
+            ),
+        );
+    }
+
+    let tmp_dir = env::temp_dir().join(format!("agent-browser-update-{}", std::process::id()));
+    if let Err(e) = fs::create_dir_all(&tmp_dir) {
+        ui::fail(
+            ErrorCode::Unknown,
+            format!("Failed to create {}: {}", tmp_dir.display(), e),
+        );
+    }
+    let result = update(check_only, &tmp_dir);
+    let _ = fs::remove_dir_all(&tmp_dir);
+
+    match result {
+        Ok(update_available) => {
+            // --check exits non-zero when out of date so CI can gate on it,
+            // with a status of its own so it can't be mistaken for a failure
+            if check_only && update_available {
+                std::process::exit(ErrorCode::UpdateAvailable.exit_code());
+            }
+        }
+        Err((code, msg)) => ui::fail(code, msg),
+    }
+}
+
+/// Returns whether a newer release than the running binary exists.
+fn update(check_only: bool, tmp_dir: &Path) -> Result<bool, (ErrorCode, String)> {
+    let release = fetch_latest_release(tmp_dir).map_err(|e| (ErrorCode::DownloadFailed, e))?;
+    let update_available = is_newer(&release.version, CURRENT_VERSION);
+    tracing::info!(
+        current = CURRENT_VERSION,
+        latest = %release.version,
+        update_available,
+        "checked for updates"
+    );
+
+    if check_only {
+        if ui::json_mode() {
+            ui::print_json_data(json!({
+                "currentVersion": CURRENT_VERSION,
+                "latestVersion": release.version,
+                "updateAvailable": update_available,
+            }));
+        } else if update_available {
+            // The answer is the point of --check, so it survives --quiet
+            println!(
+                "Update available: {} -> {}",
+                CURRENT_VERSION, release.version
+            );
+        } else {
+            ui::status(format!(
+                "{} agent-browser {} is up to date",
+                color::success_indicator(),
+                CURRENT_VERSION
+            ));
+        }
+        return Ok(update_available);
+    }
+
+    let current_exe = env::current_exe().and_then(fs::canonicalize).map_err(|e| {
+        (
+            ErrorCode::Unknown,
+            format!("Could not locate the running executable: {}", e),
+        )
+    })?;
+
+    if !update_available {
+        if ui::json_mode() {
+            ui::print_json_data(json!({
+                "previousVersion": CURRENT_VERSION,
+                "version": CURRENT_VERSION,
+                "path": current_exe,
+                "updated": false,
+            }));
+        } else {
+            ui::status(format!(
+                "{} agent-browser {} is already the latest version",
+                color::success_indicator(),
+                CURRENT_VERSION
+            ));
+        }
+        return Ok(false);
+    }
+
+    let Some(asset) = asset_name(env::consts::OS, env::consts::ARCH) else {
+        return Err((
+            ErrorCode::Unsupported,
+            format!(
+                "No prebuilt binary for {} / {}",
+                env::consts::OS,
+                env::consts::ARCH
+            ),
+        ));
+    };
+    let missing = |name: &str| {
+        (
+            ErrorCode::DownloadFailed,
+            format!("Release v{} has no {} asset", release.version, name),
+        )
+    };
+    let binary_url = release.asset_url(&asset).ok_or_else(|| missing(&asset))?;
+    let sums_url = release
+        .asset_url(CHECKSUMS_ASSET)
+        .ok_or_else(|| missing(CHECKSUMS_ASSET))?;
+
+    if current_exe
+        .components()
+        .any(|c| c.as_os_str() == "node_modules")
+    {
+        ui::warn(
+            "agent-browser was installed with npm; `npm install -g agent-browser@latest` keeps the package in sync",
+        );
+    }
+
+    ui::status(format!(
+        "{} agent-browser {} -> {}...",
+        color::cyan("Updating"),
+        CURRENT_VERSION,
+        release.version
+    ));
+
+    let download = tmp_dir.join(&asset);
+    let sums_path = tmp_dir.join(CHECKSUMS_ASSET);
+    download_files(&[(sums_url, &sums_path), (binary_url, &download)])
+        .map_err(|e| (ErrorCode::DownloadFailed, e))?;
+
+    let sums = fs::read_to_string(&sums_path).map_err(|e| {
+        (
+            ErrorCode::DownloadFailed,
+            format!("Failed to read checksums: {}", e),
+        )
+    })?;
+    let expected = expected_checksum(&sums, &asset).ok_or_else(|| {
+        (
+            ErrorCode::DownloadFailed,
+            format!("{} does not list {}", CHECKSUMS_ASSET, asset),
+        )
+    })?;
+    let bytes = fs::read(&download).map_err(|e| {
+        (
+            ErrorCode::DownloadFailed,
+            format!("Failed to read download: {}", e),
+        )
+    })?;
+    let actual = sha256_hex(&bytes);
+    if !actual.eq_ignore_ascii_case(&expected) {
+        return Err((
+            ErrorCode::DownloadFailed,
+            format!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                asset, expected, actual
+            ),
+        ));
+    }
+    tracing::debug!(asset = %asset, sha256 = %actual, "checksum verified");
+
+    replace_executable(&current_exe, &bytes).map_err(|e| (ErrorCode::Unknown, e))?;
+    tracing::info!(version = %release.version, path = %current_exe.display(), "updated");
+
+    if ui::json_mode() {
+        ui::print_json_data(json!({
+            "previousVersion": CURRENT_VERSION,
+            "version": release.version,
+            "path": current_exe,
+            "updated": true,
+        }));
+    } else {
+        ui::status(format!(
+            "{} Updated to agent-browser {}\n  {}",
+            color::success_indicator(),
+            release.version,
+            current_exe.display()
+        ));
+    }
+    Ok(true)
+}
+
+fn fetch_latest_release(tmp_dir: &Path) -> Result<Release, String> {
+    let url = format!(
+        "https://api.github.com/repos/{}/releases/latest",
+        GITHUB_REPO
+    );
+    let path = tmp_dir.join("release.json");
+    download_file(&url, &path)?;
+    let body = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", url, e))?;
+    parse_release(&body)
+}
+
+fn parse_release(body: &str) -> Result<Release, String> {
+    let value: Value =
+        serde_json::from_str(body).map_err(|e| format!("Invalid release metadata: {}", e))?;
+    let tag = value
+        .get("tag_name")
+        .and_then(|v| v.as_str())
+        .ok_or("Release metadata has no tag_name")?;
+    let assets = value
+        .get("assets")
+        .and_then(|v| v.as_array())
+        .map(|assets| {
+            assets
+                .iter()
+                .filter_map(|a| {
+                    Some((
+                        a.get("name")?.as_str()?.to_string(),
+                        a.get("browser_download_url")?.as_str()?.to_string(),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(Release {
+        version: tag.trim_start_matches('v').to_string(),
+        assets,
+    })
+}
+
+/// Release asset for a platform, named like the npm postinstall script
+/// expects (`agent-browser-<platform>-<arch>`).
+fn asset_name(os: &str, arch: &str) -> Option<String> {
+    let platform = match os {
+        "linux" => "linux",
+        "macos" => "darwin",
+        "windows" => "win32",
+        _ => return None,
+    };
+    let arch = match arch {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        _ => return None,
+    };
+    let ext = if os == "windows" { ".exe" } else { "" };
+    Some(format!("agent-browser-{}-{}{}", platform, arch, ext))
+}
+
+fn version_parts(version: &str) -> Vec<u64> {
+    version
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or("")
+        .split('.')
+        .map(|p| p.parse().unwrap_or(0))
+        .collect()
+}
+
+fn is_newer(candidate: &str, current: &str) -> bool {
+    version_parts(candidate) > version_parts(current)
+}
+
+/// Find a file's hash in `sha256sum` output (`<hex>  <name>`, where the
+/// name may be prefixed with `*` for binary mode).
+fn expected_checksum(sums: &str, asset: &str) -> Option<String> {
+    sums.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+        (name.trim_start().trim_start_matches('*') == asset).then(|| hash.to_string())
+    })
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+fn replace_executable(current: &Path, bytes: &[u8]) -> Result<(), String> {
+    // Stage in the same directory so the final rename never crosses filesystems
+    let staged = sibling(current, ".new");
+    fs::write(&staged, bytes)
+        .map_err(|e| format!("Failed to write {}: {}", staged.display(), e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(e) = fs::set_permissions(&staged, fs::Permissions::from_mode(0o755)) {
+            let _ = fs::remove_file(&staged);
+            return Err(format!(
+                "Failed to mark {} executable: {}",
+                staged.display(),
+                e
+            ));
+        }
+    }
+
+    // A running executable can't be overwritten on Windows, but it can be renamed
+    #[cfg(windows)]
+    {
+        let old = sibling(current, ".old");
+        let _ = fs::remove_file(&old);
+        if let Err(e) = fs::rename(current, &old) {
+            let _ = fs::remove_file(&staged);
+            return Err(format!("Failed to move {} aside: {}", current.display(), e));
+        }
+    }
+
+    fs::rename(&staged, current).map_err(|e| {
+        let _ = fs::remove_file(&staged);
+        // Put the old executable back rather than leave nothing at its path
+        #[cfg(windows)]
+        let _ = fs::rename(sibling(current, ".old"), current);
+        format!("Failed to replace {}: {}", current.display(), e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_and_assets() {
+        assert!(is_newer("0.10.0", "0.9.1"));
+        assert!(is_newer("v1.0.0", "0.9.1"));
+        assert!(!is_newer("0.9.1", "0.9.1"));
+        assert!(!is_newer("0.9.0", "0.9.1"));
+        assert_eq!(
+            asset_name("macos", "aarch64").as_deref(),
+            Some("agent-browser-darwin-arm64")
+        );
+        assert_eq!(
+            asset_name("windows", "x86_64").as_deref(),
+            Some("agent-browser-win32-x64.exe")
+        );
+        assert_eq!(asset_name("freebsd", "x86_64"), None);
+    }
+
+    #[test]
+    fn test_release_checksum_lookup() {
+        let release = parse_release(
+            r#"{"tag_name":"v0.10.0","assets":[{"name":"SHA256SUMS","browser_download_url":"https://example.com/SHA256SUMS"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(release.version, "0.10.0");
+        assert_eq!(
+            release.asset_url("SHA256SUMS"),
+            Some("https://example.com/SHA256SUMS")
+        );
+
+        let hash = sha256_hex(b"abc");
+        assert_eq!(
+            hash,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        let sums = format!(
+            "0000  agent-browser-linux-arm64\n{} *agent-browser-linux-x64\n",
+            hash
+        );
+        assert_eq!(
+            expected_checksum(&sums, "agent-browser-linux-x64"),
+            Some(hash)
+        );
+        assert_eq!(expected_checksum(&sums, "agent-browser-darwin-x64"), None);
+    }
+}
@@ -0,0 +1,322 @@
+//! Results sinks (`--sink sqlite://path.db#table`).
+//!
+//! A sink appends what a command collected to a SQLite table, so an agent's
+//! history stays queryable across runs: a command's rows (as with
+//! `--output-format`), each `poll` reading as it arrives, and one row per step
+//! of a `run`. The table (default `results`) is created on first use, and
+//! columns are added as new keys show up. Every row also records when it was
+//! collected (`collected_at`, ISO 8601 UTC), the `session` and the `command`.
+//!
+//! Like uploads with curl, writes go through the `sqlite3` command-line tool,
+//! one transaction per append, so nothing needs linking in.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{Map, Value};
+
+use crate::export::{self, Kind};
+
+/// Columns every row gets; data keys with these names get a `data_` prefix
+const META_COLUMNS: &[&str] = &["id", "collected_at", "session", "command"];
+
+#[derive(Debug, PartialEq)]
+pub struct Sink {
+    pub path: PathBuf,
+    pub table: String,
+}
+
+pub fn parse(uri: &str) -> Result<Sink, String> {
+    let rest = uri.strip_prefix("sqlite://").ok_or_else(|| {
+        format!(
+            "Unsupported sink: {} (expected sqlite://path.db#table)",
+            uri
+        )
+    })?;
+    let (path, table) = rest.split_once('#').unwrap_or((rest, "results"));
+    if path.is_empty() {
+        return Err(format!("Sink {} has no database path", uri));
+    }
+    let valid = table
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && table.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(format!(
+            "Invalid sink table: '{}' (use letters, digits and _)",
+            table
+        ));
+    }
+    Ok(Sink {
+        path: PathBuf::from(path),
+        table: table.to_string(),
+    })
+}
+
+/// Milliseconds since the epoch, for `collected_at`
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+fn identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn literal(value: &Value, kind: Kind) -> String {
+    match (value, kind) {
+        (Value::Null, _) => "NULL".to_string(),
+        (Value::Bool(b), Kind::Boolean) => u8::from(*b).to_string(),
+        (Value::Number(n), Kind::Integer | Kind::Float) => match n.as_f64() {
+            Some(f) if f.is_finite() => n.to_string(),
+            _ => "NULL".to_string(),
+        },
+        (Value::String(s), _) => format!("'{}'", s.replace('\'', "''")),
+        (other, _) => format!("'{}'", other.to_string().replace('\'', "''")),
+    }
+}
+
+fn column_type(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Boolean | Kind::Integer => "INTEGER",
+        Kind::Float => "REAL",
+        Kind::String | Kind::Json => "TEXT",
+    }
+}
+
+/// A data key as a column name, clear of the columns every row has
+fn column_name(key: &str) -> String {
+    if META_COLUMNS.contains(&key.to_ascii_lowercase().as_str()) {
+        format!("data_{}", key)
+    } else {
+        key.to_string()
+    }
+}
+
+impl Sink {
+    pub fn display(&self) -> String {
+        format!("{}#{}", self.path.display(), self.table)
+    }
+
+    /// The database path as sqlite3 takes it: a relative path starting with
+    /// `-` would otherwise be read as an option
+    fn db_arg(&self) -> PathBuf {
+        if self.path.is_relative() {
+            Path::new(".").join(&self.path)
+        } else {
+            self.path.clone()
+        }
+    }
+
+    fn sqlite(&self, sql: &str) -> Result<String, String> {
+        let mut child = Command::new("sqlite3")
+            .args(["-batch", "-bail"])
+            .arg(self.db_arg())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run sqlite3 (is it installed?): {}", e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(sql.as_bytes())
+                .map_err(|e| format!("Failed to write to sqlite3: {}", e))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("Failed to run sqlite3: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "sqlite3 failed on {}: {}",
+                self.path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// The statements that append rows, given the table's current columns
+    fn append_sql(
+        &self,
+        existing: &[String],
+        session: &str,
+        command: &str,
+        rows: &[(u64, Map<String, Value>)],
+    ) -> String {
+        let table = identifier(&self.table);
+        // SQLite column names ignore case, so keys that differ only in case
+        // share the spelling seen first (in the table, else in the rows). Two
+        // such keys in one row are both kept, the later one with a suffix.
+        let mut spellings: HashMap<String, String> = existing
+            .iter()
+            .map(|c| (c.to_ascii_lowercase(), c.clone()))
+            .collect();
+        let mut spelling = |name: String| {
+            spellings
+                .entry(name.to_ascii_lowercase())
+                .or_insert(name)
+                .clone()
+        };
+        let data: Vec<Map<String, Value>> = rows
+            .iter()
+            .map(|(_, row)| {
+                let mut out = Map::new();
+                for (key, value) in row {
+                    let name = column_name(key);
+                    let mut column = spelling(name.clone());
+                    let mut n = 2;
+                    while out.contains_key(&column) {
+                        column = spelling(format!("{}_{}", name, n));
+                        n += 1;
+                    }
+                    out.insert(column, value.clone());
+                }
+                out
+            })
+            .collect();
+        let columns = export::infer_schema(&data);
+        let mut sql = String::from("BEGIN;\n");
+        sql += &format!(
+            "CREATE TABLE IF NOT EXISTS {} (id INTEGER PRIMARY KEY AUTOINCREMENT, collected_at TEXT NOT NULL, session TEXT NOT NULL, command TEXT NOT NULL);\n",
+            table
+        );
+        let known = |name: &str| existing.iter().any(|c| c.eq_ignore_ascii_case(name));
+        for column in columns.iter().filter(|c| !known(&c.name)) {
+            sql += &format!(
+                "ALTER TABLE {} ADD COLUMN {} {};\n",
+                table,
+                identifier(&column.name),
+                column_type(column.kind)
+            );
+        }
+        for ((collected_at, _), row) in rows.iter().zip(&data) {
+            let mut names = vec![
+                "collected_at".to_string(),
+                "session".to_string(),
+                "command".to_string(),
+            ];
+            let mut values = vec![
+                format!(
+                    "strftime('%Y-%m-%dT%H:%M:%fZ', {}.0 / 1000, 'unixepoch')",
+                    collected_at
+                ),
+                literal(&Value::String(session.to_string()), Kind::String),
+                literal(&Value::String(command.to_string()), Kind::String),
+            ];
+            for column in &columns {
+                if let Some(value) = row.get(&column.name) {
+                    names.push(identifier(&column.name));
+                    values.push(literal(value, column.kind));
+                }
+            }
+            sql += &format!(
+                "INSERT INTO {} ({}) VALUES ({});\n",
+                table,
+                names.join(", "),
+                values.join(", ")
+            );
+        }
+        sql + "COMMIT;\n"
+    }
+
+    /// Append rows collected at the given times (ms since the epoch)
+    pub fn append(
+        &self,
+        session: &str,
+        command: &str,
+        rows: &[(u64, Map<String, Value>)],
+    ) -> Result<usize, String> {
+        if rows.is_empty() {
+            return Ok(0);
+        }
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let existing: Vec<String> = self
+            .sqlite(&format!(
+                "SELECT name FROM pragma_table_info('{}');\n",
+                self.table
+            ))?
+            .lines()
+            .map(String::from)
+            .collect();
+        self.sqlite(&self.append_sql(&existing, session, command, rows))?;
+        Ok(rows.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("sqlite://data/agent.db#prices").unwrap(),
+            Sink {
+                path: PathBuf::from("data/agent.db"),
+                table: "prices".to_string()
+            }
+        );
+        assert_eq!(parse("sqlite:///tmp/a.db").unwrap().table, "results");
+        assert!(parse("postgres://x").is_err());
+        assert!(parse("sqlite://#t").is_err());
+        assert!(parse("sqlite://a.db#drop table").is_err());
+    }
+
+    #[test]
+    fn test_append_sql() {
+        let sink = parse("sqlite://a.db#items").unwrap();
+        let row = |v: Value| v.as_object().unwrap().clone();
+        let rows = vec![
+            (
+                1_700_000_000_000,
+                row(json!({ "name": "O'Brien", "price": 2, "id": 7 })),
+            ),
+            (
+                1_700_000_000_500,
+                row(json!({ "name": "Ink", "price": 2.5, "ok": true })),
+            ),
+        ];
+        let sql = sink.append_sql(&["price".to_string()], "default", "eval", &rows);
+        assert!(sql.starts_with("BEGIN;\nCREATE TABLE IF NOT EXISTS \"items\""));
+        assert!(sql.contains("ALTER TABLE \"items\" ADD COLUMN \"data_id\" INTEGER;"));
+        assert!(sql.contains("ALTER TABLE \"items\" ADD COLUMN \"ok\" INTEGER;"));
+        assert!(!sql.contains("ADD COLUMN \"price\""));
+        assert!(sql.contains("'default', 'eval', 7, 'O''Brien', 2);"));
+        assert!(sql.contains("'default', 'eval', 'Ink', 2.5, 1);"));
+        assert!(sql.ends_with("COMMIT;\n"));
+    }
+
+    #[test]
+    fn test_append_sql_ignores_column_case() {
+        let sink = parse("sqlite://a.db#items").unwrap();
+        let row = |v: Value| v.as_object().unwrap().clone();
+        let rows = vec![
+            (0, row(json!({ "price": 2, "ID": 7 }))),
+            (0, row(json!({ "Name": "Ink", "name": "ink" }))),
+        ];
+        let sql = sink.append_sql(&["Price".to_string()], "default", "eval", &rows);
+        assert!(!sql.contains("ADD COLUMN \"price\""));
+        assert!(sql.contains("ADD COLUMN \"data_ID\" INTEGER;"));
+        assert!(sql.contains("(collected_at, session, command, \"Price\", \"data_ID\")"));
+        let lower = sql.to_lowercase();
+        assert_eq!(lower.matches("add column \"name\" ").count(), 1);
+        assert!(sql.contains("ADD COLUMN \"name_2\" TEXT;"));
+    }
+
+    #[test]
+    fn test_db_arg() {
+        let sink = |uri| parse(uri).unwrap().db_arg();
+        assert_eq!(sink("sqlite://-x.db"), Path::new("./-x.db"));
+        assert_eq!(sink("sqlite:///tmp/a.db"), Path::new("/tmp/a.db"));
+    }
+}
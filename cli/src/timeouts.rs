@@ -0,0 +1,247 @@
+//! Layered timeouts.
+//!
+//! From the outside in:
+//!
+//! - run deadline: a whole `run` (`run --deadline`, config `run-deadline`)
+//! - step: one script step, retries and healing included
+//!   (`run --step-timeout`, config `step-timeout`)
+//! - action: how long an action waits for its element (`--action-timeout`,
+//!   `AGENT_BROWSER_ACTION_TIMEOUT`, config `action-timeout`, then the older
+//!   `AGENT_BROWSER_DEFAULT_TIMEOUT` and config `timeout`; 60s by default)
+//! - navigation: page loads and URL waits (`--nav-timeout`,
+//!   `AGENT_BROWSER_NAV_TIMEOUT`, config `nav-timeout`; the action timeout
+//!   by default)
+//! - network idle: waits for the network to go quiet (`--idle-timeout`,
+//!   `AGENT_BROWSER_IDLE_TIMEOUT`, config `idle-timeout`; the navigation
+//!   timeout by default)
+//!
+//! Each level takes the first of its flag, its env var and config. A script
+//! run by `run` can set any level but the deadline with `timeout <level>
+//! <duration>` lines; flags given to `run` still win, so CI can tighten them
+//! without editing scripts. The command-level timeouts travel with every
+//! command together with where they were set, so a timeout error names the
+//! setting to change. Event waits with their own `--timeout` use that.
+
+use std::env;
+
+use serde_json::{json, Value};
+
+use crate::config::Config;
+use crate::flags::{parse_duration_ms, Flags};
+
+pub const DEFAULT_ACTION_MS: u64 = 60_000;
+
+/// Where one timeout level is configured
+pub struct Level {
+    /// Name in `timeout <name> <duration>` script lines
+    pub name: &'static str,
+    pub flag: &'static str,
+    pub env: &'static str,
+    pub config: &'static str,
+}
+
+pub const ACTION: Level = Level {
+    name: "action",
+    flag: "--action-timeout",
+    env: "AGENT_BROWSER_ACTION_TIMEOUT",
+    config: "action-timeout",
+};
+
+pub const NAVIGATION: Level = Level {
+    name: "nav",
+    flag: "--nav-timeout",
+    env: "AGENT_BROWSER_NAV_TIMEOUT",
+    config: "nav-timeout",
+};
+
+pub const IDLE: Level = Level {
+    name: "idle",
+    flag: "--idle-timeout",
+    env: "AGENT_BROWSER_IDLE_TIMEOUT",
+    config: "idle-timeout",
+};
+
+pub const STEP: Level = Level {
+    name: "step",
+    flag: "--step-timeout",
+    env: "AGENT_BROWSER_STEP_TIMEOUT",
+    config: "step-timeout",
+};
+
+pub const DEADLINE: Level = Level {
+    name: "deadline",
+    flag: "--deadline",
+    env: "AGENT_BROWSER_RUN_DEADLINE",
+    config: "run-deadline",
+};
+
+/// The action timeout's older settings, used when the new ones are unset
+const LEGACY_ACTION: Level = Level {
+    name: "action",
+    flag: "",
+    env: "AGENT_BROWSER_DEFAULT_TIMEOUT",
+    config: "timeout",
+};
+
+/// Levels a script's `timeout` lines can set
+pub const SCRIPT_LEVELS: &[&Level] = &[&STEP, &ACTION, &NAVIGATION, &IDLE];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timeout {
+    pub ms: u64,
+    /// The flag, env var, config key or script line it came from
+    pub source: String,
+}
+
+impl Timeout {
+    fn to_json(&self) -> Value {
+        json!({ "ms": self.ms, "source": self.source })
+    }
+}
+
+impl Level {
+    /// Env var naming where this level's env value came from, set by `run`
+    /// when the value is from a script's `timeout` line
+    pub fn source_env(&self) -> String {
+        format!("{}_SOURCE", self.env)
+    }
+}
+
+/// `30s`, `1.5s`, `750ms`
+pub fn format_ms(ms: u64) -> String {
+    if ms >= 1000 && ms.is_multiple_of(100) {
+        let seconds = ms as f64 / 1000.0;
+        format!("{}s", seconds)
+    } else {
+        format!("{}ms", ms)
+    }
+}
+
+pub fn parse(name: &str, value: &str) -> Result<u64, String> {
+    parse_duration_ms(value)
+        .filter(|&ms| ms > 0)
+        .ok_or_else(|| {
+            format!(
+                "Invalid {} value: '{}' (expected e.g. 500ms, 30s, 2m)",
+                name, value
+            )
+        })
+}
+
+/// The level's value from its flag (if given), env var or config.
+pub fn resolve(
+    level: &Level,
+    flag: Option<&str>,
+    config: &Config,
+) -> Result<Option<Timeout>, String> {
+    if let Some(value) = flag {
+        return Ok(Some(Timeout {
+            ms: parse(level.flag, value)?,
+            source: level.flag.to_string(),
+        }));
+    }
+    if let Ok(value) = env::var(level.env) {
+        return Ok(Some(Timeout {
+            ms: parse(level.env, &value)?,
+            source: env::var(level.source_env()).unwrap_or_else(|_| level.env.to_string()),
+        }));
+    }
+    Ok(config
+        .get_duration_ms(level.config)
+        .filter(|&ms| ms > 0)
+        .map(|ms| Timeout {
+            ms,
+            source: format!("config {}", level.config),
+        }))
+}
+
+/// The action, navigation and (when set) network-idle timeouts sent with
+/// every command.
+pub fn command_timeouts(flags: &Flags) -> Result<Value, String> {
+    let config = Config::load();
+    let action = match resolve(&ACTION, flags.action_timeout.as_deref(), &config)? {
+        Some(action) => action,
+        // Invalid values here were always ignored, so they still are
+        None => resolve(&LEGACY_ACTION, None, &config)
+            .ok()
+            .flatten()
+            .unwrap_or(Timeout {
+                ms: DEFAULT_ACTION_MS,
+                source: "default".to_string(),
+            }),
+    };
+    let navigation = resolve(&NAVIGATION, flags.nav_timeout.as_deref(), &config)?
+        .unwrap_or_else(|| action.clone());
+    let mut timeouts = json!({
+        "action": action.to_json(),
+        "navigation": navigation.to_json(),
+    });
+    if let Some(idle) = resolve(&IDLE, flags.idle_timeout.as_deref(), &config)? {
+        timeouts["idle"] = idle.to_json();
+    }
+    Ok(timeouts)
+}
+
+/// A `timeout <level> <duration>` script line: the level and milliseconds.
+pub fn parse_script_line(args: &[String]) -> Option<Result<(&'static Level, u64), String>> {
+    if args.first().map(String::as_str) != Some("timeout") {
+        return None;
+    }
+    let names: Vec<&str> = SCRIPT_LEVELS.iter().map(|l| l.name).collect();
+    Some(match &args[1..] {
+        [name, value] => match SCRIPT_LEVELS.iter().find(|l| l.name == name) {
+            Some(level) => parse("timeout", value).map(|ms| (*level, ms)),
+            None => Err(format!(
+                "Unknown timeout '{}' (expected {})",
+                name,
+                names.join(", ")
+            )),
+        },
+        _ => Err(format!("Usage: timeout <{}> <duration>", names.join("|"))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split(' ').map(String::from).collect()
+    }
+
+    #[test]
+    fn test_format_ms() {
+        assert_eq!(format_ms(30_000), "30s");
+        assert_eq!(format_ms(1_500), "1.5s");
+        assert_eq!(format_ms(750), "750ms");
+        assert_eq!(format_ms(1_234), "1234ms");
+    }
+
+    #[test]
+    fn test_resolve_flag_first() {
+        let config = Config::default();
+        let timeout = resolve(&NAVIGATION, Some("45s"), &config).unwrap();
+        assert_eq!(
+            timeout,
+            Some(Timeout {
+                ms: 45_000,
+                source: "--nav-timeout".to_string()
+            })
+        );
+        assert!(resolve(&NAVIGATION, Some("soon"), &config).is_err());
+        assert!(resolve(&NAVIGATION, Some("0"), &config).is_err());
+    }
+
+    #[test]
+    fn test_parse_script_line() {
+        let (level, ms) = parse_script_line(&args("timeout nav 30s"))
+            .unwrap()
+            .unwrap();
+        assert_eq!((level.name, ms), ("nav", 30_000));
+        assert!(parse_script_line(&args("timeout deadline 5m"))
+            .unwrap()
+            .is_err());
+        assert!(parse_script_line(&args("timeout step")).unwrap().is_err());
+        assert!(parse_script_line(&args("click #a")).is_none());
+    }
+}
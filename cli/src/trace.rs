@@ -0,0 +1,347 @@
+//! Trace bundles for `run --trace`.
+//!
+//! After every executed step the runner asks the daemon for a `trace_step`:
+//! a viewport screenshot plus the console messages, page errors and
+//! responses seen since the previous step. The bundle is either one
+//! self-contained HTML file (`--trace trace.html`, screenshots inlined) or a
+//! directory with `index.html`, `trace.json` and the screenshots as files.
+//! Both open offline in any browser, without agent-browser.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+
+use crate::commands::gen_id;
+use crate::connection::{daemon_ready, send_command};
+use crate::run::{ScriptResult, Status};
+
+/// Ask the daemon for the page state and activity since the last call.
+/// Returns None when no browser is running, e.g. after `close`.
+pub fn capture(session: &str) -> Option<Value> {
+    if !daemon_ready(session) {
+        return None;
+    }
+    let resp = send_command(json!({ "id": gen_id(), "action": "trace_step" }), session).ok()?;
+    resp.data.filter(|_| resp.success)
+}
+
+/// Drop activity recorded before the script started.
+pub fn reset(session: &str) {
+    if daemon_ready(session) {
+        let _ = send_command(
+            json!({ "id": gen_id(), "action": "trace_step", "reset": true }),
+            session,
+        );
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = r#"
+body { font: 14px/1.45 system-ui, sans-serif; margin: 0 auto; max-width: 1100px; padding: 24px; color: #1f2328; }
+h1 { font-size: 20px; } h2 { font-size: 17px; margin-top: 32px; }
+pre, code, td.mono { font-family: ui-monospace, monospace; font-size: 12px; }
+pre.script { background: #f6f8fa; padding: 8px 12px; overflow-x: auto; }
+pre.script .failed { background: #ffebe9; display: block; }
+.step { border: 1px solid #d0d7de; border-radius: 6px; margin: 12px 0; padding: 10px 14px; }
+.step.failed { border-color: #cf222e; } .step.skipped { opacity: .5; }
+.badge { display: inline-block; min-width: 52px; font-weight: 600; }
+.passed .badge { color: #1a7f37; } .failed .badge { color: #cf222e; } .flaky .badge { color: #9a6700; }
+.meta { color: #656d76; font-size: 12px; }
+.error { color: #cf222e; white-space: pre-wrap; }
+img { max-width: 100%; border: 1px solid #d0d7de; margin-top: 8px; }
+table { border-collapse: collapse; width: 100%; margin-top: 4px; }
+td { border-top: 1px solid #eaeef2; padding: 2px 6px; vertical-align: top; word-break: break-all; }
+summary { cursor: pointer; color: #656d76; margin-top: 6px; }
+"#;
+
+/// Where a step's screenshot is: inlined, or a file next to `index.html`.
+enum Images<'a> {
+    Inline,
+    Files(&'a Path),
+}
+
+fn render_step(
+    out: &mut String,
+    script_index: usize,
+    step: &crate::run::StepResult,
+    images: &Images,
+) -> Result<(), String> {
+    let (class, badge) = match step.status {
+        Status::Passed if step.flaky => ("flaky", "flaky"),
+        Status::Passed => ("passed", "passed"),
+        Status::Failed => ("failed", "failed"),
+        Status::Skipped => ("skipped", "skipped"),
+    };
+    let _ = write!(
+        out,
+        "<div class=\"step {}\"><span class=\"badge\">{}</span> <code>{}</code> <span class=\"meta\">line {} &middot; {}ms</span>",
+        class,
+        badge,
+        escape_html(&step.command),
+        step.line,
+        step.duration_ms
+    );
+    if let Some(error) = &step.error {
+        let _ = write!(out, "<div class=\"error\">{}</div>", escape_html(error));
+    }
+    let Some(trace) = &step.trace else {
+        out.push_str("</div>\n");
+        return Ok(());
+    };
+    let text = |key: &str| trace.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+    let _ = write!(
+        out,
+        "<div class=\"meta\">{} &middot; {}</div>",
+        escape_html(text("title")),
+        escape_html(text("url"))
+    );
+    if let Some(image) = trace.get("screenshot").and_then(|v| v.as_str()) {
+        let src = match images {
+            Images::Inline => format!("data:image/jpeg;base64,{}", image),
+            Images::Files(dir) => {
+                let name = format!("screenshots/{:02}-{:03}.jpg", script_index + 1, step.line);
+                let bytes = STANDARD
+                    .decode(image)
+                    .map_err(|e| format!("Invalid screenshot data: {}", e))?;
+                fs::write(dir.join(&name), bytes)
+                    .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+                name
+            }
+        };
+        let _ = write!(out, "<img loading=\"lazy\" src=\"{}\">", src);
+    }
+
+    let list = |key: &str| {
+        trace
+            .get(key)
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default()
+    };
+    let console = list("console");
+    let errors = list("errors");
+    if !console.is_empty() || !errors.is_empty() {
+        let _ = write!(
+            out,
+            "<details><summary>Console ({} messages, {} errors)</summary><table>",
+            console.len(),
+            errors.len()
+        );
+        for message in &console {
+            let field = |k: &str| message.get(k).and_then(|v| v.as_str()).unwrap_or_default();
+            let _ = write!(
+                out,
+                "<tr><td>{}</td><td class=\"mono\">{}</td></tr>",
+                escape_html(field("type")),
+                escape_html(field("text"))
+            );
+        }
+        for error in &errors {
+            let message = error.get("message").and_then(|v| v.as_str());
+            let _ = write!(
+                out,
+                "<tr><td class=\"error\">pageerror</td><td class=\"mono\">{}</td></tr>",
+                escape_html(message.unwrap_or_default())
+            );
+        }
+        out.push_str("</table></details>");
+    }
+    let responses = list("responses");
+    if !responses.is_empty() {
+        let failed = responses
+            .iter()
+            .filter(|r| r.get("status").and_then(|v| v.as_u64()).unwrap_or(0) >= 400)
+            .count();
+        let _ = write!(
+            out,
+            "<details><summary>Network ({} responses, {} failed)</summary><table>",
+            responses.len(),
+            failed
+        );
+        for response in &responses {
+            let field = |k: &str| response.get(k).and_then(|v| v.as_str()).unwrap_or_default();
+            let _ = write!(
+                out,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"mono\">{}</td></tr>",
+                escape_html(field("method")),
+                response.get("status").and_then(|v| v.as_u64()).unwrap_or(0),
+                escape_html(field("type")),
+                escape_html(field("url"))
+            );
+        }
+        out.push_str("</table></details>");
+    }
+    out.push_str("</div>\n");
+    Ok(())
+}
+
+fn render(results: &[ScriptResult], images: &Images) -> Result<String, String> {
+    let totals = crate::report::to_json(results);
+    let total = |key: &str| totals.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    let summary = format!(
+        "{} passed, {} failed, {} skipped ({:.1}s)",
+        total("passed"),
+        total("failed") + total("quarantined"),
+        total("skipped"),
+        total("durationMs") as f64 / 1000.0
+    );
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>agent-browser trace</title><style>{}</style></head><body>\n<h1>agent-browser trace</h1>\n<p>{}</p>\n",
+        STYLE,
+        summary
+    );
+    for (i, script) in results.iter().enumerate() {
+        let status = if script.quarantined {
+            "quarantined"
+        } else if script.status == Status::Failed {
+            "failed"
+        } else {
+            "passed"
+        };
+        let _ = writeln!(
+            out,
+            "<h2>{} <span class=\"meta\">{} &middot; {} &middot; {}ms</span></h2>",
+            escape_html(&script.name),
+            escape_html(&script.file.display().to_string()),
+            status,
+            script.duration_ms
+        );
+        let failed_line = script
+            .steps
+            .iter()
+            .find(|s| s.status == Status::Failed)
+            .map(|s| s.line);
+        out.push_str("<details open><summary>Script</summary><pre class=\"script\">");
+        for (n, line) in script.source.lines().enumerate() {
+            let class = if Some(n + 1) == failed_line {
+                " class=\"failed\""
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                out,
+                "<span{}>{:>3}  {}</span>",
+                class,
+                n + 1,
+                escape_html(line)
+            );
+        }
+        out.push_str("</pre></details>\n");
+        for step in &script.steps {
+            render_step(&mut out, i, step, images)?;
+        }
+    }
+    out.push_str("</body></html>\n");
+    Ok(out)
+}
+
+/// Write the bundle: a single HTML file when `path` ends in `.html`,
+/// otherwise a directory.
+pub fn write_bundle(path: &Path, results: &[ScriptResult]) -> Result<(), String> {
+    let is_file = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("html") || e.eq_ignore_ascii_case("htm"));
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    if is_file {
+        let html = render(results, &Images::Inline)?;
+        return fs::write(path, html)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e));
+    }
+
+    fs::create_dir_all(path.join("screenshots"))
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let html = render(results, &Images::Files(path))?;
+    fs::write(path.join("index.html"), html)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    // Machine-readable copy, with screenshots referenced by file
+    let mut data = crate::report::to_json(results);
+    if let Some(scripts) = data.get_mut("scripts").and_then(|v| v.as_array_mut()) {
+        for (i, (script, result)) in scripts.iter_mut().zip(results).enumerate() {
+            script["source"] = json!(result.source);
+            let steps = script.get_mut("steps").and_then(|v| v.as_array_mut());
+            for (step, step_result) in steps.into_iter().flatten().zip(&result.steps) {
+                if let Some(mut trace) = step_result.trace.clone() {
+                    if trace.get("screenshot").is_some_and(|v| v.is_string()) {
+                        trace["screenshot"] = json!(format!(
+                            "screenshots/{:02}-{:03}.jpg",
+                            i + 1,
+                            step_result.line
+                        ));
+                    }
+                    step["trace"] = trace;
+                }
+            }
+        }
+    }
+    fs::write(
+        path.join("trace.json"),
+        serde_json::to_string_pretty(&data).unwrap_or_default(),
+    )
+    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run::StepResult;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_render_inline_bundle() {
+        let step = StepResult {
+            line: 2,
+            command: "click <b>".to_string(),
+            status: Status::Failed,
+            duration_ms: 12,
+            error: Some("Element not found".to_string()),
+            code: None,
+            artifacts: Vec::new(),
+            attempts: 1,
+            flaky: false,
+            retry_errors: Vec::new(),
+            healed: None,
+            capture: None,
+            trace: Some(json!({
+                "url": "https://example.com/",
+                "title": "Example",
+                "screenshot": "AAAA",
+                "console": [{ "type": "log", "text": "hello" }],
+                "errors": [],
+                "responses": [{ "method": "GET", "status": 404, "type": "fetch", "url": "https://example.com/api" }],
+            })),
+        };
+        let results = vec![ScriptResult {
+            name: "flow".to_string(),
+            file: PathBuf::from("flow.ab"),
+            params: Default::default(),
+            row: None,
+            status: Status::Failed,
+            duration_ms: 20,
+            attempts: 1,
+            flaky: false,
+            quarantined: false,
+            source: "open example.com\nclick <b>\n".to_string(),
+            steps: vec![step],
+        }];
+        let html = render(&results, &Images::Inline).unwrap();
+        assert!(html.contains("<code>click &lt;b&gt;</code>"));
+        assert!(html.contains("<span class=\"failed\">  2  click &lt;b&gt;</span>"));
+        assert!(html.contains("src=\"data:image/jpeg;base64,AAAA\""));
+        assert!(html.contains("Network (1 responses, 1 failed)"));
+        assert!(html.contains("Console (1 messages, 0 errors)"));
+    }
+}
@@ -0,0 +1,510 @@
+//! Session transcripts (`agent-browser transcript`).
+//!
+//! With `--transcript`, a session's daemon appends each step an agent takes
+//! to `~/.browseros/artifacts/<session>/transcripts/transcript.jsonl`: the
+//! observation it acted on (URL, last snapshot and a screenshot of the page
+//! just before), the action and its result. `transcript export` writes them
+//! out as a dataset of (observation, action, result) tuples, with screenshots
+//! referenced by absolute path.
+//!
+//! `transcript replay` runs a transcript's actions again, in this session,
+//! and flags the steps where the live page no longer matches what was
+//! recorded: a different URL before the action, a different outcome, or,
+//! with `--compare-snapshots`, a different snapshot. Wherever the agent took
+//! a fresh snapshot, the replay takes it again with the same options, which
+//! also renews the refs the following actions use.
+
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::{json, Value};
+
+use crate::artifacts;
+use crate::color;
+use crate::commands::gen_id;
+use crate::connection::send_command;
+use crate::diff;
+use crate::errors::ErrorCode;
+use crate::ui;
+
+const USAGE: &str = "transcript <export [--format jsonl|json] [--out <file>] | replay <file.jsonl> [--pause-on-step] [--compare-snapshots]>";
+
+/// Snapshot diff lines shown under a diverged step
+const MAX_HUNK_LINES: usize = 20;
+
+pub fn transcript_path(session: &str) -> PathBuf {
+    artifacts::session_dir(session)
+        .join("transcripts")
+        .join("transcript.jsonl")
+}
+
+/// The steps in a transcript file, and how many lines couldn't be read
+pub fn read_steps(path: &Path) -> Result<(Vec<Value>, usize), String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    let mut steps = Vec::new();
+    let mut skipped = 0;
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        match serde_json::from_str::<Value>(line) {
+            Ok(step) if step.get("action").is_some_and(Value::is_object) => steps.push(step),
+            _ => skipped += 1,
+        }
+    }
+    Ok((steps, skipped))
+}
+
+/// A recorded step as an (observation, action, result) tuple
+fn tuple(step: &Value) -> Value {
+    json!({
+        "step": step["step"],
+        "time": step["time"],
+        "observation": step["observation"],
+        "action": step["action"],
+        "result": step["result"],
+        "durationMs": step["durationMs"],
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Jsonl,
+    Json,
+}
+
+fn render(steps: &[Value], format: Format) -> String {
+    let tuples = steps.iter().map(tuple);
+    match format {
+        Format::Jsonl => tuples.map(|t| format!("{}\n", t)).collect(),
+        Format::Json => {
+            let all: Vec<Value> = tuples.collect();
+            format!(
+                "{}\n",
+                serde_json::to_string_pretty(&all).unwrap_or_default()
+            )
+        }
+    }
+}
+
+/// A short description of a recorded action, e.g. `click @e3`
+fn describe(action: &Value) -> String {
+    let mut text = action["action"].as_str().unwrap_or("?").to_string();
+    for key in ["url", "selector", "value", "text", "key", "script"] {
+        if let Some(value) = action.get(key).and_then(Value::as_str) {
+            let mut shown: String = value.chars().take(60).collect();
+            if shown.len() < value.len() {
+                shown.push('…');
+            }
+            text.push(' ');
+            text.push_str(&shown);
+        }
+    }
+    text
+}
+
+/// Send a command and return its data, if it succeeded
+fn query(session: &str, mut cmd: Value) -> Option<Value> {
+    cmd["id"] = json!(gen_id());
+    send_command(cmd, session)
+        .ok()
+        .filter(|r| r.success)
+        .and_then(|r| r.data)
+}
+
+/// Ask before a step: true to run it, false to skip it; quitting exits
+fn confirm_step(number: u64, label: &str) -> bool {
+    eprint!(
+        "{} {} {} ",
+        color::bold(&format!("Step {}:", number)),
+        label,
+        color::dim("[Enter] run, [s]kip, [q]uit")
+    );
+    let _ = io::stderr().flush();
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer).unwrap_or(0) == 0 {
+        return true;
+    }
+    match answer.trim() {
+        "q" | "quit" => std::process::exit(130),
+        "s" | "skip" => false,
+        _ => true,
+    }
+}
+
+/// One replayed step: what happened and where it diverged
+struct Replayed {
+    number: u64,
+    label: String,
+    skipped: bool,
+    live: Value,
+    divergences: Vec<Value>,
+    hunks: Vec<String>,
+}
+
+fn replay_step(
+    session: &str,
+    step: &Value,
+    fresh_snapshot: Option<&str>,
+    compare_snapshots: bool,
+) -> Replayed {
+    let number = step["step"].as_u64().unwrap_or(0);
+    let mut replayed = Replayed {
+        number,
+        label: describe(&step["action"]),
+        skipped: false,
+        live: Value::Null,
+        divergences: Vec::new(),
+        hunks: Vec::new(),
+    };
+    let observation = &step["observation"];
+
+    if let Some(recorded) = observation["url"].as_str() {
+        let live = query(session, json!({ "action": "url" }));
+        let live = live.as_ref().and_then(|d| d["url"].as_str());
+        if let Some(live) = live.filter(|live| *live != recorded) {
+            replayed.divergences.push(json!({
+                "kind": "url",
+                "recorded": recorded,
+                "live": live,
+            }));
+        }
+    }
+
+    if let Some(recorded) = fresh_snapshot {
+        let mut cmd = observation
+            .get("snapshotOptions")
+            .filter(|o| o.is_object())
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        cmd["action"] = json!("snapshot");
+        let live = query(session, cmd);
+        let live = live.as_ref().and_then(|d| d["snapshot"].as_str());
+        if let (true, Some(live)) = (compare_snapshots, live) {
+            let (diff, hunks) = diff::compare_text(recorded, live);
+            if diff["changed"].as_bool() == Some(true) {
+                replayed.divergences.push(json!({
+                    "kind": "snapshot",
+                    "added": diff["added"],
+                    "removed": diff["removed"],
+                }));
+                replayed.hunks = hunks;
+            }
+        }
+    }
+
+    let mut cmd = step["action"].clone();
+    cmd["id"] = json!(gen_id());
+    replayed.live = match send_command(cmd, session) {
+        Ok(resp) if resp.success => json!({ "success": true }),
+        Ok(resp) => json!({ "success": false, "error": resp.error, "code": resp.code }),
+        Err(e) => json!({ "success": false, "error": e }),
+    };
+    let recorded = &step["result"];
+    if recorded["success"] != replayed.live["success"] {
+        replayed.divergences.push(json!({
+            "kind": "result",
+            "recorded": { "success": recorded["success"], "error": recorded["error"] },
+            "live": replayed.live,
+        }));
+    }
+    replayed
+}
+
+fn print_replayed(replayed: &Replayed) {
+    if replayed.skipped {
+        println!(
+            "{} {:>3} {}",
+            color::dim("-"),
+            replayed.number,
+            color::dim(&format!("{} (skipped)", replayed.label))
+        );
+        return;
+    }
+    let indicator = if replayed.divergences.is_empty() {
+        color::success_indicator()
+    } else {
+        color::error_indicator()
+    };
+    println!("{} {:>3} {}", indicator, replayed.number, replayed.label);
+    for divergence in &replayed.divergences {
+        let detail = match divergence["kind"].as_str() {
+            Some("url") => format!(
+                "url was {}, now {}",
+                divergence["recorded"].as_str().unwrap_or(""),
+                divergence["live"].as_str().unwrap_or("")
+            ),
+            Some("snapshot") => format!(
+                "snapshot changed: {} line(s) added, {} removed",
+                divergence["added"], divergence["removed"]
+            ),
+            _ => match divergence["live"]["error"].as_str() {
+                Some(error) => format!("failed, but succeeded when recorded: {}", error),
+                None => "succeeded, but failed when recorded".to_string(),
+            },
+        };
+        println!("      {}", color::red(&detail));
+    }
+    for line in replayed.hunks.iter().take(MAX_HUNK_LINES) {
+        println!("      {}", line);
+    }
+    if replayed.hunks.len() > MAX_HUNK_LINES {
+        println!(
+            "      {}",
+            color::dim(&format!(
+                "... {} more line(s)",
+                replayed.hunks.len() - MAX_HUNK_LINES
+            ))
+        );
+    }
+}
+
+fn replay(args: &[String], session: &str, global_args: &[String]) {
+    let invalid = |message: String| -> ! {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            format!("{}\nUsage: agent-browser {}", message, USAGE),
+        )
+    };
+    let (mut file, mut pause, mut compare_snapshots) = (None, false, false);
+    for arg in &args[2..] {
+        match arg.as_str() {
+            "--pause-on-step" => pause = true,
+            "--compare-snapshots" => compare_snapshots = true,
+            other if !other.starts_with("--") && file.is_none() => {
+                file = Some(PathBuf::from(other))
+            }
+            other => invalid(format!("Unknown argument: {}", other)),
+        }
+    }
+    let Some(file) = file else {
+        invalid("transcript replay needs a transcript file".into());
+    };
+    let (steps, skipped_lines) =
+        read_steps(&file).unwrap_or_else(|e| ui::fail(ErrorCode::InvalidArgs, e));
+    if steps.is_empty() {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            format!("No steps in {}", file.display()),
+        );
+    }
+    if skipped_lines > 0 {
+        ui::warn(format!(
+            "Skipped {} unreadable line(s) in {}",
+            skipped_lines,
+            file.display()
+        ));
+    }
+    // Prompts need someone to answer them
+    let pause = pause && io::stdin().is_terminal();
+
+    // Start the session's daemon with this invocation's launch options
+    let exe = env::current_exe().unwrap_or_else(|_| PathBuf::from("agent-browser"));
+    match Command::new(exe)
+        .args(global_args)
+        .args(["--json", "which"])
+        .output()
+    {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => ui::fail(
+            ErrorCode::Daemon,
+            format!(
+                "Could not start the daemon: {}",
+                String::from_utf8_lossy(&output.stdout).trim()
+            ),
+        ),
+        Err(e) => ui::fail(
+            ErrorCode::Daemon,
+            format!("Could not start the daemon: {}", e),
+        ),
+    }
+
+    let mut results = Vec::new();
+    let mut previous_snapshot: Option<&str> = None;
+    for step in &steps {
+        let snapshot = step["observation"]["snapshot"].as_str();
+        // A snapshot the previous step already had isn't taken again
+        let fresh = snapshot.filter(|s| Some(*s) != previous_snapshot);
+        previous_snapshot = snapshot;
+        let label = describe(&step["action"]);
+        let number = step["step"].as_u64().unwrap_or(0);
+        let replayed = if pause && !confirm_step(number, &label) {
+            Replayed {
+                number,
+                label,
+                skipped: true,
+                live: Value::Null,
+                divergences: Vec::new(),
+                hunks: Vec::new(),
+            }
+        } else {
+            replay_step(session, step, fresh, compare_snapshots)
+        };
+        if !ui::json_mode() {
+            print_replayed(&replayed);
+        }
+        results.push(replayed);
+    }
+
+    let diverged = results.iter().filter(|r| !r.divergences.is_empty()).count();
+    if ui::json_mode() {
+        let steps: Vec<Value> = results
+            .iter()
+            .map(|r| {
+                json!({
+                    "step": r.number,
+                    "description": r.label,
+                    "skipped": r.skipped,
+                    "result": r.live,
+                    "diverged": !r.divergences.is_empty(),
+                    "divergences": r.divergences,
+                })
+            })
+            .collect();
+        ui::print_json_data(json!({
+            "file": file,
+            "replayed": results.iter().filter(|r| !r.skipped).count(),
+            "diverged": diverged,
+            "steps": steps,
+        }));
+    } else if diverged == 0 {
+        ui::status(format!(
+            "{} Replayed {} steps with no divergence",
+            color::success_indicator(),
+            results.len()
+        ));
+    } else {
+        ui::status(format!(
+            "{} {} of {} steps diverged from the transcript",
+            color::error_indicator(),
+            diverged,
+            results.len()
+        ));
+    }
+    if diverged > 0 {
+        std::process::exit(1);
+    }
+}
+
+pub fn run_transcript(args: &[String], session: &str, out: Option<&str>, global_args: &[String]) {
+    let invalid = |message: String| -> ! {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            format!("{}\nUsage: agent-browser {}", message, USAGE),
+        )
+    };
+    match args.get(1).map(String::as_str) {
+        Some("export") => {
+            let mut format = Format::Jsonl;
+            let mut i = 2;
+            while i < args.len() {
+                match args[i].as_str() {
+                    "--format" => {
+                        format = match args.get(i + 1).map(String::as_str) {
+                            Some("jsonl") => Format::Jsonl,
+                            Some("json") => Format::Json,
+                            Some(other) => invalid(format!(
+                                "Unknown transcript format: {} (expected jsonl or json)",
+                                other
+                            )),
+                            None => invalid("--format needs a value".into()),
+                        };
+                        i += 1;
+                    }
+                    other => invalid(format!("Unknown argument: {}", other)),
+                }
+                i += 1;
+            }
+            let path = transcript_path(session);
+            if !path.exists() {
+                ui::fail(
+                    ErrorCode::InvalidArgs,
+                    format!(
+                        "No transcript for session {}; record one with --transcript",
+                        session
+                    ),
+                );
+            }
+            let (steps, skipped) =
+                read_steps(&path).unwrap_or_else(|e| ui::fail(ErrorCode::Unknown, e));
+            if skipped > 0 {
+                ui::warn(format!(
+                    "Skipped {} unreadable line(s) in {}",
+                    skipped,
+                    path.display()
+                ));
+            }
+            let text = render(&steps, format);
+            let Some(out) = out else {
+                let _ = std::io::stdout().write_all(text.as_bytes());
+                return;
+            };
+            if let Err(e) = fs::write(out, text) {
+                ui::fail(
+                    ErrorCode::Unknown,
+                    format!("Could not write {}: {}", out, e),
+                );
+            }
+            if ui::json_mode() {
+                ui::print_json_data(json!({ "path": out, "steps": steps.len() }));
+            } else {
+                ui::status(format!(
+                    "{} Exported {} steps to {}",
+                    color::success_indicator(),
+                    steps.len(),
+                    out
+                ));
+            }
+        }
+        Some("replay") => replay(args, session, global_args),
+        Some(other) => invalid(format!("Unknown transcript subcommand: {}", other)),
+        None => invalid("transcript needs a subcommand".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_and_render_steps() {
+        let dir = std::env::temp_dir().join(format!("ab-transcript-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("transcript.jsonl");
+        let step = json!({
+            "step": 1,
+            "time": "2026-01-31T10:00:00.000Z",
+            "observation": { "url": "https://example.com/", "snapshot": "- link", "screenshot": "/tmp/step-0001.png" },
+            "action": { "action": "click", "selector": "@e1" },
+            "result": { "success": true, "data": {} },
+            "durationMs": 12,
+        });
+        fs::write(&path, format!("{}\nnot json\n\n{}\n", step, step)).unwrap();
+
+        let (steps, skipped) = read_steps(&path).unwrap();
+        assert_eq!((steps.len(), skipped), (2, 1));
+
+        let jsonl = render(&steps, Format::Jsonl);
+        let first: Value = serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+        assert_eq!(first["observation"]["screenshot"], "/tmp/step-0001.png");
+        assert_eq!(first["action"]["action"], "click");
+        let all: Value = serde_json::from_str(&render(&steps, Format::Json)).unwrap();
+        assert_eq!(all.as_array().unwrap().len(), 2);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            describe(&json!({ "action": "fill", "selector": "@e2", "value": "ada" })),
+            "fill @e2 ada"
+        );
+        let long = "x".repeat(80);
+        assert_eq!(
+            describe(&json!({ "action": "evaluate", "script": long })),
+            format!("evaluate {}…", "x".repeat(60))
+        );
+        assert_eq!(describe(&json!({ "action": "reload" })), "reload");
+    }
+}
@@ -0,0 +1,109 @@
+//! Output modes shared by every subcommand.
+//!
+//! `--json` prints a single JSON object per invocation on stdout. Every object
+//! carries a `version` field so scripts can detect schema changes. `--quiet`
+//! drops progress and confirmation lines but keeps requested data and errors.
+//! Diagnostics (`-v`, `--log-file`) go through `tracing`; see `logging.rs`.
+
+use std::fmt::Display;
+use std::process::exit;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde_json::{json, Value};
+
+use crate::color;
+use crate::errors::ErrorCode;
+
+/// Version of the `--json` envelope. Bump when a field is renamed or removed.
+pub const JSON_VERSION: u32 = 1;
+
+static JSON: AtomicBool = AtomicBool::new(false);
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+pub fn init(json: bool, quiet: bool) {
+    JSON.store(json, Ordering::Relaxed);
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn json_mode() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+pub fn quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Wrap a result in the versioned `--json` envelope.
+pub fn envelope(success: bool, data: Option<Value>, error: Option<&str>) -> Value {
+    let mut out = json!({ "version": JSON_VERSION, "success": success });
+    if let Some(data) = data {
+        out["data"] = data;
+    }
+    if let Some(error) = error {
+        out["error"] = json!(error);
+    }
+    out
+}
+
+/// Print a successful `--json` result.
+pub fn print_json_data(data: Value) {
+    println!("{}", envelope(true, Some(data), None));
+}
+
+/// Progress or confirmation line on stdout. Dropped by `--quiet` and `--json`.
+pub fn status(msg: impl Display) {
+    if !quiet() && !json_mode() {
+        println!("{}", msg);
+    }
+}
+
+/// Warning on stderr. Dropped by `--quiet`.
+pub fn warn(msg: impl Display) {
+    if !quiet() {
+        eprintln!("{} {}", color::warning_indicator(), msg);
+    }
+}
+
+/// Report an error and exit with the code's exit status.
+pub fn fail(code: ErrorCode, msg: impl Display) -> ! {
+    fail_with_type(code, msg, None)
+}
+
+/// Like [`fail`], adding a finer-grained `type` to the JSON output.
+pub fn fail_with_type(code: ErrorCode, msg: impl Display, error_type: Option<&str>) -> ! {
+    let msg = msg.to_string();
+    match error_type {
+        Some(error_type) => tracing::error!(code = code.as_str(), error_type, "{}", msg),
+        None => tracing::error!(code = code.as_str(), "{}", msg),
+    }
+    if json_mode() {
+        let mut out = envelope(false, None, Some(&msg));
+        out["code"] = json!(code.as_str());
+        if let Some(error_type) = error_type {
+            out["type"] = json!(error_type);
+        }
+        println!("{}", out);
+    } else {
+        eprintln!("{} {}", color::error_indicator(), msg);
+    }
+    exit(code.exit_code());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_has_version() {
+        let ok = envelope(true, Some(json!({ "url": "x" })), None);
+        assert_eq!(ok["version"], JSON_VERSION);
+        assert_eq!(ok["success"], true);
+        assert_eq!(ok["data"]["url"], "x");
+        assert!(ok.get("error").is_none());
+
+        let err = envelope(false, None, Some("boom"));
+        assert_eq!(err["success"], false);
+        assert_eq!(err["error"], "boom");
+        assert!(err.get("data").is_none());
+    }
+}
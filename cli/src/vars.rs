@@ -0,0 +1,284 @@
+//! Variables (`agent-browser var`).
+//!
+//! Each session has a key-value store in `~/.browseros/vars/<session>.json`.
+//! `{{name}}` in any command's arguments is replaced with the variable's value
+//! before the command is parsed, and since every step of a `run` script is its
+//! own invocation, scripts get the same substitution. `--into <name>` stores
+//! what a command returned (the text of `get text`, the result of `eval`, ...)
+//! so data can flow from one step to the next without glue code. Names that
+//! aren't set are left as written, so `{{` in page scripts survives.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+
+use crate::color;
+use crate::errors::ErrorCode;
+use crate::install::get_browseros_home;
+use crate::ui;
+
+pub type Vars = BTreeMap<String, Value>;
+
+/// Response fields `--into` stores, in order of preference
+const EXTRACTED_FIELDS: &[&str] = &[
+    "result", "text", "value", "html", "count", "url", "title", "selector",
+];
+
+pub fn vars_path(session: &str) -> PathBuf {
+    get_browseros_home()
+        .join("vars")
+        .join(format!("{}.json", session))
+}
+
+pub fn load(session: &str) -> Vars {
+    fs::read_to_string(vars_path(session))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(session: &str, vars: &Vars) -> Result<(), String> {
+    let path = vars_path(session);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let text = serde_json::to_string_pretty(vars).unwrap_or_default();
+    fs::write(&path, text + "\n").map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+pub fn set(session: &str, name: &str, value: Value) -> Result<(), String> {
+    let mut vars = load(session);
+    vars.insert(name.to_string(), value);
+    save(session, &vars)
+}
+
+pub fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
+
+/// A value as it is substituted: strings as they are, anything else as JSON
+pub fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Replace `{{name}}` (spaces inside the braces allowed) with variables.
+/// Unknown names and anything that isn't a valid name are left as written.
+pub fn interpolate(text: &str, vars: &Vars) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let value = after.find("}}").and_then(|end| {
+            let name = after[..end].trim();
+            vars.get(name)
+                .filter(|_| valid_name(name))
+                .map(|v| (display(v), end))
+        });
+        match value {
+            Some((value, end)) => {
+                out.push_str(&value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                out.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Interpolate command arguments, reading the store only when needed
+pub fn interpolate_args(args: &[String], session: &str) -> Vec<String> {
+    if !args.iter().any(|a| a.contains("{{")) {
+        return args.to_vec();
+    }
+    let vars = load(session);
+    args.iter().map(|a| interpolate(a, &vars)).collect()
+}
+
+/// The value a command returned, for `--into`
+pub fn extract(data: &Value) -> Option<Value> {
+    EXTRACTED_FIELDS
+        .iter()
+        .find_map(|field| data.get(field))
+        .cloned()
+}
+
+/// Store what a command returned in `name`, failing if it returned nothing
+pub fn store_result(session: &str, name: &str, action: &str, data: Option<&Value>) {
+    let Some(value) = data.and_then(extract) else {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            format!("'{}' returned no value to store in {}", action, name),
+        );
+    };
+    if let Err(e) = set(session, name, value) {
+        ui::fail(ErrorCode::Unknown, e);
+    }
+}
+
+const USAGE: &str =
+    "var <set <name> <value> [--parse-json] | get <name> | list | unset <name> | clear>";
+
+fn name_arg<'a>(args: &'a [String], op: &str) -> &'a str {
+    let name = args.get(2).map(String::as_str).unwrap_or_else(|| {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            format!("var {} needs a name\nUsage: agent-browser {}", op, USAGE),
+        )
+    });
+    if !valid_name(name) {
+        ui::fail(
+            ErrorCode::InvalidArgs,
+            format!(
+                "Invalid variable name: '{}' (use letters, digits, _, - and .)",
+                name
+            ),
+        );
+    }
+    name
+}
+
+/// Handle `agent-browser var` (runs locally, no daemon)
+pub fn run_var(args: &[String], session: &str) {
+    let fail = |e: String| -> ! { ui::fail(ErrorCode::Unknown, e) };
+    match args.get(1).map(String::as_str) {
+        Some("set") => {
+            let name = name_arg(args, "set");
+            let raw = args.get(3).unwrap_or_else(|| {
+                ui::fail(
+                    ErrorCode::InvalidArgs,
+                    format!("var set needs a value\nUsage: agent-browser {}", USAGE),
+                )
+            });
+            // --parse-json stores numbers, booleans, lists and objects as such
+            let value = if args[4..].iter().any(|a| a == "--parse-json") {
+                serde_json::from_str(raw).unwrap_or_else(|e| {
+                    ui::fail(
+                        ErrorCode::InvalidArgs,
+                        format!("Invalid JSON value for {}: {}", name, e),
+                    )
+                })
+            } else {
+                Value::String(raw.clone())
+            };
+            set(session, name, value.clone()).unwrap_or_else(|e| fail(e));
+            if ui::json_mode() {
+                ui::print_json_data(json!({ "name": name, "value": value }));
+            } else {
+                ui::status(format!(
+                    "{} {} = {}",
+                    color::success_indicator(),
+                    name,
+                    display(&value)
+                ));
+            }
+        }
+        Some("get") => {
+            let name = name_arg(args, "get");
+            let Some(value) = load(session).remove(name) else {
+                ui::fail(
+                    ErrorCode::InvalidArgs,
+                    format!("Variable not set: {} (session {})", name, session),
+                );
+            };
+            if ui::json_mode() {
+                ui::print_json_data(json!({ "name": name, "value": value }));
+            } else {
+                println!("{}", display(&value));
+            }
+        }
+        Some("unset") => {
+            let name = name_arg(args, "unset");
+            let mut vars = load(session);
+            let removed = vars.remove(name).is_some();
+            save(session, &vars).unwrap_or_else(|e| fail(e));
+            if ui::json_mode() {
+                ui::print_json_data(json!({ "name": name, "removed": removed }));
+            } else if removed {
+                ui::status(format!("{} Unset {}", color::success_indicator(), name));
+            }
+        }
+        Some("clear") => {
+            let _ = fs::remove_file(vars_path(session));
+            if ui::json_mode() {
+                ui::print_json_data(json!({ "cleared": true }));
+            } else {
+                ui::status(format!(
+                    "{} Cleared the variables of session {}",
+                    color::success_indicator(),
+                    session
+                ));
+            }
+        }
+        Some("list") | None => {
+            let vars = load(session);
+            if ui::json_mode() {
+                ui::print_json_data(json!({ "vars": vars }));
+            } else if vars.is_empty() {
+                println!("No variables set in session {}", session);
+            } else {
+                for (name, value) in &vars {
+                    println!("{} = {}", color::cyan(name), display(value));
+                }
+            }
+        }
+        Some(other) => ui::fail(
+            ErrorCode::InvalidArgs,
+            format!(
+                "Unknown var operation: {}\nUsage: agent-browser {}",
+                other, USAGE
+            ),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate() {
+        let vars: Vars = [
+            ("user".to_string(), json!("ada")),
+            ("count".to_string(), json!(3)),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            interpolate("{{user}} has {{ count }} items", &vars),
+            "ada has 3 items"
+        );
+        // Unknown names, bad names and unclosed braces are kept
+        assert_eq!(
+            interpolate("{{missing}} {{a b}} {{user", &vars),
+            "{{missing}} {{a b}} {{user"
+        );
+    }
+
+    #[test]
+    fn test_extract() {
+        assert_eq!(extract(&json!({ "text": "Hi" })), Some(json!("Hi")));
+        assert_eq!(
+            extract(&json!({ "attribute": "href", "value": "/a" })),
+            Some(json!("/a"))
+        );
+        assert_eq!(
+            extract(&json!({ "result": { "n": 1 } })),
+            Some(json!({ "n": 1 }))
+        );
+        assert_eq!(extract(&json!({ "clicked": true })), None);
+    }
+}
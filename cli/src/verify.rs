@@ -0,0 +1,207 @@
+//! Smoke tests (`agent-browser verify`).
+//!
+//! Runs a fixed sequence of commands in a separate, headless `verify`
+//! session, each as its own `agent-browser --json` invocation like the steps
+//! of `run`: launch with a `data:` URL, evaluate JavaScript, load a real HTTPS
+//! page, take a screenshot, and close. The first failed stage stops the test
+//! and is reported with a hint about what usually causes it, and the command
+//! exits non-zero, so provisioning scripts and CI can gate on it. `init` runs
+//! its own stages through the same runner.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+use crate::color;
+use crate::errors::ErrorCode;
+use crate::run::{run_step, Status, Step, StepResult};
+use crate::ui;
+
+pub const SESSION: &str = "verify";
+const HTTPS_URL: &str = "https://example.com";
+const DATA_URL: &str =
+    "data:text/html,<title>agent-browser verify</title><h1 id=ok>agent-browser</h1>";
+
+pub struct Stage {
+    pub name: &'static str,
+    pub args: Vec<String>,
+    /// What usually makes this stage fail
+    pub hint: &'static str,
+}
+
+impl Stage {
+    pub fn new(name: &'static str, args: &[&str], hint: &'static str) -> Stage {
+        Stage {
+            name,
+            args: args.iter().map(|a| a.to_string()).collect(),
+            hint,
+        }
+    }
+}
+
+fn stages(screenshot: &Path) -> Vec<Stage> {
+    let screenshot = screenshot.to_string_lossy();
+    vec![
+        Stage::new(
+            "launch",
+            &["open", DATA_URL],
+            "The browser did not start. Check 'agent-browser which' for the executable, and on Linux 'agent-browser install --with-deps' for missing libraries.",
+        ),
+        Stage::new(
+            "javascript",
+            &["eval", "document.getElementById('ok').textContent.length"],
+            "The page loaded but could not run JavaScript; check --args and the profile for anything that disables it.",
+        ),
+        Stage::new(
+            "https",
+            &["open", HTTPS_URL],
+            "Could not load an HTTPS page. Check network access, --proxy, and --extra-ca for TLS-intercepting proxies.",
+        ),
+        Stage::new(
+            "screenshot",
+            &["screenshot", &screenshot],
+            "Could not capture the page; in containers check /dev/shm ('agent-browser print-dockerfile').",
+        ),
+    ]
+}
+
+/// Global flags for the stages: the caller's, headless, in `session`, and
+/// without `--json` since every stage reports through its own envelope
+pub fn stage_args(global_args: &[String], session: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut iter = global_args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--json" | "--headed" => {}
+            "--session" => {
+                iter.next();
+            }
+            _ => args.push(arg.clone()),
+        }
+    }
+    args.extend(["--session".to_string(), session.to_string()]);
+    args
+}
+
+/// Run `stages` in order, stopping at the first failure, then close the
+/// session whatever happened
+pub fn run_stages(global_args: &[String], stages: &[Stage]) -> Vec<StepResult> {
+    let exe = env::current_exe().unwrap_or_else(|_| PathBuf::from("agent-browser"));
+    let mut results: Vec<StepResult> = Vec::new();
+    for (i, stage) in stages.iter().enumerate() {
+        let step = Step {
+            line: i + 1,
+            args: stage.args.clone(),
+        };
+        let result = run_step(&exe, global_args, &step);
+        if !ui::json_mode() {
+            let indicator = match result.status {
+                Status::Passed => color::success_indicator(),
+                _ => color::error_indicator(),
+            };
+            println!(
+                "  {} {:<12} {}",
+                indicator,
+                stage.name,
+                color::dim(&format!("{}ms", result.duration_ms))
+            );
+            if let Some(ref error) = result.error {
+                println!("    {}", color::red(error));
+            }
+        }
+        let failed = result.status == Status::Failed;
+        results.push(result);
+        if failed {
+            break;
+        }
+    }
+    let close = Step {
+        line: 0,
+        args: vec!["close".to_string()],
+    };
+    run_step(&exe, global_args, &close);
+    results
+}
+
+/// Handle `agent-browser verify` (runs locally; the stages start their own session)
+pub fn run_verify(global_args: &[String]) {
+    let screenshot =
+        env::temp_dir().join(format!("agent-browser-verify-{}.png", std::process::id()));
+    let stages = stages(&screenshot);
+    if !ui::json_mode() {
+        println!("{}", color::bold("Verifying agent-browser"));
+    }
+    let mut results = run_stages(&stage_args(global_args, SESSION), &stages);
+    // A screenshot that reports success must also have written a file
+    let completed = results.len() == stages.len();
+    if let Some(last) = results.last_mut() {
+        if completed && last.status == Status::Passed && !screenshot.is_file() {
+            last.status = Status::Failed;
+            last.error = Some(format!("{} was not written", screenshot.display()));
+        }
+    }
+    let _ = fs::remove_file(&screenshot);
+
+    if let Some((result, stage)) = results
+        .iter()
+        .zip(&stages)
+        .find(|(r, _)| r.status == Status::Failed)
+    {
+        ui::fail(
+            ErrorCode::from_code(result.code.as_deref()),
+            format!(
+                "Verification failed at {}: {}\n{}",
+                stage.name,
+                result.error.as_deref().unwrap_or("unknown error"),
+                stage.hint
+            ),
+        );
+    }
+
+    if ui::json_mode() {
+        let report: Vec<_> = results
+            .iter()
+            .zip(&stages)
+            .map(|(result, stage)| {
+                json!({
+                    "stage": stage.name,
+                    "command": result.command,
+                    "durationMs": result.duration_ms,
+                })
+            })
+            .collect();
+        ui::print_json_data(json!({ "passed": true, "stages": report }));
+    } else {
+        println!("{} agent-browser works", color::success_indicator());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_stage_args_headless_in_own_session() {
+        assert_eq!(
+            stage_args(
+                &args("--json --headed --session work --browser chrome"),
+                SESSION
+            ),
+            args("--browser chrome --session verify")
+        );
+    }
+
+    #[test]
+    fn test_stages_end_with_screenshot() {
+        let stages = stages(Path::new("/tmp/v.png"));
+        assert_eq!(stages[0].args[0], "open");
+        assert!(stages[0].args[1].starts_with("data:"));
+        assert_eq!(stages.last().unwrap().args, args("screenshot /tmp/v.png"));
+    }
+}
@@ -0,0 +1,320 @@
+//! Visual regression comparison (`compare-screenshot`).
+//!
+//! The daemon takes the screenshot (with `--mask` elements painted over);
+//! the comparison runs here. Pixels are compared with the perceptual YIQ
+//! color distance used by pixelmatch, so `--threshold` means the same as in
+//! Playwright's `toHaveScreenshot`: 0 is exact, 1 accepts anything.
+//!
+//! A missing baseline is created from the current screenshot. Failed
+//! comparisons write a diff image: the baseline faded to gray with differing
+//! pixels in red.
+
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::color;
+use crate::errors::ErrorCode;
+use crate::ui;
+
+/// Largest possible YIQ delta between two colors.
+const MAX_YIQ_DELTA: f64 = 35215.0;
+
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    /// RGBA8 pixels, row-major
+    pub pixels: Vec<u8>,
+}
+
+pub struct Comparison {
+    pub diff_pixels: u64,
+    pub total_pixels: u64,
+    /// Baseline faded to gray with differing pixels in red
+    pub diff_image: Image,
+}
+
+pub fn read_png(path: &Path) -> Result<Image, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut decoder = png::Decoder::new(std::io::BufReader::new(file));
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder
+        .read_info()
+        .map_err(|e| format!("{} is not a valid PNG: {}", path.display(), e))?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buf)
+        .map_err(|e| format!("Failed to decode {}: {}", path.display(), e))?;
+    buf.truncate(info.buffer_size());
+
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => buf
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        png::ColorType::Grayscale => buf.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+        png::ColorType::Indexed => {
+            return Err(format!("Unsupported PNG color type in {}", path.display()))
+        }
+    };
+    Ok(Image {
+        width: info.width,
+        height: info.height,
+        pixels,
+    })
+}
+
+pub fn write_png(path: &Path, image: &Image) -> Result<(), String> {
+    let file =
+        File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), image.width, image.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut w| w.write_image_data(&image.pixels))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Blend a pixel with a white background, as it would be seen on screen.
+fn blend(p: &[u8]) -> (f64, f64, f64) {
+    let a = p[3] as f64 / 255.0;
+    let c = |v: u8| 255.0 + (v as f64 - 255.0) * a;
+    (c(p[0]), c(p[1]), c(p[2]))
+}
+
+/// Squared perceptual distance between two RGBA pixels (pixelmatch's YIQ metric).
+fn color_delta(a: &[u8], b: &[u8]) -> f64 {
+    if a == b {
+        return 0.0;
+    }
+    let (r1, g1, b1) = blend(a);
+    let (r2, g2, b2) = blend(b);
+    let y = |r: f64, g: f64, b: f64| r * 0.29889531 + g * 0.58662247 + b * 0.11448223;
+    let i = |r: f64, g: f64, b: f64| r * 0.59597799 - g * 0.2741761 - b * 0.32180189;
+    let q = |r: f64, g: f64, b: f64| r * 0.21147017 - g * 0.52261711 + b * 0.31114694;
+    let dy = y(r1, g1, b1) - y(r2, g2, b2);
+    let di = i(r1, g1, b1) - i(r2, g2, b2);
+    let dq = q(r1, g1, b1) - q(r2, g2, b2);
+    0.5053 * dy * dy + 0.299 * di * di + 0.1957 * dq * dq
+}
+
+/// Compare two images of the same size. `threshold` is the per-pixel color
+/// tolerance from 0 (exact) to 1.
+pub fn compare(baseline: &Image, current: &Image, threshold: f64) -> Result<Comparison, String> {
+    if (baseline.width, baseline.height) != (current.width, current.height) {
+        return Err(format!(
+            "Size differs: baseline is {}x{}, screenshot is {}x{}",
+            baseline.width, baseline.height, current.width, current.height
+        ));
+    }
+    let max_delta = MAX_YIQ_DELTA * threshold * threshold;
+    let mut diff_pixels = 0;
+    let mut out = Vec::with_capacity(baseline.pixels.len());
+    for (a, b) in baseline
+        .pixels
+        .chunks_exact(4)
+        .zip(current.pixels.chunks_exact(4))
+    {
+        if color_delta(a, b) > max_delta {
+            diff_pixels += 1;
+            out.extend_from_slice(&[255, 0, 0, 255]);
+        } else {
+            let (r, g, b) = blend(a);
+            let gray = (r * 0.299 + g * 0.587 + b * 0.114) as u8;
+            // Fade toward white so the red stands out
+            let faded = 255 - (255 - gray) / 10;
+            out.extend_from_slice(&[faded, faded, faded, 255]);
+        }
+    }
+    Ok(Comparison {
+        diff_pixels,
+        total_pixels: baseline.width as u64 * baseline.height as u64,
+        diff_image: Image {
+            width: baseline.width,
+            height: baseline.height,
+            pixels: out,
+        },
+    })
+}
+
+/// `base.png` -> `base.diff.png`
+pub fn default_diff_path(baseline: &Path) -> PathBuf {
+    let stem = baseline
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("screenshot");
+    baseline.with_file_name(format!("{}.diff.png", stem))
+}
+
+fn save_baseline(screenshot: &Path, baseline: &Path) -> Result<(), String> {
+    if let Some(parent) = baseline.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::copy(screenshot, baseline)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to write {}: {}", baseline.display(), e))
+}
+
+/// Finish `compare-screenshot` once the daemon has saved the screenshot.
+/// `options` holds baseline, threshold, maxDiffRatio, diff and updateBaseline.
+pub fn finish_compare(screenshot: &Path, options: &Value) -> ! {
+    let baseline = PathBuf::from(options["baseline"].as_str().unwrap_or_default());
+    let threshold = options["threshold"].as_f64().unwrap_or(0.1);
+    let max_ratio = options["maxDiffRatio"].as_f64().unwrap_or(0.0);
+    let diff_path = options["diff"]
+        .as_str()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| default_diff_path(&baseline));
+    let update = options["updateBaseline"].as_bool().unwrap_or(false);
+
+    let result = run_compare(
+        screenshot, &baseline, &diff_path, threshold, max_ratio, update,
+    );
+    let _ = fs::remove_file(screenshot);
+    let (data, passed) = result.unwrap_or_else(|e| ui::fail(ErrorCode::Unknown, e));
+
+    if ui::json_mode() {
+        if passed {
+            ui::print_json_data(data);
+        } else {
+            let mut out = ui::envelope(false, Some(data), Some("Screenshot differs from baseline"));
+            out["code"] = json!(ErrorCode::VisualMismatch.as_str());
+            println!("{}", out);
+        }
+    } else {
+        let status = data["status"].as_str().unwrap_or("");
+        match status {
+            "created" | "updated" => ui::status(format!(
+                "{} Baseline {}: {}",
+                color::success_indicator(),
+                status,
+                baseline.display()
+            )),
+            "passed" => ui::status(format!(
+                "{} Matches baseline {}",
+                color::success_indicator(),
+                color::dim(&format!("({} pixels differ)", data["diffPixels"]))
+            )),
+            _ => {
+                eprintln!(
+                    "{} Screenshot differs from baseline: {} of {} pixels ({:.2}%)",
+                    color::error_indicator(),
+                    data["diffPixels"],
+                    data["totalPixels"],
+                    data["diffRatio"].as_f64().unwrap_or(0.0) * 100.0
+                );
+                if let Some(diff) = data["diff"].as_str() {
+                    eprintln!("  diff: {}", diff);
+                }
+            }
+        }
+    }
+    std::process::exit(if passed {
+        0
+    } else {
+        ErrorCode::VisualMismatch.exit_code()
+    })
+}
+
+fn run_compare(
+    screenshot: &Path,
+    baseline: &Path,
+    diff_path: &Path,
+    threshold: f64,
+    max_ratio: f64,
+    update: bool,
+) -> Result<(Value, bool), String> {
+    let exists = baseline.exists();
+    if update || !exists {
+        save_baseline(screenshot, baseline)?;
+        let status = if exists { "updated" } else { "created" };
+        let data = json!({ "status": status, "baseline": baseline, "artifacts": [baseline] });
+        return Ok((data, true));
+    }
+
+    let base = read_png(baseline)?;
+    let current = read_png(screenshot)?;
+    let comparison = match compare(&base, &current, threshold) {
+        Ok(c) => c,
+        Err(size_error) => {
+            let actual = baseline.with_file_name(format!(
+                "{}.actual.png",
+                baseline
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("screenshot")
+            ));
+            fs::copy(screenshot, &actual)
+                .map_err(|e| format!("Failed to write {}: {}", actual.display(), e))?;
+            return Err(format!("{} (saved as {})", size_error, actual.display()));
+        }
+    };
+    let ratio = comparison.diff_pixels as f64 / comparison.total_pixels.max(1) as f64;
+    let passed = ratio <= max_ratio;
+    let mut data = json!({
+        "status": if passed { "passed" } else { "failed" },
+        "baseline": baseline,
+        "diffPixels": comparison.diff_pixels,
+        "totalPixels": comparison.total_pixels,
+        "diffRatio": ratio,
+    });
+    if !passed {
+        write_png(diff_path, &comparison.diff_image)?;
+        data["diff"] = json!(diff_path);
+        data["artifacts"] = json!([diff_path]);
+    }
+    Ok((data, passed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, rgba: [u8; 4]) -> Image {
+        Image {
+            width,
+            height,
+            pixels: rgba.repeat((width * height) as usize),
+        }
+    }
+
+    #[test]
+    fn test_compare_counts_differing_pixels() {
+        let base = solid(4, 4, [255, 255, 255, 255]);
+        let mut current = solid(4, 4, [255, 255, 255, 255]);
+        current.pixels[0..4].copy_from_slice(&[0, 0, 0, 255]);
+        // Barely different; within the default tolerance
+        current.pixels[4..8].copy_from_slice(&[250, 250, 250, 255]);
+
+        let result = compare(&base, &current, 0.1).unwrap();
+        assert_eq!(result.diff_pixels, 1);
+        assert_eq!(result.total_pixels, 16);
+        assert_eq!(&result.diff_image.pixels[0..4], &[255, 0, 0, 255]);
+
+        assert_eq!(compare(&base, &current, 0.0).unwrap().diff_pixels, 2);
+        assert!(compare(&base, &solid(4, 5, [0, 0, 0, 255]), 0.1).is_err());
+    }
+
+    #[test]
+    fn test_png_round_trip() {
+        let dir = std::env::temp_dir().join(format!("ab-visual-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("img.png");
+        let image = solid(3, 2, [10, 20, 30, 255]);
+        write_png(&path, &image).unwrap();
+        let read = read_png(&path).unwrap();
+        assert_eq!((read.width, read.height), (3, 2));
+        assert_eq!(read.pixels, image.pixels);
+        assert_eq!(default_diff_path(&path), dir.join("img.diff.png"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
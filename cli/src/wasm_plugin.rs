@@ -0,0 +1,481 @@
+//! In-process WASM plugins.
+//!
+//! A `<name>.wasm` file in `~/.browseros/plugins` becomes `agent-browser
+//! <name>`, like an `agent-browser-<name>` executable on `PATH`, but runs
+//! inside the CLI under an interpreter. It has no imports besides the ones
+//! below, so it can't open files or sockets itself, and each run gets a fixed
+//! amount of fuel so a runaway loop fails instead of hanging the CLI.
+//!
+//! Its commands are limited to [`ALLOWED_COMMANDS`]: navigating, interacting
+//! with and reading the page. Anything that writes files, runs scripts or
+//! changes configuration is refused. A plugin can still navigate to any URL
+//! and read what the page shows, so only install plugins you trust.
+//!
+//! The module exports `memory`, `alloc(len) -> ptr` and
+//! `run(ptr, len) -> status`. `run` gets the arguments after the plugin name
+//! as a JSON array of strings and returns 0 on success. From the
+//! `agent_browser` import module it can call:
+//! - `command(ptr, len) -> i64`: run a command given as a JSON array of
+//!   arguments, e.g. `["get", "text", "h1"]`, in the calling session. The
+//!   result, `{"success", "data", "error", "code"}`, is written to memory
+//!   from `alloc`, and its pointer and length come back as `ptr << 32 | len`.
+//! - `output(ptr, len)`: set the plugin's result, as JSON.
+//! - `error(ptr, len)`: set the message to fail with when `run` returns
+//!   non-zero.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde_json::{json, Value};
+use wasmi::core::TrapCode;
+use wasmi::{Caller, Config, Engine, Error, Extern, Linker, Memory, Module, Store};
+
+use crate::errors::ErrorCode;
+use crate::flags::{
+    Flags, GLOBAL_FLAGS, GLOBAL_FLAGS_WITH_OPTIONAL_VALUE, GLOBAL_FLAGS_WITH_VALUE,
+};
+use crate::install::get_browseros_home;
+use crate::ui;
+
+pub const EXTENSION: &str = "wasm";
+
+/// Instructions a plugin may execute per run, not counting the time its
+/// commands take.
+const FUEL: u64 = 1_000_000_000;
+
+/// Commands a plugin may run.
+const ALLOWED_COMMANDS: &[&str] = &[
+    "open",
+    "goto",
+    "navigate",
+    "back",
+    "forward",
+    "reload",
+    "click",
+    "dblclick",
+    "type",
+    "fill",
+    "press",
+    "key",
+    "keydown",
+    "keyup",
+    "hover",
+    "focus",
+    "select",
+    "check",
+    "uncheck",
+    "scroll",
+    "scrollintoview",
+    "scrollinto",
+    "drag",
+    "snapshot",
+    "get",
+    "is",
+    "find",
+    "wait",
+    "highlight",
+    "dismiss-overlays",
+];
+
+/// URL schemes a plugin can't open, since they read local files or run
+/// scripts in the page.
+const BLOCKED_SCHEMES: &[&str] = &["file:", "javascript:", "data:", "view-source:"];
+
+/// Where WASM plugins are looked up.
+pub fn plugins_dir() -> PathBuf {
+    get_browseros_home().join("plugins")
+}
+
+/// Why a plugin may not run `args`, if it may not.
+fn refuse(args: &[String]) -> Option<String> {
+    let command = args[0].as_str();
+    if !ALLOWED_COMMANDS.contains(&command) {
+        return Some(format!("Plugins can't run {}", command));
+    }
+    for arg in &args[1..] {
+        let flag = arg.split('=').next().unwrap_or(arg);
+        if GLOBAL_FLAGS.contains(&flag)
+            || GLOBAL_FLAGS_WITH_VALUE.contains(&flag)
+            || GLOBAL_FLAGS_WITH_OPTIONAL_VALUE.contains(&flag)
+        {
+            return Some(format!("Plugins can't pass {}", flag));
+        }
+    }
+    if command == "wait" && args.iter().any(|a| a == "--download" || a == "-d") {
+        return Some("Plugins can't save downloads".to_string());
+    }
+    if matches!(command, "open" | "goto" | "navigate") {
+        let blocked = args[1..].iter().any(|a| {
+            let a = a.to_ascii_lowercase();
+            BLOCKED_SCHEMES.iter().any(|scheme| a.starts_with(scheme))
+        });
+        if blocked {
+            return Some("Plugins can only open web pages".to_string());
+        }
+    }
+    None
+}
+
+type Commands = Box<dyn FnMut(&[String]) -> Value>;
+
+struct Host {
+    commands: Commands,
+    output: Value,
+    error: Option<String>,
+}
+
+fn memory(caller: &Caller<'_, Host>) -> Result<Memory, Error> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| Error::new("plugin doesn't export memory"))
+}
+
+fn read(caller: &Caller<'_, Host>, ptr: i32, len: i32) -> Result<Vec<u8>, Error> {
+    let memory = memory(caller)?;
+    let (ptr, len) = (ptr as u32 as usize, len as u32 as usize);
+    memory
+        .data(caller)
+        .get(ptr..ptr.saturating_add(len))
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| Error::new("plugin passed memory out of bounds"))
+}
+
+fn read_json(caller: &Caller<'_, Host>, ptr: i32, len: i32) -> Result<Value, Error> {
+    serde_json::from_slice(&read(caller, ptr, len)?)
+        .map_err(|e| Error::new(format!("plugin passed invalid JSON: {}", e)))
+}
+
+/// Copy bytes into memory the plugin allocates, as `ptr << 32 | len`.
+fn write(caller: &mut Caller<'_, Host>, bytes: &[u8]) -> Result<i64, Error> {
+    let alloc = caller
+        .get_export("alloc")
+        .and_then(Extern::into_func)
+        .ok_or_else(|| Error::new("plugin doesn't export alloc"))?
+        .typed::<i32, i32>(&*caller)?;
+    let ptr = alloc.call(&mut *caller, bytes.len() as i32)?;
+    memory(caller)?
+        .write(&mut *caller, ptr as u32 as usize, bytes)
+        .map_err(|e| Error::new(e.to_string()))?;
+    Ok((i64::from(ptr as u32) << 32) | bytes.len() as i64)
+}
+
+fn linker(engine: &Engine) -> Result<Linker<Host>, Error> {
+    let mut linker = Linker::new(engine);
+    linker.func_wrap(
+        "agent_browser",
+        "command",
+        |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> Result<i64, Error> {
+            let args: Option<Vec<String>> = serde_json::from_value(read_json(&caller, ptr, len)?)
+                .ok()
+                .filter(|args: &Vec<String>| !args.is_empty());
+            let response = match args {
+                Some(args) => match refuse(&args) {
+                    Some(error) => json!({ "success": false, "error": error }),
+                    None => (caller.data_mut().commands)(&args),
+                },
+                None => json!({
+                    "success": false,
+                    "error": "A command is a non-empty JSON array of strings",
+                }),
+            };
+            write(&mut caller, response.to_string().as_bytes())
+        },
+    )?;
+    linker.func_wrap(
+        "agent_browser",
+        "output",
+        |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> Result<(), Error> {
+            caller.data_mut().output = read_json(&caller, ptr, len)?;
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        "agent_browser",
+        "error",
+        |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> Result<(), Error> {
+            let message = String::from_utf8_lossy(&read(&caller, ptr, len)?).to_string();
+            caller.data_mut().error = Some(message);
+            Ok(())
+        },
+    )?;
+    Ok(linker)
+}
+
+/// Run a plugin with its arguments and `fuel`, returning what it output.
+fn execute(wasm: &[u8], args: &[String], commands: Commands, fuel: u64) -> Result<Value, String> {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config);
+    let module = Module::new(&engine, wasm).map_err(|e| format!("Invalid module: {}", e))?;
+    let mut store = Store::new(
+        &engine,
+        Host {
+            commands,
+            output: Value::Null,
+            error: None,
+        },
+    );
+    store.set_fuel(fuel).map_err(|e| e.to_string())?;
+    let instance = linker(&engine)
+        .and_then(|linker| linker.instantiate(&mut store, &module))
+        .and_then(|pre| pre.start(&mut store))
+        .map_err(|e| e.to_string())?;
+    let run = instance
+        .get_typed_func::<(i32, i32), i32>(&store, "run")
+        .map_err(|_| "Plugin doesn't export run(ptr, len) -> i32".to_string())?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&store, "alloc")
+        .map_err(|_| "Plugin doesn't export alloc(len) -> ptr".to_string())?;
+    let memory = instance
+        .get_memory(&store, "memory")
+        .ok_or("Plugin doesn't export memory")?;
+
+    let args = Value::from(args).to_string();
+    let len = args.len() as i32;
+    let ptr = alloc.call(&mut store, len).map_err(|e| e.to_string())?;
+    memory
+        .write(&mut store, ptr as u32 as usize, args.as_bytes())
+        .map_err(|e| e.to_string())?;
+    let status = run.call(&mut store, (ptr, len)).map_err(|e| {
+        if e.as_trap_code() == Some(TrapCode::OutOfFuel) {
+            "Plugin ran out of fuel (is it stuck in a loop?)".to_string()
+        } else {
+            e.to_string()
+        }
+    })?;
+    let host = store.into_data();
+    if status == 0 {
+        Ok(host.output)
+    } else {
+        Err(host
+            .error
+            .unwrap_or_else(|| format!("Plugin exited with status {}", status)))
+    }
+}
+
+/// A command from a plugin, run by the calling binary in the same session,
+/// returning its `--json` envelope.
+fn run_command(exe: &Path, session: &str, args: &[String]) -> Value {
+    let output = Command::new(exe)
+        .arg("--json")
+        .args(args)
+        .env("AGENT_BROWSER_SESSION", session)
+        .stdin(Stdio::null())
+        .output();
+    let output = match output {
+        Ok(output) => output,
+        Err(e) => {
+            return json!({
+                "success": false,
+                "error": format!("Failed to run {}: {}", args[0], e),
+            })
+        }
+    };
+    let envelope = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .rev()
+        .find_map(|line| serde_json::from_str::<Value>(line).ok());
+    match envelope {
+        Some(envelope) => json!({
+            "success": envelope["success"].as_bool().unwrap_or(false) && output.status.success(),
+            "data": envelope["data"],
+            "error": envelope["error"],
+            "code": envelope["code"],
+        }),
+        None => json!({
+            "success": false,
+            "error": String::from_utf8_lossy(&output.stderr).trim(),
+        }),
+    }
+}
+
+/// Run a WASM plugin and exit, printing its output like a command's data.
+pub fn run_wasm_plugin(path: &Path, name: &str, args: &[String], flags: &Flags) -> ! {
+    let plugin_args = args
+        .iter()
+        .position(|a| a == name)
+        .map(|i| &args[i + 1..])
+        .unwrap_or(&[]);
+    let wasm = fs::read(path).unwrap_or_else(|e| {
+        ui::fail(
+            ErrorCode::Unknown,
+            format!("Failed to read plugin {}: {}", path.display(), e),
+        )
+    });
+    let exe = env::current_exe()
+        .unwrap_or_else(|e| ui::fail(ErrorCode::Unknown, format!("No executable path: {}", e)));
+    let session = flags.session.clone();
+
+    tracing::debug!(plugin = name, path = %path.display(), "running WASM plugin");
+    let commands: Commands = Box::new(move |args| run_command(&exe, &session, args));
+    match execute(&wasm, plugin_args, commands, FUEL) {
+        Ok(output) => {
+            if ui::json_mode() {
+                ui::print_json_data(output);
+            } else {
+                match output {
+                    Value::Null => {}
+                    Value::String(text) => println!("{}", text),
+                    other => println!(
+                        "{}",
+                        serde_json::to_string_pretty(&other).unwrap_or_default()
+                    ),
+                }
+            }
+            std::process::exit(0)
+        }
+        Err(e) => ui::fail(ErrorCode::Unknown, format!("Plugin {}: {}", name, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs `get title` and outputs the response; fails with no arguments.
+    const TITLE_PLUGIN: &str = r#"
+        (module
+          (import "agent_browser" "command" (func $command (param i32 i32) (result i64)))
+          (import "agent_browser" "output" (func $output (param i32 i32)))
+          (import "agent_browser" "error" (func $error (param i32 i32)))
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 1024))
+          (data (i32.const 0) "[\"get\",\"title\"]")
+          (data (i32.const 32) "no url given")
+          (func (export "alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (func (export "run") (param $ptr i32) (param $len i32) (result i32)
+            (local $result i64)
+            (if (i32.eq (local.get $len) (i32.const 2))
+              (then
+                (call $error (i32.const 32) (i32.const 12))
+                (return (i32.const 1))))
+            (local.set $result (call $command (i32.const 0) (i32.const 15)))
+            (call $output
+              (i32.wrap_i64 (i64.shr_u (local.get $result) (i64.const 32)))
+              (i32.wrap_i64 (local.get $result)))
+            (i32.const 0)))
+    "#;
+
+    fn title_commands() -> Commands {
+        Box::new(|args| {
+            assert_eq!(args, ["get", "title"]);
+            json!({ "success": true, "data": { "title": "Example" } })
+        })
+    }
+
+    #[test]
+    fn test_plugin_runs_commands_and_outputs() {
+        let wasm = wat::parse_str(TITLE_PLUGIN).unwrap();
+        let output = execute(
+            &wasm,
+            &["https://example.com".to_string()],
+            title_commands(),
+            FUEL,
+        );
+        assert_eq!(
+            output,
+            Ok(json!({ "success": true, "data": { "title": "Example" } }))
+        );
+        assert_eq!(
+            execute(&wasm, &[], title_commands(), FUEL),
+            Err("no url given".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_plugins() {
+        assert!(execute(b"not wasm", &[], title_commands(), FUEL)
+            .unwrap_err()
+            .starts_with("Invalid module"));
+        let no_run = wat::parse_str(r#"(module (memory (export "memory") 1))"#).unwrap();
+        assert_eq!(
+            execute(&no_run, &[], title_commands(), FUEL),
+            Err("Plugin doesn't export run(ptr, len) -> i32".to_string())
+        );
+    }
+
+    #[test]
+    fn test_plugin_commands_are_restricted() {
+        let args = |s: &str| -> Vec<String> { s.split(' ').map(String::from).collect() };
+        assert_eq!(refuse(&args("get text h1")), None);
+        assert_eq!(refuse(&args("open https://example.com")), None);
+        assert_eq!(refuse(&args("snapshot -i -d 3")), None);
+        assert_eq!(
+            refuse(&args("eval document.cookie")),
+            Some("Plugins can't run eval".to_string())
+        );
+        for denied in [
+            "config set on-session-start x",
+            "screenshot /tmp/x.png",
+            "upload #f /etc/passwd",
+            "cdp Runtime.evaluate",
+            "run flow.ab",
+            "plugins",
+        ] {
+            assert!(refuse(&args(denied)).is_some(), "{}", denied);
+        }
+        assert!(refuse(&args("click #a --profile /tmp/p")).is_some());
+        assert!(refuse(&args("get text h1 --out=/tmp/x")).is_some());
+        assert!(refuse(&args("wait --download /tmp/x")).is_some());
+        assert!(refuse(&args("open file:///etc/passwd")).is_some());
+        assert!(refuse(&args("goto JavaScript:alert(1)")).is_some());
+    }
+
+    #[test]
+    fn test_refused_commands_are_not_run() {
+        // Runs its arguments as a command and outputs the response
+        let wasm = wat::parse_str(
+            r#"
+            (module
+              (import "agent_browser" "command" (func $command (param i32 i32) (result i64)))
+              (import "agent_browser" "output" (func $output (param i32 i32)))
+              (memory (export "memory") 1)
+              (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+              (func (export "run") (param $ptr i32) (param $len i32) (result i32)
+                (local $result i64)
+                (local.set $result (call $command (local.get $ptr) (local.get $len)))
+                (call $output
+                  (i32.wrap_i64 (i64.shr_u (local.get $result) (i64.const 32)))
+                  (i32.wrap_i64 (local.get $result)))
+                (i32.const 0)))
+            "#,
+        )
+        .unwrap();
+        let commands: Commands = Box::new(|args| panic!("ran {:?}", args));
+        assert_eq!(
+            execute(
+                &wasm,
+                &["eval".to_string(), "1".to_string()],
+                commands,
+                FUEL
+            ),
+            Ok(json!({ "success": false, "error": "Plugins can't run eval" }))
+        );
+    }
+
+    #[test]
+    fn test_plugin_runs_out_of_fuel() {
+        let wasm = wat::parse_str(
+            r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "alloc") (param i32) (result i32) (i32.const 0))
+              (func (export "run") (param i32 i32) (result i32)
+                (loop $forever (br $forever))
+                (i32.const 0)))
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            execute(&wasm, &[], title_commands(), 10_000),
+            Err("Plugin ran out of fuel (is it stuck in a loop?)".to_string())
+        );
+    }
+}
@@ -0,0 +1,5927 @@
+// Generated by scripts/generate-client.js from src/protocol.ts. Do not edit.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::Command;
+
+/// Every action the daemon accepts
+pub const ACTIONS: &[&str] = &[
+    "launch",
+    "navigate",
+    "click",
+    "type",
+    "fill",
+    "check",
+    "uncheck",
+    "upload",
+    "dblclick",
+    "focus",
+    "drag",
+    "frame",
+    "mainframe",
+    "getbyrole",
+    "getbytext",
+    "getbylabel",
+    "getbyplaceholder",
+    "press",
+    "screenshot",
+    "snapshot",
+    "evaluate",
+    "wait",
+    "scroll",
+    "select",
+    "hover",
+    "content",
+    "close",
+    "tab_new",
+    "tab_list",
+    "targets",
+    "robots",
+    "audit_perf",
+    "audit_seo",
+    "audit_security",
+    "tls_info",
+    "clock_set",
+    "clock_advance",
+    "clock_resume",
+    "schedule_add",
+    "schedule_list",
+    "schedule_remove",
+    "schedule_logs",
+    "jobs",
+    "which",
+    "cancel",
+    "lanes",
+    "tab_switch",
+    "tab_close",
+    "session_options",
+    "dismiss_overlays",
+    "stats",
+    "memory",
+    "cdp",
+    "cdp_listen",
+    "watch",
+    "poll",
+    "trace_step",
+    "capture_step",
+    "window_new",
+    "cookies_get",
+    "cookies_set",
+    "cookies_clear",
+    "storage_get",
+    "storage_set",
+    "storage_clear",
+    "dialog",
+    "pdf",
+    "archive",
+    "freeze",
+    "selectors_suggest",
+    "selectors_heal",
+    "route",
+    "unroute",
+    "requests",
+    "download",
+    "geolocation",
+    "permissions",
+    "viewport",
+    "useragent",
+    "device",
+    "back",
+    "forward",
+    "reload",
+    "url",
+    "title",
+    "getattribute",
+    "gettext",
+    "isvisible",
+    "isenabled",
+    "ischecked",
+    "count",
+    "boundingbox",
+    "styles",
+    "video_start",
+    "video_stop",
+    "recording_start",
+    "recording_stop",
+    "recording_restart",
+    "trace_start",
+    "trace_stop",
+    "capture_start",
+    "capture_stop",
+    "apis",
+    "input",
+    "ws",
+    "har_replay",
+    "har_start",
+    "har_stop",
+    "state_save",
+    "state_load",
+    "session_save",
+    "session_restore",
+    "console",
+    "errors",
+    "keyboard",
+    "wheel",
+    "tap",
+    "clipboard",
+    "highlight",
+    "inspect",
+    "clear",
+    "selectall",
+    "innertext",
+    "innerhtml",
+    "inputvalue",
+    "setvalue",
+    "dispatch",
+    "evalhandle",
+    "expose",
+    "addscript",
+    "addstyle",
+    "emulatemedia",
+    "offline",
+    "headers",
+    "pause",
+    "getbyalttext",
+    "getbytitle",
+    "getbytestid",
+    "nth",
+    "waitforurl",
+    "waitforloadstate",
+    "setcontent",
+    "timezone",
+    "locale",
+    "credentials",
+    "mousemove",
+    "mousedown",
+    "mouseup",
+    "bringtofront",
+    "waitforfunction",
+    "scrollintoview",
+    "addinitscript",
+    "keydown",
+    "keyup",
+    "inserttext",
+    "multiselect",
+    "waitfordownload",
+    "waitforpopup",
+    "waitforrequest",
+    "responsebody",
+    "screencast_start",
+    "screencast_stop",
+    "input_mouse",
+    "input_keyboard",
+    "input_touch",
+    "swipe",
+    "device_list",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchViewport {
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LaunchBrowser {
+    #[serde(rename = "chromium")]
+    Chromium,
+    #[serde(rename = "firefox")]
+    Firefox,
+    #[serde(rename = "webkit")]
+    Webkit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchProxy {
+    pub server: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bypass: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchClientCertificates {
+    pub origin: String,
+    #[serde(rename = "certPath", skip_serializing_if = "Option::is_none")]
+    pub cert_path: Option<String>,
+    #[serde(rename = "keyPath", skip_serializing_if = "Option::is_none")]
+    pub key_path: Option<String>,
+    #[serde(rename = "pfxPath", skip_serializing_if = "Option::is_none")]
+    pub pfx_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passphrase: Option<String>,
+}
+
+/// The `launch` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Launch {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headless: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub viewport: Option<LaunchViewport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub browser: Option<LaunchBrowser>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    #[serde(rename = "cdpPort", skip_serializing_if = "Option::is_none")]
+    pub cdp_port: Option<f64>,
+    #[serde(rename = "cdpUrl", skip_serializing_if = "Option::is_none")]
+    pub cdp_url: Option<String>,
+    #[serde(rename = "executablePath", skip_serializing_if = "Option::is_none")]
+    pub executable_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<LaunchProxy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub args: Option<Vec<String>>,
+    #[serde(rename = "userAgent", skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(rename = "ignoreHTTPSErrors", skip_serializing_if = "Option::is_none")]
+    pub ignore_https_errors: Option<bool>,
+    #[serde(rename = "extraCa", skip_serializing_if = "Option::is_none")]
+    pub extra_ca: Option<Vec<String>>,
+    #[serde(
+        rename = "ignoreCertErrorsFor",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub ignore_cert_errors_for: Option<Vec<String>>,
+    #[serde(rename = "clientCertificates", skip_serializing_if = "Option::is_none")]
+    pub client_certificates: Option<Vec<LaunchClientCertificates>>,
+    #[serde(
+        rename = "authServerAllowlist",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub auth_server_allowlist: Option<Vec<String>>,
+    #[serde(
+        rename = "authDelegateAllowlist",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub auth_delegate_allowlist: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stealth: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deterministic: Option<bool>,
+    #[serde(rename = "initScripts", skip_serializing_if = "Option::is_none")]
+    pub init_scripts: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    #[serde(rename = "remoteToken", skip_serializing_if = "Option::is_none")]
+    pub remote_token: Option<String>,
+    #[serde(rename = "remoteKeepAlive", skip_serializing_if = "Option::is_none")]
+    pub remote_keep_alive: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    #[serde(rename = "storageState", skip_serializing_if = "Option::is_none")]
+    pub storage_state: Option<String>,
+}
+
+impl Launch {
+    pub fn new() -> Self {
+        Self {
+            headless: None,
+            viewport: None,
+            browser: None,
+            channel: None,
+            cdp_port: None,
+            cdp_url: None,
+            executable_path: None,
+            extensions: None,
+            headers: None,
+            proxy: None,
+            args: None,
+            user_agent: None,
+            provider: None,
+            ignore_https_errors: None,
+            extra_ca: None,
+            ignore_cert_errors_for: None,
+            client_certificates: None,
+            auth_server_allowlist: None,
+            auth_delegate_allowlist: None,
+            stealth: None,
+            deterministic: None,
+            init_scripts: None,
+            remote: None,
+            remote_token: None,
+            remote_keep_alive: None,
+            profile: None,
+            storage_state: None,
+        }
+    }
+
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.headless = Some(headless);
+        self
+    }
+
+    pub fn viewport(mut self, viewport: LaunchViewport) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    pub fn browser(mut self, browser: LaunchBrowser) -> Self {
+        self.browser = Some(browser);
+        self
+    }
+
+    pub fn channel(mut self, channel: impl Into<String>) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+
+    pub fn cdp_port(mut self, cdp_port: f64) -> Self {
+        self.cdp_port = Some(cdp_port);
+        self
+    }
+
+    pub fn cdp_url(mut self, cdp_url: impl Into<String>) -> Self {
+        self.cdp_url = Some(cdp_url.into());
+        self
+    }
+
+    pub fn executable_path(mut self, executable_path: impl Into<String>) -> Self {
+        self.executable_path = Some(executable_path.into());
+        self
+    }
+
+    pub fn extensions(mut self, extensions: Vec<String>) -> Self {
+        self.extensions = Some(extensions);
+        self
+    }
+
+    pub fn headers(mut self, headers: HashMap<String, String>) -> Self {
+        self.headers = Some(headers);
+        self
+    }
+
+    pub fn proxy(mut self, proxy: LaunchProxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    pub fn args(mut self, args: Vec<String>) -> Self {
+        self.args = Some(args);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn provider(mut self, provider: impl Into<String>) -> Self {
+        self.provider = Some(provider.into());
+        self
+    }
+
+    pub fn ignore_https_errors(mut self, ignore_https_errors: bool) -> Self {
+        self.ignore_https_errors = Some(ignore_https_errors);
+        self
+    }
+
+    pub fn extra_ca(mut self, extra_ca: Vec<String>) -> Self {
+        self.extra_ca = Some(extra_ca);
+        self
+    }
+
+    pub fn ignore_cert_errors_for(mut self, ignore_cert_errors_for: Vec<String>) -> Self {
+        self.ignore_cert_errors_for = Some(ignore_cert_errors_for);
+        self
+    }
+
+    pub fn client_certificates(
+        mut self,
+        client_certificates: Vec<LaunchClientCertificates>,
+    ) -> Self {
+        self.client_certificates = Some(client_certificates);
+        self
+    }
+
+    pub fn auth_server_allowlist(mut self, auth_server_allowlist: Vec<String>) -> Self {
+        self.auth_server_allowlist = Some(auth_server_allowlist);
+        self
+    }
+
+    pub fn auth_delegate_allowlist(mut self, auth_delegate_allowlist: Vec<String>) -> Self {
+        self.auth_delegate_allowlist = Some(auth_delegate_allowlist);
+        self
+    }
+
+    pub fn stealth(mut self, stealth: bool) -> Self {
+        self.stealth = Some(stealth);
+        self
+    }
+
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = Some(deterministic);
+        self
+    }
+
+    pub fn init_scripts(mut self, init_scripts: Vec<String>) -> Self {
+        self.init_scripts = Some(init_scripts);
+        self
+    }
+
+    pub fn remote(mut self, remote: impl Into<String>) -> Self {
+        self.remote = Some(remote.into());
+        self
+    }
+
+    pub fn remote_token(mut self, remote_token: impl Into<String>) -> Self {
+        self.remote_token = Some(remote_token.into());
+        self
+    }
+
+    pub fn remote_keep_alive(mut self, remote_keep_alive: i64) -> Self {
+        self.remote_keep_alive = Some(remote_keep_alive);
+        self
+    }
+
+    pub fn profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    pub fn storage_state(mut self, storage_state: impl Into<String>) -> Self {
+        self.storage_state = Some(storage_state.into());
+        self
+    }
+}
+
+impl Command for Launch {
+    const ACTION: &'static str = "launch";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NavigateWaitUntil {
+    #[serde(rename = "load")]
+    Load,
+    #[serde(rename = "domcontentloaded")]
+    Domcontentloaded,
+    #[serde(rename = "networkidle")]
+    Networkidle,
+}
+
+/// The `navigate` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Navigate {
+    pub url: String,
+    #[serde(rename = "waitUntil", skip_serializing_if = "Option::is_none")]
+    pub wait_until: Option<NavigateWaitUntil>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<HashMap<String, String>>,
+}
+
+impl Navigate {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            wait_until: None,
+            headers: None,
+        }
+    }
+
+    pub fn wait_until(mut self, wait_until: NavigateWaitUntil) -> Self {
+        self.wait_until = Some(wait_until);
+        self
+    }
+
+    pub fn headers(mut self, headers: HashMap<String, String>) -> Self {
+        self.headers = Some(headers);
+        self
+    }
+}
+
+impl Command for Navigate {
+    const ACTION: &'static str = "navigate";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClickButton {
+    #[serde(rename = "left")]
+    Left,
+    #[serde(rename = "right")]
+    Right,
+    #[serde(rename = "middle")]
+    Middle,
+}
+
+/// The `click` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Click {
+    pub selector: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub button: Option<ClickButton>,
+    #[serde(rename = "clickCount", skip_serializing_if = "Option::is_none")]
+    pub click_count: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay: Option<f64>,
+}
+
+impl Click {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+            button: None,
+            click_count: None,
+            delay: None,
+        }
+    }
+
+    pub fn button(mut self, button: ClickButton) -> Self {
+        self.button = Some(button);
+        self
+    }
+
+    pub fn click_count(mut self, click_count: f64) -> Self {
+        self.click_count = Some(click_count);
+        self
+    }
+
+    pub fn delay(mut self, delay: f64) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+impl Command for Click {
+    const ACTION: &'static str = "click";
+}
+
+/// The `type` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Type {
+    pub selector: String,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clear: Option<bool>,
+}
+
+impl Type {
+    pub fn new(selector: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+            text: text.into(),
+            delay: None,
+            clear: None,
+        }
+    }
+
+    pub fn delay(mut self, delay: f64) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    pub fn clear(mut self, clear: bool) -> Self {
+        self.clear = Some(clear);
+        self
+    }
+}
+
+impl Command for Type {
+    const ACTION: &'static str = "type";
+}
+
+/// The `fill` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fill {
+    pub selector: String,
+    pub value: String,
+}
+
+impl Fill {
+    pub fn new(selector: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+            value: value.into(),
+        }
+    }
+}
+
+impl Command for Fill {
+    const ACTION: &'static str = "fill";
+}
+
+/// The `check` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Check {
+    pub selector: String,
+}
+
+impl Check {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Check {
+    const ACTION: &'static str = "check";
+}
+
+/// The `uncheck` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Uncheck {
+    pub selector: String,
+}
+
+impl Uncheck {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Uncheck {
+    const ACTION: &'static str = "uncheck";
+}
+
+/// The `upload` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Upload {
+    pub selector: String,
+    pub files: Value,
+}
+
+impl Upload {
+    pub fn new(selector: impl Into<String>, files: Value) -> Self {
+        Self {
+            selector: selector.into(),
+            files,
+        }
+    }
+}
+
+impl Command for Upload {
+    const ACTION: &'static str = "upload";
+}
+
+/// The `dblclick` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dblclick {
+    pub selector: String,
+}
+
+impl Dblclick {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Dblclick {
+    const ACTION: &'static str = "dblclick";
+}
+
+/// The `focus` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Focus {
+    pub selector: String,
+}
+
+impl Focus {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Focus {
+    const ACTION: &'static str = "focus";
+}
+
+/// The `drag` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Drag {
+    pub source: String,
+    pub target: String,
+}
+
+impl Drag {
+    pub fn new(source: impl Into<String>, target: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            target: target.into(),
+        }
+    }
+}
+
+impl Command for Drag {
+    const ACTION: &'static str = "drag";
+}
+
+/// The `frame` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Frame {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl Frame {
+    pub fn new() -> Self {
+        Self {
+            selector: None,
+            name: None,
+            url: None,
+        }
+    }
+
+    pub fn selector(mut self, selector: impl Into<String>) -> Self {
+        self.selector = Some(selector.into());
+        self
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+impl Command for Frame {
+    const ACTION: &'static str = "frame";
+}
+
+/// The `mainframe` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Mainframe {}
+
+impl Mainframe {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for Mainframe {
+    const ACTION: &'static str = "mainframe";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GetbyroleSubaction {
+    #[serde(rename = "click")]
+    Click,
+    #[serde(rename = "fill")]
+    Fill,
+    #[serde(rename = "check")]
+    Check,
+    #[serde(rename = "hover")]
+    Hover,
+}
+
+/// The `getbyrole` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Getbyrole {
+    pub role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub subaction: GetbyroleSubaction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+impl Getbyrole {
+    pub fn new(role: impl Into<String>, subaction: GetbyroleSubaction) -> Self {
+        Self {
+            role: role.into(),
+            name: None,
+            subaction,
+            value: None,
+        }
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+}
+
+impl Command for Getbyrole {
+    const ACTION: &'static str = "getbyrole";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GetbytextSubaction {
+    #[serde(rename = "click")]
+    Click,
+    #[serde(rename = "hover")]
+    Hover,
+}
+
+/// The `getbytext` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Getbytext {
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exact: Option<bool>,
+    pub subaction: GetbytextSubaction,
+}
+
+impl Getbytext {
+    pub fn new(text: impl Into<String>, subaction: GetbytextSubaction) -> Self {
+        Self {
+            text: text.into(),
+            exact: None,
+            subaction,
+        }
+    }
+
+    pub fn exact(mut self, exact: bool) -> Self {
+        self.exact = Some(exact);
+        self
+    }
+}
+
+impl Command for Getbytext {
+    const ACTION: &'static str = "getbytext";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GetbylabelSubaction {
+    #[serde(rename = "click")]
+    Click,
+    #[serde(rename = "fill")]
+    Fill,
+    #[serde(rename = "check")]
+    Check,
+}
+
+/// The `getbylabel` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Getbylabel {
+    pub label: String,
+    pub subaction: GetbylabelSubaction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+impl Getbylabel {
+    pub fn new(label: impl Into<String>, subaction: GetbylabelSubaction) -> Self {
+        Self {
+            label: label.into(),
+            subaction,
+            value: None,
+        }
+    }
+
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+}
+
+impl Command for Getbylabel {
+    const ACTION: &'static str = "getbylabel";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GetbyplaceholderSubaction {
+    #[serde(rename = "click")]
+    Click,
+    #[serde(rename = "fill")]
+    Fill,
+}
+
+/// The `getbyplaceholder` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Getbyplaceholder {
+    pub placeholder: String,
+    pub subaction: GetbyplaceholderSubaction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+impl Getbyplaceholder {
+    pub fn new(placeholder: impl Into<String>, subaction: GetbyplaceholderSubaction) -> Self {
+        Self {
+            placeholder: placeholder.into(),
+            subaction,
+            value: None,
+        }
+    }
+
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+}
+
+impl Command for Getbyplaceholder {
+    const ACTION: &'static str = "getbyplaceholder";
+}
+
+/// The `press` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Press {
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+}
+
+impl Press {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            selector: None,
+        }
+    }
+
+    pub fn selector(mut self, selector: impl Into<String>) -> Self {
+        self.selector = Some(selector.into());
+        self
+    }
+}
+
+impl Command for Press {
+    const ACTION: &'static str = "press";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScreenshotFormat {
+    #[serde(rename = "png")]
+    Png,
+    #[serde(rename = "jpeg")]
+    Jpeg,
+}
+
+/// The `screenshot` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Screenshot {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(rename = "fullPage", skip_serializing_if = "Option::is_none")]
+    pub full_page: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ScreenshotFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mask: Option<Vec<String>>,
+}
+
+impl Screenshot {
+    pub fn new() -> Self {
+        Self {
+            path: None,
+            full_page: None,
+            selector: None,
+            format: None,
+            quality: None,
+            mask: None,
+        }
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn full_page(mut self, full_page: bool) -> Self {
+        self.full_page = Some(full_page);
+        self
+    }
+
+    pub fn selector(mut self, selector: impl Into<String>) -> Self {
+        self.selector = Some(selector.into());
+        self
+    }
+
+    pub fn format(mut self, format: ScreenshotFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn quality(mut self, quality: f64) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    pub fn mask(mut self, mask: Vec<String>) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+}
+
+impl Command for Screenshot {
+    const ACTION: &'static str = "screenshot";
+}
+
+/// The `snapshot` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interactive: Option<bool>,
+    #[serde(rename = "maxDepth", skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compact: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fresh: Option<bool>,
+}
+
+impl Snapshot {
+    pub fn new() -> Self {
+        Self {
+            interactive: None,
+            max_depth: None,
+            compact: None,
+            selector: None,
+            fresh: None,
+        }
+    }
+
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        self.interactive = Some(interactive);
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: f64) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = Some(compact);
+        self
+    }
+
+    pub fn selector(mut self, selector: impl Into<String>) -> Self {
+        self.selector = Some(selector.into());
+        self
+    }
+
+    pub fn fresh(mut self, fresh: bool) -> Self {
+        self.fresh = Some(fresh);
+        self
+    }
+}
+
+impl Command for Snapshot {
+    const ACTION: &'static str = "snapshot";
+}
+
+/// The `evaluate` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Evaluate {
+    pub script: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub args: Option<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+}
+
+impl Evaluate {
+    pub fn new(script: impl Into<String>) -> Self {
+        Self {
+            script: script.into(),
+            args: None,
+            target: None,
+        }
+    }
+
+    pub fn args(mut self, args: Vec<Value>) -> Self {
+        self.args = Some(args);
+        self
+    }
+
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+}
+
+impl Command for Evaluate {
+    const ACTION: &'static str = "evaluate";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WaitState {
+    #[serde(rename = "attached")]
+    Attached,
+    #[serde(rename = "detached")]
+    Detached,
+    #[serde(rename = "visible")]
+    Visible,
+    #[serde(rename = "hidden")]
+    Hidden,
+}
+
+/// The `wait` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Wait {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<WaitState>,
+}
+
+impl Wait {
+    pub fn new() -> Self {
+        Self {
+            selector: None,
+            timeout: None,
+            state: None,
+        }
+    }
+
+    pub fn selector(mut self, selector: impl Into<String>) -> Self {
+        self.selector = Some(selector.into());
+        self
+    }
+
+    pub fn timeout(mut self, timeout: f64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn state(mut self, state: WaitState) -> Self {
+        self.state = Some(state);
+        self
+    }
+}
+
+impl Command for Wait {
+    const ACTION: &'static str = "wait";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScrollDirection {
+    #[serde(rename = "up")]
+    Up,
+    #[serde(rename = "down")]
+    Down,
+    #[serde(rename = "left")]
+    Left,
+    #[serde(rename = "right")]
+    Right,
+}
+
+/// The `scroll` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Scroll {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<ScrollDirection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<f64>,
+}
+
+impl Scroll {
+    pub fn new() -> Self {
+        Self {
+            selector: None,
+            x: None,
+            y: None,
+            direction: None,
+            amount: None,
+        }
+    }
+
+    pub fn selector(mut self, selector: impl Into<String>) -> Self {
+        self.selector = Some(selector.into());
+        self
+    }
+
+    pub fn x(mut self, x: f64) -> Self {
+        self.x = Some(x);
+        self
+    }
+
+    pub fn y(mut self, y: f64) -> Self {
+        self.y = Some(y);
+        self
+    }
+
+    pub fn direction(mut self, direction: ScrollDirection) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    pub fn amount(mut self, amount: f64) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+}
+
+impl Command for Scroll {
+    const ACTION: &'static str = "scroll";
+}
+
+/// The `select` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Select {
+    pub selector: String,
+    pub values: Value,
+}
+
+impl Select {
+    pub fn new(selector: impl Into<String>, values: Value) -> Self {
+        Self {
+            selector: selector.into(),
+            values,
+        }
+    }
+}
+
+impl Command for Select {
+    const ACTION: &'static str = "select";
+}
+
+/// The `hover` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hover {
+    pub selector: String,
+}
+
+impl Hover {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Hover {
+    const ACTION: &'static str = "hover";
+}
+
+/// The `content` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Content {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+}
+
+impl Content {
+    pub fn new() -> Self {
+        Self { selector: None }
+    }
+
+    pub fn selector(mut self, selector: impl Into<String>) -> Self {
+        self.selector = Some(selector.into());
+        self
+    }
+}
+
+impl Command for Content {
+    const ACTION: &'static str = "content";
+}
+
+/// The `close` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Close {}
+
+impl Close {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for Close {
+    const ACTION: &'static str = "close";
+}
+
+/// The `tab_new` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TabNew {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl TabNew {
+    pub fn new() -> Self {
+        Self { url: None }
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+impl Command for TabNew {
+    const ACTION: &'static str = "tab_new";
+}
+
+/// The `tab_list` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TabList {}
+
+impl TabList {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for TabList {
+    const ACTION: &'static str = "tab_list";
+}
+
+/// The `targets` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Targets {}
+
+impl Targets {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for Targets {
+    const ACTION: &'static str = "targets";
+}
+
+/// The `robots` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Robots {
+    pub url: String,
+}
+
+impl Robots {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+}
+
+impl Command for Robots {
+    const ACTION: &'static str = "robots";
+}
+
+/// The `audit_perf` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditPerf {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl AuditPerf {
+    pub fn new() -> Self {
+        Self { url: None }
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+impl Command for AuditPerf {
+    const ACTION: &'static str = "audit_perf";
+}
+
+/// The `audit_seo` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditSeo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<i64>,
+}
+
+impl AuditSeo {
+    pub fn new() -> Self {
+        Self {
+            url: None,
+            links: None,
+        }
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn links(mut self, links: i64) -> Self {
+        self.links = Some(links);
+        self
+    }
+}
+
+impl Command for AuditSeo {
+    const ACTION: &'static str = "audit_seo";
+}
+
+/// The `audit_security` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditSecurity {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl AuditSecurity {
+    pub fn new() -> Self {
+        Self { url: None }
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+impl Command for AuditSecurity {
+    const ACTION: &'static str = "audit_security";
+}
+
+/// The `tls_info` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsInfo {
+    pub url: String,
+}
+
+impl TlsInfo {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+}
+
+impl Command for TlsInfo {
+    const ACTION: &'static str = "tls_info";
+}
+
+/// The `clock_set` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockSet {
+    pub time: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tick: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+}
+
+impl ClockSet {
+    pub fn new(time: impl Into<String>) -> Self {
+        Self {
+            time: time.into(),
+            tick: None,
+            timezone: None,
+        }
+    }
+
+    pub fn tick(mut self, tick: bool) -> Self {
+        self.tick = Some(tick);
+        self
+    }
+
+    pub fn timezone(mut self, timezone: impl Into<String>) -> Self {
+        self.timezone = Some(timezone.into());
+        self
+    }
+}
+
+impl Command for ClockSet {
+    const ACTION: &'static str = "clock_set";
+}
+
+/// The `clock_advance` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockAdvance {
+    pub ms: i64,
+}
+
+impl ClockAdvance {
+    pub fn new(ms: i64) -> Self {
+        Self { ms }
+    }
+}
+
+impl Command for ClockAdvance {
+    const ACTION: &'static str = "clock_advance";
+}
+
+/// The `clock_resume` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClockResume {}
+
+impl ClockResume {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for ClockResume {
+    const ACTION: &'static str = "clock_resume";
+}
+
+/// The `schedule_add` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleAdd {
+    pub cron: String,
+    pub command: Vec<String>,
+}
+
+impl ScheduleAdd {
+    pub fn new(cron: impl Into<String>, command: Vec<String>) -> Self {
+        Self {
+            cron: cron.into(),
+            command,
+        }
+    }
+}
+
+impl Command for ScheduleAdd {
+    const ACTION: &'static str = "schedule_add";
+}
+
+/// The `schedule_list` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleList {}
+
+impl ScheduleList {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for ScheduleList {
+    const ACTION: &'static str = "schedule_list";
+}
+
+/// The `schedule_remove` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleRemove {
+    #[serde(rename = "jobId")]
+    pub job_id: String,
+}
+
+impl ScheduleRemove {
+    pub fn new(job_id: impl Into<String>) -> Self {
+        Self {
+            job_id: job_id.into(),
+        }
+    }
+}
+
+impl Command for ScheduleRemove {
+    const ACTION: &'static str = "schedule_remove";
+}
+
+/// The `schedule_logs` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleLogs {
+    #[serde(rename = "jobId")]
+    pub job_id: String,
+}
+
+impl ScheduleLogs {
+    pub fn new(job_id: impl Into<String>) -> Self {
+        Self {
+            job_id: job_id.into(),
+        }
+    }
+}
+
+impl Command for ScheduleLogs {
+    const ACTION: &'static str = "schedule_logs";
+}
+
+/// The `jobs` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Jobs {}
+
+impl Jobs {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for Jobs {
+    const ACTION: &'static str = "jobs";
+}
+
+/// The `which` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Which {
+    #[serde(rename = "executablePath", skip_serializing_if = "Option::is_none")]
+    pub executable_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+}
+
+impl Which {
+    pub fn new() -> Self {
+        Self {
+            executable_path: None,
+            channel: None,
+        }
+    }
+
+    pub fn executable_path(mut self, executable_path: impl Into<String>) -> Self {
+        self.executable_path = Some(executable_path.into());
+        self
+    }
+
+    pub fn channel(mut self, channel: impl Into<String>) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+}
+
+impl Command for Which {
+    const ACTION: &'static str = "which";
+}
+
+/// The `cancel` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cancel {}
+
+impl Cancel {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for Cancel {
+    const ACTION: &'static str = "cancel";
+}
+
+/// The `lanes` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Lanes {}
+
+impl Lanes {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for Lanes {
+    const ACTION: &'static str = "lanes";
+}
+
+/// The `tab_switch` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TabSwitch {
+    pub index: f64,
+}
+
+impl TabSwitch {
+    pub fn new(index: f64) -> Self {
+        Self { index }
+    }
+}
+
+impl Command for TabSwitch {
+    const ACTION: &'static str = "tab_switch";
+}
+
+/// The `tab_close` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TabClose {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<f64>,
+}
+
+impl TabClose {
+    pub fn new() -> Self {
+        Self { index: None }
+    }
+
+    pub fn index(mut self, index: f64) -> Self {
+        self.index = Some(index);
+        self
+    }
+}
+
+impl Command for TabClose {
+    const ACTION: &'static str = "tab_close";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionOptionsPopupPolicy {
+    #[serde(rename = "follow")]
+    Follow,
+    #[serde(rename = "block")]
+    Block,
+    #[serde(rename = "list")]
+    List,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardrailRule {
+    pub name: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub methods: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub navigation: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purchase: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    pub count: i64,
+    pub per: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionOptionsRateLimits {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests: Option<Vec<RateRule>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub navigations: Option<Vec<RateRule>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionOptionsLanesActionRate {
+    pub count: i64,
+    pub per: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionOptionsLanes {
+    #[serde(rename = "maxConcurrent", skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<i64>,
+    #[serde(rename = "actionRate", skip_serializing_if = "Option::is_none")]
+    pub action_rate: Option<SessionOptionsLanesActionRate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionOptionsTabMemoryMode {
+    #[serde(rename = "reload")]
+    Reload,
+    #[serde(rename = "recreate")]
+    Recreate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionOptionsTabMemory {
+    pub limit: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<SessionOptionsTabMemoryMode>,
+}
+
+/// The `session_options` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionOptions {
+    #[serde(rename = "popupPolicy", skip_serializing_if = "Option::is_none")]
+    pub popup_policy: Option<SessionOptionsPopupPolicy>,
+    #[serde(
+        rename = "autoDismissOverlays",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub auto_dismiss_overlays: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adblock: Option<bool>,
+    #[serde(rename = "respectRobots", skip_serializing_if = "Option::is_none")]
+    pub respect_robots: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<bool>,
+    #[serde(rename = "auditLog", skip_serializing_if = "Option::is_none")]
+    pub audit_log: Option<bool>,
+    #[serde(rename = "readOnly", skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guardrails: Option<Vec<GuardrailRule>>,
+    #[serde(rename = "rateLimits", skip_serializing_if = "Option::is_none")]
+    pub rate_limits: Option<SessionOptionsRateLimits>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lanes: Option<SessionOptionsLanes>,
+    #[serde(rename = "tabMemory", skip_serializing_if = "Option::is_none")]
+    pub tab_memory: Option<SessionOptionsTabMemory>,
+}
+
+impl SessionOptions {
+    pub fn new() -> Self {
+        Self {
+            popup_policy: None,
+            auto_dismiss_overlays: None,
+            adblock: None,
+            respect_robots: None,
+            transcript: None,
+            audit_log: None,
+            read_only: None,
+            guardrails: None,
+            rate_limits: None,
+            lanes: None,
+            tab_memory: None,
+        }
+    }
+
+    pub fn popup_policy(mut self, popup_policy: SessionOptionsPopupPolicy) -> Self {
+        self.popup_policy = Some(popup_policy);
+        self
+    }
+
+    pub fn auto_dismiss_overlays(mut self, auto_dismiss_overlays: bool) -> Self {
+        self.auto_dismiss_overlays = Some(auto_dismiss_overlays);
+        self
+    }
+
+    pub fn adblock(mut self, adblock: bool) -> Self {
+        self.adblock = Some(adblock);
+        self
+    }
+
+    pub fn respect_robots(mut self, respect_robots: bool) -> Self {
+        self.respect_robots = Some(respect_robots);
+        self
+    }
+
+    pub fn transcript(mut self, transcript: bool) -> Self {
+        self.transcript = Some(transcript);
+        self
+    }
+
+    pub fn audit_log(mut self, audit_log: bool) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = Some(read_only);
+        self
+    }
+
+    pub fn guardrails(mut self, guardrails: Vec<GuardrailRule>) -> Self {
+        self.guardrails = Some(guardrails);
+        self
+    }
+
+    pub fn rate_limits(mut self, rate_limits: SessionOptionsRateLimits) -> Self {
+        self.rate_limits = Some(rate_limits);
+        self
+    }
+
+    pub fn lanes(mut self, lanes: SessionOptionsLanes) -> Self {
+        self.lanes = Some(lanes);
+        self
+    }
+
+    pub fn tab_memory(mut self, tab_memory: SessionOptionsTabMemory) -> Self {
+        self.tab_memory = Some(tab_memory);
+        self
+    }
+}
+
+impl Command for SessionOptions {
+    const ACTION: &'static str = "session_options";
+}
+
+/// The `dismiss_overlays` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DismissOverlays {}
+
+impl DismissOverlays {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for DismissOverlays {
+    const ACTION: &'static str = "dismiss_overlays";
+}
+
+/// The `stats` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stats {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reset: Option<bool>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self { reset: None }
+    }
+
+    pub fn reset(mut self, reset: bool) -> Self {
+        self.reset = Some(reset);
+        self
+    }
+}
+
+impl Command for Stats {
+    const ACTION: &'static str = "stats";
+}
+
+/// The `memory` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Memory {}
+
+impl Memory {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for Memory {
+    const ACTION: &'static str = "memory";
+}
+
+/// The `cdp` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cdp {
+    pub method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<HashMap<String, Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait: Option<i64>,
+}
+
+impl Cdp {
+    pub fn new(method: impl Into<String>) -> Self {
+        Self {
+            method: method.into(),
+            params: None,
+            events: None,
+            wait: None,
+        }
+    }
+
+    pub fn params(mut self, params: HashMap<String, Value>) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    pub fn events(mut self, events: Vec<String>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub fn wait(mut self, wait: i64) -> Self {
+        self.wait = Some(wait);
+        self
+    }
+}
+
+impl Command for Cdp {
+    const ACTION: &'static str = "cdp";
+}
+
+/// The `cdp_listen` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CdpListen {
+    pub events: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<i64>,
+}
+
+impl CdpListen {
+    pub fn new(events: Vec<String>) -> Self {
+        Self {
+            events,
+            count: None,
+            timeout: None,
+        }
+    }
+
+    pub fn count(mut self, count: i64) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: i64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl Command for CdpListen {
+    const ACTION: &'static str = "cdp_listen";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WatchEvents {
+    #[serde(rename = "childList")]
+    ChildList,
+    #[serde(rename = "attributes")]
+    Attributes,
+    #[serde(rename = "characterData")]
+    CharacterData,
+}
+
+/// The `watch` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Watch {
+    pub selector: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<WatchEvents>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<i64>,
+}
+
+impl Watch {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+            events: None,
+            count: None,
+            timeout: None,
+        }
+    }
+
+    pub fn events(mut self, events: Vec<WatchEvents>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub fn count(mut self, count: i64) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: i64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl Command for Watch {
+    const ACTION: &'static str = "watch";
+}
+
+/// The `poll` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Poll {
+    pub selector: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attribute: Option<String>,
+    pub interval: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reload: Option<bool>,
+    #[serde(rename = "untilChanged", skip_serializing_if = "Option::is_none")]
+    pub until_changed: Option<bool>,
+    #[serde(rename = "untilMatch", skip_serializing_if = "Option::is_none")]
+    pub until_match: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<i64>,
+}
+
+impl Poll {
+    pub fn new(selector: impl Into<String>, interval: i64) -> Self {
+        Self {
+            selector: selector.into(),
+            attribute: None,
+            interval,
+            reload: None,
+            until_changed: None,
+            until_match: None,
+            max: None,
+        }
+    }
+
+    pub fn attribute(mut self, attribute: impl Into<String>) -> Self {
+        self.attribute = Some(attribute.into());
+        self
+    }
+
+    pub fn reload(mut self, reload: bool) -> Self {
+        self.reload = Some(reload);
+        self
+    }
+
+    pub fn until_changed(mut self, until_changed: bool) -> Self {
+        self.until_changed = Some(until_changed);
+        self
+    }
+
+    pub fn until_match(mut self, until_match: impl Into<String>) -> Self {
+        self.until_match = Some(until_match.into());
+        self
+    }
+
+    pub fn max(mut self, max: i64) -> Self {
+        self.max = Some(max);
+        self
+    }
+}
+
+impl Command for Poll {
+    const ACTION: &'static str = "poll";
+}
+
+/// The `trace_step` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TraceStep {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reset: Option<bool>,
+}
+
+impl TraceStep {
+    pub fn new() -> Self {
+        Self { reset: None }
+    }
+
+    pub fn reset(mut self, reset: bool) -> Self {
+        self.reset = Some(reset);
+        self
+    }
+}
+
+impl Command for TraceStep {
+    const ACTION: &'static str = "trace_step";
+}
+
+/// The `capture_step` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CaptureStep {}
+
+impl CaptureStep {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for CaptureStep {
+    const ACTION: &'static str = "capture_step";
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowNewViewport {
+    pub width: f64,
+    pub height: f64,
+}
+
+/// The `window_new` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WindowNew {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub viewport: Option<WindowNewViewport>,
+}
+
+impl WindowNew {
+    pub fn new() -> Self {
+        Self { viewport: None }
+    }
+
+    pub fn viewport(mut self, viewport: WindowNewViewport) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+}
+
+impl Command for WindowNew {
+    const ACTION: &'static str = "window_new";
+}
+
+/// The `cookies_get` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CookiesGet {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub urls: Option<Vec<String>>,
+}
+
+impl CookiesGet {
+    pub fn new() -> Self {
+        Self { urls: None }
+    }
+
+    pub fn urls(mut self, urls: Vec<String>) -> Self {
+        self.urls = Some(urls);
+        self
+    }
+}
+
+impl Command for CookiesGet {
+    const ACTION: &'static str = "cookies_get";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CookiesSetCookiesSameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CookiesSetCookies {
+    pub name: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires: Option<f64>,
+    #[serde(rename = "httpOnly", skip_serializing_if = "Option::is_none")]
+    pub http_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secure: Option<bool>,
+    #[serde(rename = "sameSite", skip_serializing_if = "Option::is_none")]
+    pub same_site: Option<CookiesSetCookiesSameSite>,
+}
+
+/// The `cookies_set` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CookiesSet {
+    pub cookies: Vec<CookiesSetCookies>,
+}
+
+impl CookiesSet {
+    pub fn new(cookies: Vec<CookiesSetCookies>) -> Self {
+        Self { cookies }
+    }
+}
+
+impl Command for CookiesSet {
+    const ACTION: &'static str = "cookies_set";
+}
+
+/// The `cookies_clear` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CookiesClear {}
+
+impl CookiesClear {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for CookiesClear {
+    const ACTION: &'static str = "cookies_clear";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StorageGetType {
+    #[serde(rename = "local")]
+    Local,
+    #[serde(rename = "session")]
+    Session,
+}
+
+/// The `storage_get` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageGet {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub r#type: StorageGetType,
+}
+
+impl StorageGet {
+    pub fn new(r#type: StorageGetType) -> Self {
+        Self { key: None, r#type }
+    }
+
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+}
+
+impl Command for StorageGet {
+    const ACTION: &'static str = "storage_get";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StorageSetType {
+    #[serde(rename = "local")]
+    Local,
+    #[serde(rename = "session")]
+    Session,
+}
+
+/// The `storage_set` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageSet {
+    pub key: String,
+    pub value: String,
+    pub r#type: StorageSetType,
+}
+
+impl StorageSet {
+    pub fn new(key: impl Into<String>, value: impl Into<String>, r#type: StorageSetType) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+            r#type,
+        }
+    }
+}
+
+impl Command for StorageSet {
+    const ACTION: &'static str = "storage_set";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StorageClearType {
+    #[serde(rename = "local")]
+    Local,
+    #[serde(rename = "session")]
+    Session,
+}
+
+/// The `storage_clear` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageClear {
+    pub r#type: StorageClearType,
+}
+
+impl StorageClear {
+    pub fn new(r#type: StorageClearType) -> Self {
+        Self { r#type }
+    }
+}
+
+impl Command for StorageClear {
+    const ACTION: &'static str = "storage_clear";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DialogResponse {
+    #[serde(rename = "accept")]
+    Accept,
+    #[serde(rename = "dismiss")]
+    Dismiss,
+}
+
+/// The `dialog` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dialog {
+    pub response: DialogResponse,
+    #[serde(rename = "promptText", skip_serializing_if = "Option::is_none")]
+    pub prompt_text: Option<String>,
+}
+
+impl Dialog {
+    pub fn new(response: DialogResponse) -> Self {
+        Self {
+            response,
+            prompt_text: None,
+        }
+    }
+
+    pub fn prompt_text(mut self, prompt_text: impl Into<String>) -> Self {
+        self.prompt_text = Some(prompt_text.into());
+        self
+    }
+}
+
+impl Command for Dialog {
+    const ACTION: &'static str = "dialog";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PdfFormat {
+    Letter,
+    Legal,
+    Tabloid,
+    Ledger,
+    A0,
+    A1,
+    A2,
+    A3,
+    A4,
+    A5,
+    A6,
+}
+
+/// The `pdf` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Pdf {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<PdfFormat>,
+}
+
+impl Pdf {
+    pub fn new() -> Self {
+        Self {
+            path: None,
+            format: None,
+        }
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn format(mut self, format: PdfFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+}
+
+impl Command for Pdf {
+    const ACTION: &'static str = "pdf";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArchiveFormat {
+    #[serde(rename = "mhtml")]
+    Mhtml,
+    #[serde(rename = "warc")]
+    Warc,
+}
+
+/// The `archive` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Archive {
+    pub format: ArchiveFormat,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl Archive {
+    pub fn new(format: ArchiveFormat) -> Self {
+        Self { format, path: None }
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl Command for Archive {
+    const ACTION: &'static str = "archive";
+}
+
+/// The `freeze` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Freeze {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl Freeze {
+    pub fn new() -> Self {
+        Self { path: None }
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl Command for Freeze {
+    const ACTION: &'static str = "freeze";
+}
+
+/// The `selectors_suggest` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectorsSuggest {
+    pub selector: String,
+}
+
+impl SelectorsSuggest {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for SelectorsSuggest {
+    const ACTION: &'static str = "selectors_suggest";
+}
+
+/// The `selectors_heal` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectorsHeal {
+    pub selector: String,
+}
+
+impl SelectorsHeal {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for SelectorsHeal {
+    const ACTION: &'static str = "selectors_heal";
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RouteResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(rename = "contentType", skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<HashMap<String, String>>,
+}
+
+/// The `route` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Route {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<RouteResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abort: Option<bool>,
+}
+
+impl Route {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            response: None,
+            abort: None,
+        }
+    }
+
+    pub fn response(mut self, response: RouteResponse) -> Self {
+        self.response = Some(response);
+        self
+    }
+
+    pub fn abort(mut self, abort: bool) -> Self {
+        self.abort = Some(abort);
+        self
+    }
+}
+
+impl Command for Route {
+    const ACTION: &'static str = "route";
+}
+
+/// The `unroute` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Unroute {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl Unroute {
+    pub fn new() -> Self {
+        Self { url: None }
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+impl Command for Unroute {
+    const ACTION: &'static str = "unroute";
+}
+
+/// The `requests` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Requests {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clear: Option<bool>,
+}
+
+impl Requests {
+    pub fn new() -> Self {
+        Self {
+            filter: None,
+            clear: None,
+        }
+    }
+
+    pub fn filter(mut self, filter: impl Into<String>) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    pub fn clear(mut self, clear: bool) -> Self {
+        self.clear = Some(clear);
+        self
+    }
+}
+
+impl Command for Requests {
+    const ACTION: &'static str = "requests";
+}
+
+/// The `download` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Download {
+    pub selector: String,
+    pub path: String,
+}
+
+impl Download {
+    pub fn new(selector: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+            path: path.into(),
+        }
+    }
+}
+
+impl Command for Download {
+    const ACTION: &'static str = "download";
+}
+
+/// The `geolocation` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Geolocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accuracy: Option<f64>,
+}
+
+impl Geolocation {
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+            accuracy: None,
+        }
+    }
+
+    pub fn accuracy(mut self, accuracy: f64) -> Self {
+        self.accuracy = Some(accuracy);
+        self
+    }
+}
+
+impl Command for Geolocation {
+    const ACTION: &'static str = "geolocation";
+}
+
+/// The `permissions` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Permissions {
+    pub permissions: Vec<String>,
+    pub grant: bool,
+}
+
+impl Permissions {
+    pub fn new(permissions: Vec<String>, grant: bool) -> Self {
+        Self { permissions, grant }
+    }
+}
+
+impl Command for Permissions {
+    const ACTION: &'static str = "permissions";
+}
+
+/// The `viewport` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Viewport {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Viewport {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self { width, height }
+    }
+}
+
+impl Command for Viewport {
+    const ACTION: &'static str = "viewport";
+}
+
+/// The `useragent` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Useragent {
+    #[serde(rename = "userAgent")]
+    pub user_agent: String,
+}
+
+impl Useragent {
+    pub fn new(user_agent: impl Into<String>) -> Self {
+        Self {
+            user_agent: user_agent.into(),
+        }
+    }
+}
+
+impl Command for Useragent {
+    const ACTION: &'static str = "useragent";
+}
+
+/// The `device` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Device {
+    pub device: String,
+}
+
+impl Device {
+    pub fn new(device: impl Into<String>) -> Self {
+        Self {
+            device: device.into(),
+        }
+    }
+}
+
+impl Command for Device {
+    const ACTION: &'static str = "device";
+}
+
+/// The `back` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Back {}
+
+impl Back {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for Back {
+    const ACTION: &'static str = "back";
+}
+
+/// The `forward` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Forward {}
+
+impl Forward {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for Forward {
+    const ACTION: &'static str = "forward";
+}
+
+/// The `reload` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Reload {}
+
+impl Reload {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for Reload {
+    const ACTION: &'static str = "reload";
+}
+
+/// The `url` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Url {}
+
+impl Url {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for Url {
+    const ACTION: &'static str = "url";
+}
+
+/// The `title` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Title {}
+
+impl Title {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for Title {
+    const ACTION: &'static str = "title";
+}
+
+/// The `getattribute` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Getattribute {
+    pub selector: String,
+    pub attribute: String,
+}
+
+impl Getattribute {
+    pub fn new(selector: impl Into<String>, attribute: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+            attribute: attribute.into(),
+        }
+    }
+}
+
+impl Command for Getattribute {
+    const ACTION: &'static str = "getattribute";
+}
+
+/// The `gettext` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Gettext {
+    pub selector: String,
+}
+
+impl Gettext {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Gettext {
+    const ACTION: &'static str = "gettext";
+}
+
+/// The `isvisible` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Isvisible {
+    pub selector: String,
+}
+
+impl Isvisible {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Isvisible {
+    const ACTION: &'static str = "isvisible";
+}
+
+/// The `isenabled` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Isenabled {
+    pub selector: String,
+}
+
+impl Isenabled {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Isenabled {
+    const ACTION: &'static str = "isenabled";
+}
+
+/// The `ischecked` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ischecked {
+    pub selector: String,
+}
+
+impl Ischecked {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Ischecked {
+    const ACTION: &'static str = "ischecked";
+}
+
+/// The `count` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Count {
+    pub selector: String,
+}
+
+impl Count {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Count {
+    const ACTION: &'static str = "count";
+}
+
+/// The `boundingbox` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Boundingbox {
+    pub selector: String,
+}
+
+impl Boundingbox {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Boundingbox {
+    const ACTION: &'static str = "boundingbox";
+}
+
+/// The `styles` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Styles {
+    pub selector: String,
+}
+
+impl Styles {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Styles {
+    const ACTION: &'static str = "styles";
+}
+
+/// The `video_start` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoStart {
+    pub path: String,
+}
+
+impl VideoStart {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Command for VideoStart {
+    const ACTION: &'static str = "video_start";
+}
+
+/// The `video_stop` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VideoStop {}
+
+impl VideoStop {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for VideoStop {
+    const ACTION: &'static str = "video_stop";
+}
+
+/// The `recording_start` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingStart {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl RecordingStart {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            url: None,
+        }
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+impl Command for RecordingStart {
+    const ACTION: &'static str = "recording_start";
+}
+
+/// The `recording_stop` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecordingStop {}
+
+impl RecordingStop {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for RecordingStop {
+    const ACTION: &'static str = "recording_stop";
+}
+
+/// The `recording_restart` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingRestart {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl RecordingRestart {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            url: None,
+        }
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+impl Command for RecordingRestart {
+    const ACTION: &'static str = "recording_restart";
+}
+
+/// The `trace_start` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TraceStart {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub screenshots: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshots: Option<bool>,
+}
+
+impl TraceStart {
+    pub fn new() -> Self {
+        Self {
+            screenshots: None,
+            snapshots: None,
+        }
+    }
+
+    pub fn screenshots(mut self, screenshots: bool) -> Self {
+        self.screenshots = Some(screenshots);
+        self
+    }
+
+    pub fn snapshots(mut self, snapshots: bool) -> Self {
+        self.snapshots = Some(snapshots);
+        self
+    }
+}
+
+impl Command for TraceStart {
+    const ACTION: &'static str = "trace_start";
+}
+
+/// The `trace_stop` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TraceStop {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl TraceStop {
+    pub fn new() -> Self {
+        Self { path: None }
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl Command for TraceStop {
+    const ACTION: &'static str = "trace_stop";
+}
+
+/// The `capture_start` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureStart {
+    pub url: String,
+    pub out: String,
+    #[serde(rename = "maxSize", skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime: Option<Vec<String>>,
+}
+
+impl CaptureStart {
+    pub fn new(url: impl Into<String>, out: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            out: out.into(),
+            max_size: None,
+            mime: None,
+        }
+    }
+
+    pub fn max_size(mut self, max_size: i64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    pub fn mime(mut self, mime: Vec<String>) -> Self {
+        self.mime = Some(mime);
+        self
+    }
+}
+
+impl Command for CaptureStart {
+    const ACTION: &'static str = "capture_start";
+}
+
+/// The `capture_stop` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CaptureStop {}
+
+impl CaptureStop {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for CaptureStop {
+    const ACTION: &'static str = "capture_stop";
+}
+
+/// The `apis` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Apis {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clear: Option<bool>,
+}
+
+impl Apis {
+    pub fn new() -> Self {
+        Self {
+            filter: None,
+            clear: None,
+        }
+    }
+
+    pub fn filter(mut self, filter: impl Into<String>) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    pub fn clear(mut self, clear: bool) -> Self {
+        self.clear = Some(clear);
+        self
+    }
+}
+
+impl Command for Apis {
+    const ACTION: &'static str = "apis";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputOp {
+    #[serde(rename = "record")]
+    Record,
+    #[serde(rename = "stop")]
+    Stop,
+    #[serde(rename = "replay")]
+    Replay,
+}
+
+/// The `input` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Input {
+    pub op: InputOp,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f64>,
+}
+
+impl Input {
+    pub fn new(op: InputOp) -> Self {
+        Self {
+            op,
+            path: None,
+            speed: None,
+        }
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn speed(mut self, speed: f64) -> Self {
+        self.speed = Some(speed);
+        self
+    }
+}
+
+impl Command for Input {
+    const ACTION: &'static str = "input";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WsOp {
+    #[serde(rename = "list")]
+    List,
+    #[serde(rename = "tail")]
+    Tail,
+    #[serde(rename = "export")]
+    Export,
+    #[serde(rename = "clear")]
+    Clear,
+}
+
+/// The `ws` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ws {
+    pub op: WsOp,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub socket: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl Ws {
+    pub fn new(op: WsOp) -> Self {
+        Self {
+            op,
+            socket: None,
+            count: None,
+            path: None,
+        }
+    }
+
+    pub fn socket(mut self, socket: impl Into<String>) -> Self {
+        self.socket = Some(socket.into());
+        self
+    }
+
+    pub fn count(mut self, count: i64) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl Command for Ws {
+    const ACTION: &'static str = "ws";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HarReplayNotFound {
+    #[serde(rename = "abort")]
+    Abort,
+    #[serde(rename = "fallback")]
+    Fallback,
+}
+
+/// The `har_replay` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarReplay {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(rename = "notFound", skip_serializing_if = "Option::is_none")]
+    pub not_found: Option<HarReplayNotFound>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#match: Option<String>,
+}
+
+impl HarReplay {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            url: None,
+            not_found: None,
+            r#match: None,
+        }
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn not_found(mut self, not_found: HarReplayNotFound) -> Self {
+        self.not_found = Some(not_found);
+        self
+    }
+
+    pub fn r#match(mut self, r#match: impl Into<String>) -> Self {
+        self.r#match = Some(r#match.into());
+        self
+    }
+}
+
+impl Command for HarReplay {
+    const ACTION: &'static str = "har_replay";
+}
+
+/// The `har_start` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HarStart {}
+
+impl HarStart {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for HarStart {
+    const ACTION: &'static str = "har_start";
+}
+
+/// The `har_stop` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarStop {
+    pub path: String,
+}
+
+impl HarStop {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Command for HarStop {
+    const ACTION: &'static str = "har_stop";
+}
+
+/// The `state_save` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateSave {
+    pub path: String,
+}
+
+impl StateSave {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Command for StateSave {
+    const ACTION: &'static str = "state_save";
+}
+
+/// The `state_load` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateLoad {
+    pub path: String,
+}
+
+impl StateLoad {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Command for StateLoad {
+    const ACTION: &'static str = "state_load";
+}
+
+/// The `session_save` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSave {
+    pub path: String,
+}
+
+impl SessionSave {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Command for SessionSave {
+    const ACTION: &'static str = "session_save";
+}
+
+/// The `session_restore` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRestore {
+    pub path: String,
+}
+
+impl SessionRestore {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Command for SessionRestore {
+    const ACTION: &'static str = "session_restore";
+}
+
+/// The `console` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Console {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clear: Option<bool>,
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Self { clear: None }
+    }
+
+    pub fn clear(mut self, clear: bool) -> Self {
+        self.clear = Some(clear);
+        self
+    }
+}
+
+impl Command for Console {
+    const ACTION: &'static str = "console";
+}
+
+/// The `errors` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Errors {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clear: Option<bool>,
+}
+
+impl Errors {
+    pub fn new() -> Self {
+        Self { clear: None }
+    }
+
+    pub fn clear(mut self, clear: bool) -> Self {
+        self.clear = Some(clear);
+        self
+    }
+}
+
+impl Command for Errors {
+    const ACTION: &'static str = "errors";
+}
+
+/// The `keyboard` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keyboard {
+    pub keys: String,
+}
+
+impl Keyboard {
+    pub fn new(keys: impl Into<String>) -> Self {
+        Self { keys: keys.into() }
+    }
+}
+
+impl Command for Keyboard {
+    const ACTION: &'static str = "keyboard";
+}
+
+/// The `wheel` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Wheel {
+    #[serde(rename = "deltaX", skip_serializing_if = "Option::is_none")]
+    pub delta_x: Option<f64>,
+    #[serde(rename = "deltaY", skip_serializing_if = "Option::is_none")]
+    pub delta_y: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+}
+
+impl Wheel {
+    pub fn new() -> Self {
+        Self {
+            delta_x: None,
+            delta_y: None,
+            selector: None,
+        }
+    }
+
+    pub fn delta_x(mut self, delta_x: f64) -> Self {
+        self.delta_x = Some(delta_x);
+        self
+    }
+
+    pub fn delta_y(mut self, delta_y: f64) -> Self {
+        self.delta_y = Some(delta_y);
+        self
+    }
+
+    pub fn selector(mut self, selector: impl Into<String>) -> Self {
+        self.selector = Some(selector.into());
+        self
+    }
+}
+
+impl Command for Wheel {
+    const ACTION: &'static str = "wheel";
+}
+
+/// The `tap` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tap {
+    pub selector: String,
+}
+
+impl Tap {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Tap {
+    const ACTION: &'static str = "tap";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClipboardOperation {
+    #[serde(rename = "copy")]
+    Copy,
+    #[serde(rename = "paste")]
+    Paste,
+    #[serde(rename = "read")]
+    Read,
+}
+
+/// The `clipboard` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Clipboard {
+    pub operation: ClipboardOperation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl Clipboard {
+    pub fn new(operation: ClipboardOperation) -> Self {
+        Self {
+            operation,
+            text: None,
+        }
+    }
+
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+}
+
+impl Command for Clipboard {
+    const ACTION: &'static str = "clipboard";
+}
+
+/// The `highlight` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Highlight {
+    pub selector: String,
+}
+
+impl Highlight {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Highlight {
+    const ACTION: &'static str = "highlight";
+}
+
+/// The `inspect` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Inspect {
+    pub selector: String,
+}
+
+impl Inspect {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Inspect {
+    const ACTION: &'static str = "inspect";
+}
+
+/// The `clear` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Clear {
+    pub selector: String,
+}
+
+impl Clear {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Clear {
+    const ACTION: &'static str = "clear";
+}
+
+/// The `selectall` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Selectall {
+    pub selector: String,
+}
+
+impl Selectall {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Selectall {
+    const ACTION: &'static str = "selectall";
+}
+
+/// The `innertext` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Innertext {
+    pub selector: String,
+}
+
+impl Innertext {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Innertext {
+    const ACTION: &'static str = "innertext";
+}
+
+/// The `innerhtml` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Innerhtml {
+    pub selector: String,
+}
+
+impl Innerhtml {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Innerhtml {
+    const ACTION: &'static str = "innerhtml";
+}
+
+/// The `inputvalue` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Inputvalue {
+    pub selector: String,
+}
+
+impl Inputvalue {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Inputvalue {
+    const ACTION: &'static str = "inputvalue";
+}
+
+/// The `setvalue` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Setvalue {
+    pub selector: String,
+    pub value: String,
+}
+
+impl Setvalue {
+    pub fn new(selector: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+            value: value.into(),
+        }
+    }
+}
+
+impl Command for Setvalue {
+    const ACTION: &'static str = "setvalue";
+}
+
+/// The `dispatch` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dispatch {
+    pub selector: String,
+    pub event: String,
+    #[serde(rename = "eventInit", skip_serializing_if = "Option::is_none")]
+    pub event_init: Option<HashMap<String, Value>>,
+}
+
+impl Dispatch {
+    pub fn new(selector: impl Into<String>, event: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+            event: event.into(),
+            event_init: None,
+        }
+    }
+
+    pub fn event_init(mut self, event_init: HashMap<String, Value>) -> Self {
+        self.event_init = Some(event_init);
+        self
+    }
+}
+
+impl Command for Dispatch {
+    const ACTION: &'static str = "dispatch";
+}
+
+/// The `evalhandle` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Evalhandle {
+    pub script: String,
+}
+
+impl Evalhandle {
+    pub fn new(script: impl Into<String>) -> Self {
+        Self {
+            script: script.into(),
+        }
+    }
+}
+
+impl Command for Evalhandle {
+    const ACTION: &'static str = "evalhandle";
+}
+
+/// The `expose` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Expose {
+    pub name: String,
+}
+
+impl Expose {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+impl Command for Expose {
+    const ACTION: &'static str = "expose";
+}
+
+/// The `addscript` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Addscript {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl Addscript {
+    pub fn new() -> Self {
+        Self {
+            content: None,
+            url: None,
+        }
+    }
+
+    pub fn content(mut self, content: impl Into<String>) -> Self {
+        self.content = Some(content.into());
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+impl Command for Addscript {
+    const ACTION: &'static str = "addscript";
+}
+
+/// The `addstyle` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Addstyle {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl Addstyle {
+    pub fn new() -> Self {
+        Self {
+            content: None,
+            url: None,
+        }
+    }
+
+    pub fn content(mut self, content: impl Into<String>) -> Self {
+        self.content = Some(content.into());
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+impl Command for Addstyle {
+    const ACTION: &'static str = "addstyle";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EmulatemediaMedia {
+    #[serde(rename = "screen")]
+    Screen,
+    #[serde(rename = "print")]
+    Print,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EmulatemediaColorScheme {
+    #[serde(rename = "light")]
+    Light,
+    #[serde(rename = "dark")]
+    Dark,
+    #[serde(rename = "no-preference")]
+    NoPreference,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EmulatemediaReducedMotion {
+    #[serde(rename = "reduce")]
+    Reduce,
+    #[serde(rename = "no-preference")]
+    NoPreference,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EmulatemediaForcedColors {
+    #[serde(rename = "active")]
+    Active,
+    #[serde(rename = "none")]
+    None,
+}
+
+/// The `emulatemedia` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Emulatemedia {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media: Option<EmulatemediaMedia>,
+    #[serde(rename = "colorScheme", skip_serializing_if = "Option::is_none")]
+    pub color_scheme: Option<EmulatemediaColorScheme>,
+    #[serde(rename = "reducedMotion", skip_serializing_if = "Option::is_none")]
+    pub reduced_motion: Option<EmulatemediaReducedMotion>,
+    #[serde(rename = "forcedColors", skip_serializing_if = "Option::is_none")]
+    pub forced_colors: Option<EmulatemediaForcedColors>,
+}
+
+impl Emulatemedia {
+    pub fn new() -> Self {
+        Self {
+            media: None,
+            color_scheme: None,
+            reduced_motion: None,
+            forced_colors: None,
+        }
+    }
+
+    pub fn media(mut self, media: EmulatemediaMedia) -> Self {
+        self.media = Some(media);
+        self
+    }
+
+    pub fn color_scheme(mut self, color_scheme: EmulatemediaColorScheme) -> Self {
+        self.color_scheme = Some(color_scheme);
+        self
+    }
+
+    pub fn reduced_motion(mut self, reduced_motion: EmulatemediaReducedMotion) -> Self {
+        self.reduced_motion = Some(reduced_motion);
+        self
+    }
+
+    pub fn forced_colors(mut self, forced_colors: EmulatemediaForcedColors) -> Self {
+        self.forced_colors = Some(forced_colors);
+        self
+    }
+}
+
+impl Command for Emulatemedia {
+    const ACTION: &'static str = "emulatemedia";
+}
+
+/// The `offline` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Offline {
+    pub offline: bool,
+}
+
+impl Offline {
+    pub fn new(offline: bool) -> Self {
+        Self { offline }
+    }
+}
+
+impl Command for Offline {
+    const ACTION: &'static str = "offline";
+}
+
+/// The `headers` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Headers {
+    pub headers: HashMap<String, String>,
+}
+
+impl Headers {
+    pub fn new(headers: HashMap<String, String>) -> Self {
+        Self { headers }
+    }
+}
+
+impl Command for Headers {
+    const ACTION: &'static str = "headers";
+}
+
+/// The `pause` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Pause {}
+
+impl Pause {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for Pause {
+    const ACTION: &'static str = "pause";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GetbyalttextSubaction {
+    #[serde(rename = "click")]
+    Click,
+    #[serde(rename = "hover")]
+    Hover,
+}
+
+/// The `getbyalttext` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Getbyalttext {
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exact: Option<bool>,
+    pub subaction: GetbyalttextSubaction,
+}
+
+impl Getbyalttext {
+    pub fn new(text: impl Into<String>, subaction: GetbyalttextSubaction) -> Self {
+        Self {
+            text: text.into(),
+            exact: None,
+            subaction,
+        }
+    }
+
+    pub fn exact(mut self, exact: bool) -> Self {
+        self.exact = Some(exact);
+        self
+    }
+}
+
+impl Command for Getbyalttext {
+    const ACTION: &'static str = "getbyalttext";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GetbytitleSubaction {
+    #[serde(rename = "click")]
+    Click,
+    #[serde(rename = "hover")]
+    Hover,
+}
+
+/// The `getbytitle` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Getbytitle {
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exact: Option<bool>,
+    pub subaction: GetbytitleSubaction,
+}
+
+impl Getbytitle {
+    pub fn new(text: impl Into<String>, subaction: GetbytitleSubaction) -> Self {
+        Self {
+            text: text.into(),
+            exact: None,
+            subaction,
+        }
+    }
+
+    pub fn exact(mut self, exact: bool) -> Self {
+        self.exact = Some(exact);
+        self
+    }
+}
+
+impl Command for Getbytitle {
+    const ACTION: &'static str = "getbytitle";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GetbytestidSubaction {
+    #[serde(rename = "click")]
+    Click,
+    #[serde(rename = "fill")]
+    Fill,
+    #[serde(rename = "check")]
+    Check,
+    #[serde(rename = "hover")]
+    Hover,
+}
+
+/// The `getbytestid` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Getbytestid {
+    #[serde(rename = "testId")]
+    pub test_id: String,
+    pub subaction: GetbytestidSubaction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+impl Getbytestid {
+    pub fn new(test_id: impl Into<String>, subaction: GetbytestidSubaction) -> Self {
+        Self {
+            test_id: test_id.into(),
+            subaction,
+            value: None,
+        }
+    }
+
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+}
+
+impl Command for Getbytestid {
+    const ACTION: &'static str = "getbytestid";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NthSubaction {
+    #[serde(rename = "click")]
+    Click,
+    #[serde(rename = "fill")]
+    Fill,
+    #[serde(rename = "check")]
+    Check,
+    #[serde(rename = "hover")]
+    Hover,
+    #[serde(rename = "text")]
+    Text,
+}
+
+/// The `nth` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Nth {
+    pub selector: String,
+    pub index: f64,
+    pub subaction: NthSubaction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+impl Nth {
+    pub fn new(selector: impl Into<String>, index: f64, subaction: NthSubaction) -> Self {
+        Self {
+            selector: selector.into(),
+            index,
+            subaction,
+            value: None,
+        }
+    }
+
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+}
+
+impl Command for Nth {
+    const ACTION: &'static str = "nth";
+}
+
+/// The `waitforurl` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Waitforurl {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<f64>,
+}
+
+impl Waitforurl {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            timeout: None,
+        }
+    }
+
+    pub fn timeout(mut self, timeout: f64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl Command for Waitforurl {
+    const ACTION: &'static str = "waitforurl";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WaitforloadstateState {
+    #[serde(rename = "load")]
+    Load,
+    #[serde(rename = "domcontentloaded")]
+    Domcontentloaded,
+    #[serde(rename = "networkidle")]
+    Networkidle,
+}
+
+/// The `waitforloadstate` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Waitforloadstate {
+    pub state: WaitforloadstateState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<f64>,
+}
+
+impl Waitforloadstate {
+    pub fn new(state: WaitforloadstateState) -> Self {
+        Self {
+            state,
+            timeout: None,
+        }
+    }
+
+    pub fn timeout(mut self, timeout: f64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl Command for Waitforloadstate {
+    const ACTION: &'static str = "waitforloadstate";
+}
+
+/// The `setcontent` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Setcontent {
+    pub html: String,
+}
+
+impl Setcontent {
+    pub fn new(html: impl Into<String>) -> Self {
+        Self { html: html.into() }
+    }
+}
+
+impl Command for Setcontent {
+    const ACTION: &'static str = "setcontent";
+}
+
+/// The `timezone` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Timezone {
+    pub timezone: String,
+}
+
+impl Timezone {
+    pub fn new(timezone: impl Into<String>) -> Self {
+        Self {
+            timezone: timezone.into(),
+        }
+    }
+}
+
+impl Command for Timezone {
+    const ACTION: &'static str = "timezone";
+}
+
+/// The `locale` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Locale {
+    pub locale: String,
+}
+
+impl Locale {
+    pub fn new(locale: impl Into<String>) -> Self {
+        Self {
+            locale: locale.into(),
+        }
+    }
+}
+
+impl Command for Locale {
+    const ACTION: &'static str = "locale";
+}
+
+/// The `credentials` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl Credentials {
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+}
+
+impl Command for Credentials {
+    const ACTION: &'static str = "credentials";
+}
+
+/// The `mousemove` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Mousemove {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Mousemove {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Command for Mousemove {
+    const ACTION: &'static str = "mousemove";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MousedownButton {
+    #[serde(rename = "left")]
+    Left,
+    #[serde(rename = "right")]
+    Right,
+    #[serde(rename = "middle")]
+    Middle,
+}
+
+/// The `mousedown` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Mousedown {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub button: Option<MousedownButton>,
+}
+
+impl Mousedown {
+    pub fn new() -> Self {
+        Self { button: None }
+    }
+
+    pub fn button(mut self, button: MousedownButton) -> Self {
+        self.button = Some(button);
+        self
+    }
+}
+
+impl Command for Mousedown {
+    const ACTION: &'static str = "mousedown";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MouseupButton {
+    #[serde(rename = "left")]
+    Left,
+    #[serde(rename = "right")]
+    Right,
+    #[serde(rename = "middle")]
+    Middle,
+}
+
+/// The `mouseup` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Mouseup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub button: Option<MouseupButton>,
+}
+
+impl Mouseup {
+    pub fn new() -> Self {
+        Self { button: None }
+    }
+
+    pub fn button(mut self, button: MouseupButton) -> Self {
+        self.button = Some(button);
+        self
+    }
+}
+
+impl Command for Mouseup {
+    const ACTION: &'static str = "mouseup";
+}
+
+/// The `bringtofront` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Bringtofront {}
+
+impl Bringtofront {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for Bringtofront {
+    const ACTION: &'static str = "bringtofront";
+}
+
+/// The `waitforfunction` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Waitforfunction {
+    pub expression: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<f64>,
+}
+
+impl Waitforfunction {
+    pub fn new(expression: impl Into<String>) -> Self {
+        Self {
+            expression: expression.into(),
+            timeout: None,
+        }
+    }
+
+    pub fn timeout(mut self, timeout: f64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl Command for Waitforfunction {
+    const ACTION: &'static str = "waitforfunction";
+}
+
+/// The `scrollintoview` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scrollintoview {
+    pub selector: String,
+}
+
+impl Scrollintoview {
+    pub fn new(selector: impl Into<String>) -> Self {
+        Self {
+            selector: selector.into(),
+        }
+    }
+}
+
+impl Command for Scrollintoview {
+    const ACTION: &'static str = "scrollintoview";
+}
+
+/// The `addinitscript` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Addinitscript {
+    pub script: String,
+}
+
+impl Addinitscript {
+    pub fn new(script: impl Into<String>) -> Self {
+        Self {
+            script: script.into(),
+        }
+    }
+}
+
+impl Command for Addinitscript {
+    const ACTION: &'static str = "addinitscript";
+}
+
+/// The `keydown` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keydown {
+    pub key: String,
+}
+
+impl Keydown {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self { key: key.into() }
+    }
+}
+
+impl Command for Keydown {
+    const ACTION: &'static str = "keydown";
+}
+
+/// The `keyup` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keyup {
+    pub key: String,
+}
+
+impl Keyup {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self { key: key.into() }
+    }
+}
+
+impl Command for Keyup {
+    const ACTION: &'static str = "keyup";
+}
+
+/// The `inserttext` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Inserttext {
+    pub text: String,
+}
+
+impl Inserttext {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into() }
+    }
+}
+
+impl Command for Inserttext {
+    const ACTION: &'static str = "inserttext";
+}
+
+/// The `multiselect` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Multiselect {
+    pub selector: String,
+    pub values: Vec<String>,
+}
+
+impl Multiselect {
+    pub fn new(selector: impl Into<String>, values: Vec<String>) -> Self {
+        Self {
+            selector: selector.into(),
+            values,
+        }
+    }
+}
+
+impl Command for Multiselect {
+    const ACTION: &'static str = "multiselect";
+}
+
+/// The `waitfordownload` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Waitfordownload {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arm: Option<bool>,
+}
+
+impl Waitfordownload {
+    pub fn new() -> Self {
+        Self {
+            path: None,
+            timeout: None,
+            arm: None,
+        }
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn timeout(mut self, timeout: f64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn arm(mut self, arm: bool) -> Self {
+        self.arm = Some(arm);
+        self
+    }
+}
+
+impl Command for Waitfordownload {
+    const ACTION: &'static str = "waitfordownload";
+}
+
+/// The `waitforpopup` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Waitforpopup {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arm: Option<bool>,
+}
+
+impl Waitforpopup {
+    pub fn new() -> Self {
+        Self {
+            timeout: None,
+            arm: None,
+        }
+    }
+
+    pub fn timeout(mut self, timeout: f64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn arm(mut self, arm: bool) -> Self {
+        self.arm = Some(arm);
+        self
+    }
+}
+
+impl Command for Waitforpopup {
+    const ACTION: &'static str = "waitforpopup";
+}
+
+/// The `waitforrequest` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Waitforrequest {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arm: Option<bool>,
+}
+
+impl Waitforrequest {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            timeout: None,
+            arm: None,
+        }
+    }
+
+    pub fn timeout(mut self, timeout: f64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn arm(mut self, arm: bool) -> Self {
+        self.arm = Some(arm);
+        self
+    }
+}
+
+impl Command for Waitforrequest {
+    const ACTION: &'static str = "waitforrequest";
+}
+
+/// The `responsebody` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Responsebody {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<f64>,
+}
+
+impl Responsebody {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            timeout: None,
+        }
+    }
+
+    pub fn timeout(mut self, timeout: f64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl Command for Responsebody {
+    const ACTION: &'static str = "responsebody";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScreencastStartFormat {
+    #[serde(rename = "jpeg")]
+    Jpeg,
+    #[serde(rename = "png")]
+    Png,
+}
+
+/// The `screencast_start` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScreencastStart {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ScreencastStartFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<f64>,
+    #[serde(rename = "maxWidth", skip_serializing_if = "Option::is_none")]
+    pub max_width: Option<f64>,
+    #[serde(rename = "maxHeight", skip_serializing_if = "Option::is_none")]
+    pub max_height: Option<f64>,
+    #[serde(rename = "everyNthFrame", skip_serializing_if = "Option::is_none")]
+    pub every_nth_frame: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fps: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl ScreencastStart {
+    pub fn new() -> Self {
+        Self {
+            format: None,
+            quality: None,
+            max_width: None,
+            max_height: None,
+            every_nth_frame: None,
+            scale: None,
+            fps: None,
+            path: None,
+        }
+    }
+
+    pub fn format(mut self, format: ScreencastStartFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn quality(mut self, quality: f64) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    pub fn max_width(mut self, max_width: f64) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn max_height(mut self, max_height: f64) -> Self {
+        self.max_height = Some(max_height);
+        self
+    }
+
+    pub fn every_nth_frame(mut self, every_nth_frame: f64) -> Self {
+        self.every_nth_frame = Some(every_nth_frame);
+        self
+    }
+
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    pub fn fps(mut self, fps: f64) -> Self {
+        self.fps = Some(fps);
+        self
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl Command for ScreencastStart {
+    const ACTION: &'static str = "screencast_start";
+}
+
+/// The `screencast_stop` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScreencastStop {}
+
+impl ScreencastStop {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for ScreencastStop {
+    const ACTION: &'static str = "screencast_stop";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputMouseType {
+    #[serde(rename = "mousePressed")]
+    MousePressed,
+    #[serde(rename = "mouseReleased")]
+    MouseReleased,
+    #[serde(rename = "mouseMoved")]
+    MouseMoved,
+    #[serde(rename = "mouseWheel")]
+    MouseWheel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputMouseButton {
+    #[serde(rename = "left")]
+    Left,
+    #[serde(rename = "right")]
+    Right,
+    #[serde(rename = "middle")]
+    Middle,
+    #[serde(rename = "none")]
+    None,
+}
+
+/// The `input_mouse` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputMouse {
+    pub r#type: InputMouseType,
+    pub x: f64,
+    pub y: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub button: Option<InputMouseButton>,
+    #[serde(rename = "clickCount", skip_serializing_if = "Option::is_none")]
+    pub click_count: Option<f64>,
+    #[serde(rename = "deltaX", skip_serializing_if = "Option::is_none")]
+    pub delta_x: Option<f64>,
+    #[serde(rename = "deltaY", skip_serializing_if = "Option::is_none")]
+    pub delta_y: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modifiers: Option<f64>,
+}
+
+impl InputMouse {
+    pub fn new(r#type: InputMouseType, x: f64, y: f64) -> Self {
+        Self {
+            r#type,
+            x,
+            y,
+            button: None,
+            click_count: None,
+            delta_x: None,
+            delta_y: None,
+            modifiers: None,
+        }
+    }
+
+    pub fn button(mut self, button: InputMouseButton) -> Self {
+        self.button = Some(button);
+        self
+    }
+
+    pub fn click_count(mut self, click_count: f64) -> Self {
+        self.click_count = Some(click_count);
+        self
+    }
+
+    pub fn delta_x(mut self, delta_x: f64) -> Self {
+        self.delta_x = Some(delta_x);
+        self
+    }
+
+    pub fn delta_y(mut self, delta_y: f64) -> Self {
+        self.delta_y = Some(delta_y);
+        self
+    }
+
+    pub fn modifiers(mut self, modifiers: f64) -> Self {
+        self.modifiers = Some(modifiers);
+        self
+    }
+}
+
+impl Command for InputMouse {
+    const ACTION: &'static str = "input_mouse";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputKeyboardType {
+    #[serde(rename = "keyDown")]
+    KeyDown,
+    #[serde(rename = "keyUp")]
+    KeyUp,
+    #[serde(rename = "char")]
+    Char,
+}
+
+/// The `input_keyboard` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputKeyboard {
+    pub r#type: InputKeyboardType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modifiers: Option<f64>,
+}
+
+impl InputKeyboard {
+    pub fn new(r#type: InputKeyboardType) -> Self {
+        Self {
+            r#type,
+            key: None,
+            code: None,
+            text: None,
+            modifiers: None,
+        }
+    }
+
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    pub fn modifiers(mut self, modifiers: f64) -> Self {
+        self.modifiers = Some(modifiers);
+        self
+    }
+}
+
+impl Command for InputKeyboard {
+    const ACTION: &'static str = "input_keyboard";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputTouchType {
+    #[serde(rename = "touchStart")]
+    TouchStart,
+    #[serde(rename = "touchEnd")]
+    TouchEnd,
+    #[serde(rename = "touchMove")]
+    TouchMove,
+    #[serde(rename = "touchCancel")]
+    TouchCancel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputTouchTouchPoints {
+    pub x: f64,
+    pub y: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<f64>,
+}
+
+/// The `input_touch` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputTouch {
+    pub r#type: InputTouchType,
+    #[serde(rename = "touchPoints")]
+    pub touch_points: Vec<InputTouchTouchPoints>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modifiers: Option<f64>,
+}
+
+impl InputTouch {
+    pub fn new(r#type: InputTouchType, touch_points: Vec<InputTouchTouchPoints>) -> Self {
+        Self {
+            r#type,
+            touch_points,
+            modifiers: None,
+        }
+    }
+
+    pub fn modifiers(mut self, modifiers: f64) -> Self {
+        self.modifiers = Some(modifiers);
+        self
+    }
+}
+
+impl Command for InputTouch {
+    const ACTION: &'static str = "input_touch";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwipeDirection {
+    #[serde(rename = "up")]
+    Up,
+    #[serde(rename = "down")]
+    Down,
+    #[serde(rename = "left")]
+    Left,
+    #[serde(rename = "right")]
+    Right,
+}
+
+/// The `swipe` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Swipe {
+    pub direction: SwipeDirection,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
+}
+
+impl Swipe {
+    pub fn new(direction: SwipeDirection) -> Self {
+        Self {
+            direction,
+            distance: None,
+        }
+    }
+
+    pub fn distance(mut self, distance: f64) -> Self {
+        self.distance = Some(distance);
+        self
+    }
+}
+
+impl Command for Swipe {
+    const ACTION: &'static str = "swipe";
+}
+
+/// The `device_list` command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeviceList {}
+
+impl DeviceList {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for DeviceList {
+    const ACTION: &'static str = "device_list";
+}
+
+#[cfg(feature = "native")]
+impl crate::Client {
+    /// Send a `launch` command with its required fields; use `send(Launch::new(..))` to set others
+    pub async fn launch(&self) -> crate::Result<Value> {
+        self.send(Launch::new()).await
+    }
+
+    /// Send a `navigate` command with its required fields; use `send(Navigate::new(..))` to set others
+    pub async fn navigate(&self, url: impl Into<String>) -> crate::Result<Value> {
+        self.send(Navigate::new(url)).await
+    }
+
+    /// Send a `click` command with its required fields; use `send(Click::new(..))` to set others
+    pub async fn click(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Click::new(selector)).await
+    }
+
+    /// Send a `type` command with its required fields; use `send(Type::new(..))` to set others
+    pub async fn r#type(
+        &self,
+        selector: impl Into<String>,
+        text: impl Into<String>,
+    ) -> crate::Result<Value> {
+        self.send(Type::new(selector, text)).await
+    }
+
+    /// Send a `fill` command with its required fields; use `send(Fill::new(..))` to set others
+    pub async fn fill(
+        &self,
+        selector: impl Into<String>,
+        value: impl Into<String>,
+    ) -> crate::Result<Value> {
+        self.send(Fill::new(selector, value)).await
+    }
+
+    /// Send a `check` command with its required fields; use `send(Check::new(..))` to set others
+    pub async fn check(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Check::new(selector)).await
+    }
+
+    /// Send a `uncheck` command with its required fields; use `send(Uncheck::new(..))` to set others
+    pub async fn uncheck(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Uncheck::new(selector)).await
+    }
+
+    /// Send a `upload` command with its required fields; use `send(Upload::new(..))` to set others
+    pub async fn upload(&self, selector: impl Into<String>, files: Value) -> crate::Result<Value> {
+        self.send(Upload::new(selector, files)).await
+    }
+
+    /// Send a `dblclick` command with its required fields; use `send(Dblclick::new(..))` to set others
+    pub async fn dblclick(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Dblclick::new(selector)).await
+    }
+
+    /// Send a `focus` command with its required fields; use `send(Focus::new(..))` to set others
+    pub async fn focus(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Focus::new(selector)).await
+    }
+
+    /// Send a `drag` command with its required fields; use `send(Drag::new(..))` to set others
+    pub async fn drag(
+        &self,
+        source: impl Into<String>,
+        target: impl Into<String>,
+    ) -> crate::Result<Value> {
+        self.send(Drag::new(source, target)).await
+    }
+
+    /// Send a `frame` command with its required fields; use `send(Frame::new(..))` to set others
+    pub async fn frame(&self) -> crate::Result<Value> {
+        self.send(Frame::new()).await
+    }
+
+    /// Send a `mainframe` command with its required fields; use `send(Mainframe::new(..))` to set others
+    pub async fn mainframe(&self) -> crate::Result<Value> {
+        self.send(Mainframe::new()).await
+    }
+
+    /// Send a `getbyrole` command with its required fields; use `send(Getbyrole::new(..))` to set others
+    pub async fn getbyrole(
+        &self,
+        role: impl Into<String>,
+        subaction: GetbyroleSubaction,
+    ) -> crate::Result<Value> {
+        self.send(Getbyrole::new(role, subaction)).await
+    }
+
+    /// Send a `getbytext` command with its required fields; use `send(Getbytext::new(..))` to set others
+    pub async fn getbytext(
+        &self,
+        text: impl Into<String>,
+        subaction: GetbytextSubaction,
+    ) -> crate::Result<Value> {
+        self.send(Getbytext::new(text, subaction)).await
+    }
+
+    /// Send a `getbylabel` command with its required fields; use `send(Getbylabel::new(..))` to set others
+    pub async fn getbylabel(
+        &self,
+        label: impl Into<String>,
+        subaction: GetbylabelSubaction,
+    ) -> crate::Result<Value> {
+        self.send(Getbylabel::new(label, subaction)).await
+    }
+
+    /// Send a `getbyplaceholder` command with its required fields; use `send(Getbyplaceholder::new(..))` to set others
+    pub async fn getbyplaceholder(
+        &self,
+        placeholder: impl Into<String>,
+        subaction: GetbyplaceholderSubaction,
+    ) -> crate::Result<Value> {
+        self.send(Getbyplaceholder::new(placeholder, subaction))
+            .await
+    }
+
+    /// Send a `press` command with its required fields; use `send(Press::new(..))` to set others
+    pub async fn press(&self, key: impl Into<String>) -> crate::Result<Value> {
+        self.send(Press::new(key)).await
+    }
+
+    /// Send a `screenshot` command with its required fields; use `send(Screenshot::new(..))` to set others
+    pub async fn screenshot(&self) -> crate::Result<Value> {
+        self.send(Screenshot::new()).await
+    }
+
+    /// Send a `snapshot` command with its required fields; use `send(Snapshot::new(..))` to set others
+    pub async fn snapshot(&self) -> crate::Result<Value> {
+        self.send(Snapshot::new()).await
+    }
+
+    /// Send a `evaluate` command with its required fields; use `send(Evaluate::new(..))` to set others
+    pub async fn evaluate(&self, script: impl Into<String>) -> crate::Result<Value> {
+        self.send(Evaluate::new(script)).await
+    }
+
+    /// Send a `wait` command with its required fields; use `send(Wait::new(..))` to set others
+    pub async fn wait(&self) -> crate::Result<Value> {
+        self.send(Wait::new()).await
+    }
+
+    /// Send a `scroll` command with its required fields; use `send(Scroll::new(..))` to set others
+    pub async fn scroll(&self) -> crate::Result<Value> {
+        self.send(Scroll::new()).await
+    }
+
+    /// Send a `select` command with its required fields; use `send(Select::new(..))` to set others
+    pub async fn select(&self, selector: impl Into<String>, values: Value) -> crate::Result<Value> {
+        self.send(Select::new(selector, values)).await
+    }
+
+    /// Send a `hover` command with its required fields; use `send(Hover::new(..))` to set others
+    pub async fn hover(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Hover::new(selector)).await
+    }
+
+    /// Send a `content` command with its required fields; use `send(Content::new(..))` to set others
+    pub async fn content(&self) -> crate::Result<Value> {
+        self.send(Content::new()).await
+    }
+
+    /// Send a `close` command with its required fields; use `send(Close::new(..))` to set others
+    pub async fn close(&self) -> crate::Result<Value> {
+        self.send(Close::new()).await
+    }
+
+    /// Send a `tab_new` command with its required fields; use `send(TabNew::new(..))` to set others
+    pub async fn tab_new(&self) -> crate::Result<Value> {
+        self.send(TabNew::new()).await
+    }
+
+    /// Send a `tab_list` command with its required fields; use `send(TabList::new(..))` to set others
+    pub async fn tab_list(&self) -> crate::Result<Value> {
+        self.send(TabList::new()).await
+    }
+
+    /// Send a `targets` command with its required fields; use `send(Targets::new(..))` to set others
+    pub async fn targets(&self) -> crate::Result<Value> {
+        self.send(Targets::new()).await
+    }
+
+    /// Send a `robots` command with its required fields; use `send(Robots::new(..))` to set others
+    pub async fn robots(&self, url: impl Into<String>) -> crate::Result<Value> {
+        self.send(Robots::new(url)).await
+    }
+
+    /// Send a `audit_perf` command with its required fields; use `send(AuditPerf::new(..))` to set others
+    pub async fn audit_perf(&self) -> crate::Result<Value> {
+        self.send(AuditPerf::new()).await
+    }
+
+    /// Send a `audit_seo` command with its required fields; use `send(AuditSeo::new(..))` to set others
+    pub async fn audit_seo(&self) -> crate::Result<Value> {
+        self.send(AuditSeo::new()).await
+    }
+
+    /// Send a `audit_security` command with its required fields; use `send(AuditSecurity::new(..))` to set others
+    pub async fn audit_security(&self) -> crate::Result<Value> {
+        self.send(AuditSecurity::new()).await
+    }
+
+    /// Send a `tls_info` command with its required fields; use `send(TlsInfo::new(..))` to set others
+    pub async fn tls_info(&self, url: impl Into<String>) -> crate::Result<Value> {
+        self.send(TlsInfo::new(url)).await
+    }
+
+    /// Send a `clock_set` command with its required fields; use `send(ClockSet::new(..))` to set others
+    pub async fn clock_set(&self, time: impl Into<String>) -> crate::Result<Value> {
+        self.send(ClockSet::new(time)).await
+    }
+
+    /// Send a `clock_advance` command with its required fields; use `send(ClockAdvance::new(..))` to set others
+    pub async fn clock_advance(&self, ms: i64) -> crate::Result<Value> {
+        self.send(ClockAdvance::new(ms)).await
+    }
+
+    /// Send a `clock_resume` command with its required fields; use `send(ClockResume::new(..))` to set others
+    pub async fn clock_resume(&self) -> crate::Result<Value> {
+        self.send(ClockResume::new()).await
+    }
+
+    /// Send a `schedule_add` command with its required fields; use `send(ScheduleAdd::new(..))` to set others
+    pub async fn schedule_add(
+        &self,
+        cron: impl Into<String>,
+        command: Vec<String>,
+    ) -> crate::Result<Value> {
+        self.send(ScheduleAdd::new(cron, command)).await
+    }
+
+    /// Send a `schedule_list` command with its required fields; use `send(ScheduleList::new(..))` to set others
+    pub async fn schedule_list(&self) -> crate::Result<Value> {
+        self.send(ScheduleList::new()).await
+    }
+
+    /// Send a `schedule_remove` command with its required fields; use `send(ScheduleRemove::new(..))` to set others
+    pub async fn schedule_remove(&self, job_id: impl Into<String>) -> crate::Result<Value> {
+        self.send(ScheduleRemove::new(job_id)).await
+    }
+
+    /// Send a `schedule_logs` command with its required fields; use `send(ScheduleLogs::new(..))` to set others
+    pub async fn schedule_logs(&self, job_id: impl Into<String>) -> crate::Result<Value> {
+        self.send(ScheduleLogs::new(job_id)).await
+    }
+
+    /// Send a `jobs` command with its required fields; use `send(Jobs::new(..))` to set others
+    pub async fn jobs(&self) -> crate::Result<Value> {
+        self.send(Jobs::new()).await
+    }
+
+    /// Send a `which` command with its required fields; use `send(Which::new(..))` to set others
+    pub async fn which(&self) -> crate::Result<Value> {
+        self.send(Which::new()).await
+    }
+
+    /// Send a `cancel` command with its required fields; use `send(Cancel::new(..))` to set others
+    pub async fn cancel(&self) -> crate::Result<Value> {
+        self.send(Cancel::new()).await
+    }
+
+    /// Send a `lanes` command with its required fields; use `send(Lanes::new(..))` to set others
+    pub async fn lanes(&self) -> crate::Result<Value> {
+        self.send(Lanes::new()).await
+    }
+
+    /// Send a `tab_switch` command with its required fields; use `send(TabSwitch::new(..))` to set others
+    pub async fn tab_switch(&self, index: f64) -> crate::Result<Value> {
+        self.send(TabSwitch::new(index)).await
+    }
+
+    /// Send a `tab_close` command with its required fields; use `send(TabClose::new(..))` to set others
+    pub async fn tab_close(&self) -> crate::Result<Value> {
+        self.send(TabClose::new()).await
+    }
+
+    /// Send a `session_options` command with its required fields; use `send(SessionOptions::new(..))` to set others
+    pub async fn session_options(&self) -> crate::Result<Value> {
+        self.send(SessionOptions::new()).await
+    }
+
+    /// Send a `dismiss_overlays` command with its required fields; use `send(DismissOverlays::new(..))` to set others
+    pub async fn dismiss_overlays(&self) -> crate::Result<Value> {
+        self.send(DismissOverlays::new()).await
+    }
+
+    /// Send a `stats` command with its required fields; use `send(Stats::new(..))` to set others
+    pub async fn stats(&self) -> crate::Result<Value> {
+        self.send(Stats::new()).await
+    }
+
+    /// Send a `memory` command with its required fields; use `send(Memory::new(..))` to set others
+    pub async fn memory(&self) -> crate::Result<Value> {
+        self.send(Memory::new()).await
+    }
+
+    /// Send a `cdp` command with its required fields; use `send(Cdp::new(..))` to set others
+    pub async fn cdp(&self, method: impl Into<String>) -> crate::Result<Value> {
+        self.send(Cdp::new(method)).await
+    }
+
+    /// Send a `cdp_listen` command with its required fields; use `send(CdpListen::new(..))` to set others
+    pub async fn cdp_listen(&self, events: Vec<String>) -> crate::Result<Value> {
+        self.send(CdpListen::new(events)).await
+    }
+
+    /// Send a `watch` command with its required fields; use `send(Watch::new(..))` to set others
+    pub async fn watch(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Watch::new(selector)).await
+    }
+
+    /// Send a `poll` command with its required fields; use `send(Poll::new(..))` to set others
+    pub async fn poll(&self, selector: impl Into<String>, interval: i64) -> crate::Result<Value> {
+        self.send(Poll::new(selector, interval)).await
+    }
+
+    /// Send a `trace_step` command with its required fields; use `send(TraceStep::new(..))` to set others
+    pub async fn trace_step(&self) -> crate::Result<Value> {
+        self.send(TraceStep::new()).await
+    }
+
+    /// Send a `capture_step` command with its required fields; use `send(CaptureStep::new(..))` to set others
+    pub async fn capture_step(&self) -> crate::Result<Value> {
+        self.send(CaptureStep::new()).await
+    }
+
+    /// Send a `window_new` command with its required fields; use `send(WindowNew::new(..))` to set others
+    pub async fn window_new(&self) -> crate::Result<Value> {
+        self.send(WindowNew::new()).await
+    }
+
+    /// Send a `cookies_get` command with its required fields; use `send(CookiesGet::new(..))` to set others
+    pub async fn cookies_get(&self) -> crate::Result<Value> {
+        self.send(CookiesGet::new()).await
+    }
+
+    /// Send a `cookies_set` command with its required fields; use `send(CookiesSet::new(..))` to set others
+    pub async fn cookies_set(&self, cookies: Vec<CookiesSetCookies>) -> crate::Result<Value> {
+        self.send(CookiesSet::new(cookies)).await
+    }
+
+    /// Send a `cookies_clear` command with its required fields; use `send(CookiesClear::new(..))` to set others
+    pub async fn cookies_clear(&self) -> crate::Result<Value> {
+        self.send(CookiesClear::new()).await
+    }
+
+    /// Send a `storage_get` command with its required fields; use `send(StorageGet::new(..))` to set others
+    pub async fn storage_get(&self, r#type: StorageGetType) -> crate::Result<Value> {
+        self.send(StorageGet::new(r#type)).await
+    }
+
+    /// Send a `storage_set` command with its required fields; use `send(StorageSet::new(..))` to set others
+    pub async fn storage_set(
+        &self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+        r#type: StorageSetType,
+    ) -> crate::Result<Value> {
+        self.send(StorageSet::new(key, value, r#type)).await
+    }
+
+    /// Send a `storage_clear` command with its required fields; use `send(StorageClear::new(..))` to set others
+    pub async fn storage_clear(&self, r#type: StorageClearType) -> crate::Result<Value> {
+        self.send(StorageClear::new(r#type)).await
+    }
+
+    /// Send a `dialog` command with its required fields; use `send(Dialog::new(..))` to set others
+    pub async fn dialog(&self, response: DialogResponse) -> crate::Result<Value> {
+        self.send(Dialog::new(response)).await
+    }
+
+    /// Send a `pdf` command with its required fields; use `send(Pdf::new(..))` to set others
+    pub async fn pdf(&self) -> crate::Result<Value> {
+        self.send(Pdf::new()).await
+    }
+
+    /// Send a `archive` command with its required fields; use `send(Archive::new(..))` to set others
+    pub async fn archive(&self, format: ArchiveFormat) -> crate::Result<Value> {
+        self.send(Archive::new(format)).await
+    }
+
+    /// Send a `freeze` command with its required fields; use `send(Freeze::new(..))` to set others
+    pub async fn freeze(&self) -> crate::Result<Value> {
+        self.send(Freeze::new()).await
+    }
+
+    /// Send a `selectors_suggest` command with its required fields; use `send(SelectorsSuggest::new(..))` to set others
+    pub async fn selectors_suggest(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(SelectorsSuggest::new(selector)).await
+    }
+
+    /// Send a `selectors_heal` command with its required fields; use `send(SelectorsHeal::new(..))` to set others
+    pub async fn selectors_heal(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(SelectorsHeal::new(selector)).await
+    }
+
+    /// Send a `route` command with its required fields; use `send(Route::new(..))` to set others
+    pub async fn route(&self, url: impl Into<String>) -> crate::Result<Value> {
+        self.send(Route::new(url)).await
+    }
+
+    /// Send a `unroute` command with its required fields; use `send(Unroute::new(..))` to set others
+    pub async fn unroute(&self) -> crate::Result<Value> {
+        self.send(Unroute::new()).await
+    }
+
+    /// Send a `requests` command with its required fields; use `send(Requests::new(..))` to set others
+    pub async fn requests(&self) -> crate::Result<Value> {
+        self.send(Requests::new()).await
+    }
+
+    /// Send a `download` command with its required fields; use `send(Download::new(..))` to set others
+    pub async fn download(
+        &self,
+        selector: impl Into<String>,
+        path: impl Into<String>,
+    ) -> crate::Result<Value> {
+        self.send(Download::new(selector, path)).await
+    }
+
+    /// Send a `geolocation` command with its required fields; use `send(Geolocation::new(..))` to set others
+    pub async fn geolocation(&self, latitude: f64, longitude: f64) -> crate::Result<Value> {
+        self.send(Geolocation::new(latitude, longitude)).await
+    }
+
+    /// Send a `permissions` command with its required fields; use `send(Permissions::new(..))` to set others
+    pub async fn permissions(&self, permissions: Vec<String>, grant: bool) -> crate::Result<Value> {
+        self.send(Permissions::new(permissions, grant)).await
+    }
+
+    /// Send a `viewport` command with its required fields; use `send(Viewport::new(..))` to set others
+    pub async fn viewport(&self, width: f64, height: f64) -> crate::Result<Value> {
+        self.send(Viewport::new(width, height)).await
+    }
+
+    /// Send a `useragent` command with its required fields; use `send(Useragent::new(..))` to set others
+    pub async fn useragent(&self, user_agent: impl Into<String>) -> crate::Result<Value> {
+        self.send(Useragent::new(user_agent)).await
+    }
+
+    /// Send a `device` command with its required fields; use `send(Device::new(..))` to set others
+    pub async fn device(&self, device: impl Into<String>) -> crate::Result<Value> {
+        self.send(Device::new(device)).await
+    }
+
+    /// Send a `back` command with its required fields; use `send(Back::new(..))` to set others
+    pub async fn back(&self) -> crate::Result<Value> {
+        self.send(Back::new()).await
+    }
+
+    /// Send a `forward` command with its required fields; use `send(Forward::new(..))` to set others
+    pub async fn forward(&self) -> crate::Result<Value> {
+        self.send(Forward::new()).await
+    }
+
+    /// Send a `reload` command with its required fields; use `send(Reload::new(..))` to set others
+    pub async fn reload(&self) -> crate::Result<Value> {
+        self.send(Reload::new()).await
+    }
+
+    /// Send a `url` command with its required fields; use `send(Url::new(..))` to set others
+    pub async fn url(&self) -> crate::Result<Value> {
+        self.send(Url::new()).await
+    }
+
+    /// Send a `title` command with its required fields; use `send(Title::new(..))` to set others
+    pub async fn title(&self) -> crate::Result<Value> {
+        self.send(Title::new()).await
+    }
+
+    /// Send a `getattribute` command with its required fields; use `send(Getattribute::new(..))` to set others
+    pub async fn getattribute(
+        &self,
+        selector: impl Into<String>,
+        attribute: impl Into<String>,
+    ) -> crate::Result<Value> {
+        self.send(Getattribute::new(selector, attribute)).await
+    }
+
+    /// Send a `gettext` command with its required fields; use `send(Gettext::new(..))` to set others
+    pub async fn gettext(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Gettext::new(selector)).await
+    }
+
+    /// Send a `isvisible` command with its required fields; use `send(Isvisible::new(..))` to set others
+    pub async fn isvisible(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Isvisible::new(selector)).await
+    }
+
+    /// Send a `isenabled` command with its required fields; use `send(Isenabled::new(..))` to set others
+    pub async fn isenabled(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Isenabled::new(selector)).await
+    }
+
+    /// Send a `ischecked` command with its required fields; use `send(Ischecked::new(..))` to set others
+    pub async fn ischecked(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Ischecked::new(selector)).await
+    }
+
+    /// Send a `count` command with its required fields; use `send(Count::new(..))` to set others
+    pub async fn count(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Count::new(selector)).await
+    }
+
+    /// Send a `boundingbox` command with its required fields; use `send(Boundingbox::new(..))` to set others
+    pub async fn boundingbox(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Boundingbox::new(selector)).await
+    }
+
+    /// Send a `styles` command with its required fields; use `send(Styles::new(..))` to set others
+    pub async fn styles(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Styles::new(selector)).await
+    }
+
+    /// Send a `video_start` command with its required fields; use `send(VideoStart::new(..))` to set others
+    pub async fn video_start(&self, path: impl Into<String>) -> crate::Result<Value> {
+        self.send(VideoStart::new(path)).await
+    }
+
+    /// Send a `video_stop` command with its required fields; use `send(VideoStop::new(..))` to set others
+    pub async fn video_stop(&self) -> crate::Result<Value> {
+        self.send(VideoStop::new()).await
+    }
+
+    /// Send a `recording_start` command with its required fields; use `send(RecordingStart::new(..))` to set others
+    pub async fn recording_start(&self, path: impl Into<String>) -> crate::Result<Value> {
+        self.send(RecordingStart::new(path)).await
+    }
+
+    /// Send a `recording_stop` command with its required fields; use `send(RecordingStop::new(..))` to set others
+    pub async fn recording_stop(&self) -> crate::Result<Value> {
+        self.send(RecordingStop::new()).await
+    }
+
+    /// Send a `recording_restart` command with its required fields; use `send(RecordingRestart::new(..))` to set others
+    pub async fn recording_restart(&self, path: impl Into<String>) -> crate::Result<Value> {
+        self.send(RecordingRestart::new(path)).await
+    }
+
+    /// Send a `trace_start` command with its required fields; use `send(TraceStart::new(..))` to set others
+    pub async fn trace_start(&self) -> crate::Result<Value> {
+        self.send(TraceStart::new()).await
+    }
+
+    /// Send a `trace_stop` command with its required fields; use `send(TraceStop::new(..))` to set others
+    pub async fn trace_stop(&self) -> crate::Result<Value> {
+        self.send(TraceStop::new()).await
+    }
+
+    /// Send a `capture_start` command with its required fields; use `send(CaptureStart::new(..))` to set others
+    pub async fn capture_start(
+        &self,
+        url: impl Into<String>,
+        out: impl Into<String>,
+    ) -> crate::Result<Value> {
+        self.send(CaptureStart::new(url, out)).await
+    }
+
+    /// Send a `capture_stop` command with its required fields; use `send(CaptureStop::new(..))` to set others
+    pub async fn capture_stop(&self) -> crate::Result<Value> {
+        self.send(CaptureStop::new()).await
+    }
+
+    /// Send a `apis` command with its required fields; use `send(Apis::new(..))` to set others
+    pub async fn apis(&self) -> crate::Result<Value> {
+        self.send(Apis::new()).await
+    }
+
+    /// Send a `input` command with its required fields; use `send(Input::new(..))` to set others
+    pub async fn input(&self, op: InputOp) -> crate::Result<Value> {
+        self.send(Input::new(op)).await
+    }
+
+    /// Send a `ws` command with its required fields; use `send(Ws::new(..))` to set others
+    pub async fn ws(&self, op: WsOp) -> crate::Result<Value> {
+        self.send(Ws::new(op)).await
+    }
+
+    /// Send a `har_replay` command with its required fields; use `send(HarReplay::new(..))` to set others
+    pub async fn har_replay(&self, path: impl Into<String>) -> crate::Result<Value> {
+        self.send(HarReplay::new(path)).await
+    }
+
+    /// Send a `har_start` command with its required fields; use `send(HarStart::new(..))` to set others
+    pub async fn har_start(&self) -> crate::Result<Value> {
+        self.send(HarStart::new()).await
+    }
+
+    /// Send a `har_stop` command with its required fields; use `send(HarStop::new(..))` to set others
+    pub async fn har_stop(&self, path: impl Into<String>) -> crate::Result<Value> {
+        self.send(HarStop::new(path)).await
+    }
+
+    /// Send a `state_save` command with its required fields; use `send(StateSave::new(..))` to set others
+    pub async fn state_save(&self, path: impl Into<String>) -> crate::Result<Value> {
+        self.send(StateSave::new(path)).await
+    }
+
+    /// Send a `state_load` command with its required fields; use `send(StateLoad::new(..))` to set others
+    pub async fn state_load(&self, path: impl Into<String>) -> crate::Result<Value> {
+        self.send(StateLoad::new(path)).await
+    }
+
+    /// Send a `session_save` command with its required fields; use `send(SessionSave::new(..))` to set others
+    pub async fn session_save(&self, path: impl Into<String>) -> crate::Result<Value> {
+        self.send(SessionSave::new(path)).await
+    }
+
+    /// Send a `session_restore` command with its required fields; use `send(SessionRestore::new(..))` to set others
+    pub async fn session_restore(&self, path: impl Into<String>) -> crate::Result<Value> {
+        self.send(SessionRestore::new(path)).await
+    }
+
+    /// Send a `console` command with its required fields; use `send(Console::new(..))` to set others
+    pub async fn console(&self) -> crate::Result<Value> {
+        self.send(Console::new()).await
+    }
+
+    /// Send a `errors` command with its required fields; use `send(Errors::new(..))` to set others
+    pub async fn errors(&self) -> crate::Result<Value> {
+        self.send(Errors::new()).await
+    }
+
+    /// Send a `keyboard` command with its required fields; use `send(Keyboard::new(..))` to set others
+    pub async fn keyboard(&self, keys: impl Into<String>) -> crate::Result<Value> {
+        self.send(Keyboard::new(keys)).await
+    }
+
+    /// Send a `wheel` command with its required fields; use `send(Wheel::new(..))` to set others
+    pub async fn wheel(&self) -> crate::Result<Value> {
+        self.send(Wheel::new()).await
+    }
+
+    /// Send a `tap` command with its required fields; use `send(Tap::new(..))` to set others
+    pub async fn tap(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Tap::new(selector)).await
+    }
+
+    /// Send a `clipboard` command with its required fields; use `send(Clipboard::new(..))` to set others
+    pub async fn clipboard(&self, operation: ClipboardOperation) -> crate::Result<Value> {
+        self.send(Clipboard::new(operation)).await
+    }
+
+    /// Send a `highlight` command with its required fields; use `send(Highlight::new(..))` to set others
+    pub async fn highlight(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Highlight::new(selector)).await
+    }
+
+    /// Send a `inspect` command with its required fields; use `send(Inspect::new(..))` to set others
+    pub async fn inspect(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Inspect::new(selector)).await
+    }
+
+    /// Send a `clear` command with its required fields; use `send(Clear::new(..))` to set others
+    pub async fn clear(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Clear::new(selector)).await
+    }
+
+    /// Send a `selectall` command with its required fields; use `send(Selectall::new(..))` to set others
+    pub async fn selectall(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Selectall::new(selector)).await
+    }
+
+    /// Send a `innertext` command with its required fields; use `send(Innertext::new(..))` to set others
+    pub async fn innertext(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Innertext::new(selector)).await
+    }
+
+    /// Send a `innerhtml` command with its required fields; use `send(Innerhtml::new(..))` to set others
+    pub async fn innerhtml(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Innerhtml::new(selector)).await
+    }
+
+    /// Send a `inputvalue` command with its required fields; use `send(Inputvalue::new(..))` to set others
+    pub async fn inputvalue(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Inputvalue::new(selector)).await
+    }
+
+    /// Send a `setvalue` command with its required fields; use `send(Setvalue::new(..))` to set others
+    pub async fn setvalue(
+        &self,
+        selector: impl Into<String>,
+        value: impl Into<String>,
+    ) -> crate::Result<Value> {
+        self.send(Setvalue::new(selector, value)).await
+    }
+
+    /// Send a `dispatch` command with its required fields; use `send(Dispatch::new(..))` to set others
+    pub async fn dispatch(
+        &self,
+        selector: impl Into<String>,
+        event: impl Into<String>,
+    ) -> crate::Result<Value> {
+        self.send(Dispatch::new(selector, event)).await
+    }
+
+    /// Send a `evalhandle` command with its required fields; use `send(Evalhandle::new(..))` to set others
+    pub async fn evalhandle(&self, script: impl Into<String>) -> crate::Result<Value> {
+        self.send(Evalhandle::new(script)).await
+    }
+
+    /// Send a `expose` command with its required fields; use `send(Expose::new(..))` to set others
+    pub async fn expose(&self, name: impl Into<String>) -> crate::Result<Value> {
+        self.send(Expose::new(name)).await
+    }
+
+    /// Send a `addscript` command with its required fields; use `send(Addscript::new(..))` to set others
+    pub async fn addscript(&self) -> crate::Result<Value> {
+        self.send(Addscript::new()).await
+    }
+
+    /// Send a `addstyle` command with its required fields; use `send(Addstyle::new(..))` to set others
+    pub async fn addstyle(&self) -> crate::Result<Value> {
+        self.send(Addstyle::new()).await
+    }
+
+    /// Send a `emulatemedia` command with its required fields; use `send(Emulatemedia::new(..))` to set others
+    pub async fn emulatemedia(&self) -> crate::Result<Value> {
+        self.send(Emulatemedia::new()).await
+    }
+
+    /// Send a `offline` command with its required fields; use `send(Offline::new(..))` to set others
+    pub async fn offline(&self, offline: bool) -> crate::Result<Value> {
+        self.send(Offline::new(offline)).await
+    }
+
+    /// Send a `headers` command with its required fields; use `send(Headers::new(..))` to set others
+    pub async fn headers(&self, headers: HashMap<String, String>) -> crate::Result<Value> {
+        self.send(Headers::new(headers)).await
+    }
+
+    /// Send a `pause` command with its required fields; use `send(Pause::new(..))` to set others
+    pub async fn pause(&self) -> crate::Result<Value> {
+        self.send(Pause::new()).await
+    }
+
+    /// Send a `getbyalttext` command with its required fields; use `send(Getbyalttext::new(..))` to set others
+    pub async fn getbyalttext(
+        &self,
+        text: impl Into<String>,
+        subaction: GetbyalttextSubaction,
+    ) -> crate::Result<Value> {
+        self.send(Getbyalttext::new(text, subaction)).await
+    }
+
+    /// Send a `getbytitle` command with its required fields; use `send(Getbytitle::new(..))` to set others
+    pub async fn getbytitle(
+        &self,
+        text: impl Into<String>,
+        subaction: GetbytitleSubaction,
+    ) -> crate::Result<Value> {
+        self.send(Getbytitle::new(text, subaction)).await
+    }
+
+    /// Send a `getbytestid` command with its required fields; use `send(Getbytestid::new(..))` to set others
+    pub async fn getbytestid(
+        &self,
+        test_id: impl Into<String>,
+        subaction: GetbytestidSubaction,
+    ) -> crate::Result<Value> {
+        self.send(Getbytestid::new(test_id, subaction)).await
+    }
+
+    /// Send a `nth` command with its required fields; use `send(Nth::new(..))` to set others
+    pub async fn nth(
+        &self,
+        selector: impl Into<String>,
+        index: f64,
+        subaction: NthSubaction,
+    ) -> crate::Result<Value> {
+        self.send(Nth::new(selector, index, subaction)).await
+    }
+
+    /// Send a `waitforurl` command with its required fields; use `send(Waitforurl::new(..))` to set others
+    pub async fn waitforurl(&self, url: impl Into<String>) -> crate::Result<Value> {
+        self.send(Waitforurl::new(url)).await
+    }
+
+    /// Send a `waitforloadstate` command with its required fields; use `send(Waitforloadstate::new(..))` to set others
+    pub async fn waitforloadstate(&self, state: WaitforloadstateState) -> crate::Result<Value> {
+        self.send(Waitforloadstate::new(state)).await
+    }
+
+    /// Send a `setcontent` command with its required fields; use `send(Setcontent::new(..))` to set others
+    pub async fn setcontent(&self, html: impl Into<String>) -> crate::Result<Value> {
+        self.send(Setcontent::new(html)).await
+    }
+
+    /// Send a `timezone` command with its required fields; use `send(Timezone::new(..))` to set others
+    pub async fn timezone(&self, timezone: impl Into<String>) -> crate::Result<Value> {
+        self.send(Timezone::new(timezone)).await
+    }
+
+    /// Send a `locale` command with its required fields; use `send(Locale::new(..))` to set others
+    pub async fn locale(&self, locale: impl Into<String>) -> crate::Result<Value> {
+        self.send(Locale::new(locale)).await
+    }
+
+    /// Send a `credentials` command with its required fields; use `send(Credentials::new(..))` to set others
+    pub async fn credentials(
+        &self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> crate::Result<Value> {
+        self.send(Credentials::new(username, password)).await
+    }
+
+    /// Send a `mousemove` command with its required fields; use `send(Mousemove::new(..))` to set others
+    pub async fn mousemove(&self, x: f64, y: f64) -> crate::Result<Value> {
+        self.send(Mousemove::new(x, y)).await
+    }
+
+    /// Send a `mousedown` command with its required fields; use `send(Mousedown::new(..))` to set others
+    pub async fn mousedown(&self) -> crate::Result<Value> {
+        self.send(Mousedown::new()).await
+    }
+
+    /// Send a `mouseup` command with its required fields; use `send(Mouseup::new(..))` to set others
+    pub async fn mouseup(&self) -> crate::Result<Value> {
+        self.send(Mouseup::new()).await
+    }
+
+    /// Send a `bringtofront` command with its required fields; use `send(Bringtofront::new(..))` to set others
+    pub async fn bringtofront(&self) -> crate::Result<Value> {
+        self.send(Bringtofront::new()).await
+    }
+
+    /// Send a `waitforfunction` command with its required fields; use `send(Waitforfunction::new(..))` to set others
+    pub async fn waitforfunction(&self, expression: impl Into<String>) -> crate::Result<Value> {
+        self.send(Waitforfunction::new(expression)).await
+    }
+
+    /// Send a `scrollintoview` command with its required fields; use `send(Scrollintoview::new(..))` to set others
+    pub async fn scrollintoview(&self, selector: impl Into<String>) -> crate::Result<Value> {
+        self.send(Scrollintoview::new(selector)).await
+    }
+
+    /// Send a `addinitscript` command with its required fields; use `send(Addinitscript::new(..))` to set others
+    pub async fn addinitscript(&self, script: impl Into<String>) -> crate::Result<Value> {
+        self.send(Addinitscript::new(script)).await
+    }
+
+    /// Send a `keydown` command with its required fields; use `send(Keydown::new(..))` to set others
+    pub async fn keydown(&self, key: impl Into<String>) -> crate::Result<Value> {
+        self.send(Keydown::new(key)).await
+    }
+
+    /// Send a `keyup` command with its required fields; use `send(Keyup::new(..))` to set others
+    pub async fn keyup(&self, key: impl Into<String>) -> crate::Result<Value> {
+        self.send(Keyup::new(key)).await
+    }
+
+    /// Send a `inserttext` command with its required fields; use `send(Inserttext::new(..))` to set others
+    pub async fn inserttext(&self, text: impl Into<String>) -> crate::Result<Value> {
+        self.send(Inserttext::new(text)).await
+    }
+
+    /// Send a `multiselect` command with its required fields; use `send(Multiselect::new(..))` to set others
+    pub async fn multiselect(
+        &self,
+        selector: impl Into<String>,
+        values: Vec<String>,
+    ) -> crate::Result<Value> {
+        self.send(Multiselect::new(selector, values)).await
+    }
+
+    /// Send a `waitfordownload` command with its required fields; use `send(Waitfordownload::new(..))` to set others
+    pub async fn waitfordownload(&self) -> crate::Result<Value> {
+        self.send(Waitfordownload::new()).await
+    }
+
+    /// Send a `waitforpopup` command with its required fields; use `send(Waitforpopup::new(..))` to set others
+    pub async fn waitforpopup(&self) -> crate::Result<Value> {
+        self.send(Waitforpopup::new()).await
+    }
+
+    /// Send a `waitforrequest` command with its required fields; use `send(Waitforrequest::new(..))` to set others
+    pub async fn waitforrequest(&self, url: impl Into<String>) -> crate::Result<Value> {
+        self.send(Waitforrequest::new(url)).await
+    }
+
+    /// Send a `responsebody` command with its required fields; use `send(Responsebody::new(..))` to set others
+    pub async fn responsebody(&self, url: impl Into<String>) -> crate::Result<Value> {
+        self.send(Responsebody::new(url)).await
+    }
+
+    /// Send a `screencast_start` command with its required fields; use `send(ScreencastStart::new(..))` to set others
+    pub async fn screencast_start(&self) -> crate::Result<Value> {
+        self.send(ScreencastStart::new()).await
+    }
+
+    /// Send a `screencast_stop` command with its required fields; use `send(ScreencastStop::new(..))` to set others
+    pub async fn screencast_stop(&self) -> crate::Result<Value> {
+        self.send(ScreencastStop::new()).await
+    }
+
+    /// Send a `input_mouse` command with its required fields; use `send(InputMouse::new(..))` to set others
+    pub async fn input_mouse(
+        &self,
+        r#type: InputMouseType,
+        x: f64,
+        y: f64,
+    ) -> crate::Result<Value> {
+        self.send(InputMouse::new(r#type, x, y)).await
+    }
+
+    /// Send a `input_keyboard` command with its required fields; use `send(InputKeyboard::new(..))` to set others
+    pub async fn input_keyboard(&self, r#type: InputKeyboardType) -> crate::Result<Value> {
+        self.send(InputKeyboard::new(r#type)).await
+    }
+
+    /// Send a `input_touch` command with its required fields; use `send(InputTouch::new(..))` to set others
+    pub async fn input_touch(
+        &self,
+        r#type: InputTouchType,
+        touch_points: Vec<InputTouchTouchPoints>,
+    ) -> crate::Result<Value> {
+        self.send(InputTouch::new(r#type, touch_points)).await
+    }
+
+    /// Send a `swipe` command with its required fields; use `send(Swipe::new(..))` to set others
+    pub async fn swipe(&self, direction: SwipeDirection) -> crate::Result<Value> {
+        self.send(Swipe::new(direction)).await
+    }
+
+    /// Send a `device_list` command with its required fields; use `send(DeviceList::new(..))` to set others
+    pub async fn device_list(&self) -> crate::Result<Value> {
+        self.send(DeviceList::new()).await
+    }
+}
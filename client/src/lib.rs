@@ -0,0 +1,208 @@
+//! Typed Rust client for the agent-browser daemon.
+//!
+//! Each session runs a daemon that owns its browser and listens on a Unix
+//! socket (a localhost TCP port on Windows). [`Client`] sends it commands, one
+//! connection per command, so a client can be shared and used concurrently.
+//!
+//! The command types in [`commands`] are generated from the schemas the
+//! daemon validates commands with, by `scripts/generate-client.js`. Every
+//! action has a struct with a constructor taking its required fields and a
+//! setter per optional one, and a [`Client`] method of the same name:
+//!
+//! ```no_run
+//! # #[cfg(feature = "native")]
+//! use agent_browser_client::{commands::Snapshot, Client};
+//!
+//! # #[cfg(feature = "native")]
+//! # async fn example() -> agent_browser_client::Result<()> {
+//! let client = Client::new("default");
+//! client.navigate("https://example.com").await?;
+//! let snapshot = client.send(Snapshot::new().interactive(true)).await?;
+//! println!("{}", snapshot["snapshot"]);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Client`] and its tokio runtime come with the default `native` feature.
+//! Without it, the crate is the command types, the wire format and
+//! [`remote::RemoteClient`], which only need `std` and build for
+//! `wasm32-wasip2`, so edge and serverless runtimes can drive a daemon
+//! listening on TCP elsewhere.
+
+pub mod commands;
+#[cfg(feature = "native")]
+mod native;
+pub mod remote;
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[cfg(feature = "native")]
+pub use native::{sessions, Client, EventStream};
+
+/// A command the daemon accepts, sent with `Client::send`
+pub trait Command: Serialize {
+    const ACTION: &'static str;
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// The daemon couldn't be reached, or the connection failed
+    Io(std::io::Error),
+    /// The daemon didn't answer in time
+    Timeout,
+    /// The daemon sent something that isn't a response
+    Protocol(String),
+    /// The daemon ran the command and it failed
+    Command {
+        message: String,
+        /// Stable error code, e.g. `E_SELECTOR_NOT_FOUND`
+        code: Option<String>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "daemon connection failed: {}", e),
+            Error::Timeout => write!(f, "daemon did not respond in time"),
+            Error::Protocol(message) => write!(f, "invalid response from daemon: {}", message),
+            Error::Command {
+                message,
+                code: Some(code),
+            } => write!(f, "{} ({})", message, code),
+            Error::Command { message, .. } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Deserialize)]
+struct Response {
+    success: bool,
+    data: Option<Value>,
+    error: Option<String>,
+    code: Option<String>,
+}
+
+impl Response {
+    fn into_result(self) -> Result<Value> {
+        if self.success {
+            Ok(self.data.unwrap_or(Value::Null))
+        } else {
+            Err(Error::Command {
+                message: self.error.unwrap_or_else(|| "Unknown error".to_string()),
+                code: self.code,
+            })
+        }
+    }
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A command as the JSON line the daemon reads
+fn request_line(action: &str, mut request: Map<String, Value>) -> Result<Vec<u8>> {
+    let id = format!("c{}", NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    request.insert("id".to_string(), Value::String(id));
+    request.insert("action".to_string(), Value::String(action.to_string()));
+    let mut line = serde_json::to_vec(&request).map_err(|e| Error::Protocol(e.to_string()))?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+/// What a line from the daemon is: one event of a stream, or the response
+enum Message {
+    Event(Value),
+    Response(Result<Value>),
+}
+
+fn parse_message(line: &str) -> Result<Message> {
+    let mut message: Value =
+        serde_json::from_str(line).map_err(|e| Error::Protocol(e.to_string()))?;
+    if let Some(event) = message.get_mut("event") {
+        return Ok(Message::Event(event.take()));
+    }
+    let response: Response =
+        serde_json::from_value(message).map_err(|e| Error::Protocol(e.to_string()))?;
+    Ok(Message::Response(response.into_result()))
+}
+
+fn closed() -> Error {
+    Error::Protocol("daemon closed the connection".to_string())
+}
+
+fn command_fields<C: Command>(cmd: &C) -> Result<Map<String, Value>> {
+    match serde_json::to_value(cmd) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) => Err(Error::Protocol("commands must be JSON objects".to_string())),
+        Err(e) => Err(Error::Protocol(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::commands::ACTIONS;
+    use super::*;
+
+    #[test]
+    fn test_wire_format() {
+        let mut fields = Map::new();
+        fields.insert("url".to_string(), "https://example.com".into());
+        let line = request_line("navigate", fields).unwrap();
+        assert_eq!(line.last(), Some(&b'\n'));
+        let request: Value = serde_json::from_slice(&line).unwrap();
+        assert_eq!(request["action"], "navigate");
+        assert!(request["id"].as_str().unwrap().starts_with('c'));
+
+        let event = parse_message(r#"{"id":"c1","event":{"type":"childList"}}"#).unwrap();
+        assert!(matches!(event, Message::Event(e) if e["type"] == "childList"));
+        let failed = r#"{"id":"c1","success":false,"error":"Nope","code":"E_TIMEOUT"}"#;
+        match parse_message(failed).unwrap() {
+            Message::Response(Err(Error::Command { code, .. })) => {
+                assert_eq!(code.as_deref(), Some("E_TIMEOUT"))
+            }
+            _ => panic!("expected a failed response"),
+        }
+        assert!(parse_message("not json").is_err());
+    }
+
+    /// commands.rs must be regenerated when src/protocol.ts gains an action
+    #[test]
+    fn test_commands_match_protocol() {
+        let protocol = concat!(env!("CARGO_MANIFEST_DIR"), "/../src/protocol.ts");
+        let Ok(source) = std::fs::read_to_string(protocol) else {
+            return;
+        };
+        let mut missing = Vec::new();
+        for part in source.split("action: z.literal('").skip(1) {
+            let action = &part[..part.find('\'').unwrap()];
+            if !ACTIONS.contains(&action) {
+                missing.push(action);
+            }
+        }
+        assert!(
+            missing.is_empty(),
+            "run `node scripts/generate-client.js`; missing: {:?}",
+            missing
+        );
+    }
+}
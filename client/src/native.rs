@@ -0,0 +1,323 @@
+//! The tokio client for daemons on this machine, found by session name.
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+use crate::{closed, command_fields, parse_message, request_line, Command, Error, Message, Result};
+
+type Reader = BufReader<Box<dyn AsyncRead + Unpin + Send>>;
+type Writer = Box<dyn AsyncWrite + Unpin + Send>;
+
+/// A session's daemon
+#[derive(Debug, Clone)]
+pub struct Client {
+    session: String,
+    socket_dir: PathBuf,
+    timeout: Duration,
+}
+
+impl Client {
+    /// A client for the named session, found where the CLI puts it
+    pub fn new(session: impl Into<String>) -> Self {
+        Client {
+            session: session.into(),
+            socket_dir: socket_dir(),
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Look for the session's socket in this directory instead
+    pub fn socket_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.socket_dir = dir.into();
+        self
+    }
+
+    /// How long to wait for a response; 30 seconds by default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    /// Start the session's daemon unless it's running, with the `agent-browser`
+    /// CLI (or the one `AGENT_BROWSER_BIN` points to). The browser isn't
+    /// opened until a command needs it.
+    pub async fn start(&self) -> Result<()> {
+        if self.connect().await.is_ok() {
+            return Ok(());
+        }
+        let bin = env::var_os("AGENT_BROWSER_BIN").unwrap_or_else(|| "agent-browser".into());
+        let output = tokio::process::Command::new(bin)
+            .args(["--session", &self.session, "which"])
+            .env("AGENT_BROWSER_SOCKET_DIR", &self.socket_dir)
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(Error::Io(std::io::Error::other(format!(
+                "agent-browser could not start the daemon: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))));
+        }
+        Ok(())
+    }
+
+    /// Run a command and return its response's `data`
+    pub async fn send<C: Command>(&self, cmd: C) -> Result<Value> {
+        self.send_raw(C::ACTION, command_fields(&cmd)?).await
+    }
+
+    /// Run a command and deserialize its response's `data`
+    pub async fn send_as<C: Command, T: DeserializeOwned>(&self, cmd: C) -> Result<T> {
+        let data = self.send(cmd).await?;
+        serde_json::from_value(data).map_err(|e| Error::Protocol(e.to_string()))
+    }
+
+    /// Run a command given by its action and fields, for callers that build
+    /// commands at runtime, like the language bindings
+    pub async fn send_raw(&self, action: &str, fields: Map<String, Value>) -> Result<Value> {
+        // Keep the write half open: the daemon ends the connection when it closes
+        let (mut reader, _writer) = self.open(action, fields).await?;
+        let message = tokio::time::timeout(self.timeout, read_message(&mut reader))
+            .await
+            .map_err(|_| Error::Timeout)??;
+        match message {
+            Message::Response(result) => result,
+            Message::Event(_) => Err(Error::Protocol(format!(
+                "{} streams events; use stream()",
+                action
+            ))),
+        }
+    }
+
+    /// Run a streaming command (`watch`, `poll`, `cdp_listen`) and read its
+    /// events as they arrive. There is no timeout, since a stream can stay
+    /// quiet for as long as the page does.
+    pub async fn stream<C: Command>(&self, cmd: C) -> Result<EventStream> {
+        self.stream_raw(C::ACTION, command_fields(&cmd)?).await
+    }
+
+    /// [`Client::stream`] for a command given by its action and fields
+    pub async fn stream_raw(
+        &self,
+        action: &str,
+        fields: Map<String, Value>,
+    ) -> Result<EventStream> {
+        let (reader, writer) = self.open(action, fields).await?;
+        Ok(EventStream {
+            reader,
+            _writer: writer,
+            result: None,
+        })
+    }
+
+    async fn open(&self, action: &str, request: Map<String, Value>) -> Result<(Reader, Writer)> {
+        let line = request_line(action, request)?;
+        let (reader, mut writer) = self.connect().await?;
+        writer.write_all(&line).await?;
+        Ok((BufReader::new(reader), writer))
+    }
+
+    #[cfg(unix)]
+    async fn connect(&self) -> Result<(Box<dyn AsyncRead + Unpin + Send>, Writer)> {
+        let path = self.socket_dir.join(format!("{}.sock", self.session));
+        let (reader, writer) = tokio::net::UnixStream::connect(path).await?.into_split();
+        Ok((Box::new(reader), Box::new(writer)))
+    }
+
+    #[cfg(windows)]
+    async fn connect(&self) -> Result<(Box<dyn AsyncRead + Unpin + Send>, Writer)> {
+        let port = port_for_session(&self.session);
+        let (reader, writer) = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await?
+            .into_split();
+        Ok((Box::new(reader), Box::new(writer)))
+    }
+}
+
+/// Events from a streaming command, then its final response
+pub struct EventStream {
+    reader: Reader,
+    // Closing the connection stops the stream, so it's kept until dropped
+    _writer: Writer,
+    result: Option<Result<Value>>,
+}
+
+impl EventStream {
+    /// The next event, or None once the daemon has sent its final response
+    pub async fn next(&mut self) -> Result<Option<Value>> {
+        if self.result.is_some() {
+            return Ok(None);
+        }
+        match read_message(&mut self.reader).await? {
+            Message::Event(event) => Ok(Some(event)),
+            Message::Response(result) => {
+                self.result = Some(result);
+                Ok(None)
+            }
+        }
+    }
+
+    /// The final response's `data`, reading past any events left
+    pub async fn finish(mut self) -> Result<Value> {
+        while self.next().await?.is_some() {}
+        self.result.take().unwrap_or(Ok(Value::Null))
+    }
+}
+
+async fn read_message(reader: &mut Reader) -> Result<Message> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+        return Err(closed());
+    }
+    parse_message(&line)
+}
+
+/// Names of the sessions whose daemon accepts connections
+pub async fn sessions() -> Vec<String> {
+    let dir = socket_dir();
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut sessions = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(session) = name.strip_suffix(".pid").filter(|s| !s.is_empty()) {
+            let client = Client::new(session).socket_dir(&dir);
+            if client.connect().await.is_ok() {
+                sessions.push(session.to_string());
+            }
+        }
+    }
+    sessions.sort();
+    sessions
+}
+
+/// Where the CLI keeps daemon sockets: AGENT_BROWSER_SOCKET_DIR, else
+/// XDG_RUNTIME_DIR/agent-browser, else ~/.agent-browser
+fn socket_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("AGENT_BROWSER_SOCKET_DIR").filter(|d| !d.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    if let Some(dir) = env::var_os("XDG_RUNTIME_DIR").filter(|d| !d.is_empty()) {
+        return PathBuf::from(dir).join("agent-browser");
+    }
+    let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"));
+    match home.filter(|h| !h.is_empty()) {
+        Some(home) => PathBuf::from(home).join(".agent-browser"),
+        None => env::temp_dir().join("agent-browser"),
+    }
+}
+
+/// The daemon's TCP port on Windows, derived from the session name like the CLI does
+#[cfg(windows)]
+fn port_for_session(session: &str) -> u16 {
+    let mut hash: i32 = 0;
+    for c in session.chars() {
+        hash = ((hash << 5).wrapping_sub(hash)).wrapping_add(c as i32);
+    }
+    49152 + ((hash.unsigned_abs() % 16383) as u16)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::commands::{Snapshot, Watch};
+    use tokio::net::UnixListener;
+
+    /// A daemon that answers each request line with `reply(request)`
+    fn fake_daemon(
+        name: &str,
+        reply: impl Fn(Value) -> Vec<Value> + Send + 'static,
+    ) -> (Client, PathBuf) {
+        let dir = env::temp_dir().join(format!("ab-client-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.sock");
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (read, mut write) = stream.into_split();
+                let mut lines = BufReader::new(read).lines();
+                if let Ok(Some(line)) = lines.next_line().await {
+                    for message in reply(serde_json::from_str(&line).unwrap()) {
+                        let mut out = message.to_string();
+                        out.push('\n');
+                        write.write_all(out.as_bytes()).await.unwrap();
+                    }
+                }
+            }
+        });
+        (Client::new("test").socket_dir(&dir), dir)
+    }
+
+    #[tokio::test]
+    async fn test_send_typed_command() {
+        let (client, dir) = fake_daemon("send", |request| {
+            vec![serde_json::json!({
+                "id": request["id"],
+                "success": true,
+                "data": { "echo": request },
+            })]
+        });
+        let data = client
+            .send(Snapshot::new().interactive(true).max_depth(3.0))
+            .await
+            .unwrap();
+        let echo = &data["echo"];
+        assert_eq!(echo["action"], "snapshot");
+        assert_eq!(echo["interactive"], true);
+        assert_eq!(echo["maxDepth"], 3.0);
+        assert!(echo.get("compact").is_none());
+
+        let url = client.navigate("https://example.com").await.unwrap();
+        assert_eq!(url["echo"]["url"], "https://example.com");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_command_error() {
+        let (client, dir) = fake_daemon("error", |request| {
+            vec![serde_json::json!({
+                "id": request["id"],
+                "success": false,
+                "error": "Element not found",
+                "code": "E_SELECTOR_NOT_FOUND",
+            })]
+        });
+        match client.click("@e9").await {
+            Err(Error::Command { message, code }) => {
+                assert_eq!(message, "Element not found");
+                assert_eq!(code.as_deref(), Some("E_SELECTOR_NOT_FOUND"));
+            }
+            other => panic!("expected a command error, got {:?}", other),
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_stream_events() {
+        let (client, dir) = fake_daemon("stream", |request| {
+            let id = request["id"].clone();
+            vec![
+                serde_json::json!({ "id": id, "event": { "type": "childList" } }),
+                serde_json::json!({ "id": id, "event": { "type": "attributes" } }),
+                serde_json::json!({ "id": id, "success": true, "data": { "count": 2 } }),
+            ]
+        });
+        let mut stream = client.stream(Watch::new("#feed").count(2)).await.unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap()["type"], "childList");
+        assert_eq!(stream.next().await.unwrap().unwrap()["type"], "attributes");
+        assert_eq!(stream.finish().await.unwrap()["count"], 2);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,243 @@
+//! A blocking client for a daemon listening on TCP, for runtimes without
+//! tokio or local sockets, such as `wasm32-wasip2`.
+//!
+//! The daemon listens on TCP when started with `AGENT_BROWSER_LISTEN_PORT`
+//! set. If it also has `AGENT_BROWSER_LISTEN_TOKEN`, every command must carry
+//! that token, which [`RemoteClient::token`] adds.
+//!
+//! ```no_run
+//! use agent_browser_client::{commands::Navigate, remote::RemoteClient};
+//!
+//! # fn example() -> agent_browser_client::Result<()> {
+//! let client = RemoteClient::new("browser.internal:7070").token("secret");
+//! client.send(Navigate::new("https://example.com"))?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::{closed, command_fields, parse_message, request_line, Command, Error, Message, Result};
+
+/// A daemon reached over TCP
+#[derive(Debug, Clone)]
+pub struct RemoteClient {
+    address: String,
+    token: Option<String>,
+    timeout: Duration,
+}
+
+impl RemoteClient {
+    /// A client for the daemon at `host:port`
+    pub fn new(address: impl Into<String>) -> Self {
+        RemoteClient {
+            address: address.into(),
+            token: None,
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Send this token with every command, for a daemon started with
+    /// `AGENT_BROWSER_LISTEN_TOKEN`
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// How long to wait for a response; 30 seconds by default
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Run a command and return its response's `data`
+    pub fn send<C: Command>(&self, cmd: C) -> Result<Value> {
+        self.send_raw(C::ACTION, command_fields(&cmd)?)
+    }
+
+    /// Run a command and deserialize its response's `data`
+    pub fn send_as<C: Command, T: DeserializeOwned>(&self, cmd: C) -> Result<T> {
+        let data = self.send(cmd)?;
+        serde_json::from_value(data).map_err(|e| Error::Protocol(e.to_string()))
+    }
+
+    /// Run a command given by its action and fields
+    pub fn send_raw(&self, action: &str, fields: Map<String, Value>) -> Result<Value> {
+        let mut reader = self.open(action, fields)?;
+        reader.get_ref().set_read_timeout(Some(self.timeout))?;
+        match read_message(&mut reader).map_err(timed_out)? {
+            Message::Response(result) => result,
+            Message::Event(_) => Err(Error::Protocol(format!(
+                "{} streams events; use stream()",
+                action
+            ))),
+        }
+    }
+
+    /// Run a streaming command and read its events as they arrive, with no
+    /// timeout
+    pub fn stream<C: Command>(&self, cmd: C) -> Result<RemoteEventStream> {
+        self.stream_raw(C::ACTION, command_fields(&cmd)?)
+    }
+
+    /// [`RemoteClient::stream`] for a command given by its action and fields
+    pub fn stream_raw(
+        &self,
+        action: &str,
+        fields: Map<String, Value>,
+    ) -> Result<RemoteEventStream> {
+        Ok(RemoteEventStream {
+            reader: self.open(action, fields)?,
+            result: None,
+        })
+    }
+
+    fn open(&self, action: &str, mut fields: Map<String, Value>) -> Result<BufReader<TcpStream>> {
+        if let Some(token) = &self.token {
+            fields.insert("token".to_string(), Value::String(token.clone()));
+        }
+        let line = request_line(action, fields)?;
+        let mut stream = TcpStream::connect(&self.address)?;
+        stream.write_all(&line)?;
+        Ok(BufReader::new(stream))
+    }
+}
+
+/// Events from a streaming command, then its final response
+pub struct RemoteEventStream {
+    reader: BufReader<TcpStream>,
+    result: Option<Result<Value>>,
+}
+
+/// Yields each event; the stream ends once the daemon has sent its final
+/// response, which [`RemoteEventStream::finish`] returns
+impl Iterator for RemoteEventStream {
+    type Item = Result<Value>;
+
+    fn next(&mut self) -> Option<Result<Value>> {
+        if self.result.is_some() {
+            return None;
+        }
+        match read_message(&mut self.reader) {
+            Ok(Message::Event(event)) => Some(Ok(event)),
+            Ok(Message::Response(result)) => {
+                self.result = Some(result);
+                None
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl RemoteEventStream {
+    /// The final response's `data`, reading past any events left
+    pub fn finish(mut self) -> Result<Value> {
+        for event in self.by_ref() {
+            event?;
+        }
+        self.result.take().unwrap_or(Ok(Value::Null))
+    }
+}
+
+fn read_message(reader: &mut BufReader<TcpStream>) -> Result<Message> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(closed());
+    }
+    parse_message(&line)
+}
+
+/// A read that hit the socket's timeout is the daemon not answering in time
+fn timed_out(e: Error) -> Error {
+    match e {
+        Error::Io(io)
+            if matches!(
+                io.kind(),
+                std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+            ) =>
+        {
+            Error::Timeout
+        }
+        e => e,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{Snapshot, Watch};
+    use std::net::TcpListener;
+
+    /// A daemon on a local port that answers each request line with `reply(request)`
+    fn fake_daemon(reply: impl Fn(Value) -> Vec<Value> + Send + 'static) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut write = stream.try_clone().unwrap();
+                let mut line = String::new();
+                if BufReader::new(stream).read_line(&mut line).unwrap_or(0) == 0 {
+                    continue;
+                }
+                for message in reply(serde_json::from_str(&line).unwrap()) {
+                    let mut out = message.to_string();
+                    out.push('\n');
+                    write.write_all(out.as_bytes()).unwrap();
+                }
+            }
+        });
+        address
+    }
+
+    #[test]
+    fn test_send_with_token() {
+        let address = fake_daemon(|request| {
+            if request["token"] != "secret" {
+                return vec![serde_json::json!({
+                    "id": request["id"],
+                    "success": false,
+                    "error": "Invalid token",
+                })];
+            }
+            vec![serde_json::json!({
+                "id": request["id"],
+                "success": true,
+                "data": { "echo": request },
+            })]
+        });
+        let client = RemoteClient::new(&address).token("secret");
+        let data = client.send(Snapshot::new().interactive(true)).unwrap();
+        assert_eq!(data["echo"]["action"], "snapshot");
+        assert_eq!(data["echo"]["interactive"], true);
+
+        match RemoteClient::new(&address).send(Snapshot::new()) {
+            Err(Error::Command { message, .. }) => assert_eq!(message, "Invalid token"),
+            other => panic!("expected a command error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_stream_events() {
+        let address = fake_daemon(|request| {
+            let id = request["id"].clone();
+            vec![
+                serde_json::json!({ "id": id, "event": { "type": "childList" } }),
+                serde_json::json!({ "id": id, "success": true, "data": { "count": 1 } }),
+            ]
+        });
+        let mut stream = RemoteClient::new(address)
+            .stream(Watch::new("#feed").count(1))
+            .unwrap();
+        assert_eq!(stream.next().unwrap().unwrap()["type"], "childList");
+        assert_eq!(stream.finish().unwrap()["count"], 1);
+    }
+}
@@ -0,0 +1,86 @@
+//! JSON values to and from `google.protobuf.Value`, which carries command
+//! fields and data over gRPC as the same JSON the daemon speaks.
+
+use prost_types::value::Kind;
+use prost_types::{ListValue, Struct};
+use serde_json::{Map, Number, Value};
+
+pub fn to_proto(value: Value) -> prost_types::Value {
+    let kind = match value {
+        Value::Null => Kind::NullValue(0),
+        Value::Bool(b) => Kind::BoolValue(b),
+        Value::Number(n) => Kind::NumberValue(n.as_f64().unwrap_or(0.0)),
+        Value::String(s) => Kind::StringValue(s),
+        Value::Array(items) => Kind::ListValue(ListValue {
+            values: items.into_iter().map(to_proto).collect(),
+        }),
+        Value::Object(map) => Kind::StructValue(to_struct(map)),
+    };
+    prost_types::Value { kind: Some(kind) }
+}
+
+fn to_struct(map: Map<String, Value>) -> Struct {
+    Struct {
+        fields: map.into_iter().map(|(k, v)| (k, to_proto(v))).collect(),
+    }
+}
+
+pub fn from_proto(value: prost_types::Value) -> Value {
+    match value.kind {
+        None | Some(Kind::NullValue(_)) => Value::Null,
+        Some(Kind::BoolValue(b)) => Value::Bool(b),
+        Some(Kind::NumberValue(n)) => number(n),
+        Some(Kind::StringValue(s)) => Value::String(s),
+        Some(Kind::ListValue(list)) => {
+            Value::Array(list.values.into_iter().map(from_proto).collect())
+        }
+        Some(Kind::StructValue(s)) => Value::Object(from_struct(s)),
+    }
+}
+
+pub fn from_struct(s: Struct) -> Map<String, Value> {
+    s.fields
+        .into_iter()
+        .map(|(k, v)| (k, from_proto(v)))
+        .collect()
+}
+
+/// Protobuf numbers are all doubles; whole ones go back to the daemon as
+/// integers, since schemas like `count` take `z.number().int()`
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 9_007_199_254_740_992.0 {
+        Value::from(n as i64)
+    } else {
+        Number::from_f64(n)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_round_trip() {
+        let value = json!({
+            "url": "https://example.com",
+            "count": 3,
+            "ratio": 0.5,
+            "events": ["childList", "attributes"],
+            "headers": {"X-Test": "1"},
+            "fresh": true,
+            "selector": null,
+        });
+        assert_eq!(from_proto(to_proto(value.clone())), value);
+    }
+
+    #[test]
+    fn test_whole_numbers_become_integers() {
+        assert_eq!(number(5.0), json!(5));
+        assert!(number(5.0).is_i64());
+        assert_eq!(number(-0.25), json!(-0.25));
+        assert_eq!(number(f64::NAN), Value::Null);
+    }
+}
@@ -0,0 +1,202 @@
+//! The gRPC control interface: `proto/agent_browser.proto`'s `AgentBrowser`
+//! service, answered by forwarding each call to the session's daemon with
+//! `agent-browser-client`.
+
+use std::time::Duration;
+
+use agent_browser_client::{Client, Error};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::metadata::MetadataValue;
+use tonic::{Code, Request, Response, Status};
+
+mod convert;
+
+pub mod proto {
+    tonic::include_proto!("agent_browser.v1");
+}
+
+use proto::agent_browser_server::{AgentBrowser, AgentBrowserServer};
+use proto::{
+    event, ActionRequest, ActionResponse, CloseSessionRequest, CloseSessionResponse, Event,
+    ListSessionsRequest, ListSessionsResponse, Session, StartSessionRequest,
+};
+
+/// Events buffered per stream before reading from the daemon pauses
+const STREAM_BUFFER: usize = 16;
+
+/// Metadata key carrying the daemon's error code on failed calls
+pub const ERROR_CODE_KEY: &str = "agent-browser-code";
+
+#[derive(Debug, Default)]
+pub struct AgentBrowserService;
+
+/// The service, refusing calls without `authorization: Bearer <token>` when a
+/// token is set
+pub fn service(
+    token: Option<String>,
+) -> tonic::service::interceptor::InterceptedService<
+    AgentBrowserServer<AgentBrowserService>,
+    impl tonic::service::Interceptor + Clone,
+> {
+    let expected = token.map(|t| format!("Bearer {}", t));
+    AgentBrowserServer::with_interceptor(AgentBrowserService, move |request: Request<()>| {
+        if let Some(expected) = &expected {
+            let given = request.metadata().get("authorization");
+            if given.and_then(|v| v.to_str().ok()) != Some(expected.as_str()) {
+                return Err(Status::unauthenticated("Missing or wrong bearer token"));
+            }
+        }
+        Ok(request)
+    })
+}
+
+/// Session names become socket file names, so callers are kept to plain
+/// names that can't point outside the socket directory
+fn client(name: &str) -> Result<Client, Status> {
+    let name = if name.is_empty() { "default" } else { name };
+    let valid = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(Status::invalid_argument(format!(
+            "Invalid session name '{}': use letters, digits, '-' and '_'",
+            name
+        )));
+    }
+    Ok(Client::new(name))
+}
+
+fn to_status(err: Error) -> Status {
+    match err {
+        Error::Command { message, code } => {
+            let grpc = match code.as_deref() {
+                Some("E_INVALID_COMMAND") => Code::InvalidArgument,
+                Some("E_SELECTOR_NOT_FOUND") => Code::NotFound,
+                Some("E_SELECTOR_AMBIGUOUS" | "E_ELEMENT_NOT_INTERACTABLE") => {
+                    Code::FailedPrecondition
+                }
+                Some("E_TIMEOUT" | "E_NAV_TIMEOUT") => Code::DeadlineExceeded,
+                Some("E_BROWSER_CRASHED") => Code::Unavailable,
+                Some("E_UNSUPPORTED") => Code::Unimplemented,
+                Some("E_CANCELLED") => Code::Cancelled,
+                _ => Code::Unknown,
+            };
+            let mut status = Status::new(grpc, message);
+            if let Some(value) = code.and_then(|c| MetadataValue::try_from(c).ok()) {
+                status.metadata_mut().insert(ERROR_CODE_KEY, value);
+            }
+            status
+        }
+        Error::Timeout => Status::deadline_exceeded(err.to_string()),
+        Error::Io(_) => Status::unavailable(err.to_string()),
+        Error::Protocol(_) => Status::internal(err.to_string()),
+    }
+}
+
+fn action(
+    request: ActionRequest,
+) -> Result<(Client, String, serde_json::Map<String, Value>), Status> {
+    if request.action.is_empty() {
+        return Err(Status::invalid_argument("action is required"));
+    }
+    let mut client = client(&request.session)?;
+    if request.timeout_ms > 0 {
+        client = client.timeout(Duration::from_millis(request.timeout_ms.into()));
+    }
+    let fields = request.fields.map(convert::from_struct).unwrap_or_default();
+    Ok((client, request.action, fields))
+}
+
+#[tonic::async_trait]
+impl AgentBrowser for AgentBrowserService {
+    async fn list_sessions(
+        &self,
+        _request: Request<ListSessionsRequest>,
+    ) -> Result<Response<ListSessionsResponse>, Status> {
+        let sessions = agent_browser_client::sessions()
+            .await
+            .into_iter()
+            .map(|name| Session { name })
+            .collect();
+        Ok(Response::new(ListSessionsResponse { sessions }))
+    }
+
+    async fn start_session(
+        &self,
+        request: Request<StartSessionRequest>,
+    ) -> Result<Response<Session>, Status> {
+        let client = client(&request.into_inner().name)?;
+        client.start().await.map_err(to_status)?;
+        Ok(Response::new(Session {
+            name: client.session().to_string(),
+        }))
+    }
+
+    async fn close_session(
+        &self,
+        request: Request<CloseSessionRequest>,
+    ) -> Result<Response<CloseSessionResponse>, Status> {
+        let client = client(&request.into_inner().name)?;
+        client
+            .send_raw("close", Default::default())
+            .await
+            .map_err(to_status)?;
+        Ok(Response::new(CloseSessionResponse {}))
+    }
+
+    async fn execute(
+        &self,
+        request: Request<ActionRequest>,
+    ) -> Result<Response<ActionResponse>, Status> {
+        let (client, action, fields) = action(request.into_inner())?;
+        let data = client.send_raw(&action, fields).await.map_err(to_status)?;
+        Ok(Response::new(ActionResponse {
+            data: Some(convert::to_proto(data)),
+        }))
+    }
+
+    type StreamStream = ReceiverStream<Result<Event, Status>>;
+
+    async fn stream(
+        &self,
+        request: Request<ActionRequest>,
+    ) -> Result<Response<Self::StreamStream>, Status> {
+        let (client, action, fields) = action(request.into_inner())?;
+        let mut events = client
+            .stream_raw(&action, fields)
+            .await
+            .map_err(to_status)?;
+        // A bounded channel: when the caller falls behind, sends wait, reads
+        // from the daemon stop, and the daemon's socket backs up in turn
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+        tokio::spawn(async move {
+            loop {
+                // Watch for the caller hanging up even while the page is quiet:
+                // returning drops the stream, closing the connection, which
+                // stops the action
+                let next = tokio::select! {
+                    next = events.next() => next,
+                    _ = tx.closed() => return,
+                };
+                let message = match next {
+                    Ok(Some(value)) => Ok(Event {
+                        kind: Some(event::Kind::Event(convert::to_proto(value))),
+                    }),
+                    Ok(None) => break,
+                    Err(err) => Err(to_status(err)),
+                };
+                let failed = message.is_err();
+                if tx.send(message).await.is_err() || failed {
+                    return;
+                }
+            }
+            let result = events.finish().await.map(|data| Event {
+                kind: Some(event::Kind::Result(convert::to_proto(data))),
+            });
+            let _ = tx.send(result.map_err(to_status)).await;
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
@@ -0,0 +1,83 @@
+//! `agent-browser-grpc`: serves the gRPC control interface, configured like
+//! the daemon's other servers through environment variables.
+
+use std::net::SocketAddr;
+use std::process::exit;
+
+use tonic::transport::Server;
+
+const DEFAULT_PORT: u16 = 50051;
+
+fn address() -> Result<SocketAddr, String> {
+    let host = std::env::var("AGENT_BROWSER_GRPC_HOST").unwrap_or_else(|_| "127.0.0.1".into());
+    let port = match std::env::var("AGENT_BROWSER_GRPC_PORT") {
+        Ok(port) => port
+            .parse::<u16>()
+            .map_err(|_| format!("Invalid AGENT_BROWSER_GRPC_PORT: {}", port))?,
+        Err(_) => DEFAULT_PORT,
+    };
+    // Bare IPv6 hosts need brackets to parse with a port
+    let address = if host.contains(':') && !host.starts_with('[') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    };
+    address
+        .parse()
+        .map_err(|_| format!("Invalid AGENT_BROWSER_GRPC_HOST: {}", host))
+}
+
+async fn shutdown() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        if let Ok(mut term) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = term.recv() => {}
+            }
+            return;
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+#[tokio::main]
+async fn main() {
+    if std::env::args().any(|a| a == "--help" || a == "-h") {
+        println!(
+            "agent-browser-grpc: gRPC control interface for agent-browser sessions\n\n\
+             Environment:\n  \
+             AGENT_BROWSER_GRPC_HOST   Address to listen on (default: 127.0.0.1)\n  \
+             AGENT_BROWSER_GRPC_PORT   Port to listen on (default: {})\n  \
+             AGENT_BROWSER_GRPC_TOKEN  Require `authorization: Bearer <token>` metadata",
+            DEFAULT_PORT
+        );
+        return;
+    }
+    let address = match address() {
+        Ok(address) => address,
+        Err(message) => {
+            eprintln!("{}", message);
+            exit(1);
+        }
+    };
+    let token = std::env::var("AGENT_BROWSER_GRPC_TOKEN")
+        .ok()
+        .filter(|t| !t.is_empty());
+    if token.is_none() && !address.ip().is_loopback() {
+        eprintln!(
+            "Warning: serving on {} without AGENT_BROWSER_GRPC_TOKEN",
+            address
+        );
+    }
+    println!("gRPC control interface at {}", address);
+    let served = Server::builder()
+        .add_service(agent_browser_grpc::service(token))
+        .serve_with_shutdown(address, shutdown())
+        .await;
+    if let Err(e) = served {
+        eprintln!("gRPC server failed: {}", e);
+        exit(1);
+    }
+}
@@ -0,0 +1,178 @@
+#![cfg(unix)]
+
+use agent_browser_grpc::proto::agent_browser_client::AgentBrowserClient;
+use agent_browser_grpc::proto::{event, ActionRequest, ListSessionsRequest};
+use agent_browser_grpc::{service, ERROR_CODE_KEY};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, UnixListener};
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::{Channel, Server};
+use tonic::{Code, Request};
+
+/// A daemon for session "test" that echoes commands, fails `click`, and
+/// streams two events for `watch`
+fn fake_daemon() {
+    let dir = std::env::temp_dir().join(format!("ab-grpc-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("test.sock");
+    let _ = std::fs::remove_file(&path);
+    // Set before any client is made; this is the only test in the binary
+    std::env::set_var("AGENT_BROWSER_SOCKET_DIR", &dir);
+    let listener = UnixListener::bind(&path).unwrap();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let (read, mut write) = stream.into_split();
+            let mut lines = BufReader::new(read).lines();
+            let Ok(Some(line)) = lines.next_line().await else {
+                continue;
+            };
+            let request: Value = serde_json::from_str(&line).unwrap();
+            let id = &request["id"];
+            let messages = match request["action"].as_str() {
+                Some("click") => vec![json!({
+                    "id": id, "success": false,
+                    "error": "Element not found", "code": "E_SELECTOR_NOT_FOUND",
+                })],
+                Some("watch") => vec![
+                    json!({ "id": id, "event": { "type": "childList", "added": 1 } }),
+                    json!({ "id": id, "event": { "type": "childList", "added": 2 } }),
+                    json!({ "id": id, "success": true, "data": { "events": 2 } }),
+                ],
+                _ => vec![json!({ "id": id, "success": true, "data": { "echo": request } })],
+            };
+            for message in messages {
+                let _ = write.write_all(format!("{}\n", message).as_bytes()).await;
+            }
+        }
+    });
+}
+
+async fn serve(token: Option<&str>) -> AgentBrowserClient<Channel> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let service = service(token.map(String::from));
+    tokio::spawn(
+        Server::builder()
+            .add_service(service)
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    AgentBrowserClient::connect(format!("http://{}", address))
+        .await
+        .unwrap()
+}
+
+fn request(action: &str, fields: Value) -> ActionRequest {
+    let Value::Object(fields) = fields else {
+        panic!("fields must be an object")
+    };
+    let fields = prost_types::Struct {
+        fields: fields
+            .into_iter()
+            .map(|(k, v)| {
+                let kind = match v {
+                    Value::String(s) => prost_types::value::Kind::StringValue(s),
+                    Value::Number(n) => prost_types::value::Kind::NumberValue(n.as_f64().unwrap()),
+                    other => panic!("unsupported field {}", other),
+                };
+                (k, prost_types::Value { kind: Some(kind) })
+            })
+            .collect(),
+    };
+    ActionRequest {
+        session: "test".to_string(),
+        action: action.to_string(),
+        fields: Some(fields),
+        timeout_ms: 5000,
+    }
+}
+
+fn string(value: &Option<prost_types::Value>, path: &[&str]) -> Option<String> {
+    let mut value = value.as_ref()?;
+    for key in path {
+        match &value.kind {
+            Some(prost_types::value::Kind::StructValue(s)) => value = s.fields.get(*key)?,
+            _ => return None,
+        }
+    }
+    match &value.kind {
+        Some(prost_types::value::Kind::StringValue(s)) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn test_service() {
+    fake_daemon();
+    let mut client = serve(None).await;
+
+    // Execute forwards the action and its fields
+    let response = client
+        .execute(request(
+            "navigate",
+            json!({ "url": "https://example.com", "count": 3 }),
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(
+        string(&response.data, &["echo", "action"]).as_deref(),
+        Some("navigate")
+    );
+    assert_eq!(
+        string(&response.data, &["echo", "url"]).as_deref(),
+        Some("https://example.com")
+    );
+
+    // Failed commands map to gRPC codes and carry the daemon's code
+    let status = client
+        .execute(request("click", json!({ "selector": "@e9" })))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::NotFound);
+    assert_eq!(status.message(), "Element not found");
+    assert_eq!(
+        status.metadata().get(ERROR_CODE_KEY).unwrap(),
+        "E_SELECTOR_NOT_FOUND"
+    );
+
+    // Streams send each event, then the result
+    let mut stream = client
+        .stream(request("watch", json!({ "selector": "#feed" })))
+        .await
+        .unwrap()
+        .into_inner();
+    let mut kinds = Vec::new();
+    while let Some(message) = stream.message().await.unwrap() {
+        kinds.push(match message.kind.unwrap() {
+            event::Kind::Event(_) => "event",
+            event::Kind::Result(_) => "result",
+        });
+    }
+    assert_eq!(kinds, ["event", "event", "result"]);
+
+    // Unreachable sessions and bad names
+    let mut missing = request("snapshot", json!({}));
+    missing.session = "missing".to_string();
+    let status = client.execute(missing).await.unwrap_err();
+    assert_eq!(status.code(), Code::Unavailable);
+    let mut escape = request("snapshot", json!({}));
+    escape.session = "../etc".to_string();
+    let status = client.execute(escape).await.unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+
+    client.list_sessions(ListSessionsRequest {}).await.unwrap();
+
+    // A token is required once set
+    let mut guarded = serve(Some("secret")).await;
+    let status = guarded
+        .execute(request("title", json!({})))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+    let mut authorized = Request::new(request("title", json!({})));
+    authorized
+        .metadata_mut()
+        .insert("authorization", "Bearer secret".parse().unwrap());
+    guarded.execute(authorized).await.unwrap();
+}
@@ -0,0 +1,344 @@
+#!/usr/bin/env node
+
+/**
+ * Generates client/src/commands.rs, the typed commands of the Rust client,
+ * from the zod schemas the daemon validates commands with (src/protocol.ts).
+ * Run it after changing a command schema; client tests fail when an action
+ * is missing.
+ *
+ * The schemas are read as source text rather than imported, so this needs no
+ * build or installed dependencies. Shapes it doesn't recognize (unions,
+ * z.unknown()) become serde_json::Value.
+ */
+
+import { execSync } from "child_process";
+import { readFileSync, writeFileSync } from "fs";
+import { dirname, join } from "path";
+import { fileURLToPath } from "url";
+
+const __dirname = dirname(fileURLToPath(import.meta.url));
+const rootDir = join(__dirname, "..");
+const protocolPath = join(rootDir, "src", "protocol.ts");
+const outPath = join(rootDir, "client", "src", "commands.rs");
+
+const RUST_KEYWORDS = new Set([
+  "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+  "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+  "mut", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true", "type",
+  "unsafe", "use", "where", "while",
+]);
+
+// --- Scanning ---------------------------------------------------------------
+
+/**
+ * Calls visit(i) for each character outside strings, regexes and comments,
+ * and returns the source with comments removed.
+ */
+function scan(src, visit = () => {}) {
+  let out = "";
+  let i = 0;
+  let last = "";
+  while (i < src.length) {
+    const c = src[i];
+    if (c === "/" && src[i + 1] === "/") {
+      while (i < src.length && src[i] !== "\n") i++;
+      continue;
+    }
+    if (c === "/" && src[i + 1] === "*") {
+      const end = src.indexOf("*/", i + 2);
+      i = end < 0 ? src.length : end + 2;
+      continue;
+    }
+    if (c === "'" || c === '"' || c === "`" || (c === "/" && /[(,=:[!&|?{};]/.test(last))) {
+      const start = i++;
+      while (i < src.length && src[i] !== c) i += src[i] === "\\" ? 2 : 1;
+      i++;
+      if (c === "/") while (/[a-z]/.test(src[i] ?? "")) i++;
+      out += src.slice(start, i);
+      last = c;
+      continue;
+    }
+    visit(out.length, c);
+    out += c;
+    if (!/\s/.test(c)) last = c;
+    i++;
+  }
+  return out;
+}
+
+/** Splits on a separator that's outside brackets, strings and regexes */
+function splitTopLevel(src, sep) {
+  const parts = [];
+  let depth = 0;
+  let start = 0;
+  const clean = scan(src, (i, c) => {
+    if ("([{".includes(c)) depth++;
+    else if (")]}".includes(c)) depth--;
+    else if (c === sep && depth === 0) {
+      parts.push([start, i]);
+      start = i + 1;
+    }
+  });
+  parts.push([start, clean.length]);
+  return parts.map(([a, b]) => clean.slice(a, b).trim()).filter((p) => p !== "");
+}
+
+/** Index of the bracket closing the one at `open` */
+function closing(src, open) {
+  let depth = 0;
+  let found = -1;
+  scan(src.slice(open), (i, c) => {
+    if (found >= 0) return;
+    if ("([{".includes(c)) depth++;
+    else if (")]}".includes(c) && --depth === 0) found = open + i;
+  });
+  return found;
+}
+
+/**
+ * An expression as its head and method calls: `z.string().min(1)` is
+ * { head: "z", calls: [["string", ""], ["min", "1"]] }.
+ */
+function parseChain(expr) {
+  const head = expr.match(/^[\w$]+/)?.[0] ?? expr;
+  const calls = [];
+  let i = head.length;
+  while (i < expr.length) {
+    const m = expr.slice(i).match(/^\s*\.\s*([\w$]+)\s*/);
+    if (!m) break;
+    i += m[0].length;
+    if (expr[i] === "(") {
+      const end = closing(expr, i);
+      calls.push([m[1], expr.slice(i + 1, end).trim()]);
+      i = end + 1;
+    } else {
+      calls.push([m[1], null]);
+    }
+  }
+  return { head, calls };
+}
+
+/** `{ a: x, b: y }` as [["a", "x"], ["b", "y"]] */
+function objectEntries(src) {
+  const body = src.trim().replace(/^\{/, "").replace(/\}$/, "");
+  return splitTopLevel(body, ",").map((entry) => {
+    const colon = entry.indexOf(":");
+    return [entry.slice(0, colon).trim().replace(/^['"]|['"]$/g, ""), entry.slice(colon + 1).trim()];
+  });
+}
+
+// --- Naming -----------------------------------------------------------------
+
+const pascal = (s) =>
+  s
+    .split(/[^A-Za-z0-9]+/)
+    .filter(Boolean)
+    .map((w) => w[0].toUpperCase() + w.slice(1))
+    .join("")
+    .replace(/^(\d)/, "V$1");
+
+const snake = (s) =>
+  s
+    .replace(/([a-z0-9])([A-Z])/g, "$1_$2")
+    .replace(/([A-Z]+)([A-Z][a-z])/g, "$1_$2")
+    .replace(/[^A-Za-z0-9]+/g, "_")
+    .toLowerCase();
+
+const ident = (s) => (RUST_KEYWORDS.has(s) ? `r#${s}` : s);
+
+// --- Types ------------------------------------------------------------------
+
+const definitions = new Map();
+const items = [];
+const emitted = new Set();
+
+function collectDefinitions(src) {
+  const re = /^const (\w+) = /gm;
+  let m;
+  while ((m = re.exec(src))) {
+    const start = m.index + m[0].length;
+    let depth = 0;
+    let end = -1;
+    scan(src.slice(start), (i, c) => {
+      if (end >= 0) return;
+      if ("([{".includes(c)) depth++;
+      else if (")]}".includes(c)) depth--;
+      else if (c === ";" && depth === 0) end = start + i;
+    });
+    definitions.set(m[1], src.slice(start, end).trim());
+  }
+}
+
+/** The Rust type of a zod expression, and whether the field may be left out */
+function rustType(expr, name) {
+  const { head, calls } = parseChain(expr);
+  const optional = calls.some(([method]) =>
+    ["optional", "nullable", "nullish", "default"].includes(method)
+  );
+  let type = "Value";
+  if (head === "z" && calls.length > 0) {
+    const [kind, args] = calls[0];
+    switch (kind) {
+      case "string":
+        type = "String";
+        break;
+      case "number":
+        type = calls.some(([method]) => method === "int") ? "i64" : "f64";
+        break;
+      case "boolean":
+        type = "bool";
+        break;
+      case "literal":
+        type = /^['"]/.test(args) ? "String" : "Value";
+        break;
+      case "enum":
+        type = enumType(name, args);
+        break;
+      case "array":
+        type = `Vec<${rustType(args, name).type}>`;
+        break;
+      case "record": {
+        const parts = splitTopLevel(args, ",");
+        type = `HashMap<String, ${rustType(parts[parts.length - 1], name).type}>`;
+        break;
+      }
+      case "object":
+        type = structType(name, objectEntries(args));
+        break;
+    }
+  } else if (definitions.has(head)) {
+    const base = rustType(definitions.get(head), pascal(head.replace(/Schema$/, "")));
+    type = base.type;
+  }
+  return { type, optional };
+}
+
+function enumType(name, args) {
+  const values = splitTopLevel(args.replace(/^\[|\]$/g, ""), ",").map((v) => v.slice(1, -1));
+  if (emitted.has(name)) return name;
+  emitted.add(name);
+  const variants = values.map((value) => {
+    const variant = pascal(value);
+    const rename = value !== variant ? `    #[serde(rename = "${value}")]\n` : "";
+    return `${rename}    ${variant},\n`;
+  });
+  items.push(
+    `#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]\npub enum ${name} {\n${variants.join("")}}\n`
+  );
+  return name;
+}
+
+function fieldsOf(name, entries) {
+  return entries
+    .filter(([key, expr]) => !(key === "action" && expr.startsWith("z.literal")))
+    .map(([key, expr]) => {
+      const { type, optional } = rustType(expr, name + pascal(key));
+      return { key, field: snake(key), type, optional };
+    });
+}
+
+function structDefinition(name, fields, doc) {
+  const body = fields
+    .map(({ key, field, type, optional }) => {
+      const attrs = [];
+      if (field !== key) attrs.push(`rename = "${key}"`);
+      if (optional) attrs.push('skip_serializing_if = "Option::is_none"');
+      const attr = attrs.length ? `    #[serde(${attrs.join(", ")})]\n` : "";
+      return `${attr}    pub ${ident(field)}: ${optional ? `Option<${type}>` : type},\n`;
+    })
+    .join("");
+  const derive = fields.every((f) => f.optional) ? "Debug, Clone, Default" : "Debug, Clone";
+  return `${doc}#[derive(${derive}, Serialize, Deserialize)]\npub struct ${name} {\n${body}}\n`;
+}
+
+function structType(name, entries) {
+  if (emitted.has(name)) return name;
+  emitted.add(name);
+  items.push(structDefinition(name, fieldsOf(name, entries), ""));
+  return name;
+}
+
+/** Constructor arguments: owned strings are taken as `impl Into<String>` */
+const param = ({ field, type }) =>
+  `${ident(field)}: ${type === "String" ? "impl Into<String>" : type}`;
+const arg = ({ field, type }) => (type === "String" ? `${ident(field)}.into()` : ident(field));
+
+function commandItems(action, entries) {
+  const name = pascal(action);
+  const fields = fieldsOf(name, entries);
+  const required = fields.filter((f) => !f.optional);
+  const optional = fields.filter((f) => f.optional);
+  emitted.add(name);
+
+  const inits = fields
+    .map((f) => {
+      const value = f.optional ? "None" : arg(f);
+      return value === ident(f.field) ? `            ${value},\n` : `            ${ident(f.field)}: ${value},\n`;
+    })
+    .join("");
+  const setters = optional
+    .map(
+      (f) =>
+        `\n    pub fn ${ident(f.field)}(mut self, ${param(f)}) -> Self {\n        self.${ident(f.field)} = Some(${arg(f)});\n        self\n    }\n`
+    )
+    .join("");
+  items.push(
+    structDefinition(name, fields, `/// The \`${action}\` command\n`) +
+      `\nimpl ${name} {\n    pub fn new(${required.map(param).join(", ")}) -> Self {\n        Self {\n${inits}        }\n    }\n${setters}}\n\n` +
+      `impl Command for ${name} {\n    const ACTION: &'static str = "${action}";\n}\n`
+  );
+
+  return (
+    `    /// Send a \`${action}\` command with its required fields; use \`send(${name}::new(..))\` to set others\n` +
+    `    pub async fn ${ident(snake(action))}(&self${required.map((f) => `, ${param(f)}`).join("")}) -> crate::Result<Value> {\n` +
+    `        self.send(${name}::new(${required.map((f) => ident(f.field)).join(", ")})).await\n    }\n`
+  );
+}
+
+// --- Output -----------------------------------------------------------------
+
+const source = scan(readFileSync(protocolPath, "utf-8"));
+collectDefinitions(source);
+
+const union = parseChain(definitions.get("commandSchema"));
+const members = splitTopLevel(union.calls[0][1], ",")[1].replace(/^\[|\]$/g, "");
+const methods = [];
+const actions = [];
+for (const member of splitTopLevel(members, ",")) {
+  const { head, calls } = parseChain(definitions.get(member));
+  const extend = calls.find(([method]) => method === "extend");
+  if (head !== "baseCommandSchema" || !extend) {
+    throw new Error(`${member} isn't baseCommandSchema.extend({ ... })`);
+  }
+  const entries = objectEntries(extend[1]);
+  const action = entries.find(([key]) => key === "action")[1].match(/z\.literal\('(\w+)'\)/)[1];
+  actions.push(action);
+  methods.push(commandItems(action, entries));
+}
+
+const output = `// Generated by scripts/generate-client.js from src/protocol.ts. Do not edit.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::Command;
+
+/// Every action the daemon accepts
+pub const ACTIONS: &[&str] = &[
+${actions.map((a) => `    "${a}",\n`).join("")}];
+
+${items.join("\n")}
+#[cfg(feature = "native")]
+impl crate::Client {
+${methods.join("\n")}}
+`;
+
+writeFileSync(outPath, output);
+try {
+  execSync(`rustfmt --edition 2021 "${outPath}"`, { stdio: "pipe" });
+} catch {
+  console.warn("rustfmt not found; commands.rs is unformatted");
+}
+console.log(`Wrote ${actions.length} commands to client/src/commands.rs`);
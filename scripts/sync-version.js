@@ -44,6 +44,25 @@ if (cargoVersionRegex.test(cargoToml)) {
   process.exit(1);
 }
 
+// The Rust client, gRPC server and language bindings are released with the CLI
+for (const crate of ["client", "grpc", "bindings/python", "bindings/node", "bindings/c"]) {
+  const tomlPath = join(rootDir, crate, "Cargo.toml");
+  const toml = readFileSync(tomlPath, "utf-8");
+  if (toml.match(cargoVersionRegex)?.[0] !== newCargoVersion) {
+    writeFileSync(tomlPath, toml.replace(cargoVersionRegex, newCargoVersion));
+    console.log(`  Updated ${crate}/Cargo.toml -> ${newCargoVersion}`);
+  }
+}
+
+// The Node bindings' package.json carries the version too
+const nodePackagePath = join(rootDir, "bindings", "node", "package.json");
+const nodePackage = JSON.parse(readFileSync(nodePackagePath, "utf-8"));
+if (nodePackage.version !== version) {
+  nodePackage.version = version;
+  writeFileSync(nodePackagePath, JSON.stringify(nodePackage, null, 2) + "\n");
+  console.log(`  Updated bindings/node/package.json -> ${version}`);
+}
+
 // Update Cargo.lock to match Cargo.toml
 if (cargoTomlUpdated) {
   try {
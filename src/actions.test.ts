@@ -1,5 +1,5 @@
 import { describe, it, expect } from 'vitest';
-import { toAIFriendlyError } from './actions.js';
+import { toAIFriendlyError, isTransientFailure } from './actions.js';
 
 describe('toAIFriendlyError', () => {
   describe('element blocked by overlay', () => {
@@ -37,3 +37,58 @@ describe('toAIFriendlyError', () => {
     });
   });
 });
+
+describe('isTransientFailure', () => {
+  it('should retry overlay and visibility failures', () => {
+    const blocked = toAIFriendlyError(new Error('<div> intercepts pointer events'), '@e1');
+    expect(isTransientFailure(blocked.message)).toBe(true);
+    expect(isTransientFailure('Element is not attached to the DOM')).toBe(true);
+  });
+
+  it('should not retry ambiguous selectors', () => {
+    const ambiguous = toAIFriendlyError(
+      new Error('strict mode violation: locator resolved to 3 elements'),
+      'button'
+    );
+    expect(isTransientFailure(ambiguous.message)).toBe(false);
+  });
+
+  it('should retry an element that is briefly missing', () => {
+    const missing = toAIFriendlyError(
+      new Error(
+        'TimeoutError: locator.click: Timeout 10000ms exceeded.\n' +
+          'Call log:\n' +
+          "  - waiting for locator('#missing')"
+      ),
+      '#missing'
+    );
+    expect(missing.message).toContain('not found');
+    expect(isTransientFailure(missing.message)).toBe(true);
+  });
+
+  it('should not retry a click that was already sent', () => {
+    expect(
+      isTransientFailure(
+        'Action on "#save" timed out. The element may be blocked, still loading, or not ' +
+          "interactable. Run 'snapshot' to check the current page state. " +
+          'The click was already sent.'
+      )
+    ).toBe(false);
+  });
+
+  it('should retry a timeout waiting on a resolved element', () => {
+    const stuck = toAIFriendlyError(
+      new Error(
+        'TimeoutError: locator.click: Timeout 10000ms exceeded.\n' +
+          'Call log:\n' +
+          "  - waiting for locator('#save')\n" +
+          '    - locator resolved to <button id="save">Save</button>\n' +
+          '  - attempting click action\n' +
+          '    - waiting for element to be visible, enabled and stable'
+      ),
+      '#save'
+    );
+    expect(stuck.message).toContain('timed out');
+    expect(isTransientFailure(stuck.message)).toBe(true);
+  });
+});
@@ -1,8 +1,8 @@
-import type { Page, Frame } from 'playwright-core';
-import { mkdirSync } from 'node:fs';
+import type { Page, Frame, Download, Locator, Request, ElementHandle } from 'playwright-core';
+import { existsSync, writeFileSync } from 'node:fs';
 import path from 'node:path';
-import type { BrowserManager, ScreencastFrame } from './browser.js';
-import { getAppDir } from './daemon.js';
+import { getDefaultTimeout, type BrowserManager, type ScreencastFrame } from './browser.js';
+import { artifactPath, savedFile, timestampedName } from './artifacts.js';
 import type {
   Command,
   Response,
@@ -31,7 +31,13 @@ import type {
   ContentCommand,
   TabNewCommand,
   TabSwitchCommand,
+  TargetsCommand,
+  AuditPerfCommand,
+  AuditSeoCommand,
+  AuditSecurityCommand,
   TabCloseCommand,
+  SessionOptionsCommand,
+  DismissOverlaysCommand,
   WindowNewCommand,
   CookiesSetCommand,
   StorageGetCommand,
@@ -39,6 +45,10 @@ import type {
   StorageClearCommand,
   DialogCommand,
   PdfCommand,
+  ArchiveCommand,
+  FreezeCommand,
+  SelectorsSuggestCommand,
+  SelectorsHealCommand,
   RouteCommand,
   RequestsCommand,
   DownloadCommand,
@@ -65,6 +75,8 @@ import type {
   TapCommand,
   ClipboardCommand,
   HighlightCommand,
+  InspectCommand,
+  InspectData,
   ClearCommand,
   SelectAllCommand,
   InnerTextCommand,
@@ -98,6 +110,8 @@ import type {
   InsertTextCommand,
   MultiSelectCommand,
   WaitForDownloadCommand,
+  WaitForPopupCommand,
+  WaitForRequestCommand,
   ResponseBodyCommand,
   ScreencastStartCommand,
   ScreencastStopCommand,
@@ -112,6 +126,14 @@ import type {
   EvaluateData,
   ContentData,
   TabListData,
+  TargetsData,
+  PerfAuditData,
+  ArchiveData,
+  FreezeData,
+  SelectorsData,
+  SelectorsHealData,
+  SeoAuditData,
+  SecurityAuditData,
   TabNewData,
   TabSwitchData,
   TabCloseData,
@@ -122,8 +144,45 @@ import type {
   RecordingRestartData,
   InputEventData,
   StylesData,
+  StatsCommand,
+  CdpCommand,
+  CdpData,
+  CdpListenCommand,
+  CdpEvent,
+  WatchCommand,
+  DomMutationEvent,
+  PollCommand,
+  PollEvent,
+  PollData,
+  TraceStepCommand,
+  CaptureStepCommand,
+  TraceStepData,
+  CaptureStepData,
+  StatsData,
+  CaptureStartCommand,
+  ApisCommand,
+  WsCommand,
+  InputCommand,
+  HarReplayCommand,
 } from './types.js';
 import { successResponse, errorResponse } from './protocol.js';
+import { classifyError } from './errors.js';
+import { dismissOverlays } from './overlays.js';
+import { withSpan } from './otel.js';
+import { readTimeline, replayTimeline } from './input-timeline.js';
+import { Humanizer } from './humanize.js';
+import { auditPerf } from './perf-audit.js';
+import { archivePage } from './archive.js';
+import { freezePage } from './freeze.js';
+import { healSelector, suggestSelectors } from './selectors.js';
+import { describeTimeout, IDLE_ERROR, withTimeouts } from './timeouts.js';
+import { isCancelled } from './cancel.js';
+import { getEnhancedSnapshot, parseRef } from './snapshot.js';
+import { auditSeo } from './seo-audit.js';
+import { auditSecurity } from './security-audit.js';
+import { FrameWriter } from './screencast.js';
+import { readOnlyViolation } from './read-only.js';
+import { describeViolations } from './guardrails.js';
 
 // Callback for screencast frames - will be set by the daemon when streaming is active
 let screencastFrameCallback: ((frame: ScreencastFrame) => void) | null = null;
@@ -138,6 +197,9 @@ export function setScreencastFrameCallback(
   screencastFrameCallback = callback;
 }
 
+// Where `screencast start <path>` writes frames, until `screencast stop`
+let screencastWriter: FrameWriter | null = null;
+
 // Snapshot response type
 interface SnapshotData {
   snapshot: string;
@@ -180,6 +242,17 @@ export function toAIFriendlyError(error: unknown, selector: string): Error {
     );
   }
 
+  // Handle timeout before the locator ever resolved (nothing matches the selector)
+  if (
+    message.includes('Timeout') &&
+    message.includes('waiting for') &&
+    !message.includes('resolved to')
+  ) {
+    return new Error(
+      `Element "${selector}" not found. Run 'snapshot' to see current page elements.`
+    );
+  }
+
   // Handle general timeout (element exists but action couldn't complete)
   if (message.includes('Timeout') && message.includes('exceeded')) {
     return new Error(
@@ -204,9 +277,165 @@ export function toAIFriendlyError(error: unknown, selector: string): Error {
 }
 
 /**
- * Execute a command and return a response
+ * Element actions that overlays can get in the way of; with auto-dismiss on,
+ * overlays are cleared before they run
+ */
+const ELEMENT_ACTIONS = new Set([
+  'click',
+  'dblclick',
+  'tap',
+  'hover',
+  'focus',
+  'fill',
+  'type',
+  'clear',
+  'check',
+  'uncheck',
+  'select',
+  'multiselect',
+  'upload',
+  'drag',
+  'scrollintoview',
+]);
+
+/**
+ * Element actions that are safe to retry after a transient failure. Running
+ * them twice does no harm, except click, which is retried only when the click
+ * never reached the page (see handleClick). Typing, filling, selecting,
+ * uploading and dragging are never retried: a partial attempt can't be
+ * undone, so a retry could enter text twice.
+ */
+const RETRYABLE_ACTIONS = new Set(['click', 'hover', 'focus', 'scrollintoview']);
+
+/** Added to a click error once Playwright has sent the click to the page */
+const CLICK_SENT = 'The click was already sent.';
+
+/**
+ * Check whether a failure was caused by DOM churn (re-render, overlay, animation)
+ * rather than a wrong selector. Matches both raw Playwright messages and those
+ * rewritten by toAIFriendlyError. An element that isn't found may be between
+ * renders, so that is retried too; a selector matching several elements isn't.
+ * @internal Exported for testing
+ */
+export function isTransientFailure(message: string): boolean {
+  if (message.includes(CLICK_SENT)) return false;
+  return /not found|blocked by another element|intercepts pointer events|not visible|detached|not attached|not stable|Action on .* timed out|waiting for element to be/i.test(
+    message
+  );
+}
+
+const DEFAULT_RETRY_BACKOFF = 500;
+
+/**
+ * Execute a command and return a response.
+ * Element actions are retried when the command carries a retry policy: between
+ * attempts overlays are dismissed and the target is scrolled into view, and the
+ * selector or ref is resolved again on the next attempt.
  */
 export async function executeCommand(command: Command, browser: BrowserManager): Promise<Response> {
+  const violation = browser.getReadOnly().enabled ? readOnlyViolation(command) : null;
+  if (violation) {
+    return errorResponse(command.id, violation, 'E_READ_ONLY');
+  }
+  const page = command.timeouts && browser.isLaunched() ? browser.getPage() : null;
+  const response = await withTimeouts(page, command.timeouts, getDefaultTimeout(), () =>
+    executeWithOverrides(command, browser)
+  );
+  if (!response.success && (response.code === 'E_TIMEOUT' || response.code === 'E_NAV_TIMEOUT')) {
+    return errorResponse(command.id, describeTimeout(command, response.error), response.code);
+  }
+  return response;
+}
+
+async function executeWithOverrides(command: Command, browser: BrowserManager): Promise<Response> {
+  // Requests blocked between commands don't count against this one
+  browser.takeGuardrailViolations();
+  // --robots-override and --allow-purchases lift their checks for this command only
+  if (command.robotsOverride) browser.setRobotsOverride(true);
+  if (command.allowPurchases) browser.setAllowPurchases(true);
+  let response: Response;
+  try {
+    response = await executeWithRetries(command, browser);
+  } finally {
+    if (command.robotsOverride) browser.setRobotsOverride(false);
+    if (command.allowPurchases) browser.setAllowPurchases(false);
+  }
+
+  // Whatever the command did otherwise, a blocked request is what the agent needs to hear about
+  const violations = browser.takeGuardrailViolations();
+  if (violations.length === 0) {
+    return response;
+  }
+  return {
+    id: command.id,
+    success: false,
+    error: describeViolations(violations),
+    code: 'E_GUARDRAIL',
+    data: { violations },
+  };
+}
+
+async function executeWithRetries(command: Command, browser: BrowserManager): Promise<Response> {
+  const retries = RETRYABLE_ACTIONS.has(command.action) ? (command.retries ?? 0) : 0;
+  const backoff = command.retryBackoff ?? DEFAULT_RETRY_BACKOFF;
+
+  // With auto-dismiss on, clear interstitials before acting on or reading the page
+  if (
+    browser.getAutoDismissOverlays() &&
+    (ELEMENT_ACTIONS.has(command.action) || command.action === 'snapshot')
+  ) {
+    await withSpan('overlays.dismiss', {}, () => dismissOverlays(browser.getPage())).catch(
+      () => []
+    );
+  }
+
+  let response = await dispatchCommand(command, browser);
+  if (
+    response.success &&
+    command.action === 'navigate' &&
+    browser.getAutoDismissOverlays()
+  ) {
+    await withSpan('overlays.dismiss', {}, () => dismissOverlays(browser.getPage())).catch(
+      () => []
+    );
+  }
+  for (let attempt = 1; attempt <= retries; attempt++) {
+    if (response.success || !isTransientFailure(response.error) || isCancelled(command)) {
+      return response;
+    }
+    await withSpan('retry', { attempt, 'retry.error': response.error }, async () => {
+      await new Promise((resolve) => setTimeout(resolve, backoff * 2 ** (attempt - 1)));
+      await prepareRetry(command, browser);
+    });
+    response = await dispatchCommand(command, browser);
+    if (!response.success && attempt === retries) {
+      response = errorResponse(
+        command.id,
+        `${response.error} (after ${retries} retries)`,
+        response.code
+      );
+    }
+  }
+  return response;
+}
+
+/**
+ * Clear the usual obstacles before retrying an element action
+ */
+async function prepareRetry(command: Command, browser: BrowserManager): Promise<void> {
+  const page = browser.getPage();
+  await dismissOverlays(page).catch(() => []);
+
+  const selector = (command as { selector?: unknown }).selector;
+  if (typeof selector === 'string') {
+    await browser
+      .getLocator(selector)
+      .scrollIntoViewIfNeeded({ timeout: 1000 })
+      .catch(() => {});
+  }
+}
+
+async function dispatchCommand(command: Command, browser: BrowserManager): Promise<Response> {
   try {
     switch (command.action) {
       case 'launch':
@@ -267,10 +496,45 @@ export async function executeCommand(command: Command, browser: BrowserManager):
         return await handleTabNew(command, browser);
       case 'tab_list':
         return await handleTabList(command, browser);
+      case 'targets':
+        return await handleTargets(command, browser);
+      case 'robots':
+        return successResponse(command.id, await browser.checkRobots(command.url));
+      case 'audit_perf':
+        return await handleAuditPerf(command, browser);
+      case 'audit_seo':
+        return await handleAuditSeo(command, browser);
+      case 'audit_security':
+        return await handleAuditSecurity(command, browser);
+      case 'tls_info':
+        return successResponse(command.id, await browser.inspectTls(command.url));
+      case 'clock_set':
+        return successResponse(
+          command.id,
+          await browser.setClock(command.time, command.tick ?? false, command.timezone)
+        );
+      case 'clock_advance':
+        return successResponse(command.id, await browser.advanceClock(command.ms));
+      case 'clock_resume':
+        return successResponse(command.id, await browser.resumeClock());
       case 'tab_switch':
         return await handleTabSwitch(command, browser);
       case 'tab_close':
         return await handleTabClose(command, browser);
+      case 'session_options':
+        return await handleSessionOptions(command, browser);
+      case 'dismiss_overlays':
+        return await handleDismissOverlays(command, browser);
+      case 'stats':
+        return handleStats(command, browser);
+      case 'memory':
+        return successResponse(command.id, await browser.getMemory());
+      case 'cdp':
+        return await handleCdp(command, browser);
+      case 'trace_step':
+        return await handleTraceStep(command, browser);
+      case 'capture_step':
+        return await handleCaptureStep(command, browser);
       case 'window_new':
         return await handleWindowNew(command, browser);
       case 'cookies_get':
@@ -289,6 +553,14 @@ export async function executeCommand(command: Command, browser: BrowserManager):
         return await handleDialog(command, browser);
       case 'pdf':
         return await handlePdf(command, browser);
+      case 'archive':
+        return await handleArchive(command, browser);
+      case 'freeze':
+        return await handleFreeze(command, browser);
+      case 'selectors_suggest':
+        return await handleSelectorsSuggest(command, browser);
+      case 'selectors_heal':
+        return await handleSelectorsHeal(command, browser);
       case 'route':
         return await handleRoute(command, browser);
       case 'unroute':
@@ -341,6 +613,18 @@ export async function executeCommand(command: Command, browser: BrowserManager):
         return await handleTraceStart(command, browser);
       case 'trace_stop':
         return await handleTraceStop(command, browser);
+      case 'capture_start':
+        return handleCaptureStart(command, browser);
+      case 'capture_stop':
+        return successResponse(command.id, browser.stopCapture());
+      case 'apis':
+        return handleApis(command, browser);
+      case 'ws':
+        return handleWs(command, browser);
+      case 'input':
+        return await handleInput(command, browser);
+      case 'har_replay':
+        return await handleHarReplay(command, browser);
       case 'har_start':
         return await handleHarStart(command, browser);
       case 'har_stop':
@@ -349,6 +633,16 @@ export async function executeCommand(command: Command, browser: BrowserManager):
         return await handleStateSave(command, browser);
       case 'state_load':
         return await handleStateLoad(command, browser);
+      case 'session_save':
+        return successResponse(command.id, {
+          ...savedFile(command.path),
+          ...(await browser.saveSession(command.path)),
+        });
+      case 'session_restore':
+        return successResponse(command.id, {
+          path: command.path,
+          ...(await browser.restoreSession(command.path)),
+        });
       case 'console':
         return await handleConsole(command, browser);
       case 'errors':
@@ -363,6 +657,8 @@ export async function executeCommand(command: Command, browser: BrowserManager):
         return await handleClipboard(command, browser);
       case 'highlight':
         return await handleHighlight(command, browser);
+      case 'inspect':
+        return await handleInspect(command, browser);
       case 'clear':
         return await handleClear(command, browser);
       case 'selectall':
@@ -437,6 +733,10 @@ export async function executeCommand(command: Command, browser: BrowserManager):
         return await handleMultiSelect(command, browser);
       case 'waitfordownload':
         return await handleWaitForDownload(command, browser);
+      case 'waitforpopup':
+        return await handleWaitForPopup(command, browser);
+      case 'waitforrequest':
+        return await handleWaitForRequest(command, browser);
       case 'responsebody':
         return await handleResponseBody(command, browser);
       case 'screencast_start':
@@ -458,12 +758,16 @@ export async function executeCommand(command: Command, browser: BrowserManager):
       default: {
         // TypeScript narrows to never here, but we handle it for safety
         const unknownCommand = command as { id: string; action: string };
-        return errorResponse(unknownCommand.id, `Unknown action: ${unknownCommand.action}`);
+        return errorResponse(
+          unknownCommand.id,
+          `Unknown action: ${unknownCommand.action}`,
+          'E_INVALID_COMMAND'
+        );
       }
     }
   } catch (error) {
     const message = error instanceof Error ? error.message : String(error);
-    return errorResponse(command.id, message);
+    return errorResponse(command.id, message, classifyError(message, command.action));
   }
 }
 
@@ -481,14 +785,30 @@ async function handleNavigate(
 ): Promise<Response<NavigateData>> {
   const page = browser.getPage();
 
+  if (browser.getRespectRobots()) {
+    const robots = await browser.checkRobots(command.url);
+    if (!robots.allowed) {
+      throw new Error(
+        `Blocked by robots.txt: ${command.url} (${robots.rule}). Pass --robots-override to open it anyway`
+      );
+    }
+  }
+
   // If headers are provided, set up scoped headers for this origin
   if (command.headers && Object.keys(command.headers).length > 0) {
     await browser.setScopedHeaders(command.url, command.headers);
   }
 
+  const idle = command.waitUntil === 'networkidle' ? command.timeouts?.idle : undefined;
   await page.goto(command.url, {
-    waitUntil: command.waitUntil ?? 'load',
+    waitUntil: idle ? 'load' : (command.waitUntil ?? 'load'),
   });
+  if (idle) {
+    // Network idle has its own timeout, after the page has loaded
+    await page.waitForLoadState('networkidle', { timeout: idle.ms }).catch((error: Error) => {
+      throw new Error(`${IDLE_ERROR}: ${error.message}`);
+    });
+  }
 
   return successResponse(command.id, {
     url: page.url(),
@@ -501,13 +821,23 @@ async function handleClick(command: ClickCommand, browser: BrowserManager): Prom
   const locator = browser.getLocator(command.selector);
 
   try {
-    await locator.click({
-      button: command.button,
-      clickCount: command.clickCount,
-      delay: command.delay,
-    });
+    if (command.humanize) {
+      await new Humanizer(command.humanizeSeed).click(browser.getPage(), locator, command);
+    } else {
+      await locator.click({
+        button: command.button,
+        clickCount: command.clickCount,
+        delay: command.delay,
+      });
+    }
   } catch (error) {
-    throw toAIFriendlyError(error, command.selector);
+    const friendly = toAIFriendlyError(error, command.selector);
+    // The call log shows whether the click reached the page before the failure
+    const log = error instanceof Error ? error.message : String(error);
+    if (/performing click action|click action done/.test(log)) {
+      throw new Error(`${friendly.message} ${CLICK_SENT}`);
+    }
+    throw friendly;
   }
 
   return successResponse(command.id, { clicked: true });
@@ -521,9 +851,18 @@ async function handleType(command: TypeCommand, browser: BrowserManager): Promis
       await locator.fill('');
     }
 
-    await locator.pressSequentially(command.text, {
-      delay: command.delay,
-    });
+    if (command.humanize) {
+      await new Humanizer(command.humanizeSeed).type(
+        browser.getPage(),
+        locator,
+        command.text,
+        command.delay
+      );
+    } else {
+      await locator.pressSequentially(command.text, {
+        delay: command.delay,
+      });
+    }
   } catch (error) {
     throw toAIFriendlyError(error, command.selector);
   }
@@ -558,25 +897,22 @@ async function handleScreenshot(
     options.quality = command.quality;
   }
 
+  if (command.mask?.length) {
+    options.mask = command.mask.map((selector) => browser.getLocator(selector));
+  }
+
   let target: Page | ReturnType<Page['locator']> = page;
   if (command.selector) {
     target = browser.getLocator(command.selector);
   }
 
   try {
-    let savePath = command.path;
-    if (!savePath) {
-      const ext = command.format === 'jpeg' ? 'jpg' : 'png';
-      const timestamp = new Date().toISOString().replace(/[:.]/g, '-');
-      const random = Math.random().toString(36).substring(2, 8);
-      const filename = `screenshot-${timestamp}-${random}.${ext}`;
-      const screenshotDir = path.join(getAppDir(), 'tmp', 'screenshots');
-      mkdirSync(screenshotDir, { recursive: true });
-      savePath = path.join(screenshotDir, filename);
-    }
+    const ext = command.format === 'jpeg' ? 'jpg' : 'png';
+    const savePath =
+      command.path ?? artifactPath('screenshots', timestampedName('screenshot', ext));
 
     await target.screenshot({ ...options, path: savePath });
-    return successResponse(command.id, { path: savePath });
+    return successResponse(command.id, savedFile(savePath));
   } catch (error) {
     if (command.selector) {
       throw toAIFriendlyError(error, command.selector);
@@ -593,6 +929,7 @@ async function handleSnapshot(
     maxDepth?: number;
     compact?: boolean;
     selector?: string;
+    fresh?: boolean;
   },
   browser: BrowserManager
 ): Promise<Response<SnapshotData>> {
@@ -603,6 +940,7 @@ async function handleSnapshot(
     maxDepth: command.maxDepth,
     compact: command.compact,
     selector: command.selector,
+    fresh: command.fresh,
   });
 
   // Simplify refs for output (just role and name)
@@ -621,6 +959,11 @@ async function handleEvaluate(
   command: EvaluateCommand,
   browser: BrowserManager
 ): Promise<Response<EvaluateData>> {
+  if (command.target) {
+    const result = await browser.evaluateInTarget(command.target, command.script);
+    return successResponse(command.id, { result });
+  }
+
   const page = browser.getPage();
 
   // Evaluate the script directly as a string expression
@@ -649,12 +992,15 @@ async function handleWait(command: WaitCommand, browser: BrowserManager): Promis
 
 async function handleScroll(command: ScrollCommand, browser: BrowserManager): Promise<Response> {
   const page = browser.getPage();
+  const human = command.humanize ? new Humanizer(command.humanizeSeed) : null;
 
   if (command.selector) {
     const element = page.locator(command.selector);
     await element.scrollIntoViewIfNeeded();
 
-    if (command.x !== undefined || command.y !== undefined) {
+    if (human && (command.x !== undefined || command.y !== undefined)) {
+      await human.scroll(page, command.x ?? 0, command.y ?? 0, element);
+    } else if (command.x !== undefined || command.y !== undefined) {
       await element.evaluate(
         (el, { x, y }) => {
           el.scrollBy(x ?? 0, y ?? 0);
@@ -685,7 +1031,11 @@ async function handleScroll(command: ScrollCommand, browser: BrowserManager): Pr
       }
     }
 
-    await page.evaluate(`window.scrollBy(${deltaX}, ${deltaY})`);
+    if (human) {
+      await human.scroll(page, deltaX, deltaY);
+    } else {
+      await page.evaluate(`window.scrollBy(${deltaX}, ${deltaY})`);
+    }
   }
 
   return successResponse(command.id, { scrolled: true });
@@ -765,6 +1115,43 @@ async function handleTabList(
   });
 }
 
+async function handleTargets(
+  command: TargetsCommand,
+  browser: BrowserManager
+): Promise<Response<TargetsData>> {
+  return successResponse(command.id, { targets: await browser.listTargets() });
+}
+
+async function handleAuditPerf(
+  command: AuditPerfCommand,
+  browser: BrowserManager
+): Promise<Response<PerfAuditData>> {
+  return successResponse(
+    command.id,
+    await auditPerf(browser.getPage(), command.url, command.timeouts?.idle?.ms)
+  );
+}
+
+async function handleAuditSeo(
+  command: AuditSeoCommand,
+  browser: BrowserManager
+): Promise<Response<SeoAuditData>> {
+  return successResponse(
+    command.id,
+    await auditSeo(browser.getPage(), command.url, command.links)
+  );
+}
+
+async function handleAuditSecurity(
+  command: AuditSecurityCommand,
+  browser: BrowserManager
+): Promise<Response<SecurityAuditData>> {
+  return successResponse(
+    command.id,
+    await auditSecurity(browser.getPage(), command.url, command.timeouts?.idle?.ms)
+  );
+}
+
 async function handleTabSwitch(
   command: TabSwitchCommand,
   browser: BrowserManager
@@ -785,6 +1172,357 @@ async function handleTabClose(
   return successResponse(command.id, result);
 }
 
+async function handleSessionOptions(
+  command: SessionOptionsCommand,
+  browser: BrowserManager
+): Promise<Response> {
+  if (command.popupPolicy !== undefined) {
+    browser.setPopupPolicy(command.popupPolicy);
+  }
+  if (command.autoDismissOverlays !== undefined) {
+    browser.setAutoDismissOverlays(command.autoDismissOverlays);
+  }
+  if (command.adblock !== undefined) {
+    await browser.setAdblock(command.adblock);
+  }
+  if (command.respectRobots !== undefined) {
+    await browser.setRespectRobots(command.respectRobots);
+  }
+  if (command.rateLimits !== undefined) {
+    await browser.setRateLimits(command.rateLimits);
+  }
+  if (command.tabMemory !== undefined) {
+    browser.setTabMemoryLimit(command.tabMemory.limit, command.tabMemory.mode);
+  }
+  if (command.readOnly) {
+    await browser.setReadOnly();
+  }
+  if (command.guardrails !== undefined) {
+    await browser.setGuardrails(command.guardrails);
+  }
+  return successResponse(command.id, {
+    popupPolicy: browser.getPopupPolicy(),
+    autoDismissOverlays: browser.getAutoDismissOverlays(),
+    adblock: browser.getAdblock().enabled,
+    readOnly: browser.getReadOnly().enabled,
+    guardrails: browser.getGuardrails()?.length ?? 0,
+  });
+}
+
+function handleStats(command: StatsCommand, browser: BrowserManager): Response<StatsData> {
+  const stats = browser.getStats();
+  if (command.reset) {
+    browser.resetStats();
+  }
+  return successResponse(command.id, stats);
+}
+
+/** How long `cdp --events` collects events after the call returns */
+const DEFAULT_CDP_EVENT_WAIT = 1000;
+
+async function handleCdp(command: CdpCommand, browser: BrowserManager): Promise<Response<CdpData>> {
+  const cdp = await browser.getCDPSession();
+  const events: CdpData['events'] = [];
+  const unsubscribe = (command.events ?? []).map((method) => {
+    const listener = (params: unknown) => events.push({ method, params });
+    cdp.on(method as never, listener);
+    return () => cdp.off(method as never, listener);
+  });
+  try {
+    const result = await cdp.send(command.method as never, command.params as never);
+    if (unsubscribe.length > 0) {
+      await new Promise((resolve) => setTimeout(resolve, command.wait ?? DEFAULT_CDP_EVENT_WAIT));
+    }
+    return successResponse(command.id, { method: command.method, result, events });
+  } catch (error) {
+    // Protocol errors say "wasn't found", which would otherwise read as a selector miss
+    const message = error instanceof Error ? error.message : String(error);
+    const code =
+      classifyError(message) === 'E_BROWSER_CRASHED'
+        ? 'E_BROWSER_CRASHED'
+        : /wasn't found|Invalid parameters/i.test(message)
+          ? 'E_INVALID_COMMAND'
+          : 'E_UNKNOWN';
+    return errorResponse(command.id, message, code);
+  } finally {
+    unsubscribe.forEach((off) => off());
+  }
+}
+
+/**
+ * Pass protocol events to `emit` until `count` have arrived, `timeout` runs
+ * out or `closed` resolves (the client went away). Domains are enabled on the
+ * session first, since most only send events once enabled.
+ */
+export async function streamCdpEvents(
+  command: CdpListenCommand,
+  browser: BrowserManager,
+  emit: (event: CdpEvent) => void,
+  closed: Promise<void>
+): Promise<Response<{ count: number }>> {
+  const cdp = await browser.getCDPSession();
+  const domains = new Set(command.events.map((event) => event.split('.')[0]));
+  for (const domain of domains) {
+    await cdp.send(`${domain}.enable` as never).catch(() => {
+      // Some domains (e.g. Target) have no enable method or need parameters
+    });
+  }
+
+  let count = 0;
+  let finish!: (response: Response<{ count: number }>) => void;
+  const done = new Promise<Response<{ count: number }>>((resolve) => (finish = resolve));
+  let finished = false;
+  const end = (response: Response<{ count: number }>) => {
+    finished = true;
+    finish(response);
+  };
+
+  const unsubscribe = command.events.map((method) => {
+    const listener = (params: unknown) => {
+      if (finished) return;
+      emit({ method, params, timestamp: Date.now() });
+      count++;
+      if (command.count && count >= command.count) {
+        end(successResponse(command.id, { count }));
+      }
+    };
+    cdp.on(method as never, listener);
+    return () => cdp.off(method as never, listener);
+  });
+  const timer = command.timeout
+    ? setTimeout(
+        () =>
+          end(
+            errorResponse(
+              command.id,
+              `Timed out after ${command.timeout}ms waiting for ${command.events.join(', ')}`,
+              'E_TIMEOUT'
+            )
+          ),
+        command.timeout
+      )
+    : undefined;
+  void closed.then(() => end(successResponse(command.id, { count })));
+
+  try {
+    return await done;
+  } finally {
+    clearTimeout(timer);
+    unsubscribe.forEach((off) => off());
+  }
+}
+
+/**
+ * Pass DOM mutations of the watched elements to `emit`, with the same
+ * `count`, `timeout` and `closed` handling as streamCdpEvents.
+ */
+export async function streamMutations(
+  command: WatchCommand,
+  browser: BrowserManager,
+  emit: (event: DomMutationEvent) => void,
+  closed: Promise<void>
+): Promise<Response<{ count: number }>> {
+  let count = 0;
+  let finish!: (response: Response<{ count: number }>) => void;
+  const done = new Promise<Response<{ count: number }>>((resolve) => (finish = resolve));
+  let finished = false;
+  const end = (response: Response<{ count: number }>) => {
+    finished = true;
+    finish(response);
+  };
+
+  let stop: () => void;
+  try {
+    stop = await browser.watchDom(
+      command.selector,
+      command.events ?? ['childList', 'attributes', 'characterData'],
+      (event) => {
+        if (finished) return;
+        emit(event);
+        count++;
+        if (command.count && count >= command.count) {
+          end(successResponse(command.id, { count }));
+        }
+      }
+    );
+  } catch (error) {
+    const message = error instanceof Error ? error.message : String(error);
+    return errorResponse(command.id, message, 'E_INVALID_COMMAND');
+  }
+  const timer = command.timeout
+    ? setTimeout(
+        () =>
+          end(
+            errorResponse(
+              command.id,
+              `Timed out after ${command.timeout}ms waiting for changes to ${command.selector}`,
+              'E_TIMEOUT'
+            )
+          ),
+        command.timeout
+      )
+    : undefined;
+  void closed.then(() => end(successResponse(command.id, { count })));
+
+  try {
+    return await done;
+  } finally {
+    clearTimeout(timer);
+    stop();
+  }
+}
+
+/** Text or attribute of the first match, whitespace collapsed; null when there is none */
+async function readPollValue(locator: Locator, attribute?: string): Promise<string | null> {
+  const element = locator.first();
+  if ((await element.count()) === 0) return null;
+  // Gone again between the count and the read: same as missing
+  const raw = attribute
+    ? await element.getAttribute(attribute, { timeout: 5000 }).catch(() => null)
+    : await element.innerText({ timeout: 5000 }).catch(() => null);
+  return raw === null ? null : raw.replace(/\s+/g, ' ').trim();
+}
+
+/**
+ * Read the element every `interval` ms and pass each reading to `emit`, until
+ * a stop condition holds, `max` readings have been taken or `closed` resolves.
+ * Running out of readings before a stop condition holds is a timeout.
+ */
+export async function streamPoll(
+  command: PollCommand,
+  browser: BrowserManager,
+  emit: (event: PollEvent) => void,
+  closed: Promise<void>
+): Promise<Response<PollData>> {
+  const pattern = command.untilMatch !== undefined ? new RegExp(command.untilMatch) : null;
+  let stopped = false;
+  void closed.then(() => (stopped = true));
+
+  let checks = 0;
+  let first: string | null = null;
+  let value: string | null = null;
+  try {
+    while (!stopped) {
+      if (checks > 0) {
+        let timer: NodeJS.Timeout | undefined;
+        await Promise.race([
+          new Promise((resolve) => (timer = setTimeout(resolve, command.interval))),
+          closed,
+        ]);
+        clearTimeout(timer);
+        if (stopped) break;
+        if (command.reload) {
+          await browser
+            .getPage()
+            .reload({ waitUntil: 'load' })
+            .catch(() => {
+              // Site down or slow: this reading finds the element missing
+            });
+        }
+      }
+
+      const previous = value;
+      value = await readPollValue(browser.getLocator(command.selector), command.attribute);
+      checks++;
+      if (checks === 1) first = value;
+      emit({
+        check: checks,
+        value,
+        changed: checks > 1 && value !== previous,
+        url: browser.getPage().url(),
+        timestamp: Date.now(),
+      });
+
+      const matched =
+        (command.untilChanged && value !== first) ||
+        (pattern !== null && value !== null && pattern.test(value));
+      if (matched) {
+        return successResponse(command.id, { checks, value, matched: true });
+      }
+      if (command.max && checks >= command.max) {
+        if (command.untilChanged || pattern) {
+          return errorResponse(
+            command.id,
+            `Stop condition not met after ${checks} checks of ${command.selector}`,
+            'E_TIMEOUT'
+          );
+        }
+        break;
+      }
+    }
+  } catch (error) {
+    const message = error instanceof Error ? error.message : String(error);
+    return errorResponse(command.id, message);
+  }
+  return successResponse(command.id, { checks, value, matched: false });
+}
+
+async function handleTraceStep(
+  command: TraceStepCommand,
+  browser: BrowserManager
+): Promise<Response<TraceStepData | { reset: true }>> {
+  const buffer = browser.takeTraceBuffer();
+  if (command.reset) {
+    return successResponse(command.id, { reset: true });
+  }
+  const page = browser.getPage();
+  let screenshot: string | undefined;
+  try {
+    const image = await page.screenshot({ type: 'jpeg', quality: 70, timeout: 5000 });
+    screenshot = image.toString('base64');
+  } catch {
+    // Page is mid-navigation or crashed; the step is still traced
+  }
+  const title = await page.title().catch(() => '');
+  return successResponse(command.id, { url: page.url(), title, screenshot, ...buffer });
+}
+
+/** Console messages kept with a step capture */
+const CAPTURE_CONSOLE_MESSAGES = 20;
+/** Snapshot lines kept with a step capture */
+const CAPTURE_SNAPSHOT_LINES = 150;
+
+async function handleCaptureStep(
+  command: CaptureStepCommand,
+  browser: BrowserManager
+): Promise<Response<CaptureStepData>> {
+  const page = browser.getPage();
+  let screenshot: { path?: string; artifacts: string[] } = { artifacts: [] };
+  try {
+    const path = artifactPath('screenshots', timestampedName('step', 'png'));
+    await page.screenshot({ path, fullPage: true, timeout: 5000 });
+    screenshot = savedFile(path);
+  } catch {
+    // Page is mid-navigation or crashed; the rest is still captured
+  }
+  const title = await page.title().catch(() => '');
+  const messages = browser
+    .getConsoleMessages()
+    .slice(-CAPTURE_CONSOLE_MESSAGES)
+    .map(({ type, text }) => ({ type, text }));
+  // Not through the browser manager, which would replace the script's refs
+  const tree = await getEnhancedSnapshot(page, { interactive: true, compact: true })
+    .then((snapshot) => snapshot.tree)
+    .catch(() => '');
+  const lines = tree.split('\n');
+  return successResponse(command.id, {
+    url: page.url(),
+    title,
+    ...screenshot,
+    console: messages,
+    snapshot: lines.slice(0, CAPTURE_SNAPSHOT_LINES).join('\n'),
+    truncated: lines.length > CAPTURE_SNAPSHOT_LINES,
+  });
+}
+
+async function handleDismissOverlays(
+  command: DismissOverlaysCommand,
+  browser: BrowserManager
+): Promise<Response> {
+  const dismissed = await dismissOverlays(browser.getPage());
+  return successResponse(command.id, { dismissed });
+}
+
 async function handleWindowNew(
   command: WindowNewCommand,
   browser: BrowserManager
@@ -1054,11 +1792,36 @@ async function handleDialog(command: DialogCommand, browser: BrowserManager): Pr
 
 async function handlePdf(command: PdfCommand, browser: BrowserManager): Promise<Response> {
   const page = browser.getPage();
+  const savePath = command.path ?? artifactPath('pdfs', timestampedName('page', 'pdf'));
   await page.pdf({
-    path: command.path,
+    path: savePath,
     format: command.format ?? 'Letter',
   });
-  return successResponse(command.id, { path: command.path });
+  return successResponse(command.id, savedFile(savePath));
+}
+
+async function handleArchive(
+  command: ArchiveCommand,
+  browser: BrowserManager
+): Promise<Response<ArchiveData>> {
+  const savePath =
+    command.path ?? artifactPath('archives', timestampedName('page', command.format));
+  const archive = await archivePage(
+    browser.getPage(),
+    await browser.getCDPSession(),
+    command.format,
+    savePath
+  );
+  return successResponse(command.id, { ...savedFile(savePath), ...archive });
+}
+
+async function handleFreeze(
+  command: FreezeCommand,
+  browser: BrowserManager
+): Promise<Response<FreezeData>> {
+  const savePath = command.path ?? artifactPath('pages', timestampedName('page', 'html'));
+  const frozen = await freezePage(browser.getPage(), await browser.getCDPSession(), savePath);
+  return successResponse(command.id, { ...savedFile(savePath), ...frozen });
 }
 
 // Network & Request handlers
@@ -1106,7 +1869,7 @@ async function handleDownload(
 
   await download.saveAs(command.path);
   return successResponse(command.id, {
-    path: command.path,
+    ...savedFile(command.path),
     suggestedFilename: download.suggestedFilename(),
   });
 }
@@ -1278,6 +2041,64 @@ async function handleIsChecked(
   return successResponse(command.id, { checked });
 }
 
+/** Fallbacks below this score could match another element after a change */
+const FALLBACK_MIN_SCORE = 50;
+
+async function handleSelectorsSuggest(
+  command: SelectorsSuggestCommand,
+  browser: BrowserManager
+): Promise<Response<SelectorsData>> {
+  const ref = parseRef(command.selector);
+  const refData = ref ? browser.getRefMap()[ref] : undefined;
+  if (ref && !refData) {
+    return errorResponse(command.id, `Unknown ref: ${command.selector} (take a new snapshot)`);
+  }
+  if (refData?.frames?.length) {
+    return errorResponse(command.id, 'Selector suggestions are not supported inside iframes');
+  }
+  // Cursor-interactive refs have no accessible role, only a CSS selector
+  const accessible =
+    refData && refData.role !== 'clickable' && refData.role !== 'focusable'
+      ? { role: refData.role, name: refData.name }
+      : undefined;
+  const candidates = await suggestSelectors(
+    browser.getPage(),
+    browser.getLocator(command.selector),
+    accessible
+  );
+  const fallbacks = candidates
+    .filter((c) => c.score >= FALLBACK_MIN_SCORE && c.selector !== command.selector)
+    .map((c) => c.selector);
+  if (ref) browser.setRefFallbacks(ref, fallbacks);
+  else browser.setSelectorFallbacks(command.selector, fallbacks);
+  return successResponse(command.id, {
+    target: command.selector,
+    selector: candidates[0]?.selector ?? null,
+    candidates,
+    fallbacks,
+  });
+}
+
+async function handleSelectorsHeal(
+  command: SelectorsHealCommand,
+  browser: BrowserManager
+): Promise<Response<SelectorsHealData>> {
+  const ref = parseRef(command.selector);
+  const refData = ref ? browser.getRefMap()[ref] : undefined;
+  if (refData?.frames?.length) {
+    return errorResponse(command.id, 'Selector healing is not supported inside iframes');
+  }
+  const accessible =
+    refData && refData.role !== 'clickable' && refData.role !== 'focusable'
+      ? { role: refData.role, name: refData.name }
+      : undefined;
+  const fallbacks = refData
+    ? (refData.fallbacks ?? [])
+    : browser.getSelectorFallbacks(command.selector);
+  const healed = await healSelector(browser.getPage(), command.selector, fallbacks, accessible);
+  return successResponse(command.id, { target: command.selector, ...healed });
+}
+
 async function handleCount(command: CountCommand, browser: BrowserManager): Promise<Response> {
   const page = browser.getPage();
   const count = await page.locator(command.selector).count();
@@ -1371,7 +2192,7 @@ async function handleVideoStop(
   const video = page.video();
   if (video) {
     const path = await video.path();
-    return successResponse(command.id, { path });
+    return successResponse(command.id, savedFile(path));
   }
   return successResponse(command.id, { note: 'No video recording active' });
 }
@@ -1391,8 +2212,106 @@ async function handleTraceStop(
   command: TraceStopCommand,
   browser: BrowserManager
 ): Promise<Response> {
-  await browser.stopTracing(command.path);
-  return successResponse(command.id, { path: command.path });
+  const savePath = command.path ?? artifactPath('traces', timestampedName('trace', 'zip'));
+  await browser.stopTracing(savePath);
+  return successResponse(command.id, savedFile(savePath));
+}
+
+function handleCaptureStart(command: CaptureStartCommand, browser: BrowserManager): Response {
+  const out = path.resolve(command.out);
+  browser.startCapture({ url: command.url, out, maxSize: command.maxSize, mime: command.mime });
+  return successResponse(command.id, { capturing: true, dir: out });
+}
+
+function handleApis(command: ApisCommand, browser: BrowserManager): Response {
+  if (command.clear) {
+    browser.clearApis();
+    return successResponse(command.id, { cleared: true });
+  }
+  return successResponse(command.id, { apis: browser.getApis(command.filter) });
+}
+
+async function handleInput(command: InputCommand, browser: BrowserManager): Promise<Response> {
+  switch (command.op) {
+    case 'record':
+      if (!command.path) {
+        throw new Error('Missing timeline path');
+      }
+      await browser.startInputRecording(command.path);
+      return successResponse(command.id, { recording: command.path });
+    case 'stop': {
+      const saved = browser.stopInputRecording();
+      return successResponse(command.id, { ...saved, ...savedFile(saved.path) });
+    }
+    case 'replay': {
+      if (!command.path) {
+        throw new Error('Missing timeline path');
+      }
+      if (!existsSync(command.path)) {
+        throw new Error(`Input timeline not found: ${command.path}`);
+      }
+      const timeline = readTimeline(command.path);
+      const result = await replayTimeline(browser.getPage(), timeline, command.speed);
+      return successResponse(command.id, { path: command.path, ...result });
+    }
+  }
+}
+
+function handleWs(command: WsCommand, browser: BrowserManager): Response {
+  const tracker = browser.getWebSockets();
+  switch (command.op) {
+    case 'list':
+      return successResponse(command.id, { sockets: tracker.list() });
+    case 'tail': {
+      if (!command.socket) {
+        throw new Error('Missing socket id. Run: agent-browser ws list');
+      }
+      const socket = tracker.get(command.socket);
+      if (!socket) {
+        throw new Error(`No WebSocket matches "${command.socket}". Run: agent-browser ws list`);
+      }
+      const { frames, ...info } = socket;
+      return successResponse(command.id, {
+        socket: info,
+        frames: frames.slice(-(command.count ?? 20)),
+      });
+    }
+    case 'export': {
+      if (!command.path) {
+        throw new Error('Missing output path');
+      }
+      const out = path.resolve(command.path);
+      const sockets = tracker.all();
+      writeFileSync(out, JSON.stringify({ webSockets: sockets }, null, 2));
+      return successResponse(command.id, { path: out, sockets: sockets.length });
+    }
+    case 'clear':
+      tracker.clear();
+      return successResponse(command.id, { cleared: true });
+  }
+}
+
+async function handleHarReplay(
+  command: HarReplayCommand,
+  browser: BrowserManager
+): Promise<Response> {
+  if (!existsSync(command.path)) {
+    throw new Error(`HAR file not found: ${command.path}`);
+  }
+  await browser.replayFromHar(command.path, {
+    notFound: command.notFound ?? 'abort',
+    url: command.match,
+  });
+  if (!command.url) {
+    return successResponse(command.id, { replaying: command.path });
+  }
+  const page = browser.getPage();
+  await page.goto(command.url, { waitUntil: 'load' });
+  return successResponse(command.id, {
+    replaying: command.path,
+    url: page.url(),
+    title: await page.title(),
+  });
 }
 
 async function handleHarStart(
@@ -1419,7 +2338,7 @@ async function handleStateSave(
   browser: BrowserManager
 ): Promise<Response> {
   await browser.saveStorageState(command.path);
-  return successResponse(command.id, { path: command.path });
+  return successResponse(command.id, savedFile(command.path));
 }
 
 async function handleStateLoad(
@@ -1510,6 +2429,143 @@ async function handleHighlight(
   return successResponse(command.id, { highlighted: true });
 }
 
+/**
+ * Child indexes from the top document's root element down to `el`, noting where
+ * the path enters an open shadow root. Null inside iframes and closed shadow roots,
+ * which an expression evaluated on the top document can't reach.
+ */
+function elementPath(el: Element): Array<[number, boolean]> | null {
+  if (window !== window.top) return null;
+  const steps: Array<[number, boolean]> = [];
+  let node = el;
+  while (node !== document.documentElement) {
+    const parent = node.parentNode;
+    if (!parent) return null;
+    const shadow = parent instanceof ShadowRoot;
+    if (shadow && (parent as ShadowRoot).mode !== 'open') return null;
+    steps.unshift([Array.prototype.indexOf.call(parent.children, node), shadow]);
+    node = shadow ? (parent as ShadowRoot).host : (parent as Element);
+  }
+  return steps;
+}
+
+async function handleInspect(
+  command: InspectCommand,
+  browser: BrowserManager
+): Promise<Response<InspectData>> {
+  const locator = browser.getLocator(command.selector);
+
+  // Evaluated in the page as a string (see handleStyles)
+  const inspectScript = `(function(el) {
+    const s = getComputedStyle(el);
+    const r = el.getBoundingClientRect();
+    const describe = (node) => {
+      let d = node.tagName.toLowerCase();
+      if (node.id) d += '#' + node.id;
+      if (typeof node.className === 'string' && node.className.trim()) {
+        d += '.' + node.className.trim().split(/\\s+/).join('.');
+      }
+      return d;
+    };
+
+    const styles = {};
+    for (const prop of ['display', 'visibility', 'opacity', 'pointerEvents', 'position',
+      'zIndex', 'overflow', 'cursor', 'color', 'backgroundColor', 'fontSize']) {
+      styles[prop] = s[prop];
+    }
+
+    const attributes = {};
+    for (const attr of el.attributes) attributes[attr.name] = attr.value;
+
+    const visible = r.width > 0 && r.height > 0 &&
+      s.visibility !== 'hidden' && s.display !== 'none' && s.opacity !== '0';
+    const inViewport = r.bottom > 0 && r.right > 0 &&
+      r.top < window.innerHeight && r.left < window.innerWidth;
+
+    let occludedBy = null;
+    if (visible && inViewport) {
+      const top = document.elementFromPoint(r.x + r.width / 2, r.y + r.height / 2);
+      if (top && top !== el && !el.contains(top)) occludedBy = describe(top);
+    }
+
+    const inline = [];
+    for (const attr of el.attributes) {
+      if (attr.name.startsWith('on')) inline.push(attr.name.slice(2));
+    }
+
+    return {
+      tag: el.tagName.toLowerCase(),
+      box: {
+        x: Math.round(r.x),
+        y: Math.round(r.y),
+        width: Math.round(r.width),
+        height: Math.round(r.height),
+      },
+      styles,
+      attributes,
+      visible,
+      occludedBy,
+      scroll: {
+        top: el.scrollTop,
+        left: el.scrollLeft,
+        windowX: Math.round(window.scrollX),
+        windowY: Math.round(window.scrollY),
+        inViewport,
+      },
+      listeners: inline,
+    };
+  })`;
+
+  let data: InspectData;
+  try {
+    data = (await locator.evaluate((el, script) => {
+      const fn = eval(script);
+      return fn(el);
+    }, inspectScript)) as InspectData;
+  } catch (error) {
+    throw toAIFriendlyError(error, command.selector);
+  }
+
+  // addEventListener registrations are only visible through CDP, which can't see
+  // Playwright's handles. Find the element's position in the tree through its handle
+  // and resolve that position over CDP instead of marking the page, then fall back
+  // to inline handlers if that fails.
+  let handle: ElementHandle | null = null;
+  try {
+    handle = await locator.elementHandle({ timeout: 5000 });
+    const steps = handle && (await handle.evaluate(elementPath));
+    if (steps) {
+      const cdp = await browser.getCDPSession();
+      const expression =
+        'document.documentElement' +
+        steps
+          .map(([index, shadow]) => `${shadow ? '.shadowRoot' : ''}.children[${index}]`)
+          .join('');
+      const { result } = await cdp.send('Runtime.evaluate', { expression });
+      if (result.objectId) {
+        try {
+          // The page may have changed in between; only trust the same kind of element
+          const { node } = await cdp.send('DOM.describeNode', { objectId: result.objectId });
+          if (node.nodeName.toLowerCase() === data.tag) {
+            const { listeners } = await cdp.send('DOMDebugger.getEventListeners', {
+              objectId: result.objectId,
+            });
+            data.listeners = [...new Set([...data.listeners, ...listeners.map((l) => l.type)])];
+          }
+        } finally {
+          await cdp.send('Runtime.releaseObject', { objectId: result.objectId });
+        }
+      }
+    }
+  } catch {
+    // CDP unavailable (non-Chromium) - keep inline handlers only
+  } finally {
+    await handle?.dispose();
+  }
+
+  return successResponse(command.id, data);
+}
+
 async function handleClear(command: ClearCommand, browser: BrowserManager): Promise<Response> {
   const page = browser.getPage();
   await page.locator(command.selector).clear();
@@ -1750,7 +2806,8 @@ async function handleWaitForLoadState(
   browser: BrowserManager
 ): Promise<Response> {
   const page = browser.getPage();
-  await page.waitForLoadState(command.state, { timeout: command.timeout });
+  const idle = command.state === 'networkidle' ? command.timeouts?.idle?.ms : undefined;
+  await page.waitForLoadState(command.state, { timeout: command.timeout ?? idle });
   return successResponse(command.id, { state: command.state });
 }
 
@@ -1892,23 +2949,72 @@ async function handleWaitForDownload(
   browser: BrowserManager
 ): Promise<Response> {
   const page = browser.getPage();
-  const download = await page.waitForEvent('download', { timeout: command.timeout });
-
-  let filePath: string;
-  if (command.path) {
-    filePath = command.path;
-    await download.saveAs(filePath);
-  } else {
-    filePath = (await download.path()) || download.suggestedFilename();
+  if (command.arm) {
+    browser.armWait('download', page.waitForEvent('download', { timeout: command.timeout }));
+    return successResponse(command.id, { armed: 'download' });
   }
 
+  const download =
+    (await browser.takeArmedWait<Download>('download')) ??
+    (await page.waitForEvent('download', { timeout: command.timeout }));
+
+  // Playwright deletes its own copy when the context closes, so always save one
+  const filePath = command.path ?? artifactPath('downloads', download.suggestedFilename());
+  await download.saveAs(filePath);
+
   return successResponse(command.id, {
-    path: filePath,
+    ...savedFile(filePath),
     filename: download.suggestedFilename(),
     url: download.url(),
   });
 }
 
+async function handleWaitForPopup(
+  command: WaitForPopupCommand,
+  browser: BrowserManager
+): Promise<Response> {
+  const page = browser.getPage();
+  if (command.arm) {
+    browser.armWait('popup', page.waitForEvent('popup', { timeout: command.timeout }));
+    return successResponse(command.id, { armed: 'popup' });
+  }
+
+  const popup =
+    (await browser.takeArmedWait<Page>('popup')) ??
+    (await page.waitForEvent('popup', { timeout: command.timeout }));
+  await popup.waitForLoadState('domcontentloaded').catch(() => {});
+
+  return successResponse(command.id, {
+    url: popup.url(),
+    title: await popup.title(),
+    index: browser.getPages().indexOf(popup),
+  });
+}
+
+async function handleWaitForRequest(
+  command: WaitForRequestCommand,
+  browser: BrowserManager
+): Promise<Response> {
+  const page = browser.getPage();
+  const key = `request:${command.url}`;
+  const waitForMatch = () =>
+    page.waitForRequest((req) => req.url().includes(command.url), {
+      timeout: command.timeout,
+    });
+
+  if (command.arm) {
+    browser.armWait(key, waitForMatch());
+    return successResponse(command.id, { armed: key });
+  }
+
+  const request = (await browser.takeArmedWait<Request>(key)) ?? (await waitForMatch());
+  return successResponse(command.id, {
+    url: request.url(),
+    method: request.method(),
+    resourceType: request.resourceType(),
+  });
+}
+
 async function handleResponseBody(
   command: ResponseBodyCommand,
   browser: BrowserManager
@@ -1940,22 +3046,44 @@ async function handleScreencastStart(
   command: ScreencastStartCommand,
   browser: BrowserManager
 ): Promise<Response<ScreencastStartData>> {
-  if (!screencastFrameCallback) {
-    throw new Error('Screencast frame callback not set. Start the streaming server first.');
+  if (!command.path && !screencastFrameCallback) {
+    throw new Error(
+      'Screencast frame callback not set. Start the streaming server first, or give a file to write frames to.'
+    );
+  }
+  if (browser.isScreencasting()) {
+    throw new Error('Screencast already active');
   }
 
-  await browser.startScreencast(screencastFrameCallback, {
-    format: command.format,
-    quality: command.quality,
-    maxWidth: command.maxWidth,
-    maxHeight: command.maxHeight,
-    everyNthFrame: command.everyNthFrame,
-  });
+  // A file or named pipe gets the frames instead of the stream server's clients
+  const writer = command.path ? new FrameWriter(command.path) : null;
+  try {
+    await browser.startScreencast(
+      writer ? (frame) => writer.write(frame) : screencastFrameCallback!,
+      {
+        format: command.format,
+        quality: command.quality,
+        maxWidth: command.maxWidth,
+        maxHeight: command.maxHeight,
+        everyNthFrame: command.everyNthFrame,
+        scale: command.scale,
+        fps: command.fps,
+      }
+    );
+  } catch (err) {
+    await writer?.close();
+    throw err;
+  }
+  await screencastWriter?.close();
+  screencastWriter = writer;
 
   return successResponse(command.id, {
     started: true,
     format: command.format ?? 'jpeg',
     quality: command.quality ?? 80,
+    ...(command.path ? { path: command.path } : {}),
+    ...(command.scale ? { scale: command.scale } : {}),
+    ...(command.fps ? { fps: command.fps } : {}),
   });
 }
 
@@ -1964,7 +3092,9 @@ async function handleScreencastStop(
   browser: BrowserManager
 ): Promise<Response<ScreencastStopData>> {
   await browser.stopScreencast();
-  return successResponse(command.id, { stopped: true });
+  const writer = screencastWriter;
+  screencastWriter = null;
+  return successResponse(command.id, writer ? await writer.close() : { stopped: true });
 }
 
 async function handleInputMouse(
@@ -2028,7 +3158,10 @@ async function handleRecordingStop(
   browser: BrowserManager
 ): Promise<Response<RecordingStopData>> {
   const result = await browser.stopRecording();
-  return successResponse(command.id, result);
+  return successResponse(command.id, {
+    ...result,
+    artifacts: result.path ? [path.resolve(result.path)] : [],
+  });
 }
 
 async function handleRecordingRestart(
@@ -0,0 +1,100 @@
+import { describe, it, expect } from 'vitest';
+import { FilterEngine, parseFilter } from './adblock.js';
+
+const LIST = `[Adblock Plus 2.0]
+! Title: test list
+||ads.example.com^
+||tracker.net/pixel.gif
+/banner/*/ad_$image
+||cdn.example.org^$script,third-party
+||widgets.example.org^$domain=news.com|~sports.news.com
+@@||ads.example.com/allowed/
+example.com##.ad-banner
+||rewrite.example.com^$redirect=noop.js
+`;
+
+function engine(): FilterEngine {
+  const e = new FilterEngine();
+  e.addList(LIST);
+  return e;
+}
+
+describe('parseFilter', () => {
+  it('skips comments, headers and cosmetic rules', () => {
+    expect(parseFilter('! comment')).toBeNull();
+    expect(parseFilter('[Adblock Plus 2.0]')).toBeNull();
+    expect(parseFilter('example.com##.ad')).toBeNull();
+    expect(parseFilter('example.com#@#.ad')).toBeNull();
+  });
+
+  it('skips filters with unsupported options', () => {
+    expect(parseFilter('||example.com^$redirect=noop.js')).toBeNull();
+    expect(parseFilter('||example.com^$csp=script-src none')).toBeNull();
+  });
+
+  it('skips filters that would match everything', () => {
+    expect(parseFilter('*')).toBeNull();
+    expect(parseFilter('|')).toBeNull();
+  });
+
+  it('parses regex filters', () => {
+    const filter = parseFilter('/\\/ads?\\d+\\.js/$script');
+    expect(filter?.regex.test('https://x.com/ad3.js')).toBe(true);
+    expect(filter?.types?.has('script')).toBe(true);
+  });
+});
+
+describe('FilterEngine', () => {
+  it('counts usable filters', () => {
+    expect(engine().size).toBe(6);
+  });
+
+  it('matches domain anchors and subdomains', () => {
+    const e = engine();
+    expect(e.shouldBlock({ url: 'https://ads.example.com/x.js', type: 'script' })).toBe(true);
+    expect(e.shouldBlock({ url: 'https://eu.ads.example.com/x.js', type: 'script' })).toBe(true);
+    expect(e.shouldBlock({ url: 'https://badads.example.com/x.js', type: 'script' })).toBe(false);
+    expect(e.shouldBlock({ url: 'https://example.com/ads.example.com', type: 'script' })).toBe(
+      false
+    );
+  });
+
+  it('honors exceptions', () => {
+    const e = engine();
+    expect(e.shouldBlock({ url: 'https://ads.example.com/allowed/a.js', type: 'script' })).toBe(
+      false
+    );
+  });
+
+  it('matches wildcards and resource types', () => {
+    const e = engine();
+    const url = 'https://site.com/banner/top/ad_1.png';
+    expect(e.shouldBlock({ url, type: 'image' })).toBe(true);
+    expect(e.shouldBlock({ url, type: 'script' })).toBe(false);
+  });
+
+  it('checks third-party against the requesting page', () => {
+    const e = engine();
+    const url = 'https://cdn.example.org/lib.js';
+    expect(e.shouldBlock({ url, type: 'script', sourceUrl: 'https://blog.com/' })).toBe(true);
+    expect(e.shouldBlock({ url, type: 'script', sourceUrl: 'https://www.example.org/' })).toBe(
+      false
+    );
+  });
+
+  it('checks domain= against the requesting page', () => {
+    const e = engine();
+    const url = 'https://widgets.example.org/w.js';
+    expect(e.shouldBlock({ url, type: 'script', sourceUrl: 'https://www.news.com/' })).toBe(true);
+    expect(e.shouldBlock({ url, type: 'script', sourceUrl: 'https://sports.news.com/' })).toBe(
+      false
+    );
+    expect(e.shouldBlock({ url, type: 'script', sourceUrl: 'https://other.com/' })).toBe(false);
+  });
+
+  it('leaves unrelated requests alone', () => {
+    expect(engine().shouldBlock({ url: 'https://example.com/index.html', type: 'document' })).toBe(
+      false
+    );
+  });
+});
@@ -0,0 +1,260 @@
+/**
+ * Network filter engine for EasyList-compatible filter lists.
+ *
+ * Supports the network-filter subset of Adblock Plus syntax: `||` domain
+ * anchors, `|` start/end anchors, `*` wildcards, `^` separators, `@@`
+ * exceptions, /regex/ filters, and the options `third-party`, `domain=`,
+ * `match-case` and resource types. Cosmetic (element hiding) rules are
+ * ignored, and so are filters with options we can't honor (e.g. `redirect`,
+ * `csp`), so nothing is blocked on a misread rule.
+ *
+ * Filters are indexed by a literal token so each request is only checked
+ * against the few filters that could possibly match it.
+ */
+
+import { existsSync, readdirSync, readFileSync } from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+
+/** Directory `agent-browser filters update` downloads lists into. */
+export function getFiltersDir(): string {
+  return path.join(os.homedir(), '.browseros', 'filters');
+}
+
+/** Playwright resource types each filter option applies to. */
+const TYPE_OPTIONS: Record<string, string[]> = {
+  script: ['script'],
+  image: ['image'],
+  stylesheet: ['stylesheet'],
+  font: ['font'],
+  media: ['media'],
+  xmlhttprequest: ['xhr', 'fetch'],
+  xhr: ['xhr', 'fetch'],
+  subdocument: ['document'],
+  websocket: ['websocket'],
+  ping: ['ping'],
+  object: ['other'],
+  other: ['other', 'eventsource', 'manifest', 'texttrack'],
+};
+
+/** Options that don't change which requests match. */
+const IGNORED_OPTIONS = new Set(['important', 'all', 'match-case']);
+
+interface Filter {
+  regex: RegExp;
+  exception: boolean;
+  types?: Set<string>;
+  excludedTypes?: Set<string>;
+  thirdParty?: boolean;
+  domains?: string[];
+  excludedDomains?: string[];
+}
+
+export interface FilterRequest {
+  url: string;
+  /** Playwright resource type, e.g. `script` or `xhr` */
+  type: string;
+  /** URL of the page or frame that made the request */
+  sourceUrl?: string;
+}
+
+function hostOf(url: string | undefined): string {
+  if (!url) return '';
+  try {
+    return new URL(url).hostname.toLowerCase();
+  } catch {
+    return '';
+  }
+}
+
+/** Last two labels of a host; an approximation of the registrable domain. */
+function siteOf(host: string): string {
+  return host.split('.').slice(-2).join('.');
+}
+
+function hostMatches(host: string, domain: string): boolean {
+  return host === domain || host.endsWith('.' + domain);
+}
+
+function patternToRegex(pattern: string, matchCase: boolean): RegExp {
+  let source = '';
+  let rest = pattern;
+  if (rest.startsWith('||')) {
+    source = '^[a-z][a-z0-9+.-]*:\\/\\/(?:[^\\/?#]*\\.)?';
+    rest = rest.slice(2);
+  } else if (rest.startsWith('|')) {
+    source = '^';
+    rest = rest.slice(1);
+  }
+  let suffix = '';
+  if (rest.endsWith('|')) {
+    suffix = '$';
+    rest = rest.slice(0, -1);
+  }
+  for (const ch of rest) {
+    if (ch === '*') source += '.*';
+    else if (ch === '^') source += '(?:[^\\w\\-.%]|$)';
+    else source += ch.replace(/[.+?${}()|[\]\\/]/g, '\\$&');
+  }
+  return new RegExp(source + suffix, matchCase ? '' : 'i');
+}
+
+/**
+ * Longest literal run that every matching URL must contain as a whole token.
+ * Runs next to a wildcard may be part of a longer token, so they don't count.
+ */
+function indexToken(pattern: string): string | null {
+  if (pattern.startsWith('/')) return null;
+  const body = pattern.replace(/^\|\|?/, '').replace(/\|$/, '');
+  let best: string | null = null;
+  for (const match of body.matchAll(/[a-z0-9%]+/gi)) {
+    const start = match.index ?? 0;
+    const end = start + match[0].length;
+    // An anchor bounds the run at either end of the pattern
+    const boundedBefore = start === 0 ? pattern.startsWith('|') : body[start - 1] !== '*';
+    const boundedAfter = end === body.length ? pattern.endsWith('|') : body[end] !== '*';
+    if (!boundedBefore || !boundedAfter) continue;
+    if (match[0].length >= 2 && (!best || match[0].length > best.length)) {
+      best = match[0].toLowerCase();
+    }
+  }
+  return best;
+}
+
+/** Parse one list line into a filter, or null if it isn't a usable network filter. */
+export function parseFilter(line: string): (Filter & { token: string | null }) | null {
+  let text = line.trim();
+  if (!text || text.startsWith('!') || text.startsWith('[')) return null;
+  if (/#[@?$%]?#|#\+js\(/.test(text)) return null;
+
+  const exception = text.startsWith('@@');
+  if (exception) text = text.slice(2);
+
+  let pattern = text;
+  let optionText = '';
+  const isRegex = /^\/.*\/(\$.*)?$/.test(text);
+  const dollar = isRegex ? text.lastIndexOf('/$') + 1 : text.lastIndexOf('$');
+  if (dollar > 0) {
+    pattern = text.slice(0, dollar);
+    optionText = text.slice(dollar + 1);
+  }
+
+  const filter: Filter = { regex: /(?:)/, exception };
+  let matchCase = false;
+  const types = new Set<string>();
+  const excludedTypes = new Set<string>();
+  for (const raw of optionText ? optionText.split(',') : []) {
+    const negated = raw.startsWith('~');
+    const name = (negated ? raw.slice(1) : raw).toLowerCase();
+    if (name === 'third-party' || name === '3p') {
+      filter.thirdParty = !negated;
+    } else if (name === 'first-party' || name === '1p') {
+      filter.thirdParty = negated;
+    } else if (name === 'match-case') {
+      matchCase = true;
+    } else if (name.startsWith('domain=')) {
+      const domains = raw.slice('domain='.length).toLowerCase().split('|');
+      filter.domains = domains.filter((d) => !d.startsWith('~'));
+      filter.excludedDomains = domains.filter((d) => d.startsWith('~')).map((d) => d.slice(1));
+    } else if (TYPE_OPTIONS[name]) {
+      for (const t of TYPE_OPTIONS[name]) (negated ? excludedTypes : types).add(t);
+    } else if (!IGNORED_OPTIONS.has(name)) {
+      return null;
+    }
+  }
+  if (types.size) filter.types = types;
+  if (excludedTypes.size) filter.excludedTypes = excludedTypes;
+
+  if (isRegex) {
+    try {
+      filter.regex = new RegExp(pattern.slice(1, -1), matchCase ? '' : 'i');
+    } catch {
+      return null;
+    }
+  } else {
+    // A bare pattern with no anchors or literals would block everything
+    if (!pattern.replace(/[*|^]/g, '')) return null;
+    filter.regex = patternToRegex(pattern, matchCase);
+  }
+  return { ...filter, token: indexToken(pattern) };
+}
+
+function filterApplies(filter: Filter, request: FilterRequest, url: string): boolean {
+  if (filter.types && !filter.types.has(request.type)) return false;
+  if (filter.excludedTypes?.has(request.type)) return false;
+  if (filter.thirdParty !== undefined || filter.domains || filter.excludedDomains) {
+    const source = hostOf(request.sourceUrl);
+    if (filter.thirdParty !== undefined) {
+      const thirdParty = !!source && siteOf(source) !== siteOf(hostOf(url));
+      if (thirdParty !== filter.thirdParty) return false;
+    }
+    if (filter.domains?.length && !filter.domains.some((d) => hostMatches(source, d))) {
+      return false;
+    }
+    if (filter.excludedDomains?.some((d) => hostMatches(source, d))) return false;
+  }
+  return filter.regex.test(url);
+}
+
+class FilterIndex {
+  private byToken = new Map<string, Filter[]>();
+  private untokenized: Filter[] = [];
+
+  add(filter: Filter, token: string | null): void {
+    if (!token) {
+      this.untokenized.push(filter);
+      return;
+    }
+    const bucket = this.byToken.get(token);
+    if (bucket) bucket.push(filter);
+    else this.byToken.set(token, [filter]);
+  }
+
+  matches(request: FilterRequest, tokens: Set<string>): boolean {
+    for (const token of tokens) {
+      const bucket = this.byToken.get(token);
+      if (bucket?.some((f) => filterApplies(f, request, request.url))) return true;
+    }
+    return this.untokenized.some((f) => filterApplies(f, request, request.url));
+  }
+}
+
+export class FilterEngine {
+  private blocks = new FilterIndex();
+  private exceptions = new FilterIndex();
+  private count = 0;
+
+  /** Add the filters from one list. Returns how many were usable. */
+  addList(text: string): number {
+    let added = 0;
+    for (const line of text.split(/\r?\n/)) {
+      const filter = parseFilter(line);
+      if (!filter) continue;
+      (filter.exception ? this.exceptions : this.blocks).add(filter, filter.token);
+      added++;
+    }
+    this.count += added;
+    return added;
+  }
+
+  get size(): number {
+    return this.count;
+  }
+
+  shouldBlock(request: FilterRequest): boolean {
+    const tokens = new Set(request.url.toLowerCase().match(/[a-z0-9%]+/g) ?? []);
+    return this.blocks.matches(request, tokens) && !this.exceptions.matches(request, tokens);
+  }
+
+  /** Load every `.txt` list in a directory. */
+  static fromDirectory(dir: string): FilterEngine {
+    const engine = new FilterEngine();
+    if (!existsSync(dir)) return engine;
+    for (const name of readdirSync(dir).sort()) {
+      if (name.endsWith('.txt')) {
+        engine.addList(readFileSync(path.join(dir, name), 'utf-8'));
+      }
+    }
+    return engine;
+  }
+}
@@ -0,0 +1,39 @@
+import { describe, it, expect } from 'vitest';
+import { graphqlOperation, schemaSketch, urlTemplate } from './apis.js';
+
+describe('urlTemplate', () => {
+  it('replaces ids and drops query values', () => {
+    expect(urlTemplate('https://api.example.com/users/42/posts?page=2&sort=new')).toBe(
+      'https://api.example.com/users/{id}/posts?page=&sort='
+    );
+    expect(
+      urlTemplate('https://example.com/orders/3f2504e0-4f89-11d3-9a0c-0305e82c3301')
+    ).toBe('https://example.com/orders/{uuid}');
+    expect(urlTemplate('https://example.com/v2/search')).toBe('https://example.com/v2/search');
+  });
+});
+
+describe('graphqlOperation', () => {
+  it('reads the operation name from the body', () => {
+    expect(graphqlOperation('https://x.com/graphql', '{"operationName":"GetUser"}')).toBe(
+      'GetUser'
+    );
+    expect(
+      graphqlOperation('https://x.com/graphql', '{"query":"query Feed($n: Int) { feed }"}')
+    ).toBe('Feed');
+    expect(graphqlOperation('https://x.com/api', '{"name":"x"}')).toBeUndefined();
+  });
+
+  it('reads the operation name from a GET query string', () => {
+    expect(graphqlOperation('https://x.com/graphql?operationName=Home', null)).toBe('Home');
+  });
+});
+
+describe('schemaSketch', () => {
+  it('replaces values with their types', () => {
+    expect(schemaSketch({ items: [{ id: 1, name: 'a', tags: [] }], next: null })).toEqual({
+      items: [{ id: 'number', name: 'string', tags: [] }],
+      next: 'null',
+    });
+  });
+});
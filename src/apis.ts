@@ -0,0 +1,164 @@
+/**
+ * Summarize the XHR/fetch API calls a site makes (`network apis`).
+ *
+ * Calls are grouped by method and URL template, so `/users/42` and
+ * `/users/7` are one endpoint, `/users/{id}`. GraphQL requests are grouped
+ * by operation name instead, since they all share one URL. The first JSON
+ * response of each endpoint is reduced to a schema sketch: its shape with
+ * values replaced by their types.
+ */
+
+import type { Response } from 'playwright-core';
+
+export interface ApiEndpoint {
+  method: string;
+  /** URL template, e.g. https://api.example.com/users/{id}?page= */
+  url: string;
+  /** GraphQL operation name, when the request is a GraphQL call */
+  operation?: string;
+  count: number;
+  statuses: number[];
+  mimeType: string;
+  /** Shape of the first JSON response body */
+  schema?: unknown;
+}
+
+/** Endpoints kept per session; further new endpoints are ignored. */
+const MAX_ENDPOINTS = 500;
+/** Larger bodies aren't read for a schema sketch. */
+const MAX_SCHEMA_BODY = 512 * 1024;
+const MAX_SCHEMA_DEPTH = 4;
+const MAX_SCHEMA_KEYS = 30;
+
+function templateSegment(segment: string): string {
+  if (/^\d+$/.test(segment)) return '{id}';
+  if (/^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$/i.test(segment)) {
+    return '{uuid}';
+  }
+  // Long tokens mixing letters and digits; slugs like get-user-preferences stay
+  if (/^[0-9a-f]{16,}$/i.test(segment) || /^(?=.*\d)(?=.*[A-Za-z])[\w-]{24,}$/.test(segment)) {
+    return '{hash}';
+  }
+  return segment;
+}
+
+/** Replace IDs in the path with placeholders and drop query values. */
+export function urlTemplate(url: string): string {
+  let parsed: URL;
+  try {
+    parsed = new URL(url);
+  } catch {
+    return url;
+  }
+  const pathname = parsed.pathname.split('/').map(templateSegment).join('/');
+  const keys = [...new Set(parsed.searchParams.keys())].sort();
+  const query = keys.length ? '?' + keys.map((k) => `${k}=`).join('&') : '';
+  return `${parsed.origin}${pathname}${query}`;
+}
+
+/** Operation name of a GraphQL request body, or of a persisted-query GET. */
+export function graphqlOperation(url: string, postData: string | null): string | undefined {
+  const fromBody = (body: unknown): string | undefined => {
+    if (!body || typeof body !== 'object') return undefined;
+    const b = body as { operationName?: unknown; query?: unknown };
+    if (typeof b.operationName === 'string' && b.operationName) return b.operationName;
+    if (typeof b.query === 'string') {
+      const match = b.query.match(/^\s*(query|mutation|subscription)\s+(\w+)/);
+      if (match) return match[2];
+      if (/^\s*\{/.test(b.query)) return '(anonymous)';
+    }
+    return undefined;
+  };
+  if (postData) {
+    try {
+      const body = JSON.parse(postData);
+      // Batched queries: name the batch after its operations
+      if (Array.isArray(body)) {
+        const names = body.map(fromBody).filter(Boolean);
+        return names.length ? names.join('+') : undefined;
+      }
+      return fromBody(body);
+    } catch {
+      return undefined;
+    }
+  }
+  try {
+    const name = new URL(url).searchParams.get('operationName');
+    return name || undefined;
+  } catch {
+    return undefined;
+  }
+}
+
+/** The shape of a JSON value, with leaves replaced by their type names. */
+export function schemaSketch(value: unknown, depth: number = 0): unknown {
+  if (value === null) return 'null';
+  if (Array.isArray(value)) {
+    if (value.length === 0) return [];
+    return depth >= MAX_SCHEMA_DEPTH ? ['...'] : [schemaSketch(value[0], depth + 1)];
+  }
+  if (typeof value === 'object') {
+    if (depth >= MAX_SCHEMA_DEPTH) return '{...}';
+    const entries = Object.entries(value as Record<string, unknown>);
+    const sketch: Record<string, unknown> = {};
+    for (const [key, v] of entries.slice(0, MAX_SCHEMA_KEYS)) {
+      sketch[key] = schemaSketch(v, depth + 1);
+    }
+    if (entries.length > MAX_SCHEMA_KEYS) {
+      sketch['...'] = `${entries.length - MAX_SCHEMA_KEYS} more`;
+    }
+    return sketch;
+  }
+  return typeof value;
+}
+
+export class ApiCatalog {
+  private endpoints: Map<string, ApiEndpoint> = new Map();
+
+  async record(response: Response): Promise<void> {
+    const request = response.request();
+    const type = request.resourceType();
+    if (type !== 'xhr' && type !== 'fetch') return;
+
+    const method = request.method();
+    const url = urlTemplate(request.url());
+    const operation = graphqlOperation(request.url(), request.postData());
+    const key = `${method} ${url} ${operation ?? ''}`;
+    const contentType = response.headers()['content-type'] ?? '';
+    const mimeType = contentType.split(';')[0].trim().toLowerCase();
+
+    let endpoint = this.endpoints.get(key);
+    if (!endpoint) {
+      if (this.endpoints.size >= MAX_ENDPOINTS) return;
+      endpoint = { method, url, operation, count: 0, statuses: [], mimeType };
+      this.endpoints.set(key, endpoint);
+    }
+    endpoint.count++;
+    const status = response.status();
+    if (!endpoint.statuses.includes(status)) endpoint.statuses.push(status);
+
+    const isJson = mimeType === 'application/json' || mimeType.endsWith('+json');
+    const declared = Number(response.headers()['content-length']);
+    if (endpoint.schema !== undefined || !isJson || declared > MAX_SCHEMA_BODY) return;
+    try {
+      const body = await response.text();
+      if (body.length <= MAX_SCHEMA_BODY) {
+        endpoint.schema = schemaSketch(JSON.parse(body));
+      }
+    } catch {
+      // Not valid JSON, or the body is no longer available
+    }
+  }
+
+  list(filter?: string): ApiEndpoint[] {
+    const all = [...this.endpoints.values()];
+    const matching = filter
+      ? all.filter((e) => e.url.includes(filter) || e.operation?.includes(filter))
+      : all;
+    return matching.sort((a, b) => b.count - a.count);
+  }
+
+  clear(): void {
+    this.endpoints.clear();
+  }
+}
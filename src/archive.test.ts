@@ -0,0 +1,75 @@
+import { describe, it, expect } from 'vitest';
+import { buildWarc, frameResources, warcDate, warcRecord } from './archive.js';
+
+describe('page archive', () => {
+  it('frames a WARC record with its length and digest', () => {
+    const date = new Date('2026-03-01T12:30:45.123Z');
+    const record = warcRecord(
+      {
+        type: 'resource',
+        uri: 'https://a.com/',
+        contentType: 'text/html',
+        body: Buffer.from('<p>hi</p>'),
+        id: '00000000-0000-4000-8000-000000000001',
+      },
+      date
+    ).toString();
+    const [head, rest] = record.split('\r\n\r\n');
+    expect(warcDate(date)).toBe('2026-03-01T12:30:45Z');
+    expect(head.split('\r\n')).toEqual([
+      'WARC/1.1',
+      'WARC-Type: resource',
+      'WARC-Record-ID: <urn:uuid:00000000-0000-4000-8000-000000000001>',
+      'WARC-Date: 2026-03-01T12:30:45Z',
+      'WARC-Target-URI: https://a.com/',
+      expect.stringMatching(/^WARC-Block-Digest: sha256:[0-9a-f]{64}$/),
+      'Content-Type: text/html',
+      'Content-Length: 9',
+    ]);
+    expect(rest).toBe('<p>hi</p>');
+    expect(record.endsWith('<p>hi</p>\r\n\r\n')).toBe(true);
+  });
+
+  it('starts with a warcinfo record and links conversions', () => {
+    const warc = buildWarc(
+      'page.warc',
+      [
+        {
+          type: 'conversion',
+          uri: 'https://a.com/',
+          contentType: 'image/png',
+          body: Buffer.from('png'),
+          refersTo: 'abc',
+        },
+      ],
+      new Date(0)
+    ).toString();
+    expect(warc.startsWith('WARC/1.1\r\nWARC-Type: warcinfo\r\n')).toBe(true);
+    expect(warc).toContain('filename: page.warc');
+    expect(warc).toContain('WARC-Refers-To: <urn:uuid:abc>');
+    expect(warc.match(/^WARC\/1\.1$/gm)).toHaveLength(2);
+  });
+
+  it('lists each frame and loaded resource once', () => {
+    const resources = frameResources({
+      frame: { id: 'main', url: 'https://a.com/', mimeType: 'text/html' },
+      resources: [
+        { url: 'https://a.com/app.css', mimeType: 'text/css' },
+        { url: 'https://a.com/app.css', mimeType: 'text/css' },
+        { url: 'https://a.com/gone.js', mimeType: 'text/javascript', failed: true },
+        { url: 'data:image/png;base64,AAAA', mimeType: 'image/png' },
+      ],
+      childFrames: [
+        {
+          frame: { id: 'ad', url: 'https://ads.com/frame', mimeType: 'text/html' },
+          resources: [{ url: 'https://a.com/app.css', mimeType: 'text/css' }],
+        },
+      ],
+    });
+    expect(resources.map((r) => [r.frameId, r.url])).toEqual([
+      ['main', 'https://a.com/'],
+      ['main', 'https://a.com/app.css'],
+      ['ad', 'https://ads.com/frame'],
+    ]);
+  });
+});
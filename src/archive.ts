@@ -0,0 +1,191 @@
+/**
+ * Page archives (`archive`).
+ *
+ * MHTML is Chrome's own snapshot (`Page.captureSnapshot`): the rendered DOM
+ * with its stylesheets, images and frames in one file that browsers open
+ * offline. WARC 1.1 is built here, for tools like pywb and replayweb.page: a
+ * `resource` record for every document and subresource the page's frames
+ * loaded, read back from the browser with `Page.getResourceContent`, then
+ * `conversion` records for the rendered DOM and a full-page screenshot that
+ * refer to the top document. Resources the browser no longer holds are
+ * counted as skipped rather than failing the archive.
+ */
+
+import { createHash, randomUUID } from 'node:crypto';
+import { writeFileSync } from 'node:fs';
+import type { CDPSession, Page } from 'playwright-core';
+import type { ArchiveData, ArchiveFormat } from './types.js';
+
+export interface WarcRecord {
+  type: 'warcinfo' | 'resource' | 'conversion';
+  /** `WARC-Target-URI`; warcinfo records have none */
+  uri?: string;
+  contentType: string;
+  body: Buffer;
+  /** Record ID of the record this one was derived from */
+  refersTo?: string;
+  /** Generated when missing */
+  id?: string;
+}
+
+interface FrameTree {
+  frame: { id: string; url: string; mimeType: string };
+  resources: { url: string; mimeType: string; failed?: boolean; canceled?: boolean }[];
+  childFrames?: FrameTree[];
+}
+
+/** `WARC-Date`: UTC to the second */
+export function warcDate(date: Date): string {
+  return date.toISOString().replace(/\.\d{3}Z$/, 'Z');
+}
+
+/** One record: headers, a blank line, the block, then two CRLFs */
+export function warcRecord(record: WarcRecord, date: Date): Buffer {
+  const headers = [
+    'WARC/1.1',
+    `WARC-Type: ${record.type}`,
+    `WARC-Record-ID: <urn:uuid:${record.id ?? randomUUID()}>`,
+    `WARC-Date: ${warcDate(date)}`,
+  ];
+  if (record.uri) headers.push(`WARC-Target-URI: ${record.uri}`);
+  if (record.refersTo) headers.push(`WARC-Refers-To: <urn:uuid:${record.refersTo}>`);
+  headers.push(
+    `WARC-Block-Digest: sha256:${createHash('sha256').update(record.body).digest('hex')}`,
+    `Content-Type: ${record.contentType}`,
+    `Content-Length: ${record.body.length}`
+  );
+  return Buffer.concat([
+    Buffer.from(headers.join('\r\n') + '\r\n\r\n'),
+    record.body,
+    Buffer.from('\r\n\r\n'),
+  ]);
+}
+
+/** A WARC file: a warcinfo record describing the capture, then the records */
+export function buildWarc(filename: string, records: WarcRecord[], date: Date): Buffer {
+  const info = Buffer.from(
+    [`software: agent-browser`, `format: WARC File Format 1.1`, `filename: ${filename}`].join(
+      '\r\n'
+    ) + '\r\n'
+  );
+  return Buffer.concat([
+    warcRecord({ type: 'warcinfo', contentType: 'application/warc-fields', body: info }, date),
+    ...records.map((record) => warcRecord(record, date)),
+  ]);
+}
+
+/** Frames and their resources, documents first, each URL once */
+export function frameResources(
+  tree: FrameTree
+): { frameId: string; url: string; mimeType: string }[] {
+  const seen = new Set<string>();
+  const out: { frameId: string; url: string; mimeType: string }[] = [];
+  const add = (frameId: string, url: string, mimeType: string) => {
+    // Inline data has nothing to replay, and blobs don't outlive the page
+    if (seen.has(url) || !/^https?:/.test(url)) return;
+    seen.add(url);
+    out.push({ frameId, url, mimeType });
+  };
+  const walk = (node: FrameTree) => {
+    add(node.frame.id, node.frame.url, node.frame.mimeType);
+    for (const resource of node.resources) {
+      if (!resource.failed && !resource.canceled) {
+        add(node.frame.id, resource.url, resource.mimeType);
+      }
+    }
+    node.childFrames?.forEach(walk);
+  };
+  walk(tree);
+  return out;
+}
+
+export interface PageResource {
+  url: string;
+  mimeType: string;
+  body: Buffer;
+  /** CDP returned it as text, so the body is UTF-8 */
+  text: boolean;
+}
+
+/** The bodies of every document and subresource the page's frames loaded */
+export async function pageResources(
+  cdp: CDPSession
+): Promise<{ resources: PageResource[]; skipped: number }> {
+  await cdp.send('Page.enable');
+  const { frameTree } = await cdp.send('Page.getResourceTree');
+  const resources: PageResource[] = [];
+  let skipped = 0;
+  for (const { frameId, url, mimeType } of frameResources(frameTree as FrameTree)) {
+    try {
+      const { content, base64Encoded } = await cdp.send('Page.getResourceContent', {
+        frameId,
+        url,
+      });
+      resources.push({
+        url,
+        mimeType: mimeType || 'application/octet-stream',
+        body: Buffer.from(content, base64Encoded ? 'base64' : 'utf8'),
+        text: !base64Encoded,
+      });
+    } catch {
+      skipped++;
+    }
+  }
+  return { resources, skipped };
+}
+
+async function warcRecords(
+  page: Page,
+  cdp: CDPSession
+): Promise<{ records: WarcRecord[]; skipped: number }> {
+  const { resources, skipped } = await pageResources(cdp);
+  const records: WarcRecord[] = resources.map(({ url, mimeType, body }) => ({
+    type: 'resource',
+    uri: url,
+    contentType: mimeType,
+    body,
+    id: randomUUID(),
+  }));
+  const top = records.find((record) => record.uri === page.url());
+  const uri = page.url();
+  records.push(
+    {
+      type: 'conversion',
+      uri,
+      contentType: 'text/html; charset=utf-8',
+      body: Buffer.from(await page.content()),
+      refersTo: top?.id,
+    },
+    {
+      type: 'conversion',
+      uri,
+      contentType: 'image/png',
+      body: await page.screenshot({ fullPage: true }),
+      refersTo: top?.id,
+    }
+  );
+  return { records, skipped };
+}
+
+/** Write the current page to `path` as MHTML or WARC */
+export async function archivePage(
+  page: Page,
+  cdp: CDPSession,
+  format: ArchiveFormat,
+  path: string
+): Promise<Omit<ArchiveData, 'path' | 'artifacts'>> {
+  const url = page.url();
+  const title = await page.title();
+  if (format === 'mhtml') {
+    const { data } = await cdp.send('Page.captureSnapshot', { format: 'mhtml' });
+    writeFileSync(path, data);
+    // One Content-Location per part: the document, then each subresource
+    const resources = Math.max(0, (data.match(/^Content-Location:/gim)?.length ?? 1) - 1);
+    return { format, url, title, resources, skipped: 0, bytes: Buffer.byteLength(data) };
+  }
+  const { records, skipped } = await warcRecords(page, cdp);
+  const warc = buildWarc(path.split(/[\\/]/).pop() ?? path, records, new Date());
+  writeFileSync(path, warc);
+  const resources = records.filter((record) => record.type === 'resource').length;
+  return { format, url, title, resources, skipped, bytes: warc.length };
+}
@@ -0,0 +1,45 @@
+/**
+ * Per-session artifacts directory.
+ *
+ * Files a command produces without an explicit path (screenshots, PDFs,
+ * traces, downloads) are written under `~/.browseros/artifacts/<session>/`,
+ * one subdirectory per kind. `agent-browser artifacts` lists, opens and prunes
+ * them. Commands report every file they wrote in an `artifacts` array.
+ */
+
+import { mkdirSync } from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+
+export type ArtifactKind = 'screenshots' | 'pdfs' | 'archives' | 'pages' | 'traces' | 'downloads';
+
+/** Root of all sessions' artifacts; `AGENT_BROWSER_ARTIFACTS_DIR` overrides it. */
+export function getArtifactsRoot(): string {
+  return (
+    process.env.AGENT_BROWSER_ARTIFACTS_DIR || path.join(os.homedir(), '.browseros', 'artifacts')
+  );
+}
+
+export function getArtifactsDir(session?: string): string {
+  return path.join(getArtifactsRoot(), session ?? process.env.AGENT_BROWSER_SESSION ?? 'default');
+}
+
+/** `screenshot-2026-01-31T10-00-00-000Z-x7k2p9.png` */
+export function timestampedName(prefix: string, ext: string): string {
+  const timestamp = new Date().toISOString().replace(/[:.]/g, '-');
+  const random = Math.random().toString(36).substring(2, 8);
+  return `${prefix}-${timestamp}-${random}.${ext}`;
+}
+
+/** Path for a new artifact, creating its directory. */
+export function artifactPath(kind: ArtifactKind, filename: string): string {
+  const dir = path.join(getArtifactsDir(), kind);
+  mkdirSync(dir, { recursive: true });
+  // Downloads keep the server's name, which must not escape the directory
+  return path.join(dir, path.basename(filename));
+}
+
+/** `{ path, artifacts: [absolute path] }` for a response that wrote one file. */
+export function savedFile(filePath: string): { path: string; artifacts: string[] } {
+  return { path: filePath, artifacts: [path.resolve(filePath)] };
+}
@@ -0,0 +1,86 @@
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import { createHash } from 'node:crypto';
+import { appendFileSync, mkdtempSync, readFileSync, rmSync, statSync } from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { AuditLog, GENESIS } from './audit-log.js';
+import type { Command } from './types.js';
+
+let dir: string;
+let file: string;
+
+beforeEach(() => {
+  dir = mkdtempSync(path.join(os.tmpdir(), 'audit-'));
+  file = path.join(dir, 'audit', 'work.jsonl');
+});
+
+afterEach(() => {
+  rmSync(dir, { recursive: true, force: true });
+});
+
+const command = (fields: Record<string, unknown>) => ({ id: 'c1', ...fields }) as Command;
+
+const lines = () => readFileSync(file, 'utf-8').trim().split('\n');
+
+describe('AuditLog', () => {
+  it('chains each entry to the one before it', () => {
+    const log = new AuditLog('work', file);
+    log.command(command({ action: 'navigate', url: 'https://example.com' }), {
+      id: 'c1',
+      success: true,
+      data: {},
+    });
+    log.activity({ type: 'navigation', url: 'https://example.com/' });
+    log.activity({ type: 'download', url: 'https://example.com/a.pdf', filename: 'a.pdf' });
+
+    const entries = lines().map((l) => JSON.parse(l));
+    expect(entries.map((e) => e.seq)).toEqual([1, 2, 3]);
+    expect(entries[0]).toMatchObject({
+      session: 'work',
+      type: 'command',
+      action: 'navigate',
+      command: { action: 'navigate', url: 'https://example.com' },
+      success: true,
+      prev: GENESIS,
+    });
+    expect(entries[0].command.id).toBeUndefined();
+    expect(entries[1].prev).toBe(entries[0].hash);
+    expect(entries[2]).toMatchObject({ type: 'download', filename: 'a.pdf' });
+    expect(entries[2].prev).toBe(entries[1].hash);
+
+    for (const line of lines()) {
+      const { hash } = JSON.parse(line);
+      const unhashed = line.replace(`,"hash":"${hash}"}`, '}');
+      expect(createHash('sha256').update(unhashed).digest('hex')).toBe(hash);
+    }
+    expect(statSync(file).mode & 0o777).toBe(0o600);
+  });
+
+  it('redacts secrets and records failure codes', () => {
+    const entry = new AuditLog('work', file).command(
+      command({ action: 'auth_login', name: 'github', password: 'hunter2' }),
+      { id: 'c1', success: false, error: 'Login failed', code: 'E_AUTH' }
+    );
+    expect(entry).toMatchObject({
+      command: { name: 'github', password: '[redacted]' },
+      success: false,
+      code: 'E_AUTH',
+    });
+    expect(readFileSync(file, 'utf-8')).not.toContain('hunter2');
+  });
+
+  it('continues the chain after a restart', () => {
+    const first = new AuditLog('work', file).activity({ type: 'navigation', url: 'about:blank' });
+    const next = new AuditLog('work', file).activity({ type: 'navigation', url: 'about:blank' });
+    expect(next.seq).toBe(2);
+    expect(next.prev).toBe(first.hash);
+  });
+
+  it('refuses to extend a log whose last line is broken', () => {
+    new AuditLog('work', file).activity({ type: 'navigation', url: 'about:blank' });
+    appendFileSync(file, '{"seq":2,"time":\n');
+    expect(() => new AuditLog('work', file)).toThrow(/audit-log verify/);
+    appendFileSync(file, '{"seq":2}\n');
+    expect(() => new AuditLog('work', file)).toThrow(/not an audit entry/);
+  });
+});
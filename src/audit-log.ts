@@ -0,0 +1,127 @@
+/**
+ * Tamper-evident audit log of what an agent did in a session.
+ *
+ * With `--audit-log` (or `AGENT_BROWSER_AUDIT_LOG=1`), the daemon appends a
+ * line to `~/.browseros/audit/<session>.jsonl` for every command, every
+ * main-frame navigation and every download. Each line carries the SHA-256 of
+ * the line before it (`prev`) and of itself (`hash`), so editing, removing or
+ * reordering an entry breaks the chain from that point on.
+ * `agent-browser audit-log verify` walks the chain and prints the hash of the
+ * last entry; keeping that somewhere else is what catches a truncated tail.
+ *
+ * A line's hash covers its exact text without the trailing `"hash"` field,
+ * so a verifier doesn't need to re-serialize entries the same way.
+ */
+
+import { createHash } from 'node:crypto';
+import { appendFileSync, existsSync, mkdirSync, readFileSync } from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import type { Command, PageActivity, Response } from './types.js';
+
+/** `prev` of the first entry */
+export const GENESIS = '0'.repeat(64);
+
+/** Command fields whose values never reach the log */
+const SECRET_FIELDS = /password|passphrase|secret|token|authorization|cookie/i;
+
+interface CommandRecord {
+  type: 'command';
+  action: string;
+  /** The command as sent, without its id and with secrets redacted */
+  command: Record<string, unknown>;
+  success: boolean;
+  code?: string;
+}
+
+export type AuditEntry = {
+  seq: number;
+  time: string;
+  session: string;
+  prev: string;
+  hash: string;
+} & (CommandRecord | PageActivity);
+
+export function auditLogPath(session: string): string {
+  return path.join(os.homedir(), '.browseros', 'audit', `${session}.jsonl`);
+}
+
+/** A command as sent, without its id and with secret values replaced */
+function redact(command: Command): Record<string, unknown> {
+  const copy: Record<string, unknown> = {};
+  for (const [key, value] of Object.entries(command)) {
+    if (key === 'id') continue;
+    copy[key] = SECRET_FIELDS.test(key) && value !== undefined ? '[redacted]' : value;
+  }
+  return copy;
+}
+
+function sha256(text: string): string {
+  return createHash('sha256').update(text).digest('hex');
+}
+
+export class AuditLog {
+  readonly file: string;
+  private seq = 0;
+  private prev = GENESIS;
+
+  constructor(
+    readonly session: string,
+    file: string = auditLogPath(session)
+  ) {
+    this.file = file;
+    // A restarted daemon extends the chain it left behind
+    if (existsSync(file)) {
+      const last = readFileSync(file, 'utf-8').trimEnd().split('\n').pop();
+      if (last) {
+        let entry: Partial<AuditEntry> | null = null;
+        try {
+          entry = JSON.parse(last) as Partial<AuditEntry>;
+        } catch {
+          // Reported below with the other ways the last line can be wrong
+        }
+        if (typeof entry?.seq !== 'number' || typeof entry.hash !== 'string') {
+          // Starting over would hide whatever cut the chain, so refuse instead
+          throw new Error(
+            `The last line of ${file} is not an audit entry, so the chain can't be extended. ` +
+              `Run 'agent-browser audit-log verify' to see where it breaks`
+          );
+        }
+        this.seq = entry.seq;
+        this.prev = entry.hash;
+      }
+    }
+  }
+
+  command(command: Command, response: Response): AuditEntry {
+    return this.append({
+      type: 'command',
+      action: command.action,
+      command: redact(command),
+      success: response.success,
+      ...(!response.success && response.code && { code: response.code }),
+    });
+  }
+
+  /** A navigation or download the page made, whichever command caused it */
+  activity(activity: PageActivity): AuditEntry {
+    return this.append(activity);
+  }
+
+  private append(body: CommandRecord | PageActivity): AuditEntry {
+    const unhashed = JSON.stringify({
+      seq: this.seq + 1,
+      time: new Date().toISOString(),
+      session: this.session,
+      ...body,
+      prev: this.prev,
+    });
+    const hash = sha256(unhashed);
+    const line = `${unhashed.slice(0, -1)},"hash":"${hash}"}`;
+    mkdirSync(path.dirname(this.file), { recursive: true, mode: 0o700 });
+    appendFileSync(this.file, line + '\n', { mode: 0o600 });
+    this.seq++;
+    this.prev = hash;
+    return JSON.parse(line) as AuditEntry;
+  }
+}
@@ -0,0 +1,138 @@
+import { describe, it, expect } from 'vitest';
+import { mkdtempSync, rmSync, writeFileSync } from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import {
+  BrowserPool,
+  poolOptionsFromEnv,
+  readDevToolsPort,
+  type WarmBrowser,
+} from './browser-pool.js';
+
+/** A launcher whose browsers are numbered and record whether they were stopped */
+function fakeLauncher(clock: { now: number }) {
+  const launched: (WarmBrowser & { stopped: boolean })[] = [];
+  const launcher = async () => {
+    const browser = {
+      endpoint: `http://127.0.0.1:${9000 + launched.length}`,
+      launchedAt: clock.now,
+      uses: 0,
+      stopped: false,
+      stop: () => {
+        browser.stopped = true;
+      },
+    };
+    launched.push(browser);
+    return browser;
+  };
+  return { launcher, launched };
+}
+
+const settle = () => new Promise((resolve) => setTimeout(resolve, 0));
+
+describe('BrowserPool', () => {
+  it('hands out warm browsers and launches their replacements', async () => {
+    const clock = { now: 0 };
+    const { launcher, launched } = fakeLauncher(clock);
+    const pool = new BrowserPool({ size: 2 }, launcher, async () => {}, () => clock.now);
+    pool.start();
+    await settle();
+    expect(pool.stats()).toMatchObject({ idle: 2, leased: 0 });
+
+    const browser = await pool.acquire();
+    expect(browser.endpoint).toBe('http://127.0.0.1:9000');
+    await settle();
+    expect(launched).toHaveLength(3);
+    expect(pool.stats()).toMatchObject({ idle: 2, leased: 1, hits: 1, misses: 0 });
+
+    // Closed after its session by default
+    await pool.release(browser);
+    expect(launched[0].stopped).toBe(true);
+    expect(pool.stats()).toMatchObject({ idle: 2, leased: 0, retired: 1 });
+    pool.stop();
+    expect(launched.every((b) => b.stopped)).toBe(true);
+  });
+
+  it('puts reused browsers back until they are too old', async () => {
+    const clock = { now: 0 };
+    const { launcher, launched } = fakeLauncher(clock);
+    const resets: string[] = [];
+    const pool = new BrowserPool(
+      { size: 1, recycle: 'reuse', maxAgeMs: 1000 },
+      launcher,
+      async (browser) => {
+        resets.push(browser.endpoint);
+      },
+      () => clock.now
+    );
+    pool.start();
+    await settle();
+
+    const first = await pool.acquire();
+    await settle();
+    await pool.release(first);
+    // The pool is already full with the replacement, so the reused one is closed
+    expect(first.stopped).toBe(true);
+
+    const second = await pool.acquire();
+    await pool.release(second);
+    expect(resets).toEqual(['http://127.0.0.1:9000', 'http://127.0.0.1:9001']);
+
+    clock.now = 1500;
+    pool.sweep();
+    await settle();
+    expect(launched.filter((b) => !b.stopped)).toHaveLength(1);
+    expect(pool.stats().retired).toBeGreaterThan(0);
+    pool.stop();
+  });
+
+  it('launches a browser when none is idle, and records launch failures', async () => {
+    const clock = { now: 0 };
+    let fail = true;
+    const { launcher } = fakeLauncher(clock);
+    const pool = new BrowserPool(
+      { size: 1 },
+      () => (fail ? Promise.reject(new Error('no chrome')) : launcher()),
+      async () => {},
+      () => clock.now
+    );
+    pool.start();
+    await settle();
+    expect(pool.stats()).toMatchObject({ idle: 0, lastError: 'no chrome' });
+
+    fail = false;
+    const browser = await pool.acquire();
+    expect(browser.uses).toBe(1);
+    expect(pool.stats()).toMatchObject({ misses: 1, leased: 1 });
+    pool.stop();
+  });
+});
+
+describe('poolOptionsFromEnv', () => {
+  it('reads the size, max age and recycle policy', () => {
+    expect(poolOptionsFromEnv({})).toBeNull();
+    expect(
+      poolOptionsFromEnv({
+        AGENT_BROWSER_POOL_SIZE: '3',
+        AGENT_BROWSER_POOL_MAX_AGE: '60000',
+        AGENT_BROWSER_POOL_RECYCLE: 'reuse',
+      })
+    ).toEqual({ size: 3, maxAgeMs: 60000, recycle: 'reuse' });
+    expect(() =>
+      poolOptionsFromEnv({ AGENT_BROWSER_POOL_SIZE: '1', AGENT_BROWSER_POOL_RECYCLE: 'keep' })
+    ).toThrow('close or reuse');
+  });
+});
+
+describe('readDevToolsPort', () => {
+  it('reads the port Chromium picked', () => {
+    const dir = mkdtempSync(path.join(os.tmpdir(), 'browser-pool-test-'));
+    try {
+      expect(readDevToolsPort(dir)).toBeNull();
+      writeFileSync(path.join(dir, 'DevToolsActivePort'), '41234\n/devtools/browser/abc\n');
+      expect(readDevToolsPort(dir)).toBe(41234);
+    } finally {
+      rmSync(dir, { recursive: true, force: true });
+    }
+  });
+});
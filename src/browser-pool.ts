@@ -0,0 +1,281 @@
+/**
+ * Warm browsers for the job API.
+ *
+ * Every job runs in a session of its own, and launching that session's
+ * browser takes most of its first command. With `AGENT_BROWSER_POOL_SIZE`
+ * set, a daemon serving jobs keeps that many headless browsers launched and
+ * idle, and a starting job attaches to one over CDP instead of launching its
+ * own; a browser takes its place in the background.
+ *
+ * When the job's session is done with a browser, `AGENT_BROWSER_POOL_RECYCLE`
+ * decides what happens to it: `close` (default) closes it, so no cookies or
+ * cache carry over to the next job, while `reuse` puts it back with a single
+ * blank tab, for trusted jobs that gain from a warm cache. Either way a
+ * browser idle for longer than `AGENT_BROWSER_POOL_MAX_AGE` milliseconds
+ * after its launch is closed and replaced.
+ */
+
+import { spawn } from 'node:child_process';
+import { mkdtempSync, readFileSync, rmSync } from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { chromium } from 'playwright-core';
+import { containerArgs } from './container.js';
+import { BROWSER_ARGS, resolveExecutable } from './executable.js';
+import type { PoolData, PoolRecycle } from './types.js';
+
+const DEFAULT_MAX_AGE_MS = 30 * 60_000;
+/** How often idle browsers are checked against the max age */
+const SWEEP_INTERVAL_MS = 30_000;
+/** How long a launched browser gets to open its DevTools port */
+const LAUNCH_TIMEOUT_MS = 20_000;
+
+/** A launched browser a session can attach to with `--cdp` */
+export interface WarmBrowser {
+  /** The DevTools HTTP endpoint, e.g. http://127.0.0.1:9222 */
+  endpoint: string;
+  /** When it was launched, ms since epoch */
+  launchedAt: number;
+  /** Sessions it has served */
+  uses: number;
+  stop(): void;
+}
+
+export type BrowserLauncher = () => Promise<WarmBrowser>;
+
+/** Puts a reused browser back to a single blank tab */
+export type BrowserReset = (browser: WarmBrowser) => Promise<void>;
+
+export interface PoolOptions {
+  size: number;
+  maxAgeMs?: number;
+  recycle?: PoolRecycle;
+}
+
+/** Pool settings from the environment, or null without `AGENT_BROWSER_POOL_SIZE` */
+export function poolOptionsFromEnv(env: NodeJS.ProcessEnv = process.env): PoolOptions | null {
+  const size = parseInt(env.AGENT_BROWSER_POOL_SIZE ?? '', 10);
+  if (!(size > 0)) return null;
+  const maxAge = parseInt(env.AGENT_BROWSER_POOL_MAX_AGE ?? '', 10);
+  const recycle = env.AGENT_BROWSER_POOL_RECYCLE;
+  if (recycle !== undefined && recycle !== 'close' && recycle !== 'reuse') {
+    throw new Error(`AGENT_BROWSER_POOL_RECYCLE must be close or reuse, not "${recycle}"`);
+  }
+  return {
+    size,
+    ...(maxAge > 0 ? { maxAgeMs: maxAge } : {}),
+    ...(recycle ? { recycle } : {}),
+  };
+}
+
+export class BrowserPool {
+  readonly size: number;
+  readonly maxAgeMs: number;
+  readonly recycle: PoolRecycle;
+  private idle: WarmBrowser[] = [];
+  private leased = new Set<WarmBrowser>();
+  private launching = 0;
+  private stopped = false;
+  private sweeper: NodeJS.Timeout | null = null;
+  /** Sessions given a warm browser, and those that had to wait for a launch */
+  private hits = 0;
+  private misses = 0;
+  private retired = 0;
+  private lastError: string | null = null;
+
+  constructor(
+    options: PoolOptions,
+    private launcher: BrowserLauncher = launchWarmBrowser,
+    private reset: BrowserReset = resetBrowser,
+    private now: () => number = Date.now
+  ) {
+    this.size = Math.max(1, options.size);
+    this.maxAgeMs = options.maxAgeMs ?? DEFAULT_MAX_AGE_MS;
+    this.recycle = options.recycle ?? 'close';
+  }
+
+  /** Launch browsers up to the pool size, and keep retiring old ones */
+  start(): void {
+    this.fill();
+    this.sweeper ??= setInterval(() => this.sweep(), SWEEP_INTERVAL_MS);
+    this.sweeper.unref();
+  }
+
+  /**
+   * A browser for a starting session: an idle one if there is one, else one
+   * launched now. It's the caller's until {@link release}.
+   */
+  async acquire(): Promise<WarmBrowser> {
+    if (this.stopped) throw new Error('The browser pool is stopped');
+    this.sweep();
+    const browser = this.idle.shift();
+    let leased: WarmBrowser;
+    if (browser) {
+      this.hits++;
+      leased = browser;
+    } else {
+      this.misses++;
+      leased = await this.launcher();
+    }
+    leased.uses++;
+    this.leased.add(leased);
+    this.fill();
+    return leased;
+  }
+
+  /** Hand back a browser whose session is done with it */
+  async release(browser: WarmBrowser): Promise<void> {
+    if (!this.leased.delete(browser)) return;
+    if (this.recycle === 'reuse' && !this.stopped && !this.expired(browser)) {
+      try {
+        await this.reset(browser);
+        if (!this.stopped && this.idle.length < this.size) {
+          this.idle.push(browser);
+          return;
+        }
+      } catch (err) {
+        this.lastError = (err as Error).message;
+      }
+    }
+    this.retire(browser);
+    this.fill();
+  }
+
+  /** Close every browser, leased ones included */
+  stop(): void {
+    this.stopped = true;
+    if (this.sweeper) clearInterval(this.sweeper);
+    this.sweeper = null;
+    for (const browser of [...this.idle, ...this.leased]) browser.stop();
+    this.idle = [];
+    this.leased.clear();
+  }
+
+  stats(): PoolData {
+    return {
+      size: this.size,
+      maxAgeMs: this.maxAgeMs,
+      recycle: this.recycle,
+      idle: this.idle.length,
+      leased: this.leased.size,
+      launching: this.launching,
+      hits: this.hits,
+      misses: this.misses,
+      retired: this.retired,
+      ...(this.lastError ? { lastError: this.lastError } : {}),
+    };
+  }
+
+  /** Retire idle browsers past the max age, and launch their replacements */
+  sweep(): void {
+    const expired = this.idle.filter((browser) => this.expired(browser));
+    if (expired.length === 0) return;
+    this.idle = this.idle.filter((browser) => !expired.includes(browser));
+    for (const browser of expired) this.retire(browser);
+    this.fill();
+  }
+
+  private expired(browser: WarmBrowser): boolean {
+    return this.now() - browser.launchedAt >= this.maxAgeMs;
+  }
+
+  private retire(browser: WarmBrowser): void {
+    this.retired++;
+    browser.stop();
+  }
+
+  private fill(): void {
+    while (!this.stopped && this.idle.length + this.launching < this.size) {
+      this.launching++;
+      this.launcher().then(
+        (browser) => {
+          this.launching--;
+          if (this.stopped || this.idle.length >= this.size) browser.stop();
+          else this.idle.push(browser);
+        },
+        (err: Error) => {
+          // Left short until the next acquire, release or sweep, so a browser
+          // that can't start isn't relaunched in a loop
+          this.launching--;
+          this.lastError = err.message;
+        }
+      );
+    }
+  }
+}
+
+/**
+ * Launch a headless browser on a free DevTools port, in a profile of its own
+ * that's deleted when it's stopped.
+ */
+export async function launchWarmBrowser(): Promise<WarmBrowser> {
+  const resolved = resolveExecutable();
+  const executable = resolved.path ?? chromium.executablePath();
+  const userDataDir = mkdtempSync(path.join(os.tmpdir(), 'agent-browser-pool-'));
+  const child = spawn(
+    executable,
+    [
+      '--headless=new',
+      '--remote-debugging-port=0',
+      `--user-data-dir=${userDataDir}`,
+      '--no-first-run',
+      '--no-default-browser-check',
+      ...containerArgs(),
+      ...(resolved.browser ? BROWSER_ARGS[resolved.browser] : []),
+      'about:blank',
+    ],
+    { stdio: 'ignore' }
+  );
+  let exited = false;
+  child.once('exit', () => {
+    exited = true;
+    rmSync(userDataDir, { recursive: true, force: true });
+  });
+  child.once('error', () => {
+    exited = true;
+  });
+  const stop = () => {
+    if (!exited) child.kill('SIGTERM');
+  };
+
+  // Chromium writes the port it picked to DevToolsActivePort once it listens
+  const deadline = Date.now() + LAUNCH_TIMEOUT_MS;
+  while (Date.now() < deadline && !exited) {
+    const port = readDevToolsPort(userDataDir);
+    if (port) {
+      return { endpoint: `http://127.0.0.1:${port}`, launchedAt: Date.now(), uses: 0, stop };
+    }
+    await new Promise((resolve) => setTimeout(resolve, 50));
+  }
+  stop();
+  throw new Error(
+    exited
+      ? `${executable} exited before it opened a DevTools port`
+      : `${executable} didn't open a DevTools port within ${LAUNCH_TIMEOUT_MS / 1000}s`
+  );
+}
+
+/** The port from a profile's DevToolsActivePort file, or null before it's written */
+export function readDevToolsPort(userDataDir: string): number | null {
+  try {
+    const port = parseInt(readFileSync(path.join(userDataDir, 'DevToolsActivePort'), 'utf-8'), 10);
+    return port > 0 ? port : null;
+  } catch {
+    return null;
+  }
+}
+
+/** Open a blank tab, then close every other one, over the DevTools HTTP API */
+export async function resetBrowser(browser: WarmBrowser): Promise<void> {
+  const list = async (): Promise<{ id: string; type: string }[]> => {
+    const response = await fetch(`${browser.endpoint}/json/list`);
+    if (!response.ok) throw new Error(`DevTools /json/list answered ${response.status}`);
+    return (await response.json()) as { id: string; type: string }[];
+  };
+  const before = (await list()).filter((target) => target.type === 'page');
+  const created = await fetch(`${browser.endpoint}/json/new?about:blank`, { method: 'PUT' });
+  if (!created.ok) throw new Error(`DevTools /json/new answered ${created.status}`);
+  for (const target of before) {
+    await fetch(`${browser.endpoint}/json/close/${target.id}`);
+  }
+}
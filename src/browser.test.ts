@@ -1,5 +1,6 @@
 import { describe, it, expect, beforeAll, afterAll, vi } from 'vitest';
 import { BrowserManager } from './browser.js';
+import { executeCommand } from './actions.js';
 import { chromium } from 'playwright-core';
 
 describe('BrowserManager', () => {
@@ -386,6 +387,33 @@ describe('BrowserManager', () => {
     });
   });
 
+  describe('iframe snapshot', () => {
+    it('should include iframe content with refs that resolve inside the frame', async () => {
+      const page = browser.getPage();
+      await page.setContent(`
+        <html>
+          <body>
+            <button>Outer</button>
+            <iframe srcdoc="<button onclick='this.textContent=&quot;Done&quot;'>Inner</button>"></iframe>
+          </body>
+        </html>
+      `);
+      await page.frameLocator('iframe').getByRole('button').waitFor();
+
+      const { tree, refs } = await browser.getSnapshot();
+      expect(tree).toContain('button "Outer"');
+      expect(tree).toContain('button "Inner"');
+
+      const innerRef = Object.keys(refs).find((k) => refs[k].name === 'Inner');
+      expect(innerRef).toBeDefined();
+      expect(refs[innerRef!].frames).toHaveLength(1);
+
+      await browser.getLocator(`@${innerRef}`).click();
+      const text = await page.frameLocator('iframe').getByRole('button').textContent();
+      expect(text).toBe('Done');
+    });
+  });
+
   describe('locator resolution', () => {
     it('should resolve CSS selector', async () => {
       const page = browser.getPage();
@@ -405,6 +433,31 @@ describe('BrowserManager', () => {
     });
   });
 
+  describe('inspect', () => {
+    it('should report listeners without changing the element', async () => {
+      const page = browser.getPage();
+      await page.setContent('<div><p>a</p><button onclick="void 0">Go</button></div>');
+      await page.evaluate(() => {
+        const button = document.querySelector('button') as HTMLButtonElement;
+        button.addEventListener('mouseover', () => {});
+        (window as any).mutations = 0;
+        new MutationObserver((records) => {
+          (window as any).mutations += records.length;
+        }).observe(document, { attributes: true, subtree: true });
+      });
+
+      const response = await executeCommand(
+        { id: '1', action: 'inspect', selector: 'button' },
+        browser
+      );
+      expect(response.success).toBe(true);
+      const data = (response as { data: { tag: string; listeners: string[] } }).data;
+      expect(data.tag).toBe('button');
+      expect(data.listeners).toEqual(expect.arrayContaining(['click', 'mouseover']));
+      expect(await page.evaluate(() => (window as any).mutations)).toBe(0);
+    });
+  });
+
   describe('scoped headers', () => {
     it('should register route for scoped headers', async () => {
       // Test that setScopedHeaders doesn't throw and completes successfully
@@ -460,6 +513,52 @@ describe('BrowserManager', () => {
     });
   });
 
+  describe('read-only sessions', () => {
+    it('blocks a form POST even when a page route or scoped headers match it', async () => {
+      const readOnly = new BrowserManager();
+      await readOnly.launch({ headless: true });
+      try {
+        await readOnly.setReadOnly();
+        await readOnly.addRoute('**/*', {});
+        await readOnly.setScopedHeaders('127.0.0.1:9', { 'X-Test': 'value' });
+        const page = readOnly.getPage();
+        await page.setContent(
+          '<form method="post" action="http://127.0.0.1:9/submit"><input name="q"></form>'
+        );
+        await page.evaluate(() => (document.querySelector('form') as HTMLFormElement).submit());
+        await expect.poll(() => readOnly.getReadOnly().blocked).toBe(1);
+      } finally {
+        await readOnly.close();
+      }
+    });
+  });
+
+  describe('guardrails', () => {
+    it('blocks a checkout POST even when a page route or scoped headers match it', async () => {
+      const guarded = new BrowserManager();
+      await guarded.launch({ headless: true });
+      try {
+        await guarded.setGuardrails([]);
+        await guarded.addRoute('**/*', {});
+        await guarded.setScopedHeaders('127.0.0.1:9', { 'X-Test': 'value' });
+        const page = guarded.getPage();
+        await page.setContent(
+          '<form method="post" action="http://127.0.0.1:9/checkout"><input name="q"></form>'
+        );
+        await page.evaluate(() => (document.querySelector('form') as HTMLFormElement).submit());
+        const rules: string[] = [];
+        await expect
+          .poll(() => {
+            rules.push(...guarded.takeGuardrailViolations().map((v) => v.rule));
+            return rules;
+          })
+          .toEqual(['checkout-submit']);
+      } finally {
+        await guarded.close();
+      }
+    });
+  });
+
   describe('CDP session', () => {
     it('should create CDP session on demand', async () => {
       const cdp = await browser.getCDPSession();
@@ -614,6 +713,24 @@ describe('BrowserManager', () => {
       // Screencast should be stopped (it's page-specific)
       expect(browser.isScreencasting()).toBe(false);
     });
+
+    it('should invalidate CDP session when a followed popup closes', async () => {
+      const opener = browser.getPage();
+      browser.setPopupPolicy('follow');
+      try {
+        await opener.evaluate(() => window.open('about:blank'));
+        await expect.poll(() => browser.getPage() !== opener).toBe(true);
+        const popupCdp = await browser.getCDPSession();
+        await browser.startScreencast(() => {});
+
+        await browser.getPage().close();
+        await expect.poll(() => browser.getPage()).toBe(opener);
+        await expect.poll(() => browser.isScreencasting()).toBe(false);
+        expect(await browser.getCDPSession()).not.toBe(popupCdp);
+      } finally {
+        browser.setPopupPolicy('list');
+      }
+    });
   });
 
   describe('input injection', () => {
@@ -7,6 +7,7 @@ import {
   type BrowserContext,
   type Page,
   type Frame,
+  type FrameLocator,
   type Dialog,
   type Request,
   type Route,
@@ -17,9 +18,95 @@ import {
 import { execSync, spawn, type ChildProcess } from 'node:child_process';
 import path from 'node:path';
 import os from 'node:os';
-import { existsSync, mkdirSync, rmSync } from 'node:fs';
-import type { LaunchCommand } from './types.js';
+import { existsSync, mkdirSync, readFileSync, rmSync, writeFileSync } from 'node:fs';
+import type {
+  ClientCertificate,
+  ClockData,
+  DomMutationEvent,
+  DomMutationType,
+  GuardrailRule,
+  GuardrailViolation,
+  LaunchCommand,
+  PopupPolicy,
+  MemoryData,
+  PageActivity,
+  RateRule,
+  RecycleMode,
+  RobotsDecision,
+  SessionRestoreData,
+  SessionSaveData,
+  StatsData,
+  TabMemory,
+  TabRecycle,
+  TlsInfoData,
+  TraceBuffer,
+} from './types.js';
+import { FilterEngine, getFiltersDir } from './adblock.js';
+import { ResponseCapture, type CaptureOptions } from './capture.js';
+import { ApiCatalog, type ApiEndpoint } from './apis.js';
+import { WebSocketTracker } from './websockets.js';
+import { TargetRegistry, type TargetInfo } from './targets.js';
+import { InputRecorder } from './input-timeline.js';
+import { DomWatcher } from './dom-watch.js';
+import { RateLimiter, hostOf } from './rate-limit.js';
+import { MemoryWatchdog, formatBytes } from './memory-watchdog.js';
+import { FrameThrottle } from './screencast.js';
+import { RobotsCache } from './robots.js';
+import { isSafeRequest } from './read-only.js';
+import { BUILTIN_RULES, Guardrails } from './guardrails.js';
+import { TlsTrust, inspectCertificate, readCaFile } from './tls.js';
+import { STEALTH_ARGS, STEALTH_SCRIPT } from './stealth.js';
+import { DETERMINISTIC_ARGS, DETERMINISTIC_SCRIPT } from './deterministic.js';
+import { containerArgs } from './container.js';
+import { BROWSER_ARGS, resolveExecutable } from './executable.js';
+import type { BrowserState } from './health.js';
+import {
+  REMOTE_KEEPALIVE_MS,
+  createGridSession,
+  deleteGridSession,
+  redactUrl,
+  resolveRemote,
+} from './remote.js';
+import { parseClockTime, wallTime } from './clock.js';
+import {
+  SESSION_FILE_VERSION,
+  parseSessionFile,
+  storageOrigin,
+  type SessionFile,
+} from './session-file.js';
+import { traceCDP } from './otel.js';
 import { type RefMap, type EnhancedSnapshot, getEnhancedSnapshot, parseRef } from './snapshot.js';
+import { SnapshotCache } from './snapshot-cache.js';
+
+function emptyNetStats() {
+  return { since: Date.now(), requests: 0, failed: 0, cacheHits: 0, bytesIn: 0, bytesOut: 0 };
+}
+
+/** Per-kind limit of activity kept between `run --trace` steps */
+const MAX_TRACE_ENTRIES = 500;
+
+/** Actions that can be cancelled while holding a mouse button or key */
+const INPUT_ACTIONS = new Set([
+  'click',
+  'dblclick',
+  'drag',
+  'press',
+  'type',
+  'input_mouse',
+  'input_keyboard',
+]);
+
+function emptyTraceBuffer(): TraceBuffer {
+  return { console: [], errors: [], responses: [] };
+}
+
+function hostOf(url: string): string {
+  try {
+    return new URL(url).host || url.split(':')[0];
+  } catch {
+    return '';
+  }
+}
 
 // Screencast frame data from CDP
 export interface ScreencastFrame {
@@ -34,6 +121,8 @@ export interface ScreencastFrame {
     timestamp?: number;
   };
   sessionId: number;
+  /** `data` decoded, once, by the first consumer that needs bytes (see screencast.ts) */
+  bytes?: Buffer;
 }
 
 // Screencast options
@@ -43,6 +132,10 @@ export interface ScreencastOptions {
   maxWidth?: number;
   maxHeight?: number;
   everyNthFrame?: number;
+  /** Frame size relative to the viewport; overrides maxWidth/maxHeight */
+  scale?: number;
+  /** Most frames passed to the callback per second */
+  fps?: number;
 }
 
 interface TrackedRequest {
@@ -64,6 +157,14 @@ interface PageError {
   timestamp: number;
 }
 
+/**
+ * Default action timeout in ms (AGENT_BROWSER_DEFAULT_TIMEOUT, set from config or env)
+ */
+export function getDefaultTimeout(): number {
+  const value = parseInt(process.env.AGENT_BROWSER_DEFAULT_TIMEOUT || '', 10);
+  return value > 0 ? value : 60000;
+}
+
 /**
  * Manages the Playwright browser lifecycle with multiple tabs/windows
  */
@@ -78,6 +179,10 @@ export class BrowserManager {
   private kernelSessionId: string | null = null;
   private kernelApiKey: string | null = null;
   private browserOSProcess: ChildProcess | null = null;
+  private remote: string | null = null;
+  private remoteKeepAlive: NodeJS.Timeout | null = null;
+  private gridSession: { hub: string; id: string; headers: Record<string, string> } | null =
+    null;
   private launchProvider: string | null = null;
   private contexts: BrowserContext[] = [];
   private pages: Page[] = [];
@@ -90,8 +195,51 @@ export class BrowserManager {
   private pageErrors: PageError[] = [];
   private isRecordingHar: boolean = false;
   private refMap: RefMap = {};
+  /** Fallbacks suggested for plain selectors, tried when healing them */
+  private selectorFallbacks = new Map<string, string[]>();
   private lastSnapshot: string = '';
+  /** ARIA trees of frames that haven't changed since their last snapshot */
+  private snapshotCache = new SnapshotCache();
   private scopedHeaderRoutes: Map<string, (route: Route) => Promise<void>> = new Map();
+  private armedWaits: Map<string, Promise<unknown>> = new Map();
+  private popupPolicy: PopupPolicy = 'list';
+  private activityListener: ((activity: PageActivity) => void) | null = null;
+  private popupOpeners: Map<Page, Page> = new Map();
+  private autoDismissOverlays: boolean = false;
+  private adblockHandler: ((route: Route) => Promise<void>) | null = null;
+  private adblockBlocked: number = 0;
+  private requestLimiter: RateLimiter | null = null;
+  private navigationLimiter: RateLimiter | null = null;
+  private rateLimitHandler: ((route: Route) => Promise<void>) | null = null;
+  private memoryWatchdog = new MemoryWatchdog();
+  private robots: RobotsCache = new RobotsCache();
+  private robotsHandler: ((route: Route) => Promise<void>) | null = null;
+  private readOnlyHandler: ((route: Route) => Promise<void>) | null = null;
+  private readOnlyBlocked: number = 0;
+  private guardrails: Guardrails | null = null;
+  private guardrailsHandler: ((route: Route) => Promise<void>) | null = null;
+  private guardrailViolations: GuardrailViolation[] = [];
+  private allowPurchases: boolean = false;
+  private tlsTrust: TlsTrust | null = null;
+  private clientCertificates: ClientCertificate[] = [];
+  private stealth: boolean = false;
+  private deterministic: boolean = false;
+  private initScripts: string[] = [];
+  /** Contexts with the fake clock installed, and whether it is paused */
+  private clocks = new WeakMap<BrowserContext, { paused: boolean }>();
+  private timezoneOverride: string | null = null;
+  private timezoneSessions = new WeakMap<Page, CDPSession>();
+  private robotsOverride: boolean = false;
+  private netStats = emptyNetStats();
+  private hostStats: Map<string, { requests: number; bytes: number }> = new Map();
+  private responseCapture: ResponseCapture | null = null;
+  private apiCatalog: ApiCatalog = new ApiCatalog();
+  private webSockets: WebSocketTracker = new WebSocketTracker();
+  private targets: TargetRegistry = new TargetRegistry();
+  private inputRecorder: InputRecorder = new InputRecorder();
+  private domWatcher: DomWatcher = new DomWatcher();
+  private traceBuffer: TraceBuffer = emptyTraceBuffer();
+  private harReplay: { path: string; notFound: 'abort' | 'fallback'; url?: string } | null = null;
 
   // CDP session for screencast and input injection
   private cdpSession: CDPSession | null = null;
@@ -113,6 +261,42 @@ export class BrowserManager {
     return this.browser !== null || this.isPersistentContext;
   }
 
+  /**
+   * The CDP endpoint when attached to a browser other sessions can attach
+   * to as well; null for a browser this session launched
+   */
+  sharedBrowser(): string | null {
+    if (this.cdpEndpoint === null) return null;
+    return /^[a-z]+:\/\//.test(this.cdpEndpoint)
+      ? this.cdpEndpoint
+      : `http://localhost:${this.cdpEndpoint}`;
+  }
+
+  /**
+   * Whether the launched browser is still connected and answering, for the
+   * health endpoints. Chromium is asked for its version over CDP; other
+   * browsers evaluate a no-op in the page.
+   */
+  async browserState(timeout: number): Promise<BrowserState> {
+    if (!this.isLaunched()) return 'not-launched';
+    const browser = this.browser ?? this.contexts[0]?.browser() ?? null;
+    if (browser && !browser.isConnected()) return 'disconnected';
+    const ping = async () => {
+      try {
+        await (await this.getCDPSession()).send('Browser.getVersion');
+      } catch {
+        await this.getPage().evaluate('1');
+      }
+    };
+    let timer: NodeJS.Timeout | undefined;
+    const timedOut = new Promise<false>((resolve) => {
+      timer = setTimeout(() => resolve(false), timeout);
+    });
+    const answered = await Promise.race([ping().then(() => true, () => false), timedOut]);
+    clearTimeout(timer);
+    return answered ? 'running' : 'unresponsive';
+  }
+
   /**
    * Get enhanced snapshot with refs and cache the ref map
    */
@@ -122,9 +306,15 @@ export class BrowserManager {
     maxDepth?: number;
     compact?: boolean;
     selector?: string;
+    /** Walk every frame again instead of reusing unchanged ones */
+    fresh?: boolean;
   }): Promise<EnhancedSnapshot> {
     const page = this.getPage();
-    const snapshot = await getEnhancedSnapshot(page, options);
+    const { fresh, ...rest } = options ?? {};
+    const snapshot = await getEnhancedSnapshot(page, {
+      ...rest,
+      ...(fresh ? {} : { cache: this.snapshotCache }),
+    });
     this.refMap = snapshot.refs;
     this.lastSnapshot = snapshot.tree;
     return snapshot;
@@ -154,15 +344,21 @@ export class BrowserManager {
     // These have pseudo-roles 'clickable' or 'focusable' and a CSS selector
     if (refData.role === 'clickable' || refData.role === 'focusable') {
       // The selector is a CSS selector, use it directly
-      return page.locator(refData.selector);
+      return this.withFallbacks(page.locator(refData.selector), refData.fallbacks);
+    }
+
+    // Refs from iframe content resolve inside their frame chain
+    let scope: Page | FrameLocator = page;
+    for (const frameSelector of refData.frames ?? []) {
+      scope = scope.frameLocator(frameSelector);
     }
 
     // Build locator with exact: true to avoid substring matches
     let locator: Locator;
     if (refData.name) {
-      locator = page.getByRole(refData.role as any, { name: refData.name, exact: true });
+      locator = scope.getByRole(refData.role as any, { name: refData.name, exact: true });
     } else {
-      locator = page.getByRole(refData.role as any);
+      locator = scope.getByRole(refData.role as any);
     }
 
     // If an nth index is stored (for disambiguation), use it
@@ -170,7 +366,43 @@ export class BrowserManager {
       locator = locator.nth(refData.nth);
     }
 
-    return locator;
+    return this.withFallbacks(locator, refData.fallbacks);
+  }
+
+  /**
+   * Either the locator or any of the fallback selectors. The fallbacks were
+   * checked to match the same element, so while the page is unchanged they
+   * add nothing, and after it changes whichever still matches is used.
+   */
+  private withFallbacks(locator: Locator, fallbacks?: string[]): Locator {
+    const page = this.getPage();
+    return (fallbacks ?? []).reduce(
+      (either, selector) => either.or(page.locator(selector)),
+      locator
+    );
+  }
+
+  /**
+   * Store fallback selectors for a ref, used from then on when resolving it
+   */
+  setRefFallbacks(refArg: string, fallbacks: string[]): boolean {
+    const ref = parseRef(refArg);
+    const refData = ref ? this.refMap[ref] : undefined;
+    if (!refData) return false;
+    refData.fallbacks = fallbacks;
+    return true;
+  }
+
+  /**
+   * Store fallback selectors for a plain selector. Unlike a ref's, they are
+   * not used when resolving it, only by `selectors heal` once it stops matching
+   */
+  setSelectorFallbacks(selector: string, fallbacks: string[]): void {
+    this.selectorFallbacks.set(selector, fallbacks);
+  }
+
+  getSelectorFallbacks(selector: string): string[] {
+    return this.selectorFallbacks.get(selector) ?? [];
   }
 
   /**
@@ -345,7 +577,9 @@ export class BrowserManager {
           headers: options.response.headers,
         });
       } else {
-        await route.continue();
+        // Not continue(): the context handlers for read-only, guardrails and
+        // the rest would never see the request
+        await route.fallback();
       }
     };
 
@@ -459,6 +693,20 @@ export class BrowserManager {
    */
   startConsoleTracking(): void {
     const page = this.getPage();
+    page.on('framenavigated', (frame) => {
+      if (frame === page.mainFrame()) {
+        this.activityListener?.({ type: 'navigation', url: frame.url() });
+      }
+    });
+
+    page.on('download', (download) => {
+      this.activityListener?.({
+        type: 'download',
+        url: download.url(),
+        filename: download.suggestedFilename(),
+      });
+    });
+
     page.on('console', (msg) => {
       this.consoleMessages.push({
         type: msg.type(),
@@ -509,6 +757,347 @@ export class BrowserManager {
     this.pageErrors = [];
   }
 
+  /**
+   * Set how pages opened by window.open / target="_blank" are handled:
+   * follow switches to them (and back to the opener when they close),
+   * block closes them immediately, list only adds them to the tab list.
+   */
+  setPopupPolicy(policy: PopupPolicy): void {
+    this.popupPolicy = policy;
+  }
+
+  getPopupPolicy(): PopupPolicy {
+    return this.popupPolicy;
+  }
+
+  /**
+   * Be told about every main-frame navigation and download on any page,
+   * including ones no command asked for (redirects, scripted navigations)
+   */
+  setActivityListener(listener: ((activity: PageActivity) => void) | null): void {
+    this.activityListener = listener;
+  }
+
+  /**
+   * Dismiss cookie banners and similar overlays automatically after navigation
+   * and before element actions and snapshots
+   */
+  setAutoDismissOverlays(enabled: boolean): void {
+    this.autoDismissOverlays = enabled;
+  }
+
+  getAutoDismissOverlays(): boolean {
+    return this.autoDismissOverlays;
+  }
+
+  /**
+   * Block ad and tracker requests using the filter lists downloaded by
+   * `agent-browser filters update` (plus any custom lists in the same directory)
+   */
+  async setAdblock(enabled: boolean): Promise<void> {
+    if (!enabled) {
+      const handler = this.adblockHandler;
+      this.adblockHandler = null;
+      if (handler) {
+        await Promise.all(this.contexts.map((context) => context.unroute('**/*', handler)));
+      }
+      return;
+    }
+    if (this.adblockHandler) return;
+
+    const engine = FilterEngine.fromDirectory(getFiltersDir());
+    if (engine.size === 0) {
+      throw new Error(
+        `No filter lists found in ${getFiltersDir()}. Run: agent-browser filters update`
+      );
+    }
+    this.adblockHandler = async (route: Route) => {
+      const request = route.request();
+      let sourceUrl: string | undefined;
+      try {
+        sourceUrl = request.frame().url();
+      } catch {
+        // Service worker requests have no frame
+      }
+      if (engine.shouldBlock({ url: request.url(), type: request.resourceType(), sourceUrl })) {
+        this.adblockBlocked++;
+        await route.abort('blockedbyclient');
+      } else {
+        await route.fallback();
+      }
+    };
+    await Promise.all(this.contexts.map((context) => context.route('**/*', this.adblockHandler!)));
+  }
+
+  getAdblock(): { enabled: boolean; blocked: number } {
+    return { enabled: this.adblockHandler !== null, blocked: this.adblockBlocked };
+  }
+
+  /**
+   * Block top-level navigations that the site's robots.txt disallows
+   */
+  async setRespectRobots(enabled: boolean): Promise<void> {
+    if (!enabled) {
+      const handler = this.robotsHandler;
+      this.robotsHandler = null;
+      if (handler) {
+        await Promise.all(this.contexts.map((context) => context.unroute('**/*', handler)));
+      }
+      return;
+    }
+    if (this.robotsHandler) return;
+
+    this.robotsHandler = async (route: Route) => {
+      const request = route.request();
+      if (
+        !this.robotsOverride &&
+        request.isNavigationRequest() &&
+        request.frame().parentFrame() === null &&
+        !(await this.robots.check(request.url())).allowed
+      ) {
+        await route.abort('blockedbyclient');
+        return;
+      }
+      await route.fallback();
+    };
+    await Promise.all(this.contexts.map((context) => context.route('**/*', this.robotsHandler!)));
+  }
+
+  getRespectRobots(): boolean {
+    return this.robotsHandler !== null && !this.robotsOverride;
+  }
+
+  /** Let navigations ignore robots.txt while one command runs (--robots-override) */
+  setRobotsOverride(override: boolean): void {
+    this.robotsOverride = override;
+  }
+
+  /**
+   * Abort every request that could change something on the server (see
+   * read-only.ts). There is no turning it off again.
+   */
+  async setReadOnly(): Promise<void> {
+    if (this.readOnlyHandler) return;
+    this.readOnlyHandler = async (route: Route) => {
+      if (isSafeRequest(route.request().method())) {
+        await route.fallback();
+        return;
+      }
+      this.readOnlyBlocked++;
+      await route.abort('blockedbyclient');
+    };
+    await Promise.all(this.contexts.map((context) => context.route('**/*', this.readOnlyHandler!)));
+  }
+
+  getReadOnly(): { enabled: boolean; blocked: number } {
+    return { enabled: this.readOnlyHandler !== null, blocked: this.readOnlyBlocked };
+  }
+
+  /**
+   * Abort requests that match the built-in guardrails or these extra rules
+   * (see guardrails.ts). Calling it again replaces the extra rules.
+   */
+  async setGuardrails(rules: GuardrailRule[]): Promise<void> {
+    this.guardrails = new Guardrails([...BUILTIN_RULES, ...rules]);
+    if (this.guardrailsHandler) return;
+
+    this.guardrailsHandler = async (route: Route) => {
+      const request = route.request();
+      let navigation = false;
+      try {
+        navigation = request.isNavigationRequest() && request.frame().parentFrame() === null;
+      } catch {
+        // Service worker requests have no frame
+      }
+      const method = request.method();
+      const rule = this.guardrails?.check(
+        { url: request.url(), method, navigation },
+        this.allowPurchases
+      );
+      if (!rule) {
+        await route.fallback();
+        return;
+      }
+      this.guardrailViolations.push({
+        rule: rule.name,
+        method,
+        url: request.url(),
+        purchase: rule.purchase ?? false,
+      });
+      await route.abort('blockedbyclient');
+    };
+    await Promise.all(
+      this.contexts.map((context) => context.route('**/*', this.guardrailsHandler!))
+    );
+  }
+
+  getGuardrails(): GuardrailRule[] | null {
+    return this.guardrails?.rules ?? null;
+  }
+
+  /** Let purchase requests through while one command runs (--allow-purchases) */
+  setAllowPurchases(allow: boolean): void {
+    this.allowPurchases = allow;
+  }
+
+  /** Requests guardrails blocked since the last call */
+  takeGuardrailViolations(): GuardrailViolation[] {
+    const violations = this.guardrailViolations;
+    this.guardrailViolations = [];
+    return violations;
+  }
+
+  checkRobots(url: string): Promise<RobotsDecision> {
+    return this.robots.check(url);
+  }
+
+  /** Certificate chain for a URL, verified against the roots plus any --extra-ca */
+  inspectTls(url: string): Promise<TlsInfoData> {
+    return inspectCertificate(url, this.tlsTrust?.extraCa ?? []);
+  }
+
+  /**
+   * Install the fake clock at `time`, paused unless `tick` is set. The clock
+   * can only be installed once per context, so later calls move it instead.
+   */
+  async setClock(value: string, tick: boolean, timezone?: string): Promise<ClockData> {
+    const zone = timezone ?? this.timezoneOverride ?? undefined;
+    const time = parseClockTime(value, zone);
+    if (timezone) {
+      await this.setTimezone(timezone);
+    }
+    const page = this.getPage();
+    const clock = page.context().clock;
+    let state = this.clocks.get(page.context());
+    if (!state) {
+      await clock.install({ time });
+      state = { paused: false };
+      this.clocks.set(page.context(), state);
+    }
+    if (!tick && !state.paused) {
+      // pauseAt only moves forward, so stop just ahead of the page's current time
+      const now = await page.evaluate(() => Date.now());
+      await clock.pauseAt(now + 100);
+      state.paused = true;
+    }
+    await clock.setSystemTime(time);
+    if (tick && state.paused) {
+      await clock.resume();
+      state.paused = false;
+    }
+    return this.clockData(time, state.paused);
+  }
+
+  /** Run the fake clock forward, firing the timers that fall due on the way. */
+  async advanceClock(ms: number): Promise<ClockData> {
+    const page = this.getPage();
+    const state = this.clocks.get(page.context());
+    if (!state) {
+      throw new Error('No fake clock in this session; run "clock set <time>" first');
+    }
+    await page.clock.runFor(ms);
+    return this.clockData(await page.evaluate(() => Date.now()), state.paused);
+  }
+
+  /** Let a paused fake clock tick again from where it is. */
+  async resumeClock(): Promise<ClockData> {
+    const page = this.getPage();
+    const state = this.clocks.get(page.context());
+    if (!state) {
+      throw new Error('No fake clock in this session; run "clock set <time>" first');
+    }
+    await page.clock.resume();
+    state.paused = false;
+    return this.clockData(await page.evaluate(() => Date.now()), false);
+  }
+
+  private clockData(time: number, paused: boolean): ClockData {
+    const timezone = this.timezoneOverride;
+    return {
+      time: new Date(time).toISOString(),
+      paused,
+      timezone,
+      ...(timezone && { local: wallTime(time, timezone) }),
+    };
+  }
+
+  /** Emulate `timezone` in every page, now and as they open. */
+  private async setTimezone(timezone: string): Promise<void> {
+    // Throws for names the browser wouldn't know either
+    Intl.DateTimeFormat('en-US', { timeZone: timezone });
+    this.timezoneOverride = timezone;
+    await Promise.all(this.pages.map((page) => this.applyTimezone(page)));
+  }
+
+  private async applyTimezone(page: Page): Promise<void> {
+    if (!this.timezoneOverride) return;
+    // Overrides last as long as the session that set them, so keep it attached
+    let session = this.timezoneSessions.get(page);
+    if (!session) {
+      session = await page.context().newCDPSession(page);
+      this.timezoneSessions.set(page, session);
+    }
+    await session.send('Emulation.setTimezoneOverride', { timezoneId: this.timezoneOverride });
+  }
+
+  /**
+   * Limit how often each domain is requested and navigated to. An empty rule
+   * list removes that limit. Unchanged rules keep their limiter, so spacing
+   * carries over between commands.
+   */
+  async setRateLimits(limits: { requests?: RateRule[]; navigations?: RateRule[] }): Promise<void> {
+    const update = (current: RateLimiter | null, rules: RateRule[] | undefined) => {
+      if (rules === undefined) return current;
+      if (rules.length === 0) return null;
+      if (current && JSON.stringify(current.rules) === JSON.stringify(rules)) return current;
+      return new RateLimiter(rules);
+    };
+    this.requestLimiter = update(this.requestLimiter, limits.requests);
+    this.navigationLimiter = update(this.navigationLimiter, limits.navigations);
+
+    const active = this.requestLimiter !== null || this.navigationLimiter !== null;
+    if (!active && this.rateLimitHandler) {
+      const handler = this.rateLimitHandler;
+      this.rateLimitHandler = null;
+      await Promise.all(this.contexts.map((context) => context.unroute('**/*', handler)));
+    } else if (active && !this.rateLimitHandler) {
+      this.rateLimitHandler = async (route: Route) => {
+        const request = route.request();
+        const host = hostOf(request.url());
+        if (host) {
+          if (request.isNavigationRequest()) {
+            await this.navigationLimiter?.acquire(host);
+          }
+          await this.requestLimiter?.acquire(host);
+        }
+        await route.fallback();
+      };
+      await Promise.all(
+        this.contexts.map((context) => context.route('**/*', this.rateLimitHandler!))
+      );
+    }
+  }
+
+  /**
+   * Register a pending wait so a later command can collect its result.
+   * Arming before the triggering action avoids missing events that fire
+   * between the action and the wait command.
+   */
+  armWait(key: string, promise: Promise<unknown>): void {
+    // Avoid unhandled rejections if the armed wait times out before it is collected
+    promise.catch(() => {});
+    this.armedWaits.set(key, promise);
+  }
+
+  /**
+   * Take a previously armed wait, if any. Each armed wait can be collected once.
+   */
+  takeArmedWait<T>(key: string): Promise<T> | undefined {
+    const promise = this.armedWaits.get(key);
+    this.armedWaits.delete(key);
+    return promise as Promise<T> | undefined;
+  }
+
   /**
    * Start HAR recording
    */
@@ -569,10 +1158,11 @@ export class BrowserManager {
       await page.unroute(urlPattern, existingHandler);
     }
 
-    // Create handler that adds headers to matching requests
+    // Create handler that adds headers to matching requests, leaving the
+    // request to the context handlers (read-only, guardrails, ...) after it
     const handler = async (route: Route) => {
       const requestHeaders = route.request().headers();
-      await route.continue({
+      await route.fallback({
         headers: {
           ...requestHeaders,
           ...headers,
@@ -647,6 +1237,120 @@ export class BrowserManager {
     }
   }
 
+  /**
+   * Write the open tabs, their scroll positions and sessionStorage, and the
+   * context's cookies and localStorage to a session file
+   */
+  async saveSession(file: string): Promise<SessionSaveData> {
+    const context = this.contexts[0];
+    if (!context) {
+      throw new Error('Browser not launched');
+    }
+    const tabs = await Promise.all(
+      this.pages.map(async (page) => {
+        const state = await page
+          .evaluate(() => {
+            const session: Record<string, string> = {};
+            for (let i = 0; i < sessionStorage.length; i++) {
+              const key = sessionStorage.key(i)!;
+              session[key] = sessionStorage.getItem(key) ?? '';
+            }
+            return { scroll: { x: scrollX, y: scrollY }, sessionStorage: session };
+          })
+          .catch(() => ({ scroll: { x: 0, y: 0 }, sessionStorage: {} }));
+        return { url: page.url(), title: await page.title().catch(() => ''), ...state };
+      })
+    );
+    const session: SessionFile = {
+      version: SESSION_FILE_VERSION,
+      savedAt: new Date().toISOString(),
+      active: this.activePageIndex,
+      tabs,
+      storage: await context.storageState(),
+    };
+    // Cookies are credentials: keep the file private to the user
+    writeFileSync(file, JSON.stringify(session, null, 2), { mode: 0o600 });
+    return {
+      tabs: tabs.length,
+      cookies: session.storage.cookies.length,
+      origins: session.storage.origins.length,
+    };
+  }
+
+  /**
+   * Reopen the tabs of a session file and reapply its storage. Tabs open
+   * after the ones already open, except that a lone blank tab is reused.
+   */
+  async restoreSession(file: string): Promise<SessionRestoreData> {
+    if (!existsSync(file)) {
+      throw new Error(`Session file not found: ${file}`);
+    }
+    const session = parseSessionFile(readFileSync(file, 'utf8'), file);
+    const context = this.contexts[0];
+    if (!context) {
+      throw new Error('Browser not launched');
+    }
+    await context.addCookies(session.storage.cookies);
+
+    // Storage can only be written from a page on its origin; serve an empty
+    // page for each so nothing on the real site runs before it is seeded
+    const seeding = session.storage.origins.filter((o) => o.localStorage.length > 0);
+    if (seeding.length > 0) {
+      const page = await context.newPage();
+      await page.route('**/*', (route) => route.fulfill({ contentType: 'text/html', body: '' }));
+      try {
+        for (const { origin, localStorage: items } of seeding) {
+          await page.goto(origin);
+          await page.evaluate((entries) => {
+            for (const { name, value } of entries) localStorage.setItem(name, value);
+          }, items);
+        }
+      } finally {
+        await page.close();
+        this.pages = this.pages.filter((p) => p !== page);
+      }
+    }
+
+    const reuse = this.pages.length === 1 && this.pages[0].url() === 'about:blank';
+    const first = reuse ? 0 : this.pages.length;
+    for (const [i, tab] of session.tabs.entries()) {
+      if (i > 0 || !reuse) {
+        await this.newTab();
+      }
+      const page = this.getPage();
+      const origin = storageOrigin(tab.url);
+      if (origin && Object.keys(tab.sessionStorage).length > 0) {
+        // sessionStorage belongs to the tab, so seed it in the tab itself
+        const blank = (route: Route) => route.fulfill({ contentType: 'text/html', body: '' });
+        await page.route('**/*', blank);
+        try {
+          await page.goto(origin);
+          await page.evaluate((items) => {
+            for (const [key, value] of Object.entries(items)) sessionStorage.setItem(key, value);
+          }, tab.sessionStorage);
+        } finally {
+          await page.unroute('**/*', blank);
+        }
+      }
+      if (tab.url !== 'about:blank') {
+        await page.goto(tab.url, { waitUntil: 'load' }).catch(() => undefined);
+        const { x, y } = tab.scroll;
+        if (x !== 0 || y !== 0) {
+          await page.evaluate(([left, top]) => scrollTo(left, top), [x, y]).catch(() => undefined);
+        }
+      }
+    }
+    await this.switchTo(first + session.active);
+
+    return {
+      tabs: session.tabs.length,
+      cookies: session.storage.cookies.length,
+      origins: session.storage.origins.length,
+      active: first + session.active,
+      savedAt: session.savedAt ?? null,
+    };
+  }
+
   /**
    * Get all pages
    */
@@ -736,29 +1440,9 @@ export class BrowserManager {
    * AGENT_BROWSER_EXECUTABLE_PATH takes precedence.
    */
   private getBrowserOSExecutablePath(): string {
-    if (process.env.AGENT_BROWSER_EXECUTABLE_PATH) {
-      return process.env.AGENT_BROWSER_EXECUTABLE_PATH;
-    }
-
-    const home = os.homedir();
-    const candidates =
-      process.platform === 'darwin'
-        ? [
-            '/Applications/BrowserOS.app/Contents/MacOS/BrowserOS',
-            path.join(home, '.browseros', 'BrowserOS.app', 'Contents', 'MacOS', 'BrowserOS'),
-          ]
-        : process.platform === 'win32'
-          ? [
-              path.join(home, '.browseros', 'BrowserOS.exe'),
-              'C:\\Program Files\\BrowserOS\\BrowserOS.exe',
-            ]
-          : [
-              path.join(home, '.browseros', 'bin', 'BrowserOS'),
-              path.join(home, '.browseros', 'bin', 'BrowserOS.AppImage'),
-            ];
-
-    const existing = candidates.find((candidate) => existsSync(candidate));
-    return existing ?? candidates[0];
+    // Name the first place looked in if it isn't installed anywhere
+    const { path: found, candidates } = resolveExecutable({ browser: 'browseros' });
+    return found ?? candidates[0].path;
   }
 
   /**
@@ -877,7 +1561,7 @@ export class BrowserManager {
   /**
    * Launch BrowserOS with a persistent profile and connect via CDP.
    */
-  private async connectToBrowserOSNew(): Promise<void> {
+  private async connectToBrowserOSNew(extensions?: string[]): Promise<void> {
     const profileName = this.getBrowserOSProfileName();
     const cdpPort = this.getBrowserOSCDPPort(profileName);
     const executablePath = this.getBrowserOSExecutablePath();
@@ -897,7 +1581,11 @@ export class BrowserManager {
       '--disable-browseros-server',
       `--remote-debugging-port=${cdpPort}`,
       `--user-data-dir=${userDataDir}`,
+      ...containerArgs(),
     ];
+    if (extensions?.length) {
+      launchArgs.push(`--load-extension=${extensions.join(',')}`);
+    }
 
     const browserOSProcess = spawn(executablePath, launchArgs, {
       stdio: 'ignore',
@@ -908,7 +1596,9 @@ export class BrowserManager {
     }
 
     this.browserOSProcess = browserOSProcess;
+    let exited = false;
     browserOSProcess.once('exit', () => {
+      exited = true;
       if (this.browserOSProcess === browserOSProcess) {
         this.browserOSProcess = null;
       }
@@ -922,6 +1612,16 @@ export class BrowserManager {
         browserOSProcess.kill('SIGTERM');
       }
       this.browserOSProcess = null;
+      // Gatekeeper kills an app it won't run before it opens a window
+      const app = executablePath.match(/^(.*?\.app)\//)?.[1];
+      if (exited && process.platform === 'darwin' && app) {
+        throw new Error(
+          `BrowserOS exited before it started; macOS may have blocked it. ` +
+            `Check with: spctl --assess -vv "${app}"\n` +
+            `If it's rejected, run: xattr -dr com.apple.quarantine "${app}", ` +
+            'or open it once from Finder with Control-click > Open'
+        );
+      }
       throw error;
     }
   }
@@ -1161,7 +1861,7 @@ export class BrowserManager {
       this.kernelSessionId = session.session_id;
       this.kernelApiKey = kernelApiKey;
       this.browser = browser;
-      context.setDefaultTimeout(60000);
+      context.setDefaultTimeout(getDefaultTimeout());
       this.contexts.push(context);
       this.pages.push(page);
       this.activePageIndex = 0;
@@ -1234,7 +1934,7 @@ export class BrowserManager {
       this.browserUseSessionId = session.id;
       this.browserUseApiKey = browserUseApiKey;
       this.browser = browser;
-      context.setDefaultTimeout(60000);
+      context.setDefaultTimeout(getDefaultTimeout());
       this.contexts.push(context);
       this.pages.push(page);
       this.activePageIndex = 0;
@@ -1248,6 +1948,57 @@ export class BrowserManager {
     }
   }
 
+  /**
+   * Connect to a browser in a remote pool (see remote.ts). The connection is
+   * pinged every `keepAlive` ms so the pool doesn't drop it as idle.
+   */
+  private async connectToRemote(remote: string, token?: string, keepAlive?: number): Promise<void> {
+    const target = resolveRemote(remote, token);
+    let cdpUrl = target.url;
+    if (target.kind === 'grid') {
+      const session = await createGridSession(target.url, target.headers);
+      this.gridSession = { hub: target.url, id: session.id, headers: target.headers };
+      cdpUrl = session.cdpUrl;
+    }
+
+    try {
+      const browser = await chromium
+        .connectOverCDP(cdpUrl, { headers: target.headers })
+        .catch((err: Error) => {
+          throw new Error(
+            `Failed to connect to remote browser at ${redactUrl(cdpUrl)}: ${err.message.split('\n')[0]}`
+          );
+        });
+      const context = browser.contexts()[0] ?? (await browser.newContext());
+      const page = context.pages()[0] ?? (await context.newPage());
+
+      this.browser = browser;
+      this.remote = remote;
+      context.setDefaultTimeout(getDefaultTimeout());
+      this.contexts.push(context);
+      this.pages.push(page);
+      this.activePageIndex = 0;
+      this.setupPageTracking(page);
+      this.setupContextTracking(context);
+
+      const interval = keepAlive ?? REMOTE_KEEPALIVE_MS;
+      if (interval > 0) {
+        const session = await browser.newBrowserCDPSession();
+        this.remoteKeepAlive = setInterval(() => {
+          session.send('Browser.getVersion').catch(() => {});
+        }, interval);
+        this.remoteKeepAlive.unref();
+      }
+    } catch (error) {
+      if (this.gridSession) {
+        const { hub, id, headers } = this.gridSession;
+        this.gridSession = null;
+        await deleteGridSession(hub, id, headers).catch(() => {});
+      }
+      throw error;
+    }
+  }
+
   /**
    * Launch the browser with the specified options
    * If already launched, this is a no-op (browser stays open)
@@ -1256,10 +2007,18 @@ export class BrowserManager {
     // Determine CDP endpoint: prefer cdpUrl over cdpPort for flexibility
     const cdpEndpoint = options.cdpUrl ?? (options.cdpPort ? String(options.cdpPort) : undefined);
     const provider = options.provider ?? process.env.AGENT_BROWSER_PROVIDER ?? null;
+    const remote = options.remote ?? null;
     const hasExtensions = !!options.extensions?.length;
     const hasProfile = !!options.profile;
     const hasStorageState = !!options.storageState;
 
+    if (remote && (cdpEndpoint || provider)) {
+      throw new Error('A remote browser cannot be combined with CDP or a provider');
+    }
+    if (remote && (hasExtensions || hasProfile)) {
+      throw new Error('Extensions and profiles need a local browser, not a remote one');
+    }
+
     if (hasExtensions && cdpEndpoint) {
       throw new Error('Extensions cannot be used with CDP connection');
     }
@@ -1284,6 +2043,9 @@ export class BrowserManager {
       const needsRelaunch =
         (!cdpEndpoint && this.cdpEndpoint !== null) ||
         (!!cdpEndpoint && this.needsCdpReconnect(cdpEndpoint)) ||
+        // A pool that dropped the connection is reconnected on the next command
+        remote !== this.remote ||
+        (!!remote && !this.browser?.isConnected()) ||
         provider !== this.launchProvider;
       if (needsRelaunch) {
         await this.close();
@@ -1292,12 +2054,42 @@ export class BrowserManager {
       }
     }
 
+    const clientCertificates = options.clientCertificates ?? [];
+    if (clientCertificates.length > 0 && (cdpEndpoint || provider || remote)) {
+      throw new Error(
+        'Client certificates need a browser launched by agent-browser, not CDP, a provider or a remote browser'
+      );
+    }
+    this.clientCertificates = clientCertificates;
+    this.stealth = options.stealth ?? false;
+    this.deterministic = options.deterministic ?? false;
+    this.initScripts = (options.initScripts ?? []).map((file) => {
+      try {
+        return readFileSync(file, 'utf-8');
+      } catch (err) {
+        throw new Error(`Can't read init script ${file}: ${(err as Error).message}`);
+      }
+    });
+
+    const extraCa = (options.extraCa ?? []).flatMap(readCaFile);
+    const ignoreCertHosts = options.ignoreCertErrorsFor ?? [];
+    this.tlsTrust =
+      extraCa.length > 0 || ignoreCertHosts.length > 0
+        ? new TlsTrust(extraCa, ignoreCertHosts, clientCertificates)
+        : null;
+
     if (cdpEndpoint) {
       await this.connectViaCDP(cdpEndpoint);
       this.launchProvider = null;
       return;
     }
 
+    if (remote) {
+      await this.connectToRemote(remote, options.remoteToken, options.remoteKeepAlive);
+      this.launchProvider = null;
+      return;
+    }
+
     // Cloud browser providers require explicit opt-in via -p flag or AGENT_BROWSER_PROVIDER env var
     // -p flag takes precedence over env var.
     if (provider === 'browseros-existing') {
@@ -1306,7 +2098,7 @@ export class BrowserManager {
       return;
     }
     if (provider === 'browseros-new') {
-      await this.connectToBrowserOSNew();
+      await this.connectToBrowserOSNew(options.extensions);
       this.launchProvider = provider;
       return;
     }
@@ -1338,9 +2130,33 @@ export class BrowserManager {
       throw new Error('allowFileAccess is only supported in Chromium');
     }
 
+    const authServers = options.authServerAllowlist ?? [];
+    const authDelegates = options.authDelegateAllowlist ?? [];
+    if ((authServers.length > 0 || authDelegates.length > 0) && browserType !== 'chromium') {
+      throw new Error('Integrated auth allowlists are only supported in Chromium');
+    }
+    if (options.stealth && browserType !== 'chromium') {
+      throw new Error('Stealth is only supported in Chromium');
+    }
+    if (options.channel && browserType !== 'chromium') {
+      throw new Error('--browser picks a Chromium-based browser and needs the chromium engine');
+    }
+
     const launcher =
       browserType === 'firefox' ? firefox : browserType === 'webkit' ? webkit : chromium;
     const viewport = options.viewport ?? { width: 1280, height: 720 };
+    // Chromium launches use BrowserOS when it's installed, then the system's
+    // Chrome, Edge or Chromium, else Playwright's bundled build
+    const resolved =
+      browserType === 'chromium'
+        ? resolveExecutable({ browser: options.channel, explicit: options.executablePath })
+        : null;
+    if (options.channel && !resolved?.path) {
+      throw new Error(
+        `${options.channel} is not installed. Run: agent-browser --browser ${options.channel} which`
+      );
+    }
+    const executablePath = resolved ? (resolved.path ?? undefined) : options.executablePath;
 
     // Build base args array with file access flags if enabled
     // --allow-file-access-from-files: allows file:// URLs to read other file:// URLs via XHR/fetch
@@ -1348,31 +2164,51 @@ export class BrowserManager {
     const fileAccessArgs = options.allowFileAccess
       ? ['--allow-file-access-from-files', '--allow-file-access']
       : [];
+    // Integrated Windows auth: the servers that may be sent NTLM/Negotiate
+    // credentials without a prompt, and those that may receive delegated tickets
+    const authArgs = [
+      ...(authServers.length > 0 ? [`--auth-server-allowlist=${authServers.join(',')}`] : []),
+      ...(authDelegates.length > 0
+        ? [`--auth-negotiate-delegate-allowlist=${authDelegates.join(',')}`]
+        : []),
+    ];
+    const launchArgs = [
+      ...fileAccessArgs,
+      ...authArgs,
+      ...(options.stealth ? STEALTH_ARGS : []),
+      ...(options.deterministic && browserType === 'chromium' ? DETERMINISTIC_ARGS : []),
+      ...(browserType === 'chromium' ? containerArgs() : []),
+      ...(resolved?.browser ? BROWSER_ARGS[resolved.browser] : []),
+    ];
     const baseArgs = options.args
-      ? [...fileAccessArgs, ...options.args]
-      : fileAccessArgs.length > 0
-        ? fileAccessArgs
+      ? [...launchArgs, ...options.args]
+      : launchArgs.length > 0
+        ? launchArgs
         : undefined;
 
     let context: BrowserContext;
     if (hasExtensions) {
-      // Extensions require persistent context in a temp directory
+      // Extensions require a persistent context: the profile if given, else a temp directory
       const extPaths = options.extensions!.join(',');
       const session = process.env.AGENT_BROWSER_SESSION || 'default';
       // Combine extension args with custom args and file access args
       const extArgs = [`--disable-extensions-except=${extPaths}`, `--load-extension=${extPaths}`];
       const allArgs = baseArgs ? [...extArgs, ...baseArgs] : extArgs;
+      const userDataDir = hasProfile
+        ? options.profile!.replace(/^~\//, os.homedir() + '/')
+        : path.join(os.tmpdir(), `agent-browser-ext-${session}`);
       context = await launcher.launchPersistentContext(
-        path.join(os.tmpdir(), `agent-browser-ext-${session}`),
+        userDataDir,
         {
           headless: false,
-          executablePath: options.executablePath,
+          executablePath,
           args: allArgs,
           viewport,
           extraHTTPHeaders: options.headers,
           userAgent: options.userAgent,
           ...(options.proxy && { proxy: options.proxy }),
           ignoreHTTPSErrors: options.ignoreHTTPSErrors ?? false,
+          ...(clientCertificates.length > 0 && { clientCertificates }),
         }
       );
       this.isPersistentContext = true;
@@ -1382,20 +2218,21 @@ export class BrowserManager {
       const profilePath = options.profile!.replace(/^~\//, os.homedir() + '/');
       context = await launcher.launchPersistentContext(profilePath, {
         headless: options.headless ?? true,
-        executablePath: options.executablePath,
+        executablePath,
         args: baseArgs,
         viewport,
         extraHTTPHeaders: options.headers,
         userAgent: options.userAgent,
         ...(options.proxy && { proxy: options.proxy }),
         ignoreHTTPSErrors: options.ignoreHTTPSErrors ?? false,
+        ...(clientCertificates.length > 0 && { clientCertificates }),
       });
       this.isPersistentContext = true;
     } else {
       // Regular ephemeral browser
       this.browser = await launcher.launch({
         headless: options.headless ?? true,
-        executablePath: options.executablePath,
+        executablePath,
         args: baseArgs,
       });
       this.cdpEndpoint = null;
@@ -1405,11 +2242,12 @@ export class BrowserManager {
         userAgent: options.userAgent,
         ...(options.proxy && { proxy: options.proxy }),
         ignoreHTTPSErrors: options.ignoreHTTPSErrors ?? false,
+        ...(clientCertificates.length > 0 && { clientCertificates }),
         ...(options.storageState && { storageState: options.storageState }),
       });
     }
 
-    context.setDefaultTimeout(60000);
+    context.setDefaultTimeout(getDefaultTimeout());
     this.contexts.push(context);
     this.setupContextTracking(context);
 
@@ -1499,46 +2337,410 @@ export class BrowserManager {
     }
   }
 
+  /**
+   * Count requests, bytes and cache hits for `agent-browser stats`.
+   * Cache hits and WebSocket traffic come from CDP, so they are only
+   * tracked on Chromium.
+   */
+  private trackNetworkStats(page: Page): void {
+    page.on('request', (request) => {
+      this.netStats.requests++;
+      const host = hostOf(request.url());
+      const entry = this.hostStats.get(host) ?? { requests: 0, bytes: 0 };
+      entry.requests++;
+      this.hostStats.set(host, entry);
+    });
+    page.on('requestfailed', () => {
+      this.netStats.failed++;
+    });
+    page.on('requestfinished', (request) => {
+      request
+        .sizes()
+        .then((sizes) => {
+          const bytesIn = sizes.responseHeadersSize + sizes.responseBodySize;
+          this.netStats.bytesIn += bytesIn;
+          this.netStats.bytesOut += sizes.requestHeadersSize + sizes.requestBodySize;
+          const entry = this.hostStats.get(hostOf(request.url()));
+          if (entry) entry.bytes += bytesIn;
+        })
+        .catch(() => {
+          // Page closed before sizes were available
+        });
+    });
+
+    page
+      .context()
+      .newCDPSession(page)
+      .then(traceCDP)
+      .then(async (cdp) => {
+        cdp.on('Network.requestServedFromCache', () => {
+          this.netStats.cacheHits++;
+        });
+        cdp.on('Network.responseReceived', (event) => {
+          if (event.response.fromDiskCache || event.response.status === 304) {
+            this.netStats.cacheHits++;
+          }
+        });
+        this.webSockets.attach(cdp);
+        await cdp.send('Network.enable');
+      })
+      .catch(() => {
+        // Not Chromium
+      });
+  }
+
+  /** Recycle tabs whose JS heap grows past `limit` bytes; 0 stops it */
+  setTabMemoryLimit(limit: number, mode?: RecycleMode): void {
+    this.memoryWatchdog.configure(limit, mode);
+  }
+
+  /** Each tab's JS heap; 0 for tabs that can't be measured */
+  async measureTabs(): Promise<TabMemory[]> {
+    return Promise.all(
+      this.pages.map(async (page, index) => {
+        let usage = { usedSize: 0, totalSize: 0 };
+        try {
+          const cdp = await page.context().newCDPSession(page);
+          try {
+            usage = await cdp.send('Runtime.getHeapUsage');
+          } finally {
+            await cdp.detach().catch(() => {});
+          }
+        } catch {
+          // Closed meanwhile, or not Chromium
+        }
+        return { index, url: page.url(), heapUsed: usage.usedSize, heapTotal: usage.totalSize };
+      })
+    );
+  }
+
+  async getMemory(): Promise<MemoryData> {
+    const watchdog = this.memoryWatchdog;
+    return {
+      limit: watchdog.limit,
+      mode: watchdog.mode,
+      tabs: await this.measureTabs(),
+      recycles: watchdog.recycles,
+      total: watchdog.total,
+    };
+  }
+
+  /**
+   * Recycle the tabs over --max-tab-memory. Tabs are only measured when a
+   * check is due, so this is cheap to call after every command.
+   */
+  async recycleOversizedTabs(): Promise<TabRecycle[]> {
+    const watchdog = this.memoryWatchdog;
+    if (!this.isLaunched() || !watchdog.due()) return [];
+    const pages = [...this.pages];
+    const recycles: TabRecycle[] = [];
+    for (const tab of watchdog.over(await this.measureTabs())) {
+      const page = pages[tab.index];
+      const recycle: TabRecycle = {
+        at: Date.now(),
+        index: this.pages.indexOf(page),
+        url: tab.url,
+        heapUsed: tab.heapUsed,
+        limit: watchdog.limit ?? 0,
+        mode: watchdog.mode,
+      };
+      if (recycle.index === -1) continue;
+      try {
+        await this.recycleTab(page, watchdog.mode);
+      } catch (error) {
+        recycle.error = error instanceof Error ? error.message : String(error);
+      }
+      watchdog.record(recycle);
+      recycles.push(recycle);
+      console.error(
+        `Recycled tab ${recycle.index} (${redactUrl(tab.url)}) by ${recycle.mode}: ` +
+          `JS heap ${formatBytes(tab.heapUsed)} over ${formatBytes(recycle.limit)}` +
+          (recycle.error ? ` (failed: ${recycle.error})` : '')
+      );
+    }
+    return recycles;
+  }
+
+  /** Reload `page`, or replace it with a fresh tab on the same URL */
+  private async recycleTab(page: Page, mode: RecycleMode): Promise<void> {
+    if (mode === 'reload') {
+      await page.reload({ waitUntil: 'load' });
+      return;
+    }
+    const url = page.url();
+    if (this.pages.indexOf(page) === this.activePageIndex) {
+      await this.invalidateCDPSession();
+    }
+    const fresh = await page.context().newPage();
+    const added = this.pages.indexOf(fresh);
+    if (added === -1) {
+      this.setupPageTracking(fresh);
+    } else {
+      this.pages.splice(added, 1);
+    }
+    const index = this.pages.indexOf(page);
+    if (index === -1) {
+      await fresh.close();
+      throw new Error('Tab closed before it could be recycled');
+    }
+    // The fresh tab takes the old one's index, so tab numbers stay the same
+    this.pages[index] = fresh;
+    await page.close().catch(() => {});
+    if (url && url !== 'about:blank') {
+      await fresh.goto(url, { waitUntil: 'load' });
+    }
+  }
+
+  getStats(topHosts: number = 10): StatsData {
+    const hosts = [...this.hostStats.entries()]
+      .map(([host, s]) => ({ host, ...s }))
+      .sort((a, b) => b.bytes - a.bytes || b.requests - a.requests)
+      .slice(0, topHosts);
+    const limiters = [this.requestLimiter, this.navigationLimiter];
+    return {
+      ...this.netStats,
+      blocked: this.adblockBlocked,
+      throttled: limiters.reduce((sum, l) => sum + (l?.throttled ?? 0), 0),
+      throttledMs: Math.round(limiters.reduce((sum, l) => sum + (l?.waitedMs ?? 0), 0)),
+      topHosts: hosts,
+    };
+  }
+
+  /**
+   * Console messages, page errors and responses since the previous call
+   */
+  takeTraceBuffer(): TraceBuffer {
+    const buffer = this.traceBuffer;
+    this.traceBuffer = emptyTraceBuffer();
+    return buffer;
+  }
+
+  private pushTrace<K extends keyof TraceBuffer>(key: K, entry: TraceBuffer[K][number]): void {
+    const list = this.traceBuffer[key] as TraceBuffer[K][number][];
+    list.push(entry);
+    if (list.length > MAX_TRACE_ENTRIES) list.shift();
+  }
+
+  resetStats(): void {
+    this.netStats = emptyNetStats();
+    this.hostStats.clear();
+    this.adblockBlocked = 0;
+    for (const limiter of [this.requestLimiter, this.navigationLimiter]) {
+      if (limiter) {
+        limiter.throttled = 0;
+        limiter.waitedMs = 0;
+      }
+    }
+  }
+
+  /**
+   * Save matching response bodies from every page until stopCapture()
+   */
+  startCapture(options: CaptureOptions): void {
+    if (this.responseCapture) {
+      throw new Error(
+        `Already capturing to ${this.responseCapture.options.out}. Run: agent-browser network capture stop`
+      );
+    }
+    this.responseCapture = new ResponseCapture(options);
+  }
+
+  stopCapture(): { dir: string; saved: number; skipped: number } {
+    if (!this.responseCapture) {
+      throw new Error('No capture running');
+    }
+    const summary = this.responseCapture.summary();
+    this.responseCapture = null;
+    return summary;
+  }
+
+  /**
+   * XHR/fetch endpoints observed so far, most called first
+   */
+  getApis(filter?: string): ApiEndpoint[] {
+    return this.apiCatalog.list(filter);
+  }
+
+  clearApis(): void {
+    this.apiCatalog.clear();
+  }
+
+  getWebSockets(): WebSocketTracker {
+    return this.webSockets;
+  }
+
+  /**
+   * Record trusted mouse and keyboard input in every page until
+   * stopInputRecording() writes it to `path`
+   */
+  async startInputRecording(path: string): Promise<void> {
+    await this.inputRecorder.start(path, this.contexts, this.getPage());
+  }
+
+  stopInputRecording(): { path: string; events: number; duration: number } {
+    return this.inputRecorder.stop();
+  }
+
+  /** Report mutations of `selector` in the active page until the returned function is called. */
+  watchDom(
+    selector: string,
+    types: DomMutationType[],
+    emit: (event: DomMutationEvent) => void
+  ): Promise<() => void> {
+    return this.domWatcher.watch(this.getPage(), selector, types, emit);
+  }
+
+  /**
+   * Answer requests from a recorded HAR file in every context, including
+   * ones opened later. Requests missing from the HAR are aborted, or sent to
+   * the network with notFound 'fallback'. Lasts until the browser closes.
+   */
+  async replayFromHar(
+    harPath: string,
+    options: { notFound: 'abort' | 'fallback'; url?: string }
+  ): Promise<void> {
+    if (this.harReplay) {
+      throw new Error(
+        `Already replaying ${this.harReplay.path}. Run agent-browser close to start over`
+      );
+    }
+    this.harReplay = { path: harPath, ...options };
+    await Promise.all(this.contexts.map((context) => context.routeFromHAR(harPath, options)));
+  }
+
   /**
    * Set up console, error, and close tracking for a page
    */
   private setupPageTracking(page: Page): void {
-    page.on('console', (msg) => {
-      this.consoleMessages.push({
-        type: msg.type(),
-        text: msg.text(),
+    this.trackNetworkStats(page);
+    if (this.timezoneOverride) {
+      this.applyTimezone(page).catch(() => {
+        // Closed before the override landed
+      });
+    }
+    if (this.deterministic) {
+      page.emulateMedia({ reducedMotion: 'reduce' }).catch(() => {
+        // Closed before the emulation landed
+      });
+    }
+
+    page.on('response', (response) => {
+      this.responseCapture?.handle(response).catch(() => {
+        // Capture directory became unwritable; keep the page running
+      });
+      this.apiCatalog.record(response).catch(() => {
+        // Request details unavailable once the page is gone
+      });
+      const request = response.request();
+      this.pushTrace('responses', {
+        method: request.method(),
+        url: response.url(),
+        status: response.status(),
+        type: request.resourceType(),
         timestamp: Date.now(),
       });
     });
 
+    page.on('console', (msg) => {
+      const message = { type: msg.type(), text: msg.text(), timestamp: Date.now() };
+      this.consoleMessages.push(message);
+      this.pushTrace('console', message);
+    });
+
     page.on('pageerror', (error) => {
-      this.pageErrors.push({
-        message: error.message,
-        timestamp: Date.now(),
-      });
+      const pageError = { message: error.message, timestamp: Date.now() };
+      this.pageErrors.push(pageError);
+      this.pushTrace('errors', pageError);
     });
 
     page.on('close', () => {
       const index = this.pages.indexOf(page);
+      const opener = this.popupOpeners.get(page);
+      this.popupOpeners.delete(page);
       if (index !== -1) {
+        const wasActive = index === this.activePageIndex;
         this.pages.splice(index, 1);
-        if (this.activePageIndex >= this.pages.length) {
+        if (wasActive && opener && this.pages.includes(opener)) {
+          // A followed popup closed itself (e.g. end of an OAuth flow): return to its opener.
+          // Invalidating first stops a screencast on the popup's session before it's dropped.
+          void this.invalidateCDPSession();
+          this.activePageIndex = this.pages.indexOf(opener);
+        } else if (this.activePageIndex > index) {
+          this.activePageIndex--;
+        } else if (this.activePageIndex >= this.pages.length) {
           this.activePageIndex = Math.max(0, this.pages.length - 1);
         }
       }
     });
   }
 
+  /**
+   * Apply the popup policy to a page that was opened by another page
+   */
+  private async handlePopup(page: Page): Promise<void> {
+    const opener = await page.opener().catch(() => null);
+    if (!opener) return;
+
+    if (this.popupPolicy === 'block') {
+      await page.close().catch(() => {});
+      return;
+    }
+
+    if (this.popupPolicy === 'follow') {
+      this.popupOpeners.set(page, opener);
+      const index = this.pages.indexOf(page);
+      if (index !== -1 && index !== this.activePageIndex) {
+        await this.invalidateCDPSession();
+        this.activePageIndex = index;
+      }
+    }
+  }
+
   /**
    * Set up tracking for new pages in a context (for CDP connections and popups/new tabs)
    * This handles pages created externally (e.g., via target="_blank" links)
    */
   private setupContextTracking(context: BrowserContext): void {
+    if (this.stealth) {
+      void context.addInitScript(STEALTH_SCRIPT);
+    }
+    if (this.deterministic) {
+      void context.addInitScript(DETERMINISTIC_SCRIPT);
+    }
+    // After stealth, so user scripts see the adjusted values; in the order given
+    for (const script of this.initScripts) {
+      void context.addInitScript(script);
+    }
+    // Registered first so it runs last, after the handlers that block or delay requests
+    if (this.tlsTrust) {
+      void context.route('**/*', this.tlsTrust.handler);
+    }
+    if (this.adblockHandler) {
+      void context.route('**/*', this.adblockHandler);
+    }
+    if (this.rateLimitHandler) {
+      void context.route('**/*', this.rateLimitHandler);
+    }
+    if (this.robotsHandler) {
+      void context.route('**/*', this.robotsHandler);
+    }
+    if (this.guardrailsHandler) {
+      void context.route('**/*', this.guardrailsHandler);
+    }
+    // Last, so a write is refused before anything else holds it up
+    if (this.readOnlyHandler) {
+      void context.route('**/*', this.readOnlyHandler);
+    }
+    if (this.harReplay) {
+      const { path: harPath, ...options } = this.harReplay;
+      void context.routeFromHAR(harPath, options);
+    }
     context.on('page', (page) => {
       // Only add if not already tracked (avoids duplicates when newTab() creates pages)
       if (!this.pages.includes(page)) {
         this.pages.push(page);
         this.setupPageTracking(page);
+        void this.handlePopup(page);
       }
     });
   }
@@ -1579,8 +2781,9 @@ export class BrowserManager {
 
     const context = await this.browser.newContext({
       viewport: viewport ?? { width: 1280, height: 720 },
+      ...(this.clientCertificates.length > 0 && { clientCertificates: this.clientCertificates }),
     });
-    context.setDefaultTimeout(60000);
+    context.setDefaultTimeout(getDefaultTimeout());
     this.contexts.push(context);
     this.setupContextTracking(context);
 
@@ -1683,6 +2886,41 @@ export class BrowserManager {
     return tabs;
   }
 
+  /**
+   * List tabs and the dedicated, service and shared workers running in them
+   */
+  async listTargets(): Promise<TargetInfo[]> {
+    return this.targets.list(this.pages, this.contexts, this.browser);
+  }
+
+  /**
+   * Evaluate a script in a target from listTargets()
+   */
+  async evaluateInTarget(id: string, script: string): Promise<unknown> {
+    return this.targets.evaluate(id, script, this.pages, this.contexts, this.browser);
+  }
+
+  /**
+   * Bring the session back to a state the next command can rely on after
+   * the commands in flight (`actions`) were cancelled: stop a page load in
+   * progress, and let go of the mouse button and modifier keys an
+   * interrupted click, drag or key press may still be holding.
+   */
+  async settleAfterCancel(actions: string[]): Promise<void> {
+    if (!this.isLaunched()) return;
+    const page = this.getPage();
+    try {
+      await (await this.getCDPSession()).send('Page.stopLoading');
+    } catch {
+      // Not Chromium, or the page is gone
+    }
+    if (!actions.some((action) => INPUT_ACTIONS.has(action))) return;
+    await page.mouse.up().catch(() => {});
+    for (const key of ['Shift', 'Control', 'Alt', 'Meta']) {
+      await page.keyboard.up(key).catch(() => {});
+    }
+  }
+
   /**
    * Get or create a CDP session for the current page
    * Only works with Chromium-based browsers
@@ -1696,7 +2934,7 @@ export class BrowserManager {
     const context = page.context();
 
     // Create a new CDP session attached to the page
-    this.cdpSession = await context.newCDPSession(page);
+    this.cdpSession = traceCDP(await context.newCDPSession(page));
     return this.cdpSession;
   }
 
@@ -1723,6 +2961,7 @@ export class BrowserManager {
     const cdp = await this.getCDPSession();
     this.frameCallback = callback;
     this.screencastActive = true;
+    const throttle = new FrameThrottle(options?.fps);
 
     // Create and store the frame handler so we can remove it later
     this.screencastFrameHandler = async (params: any) => {
@@ -1735,8 +2974,8 @@ export class BrowserManager {
       // Acknowledge the frame to receive the next one
       await cdp.send('Page.screencastFrameAck', { sessionId: params.sessionId });
 
-      // Call the callback with the frame
-      if (this.frameCallback) {
+      // Call the callback with the frame, unless it's over the frame rate
+      if (this.frameCallback && throttle.admit()) {
         this.frameCallback(frame);
       }
     };
@@ -1744,12 +2983,21 @@ export class BrowserManager {
     // Listen for screencast frames
     cdp.on('Page.screencastFrame', this.screencastFrameHandler);
 
+    // Scaled frames are made smaller by the browser rather than resized here
+    let maxWidth = options?.maxWidth ?? 1280;
+    let maxHeight = options?.maxHeight ?? 720;
+    if (options?.scale) {
+      const viewport = this.getPage().viewportSize() ?? { width: 1280, height: 720 };
+      maxWidth = Math.max(1, Math.round(viewport.width * options.scale));
+      maxHeight = Math.max(1, Math.round(viewport.height * options.scale));
+    }
+
     // Start the screencast
     await cdp.send('Page.startScreencast', {
       format: options?.format ?? 'jpeg',
       quality: options?.quality ?? 80,
-      maxWidth: options?.maxWidth ?? 1280,
-      maxHeight: options?.maxHeight ?? 720,
+      maxWidth,
+      maxHeight,
       everyNthFrame: options?.everyNthFrame ?? 1,
     });
   }
@@ -1952,6 +3200,7 @@ export class BrowserManager {
         size: viewport,
       },
       storageState,
+      ...(this.clientCertificates.length > 0 && { clientCertificates: this.clientCertificates }),
     });
     this.recordingContext.setDefaultTimeout(10000);
 
@@ -2082,6 +3331,8 @@ export class BrowserManager {
    * Close the browser and clean up
    */
   async close(): Promise<void> {
+    this.armedWaits.clear();
+
     // Stop recording if active (saves video)
     if (this.recordingContext) {
       await this.stopRecording();
@@ -2117,6 +3368,19 @@ export class BrowserManager {
         console.error('Failed to close Kernel session:', error);
       });
       this.browser = null;
+    } else if (this.remote !== null) {
+      // Remote pool: disconnecting hands the browser back; a Grid session is ended explicitly
+      if (this.remoteKeepAlive) clearInterval(this.remoteKeepAlive);
+      this.remoteKeepAlive = null;
+      await this.browser?.close().catch(() => {});
+      this.browser = null;
+      if (this.gridSession) {
+        const { hub, id, headers } = this.gridSession;
+        await deleteGridSession(hub, id, headers).catch((error) => {
+          console.error('Failed to end Selenium Grid session:', error);
+        });
+        this.gridSession = null;
+      }
     } else if (this.cdpEndpoint !== null) {
       // CDP: only disconnect, don't close external app's pages
       if (this.browser) {
@@ -2145,6 +3409,7 @@ export class BrowserManager {
     this.pages = [];
     this.contexts = [];
     this.cdpEndpoint = null;
+    this.remote = null;
     this.browserbaseSessionId = null;
     this.browserbaseApiKey = null;
     this.browserUseSessionId = null;
@@ -2156,6 +3421,10 @@ export class BrowserManager {
     this.activePageIndex = 0;
     this.refMap = {};
     this.lastSnapshot = '';
+    this.snapshotCache.clear();
     this.frameCallback = null;
+    this.targets = new TargetRegistry();
+    this.inputRecorder = new InputRecorder();
+    this.domWatcher = new DomWatcher();
   }
 }
@@ -0,0 +1,31 @@
+import { describe, it, expect } from 'vitest';
+import { InFlight, isCancelled } from './cancel.js';
+import type { Command, Response } from './types.js';
+
+const command = (id: string, action: string) => ({ id, action }) as Command;
+
+describe('InFlight', () => {
+  it('answers cancelled commands at once', async () => {
+    const running = new InFlight();
+    const wait = command('1', 'wait');
+    const response = running.run(wait, () => new Promise<Response>(() => {}));
+    expect(running.cancel()).toEqual(['wait']);
+    expect(await response).toEqual({
+      id: '1',
+      success: false,
+      error: 'Cancelled',
+      code: 'E_CANCELLED',
+    });
+    expect(isCancelled(wait)).toBe(true);
+    expect(running.cancel()).toEqual([]);
+  });
+
+  it('leaves finished commands alone', async () => {
+    const running = new InFlight();
+    const click = command('2', 'click');
+    const response = await running.run(click, async () => ({ id: '2', success: true, data: {} }));
+    expect(response.success).toBe(true);
+    expect(running.cancel()).toEqual([]);
+    expect(isCancelled(click)).toBe(false);
+  });
+});
@@ -0,0 +1,47 @@
+/**
+ * Cooperative cancellation of the commands in flight.
+ *
+ * The CLI sends `cancel` when Ctrl-C is pressed (and `run` does for a job
+ * cancelled with DELETE /jobs/<id>). Every command in flight is answered
+ * with E_CANCELLED at once, so its client can finish up, and the session is
+ * brought back to a state the next command can rely on (see
+ * BrowserManager.settleAfterCancel). Work that loops, such as action retries,
+ * checks `isCancelled` and stops early; a Playwright call already waiting on
+ * the page can't be interrupted and is left to time out, with its result
+ * dropped.
+ */
+
+import { errorResponse } from './protocol.js';
+import type { Command, Response } from './types.js';
+
+const cancelled = new WeakSet<Command>();
+
+export function isCancelled(command: Command): boolean {
+  return cancelled.has(command);
+}
+
+export class InFlight {
+  private running = new Map<Command, () => void>();
+
+  /** Run `execute` for `command`, unless it is cancelled first */
+  run(command: Command, execute: () => Promise<Response>): Promise<Response> {
+    const stopped = new Promise<Response>((resolve) => {
+      this.running.set(command, () =>
+        resolve(errorResponse(command.id, 'Cancelled', 'E_CANCELLED'))
+      );
+    });
+    return Promise.race([execute(), stopped]).finally(() => this.running.delete(command));
+  }
+
+  /** Answer every command in flight with E_CANCELLED; returns their actions */
+  cancel(): string[] {
+    const actions: string[] = [];
+    for (const [command, stop] of this.running) {
+      cancelled.add(command);
+      stop();
+      actions.push(command.action);
+    }
+    this.running.clear();
+    return actions;
+  }
+}
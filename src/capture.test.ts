@@ -0,0 +1,43 @@
+import { describe, it, expect } from 'vitest';
+import { captureFileName, mimeMatches, urlMatches } from './capture.js';
+
+describe('urlMatches', () => {
+  it('matches substrings', () => {
+    expect(urlMatches('/api/', 'https://example.com/api/items')).toBe(true);
+    expect(urlMatches('/api/', 'https://example.com/static/app.js')).toBe(false);
+  });
+
+  it('matches wildcard patterns against the whole URL', () => {
+    expect(urlMatches('*/api/*.json', 'https://example.com/api/items.json')).toBe(true);
+    expect(urlMatches('*/api/*.json', 'https://example.com/api/items')).toBe(false);
+  });
+});
+
+describe('mimeMatches', () => {
+  it('accepts everything without filters', () => {
+    expect(mimeMatches(undefined, 'text/html')).toBe(true);
+  });
+
+  it('matches exact types and wildcards', () => {
+    expect(mimeMatches(['application/json', 'image/*'], 'image/png')).toBe(true);
+    expect(mimeMatches(['application/json', 'image/*'], 'application/json')).toBe(true);
+    expect(mimeMatches(['application/json', 'image/*'], 'text/html')).toBe(false);
+  });
+});
+
+describe('captureFileName', () => {
+  it('numbers files and derives the extension from the MIME type', () => {
+    expect(captureFileName(3, 'https://api.example.com/v1/items?page=2', 'application/json')).toBe(
+      '0003-api.example.com-v1-items.json'
+    );
+    expect(captureFileName(12, 'https://cdn.example.com/logo.png', 'image/png')).toBe(
+      '0012-cdn.example.com-logo.png'
+    );
+    expect(captureFileName(1, 'https://x.com/feed', 'application/ld+json')).toBe(
+      '0001-x.com-feed.json'
+    );
+    expect(captureFileName(1, 'https://x.com/blob', 'application/octet-stream')).toBe(
+      '0001-x.com-blob.bin'
+    );
+  });
+});
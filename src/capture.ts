@@ -0,0 +1,143 @@
+/**
+ * Save response bodies to disk as they arrive (`network capture`).
+ *
+ * Each saved body gets a numbered file named after its URL, and a line in
+ * `index.jsonl` in the same directory recording the URL, status, MIME type
+ * and size, so callers can find the response they want without guessing at
+ * file names.
+ */
+
+import { appendFileSync, mkdirSync, writeFileSync } from 'node:fs';
+import path from 'node:path';
+import type { Response } from 'playwright-core';
+
+export interface CaptureOptions {
+  /** Substring of the URL, or a pattern where `*` matches anything */
+  url: string;
+  /** Directory to write bodies into */
+  out: string;
+  /** Skip bodies larger than this many bytes */
+  maxSize?: number;
+  /** MIME types to keep, e.g. `application/json` or `image/*` */
+  mime?: string[];
+}
+
+export interface CaptureEntry {
+  file: string;
+  url: string;
+  status: number;
+  mimeType: string;
+  size: number;
+  timestamp: number;
+}
+
+const EXTENSIONS: Record<string, string> = {
+  'application/json': 'json',
+  'application/javascript': 'js',
+  'text/javascript': 'js',
+  'text/html': 'html',
+  'text/css': 'css',
+  'text/plain': 'txt',
+  'text/csv': 'csv',
+  'application/xml': 'xml',
+  'text/xml': 'xml',
+  'application/pdf': 'pdf',
+  'image/png': 'png',
+  'image/jpeg': 'jpg',
+  'image/gif': 'gif',
+  'image/webp': 'webp',
+  'image/svg+xml': 'svg',
+  'image/avif': 'avif',
+};
+
+export function urlMatches(pattern: string, url: string): boolean {
+  if (!pattern.includes('*')) return url.includes(pattern);
+  const source = pattern
+    .split('*')
+    .map((part) => part.replace(/[.+?^${}()|[\]\\]/g, '\\$&'))
+    .join('.*');
+  return new RegExp(`^${source}$`).test(url);
+}
+
+export function mimeMatches(filters: string[] | undefined, mimeType: string): boolean {
+  if (!filters || filters.length === 0) return true;
+  return filters.some((filter) => {
+    const f = filter.trim().toLowerCase();
+    return f.endsWith('/*') ? mimeType.startsWith(f.slice(0, -1)) : mimeType === f;
+  });
+}
+
+/** `0003-api.example.com-v1-items.json` for the third capture of /v1/items */
+export function captureFileName(index: number, url: string, mimeType: string): string {
+  let stem = url;
+  try {
+    const parsed = new URL(url);
+    stem = parsed.host + parsed.pathname;
+  } catch {
+    // Keep the raw URL
+  }
+  stem = stem
+    .replace(/[^a-zA-Z0-9._-]+/g, '-')
+    .replace(/^-+|-+$/g, '')
+    .slice(0, 80);
+  const known = EXTENSIONS[mimeType];
+  const ext = known ?? (mimeType.endsWith('+json') ? 'json' : 'bin');
+  stem = stem.replace(new RegExp(`\\.${ext}$`), '');
+  return `${String(index).padStart(4, '0')}-${stem || 'response'}.${ext}`;
+}
+
+export class ResponseCapture {
+  private saved = 0;
+  private skipped = 0;
+
+  constructor(readonly options: CaptureOptions) {
+    mkdirSync(options.out, { recursive: true });
+  }
+
+  async handle(response: Response): Promise<void> {
+    const url = response.url();
+    if (!urlMatches(this.options.url, url)) return;
+    const contentType = response.headers()['content-type'] ?? '';
+    const mimeType = contentType.split(';')[0].trim().toLowerCase();
+    if (!mimeMatches(this.options.mime, mimeType)) return;
+
+    // Redirects have no body to read
+    const status = response.status();
+    if (status >= 300 && status < 400) return;
+
+    const { maxSize } = this.options;
+    const declared = Number(response.headers()['content-length']);
+    if (maxSize !== undefined && declared > maxSize) {
+      this.skipped++;
+      return;
+    }
+    let body: Buffer;
+    try {
+      body = await response.body();
+    } catch {
+      // Body was evicted or the page navigated away
+      return;
+    }
+    if (maxSize !== undefined && body.length > maxSize) {
+      this.skipped++;
+      return;
+    }
+
+    this.saved++;
+    const file = captureFileName(this.saved, url, mimeType);
+    writeFileSync(path.join(this.options.out, file), body);
+    const entry: CaptureEntry = {
+      file,
+      url,
+      status,
+      mimeType,
+      size: body.length,
+      timestamp: Date.now(),
+    };
+    appendFileSync(path.join(this.options.out, 'index.jsonl'), JSON.stringify(entry) + '\n');
+  }
+
+  summary(): { dir: string; saved: number; skipped: number } {
+    return { dir: this.options.out, saved: this.saved, skipped: this.skipped };
+  }
+}
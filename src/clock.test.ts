@@ -0,0 +1,25 @@
+import { describe, it, expect } from 'vitest';
+import { parseClockTime, wallTime, zoneOffset } from './clock.js';
+
+describe('clock', () => {
+  it('reads absolute times as given', () => {
+    expect(parseClockTime('2024-06-01T10:00:00Z')).toBe(Date.UTC(2024, 5, 1, 10));
+    expect(parseClockTime('2024-06-01T10:00:00+02:00', 'Asia/Tokyo')).toBe(Date.UTC(2024, 5, 1, 8));
+    expect(parseClockTime('1717236000000')).toBe(1717236000000);
+    expect(() => parseClockTime('next tuesday')).toThrow('Invalid time');
+  });
+
+  it('reads wall times in the emulated timezone', () => {
+    expect(parseClockTime('2024-06-01T09:00', 'Asia/Tokyo')).toBe(Date.UTC(2024, 5, 1, 0));
+    expect(parseClockTime('2024-06-01', 'America/New_York')).toBe(Date.UTC(2024, 5, 1, 4));
+    // Either side of the switch to summer time in Berlin
+    expect(parseClockTime('2024-03-31T01:30', 'Europe/Berlin')).toBe(Date.UTC(2024, 2, 31, 0, 30));
+    expect(parseClockTime('2024-03-31T04:00', 'Europe/Berlin')).toBe(Date.UTC(2024, 2, 31, 2));
+  });
+
+  it('reports offsets and wall times', () => {
+    expect(zoneOffset(Date.UTC(2024, 0, 1), 'Europe/Berlin')).toBe(3600000);
+    expect(zoneOffset(Date.UTC(2024, 6, 1), 'Europe/Berlin')).toBe(7200000);
+    expect(wallTime(Date.UTC(2024, 5, 1, 10), 'Europe/Berlin')).toBe('2024-06-01 12:00:00');
+  });
+});
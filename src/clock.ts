@@ -0,0 +1,65 @@
+/**
+ * Fake clock for time-dependent flows.
+ *
+ * `clock set` installs Playwright's fake timers in the session's pages, so
+ * `Date`, `performance.now()` and timers follow a clock the caller controls:
+ * paused at the given time until `clock advance` moves it, or ticking from
+ * it with `--tick`. `--timezone` also sets the browser's timezone through CDP
+ * Emulation, so local dates on the page agree with the time that was set.
+ */
+
+const WALL_TIME = /^\d{4}-\d{2}-\d{2}(T\d{2}:\d{2}(:\d{2}(\.\d+)?)?)?$/;
+
+/** Milliseconds a timezone is ahead of UTC at a given instant. */
+export function zoneOffset(time: number, timeZone: string): number {
+  const parts = Object.fromEntries(
+    new Intl.DateTimeFormat('en-US', {
+      timeZone,
+      hourCycle: 'h23',
+      year: 'numeric',
+      month: 'numeric',
+      day: 'numeric',
+      hour: 'numeric',
+      minute: 'numeric',
+      second: 'numeric',
+    })
+      .formatToParts(time)
+      .map((part) => [part.type, Number(part.value)])
+  );
+  const wall = Date.UTC(
+    parts.year,
+    parts.month - 1,
+    parts.day,
+    parts.hour,
+    parts.minute,
+    parts.second
+  );
+  return wall - Math.floor(time / 1000) * 1000;
+}
+
+/**
+ * Epoch milliseconds for `clock set`. Times with an offset (or `Z`) and epoch
+ * numbers are absolute; a wall time without one is read in `timeZone` when
+ * given, so `2024-06-01T09:00 --timezone Asia/Tokyo` is 9am in Tokyo.
+ */
+export function parseClockTime(value: string, timeZone?: string): number {
+  if (/^\d+$/.test(value)) return Number(value);
+  let time: number;
+  if (timeZone && WALL_TIME.test(value)) {
+    const asUtc = Date.parse(`${value.includes('T') ? value : `${value}T00:00`}Z`);
+    // Second pass lands on the right side of a DST change
+    time = asUtc - zoneOffset(asUtc, timeZone);
+    time = asUtc - zoneOffset(time, timeZone);
+  } else {
+    time = Date.parse(value);
+  }
+  if (Number.isNaN(time)) {
+    throw new Error(`Invalid time: ${value} (expected e.g. 2024-06-01T10:00:00Z)`);
+  }
+  return time;
+}
+
+/** `2024-06-01 12:00:00` in the given timezone */
+export function wallTime(time: number, timeZone: string): string {
+  return new Date(time).toLocaleString('sv-SE', { timeZone });
+}
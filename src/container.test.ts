@@ -0,0 +1,48 @@
+import { describe, it, expect } from 'vitest';
+import { MIN_SHM_BYTES, containerArgs, detectContainer, type ContainerProbe } from './container.js';
+
+function probe(
+  env: Record<string, string> = {},
+  files: Record<string, string> = {}
+): ContainerProbe {
+  return {
+    env,
+    exists: (path) => path in files,
+    read: (path) => files[path] ?? null,
+  };
+}
+
+describe('container', () => {
+  it('finds the runtime from marker files and the environment', () => {
+    expect(detectContainer(probe())).toBeNull();
+    expect(detectContainer(probe({}, { '/.dockerenv': '' }))).toBe('docker');
+    expect(detectContainer(probe({}, { '/run/.containerenv': '' }))).toBe('podman');
+    expect(detectContainer(probe({ KUBERNETES_SERVICE_HOST: '10.0.0.1' }))).toBe('kubernetes');
+    expect(detectContainer(probe({ container: 'lxc' }))).toBe('lxc');
+  });
+
+  it('finds the runtime from cgroups and mounts', () => {
+    const cgroupV1 = '12:memory:/kubepods/burstable/pod1234/abcd\n0::/\n';
+    expect(detectContainer(probe({}, { '/proc/1/cgroup': cgroupV1 }))).toBe('kubernetes');
+    const mountinfo =
+      '612 590 0:52 /var/lib/docker/containers/abcd/hosts /etc/hosts rw - ext4 /dev/sda1 rw\n';
+    expect(
+      detectContainer(probe({}, { '/proc/1/cgroup': '0::/\n', '/proc/self/mountinfo': mountinfo }))
+    ).toBe('docker');
+    expect(detectContainer(probe({}, { '/proc/1/cgroup': '0::/init.scope\n' }))).toBeNull();
+  });
+
+  it('can be forced on or off', () => {
+    expect(detectContainer(probe({ AGENT_BROWSER_CONTAINER: '1' }))).toBe('container');
+    expect(detectContainer(probe({ AGENT_BROWSER_CONTAINER: '0' }, { '/.dockerenv': '' }))).toBeNull();
+  });
+
+  it('moves shared memory to /tmp only when /dev/shm is small', () => {
+    expect(containerArgs(null, 0)).toEqual([]);
+    expect(containerArgs('docker', 64 * 1024 * 1024)).toEqual([
+      '--no-sandbox',
+      '--disable-dev-shm-usage',
+    ]);
+    expect(containerArgs('docker', MIN_SHM_BYTES)).toEqual(['--no-sandbox']);
+  });
+});
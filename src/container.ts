@@ -0,0 +1,90 @@
+/**
+ * Container self-detection.
+ *
+ * Chromium needs a couple of switches to run in a container: the sandbox
+ * can't be set up without user namespaces (and refuses to run as root), and
+ * Docker gives containers a 64 MB `/dev/shm`, which tabs quickly outgrow and
+ * then crash. When the daemon finds itself in a container it launches
+ * Chromium with `--no-sandbox`, plus `--disable-dev-shm-usage` (shared memory
+ * in /tmp) if `/dev/shm` is smaller than {@link MIN_SHM_BYTES}.
+ *
+ * Detection looks for the marker files Docker and Podman create, the
+ * Kubernetes service environment, the systemd `container` variable and
+ * container runtimes in `/proc/1/cgroup` (cgroup v1) or `/proc/self/mountinfo`
+ * (cgroup v2). `AGENT_BROWSER_CONTAINER=1` forces the preset, `=0` turns it off.
+ */
+
+import { existsSync, readFileSync, statfsSync } from 'node:fs';
+
+/** Below this `/dev/shm` is too small for Chromium and /tmp is used instead */
+export const MIN_SHM_BYTES = 512 * 1024 * 1024;
+
+/** Runtimes recognized in cgroup paths and mounts, most specific first */
+const RUNTIME_PATTERNS: [RegExp, string][] = [
+  [/kubepods/, 'kubernetes'],
+  [/libpod/, 'podman'],
+  [/\/docker[/-]/, 'docker'],
+  [/containerd/, 'containerd'],
+  [/\/lxc\//, 'lxc'],
+];
+
+export interface ContainerProbe {
+  env: NodeJS.ProcessEnv;
+  exists: (path: string) => boolean;
+  read: (path: string) => string | null;
+}
+
+const hostProbe: ContainerProbe = {
+  env: process.env,
+  exists: existsSync,
+  read: (path) => {
+    try {
+      return readFileSync(path, 'utf8');
+    } catch {
+      return null;
+    }
+  },
+};
+
+/** Name of the container runtime the process runs under, or null on a host */
+export function detectContainer(probe: ContainerProbe = hostProbe): string | null {
+  const forced = probe.env.AGENT_BROWSER_CONTAINER;
+  if (forced === '0') return null;
+
+  const detected = (() => {
+    if (probe.env.KUBERNETES_SERVICE_HOST) return 'kubernetes';
+    if (probe.exists('/run/.containerenv')) return 'podman';
+    if (probe.exists('/.dockerenv')) return 'docker';
+    if (probe.env.container) return probe.env.container;
+    for (const file of ['/proc/1/cgroup', '/proc/self/mountinfo']) {
+      const content = probe.read(file);
+      if (!content) continue;
+      const match = RUNTIME_PATTERNS.find(([pattern]) => pattern.test(content));
+      if (match) return match[1];
+    }
+    return null;
+  })();
+
+  return detected ?? (forced === '1' ? 'container' : null);
+}
+
+/** Size of `/dev/shm` in bytes, or null if it can't be read */
+export function shmSize(): number | null {
+  try {
+    const stats = statfsSync('/dev/shm');
+    return stats.blocks * stats.bsize;
+  } catch {
+    return null;
+  }
+}
+
+/** Chromium switches for running in a container; empty on a host */
+export function containerArgs(
+  container: string | null = detectContainer(),
+  shm: number | null = shmSize()
+): string[] {
+  if (!container) return [];
+  const args = ['--no-sandbox'];
+  if (shm === null || shm < MIN_SHM_BYTES) args.push('--disable-dev-shm-usage');
+  return args;
+}
@@ -1,13 +1,33 @@
 import * as net from 'net';
+import type * as http from 'http';
 import * as fs from 'fs';
 import * as path from 'path';
 import * as os from 'os';
 import { BrowserManager } from './browser.js';
 import { IOSManager } from './ios-manager.js';
-import { parseCommand, serializeResponse, errorResponse } from './protocol.js';
-import { executeCommand } from './actions.js';
+import {
+  validateCommand,
+  errorResponse,
+  successResponse,
+  type ParseResult,
+} from './protocol.js';
+import { WireReader, encodeMessage } from './wire.js';
+import { executeCommand, streamCdpEvents, streamMutations, streamPoll } from './actions.js';
 import { executeIOSCommand } from './ios-actions.js';
+import type { CancelData, Command, JobsData, Response } from './types.js';
 import { StreamServer } from './stream-server.js';
+import { Metrics, startMetricsServer } from './metrics.js';
+import { traceCommand, withSpan } from './otel.js';
+import { Scheduler, handleSchedule, isScheduleCommand } from './schedule.js';
+import { JobQueue, startJobServer } from './job-queue.js';
+import { BrowserPool, poolOptionsFromEnv } from './browser-pool.js';
+import { captureOptionsFromEnv } from './screencast.js';
+import { PROBE_TIMEOUT_MS, drainTimeout, startHealthServer } from './health.js';
+import { resolveExecutable } from './executable.js';
+import { InFlight, isCancelled } from './cancel.js';
+import { LaneScheduler } from './lanes.js';
+import { Transcript } from './transcript.js';
+import { AuditLog } from './audit-log.js';
 
 // Manager type - either desktop browser or iOS
 type Manager = BrowserManager | IOSManager;
@@ -194,6 +214,18 @@ export async function startDaemon(options?: {
   // Create appropriate manager
   const manager: Manager = isIOS ? new IOSManager() : new BrowserManager();
   let shuttingDown = false;
+  // Set by SIGTERM: new commands are refused while those in flight finish
+  let draining = false;
+  let inFlight = 0;
+  // Commands a `cancel` can stop
+  const running = new InFlight();
+  // Turns on the browser, with --max-concurrent or --action-rate
+  const lanes = new LaneScheduler();
+  const laneKey = () => {
+    const shared = manager instanceof BrowserManager ? manager.sharedBrowser() : null;
+    return shared ? `cdp:${shared}` : `session:${currentSession}`;
+  };
+  const laneStats = () => (lanes.enabled ? lanes.snapshot(laneKey()) : null);
 
   // Start stream server if port is specified (or use default if env var is set)
   // Note: Stream server only works with BrowserManager (desktop), not iOS
@@ -204,7 +236,7 @@ export async function startDaemon(options?: {
       : 0);
 
   if (streamPort > 0 && !isIOS && manager instanceof BrowserManager) {
-    streamServer = new StreamServer(manager, streamPort);
+    streamServer = new StreamServer(manager, streamPort, captureOptionsFromEnv());
     await streamServer.start();
 
     // Write stream port to file for clients to discover
@@ -212,39 +244,255 @@ export async function startDaemon(options?: {
     fs.writeFileSync(streamPortFile, streamPort.toString());
   }
 
+  // Serve Prometheus metrics if AGENT_BROWSER_METRICS_PORT is set
+  const metrics = new Metrics(currentSession);
+  const metricsPort = parseInt(process.env.AGENT_BROWSER_METRICS_PORT ?? '', 10);
+  let metricsServer: http.Server | null = null;
+  if (metricsPort > 0) {
+    metricsServer = await startMetricsServer(
+      metrics,
+      metricsPort,
+      () => manager.isLaunched(),
+      laneStats
+    );
+  }
+
+  // Recurring jobs saved for this session start counting down right away
+  const scheduler = new Scheduler(currentSession, isDaemonRunning);
+  scheduler.load();
+
+  // Accept scripts over HTTP if AGENT_BROWSER_JOBS_PORT is set, with warm
+  // browsers for them if AGENT_BROWSER_POOL_SIZE is too
+  const jobsPort = parseInt(process.env.AGENT_BROWSER_JOBS_PORT ?? '', 10);
+  const poolOptions = jobsPort > 0 ? poolOptionsFromEnv() : null;
+  const pool = poolOptions ? new BrowserPool(poolOptions) : null;
+  const jobQueue = JobQueue.forSession(currentSession, isDaemonRunning, pool);
+  let jobServer: http.Server | null = null;
+  if (jobsPort > 0) {
+    jobServer = await startJobServer(jobQueue, jobsPort);
+    pool?.start();
+  }
+
+  // Serve /healthz and /readyz if AGENT_BROWSER_HEALTH_PORT is set
+  const healthPort = parseInt(process.env.AGENT_BROWSER_HEALTH_PORT ?? '', 10);
+  let healthServer: http.Server | null = null;
+  if (healthPort > 0) {
+    healthServer = await startHealthServer(
+      {
+        browser: async () =>
+          manager instanceof BrowserManager
+            ? manager.browserState(PROBE_TIMEOUT_MS)
+            : manager.isLaunched()
+              ? 'running'
+              : 'not-launched',
+        draining: () => draining,
+        inFlight: () => inFlight,
+      },
+      healthPort
+    );
+  }
+
+  // Record what the agent does once --transcript turns it on
+  let transcript: Transcript | null = null;
+
+  // Keep a tamper-evident record once --audit-log or AGENT_BROWSER_AUDIT_LOG
+  // turns it on; there is no turning it off for the rest of the session
+  let auditLog: AuditLog | null = null;
+  const audit = (write: (log: AuditLog) => void) => {
+    if (!auditLog) return;
+    try {
+      write(auditLog);
+    } catch (err) {
+      console.error('Audit log error:', err);
+    }
+  };
+  const startAuditLog = () => {
+    auditLog ??= new AuditLog(currentSession);
+    if (manager instanceof BrowserManager) {
+      manager.setActivityListener((activity) => audit((log) => log.activity(activity)));
+    }
+  };
+  if (process.env.AGENT_BROWSER_AUDIT_LOG) {
+    try {
+      startAuditLog();
+    } catch (err) {
+      console.error(err instanceof Error ? err.message : err);
+      process.exit(1);
+    }
+  }
+
+  // The page as the agent saw it before a transcript step
+  const observePage = async (recorder: Transcript) => {
+    if (!(manager instanceof BrowserManager) || !manager.isLaunched()) {
+      return { url: null, screenshot: null };
+    }
+    const page = manager.getPage();
+    const file = recorder.nextScreenshot();
+    const screenshot = await page.screenshot({ path: file, timeout: 5000 }).then(
+      () => file,
+      () => null
+    );
+    return { url: page.url(), screenshot };
+  };
+
+  // Run a command, recording its latency and outcome and any browser launch
+  const execute = async (command: Command): Promise<Response> => {
+    const wasLaunched = manager.isLaunched();
+    const before = transcript?.isStep(command) ? await observePage(transcript) : null;
+    const started = performance.now();
+    const response =
+      isIOS && manager instanceof IOSManager
+        ? await executeIOSCommand(command, manager)
+        : await executeCommand(command, manager as BrowserManager);
+    const elapsed = performance.now() - started;
+    metrics.record(command.action, response, elapsed / 1000);
+    if (!wasLaunched && manager.isLaunched()) metrics.recordLaunch();
+    if (transcript && before) transcript.record(command, response, before, elapsed);
+    else transcript?.observe(command, response);
+    audit((log) => log.command(command, response));
+    return response;
+  };
+
+  // Launch with settings from the environment when a command arrives before `launch`
+  const autoLaunch = async (command: Command): Promise<void> => {
+    if (isIOS && manager instanceof IOSManager) {
+      // Auto-launch iOS Safari
+      // Check for device in command first (for reused daemons), then fall back to env vars
+      const cmd = command as { iosDevice?: string };
+      const iosDevice = cmd.iosDevice || process.env.AGENT_BROWSER_IOS_DEVICE;
+      await manager.launch({
+        device: iosDevice,
+        udid: process.env.AGENT_BROWSER_IOS_UDID,
+      });
+    } else if (manager instanceof BrowserManager) {
+      // Auto-launch desktop browser
+      const extensions = process.env.AGENT_BROWSER_EXTENSIONS
+        ? process.env.AGENT_BROWSER_EXTENSIONS.split(',')
+            .map((p) => p.trim())
+            .filter(Boolean)
+        : undefined;
+
+      // Parse args from env (comma or newline separated)
+      const argsEnv = process.env.AGENT_BROWSER_ARGS;
+      const args = argsEnv
+        ? argsEnv
+            .split(/[,\n]/)
+            .map((a) => a.trim())
+            .filter((a) => a.length > 0)
+        : undefined;
+
+      // Parse proxy from env
+      const proxyServer = process.env.AGENT_BROWSER_PROXY;
+      const proxyBypass = process.env.AGENT_BROWSER_PROXY_BYPASS;
+      const proxy = proxyServer
+        ? {
+            server: proxyServer,
+            ...(proxyBypass && { bypass: proxyBypass }),
+          }
+        : undefined;
+
+      const ignoreHTTPSErrors = process.env.AGENT_BROWSER_IGNORE_HTTPS_ERRORS === '1';
+      const list = (value: string | undefined) =>
+        value
+          ?.split(',')
+          .map((s) => s.trim())
+          .filter(Boolean);
+      const allowFileAccess = process.env.AGENT_BROWSER_ALLOW_FILE_ACCESS === '1';
+      const clientCert = process.env.AGENT_BROWSER_CLIENT_CERT;
+      const clientKey = process.env.AGENT_BROWSER_CLIENT_KEY;
+      const passphrase = process.env.AGENT_BROWSER_CLIENT_CERT_PASSPHRASE;
+      const clientCertificates = clientCert
+        ? (list(process.env.AGENT_BROWSER_CLIENT_CERT_ORIGIN) ?? []).map((origin) => ({
+            origin,
+            ...(clientKey ? { certPath: clientCert, keyPath: clientKey } : { pfxPath: clientCert }),
+            ...(passphrase && { passphrase }),
+          }))
+        : undefined;
+      await manager.launch({
+        id: 'auto',
+        action: 'launch' as const,
+        headless: process.env.AGENT_BROWSER_HEADED !== '1',
+        executablePath: process.env.AGENT_BROWSER_EXECUTABLE_PATH,
+        channel: process.env.AGENT_BROWSER_BROWSER,
+        extensions: extensions,
+        profile: process.env.AGENT_BROWSER_PROFILE,
+        storageState: process.env.AGENT_BROWSER_STATE,
+        args,
+        userAgent: process.env.AGENT_BROWSER_USER_AGENT,
+        proxy,
+        ignoreHTTPSErrors: ignoreHTTPSErrors,
+        allowFileAccess: allowFileAccess,
+        extraCa: list(process.env.AGENT_BROWSER_EXTRA_CA),
+        ignoreCertErrorsFor: list(process.env.AGENT_BROWSER_IGNORE_CERT_ERRORS_FOR),
+        clientCertificates,
+        authServerAllowlist: list(process.env.AGENT_BROWSER_AUTH_SERVER_ALLOWLIST),
+        authDelegateAllowlist: list(process.env.AGENT_BROWSER_AUTH_DELEGATE_ALLOWLIST),
+        stealth: process.env.AGENT_BROWSER_STEALTH === '1',
+        deterministic: process.env.AGENT_BROWSER_DETERMINISTIC === '1',
+        initScripts: list(process.env.AGENT_BROWSER_INIT_SCRIPT),
+      });
+    }
+  };
+
+  // Connections that came in over the TCP listener rather than the local socket
+  const remoteSockets = new WeakSet<net.Socket>();
+  const listenToken = process.env.AGENT_BROWSER_LISTEN_TOKEN || undefined;
+
   const server = net.createServer((socket) => {
-    let buffer = '';
+    const wire = new WireReader();
     let httpChecked = false;
+    // Answers go out in the format the client speaks
+    const send = (message: unknown) =>
+      socket.write(encodeMessage(wire.format ?? 'json', message));
 
     socket.on('data', async (data) => {
-      buffer += data.toString();
+      wire.push(data);
 
       // Security: Detect and reject HTTP requests to prevent cross-origin attacks.
       // Browsers using fetch() must send HTTP headers (e.g., "POST / HTTP/1.1"),
-      // while legitimate clients send raw JSON starting with "{".
+      // while legitimate clients send raw JSON starting with "{" or a binary frame.
       if (!httpChecked) {
         httpChecked = true;
-        const trimmed = buffer.trimStart();
+        const trimmed = wire.peek().trimStart();
         if (/^(GET|POST|PUT|DELETE|HEAD|OPTIONS|PATCH|CONNECT|TRACE)\s/i.test(trimmed)) {
           socket.destroy();
           return;
         }
       }
 
-      // Process complete lines
-      while (buffer.includes('\n')) {
-        const newlineIdx = buffer.indexOf('\n');
-        const line = buffer.substring(0, newlineIdx);
-        buffer = buffer.substring(newlineIdx + 1);
-
-        if (!line.trim()) continue;
-
+      // Process complete messages
+      for (let received = wire.next(); received; received = wire.next()) {
         try {
-          const parseResult = parseCommand(line);
+          const parseResult: ParseResult =
+            'error' in received
+              ? { success: false, error: received.error }
+              : validateCommand(received.value);
 
           if (!parseResult.success) {
-            const resp = errorResponse(parseResult.id ?? 'unknown', parseResult.error);
-            socket.write(serializeResponse(resp) + '\n');
+            const resp = errorResponse(
+              parseResult.id ?? 'unknown',
+              parseResult.error,
+              'E_INVALID_COMMAND'
+            );
+            send(resp);
+            continue;
+          }
+
+          // Commands over the TCP listener must carry its token, if it has one
+          if (
+            listenToken &&
+            remoteSockets.has(socket) &&
+            (received as { value: { token?: unknown } }).value.token !== listenToken
+          ) {
+            send(
+              errorResponse(parseResult.command.id, 'Missing or wrong token', 'E_INVALID_COMMAND')
+            );
+            continue;
+          }
+
+          if (draining) {
+            const resp = errorResponse(parseResult.command.id, 'The daemon is shutting down');
+            send(resp);
             continue;
           }
 
@@ -258,86 +506,144 @@ export async function startDaemon(options?: {
                 success: true as const,
                 data: { devices },
               };
-              socket.write(serializeResponse(response) + '\n');
+              send(response);
             } catch (err) {
               const message = err instanceof Error ? err.message : String(err);
-              socket.write(
-                serializeResponse(errorResponse(parseResult.command.id, message)) + '\n'
-              );
+              send(errorResponse(parseResult.command.id, message));
             }
             continue;
           }
 
           // Auto-launch if not already launched and this isn't a launch/close command
+          const command = parseResult.command;
+
+          // Schedules belong to the daemon rather than the browser, so nothing is launched
+          if (isScheduleCommand(command)) {
+            send(handleSchedule(command, scheduler));
+            continue;
+          }
+          if (command.action === 'jobs') {
+            const host = process.env.AGENT_BROWSER_JOBS_HOST || '127.0.0.1';
+            const data: JobsData = {
+              url: jobServer ? `http://${host}:${jobsPort}` : null,
+              concurrency: jobQueue.concurrency,
+              jobs: jobQueue.list(),
+              pool: pool?.stats() ?? null,
+            };
+            send(successResponse(command.id, data));
+            continue;
+          }
+          // Stops the commands awaited on other connections
+          if (command.action === 'cancel') {
+            const data: CancelData = { cancelled: running.cancel() };
+            if (data.cancelled.length > 0 && manager instanceof BrowserManager) {
+              await manager.settleAfterCancel(data.cancelled).catch(() => {});
+            }
+            send(successResponse(command.id, data));
+            continue;
+          }
+          if (command.action === 'lanes') {
+            const data = lanes.snapshot(laneKey());
+            send(successResponse(command.id, data));
+            continue;
+          }
+          if (command.action === 'session_options' && command.lanes) {
+            lanes.configure(command.lanes);
+          }
+          if (command.action === 'session_options' && command.transcript) {
+            transcript ??= new Transcript();
+          }
+          if (command.action === 'session_options' && command.auditLog) {
+            try {
+              startAuditLog();
+            } catch (err) {
+              const message = err instanceof Error ? err.message : String(err);
+              send(errorResponse(command.id, message, 'E_AUDIT_CHAIN_BROKEN'));
+              continue;
+            }
+          }
+          if (command.action === 'which') {
+            const data = resolveExecutable({
+              explicit: command.executablePath,
+              browser: command.channel ?? process.env.AGENT_BROWSER_BROWSER,
+            });
+            send(successResponse(command.id, data));
+            continue;
+          }
+
+          // cdp-listen writes events to this socket until it finishes or the client leaves
+          if (command.action === 'cdp_listen' && manager instanceof BrowserManager) {
+            if (!manager.isLaunched()) {
+              await autoLaunch(command);
+              metrics.recordLaunch();
+            }
+            const closed = new Promise<void>((resolve) => socket.once('close', () => resolve()));
+            const response = await streamCdpEvents(
+              command,
+              manager,
+              (event) => send({ id: command.id, event }),
+              closed
+            );
+            if (!socket.destroyed) send(response);
+            continue;
+          }
+
+          // watch and poll stream their observations the same way
           if (
-            !manager.isLaunched() &&
-            parseResult.command.action !== 'launch' &&
-            parseResult.command.action !== 'close'
+            (command.action === 'watch' || command.action === 'poll') &&
+            manager instanceof BrowserManager
           ) {
-            if (isIOS && manager instanceof IOSManager) {
-              // Auto-launch iOS Safari
-              // Check for device in command first (for reused daemons), then fall back to env vars
-              const cmd = parseResult.command as { iosDevice?: string };
-              const iosDevice = cmd.iosDevice || process.env.AGENT_BROWSER_IOS_DEVICE;
-              await manager.launch({
-                device: iosDevice,
-                udid: process.env.AGENT_BROWSER_IOS_UDID,
-              });
-            } else if (manager instanceof BrowserManager) {
-              // Auto-launch desktop browser
-              const extensions = process.env.AGENT_BROWSER_EXTENSIONS
-                ? process.env.AGENT_BROWSER_EXTENSIONS.split(',')
-                    .map((p) => p.trim())
-                    .filter(Boolean)
-                : undefined;
-
-              // Parse args from env (comma or newline separated)
-              const argsEnv = process.env.AGENT_BROWSER_ARGS;
-              const args = argsEnv
-                ? argsEnv
-                    .split(/[,\n]/)
-                    .map((a) => a.trim())
-                    .filter((a) => a.length > 0)
-                : undefined;
-
-              // Parse proxy from env
-              const proxyServer = process.env.AGENT_BROWSER_PROXY;
-              const proxyBypass = process.env.AGENT_BROWSER_PROXY_BYPASS;
-              const proxy = proxyServer
-                ? {
-                    server: proxyServer,
-                    ...(proxyBypass && { bypass: proxyBypass }),
-                  }
-                : undefined;
-
-              const ignoreHTTPSErrors = process.env.AGENT_BROWSER_IGNORE_HTTPS_ERRORS === '1';
-              const allowFileAccess = process.env.AGENT_BROWSER_ALLOW_FILE_ACCESS === '1';
-              await manager.launch({
-                id: 'auto',
-                action: 'launch' as const,
-                headless: process.env.AGENT_BROWSER_HEADED !== '1',
-                executablePath: process.env.AGENT_BROWSER_EXECUTABLE_PATH,
-                extensions: extensions,
-                profile: process.env.AGENT_BROWSER_PROFILE,
-                storageState: process.env.AGENT_BROWSER_STATE,
-                args,
-                userAgent: process.env.AGENT_BROWSER_USER_AGENT,
-                proxy,
-                ignoreHTTPSErrors: ignoreHTTPSErrors,
-                allowFileAccess: allowFileAccess,
-              });
+            if (!manager.isLaunched()) {
+              await autoLaunch(command);
+              metrics.recordLaunch();
             }
+            const closed = new Promise<void>((resolve) => socket.once('close', () => resolve()));
+            const emit = (event: unknown) =>
+              send({ id: command.id, event });
+            const response =
+              command.action === 'watch'
+                ? await streamMutations(command, manager, emit, closed)
+                : await streamPoll(command, manager, emit, closed);
+            if (!socket.destroyed) send(response);
+            continue;
           }
 
-          // Handle close command specially - shuts down daemon
-          if (parseResult.command.action === 'close') {
-            const response =
-              isIOS && manager instanceof IOSManager
-                ? await executeIOSCommand(parseResult.command, manager)
-                : await executeCommand(parseResult.command, manager as BrowserManager);
-            socket.write(serializeResponse(response) + '\n');
+          inFlight++;
+          const response = await running
+            .run(command, () =>
+              traceCommand(command, async () => {
+                if (
+                  !manager.isLaunched() &&
+                  command.action !== 'launch' &&
+                  command.action !== 'close'
+                ) {
+                  await withSpan('browser.launch', {}, () => autoLaunch(command));
+                  metrics.recordLaunch();
+                }
+                if (
+                  !lanes.enabled ||
+                  command.action === 'launch' ||
+                  command.action === 'close' ||
+                  command.action === 'session_options'
+                ) {
+                  return execute(command);
+                }
+                return lanes.run(laneKey(), () => execute(command), () => isCancelled(command));
+              })
+            )
+            .finally(() => inFlight--);
+          send(response);
+
+          // Between commands, recycle tabs over --max-tab-memory
+          if (inFlight === 0 && command.action !== 'close' && manager instanceof BrowserManager) {
+            const recycled = await manager.recycleOversizedTabs().catch(() => []);
+            metrics.recordRecycles(recycled.length);
+          }
 
-            if (!shuttingDown) {
+          // Handle close command specially - shuts down daemon, unless it has jobs to run
+          // or serves the job API or health endpoints
+          if (command.action === 'close') {
+            if (!shuttingDown && !draining && !scheduler.hasJobs() && !jobServer && !healthServer) {
               shuttingDown = true;
               setTimeout(() => {
                 server.close();
@@ -347,16 +653,9 @@ export async function startDaemon(options?: {
             }
             return;
           }
-
-          // Execute command with appropriate handler
-          const response =
-            isIOS && manager instanceof IOSManager
-              ? await executeIOSCommand(parseResult.command, manager)
-              : await executeCommand(parseResult.command, manager as BrowserManager);
-          socket.write(serializeResponse(response) + '\n');
         } catch (err) {
           const message = err instanceof Error ? err.message : String(err);
-          socket.write(serializeResponse(errorResponse('error', message)) + '\n');
+          send(errorResponse('error', message));
         }
       }
     });
@@ -393,6 +692,21 @@ export async function startDaemon(options?: {
     process.exit(1);
   });
 
+  // Also take commands over TCP if AGENT_BROWSER_LISTEN_PORT is set, for
+  // clients that can't reach the local socket, like the WASM client
+  const listenPort = parseInt(process.env.AGENT_BROWSER_LISTEN_PORT ?? '', 10);
+  let listenServer: net.Server | null = null;
+  if (listenPort > 0) {
+    listenServer = net.createServer((socket) => {
+      remoteSockets.add(socket);
+      server.emit('connection', socket);
+    });
+    listenServer.on('error', (err) => {
+      console.error('Listener error:', err);
+    });
+    listenServer.listen(listenPort, process.env.AGENT_BROWSER_LISTEN_HOST || '127.0.0.1');
+  }
+
   // Handle shutdown signals
   const shutdown = async () => {
     if (shuttingDown) return;
@@ -411,14 +725,32 @@ export async function startDaemon(options?: {
       }
     }
 
+    metricsServer?.close();
+    jobServer?.close();
+    healthServer?.close();
+    listenServer?.close();
+    jobQueue.stop();
+    pool?.stop();
     await manager.close();
     server.close();
     cleanupSocket();
     process.exit(0);
   };
 
+  // Kubernetes stops pods with SIGTERM: let commands and jobs in flight finish first
+  const drain = async () => {
+    if (shuttingDown || draining) return;
+    draining = true;
+    const idle = (async () => {
+      await jobQueue.drain();
+      while (inFlight > 0) await new Promise((resolve) => setTimeout(resolve, 100));
+    })();
+    await Promise.race([idle, new Promise((resolve) => setTimeout(resolve, drainTimeout()))]);
+    await shutdown();
+  };
+
   process.on('SIGINT', shutdown);
-  process.on('SIGTERM', shutdown);
+  process.on('SIGTERM', drain);
   process.on('SIGHUP', shutdown);
 
   // Handle unexpected errors - always cleanup
@@ -436,6 +768,7 @@ export async function startDaemon(options?: {
 
   // Cleanup on normal exit
   process.on('exit', () => {
+    pool?.stop();
     cleanupSocket();
   });
 
@@ -0,0 +1,37 @@
+import { describe, it, expect } from 'vitest';
+import { runInNewContext } from 'node:vm';
+import { DETERMINISTIC_CSS, DETERMINISTIC_SCRIPT } from './deterministic.js';
+
+/** Run the init script in a fresh document and draw a few random numbers */
+function loadDocument(): { random: number[]; styles: string[] } {
+  const styles: string[] = [];
+  const document = {
+    head: { appendChild: (el: { textContent: string }) => styles.push(el.textContent) },
+    documentElement: {},
+    createElement: () => ({ textContent: '' }),
+  };
+  const sandbox: Record<string, unknown> = { document };
+  sandbox.window = sandbox;
+  const random = runInNewContext(
+    `${DETERMINISTIC_SCRIPT}; [Math.random(), Math.random(), Math.random()]`,
+    sandbox
+  ) as number[];
+  return { random: Array.from(random), styles };
+}
+
+describe('deterministic', () => {
+  it('starts the same Math.random sequence in every document', () => {
+    const first = loadDocument();
+    const second = loadDocument();
+    expect(first.random).toEqual(second.random);
+    expect(new Set(first.random).size).toBe(3);
+    for (const value of first.random) {
+      expect(value).toBeGreaterThanOrEqual(0);
+      expect(value).toBeLessThan(1);
+    }
+  });
+
+  it('adds the stable-rendering stylesheet', () => {
+    expect(loadDocument().styles).toEqual([DETERMINISTIC_CSS]);
+  });
+});
@@ -0,0 +1,51 @@
+/**
+ * `--deterministic`: stable rendering for screenshots and visual diffs.
+ *
+ * Two runs of the same page rarely paint the same pixels: `Math.random`
+ * differs, animations and transitions are caught mid-frame, the text caret
+ * blinks and smooth scrolling leaves the viewport between positions. The init
+ * script below seeds `Math.random` with a fixed seed in every document and
+ * adds a stylesheet that jumps animations and transitions to their end and
+ * hides the caret. Pages are also told the user prefers reduced motion, and
+ * Chromium is launched without smooth scrolling. Pair it with `clock set` to
+ * pin `Date` as well.
+ */
+
+import { createRng } from './humanize.js';
+
+/** Every document starts the same sequence */
+export const DETERMINISTIC_SEED = 0x5eed;
+
+/** Chromium switch that makes scrolling jump instead of animate */
+export const DETERMINISTIC_ARGS = ['--disable-smooth-scrolling'];
+
+export const DETERMINISTIC_CSS = `*, *::before, *::after {
+  animation-delay: 0s !important;
+  animation-duration: 0s !important;
+  animation-iteration-count: 1 !important;
+  transition-delay: 0s !important;
+  transition-duration: 0s !important;
+  caret-color: transparent !important;
+  scroll-behavior: auto !important;
+}`;
+
+export const DETERMINISTIC_SCRIPT = `(() => {
+  if (window.__agentBrowserDeterministic) return;
+  Object.defineProperty(window, '__agentBrowserDeterministic', { value: true });
+  const createRng = ${createRng.toString()};
+  Math.random = createRng(${DETERMINISTIC_SEED});
+
+  // Init scripts can run before the parser creates <html>
+  const style = document.createElement('style');
+  style.textContent = ${JSON.stringify(DETERMINISTIC_CSS)};
+  const attach = () => (document.head || document.documentElement).appendChild(style);
+  if (document.documentElement) {
+    attach();
+  } else {
+    new MutationObserver((_, observer) => {
+      if (!document.documentElement) return;
+      observer.disconnect();
+      attach();
+    }).observe(document, { childList: true });
+  }
+})()`;
@@ -0,0 +1,149 @@
+/**
+ * DOM mutation watching.
+ *
+ * `watch --selector` puts a MutationObserver in the current page and reports
+ * changes inside the matching elements, or elements matching it being added
+ * and removed, as they happen. Agents can follow a live dashboard or chat feed
+ * this way instead of polling snapshots. The observer is put back after each
+ * navigation for as long as the watch lasts.
+ */
+
+import type { BrowserContext, Page } from 'playwright-core';
+import type { DomMutationEvent, DomMutationType } from './types.js';
+
+const BINDING = '__agentBrowserMutations';
+
+interface ObserveArgs {
+  binding: string;
+  id: number;
+  selector: string;
+  types: DomMutationType[];
+}
+
+type PageMutation = Omit<DomMutationEvent, 'url' | 'timestamp'>;
+
+/**
+ * Runs in the page. Each batch of records goes to the binding, whose answer
+ * says whether the watch is still on; the observer disconnects once it isn't.
+ */
+function observe({ binding, id, selector, types }: ObserveArgs): void {
+  // Throws here, rather than in the observer, for an invalid selector
+  document.querySelector(selector);
+  const report = (window as unknown as Record<string, (...args: unknown[]) => Promise<boolean>>)[
+    binding
+  ];
+  // Enough to tell messages apart without copying whole subtrees
+  const text = (node: Node) => (node.textContent ?? '').replace(/\s+/g, ' ').trim().slice(0, 200);
+  const describe = (node: Node): string => {
+    if (!(node instanceof Element)) return node.nodeName.toLowerCase();
+    const hash = node.id ? `#${node.id}` : '';
+    const classes = Array.from(node.classList, (c) => `.${c}`)
+      .slice(0, 3)
+      .join('');
+    return `${node.tagName.toLowerCase()}${hash}${classes}`;
+  };
+  const summarize = (list: NodeList) =>
+    Array.from(list)
+      .filter((node) => node instanceof Element || text(node))
+      .map((node) => ({ node: describe(node), text: text(node) }));
+  const matches = (node: Node) =>
+    node instanceof Element && (node.matches(selector) || node.querySelector(selector) !== null);
+
+  const observer = new MutationObserver((records) => {
+    const events: PageMutation[] = [];
+    for (const record of records) {
+      const target =
+        record.target instanceof Element ? record.target : record.target.parentElement;
+      const inside = target?.closest(selector);
+      const changed =
+        record.type === 'childList' &&
+        [...Array.from(record.addedNodes), ...Array.from(record.removedNodes)].some(matches);
+      if (!inside && !changed) continue;
+      const event: PageMutation = { type: record.type, target: describe(target ?? record.target) };
+      if (record.type === 'attributes' && target && record.attributeName) {
+        event.attribute = record.attributeName;
+        event.oldValue = record.oldValue;
+        event.value = target.getAttribute(record.attributeName);
+      } else if (record.type === 'characterData') {
+        event.oldValue = record.oldValue;
+        event.value = record.target.textContent;
+      } else {
+        event.added = summarize(record.addedNodes);
+        event.removed = summarize(record.removedNodes);
+      }
+      events.push(event);
+    }
+    if (events.length === 0) return;
+    report(id, events).then(
+      (watching) => {
+        if (!watching) observer.disconnect();
+      },
+      () => observer.disconnect()
+    );
+  });
+  observer.observe(document, {
+    subtree: true,
+    childList: types.includes('childList'),
+    attributes: types.includes('attributes'),
+    attributeOldValue: types.includes('attributes'),
+    characterData: types.includes('characterData'),
+    characterDataOldValue: types.includes('characterData'),
+  });
+}
+
+export class DomWatcher {
+  private bound = new WeakSet<BrowserContext>();
+  private listeners = new Map<number, (event: PageMutation, page: Page) => void>();
+  private nextId = 1;
+
+  /**
+   * Report mutations of `selector` in `page` to `emit` until the returned
+   * function is called. Rejects if the selector isn't valid CSS.
+   */
+  async watch(
+    page: Page,
+    selector: string,
+    types: DomMutationType[],
+    emit: (event: DomMutationEvent) => void
+  ): Promise<() => void> {
+    const context = page.context();
+    // Bindings can't be removed, so each context gets one for its lifetime
+    if (!this.bound.has(context)) {
+      await context.exposeBinding(
+        BINDING,
+        ({ page: source }, id: number, events: PageMutation[]) => {
+          const listener = this.listeners.get(id);
+          if (!listener) return false;
+          for (const event of events) listener(event, source);
+          return true;
+        }
+      );
+      this.bound.add(context);
+    }
+
+    const id = this.nextId++;
+    const args: ObserveArgs = { binding: BINDING, id, selector, types };
+    this.listeners.set(id, (event, source) => {
+      if (source === page) emit({ ...event, url: page.url(), timestamp: Date.now() });
+    });
+    const reinstall = () => {
+      page.evaluate(observe, args).catch(() => {
+        // Navigated away again before the page was ready
+      });
+    };
+    try {
+      await page.evaluate(observe, args);
+    } catch (err) {
+      this.listeners.delete(id);
+      const message = (err as Error).message;
+      throw new Error(
+        /not a valid selector/i.test(message) ? `Invalid CSS selector: ${selector}` : message
+      );
+    }
+    page.on('domcontentloaded', reinstall);
+    return () => {
+      this.listeners.delete(id);
+      page.off('domcontentloaded', reinstall);
+    };
+  }
+}
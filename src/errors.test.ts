@@ -0,0 +1,52 @@
+import { describe, it, expect } from 'vitest';
+import { classifyError } from './errors.js';
+import { toAIFriendlyError } from './actions.js';
+
+describe('classifyError', () => {
+  it('should classify rewritten locator errors', () => {
+    const notFound = toAIFriendlyError(
+      new Error("locator.click: waiting for locator('#missing') to be visible"),
+      '#missing'
+    );
+    expect(classifyError(notFound.message, 'click')).toBe('E_SELECTOR_NOT_FOUND');
+
+    const ambiguous = toAIFriendlyError(
+      new Error('strict mode violation: locator resolved to 3 elements'),
+      'button'
+    );
+    expect(classifyError(ambiguous.message, 'click')).toBe('E_SELECTOR_AMBIGUOUS');
+
+    const blocked = toAIFriendlyError(
+      new Error('Timeout 5000ms exceeded. <div id="modal"> intercepts pointer events'),
+      '@e2'
+    );
+    expect(classifyError(blocked.message, 'click')).toBe('E_ELEMENT_NOT_INTERACTABLE');
+  });
+
+  it('should separate navigation timeouts from action timeouts', () => {
+    expect(classifyError('page.goto: Timeout 30000ms exceeded.', 'navigate')).toBe('E_NAV_TIMEOUT');
+    expect(classifyError('Action on "@e1" timed out.', 'click')).toBe('E_TIMEOUT');
+    expect(classifyError('page.goto: net::ERR_NAME_NOT_RESOLVED', 'navigate')).toBe('E_NAV_FAILED');
+  });
+
+  it('should detect a crashed or closed browser', () => {
+    expect(classifyError('Target page, context or browser has been closed', 'click')).toBe(
+      'E_BROWSER_CRASHED'
+    );
+    expect(classifyError('Page crashed', 'snapshot')).toBe('E_BROWSER_CRASHED');
+  });
+
+  it('should attribute any failure of a download action to the download', () => {
+    expect(classifyError('Timeout 30000ms exceeded.', 'waitfordownload')).toBe('E_DOWNLOAD_FAILED');
+  });
+
+  it('should reject unknown eval targets as invalid commands', () => {
+    expect(classifyError('Unknown target: w9. Run: agent-browser targets', 'evaluate')).toBe(
+      'E_INVALID_COMMAND'
+    );
+  });
+
+  it('should fall back to E_UNKNOWN', () => {
+    expect(classifyError('Something odd happened')).toBe('E_UNKNOWN');
+  });
+});
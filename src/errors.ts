@@ -0,0 +1,71 @@
+/**
+ * Stable, machine-readable error codes.
+ *
+ * Error responses carry a `code` next to the human-readable `error` message so
+ * orchestrating agents can branch on the kind of failure. Codes are part of the
+ * CLI contract: add new ones freely, but never rename or repurpose existing ones.
+ * The CLI maps each code to a distinct process exit code (cli/src/errors.rs).
+ */
+
+export type ErrorCode =
+  | 'E_INVALID_COMMAND'
+  | 'E_SELECTOR_NOT_FOUND'
+  | 'E_SELECTOR_AMBIGUOUS'
+  | 'E_ELEMENT_NOT_INTERACTABLE'
+  | 'E_TIMEOUT'
+  | 'E_NAV_TIMEOUT'
+  | 'E_NAV_FAILED'
+  | 'E_BROWSER_CRASHED'
+  | 'E_DOWNLOAD_FAILED'
+  | 'E_UNSUPPORTED'
+  | 'E_CANCELLED'
+  | 'E_READ_ONLY'
+  | 'E_GUARDRAIL'
+  | 'E_AUDIT_CHAIN_BROKEN'
+  | 'E_UNKNOWN';
+
+const DOWNLOAD_ACTIONS = new Set(['download', 'waitfordownload']);
+const NAVIGATION_ACTIONS = new Set(['navigate', 'back', 'forward', 'reload']);
+
+/**
+ * Derive an error code from an error message. Matches both raw Playwright
+ * messages and the rewritten ones produced by toAIFriendlyError.
+ */
+export function classifyError(message: string, action?: string): ErrorCode {
+  if (
+    /Target (page, context or browser )?(has been )?closed|Browser has been closed|browser has disconnected|crashed/i.test(
+      message
+    )
+  ) {
+    return 'E_BROWSER_CRASHED';
+  }
+  if (action && DOWNLOAD_ACTIONS.has(action)) {
+    return 'E_DOWNLOAD_FAILED';
+  }
+  if (/net::ERR_|NS_ERROR_|Cannot navigate to invalid URL|Blocked by robots\.txt/.test(message)) {
+    return 'E_NAV_FAILED';
+  }
+  const isTimeout = /Timeout .*exceeded|timed out/i.test(message);
+  if (isTimeout && ((action && NAVIGATION_ACTIONS.has(action)) || /page\.goto|navigation/i.test(message))) {
+    return 'E_NAV_TIMEOUT';
+  }
+  if (/matched \d+ elements|strict mode violation/.test(message)) {
+    return 'E_SELECTOR_AMBIGUOUS';
+  }
+  if (/blocked by another element|intercepts pointer events|is not visible|not enabled|not editable/i.test(message)) {
+    return 'E_ELEMENT_NOT_INTERACTABLE';
+  }
+  if (/not found|Unknown ref|No element/i.test(message)) {
+    return 'E_SELECTOR_NOT_FOUND';
+  }
+  if (isTimeout) {
+    return 'E_TIMEOUT';
+  }
+  if (/not supported|unsupported/i.test(message)) {
+    return 'E_UNSUPPORTED';
+  }
+  if (/^Unknown (action|target)|Validation error|Invalid JSON/i.test(message)) {
+    return 'E_INVALID_COMMAND';
+  }
+  return 'E_UNKNOWN';
+}
@@ -0,0 +1,147 @@
+import { describe, it, expect } from 'vitest';
+import { browserOf, parseRegQuery, resolveExecutable } from './executable.js';
+
+describe('resolveExecutable', () => {
+  const windows = {
+    platform: 'win32' as const,
+    home: 'C:\\Users\\ada',
+    env: { LOCALAPPDATA: 'C:\\Users\\ada\\AppData\\Local', ProgramFiles: 'C:\\Program Files' },
+  };
+  const linux = { platform: 'linux' as const, home: '/home/ada', env: { PATH: '/usr/bin' } };
+
+  it('reads the App Paths registry entry', () => {
+    const output =
+      '\r\nHKEY_LOCAL_MACHINE\\Software\\Microsoft\\Windows\\CurrentVersion\\App Paths\\BrowserOS.exe\r\n' +
+      '    (Default)    REG_SZ    "C:\\Program Files\\BrowserOS\\Application\\BrowserOS.exe"\r\n';
+    expect(parseRegQuery(output)).toBe('C:\\Program Files\\BrowserOS\\Application\\BrowserOS.exe');
+    expect(parseRegQuery('ERROR: The system was unable to find the specified registry key')).toBe(
+      null
+    );
+  });
+
+  it('prefers the registry, then per-user and machine-wide installs on Windows', () => {
+    const registered = 'D:\\Apps\\BrowserOS\\BrowserOS.exe';
+    const fromRegistry = resolveExecutable({
+      ...windows,
+      exists: (file) => file === registered,
+      readRegistry: (key) =>
+        key.startsWith('HKLM') && key.endsWith('BrowserOS.exe') ? registered : null,
+    });
+    expect(fromRegistry).toMatchObject({
+      path: registered,
+      browser: 'browseros',
+      source: 'BrowserOS: registry (HKLM App Paths)',
+    });
+
+    const perUser = 'C:\\Users\\ada\\AppData\\Local\\BrowserOS\\Application\\BrowserOS.exe';
+    const fromLocalAppData = resolveExecutable({
+      ...windows,
+      browser: 'browseros',
+      exists: (file) => file === perUser || file.startsWith('C:\\Program Files'),
+      readRegistry: () => null,
+    });
+    expect(fromLocalAppData).toMatchObject({ path: perUser, source: 'BrowserOS: LOCALAPPDATA' });
+    expect(fromLocalAppData.candidates.map((c) => c.source)).toEqual([
+      'BrowserOS: LOCALAPPDATA',
+      'BrowserOS: LOCALAPPDATA',
+      'BrowserOS: Program Files',
+      'BrowserOS: Program Files',
+      'BrowserOS: agent-browser install',
+    ]);
+  });
+
+  it('falls back to the system Chrome when BrowserOS is not installed', () => {
+    const resolved = resolveExecutable({
+      ...linux,
+      exists: (file) => file === '/usr/bin/google-chrome',
+    });
+    expect(resolved).toMatchObject({
+      path: '/usr/bin/google-chrome',
+      browser: 'chrome',
+      source: 'Chrome: PATH',
+    });
+  });
+
+  it('only looks for the browser --browser names', () => {
+    const resolved = resolveExecutable({
+      ...linux,
+      browser: 'edge',
+      exists: (file) =>
+        file === '/usr/bin/google-chrome' || file === '/opt/microsoft/msedge/msedge',
+    });
+    expect(resolved).toMatchObject({ path: '/opt/microsoft/msedge/msedge', browser: 'edge' });
+    expect(new Set(resolved.candidates.map((c) => c.browser))).toEqual(new Set(['edge']));
+
+    const custom = resolveExecutable({ ...linux, browser: 'path:/opt/thorium/thorium' });
+    expect(custom).toMatchObject({ path: '/opt/thorium/thorium', source: '--browser path:' });
+    expect(custom.candidates).toHaveLength(1);
+
+    expect(() => resolveExecutable({ ...linux, browser: 'firefox' })).toThrow(/Unknown browser/);
+    expect(() => resolveExecutable({ ...linux, browser: 'path:' })).toThrow(/needs an executable/);
+  });
+
+  it('prefers the newest extracted AppImage on Linux', () => {
+    const versions = '/home/ada/.browseros/versions';
+    const resolved = resolveExecutable({
+      ...linux,
+      browser: 'browseros',
+      exists: () => true,
+      readDir: (dir) => (dir === versions ? ['0.38.0.1', '0.39.0.3'] : []),
+    });
+    expect(resolved).toMatchObject({
+      path: `${versions}/0.39.0.3/squashfs-root/AppRun`,
+      browser: 'browseros',
+      source: 'BrowserOS: extracted AppImage',
+    });
+    expect(resolved.candidates[1].path).toBe(`${versions}/0.38.0.1/squashfs-root/AppRun`);
+  });
+
+  it('uses an explicit path even if it does not exist', () => {
+    const resolved = resolveExecutable({
+      explicit: '/opt/missing/BrowserOS',
+      platform: 'linux',
+      home: '/home/ada',
+      env: { AGENT_BROWSER_EXECUTABLE_PATH: '/opt/other/BrowserOS' },
+      exists: (file) => file !== '/opt/missing/BrowserOS',
+    });
+    expect(resolved).toMatchObject({
+      path: '/opt/missing/BrowserOS',
+      browser: 'browseros',
+      source: '--executable-path',
+    });
+    expect(resolved.candidates[0].exists).toBe(false);
+  });
+
+  it('falls back to the bundled Chromium when nothing is installed', () => {
+    const resolved = resolveExecutable({
+      ...linux,
+      env: {},
+      exists: () => false,
+      readDir: () => [],
+    });
+    expect(resolved.path).toBeNull();
+    expect(resolved.candidates.map((c) => c.path)).toEqual([
+      '/home/ada/.browseros/bin/BrowserOS',
+      '/home/ada/.browseros/bin/BrowserOS.AppImage',
+      '/opt/google/chrome/chrome',
+      'google-chrome-stable',
+      'google-chrome',
+      '/opt/microsoft/msedge/msedge',
+      'microsoft-edge-stable',
+      'microsoft-edge',
+      'chromium',
+      'chromium-browser',
+    ]);
+  });
+});
+
+describe('browserOf', () => {
+  it('recognises browsers by executable name', () => {
+    expect(browserOf('C:\\Program Files\\Microsoft\\Edge\\Application\\msedge.exe')).toBe('edge');
+    expect(browserOf('/Applications/Google Chrome.app/Contents/MacOS/Google Chrome')).toBe(
+      'chrome'
+    );
+    expect(browserOf('/usr/bin/chromium-browser')).toBe('chromium');
+    expect(browserOf('/opt/thorium/thorium')).toBeNull();
+  });
+});
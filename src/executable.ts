@@ -0,0 +1,238 @@
+/**
+ * Where the browser executable is.
+ *
+ * An explicit path (`--executable-path`, `AGENT_BROWSER_EXECUTABLE_PATH`)
+ * always wins. Otherwise `--browser` picks a Chromium-based browser: BrowserOS,
+ * Chrome, Edge, Chromium, or `path:<exe>` for any other. Without it BrowserOS
+ * is preferred and the system's Chrome, Edge and Chromium are tried after it,
+ * so the CLI works before BrowserOS is downloaded.
+ *
+ * Each browser is looked for in its standard install locations: on Windows
+ * the `App Paths` entries installers write to the registry (per-user, then
+ * machine-wide), then `%LOCALAPPDATA%` and Program Files; on macOS
+ * `/Applications`; on Linux the package install directories and `PATH`.
+ * BrowserOS is also looked for where `agent-browser install` puts it, an
+ * extracted AppImage (`install --extract-appimage`) first. Without
+ * any of them the launcher falls back to Playwright's bundled Chromium.
+ * `agent-browser which` prints this chain.
+ */
+
+import { execFileSync } from 'node:child_process';
+import { existsSync, readdirSync } from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import type { BrowserName, ExecutableCandidate, WhichData } from './types.js';
+
+export const BROWSERS: BrowserName[] = ['browseros', 'chrome', 'edge', 'chromium'];
+
+/** Switches each browser needs on top of Playwright's defaults */
+export const BROWSER_ARGS: Record<BrowserName, string[]> = {
+  // BrowserOS starts its own agent server unless told not to
+  browseros: ['--disable-browseros-server'],
+  // Branded Chrome asks EU users to pick a search engine and offers sign-in sync
+  chrome: ['--disable-search-engine-choice-screen', '--disable-sync'],
+  edge: ['--disable-sync'],
+  chromium: [],
+};
+
+const LABELS: Record<BrowserName, string> = {
+  browseros: 'BrowserOS',
+  chrome: 'Chrome',
+  edge: 'Edge',
+  chromium: 'Chromium',
+};
+
+interface Install {
+  /** Executable name under App Paths in the Windows registry */
+  appPath?: string;
+  /** Directories below %LOCALAPPDATA% and Program Files */
+  windows: string[];
+  mac: string[];
+  linux: string[];
+  /** Commands looked up on PATH (Linux) */
+  commands: string[];
+}
+
+const INSTALLS: Record<BrowserName, Install> = {
+  browseros: {
+    appPath: 'BrowserOS.exe',
+    windows: ['BrowserOS\\Application\\BrowserOS.exe', 'BrowserOS\\BrowserOS.exe'],
+    mac: ['/Applications/BrowserOS.app/Contents/MacOS/BrowserOS'],
+    linux: [],
+    commands: [],
+  },
+  chrome: {
+    appPath: 'chrome.exe',
+    windows: ['Google\\Chrome\\Application\\chrome.exe'],
+    mac: ['/Applications/Google Chrome.app/Contents/MacOS/Google Chrome'],
+    linux: ['/opt/google/chrome/chrome'],
+    commands: ['google-chrome-stable', 'google-chrome'],
+  },
+  edge: {
+    appPath: 'msedge.exe',
+    windows: ['Microsoft\\Edge\\Application\\msedge.exe'],
+    mac: ['/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge'],
+    linux: ['/opt/microsoft/msedge/msedge'],
+    commands: ['microsoft-edge-stable', 'microsoft-edge'],
+  },
+  chromium: {
+    windows: ['Chromium\\Application\\chrome.exe'],
+    mac: ['/Applications/Chromium.app/Contents/MacOS/Chromium'],
+    linux: [],
+    commands: ['chromium', 'chromium-browser'],
+  },
+};
+
+export interface ResolveOptions {
+  /** `--browser`: a name from {@link BROWSERS} or `path:<exe>` */
+  browser?: string;
+  /** `--executable-path` of this launch */
+  explicit?: string;
+  platform?: NodeJS.Platform;
+  env?: NodeJS.ProcessEnv;
+  home?: string;
+  exists?: (file: string) => boolean;
+  /** Entries of a directory, or [] if it can't be read */
+  readDir?: (dir: string) => string[];
+  /** Default value of a registry key, or null */
+  readRegistry?: (key: string) => string | null;
+}
+
+/** The path in `reg query <key> /ve` output, e.g. `(Default)    REG_SZ    C:\...` */
+export function parseRegQuery(output: string): string | null {
+  const match = output.match(/^\s*\(Default\)\s+REG_(?:EXPAND_)?SZ\s+(.+?)\s*$/m);
+  return match ? match[1].replace(/^"(.*)"$/, '$1') : null;
+}
+
+function readDir(dir: string): string[] {
+  try {
+    return readdirSync(dir);
+  } catch {
+    return [];
+  }
+}
+
+function readRegistry(key: string): string | null {
+  try {
+    const output = execFileSync('reg', ['query', key, '/ve'], {
+      encoding: 'utf8',
+      stdio: ['ignore', 'pipe', 'ignore'],
+      windowsHide: true,
+    });
+    return parseRegQuery(output);
+  } catch {
+    return null;
+  }
+}
+
+/** Which browser an explicit path most likely is, for its switches */
+export function browserOf(file: string): BrowserName | null {
+  const name = path.basename(file.replace(/\\/g, '/')).toLowerCase();
+  if (name.includes('browseros')) return 'browseros';
+  if (name.includes('msedge') || name.includes('microsoft-edge')) return 'edge';
+  if (name.includes('chromium')) return 'chromium';
+  if (name.includes('chrome')) return 'chrome';
+  return null;
+}
+
+/** Check `--browser` and return the browser it names, or the path it gives */
+export function parseBrowserOption(browser: string): BrowserName | { path: string } {
+  if (browser.startsWith('path:')) {
+    const file = browser.slice('path:'.length);
+    if (!file) throw new Error('--browser path: needs an executable, e.g. path:/opt/chrome/chrome');
+    return { path: file };
+  }
+  if (!(BROWSERS as string[]).includes(browser)) {
+    throw new Error(
+      `Unknown browser: ${browser} (expected ${BROWSERS.join(', ')}, or path:<executable>)`
+    );
+  }
+  return browser as BrowserName;
+}
+
+/** Every place the executable is looked for, in order, and which of them exist */
+export function resolveExecutable(options: ResolveOptions = {}): WhichData {
+  const platform = options.platform ?? process.platform;
+  const env = options.env ?? process.env;
+  const home = options.home ?? os.homedir();
+  const exists = options.exists ?? existsSync;
+  const registry = options.readRegistry ?? readRegistry;
+  const list = options.readDir ?? readDir;
+  const selected = options.browser ? parseBrowserOption(options.browser) : null;
+
+  const candidates: ExecutableCandidate[] = [];
+  const add = (browser: BrowserName | null, source: string, file: string | null | undefined) => {
+    if (file) candidates.push({ browser, source, path: file, exists: exists(file) });
+  };
+
+  add(null, '--executable-path', options.explicit);
+  add(null, 'AGENT_BROWSER_EXECUTABLE_PATH', env.AGENT_BROWSER_EXECUTABLE_PATH);
+  if (selected && typeof selected === 'object') add(null, '--browser path:', selected.path);
+  for (const candidate of candidates) candidate.browser = browserOf(candidate.path);
+  // An explicit path is used even if it's wrong, so the launch error names it
+  const explicit = candidates[0];
+
+  const browsers = typeof selected === 'string' ? [selected] : selected ? [] : BROWSERS;
+  for (const browser of browsers) {
+    const install = INSTALLS[browser];
+    const label = LABELS[browser];
+    if (platform === 'win32') {
+      const win = path.win32;
+      if (install.appPath) {
+        const key = `Software\\Microsoft\\Windows\\CurrentVersion\\App Paths\\${install.appPath}`;
+        add(browser, `${label}: registry (HKCU App Paths)`, registry(`HKCU\\${key}`));
+        add(browser, `${label}: registry (HKLM App Paths)`, registry(`HKLM\\${key}`));
+      }
+      for (const [source, dir] of [
+        ['LOCALAPPDATA', env.LOCALAPPDATA],
+        ['Program Files', env.ProgramFiles ?? 'C:\\Program Files'],
+        ['Program Files (x86)', env['ProgramFiles(x86)']],
+      ] as const) {
+        if (!dir) continue;
+        for (const file of install.windows) add(browser, `${label}: ${source}`, win.join(dir, file));
+      }
+      if (browser === 'browseros') {
+        add(browser, `${label}: agent-browser install`, win.join(home, '.browseros', 'BrowserOS.exe'));
+      }
+    } else if (platform === 'darwin') {
+      for (const file of install.mac) {
+        add(browser, `${label}: /Applications`, file);
+        add(browser, `${label}: ~/Applications`, path.join(home, file));
+      }
+      if (browser === 'browseros') {
+        add(
+          browser,
+          `${label}: agent-browser install`,
+          path.join(home, '.browseros', 'BrowserOS.app', 'Contents', 'MacOS', 'BrowserOS')
+        );
+      }
+    } else {
+      if (browser === 'browseros') {
+        // `install --extract-appimage` unpacks here, for systems without FUSE
+        const versions = path.join(home, '.browseros', 'versions');
+        for (const version of list(versions).sort().reverse()) {
+          const appRun = path.join(versions, version, 'squashfs-root', 'AppRun');
+          add(browser, `${label}: extracted AppImage`, appRun);
+        }
+        const bin = path.join(home, '.browseros', 'bin');
+        add(browser, `${label}: agent-browser install`, path.join(bin, 'BrowserOS'));
+        add(browser, `${label}: agent-browser install`, path.join(bin, 'BrowserOS.AppImage'));
+      }
+      for (const file of install.linux) add(browser, label, file);
+      for (const command of install.commands) {
+        const dirs = (env.PATH ?? '').split(path.delimiter).filter(Boolean);
+        const onPath = dirs.map((dir) => path.join(dir, command)).find(exists);
+        // Not on PATH shows as the bare command
+        candidates.push({ browser, source: `${label}: PATH`, path: onPath ?? command, exists: !!onPath });
+      }
+    }
+  }
+
+  const found = explicit ?? candidates.find((candidate) => candidate.exists);
+  return {
+    path: found?.path ?? null,
+    browser: found?.browser ?? null,
+    source: found?.source ?? null,
+    candidates,
+  };
+}
@@ -0,0 +1,37 @@
+import { describe, it, expect } from 'vitest';
+import { dataUrl, inlineCssUrls } from './freeze.js';
+
+describe('freeze', () => {
+  it('rewrites css urls against the stylesheet', () => {
+    const seen: string[] = [];
+    const css = inlineCssUrls(
+      `@font-face { src: url('../fonts/a.woff2') format('woff2'); }
+       .hero { background: url( img/bg.png ) , url("data:image/gif;base64,R0lG"); }
+       .icon { filter: url(#shadow); }`,
+      'https://a.com/css/site.css',
+      (ref, base) => {
+        seen.push(ref);
+        if (ref.startsWith('data:') || ref.startsWith('#')) return ref;
+        return new URL(ref, base).href;
+      }
+    );
+    expect(seen).toEqual([
+      '../fonts/a.woff2',
+      'img/bg.png',
+      'data:image/gif;base64,R0lG',
+      '#shadow',
+    ]);
+    expect(css).toContain(`url("https://a.com/fonts/a.woff2") format('woff2')`);
+    expect(css).toContain(
+      'url("https://a.com/css/img/bg.png") , url("data:image/gif;base64,R0lG")'
+    );
+    expect(css).toContain('url("#shadow")');
+  });
+
+  it('builds data urls', () => {
+    expect(dataUrl('image/png', Buffer.from([1, 2, 3]), false)).toBe('data:image/png;base64,AQID');
+    expect(dataUrl('text/css', Buffer.from('a{}'), true)).toBe(
+      'data:text/css;charset=utf-8;base64,YXt9'
+    );
+  });
+});
@@ -0,0 +1,227 @@
+/**
+ * Single-file HTML snapshots (`freeze`).
+ *
+ * The page's current DOM, form state included, is written out with what it
+ * needs inlined: stylesheets become <style> elements, and images, fonts and
+ * other files referenced from HTML or CSS become data: URLs, read back from
+ * the browser (see `pageResources`) rather than fetched again. Scripts, event
+ * handler attributes and <noscript> are removed and a CSP forbids scripts, so
+ * the copy shows the page as it was and can't change. Links are made
+ * absolute. Files the browser no longer holds and frames keep pointing at the
+ * web, and are counted as external.
+ */
+
+import { writeFileSync } from 'node:fs';
+import type { CDPSession, Page } from 'playwright-core';
+import { pageResources } from './archive.js';
+import type { FreezeData } from './types.js';
+
+interface FrozenDocument {
+  html: string;
+  inlined: number;
+  external: number;
+}
+
+/** Rewrite every `url(...)` in CSS, resolved against `base` */
+export function inlineCssUrls(
+  css: string,
+  base: string,
+  file: (ref: string, base: string) => string
+): string {
+  return css.replace(
+    /url\(\s*(['"]?)([^'")]*)\1\s*\)/g,
+    (_match, _quote, ref: string) => `url("${file(ref.trim(), base)}")`
+  );
+}
+
+export function dataUrl(mimeType: string, body: Buffer, text: boolean): string {
+  return `data:${mimeType}${text ? ';charset=utf-8' : ''};base64,${body.toString('base64')}`;
+}
+
+/**
+ * Runs in the page, as source text so it can take `inlineCss` (the function
+ * above) along. `files` maps absolute URLs to data: URLs, `styles` maps
+ * stylesheet URLs to their text.
+ */
+function freezeDocument(
+  inlineCss: typeof inlineCssUrls,
+  files: Record<string, string>,
+  styles: Record<string, string>,
+  frozenAt: string
+): FrozenDocument {
+  let inlined = 0;
+  let external = 0;
+  const absolute = (ref: string, base = document.baseURI): string => {
+    try {
+      return new URL(ref, base).href;
+    } catch {
+      return ref;
+    }
+  };
+  // A reference as a data: URL when the browser has it, else absolute
+  const file = (ref: string, base = document.baseURI): string => {
+    if (!ref || ref.startsWith('data:') || ref.startsWith('#')) return ref;
+    const url = absolute(ref, base);
+    if (files[url]) {
+      inlined++;
+      return files[url];
+    }
+    if (/^https?:/.test(url)) external++;
+    return url;
+  };
+  const sheetText = (sheet: CSSStyleSheet | null): string | null => {
+    try {
+      return sheet ? Array.from(sheet.cssRules, (rule) => rule.cssText).join('\n') : null;
+    } catch {
+      // Cross-origin sheets can't be read from the page
+      return null;
+    }
+  };
+  const style = (css: string, base: string, media?: string | null) => {
+    const el = document.createElement('style');
+    if (media) el.setAttribute('media', media);
+    el.textContent = inlineCss(css, base, file);
+    return el;
+  };
+
+  const root = document.documentElement.cloneNode(true) as HTMLElement;
+  const live = Array.from(document.documentElement.querySelectorAll('*'));
+  const copies = Array.from(root.querySelectorAll('*'));
+  live.forEach((el, i) => {
+    const copy = copies[i];
+    const tag = el.localName;
+    for (const attr of Array.from(copy.attributes)) {
+      if (/^on/i.test(attr.name) || /^\s*javascript:/i.test(attr.value)) {
+        copy.removeAttribute(attr.name);
+      }
+    }
+    if (copy.hasAttribute('style') && copy.getAttribute('style')!.includes('url(')) {
+      copy.setAttribute('style', inlineCss(copy.getAttribute('style')!, document.baseURI, file));
+    }
+    const httpEquiv = el.getAttribute('http-equiv') ?? '';
+    if (
+      ['script', 'noscript', 'base'].includes(tag) ||
+      (tag === 'meta' && /refresh|content-security-policy/i.test(httpEquiv))
+    ) {
+      copy.remove();
+    } else if (tag === 'link') {
+      const link = el as HTMLLinkElement;
+      const rel = link.rel.toLowerCase();
+      if (/\bstylesheet\b/.test(rel)) {
+        const css = sheetText(link.sheet) ?? styles[link.href];
+        if (css !== undefined) {
+          copy.replaceWith(style(css, link.href, link.media));
+        } else {
+          copy.setAttribute('href', link.href);
+          external++;
+        }
+      } else if (/preload|prefetch|preconnect|modulepreload/.test(rel)) {
+        copy.remove();
+      } else if (link.hasAttribute('href')) {
+        copy.setAttribute('href', /\bicon\b/.test(rel) ? file(link.href) : link.href);
+      }
+    } else if (tag === 'style') {
+      const css = sheetText((el as HTMLStyleElement).sheet) ?? el.textContent ?? '';
+      copy.textContent = inlineCss(css, document.baseURI, file);
+    } else if (tag === 'img') {
+      const img = el as HTMLImageElement;
+      const src = img.currentSrc || img.getAttribute('src');
+      if (src) copy.setAttribute('src', file(src));
+      ['srcset', 'sizes', 'loading'].forEach((name) => copy.removeAttribute(name));
+    } else if (tag === 'source' && el.parentElement?.localName === 'picture') {
+      // The <img> carries the source the browser picked
+      copy.remove();
+    } else if (tag === 'canvas') {
+      try {
+        const img = document.createElement('img');
+        img.src = (el as HTMLCanvasElement).toDataURL();
+        for (const attr of ['id', 'class', 'style', 'width', 'height']) {
+          const value = el.getAttribute(attr);
+          if (value !== null) img.setAttribute(attr, value);
+        }
+        copy.replaceWith(img);
+      } catch {
+        // A tainted canvas stays blank
+      }
+    } else if (tag === 'input') {
+      const input = el as HTMLInputElement;
+      if (input.type === 'checkbox' || input.type === 'radio') {
+        copy.toggleAttribute('checked', input.checked);
+      } else if (input.type === 'image' && input.src) {
+        copy.setAttribute('src', file(input.src));
+      } else if (input.type !== 'password' && input.type !== 'file') {
+        copy.setAttribute('value', input.value);
+      }
+    } else if (tag === 'textarea') {
+      copy.textContent = (el as HTMLTextAreaElement).value;
+    } else if (tag === 'option') {
+      copy.toggleAttribute('selected', (el as HTMLOptionElement).selected);
+    } else {
+      for (const name of ['src', 'poster']) {
+        const value = el.getAttribute(name);
+        if (value === null) continue;
+        // Frames stay live links; their documents would bring scripts back
+        copy.setAttribute(name, tag === 'iframe' ? absolute(value) : file(value));
+      }
+      for (const name of ['href', 'action']) {
+        const value = el.getAttribute(name);
+        if (value !== null && !value.startsWith('#')) copy.setAttribute(name, absolute(value));
+      }
+      if (tag === 'image' || tag === 'use') {
+        const href = el.getAttribute('href') ?? el.getAttribute('xlink:href');
+        if (href && !href.startsWith('#')) copy.setAttribute('href', file(href));
+      }
+    }
+  });
+
+  let head = root.querySelector('head');
+  if (!head) {
+    head = document.createElement('head');
+    root.prepend(head);
+  }
+  for (const sheet of document.adoptedStyleSheets ?? []) {
+    const css = sheetText(sheet);
+    if (css) head.append(style(css, document.baseURI));
+  }
+  head
+    .querySelectorAll('meta[charset], meta[http-equiv="content-type" i]')
+    .forEach((el) => el.remove());
+  const csp = document.createElement('meta');
+  csp.httpEquiv = 'Content-Security-Policy';
+  csp.content = "script-src 'none'; object-src 'none'";
+  const charset = document.createElement('meta');
+  charset.setAttribute('charset', 'utf-8');
+  head.prepend(charset, csp);
+
+  const doctype = document.doctype ? `<!DOCTYPE ${document.doctype.name}>\n` : '';
+  const source = location.href.replace(/--/g, '%2D%2D');
+  const note = `<!-- Frozen by agent-browser from ${source} at ${frozenAt} -->\n`;
+  return { html: doctype + note + root.outerHTML, inlined, external };
+}
+
+/** Write the current page to `path` as one self-contained HTML file */
+export async function freezePage(
+  page: Page,
+  cdp: CDPSession,
+  path: string
+): Promise<Omit<FreezeData, 'path' | 'artifacts'>> {
+  const { resources } = await pageResources(cdp);
+  const files: Record<string, string> = {};
+  const styles: Record<string, string> = {};
+  for (const { url, mimeType, body, text } of resources) {
+    files[url] = dataUrl(mimeType, body, text);
+    if (mimeType === 'text/css') styles[url] = body.toString('utf8');
+  }
+  const args = [files, styles, new Date().toISOString()].map((arg) => JSON.stringify(arg));
+  const frozen = (await page.evaluate(
+    `(${freezeDocument})(${inlineCssUrls}, ${args.join(', ')})`
+  )) as FrozenDocument;
+  writeFileSync(path, frozen.html);
+  return {
+    url: page.url(),
+    title: await page.title(),
+    inlined: frozen.inlined,
+    external: frozen.external,
+    bytes: Buffer.byteLength(frozen.html),
+  };
+}
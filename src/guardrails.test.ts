@@ -0,0 +1,52 @@
+import { describe, it, expect } from 'vitest';
+import { BUILTIN_RULES, Guardrails, describeViolations } from './guardrails.js';
+
+const guardrails = new Guardrails(BUILTIN_RULES);
+const post = (url: string) => ({ url, method: 'POST', navigation: false });
+const load = (url: string) => ({ url, method: 'GET', navigation: false });
+const open = (url: string) => ({ url, method: 'GET', navigation: true });
+
+describe('guardrails', () => {
+  it('blocks writes to payment endpoints', () => {
+    expect(guardrails.check(post('https://api.stripe.com/v1/payment_intents'))?.name).toBe(
+      'payment-provider'
+    );
+    expect(guardrails.check(post('https://shop.example.com/api/payments'))?.name).toBe(
+      'payment-endpoint'
+    );
+    expect(guardrails.check(post('https://shop.example.com/api/cart'))).toBeNull();
+    expect(guardrails.check(load('https://shop.example.com/api/payments'))).toBeNull();
+  });
+
+  it('blocks opening checkout and confirmation pages', () => {
+    expect(guardrails.check(open('https://shop.example.com/checkout?step=2'))?.name).toBe(
+      'checkout-page'
+    );
+    expect(guardrails.check(open('https://shop.example.com/order/confirm'))).not.toBeNull();
+    expect(guardrails.check(open('https://shop.example.com/products/checkout-guide'))).toBeNull();
+    // Only as a page: a script may still fetch the checkout page's data
+    expect(guardrails.check(load('https://shop.example.com/checkout'))).toBeNull();
+  });
+
+  it('lets purchases through with --allow-purchases, but not custom rules', () => {
+    const custom = new Guardrails([
+      ...BUILTIN_RULES,
+      { name: 'no-deletes', url: '/admin/', methods: ['delete'] },
+    ]);
+    expect(custom.check(open('https://shop.example.com/checkout'), true)).toBeNull();
+    const remove = { ...post('https://shop.example.com/admin/users/7'), method: 'DELETE' };
+    expect(custom.check(remove, true)?.name).toBe('no-deletes');
+  });
+
+  it('describes violations', () => {
+    const checkout = { rule: 'checkout-page', method: 'GET', url: 'https://a.test/checkout' };
+    const pay = { rule: 'payment-endpoint', method: 'POST', url: 'https://a.test/pay' };
+    expect(describeViolations([{ ...checkout, purchase: true }, { ...pay, purchase: true }])).toBe(
+      'Blocked by guardrail checkout-page: GET https://a.test/checkout (and 1 more). ' +
+        'Pass --allow-purchases if this is meant to happen'
+    );
+    expect(describeViolations([{ ...pay, rule: 'no-pay', purchase: false }])).toBe(
+      'Blocked by guardrail no-pay: POST https://a.test/pay'
+    );
+  });
+});
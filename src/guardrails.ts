@@ -0,0 +1,79 @@
+/**
+ * Guardrails that keep an autonomous agent from spending money or setting
+ * off something it can't take back.
+ *
+ * With `--guardrails`, every request the browser makes is checked against a
+ * ruleset where requests are intercepted, and the first rule it matches
+ * aborts it. The built-in rules block writes to payment endpoints and
+ * opening checkout, order and confirmation pages; `--guardrail-rules` adds
+ * rules of your own. A command during which something was blocked fails
+ * with `E_GUARDRAIL`, listing the violations, even if the request was one
+ * the page made on its own. Rules marked `purchase` (all of the built-in
+ * ones) stand aside for a command run with `--allow-purchases`.
+ */
+
+import type { GuardrailRule, GuardrailViolation } from './types.js';
+
+export const BUILTIN_RULES: GuardrailRule[] = [
+  {
+    name: 'payment-provider',
+    url: '^https?://(api\\.stripe\\.com|api(-m)?\\.(sandbox\\.)?paypal\\.com|payments\\.braintree-api\\.com|[^/]*checkout[^/]*\\.adyen\\.com|api\\.checkout\\.com|connect\\.squareup\\.com)/',
+    methods: ['POST', 'PUT', 'PATCH'],
+    purchase: true,
+  },
+  {
+    name: 'payment-endpoint',
+    url: '/(pay|payments?|charges?|billing|purchases?|transactions?|subscriptions?)(/|\\?|\\.|$)',
+    methods: ['POST', 'PUT', 'PATCH'],
+    purchase: true,
+  },
+  {
+    name: 'checkout-page',
+    url: '/(checkout|place-?order|buy-?now|order-?confirm(ation)?|confirm(ation)?)(/|\\?|\\.|$)',
+    navigation: true,
+    purchase: true,
+  },
+  {
+    name: 'checkout-submit',
+    url: '/(checkout|place-?order|buy-?now|confirm(ation)?)(/|\\?|\\.|$)',
+    methods: ['POST', 'PUT', 'PATCH'],
+    purchase: true,
+  },
+];
+
+export interface GuardedRequest {
+  url: string;
+  method: string;
+  /** A document load in the top frame */
+  navigation: boolean;
+}
+
+export class Guardrails {
+  private compiled: { rule: GuardrailRule; pattern: RegExp }[];
+
+  constructor(readonly rules: GuardrailRule[]) {
+    this.compiled = rules.map((rule) => ({ rule, pattern: new RegExp(rule.url, 'i') }));
+  }
+
+  /** The rule that blocks a request, if any */
+  check(request: GuardedRequest, allowPurchases = false): GuardrailRule | null {
+    const method = request.method.toUpperCase();
+    for (const { rule, pattern } of this.compiled) {
+      if (allowPurchases && rule.purchase) continue;
+      if (rule.navigation && !request.navigation) continue;
+      if (rule.methods && !rule.methods.some((m) => m.toUpperCase() === method)) continue;
+      if (pattern.test(request.url)) return rule;
+    }
+    return null;
+  }
+}
+
+/** What a blocked request broke, for the error and its details */
+export function describeViolations(violations: GuardrailViolation[]): string {
+  const [first] = violations;
+  const more = violations.length > 1 ? ` (and ${violations.length - 1} more)` : '';
+  const hint = violations.some((v) => v.purchase)
+    ? '. Pass --allow-purchases if this is meant to happen'
+    : '';
+  return `Blocked by guardrail ${first.rule}: ${first.method} ${first.url}${more}${hint}`;
+}
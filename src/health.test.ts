@@ -0,0 +1,62 @@
+import { describe, it, expect, afterEach } from 'vitest';
+import type { Server } from 'node:http';
+import type { AddressInfo } from 'node:net';
+import { startHealthServer, type BrowserState, type HealthProbe } from './health.js';
+
+describe('startHealthServer', () => {
+  let server: Server;
+  let browser: BrowserState;
+  let draining: boolean;
+
+  const probe: HealthProbe = {
+    browser: async () => browser,
+    draining: () => draining,
+    inFlight: () => 1,
+  };
+
+  const status = async (endpoint: string) => {
+    const base = `http://127.0.0.1:${(server.address() as AddressInfo).port}`;
+    return (await fetch(`${base}${endpoint}`)).status;
+  };
+
+  afterEach(() => {
+    server.close();
+  });
+
+  it('is live and ready before and after the browser launches', async () => {
+    server = await startHealthServer(probe, 0);
+    draining = false;
+    browser = 'not-launched';
+    expect(await status('/healthz')).toBe(200);
+    expect(await status('/readyz')).toBe(200);
+    browser = 'running';
+    const base = `http://127.0.0.1:${(server.address() as AddressInfo).port}`;
+    expect(await (await fetch(`${base}/readyz`)).json()).toEqual({
+      status: 'ok',
+      browser: 'running',
+      draining: false,
+      inFlight: 1,
+    });
+    expect(await status('/metrics')).toBe(404);
+  });
+
+  it('is not ready while the browser hangs or the daemon drains', async () => {
+    server = await startHealthServer(probe, 0);
+    draining = false;
+    browser = 'unresponsive';
+    expect(await status('/healthz')).toBe(200);
+    expect(await status('/readyz')).toBe(503);
+    browser = 'running';
+    draining = true;
+    expect(await status('/healthz')).toBe(200);
+    expect(await status('/readyz')).toBe(503);
+  });
+
+  it('is not live once the browser has disconnected', async () => {
+    server = await startHealthServer(probe, 0);
+    draining = false;
+    browser = 'disconnected';
+    expect(await status('/healthz')).toBe(503);
+    expect(await status('/readyz')).toBe(503);
+  });
+});
@@ -0,0 +1,91 @@
+/**
+ * Health and readiness endpoints for running a daemon as a pod.
+ *
+ * With `AGENT_BROWSER_HEALTH_PORT` set, the daemon serves:
+ *
+ *   GET /healthz  200 unless the browser was launched and has since crashed or
+ *                 disconnected; a liveness probe restarts the pod then
+ *   GET /readyz   200 while the daemon takes commands: not shutting down, and
+ *                 the browser (if launched) answers over CDP within
+ *                 {@link PROBE_TIMEOUT_MS}
+ *
+ * Both answer with the same JSON body, e.g.
+ * `{"status":"ok","browser":"running","draining":false,"inFlight":0}`.
+ *
+ * On SIGTERM the daemon drains: /readyz turns 503, new commands and jobs are
+ * refused, and it waits up to `AGENT_BROWSER_DRAIN_TIMEOUT` ms (default
+ * {@link DEFAULT_DRAIN_TIMEOUT_MS}, inside Kubernetes' default 30 s grace
+ * period) for commands and jobs in flight before closing the browser.
+ */
+
+import * as http from 'http';
+
+/** How long the browser gets to answer a readiness probe */
+export const PROBE_TIMEOUT_MS = 2000;
+
+export const DEFAULT_DRAIN_TIMEOUT_MS = 25_000;
+
+export type BrowserState = 'not-launched' | 'running' | 'unresponsive' | 'disconnected';
+
+export interface HealthStatus {
+  status: 'ok' | 'unavailable';
+  browser: BrowserState;
+  draining: boolean;
+  inFlight: number;
+}
+
+export interface HealthProbe {
+  browser: () => Promise<BrowserState>;
+  draining: () => boolean;
+  inFlight: () => number;
+}
+
+/** Liveness and readiness, with the status each endpoint should answer */
+export async function checkHealth(
+  probe: HealthProbe
+): Promise<{ live: boolean; ready: boolean; body: HealthStatus }> {
+  const browser = await probe.browser();
+  const draining = probe.draining();
+  const live = browser !== 'disconnected';
+  const ready = live && browser !== 'unresponsive' && !draining;
+  return {
+    live,
+    ready,
+    body: { status: ready ? 'ok' : 'unavailable', browser, draining, inFlight: probe.inFlight() },
+  };
+}
+
+/** `AGENT_BROWSER_DRAIN_TIMEOUT`, or the default */
+export function drainTimeout(): number {
+  const timeout = parseInt(process.env.AGENT_BROWSER_DRAIN_TIMEOUT ?? '', 10);
+  return timeout >= 0 ? timeout : DEFAULT_DRAIN_TIMEOUT_MS;
+}
+
+/**
+ * Serve `GET /healthz` and `GET /readyz` on the given port. Binds to
+ * 127.0.0.1 unless `AGENT_BROWSER_HEALTH_HOST` says otherwise; kubelet
+ * probes the pod IP, so pods need `0.0.0.0`.
+ */
+export async function startHealthServer(probe: HealthProbe, port: number): Promise<http.Server> {
+  const server = http.createServer((req, res) => {
+    const endpoint = req.url?.split('?')[0];
+    if (req.method !== 'GET' || (endpoint !== '/healthz' && endpoint !== '/readyz')) {
+      res.writeHead(404).end();
+      return;
+    }
+    checkHealth(probe).then(
+      ({ live, ready, body }) => {
+        const ok = endpoint === '/healthz' ? live : ready;
+        res.writeHead(ok ? 200 : 503, { 'Content-Type': 'application/json' });
+        res.end(JSON.stringify(body));
+      },
+      () => res.writeHead(503).end()
+    );
+  });
+  const host = process.env.AGENT_BROWSER_HEALTH_HOST || '127.0.0.1';
+  await new Promise<void>((resolve, reject) => {
+    server.once('error', reject);
+    server.listen(port, host, () => resolve());
+  });
+  return server;
+}
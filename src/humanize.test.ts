@@ -0,0 +1,39 @@
+import { describe, it, expect } from 'vitest';
+import type { Page } from 'playwright-core';
+import { Humanizer } from './humanize.js';
+
+function fakePage(): { page: Page; moves: number[][]; wheels: number[][] } {
+  const moves: number[][] = [];
+  const wheels: number[][] = [];
+  const page = {
+    viewportSize: () => ({ width: 800, height: 600 }),
+    mouse: {
+      move: async (x: number, y: number) => void moves.push([x, y]),
+      wheel: async (dx: number, dy: number) => void wheels.push([dx, dy]),
+    },
+  };
+  return { page: page as unknown as Page, moves, wheels };
+}
+
+describe('Humanizer', () => {
+  it('repeats the same path for the same seed', async () => {
+    const a = fakePage();
+    const b = fakePage();
+    await new Humanizer(42).moveTo(a.page, 120, 80);
+    await new Humanizer(42).moveTo(b.page, 120, 80);
+    expect(a.moves).toEqual(b.moves);
+    expect(a.moves.length).toBeGreaterThanOrEqual(8);
+    expect(a.moves[a.moves.length - 1]).toEqual([120, 80]);
+
+    const c = fakePage();
+    await new Humanizer(7).moveTo(c.page, 120, 80);
+    expect(c.moves).not.toEqual(a.moves);
+  });
+
+  it('scrolls the full distance in several wheel ticks', async () => {
+    const { page, wheels } = fakePage();
+    await new Humanizer(1).scroll(page, 0, 450);
+    expect(wheels.length).toBeGreaterThan(1);
+    expect(wheels.reduce((sum, [, dy]) => sum + dy, 0)).toBe(450);
+  });
+});
@@ -0,0 +1,157 @@
+/**
+ * Human-like input for click, type and scroll.
+ *
+ * With `--humanize` the mouse travels to its target along a curved, eased
+ * path and lands off-center, key presses come at an uneven cadence with the
+ * occasional longer pause, and scrolling arrives as a series of wheel ticks
+ * instead of a single jump. Every random choice comes from one seeded
+ * generator, so passing `--humanize-seed` makes a command's input repeatable.
+ */
+
+import type { Locator, Page } from 'playwright-core';
+
+/** Last known pointer position per page, since Playwright doesn't expose it */
+const pointers = new WeakMap<Page, { x: number; y: number }>();
+
+/** mulberry32: small, fast and good enough for timing jitter */
+export function createRng(seed: number): () => number {
+  let state = seed >>> 0;
+  return () => {
+    state = (state + 0x6d2b79f5) >>> 0;
+    let t = state;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
+}
+
+export class Humanizer {
+  private random: () => number;
+
+  constructor(seed?: number) {
+    this.random = createRng(seed ?? Math.floor(Math.random() * 2 ** 32));
+  }
+
+  /** Uniform value in [min, max) */
+  private between(min: number, max: number): number {
+    return min + this.random() * (max - min);
+  }
+
+  private pause(min: number, max: number): Promise<void> {
+    return new Promise((resolve) => setTimeout(resolve, this.between(min, max)));
+  }
+
+  /**
+   * Move the pointer to (x, y) along a cubic Bezier curve with eased speed,
+   * taking more steps for longer distances.
+   */
+  async moveTo(page: Page, x: number, y: number): Promise<void> {
+    const viewport = page.viewportSize() ?? { width: 1280, height: 720 };
+    const from = pointers.get(page) ?? {
+      x: this.between(0, viewport.width),
+      y: this.between(0, viewport.height),
+    };
+    const distance = Math.hypot(x - from.x, y - from.y);
+    const steps = Math.max(8, Math.min(60, Math.round(distance / 12)));
+    // Control points pulled sideways from the straight line for a natural arc
+    const spread = Math.min(distance * 0.3, 120);
+    const c1 = {
+      x: from.x + (x - from.x) * 0.3 + this.between(-spread, spread),
+      y: from.y + (y - from.y) * 0.3 + this.between(-spread, spread),
+    };
+    const c2 = {
+      x: from.x + (x - from.x) * 0.7 + this.between(-spread, spread),
+      y: from.y + (y - from.y) * 0.7 + this.between(-spread, spread),
+    };
+
+    for (let i = 1; i <= steps; i++) {
+      // Ease in and out: slow start, fast middle, slow approach
+      const p = i / steps;
+      const t = p < 0.5 ? 2 * p * p : 1 - (-2 * p + 2) ** 2 / 2;
+      const u = 1 - t;
+      const px = u ** 3 * from.x + 3 * u * u * t * c1.x + 3 * u * t * t * c2.x + t ** 3 * x;
+      const py = u ** 3 * from.y + 3 * u * u * t * c1.y + 3 * u * t * t * c2.y + t ** 3 * y;
+      await page.mouse.move(px, py);
+      await this.pause(4, 16);
+    }
+    pointers.set(page, { x, y });
+  }
+
+  /** A point inside the element's box, biased toward the center. */
+  private async targetPoint(locator: Locator): Promise<{ x: number; y: number }> {
+    await locator.scrollIntoViewIfNeeded();
+    const box = await locator.boundingBox();
+    if (!box) {
+      throw new Error('Element is not visible');
+    }
+    // Average of two samples clusters around the middle of the range
+    const offset = () => (this.random() + this.random()) / 2 - 0.5;
+    return {
+      x: box.x + box.width / 2 + offset() * box.width * 0.6,
+      y: box.y + box.height / 2 + offset() * box.height * 0.6,
+    };
+  }
+
+  async click(
+    page: Page,
+    locator: Locator,
+    options: { button?: 'left' | 'right' | 'middle'; clickCount?: number; delay?: number } = {}
+  ): Promise<void> {
+    // Wait for the element to be actionable without clicking it
+    await locator.click({ trial: true });
+    const { x, y } = await this.targetPoint(locator);
+    await this.moveTo(page, x, y);
+    await this.pause(60, 180);
+    for (let i = 0; i < (options.clickCount ?? 1); i++) {
+      if (i > 0) await this.pause(70, 140);
+      await page.mouse.down({ button: options.button, clickCount: i + 1 });
+      await this.pause(options.delay ?? 40, (options.delay ?? 40) + 80);
+      await page.mouse.up({ button: options.button, clickCount: i + 1 });
+    }
+  }
+
+  /**
+   * Click into the field, then type one character at a time with a varying
+   * gap, a little longer after spaces and punctuation and now and then a
+   * longer hesitation. `delay` sets the typical gap.
+   */
+  async type(page: Page, locator: Locator, text: string, delay?: number): Promise<void> {
+    await this.click(page, locator);
+    const base = delay ?? 90;
+    for (const char of text) {
+      await page.keyboard.type(char);
+      let gap = this.between(base * 0.5, base * 1.6);
+      if (/[\s.,;:!?]/.test(char)) gap += this.between(40, 160);
+      if (this.random() < 0.04) gap += this.between(250, 700);
+      await new Promise((resolve) => setTimeout(resolve, gap));
+    }
+  }
+
+  /**
+   * Scroll by (deltaX, deltaY) as a burst of wheel ticks that speed up and
+   * then slow down. With a locator the pointer moves over it first, so the
+   * wheel scrolls that element.
+   */
+  async scroll(page: Page, deltaX: number, deltaY: number, locator?: Locator): Promise<void> {
+    if (locator) {
+      const { x, y } = await this.targetPoint(locator);
+      await this.moveTo(page, x, y);
+    } else if (!pointers.has(page)) {
+      const viewport = page.viewportSize() ?? { width: 1280, height: 720 };
+      await this.moveTo(page, viewport.width / 2, viewport.height / 2);
+    }
+    const ticks = Math.max(1, Math.round(Math.max(Math.abs(deltaX), Math.abs(deltaY)) / 100));
+    let doneX = 0;
+    let doneY = 0;
+    for (let i = 1; i <= ticks; i++) {
+      const p = i / ticks;
+      const t = p < 0.5 ? 2 * p * p : 1 - (-2 * p + 2) ** 2 / 2;
+      const stepX = Math.round(deltaX * t) - doneX;
+      const stepY = Math.round(deltaY * t) - doneY;
+      await page.mouse.wheel(stepX, stepY);
+      doneX += stepX;
+      doneY += stepY;
+      await this.pause(20, 60);
+    }
+  }
+}
@@ -0,0 +1,178 @@
+/**
+ * Raw input recording and replay.
+ *
+ * `input record` injects a listener into every page that reports trusted
+ * mouse, wheel and keyboard events with their timestamps, and `input stop`
+ * saves them as a JSON timeline. `input replay` sends the same events through
+ * Playwright's mouse and keyboard with the original gaps between them, for
+ * apps that look at how input arrives as well as what it does.
+ */
+
+import { readFileSync, writeFileSync } from 'node:fs';
+import type { BrowserContext, Page } from 'playwright-core';
+import type { InputTimeline, TimelineEvent } from './types.js';
+
+const BINDING = '__agentBrowserInput';
+
+/**
+ * Installed in every document. Only the top frame records, since replay
+ * addresses the viewport. Listeners remove themselves once the daemon says
+ * recording has stopped, so pages don't keep calling the binding afterwards.
+ */
+const RECORDER_SCRIPT = `(() => {
+  if (window !== window.top || window.__agentBrowserInputInstalled) return;
+  window.__agentBrowserInputInstalled = true;
+  const buttons = ['left', 'middle', 'right'];
+  const types = ['mousemove', 'mousedown', 'mouseup', 'wheel', 'keydown', 'keyup'];
+  const handler = (e) => {
+    if (!e.isTrusted) return;
+    const event = { time: performance.timeOrigin + e.timeStamp, type: e.type };
+    if (e.type.startsWith('key')) {
+      event.key = e.key;
+      event.code = e.code;
+    } else {
+      event.x = e.clientX;
+      event.y = e.clientY;
+    }
+    if (e.type === 'mousedown' || e.type === 'mouseup') event.button = buttons[e.button] || 'left';
+    if (e.type === 'wheel') {
+      event.deltaX = e.deltaX;
+      event.deltaY = e.deltaY;
+    }
+    window.${BINDING}(event).then((recording) => {
+      if (recording) return;
+      types.forEach((t) => window.removeEventListener(t, handler, true));
+      window.__agentBrowserInputInstalled = false;
+    }, () => {});
+  };
+  types.forEach((t) => window.addEventListener(t, handler, { capture: true, passive: true }));
+})()`;
+
+type RawEvent = Omit<TimelineEvent, 't'> & { time: number };
+
+interface Recording {
+  path: string;
+  started: number;
+  url: string;
+  viewport: { width: number; height: number } | null;
+  events: RawEvent[];
+}
+
+export class InputRecorder {
+  private recording: Recording | null = null;
+  private bound = new WeakSet<BrowserContext>();
+
+  async start(path: string, contexts: BrowserContext[], page: Page): Promise<void> {
+    if (this.recording) {
+      throw new Error(`Already recording input to ${this.recording.path}. Run: agent-browser input stop`);
+    }
+    this.recording = {
+      path,
+      started: Date.now(),
+      url: page.url(),
+      viewport: page.viewportSize(),
+      events: [],
+    };
+    for (const context of contexts) {
+      // Bindings can't be removed, so each context gets one for its lifetime
+      if (!this.bound.has(context)) {
+        await context.exposeBinding(BINDING, (_source, event: RawEvent) => {
+          if (!this.recording) return false;
+          this.recording.events.push(event);
+          return true;
+        });
+        await context.addInitScript(RECORDER_SCRIPT);
+        this.bound.add(context);
+      }
+      for (const existing of context.pages()) {
+        await existing.evaluate(RECORDER_SCRIPT).catch(() => {});
+      }
+    }
+  }
+
+  /** Stop recording and write the timeline to the path given to start(). */
+  stop(): { path: string; events: number; duration: number } {
+    const recording = this.recording;
+    if (!recording) {
+      throw new Error('Not recording input. Run: agent-browser input record <file>');
+    }
+    this.recording = null;
+
+    const events: TimelineEvent[] = recording.events
+      .filter((e) => e.time >= recording.started)
+      .sort((a, b) => a.time - b.time)
+      .map(({ time, ...event }) => ({ t: Math.round((time - recording.started) * 10) / 10, ...event }));
+    const timeline: InputTimeline = {
+      version: 1,
+      url: recording.url,
+      viewport: recording.viewport,
+      duration: events.length > 0 ? events[events.length - 1].t : 0,
+      events,
+    };
+    writeFileSync(recording.path, JSON.stringify(timeline, null, 2));
+    return { path: recording.path, events: events.length, duration: timeline.duration };
+  }
+}
+
+export function readTimeline(path: string): InputTimeline {
+  let timeline: InputTimeline;
+  try {
+    timeline = JSON.parse(readFileSync(path, 'utf-8'));
+  } catch (err) {
+    throw new Error(`Can't read input timeline ${path}: ${(err as Error).message}`);
+  }
+  if (timeline?.version !== 1 || !Array.isArray(timeline.events)) {
+    throw new Error(`Not an input timeline: ${path}`);
+  }
+  return timeline;
+}
+
+/**
+ * Send every event in order, waiting until its offset from the start (divided
+ * by `speed`) so the original pacing is kept.
+ */
+export async function replayTimeline(
+  page: Page,
+  timeline: InputTimeline,
+  speed: number = 1
+): Promise<{ events: number; duration: number }> {
+  const start = Date.now();
+  let position: { x: number; y: number } | null = null;
+  const moveTo = async (x: number, y: number) => {
+    if (position?.x === x && position?.y === y) return;
+    await page.mouse.move(x, y);
+    position = { x, y };
+  };
+
+  for (const event of timeline.events) {
+    const delay = start + event.t / speed - Date.now();
+    if (delay > 0) await new Promise((resolve) => setTimeout(resolve, delay));
+
+    switch (event.type) {
+      case 'mousemove':
+        await moveTo(event.x!, event.y!);
+        break;
+      case 'mousedown':
+        await moveTo(event.x!, event.y!);
+        await page.mouse.down({ button: event.button });
+        break;
+      case 'mouseup':
+        await moveTo(event.x!, event.y!);
+        await page.mouse.up({ button: event.button });
+        break;
+      case 'wheel':
+        await moveTo(event.x!, event.y!);
+        await page.mouse.wheel(event.deltaX ?? 0, event.deltaY ?? 0);
+        break;
+      case 'keydown':
+      case 'keyup': {
+        // Fall back to the physical key for values Playwright doesn't know, like "Dead"
+        const press = (key: string) =>
+          event.type === 'keydown' ? page.keyboard.down(key) : page.keyboard.up(key);
+        await press(event.key!).catch(() => press(event.code!));
+        break;
+      }
+    }
+  }
+  return { events: timeline.events.length, duration: Date.now() - start };
+}
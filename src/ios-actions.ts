@@ -5,13 +5,14 @@
 
 import type { IOSManager } from './ios-manager.js';
 import type { Command, Response } from './types.js';
+import { classifyError } from './errors.js';
 
 function successResponse<T>(id: string, data: T): Response<T> {
   return { id, success: true, data };
 }
 
 function errorResponse(id: string, error: string): Response {
-  return { id, success: false, error };
+  return { id, success: false, error, code: classifyError(error) };
 }
 
 /**
@@ -254,6 +255,10 @@ export async function executeIOSCommand(command: Command, manager: IOSManager):
       case 'pdf':
         return errorResponse(id, 'PDF generation is not supported on iOS Safari.');
 
+      case 'archive':
+      case 'freeze':
+        return errorResponse(id, 'Page archives are not supported on iOS (requires CDP).');
+
       case 'screencast_start':
       case 'screencast_stop':
         return errorResponse(id, 'Screencast is not supported on iOS (requires CDP).');
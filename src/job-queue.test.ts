@@ -0,0 +1,172 @@
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import { mkdtempSync, rmSync, writeFileSync } from 'node:fs';
+import type { Server } from 'node:http';
+import type { AddressInfo } from 'node:net';
+import os from 'node:os';
+import path from 'node:path';
+import { JobQueue, startJobServer, type JobRunner } from './job-queue.js';
+
+/** A runner whose jobs finish when the test says so */
+function fakeRunner() {
+  const started: string[] = [];
+  const exits = new Map<string, (code: number) => void>();
+  const outputs = new Map<string, (chunk: string) => void>();
+  const runner: JobRunner = (job, output) => {
+    started.push(job.id);
+    outputs.set(job.id, output);
+    return {
+      done: new Promise((resolve) => exits.set(job.id, resolve)),
+      cancel: () => exits.get(job.id)!(143),
+    };
+  };
+  return { runner, started, exit: (id: string, code = 0) => exits.get(id)!(code), outputs };
+}
+
+const settle = () => new Promise((resolve) => setTimeout(resolve, 0));
+
+describe('JobQueue', () => {
+  let dir: string;
+
+  beforeEach(() => {
+    dir = mkdtempSync(path.join(os.tmpdir(), 'job-queue-test-'));
+  });
+
+  afterEach(() => {
+    rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('runs the highest priority first, within the concurrency limit', async () => {
+    const { runner, started, exit } = fakeRunner();
+    const queue = new JobQueue(runner, dir, 1);
+    queue.submit({ script: 'open a.com' });
+    queue.submit({ script: 'open b.com' });
+    queue.submit({ script: 'open c.com', priority: 5 });
+    queue.submit({ script: 'open d.com', priority: 5 });
+    expect(started).toEqual(['1']);
+
+    exit('1');
+    await settle();
+    exit('3', 1);
+    await settle();
+    expect(started).toEqual(['1', '3', '4']);
+    expect(queue.get('1')).toMatchObject({ status: 'passed', exitCode: 0 });
+    expect(queue.get('3')).toMatchObject({ status: 'failed', exitCode: 1 });
+    expect(queue.get('2').status).toBe('queued');
+  });
+
+  it('cancels queued and running jobs', async () => {
+    const { runner, started } = fakeRunner();
+    const queue = new JobQueue(runner, dir, 1);
+    queue.submit({ script: 'open a.com' });
+    queue.submit({ script: 'open b.com' });
+    queue.submit({ script: 'open c.com' });
+
+    expect(queue.cancel('2').status).toBe('cancelled');
+    expect(queue.cancel('1').status).toBe('cancelled');
+    await settle();
+    expect(queue.get('1')).toMatchObject({ status: 'cancelled', exitCode: 143 });
+    expect(started).toEqual(['1', '3']);
+    expect(() => queue.cancel('1')).toThrow('already cancelled');
+    expect(() => queue.get('9')).toThrow('No job 9');
+  });
+
+  it('drains by finishing running jobs and dropping queued ones', async () => {
+    const { runner, started, exit } = fakeRunner();
+    const queue = new JobQueue(runner, dir, 1);
+    queue.submit({ script: 'open a.com' });
+    queue.submit({ script: 'open b.com' });
+
+    let drained = false;
+    const drain = queue.drain().then(() => (drained = true));
+    expect(queue.get('2').status).toBe('cancelled');
+    expect(() => queue.submit({ script: 'open c.com' })).toThrow('shutting down');
+    await settle();
+    expect(drained).toBe(false);
+
+    exit('1');
+    await drain;
+    expect(queue.get('1').status).toBe('passed');
+    expect(started).toEqual(['1']);
+  });
+
+  it('keeps output and files in the job directory', async () => {
+    const { runner, exit, outputs } = fakeRunner();
+    const queue = new JobQueue(runner, dir);
+    const job = queue.submit({ script: 'open a.com', args: ['--reporter', 'json'] });
+    const followed: (string | null)[] = [];
+    outputs.get('1')!('step 1\n');
+    queue.logs('1', (chunk) => followed.push(chunk));
+    outputs.get('1')!('step 2\n');
+    writeFileSync(path.join(job.dir, 'report.json'), '{}');
+    exit('1');
+    await settle();
+
+    expect(followed).toEqual(['step 2\n', null]);
+    expect(queue.logs('1').output).toBe('step 1\nstep 2\n');
+    expect(queue.artifacts('1').map((a) => a.path)).toEqual([
+      'output.log',
+      'report.json',
+      'script.ab',
+    ]);
+    expect(() => queue.artifactPath('1', '../../etc/passwd')).toThrow('No artifact');
+  });
+
+  it('rejects malformed jobs', () => {
+    const queue = new JobQueue(fakeRunner().runner, dir);
+    expect(() => queue.submit({ script: ' ' })).toThrow('"script"');
+    expect(() => queue.submit({ script: 'open a.com', priority: 1.5 })).toThrow('"priority"');
+    expect(() => queue.submit({ script: 'open a.com', args: [1] as unknown as string[] })).toThrow(
+      '"args"'
+    );
+  });
+});
+
+describe('startJobServer', () => {
+  let dir: string;
+  let server: Server;
+  let base: string;
+  let fake: ReturnType<typeof fakeRunner>;
+
+  beforeEach(async () => {
+    dir = mkdtempSync(path.join(os.tmpdir(), 'job-server-test-'));
+    fake = fakeRunner();
+    server = await startJobServer(new JobQueue(fake.runner, dir), 0);
+    base = `http://127.0.0.1:${(server.address() as AddressInfo).port}`;
+  });
+
+  afterEach(() => {
+    server.close();
+    rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('submits jobs and reports on them', async () => {
+    const post = await fetch(`${base}/jobs`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ script: 'open example.com', name: 'smoke' }),
+    });
+    expect(post.status).toBe(201);
+    expect((await post.json()).job).toMatchObject({ id: '1', name: 'smoke', status: 'running' });
+
+    fake.outputs.get('1')!('done\n');
+    fake.exit('1');
+    await settle();
+    const job = await (await fetch(`${base}/jobs/1`)).json();
+    expect(job.job.status).toBe('passed');
+    expect(await (await fetch(`${base}/jobs/1/logs?follow=1`)).text()).toBe('done\n');
+    const script = await fetch(`${base}/jobs/1/artifacts/script.ab`);
+    expect(await script.text()).toBe('open example.com');
+    expect((await fetch(`${base}/jobs/2`)).status).toBe(404);
+  });
+
+  it('refuses requests from web pages', async () => {
+    const res = await fetch(`${base}/jobs`, { headers: { Origin: 'https://evil.example' } });
+    expect(res.status).toBe(403);
+    const form = await fetch(`${base}/jobs`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'text/plain' },
+      body: '{"script":"open example.com"}',
+    });
+    expect(form.status).toBe(415);
+  });
+});
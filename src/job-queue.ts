@@ -0,0 +1,491 @@
+/**
+ * A queue of scripts run by the daemon, with a REST API.
+ *
+ * With `AGENT_BROWSER_JOBS_PORT` set, the daemon accepts `run` scripts over
+ * HTTP and works through them by priority, at most
+ * `AGENT_BROWSER_JOBS_CONCURRENCY` at a time. Each job runs in a session of
+ * its own and a directory under the session's artifacts, so a backend can
+ * submit work, follow its output and collect what it produced:
+ *
+ *   POST   /jobs                      {"script": "...", "priority"?, "name"?, "args"?}
+ *   GET    /jobs                      All jobs, newest last
+ *   GET    /jobs/:id                  One job's status
+ *   DELETE /jobs/:id                  Cancel a queued or running job, mid-step
+ *   GET    /jobs/:id/logs             Output so far; ?follow=1 streams until it ends
+ *   GET    /jobs/:id/artifacts        Files in the job's directory
+ *   GET    /jobs/:id/artifacts/<path> One of those files
+ *
+ * Jobs are kept in memory only; a restarted daemon starts with an empty queue.
+ */
+
+import { spawn, type ChildProcess } from 'node:child_process';
+import {
+  closeSync,
+  createReadStream,
+  mkdirSync,
+  openSync,
+  readdirSync,
+  readFileSync,
+  rmSync,
+  statSync,
+  writeFileSync,
+  writeSync,
+} from 'node:fs';
+import * as http from 'node:http';
+import path from 'node:path';
+import { getArtifactsDir } from './artifacts.js';
+import type { BrowserPool } from './browser-pool.js';
+import { sessionEnv } from './schedule.js';
+import type { QueuedJob } from './types.js';
+
+const DEFAULT_CONCURRENCY = 2;
+/** Finished jobs kept, with their directories; older ones are deleted */
+const KEEP_FINISHED = 100;
+const MAX_BODY = 1024 * 1024;
+/** How long a cancelled job gets to stop its step and write its reports */
+const CANCEL_GRACE_MS = 10_000;
+
+const CONTENT_TYPES: Record<string, string> = {
+  '.html': 'text/html; charset=utf-8',
+  '.json': 'application/json',
+  '.xml': 'application/xml',
+  '.log': 'text/plain; charset=utf-8',
+  '.ab': 'text/plain; charset=utf-8',
+  '.txt': 'text/plain; charset=utf-8',
+  '.png': 'image/png',
+  '.jpg': 'image/jpeg',
+  '.jpeg': 'image/jpeg',
+  '.pdf': 'application/pdf',
+  '.zip': 'application/zip',
+};
+
+export interface JobRequest {
+  script: string;
+  name?: string;
+  priority?: number;
+  args?: string[];
+}
+
+/** A started job: resolves to its exit code, and can be stopped early */
+export interface JobProcess {
+  done: Promise<number>;
+  cancel(): void;
+}
+
+/** Starts `job`, passing everything it prints to `output` */
+export type JobRunner = (job: QueuedJob, output: (chunk: string) => void) => JobProcess;
+
+/** Raised for requests the queue can't act on; `status` is the HTTP status to answer with */
+export class JobError extends Error {
+  constructor(
+    message: string,
+    public status: number
+  ) {
+    super(message);
+  }
+}
+
+interface Entry {
+  job: QueuedJob;
+  process?: JobProcess;
+  log?: number;
+  listeners: Set<(chunk: string | null) => void>;
+}
+
+/**
+ * Run the job's script with the CLI that started this daemon, in the job's
+ * directory and its own session. Its browser is closed afterwards, or with a
+ * pool, attached from the pool and handed back to it.
+ */
+export function cliRunner(
+  session: string,
+  sessionRunning: (session: string) => boolean,
+  pool: BrowserPool | null = null
+): JobRunner {
+  return (job, output) => {
+    const cli = process.env.AGENT_BROWSER_CLI;
+    if (!cli) {
+      output('The job queue needs a daemon started by the agent-browser CLI\n');
+      return { done: Promise.resolve(1), cancel: () => {} };
+    }
+    const env = sessionEnv(`${session}-job-${job.id}`);
+    let child: ChildProcess | null = null;
+    let cancelled = false;
+    const closeSession = () =>
+      new Promise<void>((resolve) => {
+        if (!sessionRunning(env.AGENT_BROWSER_SESSION!)) return resolve();
+        spawn(cli, ['close'], { env, stdio: 'ignore' })
+          .on('error', () => resolve())
+          .on('close', () => resolve());
+      });
+    const done = (async () => {
+      const browser = pool
+        ? await pool.acquire().catch((err: Error) => {
+            output(`No browser from the pool, launching one: ${err.message}\n`);
+            return null;
+          })
+        : null;
+      if (cancelled) {
+        if (browser) await pool!.release(browser);
+        return 130;
+      }
+      const cdp = browser ? ['--cdp', browser.endpoint] : [];
+      const running = spawn(cli, [...cdp, 'run', 'script.ab', ...job.args], {
+        cwd: job.dir,
+        env,
+        stdio: ['ignore', 'pipe', 'pipe'],
+      });
+      child = running;
+      running.stdout.on('data', (data: Buffer) => output(data.toString()));
+      running.stderr.on('data', (data: Buffer) => output(data.toString()));
+      const code = await new Promise<number>((resolve) => {
+        running.on('error', (err) => {
+          output(`${err.message}\n`);
+          resolve(1);
+        });
+        running.on('close', (code) => resolve(code ?? 1));
+      });
+      // The session only disconnects from a pooled browser, so it's handed back after
+      await closeSession();
+      if (browser) await pool!.release(browser);
+      return code;
+    })();
+    return {
+      done,
+      // Like Ctrl-C: `run` cancels the step in flight and still writes its reports
+      cancel: () => {
+        cancelled = true;
+        const running = child;
+        if (!running) return;
+        running.kill('SIGINT');
+        setTimeout(() => running.kill('SIGKILL'), CANCEL_GRACE_MS).unref();
+      },
+    };
+  };
+}
+
+export class JobQueue {
+  private entries = new Map<string, Entry>();
+  private nextId = 1;
+  private stopped = false;
+  readonly concurrency: number;
+
+  constructor(
+    private runner: JobRunner,
+    private root: string,
+    concurrency: number = DEFAULT_CONCURRENCY
+  ) {
+    this.concurrency = Math.max(1, concurrency);
+  }
+
+  /** A queue for `session`, configured from the environment */
+  static forSession(
+    session: string,
+    sessionRunning: (session: string) => boolean,
+    pool: BrowserPool | null = null
+  ): JobQueue {
+    const concurrency = parseInt(process.env.AGENT_BROWSER_JOBS_CONCURRENCY ?? '', 10);
+    return new JobQueue(
+      cliRunner(session, sessionRunning, pool),
+      path.join(getArtifactsDir(session), 'jobs'),
+      concurrency > 0 ? concurrency : DEFAULT_CONCURRENCY
+    );
+  }
+
+  submit(request: JobRequest): QueuedJob {
+    if (this.stopped) throw new JobError('The daemon is shutting down', 503);
+    const { script, name, priority = 0, args = [] } = request ?? {};
+    if (typeof script !== 'string' || !script.trim()) {
+      throw new JobError('"script" must be the text of a run script', 400);
+    }
+    if (!Number.isInteger(priority)) throw new JobError('"priority" must be an integer', 400);
+    if (!Array.isArray(args) || args.some((arg) => typeof arg !== 'string')) {
+      throw new JobError('"args" must be an array of strings', 400);
+    }
+    if (name !== undefined && typeof name !== 'string') {
+      throw new JobError('"name" must be a string', 400);
+    }
+
+    const id = String(this.nextId++);
+    const dir = path.join(this.root, id);
+    mkdirSync(dir, { recursive: true });
+    writeFileSync(path.join(dir, 'script.ab'), script);
+    const job: QueuedJob = {
+      id,
+      ...(name ? { name } : {}),
+      status: 'queued',
+      priority,
+      args,
+      submittedAt: new Date().toISOString(),
+      dir,
+    };
+    this.entries.set(id, { job, listeners: new Set() });
+    this.pump();
+    return job;
+  }
+
+  list(): QueuedJob[] {
+    return [...this.entries.values()].map((entry) => entry.job);
+  }
+
+  get(id: string): QueuedJob {
+    return this.find(id).job;
+  }
+
+  cancel(id: string): QueuedJob {
+    const entry = this.find(id);
+    const { job } = entry;
+    if (job.status === 'queued') {
+      this.finish(entry, 'cancelled');
+      this.pump();
+    } else if (job.status === 'running') {
+      // The process exiting finishes it; the status stays cancelled
+      job.status = 'cancelled';
+      entry.process?.cancel();
+    } else {
+      throw new JobError(`Job ${id} already ${job.status}`, 409);
+    }
+    return job;
+  }
+
+  /** Output so far. `follow` gets every later chunk, then null when the job ends. */
+  logs(id: string, follow?: (chunk: string | null) => void): { output: string; stop: () => void } {
+    const entry = this.find(id);
+    let output = '';
+    try {
+      output = readFileSync(path.join(entry.job.dir, 'output.log'), 'utf-8');
+    } catch {
+      // Not started yet
+    }
+    if (!follow || entry.job.finishedAt) return { output, stop: () => {} };
+    entry.listeners.add(follow);
+    return { output, stop: () => entry.listeners.delete(follow) };
+  }
+
+  /** Files in the job's directory, relative to it */
+  artifacts(id: string): { path: string; size: number }[] {
+    const { dir } = this.find(id).job;
+    const files: { path: string; size: number }[] = [];
+    const walk = (current: string) => {
+      for (const item of readdirSync(current, { withFileTypes: true })) {
+        const full = path.join(current, item.name);
+        if (item.isDirectory()) walk(full);
+        else if (item.isFile()) {
+          files.push({ path: path.relative(dir, full), size: statSync(full).size });
+        }
+      }
+    };
+    walk(dir);
+    return files.sort((a, b) => a.path.localeCompare(b.path));
+  }
+
+  /** Absolute path of one of the job's files; never outside its directory */
+  artifactPath(id: string, name: string): string {
+    const { dir } = this.find(id).job;
+    const file = path.resolve(dir, name);
+    if (!file.startsWith(dir + path.sep)) throw new JobError(`No artifact ${name}`, 404);
+    try {
+      if (statSync(file).isFile()) return file;
+    } catch {
+      // Fall through
+    }
+    throw new JobError(`No artifact ${name}`, 404);
+  }
+
+  /** Refuse new jobs and drop queued ones, then wait for running ones to end */
+  async drain(): Promise<void> {
+    this.stopped = true;
+    const running: Promise<unknown>[] = [];
+    for (const entry of this.entries.values()) {
+      if (entry.job.status === 'queued') this.finish(entry, 'cancelled');
+      else if (entry.process && !entry.job.finishedAt) running.push(entry.process.done);
+    }
+    await Promise.allSettled(running);
+  }
+
+  /** Cancel everything, for a daemon shutting down */
+  stop(): void {
+    this.stopped = true;
+    for (const entry of this.entries.values()) {
+      if (entry.job.status === 'queued') this.finish(entry, 'cancelled');
+      else if (entry.job.status === 'running') this.cancel(entry.job.id);
+    }
+  }
+
+  private find(id: string): Entry {
+    const entry = this.entries.get(id);
+    if (!entry) throw new JobError(`No job ${id}`, 404);
+    return entry;
+  }
+
+  /** Start queued jobs, highest priority first, while there is room */
+  private pump(): void {
+    // Cancelled jobs count until their process has exited
+    const running = () =>
+      [...this.entries.values()].filter((entry) => entry.process && !entry.job.finishedAt).length;
+    while (!this.stopped && running() < this.concurrency) {
+      const next = [...this.entries.values()]
+        .filter((entry) => entry.job.status === 'queued')
+        // Stable, so equal priorities keep submission order
+        .sort((a, b) => b.job.priority - a.job.priority)[0];
+      if (!next) return;
+      this.start(next);
+    }
+  }
+
+  private start(entry: Entry): void {
+    const { job } = entry;
+    job.status = 'running';
+    job.startedAt = new Date().toISOString();
+    entry.log = openSync(path.join(job.dir, 'output.log'), 'a');
+    const output = (chunk: string) => {
+      if (entry.log !== undefined) writeSync(entry.log, chunk);
+      for (const listener of entry.listeners) listener(chunk);
+    };
+    entry.process = this.runner(job, output);
+    entry.process.done.then(
+      (code) => this.finish(entry, code === 0 ? 'passed' : 'failed', code),
+      (err: Error) => {
+        output(`${err.message}\n`);
+        this.finish(entry, 'failed', 1);
+      }
+    );
+  }
+
+  private finish(entry: Entry, status: QueuedJob['status'], exitCode?: number): void {
+    const { job } = entry;
+    if (job.finishedAt) return;
+    // A job cancelled while running stays cancelled whatever its exit code
+    if (job.status !== 'cancelled') job.status = status;
+    job.finishedAt = new Date().toISOString();
+    if (exitCode !== undefined) job.exitCode = exitCode;
+    if (entry.log !== undefined) closeSync(entry.log);
+    entry.log = undefined;
+    for (const listener of entry.listeners) listener(null);
+    entry.listeners.clear();
+    this.prune();
+    this.pump();
+  }
+
+  private prune(): void {
+    const finished = this.list().filter((job) => job.finishedAt);
+    for (const job of finished.slice(0, Math.max(0, finished.length - KEEP_FINISHED))) {
+      this.entries.delete(job.id);
+      rmSync(job.dir, { recursive: true, force: true });
+    }
+  }
+}
+
+function readBody(req: http.IncomingMessage): Promise<string> {
+  return new Promise((resolve, reject) => {
+    let body = '';
+    req.setEncoding('utf-8');
+    req.on('data', (chunk: string) => {
+      body += chunk;
+      if (body.length > MAX_BODY) {
+        reject(new JobError('Request body too large', 413));
+        req.destroy();
+      }
+    });
+    req.on('end', () => resolve(body));
+    req.on('error', reject);
+  });
+}
+
+async function handle(
+  queue: JobQueue,
+  token: string | undefined,
+  req: http.IncomingMessage,
+  res: http.ServerResponse
+): Promise<void> {
+  const json = (status: number, body: unknown) => {
+    res.writeHead(status, { 'Content-Type': 'application/json' });
+    res.end(JSON.stringify(body));
+  };
+  // Web pages can reach localhost too; they always send an Origin, API clients don't
+  if (req.headers.origin) throw new JobError('Cross-origin requests are not allowed', 403);
+  if (token && req.headers.authorization !== `Bearer ${token}`) {
+    throw new JobError('Missing or wrong bearer token', 401);
+  }
+
+  const url = new URL(req.url ?? '/', 'http://localhost');
+  const [root, id, resource, ...rest] = url.pathname.split('/').filter(Boolean);
+  if (root !== 'jobs') throw new JobError('Not found', 404);
+  const method = req.method ?? 'GET';
+
+  if (!id) {
+    if (method === 'GET') return json(200, { jobs: queue.list() });
+    if (method !== 'POST') throw new JobError(`${method} not allowed`, 405);
+    if (!req.headers['content-type']?.startsWith('application/json')) {
+      throw new JobError('Send the job as application/json', 415);
+    }
+    let request: JobRequest;
+    try {
+      request = JSON.parse(await readBody(req));
+    } catch (err) {
+      if (err instanceof JobError) throw err;
+      throw new JobError('Request body is not valid JSON', 400);
+    }
+    return json(201, { job: queue.submit(request) });
+  }
+
+  if (!resource) {
+    if (method === 'GET') return json(200, { job: queue.get(id) });
+    if (method === 'DELETE') return json(200, { job: queue.cancel(id) });
+    throw new JobError(`${method} not allowed`, 405);
+  }
+  if (method !== 'GET') throw new JobError(`${method} not allowed`, 405);
+
+  if (resource === 'logs' && rest.length === 0) {
+    const follow = ['1', 'true'].includes(url.searchParams.get('follow') ?? '');
+    res.writeHead(200, { 'Content-Type': 'text/plain; charset=utf-8' });
+    if (!follow) {
+      res.end(queue.logs(id).output);
+      return;
+    }
+    const { output, stop } = queue.logs(id, (chunk) => {
+      if (chunk === null) res.end();
+      else res.write(chunk);
+    });
+    res.write(output);
+    if (queue.get(id).finishedAt) res.end();
+    res.on('close', stop);
+    return;
+  }
+
+  if (resource === 'artifacts') {
+    if (rest.length === 0) return json(200, { artifacts: queue.artifacts(id) });
+    const file = queue.artifactPath(id, rest.map(decodeURIComponent).join('/'));
+    const type = CONTENT_TYPES[path.extname(file).toLowerCase()] ?? 'application/octet-stream';
+    res.writeHead(200, { 'Content-Type': type, 'Content-Length': statSync(file).size });
+    createReadStream(file).pipe(res);
+    return;
+  }
+  throw new JobError('Not found', 404);
+}
+
+/**
+ * Serve the job API on the given port. Binds to 127.0.0.1 unless
+ * `AGENT_BROWSER_JOBS_HOST` says otherwise; with `AGENT_BROWSER_JOBS_TOKEN`
+ * set, requests need `Authorization: Bearer <token>`.
+ */
+export async function startJobServer(queue: JobQueue, port: number): Promise<http.Server> {
+  const token = process.env.AGENT_BROWSER_JOBS_TOKEN || undefined;
+  const server = http.createServer((req, res) => {
+    handle(queue, token, req, res).catch((err: Error) => {
+      const status = err instanceof JobError ? err.status : 500;
+      if (res.headersSent) {
+        res.end();
+        return;
+      }
+      res.writeHead(status, { 'Content-Type': 'application/json' });
+      res.end(JSON.stringify({ error: err.message }));
+    });
+  });
+  const host = process.env.AGENT_BROWSER_JOBS_HOST || '127.0.0.1';
+  await new Promise<void>((resolve, reject) => {
+    server.once('error', reject);
+    server.listen(port, host, () => resolve());
+  });
+  return server;
+}
@@ -0,0 +1,128 @@
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import { mkdtempSync, rmSync, writeFileSync } from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { Lane, LaneScheduler } from './lanes.js';
+
+let dir: string;
+
+beforeEach(() => {
+  dir = mkdtempSync(path.join(os.tmpdir(), 'lanes-'));
+});
+
+afterEach(() => {
+  rmSync(dir, { recursive: true, force: true });
+});
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+describe('Lane', () => {
+  it('serves tickets oldest first', () => {
+    const lane = new Lane(dir, 100, () => true);
+    const first = lane.enqueue();
+    const second = lane.enqueue();
+    expect(lane.head()).toBe(first);
+    expect(lane.queued()).toBe(2);
+    lane.leave(first);
+    expect(lane.head()).toBe(second);
+  });
+
+  it('caps slots and frees those of dead daemons', () => {
+    const alive = new Set([100, 200]);
+    const lane = new Lane(dir, 100, (pid) => alive.has(pid));
+    const other = new Lane(dir, 200, (pid) => alive.has(pid));
+    const slot = lane.takeSlot(2);
+    expect(other.takeSlot(2)).not.toBeNull();
+    expect(lane.takeSlot(2)).toBeNull();
+    expect(lane.active()).toBe(2);
+
+    lane.releaseSlot(slot!);
+    expect(lane.takeSlot(2)).not.toBeNull();
+
+    alive.delete(200);
+    expect(lane.active()).toBe(1);
+    expect(lane.takeSlot(2)).not.toBeNull();
+  });
+
+  it('drops tickets of dead daemons', () => {
+    const lane = new Lane(dir, 100, (pid) => pid === 100);
+    writeFileSync(path.join(dir, 'queue', '000000000000001-000000-300'), '300');
+    const ticket = lane.enqueue();
+    expect(lane.head()).toBe(ticket);
+    expect(lane.queued()).toBe(1);
+  });
+
+  it('spaces out reserved starts', () => {
+    const lane = new Lane(dir, 100, () => true);
+    expect(lane.reserveStart(500, 1_000)).toBe(0);
+    expect(lane.reserveStart(500, 1_100)).toBe(400);
+    expect(lane.reserveStart(500, 3_000)).toBe(0);
+  });
+});
+
+describe('LaneScheduler', () => {
+  it('runs commands directly without limits', async () => {
+    const scheduler = new LaneScheduler(() => new Lane(dir, 100, () => true));
+    expect(scheduler.enabled).toBe(false);
+    expect(await scheduler.run('b', async () => 'done')).toBe('done');
+  });
+
+  it('caps the commands running at once', async () => {
+    const scheduler = new LaneScheduler(() => new Lane(dir, 100, () => true));
+    scheduler.configure({ maxConcurrent: 1 });
+    let running = 0;
+    let most = 0;
+    const task = async () => {
+      most = Math.max(most, ++running);
+      await sleep(10);
+      running--;
+    };
+    await Promise.all([1, 2, 3].map(() => scheduler.run('b', task)));
+    expect(most).toBe(1);
+    expect(scheduler.snapshot('b')).toMatchObject({
+      maxConcurrent: 1,
+      waiting: 0,
+      active: 0,
+      scheduled: 3,
+    });
+
+    scheduler.configure({ maxConcurrent: 0 });
+    expect(scheduler.enabled).toBe(false);
+  });
+
+  it('takes turns between sessions', async () => {
+    const chatty = new LaneScheduler(() => new Lane(dir, 100, () => true));
+    const quiet = new LaneScheduler(() => new Lane(dir, 200, () => true));
+    chatty.configure({ maxConcurrent: 1 });
+    quiet.configure({ maxConcurrent: 1 });
+    const order: string[] = [];
+    const task = (name: string) => async () => {
+      order.push(name);
+      await sleep(40);
+    };
+
+    const runs = ['a1', 'a2', 'a3'].map((name) => chatty.run('b', task(name)));
+    await sleep(10);
+    runs.push(quiet.run('b', task('b1')));
+    await Promise.all(runs);
+    expect(order).toEqual(['a1', 'a2', 'b1', 'a3']);
+  });
+
+  it('stops waiting once cancelled', async () => {
+    const scheduler = new LaneScheduler(() => new Lane(dir, 100, () => true));
+    scheduler.configure({ maxConcurrent: 1 });
+    const first = scheduler.run('b', () => sleep(50));
+    let ran = false;
+    await expect(
+      scheduler.run(
+        'b',
+        async () => {
+          ran = true;
+        },
+        () => true
+      )
+    ).rejects.toThrow('Cancelled');
+    await first;
+    expect(ran).toBe(false);
+  });
+});
@@ -0,0 +1,283 @@
+/**
+ * Fair sharing of one browser between sessions.
+ *
+ * Sessions attached to the same browser over CDP (`--cdp`, or `--existing`
+ * with the same BrowserOS profile) each have a daemon of their own, so one
+ * chatty agent can flood the browser while the others wait.
+ * `--max-concurrent <n>` caps the commands running on the browser at once,
+ * across every session attached to it, and `--action-rate <n>/<unit>` caps
+ * how often they may start. Commands over the limits queue. Each session
+ * holds one place in the shared queue at a time and the oldest place goes
+ * first, so sessions take turns however many commands each has waiting. A
+ * browser the session launched itself is only shared by the session's own
+ * clients.
+ *
+ * The daemons coordinate through a directory per browser under
+ * `~/.browseros/lanes/`: a file per running command (`slot-<n>`), one per
+ * waiting session (`queue/<ticket>`), and when the next rate-limited command
+ * may start (`next-start`). Files left by a daemon that died are removed by
+ * the next one to look.
+ */
+
+import { createHash } from 'node:crypto';
+import {
+  mkdirSync,
+  openSync,
+  closeSync,
+  readdirSync,
+  readFileSync,
+  unlinkSync,
+  writeFileSync,
+  writeSync,
+} from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import type { LaneLimits, LaneStatsData } from './types.js';
+
+const POLL_MS = 20;
+
+export function lanesDir(): string {
+  return path.join(os.homedir(), '.browseros', 'lanes');
+}
+
+/** The directory shared by every daemon attached to the browser `key` */
+export function laneDir(key: string): string {
+  return path.join(lanesDir(), createHash('sha256').update(key).digest('hex').slice(0, 16));
+}
+
+function pidAlive(pid: number): boolean {
+  try {
+    process.kill(pid, 0);
+    return true;
+  } catch (err) {
+    // EPERM: alive, but someone else's
+    return (err as NodeJS.ErrnoException).code === 'EPERM';
+  }
+}
+
+const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+/** The files of one lane directory */
+export class Lane {
+  private seq = 0;
+
+  constructor(
+    readonly dir: string,
+    private pid: number = process.pid,
+    private alive: (pid: number) => boolean = pidAlive
+  ) {
+    mkdirSync(path.join(dir, 'queue'), { recursive: true });
+  }
+
+  private owner(file: string): number {
+    try {
+      return parseInt(readFileSync(file, 'utf-8'), 10);
+    } catch {
+      return NaN;
+    }
+  }
+
+  /** Remove `file` if the daemon that wrote it is gone; true if it was */
+  private reap(file: string, pid: number = this.owner(file)): boolean {
+    if (!Number.isFinite(pid) || this.alive(pid)) return false;
+    try {
+      unlinkSync(file);
+    } catch {
+      // Someone else reaped it
+    }
+    return true;
+  }
+
+  /** Join the queue; tickets sort by when they were taken */
+  enqueue(): string {
+    const at = String(Date.now()).padStart(15, '0');
+    const ticket = `${at}-${String(this.seq++).padStart(6, '0')}-${this.pid}`;
+    writeFileSync(path.join(this.dir, 'queue', ticket), String(this.pid));
+    return ticket;
+  }
+
+  leave(ticket: string): void {
+    try {
+      unlinkSync(path.join(this.dir, 'queue', ticket));
+    } catch {
+      // Already gone
+    }
+  }
+
+  private tickets(): string[] {
+    let names: string[];
+    try {
+      names = readdirSync(path.join(this.dir, 'queue'));
+    } catch {
+      return [];
+    }
+    return names
+      .sort()
+      .filter(
+        (name) => !this.reap(path.join(this.dir, 'queue', name), parseInt(name.split('-')[2], 10))
+      );
+  }
+
+  /** The ticket whose turn it is */
+  head(): string | undefined {
+    return this.tickets()[0];
+  }
+
+  queued(): number {
+    return this.tickets().length;
+  }
+
+  private slots(): string[] {
+    try {
+      return readdirSync(this.dir).filter((name) => name.startsWith('slot-'));
+    } catch {
+      return [];
+    }
+  }
+
+  /** Commands running on the browser, from every session */
+  active(): number {
+    return this.slots().filter((name) => !this.reap(path.join(this.dir, name))).length;
+  }
+
+  /** Claim one of `max` slots; null if they're all taken */
+  takeSlot(max: number): string | null {
+    for (let i = 0; i < max; i++) {
+      const file = path.join(this.dir, `slot-${i}`);
+      for (let attempt = 0; attempt < 2; attempt++) {
+        try {
+          const fd = openSync(file, 'wx');
+          writeSync(fd, String(this.pid));
+          closeSync(fd);
+          return file;
+        } catch {
+          // Taken; free it up if its daemon died, then try once more
+          if (!this.reap(file)) break;
+        }
+      }
+    }
+    return null;
+  }
+
+  releaseSlot(file: string): void {
+    try {
+      unlinkSync(file);
+    } catch {
+      // Already gone
+    }
+  }
+
+  /**
+   * Milliseconds until a command may start under `interval`, reserving its
+   * start. Only the queue's head calls this, so reservations don't race.
+   */
+  reserveStart(interval: number, now: number = Date.now()): number {
+    const file = path.join(this.dir, 'next-start');
+    let next = 0;
+    try {
+      next = parseInt(readFileSync(file, 'utf-8'), 10) || 0;
+    } catch {
+      // First rate-limited command
+    }
+    const start = Math.max(now, next);
+    writeFileSync(file, String(start + interval));
+    return start - now;
+  }
+}
+
+/** Runs commands through the lane of the browser they go to */
+export class LaneScheduler {
+  private limits: LaneLimits = {};
+  private lanes = new Map<string, Lane>();
+  /** This daemon's commands waiting for their turn, in arrival order */
+  private waiting: (() => void)[] = [];
+  private queuing = false;
+  private stats = { scheduled: 0, delayed: 0, waitedMs: 0 };
+
+  constructor(private lane: (key: string) => Lane = (key) => new Lane(laneDir(key))) {}
+
+  configure(limits: LaneLimits): void {
+    this.limits = { ...this.limits, ...limits };
+    if (limits.maxConcurrent === 0) delete this.limits.maxConcurrent;
+    if (limits.actionRate === null) delete this.limits.actionRate;
+  }
+
+  get enabled(): boolean {
+    return this.limits.maxConcurrent !== undefined || this.limits.actionRate !== undefined;
+  }
+
+  private laneFor(key: string): Lane {
+    let lane = this.lanes.get(key);
+    if (!lane) {
+      lane = this.lane(key);
+      this.lanes.set(key, lane);
+    }
+    return lane;
+  }
+
+  /**
+   * Run `fn` once the browser `key` has room for it. Throws without running
+   * it if `stopped` turns true while it waits.
+   */
+  async run<T>(
+    key: string,
+    fn: () => Promise<T>,
+    stopped: () => boolean = () => false
+  ): Promise<T> {
+    if (!this.enabled) return fn();
+    const lane = this.laneFor(key);
+    const slot = await this.acquire(lane, stopped);
+    try {
+      return await fn();
+    } finally {
+      if (slot) lane.releaseSlot(slot);
+    }
+  }
+
+  private async acquire(lane: Lane, stopped: () => boolean): Promise<string | null> {
+    const started = Date.now();
+    // One place in the shared queue per session: the rest wait here
+    if (this.queuing) await new Promise<void>((resolve) => this.waiting.push(resolve));
+    this.queuing = true;
+    let slot: string | null = null;
+    let ticket: string | undefined;
+    try {
+      ticket = lane.enqueue();
+      const { maxConcurrent, actionRate } = this.limits;
+      while (lane.head() !== ticket || (maxConcurrent && !(slot = lane.takeSlot(maxConcurrent)))) {
+        if (stopped()) throw new Error('Cancelled while queued');
+        await sleep(POLL_MS);
+      }
+      const delay = actionRate ? lane.reserveStart(actionRate.per / actionRate.count) : 0;
+      lane.leave(ticket);
+      if (delay > 0) await sleep(delay);
+    } catch (err) {
+      if (slot) lane.releaseSlot(slot);
+      throw err;
+    } finally {
+      if (ticket) lane.leave(ticket);
+      const next = this.waiting.shift();
+      if (next) next();
+      else this.queuing = false;
+    }
+    const waited = Date.now() - started;
+    this.stats.scheduled++;
+    if (waited >= POLL_MS) {
+      this.stats.delayed++;
+      this.stats.waitedMs += waited;
+    }
+    return slot;
+  }
+
+  /** Limits, queue lengths and waits, for `lanes` and the metrics */
+  snapshot(key: string): LaneStatsData {
+    const lane = this.enabled ? this.laneFor(key) : null;
+    return {
+      ...this.limits,
+      waiting: this.waiting.length + (this.queuing ? 1 : 0),
+      queued: lane?.queued() ?? 0,
+      active: lane?.active() ?? 0,
+      ...this.stats,
+    };
+  }
+}
@@ -0,0 +1,61 @@
+import { describe, it, expect } from 'vitest';
+import { CHECK_INTERVAL_MS, MemoryWatchdog, formatBytes } from './memory-watchdog.js';
+
+const MB = 1_000_000;
+
+const tab = (index: number, heapUsed: number) => ({
+  index,
+  url: `https://example.com/${index}`,
+  heapUsed,
+  heapTotal: heapUsed * 2,
+});
+
+describe('MemoryWatchdog', () => {
+  it('only checks with a limit, and not too often', () => {
+    const watchdog = new MemoryWatchdog();
+    expect(watchdog.due(100_000)).toBe(false);
+
+    watchdog.configure(500 * MB, 'recreate');
+    expect(watchdog.mode).toBe('recreate');
+    expect(watchdog.due(100_000)).toBe(true);
+    expect(watchdog.due(100_000 + CHECK_INTERVAL_MS - 1)).toBe(false);
+    expect(watchdog.due(100_000 + CHECK_INTERVAL_MS)).toBe(true);
+
+    watchdog.configure(0);
+    expect(watchdog.limit).toBeNull();
+    expect(watchdog.mode).toBe('recreate');
+    expect(watchdog.due(1_000_000)).toBe(false);
+  });
+
+  it('picks the tabs over the limit, biggest first', () => {
+    const watchdog = new MemoryWatchdog();
+    expect(watchdog.over([tab(0, 900 * MB)])).toEqual([]);
+
+    watchdog.configure(500 * MB);
+    const over = watchdog.over([tab(0, 600 * MB), tab(1, 100 * MB), tab(2, 800 * MB)]);
+    expect(over.map((t) => t.index)).toEqual([2, 0]);
+  });
+
+  it('keeps the latest recycles and counts them all', () => {
+    const watchdog = new MemoryWatchdog();
+    for (let i = 0; i < 120; i++) {
+      watchdog.record({
+        at: i,
+        index: 0,
+        url: 'https://example.com/',
+        heapUsed: 600 * MB,
+        limit: 500 * MB,
+        mode: 'reload',
+      });
+    }
+    expect(watchdog.total).toBe(120);
+    expect(watchdog.recycles).toHaveLength(100);
+    expect(watchdog.recycles[0].at).toBe(20);
+  });
+
+  it('formats sizes', () => {
+    expect(formatBytes(512)).toBe('512 B');
+    expect(formatBytes(340 * MB)).toBe('340 MB');
+    expect(formatBytes(1_500 * MB)).toBe('1.5 GB');
+  });
+});
@@ -0,0 +1,70 @@
+/**
+ * Tab recycling for long-running sessions.
+ *
+ * A crawl that keeps one tab open for hours lets the page's JS heap grow
+ * until the browser runs out of memory. With `--max-tab-memory` set, the
+ * daemon measures each tab's JS heap over CDP (`Runtime.getHeapUsage`)
+ * between commands, at most every few seconds, and recycles the tabs over
+ * the limit: `reload` reloads the page, `recreate` (`--recycle-mode
+ * recreate`) opens the same URL in a fresh tab that takes the old one's
+ * place and closes the old one, which also frees what a reload can't, like
+ * detached DOM and workers. Either way the page's in-memory state is lost.
+ * Recycles are kept for `memory` and counted in the metrics.
+ */
+
+import type { RecycleMode, TabMemory, TabRecycle } from './types.js';
+
+/** Least time between two measurements */
+export const CHECK_INTERVAL_MS = 5_000;
+/** Recycles kept for `memory` */
+const MAX_RECYCLES = 100;
+
+export class MemoryWatchdog {
+  limit: number | null = null;
+  mode: RecycleMode = 'reload';
+  private lastCheck = 0;
+  private log: TabRecycle[] = [];
+  /** Recycles since the daemon started, including those dropped from the log */
+  total = 0;
+
+  configure(limit: number, mode?: RecycleMode): void {
+    this.limit = limit > 0 ? limit : null;
+    if (mode) this.mode = mode;
+  }
+
+  /** Whether tabs should be measured now; marks the check as done */
+  due(now: number = Date.now()): boolean {
+    if (this.limit === null || now - this.lastCheck < CHECK_INTERVAL_MS) return false;
+    this.lastCheck = now;
+    return true;
+  }
+
+  /** The tabs over the limit, biggest first */
+  over(tabs: TabMemory[]): TabMemory[] {
+    const limit = this.limit;
+    if (limit === null) return [];
+    return tabs.filter((tab) => tab.heapUsed > limit).sort((a, b) => b.heapUsed - a.heapUsed);
+  }
+
+  record(recycle: TabRecycle): void {
+    this.total++;
+    this.log.push(recycle);
+    if (this.log.length > MAX_RECYCLES) this.log.shift();
+  }
+
+  get recycles(): TabRecycle[] {
+    return [...this.log];
+  }
+}
+
+/** `1.2 GB`, `340 MB`, in powers of 1000 like the CLI's sizes */
+export function formatBytes(bytes: number): string {
+  const units = ['B', 'KB', 'MB', 'GB'];
+  let value = bytes;
+  let unit = 0;
+  while (value >= 1000 && unit < units.length - 1) {
+    value /= 1000;
+    unit++;
+  }
+  return `${unit === 0 ? value : value.toFixed(1).replace(/\.0$/, '')} ${units[unit]}`;
+}
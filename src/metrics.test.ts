@@ -0,0 +1,55 @@
+import { describe, it, expect } from 'vitest';
+import { Metrics } from './metrics.js';
+
+describe('Metrics', () => {
+  it('renders counters and latency histograms per session', () => {
+    const metrics = new Metrics('work');
+    metrics.record('navigate', { id: '1', success: true, data: {} }, 0.3);
+    metrics.record('click', { id: '2', success: false, error: 'Timeout', code: 'E_TIMEOUT' }, 5.5);
+    metrics.recordLaunch();
+    metrics.recordLaunch();
+    metrics.recordRecycles(2);
+
+    const text = metrics.render(true, { rss: 100, heapUsed: 50 });
+    expect(text).toContain('agent_browser_sessions_active{session="work"} 1');
+    expect(text).toContain('agent_browser_commands_total{session="work",action="click"} 1');
+    expect(text).toContain('agent_browser_failures_total{session="work",code="E_TIMEOUT"} 1');
+    expect(text).toContain('agent_browser_navigations_total{session="work"} 1');
+    expect(text).toContain('agent_browser_browser_restarts_total{session="work"} 1');
+    expect(text).toContain('agent_browser_tab_recycles_total{session="work"} 2');
+    expect(text).toContain(
+      'agent_browser_action_duration_seconds_bucket{session="work",action="navigate",le="0.25"} 0'
+    );
+    expect(text).toContain(
+      'agent_browser_action_duration_seconds_bucket{session="work",action="navigate",le="0.5"} 1'
+    );
+    expect(text).toContain(
+      'agent_browser_action_duration_seconds_count{session="work",action="click"} 1'
+    );
+    expect(text).toContain('agent_browser_daemon_memory_bytes{session="work",type="rss"} 100');
+  });
+
+  it('renders lane queues when limits are set', () => {
+    const metrics = new Metrics('work');
+    expect(metrics.render(true, { rss: 1, heapUsed: 1 })).not.toContain('agent_browser_lane');
+
+    const text = metrics.render(
+      true,
+      { rss: 1, heapUsed: 1 },
+      {
+        maxConcurrent: 2,
+        waiting: 3,
+        queued: 2,
+        active: 2,
+        scheduled: 9,
+        delayed: 4,
+        waitedMs: 1500,
+      }
+    );
+    expect(text).toContain('agent_browser_lane_waiting{session="work"} 3');
+    expect(text).toContain('agent_browser_lane_queued{session="work"} 2');
+    expect(text).toContain('agent_browser_lane_active{session="work"} 2');
+    expect(text).toContain('agent_browser_lane_delayed_total{session="work"} 4');
+    expect(text).toContain('agent_browser_lane_wait_seconds_total{session="work"} 1.5');
+  });
+});
@@ -0,0 +1,175 @@
+/**
+ * Prometheus metrics for a daemon.
+ *
+ * With `AGENT_BROWSER_METRICS_PORT` set, the daemon serves `GET /metrics` in
+ * the Prometheus text format. Every series carries a `session` label so one
+ * scrape config can cover a fleet of sessions, each on its own port.
+ */
+
+import * as http from 'http';
+import type { LaneStatsData, Response } from './types.js';
+
+/** Upper bounds (seconds) for the action latency histogram. */
+const LATENCY_BUCKETS = [0.05, 0.1, 0.25, 0.5, 1, 2.5, 5, 10, 30, 60];
+
+const NAVIGATION_ACTIONS = new Set(['navigate', 'back', 'forward', 'reload']);
+
+interface Histogram {
+  buckets: number[];
+  sum: number;
+  count: number;
+}
+
+export interface MemoryUsage {
+  rss: number;
+  heapUsed: number;
+}
+
+export class Metrics {
+  private commands = new Map<string, number>();
+  private failures = new Map<string, number>();
+  private latency = new Map<string, Histogram>();
+  private navigations = 0;
+  private launches = 0;
+  private recycles = 0;
+
+  constructor(private session: string) {}
+
+  /** Count a finished command and its latency. */
+  record(action: string, response: Response, seconds: number): void {
+    increment(this.commands, action);
+    if (!response.success) {
+      increment(this.failures, response.code ?? 'E_UNKNOWN');
+    } else if (NAVIGATION_ACTIONS.has(action)) {
+      this.navigations++;
+    }
+
+    let histogram = this.latency.get(action);
+    if (!histogram) {
+      histogram = { buckets: LATENCY_BUCKETS.map(() => 0), sum: 0, count: 0 };
+      this.latency.set(action, histogram);
+    }
+    LATENCY_BUCKETS.forEach((bound, i) => {
+      if (seconds <= bound) histogram.buckets[i]++;
+    });
+    histogram.sum += seconds;
+    histogram.count++;
+  }
+
+  /** Count a browser launch; every launch after the first is a restart. */
+  recordLaunch(): void {
+    this.launches++;
+  }
+
+  /** Count tabs recycled for using too much memory. */
+  recordRecycles(count: number): void {
+    this.recycles += count;
+  }
+
+  render(
+    active: boolean,
+    memory: MemoryUsage = process.memoryUsage(),
+    lanes: LaneStatsData | null = null
+  ): string {
+    const session = `session="${escapeLabel(this.session)}"`;
+    const lines: string[] = [];
+    const metric = (name: string, type: string, help: string) => {
+      lines.push(`# HELP ${name} ${help}`, `# TYPE ${name} ${type}`);
+    };
+
+    metric('agent_browser_sessions_active', 'gauge', 'Whether the session has a running browser.');
+    lines.push(`agent_browser_sessions_active{${session}} ${active ? 1 : 0}`);
+
+    metric('agent_browser_commands_total', 'counter', 'Commands handled, by action.');
+    for (const [action, n] of this.commands) {
+      lines.push(`agent_browser_commands_total{${session},action="${escapeLabel(action)}"} ${n}`);
+    }
+
+    metric('agent_browser_failures_total', 'counter', 'Failed commands, by error code.');
+    for (const [code, n] of this.failures) {
+      lines.push(`agent_browser_failures_total{${session},code="${escapeLabel(code)}"} ${n}`);
+    }
+
+    metric('agent_browser_navigations_total', 'counter', 'Successful navigations.');
+    lines.push(`agent_browser_navigations_total{${session}} ${this.navigations}`);
+
+    metric('agent_browser_browser_restarts_total', 'counter', 'Browser launches after the first.');
+    lines.push(
+      `agent_browser_browser_restarts_total{${session}} ${Math.max(this.launches - 1, 0)}`
+    );
+
+    metric('agent_browser_tab_recycles_total', 'counter', 'Tabs recycled over --max-tab-memory.');
+    lines.push(`agent_browser_tab_recycles_total{${session}} ${this.recycles}`);
+
+    metric(
+      'agent_browser_action_duration_seconds',
+      'histogram',
+      'Time to handle a command, by action.'
+    );
+    for (const [action, h] of this.latency) {
+      const labels = `${session},action="${escapeLabel(action)}"`;
+      LATENCY_BUCKETS.forEach((bound, i) => {
+        lines.push(
+          `agent_browser_action_duration_seconds_bucket{${labels},le="${bound}"} ${h.buckets[i]}`
+        );
+      });
+      lines.push(`agent_browser_action_duration_seconds_bucket{${labels},le="+Inf"} ${h.count}`);
+      lines.push(`agent_browser_action_duration_seconds_sum{${labels}} ${h.sum}`);
+      lines.push(`agent_browser_action_duration_seconds_count{${labels}} ${h.count}`);
+    }
+
+    metric('agent_browser_daemon_memory_bytes', 'gauge', 'Daemon process memory, by type.');
+    lines.push(`agent_browser_daemon_memory_bytes{${session},type="rss"} ${memory.rss}`);
+    lines.push(`agent_browser_daemon_memory_bytes{${session},type="heap_used"} ${memory.heapUsed}`);
+
+    // Only with --max-concurrent or --action-rate (see lanes.ts)
+    if (lanes) {
+      metric('agent_browser_lane_waiting', 'gauge', "The session's commands waiting for a turn.");
+      lines.push(`agent_browser_lane_waiting{${session}} ${lanes.waiting}`);
+      metric('agent_browser_lane_queued', 'gauge', 'Sessions waiting for the shared browser.');
+      lines.push(`agent_browser_lane_queued{${session}} ${lanes.queued}`);
+      metric('agent_browser_lane_active', 'gauge', 'Commands running on the shared browser.');
+      lines.push(`agent_browser_lane_active{${session}} ${lanes.active}`);
+      metric('agent_browser_lane_delayed_total', 'counter', 'Commands that waited for a turn.');
+      lines.push(`agent_browser_lane_delayed_total{${session}} ${lanes.delayed}`);
+      metric('agent_browser_lane_wait_seconds_total', 'counter', 'Time commands spent waiting.');
+      lines.push(`agent_browser_lane_wait_seconds_total{${session}} ${lanes.waitedMs / 1000}`);
+    }
+
+    return lines.join('\n') + '\n';
+  }
+}
+
+/**
+ * Serve `GET /metrics` on the given port. Binds to 127.0.0.1 unless
+ * `AGENT_BROWSER_METRICS_HOST` says otherwise.
+ */
+export async function startMetricsServer(
+  metrics: Metrics,
+  port: number,
+  isActive: () => boolean,
+  lanes: () => LaneStatsData | null = () => null
+): Promise<http.Server> {
+  const server = http.createServer((req, res) => {
+    if (req.method !== 'GET' || req.url?.split('?')[0] !== '/metrics') {
+      res.writeHead(404).end();
+      return;
+    }
+    res.writeHead(200, { 'Content-Type': 'text/plain; version=0.0.4' });
+    res.end(metrics.render(isActive(), process.memoryUsage(), lanes()));
+  });
+  const host = process.env.AGENT_BROWSER_METRICS_HOST || '127.0.0.1';
+  await new Promise<void>((resolve, reject) => {
+    server.once('error', reject);
+    server.listen(port, host, () => resolve());
+  });
+  return server;
+}
+
+function increment(map: Map<string, number>, key: string): void {
+  map.set(key, (map.get(key) ?? 0) + 1);
+}
+
+function escapeLabel(value: string): string {
+  return value.replace(/\\/g, '\\\\').replace(/"/g, '\\"').replace(/\n/g, '\\n');
+}
@@ -0,0 +1,254 @@
+/**
+ * The MessagePack subset the CLI and daemon exchange: the JSON data model,
+ * so a value round-trips exactly as `JSON.parse(JSON.stringify(value))`
+ * would. Byte strings are decoded to Buffers but never produced.
+ */
+
+class Writer {
+  private buf = Buffer.allocUnsafe(256);
+  length = 0;
+
+  private reserve(n: number): void {
+    if (this.length + n <= this.buf.length) return;
+    const next = Buffer.allocUnsafe(Math.max(this.buf.length * 2, this.length + n));
+    this.buf.copy(next, 0, 0, this.length);
+    this.buf = next;
+  }
+
+  byte(b: number): void {
+    this.reserve(1);
+    this.buf[this.length++] = b;
+  }
+
+  uint(marker: number, value: number, size: 1 | 2 | 4): void {
+    this.reserve(1 + size);
+    this.buf[this.length++] = marker;
+    this.buf.writeUIntBE(value, this.length, size);
+    this.length += size;
+  }
+
+  float(value: number): void {
+    this.reserve(9);
+    this.buf[this.length++] = 0xcb;
+    this.buf.writeDoubleBE(value, this.length);
+    this.length += 8;
+  }
+
+  int64(marker: 0xcf | 0xd3, value: number): void {
+    this.reserve(9);
+    this.buf[this.length++] = marker;
+    if (marker === 0xcf) this.buf.writeBigUInt64BE(BigInt(value), this.length);
+    else this.buf.writeBigInt64BE(BigInt(value), this.length);
+    this.length += 8;
+  }
+
+  str(value: string): void {
+    const size = Buffer.byteLength(value);
+    if (size < 32) this.byte(0xa0 | size);
+    else if (size < 0x100) this.uint(0xd9, size, 1);
+    else if (size < 0x10000) this.uint(0xda, size, 2);
+    else this.uint(0xdb, size, 4);
+    this.reserve(size);
+    this.buf.write(value, this.length, 'utf-8');
+    this.length += size;
+  }
+
+  done(): Buffer {
+    return this.buf.subarray(0, this.length);
+  }
+}
+
+function encodeNumber(w: Writer, value: number): void {
+  if (!Number.isFinite(value)) {
+    w.byte(0xc0);
+  } else if (!Number.isSafeInteger(value)) {
+    w.float(value);
+  } else if (value >= 0) {
+    if (value < 0x80) w.byte(value);
+    else if (value < 0x100) w.uint(0xcc, value, 1);
+    else if (value < 0x10000) w.uint(0xcd, value, 2);
+    else if (value < 0x100000000) w.uint(0xce, value, 4);
+    else w.int64(0xcf, value);
+  } else if (value >= -32) {
+    w.byte(value & 0xff);
+  } else if (value >= -0x80) {
+    w.uint(0xd0, value & 0xff, 1);
+  } else if (value >= -0x8000) {
+    w.uint(0xd1, value & 0xffff, 2);
+  } else if (value >= -0x80000000) {
+    w.uint(0xd2, value >>> 0, 4);
+  } else {
+    w.int64(0xd3, value);
+  }
+}
+
+function header(w: Writer, size: number, fix: number, m16: number, m32: number): void {
+  if (size < 16) w.byte(fix | size);
+  else if (size < 0x10000) w.uint(m16, size, 2);
+  else w.uint(m32, size, 4);
+}
+
+function encodeValue(w: Writer, value: unknown, inArray: boolean): void {
+  if (value !== null && typeof value === 'object' && 'toJSON' in value) {
+    value = (value as { toJSON(): unknown }).toJSON();
+  }
+  switch (typeof value) {
+    case 'string':
+      w.str(value);
+      return;
+    case 'number':
+      encodeNumber(w, value);
+      return;
+    case 'boolean':
+      w.byte(value ? 0xc3 : 0xc2);
+      return;
+    case 'bigint':
+      throw new TypeError('Do not know how to serialize a BigInt');
+    case 'object':
+      break;
+    default:
+      // undefined and functions: dropped from maps, null in arrays, like JSON
+      if (inArray) w.byte(0xc0);
+      return;
+  }
+  if (value === null) {
+    w.byte(0xc0);
+  } else if (Array.isArray(value)) {
+    header(w, value.length, 0x90, 0xdc, 0xdd);
+    for (const item of value) encodeValue(w, item, true);
+  } else {
+    const entries = Object.entries(value).filter(
+      ([, v]) => v !== undefined && typeof v !== 'function' && typeof v !== 'symbol'
+    );
+    header(w, entries.length, 0x80, 0xde, 0xdf);
+    for (const [key, v] of entries) {
+      w.str(key);
+      encodeValue(w, v, false);
+    }
+  }
+}
+
+export function encode(value: unknown): Buffer {
+  const w = new Writer();
+  encodeValue(w, value, true);
+  return w.done();
+}
+
+class Reader {
+  pos = 0;
+  constructor(private buf: Buffer) {}
+
+  need(n: number): void {
+    if (this.pos + n > this.buf.length) throw new Error('Truncated MessagePack data');
+  }
+
+  uint(size: 1 | 2 | 4): number {
+    this.need(size);
+    const value = this.buf.readUIntBE(this.pos, size);
+    this.pos += size;
+    return value;
+  }
+
+  int(size: 1 | 2 | 4): number {
+    this.need(size);
+    const value = this.buf.readIntBE(this.pos, size);
+    this.pos += size;
+    return value;
+  }
+
+  bytes(n: number): Buffer {
+    this.need(n);
+    const value = this.buf.subarray(this.pos, this.pos + n);
+    this.pos += n;
+    return value;
+  }
+
+  value(): unknown {
+    const b = this.uint(1);
+    if (b < 0x80) return b;
+    if (b < 0x90) return this.map(b & 0x0f);
+    if (b < 0xa0) return this.array(b & 0x0f);
+    if (b < 0xc0) return this.bytes(b & 0x1f).toString('utf-8');
+    if (b >= 0xe0) return b - 0x100;
+    switch (b) {
+      case 0xc0:
+        return null;
+      case 0xc2:
+        return false;
+      case 0xc3:
+        return true;
+      case 0xc4:
+        return Buffer.from(this.bytes(this.uint(1)));
+      case 0xc5:
+        return Buffer.from(this.bytes(this.uint(2)));
+      case 0xc6:
+        return Buffer.from(this.bytes(this.uint(4)));
+      case 0xca:
+        return this.bytes(4).readFloatBE(0);
+      case 0xcb:
+        return this.bytes(8).readDoubleBE(0);
+      case 0xcc:
+        return this.uint(1);
+      case 0xcd:
+        return this.uint(2);
+      case 0xce:
+        return this.uint(4);
+      case 0xcf:
+        return Number(this.bytes(8).readBigUInt64BE(0));
+      case 0xd0:
+        return this.int(1);
+      case 0xd1:
+        return this.int(2);
+      case 0xd2:
+        return this.int(4);
+      case 0xd3:
+        return Number(this.bytes(8).readBigInt64BE(0));
+      case 0xd9:
+        return this.bytes(this.uint(1)).toString('utf-8');
+      case 0xda:
+        return this.bytes(this.uint(2)).toString('utf-8');
+      case 0xdb:
+        return this.bytes(this.uint(4)).toString('utf-8');
+      case 0xdc:
+        return this.array(this.uint(2));
+      case 0xdd:
+        return this.array(this.uint(4));
+      case 0xde:
+        return this.map(this.uint(2));
+      case 0xdf:
+        return this.map(this.uint(4));
+      default:
+        throw new Error(`Unsupported MessagePack type 0x${b.toString(16)}`);
+    }
+  }
+
+  array(size: number): unknown[] {
+    const items: unknown[] = [];
+    for (let i = 0; i < size; i++) items.push(this.value());
+    return items;
+  }
+
+  map(size: number): Record<string, unknown> {
+    const out: Record<string, unknown> = {};
+    for (let i = 0; i < size; i++) {
+      const key = this.value();
+      if (typeof key !== 'string') throw new Error('MessagePack map keys must be strings');
+      const value = this.value();
+      // Like JSON.parse, a "__proto__" key is an own property rather than the prototype
+      Object.defineProperty(out, key, {
+        value,
+        enumerable: true,
+        writable: true,
+        configurable: true,
+      });
+    }
+    return out;
+  }
+}
+
+export function decode(buf: Buffer): unknown {
+  const r = new Reader(buf);
+  const value = r.value();
+  if (r.pos !== buf.length) throw new Error('Trailing bytes after MessagePack value');
+  return value;
+}
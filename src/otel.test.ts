@@ -0,0 +1,63 @@
+import { describe, it, expect, vi, afterEach } from 'vitest';
+import { actionKind, parseTraceparent, traceCommand, withSpan } from './otel.js';
+
+describe('otel', () => {
+  afterEach(() => {
+    vi.unstubAllGlobals();
+  });
+
+  it('parses W3C traceparent headers', () => {
+    expect(parseTraceparent('00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01')).toEqual({
+      traceId: '0af7651916cd43dd8448eb211c80319c',
+      spanId: 'b7ad6b7169203331',
+    });
+    expect(parseTraceparent('00-00000000000000000000000000000000-b7ad6b7169203331-01')).toBeNull();
+    expect(parseTraceparent('garbage')).toBeNull();
+  });
+
+  it('groups actions by kind', () => {
+    expect(actionKind('navigate')).toBe('navigation');
+    expect(actionKind('waitforloadstate')).toBe('wait');
+    expect(actionKind('snapshot')).toBe('extraction');
+    expect(actionKind('click')).toBe('interaction');
+  });
+
+  it('exports command and child spans under the CLI span', async () => {
+    const fetch = vi.fn().mockResolvedValue(new Response(null));
+    vi.stubGlobal('fetch', fetch);
+
+    const response = await traceCommand(
+      {
+        action: 'click',
+        traceparent: '00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01',
+        otelEndpoint: 'http://localhost:4318/',
+      },
+      () =>
+        withSpan('cdp Runtime.evaluate', {}, async () => ({
+          id: '1',
+          success: false as const,
+          error: 'Element not found',
+          code: 'E_SELECTOR_NOT_FOUND' as const,
+        }))
+    );
+    expect(response.success).toBe(false);
+
+    expect(fetch).toHaveBeenCalledOnce();
+    const [url, init] = fetch.mock.calls[0];
+    expect(url).toBe('http://localhost:4318/v1/traces');
+    const [child, command] = JSON.parse(init.body).resourceSpans[0].scopeSpans[0].spans;
+    expect(command.name).toBe('command click');
+    expect(command.parentSpanId).toBe('b7ad6b7169203331');
+    expect(command.status).toEqual({ code: 2, message: 'Element not found' });
+    expect(child.name).toBe('cdp Runtime.evaluate');
+    expect(child.parentSpanId).toBe(command.spanId);
+    expect(child.traceId).toBe('0af7651916cd43dd8448eb211c80319c');
+  });
+
+  it('runs untraced commands without exporting', async () => {
+    const fetch = vi.fn();
+    vi.stubGlobal('fetch', fetch);
+    await traceCommand({ action: 'click' }, async () => ({ id: '1', success: true, data: {} }));
+    expect(fetch).not.toHaveBeenCalled();
+  });
+});
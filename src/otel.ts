@@ -0,0 +1,242 @@
+/**
+ * OpenTelemetry spans for the daemon.
+ *
+ * When the CLI traces a command (`--otel-endpoint`), the request carries a W3C
+ * `traceparent` and the collector endpoint. The daemon then records a span for
+ * handling the command, with children for browser launch, overlay dismissal,
+ * retries and every CDP call, and sends them to `<endpoint>/v1/traces` as OTLP
+ * JSON once the response is written. Commands without a `traceparent` cost
+ * nothing beyond an AsyncLocalStorage lookup.
+ */
+
+import { AsyncLocalStorage } from 'node:async_hooks';
+import { randomBytes } from 'node:crypto';
+import type { CDPSession } from 'playwright-core';
+import type { Response } from './types.js';
+
+type AttributeValue = string | number | boolean;
+
+interface SpanRecord {
+  traceId: string;
+  spanId: string;
+  parentSpanId: string;
+  name: string;
+  kind: number;
+  startTimeUnixNano: string;
+  endTimeUnixNano: string;
+  attributes: { key: string; value: Record<string, AttributeValue> }[];
+  status: { code: number; message?: string };
+}
+
+interface ActiveSpan {
+  traceId: string;
+  spanId: string;
+  spans: SpanRecord[];
+}
+
+const KIND_INTERNAL = 1;
+const KIND_SERVER = 2;
+const KIND_CLIENT = 3;
+
+const NAVIGATION_ACTIONS = new Set(['navigate', 'back', 'forward', 'reload']);
+const EXTRACTION_ACTIONS = new Set([
+  'snapshot',
+  'screenshot',
+  'pdf',
+  'archive',
+  'freeze',
+  'content',
+  'evaluate',
+  'gettext',
+  'innertext',
+  'innerhtml',
+  'inputvalue',
+  'getattribute',
+  'count',
+  'boundingbox',
+  'styles',
+  'responsebody',
+  'title',
+  'url',
+  'isvisible',
+  'isenabled',
+  'ischecked',
+]);
+
+const storage = new AsyncLocalStorage<ActiveSpan>();
+
+/**
+ * Coarse category for a command, so a trace view can group time spent
+ * navigating, waiting, reading the page and acting on it.
+ */
+export function actionKind(action: string): string {
+  if (NAVIGATION_ACTIONS.has(action)) return 'navigation';
+  if (action.startsWith('wait')) return 'wait';
+  if (EXTRACTION_ACTIONS.has(action)) return 'extraction';
+  return 'interaction';
+}
+
+/** `{ traceId, spanId }` from a W3C `traceparent` header. */
+export function parseTraceparent(value: string): { traceId: string; spanId: string } | null {
+  const match = /^[0-9a-f]{2}-([0-9a-f]{32})-([0-9a-f]{16})-[0-9a-f]{2}$/i.exec(value.trim());
+  if (!match || /^0+$/.test(match[1]) || /^0+$/.test(match[2])) return null;
+  return { traceId: match[1].toLowerCase(), spanId: match[2].toLowerCase() };
+}
+
+/**
+ * Run a command inside a span continuing the CLI's trace, then export every
+ * span recorded while it ran. Untraced commands just run.
+ */
+export async function traceCommand(
+  command: { action: string; traceparent?: string; otelEndpoint?: string },
+  fn: () => Promise<Response>
+): Promise<Response> {
+  const parent = command.traceparent ? parseTraceparent(command.traceparent) : null;
+  if (!parent || !command.otelEndpoint) return fn();
+
+  const root: ActiveSpan = { traceId: parent.traceId, spanId: newSpanId(), spans: [] };
+  const start = nowNano();
+  let response: Response | undefined;
+  let thrown: unknown;
+  try {
+    response = await storage.run(root, fn);
+    return response;
+  } catch (err) {
+    thrown = err;
+    throw err;
+  } finally {
+    const error =
+      thrown !== undefined
+        ? thrown instanceof Error
+          ? thrown.message
+          : String(thrown)
+        : response && !response.success
+          ? response.error
+          : undefined;
+    root.spans.push(
+      record(root.traceId, root.spanId, parent.spanId, `command ${command.action}`, KIND_SERVER, start, {
+        attributes: {
+          'agent_browser.action': command.action,
+          'agent_browser.kind': actionKind(command.action),
+          ...(response && !response.success && response.code
+            ? { 'agent_browser.error_code': response.code }
+            : {}),
+        },
+        error,
+      })
+    );
+    // Don't hold up the next command on the collector
+    void exportSpans(command.otelEndpoint, root.spans);
+  }
+}
+
+/**
+ * Record `fn` as a child of the current span, if the command is traced.
+ */
+export async function withSpan<T>(
+  name: string,
+  attributes: Record<string, AttributeValue>,
+  fn: () => Promise<T>,
+  kind: number = KIND_INTERNAL
+): Promise<T> {
+  const parent = storage.getStore();
+  if (!parent) return fn();
+
+  const span: ActiveSpan = { traceId: parent.traceId, spanId: newSpanId(), spans: parent.spans };
+  const start = nowNano();
+  let error: string | undefined;
+  try {
+    return await storage.run(span, fn);
+  } catch (err) {
+    error = err instanceof Error ? err.message : String(err);
+    throw err;
+  } finally {
+    parent.spans.push(
+      record(span.traceId, span.spanId, parent.spanId, name, kind, start, { attributes, error })
+    );
+  }
+}
+
+/**
+ * Record a span for every `send` on a CDP session made while a traced command
+ * runs.
+ */
+export function traceCDP(session: CDPSession): CDPSession {
+  const send = session.send.bind(session);
+  session.send = ((method: string, params?: object) =>
+    withSpan(
+      `cdp ${method}`,
+      { 'cdp.method': method },
+      () => send(method as never, params as never),
+      KIND_CLIENT
+    )) as typeof session.send;
+  return session;
+}
+
+function record(
+  traceId: string,
+  spanId: string,
+  parentSpanId: string,
+  name: string,
+  kind: number,
+  start: string,
+  options: { attributes: Record<string, AttributeValue>; error?: string }
+): SpanRecord {
+  return {
+    traceId,
+    spanId,
+    parentSpanId,
+    name,
+    kind,
+    startTimeUnixNano: start,
+    endTimeUnixNano: nowNano(),
+    attributes: Object.entries(options.attributes).map(([key, value]) => ({
+      key,
+      value: anyValue(value),
+    })),
+    status: options.error !== undefined ? { code: 2, message: options.error } : { code: 1 },
+  };
+}
+
+function anyValue(value: AttributeValue): Record<string, AttributeValue> {
+  if (typeof value === 'boolean') return { boolValue: value };
+  if (typeof value === 'number') {
+    return Number.isInteger(value) ? { intValue: String(value) } : { doubleValue: value };
+  }
+  return { stringValue: value };
+}
+
+async function exportSpans(endpoint: string, spans: SpanRecord[]): Promise<void> {
+  const base = endpoint.replace(/\/+$/, '');
+  const url = base.endsWith('/v1/traces') ? base : `${base}/v1/traces`;
+  const body = {
+    resourceSpans: [
+      {
+        resource: {
+          attributes: [{ key: 'service.name', value: { stringValue: 'agent-browser-daemon' } }],
+        },
+        scopeSpans: [{ scope: { name: 'agent-browser' }, spans }],
+      },
+    ],
+  };
+  try {
+    await fetch(url, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify(body),
+      signal: AbortSignal.timeout(5000),
+    });
+  } catch {
+    // Tracing must never affect the command; the CLI reports its own export errors
+  }
+}
+
+function newSpanId(): string {
+  return randomBytes(8).toString('hex');
+}
+
+/** Wall-clock time in nanoseconds, with sub-millisecond precision. */
+function nowNano(): string {
+  const ms = performance.timeOrigin + performance.now();
+  return (BigInt(Math.floor(ms)) * 1_000_000n + BigInt(Math.round((ms % 1) * 1e6))).toString();
+}
@@ -0,0 +1,135 @@
+/**
+ * Overlay dismissal for cookie banners, newsletter modals, and app-install banners.
+ *
+ * Interstitials are a common reason for actions to fail with "intercepts pointer
+ * events". Dismissal runs in two passes:
+ *   1. Known accept/close buttons of widely used vendors (exact selectors)
+ *   2. Text and attribute heuristics, limited to fixed/sticky containers and
+ *      dialogs so that ordinary page buttons are never clicked
+ */
+
+import type { Page } from 'playwright-core';
+
+export type OverlayCategory = 'consent' | 'newsletter' | 'app-banner';
+
+export interface DismissedOverlay {
+  category: OverlayCategory;
+  target: string;
+}
+
+/**
+ * Curated buttons of common consent platforms and banner libraries
+ */
+const KNOWN_OVERLAY_BUTTONS: Array<{ category: OverlayCategory; selector: string }> = [
+  { category: 'consent', selector: '#onetrust-accept-btn-handler' }, // OneTrust
+  { category: 'consent', selector: '#CybotCookiebotDialogBodyLevelButtonLevelOptinAllowAll' }, // Cookiebot
+  { category: 'consent', selector: '#didomi-notice-agree-button' }, // Didomi
+  { category: 'consent', selector: '.fc-cta-consent' }, // Google Funding Choices
+  { category: 'consent', selector: '#truste-consent-button' }, // TrustArc
+  { category: 'consent', selector: '.cky-btn-accept' }, // CookieYes
+  { category: 'consent', selector: '.cc-allow' }, // Osano / cookieconsent
+  { category: 'consent', selector: '[data-testid="uc-accept-all-button"]' }, // Usercentrics
+  { category: 'consent', selector: '#sp-cc-accept' }, // Amazon
+  { category: 'consent', selector: 'button#L2AGLb' }, // Google
+  { category: 'app-banner', selector: '.smartbanner-close' },
+  { category: 'app-banner', selector: '.smartbanner__exit' },
+];
+
+/**
+ * Heuristic pass, evaluated in the page. Candidate containers must be dialogs or
+ * fixed/sticky elements whose attributes or text mention the overlay type. Matching
+ * buttons are tagged with data-ab-dismiss so they can be clicked with real input events.
+ */
+const HEURISTIC_SCRIPT = `() => {
+  const categories = [
+    {
+      category: 'consent',
+      container: /cookie|consent|gdpr|privacy|tracking/i,
+      button: /^(accept( all)?( cookies)?|allow( all)?( cookies)?|agree|i agree|i accept|got it|ok(ay)?|understood)$/i,
+    },
+    {
+      category: 'newsletter',
+      container: /newsletter|subscribe|sign ?up|mailing list|discount/i,
+      button: /^(no,? thanks|not now|maybe later|close|dismiss|×|✕|x)$/i,
+    },
+    {
+      category: 'app-banner',
+      container: /app-?banner|smart-?banner|download (our|the) app|open in app|get the app/i,
+      button: /^(not now|continue (in|on|to) (browser|web|site)|close|dismiss|×|✕|x)$/i,
+    },
+  ];
+
+  const isOverlay = (el) => {
+    if (el.getAttribute('role') === 'dialog' || el.getAttribute('aria-modal') === 'true') return true;
+    if (el.tagName === 'DIALOG' && el.open) return true;
+    const position = getComputedStyle(el).position;
+    return position === 'fixed' || position === 'sticky';
+  };
+  const isVisible = (el) => {
+    const r = el.getBoundingClientRect();
+    const s = getComputedStyle(el);
+    return r.width > 0 && r.height > 0 && s.visibility !== 'hidden' && s.display !== 'none';
+  };
+  const describe = (el) => {
+    const label = (el.getAttribute('aria-label') || el.textContent || '').trim().slice(0, 40);
+    return el.tagName.toLowerCase() + (label ? ' "' + label + '"' : '');
+  };
+
+  const found = [];
+  const containers = document.querySelectorAll('body *');
+  for (const container of containers) {
+    if (!isOverlay(container) || !isVisible(container)) continue;
+    // One button per overlay, even when dialogs are nested in fixed wrappers
+    if (container.querySelector('[data-ab-dismiss]')) continue;
+    const haystack = [container.id, container.className, container.getAttribute('aria-label'),
+      (container.textContent || '').slice(0, 500)].join(' ');
+    const match = categories.find((c) => c.container.test(haystack));
+    if (!match) continue;
+
+    const buttons = container.querySelectorAll('button, [role="button"], a, input[type="button"], input[type="submit"]');
+    for (const button of buttons) {
+      if (button.hasAttribute('data-ab-dismiss') || !isVisible(button)) continue;
+      const text = (button.getAttribute('aria-label') || button.textContent || button.value || '').trim();
+      if (!match.button.test(text)) continue;
+      button.setAttribute('data-ab-dismiss', String(found.length));
+      found.push({ category: match.category, target: describe(button) });
+      break;
+    }
+  }
+  return found;
+}`;
+
+/**
+ * Dismiss visible overlays. Returns what was dismissed, in click order.
+ */
+export async function dismissOverlays(page: Page): Promise<DismissedOverlay[]> {
+  const dismissed: DismissedOverlay[] = [];
+
+  for (const { category, selector } of KNOWN_OVERLAY_BUTTONS) {
+    const button = page.locator(selector).first();
+    if (!(await button.isVisible().catch(() => false))) continue;
+    try {
+      await button.click({ timeout: 1000 });
+      dismissed.push({ category, target: selector });
+    } catch {
+      // Banner went away or is not clickable; nothing to do
+    }
+  }
+
+  // eslint-disable-next-line @typescript-eslint/no-implied-eval
+  const findCandidates = new Function('return ' + HEURISTIC_SCRIPT)();
+  const candidates = ((await page.evaluate(findCandidates).catch(() => [])) ??
+    []) as DismissedOverlay[];
+  for (let i = 0; i < candidates.length; i++) {
+    const button = page.locator(`[data-ab-dismiss="${i}"]`);
+    try {
+      await button.click({ timeout: 1000 });
+      dismissed.push(candidates[i]);
+    } catch {
+      // Overlay closed itself or moved; skip it
+    }
+    await button.evaluate((el) => (el as any).removeAttribute('data-ab-dismiss')).catch(() => {});
+  }
+
+  return dismissed;
+}
@@ -0,0 +1,78 @@
+import { describe, it, expect } from 'vitest';
+import {
+  cumulativeLayoutShift,
+  resourceType,
+  summarizePerf,
+  totalBlockingTime,
+  type RawPerfEntries,
+} from './perf-audit.js';
+
+describe('perf audit', () => {
+  it('groups resources by extension, then initiator', () => {
+    expect(resourceType('link', 'https://a.com/app.css?v=2')).toBe('stylesheet');
+    expect(resourceType('other', 'https://a.com/font.woff2')).toBe('font');
+    expect(resourceType('script', 'https://a.com/loader')).toBe('script');
+    expect(resourceType('fetch', 'https://a.com/api/items')).toBe('fetch');
+    expect(resourceType('other', 'https://a.com/thing')).toBe('other');
+  });
+
+  it('takes the worst layout shift session window', () => {
+    const shift = (startTime: number, value: number, hadRecentInput = false) => ({
+      startTime,
+      value,
+      hadRecentInput,
+    });
+    // Second window starts after a gap over 1s and adds up to more
+    expect(
+      cumulativeLayoutShift([shift(0, 0.1), shift(500, 0.05), shift(2000, 0.1), shift(2500, 0.1)])
+    ).toBe(0.2);
+    expect(cumulativeLayoutShift([shift(0, 0.3, true)])).toBe(0);
+  });
+
+  it('counts blocking time over 50ms after first contentful paint', () => {
+    const tasks = [
+      { startTime: 100, duration: 300 },
+      { startTime: 600, duration: 120 },
+      { startTime: 900, duration: 40 },
+    ];
+    expect(totalBlockingTime(tasks, 500)).toBe(70);
+  });
+
+  it('summarizes sizes by type and lists the largest requests', () => {
+    const resource = (name: string, initiatorType: string, transfer: number, encoded: number) => ({
+      name,
+      initiatorType,
+      transferSize: transfer,
+      encodedBodySize: encoded,
+      duration: 50,
+    });
+    const raw: RawPerfEntries = {
+      url: 'https://a.com/',
+      navigation: null,
+      paints: [{ name: 'first-contentful-paint', startTime: 210.4 }],
+      lcp: [{ startTime: 300 }, { startTime: 640.6 }],
+      shifts: [],
+      longTasks: null,
+      resources: [
+        resource('https://a.com/a.js', 'script', 5000, 4800),
+        // Served from cache, so only the body size is known
+        resource('https://a.com/b.js', 'script', 0, 2000),
+        resource('https://a.com/c.png', 'img', 9000, 8900),
+        // Cross-origin without Timing-Allow-Origin
+        resource('https://cdn.com/d.png', 'img', 0, 0),
+      ],
+    };
+    const audit = summarizePerf(raw);
+    expect(audit.vitals).toEqual({ fcp: 210, lcp: 641, cls: 0, tbt: null });
+    expect(audit.totals).toEqual({ requests: 4, size: 16000 });
+    expect(audit.resources).toEqual([
+      { type: 'image', count: 2, size: 9000 },
+      { type: 'script', count: 2, size: 7000 },
+    ]);
+    expect(audit.largest.map((r) => r.url)).toEqual([
+      'https://a.com/c.png',
+      'https://a.com/a.js',
+      'https://a.com/b.js',
+    ]);
+  });
+});
@@ -0,0 +1,266 @@
+/**
+ * Page load performance audit.
+ *
+ * `audit perf` loads the page fresh (the current one, or the URL given) and
+ * reads the browser's performance timeline: navigation timing, paint and
+ * layout shift entries, long tasks and every resource. The vitals are lab
+ * approximations of the web-vitals definitions, taken from a single load
+ * without user input, so they are useful for comparing steps rather than as
+ * field numbers.
+ */
+
+import type { BrowserContext, Page } from 'playwright-core';
+import type { PerfAuditData, PerfResourceType } from './types.js';
+
+/** Long tasks aren't kept in the performance buffer, so record them from the start */
+const LONG_TASK_SCRIPT = `(() => {
+  if (window !== window.top || window.__agentBrowserLongTasks) return;
+  window.__agentBrowserLongTasks = [];
+  try {
+    new PerformanceObserver((list) => {
+      for (const e of list.getEntries()) {
+        window.__agentBrowserLongTasks.push({ startTime: e.startTime, duration: e.duration });
+      }
+    }).observe({ type: 'longtask', buffered: true });
+  } catch {}
+})()`;
+
+const LARGEST = 10;
+const SETTLE_TIMEOUT = 5000;
+
+/** Timeline entries as read from the page */
+export interface RawPerfEntries {
+  url: string;
+  navigation: {
+    startTime: number;
+    domainLookupStart: number;
+    domainLookupEnd: number;
+    connectStart: number;
+    connectEnd: number;
+    secureConnectionStart: number;
+    requestStart: number;
+    responseStart: number;
+    responseEnd: number;
+    domInteractive: number;
+    domContentLoadedEventEnd: number;
+    loadEventEnd: number;
+    transferSize: number;
+    encodedBodySize: number;
+  } | null;
+  paints: { name: string; startTime: number }[];
+  lcp: { startTime: number }[];
+  shifts: { startTime: number; value: number; hadRecentInput: boolean }[];
+  longTasks: { startTime: number; duration: number }[] | null;
+  resources: {
+    name: string;
+    initiatorType: string;
+    transferSize: number;
+    encodedBodySize: number;
+    duration: number;
+  }[];
+}
+
+const instrumented = new WeakSet<BrowserContext>();
+
+async function instrument(context: BrowserContext): Promise<void> {
+  if (instrumented.has(context)) return;
+  await context.addInitScript(LONG_TASK_SCRIPT);
+  instrumented.add(context);
+}
+
+/** Load `url` (or reload the current page), wait for it to settle and audit it. */
+export async function auditPerf(
+  page: Page,
+  url?: string,
+  settleTimeout = SETTLE_TIMEOUT
+): Promise<PerfAuditData> {
+  await instrument(page.context());
+  if (url) {
+    await page.goto(url, { waitUntil: 'load' });
+  } else {
+    await page.reload({ waitUntil: 'load' });
+  }
+  // Late images and scripts still move LCP, CLS and TBT after load
+  await page.waitForLoadState('networkidle', { timeout: settleTimeout }).catch(() => {});
+  return summarizePerf(await page.evaluate(readEntries));
+}
+
+/** Runs in the page. Buffered observers also return LCP and layout shifts. */
+async function readEntries(): Promise<RawPerfEntries> {
+  const observed = (type: string) =>
+    new Promise<PerformanceEntry[]>((resolve) => {
+      try {
+        const observer = new PerformanceObserver((list) => {
+          observer.disconnect();
+          resolve(list.getEntries());
+        });
+        observer.observe({ type, buffered: true });
+        setTimeout(() => {
+          observer.disconnect();
+          resolve([]);
+        }, 100);
+      } catch {
+        resolve([]);
+      }
+    });
+  const [lcp, shifts] = await Promise.all([
+    observed('largest-contentful-paint'),
+    observed('layout-shift'),
+  ]);
+  const nav = performance.getEntriesByType('navigation')[0] as PerformanceNavigationTiming;
+  const w = window as unknown as { __agentBrowserLongTasks?: RawPerfEntries['longTasks'] };
+  return {
+    url: location.href,
+    navigation: nav ? (nav.toJSON() as RawPerfEntries['navigation']) : null,
+    paints: performance.getEntriesByType('paint').map((e) => ({
+      name: e.name,
+      startTime: e.startTime,
+    })),
+    lcp: lcp.map((e) => ({ startTime: e.startTime })),
+    shifts: shifts.map((e) => {
+      const shift = e as PerformanceEntry & { value: number; hadRecentInput: boolean };
+      return { startTime: e.startTime, value: shift.value, hadRecentInput: shift.hadRecentInput };
+    }),
+    longTasks: w.__agentBrowserLongTasks ?? null,
+    resources: (performance.getEntriesByType('resource') as PerformanceResourceTiming[]).map(
+      (e) => ({
+        name: e.name,
+        initiatorType: e.initiatorType,
+        transferSize: e.transferSize,
+        encodedBodySize: e.encodedBodySize,
+        duration: e.duration,
+      })
+    ),
+  };
+}
+
+/** Group a resource by what it is, going by its extension first and then its initiator. */
+export function resourceType(initiatorType: string, url: string): PerfResourceType {
+  let path = url;
+  try {
+    path = new URL(url).pathname.toLowerCase();
+  } catch {}
+  if (/\.m?js$/.test(path)) return 'script';
+  if (/\.css$/.test(path)) return 'stylesheet';
+  if (/\.(woff2?|ttf|otf|eot)$/.test(path)) return 'font';
+  if (/\.(png|jpe?g|gif|webp|avif|svg|ico)$/.test(path)) return 'image';
+  if (/\.(mp4|webm|mp3|ogg|wav|m3u8)$/.test(path)) return 'media';
+  switch (initiatorType) {
+    case 'script':
+      return 'script';
+    case 'css':
+      return 'stylesheet';
+    case 'img':
+    case 'image':
+      return 'image';
+    case 'video':
+    case 'audio':
+      return 'media';
+    case 'fetch':
+    case 'xmlhttprequest':
+    case 'beacon':
+      return 'fetch';
+    case 'iframe':
+    case 'frame':
+    case 'navigation':
+      return 'document';
+    default:
+      return 'other';
+  }
+}
+
+/**
+ * CLS as web-vitals defines it: the largest total of shifts within a session
+ * window, where a window ends after a 1s gap or 5s in all.
+ */
+export function cumulativeLayoutShift(shifts: RawPerfEntries['shifts']): number {
+  let worst = 0;
+  let current = 0;
+  let windowStart = 0;
+  let last = -Infinity;
+  for (const shift of shifts) {
+    if (shift.hadRecentInput) continue;
+    if (shift.startTime - last > 1000 || shift.startTime - windowStart > 5000) {
+      current = 0;
+      windowStart = shift.startTime;
+    }
+    current += shift.value;
+    last = shift.startTime;
+    worst = Math.max(worst, current);
+  }
+  return Math.round(worst * 1000) / 1000;
+}
+
+/** TBT approximation: the part over 50ms of every long task after first contentful paint. */
+export function totalBlockingTime(
+  longTasks: { startTime: number; duration: number }[],
+  fcp: number
+): number {
+  return Math.round(
+    longTasks
+      .filter((task) => task.startTime >= fcp)
+      .reduce((sum, task) => sum + Math.max(0, task.duration - 50), 0)
+  );
+}
+
+export function summarizePerf(raw: RawPerfEntries): PerfAuditData {
+  const ms = (value: number) => Math.round(value);
+  const nav = raw.navigation;
+  const fcp = raw.paints.find((p) => p.name === 'first-contentful-paint')?.startTime ?? null;
+  const lcp = raw.lcp.length > 0 ? raw.lcp[raw.lcp.length - 1].startTime : null;
+
+  // Cached and cross-origin responses without Timing-Allow-Origin report 0 bytes
+  const entries = raw.resources.map((r) => ({
+    url: r.name,
+    type: resourceType(r.initiatorType, r.name),
+    size: r.transferSize || r.encodedBodySize,
+    duration: ms(r.duration),
+  }));
+  if (nav) {
+    entries.unshift({
+      url: raw.url,
+      type: 'document',
+      size: nav.transferSize || nav.encodedBodySize,
+      duration: ms(nav.responseEnd - nav.startTime),
+    });
+  }
+
+  const byType = new Map<PerfResourceType, PerfAuditData['resources'][number]>();
+  for (const entry of entries) {
+    const group = byType.get(entry.type) ?? { type: entry.type, count: 0, size: 0 };
+    group.count++;
+    group.size += entry.size;
+    byType.set(entry.type, group);
+  }
+
+  return {
+    url: raw.url,
+    navigation: nav
+      ? {
+          ttfb: ms(nav.responseStart - nav.startTime),
+          dns: ms(nav.domainLookupEnd - nav.domainLookupStart),
+          connect: ms(nav.connectEnd - nav.connectStart),
+          tls: nav.secureConnectionStart > 0 ? ms(nav.connectEnd - nav.secureConnectionStart) : 0,
+          download: ms(nav.responseEnd - nav.responseStart),
+          domInteractive: ms(nav.domInteractive - nav.startTime),
+          domContentLoaded: ms(nav.domContentLoadedEventEnd - nav.startTime),
+          load: ms(nav.loadEventEnd - nav.startTime),
+        }
+      : null,
+    vitals: {
+      fcp: fcp === null ? null : ms(fcp),
+      lcp: lcp === null ? null : ms(lcp),
+      cls: cumulativeLayoutShift(raw.shifts),
+      tbt: raw.longTasks ? totalBlockingTime(raw.longTasks, fcp ?? 0) : null,
+    },
+    totals: {
+      requests: entries.length,
+      size: entries.reduce((sum, entry) => sum + entry.size, 0),
+    },
+    resources: [...byType.values()].sort((a, b) => b.size - a.size || b.count - a.count),
+    largest: entries
+      .filter((entry) => entry.size > 0)
+      .sort((a, b) => b.size - a.size)
+      .slice(0, LARGEST),
+  };
+}
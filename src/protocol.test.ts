@@ -112,6 +112,23 @@ describe('parseCommand', () => {
     });
   });
 
+  describe('event waits', () => {
+    it('should parse armed waitfordownload', () => {
+      const result = parseCommand(cmd({ id: '1', action: 'waitfordownload', arm: true }));
+      expect(result.success).toBe(true);
+    });
+
+    it('should parse waitforpopup', () => {
+      const result = parseCommand(cmd({ id: '1', action: 'waitforpopup', timeout: 5000 }));
+      expect(result.success).toBe(true);
+    });
+
+    it('should reject waitforrequest without url', () => {
+      const result = parseCommand(cmd({ id: '1', action: 'waitforrequest', arm: true }));
+      expect(result.success).toBe(false);
+    });
+  });
+
   describe('screenshot', () => {
     it('should parse screenshot command', () => {
       const result = parseCommand(cmd({ id: '1', action: 'screenshot', path: 'test.png' }));
@@ -409,6 +426,28 @@ describe('parseCommand', () => {
       expect(result.success).toBe(true);
     });
 
+    it('should parse targets and eval with a target', () => {
+      expect(parseCommand(cmd({ id: '1', action: 'targets' })).success).toBe(true);
+      const result = parseCommand(
+        cmd({ id: '1', action: 'evaluate', script: 'self.name', target: 'w1' })
+      );
+      expect(result.success).toBe(true);
+      expect(parseCommand(cmd({ id: '1', action: 'evaluate', script: '1', target: '' })).success).toBe(
+        false
+      );
+    });
+
+    it('should parse input record, stop and replay', () => {
+      expect(
+        parseCommand(cmd({ id: '1', action: 'input', op: 'record', path: '/tmp/t.json' })).success
+      ).toBe(true);
+      expect(parseCommand(cmd({ id: '1', action: 'input', op: 'stop' })).success).toBe(true);
+      expect(
+        parseCommand(cmd({ id: '1', action: 'input', op: 'replay', path: '/tmp/t.json', speed: 0 }))
+          .success
+      ).toBe(false);
+    });
+
     it('should parse tab_switch', () => {
       const result = parseCommand(cmd({ id: '1', action: 'tab_switch', index: 0 }));
       expect(result.success).toBe(true);
@@ -420,6 +459,222 @@ describe('parseCommand', () => {
     });
   });
 
+  describe('session options', () => {
+    it('should parse session_options', () => {
+      const result = parseCommand(
+        cmd({ id: '1', action: 'session_options', popupPolicy: 'follow', autoDismissOverlays: true })
+      );
+      expect(result.success).toBe(true);
+    });
+
+    it('should parse rate limits', () => {
+      const rateLimits = {
+        requests: [{ count: 5, per: 1000 }, { host: 'example.com', count: 1, per: 1000 }],
+        navigations: [],
+      };
+      expect(parseCommand(cmd({ id: '1', action: 'session_options', rateLimits })).success).toBe(
+        true
+      );
+      const zero = { requests: [{ count: 0, per: 1000 }] };
+      expect(
+        parseCommand(cmd({ id: '1', action: 'session_options', rateLimits: zero })).success
+      ).toBe(false);
+    });
+
+    it('should parse audit_perf', () => {
+      expect(parseCommand(cmd({ id: '1', action: 'audit_perf' })).success).toBe(true);
+      expect(
+        parseCommand(cmd({ id: '1', action: 'audit_perf', url: 'https://example.com' })).success
+      ).toBe(true);
+      expect(parseCommand(cmd({ id: '1', action: 'audit_perf', url: '' })).success).toBe(false);
+    });
+
+    it('should parse audit_seo', () => {
+      expect(parseCommand(cmd({ id: '1', action: 'audit_seo', links: 0 })).success).toBe(true);
+      expect(parseCommand(cmd({ id: '1', action: 'audit_seo', links: -1 })).success).toBe(false);
+    });
+
+    it('should parse tls_info and launch certificate options', () => {
+      expect(parseCommand(cmd({ id: '1', action: 'tls_info', url: 'https://a.test' })).success).toBe(
+        true
+      );
+      const launch = cmd({
+        id: '1',
+        action: 'launch',
+        extraCa: ['/etc/ssl/corp-root.pem'],
+        ignoreCertErrorsFor: ['dev.local', '*.corp.example'],
+      });
+      expect(parseCommand(launch).success).toBe(true);
+    });
+
+    it('should parse launch client certificates', () => {
+      const launch = (certificate: Record<string, unknown>) =>
+        parseCommand(cmd({ id: '1', action: 'launch', clientCertificates: [certificate] }));
+      const origin = 'https://vpn.corp.example';
+      expect(launch({ origin, certPath: '/me.pem', keyPath: '/me.key' }).success).toBe(true);
+      expect(launch({ origin, pfxPath: '/me.p12', passphrase: 'pw' }).success).toBe(true);
+      expect(launch({ origin, certPath: '/me.pem' }).success).toBe(false);
+      expect(launch({ origin: 'vpn.corp.example', pfxPath: '/me.p12' }).success).toBe(false);
+    });
+
+    it('should parse launch auth allowlists, stealth, deterministic and init scripts', () => {
+      const launch = cmd({
+        id: '1',
+        action: 'launch',
+        authServerAllowlist: ['*.corp.example', 'intranet'],
+        authDelegateAllowlist: ['sso.corp.example'],
+        stealth: true,
+        deterministic: true,
+        initScripts: ['/home/me/shim.js'],
+      });
+      expect(parseCommand(launch).success).toBe(true);
+      expect(
+        parseCommand(cmd({ id: '1', action: 'launch', authServerAllowlist: [''] })).success
+      ).toBe(false);
+    });
+
+    it('should parse launch remote options', () => {
+      const remote = (extra: Record<string, unknown>) =>
+        parseCommand(cmd({ id: '1', action: 'launch', remote: 'wss://pool.example.com', ...extra }));
+      expect(remote({ remoteToken: 'abc', remoteKeepAlive: 30000 }).success).toBe(true);
+      expect(remote({ remoteKeepAlive: 0 }).success).toBe(true);
+      expect(remote({ remoteKeepAlive: -1 }).success).toBe(false);
+      expect(remote({ remote: '' }).success).toBe(false);
+    });
+
+    it('should parse watch', () => {
+      expect(
+        parseCommand(
+          cmd({ id: '1', action: 'watch', selector: '#feed', events: ['childList'], count: 1 })
+        ).success
+      ).toBe(true);
+      expect(parseCommand(cmd({ id: '1', action: 'watch', selector: '' })).success).toBe(false);
+      expect(
+        parseCommand(cmd({ id: '1', action: 'watch', selector: '#feed', events: ['style'] })).success
+      ).toBe(false);
+    });
+
+    it('should parse poll', () => {
+      const poll = (extra: Record<string, unknown>) =>
+        parseCommand(cmd({ id: '1', action: 'poll', selector: '.price', interval: 30000, ...extra }));
+      expect(poll({ untilChanged: true, max: 20 }).success).toBe(true);
+      expect(poll({ untilMatch: '^done$', attribute: 'data-state', reload: true }).success).toBe(true);
+      expect(poll({ untilMatch: '(' }).success).toBe(false);
+      expect(poll({ interval: 0 }).success).toBe(false);
+    });
+
+    it('should parse schedule commands', () => {
+      const add = cmd({ id: '1', action: 'schedule_add', cron: '0 * * * *', command: ['reload'] });
+      expect(parseCommand(add).success).toBe(true);
+      expect(
+        parseCommand(cmd({ id: '1', action: 'schedule_add', cron: '@daily', command: [] })).success
+      ).toBe(false);
+      expect(parseCommand(cmd({ id: '1', action: 'schedule_list' })).success).toBe(true);
+      const logs = cmd({ id: '1', action: 'schedule_logs', jobId: '1' });
+      expect(parseCommand(logs).success).toBe(true);
+      expect(parseCommand(cmd({ id: '1', action: 'schedule_remove' })).success).toBe(false);
+    });
+
+    it('should parse jobs', () => {
+      expect(parseCommand(cmd({ id: '1', action: 'jobs' })).success).toBe(true);
+    });
+
+    it('should parse which', () => {
+      expect(parseCommand(cmd({ id: '1', action: 'which' })).success).toBe(true);
+      expect(
+        parseCommand(cmd({ id: '1', action: 'which', executablePath: '/opt/BrowserOS' })).success
+      ).toBe(true);
+      expect(parseCommand(cmd({ id: '1', action: 'which', executablePath: '' })).success).toBe(
+        false
+      );
+      expect(parseCommand(cmd({ id: '1', action: 'which', channel: 'edge' })).success).toBe(true);
+    });
+
+    it('should parse launch channels', () => {
+      const launch = (channel: string) =>
+        parseCommand(cmd({ id: '1', action: 'launch', channel })).success;
+      expect(launch('chrome')).toBe(true);
+      expect(launch('path:/opt/chrome/chrome')).toBe(true);
+      expect(launch('path:')).toBe(false);
+      expect(launch('firefox')).toBe(false);
+    });
+
+    it('should parse clock commands', () => {
+      const set = cmd({
+        id: '1',
+        action: 'clock_set',
+        time: '2024-06-01T10:00:00Z',
+        tick: true,
+        timezone: 'Europe/Berlin',
+      });
+      expect(parseCommand(set).success).toBe(true);
+      expect(parseCommand(cmd({ id: '1', action: 'clock_advance', ms: 60000 })).success).toBe(true);
+      expect(parseCommand(cmd({ id: '1', action: 'clock_advance', ms: 0 })).success).toBe(false);
+      expect(parseCommand(cmd({ id: '1', action: 'clock_resume' })).success).toBe(true);
+    });
+
+    it('should parse audit_security', () => {
+      expect(parseCommand(cmd({ id: '1', action: 'audit_security' })).success).toBe(true);
+    });
+
+    it('should parse robots and respectRobots', () => {
+      expect(
+        parseCommand(cmd({ id: '1', action: 'robots', url: 'https://example.com/a' })).success
+      ).toBe(true);
+      expect(parseCommand(cmd({ id: '1', action: 'robots' })).success).toBe(false);
+      expect(
+        parseCommand(cmd({ id: '1', action: 'session_options', respectRobots: true })).success
+      ).toBe(true);
+    });
+
+    it('should reject unknown popup policy', () => {
+      const result = parseCommand(cmd({ id: '1', action: 'session_options', popupPolicy: 'allow' }));
+      expect(result.success).toBe(false);
+    });
+
+    it('should parse dismiss_overlays', () => {
+      const result = parseCommand(cmd({ id: '1', action: 'dismiss_overlays' }));
+      expect(result.success).toBe(true);
+    });
+
+    it('should parse stats', () => {
+      const result = parseCommand(cmd({ id: '1', action: 'stats', reset: true }));
+      expect(result.success).toBe(true);
+    });
+
+    it('should parse trace_step', () => {
+      const result = parseCommand(cmd({ id: '1', action: 'trace_step', reset: true }));
+      expect(result.success).toBe(true);
+    });
+
+    it('should parse cdp', () => {
+      const result = parseCommand(
+        cmd({
+          id: '1',
+          action: 'cdp',
+          method: 'Page.reload',
+          params: { ignoreCache: true },
+          events: ['Page.loadEventFired'],
+          wait: 5000,
+        })
+      );
+      expect(result.success).toBe(true);
+    });
+
+    it('should parse cdp_listen', () => {
+      const result = parseCommand(
+        cmd({ id: '1', action: 'cdp_listen', events: ['Network.responseReceived'], count: 1 })
+      );
+      expect(result.success).toBe(true);
+      expect(parseCommand(cmd({ id: '1', action: 'cdp_listen', events: [] })).success).toBe(false);
+    });
+
+    it('should reject cdp without a Domain.method', () => {
+      const result = parseCommand(cmd({ id: '1', action: 'cdp', method: 'reload' }));
+      expect(result.success).toBe(false);
+    });
+  });
+
   describe('snapshot', () => {
     it('should parse basic snapshot command', () => {
       const result = parseCommand(cmd({ id: '1', action: 'snapshot' }));
@@ -619,6 +874,11 @@ describe('parseCommand', () => {
       const result = parseCommand(cmd({ id: '1', action: 'trace_stop', path: 'trace.zip' }));
       expect(result.success).toBe(true);
     });
+
+    it('should parse trace_stop without a path', () => {
+      const result = parseCommand(cmd({ id: '1', action: 'trace_stop' }));
+      expect(result.success).toBe(true);
+    });
   });
 
   describe('console and errors', () => {
@@ -638,6 +898,18 @@ describe('parseCommand', () => {
     });
   });
 
+  describe('inspect', () => {
+    it('should parse inspect', () => {
+      const result = parseCommand(cmd({ id: '1', action: 'inspect', selector: '@e1' }));
+      expect(result.success).toBe(true);
+    });
+
+    it('should reject inspect without selector', () => {
+      const result = parseCommand(cmd({ id: '1', action: 'inspect' }));
+      expect(result.success).toBe(false);
+    });
+  });
+
   describe('dialog', () => {
     it('should parse dialog accept', () => {
       const result = parseCommand(cmd({ id: '1', action: 'dialog', response: 'accept' }));
@@ -703,6 +975,19 @@ describe('parseCommand', () => {
       }
     });
 
+    it('should parse screencast_start to a file with scale and fps', () => {
+      const result = parseCommand(
+        cmd({ id: '1', action: 'screencast_start', path: 'frames.mjpeg', scale: 0.5, fps: 5 })
+      );
+      expect(result.success).toBe(true);
+      expect(parseCommand(cmd({ id: '1', action: 'screencast_start', scale: 2 })).success).toBe(
+        false
+      );
+      expect(parseCommand(cmd({ id: '1', action: 'screencast_start', fps: 0 })).success).toBe(
+        false
+      );
+    });
+
     it('should reject screencast_start with invalid format', () => {
       const result = parseCommand(cmd({ id: '1', action: 'screencast_start', format: 'gif' }));
       expect(result.success).toBe(false);
@@ -1,12 +1,42 @@
 import { z } from 'zod';
 import type { Command, Response } from './types.js';
+import { classifyError, type ErrorCode } from './errors.js';
 
 // Base schema for all commands
+const timeoutSettingSchema = z.object({
+  ms: z.number().int().positive(),
+  source: z.string(),
+});
+
 const baseCommandSchema = z.object({
   id: z.string(),
   action: z.string(),
+  // Retry policy for element actions (see executeCommand)
+  retries: z.number().int().nonnegative().optional(),
+  retryBackoff: z.number().nonnegative().optional(),
+  // Human-like input for click, type and scroll (see humanize.ts)
+  humanize: z.boolean().optional(),
+  humanizeSeed: z.number().int().nonnegative().optional(),
+  robotsOverride: z.boolean().optional(),
+  allowPurchases: z.boolean().optional(),
+  // OpenTelemetry trace context from the CLI (see otel.ts)
+  traceparent: z.string().optional(),
+  otelEndpoint: z.string().optional(),
+  // Layered timeouts resolved by the CLI (see timeouts.ts)
+  timeouts: z
+    .object({
+      action: timeoutSettingSchema.optional(),
+      navigation: timeoutSettingSchema.optional(),
+      idle: timeoutSettingSchema.optional(),
+    })
+    .optional(),
 });
 
+// Chromium-based browser to find (see executable.ts)
+const channelSchema = z
+  .string()
+  .regex(/^(browseros|chrome|edge|chromium|path:.+)$/, 'Expected browseros, chrome, edge, chromium or path:<exe>');
+
 // Individual action schemas
 const launchSchema = baseCommandSchema.extend({
   action: z.literal('launch'),
@@ -18,6 +48,7 @@ const launchSchema = baseCommandSchema.extend({
     })
     .optional(),
   browser: z.enum(['chromium', 'firefox', 'webkit']).optional(),
+  channel: channelSchema.optional(),
   cdpPort: z.number().positive().optional(),
   cdpUrl: z
     .string()
@@ -46,6 +77,31 @@ const launchSchema = baseCommandSchema.extend({
   userAgent: z.string().optional(),
   provider: z.string().optional(),
   ignoreHTTPSErrors: z.boolean().optional(),
+  extraCa: z.array(z.string().min(1)).optional(),
+  ignoreCertErrorsFor: z.array(z.string().min(1)).optional(),
+  clientCertificates: z
+    .array(
+      z
+        .object({
+          origin: z.string().url(),
+          certPath: z.string().min(1).optional(),
+          keyPath: z.string().min(1).optional(),
+          pfxPath: z.string().min(1).optional(),
+          passphrase: z.string().optional(),
+        })
+        .refine((c) => (c.certPath && c.keyPath) || c.pfxPath, {
+          message: 'A client certificate needs certPath and keyPath, or pfxPath',
+        })
+    )
+    .optional(),
+  authServerAllowlist: z.array(z.string().min(1)).optional(),
+  authDelegateAllowlist: z.array(z.string().min(1)).optional(),
+  stealth: z.boolean().optional(),
+  deterministic: z.boolean().optional(),
+  initScripts: z.array(z.string().min(1)).optional(),
+  remote: z.string().min(1).optional(),
+  remoteToken: z.string().optional(),
+  remoteKeepAlive: z.number().int().nonnegative().optional(),
   profile: z.string().optional(),
   storageState: z.string().optional(),
 });
@@ -203,12 +259,33 @@ const dialogSchema = baseCommandSchema.extend({
 
 const pdfSchema = baseCommandSchema.extend({
   action: z.literal('pdf'),
-  path: z.string().min(1),
+  path: z.string().min(1).optional(),
   format: z
     .enum(['Letter', 'Legal', 'Tabloid', 'Ledger', 'A0', 'A1', 'A2', 'A3', 'A4', 'A5', 'A6'])
     .optional(),
 });
 
+const archiveSchema = baseCommandSchema.extend({
+  action: z.literal('archive'),
+  format: z.enum(['mhtml', 'warc']),
+  path: z.string().min(1).optional(),
+});
+
+const freezeSchema = baseCommandSchema.extend({
+  action: z.literal('freeze'),
+  path: z.string().min(1).optional(),
+});
+
+const selectorsSuggestSchema = baseCommandSchema.extend({
+  action: z.literal('selectors_suggest'),
+  selector: z.string().min(1),
+});
+
+const selectorsHealSchema = baseCommandSchema.extend({
+  action: z.literal('selectors_heal'),
+  selector: z.string().min(1),
+});
+
 const routeSchema = baseCommandSchema.extend({
   action: z.literal('route'),
   url: z.string().min(1),
@@ -364,7 +441,48 @@ const traceStartSchema = baseCommandSchema.extend({
 
 const traceStopSchema = baseCommandSchema.extend({
   action: z.literal('trace_stop'),
+  path: z.string().min(1).optional(),
+});
+
+const captureStartSchema = baseCommandSchema.extend({
+  action: z.literal('capture_start'),
+  url: z.string().min(1),
+  out: z.string().min(1),
+  maxSize: z.number().int().positive().optional(),
+  mime: z.array(z.string().min(1)).optional(),
+});
+
+const captureStopSchema = baseCommandSchema.extend({
+  action: z.literal('capture_stop'),
+});
+
+const apisSchema = baseCommandSchema.extend({
+  action: z.literal('apis'),
+  filter: z.string().optional(),
+  clear: z.boolean().optional(),
+});
+
+const inputSchema = baseCommandSchema.extend({
+  action: z.literal('input'),
+  op: z.enum(['record', 'stop', 'replay']),
+  path: z.string().min(1).optional(),
+  speed: z.number().positive().optional(),
+});
+
+const wsSchema = baseCommandSchema.extend({
+  action: z.literal('ws'),
+  op: z.enum(['list', 'tail', 'export', 'clear']),
+  socket: z.string().min(1).optional(),
+  count: z.number().int().positive().optional(),
+  path: z.string().min(1).optional(),
+});
+
+const harReplaySchema = baseCommandSchema.extend({
+  action: z.literal('har_replay'),
   path: z.string().min(1),
+  url: z.string().min(1).optional(),
+  notFound: z.enum(['abort', 'fallback']).optional(),
+  match: z.string().min(1).optional(),
 });
 
 const harStartSchema = baseCommandSchema.extend({
@@ -386,6 +504,16 @@ const stateLoadSchema = baseCommandSchema.extend({
   path: z.string().min(1),
 });
 
+const sessionSaveSchema = baseCommandSchema.extend({
+  action: z.literal('session_save'),
+  path: z.string().min(1),
+});
+
+const sessionRestoreSchema = baseCommandSchema.extend({
+  action: z.literal('session_restore'),
+  path: z.string().min(1),
+});
+
 const consoleSchema = baseCommandSchema.extend({
   action: z.literal('console'),
   clear: z.boolean().optional(),
@@ -424,6 +552,11 @@ const highlightSchema = baseCommandSchema.extend({
   selector: z.string().min(1),
 });
 
+const inspectSchema = baseCommandSchema.extend({
+  action: z.literal('inspect'),
+  selector: z.string().min(1),
+});
+
 const clearSchema = baseCommandSchema.extend({
   action: z.literal('clear'),
   selector: z.string().min(1),
@@ -629,6 +762,20 @@ const waitForDownloadSchema = baseCommandSchema.extend({
   action: z.literal('waitfordownload'),
   path: z.string().optional(),
   timeout: z.number().positive().optional(),
+  arm: z.boolean().optional(),
+});
+
+const waitForPopupSchema = baseCommandSchema.extend({
+  action: z.literal('waitforpopup'),
+  timeout: z.number().positive().optional(),
+  arm: z.boolean().optional(),
+});
+
+const waitForRequestSchema = baseCommandSchema.extend({
+  action: z.literal('waitforrequest'),
+  url: z.string().min(1),
+  timeout: z.number().positive().optional(),
+  arm: z.boolean().optional(),
 });
 
 const responseBodySchema = baseCommandSchema.extend({
@@ -645,6 +792,9 @@ const screencastStartSchema = baseCommandSchema.extend({
   maxWidth: z.number().positive().optional(),
   maxHeight: z.number().positive().optional(),
   everyNthFrame: z.number().positive().optional(),
+  scale: z.number().min(0.05).max(1).optional(),
+  fps: z.number().positive().max(60).optional(),
+  path: z.string().min(1).optional(),
 });
 
 const screencastStopSchema = baseCommandSchema.extend({
@@ -710,6 +860,7 @@ const screenshotSchema = baseCommandSchema.extend({
   selector: z.string().min(1).nullish(),
   format: z.enum(['png', 'jpeg']).optional(),
   quality: z.number().min(0).max(100).optional(),
+  mask: z.array(z.string().min(1)).optional(),
 });
 
 const snapshotSchema = baseCommandSchema.extend({
@@ -718,12 +869,14 @@ const snapshotSchema = baseCommandSchema.extend({
   maxDepth: z.number().nonnegative().optional(),
   compact: z.boolean().optional(),
   selector: z.string().optional(),
+  fresh: z.boolean().optional(),
 });
 
 const evaluateSchema = baseCommandSchema.extend({
   action: z.literal('evaluate'),
   script: z.string().min(1),
   args: z.array(z.unknown()).optional(),
+  target: z.string().min(1).optional(),
 });
 
 const waitSchema = baseCommandSchema.extend({
@@ -772,6 +925,90 @@ const tabListSchema = baseCommandSchema.extend({
   action: z.literal('tab_list'),
 });
 
+const robotsSchema = baseCommandSchema.extend({
+  action: z.literal('robots'),
+  url: z.string().min(1),
+});
+
+const auditPerfSchema = baseCommandSchema.extend({
+  action: z.literal('audit_perf'),
+  url: z.string().min(1).optional(),
+});
+
+const auditSeoSchema = baseCommandSchema.extend({
+  action: z.literal('audit_seo'),
+  url: z.string().min(1).optional(),
+  links: z.number().int().nonnegative().optional(),
+});
+
+const tlsInfoSchema = baseCommandSchema.extend({
+  action: z.literal('tls_info'),
+  url: z.string().min(1),
+});
+
+const clockSetSchema = baseCommandSchema.extend({
+  action: z.literal('clock_set'),
+  time: z.string().min(1),
+  tick: z.boolean().optional(),
+  timezone: z.string().min(1).optional(),
+});
+
+const clockAdvanceSchema = baseCommandSchema.extend({
+  action: z.literal('clock_advance'),
+  ms: z.number().int().positive(),
+});
+
+const clockResumeSchema = baseCommandSchema.extend({
+  action: z.literal('clock_resume'),
+});
+
+const scheduleAddSchema = baseCommandSchema.extend({
+  action: z.literal('schedule_add'),
+  cron: z.string().min(1),
+  command: z.array(z.string()).min(1),
+});
+
+const scheduleListSchema = baseCommandSchema.extend({
+  action: z.literal('schedule_list'),
+});
+
+const scheduleRemoveSchema = baseCommandSchema.extend({
+  action: z.literal('schedule_remove'),
+  jobId: z.string().min(1),
+});
+
+const scheduleLogsSchema = baseCommandSchema.extend({
+  action: z.literal('schedule_logs'),
+  jobId: z.string().min(1),
+});
+
+const jobsSchema = baseCommandSchema.extend({
+  action: z.literal('jobs'),
+});
+
+const cancelSchema = baseCommandSchema.extend({
+  action: z.literal('cancel'),
+});
+
+const lanesSchema = baseCommandSchema.extend({
+  action: z.literal('lanes'),
+});
+
+const whichSchema = baseCommandSchema.extend({
+  action: z.literal('which'),
+  executablePath: z.string().min(1).optional(),
+  channel: channelSchema.optional(),
+});
+
+const auditSecuritySchema = baseCommandSchema.extend({
+  action: z.literal('audit_security'),
+  url: z.string().min(1).optional(),
+});
+
+const targetsSchema = baseCommandSchema.extend({
+  action: z.literal('targets'),
+});
+
 const tabSwitchSchema = baseCommandSchema.extend({
   action: z.literal('tab_switch'),
   index: z.number().nonnegative(),
@@ -782,6 +1019,128 @@ const tabCloseSchema = baseCommandSchema.extend({
   index: z.number().nonnegative().optional(),
 });
 
+const rateRuleSchema = z.object({
+  host: z.string().min(1).optional(),
+  count: z.number().int().positive(),
+  per: z.number().int().positive(),
+});
+
+const guardrailRuleSchema = z.object({
+  name: z.string().min(1),
+  url: z.string().refine((pattern) => {
+    try {
+      new RegExp(pattern);
+      return true;
+    } catch {
+      return false;
+    }
+  }, 'Invalid regular expression'),
+  methods: z.array(z.string().min(1)).optional(),
+  navigation: z.boolean().optional(),
+  purchase: z.boolean().optional(),
+});
+
+const sessionOptionsSchema = baseCommandSchema.extend({
+  action: z.literal('session_options'),
+  popupPolicy: z.enum(['follow', 'block', 'list']).optional(),
+  autoDismissOverlays: z.boolean().optional(),
+  adblock: z.boolean().optional(),
+  respectRobots: z.boolean().optional(),
+  transcript: z.boolean().optional(),
+  auditLog: z.boolean().optional(),
+  readOnly: z.boolean().optional(),
+  guardrails: z.array(guardrailRuleSchema).optional(),
+  rateLimits: z
+    .object({
+      requests: z.array(rateRuleSchema).optional(),
+      navigations: z.array(rateRuleSchema).optional(),
+    })
+    .optional(),
+  lanes: z
+    .object({
+      maxConcurrent: z.number().int().nonnegative().optional(),
+      actionRate: z
+        .object({ count: z.number().int().positive(), per: z.number().int().positive() })
+        .nullable()
+        .optional(),
+    })
+    .optional(),
+  tabMemory: z
+    .object({
+      limit: z.number().int().nonnegative(),
+      mode: z.enum(['reload', 'recreate']).optional(),
+    })
+    .optional(),
+});
+
+const memorySchema = baseCommandSchema.extend({
+  action: z.literal('memory'),
+});
+
+const statsSchema = baseCommandSchema.extend({
+  action: z.literal('stats'),
+  reset: z.boolean().optional(),
+});
+
+const cdpSchema = baseCommandSchema.extend({
+  action: z.literal('cdp'),
+  method: z.string().regex(/^\w+\.\w+$/, 'Expected a protocol method like Page.navigate'),
+  params: z.record(z.unknown()).optional(),
+  events: z.array(z.string().min(1)).optional(),
+  wait: z.number().int().nonnegative().optional(),
+});
+
+const cdpListenSchema = baseCommandSchema.extend({
+  action: z.literal('cdp_listen'),
+  events: z
+    .array(z.string().regex(/^\w+\.\w+$/, 'Expected a protocol event like Page.loadEventFired'))
+    .min(1),
+  count: z.number().int().positive().optional(),
+  timeout: z.number().int().positive().optional(),
+});
+
+const watchSchema = baseCommandSchema.extend({
+  action: z.literal('watch'),
+  selector: z.string().min(1),
+  events: z.array(z.enum(['childList', 'attributes', 'characterData'])).min(1).optional(),
+  count: z.number().int().positive().optional(),
+  timeout: z.number().int().positive().optional(),
+});
+
+const pollSchema = baseCommandSchema.extend({
+  action: z.literal('poll'),
+  selector: z.string().min(1),
+  attribute: z.string().min(1).optional(),
+  interval: z.number().int().positive(),
+  reload: z.boolean().optional(),
+  untilChanged: z.boolean().optional(),
+  untilMatch: z
+    .string()
+    .refine((pattern) => {
+      try {
+        new RegExp(pattern);
+        return true;
+      } catch {
+        return false;
+      }
+    }, 'Invalid regular expression')
+    .optional(),
+  max: z.number().int().positive().optional(),
+});
+
+const traceStepSchema = baseCommandSchema.extend({
+  action: z.literal('trace_step'),
+  reset: z.boolean().optional(),
+});
+
+const captureStepSchema = baseCommandSchema.extend({
+  action: z.literal('capture_step'),
+});
+
+const dismissOverlaysSchema = baseCommandSchema.extend({
+  action: z.literal('dismiss_overlays'),
+});
+
 const windowNewSchema = baseCommandSchema.extend({
   action: z.literal('window_new'),
   viewport: z
@@ -823,8 +1182,35 @@ const commandSchema = z.discriminatedUnion('action', [
   closeSchema,
   tabNewSchema,
   tabListSchema,
+  targetsSchema,
+  robotsSchema,
+  auditPerfSchema,
+  auditSeoSchema,
+  auditSecuritySchema,
+  tlsInfoSchema,
+  clockSetSchema,
+  clockAdvanceSchema,
+  clockResumeSchema,
+  scheduleAddSchema,
+  scheduleListSchema,
+  scheduleRemoveSchema,
+  scheduleLogsSchema,
+  jobsSchema,
+  whichSchema,
+  cancelSchema,
+  lanesSchema,
   tabSwitchSchema,
   tabCloseSchema,
+  sessionOptionsSchema,
+  dismissOverlaysSchema,
+  statsSchema,
+  memorySchema,
+  cdpSchema,
+  cdpListenSchema,
+  watchSchema,
+  pollSchema,
+  traceStepSchema,
+  captureStepSchema,
   windowNewSchema,
   cookiesGetSchema,
   cookiesSetSchema,
@@ -834,6 +1220,10 @@ const commandSchema = z.discriminatedUnion('action', [
   storageClearSchema,
   dialogSchema,
   pdfSchema,
+  archiveSchema,
+  freezeSchema,
+  selectorsSuggestSchema,
+  selectorsHealSchema,
   routeSchema,
   unrouteSchema,
   requestsSchema,
@@ -863,10 +1253,18 @@ const commandSchema = z.discriminatedUnion('action', [
   recordingRestartSchema,
   traceStartSchema,
   traceStopSchema,
+  captureStartSchema,
+  captureStopSchema,
+  apisSchema,
+  inputSchema,
+  wsSchema,
+  harReplaySchema,
   harStartSchema,
   harStopSchema,
   stateSaveSchema,
   stateLoadSchema,
+  sessionSaveSchema,
+  sessionRestoreSchema,
   consoleSchema,
   errorsSchema,
   keyboardSchema,
@@ -874,6 +1272,7 @@ const commandSchema = z.discriminatedUnion('action', [
   tapSchema,
   clipboardSchema,
   highlightSchema,
+  inspectSchema,
   clearSchema,
   selectAllSchema,
   innerTextSchema,
@@ -911,6 +1310,8 @@ const commandSchema = z.discriminatedUnion('action', [
   insertTextSchema,
   multiSelectSchema,
   waitForDownloadSchema,
+  waitForPopupSchema,
+  waitForRequestSchema,
   responseBodySchema,
   screencastStartSchema,
   screencastStopSchema,
@@ -937,7 +1338,13 @@ export function parseCommand(input: string): ParseResult {
   } catch {
     return { success: false, error: 'Invalid JSON' };
   }
+  return validateCommand(json);
+}
 
+/**
+ * Validate an already decoded command, e.g. one that arrived as MessagePack
+ */
+export function validateCommand(json: unknown): ParseResult {
   // Extract id for error responses if possible
   const id =
     typeof json === 'object' && json !== null && 'id' in json
@@ -963,10 +1370,14 @@ export function successResponse<T>(id: string, data: T): Response<T> {
 }
 
 /**
- * Create an error response
+ * Create an error response. The code is derived from the message unless given.
  */
-export function errorResponse(id: string, error: string): Response {
-  return { id, success: false, error };
+export function errorResponse(
+  id: string,
+  error: string,
+  code: ErrorCode = classifyError(error)
+): Response {
+  return { id, success: false, error, code };
 }
 
 /**
@@ -0,0 +1,42 @@
+import { describe, it, expect } from 'vitest';
+import { RateLimiter, hostOf } from './rate-limit.js';
+
+describe('RateLimiter', () => {
+  it('spaces requests to a host evenly', () => {
+    const limiter = new RateLimiter([{ count: 2, per: 1000 }], () => 0);
+    expect(limiter.reserve('example.com', 0)).toBe(0);
+    expect(limiter.reserve('example.com', 0)).toBe(500);
+    expect(limiter.reserve('example.com', 100)).toBe(900);
+    // Each host has its own budget under the default rule
+    expect(limiter.reserve('other.com', 100)).toBe(0);
+    expect(limiter.throttled).toBe(2);
+    expect(limiter.waitedMs).toBe(1400);
+  });
+
+  it('shares a domain rule across subdomains and adds jitter', () => {
+    const limiter = new RateLimiter(
+      [
+        { count: 10, per: 1000 },
+        { host: 'example.com', count: 1, per: 1000 },
+      ],
+      () => 1
+    );
+    expect(limiter.reserve('api.example.com', 0)).toBe(0);
+    expect(limiter.reserve('www.example.com', 0)).toBe(1250);
+    expect(limiter.reserve('notexample.com', 0)).toBe(0);
+  });
+
+  it('ignores hosts without a matching rule', () => {
+    const limiter = new RateLimiter([{ host: 'example.com', count: 1, per: 60_000 }]);
+    expect(limiter.reserve('other.com')).toBe(0);
+    expect(limiter.reserve('other.com')).toBe(0);
+  });
+});
+
+describe('hostOf', () => {
+  it('only rate limits http(s) hosts', () => {
+    expect(hostOf('https://api.example.com/v1')).toBe('api.example.com');
+    expect(hostOf('data:text/plain,hi')).toBeNull();
+    expect(hostOf('not a url')).toBeNull();
+  });
+});
@@ -0,0 +1,73 @@
+/**
+ * Per-domain request and navigation rate limits.
+ *
+ * `--rate` and `--nav-rate` (or the `rate` / `nav-rate` config keys) cap how
+ * often the browser may hit each domain. The CLI parses the limits into
+ * rules; here every request waits for its domain's next free slot before it
+ * goes out. Slots are spaced evenly, plus up to 25% random jitter, so bulk
+ * jobs send a steady trickle rather than bursts.
+ */
+
+import type { RateRule } from './types.js';
+
+const JITTER = 0.25;
+
+export class RateLimiter {
+  /** Earliest time the next request to each domain may start */
+  private next = new Map<string, number>();
+  throttled = 0;
+  waitedMs = 0;
+
+  constructor(
+    readonly rules: RateRule[],
+    private random: () => number = Math.random
+  ) {}
+
+  /**
+   * The rule for a host: the most specific `host` rule matching it or a
+   * parent domain, else the rule without a host. Requests to hosts matching a
+   * domain rule share that domain's budget.
+   */
+  private ruleFor(host: string): { key: string; rule: RateRule } | null {
+    let match: RateRule | undefined;
+    for (const rule of this.rules) {
+      if (!rule.host) continue;
+      if (host === rule.host || host.endsWith(`.${rule.host}`)) {
+        if (!match || rule.host.length > match.host!.length) match = rule;
+      }
+    }
+    if (match) return { key: match.host!, rule: match };
+    const fallback = this.rules.find((rule) => !rule.host);
+    return fallback ? { key: host, rule: fallback } : null;
+  }
+
+  /** Milliseconds a request to `host` made now has to wait, reserving its slot. */
+  reserve(host: string, now: number = Date.now()): number {
+    const match = this.ruleFor(host);
+    if (!match) return 0;
+    const interval = match.rule.per / match.rule.count;
+    const slot = Math.max(now, this.next.get(match.key) ?? 0);
+    this.next.set(match.key, slot + interval * (1 + JITTER * this.random()));
+    const delay = slot - now;
+    if (delay > 0) {
+      this.throttled++;
+      this.waitedMs += delay;
+    }
+    return delay;
+  }
+
+  /** Wait until a request to `host` may go ahead. */
+  async acquire(host: string): Promise<void> {
+    const delay = this.reserve(host);
+    if (delay > 0) await new Promise((resolve) => setTimeout(resolve, delay));
+  }
+}
+
+export function hostOf(url: string): string | null {
+  try {
+    const { protocol, hostname } = new URL(url);
+    return protocol === 'http:' || protocol === 'https:' ? hostname : null;
+  } catch {
+    return null;
+  }
+}
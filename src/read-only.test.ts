@@ -0,0 +1,51 @@
+import { describe, it, expect } from 'vitest';
+import { isSafeRequest, readOnlyViolation } from './read-only.js';
+import type { Command } from './types.js';
+
+const command = (fields: Record<string, unknown>) => ({ id: 'c1', ...fields }) as Command;
+
+describe('read-only sessions', () => {
+  it('refuses input but not reading or moving around', () => {
+    expect(readOnlyViolation(command({ action: 'click', selector: '@e1' }))).toBe(
+      'click is not allowed in a read-only session'
+    );
+    expect(readOnlyViolation(command({ action: 'press', key: 'Enter' }))).not.toBeNull();
+    for (const action of ['evaluate', 'evalhandle', 'addscript', 'setcontent', 'cdp']) {
+      expect(readOnlyViolation(command({ action }))).toBe(
+        `${action} is not allowed in a read-only session`
+      );
+    }
+    for (const action of ['navigate', 'snapshot', 'gettext', 'scroll', 'hover', 'screenshot']) {
+      expect(readOnlyViolation(command({ action }))).toBeNull();
+    }
+  });
+
+  it('refuses anything it does not know to be read-only', () => {
+    for (const action of ['dialog', 'expose', 'state_load', 'session_restore', 'storage_set']) {
+      expect(readOnlyViolation(command({ action }))).toBe(
+        `${action} is not allowed in a read-only session`
+      );
+    }
+    expect(readOnlyViolation(command({ action: 'some_new_action' }))).not.toBeNull();
+    expect(readOnlyViolation(command({ action: 'clipboard', operation: 'paste' }))).not.toBeNull();
+    expect(readOnlyViolation(command({ action: 'clipboard', operation: 'read' }))).toBeNull();
+  });
+
+  it('looks at what a locator command does with its element', () => {
+    expect(
+      readOnlyViolation(command({ action: 'getbyrole', role: 'button', subaction: 'click' }))
+    ).toBe('getbyrole click is not allowed in a read-only session');
+    expect(
+      readOnlyViolation(command({ action: 'nth', selector: 'li', index: 0, subaction: 'text' }))
+    ).toBeNull();
+    expect(readOnlyViolation(command({ action: 'input', op: 'replay' }))).not.toBeNull();
+    expect(readOnlyViolation(command({ action: 'input', op: 'record' }))).toBeNull();
+  });
+
+  it('lets only safe request methods through', () => {
+    expect(isSafeRequest('GET')).toBe(true);
+    expect(isSafeRequest('options')).toBe(true);
+    expect(isSafeRequest('POST')).toBe(false);
+    expect(isSafeRequest('DELETE')).toBe(false);
+  });
+});
@@ -0,0 +1,186 @@
+/**
+ * Read-only sessions, for looking around sensitive sites without touching
+ * anything.
+ *
+ * With `--read-only`, only commands that navigate, read or record the page
+ * run; everything else, including any action added later, is refused before
+ * it runs. That rules out acting on the page as a user would (clicks,
+ * typing, key presses, form controls, uploads, drags, dialogs) and running
+ * script in it, since a script can do anything a user can. Every request
+ * that could change something on the server (anything but GET, HEAD and
+ * OPTIONS, so form POSTs and fetch/XHR writes included) is aborted where the
+ * browser's requests are intercepted; page routes and scoped headers fall
+ * back to that check instead of sending the request themselves. Once on,
+ * read-only lasts until the daemon exits.
+ *
+ * Requests are judged by method alone: WebSocket messages and GET requests
+ * with side effects still get through.
+ */
+
+import type { Command } from './types.js';
+
+/**
+ * Commands that may run in a read-only session: they navigate, read or
+ * record the page, or change only how this browser behaves. Anything not
+ * listed is refused, so a new action stays blocked until it is added here.
+ * Input (clicks, typing, key presses, form controls, uploads, drags, dialogs),
+ * script (eval, exposed functions, init scripts, CDP) and restoring saved
+ * state are left out on purpose.
+ */
+const READ_ONLY_ACTIONS = new Set([
+  'launch',
+  'navigate',
+  'back',
+  'forward',
+  'reload',
+  'close',
+  'focus',
+  'hover',
+  'mousemove',
+  'scroll',
+  'scrollintoview',
+  'wheel',
+  'selectall',
+  'frame',
+  'mainframe',
+  'getbyrole',
+  'getbytext',
+  'getbylabel',
+  'getbyplaceholder',
+  'getbyalttext',
+  'getbytitle',
+  'getbytestid',
+  'nth',
+  'url',
+  'title',
+  'content',
+  'getattribute',
+  'gettext',
+  'innertext',
+  'innerhtml',
+  'inputvalue',
+  'isvisible',
+  'isenabled',
+  'ischecked',
+  'count',
+  'boundingbox',
+  'styles',
+  'snapshot',
+  'screenshot',
+  'pdf',
+  'archive',
+  'freeze',
+  'highlight',
+  'inspect',
+  'selectors_suggest',
+  'selectors_heal',
+  'wait',
+  'waitforurl',
+  'waitforloadstate',
+  'waitfordownload',
+  'waitforpopup',
+  'waitforrequest',
+  'responsebody',
+  'cookies_get',
+  'storage_get',
+  'state_save',
+  'session_save',
+  'clipboard',
+  'input',
+  'console',
+  'errors',
+  'requests',
+  'apis',
+  'ws',
+  'route',
+  'unroute',
+  'har_replay',
+  'har_start',
+  'har_stop',
+  'headers',
+  'credentials',
+  'offline',
+  'geolocation',
+  'permissions',
+  'viewport',
+  'useragent',
+  'device',
+  'device_list',
+  'emulatemedia',
+  'timezone',
+  'locale',
+  'addstyle',
+  'clock_set',
+  'clock_advance',
+  'clock_resume',
+  'pause',
+  'video_start',
+  'video_stop',
+  'recording_start',
+  'recording_stop',
+  'recording_restart',
+  'screencast_start',
+  'screencast_stop',
+  'trace_start',
+  'trace_stop',
+  'trace_step',
+  'capture_start',
+  'capture_stop',
+  'capture_step',
+  'tab_new',
+  'tab_list',
+  'tab_switch',
+  'tab_close',
+  'window_new',
+  'bringtofront',
+  'targets',
+  'robots',
+  'tls_info',
+  'audit_perf',
+  'audit_seo',
+  'audit_security',
+  'schedule_add',
+  'schedule_list',
+  'schedule_remove',
+  'schedule_logs',
+  'jobs',
+  'cancel',
+  'lanes',
+  'which',
+  'memory',
+  'stats',
+  'cdp_listen',
+  'watch',
+  'poll',
+  'session_options',
+]);
+
+/** Locator sub-actions that only read the element */
+const READ_SUBACTIONS = new Set(['hover', 'text']);
+
+/** Request methods that don't change anything on the server */
+const SAFE_METHODS = new Set(['GET', 'HEAD', 'OPTIONS']);
+
+/** Why a command may not run in a read-only session, or null if it may */
+export function readOnlyViolation(command: Command): string | null {
+  const { action } = command;
+  if (!READ_ONLY_ACTIONS.has(action)) {
+    return `${action} is not allowed in a read-only session`;
+  }
+  const subaction = (command as { subaction?: string }).subaction;
+  if (subaction !== undefined && !READ_SUBACTIONS.has(subaction)) {
+    return `${action} ${subaction} is not allowed in a read-only session`;
+  }
+  if (action === 'input' && (command as { op?: string }).op === 'replay') {
+    return 'Replaying input is not allowed in a read-only session';
+  }
+  if (action === 'clipboard' && (command as { operation?: string }).operation === 'paste') {
+    return 'Pasting is not allowed in a read-only session';
+  }
+  return null;
+}
+
+/** Whether a request may go out from a read-only session */
+export function isSafeRequest(method: string): boolean {
+  return SAFE_METHODS.has(method.toUpperCase());
+}
@@ -0,0 +1,54 @@
+import { describe, it, expect } from 'vitest';
+import { checkRobots, parseRobots } from './robots.js';
+
+const ROBOTS = `
+# Example
+User-agent: Googlebot
+Disallow: /
+
+User-agent: *
+Disallow: /private
+Allow: /private/press
+Disallow: /*.pdf$
+Disallow: /search?
+
+User-agent: agent-browser
+User-agent: other-bot
+Disallow: /agents-only
+`;
+
+describe('robots.txt', () => {
+  const groups = parseRobots(ROBOTS);
+
+  it('groups consecutive user-agent lines', () => {
+    expect(groups).toHaveLength(3);
+    expect(groups[2].agents).toEqual(['agent-browser', 'other-bot']);
+  });
+
+  it('uses the longest matching rule in the * group', () => {
+    const robots = { groups };
+    expect(checkRobots(robots, '/private/team', '*')).toEqual({
+      allowed: false,
+      rule: 'Disallow: /private',
+    });
+    expect(checkRobots(robots, '/private/press/2024', '*').allowed).toBe(true);
+    expect(checkRobots(robots, '/docs/guide.pdf', '*').allowed).toBe(false);
+    expect(checkRobots(robots, '/docs/guide.pdf?v=2', '*').allowed).toBe(true);
+    expect(checkRobots(robots, '/search?q=x', '*').allowed).toBe(false);
+    expect(checkRobots(robots, '/', '*')).toEqual({ allowed: true });
+  });
+
+  it('prefers a group naming agent-browser', () => {
+    const robots = { groups };
+    expect(checkRobots(robots, '/private/team').allowed).toBe(true);
+    expect(checkRobots(robots, '/agents-only/x').allowed).toBe(false);
+  });
+
+  it('applies blanket decisions when robots.txt is missing or unreachable', () => {
+    expect(checkRobots({ all: true, reason: 'No robots.txt (HTTP 404)' }, '/x').allowed).toBe(true);
+    expect(checkRobots({ all: false, reason: 'robots.txt unavailable (HTTP 503)' }, '/x')).toEqual({
+      allowed: false,
+      rule: 'robots.txt unavailable (HTTP 503)',
+    });
+  });
+});
@@ -0,0 +1,149 @@
+/**
+ * robots.txt rules for navigations.
+ *
+ * With `--respect-robots`, top-level navigations are checked against the
+ * site's robots.txt before they go out, and disallowed ones fail. Files are
+ * fetched once per origin and cached for a day. Matching follows RFC 9309:
+ * the group for `agent-browser` if there is one, else `*`, then the longest
+ * matching rule, with Allow winning ties. `*` and `$` work as wildcards.
+ */
+
+import type { RobotsDecision } from './types.js';
+
+export const ROBOTS_AGENT = 'agent-browser';
+
+const CACHE_TTL = 24 * 60 * 60 * 1000;
+const FETCH_TIMEOUT = 5000;
+
+interface Rule {
+  allow: boolean;
+  path: string;
+}
+
+interface Group {
+  agents: string[];
+  rules: Rule[];
+}
+
+/** A parsed robots.txt, or a blanket decision when it couldn't be read */
+export type Robots = { groups: Group[] } | { all: boolean; reason: string };
+
+export function parseRobots(text: string): Group[] {
+  const groups: Group[] = [];
+  let current: Group | null = null;
+  let lastWasAgent = false;
+  for (const raw of text.split(/\r?\n/)) {
+    const line = raw.replace(/#.*$/, '').trim();
+    const colon = line.indexOf(':');
+    if (colon === -1) continue;
+    const field = line.slice(0, colon).trim().toLowerCase();
+    const value = line.slice(colon + 1).trim();
+    if (field === 'user-agent') {
+      // Consecutive user-agent lines share one group
+      if (!current || !lastWasAgent) {
+        current = { agents: [], rules: [] };
+        groups.push(current);
+      }
+      current.agents.push(value.toLowerCase());
+      lastWasAgent = true;
+    } else if ((field === 'allow' || field === 'disallow') && current) {
+      // An empty Disallow allows everything, which is the default anyway
+      if (value) current.rules.push({ allow: field === 'allow', path: value });
+      lastWasAgent = false;
+    } else {
+      lastWasAgent = false;
+    }
+  }
+  return groups;
+}
+
+function matches(pattern: string, path: string): boolean {
+  const anchored = pattern.endsWith('$');
+  const body = anchored ? pattern.slice(0, -1) : pattern;
+  const regex = body
+    .split('*')
+    .map((part) => part.replace(/[.+?^${}()|[\]\\]/g, '\\$&'))
+    .join('.*');
+  return new RegExp(`^${regex}${anchored ? '$' : ''}`).test(path);
+}
+
+/** Decide whether `path` (with its query string) may be fetched. */
+export function checkRobots(
+  robots: Robots,
+  path: string,
+  agent: string = ROBOTS_AGENT
+): { allowed: boolean; rule?: string } {
+  if ('all' in robots) {
+    return { allowed: robots.all, rule: robots.reason };
+  }
+  const group =
+    robots.groups.find((g) => g.agents.includes(agent.toLowerCase())) ??
+    robots.groups.find((g) => g.agents.includes('*'));
+  if (!group) return { allowed: true };
+
+  let best: Rule | null = null;
+  for (const rule of group.rules) {
+    if (!matches(rule.path, path)) continue;
+    if (
+      !best ||
+      rule.path.length > best.path.length ||
+      (rule.path.length === best.path.length && rule.allow)
+    ) {
+      best = rule;
+    }
+  }
+  if (!best) return { allowed: true };
+  return { allowed: best.allow, rule: `${best.allow ? 'Allow' : 'Disallow'}: ${best.path}` };
+}
+
+export class RobotsCache {
+  private entries = new Map<string, { robots: Promise<Robots>; fetched: number }>();
+
+  private load(origin: string): Promise<Robots> {
+    const cached = this.entries.get(origin);
+    if (cached && Date.now() - cached.fetched < CACHE_TTL) return cached.robots;
+    const robots = fetchRobots(`${origin}/robots.txt`);
+    this.entries.set(origin, { robots, fetched: Date.now() });
+    // Try again next time rather than blocking the site for a day
+    void robots.then((r) => {
+      if ('all' in r && !r.all) this.entries.delete(origin);
+    });
+    return robots;
+  }
+
+  /** The decision for a URL. Non-http(s) URLs are always allowed. */
+  async check(url: string): Promise<RobotsDecision> {
+    let parsed: URL;
+    try {
+      parsed = new URL(url);
+    } catch {
+      return { url, allowed: true };
+    }
+    if (parsed.protocol !== 'http:' && parsed.protocol !== 'https:') {
+      return { url, allowed: true };
+    }
+    const robots = await this.load(parsed.origin);
+    return {
+      url,
+      robotsUrl: `${parsed.origin}/robots.txt`,
+      ...checkRobots(robots, parsed.pathname + parsed.search),
+    };
+  }
+}
+
+/**
+ * Per RFC 9309, a missing robots.txt (4xx) allows everything, and one that
+ * can't be reached (5xx or network error) disallows everything.
+ */
+async function fetchRobots(url: string): Promise<Robots> {
+  try {
+    const response = await fetch(url, { signal: AbortSignal.timeout(FETCH_TIMEOUT) });
+    if (response.ok) return { groups: parseRobots(await response.text()) };
+    if (response.status >= 400 && response.status < 500) {
+      return { all: true, reason: `No robots.txt (HTTP ${response.status})` };
+    }
+    return { all: false, reason: `robots.txt unavailable (HTTP ${response.status})` };
+  } catch (err) {
+    return { all: false, reason: `robots.txt unreachable: ${(err as Error).message}` };
+  }
+}
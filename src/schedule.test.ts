@@ -0,0 +1,99 @@
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import { mkdtempSync, readFileSync, rmSync } from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { nextRun, parseCron, Scheduler } from './schedule.js';
+
+/** Local time, like the schedule itself */
+const at = (text: string) => new Date(text);
+
+describe('schedule', () => {
+  describe('parseCron', () => {
+    it('expands lists, ranges and steps', () => {
+      const cron = parseCron('*/15 9-17 * * 1-5');
+      expect([...cron.minutes]).toEqual([0, 15, 30, 45]);
+      expect(cron.hours.size).toBe(9);
+      expect([...cron.weekdays]).toEqual([1, 2, 3, 4, 5]);
+      expect(parseCron('0 0 * * 7').weekdays).toEqual(new Set([0]));
+      expect(parseCron('@daily')).toEqual(parseCron('0 0 * * *'));
+    });
+
+    it('rejects malformed expressions', () => {
+      expect(() => parseCron('* * * *')).toThrow('expected 5 fields');
+      expect(() => parseCron('60 * * * *')).toThrow('Invalid cron minute');
+      expect(() => parseCron('0 * * * mon')).toThrow('Invalid cron day of week');
+      expect(() => parseCron('0 5-1 * * *')).toThrow('Invalid cron hour');
+    });
+  });
+
+  describe('nextRun', () => {
+    it('finds the next matching minute', () => {
+      const hourly = parseCron('0 * * * *');
+      expect(nextRun(hourly, at('2024-06-01T10:00:00'))).toEqual(at('2024-06-01T11:00:00'));
+      expect(nextRun(hourly, at('2024-06-01T10:59:59.999'))).toEqual(at('2024-06-01T11:00:00'));
+      // Friday evening to Monday morning
+      const weekdays = parseCron('30 9 * * 1-5');
+      expect(nextRun(weekdays, at('2024-05-31T18:00:00'))).toEqual(at('2024-06-03T09:30:00'));
+      expect(nextRun(parseCron('0 0 29 2 *'), at('2024-03-01T00:00:00'))).toEqual(
+        at('2028-02-29T00:00:00')
+      );
+    });
+
+    it('matches either day field when both are restricted', () => {
+      // The 15th, or any Monday
+      const cron = parseCron('0 12 15 * 1');
+      expect(nextRun(cron, at('2024-06-04T00:00:00'))).toEqual(at('2024-06-10T12:00:00'));
+      expect(nextRun(cron, at('2024-06-11T00:00:00'))).toEqual(at('2024-06-15T12:00:00'));
+    });
+
+    it('gives up on dates that never come', () => {
+      expect(nextRun(parseCron('0 0 30 2 *'), at('2024-01-01T00:00:00'))).toBeNull();
+    });
+  });
+
+  describe('Scheduler', () => {
+    let dir: string;
+    let schedulers: Scheduler[] = [];
+    const create = () => {
+      const scheduler = new Scheduler('test', () => false, path.join(dir, 'test.json'));
+      schedulers.push(scheduler);
+      return scheduler;
+    };
+
+    beforeEach(() => {
+      dir = mkdtempSync(path.join(os.tmpdir(), 'schedule-test-'));
+    });
+
+    afterEach(() => {
+      schedulers.forEach((scheduler) => scheduler.stop());
+      schedulers = [];
+      rmSync(dir, { recursive: true, force: true });
+    });
+
+    it('saves jobs and loads them again', () => {
+      const scheduler = create();
+      const job = scheduler.add('0 * * * *', ['run', '/checks/inventory.ab']);
+      expect(job).toMatchObject({ id: '1', running: false, lastRun: null });
+      expect(job.nextRun).not.toBeNull();
+      scheduler.add('@daily', ['open', 'example.com']);
+
+      const saved = JSON.parse(readFileSync(path.join(dir, 'test.json'), 'utf-8'));
+      expect(saved.jobs).toHaveLength(2);
+
+      const restarted = create();
+      restarted.load();
+      expect(restarted.list().map((j) => j.command[0])).toEqual(['run', 'open']);
+      restarted.remove('1');
+      expect(restarted.list().map((j) => j.id)).toEqual(['2']);
+      // Ids aren't reused
+      expect(restarted.add('@hourly', ['reload']).id).toBe('3');
+    });
+
+    it('rejects bad expressions and unknown jobs', () => {
+      const scheduler = create();
+      expect(() => scheduler.add('every hour', ['reload'])).toThrow('Invalid cron');
+      expect(() => scheduler.remove('9')).toThrow('No scheduled job 9');
+      expect(scheduler.hasJobs()).toBe(false);
+    });
+  });
+});
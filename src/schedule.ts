@@ -0,0 +1,358 @@
+/**
+ * Recurring jobs run by the daemon.
+ *
+ * `schedule add "<cron>" <command...>` saves an agent-browser command line
+ * with a five-field cron expression. While the session's daemon is up it runs
+ * the command at those times, as if typed by hand, in a session of its own so
+ * jobs never share a browser with interactive use. A job that is still running
+ * when its next time comes is skipped rather than started twice. Each run gets
+ * a directory under the session's artifacts holding its output and any files
+ * the command writes to relative paths. Jobs are saved to
+ * `~/.browseros/schedules/<session>.json` and picked up again when the daemon
+ * restarts.
+ */
+
+import { spawn } from 'node:child_process';
+import {
+  closeSync,
+  existsSync,
+  mkdirSync,
+  openSync,
+  readFileSync,
+  renameSync,
+  writeFileSync,
+  writeSync,
+} from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { getArtifactsDir } from './artifacts.js';
+import { errorResponse, successResponse } from './protocol.js';
+import type {
+  Command,
+  JobRun,
+  Response,
+  ScheduleCommand,
+  ScheduledJob,
+  ScheduleLogsData,
+} from './types.js';
+
+/** Runs kept per job */
+const HISTORY = 20;
+/** setTimeout's limit; later runs are re-armed when it fires */
+const MAX_DELAY = 2 ** 31 - 1;
+
+const MACROS: Record<string, string> = {
+  '@yearly': '0 0 1 1 *',
+  '@annually': '0 0 1 1 *',
+  '@monthly': '0 0 1 * *',
+  '@weekly': '0 0 * * 0',
+  '@daily': '0 0 * * *',
+  '@midnight': '0 0 * * *',
+  '@hourly': '0 * * * *',
+};
+
+export interface Cron {
+  minutes: Set<number>;
+  hours: Set<number>;
+  days: Set<number>;
+  months: Set<number>;
+  weekdays: Set<number>;
+  /** Day of month and day of week were both restricted: either one matches */
+  eitherDay: boolean;
+}
+
+function parseField(field: string, min: number, max: number, name: string): Set<number> {
+  const values = new Set<number>();
+  for (const part of field.split(',')) {
+    const match = /^(\*|(\d+)(?:-(\d+))?)(?:\/(\d+))?$/.exec(part);
+    if (!match) throw new Error(`Invalid cron ${name}: ${field}`);
+    const [, range, start, end, step] = match;
+    const from = range === '*' ? min : Number(start);
+    const to = range === '*' ? max : end !== undefined ? Number(end) : step ? max : from;
+    const by = step ? Number(step) : 1;
+    if (from < min || to > max || from > to || by < 1) {
+      throw new Error(`Invalid cron ${name}: ${field} (expected ${min}-${max})`);
+    }
+    for (let value = from; value <= to; value += by) values.add(value);
+  }
+  return values;
+}
+
+/** Parse `minute hour day-of-month month day-of-week`, or a macro like `@hourly`. */
+export function parseCron(expression: string): Cron {
+  const fields = (MACROS[expression.trim()] ?? expression).trim().split(/\s+/);
+  if (fields.length !== 5) {
+    throw new Error(
+      `Invalid cron expression: ${expression} (expected 5 fields, e.g. "0 * * * *")`
+    );
+  }
+  const weekdays = parseField(fields[4], 0, 7, 'day of week');
+  // 7 is Sunday too
+  if (weekdays.delete(7)) weekdays.add(0);
+  return {
+    minutes: parseField(fields[0], 0, 59, 'minute'),
+    hours: parseField(fields[1], 0, 23, 'hour'),
+    days: parseField(fields[2], 1, 31, 'day of month'),
+    months: parseField(fields[3], 1, 12, 'month'),
+    weekdays,
+    eitherDay: fields[2] !== '*' && fields[4] !== '*',
+  };
+}
+
+/** The first minute after `after` that the expression matches, in local time. */
+export function nextRun(cron: Cron, after: Date): Date | null {
+  const time = new Date(after);
+  time.setSeconds(0, 0);
+  time.setMinutes(time.getMinutes() + 1);
+  // Expressions like "0 0 30 2 *" never match
+  const limit = after.getFullYear() + 8;
+  while (time.getFullYear() <= limit) {
+    if (!cron.months.has(time.getMonth() + 1)) {
+      time.setMonth(time.getMonth() + 1, 1);
+      time.setHours(0, 0, 0, 0);
+      continue;
+    }
+    const day = cron.days.has(time.getDate());
+    const weekday = cron.weekdays.has(time.getDay());
+    if (cron.eitherDay ? !day && !weekday : !day || !weekday) {
+      time.setDate(time.getDate() + 1);
+      time.setHours(0, 0, 0, 0);
+      continue;
+    }
+    if (!cron.hours.has(time.getHours())) {
+      time.setHours(time.getHours() + 1, 0, 0, 0);
+      continue;
+    }
+    if (!cron.minutes.has(time.getMinutes())) {
+      time.setMinutes(time.getMinutes() + 1, 0, 0);
+      continue;
+    }
+    return time;
+  }
+  return null;
+}
+
+interface StoredJob {
+  id: string;
+  cron: string;
+  command: string[];
+  createdAt: string;
+  history: JobRun[];
+}
+
+/**
+ * Environment for a CLI started by the daemon in `session`. Ports belong to
+ * this daemon, so the child's daemon must not try to serve on them too.
+ */
+export function sessionEnv(session: string): NodeJS.ProcessEnv {
+  const env: NodeJS.ProcessEnv = { ...process.env, AGENT_BROWSER_SESSION: session };
+  delete env.AGENT_BROWSER_STREAM_PORT;
+  delete env.AGENT_BROWSER_METRICS_PORT;
+  delete env.AGENT_BROWSER_JOBS_PORT;
+  return env;
+}
+
+export function scheduleFile(session: string): string {
+  return path.join(os.homedir(), '.browseros', 'schedules', `${session}.json`);
+}
+
+export class Scheduler {
+  private jobs: StoredJob[] = [];
+  private nextId = 1;
+  private timers = new Map<string, NodeJS.Timeout>();
+  private running = new Set<string>();
+
+  constructor(
+    private session: string,
+    /** Whether a session's daemon is up, so finished jobs only close browsers that exist */
+    private sessionRunning: (session: string) => boolean,
+    private file: string = scheduleFile(session)
+  ) {}
+
+  /** Read saved jobs and arm their timers. A file that can't be parsed is set aside. */
+  load(): void {
+    if (!existsSync(this.file)) return;
+    try {
+      const saved = JSON.parse(readFileSync(this.file, 'utf-8'));
+      this.jobs = saved.jobs ?? [];
+      this.nextId = saved.nextId ?? this.jobs.length + 1;
+    } catch {
+      renameSync(this.file, `${this.file}.corrupt`);
+      return;
+    }
+    for (const job of this.jobs) this.arm(job);
+  }
+
+  hasJobs(): boolean {
+    return this.jobs.length > 0;
+  }
+
+  add(cron: string, command: string[]): ScheduledJob {
+    parseCron(cron);
+    const job: StoredJob = {
+      id: String(this.nextId++),
+      cron,
+      command,
+      createdAt: new Date().toISOString(),
+      history: [],
+    };
+    this.jobs.push(job);
+    this.save();
+    this.arm(job);
+    return this.describe(job);
+  }
+
+  remove(id: string): void {
+    const job = this.find(id);
+    clearTimeout(this.timers.get(id));
+    this.timers.delete(id);
+    this.jobs = this.jobs.filter((j) => j !== job);
+    this.save();
+  }
+
+  list(): ScheduledJob[] {
+    return this.jobs.map((job) => this.describe(job));
+  }
+
+  /** The output of the job's latest run that produced any */
+  logs(id: string): ScheduleLogsData {
+    const job = this.find(id);
+    const run = [...job.history].reverse().find((r) => r.dir);
+    if (!run?.dir) return { id, run: null, output: '' };
+    let output = '';
+    try {
+      output = readFileSync(path.join(run.dir, 'output.log'), 'utf-8');
+    } catch {
+      // Pruned with the rest of the artifacts
+    }
+    return { id, run, output };
+  }
+
+  stop(): void {
+    for (const timer of this.timers.values()) clearTimeout(timer);
+    this.timers.clear();
+  }
+
+  private find(id: string): StoredJob {
+    const job = this.jobs.find((j) => j.id === id);
+    if (!job) throw new Error(`No scheduled job ${id}. Run: agent-browser schedule list`);
+    return job;
+  }
+
+  private describe(job: StoredJob): ScheduledJob {
+    const next = nextRun(parseCron(job.cron), new Date());
+    return {
+      id: job.id,
+      cron: job.cron,
+      command: job.command,
+      createdAt: job.createdAt,
+      nextRun: next?.toISOString() ?? null,
+      running: this.running.has(job.id),
+      lastRun: job.history[job.history.length - 1] ?? null,
+    };
+  }
+
+  private save(): void {
+    mkdirSync(path.dirname(this.file), { recursive: true });
+    const state = { version: 1, nextId: this.nextId, jobs: this.jobs };
+    writeFileSync(this.file, JSON.stringify(state, null, 2));
+  }
+
+  private record(job: StoredJob, run: JobRun): void {
+    job.history = [...job.history, run].slice(-HISTORY);
+    // Removed while running: nothing left to save it to
+    if (this.jobs.includes(job)) this.save();
+  }
+
+  /**
+   * Wait for the next run. Each run is computed from the previous one, so a
+   * timer firing a little early can't repeat it.
+   */
+  private arm(job: StoredJob, after: Date = new Date()): void {
+    const next = nextRun(parseCron(job.cron), after);
+    if (!next) return;
+    const delay = next.getTime() - Date.now();
+    const timer = setTimeout(
+      () => {
+        if (!this.jobs.includes(job)) return;
+        if (delay > MAX_DELAY) {
+          this.arm(job, after);
+          return;
+        }
+        this.fire(job);
+        this.arm(job, next);
+      },
+      Math.max(0, Math.min(delay, MAX_DELAY))
+    );
+    this.timers.set(job.id, timer);
+  }
+
+  private fire(job: StoredJob): void {
+    const started = new Date();
+    if (this.running.has(job.id)) {
+      this.record(job, { started: started.toISOString(), status: 'skipped' });
+      return;
+    }
+    const stamp = started.toISOString().replace(/[:.]/g, '-');
+    const dir = path.join(getArtifactsDir(this.session), 'schedules', job.id, stamp);
+    mkdirSync(dir, { recursive: true });
+    const log = openSync(path.join(dir, 'output.log'), 'a');
+    this.running.add(job.id);
+
+    const session = `${this.session}-schedule-${job.id}`;
+    const env = sessionEnv(session);
+    const cli = process.env.AGENT_BROWSER_CLI;
+    let done = false;
+    const finish = (exitCode: number) => {
+      if (done) return;
+      done = true;
+      closeSync(log);
+      this.running.delete(job.id);
+      this.record(job, {
+        started: started.toISOString(),
+        finished: new Date().toISOString(),
+        status: exitCode === 0 ? 'passed' : 'failed',
+        exitCode,
+        dir,
+      });
+      // Each run gets a fresh browser
+      if (cli && this.sessionRunning(session)) {
+        spawn(cli, ['close'], { env, stdio: 'ignore' }).on('error', () => {});
+      }
+    };
+    if (!cli) {
+      writeSync(log, 'Scheduled jobs need a daemon started by the agent-browser CLI\n');
+      finish(1);
+      return;
+    }
+    writeSync(log, `$ agent-browser ${job.command.join(' ')}\n`);
+    const child = spawn(cli, job.command, { cwd: dir, env, stdio: ['ignore', log, log] });
+    child.on('error', (err) => {
+      writeSync(log, `${err.message}\n`);
+      finish(1);
+    });
+    child.on('exit', (code) => finish(code ?? 1));
+  }
+}
+
+export function isScheduleCommand(command: Command): command is ScheduleCommand {
+  return command.action.startsWith('schedule_');
+}
+
+export function handleSchedule(command: ScheduleCommand, scheduler: Scheduler): Response {
+  try {
+    switch (command.action) {
+      case 'schedule_add':
+        return successResponse(command.id, { job: scheduler.add(command.cron, command.command) });
+      case 'schedule_list':
+        return successResponse(command.id, { jobs: scheduler.list() });
+      case 'schedule_remove':
+        scheduler.remove(command.jobId);
+        return successResponse(command.id, { removed: command.jobId });
+      case 'schedule_logs':
+        return successResponse(command.id, scheduler.logs(command.jobId));
+    }
+  } catch (err) {
+    return errorResponse(command.id, (err as Error).message, 'E_INVALID_COMMAND');
+  }
+}
@@ -0,0 +1,84 @@
+import { describe, it, expect } from 'vitest';
+import { mkdtempSync, readFileSync, rmSync } from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import type { ScreencastFrame } from './browser.js';
+import {
+  FrameThrottle,
+  FrameWriter,
+  binaryFrame,
+  captureOptionsFromEnv,
+  frameBytes,
+} from './screencast.js';
+
+const metadata = {
+  offsetTop: 0,
+  pageScaleFactor: 1,
+  deviceWidth: 640,
+  deviceHeight: 360,
+  scrollOffsetX: 0,
+  scrollOffsetY: 0,
+};
+
+function frame(bytes: string): ScreencastFrame {
+  return { data: Buffer.from(bytes).toString('base64'), metadata, sessionId: 1 };
+}
+
+describe('FrameThrottle', () => {
+  it('passes at most fps frames a second', () => {
+    const throttle = new FrameThrottle(10);
+    expect([0, 50, 100, 120, 210].map((t) => throttle.admit(t))).toEqual([
+      true,
+      false,
+      true,
+      false,
+      true,
+    ]);
+    expect(new FrameThrottle().admit(0)).toBe(true);
+  });
+});
+
+describe('frames', () => {
+  it('decodes a frame once', () => {
+    const f = frame('jpeg');
+    expect(frameBytes(f)).toBe(frameBytes(f));
+    expect(frameBytes(f).toString()).toBe('jpeg');
+  });
+
+  it('prefixes binary frames with their metadata', () => {
+    const message = binaryFrame(frame('jpeg'));
+    const length = message.readUInt32BE(0);
+    expect(JSON.parse(message.subarray(4, 4 + length).toString())).toEqual(metadata);
+    expect(message.subarray(4 + length).toString()).toBe('jpeg');
+  });
+
+  it('writes frames back to back', async () => {
+    const dir = mkdtempSync(path.join(os.tmpdir(), 'screencast-test-'));
+    try {
+      const file = path.join(dir, 'frames.mjpeg');
+      const writer = new FrameWriter(file);
+      writer.write(frame('one'));
+      writer.write(frame('two'));
+      expect(await writer.close()).toMatchObject({ path: file, frames: 2, bytes: 6, dropped: 0 });
+      expect(readFileSync(file, 'utf-8')).toBe('onetwo');
+    } finally {
+      rmSync(dir, { recursive: true, force: true });
+    }
+  });
+});
+
+describe('captureOptionsFromEnv', () => {
+  it('reads quality, scale and fps', () => {
+    expect(captureOptionsFromEnv({})).toEqual({});
+    expect(
+      captureOptionsFromEnv({
+        AGENT_BROWSER_STREAM_QUALITY: '60',
+        AGENT_BROWSER_STREAM_SCALE: '0.5',
+        AGENT_BROWSER_STREAM_FPS: '5',
+      })
+    ).toEqual({ quality: 60, scale: 0.5, fps: 5 });
+    expect(() => captureOptionsFromEnv({ AGENT_BROWSER_STREAM_SCALE: '2' })).toThrow(
+      'AGENT_BROWSER_STREAM_SCALE'
+    );
+  });
+});
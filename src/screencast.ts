@@ -0,0 +1,125 @@
+/**
+ * Continuous capture for screencast and vision workflows.
+ *
+ * CDP hands each screencast frame over as base64 inside a JSON event. The
+ * stream server used to forward that string inside another JSON message,
+ * which every client then had to parse and decode again. Here a frame is
+ * decoded once, and consumers take the bytes: stream clients that connect
+ * with `?binary=1` get binary WebSocket messages, and `screencast start
+ * <path>` appends the JPEGs back to back to a file or named pipe, which
+ * `ffmpeg -f image2pipe` reads as is.
+ *
+ * Frames are capped to `fps` and scaled by `scale` in the browser, and a
+ * consumer that falls behind has frames dropped rather than buffered, so
+ * capture never grows the daemon's memory.
+ */
+
+import { createWriteStream, type WriteStream } from 'node:fs';
+import type { ScreencastFrame } from './browser.js';
+import type { ScreencastStopData } from './types.js';
+
+/** Bytes a stream client may have queued before its frames are dropped */
+export const MAX_CLIENT_BUFFER = 4 * 1024 * 1024;
+
+export interface CaptureOptions {
+  format?: 'jpeg' | 'png';
+  /** 0-100, jpeg only */
+  quality?: number;
+  /** Frame size relative to the viewport, 0-1 */
+  scale?: number;
+  /** Most frames delivered per second */
+  fps?: number;
+}
+
+/** Capture settings for the stream server from `AGENT_BROWSER_STREAM_*` */
+export function captureOptionsFromEnv(env: NodeJS.ProcessEnv = process.env): CaptureOptions {
+  const number = (name: string, min: number, max: number): number | undefined => {
+    const raw = env[name];
+    if (raw === undefined || raw === '') return undefined;
+    const value = Number(raw);
+    if (!Number.isFinite(value) || value < min || value > max) {
+      throw new Error(`${name} must be between ${min} and ${max}, not "${raw}"`);
+    }
+    return value;
+  };
+  const quality = number('AGENT_BROWSER_STREAM_QUALITY', 0, 100);
+  const scale = number('AGENT_BROWSER_STREAM_SCALE', 0.05, 1);
+  const fps = number('AGENT_BROWSER_STREAM_FPS', 0.1, 60);
+  return {
+    ...(quality !== undefined ? { quality: Math.round(quality) } : {}),
+    ...(scale !== undefined ? { scale } : {}),
+    ...(fps !== undefined ? { fps } : {}),
+  };
+}
+
+/** Passes at most `fps` frames a second; the rest are acknowledged and skipped */
+export class FrameThrottle {
+  private interval: number;
+  private last = -Infinity;
+
+  constructor(fps?: number) {
+    this.interval = fps && fps > 0 ? 1000 / fps : 0;
+  }
+
+  admit(now: number = performance.now()): boolean {
+    if (now - this.last < this.interval) return false;
+    this.last = now;
+    return true;
+  }
+}
+
+/** A frame's image bytes, decoded from CDP's base64 once and shared by every consumer */
+export function frameBytes(frame: ScreencastFrame): Buffer {
+  frame.bytes ??= Buffer.from(frame.data, 'base64');
+  return frame.bytes;
+}
+
+/**
+ * A binary stream message: a 4-byte big-endian length, the frame's metadata
+ * as that many bytes of JSON, then the image.
+ */
+export function binaryFrame(frame: ScreencastFrame): Buffer {
+  const header = Buffer.from(JSON.stringify(frame.metadata));
+  const length = Buffer.alloc(4);
+  length.writeUInt32BE(header.length);
+  return Buffer.concat([length, header, frameBytes(frame)]);
+}
+
+/** Appends frames to a file or named pipe, dropping them while the reader catches up */
+export class FrameWriter {
+  private out: WriteStream;
+  private error: string | null = null;
+  frames = 0;
+  bytes = 0;
+  dropped = 0;
+
+  constructor(readonly path: string) {
+    this.out = createWriteStream(path, { flags: 'a' });
+    this.out.on('error', (err) => {
+      this.error = err.message;
+    });
+  }
+
+  write(frame: ScreencastFrame): void {
+    if (this.error !== null || this.out.writableNeedDrain) {
+      this.dropped++;
+      return;
+    }
+    const bytes = frameBytes(frame);
+    this.out.write(bytes);
+    this.frames++;
+    this.bytes += bytes.length;
+  }
+
+  async close(): Promise<ScreencastStopData> {
+    await new Promise<void>((resolve) => this.out.end(() => resolve()));
+    return {
+      stopped: true,
+      path: this.path,
+      frames: this.frames,
+      bytes: this.bytes,
+      dropped: this.dropped,
+      ...(this.error !== null ? { error: this.error } : {}),
+    };
+  }
+}
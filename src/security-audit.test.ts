@@ -0,0 +1,66 @@
+import { describe, it, expect } from 'vitest';
+import { buildSecurityReport, siteOf, type RawSecurityPage } from './security-audit.js';
+
+const page = (overrides: Partial<RawSecurityPage> = {}): RawSecurityPage => ({
+  url: 'https://shop.example.com/',
+  headers: {
+    'content-security-policy': "default-src 'self'; frame-ancestors 'none'",
+    'strict-transport-security': 'max-age=31536000; includeSubDomains',
+  },
+  metaCsp: [],
+  cookies: [
+    { name: 'sid', domain: 'shop.example.com', secure: true, httpOnly: true, sameSite: 'Lax' },
+  ],
+  requests: [{ url: 'https://shop.example.com/', type: 'document' }],
+  ...overrides,
+});
+
+describe('security audit', () => {
+  it('groups hosts by registrable domain', () => {
+    expect(siteOf('cdn.example.com')).toBe('example.com');
+    expect(siteOf('www.bbc.co.uk')).toBe('bbc.co.uk');
+    expect(siteOf('localhost')).toBe('localhost');
+    expect(siteOf('127.0.0.1')).toBe('127.0.0.1');
+  });
+
+  it('reports no issues for a locked-down page', () => {
+    const report = buildSecurityReport(page());
+    expect(report.issues).toEqual([]);
+    expect(report.thirdParty).toEqual([]);
+    expect(report.headers.xFrameOptions).toBeNull();
+  });
+
+  it('flags weak headers, cookies and mixed content', () => {
+    const report = buildSecurityReport(
+      page({
+        headers: {
+          'content-security-policy': "script-src 'self' 'unsafe-inline'",
+          'strict-transport-security': 'max-age=3600',
+        },
+        cookies: [
+          { name: 'a', domain: 'example.com', secure: false, httpOnly: false, sameSite: 'None' },
+          { name: 'b', domain: 'example.com', secure: false, httpOnly: true, sameSite: 'Lax' },
+        ],
+        requests: [
+          { url: 'http://example.com/app.js', type: 'script' },
+          { url: 'http://img.other.net/a.png', type: 'image' },
+          { url: 'https://img.other.net/b.png', type: 'image' },
+          { url: 'data:image/png;base64,AAAA', type: 'image' },
+        ],
+      })
+    );
+    expect(report.issues.map((i) => `${i.severity} ${i.check}`)).toEqual([
+      'warning csp',
+      'warning hsts',
+      'warning framing',
+      'error cookies',
+      'warning cookies',
+      'error mixed-content',
+      'warning mixed-content',
+    ]);
+    expect(report.thirdParty).toEqual([
+      { origin: 'http://img.other.net', requests: 1, types: ['image'] },
+      { origin: 'https://img.other.net', requests: 1, types: ['image'] },
+    ]);
+  });
+});
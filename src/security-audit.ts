@@ -0,0 +1,186 @@
+/**
+ * Security header and mixed-content audit.
+ *
+ * `audit security` loads the page fresh (the current one, or the URL given)
+ * while recording every request it makes, then looks at the document's
+ * response headers and the cookies the site set. It reports the CSP, HSTS
+ * and X-Frame-Options policies, each cookie's flags, plain http requests from
+ * an https page, and the third-party origins contacted, so users can vet a
+ * site before an agent works with it.
+ */
+
+import type { Cookie, Page, Request } from 'playwright-core';
+import type { AuditIssue, SecurityAuditData } from './types.js';
+
+const SETTLE_TIMEOUT = 5000;
+/** Half a year, the minimum the HSTS preload list accepts */
+const HSTS_MIN_AGE = 15552000;
+/** Resource types that can change the page, which browsers block outright when mixed */
+const ACTIVE_TYPES = new Set(['script', 'stylesheet', 'xhr', 'fetch', 'document', 'websocket']);
+
+export interface RawSecurityPage {
+  url: string;
+  headers: Record<string, string>;
+  metaCsp: string[];
+  cookies: Pick<Cookie, 'name' | 'domain' | 'secure' | 'httpOnly' | 'sameSite'>[];
+  requests: { url: string; type: string }[];
+}
+
+export async function auditSecurity(
+  page: Page,
+  url?: string,
+  settleTimeout = SETTLE_TIMEOUT
+): Promise<SecurityAuditData> {
+  const requests: RawSecurityPage['requests'] = [];
+  const onRequest = (request: Request) => {
+    requests.push({ url: request.url(), type: request.resourceType() });
+  };
+  page.on('request', onRequest);
+  let response;
+  try {
+    response = url
+      ? await page.goto(url, { waitUntil: 'load' })
+      : await page.reload({ waitUntil: 'load' });
+    await page.waitForLoadState('networkidle', { timeout: settleTimeout }).catch(() => {});
+  } finally {
+    page.off('request', onRequest);
+  }
+  if (!response) {
+    throw new Error(`No response for ${page.url()}; audit security needs an http(s) page`);
+  }
+
+  return buildSecurityReport({
+    url: response.url(),
+    headers: await response.allHeaders(),
+    metaCsp: await page.evaluate(() =>
+      Array.from(
+        document.querySelectorAll('meta[http-equiv="content-security-policy" i]'),
+        (el) => el.getAttribute('content') ?? ''
+      )
+    ),
+    cookies: await page.context().cookies(response.url()),
+    requests,
+  });
+}
+
+/**
+ * The registrable part of a host, close enough without a public suffix list:
+ * the last two labels, or three under short second-level labels like co.uk.
+ */
+export function siteOf(host: string): string {
+  const labels = host.split('.');
+  if (labels.length <= 2 || /^[\d.]+$/.test(host)) return host;
+  const [second, top] = labels.slice(-2);
+  return labels.slice(top.length === 2 && second.length <= 3 ? -3 : -2).join('.');
+}
+
+/** Directives that matter for script injection, from `script-src` or its `default-src` fallback. */
+function scriptSources(csp: string): string[] | null {
+  const directives = new Map(
+    csp
+      .split(';')
+      .map((part) => part.trim().split(/\s+/))
+      .filter(([name]) => name)
+      .map(([name, ...values]) => [name.toLowerCase(), values] as const)
+  );
+  return directives.get('script-src') ?? directives.get('default-src') ?? null;
+}
+
+export function buildSecurityReport(raw: RawSecurityPage): SecurityAuditData {
+  const issues: AuditIssue[] = [];
+  const error = (check: string, message: string) =>
+    issues.push({ severity: 'error', check, message });
+  const warning = (check: string, message: string) =>
+    issues.push({ severity: 'warning', check, message });
+
+  const page = new URL(raw.url);
+  const https = page.protocol === 'https:';
+
+  const csp = raw.headers['content-security-policy'] ?? raw.metaCsp[0] ?? null;
+  if (!csp) {
+    warning('csp', 'No Content-Security-Policy');
+  } else {
+    const sources = scriptSources(csp);
+    const nonceOrHash = sources?.some((s) => /^'(nonce|sha\d+)-/.test(s)) ?? false;
+    if (!sources) {
+      warning('csp', 'CSP has no script-src or default-src, so scripts are unrestricted');
+    } else if (sources.includes("'unsafe-inline'") && !nonceOrHash) {
+      warning('csp', "CSP allows 'unsafe-inline' scripts");
+    }
+    if (sources?.includes("'unsafe-eval'")) warning('csp', "CSP allows 'unsafe-eval'");
+  }
+
+  const hsts = raw.headers['strict-transport-security'] ?? null;
+  if (https) {
+    const maxAge = Number(/max-age=(\d+)/i.exec(hsts ?? '')?.[1] ?? NaN);
+    if (!hsts) {
+      warning('hsts', 'No Strict-Transport-Security');
+    } else if (!(maxAge >= HSTS_MIN_AGE)) {
+      warning('hsts', `HSTS max-age is under 180 days: ${hsts}`);
+    }
+  } else {
+    error('https', 'Page is served over plain http');
+  }
+
+  const xFrameOptions = raw.headers['x-frame-options'] ?? null;
+  if (!xFrameOptions && !/frame-ancestors/i.test(raw.headers['content-security-policy'] ?? '')) {
+    warning('framing', 'No X-Frame-Options or CSP frame-ancestors; the page can be framed');
+  }
+
+  const cookies = raw.cookies.map(({ name, domain, secure, httpOnly, sameSite }) => ({
+    name,
+    domain,
+    secure,
+    httpOnly,
+    sameSite,
+  }));
+  for (const cookie of cookies) {
+    if (cookie.sameSite === 'None' && !cookie.secure) {
+      error('cookies', `Cookie ${cookie.name} is SameSite=None without Secure`);
+    } else if (https && !cookie.secure) {
+      warning('cookies', `Cookie ${cookie.name} is missing Secure`);
+    }
+  }
+
+  const mixedContent: SecurityAuditData['mixedContent'] = [];
+  const thirdParty = new Map<string, { origin: string; requests: number; types: string[] }>();
+  const site = siteOf(page.hostname);
+  for (const request of raw.requests) {
+    let target: URL;
+    try {
+      target = new URL(request.url);
+    } catch {
+      continue;
+    }
+    // data:, blob: and the like never leave the browser
+    if (!/^(https?|wss?):$/.test(target.protocol)) continue;
+    if (https && (target.protocol === 'http:' || target.protocol === 'ws:')) {
+      const active = ACTIVE_TYPES.has(request.type);
+      mixedContent.push({ url: request.url, type: request.type, active });
+      if (active) {
+        error('mixed-content', `Active mixed content (${request.type}): ${request.url}`);
+      } else {
+        warning('mixed-content', `Passive mixed content (${request.type}): ${request.url}`);
+      }
+    }
+    if (siteOf(target.hostname) !== site) {
+      const entry = thirdParty.get(target.origin) ?? {
+        origin: target.origin,
+        requests: 0,
+        types: [],
+      };
+      entry.requests++;
+      if (!entry.types.includes(request.type)) entry.types.push(request.type);
+      thirdParty.set(target.origin, entry);
+    }
+  }
+
+  return {
+    url: raw.url,
+    headers: { csp, hsts, xFrameOptions },
+    cookies,
+    mixedContent,
+    thirdParty: [...thirdParty.values()].sort((a, b) => b.requests - a.requests),
+    issues,
+  };
+}
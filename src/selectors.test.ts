@@ -0,0 +1,97 @@
+import { describe, it, expect } from 'vitest';
+import {
+  buildCandidates,
+  looksGenerated,
+  selectorHints,
+  similarity,
+  type ElementInfo,
+} from './selectors.js';
+
+const info = (overrides: Partial<ElementInfo>): ElementInfo => ({
+  tag: 'button',
+  id: null,
+  testIds: [],
+  role: 'button',
+  name: '',
+  ariaLabel: null,
+  nameAttr: null,
+  text: '',
+  path: ['body', 'main', 'button:nth-of-type(2)'],
+  anchor: null,
+  ...overrides,
+});
+
+describe('selector suggestions', () => {
+  it('spots generated ids', () => {
+    for (const id of ['ember123', 'css-1x2y3z', ':r5:', 'radix-:R1:', 'field-84213']) {
+      expect(looksGenerated(id)).toBe(true);
+    }
+    for (const id of ['login-button', 'main', 'step-2', 'searchInput']) {
+      expect(looksGenerated(id)).toBe(false);
+    }
+  });
+
+  it('ranks test ids first and css paths last', () => {
+    const candidates = buildCandidates(
+      info({
+        id: 'buy',
+        testIds: [{ attr: 'data-testid', value: 'buy-button' }],
+        name: 'Buy now',
+        text: 'Buy now',
+      })
+    );
+    expect(candidates.map((c) => [c.strategy, c.selector])).toEqual([
+      ['test-id', '[data-testid="buy-button"]'],
+      ['id', '#buy'],
+      ['role', 'role=button[name="Buy now" s]'],
+      ['text', 'text="Buy now"'],
+      ['css-path', 'body > main > button:nth-of-type(2)'],
+    ]);
+  });
+
+  it('prefers the snapshot role and scores generated values down', () => {
+    const candidates = buildCandidates(
+      info({
+        tag: 'input',
+        id: 'input-48213',
+        role: 'textbox',
+        name: 'guess',
+        nameAttr: 'email',
+        path: ['form', 'input'],
+        anchor: 'signup',
+      }),
+      { role: 'textbox', name: 'Email' }
+    );
+    expect(candidates.map((c) => [c.selector, c.score])).toEqual([
+      ['role=textbox[name="Email" s]', 85],
+      ['input[name="email"]', 75],
+      ['#input-48213', 35],
+      ['#signup > form > input', 24],
+    ]);
+  });
+});
+
+describe('selector healing', () => {
+  it('reads role and names from selectors', () => {
+    expect(selectorHints('role=button[name="Buy now" s]')).toEqual({
+      role: 'button',
+      names: ['Buy now'],
+    });
+    expect(selectorHints('[data-testid="checkoutButton"]')).toEqual({
+      role: null,
+      names: ['checkoutButton', 'checkout button'],
+    });
+    expect(selectorHints('button.submit-order#ember1234')).toEqual({
+      role: 'button',
+      names: ['submit order'],
+    });
+    expect(selectorHints('text=Sign in')).toEqual({ role: null, names: ['Sign in'] });
+  });
+
+  it('scores text similarity', () => {
+    expect(similarity('Sign in', 'sign in ')).toBe(1);
+    expect(similarity('Buy now', 'Buy it now')).toBeGreaterThan(0.6);
+    expect(similarity('Buy now', 'Cancel')).toBe(0);
+    expect(similarity('a', 'b')).toBe(0);
+  });
+});
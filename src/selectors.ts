@@ -0,0 +1,420 @@
+/**
+ * Selector suggestions (`selectors suggest`).
+ *
+ * For one element, candidate selectors are built from what tends to survive
+ * page changes: test ids, a stable-looking id, role and accessible name,
+ * aria-label, the name attribute, visible text, and a CSS path as the last
+ * resort. Each is checked against the live page and kept only if it matches
+ * that element alone. Scores (0-100) rank how likely a selector is to keep
+ * working; ids and names that look generated by a framework score lower.
+ *
+ * Suggesting for a ref also stores the candidates as the ref's fallbacks, so
+ * actions on the ref still find the element after its role or name changes.
+ *
+ * Healing (`selectors heal`) looks for the element a selector that no longer
+ * matches meant: first through fallbacks stored by an earlier suggestion,
+ * then by the accessible name the selector mentions, then by the visible
+ * element whose text is closest to it. The element found is described by its
+ * best suggestion.
+ */
+
+import type { Locator, Page } from 'playwright-core';
+import type { SelectorCandidate, SelectorsHealData } from './types.js';
+
+/** Names longer than this are likely to change with copy edits */
+const LONG_TEXT = 40;
+
+/** What the page knows about an element, read by `describeElement` */
+export interface ElementInfo {
+  tag: string;
+  id: string | null;
+  testIds: { attr: string; value: string }[];
+  role: string | null;
+  name: string;
+  ariaLabel: string | null;
+  nameAttr: string | null;
+  text: string;
+  /** `tag:nth-of-type(n)` steps from the nearest ancestor with a stable id */
+  path: string[];
+  /** That ancestor's id, if the path starts at one */
+  anchor: string | null;
+}
+
+/** Ids, names and classes generated by frameworks or build tools */
+export function looksGenerated(value: string): boolean {
+  return (
+    /\d{3,}/.test(value) ||
+    /^:|:$/.test(value) ||
+    /[a-z]\d[a-z]\d|\d[a-z]\d[a-z]/i.test(value) ||
+    /^(ember|react|mui|radix|headlessui|css|sc|jsx|svelte|ng)[-_:]/i.test(value)
+  );
+}
+
+function quote(value: string): string {
+  return JSON.stringify(value);
+}
+
+function idSelector(id: string): string {
+  return /^[A-Za-z][\w-]*$/.test(id) ? `#${id}` : `[id=${quote(id)}]`;
+}
+
+/** Candidates for an element, best first, before checking them on the page */
+export function buildCandidates(
+  info: ElementInfo,
+  accessible?: { role: string; name?: string }
+): SelectorCandidate[] {
+  const out: SelectorCandidate[] = [];
+  const add = (selector: string, strategy: SelectorCandidate['strategy'], score: number) => {
+    if (!out.some((c) => c.selector === selector)) out.push({ selector, strategy, score });
+  };
+  for (const { attr, value } of info.testIds) {
+    add(`[${attr}=${quote(value)}]`, 'test-id', looksGenerated(value) ? 60 : 95);
+  }
+  if (info.id) {
+    add(idSelector(info.id), 'id', looksGenerated(info.id) ? 35 : 90);
+  }
+  // The accessibility tree's role and name beat the page's approximation
+  const role = accessible?.role ?? info.role;
+  const name = accessible ? (accessible.name ?? '') : info.name;
+  if (role && name) {
+    const score = name.length > LONG_TEXT ? 65 : /\d/.test(name) ? 70 : 85;
+    add(`role=${role}[name=${quote(name)} s]`, 'role', score);
+  }
+  if (info.ariaLabel) {
+    add(`[aria-label=${quote(info.ariaLabel)}]`, 'aria-label', 80);
+  }
+  if (info.nameAttr) {
+    add(
+      `${info.tag}[name=${quote(info.nameAttr)}]`,
+      'name',
+      looksGenerated(info.nameAttr) ? 40 : 75
+    );
+  }
+  if (info.text && info.text.length <= LONG_TEXT * 2) {
+    add(`text=${quote(info.text)}`, 'text', info.text.length > LONG_TEXT ? 45 : 60);
+  }
+  if (info.path.length > 0) {
+    const steps = info.anchor ? [idSelector(info.anchor), ...info.path] : info.path;
+    add(steps.join(' > '), 'css-path', Math.max(5, 30 - 3 * info.path.length));
+  }
+  return out.sort((a, b) => b.score - a.score);
+}
+
+/** Runs in the page on the element */
+function describeElement(el: Element): ElementInfo {
+  const collapse = (text: string | null | undefined) => (text ?? '').replace(/\s+/g, ' ').trim();
+  // Same test as `looksGenerated`, which can't be passed in
+  const generated = (value: string) =>
+    /\d{3,}/.test(value) ||
+    /^:|:$/.test(value) ||
+    /[a-z]\d[a-z]\d|\d[a-z]\d[a-z]/i.test(value) ||
+    /^(ember|react|mui|radix|headlessui|css|sc|jsx|svelte|ng)[-_:]/i.test(value);
+  const tag = el.localName;
+  const type = (el.getAttribute('type') ?? '').toLowerCase();
+  const implicitRoles: Record<string, string> = {
+    button: 'button',
+    select: el.hasAttribute('multiple') ? 'listbox' : 'combobox',
+    textarea: 'textbox',
+    nav: 'navigation',
+    main: 'main',
+    ul: 'list',
+    ol: 'list',
+    li: 'listitem',
+    table: 'table',
+    option: 'option',
+    dialog: 'dialog',
+    form: 'form',
+  };
+  let role = el.getAttribute('role') ?? implicitRoles[tag] ?? null;
+  if (!role && tag === 'a' && el.hasAttribute('href')) role = 'link';
+  if (!role && /^h[1-6]$/.test(tag)) role = 'heading';
+  if (!role && tag === 'img' && el.getAttribute('alt')) role = 'img';
+  if (!role && tag === 'input') {
+    role =
+      (
+        {
+          checkbox: 'checkbox',
+          radio: 'radio',
+          submit: 'button',
+          button: 'button',
+          reset: 'button',
+          range: 'slider',
+          search: 'searchbox',
+        } as Record<string, string>
+      )[type] ?? (type === 'hidden' ? null : 'textbox');
+  }
+  const labelledBy = (el.getAttribute('aria-labelledby') ?? '')
+    .split(/\s+/)
+    .map((id) => document.getElementById(id)?.textContent)
+    .join(' ');
+  const labels = (el as HTMLInputElement).labels;
+  const textRoles = ['button', 'link', 'heading', 'option', 'listitem', 'checkbox', 'radio'];
+  const ownText = role && textRoles.includes(role) ? el.textContent : '';
+  const name = collapse(
+    el.getAttribute('aria-label') ||
+      labelledBy ||
+      (labels && labels.length > 0 ? Array.from(labels, (l) => l.textContent).join(' ') : '') ||
+      el.getAttribute('alt') ||
+      ownText ||
+      el.getAttribute('title') ||
+      el.getAttribute('placeholder') ||
+      (type === 'submit' ? el.getAttribute('value') : '')
+  );
+
+  const path: string[] = [];
+  let anchor: string | null = null;
+  for (let node: Element | null = el; node && node !== document.documentElement; ) {
+    if (node !== el && node.id && !generated(node.id)) {
+      anchor = node.id;
+      break;
+    }
+    const parent: Element | null = node.parentElement;
+    const same = parent
+      ? Array.from(parent.children).filter((c) => c.localName === node!.localName)
+      : [];
+    path.unshift(
+      same.length > 1
+        ? `${node.localName}:nth-of-type(${same.indexOf(node) + 1})`
+        : node.localName
+    );
+    node = parent;
+  }
+
+  const text = collapse((el as HTMLElement).innerText ?? el.textContent);
+  return {
+    tag,
+    id: el.id || null,
+    testIds: ['data-testid', 'data-test-id', 'data-test', 'data-cy', 'data-qa']
+      .filter((attr) => el.hasAttribute(attr))
+      .map((attr) => ({ attr, value: el.getAttribute(attr)! })),
+    role,
+    name,
+    ariaLabel: el.getAttribute('aria-label'),
+    nameAttr: el.getAttribute('name'),
+    // Text only identifies elements without much inside them
+    text: el.children.length <= 2 ? text : '',
+    path,
+    anchor,
+  };
+}
+
+/**
+ * The candidates that match only `target` on the page, best first. `accessible`
+ * is the role and name from the snapshot when the target is a ref.
+ */
+export async function suggestSelectors(
+  page: Page,
+  target: Locator,
+  accessible?: { role: string; name?: string }
+): Promise<SelectorCandidate[]> {
+  const handle = await target.elementHandle({ timeout: 5000 });
+  if (!handle) throw new Error('Element not found');
+  try {
+    const info = await handle.evaluate(describeElement);
+    const unique: SelectorCandidate[] = [];
+    for (const candidate of buildCandidates(info, accessible)) {
+      try {
+        const matches = page.locator(candidate.selector);
+        if ((await matches.count()) !== 1) continue;
+        if (await matches.evaluate((el, t) => el === t, handle)) unique.push(candidate);
+      } catch {
+        // Not a selector this page's engine accepts
+      }
+    }
+    return unique;
+  } finally {
+    await handle.dispose();
+  }
+}
+
+/** Nearest-text matches below this similarity aren't trusted */
+const MIN_SIMILARITY = 0.6;
+/** ...nor ones barely ahead of the next element */
+const MIN_MARGIN = 0.1;
+
+/** What a selector says about its element */
+export interface SelectorHints {
+  role: string | null;
+  /** Names, texts and identifiers, as words */
+  names: string[];
+}
+
+/** `buy-button`, `buy_button` and `buyButton` all read `buy button` */
+function words(identifier: string): string {
+  return identifier
+    .replace(/([a-z])([A-Z])/g, '$1 $2')
+    .replace(/[-_]+/g, ' ')
+    .trim()
+    .toLowerCase();
+}
+
+const TAG_ROLES: Record<string, string> = {
+  a: 'link',
+  button: 'button',
+  input: 'textbox',
+  textarea: 'textbox',
+  select: 'combobox',
+};
+
+export function selectorHints(selector: string): SelectorHints {
+  const names: string[] = [];
+  const add = (value: string) => {
+    const trimmed = value.replace(/\s+/g, ' ').trim();
+    if (trimmed && !names.includes(trimmed)) names.push(trimmed);
+  };
+  const role =
+    /^role=([\w-]+)/.exec(selector)?.[1] ??
+    TAG_ROLES[/^(a|button|input|textarea|select)\b/.exec(selector)?.[1] ?? ''] ??
+    null;
+  const quoted = Array.from(selector.matchAll(/(["'])(.*?)\1/g), (m) => m[2]);
+  quoted.forEach(add);
+  const text = /^text=([^"'].*)$/.exec(selector);
+  if (text) add(text[1]);
+  // Ids, classes and quoted identifiers like test ids, as words
+  const bare = selector.replace(/(["']).*?\1/g, '""');
+  for (const [, identifier] of bare.matchAll(/[#.]([A-Za-z][\w-]*)/g)) {
+    if (!looksGenerated(identifier)) add(words(identifier));
+  }
+  for (const value of quoted) {
+    if (/^[\w-]+$/.test(value) && !looksGenerated(value)) add(words(value));
+  }
+  return { role, names };
+}
+
+/**
+ * Dice coefficient over character pairs, 0 (nothing shared) to 1 (equal).
+ * Self-contained, as it also runs in the page.
+ */
+export function similarity(a: string, b: string): number {
+  const bigrams = (text: string) => {
+    const normalized = text.toLowerCase().replace(/\s+/g, ' ').trim();
+    return Array.from({ length: Math.max(0, normalized.length - 1) }, (_, i) =>
+      normalized.slice(i, i + 2)
+    );
+  };
+  if (a.toLowerCase().trim() === b.toLowerCase().trim()) return 1;
+  const x = bigrams(a);
+  const rest = bigrams(b);
+  const total = x.length + rest.length;
+  if (x.length === 0 || rest.length === 0) return 0;
+  let shared = 0;
+  for (const pair of x) {
+    const i = rest.indexOf(pair);
+    if (i >= 0) {
+      shared++;
+      rest.splice(i, 1);
+    }
+  }
+  return (2 * shared) / total;
+}
+
+/**
+ * Runs in the page, as source text so it can take `similarity` along. Tags
+ * the visible control whose name is closest to one of `names` with
+ * data-ab-heal and returns how close it is.
+ */
+function tagNearestText(
+  similar: typeof similarity,
+  names: string[],
+  minSimilarity: number,
+  minMargin: number
+): number | null {
+  const controls = document.querySelectorAll(
+    'a, button, input, select, textarea, summary, label, [role], [onclick], [tabindex]'
+  );
+  let best: Element | null = null;
+  let bestScore = 0;
+  let runnerUp = 0;
+  for (const el of Array.from(controls)) {
+    const rect = el.getBoundingClientRect();
+    if (rect.width === 0 || rect.height === 0) continue;
+    const text = [
+      el.getAttribute('aria-label'),
+      (el as HTMLElement).innerText,
+      el.getAttribute('placeholder'),
+      el.getAttribute('title'),
+      el.getAttribute('name'),
+      el.id,
+      el.localName === 'input' ? (el as HTMLInputElement).value : null,
+    ].filter((t): t is string => !!t && t.length <= 200);
+    let score = 0;
+    for (const name of names) {
+      for (const t of text) score = Math.max(score, similar(name, t));
+    }
+    // A label and the control inside it are the same match, not rivals
+    const related = !!best && (best.contains(el) || el.contains(best));
+    if (score > bestScore) {
+      if (!related) runnerUp = bestScore;
+      bestScore = score;
+      best = el;
+    } else if (score > runnerUp && !related) {
+      runnerUp = score;
+    }
+  }
+  if (!best || bestScore < minSimilarity || bestScore - runnerUp < minMargin) return null;
+  best.setAttribute('data-ab-heal', '');
+  return bestScore;
+}
+
+/**
+ * Find the element `selector` most likely meant now that it matches nothing
+ * (or too much), trying `fallbacks` first. `accessible` is the snapshot role
+ * and name when the selector is a ref.
+ */
+export async function healSelector(
+  page: Page,
+  selector: string,
+  fallbacks: string[],
+  accessible?: { role: string; name?: string }
+): Promise<Omit<SelectorsHealData, 'target'>> {
+  const best = async (target: Locator, strategy: SelectorsHealData['strategy']) => {
+    const [top] = await suggestSelectors(page, target);
+    return top
+      ? { selector: top.selector, strategy, score: top.score }
+      : { selector: null, strategy: null, score: null };
+  };
+  const unique = async (locator: Locator) => {
+    try {
+      return (await locator.count()) === 1;
+    } catch {
+      return false;
+    }
+  };
+
+  for (const fallback of fallbacks) {
+    if (fallback !== selector && (await unique(page.locator(fallback)))) {
+      return { selector: fallback, strategy: 'fallback', score: null };
+    }
+  }
+
+  const hints = selectorHints(selector);
+  if (accessible?.name) hints.names.unshift(accessible.name);
+  const role = accessible?.role ?? hints.role;
+  for (const name of hints.names) {
+    const locators = role
+      ? [page.getByRole(role as any, { name, exact: true })]
+      : [page.getByLabel(name, { exact: true }), page.getByText(name, { exact: true })];
+    for (const locator of locators) {
+      if (await unique(locator)) {
+        const found = await best(locator, 'accessible-name');
+        if (found.selector) return found;
+      }
+    }
+  }
+
+  if (hints.names.length > 0) {
+    const args = [hints.names, MIN_SIMILARITY, MIN_MARGIN].map((arg) => JSON.stringify(arg));
+    const tagged = (await page.evaluate(
+      `(${tagNearestText})(${similarity}, ${args.join(', ')})`
+    )) as number | null;
+    if (tagged !== null) {
+      const target = page.locator('[data-ab-heal]');
+      try {
+        return await best(target, 'nearest-text');
+      } finally {
+        await target.evaluate((el) => el.removeAttribute('data-ab-heal')).catch(() => {});
+      }
+    }
+  }
+  return { selector: null, strategy: null, score: null };
+}
@@ -0,0 +1,78 @@
+import { describe, it, expect } from 'vitest';
+import { buildSeoReport, checkStructuredData, sampleEvenly, type RawSeoPage } from './seo-audit.js';
+
+const page = (overrides: Partial<RawSeoPage> = {}): RawSeoPage => ({
+  url: 'https://example.com/',
+  titles: ['Example'],
+  descriptions: ['An example page'],
+  canonicals: ['https://example.com/'],
+  robots: [],
+  headings: [{ level: 1, text: 'Example' }],
+  links: [],
+  jsonLd: [],
+  ...overrides,
+});
+
+const noLinks = { internal: 0, checked: 0, broken: [] };
+
+describe('SEO audit', () => {
+  it('reports no issues for a complete page', () => {
+    const report = buildSeoReport(page(), noLinks);
+    expect(report.issues).toEqual([]);
+    expect(report.meta).toEqual({
+      title: 'Example',
+      description: 'An example page',
+      canonical: 'https://example.com/',
+      robots: null,
+    });
+  });
+
+  it('flags missing metadata, noindex and heading problems', () => {
+    const report = buildSeoReport(
+      page({
+        titles: [],
+        descriptions: [],
+        canonicals: [],
+        robots: ['noindex, follow'],
+        headings: [
+          { level: 2, text: 'Intro' },
+          { level: 4, text: 'Details' },
+        ],
+      }),
+      { internal: 3, checked: 3, broken: [{ url: 'https://example.com/gone', status: 404 }] }
+    );
+    expect(report.issues.map((i) => `${i.severity} ${i.check}`)).toEqual([
+      'error title',
+      'warning description',
+      'warning canonical',
+      'warning robots',
+      'error headings',
+      'warning headings',
+      'error links',
+    ]);
+    expect(report.issues[5].message).toContain('h2 to h4');
+  });
+
+  it('validates JSON-LD blocks', () => {
+    expect(
+      checkStructuredData([
+        '{"@context":"https://schema.org","@type":"Article"}',
+        '{"@graph":[{"@type":"Organization"},{"@type":["WebSite","Thing"]}]}',
+        '{"name":"no type"}',
+        '{not json',
+      ])
+    ).toEqual([
+      { types: ['Article'], valid: true },
+      { types: ['Organization', 'WebSite', 'Thing'], valid: true },
+      { types: [], valid: false, error: 'No @type' },
+      expect.objectContaining({ valid: false, error: expect.stringContaining('Invalid JSON') }),
+    ]);
+  });
+
+  it('samples links evenly across the page', () => {
+    const links = Array.from({ length: 10 }, (_, i) => i);
+    expect(sampleEvenly(links, 4)).toEqual([0, 2, 5, 7]);
+    expect(sampleEvenly(links, 20)).toHaveLength(10);
+    expect(sampleEvenly(links, 0)).toEqual([]);
+  });
+});
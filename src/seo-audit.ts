@@ -0,0 +1,208 @@
+/**
+ * SEO and metadata audit.
+ *
+ * `audit seo` reads the current page (or opens the URL given) and checks what
+ * search engines and link previews rely on: the title, meta description,
+ * canonical link and robots meta, the heading outline, JSON-LD structured
+ * data and whether a sample of the page's internal links still resolve. Each
+ * problem becomes an issue with a severity, so callers can fail on errors and
+ * just report warnings.
+ */
+
+import type { Page } from 'playwright-core';
+import type { SeoAuditData, AuditIssue } from './types.js';
+
+export const DEFAULT_LINK_SAMPLE = 20;
+
+const LINK_TIMEOUT = 10000;
+const LINK_CONCURRENCY = 5;
+const TITLE_LIMIT = 60;
+const DESCRIPTION_LIMIT = 160;
+
+/** What the page itself reports, before links are checked */
+export interface RawSeoPage {
+  url: string;
+  titles: string[];
+  descriptions: string[];
+  canonicals: string[];
+  robots: string[];
+  headings: { level: number; text: string }[];
+  links: string[];
+  jsonLd: string[];
+}
+
+export async function auditSeo(
+  page: Page,
+  url?: string,
+  sample: number = DEFAULT_LINK_SAMPLE
+): Promise<SeoAuditData> {
+  if (url) {
+    await page.goto(url, { waitUntil: 'load' });
+  }
+  const raw = await page.evaluate(readPage);
+  const origin = new URL(raw.url).origin;
+  const internal = raw.links.filter((link) => new URL(link).origin === origin);
+  const broken = await checkLinks(page, sampleEvenly(internal, sample));
+  const checked = Math.min(sample, internal.length);
+  return buildSeoReport(raw, { internal: internal.length, checked, broken });
+}
+
+/** Runs in the page. Links are absolute, without fragments, deduplicated. */
+function readPage(): RawSeoPage {
+  const attrs = (selector: string, attr: string) =>
+    Array.from(document.querySelectorAll(selector)).map((el) => el.getAttribute(attr) ?? '');
+  const links = new Set<string>();
+  for (const a of Array.from(document.querySelectorAll<HTMLAnchorElement>('a[href]'))) {
+    if (a.protocol !== 'http:' && a.protocol !== 'https:') continue;
+    const href = new URL(a.href);
+    href.hash = '';
+    links.add(href.href);
+  }
+  return {
+    url: location.href,
+    titles: Array.from(document.querySelectorAll('head title')).map((el) => el.textContent ?? ''),
+    descriptions: attrs('meta[name="description" i]', 'content'),
+    canonicals: Array.from(
+      document.querySelectorAll<HTMLLinkElement>('link[rel~="canonical" i]'),
+      (el) => el.href
+    ),
+    robots: attrs('meta[name="robots" i]', 'content'),
+    headings: Array.from(document.querySelectorAll('h1, h2, h3, h4, h5, h6')).map((el) => ({
+      level: Number(el.tagName[1]),
+      text: (el.textContent ?? '').replace(/\s+/g, ' ').trim().slice(0, 120),
+    })),
+    links: Array.from(links),
+    jsonLd: Array.from(document.querySelectorAll('script[type="application/ld+json" i]')).map(
+      (el) => el.textContent ?? ''
+    ),
+  };
+}
+
+/** Up to `n` items spread evenly over the list, so the sample covers the whole page. */
+export function sampleEvenly<T>(items: T[], n: number): T[] {
+  if (items.length <= n) return items;
+  if (n <= 0) return [];
+  return Array.from({ length: n }, (_, i) => items[Math.floor((i * items.length) / n)]);
+}
+
+/**
+ * Request each link with the page's cookies. HEAD first, since only the
+ * status matters, falling back to GET for servers that don't allow it.
+ */
+async function checkLinks(page: Page, links: string[]): Promise<SeoAuditData['links']['broken']> {
+  const request = page.context().request;
+  const broken: SeoAuditData['links']['broken'] = [];
+  const queue = [...links];
+  const worker = async () => {
+    for (let url = queue.shift(); url !== undefined; url = queue.shift()) {
+      try {
+        let response = await request.head(url, { timeout: LINK_TIMEOUT });
+        if (response.status() === 405 || response.status() === 501) {
+          response = await request.get(url, { timeout: LINK_TIMEOUT });
+        }
+        if (response.status() >= 400) broken.push({ url, status: response.status() });
+      } catch (err) {
+        broken.push({ url, status: 0, error: (err as Error).message.split('\n')[0] });
+      }
+    }
+  };
+  await Promise.all(Array.from({ length: LINK_CONCURRENCY }, worker));
+  return broken.sort((a, b) => links.indexOf(a.url) - links.indexOf(b.url));
+}
+
+/** Parse each JSON-LD block and note its @type, following @graph when present. */
+export function checkStructuredData(blocks: string[]): SeoAuditData['structuredData'] {
+  return blocks.map((text) => {
+    let data: unknown;
+    try {
+      data = JSON.parse(text);
+    } catch (err) {
+      return { types: [], valid: false, error: `Invalid JSON: ${(err as Error).message}` };
+    }
+    const objects = (value: unknown): Record<string, unknown>[] =>
+      Array.isArray(value)
+        ? value.flatMap(objects)
+        : value && typeof value === 'object'
+          ? [value as Record<string, unknown>]
+          : [];
+    const items = objects(data).flatMap((item) =>
+      item['@graph'] ? objects(item['@graph']) : [item]
+    );
+    const types = items.flatMap((item) => item['@type'] ?? []);
+    if (types.length === 0) {
+      return { types: [], valid: false, error: 'No @type' };
+    }
+    return { types: types.map(String), valid: true };
+  });
+}
+
+export function buildSeoReport(raw: RawSeoPage, links: SeoAuditData['links']): SeoAuditData {
+  const issues: AuditIssue[] = [];
+  const error = (check: string, message: string) =>
+    issues.push({ severity: 'error', check, message });
+  const warning = (check: string, message: string) =>
+    issues.push({ severity: 'warning', check, message });
+
+  const title = raw.titles[0]?.trim() || null;
+  if (!title) {
+    error('title', 'Missing <title>');
+  } else if (title.length > TITLE_LIMIT) {
+    warning('title', `Title is ${title.length} characters; results show about ${TITLE_LIMIT}`);
+  }
+
+  const description = raw.descriptions[0]?.trim() || null;
+  if (!description) {
+    warning('description', 'Missing meta description');
+  } else if (description.length > DESCRIPTION_LIMIT) {
+    warning(
+      'description',
+      `Description is ${description.length} characters; results show about ${DESCRIPTION_LIMIT}`
+    );
+  }
+  if (raw.descriptions.length > 1) warning('description', 'More than one meta description');
+
+  const canonical = raw.canonicals[0] ?? null;
+  if (!canonical) warning('canonical', 'Missing canonical link');
+  if (new Set(raw.canonicals).size > 1) {
+    error('canonical', `Conflicting canonical links: ${[...new Set(raw.canonicals)].join(', ')}`);
+  }
+
+  const robots = raw.robots.join(', ') || null;
+  const directives = (robots ?? '').toLowerCase().split(/\s*,\s*/);
+  if (directives.includes('noindex') || directives.includes('none')) {
+    warning('robots', 'Page is marked noindex');
+  }
+  if (directives.includes('nofollow') || directives.includes('none')) {
+    warning('robots', 'Links are marked nofollow');
+  }
+
+  const h1s = raw.headings.filter((h) => h.level === 1).length;
+  if (h1s === 0) error('headings', 'No <h1>');
+  if (h1s > 1) warning('headings', `${h1s} <h1> elements; use one per page`);
+  let previous = 0;
+  for (const heading of raw.headings) {
+    if (previous > 0 && heading.level > previous + 1) {
+      const skip = `h${previous} to h${heading.level}`;
+      warning('headings', `Heading skips from ${skip}: "${heading.text}"`);
+    }
+    previous = heading.level;
+  }
+
+  for (const link of links.broken) {
+    error('links', `Broken link (${link.error ?? `HTTP ${link.status}`}): ${link.url}`);
+  }
+
+  const structuredData = checkStructuredData(raw.jsonLd);
+  for (const block of structuredData) {
+    if (!block.valid) error('structured-data', `JSON-LD block: ${block.error}`);
+  }
+
+  return {
+    url: raw.url,
+    meta: { title, description, canonical, robots },
+    headings: raw.headings,
+    links,
+    structuredData,
+    issues,
+  };
+}
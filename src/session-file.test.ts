@@ -0,0 +1,28 @@
+import { describe, it, expect } from 'vitest';
+import { parseSessionFile, storageOrigin } from './session-file.js';
+
+describe('session files', () => {
+  it('fills in what older or hand-written files leave out', () => {
+    const file = parseSessionFile(
+      JSON.stringify({ version: 1, active: 5, tabs: [{ url: 'https://example.com/a' }] }),
+      'task.json'
+    );
+    expect(file.active).toBe(0);
+    expect(file.tabs[0]).toMatchObject({ scroll: { x: 0, y: 0 }, sessionStorage: {} });
+    expect(file.storage).toEqual({ cookies: [], origins: [] });
+  });
+
+  it('rejects files that are not session files', () => {
+    expect(() => parseSessionFile('{', 'x.json')).toThrow(/not a session file/);
+    expect(() => parseSessionFile('{"cookies":[]}', 'state.json')).toThrow(/no tabs/);
+    expect(() => parseSessionFile('{"version":2,"tabs":[]}', 'new.json')).toThrow(/version 2/);
+    expect(() => parseSessionFile('{"version":1,"tabs":[{}]}', 'x.json')).toThrow(/without a URL/);
+  });
+
+  it('only seeds storage for http(s) pages', () => {
+    expect(storageOrigin('https://example.com:8443/a?b#c')).toBe('https://example.com:8443');
+    expect(storageOrigin('about:blank')).toBeNull();
+    expect(storageOrigin('data:text/html,hi')).toBeNull();
+    expect(storageOrigin('not a url')).toBeNull();
+  });
+});
@@ -0,0 +1,78 @@
+/**
+ * Session files (`session save` / `session restore`).
+ *
+ * A session file records what a long task needs to pick up roughly where it
+ * stopped after a restart: the open tabs with their URLs, scroll positions
+ * and sessionStorage, which tab was active, and the context's storage state
+ * (cookies and localStorage, as Playwright's `storageState()` reports it).
+ * Restoring reopens the tabs and reapplies the storage; page state that lives
+ * only in memory, such as form input or a half-finished SPA flow, is lost.
+ */
+
+import type { BrowserContext } from 'playwright-core';
+
+export const SESSION_FILE_VERSION = 1;
+
+export type StorageState = Awaited<ReturnType<BrowserContext['storageState']>>;
+
+export interface SessionTab {
+  url: string;
+  title: string;
+  scroll: { x: number; y: number };
+  sessionStorage: Record<string, string>;
+}
+
+export interface SessionFile {
+  version: number;
+  savedAt: string;
+  /** Index into `tabs` of the tab that was active */
+  active: number;
+  tabs: SessionTab[];
+  storage: StorageState;
+}
+
+/** Parse and check a session file, so a bad one fails before any tab opens. */
+export function parseSessionFile(text: string, source: string): SessionFile {
+  let file: SessionFile;
+  try {
+    file = JSON.parse(text);
+  } catch (err) {
+    throw new Error(`${source} is not a session file: ${(err as Error).message}`);
+  }
+  if (typeof file !== 'object' || file === null || !Array.isArray(file.tabs)) {
+    throw new Error(`${source} is not a session file (no tabs)`);
+  }
+  if (file.version !== SESSION_FILE_VERSION) {
+    throw new Error(
+      `${source} has session file version ${file.version}; this agent-browser reads version ${SESSION_FILE_VERSION}`
+    );
+  }
+  for (const tab of file.tabs) {
+    if (typeof tab?.url !== 'string') {
+      throw new Error(`${source} has a tab without a URL`);
+    }
+    tab.scroll ??= { x: 0, y: 0 };
+    tab.sessionStorage ??= {};
+  }
+  file.storage ??= { cookies: [], origins: [] };
+  file.storage.cookies ??= [];
+  file.storage.origins ??= [];
+  if (!Number.isInteger(file.active) || file.active < 0 || file.active >= file.tabs.length) {
+    file.active = 0;
+  }
+  return file;
+}
+
+/**
+ * Origin whose storage can be seeded for `url`: only http(s) pages have
+ * storage worth restoring, and the rest (about:blank, data:, chrome://) are
+ * just reopened.
+ */
+export function storageOrigin(url: string): string | null {
+  try {
+    const parsed = new URL(url);
+    return parsed.protocol === 'http:' || parsed.protocol === 'https:' ? parsed.origin : null;
+  } catch {
+    return null;
+  }
+}
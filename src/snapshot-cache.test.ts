@@ -0,0 +1,58 @@
+import { describe, it, expect } from 'vitest';
+import type { Frame } from 'playwright-core';
+import { SnapshotCache } from './snapshot-cache.js';
+
+/** A frame whose DOM version the test sets */
+function fakeFrame(version: { value: string | null }): Frame {
+  return {
+    evaluate: async () => {
+      if (version.value === null) throw new Error('Execution context was destroyed');
+      return version.value;
+    },
+  } as unknown as Frame;
+}
+
+describe('SnapshotCache', () => {
+  it('reuses a frame tree until its DOM changes', async () => {
+    const cache = new SnapshotCache();
+    const version = { value: 'doc1:0' };
+    const frame = fakeFrame(version);
+    let walks = 0;
+    const walk = async () => `- heading "Walk ${++walks}"`;
+
+    expect(await cache.aria(frame, '', walk)).toBe('- heading "Walk 1"');
+    expect(await cache.aria(frame, '', walk)).toBe('- heading "Walk 1"');
+    // Scoped snapshots are kept apart
+    expect(await cache.aria(frame, '#main', walk)).toBe('- heading "Walk 2"');
+
+    version.value = 'doc1:3';
+    expect(await cache.aria(frame, '', walk)).toBe('- heading "Walk 3"');
+    expect(cache.hits).toBe(1);
+    expect(cache.misses).toBe(3);
+
+    cache.clear();
+    expect(await cache.aria(frame, '', walk)).toBe('- heading "Walk 4"');
+  });
+
+  it('walks frames it cannot version every time', async () => {
+    const cache = new SnapshotCache();
+    const frame = fakeFrame({ value: null });
+    let walks = 0;
+    const walk = async () => `- text: ${++walks}`;
+    await cache.aria(frame, '', walk);
+    expect(await cache.aria(frame, '', walk)).toBe('- text: 2');
+  });
+
+  it("doesn't keep a tree that changed while it was walked", async () => {
+    const cache = new SnapshotCache();
+    const version = { value: 'doc1:0' };
+    const frame = fakeFrame(version);
+    let walks = 0;
+    const walk = async () => {
+      version.value = `doc1:${++walks}`;
+      return `- text: ${walks}`;
+    };
+    await cache.aria(frame, '', walk);
+    expect(await cache.aria(frame, '', walk)).toBe('- text: 2');
+  });
+});
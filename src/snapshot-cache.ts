@@ -0,0 +1,110 @@
+/**
+ * Snapshot reuse for frames that haven't changed.
+ *
+ * Agents snapshot after nearly every step, and most steps leave most of the
+ * page alone. Asking Playwright for a frame's ARIA tree walks its whole DOM,
+ * which takes hundreds of milliseconds on large pages. Instead, each frame
+ * gets a MutationObserver (also watching open shadow roots) plus `input` and
+ * `change` listeners that count changes, and a frame whose document and count
+ * are the same as at its last snapshot reuses the tree it had then. Only the
+ * frames that changed are walked again, so snapshotting a mostly idle page
+ * with a few iframes costs one small evaluate per frame.
+ *
+ * Changes a DOM observer can't see, like a stylesheet's `:hover` rule showing
+ * a menu, aren't noticed; `snapshot --fresh` walks every frame regardless.
+ */
+
+import type { Frame } from 'playwright-core';
+
+/**
+ * Installs the change counter in a frame once, and returns a token that
+ * changes whenever the frame's DOM does: its document's id and the count.
+ */
+const VERSION_SCRIPT = `() => {
+  const key = '__agentBrowserDomVersion';
+  let state = window[key];
+  if (!state) {
+    state = { id: Math.random().toString(36).slice(2), count: 0 };
+    const bump = () => { state.count++; };
+    const observer = new MutationObserver((records) => {
+      bump();
+      for (const record of records) {
+        for (const node of record.addedNodes) watchShadows(node);
+      }
+    });
+    const options = { subtree: true, childList: true, attributes: true, characterData: true };
+    const watchShadows = (root) => {
+      if (!root || !root.querySelectorAll) return;
+      const all = [...root.querySelectorAll('*')];
+      for (const el of root instanceof Element ? [root, ...all] : all) {
+        if (el.shadowRoot) {
+          observer.observe(el.shadowRoot, options);
+          watchShadows(el.shadowRoot);
+        }
+      }
+    };
+    observer.observe(document, options);
+    if (document.documentElement) watchShadows(document.documentElement);
+    const attachShadow = Element.prototype.attachShadow;
+    Element.prototype.attachShadow = function (init) {
+      const shadow = attachShadow.call(this, init);
+      if (init && init.mode === 'open') observer.observe(shadow, options);
+      bump();
+      return shadow;
+    };
+    // Form values and checked states are properties, not attributes
+    document.addEventListener('input', bump, true);
+    document.addEventListener('change', bump, true);
+    window.addEventListener('resize', bump);
+    Object.defineProperty(window, key, { value: state });
+  }
+  return state.id + ':' + state.count;
+}`;
+
+interface Entry {
+  version: string;
+  aria: string;
+}
+
+export class SnapshotCache {
+  // Per frame, by the selector the snapshot was scoped to
+  private entries = new WeakMap<Frame, Map<string, Entry>>();
+  private versionFn: () => string;
+  /** Frames reused, and frames walked again, since the daemon started */
+  hits = 0;
+  misses = 0;
+
+  constructor() {
+    // eslint-disable-next-line @typescript-eslint/no-implied-eval
+    this.versionFn = new Function('return ' + VERSION_SCRIPT)();
+  }
+
+  /**
+   * The frame's ARIA tree: the one from its last snapshot if its DOM hasn't
+   * changed since, else `snapshot()`, which is kept for next time.
+   */
+  async aria(frame: Frame, selector: string, snapshot: () => Promise<string>): Promise<string> {
+    const version = await frame.evaluate(this.versionFn).catch(() => null);
+    const entries = this.entries.get(frame) ?? new Map<string, Entry>();
+    const cached = entries.get(selector);
+    if (version !== null && cached?.version === version) {
+      this.hits++;
+      return cached.aria;
+    }
+    this.misses++;
+    const aria = await snapshot();
+    if (version !== null) {
+      // Read again: a change while walking means the tree may be half old
+      const after = await frame.evaluate(this.versionFn).catch(() => null);
+      if (after === version) {
+        entries.set(selector, { version, aria });
+        this.entries.set(frame, entries);
+      }
+    }
+    return aria;
+  }
+
+  clear(): void {
+    this.entries = new WeakMap();
+  }
+}
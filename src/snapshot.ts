@@ -17,7 +17,8 @@
  *   agent-browser click @e2             # Click element by ref
  */
 
-import type { Page, Locator } from 'playwright-core';
+import type { Page, Frame, Locator } from 'playwright-core';
+import type { SnapshotCache } from './snapshot-cache.js';
 
 export interface RefMap {
   [ref: string]: {
@@ -26,6 +27,10 @@ export interface RefMap {
     name?: string;
     /** Index for disambiguation when multiple elements have same role+name */
     nth?: number;
+    /** Selectors of the iframe chain containing the element (outermost first) */
+    frames?: string[];
+    /** Selectors that also find the element, from `selectors suggest` */
+    fallbacks?: string[];
   };
 }
 
@@ -45,6 +50,8 @@ export interface SnapshotOptions {
   compact?: boolean;
   /** CSS selector to scope the snapshot */
   selector?: string;
+  /** Reuse the trees of frames that haven't changed since their last snapshot */
+  cache?: SnapshotCache;
 }
 
 // Counter for generating refs
@@ -257,9 +264,13 @@ export async function getEnhancedSnapshot(
   resetRefs();
   const refs: RefMap = {};
 
-  // Get ARIA snapshot from Playwright
+  // Get ARIA snapshot from Playwright, or the cache if the page hasn't changed
   const locator = options.selector ? page.locator(options.selector) : page.locator(':root');
-  const ariaTree = await locator.ariaSnapshot();
+  const ariaTree = options.cache
+    ? await options.cache.aria(page.mainFrame(), options.selector ?? '', () =>
+        locator.ariaSnapshot()
+      )
+    : await locator.ariaSnapshot();
 
   if (!ariaTree) {
     return {
@@ -268,8 +279,9 @@ export async function getEnhancedSnapshot(
     };
   }
 
-  // Parse and enhance the ARIA tree
-  const enhancedTree = processAriaTree(ariaTree, refs, options);
+  // Parse and enhance the ARIA tree, then pull in iframe content
+  const mainTree = processAriaTree(ariaTree, refs, options);
+  const enhancedTree = await stitchChildFrames(page.mainFrame(), mainTree, refs, options, []);
 
   // When cursor flag is set, also find cursor-interactive elements
   // that may not have proper ARIA roles
@@ -316,6 +328,100 @@ export async function getEnhancedSnapshot(
   return { tree: enhancedTree, refs };
 }
 
+/**
+ * Snapshot the visible child frames of `frame` and nest their trees under the
+ * matching `- iframe` lines of the parent tree. Out-of-process iframes are
+ * included too, since Playwright attaches to them as child targets.
+ * Frames that can't be matched to a line (e.g. in interactive mode, where
+ * iframe lines are filtered out) are appended at the end.
+ */
+async function stitchChildFrames(
+  frame: Frame,
+  tree: string,
+  refs: RefMap,
+  options: SnapshotOptions,
+  chain: string[]
+): Promise<string> {
+  // Only the top-level snapshot is scoped by selector
+  const scope = chain.length === 0 ? options.selector : undefined;
+  const describeScript = `(el, scope) => {
+    const r = el.getBoundingClientRect();
+    const s = getComputedStyle(el);
+    return {
+      index: Array.from(document.querySelectorAll('iframe, frame')).indexOf(el),
+      inScope: !scope || !!el.parentElement?.closest(scope),
+      visible: r.width > 0 && r.height > 0 && s.visibility !== 'hidden',
+    };
+  }`;
+  // eslint-disable-next-line @typescript-eslint/no-implied-eval
+  const describe = new Function('return ' + describeScript)();
+
+  const children: Array<{ frame: Frame; index: number }> = [];
+  for (const child of frame.childFrames()) {
+    const element = await child.frameElement().catch(() => null);
+    if (!element) continue;
+    const info = await element.evaluate(describe, scope).catch(() => null);
+    await element.dispose().catch(() => {});
+    if (info && info.index !== -1 && info.inScope && info.visible) {
+      children.push({ frame: child, index: info.index });
+    }
+  }
+  if (children.length === 0) return tree;
+  children.sort((a, b) => a.index - b.index);
+
+  const lines = tree === '(no interactive elements)' ? [] : tree.split('\n');
+  const iframeLines = lines
+    .map((line, i) => (/^\s*- iframe\b/.test(line) ? i : -1))
+    .filter((i) => i !== -1);
+  const inserts = new Map<number, string[]>();
+  const appended: string[] = [];
+
+  for (let k = 0; k < children.length; k++) {
+    const lineIndex = iframeLines[k];
+    const parentDepth = lineIndex !== undefined ? getIndentLevel(lines[lineIndex]) : 0;
+    const childOptions: SnapshotOptions = { ...options, selector: undefined };
+    if (options.maxDepth !== undefined) {
+      childOptions.maxDepth = options.maxDepth - parentDepth - 1;
+      if (childOptions.maxDepth < 0) continue;
+    }
+
+    const childChain = [...chain, `:is(iframe, frame) >> nth=${children[k].index}`];
+    const childFrame = children[k].frame;
+    const walk = () =>
+      childFrame
+        .locator(':root')
+        .ariaSnapshot()
+        .catch(() => '');
+    const childAria = options.cache ? await options.cache.aria(childFrame, '', walk) : await walk();
+    if (!childAria) continue;
+
+    let childTree = processAriaTree(childAria, refs, childOptions, childChain);
+    childTree = await stitchChildFrames(children[k].frame, childTree, refs, childOptions, childChain);
+    if (!childTree.trim() || childTree === '(no interactive elements)') continue;
+
+    const indent = '  '.repeat(parentDepth + 1);
+    const nested = childTree.split('\n').map((line) => indent + line);
+    if (lineIndex !== undefined) {
+      inserts.set(lineIndex, nested);
+    } else {
+      appended.push('- iframe:', ...nested);
+    }
+  }
+
+  const result: string[] = [];
+  lines.forEach((line, i) => {
+    const nested = inserts.get(i);
+    if (nested) {
+      result.push(line.endsWith(':') ? line : line + ':', ...nested);
+    } else {
+      result.push(line);
+    }
+  });
+  result.push(...appended);
+
+  return result.join('\n') || tree;
+}
+
 /**
  * Track role+name combinations to detect duplicates
  */
@@ -366,7 +472,12 @@ function createRoleNameTracker(): RoleNameTracker {
 /**
  * Process ARIA snapshot: add refs and apply filters
  */
-function processAriaTree(ariaTree: string, refs: RefMap, options: SnapshotOptions): string {
+function processAriaTree(
+  ariaTree: string,
+  refs: RefMap,
+  options: SnapshotOptions,
+  frames?: string[]
+): string {
   const lines = ariaTree.split('\n');
   const result: string[] = [];
   const tracker = createRoleNameTracker();
@@ -389,6 +500,7 @@ function processAriaTree(ariaTree: string, refs: RefMap, options: SnapshotOption
           role: roleLower,
           name,
           nth, // Always store nth, we'll use it for duplicates
+          ...(frames && { frames }),
         };
 
         let enhanced = `- ${role}`;
@@ -410,7 +522,7 @@ function processAriaTree(ariaTree: string, refs: RefMap, options: SnapshotOption
 
   // Normal processing with depth/compact filters
   for (const line of lines) {
-    const processed = processLine(line, refs, options, tracker);
+    const processed = processLine(line, refs, options, tracker, frames);
     if (processed !== null) {
       result.push(processed);
     }
@@ -430,15 +542,13 @@ function processAriaTree(ariaTree: string, refs: RefMap, options: SnapshotOption
 /**
  * Remove nth from refs that ended up not having duplicates
  * This keeps single-element locators simple (no unnecessary .nth(0))
+ * Only refs tracked by this tracker are touched, since each frame has its own.
  */
 function removeNthFromNonDuplicates(refs: RefMap, tracker: RoleNameTracker): void {
-  const duplicateKeys = tracker.getDuplicateKeys();
-
-  for (const [ref, data] of Object.entries(refs)) {
-    const key = tracker.getKey(data.role, data.name);
-    if (!duplicateKeys.has(key)) {
+  for (const trackedRefs of tracker.refsByKey.values()) {
+    if (trackedRefs.length === 1) {
       // Not a duplicate, remove nth to keep locator simple
-      delete refs[ref].nth;
+      delete refs[trackedRefs[0]].nth;
     }
   }
 }
@@ -458,7 +568,8 @@ function processLine(
   line: string,
   refs: RefMap,
   options: SnapshotOptions,
-  tracker: RoleNameTracker
+  tracker: RoleNameTracker,
+  frames?: string[]
 ): string | null {
   const depth = getIndentLevel(line);
 
@@ -517,6 +628,7 @@ function processLine(
       role: roleLower,
       name,
       nth, // Always store nth, we'll clean up non-duplicates later
+      ...(frames && { frames }),
     };
 
     // Build enhanced line with ref
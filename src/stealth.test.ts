@@ -0,0 +1,24 @@
+import { describe, it, expect } from 'vitest';
+import { languagesFor, webglIdentity } from './stealth.js';
+
+describe('stealth', () => {
+  it('replaces only software WebGL renderers', () => {
+    const swiftshader = 'ANGLE (Google, Vulkan 1.3.0 (SwiftShader Device (Subzero)), SwiftShader driver)';
+    expect(webglIdentity('Linux x86_64', 'Google Inc. (Google)', swiftshader).renderer).toContain(
+      'Mesa Intel'
+    );
+    expect(webglIdentity('MacIntel', 'Google Inc. (Google)', swiftshader).vendor).toBe(
+      'Google Inc. (Apple)'
+    );
+    expect(webglIdentity('Win32', 'Google Inc. (Google)', swiftshader).renderer).toContain('D3D11');
+    const gpu = { vendor: 'Google Inc. (NVIDIA)', renderer: 'ANGLE (NVIDIA, GeForce RTX 3060)' };
+    expect(webglIdentity('Win32', gpu.vendor, gpu.renderer)).toEqual(gpu);
+  });
+
+  it('keeps navigator.languages consistent with the locale', () => {
+    expect(languagesFor('de-DE', [])).toEqual(['de-DE', 'de']);
+    expect(languagesFor('en-US', ['en-US', 'en', 'fr'])).toEqual(['en-US', 'en', 'fr']);
+    expect(languagesFor('fr-FR', ['en-US'])).toEqual(['fr-FR', 'fr']);
+    expect(languagesFor('en', [])).toEqual(['en']);
+  });
+});
@@ -0,0 +1,170 @@
+/**
+ * `--stealth`: fingerprint adjustments for sites that turn away headless browsers.
+ *
+ * Automated Chromium differs from a desktop Chrome in ways page scripts can
+ * read: `navigator.webdriver` is true, there are no plugins, the language
+ * list can disagree with the locale, `window.chrome` is missing, notification
+ * permissions contradict each other and WebGL reports a software renderer.
+ * The init script below smooths those over in every frame before page scripts
+ * run. It changes what JavaScript sees, not how the browser talks to the
+ * network, so it is a best effort rather than a guarantee; see the README.
+ */
+
+/** Chromium switch that stops Blink from setting `navigator.webdriver` */
+export const STEALTH_ARGS = ['--disable-blink-features=AutomationControlled'];
+
+/**
+ * The WebGL vendor and renderer to report. Real GPUs are left alone; the
+ * software renderers headless Chromium falls back to are swapped for a common
+ * integrated GPU, described the way Chrome does on that platform.
+ */
+export function webglIdentity(
+  platform: string,
+  vendor: string,
+  renderer: string
+): { vendor: string; renderer: string } {
+  if (!/swiftshader|llvmpipe|software/i.test(`${vendor} ${renderer}`)) {
+    return { vendor, renderer };
+  }
+  if (/^Mac/.test(platform)) {
+    return {
+      vendor: 'Google Inc. (Apple)',
+      renderer: 'ANGLE (Apple, ANGLE Metal Renderer: Apple M1, Unspecified Version)',
+    };
+  }
+  if (/^Win/.test(platform)) {
+    return {
+      vendor: 'Google Inc. (Intel)',
+      renderer:
+        'ANGLE (Intel, Intel(R) UHD Graphics 630 (0x00003E9B) Direct3D11 vs_5_0 ps_5_0, D3D11)',
+    };
+  }
+  return {
+    vendor: 'Google Inc. (Intel)',
+    renderer: 'ANGLE (Intel, Mesa Intel(R) UHD Graphics 630 (CFL GT2), OpenGL 4.6)',
+  };
+}
+
+/** `navigator.languages` the way Chrome builds it: the locale, then its base language. */
+export function languagesFor(language: string, languages: readonly string[]): string[] {
+  if (languages.length > 0 && languages[0] === language) return [...languages];
+  const base = language.split('-')[0];
+  return base && base !== language ? [language, base] : [language];
+}
+
+export const STEALTH_SCRIPT = `(() => {
+  if (window.__agentBrowserStealth) return;
+  Object.defineProperty(window, '__agentBrowserStealth', { value: true });
+  const webglIdentity = ${webglIdentity.toString()};
+  const languagesFor = ${languagesFor.toString()};
+
+  // Patched functions still print as native code
+  const native = new WeakMap();
+  const nativeToString = Function.prototype.toString;
+  const patchedToString = function toString() {
+    return native.has(this) ? native.get(this) : nativeToString.call(this);
+  };
+  native.set(patchedToString, nativeToString.call(nativeToString));
+  Function.prototype.toString = patchedToString;
+  const getter = (proto, name, get) => {
+    const original = Object.getOwnPropertyDescriptor(proto, name);
+    if (!original) return;
+    native.set(get, 'function get ' + name + '() { [native code] }');
+    Object.defineProperty(proto, name, { ...original, get });
+  };
+  const method = (proto, name, fn) => {
+    if (typeof proto[name] !== 'function') return;
+    native.set(fn, 'function ' + name + '() { [native code] }');
+    proto[name] = fn;
+  };
+
+  getter(Navigator.prototype, 'webdriver', () => false);
+
+  const languages = Object.freeze(languagesFor(navigator.language, navigator.languages));
+  getter(Navigator.prototype, 'languages', () => languages);
+
+  // Desktop Chrome lists the same five PDF viewers everywhere
+  if (navigator.plugins.length === 0) {
+    const mimes = ['application/pdf', 'text/pdf'].map((type) => {
+      const mime = Object.create(MimeType.prototype);
+      Object.defineProperties(mime, {
+        type: { value: type },
+        suffixes: { value: 'pdf' },
+        description: { value: 'Portable Document Format' },
+      });
+      return mime;
+    });
+    const list = (proto, items, key) => {
+      const result = Object.create(proto);
+      items.forEach((item, i) => {
+        Object.defineProperty(result, i, { value: item, enumerable: true });
+      });
+      Object.defineProperties(result, {
+        length: { value: items.length },
+        item: { value: (i) => items[i] ?? null },
+        namedItem: { value: (name) => items.find((item) => item[key] === name) ?? null },
+        [Symbol.iterator]: { value: () => items[Symbol.iterator]() },
+      });
+      return result;
+    };
+    const plugins = [
+      'PDF Viewer',
+      'Chrome PDF Viewer',
+      'Chromium PDF Viewer',
+      'Microsoft Edge PDF Viewer',
+      'WebKit built-in PDF',
+    ].map((name) => {
+      const plugin = list(Plugin.prototype, mimes, 'type');
+      Object.defineProperties(plugin, {
+        name: { value: name },
+        filename: { value: 'internal-pdf-viewer' },
+        description: { value: 'Portable Document Format' },
+      });
+      return plugin;
+    });
+    for (const mime of mimes) Object.defineProperty(mime, 'enabledPlugin', { value: plugins[0] });
+    const pluginArray = list(PluginArray.prototype, plugins, 'name');
+    const mimeTypeArray = list(MimeTypeArray.prototype, mimes, 'type');
+    getter(Navigator.prototype, 'plugins', () => pluginArray);
+    getter(Navigator.prototype, 'mimeTypes', () => mimeTypeArray);
+    getter(Navigator.prototype, 'pdfViewerEnabled', () => true);
+  }
+
+  if (!window.chrome) {
+    Object.defineProperty(window, 'chrome', {
+      value: { runtime: {} },
+      writable: true,
+      configurable: true,
+    });
+  }
+
+  // Headless answers "denied" from Notification but "prompt" from the Permissions API
+  if (window.Notification && navigator.permissions) {
+    const originalQuery = Permissions.prototype.query;
+    method(Permissions.prototype, 'query', function query(descriptor) {
+      if (descriptor && descriptor.name === 'notifications') {
+        const state = Notification.permission === 'default' ? 'prompt' : Notification.permission;
+        const status = { state, onchange: null };
+        return Promise.resolve(Object.setPrototypeOf(status, PermissionStatus.prototype));
+      }
+      return originalQuery.call(this, descriptor);
+    });
+  }
+
+  const UNMASKED_VENDOR = 0x9245;
+  const UNMASKED_RENDERER = 0x9246;
+  for (const context of [window.WebGLRenderingContext, window.WebGL2RenderingContext]) {
+    if (!context) continue;
+    const original = context.prototype.getParameter;
+    method(context.prototype, 'getParameter', function getParameter(parameter) {
+      const value = original.call(this, parameter);
+      if (parameter !== UNMASKED_VENDOR && parameter !== UNMASKED_RENDERER) return value;
+      const identity = webglIdentity(
+        navigator.platform,
+        String(original.call(this, UNMASKED_VENDOR)),
+        String(original.call(this, UNMASKED_RENDERER))
+      );
+      return parameter === UNMASKED_VENDOR ? identity.vendor : identity.renderer;
+    });
+  }
+})()`;
@@ -1,6 +1,7 @@
 import { WebSocketServer, WebSocket } from 'ws';
 import type { BrowserManager, ScreencastFrame } from './browser.js';
 import { setScreencastFrameCallback } from './actions.js';
+import { MAX_CLIENT_BUFFER, binaryFrame, type CaptureOptions } from './screencast.js';
 
 // Message types for WebSocket communication
 export interface FrameMessage {
@@ -72,13 +73,17 @@ export type StreamMessage =
 export class StreamServer {
   private wss: WebSocketServer | null = null;
   private clients: Set<WebSocket> = new Set();
+  // Clients that connected with ?binary=1 and get frames as binary messages
+  private binaryClients: WeakSet<WebSocket> = new WeakSet();
   private browser: BrowserManager;
   private port: number;
+  private capture: CaptureOptions;
   private isScreencasting: boolean = false;
 
-  constructor(browser: BrowserManager, port: number = 9223) {
+  constructor(browser: BrowserManager, port: number = 9223, capture: CaptureOptions = {}) {
     this.browser = browser;
     this.port = port;
+    this.capture = capture;
   }
 
   /**
@@ -107,7 +112,9 @@ export class StreamServer {
           },
         });
 
-        this.wss.on('connection', (ws) => {
+        this.wss.on('connection', (ws, req) => {
+          const query = new URL(req.url ?? '/', 'ws://localhost').searchParams;
+          if (query.get('binary') === '1') this.binaryClients.add(ws);
           this.handleConnection(ws);
         });
 
@@ -257,20 +264,27 @@ export class StreamServer {
   }
 
   /**
-   * Broadcast a frame to all connected clients
+   * Broadcast a frame to all connected clients, each payload built once and
+   * only if a client needs it. Clients that are behind skip the frame.
    */
   private broadcastFrame(frame: ScreencastFrame): void {
-    const message: FrameMessage = {
-      type: 'frame',
-      data: frame.data,
-      metadata: frame.metadata,
-    };
-
-    const payload = JSON.stringify(message);
+    let json: string | null = null;
+    let binary: Buffer | null = null;
 
     for (const client of this.clients) {
-      if (client.readyState === WebSocket.OPEN) {
-        client.send(payload);
+      if (client.readyState !== WebSocket.OPEN || client.bufferedAmount > MAX_CLIENT_BUFFER) {
+        continue;
+      }
+      if (this.binaryClients.has(client)) {
+        binary ??= binaryFrame(frame);
+        client.send(binary, { binary: true });
+      } else {
+        json ??= JSON.stringify({
+          type: 'frame',
+          data: frame.data,
+          metadata: frame.metadata,
+        } satisfies FrameMessage);
+        client.send(json);
       }
     }
   }
@@ -334,10 +348,12 @@ export class StreamServer {
 
       await this.browser.startScreencast((frame) => this.broadcastFrame(frame), {
         format: 'jpeg',
-        quality: 80,
+        quality: this.capture.quality ?? 80,
         maxWidth: 1280,
         maxHeight: 720,
         everyNthFrame: 1,
+        scale: this.capture.scale,
+        fps: this.capture.fps,
       });
 
       // Notify all clients
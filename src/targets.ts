@@ -0,0 +1,173 @@
+/**
+ * Pages and workers as evaluation targets.
+ *
+ * `targets` lists every tab plus the dedicated, service and shared workers
+ * running in the browser, each with a short id (`p0`, `w1`, `sw1`, `shw1`)
+ * that `eval --target` accepts. Dedicated and service workers come from
+ * Playwright. Playwright doesn't expose shared workers, so they are found
+ * and evaluated through the browser's CDP session (Chromium only).
+ */
+
+import type { Browser, BrowserContext, CDPSession, Page, Worker } from 'playwright-core';
+import type { TargetInfo } from './types.js';
+
+interface SharedWorkerTarget {
+  targetId: string;
+  url: string;
+}
+
+const EVALUATE_TIMEOUT = 30_000;
+
+export class TargetRegistry {
+  /** Ids handed out so far, keyed by worker object or shared worker target id */
+  private ids = new Map<Worker | string, string>();
+  private counters: Record<string, number> = {};
+  private browserSession: CDPSession | null = null;
+
+  async list(pages: Page[], contexts: BrowserContext[], browser: Browser | null): Promise<TargetInfo[]> {
+    const targets: TargetInfo[] = pages.map((page, index) => ({
+      id: `p${index}`,
+      type: 'page',
+      url: page.url(),
+    }));
+    pages.forEach((page, index) => {
+      for (const worker of page.workers()) {
+        targets.push({ id: this.idFor(worker, 'w'), type: 'worker', url: worker.url(), page: index });
+      }
+    });
+    for (const context of contexts) {
+      for (const worker of serviceWorkers(context)) {
+        targets.push({ id: this.idFor(worker, 'sw'), type: 'service_worker', url: worker.url() });
+      }
+    }
+    for (const shared of await this.sharedWorkers(browser)) {
+      targets.push({
+        id: this.idFor(shared.targetId, 'shw'),
+        type: 'shared_worker',
+        url: shared.url,
+      });
+    }
+    return targets;
+  }
+
+  async evaluate(
+    id: string,
+    script: string,
+    pages: Page[],
+    contexts: BrowserContext[],
+    browser: Browser | null
+  ): Promise<unknown> {
+    const pageIndex = /^p(\d+)$/.exec(id);
+    if (pageIndex) {
+      const page = pages[Number(pageIndex[1])];
+      if (!page) throw unknownTarget(id);
+      return page.evaluate(script);
+    }
+
+    // Refresh ids so targets that appeared since the last list can be used
+    await this.list(pages, contexts, browser);
+    const key = [...this.ids].find(([, value]) => value === id)?.[0];
+    if (key === undefined) throw unknownTarget(id);
+    if (typeof key === 'string') return this.evaluateShared(key, script);
+    return key.evaluate(script);
+  }
+
+  private idFor(key: Worker | string, prefix: string): string {
+    let id = this.ids.get(key);
+    if (!id) {
+      this.counters[prefix] = (this.counters[prefix] ?? 0) + 1;
+      id = `${prefix}${this.counters[prefix]}`;
+      this.ids.set(key, id);
+    }
+    return id;
+  }
+
+  private async session(browser: Browser | null): Promise<CDPSession | null> {
+    if (!browser) return null;
+    if (!this.browserSession) {
+      this.browserSession = await browser.newBrowserCDPSession().catch(() => null);
+    }
+    return this.browserSession;
+  }
+
+  private async sharedWorkers(browser: Browser | null): Promise<SharedWorkerTarget[]> {
+    const cdp = await this.session(browser);
+    if (!cdp) return [];
+    try {
+      const { targetInfos } = await cdp.send('Target.getTargets');
+      return targetInfos
+        .filter((t) => t.type === 'shared_worker')
+        .map((t) => ({ targetId: t.targetId, url: t.url }));
+    } catch {
+      // Browser closed or not Chromium
+      return [];
+    }
+  }
+
+  /**
+   * Evaluate in a shared worker over a non-flattened target session, since
+   * Playwright's CDPSession can't address child sessions directly.
+   */
+  private async evaluateShared(targetId: string, script: string): Promise<unknown> {
+    const cdp = this.browserSession;
+    if (!cdp) throw new Error('Shared workers are only supported in Chromium');
+    const { sessionId } = await cdp.send('Target.attachToTarget', { targetId, flatten: false });
+    let onMessage: ((event: { sessionId?: string; message: string }) => void) | undefined;
+    let timer: ReturnType<typeof setTimeout> | undefined;
+    try {
+      const reply = new Promise<ProtocolReply>((resolve, reject) => {
+        onMessage = (event) => {
+          if (event.sessionId !== sessionId) return;
+          const data = JSON.parse(event.message) as ProtocolReply;
+          if (data.id === 1) resolve(data);
+        };
+        cdp.on('Target.receivedMessageFromTarget', onMessage);
+        timer = setTimeout(
+          () => reject(new Error(`Timed out evaluating in shared worker ${targetId}`)),
+          EVALUATE_TIMEOUT
+        );
+      });
+      await cdp.send('Target.sendMessageToTarget', {
+        sessionId,
+        message: JSON.stringify({
+          id: 1,
+          method: 'Runtime.evaluate',
+          params: { expression: script, returnByValue: true, awaitPromise: true },
+        }),
+      });
+      const data = await reply;
+      if (data.error) throw new Error(data.error.message);
+      if (data.result?.exceptionDetails) {
+        const details = data.result.exceptionDetails;
+        throw new Error(details.exception?.description ?? details.text);
+      }
+      return data.result?.result.value;
+    } finally {
+      clearTimeout(timer);
+      if (onMessage) cdp.off('Target.receivedMessageFromTarget', onMessage);
+      await cdp.send('Target.detachFromTarget', { sessionId }).catch(() => {});
+    }
+  }
+}
+
+interface ProtocolReply {
+  id: number;
+  error?: { message: string };
+  result?: {
+    result: { value?: unknown };
+    exceptionDetails?: { text: string; exception?: { description?: string } };
+  };
+}
+
+function serviceWorkers(context: BrowserContext): Worker[] {
+  try {
+    return context.serviceWorkers();
+  } catch {
+    // Not Chromium
+    return [];
+  }
+}
+
+function unknownTarget(id: string): Error {
+  return new Error(`Unknown target: ${id}. Run: agent-browser targets`);
+}
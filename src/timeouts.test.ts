@@ -0,0 +1,40 @@
+import { describe, it, expect } from 'vitest';
+import { describeTimeout, formatMs, IDLE_ERROR, timeoutKind } from './timeouts.js';
+import type { Command } from './types.js';
+
+const timeouts = {
+  action: { ms: 5000, source: '--action-timeout' },
+  navigation: { ms: 30000, source: 'config nav-timeout' },
+};
+
+describe('timeouts', () => {
+  it('picks the timeout that bounds a command', () => {
+    expect(timeoutKind({ id: '1', action: 'click', selector: '#a' } as Command)).toBe('action');
+    expect(timeoutKind({ id: '1', action: 'navigate', url: 'https://a.com' } as Command)).toBe(
+      'navigation'
+    );
+    expect(
+      timeoutKind({ id: '1', action: 'waitforloadstate', state: 'networkidle' } as Command)
+    ).toBe('idle');
+  });
+
+  it('names the timeout and its source in errors', () => {
+    const click = { id: '1', action: 'click', selector: '#a', timeouts } as Command;
+    expect(describeTimeout(click, 'Timeout 5000ms exceeded.')).toBe(
+      'Timeout 5000ms exceeded. (action timeout 5s, from --action-timeout)'
+    );
+    const open = { id: '1', action: 'navigate', url: 'https://a.com', timeouts } as Command;
+    expect(describeTimeout(open, `${IDLE_ERROR}: Timeout exceeded`)).toBe(
+      `${IDLE_ERROR}: Timeout exceeded (idle timeout 30s, from config nav-timeout)`
+    );
+    const wait = { id: '1', action: 'waitforurl', url: '**/a', timeout: 100, timeouts } as Command;
+    expect(describeTimeout(wait, 'Timeout 100ms exceeded.')).toBe('Timeout 100ms exceeded.');
+  });
+
+  it('formats durations', () => {
+    expect(formatMs(30000)).toBe('30s');
+    expect(formatMs(1500)).toBe('1.5s');
+    expect(formatMs(750)).toBe('750ms');
+    expect(formatMs(1234)).toBe('1234ms');
+  });
+});
@@ -0,0 +1,69 @@
+/**
+ * Layered timeouts (see cli/src/timeouts.rs).
+ *
+ * The CLI resolves the action and navigation timeouts (and the network-idle
+ * timeout, when one is set) from its flags, the environment and config, and
+ * sends them with every command along with where each came from. They apply
+ * to the active page for that command only, and a timeout error names the one
+ * that ran out and its source, so the caller knows which setting to change.
+ */
+
+import type { Page } from 'playwright-core';
+import type { Command, TimeoutSetting, Timeouts } from './types.js';
+
+/** Actions bounded by the navigation timeout rather than the action timeout */
+const NAVIGATION_ACTIONS = new Set(['navigate', 'back', 'forward', 'reload', 'waitforurl']);
+
+/** Which timeout bounds a command */
+export function timeoutKind(command: Command): keyof Timeouts {
+  if (command.action === 'waitforloadstate') {
+    return (command as { state?: string }).state === 'networkidle' ? 'idle' : 'navigation';
+  }
+  return NAVIGATION_ACTIONS.has(command.action) ? 'navigation' : 'action';
+}
+
+/** Prefix for errors of the network-idle wait after a navigation */
+export const IDLE_ERROR = 'Waiting for network idle';
+
+/** `30s`, `1.5s`, `750ms` */
+export function formatMs(ms: number): string {
+  return ms >= 1000 && ms % 100 === 0 ? `${ms / 1000}s` : `${ms}ms`;
+}
+
+/**
+ * A timeout error with the timeout that ran out and where it was set, e.g.
+ * `... (navigation timeout 30s, from --nav-timeout)`. Commands with their own
+ * --timeout are left as they are.
+ */
+export function describeTimeout(command: Command, message: string): string {
+  if ((command as { timeout?: number }).timeout !== undefined) return message;
+  const kind = message.startsWith(IDLE_ERROR) ? 'idle' : timeoutKind(command);
+  // Without its own, the network-idle wait is bounded by the navigation timeout
+  const setting: TimeoutSetting | undefined =
+    command.timeouts?.[kind] ?? (kind === 'idle' ? command.timeouts?.navigation : undefined);
+  if (!setting) return message;
+  return `${message} (${kind} timeout ${formatMs(setting.ms)}, from ${setting.source})`;
+}
+
+/**
+ * Run a command with its timeouts as the page's defaults, then put the
+ * daemon's own defaults back.
+ */
+export async function withTimeouts<T>(
+  page: Page | null,
+  timeouts: Timeouts | undefined,
+  fallbackMs: number,
+  run: () => Promise<T>
+): Promise<T> {
+  if (!page || !timeouts) return run();
+  if (timeouts.action) page.setDefaultTimeout(timeouts.action.ms);
+  if (timeouts.navigation) page.setDefaultNavigationTimeout(timeouts.navigation.ms);
+  try {
+    return await run();
+  } finally {
+    if (!page.isClosed()) {
+      page.setDefaultTimeout(fallbackMs);
+      page.setDefaultNavigationTimeout(fallbackMs);
+    }
+  }
+}
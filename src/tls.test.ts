@@ -0,0 +1,48 @@
+import { describe, it, expect } from 'vitest';
+import { mkdtempSync, writeFileSync } from 'node:fs';
+import { tmpdir } from 'node:os';
+import path from 'node:path';
+import { clientTlsOptions, matchesHost, readCaFile } from './tls.js';
+
+describe('tls', () => {
+  it('matches exact hosts and wildcard subdomains', () => {
+    const patterns = ['intranet.local', '*.corp.example'];
+    expect(matchesHost(patterns, 'intranet.local')).toBe(true);
+    expect(matchesHost(patterns, 'www.intranet.local')).toBe(false);
+    expect(matchesHost(patterns, 'git.corp.example')).toBe(true);
+    expect(matchesHost(patterns, 'corp.example')).toBe(false);
+    expect(matchesHost(patterns, 'evilcorp.example')).toBe(false);
+  });
+
+  it('rejects files without certificates', () => {
+    const dir = mkdtempSync(path.join(tmpdir(), 'agent-browser-tls-'));
+    const file = path.join(dir, 'ca.pem');
+    writeFileSync(file, 'not a certificate');
+    expect(() => readCaFile(file)).toThrow('No PEM certificates');
+    writeFileSync(file, '-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----\n');
+    expect(() => readCaFile(file)).toThrow('Invalid certificate');
+    expect(() => readCaFile(path.join(dir, 'missing.pem'))).toThrow("Can't read CA certificate");
+  });
+
+  it('reads client certificates as a PEM pair or a bundle', () => {
+    const dir = mkdtempSync(path.join(tmpdir(), 'agent-browser-tls-'));
+    const [cert, key, pfx] = ['me.pem', 'me.key', 'me.p12'].map((name) => path.join(dir, name));
+    writeFileSync(cert, 'cert');
+    writeFileSync(key, 'key');
+    writeFileSync(pfx, 'bundle');
+    const origin = 'https://vpn.corp.example';
+    expect(clientTlsOptions({ origin, certPath: cert, keyPath: key })).toEqual({
+      cert: Buffer.from('cert'),
+      key: Buffer.from('key'),
+      passphrase: undefined,
+    });
+    expect(clientTlsOptions({ origin, pfxPath: pfx, passphrase: 'pw' })).toEqual({
+      pfx: Buffer.from('bundle'),
+      passphrase: 'pw',
+    });
+    expect(() => clientTlsOptions({ origin, certPath: cert })).toThrow('needs a key');
+    expect(() => clientTlsOptions({ origin, pfxPath: path.join(dir, 'gone.p12') })).toThrow(
+      "Can't read client certificate"
+    );
+  });
+});
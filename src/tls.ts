@@ -0,0 +1,280 @@
+/**
+ * Certificate inspection and per-host TLS trust.
+ *
+ * `tls info` connects to a host and reports the certificate chain it serves.
+ * `--extra-ca` and `--ignore-cert-errors-for` let the browser reach internal
+ * sites without turning off certificate checks everywhere, which is all
+ * `--ignore-https-errors` can do. Chromium has no switch for either, so a
+ * route handler serves the affected https requests from Node instead: with
+ * the extra CAs added to the usual roots, or without verification for the
+ * listed hosts. Everything else goes to the network untouched. Requests served
+ * this way present the session's client certificate for their origin, as the
+ * browser would.
+ */
+
+import * as https from 'node:https';
+import * as net from 'node:net';
+import * as tls from 'node:tls';
+import { readFileSync } from 'node:fs';
+import { X509Certificate } from 'node:crypto';
+import type { Route } from 'playwright-core';
+import type { ClientCertificate, TlsCertificate, TlsInfoData } from './types.js';
+
+const CONNECT_TIMEOUT = 10000;
+const PEM_BLOCK = /-----BEGIN CERTIFICATE-----[\s\S]+?-----END CERTIFICATE-----/g;
+
+/** Node's codes for a certificate that doesn't verify, as opposed to a network failure */
+const VERIFY_ERRORS = /CERT|SELF_SIGNED|UNABLE_TO_|ALTNAME/;
+
+/** Every certificate in a PEM file, checked so a bad file fails at launch. */
+export function readCaFile(path: string): string[] {
+  let text: string;
+  try {
+    text = readFileSync(path, 'utf-8');
+  } catch (err) {
+    throw new Error(`Can't read CA certificate ${path}: ${(err as Error).message}`);
+  }
+  const certs = text.match(PEM_BLOCK) ?? [];
+  if (certs.length === 0) {
+    throw new Error(`No PEM certificates in ${path}`);
+  }
+  for (const pem of certs) {
+    try {
+      new X509Certificate(pem);
+    } catch (err) {
+      throw new Error(`Invalid certificate in ${path}: ${(err as Error).message}`);
+    }
+  }
+  return certs;
+}
+
+/** `host` matches itself exactly; `*.host` matches its subdomains. */
+export function matchesHost(patterns: string[], host: string): boolean {
+  return patterns.some((pattern) =>
+    pattern.startsWith('*.') ? host.endsWith(pattern.slice(1)) : host === pattern
+  );
+}
+
+/** Node TLS options for a client certificate, read up front so a bad path fails at launch. */
+export function clientTlsOptions(cert: ClientCertificate): tls.SecureContextOptions {
+  const read = (path: string) => {
+    try {
+      return readFileSync(path);
+    } catch (err) {
+      throw new Error(`Can't read client certificate ${path}: ${(err as Error).message}`);
+    }
+  };
+  const passphrase = cert.passphrase;
+  if (cert.pfxPath) return { pfx: read(cert.pfxPath), passphrase };
+  if (!cert.certPath || !cert.keyPath) {
+    throw new Error(`Client certificate for ${cert.origin} needs a key`);
+  }
+  return { cert: read(cert.certPath), key: read(cert.keyPath), passphrase };
+}
+
+type Trust = 'native' | 'extra-ca' | 'insecure';
+
+export class TlsTrust {
+  private decisions = new Map<string, Promise<Trust>>();
+  private clientTls: Map<string, tls.SecureContextOptions>;
+
+  constructor(
+    readonly extraCa: string[],
+    readonly ignoreHosts: string[],
+    clientCertificates: ClientCertificate[] = []
+  ) {
+    this.clientTls = new Map(
+      clientCertificates.map((cert) => [new URL(cert.origin).origin, clientTlsOptions(cert)])
+    );
+  }
+
+  /**
+   * How to load an origin. Hosts trusted by the standard roots stay with the
+   * browser; only the ones that need an extra CA are served from Node.
+   */
+  private trust(url: URL): Promise<Trust> {
+    if (matchesHost(this.ignoreHosts, url.hostname)) return Promise.resolve('insecure');
+    if (this.extraCa.length === 0) return Promise.resolve('native');
+    let decision = this.decisions.get(url.origin);
+    if (!decision) {
+      decision = connect(
+        url.hostname,
+        Number(url.port) || 443,
+        [],
+        this.clientTls.get(url.origin)
+      )
+        .then((socket): Trust => {
+          socket.destroy();
+          return socket.authorized ? 'native' : 'extra-ca';
+        })
+        // Unreachable: let the browser report it
+        .catch((): Trust => 'native');
+      this.decisions.set(url.origin, decision);
+    }
+    return decision;
+  }
+
+  handler = async (route: Route): Promise<void> => {
+    const request = route.request();
+    const url = new URL(request.url());
+    const trust = url.protocol === 'https:' ? await this.trust(url) : 'native';
+    if (trust === 'native') {
+      await route.fallback();
+      return;
+    }
+    const headers = await request.allHeaders();
+    for (const name of Object.keys(headers)) {
+      // HTTP/2 pseudo-headers, and compression the fulfilled body can't carry
+      if (name.startsWith(':') || name === 'accept-encoding') delete headers[name];
+    }
+    try {
+      const response = await fetchNode(url, {
+        method: request.method(),
+        headers,
+        body: request.postDataBuffer(),
+        ca: trust === 'extra-ca' ? [...tls.rootCertificates, ...this.extraCa] : undefined,
+        rejectUnauthorized: trust !== 'insecure',
+        client: this.clientTls.get(url.origin),
+      });
+      await route.fulfill(response);
+    } catch (err) {
+      const code = (err as NodeJS.ErrnoException).code ?? '';
+      // Not signed by the extra CAs either: the browser shows its own error
+      if (VERIFY_ERRORS.test(code)) {
+        await route.fallback();
+      } else {
+        await route.abort('failed');
+      }
+    }
+  };
+}
+
+function fetchNode(
+  url: URL,
+  options: {
+    method: string;
+    headers: Record<string, string>;
+    body: Buffer | null;
+    ca?: string[];
+    rejectUnauthorized: boolean;
+    client?: tls.SecureContextOptions;
+  }
+): Promise<{ status: number; headers: Record<string, string>; body: Buffer }> {
+  return new Promise((resolve, reject) => {
+    const req = https.request(
+      url,
+      {
+        ...options.client,
+        method: options.method,
+        headers: options.headers,
+        ca: options.ca,
+        rejectUnauthorized: options.rejectUnauthorized,
+        timeout: CONNECT_TIMEOUT,
+      },
+      (res) => {
+        const chunks: Buffer[] = [];
+        res.on('data', (chunk: Buffer) => chunks.push(chunk));
+        res.on('error', reject);
+        res.on('end', () => {
+          const headers: Record<string, string> = {};
+          for (const [name, value] of Object.entries(res.headers)) {
+            if (value === undefined || name === 'content-length' || name === 'transfer-encoding') {
+              continue;
+            }
+            // Playwright splits Set-Cookie on newlines
+            headers[name] = Array.isArray(value)
+              ? value.join(name === 'set-cookie' ? '\n' : ', ')
+              : value;
+          }
+          resolve({ status: res.statusCode ?? 502, headers, body: Buffer.concat(chunks) });
+        });
+      }
+    );
+    req.on('timeout', () => req.destroy(new Error(`Timed out connecting to ${url.host}`)));
+    req.on('error', reject);
+    req.end(options.body ?? undefined);
+  });
+}
+
+function connect(
+  host: string,
+  port: number,
+  extraCa: string[],
+  client?: tls.SecureContextOptions
+): Promise<tls.TLSSocket> {
+  return new Promise((resolve, reject) => {
+    const socket = tls.connect(
+      {
+        ...client,
+        host,
+        port,
+        // SNI only takes names, not addresses
+        servername: net.isIP(host) ? undefined : host,
+        ca: extraCa.length > 0 ? [...tls.rootCertificates, ...extraCa] : undefined,
+        rejectUnauthorized: false,
+      },
+      () => resolve(socket)
+    );
+    socket.setTimeout(CONNECT_TIMEOUT, () =>
+      socket.destroy(new Error(`Timed out connecting to ${host}:${port}`))
+    );
+    socket.on('error', reject);
+  });
+}
+
+function entityName(entity: Record<string, string | string[]> | undefined): string {
+  if (!entity) return '';
+  const first = (value: string | string[] | undefined) =>
+    Array.isArray(value) ? value[0] : value;
+  return first(entity.CN) ?? first(entity.O) ?? '';
+}
+
+function describeCertificate(cert: tls.DetailedPeerCertificate, now: number): TlsCertificate {
+  const validTo = new Date(cert.valid_to);
+  const san = cert.subjectaltname
+    ?.split(', ')
+    .map((entry) => entry.replace(/^(DNS|IP Address):/, ''));
+  return {
+    subject: entityName(cert.subject as unknown as Record<string, string>),
+    issuer: entityName(cert.issuer as unknown as Record<string, string>),
+    validFrom: new Date(cert.valid_from).toISOString(),
+    validTo: validTo.toISOString(),
+    daysLeft: Math.floor((validTo.getTime() - now) / 86400000),
+    serialNumber: cert.serialNumber,
+    fingerprint256: cert.fingerprint256,
+    ...(san && { san }),
+  };
+}
+
+/** Connect to a URL's host and describe the chain it serves, leaf first. */
+export async function inspectCertificate(url: string, extraCa: string[]): Promise<TlsInfoData> {
+  const target = new URL(url);
+  if (target.protocol !== 'https:' && target.protocol !== 'wss:') {
+    throw new Error(`Not an https URL: ${url}`);
+  }
+  const port = Number(target.port) || 443;
+  const socket = await connect(target.hostname, port, extraCa);
+  try {
+    const chain: TlsCertificate[] = [];
+    const now = Date.now();
+    const seen = new Set<string>();
+    let cert = socket.getPeerCertificate(true);
+    // The root points at itself, which ends the walk
+    while (cert && cert.fingerprint256 && !seen.has(cert.fingerprint256)) {
+      seen.add(cert.fingerprint256);
+      chain.push(describeCertificate(cert, now));
+      cert = cert.issuerCertificate;
+    }
+    return {
+      host: target.hostname,
+      port,
+      protocol: socket.getProtocol(),
+      cipher: socket.getCipher().name,
+      authorized: socket.authorized,
+      ...(socket.authorizationError && { error: String(socket.authorizationError) }),
+      chain,
+    };
+  } finally {
+    socket.destroy();
+  }
+}
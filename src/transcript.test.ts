@@ -0,0 +1,84 @@
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import { mkdtempSync, readFileSync, rmSync } from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { Transcript } from './transcript.js';
+import type { Command, Response } from './types.js';
+
+let dir: string;
+
+beforeEach(() => {
+  dir = mkdtempSync(path.join(os.tmpdir(), 'transcript-'));
+});
+
+afterEach(() => {
+  rmSync(dir, { recursive: true, force: true });
+});
+
+const command = (fields: Record<string, unknown>) => ({ id: 'c1', ...fields }) as Command;
+const ok = (data: unknown): Response => ({ id: 'c1', success: true, data });
+
+describe('Transcript', () => {
+  it('records steps with the observation before them', () => {
+    const transcript = new Transcript(dir);
+    const snapshot = command({ action: 'snapshot', interactive: true });
+    expect(transcript.isStep(snapshot)).toBe(false);
+    transcript.observe(snapshot, ok({ snapshot: '- button "Buy" [ref=e1]' }));
+
+    const click = command({ action: 'click', selector: '@e1' });
+    expect(transcript.isStep(click)).toBe(true);
+    const screenshot = transcript.nextScreenshot();
+    expect(path.basename(screenshot)).toBe('step-0001.png');
+    transcript.record(click, ok({}), { url: 'https://example.com/', screenshot }, 12.4);
+    transcript.record(
+      command({ action: 'fill', selector: '@e2', value: 'x' }),
+      { id: 'c1', success: false, error: 'Element not found', code: 'E_SELECTOR_NOT_FOUND' },
+      { url: 'https://example.com/', screenshot: null },
+      3
+    );
+
+    const lines = readFileSync(transcript.file, 'utf-8')
+      .trim()
+      .split('\n')
+      .map((l) => JSON.parse(l));
+    expect(lines).toHaveLength(2);
+    expect(lines[0]).toMatchObject({
+      step: 1,
+      observation: {
+        url: 'https://example.com/',
+        snapshot: '- button "Buy" [ref=e1]',
+        snapshotOptions: { interactive: true },
+        screenshot,
+      },
+      action: { action: 'click', selector: '@e1' },
+      result: { success: true },
+      durationMs: 12,
+    });
+    expect(lines[0].action.id).toBeUndefined();
+    expect(lines[1].result).toEqual({
+      success: false,
+      error: 'Element not found',
+      code: 'E_SELECTOR_NOT_FOUND',
+    });
+  });
+
+  it('falls back to the agent screenshot and skips bookkeeping', () => {
+    const transcript = new Transcript(dir);
+    expect(transcript.isStep(command({ action: 'session_options' }))).toBe(false);
+    transcript.observe(command({ action: 'screenshot' }), ok({ path: 'shot.png' }));
+    const step = transcript.record(
+      command({ action: 'navigate', url: 'https://example.com' }),
+      ok({ url: 'https://example.com/' }),
+      { url: null, screenshot: null },
+      1
+    );
+    expect(step.observation.screenshot).toBe(path.resolve('shot.png'));
+  });
+
+  it('continues numbering after a restart', () => {
+    const page = { url: null, screenshot: null };
+    new Transcript(dir).record(command({ action: 'reload' }), ok({}), page, 1);
+    const resumed = new Transcript(dir);
+    expect(path.basename(resumed.nextScreenshot())).toBe('step-0002.png');
+  });
+});
@@ -0,0 +1,132 @@
+/**
+ * Session transcripts, for building datasets from real agent sessions.
+ *
+ * With `--transcript`, the daemon appends a line to
+ * `~/.browseros/artifacts/<session>/transcripts/transcript.jsonl` for each
+ * command an agent runs: what it was looking at (the URL, the last snapshot
+ * and a screenshot taken just before), the action and its result. `snapshot`
+ * and `screenshot` are observations, so they update what the next step saw
+ * instead of being steps themselves. `agent-browser transcript export` turns
+ * the file into a dataset.
+ */
+
+import { appendFileSync, existsSync, mkdirSync, readFileSync } from 'node:fs';
+import path from 'node:path';
+import { getArtifactsDir } from './artifacts.js';
+import type { Command, Response } from './types.js';
+
+/** Commands that change what the agent knows rather than the page */
+const OBSERVATIONS = new Set(['snapshot', 'screenshot']);
+
+/** Daemon bookkeeping, which isn't something the agent did */
+const BOOKKEEPING = new Set([
+  'launch',
+  'session_options',
+  'stats',
+  'memory',
+  'trace_step',
+  'capture_step',
+  'device_list',
+]);
+
+export interface Observation {
+  url: string | null;
+  /** The last snapshot the agent took, which may predate earlier steps */
+  snapshot: string | null;
+  /** The fields that snapshot was taken with, so a replay can take it again */
+  snapshotOptions?: Record<string, unknown>;
+  /** Absolute path of the page as it was just before the action */
+  screenshot: string | null;
+}
+
+export interface TranscriptStep {
+  step: number;
+  time: string;
+  observation: Observation;
+  /** The command as sent, without its id */
+  action: Record<string, unknown>;
+  result: { success: boolean; data?: unknown; error?: string; code?: string };
+  durationMs: number;
+}
+
+/** A command as sent, without its id */
+function fields(command: Command): Record<string, unknown> {
+  const copy: Record<string, unknown> = { ...command };
+  delete copy.id;
+  return copy;
+}
+
+export function transcriptDir(session?: string): string {
+  return path.join(getArtifactsDir(session), 'transcripts');
+}
+
+export class Transcript {
+  readonly file: string;
+  private steps: number;
+  private snapshot: string | null = null;
+  private snapshotOptions: Record<string, unknown> | undefined;
+  private screenshot: string | null = null;
+
+  constructor(readonly dir: string = transcriptDir()) {
+    this.file = path.join(dir, 'transcript.jsonl');
+    // A restarted daemon carries on numbering where the file left off
+    this.steps = existsSync(this.file)
+      ? readFileSync(this.file, 'utf-8').split('\n').filter(Boolean).length
+      : 0;
+  }
+
+  /** Whether the command is recorded as a step */
+  isStep(command: Command): boolean {
+    return !OBSERVATIONS.has(command.action) && !BOOKKEEPING.has(command.action);
+  }
+
+  /** Where the screenshot taken before the next step goes */
+  nextScreenshot(): string {
+    mkdirSync(this.dir, { recursive: true });
+    return path.join(this.dir, `step-${String(this.steps + 1).padStart(4, '0')}.png`);
+  }
+
+  /** Remember what a snapshot or screenshot showed the agent */
+  observe(command: Command, response: Response): void {
+    if (!response.success) return;
+    const data = response.data as { snapshot?: unknown; path?: unknown } | undefined;
+    if (command.action === 'snapshot' && typeof data?.snapshot === 'string') {
+      this.snapshot = data.snapshot;
+      this.snapshotOptions = fields(command);
+      delete this.snapshotOptions.action;
+    }
+    if (command.action === 'screenshot' && typeof data?.path === 'string') {
+      this.screenshot = path.resolve(data.path);
+    }
+  }
+
+  /**
+   * Append a step. The screenshot taken before it is preferred over the
+   * agent's own last one, which falls in when none could be taken.
+   */
+  record(
+    command: Command,
+    response: Response,
+    page: { url: string | null; screenshot: string | null },
+    durationMs: number
+  ): TranscriptStep {
+    const step: TranscriptStep = {
+      step: ++this.steps,
+      time: new Date().toISOString(),
+      observation: {
+        url: page.url,
+        snapshot: this.snapshot,
+        ...(this.snapshotOptions && { snapshotOptions: this.snapshotOptions }),
+        screenshot: page.screenshot ?? this.screenshot,
+      },
+      action: fields(command),
+      result: response.success
+        ? { success: true, data: response.data }
+        : { success: false, error: response.error, code: response.code },
+      durationMs: Math.round(durationMs),
+    };
+    mkdirSync(this.dir, { recursive: true });
+    appendFileSync(this.file, JSON.stringify(step) + '\n');
+    return step;
+  }
+}
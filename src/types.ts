@@ -1,12 +1,52 @@
 import type { Page, Browser, BrowserContext } from 'playwright-core';
+import type { ErrorCode } from './errors.js';
 
 // Base command structure
 export interface BaseCommand {
   id: string;
   action: string;
+  /** Retry element actions this many times on transient failures */
+  retries?: number;
+  /** Base delay between retries in ms, doubled after each attempt */
+  retryBackoff?: number;
+  /** Human-like pointer paths and typing cadence for click, type and scroll */
+  humanize?: boolean;
+  /** Seed for humanized input, to make it repeatable */
+  humanizeSeed?: number;
+  /** Ignore robots.txt for this command's navigations */
+  robotsOverride?: boolean;
+  /** Let requests guardrails mark as purchases through for this command */
+  allowPurchases?: boolean;
+  /** W3C trace context of the CLI span this command belongs to */
+  traceparent?: string;
+  /** OTLP/HTTP collector the daemon sends its spans to */
+  otelEndpoint?: string;
+  /** Timeouts resolved by the CLI, with where each was set (see timeouts.ts) */
+  timeouts?: Timeouts;
+}
+
+export interface TimeoutSetting {
+  ms: number;
+  /** The flag, env var, config key or script line it came from */
+  source: string;
+}
+
+export interface Timeouts {
+  action?: TimeoutSetting;
+  navigation?: TimeoutSetting;
+  idle?: TimeoutSetting;
 }
 
 // Action-specific command types
+/** A client certificate for mutual TLS, presented only to its origin */
+export interface ClientCertificate {
+  origin: string;
+  certPath?: string; // PEM certificate, with keyPath
+  keyPath?: string;
+  pfxPath?: string; // PKCS#12 bundle holding both
+  passphrase?: string;
+}
+
 export interface LaunchCommand extends BaseCommand {
   action: 'launch';
   headless?: boolean;
@@ -14,6 +54,7 @@ export interface LaunchCommand extends BaseCommand {
   browser?: 'chromium' | 'firefox' | 'webkit';
   headers?: Record<string, string>;
   executablePath?: string;
+  channel?: string; // Chromium-based browser to find: browseros, chrome, edge, chromium or path:<exe>
   cdpPort?: number;
   cdpUrl?: string;
   extensions?: string[];
@@ -28,8 +69,19 @@ export interface LaunchCommand extends BaseCommand {
   args?: string[];
   userAgent?: string;
   provider?: string;
+  remote?: string; // DevTools (ws/wss) or Selenium Grid (http/https) URL of a browser pool
+  remoteToken?: string; // Sent to the pool as a bearer token
+  remoteKeepAlive?: number; // Ping interval for the remote connection in ms; 0 disables
   ignoreHTTPSErrors?: boolean;
   allowFileAccess?: boolean; // Enable file:// URL access and cross-origin file requests
+  extraCa?: string[]; // PEM files with CA certificates to trust in addition to the system roots
+  ignoreCertErrorsFor?: string[]; // Hosts (or *.domain) whose certificates aren't verified
+  clientCertificates?: ClientCertificate[];
+  authServerAllowlist?: string[]; // Hosts allowed to use integrated Windows auth (NTLM/Negotiate)
+  authDelegateAllowlist?: string[]; // Hosts Kerberos credentials may be delegated to
+  stealth?: boolean; // Hide common headless fingerprints from page scripts
+  deterministic?: boolean; // Seeded Math.random, no animations or smooth scrolling
+  initScripts?: string[]; // Script files run in every document before the page's own scripts
 }
 
 export interface NavigateCommand extends BaseCommand {
@@ -183,7 +235,8 @@ export interface DialogCommand extends BaseCommand {
 
 export interface PdfCommand extends BaseCommand {
   action: 'pdf';
-  path: string;
+  /** Defaults to the session's artifacts directory */
+  path?: string;
   format?:
     | 'Letter'
     | 'Legal'
@@ -198,6 +251,33 @@ export interface PdfCommand extends BaseCommand {
     | 'A6';
 }
 
+export type ArchiveFormat = 'mhtml' | 'warc';
+
+export interface ArchiveCommand extends BaseCommand {
+  action: 'archive';
+  format: ArchiveFormat;
+  /** Defaults to the session's artifacts directory */
+  path?: string;
+}
+
+export interface FreezeCommand extends BaseCommand {
+  action: 'freeze';
+  /** Defaults to the session's artifacts directory */
+  path?: string;
+}
+
+export interface SelectorsSuggestCommand extends BaseCommand {
+  action: 'selectors_suggest';
+  /** Ref or selector of the element */
+  selector: string;
+}
+
+export interface SelectorsHealCommand extends BaseCommand {
+  action: 'selectors_heal';
+  /** Ref or selector that no longer matches */
+  selector: string;
+}
+
 // Network interception
 export interface RouteCommand extends BaseCommand {
   action: 'route';
@@ -465,11 +545,27 @@ export interface MultiSelectCommand extends BaseCommand {
   values: string[];
 }
 
-// Wait for download
+// Wait for download (arm: start listening and return immediately)
 export interface WaitForDownloadCommand extends BaseCommand {
   action: 'waitfordownload';
   path?: string;
   timeout?: number;
+  arm?: boolean;
+}
+
+// Wait for a popup window opened by the current page
+export interface WaitForPopupCommand extends BaseCommand {
+  action: 'waitforpopup';
+  timeout?: number;
+  arm?: boolean;
+}
+
+// Wait for a request whose URL contains a pattern
+export interface WaitForRequestCommand extends BaseCommand {
+  action: 'waitforrequest';
+  url: string;
+  timeout?: number;
+  arm?: boolean;
 }
 
 // Get response body from intercepted request
@@ -487,6 +583,12 @@ export interface ScreencastStartCommand extends BaseCommand {
   maxWidth?: number;
   maxHeight?: number;
   everyNthFrame?: number;
+  /** Frame size relative to the viewport, 0-1 */
+  scale?: number;
+  /** Most frames a second */
+  fps?: number;
+  /** Append the frames to this file or named pipe rather than the stream server */
+  path?: string;
 }
 
 export interface ScreencastStopCommand extends BaseCommand {
@@ -569,7 +671,85 @@ export interface TraceStartCommand extends BaseCommand {
 
 export interface TraceStopCommand extends BaseCommand {
   action: 'trace_stop';
+  /** Defaults to the session's artifacts directory */
+  path?: string;
+}
+
+// Save response bodies matching a URL pattern to a directory
+export interface CaptureStartCommand extends BaseCommand {
+  action: 'capture_start';
+  url: string;
+  out: string;
+  maxSize?: number;
+  mime?: string[];
+}
+
+export interface CaptureStopCommand extends BaseCommand {
+  action: 'capture_stop';
+}
+
+// Summarize observed XHR/fetch endpoints
+export interface ApisCommand extends BaseCommand {
+  action: 'apis';
+  filter?: string;
+  clear?: boolean;
+}
+
+// Record raw mouse/keyboard input to a timeline, or replay one
+export interface InputCommand extends BaseCommand {
+  action: 'input';
+  op: 'record' | 'stop' | 'replay';
+  /** Timeline file to write (record) or read (replay) */
+  path?: string;
+  /** Replay pacing multiplier; 2 replays twice as fast */
+  speed?: number;
+}
+
+export interface TimelineEvent {
+  /** ms since recording started */
+  t: number;
+  type: 'mousemove' | 'mousedown' | 'mouseup' | 'wheel' | 'keydown' | 'keyup';
+  /** Viewport coordinates, for mouse and wheel events */
+  x?: number;
+  y?: number;
+  button?: 'left' | 'middle' | 'right';
+  deltaX?: number;
+  deltaY?: number;
+  /** KeyboardEvent.key and .code, for key events */
+  key?: string;
+  code?: string;
+}
+
+export interface InputTimeline {
+  version: 1;
+  url: string;
+  viewport: { width: number; height: number } | null;
+  duration: number;
+  events: TimelineEvent[];
+}
+
+// WebSocket inspection
+export interface WsCommand extends BaseCommand {
+  action: 'ws';
+  op: 'list' | 'tail' | 'export' | 'clear';
+  /** Socket id (ws1) or URL substring, for tail */
+  socket?: string;
+  /** Number of frames to show, for tail */
+  count?: number;
+  /** Output file, for export */
+  path?: string;
+}
+
+// Serve responses from a recorded HAR instead of the network
+export interface HarReplayCommand extends BaseCommand {
+  action: 'har_replay';
   path: string;
+  /** Navigate here once replay is set up */
+  url?: string;
+  /** What to do with requests missing from the HAR (default abort) */
+  notFound?: 'abort' | 'fallback';
+  /** Only replay requests whose URL matches this glob */
+  match?: string;
 }
 
 // HAR recording
@@ -593,6 +773,16 @@ export interface StorageStateLoadCommand extends BaseCommand {
   path: string;
 }
 
+export interface SessionSaveCommand extends BaseCommand {
+  action: 'session_save';
+  path: string;
+}
+
+export interface SessionRestoreCommand extends BaseCommand {
+  action: 'session_restore';
+  path: string;
+}
+
 // Console logs
 export interface ConsoleCommand extends BaseCommand {
   action: 'console';
@@ -638,6 +828,12 @@ export interface HighlightCommand extends BaseCommand {
   selector: string;
 }
 
+// Inspect element metadata (for debugging)
+export interface InspectCommand extends BaseCommand {
+  action: 'inspect';
+  selector: string;
+}
+
 // Clear input
 export interface ClearCommand extends BaseCommand {
   action: 'clear';
@@ -746,6 +942,8 @@ export interface ScreenshotCommand extends BaseCommand {
   selector?: string;
   format?: 'png' | 'jpeg';
   quality?: number;
+  /** Selectors of elements painted over before capture */
+  mask?: string[];
 }
 
 export interface SnapshotCommand extends BaseCommand {
@@ -756,6 +954,8 @@ export interface EvaluateCommand extends BaseCommand {
   action: 'evaluate';
   script: string;
   args?: unknown[];
+  /** Target id from `targets` (a tab or worker); the active page if unset */
+  target?: string;
 }
 
 export interface WaitCommand extends BaseCommand {
@@ -804,6 +1004,113 @@ export interface TabListCommand extends BaseCommand {
   action: 'tab_list';
 }
 
+// Whether robots.txt allows a URL
+export interface RobotsCommand extends BaseCommand {
+  action: 'robots';
+  url: string;
+}
+
+export interface TlsInfoCommand extends BaseCommand {
+  action: 'tls_info';
+  url: string;
+}
+
+export interface ClockSetCommand extends BaseCommand {
+  action: 'clock_set';
+  time: string;
+  tick?: boolean;
+  timezone?: string;
+}
+
+export interface ClockAdvanceCommand extends BaseCommand {
+  action: 'clock_advance';
+  ms: number;
+}
+
+export interface ClockResumeCommand extends BaseCommand {
+  action: 'clock_resume';
+}
+
+// Recurring jobs run by the daemon (see schedule.ts)
+export interface ScheduleAddCommand extends BaseCommand {
+  action: 'schedule_add';
+  cron: string;
+  /** agent-browser arguments, e.g. ['run', '/abs/check.ab'] */
+  command: string[];
+}
+
+export interface ScheduleListCommand extends BaseCommand {
+  action: 'schedule_list';
+}
+
+export interface ScheduleRemoveCommand extends BaseCommand {
+  action: 'schedule_remove';
+  jobId: string;
+}
+
+export interface ScheduleLogsCommand extends BaseCommand {
+  action: 'schedule_logs';
+  jobId: string;
+}
+
+export type ScheduleCommand =
+  | ScheduleAddCommand
+  | ScheduleListCommand
+  | ScheduleRemoveCommand
+  | ScheduleLogsCommand;
+
+// The daemon's job queue (see job-queue.ts)
+export interface JobsCommand extends BaseCommand {
+  action: 'jobs';
+}
+
+// Stop the commands in flight (see cancel.ts)
+export interface CancelCommand extends BaseCommand {
+  action: 'cancel';
+}
+
+// Queues for the browser shared with other sessions (see lanes.ts)
+export interface LanesCommand extends BaseCommand {
+  action: 'lanes';
+}
+
+// Where the browser executable is looked for (see executable.ts)
+export interface WhichCommand extends BaseCommand {
+  action: 'which';
+  executablePath?: string;
+  channel?: string;
+}
+
+export interface AuditSecurityCommand extends BaseCommand {
+  action: 'audit_security';
+  url?: string;
+}
+
+export interface AuditPerfCommand extends BaseCommand {
+  action: 'audit_perf';
+  url?: string;
+}
+
+export interface AuditSeoCommand extends BaseCommand {
+  action: 'audit_seo';
+  url?: string;
+  /** Internal links to check; 0 skips the link check */
+  links?: number;
+}
+
+export interface RobotsDecision {
+  url: string;
+  allowed: boolean;
+  robotsUrl?: string;
+  /** The rule that decided, or why the whole site is allowed or disallowed */
+  rule?: string;
+}
+
+// Tabs and workers that eval --target can run in
+export interface TargetsCommand extends BaseCommand {
+  action: 'targets';
+}
+
 export interface TabSwitchCommand extends BaseCommand {
   action: 'tab_switch';
   index: number;
@@ -814,6 +1121,226 @@ export interface TabCloseCommand extends BaseCommand {
   index?: number;
 }
 
+// Popup handling policy for pages opened by the current page
+export type PopupPolicy = 'follow' | 'block' | 'list';
+
+// A navigation or download the page made on its own account, for the audit log
+export type PageActivity =
+  | { type: 'navigation'; url: string }
+  | { type: 'download'; url: string; filename: string };
+
+// A request pattern that --guardrails blocks (see guardrails.ts)
+export interface GuardrailRule {
+  name: string;
+  /** Regular expression tested against the full URL, ignoring case */
+  url: string;
+  /** Only requests with these methods; any method if omitted */
+  methods?: string[];
+  /** Only document loads in the top frame */
+  navigation?: boolean;
+  /** Lifted by --allow-purchases */
+  purchase?: boolean;
+}
+
+export interface GuardrailViolation {
+  rule: string;
+  method: string;
+  url: string;
+  purchase: boolean;
+}
+
+// Session-wide behavior set from global CLI flags
+export interface SessionOptionsCommand extends BaseCommand {
+  action: 'session_options';
+  popupPolicy?: PopupPolicy;
+  autoDismissOverlays?: boolean;
+  adblock?: boolean;
+  respectRobots?: boolean;
+  /** Record a transcript of the session (see transcript.ts) */
+  transcript?: boolean;
+  /** Append to the session's audit log (see audit-log.ts) */
+  auditLog?: boolean;
+  /** Refuse input and server writes for the rest of the session (see read-only.ts) */
+  readOnly?: boolean;
+  /** Turn on guardrails, with these rules on top of the built-in ones */
+  guardrails?: GuardrailRule[];
+  /** Per-domain limits; an empty list removes one */
+  rateLimits?: { requests?: RateRule[]; navigations?: RateRule[] };
+  lanes?: LaneLimits;
+  /** Recycle tabs whose JS heap grows past `limit` bytes; 0 stops it */
+  tabMemory?: { limit: number; mode?: RecycleMode };
+}
+
+/** How an oversized tab is recycled (see memory-watchdog.ts) */
+export type RecycleMode = 'reload' | 'recreate';
+
+/** Limits on the browser shared by every session attached to it */
+export interface LaneLimits {
+  /** Commands running on the browser at once; 0 removes the cap */
+  maxConcurrent?: number;
+  /** At most `count` commands started per `per` ms; null removes the limit */
+  actionRate?: { count: number; per: number } | null;
+}
+
+/** At most `count` requests per `per` ms to `host` (and its subdomains), or to each host */
+export interface RateRule {
+  host?: string;
+  count: number;
+  per: number;
+}
+
+// Bandwidth and request accounting for the session
+export interface StatsCommand extends BaseCommand {
+  action: 'stats';
+  reset?: boolean;
+}
+
+// Tabs' JS heap and the tabs recycled for using too much
+export interface MemoryCommand extends BaseCommand {
+  action: 'memory';
+}
+
+// Raw DevTools protocol message on the page's CDP session
+export interface CdpCommand extends BaseCommand {
+  action: 'cdp';
+  method: string;
+  params?: Record<string, unknown>;
+  /** Events to collect while the call runs and for `wait` ms after */
+  events?: string[];
+  wait?: number;
+}
+
+// Stream DevTools protocol events to the client (see streamCdpEvents)
+export interface CdpListenCommand extends BaseCommand {
+  action: 'cdp_listen';
+  events: string[];
+  /** Finish after this many events; stream until the client disconnects if unset */
+  count?: number;
+  timeout?: number;
+}
+
+export type DomMutationType = 'childList' | 'attributes' | 'characterData';
+
+// Stream DOM mutations of matching elements to the client (see streamMutations)
+export interface WatchCommand extends BaseCommand {
+  action: 'watch';
+  selector: string;
+  /** Mutation types to report; all of them if unset */
+  events?: DomMutationType[];
+  /** Finish after this many mutations; stream until the client disconnects if unset */
+  count?: number;
+  timeout?: number;
+}
+
+export interface DomMutationEvent {
+  type: DomMutationType;
+  /** The changed element as tag#id.class */
+  target: string;
+  /** attributes: the attribute's name, old and new value; characterData: the old and new text */
+  attribute?: string;
+  oldValue?: string | null;
+  value?: string | null;
+  /** childList: the nodes added and removed, with their text */
+  added?: { node: string; text: string }[];
+  removed?: { node: string; text: string }[];
+  url: string;
+  /** ms since epoch */
+  timestamp: number;
+}
+
+// Read an element's value on an interval and stream each reading (see streamPoll)
+export interface PollCommand extends BaseCommand {
+  action: 'poll';
+  selector: string;
+  /** Read this attribute instead of the element's text */
+  attribute?: string;
+  /** ms between readings */
+  interval: number;
+  /** Reload the page before each reading after the first */
+  reload?: boolean;
+  /** Stop once the value differs from the first reading */
+  untilChanged?: boolean;
+  /** Stop once the value matches this regular expression */
+  untilMatch?: string;
+  /** Give up after this many readings */
+  max?: number;
+}
+
+export interface PollEvent {
+  check: number;
+  /** Whitespace-collapsed text or attribute value; null when the element is missing */
+  value: string | null;
+  /** Differs from the previous reading */
+  changed: boolean;
+  url: string;
+  /** ms since epoch */
+  timestamp: number;
+}
+
+export interface PollData {
+  checks: number;
+  value: string | null;
+  /** A stop condition was met */
+  matched: boolean;
+}
+
+export interface CdpEvent {
+  method: string;
+  params: unknown;
+  /** ms since epoch */
+  timestamp: number;
+}
+
+// Page state and activity since the previous call, for `run --trace`
+export interface TraceStepCommand extends BaseCommand {
+  action: 'trace_step';
+  /** Only discard the activity recorded so far */
+  reset?: boolean;
+}
+
+export interface CaptureStepCommand extends BaseCommand {
+  action: 'capture_step';
+}
+
+export interface TraceResponse {
+  method: string;
+  url: string;
+  status: number;
+  type: string;
+  timestamp: number;
+}
+
+export interface TraceBuffer {
+  console: { type: string; text: string; timestamp: number }[];
+  errors: { message: string; timestamp: number }[];
+  responses: TraceResponse[];
+}
+
+export interface TraceStepData extends TraceBuffer {
+  url: string;
+  title: string;
+  /** Base64 JPEG of the viewport */
+  screenshot?: string;
+}
+
+export interface CaptureStepData {
+  url: string;
+  title: string;
+  /** Full-page screenshot, unless the page couldn't be captured */
+  path?: string;
+  artifacts: string[];
+  /** The last console messages */
+  console: { type: string; text: string }[];
+  /** Interactive elements, cut to the first lines */
+  snapshot: string;
+  truncated: boolean;
+}
+
+// Dismiss cookie banners, newsletter modals, and app-install banners
+export interface DismissOverlaysCommand extends BaseCommand {
+  action: 'dismiss_overlays';
+}
+
 export interface WindowNewCommand extends BaseCommand {
   action: 'window_new';
   viewport?: { width: number; height: number };
@@ -852,6 +1379,31 @@ export type Command =
   | TabListCommand
   | TabSwitchCommand
   | TabCloseCommand
+  | SessionOptionsCommand
+  | DismissOverlaysCommand
+  | StatsCommand
+  | CdpCommand
+  | CdpListenCommand
+  | WatchCommand
+  | PollCommand
+  | TargetsCommand
+  | RobotsCommand
+  | AuditPerfCommand
+  | AuditSeoCommand
+  | AuditSecurityCommand
+  | TlsInfoCommand
+  | ClockSetCommand
+  | ClockAdvanceCommand
+  | ClockResumeCommand
+  | ScheduleCommand
+  | JobsCommand
+  | CancelCommand
+  | LanesCommand
+  | MemoryCommand
+  | WhichCommand
+  | InputCommand
+  | TraceStepCommand
+  | CaptureStepCommand
   | WindowNewCommand
   | CookiesGetCommand
   | CookiesSetCommand
@@ -861,6 +1413,10 @@ export type Command =
   | StorageClearCommand
   | DialogCommand
   | PdfCommand
+  | ArchiveCommand
+  | FreezeCommand
+  | SelectorsSuggestCommand
+  | SelectorsHealCommand
   | RouteCommand
   | UnrouteCommand
   | RequestsCommand
@@ -890,10 +1446,17 @@ export type Command =
   | RecordingRestartCommand
   | TraceStartCommand
   | TraceStopCommand
+  | CaptureStartCommand
+  | CaptureStopCommand
+  | ApisCommand
+  | WsCommand
+  | HarReplayCommand
   | HarStartCommand
   | HarStopCommand
   | StorageStateSaveCommand
   | StorageStateLoadCommand
+  | SessionSaveCommand
+  | SessionRestoreCommand
   | ConsoleCommand
   | ErrorsCommand
   | KeyboardCommand
@@ -901,6 +1464,7 @@ export type Command =
   | TapCommand
   | ClipboardCommand
   | HighlightCommand
+  | InspectCommand
   | ClearCommand
   | SelectAllCommand
   | InnerTextCommand
@@ -938,6 +1502,8 @@ export type Command =
   | InsertTextCommand
   | MultiSelectCommand
   | WaitForDownloadCommand
+  | WaitForPopupCommand
+  | WaitForRequestCommand
   | ResponseBodyCommand
   | ScreencastStartCommand
   | ScreencastStopCommand
@@ -958,6 +1524,9 @@ export interface ErrorResponse {
   id: string;
   success: false;
   error: string;
+  code?: ErrorCode;
+  /** Details of the failure where there are any, e.g. guardrail violations */
+  data?: unknown;
 }
 
 export type Response<T = unknown> = SuccessResponse<T> | ErrorResponse;
@@ -968,6 +1537,31 @@ export interface NavigateData {
   title: string;
 }
 
+export interface ArchiveData {
+  path: string;
+  artifacts: string[];
+  format: ArchiveFormat;
+  url: string;
+  title: string;
+  /** Subresources saved alongside the document */
+  resources: number;
+  /** Resources the browser no longer held (WARC only) */
+  skipped: number;
+  bytes: number;
+}
+
+export interface FreezeData {
+  path: string;
+  artifacts: string[];
+  url: string;
+  title: string;
+  /** References replaced with data: URLs */
+  inlined: number;
+  /** References still pointing at the web: files the browser no longer held, frames */
+  external: number;
+  bytes: number;
+}
+
 export interface ScreenshotData {
   path?: string;
   base64?: string;
@@ -1013,14 +1607,357 @@ export interface TabCloseData {
   remaining: number;
 }
 
+export interface HostStats {
+  host: string;
+  requests: number;
+  bytes: number;
+}
+
+export interface TargetInfo {
+  id: string;
+  type: 'page' | 'worker' | 'service_worker' | 'shared_worker';
+  url: string;
+  /** Tab index of the page a dedicated worker belongs to */
+  page?: number;
+}
+
+/** A problem found by an `audit` command */
+export interface AuditIssue {
+  severity: 'error' | 'warning';
+  check: string;
+  message: string;
+}
+
+export interface SeoAuditData {
+  url: string;
+  meta: {
+    title: string | null;
+    description: string | null;
+    canonical: string | null;
+    robots: string | null;
+  };
+  headings: { level: number; text: string }[];
+  links: {
+    internal: number;
+    checked: number;
+    /** status is 0 when the request failed outright */
+    broken: { url: string; status: number; error?: string }[];
+  };
+  structuredData: { types: string[]; valid: boolean; error?: string }[];
+  issues: AuditIssue[];
+}
+
+export interface SecurityAuditData {
+  url: string;
+  /** Document response headers; csp falls back to a <meta http-equiv> policy */
+  headers: { csp: string | null; hsts: string | null; xFrameOptions: string | null };
+  cookies: {
+    name: string;
+    domain: string;
+    secure: boolean;
+    httpOnly: boolean;
+    sameSite: 'Strict' | 'Lax' | 'None';
+  }[];
+  /** Plain http requests from an https page; active ones can rewrite the page */
+  mixedContent: { url: string; type: string; active: boolean }[];
+  thirdParty: { origin: string; requests: number; types: string[] }[];
+  issues: AuditIssue[];
+}
+
+export interface TlsCertificate {
+  subject: string;
+  issuer: string;
+  validFrom: string;
+  validTo: string;
+  /** Negative once expired */
+  daysLeft: number;
+  serialNumber: string;
+  fingerprint256: string;
+  san?: string[];
+}
+
+export interface TlsInfoData {
+  host: string;
+  port: number;
+  protocol: string | null;
+  cipher: string;
+  /** Whether the chain verifies against the system roots plus any --extra-ca */
+  authorized: boolean;
+  error?: string;
+  /** Leaf first, up to the root when the server sends it */
+  chain: TlsCertificate[];
+}
+
+export interface JobRun {
+  started: string;
+  finished?: string;
+  /** skipped: the previous run was still going */
+  status: 'passed' | 'failed' | 'skipped';
+  exitCode?: number;
+  /** Holds output.log and files the command wrote to relative paths */
+  dir?: string;
+}
+
+export interface ScheduledJob {
+  id: string;
+  cron: string;
+  command: string[];
+  createdAt: string;
+  nextRun: string | null;
+  running: boolean;
+  lastRun: JobRun | null;
+}
+
+export interface ScheduleLogsData {
+  id: string;
+  run: JobRun | null;
+  output: string;
+}
+
+export type QueuedJobStatus = 'queued' | 'running' | 'passed' | 'failed' | 'cancelled';
+
+export interface QueuedJob {
+  id: string;
+  name?: string;
+  status: QueuedJobStatus;
+  /** Higher runs first; equal priorities run in submission order */
+  priority: number;
+  /** Extra arguments for `run`, e.g. ['--reporter', 'junit', '-o', 'report.xml'] */
+  args: string[];
+  submittedAt: string;
+  startedAt?: string;
+  finishedAt?: string;
+  exitCode?: number;
+  /** Holds script.ab, output.log and files the script wrote to relative paths */
+  dir: string;
+}
+
+export type BrowserName = 'browseros' | 'chrome' | 'edge' | 'chromium';
+
+export interface ExecutableCandidate {
+  /** The browser at this path, if known */
+  browser: BrowserName | null;
+  /** Where the path came from, e.g. `AGENT_BROWSER_EXECUTABLE_PATH` or `Chrome: LOCALAPPDATA` */
+  source: string;
+  path: string;
+  exists: boolean;
+}
+
+export interface WhichData {
+  /** The executable launches use, or null for Playwright's bundled Chromium */
+  path: string | null;
+  browser: BrowserName | null;
+  source: string | null;
+  candidates: ExecutableCandidate[];
+}
+
+export interface JobsData {
+  /** Where the REST API listens, or null without AGENT_BROWSER_JOBS_PORT */
+  url: string | null;
+  concurrency: number;
+  jobs: QueuedJob[];
+  /** Warm browsers jobs attach to, or null without AGENT_BROWSER_POOL_SIZE */
+  pool: PoolData | null;
+}
+
+/** What the pool does with a browser its session is done with (see browser-pool.ts) */
+export type PoolRecycle = 'close' | 'reuse';
+
+export interface PoolData {
+  /** Browsers kept launched and idle */
+  size: number;
+  maxAgeMs: number;
+  recycle: PoolRecycle;
+  idle: number;
+  /** Browsers attached to a job's session */
+  leased: number;
+  launching: number;
+  /** Jobs that got a warm browser, and those that waited for a launch */
+  hits: number;
+  misses: number;
+  /** Browsers closed for their age or after their session */
+  retired: number;
+  /** Why the latest launch or reset failed */
+  lastError?: string;
+}
+
+export interface CancelData {
+  /** Actions of the commands that were stopped */
+  cancelled: string[];
+}
+
+export interface LaneStatsData {
+  maxConcurrent?: number;
+  actionRate?: { count: number; per: number } | null;
+  /** This session's commands waiting for their turn */
+  waiting: number;
+  /** Sessions waiting for the browser, this one included */
+  queued: number;
+  /** Commands running on the browser, from every session */
+  active: number;
+  /** Commands this session has run through the queue */
+  scheduled: number;
+  /** Of those, the ones that had to wait */
+  delayed: number;
+  waitedMs: number;
+}
+
+export interface SessionSaveData {
+  tabs: number;
+  cookies: number;
+  /** Origins with localStorage */
+  origins: number;
+}
+
+export interface SessionRestoreData extends SessionSaveData {
+  /** Index of the tab that is active after restoring */
+  active: number;
+  savedAt: string | null;
+}
+
+export interface ClockData {
+  time: string;
+  /** Timers only fire when the clock is advanced */
+  paused: boolean;
+  timezone: string | null;
+  /** Wall time in the emulated timezone */
+  local?: string;
+}
+
+export interface SelectorCandidate {
+  selector: string;
+  strategy: 'test-id' | 'id' | 'role' | 'aria-label' | 'name' | 'text' | 'css-path';
+  /** 0-100: how likely the selector is to keep working as the page changes */
+  score: number;
+}
+
+export interface SelectorsData {
+  target: string;
+  /** The best candidate, or null if none matches the element alone */
+  selector: string | null;
+  /** Unique on the page, best first */
+  candidates: SelectorCandidate[];
+  /** Candidates stored as fallbacks for the ref or selector */
+  fallbacks: string[];
+}
+
+export interface SelectorsHealData {
+  target: string;
+  /** A selector for the element `target` most likely meant, or null */
+  selector: string | null;
+  /** How the element was found */
+  strategy: 'fallback' | 'accessible-name' | 'nearest-text' | null;
+  /** Score of `selector` as a suggestion (0-100) */
+  score: number | null;
+}
+
+export interface TargetsData {
+  targets: TargetInfo[];
+}
+
+export type PerfResourceType =
+  | 'document'
+  | 'script'
+  | 'stylesheet'
+  | 'image'
+  | 'font'
+  | 'media'
+  | 'fetch'
+  | 'other';
+
+/** Times are milliseconds from navigation start, sizes are bytes */
+export interface PerfAuditData {
+  url: string;
+  navigation: {
+    ttfb: number;
+    dns: number;
+    connect: number;
+    tls: number;
+    download: number;
+    domInteractive: number;
+    domContentLoaded: number;
+    load: number;
+  } | null;
+  /** Lab approximations; null when the browser doesn't report the entry */
+  vitals: { fcp: number | null; lcp: number | null; cls: number; tbt: number | null };
+  totals: { requests: number; size: number };
+  resources: { type: PerfResourceType; count: number; size: number }[];
+  largest: { url: string; type: PerfResourceType; size: number; duration: number }[];
+}
+
+export interface CdpData {
+  method: string;
+  result: unknown;
+  events: { method: string; params: unknown }[];
+}
+
+export interface StatsData {
+  /** When counting started (launch or last reset), ms since epoch */
+  since: number;
+  requests: number;
+  failed: number;
+  blocked: number;
+  /** Requests held back by --rate / --nav-rate, and the total time they waited */
+  throttled: number;
+  throttledMs: number;
+  cacheHits: number;
+  /** Response bytes received (headers + encoded body) */
+  bytesIn: number;
+  /** Request bytes sent (headers + body) */
+  bytesOut: number;
+  /** Hosts with the most received bytes */
+  topHosts: HostStats[];
+}
+
+export interface TabMemory {
+  index: number;
+  url: string;
+  /** JS heap bytes in use, and allocated */
+  heapUsed: number;
+  heapTotal: number;
+}
+
+export interface TabRecycle {
+  /** When, ms since epoch */
+  at: number;
+  index: number;
+  url: string;
+  heapUsed: number;
+  limit: number;
+  mode: RecycleMode;
+  /** Set when the tab couldn't be recycled */
+  error?: string;
+}
+
+export interface MemoryData {
+  /** Bytes a tab's JS heap may use before it's recycled; null when unlimited */
+  limit: number | null;
+  mode: RecycleMode;
+  tabs: TabMemory[];
+  /** The latest recycles, oldest first */
+  recycles: TabRecycle[];
+  /** Recycles since the daemon started */
+  total: number;
+}
+
 export interface ScreencastStartData {
   started: boolean;
   format: string;
   quality: number;
+  path?: string;
+  scale?: number;
+  fps?: number;
 }
 
 export interface ScreencastStopData {
   stopped: boolean;
+  /** Set when frames were written to a file or pipe */
+  path?: string;
+  frames?: number;
+  bytes?: number;
+  /** Frames skipped while the reader was behind */
+  dropped?: number;
+  error?: string;
 }
 
 export interface RecordingStartData {
@@ -1032,6 +1969,7 @@ export interface RecordingStopData {
   path: string;
   frames: number;
   error?: string;
+  artifacts?: string[];
 }
 
 export interface RecordingRestartData {
@@ -1067,6 +2005,24 @@ export interface StylesData {
   elements: ElementStyleInfo[];
 }
 
+// Element inspection data
+export interface InspectData {
+  tag: string;
+  box: { x: number; y: number; width: number; height: number };
+  styles: Record<string, string>;
+  attributes: Record<string, string>;
+  visible: boolean;
+  occludedBy: string | null;
+  scroll: {
+    top: number;
+    left: number;
+    windowX: number;
+    windowY: number;
+    inViewport: boolean;
+  };
+  listeners: string[];
+}
+
 // Browser state
 export interface BrowserState {
   browser: Browser | null;
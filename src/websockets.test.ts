@@ -0,0 +1,57 @@
+import { EventEmitter } from 'node:events';
+import { describe, it, expect } from 'vitest';
+import type { CDPSession } from 'playwright-core';
+import { WebSocketTracker, truncatePayload } from './websockets.js';
+
+function tracker(): { ws: WebSocketTracker; cdp: EventEmitter } {
+  const ws = new WebSocketTracker();
+  const cdp = new EventEmitter();
+  ws.attach(cdp as unknown as CDPSession);
+  return { ws, cdp };
+}
+
+describe('WebSocketTracker', () => {
+  it('records handshakes and frames', () => {
+    const { ws, cdp } = tracker();
+    cdp.emit('Network.webSocketCreated', { requestId: 'r1', url: 'wss://chat.example.com/ws' });
+    cdp.emit('Network.webSocketHandshakeResponseReceived', {
+      requestId: 'r1',
+      response: { status: 101, headers: { upgrade: 'websocket' } },
+    });
+    cdp.emit('Network.webSocketFrameSent', {
+      requestId: 'r1',
+      response: { opcode: 1, payloadData: '{"type":"hello"}' },
+    });
+    cdp.emit('Network.webSocketFrameReceived', {
+      requestId: 'r1',
+      response: { opcode: 1, payloadData: '{"type":"welcome"}' },
+    });
+
+    const [socket] = ws.list();
+    expect(socket).toMatchObject({ id: 'ws1', status: 101, framesSent: 1, framesReceived: 1 });
+    expect(socket).not.toHaveProperty('frames');
+    expect(ws.get('chat.example.com')?.frames.map((f) => f.direction)).toEqual([
+      'sent',
+      'received',
+    ]);
+
+    cdp.emit('Network.webSocketClosed', { requestId: 'r1' });
+    expect(ws.get('ws1')?.closed).toBeDefined();
+  });
+
+  it('ignores frames for unknown sockets', () => {
+    const { ws, cdp } = tracker();
+    cdp.emit('Network.webSocketFrameReceived', {
+      requestId: 'missing',
+      response: { opcode: 1, payloadData: 'x' },
+    });
+    expect(ws.list()).toEqual([]);
+  });
+});
+
+describe('truncatePayload', () => {
+  it('keeps short payloads and truncates long ones', () => {
+    expect(truncatePayload('abc')).toBe('abc');
+    expect(truncatePayload('x'.repeat(5000))).toHaveLength(4099);
+  });
+});
@@ -0,0 +1,127 @@
+/**
+ * WebSocket inspection (`ws list|tail|export`).
+ *
+ * Handshakes and frames come from the CDP Network.webSocket* events, so
+ * sockets are only tracked on Chromium. Each socket keeps its most recent
+ * frames, with long payloads truncated, so a chatty dashboard can't grow the
+ * daemon without bound.
+ */
+
+import type { CDPSession } from 'playwright-core';
+
+const MAX_SOCKETS = 100;
+const MAX_FRAMES = 1000;
+const MAX_PAYLOAD = 4096;
+
+export interface WebSocketFrame {
+  direction: 'sent' | 'received';
+  /** 1 = text, 2 = binary, 8 = close, 9/10 = ping/pong */
+  opcode: number;
+  data: string;
+  /** Full payload length before truncation */
+  size: number;
+  timestamp: number;
+}
+
+export interface WebSocketInfo {
+  id: string;
+  url: string;
+  status?: number;
+  requestHeaders?: Record<string, string>;
+  responseHeaders?: Record<string, string>;
+  opened: number;
+  closed?: number;
+  error?: string;
+  framesSent: number;
+  framesReceived: number;
+  frames: WebSocketFrame[];
+}
+
+export function truncatePayload(data: string): string {
+  return data.length > MAX_PAYLOAD ? data.slice(0, MAX_PAYLOAD) + '...' : data;
+}
+
+export class WebSocketTracker {
+  private sockets: Map<string, WebSocketInfo> = new Map();
+  private nextId = 1;
+
+  attach(cdp: CDPSession): void {
+    cdp.on('Network.webSocketCreated', (event) => {
+      if (this.sockets.size >= MAX_SOCKETS) {
+        // Forget the oldest closed socket to make room
+        const closed = [...this.sockets.entries()].find(([, s]) => s.closed !== undefined);
+        if (!closed) return;
+        this.sockets.delete(closed[0]);
+      }
+      this.sockets.set(event.requestId, {
+        id: `ws${this.nextId++}`,
+        url: event.url,
+        opened: Date.now(),
+        framesSent: 0,
+        framesReceived: 0,
+        frames: [],
+      });
+    });
+    cdp.on('Network.webSocketWillSendHandshakeRequest', (event) => {
+      const socket = this.sockets.get(event.requestId);
+      if (socket) socket.requestHeaders = event.request.headers;
+    });
+    cdp.on('Network.webSocketHandshakeResponseReceived', (event) => {
+      const socket = this.sockets.get(event.requestId);
+      if (!socket) return;
+      socket.status = event.response.status;
+      socket.responseHeaders = event.response.headers;
+    });
+    cdp.on('Network.webSocketFrameSent', (event) => {
+      this.addFrame(event.requestId, 'sent', event.response);
+    });
+    cdp.on('Network.webSocketFrameReceived', (event) => {
+      this.addFrame(event.requestId, 'received', event.response);
+    });
+    cdp.on('Network.webSocketFrameError', (event) => {
+      const socket = this.sockets.get(event.requestId);
+      if (socket) socket.error = event.errorMessage;
+    });
+    cdp.on('Network.webSocketClosed', (event) => {
+      const socket = this.sockets.get(event.requestId);
+      if (socket) socket.closed = Date.now();
+    });
+  }
+
+  private addFrame(
+    requestId: string,
+    direction: 'sent' | 'received',
+    frame: { opcode: number; payloadData: string }
+  ): void {
+    const socket = this.sockets.get(requestId);
+    if (!socket) return;
+    if (direction === 'sent') socket.framesSent++;
+    else socket.framesReceived++;
+    socket.frames.push({
+      direction,
+      opcode: frame.opcode,
+      data: truncatePayload(frame.payloadData),
+      size: frame.payloadData.length,
+      timestamp: Date.now(),
+    });
+    if (socket.frames.length > MAX_FRAMES) socket.frames.shift();
+  }
+
+  /** Sockets without their frames, oldest first. */
+  list(): Omit<WebSocketInfo, 'frames'>[] {
+    return [...this.sockets.values()].map(({ frames: _frames, ...rest }) => rest);
+  }
+
+  get(id: string): WebSocketInfo | undefined {
+    return [...this.sockets.values()].find((s) => s.id === id || s.url.includes(id));
+  }
+
+  /** Every socket with its retained frames. */
+  all(): WebSocketInfo[] {
+    return [...this.sockets.values()];
+  }
+
+  clear(): void {
+    this.sockets.clear();
+  }
+}
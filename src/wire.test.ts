@@ -0,0 +1,69 @@
+import { describe, it, expect } from 'vitest';
+import { decode, encode } from './msgpack.js';
+import { FRAME_MARKER, WireReader, encodeMessage } from './wire.js';
+
+describe('msgpack', () => {
+  it('round-trips the JSON data model', () => {
+    const value = {
+      id: 'r1',
+      success: true,
+      data: {
+        snapshot: '- heading "Example" [ref=e1]\n'.repeat(20),
+        refs: { e1: { role: 'heading', name: 'Example' } },
+        counts: [
+          0, 127, 128, 255, 256, 65536, 2 ** 32, -1, -32, -33, -129, -40000, -(2 ** 31) - 1,
+        ],
+        ratio: 0.5,
+        empty: {},
+        none: null,
+        text: 'héllo ✓',
+      },
+      error: null,
+    };
+    expect(decode(encode(value))).toEqual(value);
+  });
+
+  it('drops and nulls what JSON does', () => {
+    const value = { a: undefined, b: [undefined, NaN], c: new Date(0) };
+    expect(decode(encode(value))).toEqual(JSON.parse(JSON.stringify(value)));
+  });
+
+  it('rejects truncated and trailing data', () => {
+    const bytes = encode({ url: 'https://example.com' });
+    expect(() => decode(bytes.subarray(0, bytes.length - 1))).toThrow('Truncated');
+    expect(() => decode(Buffer.concat([bytes, Buffer.from([0xc0])]))).toThrow('Trailing');
+  });
+});
+
+describe('WireReader', () => {
+  it('reads JSON lines, split across chunks', () => {
+    const wire = new WireReader();
+    wire.push(Buffer.from('{"id":"1","action":"url"}\n{"id":"2",'));
+    expect(wire.format).toBe('json');
+    expect(wire.next()).toEqual({ value: { id: '1', action: 'url' } });
+    expect(wire.next()).toBeUndefined();
+    wire.push(Buffer.from('"action":"title"}\nnot json\n'));
+    expect(wire.next()).toEqual({ value: { id: '2', action: 'title' } });
+    expect(wire.next()).toEqual({ error: 'Invalid JSON' });
+  });
+
+  it('reads frames, with the newline the CLI ends them with', () => {
+    const wire = new WireReader();
+    const command = { id: '1', action: 'snapshot', interactive: true };
+    const frame = Buffer.concat([encodeMessage('msgpack', command), Buffer.from('\n')]);
+    expect(frame[0]).toBe(FRAME_MARKER);
+    wire.push(frame.subarray(0, 3));
+    expect(wire.format).toBe('msgpack');
+    expect(wire.next()).toBeUndefined();
+    wire.push(Buffer.concat([frame.subarray(3), frame]));
+    expect(wire.next()).toEqual({ value: command });
+    expect(wire.next()).toEqual({ value: command });
+    expect(wire.next()).toBeUndefined();
+  });
+
+  it('rejects frames over the size limit', () => {
+    const wire = new WireReader();
+    wire.push(Buffer.from([FRAME_MARKER, 0xff, 0xff, 0xff, 0xff]));
+    expect(wire.next()).toMatchObject({ error: expect.stringContaining('over the') });
+  });
+});
@@ -0,0 +1,101 @@
+/**
+ * Message framing on the daemon socket.
+ *
+ * Clients speak one of two formats, picked by the first byte they send:
+ *
+ * - `json`: one JSON document per line, the original protocol, still used by
+ *   `AGENT_BROWSER_IPC=json`, the job runner and anything scripting the socket.
+ * - `msgpack`: frames of a {@link FRAME_MARKER} byte, a 4-byte big-endian
+ *   length and that many bytes of MessagePack. The CLI sends this by default.
+ *   Large responses like snapshots are copied once instead of being scanned
+ *   for newlines and string escapes.
+ *
+ * The daemon answers in the format the client used. The CLI ends each of its
+ * frames with a newline, which a daemon from before frames existed reads as
+ * an invalid JSON line and answers in JSON, so the CLI can tell and fall back.
+ */
+
+import { decode, encode } from './msgpack.js';
+
+/** First byte of every binary frame; JSON and HTTP never start with it */
+export const FRAME_MARKER = 0xab;
+/** Largest frame accepted, so a corrupt length can't make the daemon buffer forever */
+export const MAX_FRAME_BYTES = 256 * 1024 * 1024;
+
+export type WireFormat = 'json' | 'msgpack';
+
+/** A message read off the socket: its value, or why it couldn't be decoded */
+export type WireMessage = { value: unknown } | { error: string };
+
+export function encodeMessage(format: WireFormat, value: unknown): Buffer {
+  if (format === 'json') return Buffer.from(JSON.stringify(value) + '\n');
+  const payload = encode(value);
+  const frame = Buffer.allocUnsafe(5 + payload.length);
+  frame[0] = FRAME_MARKER;
+  frame.writeUInt32BE(payload.length, 1);
+  payload.copy(frame, 5);
+  return frame;
+}
+
+/** Splits a connection's bytes into messages, in whichever format it speaks */
+export class WireReader {
+  format: WireFormat | null = null;
+  private buffer = Buffer.alloc(0);
+
+  push(chunk: Buffer): void {
+    this.buffer = this.buffer.length === 0 ? chunk : Buffer.concat([this.buffer, chunk]);
+    if (this.format === null) {
+      const start = this.buffer.findIndex((b) => b !== 0x20 && b !== 0x0a && b !== 0x0d);
+      if (start >= 0) this.format = this.buffer[start] === FRAME_MARKER ? 'msgpack' : 'json';
+    }
+  }
+
+  /** The text received so far, for sniffing what kind of client this is */
+  peek(): string {
+    return this.buffer.subarray(0, 16).toString('latin1');
+  }
+
+  /** The next complete message, or undefined until more bytes arrive */
+  next(): WireMessage | undefined {
+    if (this.format === 'json') {
+      for (;;) {
+        const newline = this.buffer.indexOf(0x0a);
+        if (newline < 0) return undefined;
+        const line = this.buffer.subarray(0, newline).toString('utf-8');
+        this.buffer = this.buffer.subarray(newline + 1);
+        if (!line.trim()) continue;
+        try {
+          return { value: JSON.parse(line) };
+        } catch {
+          return { error: 'Invalid JSON' };
+        }
+      }
+    }
+    if (this.format === 'msgpack') {
+      // Skip the newline the CLI ends its frames with
+      while (this.buffer.length > 0 && this.buffer[0] !== FRAME_MARKER) {
+        const b = this.buffer[0];
+        if (b !== 0x0a && b !== 0x0d && b !== 0x20) {
+          this.buffer = Buffer.alloc(0);
+          return { error: 'Invalid frame' };
+        }
+        this.buffer = this.buffer.subarray(1);
+      }
+      if (this.buffer.length < 5) return undefined;
+      const length = this.buffer.readUInt32BE(1);
+      if (length > MAX_FRAME_BYTES) {
+        this.buffer = Buffer.alloc(0);
+        return { error: `Frame of ${length} bytes is over the ${MAX_FRAME_BYTES} byte limit` };
+      }
+      if (this.buffer.length < 5 + length) return undefined;
+      const payload = this.buffer.subarray(5, 5 + length);
+      this.buffer = this.buffer.subarray(5 + length);
+      try {
+        return { value: decode(payload) };
+      } catch (err) {
+        return { error: `Invalid MessagePack: ${(err as Error).message}` };
+      }
+    }
+    return undefined;
+  }
+}
@@ -0,0 +1,41 @@
+/**
+ * Daemon-side cost of a command round trip in each wire format: decoding the
+ * request and encoding the response. Run with `pnpm bench`; the CLI side is
+ * measured by `cargo test --release ipc_bench -- --ignored --nocapture`.
+ */
+
+import { bench, describe } from 'vitest';
+import { WireReader, encodeMessage, type WireFormat } from '../src/wire.js';
+
+const request = { id: 'r1', action: 'click', selector: '@e12' };
+const small = { id: 'r1', success: true, data: { clicked: true } };
+const snapshot = {
+  id: 'r2',
+  success: true,
+  data: {
+    snapshot: Array.from(
+      { length: 2000 },
+      (_, i) => `  - link "Result ${i}: \\"quoted\\" title" [ref=e${i}]`
+    ).join('\n'),
+    refs: Object.fromEntries(
+      Array.from({ length: 2000 }, (_, i) => [`e${i}`, { role: 'link', name: `Result ${i}` }])
+    ),
+  },
+};
+
+function roundTrip(format: WireFormat, response: unknown): void {
+  const reader = new WireReader();
+  reader.push(encodeMessage(format, request));
+  reader.next();
+  encodeMessage(format, response);
+}
+
+for (const [name, response] of [
+  ['small response', small],
+  ['2000-ref snapshot', snapshot],
+] as const) {
+  describe(name, () => {
+    bench('json lines', () => roundTrip('json', response));
+    bench('msgpack frames', () => roundTrip('msgpack', response));
+  });
+}